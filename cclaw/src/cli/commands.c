@@ -682,6 +682,7 @@ err_t cmd_help(const char* topic) {
         printf("\nUsage: cclaw <command> [options]\n");
         printf("\nCommands:\n");
         printf("  onboard          Initialize configuration\n");
+        printf("  setup            Guided setup of the agent config (provider, key, workspace)\n");
         printf("  agent            Start interactive agent\n");
         printf("  tui              Start TUI interface\n");
         printf("  daemon           Manage daemon (start/stop/restart/status)\n");
@@ -695,6 +696,9 @@ err_t cmd_help(const char* topic) {
         printf("  -h, --help       Show help for a command\n");
         printf("  -v, --version    Show version\n");
         printf("  -m, --message    Single message mode (for agent)\n");
+        printf("  --tui            Full-screen terminal UI (for agent)\n");
+        printf("  --profile NAME   Apply a [profiles.NAME] config profile (for agent)\n");
+        printf("  --dry-run        Preview changes instead of making them (for agent)\n");
         printf("\nExamples:\n");
         printf("  cclaw onboard\n");
         printf("  cclaw agent\n");
@@ -12,6 +12,7 @@
 err_t cmd_onboard(config_t* config, int argc, char** argv);
 err_t cmd_agent(config_t* config, int argc, char** argv);
 err_t cmd_agent_zeroclaw(config_t* config, int argc, char** argv);
+err_t cmd_setup_zeroclaw(config_t* config, int argc, char** argv);
 err_t cmd_daemon(config_t* config, int argc, char** argv);
 err_t cmd_status(config_t* config, int argc, char** argv);
 err_t cmd_channel(config_t* config, int argc, char** argv);
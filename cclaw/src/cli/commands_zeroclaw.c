@@ -12,7 +12,7 @@
 #include <unistd.h>
 
 // Utility: Build JSON config from cclaw config
-static char* build_zeroclaw_config(const config_t* config) {
+static char* build_zeroclaw_config(const config_t* config, bool dry_run) {
     if (!config) return NULL;
 
     // Calculate buffer size needed
@@ -29,7 +29,7 @@ static char* build_zeroclaw_config(const config_t* config) {
         "\"default_temperature\":%.2f,"
         "\"workspace_dir\":\"%.*s\","
         "\"memory\":{\"backend\":\"%.*s\"},"
-        "\"autonomy\":{\"level\":%d},"
+        "\"autonomy\":{\"level\":%d,\"dry_run\":%s},"
         "\"browser\":{\"enabled\":false},"
         "\"composio\":{\"enabled\":false}"
         "}",
@@ -39,7 +39,8 @@ static char* build_zeroclaw_config(const config_t* config) {
         config->default_temperature,
         (int)config->workspace_dir.len, config->workspace_dir.data ? config->workspace_dir.data : "~/.cclaw",
         (int)config->memory.backend.len, config->memory.backend.data ? config->memory.backend.data : "sqlite",
-        config->autonomy.level
+        config->autonomy.level,
+        dry_run ? "true" : "false"
     );
 
     return json;
@@ -51,7 +52,13 @@ err_t cmd_agent_zeroclaw(config_t* config, int argc, char** argv) {
     const char* message = NULL;
     const char* provider_override = NULL;
     const char* model_override = NULL;
+    const char* profile = NULL;
     double temperature = config->default_temperature;
+    uint64_t max_tokens = 0;
+    uint32_t max_tool_calls = 0;
+    uint64_t max_seconds = 0;
+    bool tui = false;
+    bool dry_run = false;
 
     for (int i = 0; i < argc; i++) {
         if ((strcmp(argv[i], "-m") == 0 || strcmp(argv[i], "--message") == 0) && i + 1 < argc) {
@@ -63,14 +70,30 @@ err_t cmd_agent_zeroclaw(config_t* config, int argc, char** argv) {
         } else if ((strcmp(argv[i], "--model") == 0) && i + 1 < argc) {
             model_override = argv[i + 1];
             i++;
+        } else if (strcmp(argv[i], "--profile") == 0 && i + 1 < argc) {
+            profile = argv[i + 1];
+            i++;
         } else if ((strcmp(argv[i], "-t") == 0 || strcmp(argv[i], "--temperature") == 0) && i + 1 < argc) {
             temperature = atof(argv[i + 1]);
             i++;
+        } else if (strcmp(argv[i], "--max-tokens") == 0 && i + 1 < argc) {
+            max_tokens = strtoull(argv[i + 1], NULL, 10);
+            i++;
+        } else if (strcmp(argv[i], "--max-tool-calls") == 0 && i + 1 < argc) {
+            max_tool_calls = (uint32_t)strtoul(argv[i + 1], NULL, 10);
+            i++;
+        } else if (strcmp(argv[i], "--max-seconds") == 0 && i + 1 < argc) {
+            max_seconds = strtoull(argv[i + 1], NULL, 10);
+            i++;
+        } else if (strcmp(argv[i], "--tui") == 0) {
+            tui = true;
+        } else if (strcmp(argv[i], "--dry-run") == 0) {
+            dry_run = true;
         }
     }
 
     // Build config JSON
-    char* config_json = build_zeroclaw_config(config);
+    char* config_json = build_zeroclaw_config(config, dry_run);
     if (!config_json) {
         fprintf(stderr, "Failed to build configuration\n");
         return ERR_OUT_OF_MEMORY;
@@ -85,9 +108,10 @@ err_t cmd_agent_zeroclaw(config_t* config, int argc, char** argv) {
     }
     
     zc_agent_runtime_t* runtime = NULL;
-    zc_result_t result = zc_agent_init(
+    zc_result_t result = zc_agent_init_profile(
         config_json,
         workspace,
+        profile,
         &runtime
     );
 
@@ -110,12 +134,15 @@ err_t cmd_agent_zeroclaw(config_t* config, int argc, char** argv) {
     if (message) {
         // Single message mode
         char* response = NULL;
-        result = zc_agent_run_single(
+        result = zc_agent_run_single_budget(
             runtime,
             message,
             provider_override,
             model_override,
             temperature,
+            max_tokens,
+            max_tool_calls,
+            max_seconds,
             &response
         );
 
@@ -128,13 +155,22 @@ err_t cmd_agent_zeroclaw(config_t* config, int argc, char** argv) {
             fprintf(stderr, "Agent error: %d, response: %p\n", result, (void*)response);
         }
     } else {
-        // Interactive mode
-        result = zc_agent_run_interactive(
-            runtime,
-            provider_override,
-            model_override,
-            temperature
-        );
+        // Interactive mode, full-screen with --tui
+        if (tui) {
+            result = zc_agent_run_tui(
+                runtime,
+                provider_override,
+                model_override,
+                temperature
+            );
+        } else {
+            result = zc_agent_run_interactive(
+                runtime,
+                provider_override,
+                model_override,
+                temperature
+            );
+        }
 
         if (result != ZC_OK) {
             fprintf(stderr, "Agent error: %d\n", result);
@@ -149,6 +185,25 @@ err_t cmd_agent_zeroclaw(config_t* config, int argc, char** argv) {
     return (result == ZC_OK) ? ERR_OK : ERR_FAILED;
 }
 
+// Guided setup of the ZeroClaw agent config
+err_t cmd_setup_zeroclaw(config_t* config, int argc, char** argv) {
+    (void)config;
+    (void)argc;
+    (void)argv;
+
+    char* config_path = NULL;
+    zc_result_t result = zc_agent_setup_interactive(&config_path);
+    if (result != ZC_OK) {
+        const char* reason = zc_last_error_message();
+        fprintf(stderr, "Setup failed: %s\n", reason ? reason : "unknown error");
+        return ERR_FAILED;
+    }
+
+    printf("\nRun 'cclaw agent' to start. Config: %s\n", config_path);
+    zc_free_string(config_path);
+    return ERR_OK;
+}
+
 // Build TOML config for ZeroClaw daemon
 char* build_zeroclaw_toml_config(const config_t* config) {
     if (!config) return NULL;
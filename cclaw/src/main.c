@@ -197,6 +197,9 @@ static err_t handle_command(cli_args_t* args, config_t* config) {
     if (strcmp(cmd, "onboard") == 0) {
         return cmd_onboard(config, args->sub_argc, args->sub_argv);
     }
+    else if (strcmp(cmd, "setup") == 0) {
+        return cmd_setup_zeroclaw(config, args->sub_argc, args->sub_argv);
+    }
     else if (strcmp(cmd, "agent") == 0) {
         return cmd_agent(config, args->sub_argc, args->sub_argv);
     }
@@ -0,0 +1,111 @@
+// Types for the zeroclaw native module (src/lib.rs) and the streaming
+// helpers in index.js.
+
+/** Version of the zeroclaw runtime. */
+export function version(): string
+
+/**
+ * A failed call. `code` is the C name of the result, e.g. "ZC_PROVIDER_ERROR".
+ * Budget stops ("ZC_BUDGET_EXHAUSTED") also carry the partial answer and the
+ * cap that was hit.
+ */
+export interface ZeroClawError extends Error {
+  code: string
+  partial?: string
+  limit?: 'tokens' | 'tool_calls' | 'seconds' | 'iterations'
+}
+
+export interface AgentOptions {
+  /** Overrides the config's workspace */
+  workspaceDir?: string
+  /** Config profile to apply (default: ZEROCLAW_PROFILE) */
+  profile?: string
+}
+
+export interface StreamOptions {
+  provider?: string
+  model?: string
+  /** 0 or unset for the config's temperature */
+  temperature?: number
+}
+
+export interface RunOptions extends StreamOptions {
+  /** Caps that can only lower the model profile's; 0 or unset for none */
+  maxTokens?: number
+  maxToolCalls?: number
+  maxSeconds?: number
+  maxIterations?: number
+}
+
+export interface BranchOptions {
+  model?: string
+  temperature?: number
+}
+
+export interface ToolSpec {
+  name: string
+  description: string
+  /** JSON schema of the arguments */
+  parameters: object
+}
+
+export interface ToolResult {
+  success: boolean
+  output: string
+  error: string | null
+}
+
+export interface MemoryEntry {
+  id: string
+  key: string
+  content: string
+  category: string
+  timestamp: string
+  session_id: string | null
+  score: number | null
+}
+
+export class Agent {
+  /**
+   * `config` is the JSON object zc_agent_init takes (api_key,
+   * default_provider, default_model, memory, autonomy, ...); leave it out
+   * to load ~/.zeroclaw/config.toml.
+   */
+  constructor(config?: object | null, options?: AgentOptions | null)
+  /** Answer `message` in a fresh conversation, running tools as needed. */
+  run(message: string, options?: RunOptions | null): Promise<string>
+  /**
+   * Like run, handing the text of each model reply to `onDelta` as it
+   * arrives (tool calls left out). Resolves to the final answer, which may
+   * differ once verification and guardrails have run.
+   */
+  stream(message: string, onDelta: (delta: string) => void, options?: StreamOptions | null): Promise<string>
+  /** Start a conversation that keeps its history between messages. */
+  session(): Session
+  tools(): ToolSpec[]
+  /** Run one tool under the agent's security policy, without a model. */
+  executeTool(name: string, args?: object | null): Promise<ToolResult>
+  get memory(): Memory
+}
+
+export class Session {
+  get id(): number
+  send(message: string): Promise<string>
+  sendStream(message: string, onDelta: (delta: string) => void): Promise<string>
+  /**
+   * Fork at `messageIndex` (0 = the first message sent, 1 its reply, ...)
+   * and rerun that turn. This session is left as it was.
+   */
+  branch(messageIndex: number, options?: BranchOptions | null): Promise<{ session: Session; reply: string }>
+  /** Drop the session's history. */
+  close(): void
+}
+
+export class Memory {
+  /** `category` is core (the default), daily, conversation or a custom name. */
+  store(key: string, content: string, category?: string | null): Promise<void>
+  recall(query: string, limit?: number | null): Promise<MemoryEntry[]>
+  /** Resolves to whether there was an entry under `key`. */
+  forget(key: string): Promise<boolean>
+  count(): Promise<number>
+}
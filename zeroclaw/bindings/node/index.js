@@ -0,0 +1,68 @@
+// Loads the prebuilt module for this platform (zeroclaw.<platform>-<arch>.node,
+// as written by `napi build --platform`), or ZEROCLAW_NODE_MODULE when set,
+// and adds the streaming methods on top of the native callbacks.
+'use strict'
+
+const { join } = require('path')
+
+function platformSuffix() {
+  const { platform, arch } = process
+  if (platform === 'linux') {
+    const musl = !process.report?.getReport?.().header?.glibcVersionRuntime
+    return `linux-${arch}-${musl ? 'musl' : 'gnu'}`
+  }
+  if (platform === 'win32') return `win32-${arch}-msvc`
+  return `${platform}-${arch}`
+}
+
+function load() {
+  const file = process.env.ZEROCLAW_NODE_MODULE || join(__dirname, `zeroclaw.${platformSuffix()}.node`)
+  try {
+    return require(file)
+  } catch (e) {
+    throw new Error(
+      `No zeroclaw native module for ${platformSuffix()} (${file}). ` +
+        'Build one with `npm run build` in bindings/node.',
+      { cause: e }
+    )
+  }
+}
+
+const native = load()
+
+// Runs a native streaming call: onDelta gets each piece of text as it
+// arrives, and the promise settles with the answer once the turn is over.
+function streamed(start, onDelta) {
+  return new Promise((resolve, reject) => {
+    let thrown
+    start((err, text, done) => {
+      if (err) return reject(err)
+      if (!done) {
+        if (thrown === undefined) {
+          try {
+            onDelta(text)
+          } catch (e) {
+            thrown = e
+          }
+        }
+        return
+      }
+      thrown === undefined ? resolve(text) : reject(thrown)
+    })
+  })
+}
+
+native.Agent.prototype.stream = function stream(message, onDelta, options) {
+  return streamed((callback) => this._stream(message, options, callback), onDelta)
+}
+
+native.Session.prototype.sendStream = function sendStream(message, onDelta) {
+  return streamed((callback) => this._sendStream(message, callback), onDelta)
+}
+
+module.exports = {
+  Agent: native.Agent,
+  Session: native.Session,
+  Memory: native.Memory,
+  version: native.version,
+}
@@ -0,0 +1,703 @@
+//! N-API bindings for ZeroClaw: the C FFI of the `zeroclaw` crate wrapped
+//! as a Node.js module. Blocking calls run on the libuv thread pool and come
+//! back as promises; streamed turns run on a thread of their own and report
+//! through one threadsafe function, so deltas and the final answer arrive in
+//! order.
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{
+    ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
+use napi::{Env, JsFunction, JsObject, JsUnknown, NapiRaw};
+use napi_derive::napi;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::sync::Arc;
+use zeroclaw::ffi::{self, AgentRuntime, ZcBudgetLimit, ZcDeltaFn, ZcResult, ZcTurnBudget};
+
+/// The runtime, shut down once the agent and every call in flight let go.
+pub struct Handle(*mut AgentRuntime);
+
+// SAFETY: the FFI accepts the runtime from any thread and locks what its
+// calls share
+unsafe impl Send for Handle {}
+unsafe impl Sync for Handle {}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        // SAFETY: the pointer came from `zc_agent_init_profile` and is freed once
+        unsafe { ffi::zc_agent_shutdown(self.0) }
+    }
+}
+
+/// The C name of a result code, used as the JS error's `code`.
+fn code_name(code: ZcResult) -> &'static str {
+    match code {
+        ZcResult::Ok => "ZC_OK",
+        ZcResult::Error => "ZC_ERROR",
+        ZcResult::InvalidArg => "ZC_INVALID_ARG",
+        ZcResult::NotInitialized => "ZC_NOT_INITIALIZED",
+        ZcResult::OutOfMemory => "ZC_OUT_OF_MEMORY",
+        ZcResult::BudgetExhausted => "ZC_BUDGET_EXHAUSTED",
+        ZcResult::ProviderError => "ZC_PROVIDER_ERROR",
+        ZcResult::ToolDenied => "ZC_TOOL_DENIED",
+        ZcResult::RateLimited => "ZC_RATE_LIMITED",
+        ZcResult::ContextTooLong => "ZC_CONTEXT_TOO_LONG",
+        ZcResult::BindError => "ZC_BIND_ERROR",
+    }
+}
+
+fn limit_name(limit: ZcBudgetLimit) -> Option<&'static str> {
+    match limit {
+        ZcBudgetLimit::None => None,
+        ZcBudgetLimit::Tokens => Some("tokens"),
+        ZcBudgetLimit::ToolCalls => Some("tool_calls"),
+        ZcBudgetLimit::Seconds => Some("seconds"),
+        ZcBudgetLimit::Iterations => Some("iterations"),
+    }
+}
+
+/// A failed FFI call. Built on the thread that made the call, since the
+/// FFI keeps the last error message per thread.
+struct Failure {
+    code: ZcResult,
+    message: String,
+    /// The best partial answer when a budget cap stopped the turn
+    partial: Option<String>,
+    limit: Option<&'static str>,
+}
+
+impl Failure {
+    fn new(code: ZcResult) -> Self {
+        let message = ffi::zc_last_error_message();
+        let message = if message.is_null() {
+            format!("zeroclaw call failed with {}", code_name(code))
+        } else {
+            // SAFETY: a non-NULL message is a valid string until the next
+            // failing call on this thread
+            unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned()
+        };
+        Self {
+            code,
+            message,
+            partial: None,
+            limit: None,
+        }
+    }
+
+    fn invalid(message: impl Into<String>) -> Self {
+        Self {
+            code: ZcResult::InvalidArg,
+            message: message.into(),
+            partial: None,
+            limit: None,
+        }
+    }
+
+    /// An `Error` with `code` set to the C name of the result, plus
+    /// `partial` and `limit` for budget stops.
+    fn into_js(self, env: Env) -> Result<JsObject> {
+        let mut error = env.create_error(Error::from_reason(self.message))?;
+        error.set_named_property("code", env.create_string(code_name(self.code))?)?;
+        if let Some(partial) = self.partial {
+            error.set_named_property("partial", env.create_string(&partial)?)?;
+        }
+        if let Some(limit) = self.limit {
+            error.set_named_property("limit", env.create_string(limit)?)?;
+        }
+        Ok(error)
+    }
+}
+
+fn check(code: ZcResult) -> std::result::Result<(), Failure> {
+    match code {
+        ZcResult::Ok => Ok(()),
+        code => Err(Failure::new(code)),
+    }
+}
+
+fn cstring(text: &str) -> std::result::Result<CString, Failure> {
+    CString::new(text)
+        .map_err(|_| Failure::invalid("Strings passed to zeroclaw cannot contain NUL"))
+}
+
+fn opt_cstring(text: Option<&str>) -> std::result::Result<Option<CString>, Failure> {
+    text.map(cstring).transpose()
+}
+
+fn ptr_or_null(text: &Option<CString>) -> *const c_char {
+    text.as_ref().map_or(std::ptr::null(), |text| text.as_ptr())
+}
+
+/// Copy a string the FFI handed out, then free it.
+unsafe fn take(ptr: *mut c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    let text = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+    ffi::zc_free_string(ptr);
+    Some(text)
+}
+
+/// Run an FFI call that writes a string to its last argument.
+fn call_for_string(
+    run: impl FnOnce(*mut *mut c_char) -> ZcResult,
+) -> std::result::Result<String, Failure> {
+    let mut out = std::ptr::null_mut();
+    let code = run(&mut out);
+    // SAFETY: the FFI either left `out` NULL or set it to a string it owns
+    let text = unsafe { take(out) };
+    check(code)?;
+    Ok(text.unwrap_or_default())
+}
+
+fn call_for_json(
+    run: impl FnOnce(*mut *mut c_char) -> ZcResult,
+) -> std::result::Result<serde_json::Value, Failure> {
+    let json = call_for_string(run)?;
+    serde_json::from_str(&json).map_err(|e| Failure {
+        code: ZcResult::Error,
+        message: format!("zeroclaw returned invalid JSON: {e}"),
+        partial: None,
+        limit: None,
+    })
+}
+
+/// Whatever a finished call resolves to.
+pub struct Resolved(JsUnknown);
+
+impl TypeName for Resolved {
+    fn type_name() -> &'static str {
+        "unknown"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Unknown
+    }
+}
+
+impl ToNapiValue for Resolved {
+    unsafe fn to_napi_value(_env: napi::sys::napi_env, val: Self) -> Result<napi::sys::napi_value> {
+        Ok(val.0.raw())
+    }
+}
+
+type Work<O> = Box<dyn FnOnce() -> std::result::Result<O, Failure> + Send>;
+
+/// One blocking FFI call on the libuv thread pool.
+pub struct Call<O> {
+    work: Option<Work<O>>,
+    finish: fn(Env, O) -> Result<JsUnknown>,
+    failure: Option<Failure>,
+}
+
+impl<O: Send + 'static> Task for Call<O> {
+    type Output = O;
+    type JsValue = Resolved;
+
+    fn compute(&mut self) -> Result<O> {
+        let work = self.work.take().expect("a call runs once");
+        work().map_err(|failure| {
+            let error = Error::from_reason(failure.message.clone());
+            self.failure = Some(failure);
+            error
+        })
+    }
+
+    fn resolve(&mut self, env: Env, output: O) -> Result<Resolved> {
+        (self.finish)(env, output).map(Resolved)
+    }
+
+    fn reject(&mut self, env: Env, err: Error) -> Result<Resolved> {
+        match self.failure.take() {
+            Some(failure) => Err(Error::from(failure.into_js(env)?.into_unknown())),
+            None => Err(err),
+        }
+    }
+}
+
+/// A call resolving to its output as plain JS data.
+fn call<O: serde::Serialize + Send + 'static>(
+    work: impl FnOnce() -> std::result::Result<O, Failure> + Send + 'static,
+) -> AsyncTask<Call<O>> {
+    AsyncTask::new(Call {
+        work: Some(Box::new(work)),
+        finish: |env, output| env.to_js_value(&output),
+        failure: None,
+    })
+}
+
+/// What a streamed turn reports, in order.
+enum Event {
+    Delta(String),
+    Done(String),
+    Failed(Failure),
+}
+
+type Events = ThreadsafeFunction<Event, ErrorStrategy::Fatal>;
+
+unsafe extern "C" fn send_delta(user_data: *mut c_void, delta: *const c_char) {
+    let events = &*(user_data as *const Events);
+    let delta = CStr::from_ptr(delta).to_string_lossy().into_owned();
+    events.call(Event::Delta(delta), ThreadsafeFunctionCallMode::Blocking);
+}
+
+/// Run a streamed turn on its own thread. `callback(err, text, done)` gets
+/// each delta with `done` false, then the answer with `done` true, or the
+/// error.
+fn stream_turn(
+    handle: Arc<Handle>,
+    callback: JsFunction,
+    run: impl FnOnce(*mut AgentRuntime, ZcDeltaFn, *mut c_void, *mut *mut c_char) -> ZcResult
+        + Send
+        + 'static,
+) -> Result<()> {
+    let events: Events =
+        callback.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<Event>| {
+            let env = ctx.env;
+            let null = || env.get_null().map(napi::JsNull::into_unknown);
+            Ok(match ctx.value {
+                Event::Delta(delta) => vec![
+                    null()?,
+                    env.create_string(&delta)?.into_unknown(),
+                    env.get_boolean(false)?.into_unknown(),
+                ],
+                Event::Done(answer) => vec![
+                    null()?,
+                    env.create_string(&answer)?.into_unknown(),
+                    env.get_boolean(true)?.into_unknown(),
+                ],
+                Event::Failed(failure) => vec![failure.into_js(env)?.into_unknown()],
+            })
+        })?;
+    std::thread::spawn(move || {
+        let user_data = std::ptr::addr_of!(events).cast_mut().cast::<c_void>();
+        let result = call_for_string(|out| run(handle.0, Some(send_delta), user_data, out));
+        let event = match result {
+            Ok(answer) => Event::Done(answer),
+            Err(failure) => Event::Failed(failure),
+        };
+        events.call(event, ThreadsafeFunctionCallMode::Blocking);
+    });
+    Ok(())
+}
+
+/// Version of the zeroclaw runtime.
+#[napi]
+pub fn version() -> String {
+    // SAFETY: `zc_version` returns a static NUL-terminated string
+    unsafe { CStr::from_ptr(ffi::zc_version()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[napi(object)]
+pub struct AgentOptions {
+    /// Overrides the config's workspace
+    pub workspace_dir: Option<String>,
+    /// Config profile to apply (default: `ZEROCLAW_PROFILE`)
+    pub profile: Option<String>,
+}
+
+#[napi(object)]
+pub struct RunOptions {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    /// 0 or unset for the config's temperature
+    pub temperature: Option<f64>,
+    /// Caps that can only lower the model profile's; 0 or unset for none
+    pub max_tokens: Option<u32>,
+    pub max_tool_calls: Option<u32>,
+    pub max_seconds: Option<u32>,
+    pub max_iterations: Option<u32>,
+}
+
+#[napi(object)]
+pub struct StreamOptions {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+}
+
+#[napi(object)]
+pub struct BranchOptions {
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+}
+
+/// An agent with its config, tools and memory.
+#[napi]
+pub struct Agent {
+    handle: Arc<Handle>,
+}
+
+#[napi]
+impl Agent {
+    /// `config` is the JSON object `zc_agent_init` takes; leave it out to
+    /// load `~/.zeroclaw/config.toml`.
+    #[napi(constructor)]
+    pub fn new(config: Option<serde_json::Value>, options: Option<AgentOptions>) -> Result<Self> {
+        let options = options.unwrap_or(AgentOptions {
+            workspace_dir: None,
+            profile: None,
+        });
+        let init = || -> std::result::Result<*mut AgentRuntime, Failure> {
+            let config = opt_cstring(config.map(|config| config.to_string()).as_deref())?;
+            let workspace = opt_cstring(options.workspace_dir.as_deref())?;
+            let profile = opt_cstring(options.profile.as_deref())?;
+            let mut handle = std::ptr::null_mut();
+            // SAFETY: every string is NUL-terminated or NULL
+            check(unsafe {
+                ffi::zc_agent_init_profile(
+                    ptr_or_null(&config),
+                    ptr_or_null(&workspace),
+                    ptr_or_null(&profile),
+                    &mut handle,
+                )
+            })?;
+            Ok(handle)
+        };
+        match init() {
+            Ok(handle) => Ok(Self {
+                handle: Arc::new(Handle(handle)),
+            }),
+            Err(failure) => Err(Error::from_reason(format!(
+                "{}: {}",
+                code_name(failure.code),
+                failure.message
+            ))),
+        }
+    }
+
+    /// Answer `message` in a fresh conversation, running tools as needed.
+    /// A budget stop rejects with `code` "ZC_BUDGET_EXHAUSTED", the partial
+    /// answer in `partial` and the cap in `limit`.
+    #[napi(ts_return_type = "Promise<string>")]
+    pub fn run(&self, message: String, options: Option<RunOptions>) -> AsyncTask<Call<String>> {
+        let handle = self.handle.clone();
+        call(move || {
+            let options = options.unwrap_or(RunOptions {
+                provider: None,
+                model: None,
+                temperature: None,
+                max_tokens: None,
+                max_tool_calls: None,
+                max_seconds: None,
+                max_iterations: None,
+            });
+            let message = cstring(&message)?;
+            let provider = opt_cstring(options.provider.as_deref())?;
+            let model = opt_cstring(options.model.as_deref())?;
+            let mut budget = ZcTurnBudget {
+                max_tokens: options.max_tokens.unwrap_or(0).into(),
+                max_tool_calls: options.max_tool_calls.unwrap_or(0),
+                max_seconds: options.max_seconds.unwrap_or(0).into(),
+                max_iterations: options.max_iterations.unwrap_or(0),
+                return_partial: true,
+                exhausted: ZcBudgetLimit::None,
+            };
+            let mut out = std::ptr::null_mut();
+            // SAFETY: the handle is alive and every pointer is valid for the call
+            let code = unsafe {
+                ffi::zc_agent_run_single_budget(
+                    handle.0,
+                    message.as_ptr(),
+                    ptr_or_null(&provider),
+                    ptr_or_null(&model),
+                    options.temperature.unwrap_or(0.0),
+                    &mut budget,
+                    &mut out,
+                )
+            };
+            // SAFETY: `out` is NULL or a string the FFI owns
+            let answer = unsafe { take(out) };
+            if let Err(mut failure) = check(code) {
+                failure.partial = answer;
+                failure.limit = limit_name(budget.exhausted);
+                return Err(failure);
+            }
+            Ok(answer.unwrap_or_default())
+        })
+    }
+
+    #[napi(js_name = "_stream")]
+    pub fn stream_native(
+        &self,
+        message: String,
+        options: Option<StreamOptions>,
+        callback: JsFunction,
+    ) -> Result<()> {
+        let options = options.unwrap_or(StreamOptions {
+            provider: None,
+            model: None,
+            temperature: None,
+        });
+        stream_turn(
+            self.handle.clone(),
+            callback,
+            move |handle, on_delta, user_data, out| {
+                let strings = (|| {
+                    Ok::<_, Failure>((
+                        cstring(&message)?,
+                        opt_cstring(options.provider.as_deref())?,
+                        opt_cstring(options.model.as_deref())?,
+                    ))
+                })();
+                let Ok((message, provider, model)) = strings else {
+                    return ZcResult::InvalidArg;
+                };
+                // SAFETY: the handle is alive and every pointer is valid for the call
+                unsafe {
+                    ffi::zc_agent_run_stream(
+                        handle,
+                        message.as_ptr(),
+                        ptr_or_null(&provider),
+                        ptr_or_null(&model),
+                        options.temperature.unwrap_or(0.0),
+                        on_delta,
+                        user_data,
+                        out,
+                    )
+                }
+            },
+        )
+    }
+
+    /// Start a conversation that keeps its history between messages.
+    #[napi]
+    pub fn session(&self) -> Result<Session> {
+        let mut id = 0;
+        // SAFETY: the handle is alive
+        check(unsafe { ffi::zc_session_create(self.handle.0, &mut id) })
+            .map_err(|failure| Error::from_reason(failure.message))?;
+        Ok(Session {
+            handle: self.handle.clone(),
+            id,
+        })
+    }
+
+    /// The registered tools: `name`, `description` and `parameters`.
+    #[napi(ts_return_type = "Array<{ name: string; description: string; parameters: object }>")]
+    pub fn tools(&self) -> Result<serde_json::Value> {
+        // SAFETY: the handle is alive
+        call_for_json(|out| unsafe { ffi::zc_tool_list(self.handle.0, out) })
+            .map_err(|failure| Error::from_reason(failure.message))
+    }
+
+    /// Run one tool directly under the agent's security policy. A tool that
+    /// ran but failed resolves with `success` false.
+    #[napi(ts_return_type = "Promise<{ success: boolean; output: string; error: string | null }>")]
+    pub fn execute_tool(
+        &self,
+        name: String,
+        args: Option<serde_json::Value>,
+    ) -> AsyncTask<Call<serde_json::Value>> {
+        let handle = self.handle.clone();
+        call(move || {
+            let name = cstring(&name)?;
+            let args = cstring(&args.unwrap_or_else(|| serde_json::json!({})).to_string())?;
+            let mut out = std::ptr::null_mut();
+            // SAFETY: the handle is alive and every pointer is valid for the call
+            let code =
+                unsafe { ffi::zc_tool_execute(handle.0, name.as_ptr(), args.as_ptr(), &mut out) };
+            // SAFETY: `out` is NULL or a string the FFI owns
+            match unsafe { take(out) } {
+                Some(result) => serde_json::from_str(&result).map_err(|e| Failure {
+                    code: ZcResult::Error,
+                    message: format!("zeroclaw returned invalid JSON: {e}"),
+                    partial: None,
+                    limit: None,
+                }),
+                None => Err(Failure::new(code)),
+            }
+        })
+    }
+
+    /// The agent's long-term memory.
+    #[napi(getter)]
+    pub fn memory(&self) -> Memory {
+        Memory {
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+/// A conversation on an `Agent`. Messages to one session run one after the
+/// other; separate sessions run in parallel.
+#[napi]
+pub struct Session {
+    handle: Arc<Handle>,
+    id: u64,
+}
+
+#[napi]
+impl Session {
+    #[napi(getter)]
+    pub fn id(&self) -> i64 {
+        i64::try_from(self.id).unwrap_or(i64::MAX)
+    }
+
+    /// Send `message` and resolve to the reply. A failed turn leaves the
+    /// history as it was.
+    #[napi(ts_return_type = "Promise<string>")]
+    pub fn send(&self, message: String) -> AsyncTask<Call<String>> {
+        let (handle, id) = (self.handle.clone(), self.id);
+        call(move || {
+            let message = cstring(&message)?;
+            // SAFETY: the handle is alive and every pointer is valid for the call
+            call_for_string(|out| unsafe {
+                ffi::zc_session_send(handle.0, id, message.as_ptr(), out)
+            })
+        })
+    }
+
+    #[napi(js_name = "_sendStream")]
+    pub fn send_stream_native(&self, message: String, callback: JsFunction) -> Result<()> {
+        let id = self.id;
+        stream_turn(
+            self.handle.clone(),
+            callback,
+            move |handle, on_delta, user_data, out| {
+                let Ok(message) = cstring(&message) else {
+                    return ZcResult::InvalidArg;
+                };
+                // SAFETY: the handle is alive and every pointer is valid for the call
+                unsafe {
+                    ffi::zc_session_send_stream(
+                        handle,
+                        id,
+                        message.as_ptr(),
+                        on_delta,
+                        user_data,
+                        out,
+                    )
+                }
+            },
+        )
+    }
+
+    /// Fork at `messageIndex` (0 = the first message sent, 1 its reply, ...)
+    /// and rerun that turn, optionally with another model or temperature.
+    /// Resolves to the new session and its reply; this one is unchanged.
+    #[napi(ts_return_type = "Promise<{ session: Session; reply: string }>")]
+    pub fn branch(
+        &self,
+        message_index: u32,
+        options: Option<BranchOptions>,
+    ) -> AsyncTask<Call<(Arc<Handle>, u64, String)>> {
+        let (handle, id) = (self.handle.clone(), self.id);
+        AsyncTask::new(Call {
+            work: Some(Box::new(move || {
+                let (model, temperature) = match options {
+                    Some(options) => (options.model, options.temperature),
+                    None => (None, None),
+                };
+                let model = opt_cstring(model.as_deref())?;
+                let mut branch = 0;
+                // SAFETY: the handle is alive and every pointer is valid for the call
+                let reply = call_for_string(|out| unsafe {
+                    ffi::zc_session_branch(
+                        handle.0,
+                        id,
+                        message_index.into(),
+                        ptr_or_null(&model),
+                        temperature.unwrap_or(0.0),
+                        &mut branch,
+                        out,
+                    )
+                })?;
+                Ok((handle, branch, reply))
+            })),
+            finish: |env, (handle, id, reply)| {
+                let mut result = env.create_object()?;
+                result.set_named_property("session", Session { handle, id }.into_instance(env)?)?;
+                result.set_named_property("reply", env.create_string(&reply)?)?;
+                Ok(result.into_unknown())
+            },
+            failure: None,
+        })
+    }
+
+    /// Drop the session's history.
+    #[napi]
+    pub fn close(&self) -> Result<()> {
+        // SAFETY: the handle is alive
+        check(unsafe { ffi::zc_session_destroy(self.handle.0, self.id) })
+            .map_err(|failure| Error::from_reason(failure.message))
+    }
+}
+
+/// The agent's long-term memory (`[memory] backend`).
+#[napi]
+pub struct Memory {
+    handle: Arc<Handle>,
+}
+
+#[napi]
+impl Memory {
+    /// Save `content` under `key`. `category` is core (the default), daily,
+    /// conversation or a custom name.
+    #[napi(ts_return_type = "Promise<void>")]
+    pub fn store(
+        &self,
+        key: String,
+        content: String,
+        category: Option<String>,
+    ) -> AsyncTask<Call<()>> {
+        let handle = self.handle.clone();
+        call(move || {
+            let key = cstring(&key)?;
+            let content = cstring(&content)?;
+            let category = opt_cstring(category.as_deref())?;
+            // SAFETY: the handle is alive and every pointer is valid for the call
+            check(unsafe {
+                ffi::zc_memory_store(
+                    handle.0,
+                    key.as_ptr(),
+                    content.as_ptr(),
+                    ptr_or_null(&category),
+                )
+            })
+        })
+    }
+
+    /// Entries matching `query`, best first.
+    #[napi(
+        ts_return_type = "Promise<Array<{ id: string; key: string; content: string; category: string; timestamp: string; score: number | null }>>"
+    )]
+    pub fn recall(&self, query: String, limit: Option<u32>) -> AsyncTask<Call<serde_json::Value>> {
+        let handle = self.handle.clone();
+        call(move || {
+            let query = cstring(&query)?;
+            // SAFETY: the handle is alive and every pointer is valid for the call
+            call_for_json(|out| unsafe {
+                ffi::zc_memory_recall(handle.0, query.as_ptr(), limit.unwrap_or(5), out)
+            })
+        })
+    }
+
+    /// Delete the entry under `key`; resolves to whether there was one.
+    #[napi(ts_return_type = "Promise<boolean>")]
+    pub fn forget(&self, key: String) -> AsyncTask<Call<bool>> {
+        let handle = self.handle.clone();
+        call(move || {
+            let key = cstring(&key)?;
+            let mut removed = false;
+            // SAFETY: the handle is alive and every pointer is valid for the call
+            check(unsafe { ffi::zc_memory_forget(handle.0, key.as_ptr(), &mut removed) })?;
+            Ok(removed)
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<number>")]
+    pub fn count(&self) -> AsyncTask<Call<u64>> {
+        let handle = self.handle.clone();
+        call(move || {
+            let mut count = 0;
+            // SAFETY: the handle is alive
+            check(unsafe { ffi::zc_memory_count(handle.0, &mut count) })?;
+            Ok(count)
+        })
+    }
+}
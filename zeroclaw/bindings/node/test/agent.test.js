@@ -0,0 +1,74 @@
+// Runs against the mock provider, so no API key is needed. Build the module
+// first (`npm run build:debug`).
+'use strict'
+
+const test = require('node:test')
+const assert = require('node:assert')
+const fs = require('fs')
+const os = require('os')
+const path = require('path')
+
+const workspace = fs.mkdtempSync(path.join(os.tmpdir(), 'zeroclaw-node-'))
+const fixtures = path.join(workspace, 'fixtures')
+fs.mkdirSync(fixtures)
+process.env.ZEROCLAW_MOCK_DIR = fixtures
+
+const { Agent } = require('..')
+
+const agent = new Agent(
+  { default_provider: 'mock', memory: { backend: 'sqlite', auto_save: false } },
+  { workspaceDir: workspace }
+)
+
+test('memory round trip', async () => {
+  await agent.memory.store('editor', 'prefers tabs')
+  const [entry] = await agent.memory.recall('tabs')
+  assert.strictEqual(entry.key, 'editor')
+  assert.strictEqual(await agent.memory.count(), 1)
+  assert.strictEqual(await agent.memory.forget('editor'), true)
+  assert.strictEqual(await agent.memory.forget('editor'), false)
+})
+
+test('tools run without a model', async () => {
+  fs.writeFileSync(path.join(workspace, 'notes.txt'), 'hello from node')
+  assert.ok(agent.tools().some((tool) => tool.name === 'file_read'))
+  const read = await agent.executeTool('file_read', { path: 'notes.txt' })
+  assert.deepStrictEqual(read, { success: true, output: 'hello from node', error: null })
+  const missing = await agent.executeTool('file_read', { path: 'missing.txt' })
+  assert.strictEqual(missing.success, false)
+})
+
+// Run a turn; when the mock provider names a fixture it lacks, write it with
+// `response` and run the turn again
+async function withFixture(turn, response) {
+  const err = await turn().catch((e) => e)
+  if (!(err instanceof Error)) return err
+  assert.strictEqual(err.code, 'ZC_PROVIDER_ERROR')
+  const fixture = err.message.match(/No mock fixture (\S+\.json)/)[1]
+  fs.writeFileSync(fixture, JSON.stringify({ response }))
+  return turn()
+}
+
+test('failures carry the result code, and turns stream', async () => {
+  const err = await agent.run('Say hi', { provider: 'mock:' + path.join(workspace, 'empty') }).catch((e) => e)
+  assert.strictEqual(err.code, 'ZC_PROVIDER_ERROR')
+
+  const deltas = []
+  const answer = await withFixture(
+    () => agent.stream('Say hi', (delta) => deltas.push(delta)),
+    'Hi there!'
+  )
+  assert.strictEqual(answer, 'Hi there!')
+  assert.strictEqual(deltas.join(''), 'Hi there!')
+
+  const session = agent.session()
+  assert.strictEqual(await withFixture(() => session.send('Remember me'), 'Noted.'), 'Noted.')
+  session.close()
+})
+
+test('sessions open and close', async () => {
+  const session = agent.session()
+  assert.ok(session.id > 0)
+  session.close()
+  assert.throws(() => session.close())
+})
@@ -0,0 +1,358 @@
+"""Python bindings for ZeroClaw, over the C FFI in libzeroclaw.
+
+    from zeroclaw import AgentRuntime
+
+    with AgentRuntime({"default_provider": "openrouter", "api_key": "..."}) as agent:
+        print(agent.run("Summarise README.md"))
+        chat = agent.session()
+        chat.send("Remember that I prefer tabs")
+        agent.memory.store("editor", "prefers tabs")
+
+Every blocking method has an `a`-prefixed coroutine twin (`arun`, `asend`,
+`arecall`, ...) that runs the call on a worker thread, so event loops keep
+going while the agent thinks. libzeroclaw drops the GIL for the whole call.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import ctypes
+import json
+from typing import Any
+
+from . import _native
+from ._native import (
+    BudgetExhaustedError,
+    ContextTooLongError,
+    ProviderError,
+    RateLimitedError,
+    ToolDeniedError,
+    ZeroClawError,
+)
+
+__all__ = [
+    "AgentRuntime",
+    "Session",
+    "Memory",
+    "BudgetExhaustedError",
+    "ContextTooLongError",
+    "ProviderError",
+    "RateLimitedError",
+    "ToolDeniedError",
+    "ZeroClawError",
+    "configure_runtime",
+    "version",
+]
+
+_lib = _native.lib
+
+
+def version() -> str:
+    """Version of the loaded libzeroclaw."""
+    return _lib.zc_version().decode("utf-8")
+
+
+def configure_runtime(threads: int) -> None:
+    """Set the worker threads of the runtime shared by agent calls
+    (0 = one per core). Only works before the first agent call."""
+    _native.check(_lib.zc_runtime_configure(threads))
+
+
+def _call_for_string(fn, *args) -> str | None:
+    out = ctypes.c_void_p()
+    code = fn(*args, ctypes.byref(out))
+    text = _native.take_string(out)
+    _native.check(code)
+    return text
+
+
+def _call_for_json(fn, *args) -> Any:
+    return json.loads(_call_for_string(fn, *args) or "null")
+
+
+class AgentRuntime:
+    """An agent with its config, tools and memory.
+
+    `config` is the same JSON object `zc_agent_init` takes (`api_key`,
+    `default_provider`, `default_model`, `default_temperature`,
+    `workspace_dir`, `memory`, `autonomy`, ...); leave it out to load
+    `~/.zeroclaw/config.toml`. `profile` applies a config profile.
+
+    Calls may run from several threads at once, but `close()` must wait
+    until they have returned.
+    """
+
+    def __init__(
+        self,
+        config: dict[str, Any] | None = None,
+        workspace_dir: str | None = None,
+        profile: str | None = None,
+    ):
+        handle = _native.handle_t()
+        config_json = None if config is None else json.dumps(config)
+        _native.check(
+            _lib.zc_agent_init_profile(
+                _native.encode(config_json),
+                _native.encode(workspace_dir),
+                _native.encode(profile),
+                ctypes.byref(handle),
+            )
+        )
+        self._handle = handle
+        self.memory = Memory(self)
+
+    @property
+    def handle(self) -> _native.handle_t:
+        if not self._handle:
+            raise ZeroClawError(_native.NOT_INITIALIZED, "The agent runtime is closed")
+        return self._handle
+
+    def close(self) -> None:
+        """Free the runtime. Sessions opened on it go with it."""
+        if self._handle:
+            _lib.zc_agent_shutdown(self._handle)
+            self._handle = _native.handle_t()
+
+    def __enter__(self) -> AgentRuntime:
+        return self
+
+    def __exit__(self, *exc) -> None:
+        self.close()
+
+    def __del__(self) -> None:
+        if getattr(self, "_handle", None):
+            self.close()
+
+    # ── One-shot turns ──────────────────────────────────────────────
+
+    def run(
+        self,
+        message: str,
+        *,
+        provider: str | None = None,
+        model: str | None = None,
+        temperature: float = 0.0,
+        max_tokens: int = 0,
+        max_tool_calls: int = 0,
+        max_seconds: int = 0,
+        max_iterations: int = 0,
+    ) -> str:
+        """Answer `message` in a fresh conversation, running tools as needed.
+
+        `provider`, `model` and `temperature` (0.0) default to the config.
+        The `max_*` caps (0 = none) can only lower the model profile's; when
+        one is hit this raises `BudgetExhaustedError` carrying the partial
+        answer.
+        """
+        out = ctypes.c_void_p()
+        budget = _native.TurnBudget(
+            max_tokens=max_tokens,
+            max_tool_calls=max_tool_calls,
+            max_seconds=max_seconds,
+            max_iterations=max_iterations,
+            return_partial=True,
+        )
+        code = _lib.zc_agent_run_single_budget(
+            self.handle,
+            _native.encode(message),
+            _native.encode(provider),
+            _native.encode(model),
+            temperature,
+            ctypes.byref(budget),
+            ctypes.byref(out),
+        )
+        answer = _native.take_string(out)
+        if code == _native.BUDGET_EXHAUSTED:
+            raise BudgetExhaustedError(
+                _native.last_error(code),
+                partial=answer,
+                limit=_native.BUDGET_LIMITS.get(budget.exhausted),
+            )
+        _native.check(code)
+        return answer or ""
+
+    async def arun(self, message: str, **kwargs) -> str:
+        return await asyncio.to_thread(self.run, message, **kwargs)
+
+    # ── Sessions ────────────────────────────────────────────────────
+
+    def session(self) -> Session:
+        """Start a conversation that keeps its history between messages."""
+        session_id = ctypes.c_uint64()
+        _native.check(_lib.zc_session_create(self.handle, ctypes.byref(session_id)))
+        return Session(self, session_id.value)
+
+    async def asession(self) -> Session:
+        return await asyncio.to_thread(self.session)
+
+    # ── Tools ───────────────────────────────────────────────────────
+
+    def tools(self) -> list[dict[str, Any]]:
+        """The registered tools: `name`, `description` and `parameters`
+        (a JSON schema)."""
+        return _call_for_json(_lib.zc_tool_list, self.handle)
+
+    def execute_tool(self, name: str, args: dict[str, Any] | None = None) -> dict[str, Any]:
+        """Run one tool directly, under the same security policy the agent
+        uses. Returns `{"success", "output", "error"}`; a tool that ran but
+        failed comes back with `success` false instead of raising."""
+        out = ctypes.c_void_p()
+        code = _lib.zc_tool_execute(
+            self.handle,
+            _native.encode(name),
+            _native.encode(json.dumps(args or {})),
+            ctypes.byref(out),
+        )
+        result = _native.take_string(out)
+        if result is None:
+            _native.check(code)
+        return json.loads(result)
+
+    async def aexecute_tool(
+        self, name: str, args: dict[str, Any] | None = None
+    ) -> dict[str, Any]:
+        return await asyncio.to_thread(self.execute_tool, name, args)
+
+    def stats(self) -> Any:
+        """Per-tool invocation counts, failures and latency."""
+        return _call_for_json(_lib.zc_agent_get_stats, self.handle)
+
+    def feedback_stats(self) -> Any:
+        """Feedback totals, approval rate and the latest ratings."""
+        return _call_for_json(_lib.zc_feedback_stats, self.handle)
+
+
+class Session:
+    """A conversation on an `AgentRuntime`. Messages sent to one session
+    run one after the other; separate sessions run in parallel."""
+
+    def __init__(self, agent: AgentRuntime, session_id: int):
+        self.agent = agent
+        self.id = session_id
+
+    def send(self, message: str) -> str:
+        """Send `message` and return the reply. A failed turn leaves the
+        history as it was."""
+        return _call_for_string(
+            _lib.zc_session_send, self.agent.handle, self.id, _native.encode(message)
+        ) or ""
+
+    async def asend(self, message: str) -> str:
+        return await asyncio.to_thread(self.send, message)
+
+    def branch(
+        self, message_index: int, *, model: str | None = None, temperature: float = 0.0
+    ) -> tuple[Session, str]:
+        """Fork at `message_index` (0 = the first message sent, 1 its reply,
+        ...) and rerun that turn, optionally with another model or
+        temperature. Returns the new session and its reply; this one is
+        left as it was."""
+        branch_id = ctypes.c_uint64()
+        reply = _call_for_string(
+            _lib.zc_session_branch,
+            self.agent.handle,
+            self.id,
+            message_index,
+            _native.encode(model),
+            temperature,
+            ctypes.byref(branch_id),
+        )
+        return Session(self.agent, branch_id.value), reply or ""
+
+    async def abranch(self, message_index: int, **kwargs) -> tuple[Session, str]:
+        return await asyncio.to_thread(self.branch, message_index, **kwargs)
+
+    def rate(self, message_index: int, up: bool, comment: str | None = None) -> None:
+        """Thumbs up or down on the answer at `message_index`."""
+        _native.check(
+            _lib.zc_feedback_record(
+                self.agent.handle,
+                _native.encode(f"ffi-{self.id}"),
+                message_index,
+                1 if up else -1,
+                _native.encode(comment),
+            )
+        )
+
+    def close(self) -> None:
+        """Drop the session's history."""
+        _native.check(_lib.zc_session_destroy(self.agent.handle, self.id))
+
+    def __enter__(self) -> Session:
+        return self
+
+    def __exit__(self, *exc) -> None:
+        self.close()
+
+
+class Memory:
+    """The agent's long-term memory (`[memory] backend`)."""
+
+    def __init__(self, agent: AgentRuntime):
+        self._agent = agent
+
+    def store(self, key: str, content: str, category: str = "core") -> None:
+        """Save `content` under `key`. `category` is core, daily,
+        conversation or a custom name."""
+        _native.check(
+            _lib.zc_memory_store(
+                self._agent.handle,
+                _native.encode(key),
+                _native.encode(content),
+                _native.encode(category),
+            )
+        )
+
+    def recall(self, query: str, limit: int = 5) -> list[dict[str, Any]]:
+        """Entries matching `query`, best first, each with `key`,
+        `content`, `category`, `timestamp` and `score`."""
+        return _call_for_json(
+            _lib.zc_memory_recall, self._agent.handle, _native.encode(query), limit
+        )
+
+    def forget(self, key: str) -> bool:
+        """Delete the entry under `key`; whether there was one."""
+        removed = ctypes.c_bool()
+        _native.check(
+            _lib.zc_memory_forget(
+                self._agent.handle, _native.encode(key), ctypes.byref(removed)
+            )
+        )
+        return removed.value
+
+    def count(self) -> int:
+        count = ctypes.c_uint64()
+        _native.check(_lib.zc_memory_count(self._agent.handle, ctypes.byref(count)))
+        return count.value
+
+    def browse(self, **filters: Any) -> dict[str, Any]:
+        """One page of stored entries, newest first. Filters: `category`,
+        `namespace`, `since`, `until`, `key_prefix`, `offset`, `limit`.
+        Returns `total`, `next_offset`, `entries` and the `namespaces`."""
+        return _call_for_json(
+            _lib.zc_memory_browse,
+            self._agent.handle,
+            _native.encode(json.dumps(filters)),
+        )
+
+    def forget_matching(self, **filters: Any) -> list[str]:
+        """Delete every entry the filters match (as for `browse`, paging
+        aside; at least one is required); the keys removed."""
+        result = _call_for_json(
+            _lib.zc_memory_forget_matching,
+            self._agent.handle,
+            _native.encode(json.dumps(filters)),
+        )
+        return result["removed"]
+
+    async def astore(self, key: str, content: str, category: str = "core") -> None:
+        await asyncio.to_thread(self.store, key, content, category)
+
+    async def arecall(self, query: str, limit: int = 5) -> list[dict[str, Any]]:
+        return await asyncio.to_thread(self.recall, query, limit)
+
+    async def aforget(self, key: str) -> bool:
+        return await asyncio.to_thread(self.forget, key)
+
+    async def acount(self) -> int:
+        return await asyncio.to_thread(self.count)
@@ -0,0 +1,222 @@
+"""Loading libzeroclaw and declaring the C functions the wrapper calls.
+
+Every prototype here mirrors a declaration in `include/zeroclaw.h`;
+keep the two in step (and bump ABI_VERSION) when the FFI changes.
+"""
+
+from __future__ import annotations
+
+import ctypes
+import ctypes.util
+import os
+import sys
+from pathlib import Path
+
+# ZcResult
+OK = 0
+ERROR = -1
+INVALID_ARG = -2
+NOT_INITIALIZED = -3
+OUT_OF_MEMORY = -4
+BUDGET_EXHAUSTED = -5
+PROVIDER_ERROR = -6
+TOOL_DENIED = -7
+RATE_LIMITED = -8
+CONTEXT_TOO_LONG = -9
+BIND_ERROR = -10
+
+# ZcBudgetLimit
+BUDGET_LIMITS = {0: None, 1: "tokens", 2: "tool_calls", 3: "seconds", 4: "iterations"}
+
+
+class TurnBudget(ctypes.Structure):
+    """ZcTurnBudget: per-request caps (0 = none), read back for `exhausted`."""
+
+    _fields_ = [
+        ("max_tokens", ctypes.c_uint64),
+        ("max_tool_calls", ctypes.c_uint32),
+        ("max_seconds", ctypes.c_uint64),
+        ("max_iterations", ctypes.c_uint32),
+        ("return_partial", ctypes.c_bool),
+        ("exhausted", ctypes.c_int),
+    ]
+
+# ZC_ABI_VERSION of the header these prototypes follow (major << 16 | minor)
+ABI_VERSION = 1 << 16 | 0
+
+
+class ZeroClawError(Exception):
+    """A call into libzeroclaw failed. `code` is the `ZcResult` it returned."""
+
+    def __init__(self, code: int, message: str):
+        super().__init__(message)
+        self.code = code
+
+
+class BudgetExhaustedError(ZeroClawError):
+    """The turn hit a budget cap. `partial` holds the best answer so far and
+    `limit` names the cap ("tokens", "tool_calls", "seconds" or "iterations")."""
+
+    def __init__(self, message: str, partial: str | None = None, limit: str | None = None):
+        super().__init__(BUDGET_EXHAUSTED, message)
+        self.partial = partial
+        self.limit = limit
+
+
+class ProviderError(ZeroClawError):
+    """The model provider failed after retries and fallbacks."""
+
+
+class ToolDeniedError(ZeroClawError):
+    """The security policy refused the action."""
+
+
+class RateLimitedError(ZeroClawError):
+    """A provider or the action budget is rate limiting."""
+
+
+class ContextTooLongError(ZeroClawError):
+    """The conversation no longer fits the model's context window."""
+
+
+_ERRORS = {
+    PROVIDER_ERROR: ProviderError,
+    TOOL_DENIED: ToolDeniedError,
+    RATE_LIMITED: RateLimitedError,
+    CONTEXT_TOO_LONG: ContextTooLongError,
+}
+
+_LIB_NAMES = {
+    "darwin": "libzeroclaw.dylib",
+    "win32": "zeroclaw.dll",
+}
+
+
+def _candidates():
+    """Where to look for the shared library, most specific first."""
+    explicit = os.environ.get("ZEROCLAW_LIB")
+    if explicit:
+        yield Path(explicit)
+    name = _LIB_NAMES.get(sys.platform, "libzeroclaw.so")
+    here = Path(__file__).resolve().parent
+    yield here / name
+    # A checkout: bindings/python/zeroclaw -> target/{release,debug}
+    crate = here.parents[2]
+    yield crate / "target" / "release" / name
+    yield crate / "target" / "debug" / name
+    found = ctypes.util.find_library("zeroclaw")
+    if found:
+        yield Path(found)
+
+
+def _load() -> ctypes.CDLL:
+    tried = []
+    for path in _candidates():
+        if path.name == str(path) or path.exists():
+            try:
+                return ctypes.CDLL(str(path))
+            except OSError as e:
+                tried.append(f"{path}: {e}")
+        else:
+            tried.append(f"{path}: not found")
+    raise ImportError(
+        "Could not load libzeroclaw; build it with `cargo build --release` "
+        "or point ZEROCLAW_LIB at it.\n  " + "\n  ".join(tried)
+    )
+
+
+lib = _load()
+if not hasattr(lib, "zc_abi_check") or lib.zc_abi_check(ctypes.c_uint32(ABI_VERSION)) != OK:
+    raise ImportError(
+        "libzeroclaw does not provide the C ABI these bindings were written for; "
+        "use bindings from the same release"
+    )
+
+c_str = ctypes.c_char_p
+out_str = ctypes.POINTER(ctypes.c_void_p)
+handle_t = ctypes.c_void_p
+
+
+def _declare(name, argtypes, restype=ctypes.c_int):
+    fn = getattr(lib, name)
+    fn.argtypes = argtypes
+    fn.restype = restype
+
+
+_declare("zc_last_error_message", [], ctypes.c_char_p)
+_declare("zc_version", [], ctypes.c_char_p)
+_declare("zc_free_string", [ctypes.c_void_p], None)
+_declare("zc_runtime_configure", [ctypes.c_uint32])
+_declare("zc_agent_init_profile", [c_str, c_str, c_str, ctypes.POINTER(handle_t)])
+_declare("zc_agent_shutdown", [handle_t], None)
+_declare(
+    "zc_agent_run_single_budget",
+    [
+        handle_t,
+        c_str,
+        c_str,
+        c_str,
+        ctypes.c_double,
+        ctypes.POINTER(TurnBudget),
+        out_str,
+    ],
+)
+_declare("zc_agent_get_stats", [handle_t, out_str])
+_declare(
+    "zc_feedback_record",
+    [handle_t, c_str, ctypes.c_uint64, ctypes.c_int32, c_str],
+)
+_declare("zc_feedback_stats", [handle_t, out_str])
+_declare("zc_session_create", [handle_t, ctypes.POINTER(ctypes.c_uint64)])
+_declare("zc_session_send", [handle_t, ctypes.c_uint64, c_str, out_str])
+_declare(
+    "zc_session_branch",
+    [
+        handle_t,
+        ctypes.c_uint64,
+        ctypes.c_uint64,
+        c_str,
+        ctypes.c_double,
+        ctypes.POINTER(ctypes.c_uint64),
+        out_str,
+    ],
+)
+_declare("zc_session_destroy", [handle_t, ctypes.c_uint64])
+_declare("zc_tool_list", [handle_t, out_str])
+_declare("zc_tool_execute", [handle_t, c_str, c_str, out_str])
+_declare("zc_memory_store", [handle_t, c_str, c_str, c_str])
+_declare("zc_memory_recall", [handle_t, c_str, ctypes.c_uint32, out_str])
+_declare("zc_memory_forget", [handle_t, c_str, ctypes.POINTER(ctypes.c_bool)])
+_declare("zc_memory_count", [handle_t, ctypes.POINTER(ctypes.c_uint64)])
+_declare("zc_memory_browse", [handle_t, c_str, out_str])
+_declare("zc_memory_forget_matching", [handle_t, c_str, out_str])
+
+
+def encode(text: str | None) -> bytes | None:
+    return None if text is None else text.encode("utf-8")
+
+
+def take_string(ptr: ctypes.c_void_p) -> str | None:
+    """Copy a string returned through an out-parameter and free it."""
+    if not ptr.value:
+        return None
+    try:
+        return ctypes.string_at(ptr.value).decode("utf-8")
+    finally:
+        lib.zc_free_string(ptr.value)
+
+
+def last_error(code: int) -> str:
+    message = lib.zc_last_error_message()
+    return message.decode("utf-8", "replace") if message else f"libzeroclaw returned {code}"
+
+
+def check(code: int) -> None:
+    """Raise for a failed `ZcResult`. Call it on the thread that made the
+    call: the error message is kept per thread."""
+    if code == OK:
+        return
+    message = last_error(code)
+    if code == BUDGET_EXHAUSTED:
+        raise BudgetExhaustedError(message)
+    raise _ERRORS.get(code, ZeroClawError)(code, message)
@@ -0,0 +1,65 @@
+/// Prototypes of what a tool plugin exports, for zeroclaw_tool.h; the loader
+/// in src/tools/plugin.rs looks them up by these names.
+const TOOL_PLUGIN_EXPORTS: &str = r#"#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Describe the tool. Called once, when the plugin is loaded.
+const ZcToolDescriptor *zc_tool_descriptor(void);
+
+// Run the tool with its arguments as a JSON object, writing the result
+// through `output(sink, text)`. Return 0 on success, anything else on
+// failure (the output is then the error message). Called on a worker
+// thread, possibly several at once.
+int32_t zc_tool_execute(const char *args_json, ZcToolOutputFn output, void *sink);
+
+#ifdef __cplusplus
+}
+#endif"#;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/zeroclaw.proto");
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+        tonic_prost_build::compile_protos("proto/zeroclaw.proto")?;
+    }
+
+    println!("cargo:rerun-if-env-changed=ZEROCLAW_UPDATE_HEADERS");
+    write_header("src/ffi/mod.rs", "zeroclaw.h", |_| {})?;
+    write_header("src/tools/plugin.rs", "zeroclaw_tool.h", |config| {
+        // The exports go in the trailer, which would land after an include guard
+        config.include_guard = None;
+        config.pragma_once = true;
+        config.export.include = vec!["ZcToolDescriptor".into(), "ZcToolOutputFn".into()];
+        config.trailer = Some(TOOL_PLUGIN_EXPORTS.into());
+    })
+}
+
+/// Generate the C header `name` from the Rust module `src` with cbindgen,
+/// into `OUT_DIR`. With `ZEROCLAW_UPDATE_HEADERS` set it is also written to
+/// `include/`; plain builds leave the source tree alone.
+fn write_header(
+    src: &str,
+    name: &str,
+    customize: impl FnOnce(&mut cbindgen::Config),
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed={src}");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let mut config = cbindgen::Config::from_file("cbindgen.toml")?;
+    config.after_includes = Some(format!(
+        "#define ZEROCLAW_VERSION \"{}\"",
+        env!("CARGO_PKG_VERSION")
+    ));
+    customize(&mut config);
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(src)
+        .generate()?;
+    bindings.write_to_file(std::path::Path::new(&std::env::var("OUT_DIR")?).join(name));
+    if std::env::var_os("ZEROCLAW_UPDATE_HEADERS").is_some() {
+        // Only rewritten when the interface changed, so C builds see a stable mtime
+        bindings.write_to_file(std::path::Path::new("include").join(name));
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+// Example: a native tool plugin for ZeroClaw
+//
+// Build it as a shared library into the plugins directory and enable
+// `[plugins]` in config.toml:
+//
+//     cc -shared -fPIC -I include examples/tool_plugin.c -o ~/.cclaw/plugins/word_count.so
+//
+// Any language that can export C functions works the same way (C++ with
+// extern "C", Go with cgo's //export, Zig, ...).
+
+#include <ctype.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "zeroclaw_tool.h"
+
+static const ZcToolDescriptor DESCRIPTOR = {
+    .abi_version = ZC_TOOL_ABI_VERSION,
+    .name = "word_count",
+    .description = "Count the words in a piece of text",
+    .parameters_schema = "{\"type\": \"object\", "
+                         "\"properties\": {\"text\": {\"type\": \"string\"}}, "
+                         "\"required\": [\"text\"]}",
+};
+
+const ZcToolDescriptor *zc_tool_descriptor(void) { return &DESCRIPTOR; }
+
+int32_t zc_tool_execute(const char *args_json, ZcToolOutputFn output, void *sink) {
+    // A real plugin would use a JSON parser; this finds "text": "..."
+    const char *text = strstr(args_json, "\"text\":");
+    if (text == NULL || (text = strchr(text + 7, '"')) == NULL) {
+        output(sink, "Missing \"text\" argument");
+        return 1;
+    }
+
+    unsigned words = 0;
+    int in_word = 0;
+    for (const char *c = text + 1; *c != '\0' && *c != '"'; c++) {
+        if (isspace((unsigned char)*c)) {
+            in_word = 0;
+        } else if (!in_word) {
+            in_word = 1;
+            words++;
+        }
+    }
+
+    char reply[32];
+    snprintf(reply, sizeof reply, "%u words", words);
+    output(sink, reply);
+    return 0;
+}
@@ -0,0 +1,573 @@
+// SPDX-License-Identifier: MIT
+
+#ifndef ZEROCLAW_H
+#define ZEROCLAW_H
+
+// Generated by build.rs with cbindgen; do not edit.
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#define ZEROCLAW_VERSION "1.0.8"
+
+// Bumped when a function, type or constant of this interface changes or
+// goes away; a header with another major version cannot talk to this library.
+#define ZC_ABI_VERSION_MAJOR 1
+
+// Bumped when functions or values are added; newer libraries keep serving
+// headers with a lower minor version.
+#define ZC_ABI_VERSION_MINOR 0
+
+// The ABI version as `major << 16 | minor`, what `zc_abi_version` returns.
+#define ZC_ABI_VERSION ((ZC_ABI_VERSION_MAJOR << 16) | ZC_ABI_VERSION_MINOR)
+
+// Result codes
+typedef enum {
+  ZC_RESULT_OK = 0,
+  ZC_RESULT_ERROR = -1,
+  ZC_RESULT_INVALID_ARG = -2,
+  ZC_RESULT_NOT_INITIALIZED = -3,
+  ZC_RESULT_OUT_OF_MEMORY = -4,
+  // The request hit one of its turn budget caps
+  ZC_RESULT_BUDGET_EXHAUSTED = -5,
+  // The model provider failed after retries and fallbacks
+  ZC_RESULT_PROVIDER_ERROR = -6,
+  // The security policy refused the action
+  ZC_RESULT_TOOL_DENIED = -7,
+  // A provider or the action budget is rate limiting
+  ZC_RESULT_RATE_LIMITED = -8,
+  // The conversation no longer fits the model's context window
+  ZC_RESULT_CONTEXT_TOO_LONG = -9,
+  // The daemon's gateway could not listen on the requested address
+  ZC_RESULT_BIND_ERROR = -10,
+} ZcResult;
+
+// Which turn budget cap stopped a request, for `zc_agent_run_single_budget`.
+typedef enum {
+  ZC_BUDGET_LIMIT_NONE = 0,
+  ZC_BUDGET_LIMIT_TOKENS = 1,
+  ZC_BUDGET_LIMIT_TOOL_CALLS = 2,
+  ZC_BUDGET_LIMIT_SECONDS = 3,
+  ZC_BUDGET_LIMIT_ITERATIONS = 4,
+} ZcBudgetLimit;
+
+// Opaque handle to agent runtime
+typedef struct ZcAgentRuntime ZcAgentRuntime;
+
+// Callback answering `ask_user` questions on behalf of the embedding app,
+// which with `[autonomy] approve_file_changes` also get file change
+// approvals: the diff, answered with "approve" or "reject". It must write a NUL-terminated answer of at most `answer_len` bytes into
+// `answer` and return true, or return false when the user gives no answer.
+// Called on a worker thread while the agent turn is paused.
+typedef bool (*ZcAskUserFn)(void *user_data, const char *question, char *answer, size_t answer_len);
+
+// Per-request caps for `zc_agent_run_single_budget` (0 = no cap of its
+// own). Caps can only lower the model profile's defaults.
+typedef struct {
+  uint64_t max_tokens;
+  uint32_t max_tool_calls;
+  uint64_t max_seconds;
+  // Model calls in the turn
+  uint32_t max_iterations;
+  // When a cap stops the turn, still hand back the best partial answer
+  bool return_partial;
+  // Set on return to the cap that stopped the turn, `None` otherwise
+  ZcBudgetLimit exhausted;
+} ZcTurnBudget;
+
+// Callback receiving a turn's text as the model writes it, for the
+// `*_stream` calls. `delta` is NUL-terminated UTF-8, valid only during the
+// call. Called on a worker thread while the turn runs, so it should return
+// quickly.
+typedef void (*ZcDeltaFn)(void *user_data, const char *delta);
+
+// Callback handed the URL of a sign-in page, to open in a browser or show
+// to the user. Called once, on the calling thread, before the sign-in
+// waits for the browser.
+typedef void (*ZcOpenUrlFn)(void *user_data, const char *url);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Message describing the last failed call on this thread, or NULL if none
+// has failed. The string stays valid until the next failing call on the
+// same thread; do not free it.
+const char *zc_last_error_message(void);
+
+// Set the worker thread count of the runtime shared by agent calls
+// (0 = one per core). Takes effect only before the first call that needs
+// the runtime; returns `Error` once it exists.
+ZcResult zc_runtime_configure(uint32_t threads);
+
+// Initialize ZeroClaw agent runtime
+//
+// # Safety
+// Caller must ensure config_json is a valid null-terminated UTF-8 string or NULL
+ZcResult zc_agent_init(const char *config_json,
+                       const char *workspace_dir,
+                       ZcAgentRuntime **out_handle);
+
+// Initialize the agent runtime with a config profile applied. A NULL
+// `profile` uses `ZEROCLAW_PROFILE`, if set. Profiles come from the
+// "profiles" key of `config_json`, or else from the config file.
+//
+// # Safety
+// Caller must ensure `config_json`, `workspace_dir` and `profile` are
+// valid null-terminated UTF-8 strings or NULL
+ZcResult zc_agent_init_profile(const char *config_json,
+                               const char *workspace_dir,
+                               const char *profile,
+                               ZcAgentRuntime **out_handle);
+
+// Register (or with a NULL callback, remove) the handler for the `ask_user`
+// tool. Without one, the agent cannot ask clarifying questions in
+// `zc_agent_run_single`.
+//
+// # Safety
+// Caller must ensure handle is valid, and that `callback` and `user_data`
+// stay usable from any thread until replaced or the runtime is shut down
+ZcResult zc_agent_set_ask_user(ZcAgentRuntime *handle, ZcAskUserFn callback, void *user_data);
+
+// Shutdown and free agent runtime. Sessions still open are closed, and
+// summarized first with `[memory.summary]` enabled.
+//
+// # Safety
+// Caller must ensure handle is a valid pointer returned by zc_agent_init
+void zc_agent_shutdown(ZcAgentRuntime *handle);
+
+// Run single message through agent with proper tool support
+//
+// # Safety
+// Caller must ensure handle is valid, message is null-terminated UTF-8, and out_response can be written to
+ZcResult zc_agent_run_single(ZcAgentRuntime *handle,
+                             const char *message,
+                             const char *provider,
+                             const char *model,
+                             double temperature,
+                             char **out_response);
+
+// Like `zc_agent_run_single`, within the caps of `budget` (NULL = none of
+// its own). Returns `BudgetExhausted` when a cap is hit and records it in
+// `budget->exhausted`; with `budget->return_partial` set, `out_response`
+// then holds the best partial answer (free it as usual).
+//
+// # Safety
+// Same requirements as `zc_agent_run_single`; `budget` must be NULL or
+// valid for reads and writes
+ZcResult zc_agent_run_single_budget(ZcAgentRuntime *handle,
+                                    const char *message,
+                                    const char *provider,
+                                    const char *model,
+                                    double temperature,
+                                    ZcTurnBudget *budget,
+                                    char **out_response);
+
+// Run `message` like `zc_agent_run_single`, handing the reply's text to
+// `on_delta` (with `user_data`) while the model writes it, tool calls left
+// out. The text of every model reply in the turn is streamed, including
+// what it says before using a tool; `out_response` gets the final answer,
+// which may differ once verification and guardrails have run.
+//
+// # Safety
+// Same requirements as `zc_agent_run_single`; `on_delta` and `user_data`
+// must be usable from another thread until this returns
+ZcResult zc_agent_run_stream(ZcAgentRuntime *handle,
+                             const char *message,
+                             const char *provider,
+                             const char *model,
+                             double temperature,
+                             ZcDeltaFn on_delta,
+                             void *user_data,
+                             char **out_response);
+
+// Start a conversation whose history is kept between `zc_session_send`
+// calls, so each message costs only its own tokens plus the transcript
+// instead of a fresh context. Skills and tools are fixed at creation.
+//
+// # Safety
+// Caller must ensure handle is valid and `out_session_id` can be written to
+ZcResult zc_session_create(ZcAgentRuntime *handle, uint64_t *out_session_id);
+
+// Send `message` in an open session with the default provider, model and
+// temperature. A failed turn leaves the history as it was. Returns
+// `InvalidArg` for an unknown session id.
+//
+// # Safety
+// Caller must ensure handle is valid, `message` is null-terminated UTF-8,
+// and `out_response` can be written to
+ZcResult zc_session_send(ZcAgentRuntime *handle,
+                         uint64_t session_id,
+                         const char *message,
+                         char **out_response);
+
+// `zc_session_send`, streaming the reply's text to `on_delta` as in
+// `zc_agent_run_stream`.
+//
+// # Safety
+// Same requirements as `zc_session_send`; `on_delta` and `user_data` must
+// be usable from another thread until this returns
+ZcResult zc_session_send_stream(ZcAgentRuntime *handle,
+                                uint64_t session_id,
+                                const char *message,
+                                ZcDeltaFn on_delta,
+                                void *user_data,
+                                char **out_response);
+
+// Fork a session at `message_index` and rerun the turn containing it,
+// optionally with another model or temperature (NULL / 0.0 for the
+// defaults). Messages are indexed as the caller saw them: 0 is the first
+// message sent, 1 its reply, and so on. The branch is a new session whose
+// id goes to `out_session_id`; the original is left as it was. Tool calls
+// from before the fork are not carried over, only the messages and replies.
+//
+// # Safety
+// Caller must ensure handle is valid, `model` is NULL or null-terminated
+// UTF-8, and `out_session_id` and `out_response` can be written to
+ZcResult zc_session_branch(ZcAgentRuntime *handle,
+                           uint64_t session_id,
+                           uint64_t message_index,
+                           const char *model,
+                           double temperature,
+                           uint64_t *out_session_id,
+                           char **out_response);
+
+// Close a session and drop its history, after storing a summary of it
+// when `[memory.summary]` is enabled. Returns `InvalidArg` for an unknown
+// session id.
+//
+// # Safety
+// Caller must ensure handle is valid
+ZcResult zc_session_destroy(ZcAgentRuntime *handle, uint64_t session_id);
+
+// List the agent's tools. `out_json` receives a JSON array of
+// `{name, description, parameters}` with each tool's JSON schema.
+//
+// # Safety
+// Caller must ensure handle is valid and `out_json` can be written to
+ZcResult zc_tool_list(ZcAgentRuntime *handle, char **out_json);
+
+// Run one tool directly, under the same security policy the agent uses.
+// `args_json` is the tool's argument object (NULL = `{}`); `out_json`
+// receives `{success, output, error}` whenever the tool ran. A tool that
+// reports failure returns `ToolDenied`, `RateLimited` or `Error`, and an
+// unknown tool name returns `InvalidArg`.
+//
+// # Safety
+// Caller must ensure handle is valid, `name` and `args_json` (if non-NULL)
+// are null-terminated UTF-8, and `out_json` can be written to
+ZcResult zc_tool_execute(ZcAgentRuntime *handle,
+                         const char *name,
+                         const char *args_json,
+                         char **out_json);
+
+// Usage statistics of the tools run in this agent's workspace, across
+// sessions. `out_json` receives a JSON array of `{tool, invocations,
+// failures, success_rate, mean_latency_ms, output_bytes, last_used}`, most
+// used first.
+//
+// # Safety
+// Caller must ensure handle is valid and `out_json` can be written to
+ZcResult zc_agent_get_stats(ZcAgentRuntime *handle, char **out_json);
+
+// Rate an answer: thumbs up when `rating` is positive, down when negative,
+// with an optional `comment` (NULL for none). `session_id` is a saved
+// session's id, or `ffi-<id>` for a session from `zc_session_create`;
+// `message_index` counts messages as `zc_session_branch` does. Feedback
+// goes to the workspace's `.zeroclaw/feedback.jsonl` as labels for eval
+// and fine-tuning pipelines. Returns `InvalidArg` when the message is not
+// an answer.
+//
+// # Safety
+// Caller must ensure handle is valid, `session_id` is null-terminated
+// UTF-8 and `comment` is NULL or null-terminated UTF-8
+ZcResult zc_feedback_record(ZcAgentRuntime *handle,
+                            const char *session_id,
+                            uint64_t message_index,
+                            int32_t rating,
+                            const char *comment);
+
+// Totals of the feedback given in this agent's workspace. `out_json`
+// receives `{total, up, down, approval_rate, recent}`, with the latest ten
+// entries in `recent`, newest first.
+//
+// # Safety
+// Caller must ensure handle is valid and `out_json` can be written to
+ZcResult zc_feedback_stats(ZcAgentRuntime *handle, char **out_json);
+
+// List the workspace checkpoints taken before tools changed files.
+// `out_json` receives a JSON array of `{id, created_at, label}`, newest
+// first and at most `limit` long (0 = all).
+//
+// # Safety
+// Caller must ensure handle is valid and `out_json` can be written to
+ZcResult zc_agent_list_checkpoints(ZcAgentRuntime *handle, uint32_t limit, char **out_json);
+
+// Put the workspace files back as they were at the newest checkpoint and
+// drop it. `out_json` receives `{checkpoint, files, remaining}`, or `null`
+// when there was nothing to undo.
+//
+// # Safety
+// Caller must ensure handle is valid and `out_json` can be written to
+ZcResult zc_agent_undo_last(ZcAgentRuntime *handle, char **out_json);
+
+// Store `content` under `key` in the agent's memory, replacing any entry
+// with that key. `category` is "core" (default when NULL), "daily",
+// "conversation" or a custom name.
+//
+// # Safety
+// Caller must ensure handle is valid and `key`, `content` and `category`
+// (if non-NULL) are null-terminated UTF-8
+ZcResult zc_memory_store(ZcAgentRuntime *handle,
+                         const char *key,
+                         const char *content,
+                         const char *category);
+
+// Search memory for `query`. `out_json` receives a JSON array of entries
+// (`key`, `content`, `category`, `timestamp`, `score`), best match first.
+// `limit` of 0 returns up to 5.
+//
+// # Safety
+// Caller must ensure handle is valid, `query` is null-terminated UTF-8,
+// and `out_json` can be written to
+ZcResult zc_memory_recall(ZcAgentRuntime *handle,
+                          const char *query,
+                          uint32_t limit,
+                          char **out_json);
+
+// Delete the entry stored under `key`. `out_removed` (may be NULL) is set to
+// whether an entry existed.
+//
+// # Safety
+// Caller must ensure handle is valid, `key` is null-terminated UTF-8, and
+// `out_removed` (if non-NULL) can be written to
+ZcResult zc_memory_forget(ZcAgentRuntime *handle, const char *key, bool *out_removed);
+
+// Number of entries in the agent's memory.
+//
+// # Safety
+// Caller must ensure handle is valid and `out_count` can be written to
+ZcResult zc_memory_count(ZcAgentRuntime *handle, uint64_t *out_count);
+
+// One page of what the agent remembers, like `GET /memory`. `filter_json`
+// (NULL = everything) is an object with any of `category`, `namespace`,
+// `since`, `until` (a date or RFC 3339 time), `key_prefix`, `offset` and
+// `limit` (default 50, at most 500). `out_json` receives
+// `{namespace, total, offset, limit, next_offset, entries, namespaces}`.
+//
+// # Safety
+// Caller must ensure handle is valid, `filter_json` (if non-NULL) is
+// null-terminated UTF-8, and `out_json` can be written to
+ZcResult zc_memory_browse(ZcAgentRuntime *handle, const char *filter_json, char **out_json);
+
+// Forget every memory `filter_json` matches, like `DELETE /memory`; the
+// filter must name a category, dates or a key prefix. `out_json` receives
+// `{count, removed}` with the removed keys.
+//
+// # Safety
+// Caller must ensure handle is valid, `filter_json` is null-terminated
+// UTF-8, and `out_json` can be written to
+ZcResult zc_memory_forget_matching(ZcAgentRuntime *handle,
+                                   const char *filter_json,
+                                   char **out_json);
+
+// Render the prompt template `name` from `~/.zeroclaw/prompts/` with the
+// variables in `vars_json` (a JSON object; NULL = none) and run it like
+// `zc_agent_run_single` with the default provider, model and temperature.
+// Returns `InvalidArg` for an unknown template or a missing variable.
+//
+// # Safety
+// Caller must ensure handle is valid, `name` and `vars_json` (if non-NULL)
+// are null-terminated UTF-8, and `out_response` can be written to
+ZcResult zc_agent_run_template(ZcAgentRuntime *handle,
+                               const char *name,
+                               const char *vars_json,
+                               char **out_response);
+
+// Search the configured skill registry. `query` may be NULL to list every
+// entry; `out_json` receives a JSON array of registry entries.
+//
+// # Safety
+// Caller must ensure handle is valid, `query` (if non-NULL) is a
+// null-terminated UTF-8 string, and `out_json` can be written to
+ZcResult zc_skills_search(ZcAgentRuntime *handle, const char *query, char **out_json);
+
+// Accounts connected through Composio for the configured entity.
+// `out_json` receives a JSON array of `{id, appName, status, createdAt}`.
+//
+// # Safety
+// Caller must ensure handle is valid and `out_json` can be written to
+ZcResult zc_composio_connections(ZcAgentRuntime *handle, char **out_json);
+
+// Start a Composio OAuth flow for `app` (e.g. "github"). `out_url`
+// receives the URL the user must open to finish connecting.
+//
+// # Safety
+// Caller must ensure handle is valid, `app` is a null-terminated UTF-8
+// string, and `out_url` can be written to
+ZcResult zc_composio_connect(ZcAgentRuntime *handle, const char *app, char **out_url);
+
+// Revoke the Composio connection `connection_id`, as listed by
+// `zc_composio_connections`.
+//
+// # Safety
+// Caller must ensure handle is valid and `connection_id` is a
+// null-terminated UTF-8 string
+ZcResult zc_composio_disconnect(ZcAgentRuntime *handle, const char *connection_id);
+
+// Connect `app` through Composio and block until the browser returns to
+// a local callback server, or `timeout_secs` pass (0 = five minutes).
+// `out_connection_id` receives the new connection's id, or an empty
+// string when Composio does not report one.
+//
+// # Safety
+// Caller must ensure handle is valid, `app` is a null-terminated UTF-8
+// string, `open_url` is safe to call with `user_data`, and
+// `out_connection_id` can be written to
+ZcResult zc_composio_connect_wait(ZcAgentRuntime *handle,
+                                  const char *app,
+                                  ZcOpenUrlFn open_url,
+                                  void *user_data,
+                                  uint32_t timeout_secs,
+                                  char **out_connection_id);
+
+// Sign in to the OAuth app configured under `[oauth.<provider>]` (e.g.
+// "github", "google"): `open_url` receives the consent page, and the call
+// blocks until the browser returns to a local callback server or
+// `timeout_secs` pass (0 = five minutes). The tokens are stored encrypted
+// with the secret store; `out_json` receives
+// `{provider, scope, expires_at, refreshable}`.
+//
+// # Safety
+// Caller must ensure handle is valid, `provider` is a null-terminated
+// UTF-8 string, `open_url` is safe to call with `user_data`, and
+// `out_json` can be written to
+ZcResult zc_oauth_login(ZcAgentRuntime *handle,
+                        const char *provider,
+                        ZcOpenUrlFn open_url,
+                        void *user_data,
+                        uint32_t timeout_secs,
+                        char **out_json);
+
+// Install the registry package `name` into the workspace. `out_json`
+// receives the installed skill's metadata. Skills are loaded when an agent
+// is initialized, so the new skill is visible to the next `zc_agent_init`.
+//
+// # Safety
+// Caller must ensure handle is valid, `name` is a null-terminated UTF-8
+// string, and `out_json` can be written to
+ZcResult zc_skills_install(ZcAgentRuntime *handle, const char *name, char **out_json);
+
+// Run interactive agent loop with proper tool support
+//
+// # Safety
+// Caller must ensure handle is valid
+ZcResult zc_agent_run_interactive(ZcAgentRuntime *handle,
+                                  const char *provider,
+                                  const char *model,
+                                  double temperature);
+
+// Run the full-screen terminal UI (conversation, tool, memory and cost
+// panes) until the user quits. Tool switches from `zc_agent_run_interactive`
+// do not apply here.
+//
+// # Safety
+// Caller must ensure handle is valid and `provider` / `model` (if non-NULL)
+// are null-terminated UTF-8 strings
+ZcResult zc_agent_run_tui(ZcAgentRuntime *handle,
+                          const char *provider,
+                          const char *model,
+                          double temperature);
+
+// Free a string returned by ZeroClaw
+//
+// # Safety
+// Caller must ensure s is a valid pointer returned by ZeroClaw
+void zc_free_string(char *s);
+
+// Estimated tokens in `text` for `model` (NULL for the `cl100k` default),
+// counted with the model family's tokenizer rules.
+//
+// # Safety
+// Caller must ensure `model` (if non-NULL) and `text` are null-terminated
+// UTF-8 strings and `out_count` can be written to
+ZcResult zc_count_tokens(const char *model, const char *text, uint64_t *out_count);
+
+// Check a config before `zc_agent_init`. `config_json` takes the same JSON
+// as `zc_agent_init` (NULL checks the config file it would load instead).
+// `out_warnings_json` receives a JSON array of `{"field", "message"}`
+// objects, empty when nothing was found; free it with `zc_free_string`.
+//
+// # Safety
+// Caller must ensure `config_json` (if non-NULL) is a null-terminated UTF-8
+// string and `out_warnings_json` can be written to
+ZcResult zc_config_validate(const char *config_json, char **out_warnings_json);
+
+// JSON Schema of the config file, for hosts that check or edit configs.
+// Free `out_json` with `zc_free_string`.
+//
+// # Safety
+// Caller must ensure `out_json` can be written to
+ZcResult zc_config_schema(char **out_json);
+
+// Run the guided first-run setup in the terminal: provider, API key
+// (stored encrypted), workspace and autonomy level. The config is checked
+// and written to `~/.zeroclaw/config.toml`, whose path goes to
+// `out_config_path` unless it is NULL (free it with `zc_free_string`).
+//
+// # Safety
+// Caller must ensure `out_config_path` is NULL or can be written to
+ZcResult zc_agent_setup_interactive(char **out_config_path);
+
+// Get ZeroClaw version string
+//
+// # Safety
+// Returns a static string - caller must not free
+const char *zc_version(void);
+
+// ABI version of the loaded library as `major << 16 | minor`. Compare it
+// with the `ZC_ABI_VERSION` of the header you built against, or let
+// `zc_abi_check` do it.
+uint32_t zc_abi_version(void);
+
+// Check that this library serves callers built against ABI version
+// `header_version` (pass `ZC_ABI_VERSION`): the major versions must match
+// and the library's minor version must be at least the header's.
+// Returns `ZC_RESULT_INVALID_ARG`, with the reason in
+// `zc_last_error_message`, when they cannot talk.
+ZcResult zc_abi_check(uint32_t header_version);
+
+// Start the daemon once its gateway is listening; see
+// `zc_daemon_start_ready`.
+//
+// # Safety
+// Same requirements as `zc_daemon_start_ready`
+ZcResult zc_daemon_start(const char *config_toml, const char *host, uint16_t port);
+
+// Bind the gateway, then start the daemon in the background. Returns
+// `BindError` if the port cannot be bound within `timeout_ms` (0 = 5s),
+// so success means the gateway accepts connections. `out_port` (may be
+// NULL) receives the bound port, useful with `port = 0`.
+//
+// # Safety
+// Caller must ensure `config_toml` and `host` (if non-NULL) are
+// null-terminated UTF-8 and `out_port` (if non-NULL) can be written to
+ZcResult zc_daemon_start_ready(const char *config_toml,
+                               const char *host,
+                               uint16_t port,
+                               uint32_t timeout_ms,
+                               uint16_t *out_port);
+
+// Stop the daemon, first waiting up to 30s for active agent turns and
+// tool executions to finish and the final state snapshot to be written.
+ZcResult zc_daemon_stop(void);
+
+ZcResult zc_daemon_status(char **state_json);
+
+bool zc_daemon_is_running(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ZEROCLAW_H */
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT
+
+#pragma once
+
+// Generated by build.rs with cbindgen; do not edit.
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#define ZEROCLAW_VERSION "1.0.8"
+
+// Version of the tool plugin interface. A plugin puts the version it was
+// built against in its descriptor; others are not loaded.
+#define ZC_TOOL_ABI_VERSION 1
+
+// What `zc_tool_descriptor` returns. The descriptor and its strings must
+// stay valid while the library is loaded.
+typedef struct {
+  // `ZC_TOOL_ABI_VERSION`
+  uint32_t abi_version;
+  // Tool name the model calls, e.g. `word_count`
+  const char *name;
+  // What the tool does, shown to the model
+  const char *description;
+  // JSON schema of the arguments, or NULL for none
+  const char *parameters_schema;
+} ZcToolDescriptor;
+
+// Sink `zc_tool_execute` writes its output to: pass `sink` back with
+// NUL-terminated UTF-8 text, as often as needed. The pieces are joined.
+typedef void (*ZcToolOutputFn)(void *sink, const char *text);
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Describe the tool. Called once, when the plugin is loaded.
+const ZcToolDescriptor *zc_tool_descriptor(void);
+
+// Run the tool with its arguments as a JSON object, writing the result
+// through `output(sink, text)`. Return 0 on success, anything else on
+// failure (the output is then the error message). Called on a worker
+// thread, possibly several at once.
+int32_t zc_tool_execute(const char *args_json, ZcToolOutputFn output, void *sink);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,251 @@
+//! A2A (Agent-to-Agent protocol) server.
+//!
+//! With `[a2a] enabled = true` the gateway publishes an agent card at
+//! `/.well-known/agent.json` and takes JSON-RPC 2.0 calls at `POST /a2a`:
+//! `message/send` and `message/stream` create a task that runs as a
+//! headless agent turn, `tasks/get` and `tasks/resubscribe` follow it, and
+//! `tasks/cancel` stops it. Messages that share a `contextId` continue the
+//! same conversation. Tasks live in memory, up to `[a2a] max_tasks`.
+
+pub mod rpc;
+pub mod tasks;
+
+use crate::config::A2aConfig;
+use crate::skills::Skill;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+pub use tasks::A2aServer;
+
+/// Protocol version this server speaks.
+pub const PROTOCOL_VERSION: &str = "0.3.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskState {
+    Submitted,
+    Working,
+    InputRequired,
+    Completed,
+    Canceled,
+    Failed,
+    Rejected,
+}
+
+impl TaskState {
+    /// Whether the task can change no more.
+    pub fn is_final(self) -> bool {
+        matches!(
+            self,
+            Self::Completed | Self::Canceled | Self::Failed | Self::Rejected
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Agent,
+}
+
+/// A piece of a message. Only text is understood; data parts are read as
+/// JSON and files by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Part {
+    Text { text: String },
+    Data { data: Value },
+    File { file: Value },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    pub role: Role,
+    pub parts: Vec<Part>,
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_id: Option<String>,
+    #[serde(default = "message_kind")]
+    pub kind: String,
+}
+
+fn message_kind() -> String {
+    "message".into()
+}
+
+impl Message {
+    pub fn agent(text: impl Into<String>, task_id: &str, context_id: &str) -> Self {
+        Self {
+            role: Role::Agent,
+            parts: vec![Part::Text { text: text.into() }],
+            message_id: Uuid::new_v4().to_string(),
+            task_id: Some(task_id.to_string()),
+            context_id: Some(context_id.to_string()),
+            kind: message_kind(),
+        }
+    }
+
+    /// The message as plain text for the model.
+    pub fn text(&self) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text { text } => text.clone(),
+                Part::Data { data } => data.to_string(),
+                Part::File { file } => format!(
+                    "[file: {}]",
+                    file.get("name")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unnamed")
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub state: TaskState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<Message>,
+    pub timestamp: String,
+}
+
+impl TaskStatus {
+    pub fn new(state: TaskState, message: Option<Message>) -> Self {
+        Self {
+            state,
+            message,
+            timestamp: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    pub artifact_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    pub context_id: String,
+    pub status: TaskStatus,
+    #[serde(default)]
+    pub artifacts: Vec<Artifact>,
+    #[serde(default)]
+    pub history: Vec<Message>,
+    #[serde(default = "task_kind")]
+    pub kind: String,
+}
+
+fn task_kind() -> String {
+    "task".into()
+}
+
+/// The agent card served at `/.well-known/agent.json`. `base_url` is where
+/// the gateway is reached; installed skills are listed as card skills.
+pub fn agent_card(config: &A2aConfig, base_url: &str, skills: &[Skill], bearer: bool) -> Value {
+    let mut card_skills: Vec<Value> = skills
+        .iter()
+        .map(|skill| {
+            json!({
+                "id": skill.name,
+                "name": skill.name,
+                "description": skill.description,
+                "tags": skill.tags,
+            })
+        })
+        .collect();
+    if card_skills.is_empty() {
+        card_skills.push(json!({
+            "id": "general",
+            "name": "General assistant",
+            "description": "Answers questions and carries out tasks with its tools",
+            "tags": [],
+        }));
+    }
+
+    let mut card = json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "name": config.name,
+        "description": config.description.as_deref().unwrap_or("A ZeroClaw agent"),
+        "url": format!("{}/a2a", base_url.trim_end_matches('/')),
+        "preferredTransport": "JSONRPC",
+        "version": env!("CARGO_PKG_VERSION"),
+        "capabilities": {
+            "streaming": true,
+            "pushNotifications": false,
+            "stateTransitionHistory": false,
+        },
+        "defaultInputModes": ["text/plain"],
+        "defaultOutputModes": ["text/plain"],
+        "skills": card_skills,
+    });
+    if bearer {
+        card["securitySchemes"] = json!({"bearer": {"type": "http", "scheme": "bearer"}});
+        card["security"] = json!([{"bearer": []}]);
+    }
+    card
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_parse_from_the_wire_and_flatten_to_text() {
+        let message: Message = serde_json::from_value(json!({
+            "role": "user",
+            "messageId": "m1",
+            "contextId": "c1",
+            "kind": "message",
+            "parts": [
+                {"kind": "text", "text": "Summarize this"},
+                {"kind": "data", "data": {"rows": 3}},
+                {"kind": "file", "file": {"name": "report.pdf", "uri": "https://x/report.pdf"}}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(message.context_id.as_deref(), Some("c1"));
+        assert_eq!(
+            message.text(),
+            "Summarize this\n{\"rows\":3}\n[file: report.pdf]"
+        );
+
+        let reply = serde_json::to_value(Message::agent("Done", "t1", "c1")).unwrap();
+        assert_eq!(reply["role"], "agent");
+        assert_eq!(reply["kind"], "message");
+        assert_eq!(reply["taskId"], "t1");
+        assert_eq!(
+            serde_json::to_value(TaskState::InputRequired).unwrap(),
+            "input-required"
+        );
+    }
+
+    #[test]
+    fn agent_card_points_at_the_endpoint_and_declares_auth() {
+        let config = A2aConfig::default();
+        let card = agent_card(&config, "https://claw.example.com/", &[], true);
+        assert_eq!(card["url"], "https://claw.example.com/a2a");
+        assert_eq!(card["name"], "ZeroClaw");
+        assert_eq!(card["capabilities"]["streaming"], true);
+        assert_eq!(card["skills"][0]["id"], "general");
+        assert_eq!(card["security"][0]["bearer"], json!([]));
+
+        let open = agent_card(&config, "http://127.0.0.1:3000", &[], false);
+        assert!(open.get("securitySchemes").is_none());
+    }
+}
@@ -0,0 +1,280 @@
+//! JSON-RPC 2.0 dispatch for `POST /a2a`.
+
+use super::tasks::{RpcError, INVALID_PARAMS};
+use super::{A2aServer, Message, Task};
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// How long a blocking `message/send` waits before returning the task as
+/// it stands; the caller polls `tasks/get` after that. Kept under the
+/// gateway's request timeout.
+const BLOCKING_WAIT: Duration = Duration::from_secs(25);
+
+/// What the gateway sends back for one call.
+pub enum Reply {
+    Json(Value),
+    /// Server-sent events: `first`, then every event up to the final one,
+    /// each wrapped as a JSON-RPC response to `id`
+    Stream {
+        id: Value,
+        first: Box<Task>,
+        events: broadcast::Receiver<Value>,
+    },
+}
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SendParams {
+    message: Message,
+    #[serde(default)]
+    configuration: SendConfiguration,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SendConfiguration {
+    /// Wait for the task to finish (default true)
+    #[serde(default)]
+    blocking: Option<bool>,
+    #[serde(default)]
+    history_length: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskQuery {
+    id: String,
+    #[serde(default)]
+    history_length: Option<usize>,
+}
+
+fn result(id: &Value, result: impl serde::Serialize) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "result": result})
+}
+
+fn error(id: &Value, code: i64, message: impl Into<String>) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message.into()}})
+}
+
+fn params<T: for<'de> Deserialize<'de>>(value: Value) -> Result<T, RpcError> {
+    serde_json::from_value(value).map_err(|e| RpcError {
+        code: INVALID_PARAMS,
+        message: format!("Invalid params: {e}"),
+    })
+}
+
+/// Handle one JSON-RPC request body. `guard` is held by the task a send
+/// starts, for as long as it runs.
+pub async fn dispatch<G: Send + 'static>(server: &Arc<A2aServer>, body: &[u8], guard: G) -> Reply {
+    let request: Request = match serde_json::from_slice::<Value>(body) {
+        Ok(value) => match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(e) => return Reply::Json(error(&Value::Null, INVALID_REQUEST, e.to_string())),
+        },
+        Err(e) => return Reply::Json(error(&Value::Null, PARSE_ERROR, e.to_string())),
+    };
+    if request.jsonrpc != "2.0" {
+        return Reply::Json(error(
+            &request.id,
+            INVALID_REQUEST,
+            "jsonrpc must be \"2.0\"",
+        ));
+    }
+    let id = request.id;
+
+    let outcome = match request.method.as_str() {
+        "message/send" => match params::<SendParams>(request.params) {
+            Ok(p) => send(server, p, guard).await,
+            Err(e) => Err(e),
+        },
+        "message/stream" => {
+            return match params::<SendParams>(request.params)
+                .and_then(|p| server.send(p.message, guard))
+            {
+                Ok((first, events)) => Reply::Stream {
+                    id,
+                    first: Box::new(first),
+                    events,
+                },
+                Err(e) => Reply::Json(error(&id, e.code, e.message)),
+            }
+        }
+        "tasks/resubscribe" => {
+            return match params::<TaskQuery>(request.params).and_then(|q| server.subscribe(&q.id)) {
+                Ok((first, events)) => Reply::Stream {
+                    id,
+                    first: Box::new(first),
+                    events,
+                },
+                Err(e) => Reply::Json(error(&id, e.code, e.message)),
+            }
+        }
+        "tasks/get" => {
+            params::<TaskQuery>(request.params).and_then(|q| server.get(&q.id, q.history_length))
+        }
+        "tasks/cancel" => params::<TaskQuery>(request.params).and_then(|q| server.cancel(&q.id)),
+        other => Err(RpcError {
+            code: METHOD_NOT_FOUND,
+            message: format!("Method not found: {other}"),
+        }),
+    };
+    Reply::Json(match outcome {
+        Ok(task) => result(&id, task),
+        Err(e) => error(&id, e.code, e.message),
+    })
+}
+
+async fn send<G: Send + 'static>(
+    server: &Arc<A2aServer>,
+    params: SendParams,
+    guard: G,
+) -> Result<Task, RpcError> {
+    let (task, mut events) = server.send(params.message, guard)?;
+    if params.configuration.blocking.unwrap_or(true) {
+        let _ = tokio::time::timeout(BLOCKING_WAIT, async {
+            loop {
+                match events.recv().await {
+                    Ok(event) if event["final"] == true => break,
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+        .await;
+    }
+    server.get(&task.id, params.configuration.history_length)
+}
+
+/// The JSON-RPC responses of a stream reply, ending after the final event.
+pub fn stream_responses(
+    id: Value,
+    first: &Task,
+    events: broadcast::Receiver<Value>,
+) -> impl Stream<Item = Value> {
+    let done = first.status.state.is_final();
+    let opening = result(&id, first);
+    stream::unfold(
+        (Some(opening), events, done),
+        move |(opening, mut events, done)| {
+            let id = id.clone();
+            async move {
+                if let Some(opening) = opening {
+                    return Some((opening, (None, events, done)));
+                }
+                if done {
+                    return None;
+                }
+                loop {
+                    match events.recv().await {
+                        Ok(event) => {
+                            let last = event["final"] == true;
+                            return Some((result(&id, event), (None, events, last)));
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => {}
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tasks::tests::{server, user};
+    use super::*;
+    use futures_util::StreamExt;
+
+    fn call(method: &str, params: &Value) -> Vec<u8> {
+        serde_json::to_vec(&json!({"jsonrpc": "2.0", "id": 7, "method": method, "params": params}))
+            .unwrap()
+    }
+
+    fn json(reply: Reply) -> Value {
+        match reply {
+            Reply::Json(value) => value,
+            Reply::Stream { .. } => panic!("expected a JSON reply"),
+        }
+    }
+
+    #[tokio::test]
+    async fn send_waits_for_the_answer_and_errors_follow_json_rpc() {
+        let tmp = tempfile::tempdir().unwrap();
+        let server = server(tmp.path());
+
+        let sent = json(
+            dispatch(
+                &server,
+                &call("message/send", &json!({"message": user("hi", None)})),
+                (),
+            )
+            .await,
+        );
+        assert_eq!(sent["id"], 7);
+        assert_eq!(sent["result"]["status"]["state"], "completed");
+        assert_eq!(
+            sent["result"]["artifacts"][0]["parts"][0]["text"],
+            "1 message(s), last: hi"
+        );
+
+        let task_id = sent["result"]["id"].as_str().unwrap();
+        let got = json(dispatch(&server, &call("tasks/get", &json!({"id": task_id})), ()).await);
+        assert_eq!(got["result"]["id"], task_id);
+
+        let cases = [
+            (b"{not json".to_vec(), -32700),
+            (call("tasks/list", &json!({})), -32601),
+            (call("tasks/get", &json!({"id": "missing"})), -32001),
+            (call("message/send", &json!({"message": "hi"})), -32602),
+        ];
+        for (body, code) in cases {
+            let reply = json(dispatch(&server, &body, ()).await);
+            assert_eq!(reply["error"]["code"], code, "{reply}");
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_ends_with_the_final_status() {
+        let tmp = tempfile::tempdir().unwrap();
+        let server = server(tmp.path());
+        let Reply::Stream { id, first, events } = dispatch(
+            &server,
+            &call("message/stream", &json!({"message": user("hi", None)})),
+            (),
+        )
+        .await
+        else {
+            panic!("expected a stream");
+        };
+
+        let responses: Vec<Value> = stream_responses(id, &first, events).collect().await;
+        let kinds: Vec<&str> = responses
+            .iter()
+            .map(|r| r["result"]["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            kinds,
+            ["task", "status-update", "artifact-update", "status-update"]
+        );
+        assert_eq!(responses[3]["result"]["final"], true);
+        assert_eq!(responses[3]["result"]["status"]["state"], "completed");
+    }
+}
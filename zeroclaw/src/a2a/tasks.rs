@@ -0,0 +1,570 @@
+//! The A2A task table: creates tasks, runs each as a headless agent turn
+//! and broadcasts its status and artifact updates to stream subscribers.
+
+use super::{Artifact, Message, Part, Role, Task, TaskState, TaskStatus};
+use crate::agent::budget::TurnReply;
+use crate::agent::loop_;
+use crate::channels::build_system_prompt;
+use crate::config::Config;
+use crate::memory;
+use crate::observability::traits::ObserverMetric;
+use crate::observability::{self, Observer, ObserverEvent};
+use crate::providers::{self, ChatMessage, Provider};
+use crate::runtime;
+use crate::security::SecurityPolicy;
+use crate::tools::{self, Tool};
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Events a slow stream subscriber may fall behind by before it skips.
+const EVENT_BUFFER: usize = 64;
+
+/// JSON-RPC error codes used by the A2A spec.
+pub const INVALID_PARAMS: i64 = -32602;
+pub const TASK_NOT_FOUND: i64 = -32001;
+pub const TASK_NOT_CANCELABLE: i64 = -32002;
+
+/// A JSON-RPC error returned by a task method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn not_found(id: &str) -> Self {
+        Self {
+            code: TASK_NOT_FOUND,
+            message: format!("Task not found: {id}"),
+        }
+    }
+}
+
+struct Entry {
+    task: Task,
+    /// Creation order, for context history and eviction
+    seq: u64,
+    events: broadcast::Sender<Value>,
+    handle: Option<tokio::task::AbortHandle>,
+}
+
+#[derive(Default)]
+struct Table {
+    entries: HashMap<String, Entry>,
+    next_seq: u64,
+}
+
+/// Serves A2A tasks with one agent setup shared by all of them.
+pub struct A2aServer {
+    config: Config,
+    provider: Arc<dyn Provider>,
+    observer: Arc<dyn Observer>,
+    tools: Vec<Box<dyn Tool>>,
+    system_prompt: String,
+    model: String,
+    table: Mutex<Table>,
+}
+
+/// `status-update` stream event for `task`.
+fn status_event(task: &Task) -> Value {
+    json!({
+        "kind": "status-update",
+        "taskId": task.id,
+        "contextId": task.context_id,
+        "status": task.status,
+        "final": task.status.state.is_final(),
+    })
+}
+
+/// `artifact-update` stream event for `artifact` of `task`.
+fn artifact_event(task: &Task, artifact: &Artifact) -> Value {
+    json!({
+        "kind": "artifact-update",
+        "taskId": task.id,
+        "contextId": task.context_id,
+        "artifact": artifact,
+        "lastChunk": true,
+    })
+}
+
+/// Streams the tool calls of one task as `working` status updates, and
+/// passes every event on to the configured observer.
+struct TaskObserver {
+    inner: Arc<dyn Observer>,
+    events: broadcast::Sender<Value>,
+    task_id: String,
+    context_id: String,
+}
+
+impl Observer for TaskObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        if let ObserverEvent::ToolCall { tool, success, .. } = event {
+            let note = Message::agent(
+                format!("{} {tool}", if *success { "Ran" } else { "Failed" }),
+                &self.task_id,
+                &self.context_id,
+            );
+            let _ = self.events.send(json!({
+                "kind": "status-update",
+                "taskId": self.task_id,
+                "contextId": self.context_id,
+                "status": TaskStatus::new(TaskState::Working, Some(note)),
+                "final": false,
+            }));
+        }
+        self.inner.record_event(event);
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        self.inner.record_metric(metric);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+
+    fn name(&self) -> &str {
+        "a2a"
+    }
+}
+
+impl A2aServer {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let provider = providers::create_resilient_provider(
+            config.default_provider.as_deref().unwrap_or("openrouter"),
+            config.api_key.as_deref(),
+            &config.reliability,
+            &providers::ProviderSettings::from_config(config),
+        )?;
+        Self::with_provider(config, Arc::from(provider))
+    }
+
+    pub fn with_provider(config: &Config, provider: Arc<dyn Provider>) -> Result<Self> {
+        let model = config
+            .default_model
+            .clone()
+            .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
+        let workspace = config.workspace_dir.clone();
+        let mem: Arc<dyn memory::Memory> = Arc::from(memory::create_memory(
+            &config.memory,
+            &workspace,
+            config.api_key.as_deref(),
+        )?);
+        let security = Arc::new(SecurityPolicy::from_config(&config.autonomy, &workspace));
+        let runtime: Arc<dyn runtime::RuntimeAdapter> =
+            Arc::from(runtime::create_runtime(&config.runtime)?);
+        let composio_key = if config.composio.enabled {
+            config.composio.api_key.as_deref()
+        } else {
+            None
+        };
+        let mut tools = tools::all_tools_with_runtime(
+            &security,
+            runtime,
+            mem.clone(),
+            composio_key,
+            &config.browser,
+        );
+        tools.extend(tools::optional_tools(config, &security));
+        tools::route_to_nodes(&mut tools, &config.nodes, &security);
+        if config.knowledge.enabled && crate::knowledge::supported(mem.as_ref()) {
+            tools.push(Box::new(tools::KbSearchTool::new(mem)) as Box<dyn Tool>);
+        }
+        // The remote agent cannot answer questions mid-turn
+        tools.retain(|t| t.name() != "ask_user");
+
+        let tool_descs = loop_::tool_descriptions(config, &tools);
+        let mut system_prompt = build_system_prompt(
+            &workspace,
+            &model,
+            &tool_descs,
+            &crate::skills::load_skills(&workspace),
+            Some(&crate::identity::readable_by(config.identity.for_channel("a2a"), &security)),
+        );
+        system_prompt.push_str(&loop_::build_tool_instructions_with(
+            &tools,
+            config.tool_schema_for(&model),
+        ));
+
+        Ok(Self {
+            config: config.clone(),
+            provider,
+            observer: Arc::from(observability::create_observer(&config.observability)),
+            tools,
+            system_prompt,
+            model,
+            table: Mutex::new(Table::default()),
+        })
+    }
+
+    /// The agent card, pointing at `[a2a] public_url`, else at `host` (the
+    /// `Host` of the card request).
+    pub fn card(&self, host: Option<&str>, bearer: bool) -> Value {
+        let base = self
+            .config
+            .a2a
+            .public_url
+            .clone()
+            .unwrap_or_else(|| format!("http://{}", host.unwrap_or("127.0.0.1")));
+        super::agent_card(
+            &self.config.a2a,
+            &base,
+            &crate::skills::load_skills(&self.config.workspace_dir),
+            bearer,
+        )
+    }
+
+    fn table(&self) -> MutexGuard<'_, Table> {
+        self.table
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Create a task for `message` and start running it. `guard` is held
+    /// until the turn ends. Returns the new task and its event stream.
+    pub fn send<G: Send + 'static>(
+        self: &Arc<Self>,
+        mut message: Message,
+        guard: G,
+    ) -> Result<(Task, broadcast::Receiver<Value>), RpcError> {
+        if message.role != Role::User {
+            return Err(RpcError {
+                code: INVALID_PARAMS,
+                message: "Only user messages start tasks".into(),
+            });
+        }
+        if message.text().trim().is_empty() {
+            return Err(RpcError {
+                code: INVALID_PARAMS,
+                message: "The message has no content".into(),
+            });
+        }
+
+        let mut table = self.table();
+        if let Some(id) = &message.task_id {
+            let entry = table
+                .entries
+                .get(id)
+                .ok_or_else(|| RpcError::not_found(id))?;
+            return Err(RpcError {
+                code: INVALID_PARAMS,
+                message: format!(
+                    "Task {id} is {}; send a new message with its contextId to continue",
+                    serde_json::to_value(entry.task.status.state)
+                        .ok()
+                        .and_then(|v| v.as_str().map(str::to_string))
+                        .unwrap_or_default()
+                ),
+            });
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let context_id = message
+            .context_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        message.task_id = Some(id.clone());
+        message.context_id = Some(context_id.clone());
+
+        // Earlier tasks of the context, oldest first, become the history
+        let mut earlier: Vec<&Entry> = table
+            .entries
+            .values()
+            .filter(|e| e.task.context_id == context_id)
+            .collect();
+        earlier.sort_by_key(|e| e.seq);
+        let mut history = vec![ChatMessage::system(self.system_prompt.as_str())];
+        for previous in earlier.iter().flat_map(|e| &e.task.history) {
+            history.push(match previous.role {
+                Role::User => ChatMessage::user(previous.text()),
+                Role::Agent => ChatMessage::assistant(previous.text()),
+            });
+        }
+        history.push(ChatMessage::user(message.text()));
+
+        let task = Task {
+            id: id.clone(),
+            context_id,
+            status: TaskStatus::new(TaskState::Submitted, None),
+            artifacts: Vec::new(),
+            history: vec![message],
+            kind: "task".into(),
+        };
+        let (events, receiver) = broadcast::channel(EVENT_BUFFER);
+        self.evict(&mut table);
+        let seq = table.next_seq;
+        table.next_seq += 1;
+        table.entries.insert(
+            id.clone(),
+            Entry {
+                task: task.clone(),
+                seq,
+                events: events.clone(),
+                handle: None,
+            },
+        );
+
+        let server = self.clone();
+        let task_id = id.clone();
+        let handle = tokio::spawn(async move {
+            let _guard = guard;
+            server.execute(&task_id, history, events).await;
+        });
+        if let Some(entry) = table.entries.get_mut(&id) {
+            entry.handle = Some(handle.abort_handle());
+        }
+        Ok((task, receiver))
+    }
+
+    /// Drop the oldest finished tasks to make room for one more.
+    fn evict(&self, table: &mut Table) {
+        while table.entries.len() >= self.config.a2a.max_tasks.max(1) {
+            let Some(oldest) = table
+                .entries
+                .iter()
+                .filter(|(_, e)| e.task.status.state.is_final())
+                .min_by_key(|(_, e)| e.seq)
+                .map(|(id, _)| id.clone())
+            else {
+                return;
+            };
+            table.entries.remove(&oldest);
+        }
+    }
+
+    /// The task, with only its last `history_length` messages when given.
+    pub fn get(&self, id: &str, history_length: Option<usize>) -> Result<Task, RpcError> {
+        let table = self.table();
+        let mut task = table
+            .entries
+            .get(id)
+            .map(|e| e.task.clone())
+            .ok_or_else(|| RpcError::not_found(id))?;
+        if let Some(keep) = history_length {
+            let skip = task.history.len().saturating_sub(keep);
+            task.history.drain(..skip);
+        }
+        Ok(task)
+    }
+
+    /// The task and its further events, for `tasks/resubscribe`.
+    pub fn subscribe(&self, id: &str) -> Result<(Task, broadcast::Receiver<Value>), RpcError> {
+        let table = self.table();
+        let entry = table
+            .entries
+            .get(id)
+            .ok_or_else(|| RpcError::not_found(id))?;
+        Ok((entry.task.clone(), entry.events.subscribe()))
+    }
+
+    /// Stop a task that has not finished.
+    pub fn cancel(&self, id: &str) -> Result<Task, RpcError> {
+        let mut table = self.table();
+        let entry = table
+            .entries
+            .get_mut(id)
+            .ok_or_else(|| RpcError::not_found(id))?;
+        if entry.task.status.state.is_final() {
+            return Err(RpcError {
+                code: TASK_NOT_CANCELABLE,
+                message: format!("Task {id} has already finished"),
+            });
+        }
+        if let Some(handle) = entry.handle.take() {
+            handle.abort();
+        }
+        entry.task.status = TaskStatus::new(TaskState::Canceled, None);
+        let _ = entry.events.send(status_event(&entry.task));
+        Ok(entry.task.clone())
+    }
+
+    /// Apply `change` to a task that has not finished and broadcast the
+    /// result. `None` once the task finished (e.g. it was canceled).
+    fn update(&self, id: &str, change: impl FnOnce(&mut Task)) -> Option<Task> {
+        let mut table = self.table();
+        let entry = table.entries.get_mut(id)?;
+        if entry.task.status.state.is_final() {
+            return None;
+        }
+        change(&mut entry.task);
+        if entry.task.status.state.is_final() {
+            entry.handle = None;
+        }
+        Some(entry.task.clone())
+    }
+
+    async fn execute(
+        &self,
+        id: &str,
+        mut history: Vec<ChatMessage>,
+        events: broadcast::Sender<Value>,
+    ) {
+        let Some(task) = self.update(id, |task| {
+            task.status = TaskStatus::new(TaskState::Working, None);
+        }) else {
+            return;
+        };
+        let _ = events.send(status_event(&task));
+        let context_id = task.context_id;
+
+        let observer = TaskObserver {
+            inner: self.observer.clone(),
+            events: events.clone(),
+            task_id: id.to_string(),
+            context_id: context_id.clone(),
+        };
+        let outcome = crate::agent::settings::scope(
+            &self.config,
+            crate::hooks::scope(
+                &self.config,
+                loop_::agent_turn_with_budget(
+                    self.provider.as_ref(),
+                    &mut history,
+                    &self.tools,
+                    &observer,
+                    &self.model,
+                    self.config.default_temperature,
+                    None,
+                    self.config.turn_budget_for(&self.model),
+                ),
+            ),
+        )
+        .await
+        .and_then(TurnReply::into_result);
+
+        match outcome {
+            Ok(answer) => {
+                let artifact = Artifact {
+                    artifact_id: Uuid::new_v4().to_string(),
+                    name: Some("answer".into()),
+                    parts: vec![Part::Text {
+                        text: answer.clone(),
+                    }],
+                };
+                let reply = Message::agent(answer, id, &context_id);
+                let finished = self.update(id, |task| {
+                    task.artifacts.push(artifact.clone());
+                    task.history.push(reply.clone());
+                    task.status = TaskStatus::new(TaskState::Completed, Some(reply));
+                });
+                if let Some(task) = finished {
+                    let _ = events.send(artifact_event(&task, &artifact));
+                    let _ = events.send(status_event(&task));
+                }
+            }
+            Err(e) => {
+                tracing::warn!("A2A task {id} failed: {e:#}");
+                let reply = Message::agent(format!("{e:#}"), id, &context_id);
+                if let Some(task) = self.update(id, |task| {
+                    task.status = TaskStatus::new(TaskState::Failed, Some(reply));
+                }) {
+                    let _ = events.send(status_event(&task));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+
+    /// Answers with the number of user messages it was sent, and the last.
+    fn counter() -> ScriptedProvider {
+        ScriptedProvider::answering(|messages| {
+            let users: Vec<&ChatMessage> = messages.iter().filter(|m| m.role == "user").collect();
+            format!(
+                "{} message(s), last: {}",
+                users.len(),
+                users.last().map_or("", |m| m.content.as_str())
+            )
+        })
+    }
+
+    pub(crate) fn server(workspace: &std::path::Path) -> Arc<A2aServer> {
+        let config = Config {
+            workspace_dir: workspace.to_path_buf(),
+            memory: crate::config::MemoryConfig {
+                backend: "none".into(),
+                ..crate::config::MemoryConfig::default()
+            },
+            ..Config::default()
+        };
+        Arc::new(A2aServer::with_provider(&config, Arc::new(counter())).unwrap())
+    }
+
+    pub(crate) fn user(text: &str, context_id: Option<&str>) -> Message {
+        Message {
+            role: Role::User,
+            parts: vec![Part::Text { text: text.into() }],
+            message_id: Uuid::new_v4().to_string(),
+            task_id: None,
+            context_id: context_id.map(str::to_string),
+            kind: "message".into(),
+        }
+    }
+
+    async fn finished(mut events: broadcast::Receiver<Value>) -> Value {
+        loop {
+            let event = events.recv().await.unwrap();
+            if event["final"] == true {
+                return event;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn tasks_complete_and_contexts_carry_over() {
+        let tmp = tempfile::tempdir().unwrap();
+        let server = server(tmp.path());
+
+        let (task, events) = server.send(user("hello", None), ()).unwrap();
+        assert_eq!(task.status.state, TaskState::Submitted);
+        let last = finished(events).await;
+        assert_eq!(last["status"]["state"], "completed");
+
+        let done = server.get(&task.id, None).unwrap();
+        assert_eq!(done.history.len(), 2);
+        assert_eq!(
+            done.artifacts[0].parts,
+            [Part::Text {
+                text: "1 message(s), last: hello".into()
+            }]
+        );
+
+        // Same context: the earlier exchange is part of the conversation
+        let (follow_up, events) = server
+            .send(user("and again", Some(&task.context_id)), ())
+            .unwrap();
+        finished(events).await;
+        let follow_up = server.get(&follow_up.id, Some(1)).unwrap();
+        assert_eq!(follow_up.history.len(), 1);
+        assert_eq!(follow_up.history[0].text(), "2 message(s), last: and again");
+
+        let err = server.cancel(&task.id).unwrap_err();
+        assert_eq!(err.code, TASK_NOT_CANCELABLE);
+        let mut resent = user("more", None);
+        resent.task_id = Some(task.id.clone());
+        assert_eq!(server.send(resent, ()).unwrap_err().code, INVALID_PARAMS);
+        assert_eq!(server.get("nope", None).unwrap_err().code, TASK_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn canceled_tasks_stay_canceled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let server = server(tmp.path());
+        let (task, _events) = server.send(user("slow work", None), ()).unwrap();
+        let canceled = server.cancel(&task.id).unwrap();
+        assert_eq!(canceled.status.state, TaskState::Canceled);
+        tokio::task::yield_now().await;
+        assert_eq!(
+            server.get(&task.id, None).unwrap().status.state,
+            TaskState::Canceled
+        );
+    }
+}
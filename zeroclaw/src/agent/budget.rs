@@ -0,0 +1,269 @@
+//! Limits on what a single request may spend.
+//!
+//! Model profiles set the defaults; a caller (CLI flags, FFI, gateway request
+//! body) can tighten them for one request but never raise them. Providers do
+//! not report usage, so token spend is estimated from the text sent and
+//! received with the model's [`tokens`](crate::tokens) counter.
+//!
+//! A turn stopped by a cap still answers: [`TurnReply`] carries the best
+//! partial answer together with the cap that was hit.
+
+use crate::providers::ChatMessage;
+use crate::tokens::Family;
+use serde::{Deserialize, Serialize};
+
+/// Caps for one agent turn; `None` means no cap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TurnBudget {
+    /// Estimated tokens sent and received across every model call
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+    /// Tool executions
+    #[serde(default)]
+    pub max_tool_calls: Option<u32>,
+    /// Wall-clock seconds for the whole turn
+    #[serde(default)]
+    pub max_seconds: Option<u64>,
+    /// Model calls (reply, then tool results, then the next reply, ...)
+    #[serde(default)]
+    pub max_iterations: Option<u32>,
+}
+
+impl TurnBudget {
+    /// Apply a per-request budget on top of these defaults. Each cap can only
+    /// go down: a request asking for more than the default gets the default.
+    #[must_use]
+    pub fn lowered_by(self, request: Self) -> Self {
+        fn lower<T: Ord>(default: Option<T>, request: Option<T>) -> Option<T> {
+            match (default, request) {
+                (Some(d), Some(r)) => Some(d.min(r)),
+                (d, r) => d.or(r),
+            }
+        }
+        Self {
+            max_tokens: lower(self.max_tokens, request.max_tokens),
+            max_tool_calls: lower(self.max_tool_calls, request.max_tool_calls),
+            max_seconds: lower(self.max_seconds, request.max_seconds),
+            max_iterations: lower(self.max_iterations, request.max_iterations),
+        }
+    }
+}
+
+/// Which cap a turn ran into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetExhausted {
+    Tokens(u64),
+    ToolCalls(u32),
+    Seconds(u64),
+    Iterations(u32),
+}
+
+impl BudgetExhausted {
+    /// Name of the exhausted limit, as used in config and request bodies.
+    pub fn limit(&self) -> &'static str {
+        match self {
+            Self::Tokens(_) => "max_tokens",
+            Self::ToolCalls(_) => "max_tool_calls",
+            Self::Seconds(_) => "max_seconds",
+            Self::Iterations(_) => "max_iterations",
+        }
+    }
+}
+
+impl std::fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Self::Tokens(n) | Self::Seconds(n) => *n,
+            Self::ToolCalls(n) | Self::Iterations(n) => u64::from(*n),
+        };
+        write!(
+            f,
+            "Turn budget exhausted: {} ({value}) reached",
+            self.limit()
+        )
+    }
+}
+
+impl std::error::Error for BudgetExhausted {}
+
+/// What a budgeted turn produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurnReply {
+    /// The final answer, or the best partial answer when a cap was hit
+    pub answer: String,
+    /// The cap that stopped the turn early, if any
+    pub exhausted: Option<BudgetExhausted>,
+}
+
+impl TurnReply {
+    /// The answer, or the exhausted cap as an error for callers that have no
+    /// use for a partial answer.
+    pub fn into_result(self) -> anyhow::Result<String> {
+        match self.exhausted {
+            Some(exhausted) => Err(exhausted.into()),
+            None => Ok(self.answer),
+        }
+    }
+}
+
+/// Spend within one turn, checked before each model call and tool execution.
+/// The model's reply is charged after the fact, so a turn can overshoot
+/// `max_tokens` by at most one reply.
+#[derive(Debug)]
+pub struct BudgetTracker {
+    budget: TurnBudget,
+    family: Family,
+    tokens: u64,
+    tool_calls: u32,
+    iterations: u32,
+}
+
+impl BudgetTracker {
+    pub fn new(budget: TurnBudget) -> Self {
+        Self {
+            budget,
+            family: Family::default(),
+            tokens: 0,
+            tool_calls: 0,
+            iterations: 0,
+        }
+    }
+
+    /// Count tokens the way `model`'s tokenizer does.
+    #[must_use]
+    pub fn for_model(mut self, model: &str) -> Self {
+        self.family = Family::for_model(model);
+        self
+    }
+
+    /// Charge the prompt about to be sent, or refuse if it would not fit.
+    pub fn charge_prompt(&mut self, messages: &[ChatMessage]) -> Result<(), BudgetExhausted> {
+        let prompt = self.family.count_messages(messages);
+        if let Some(max) = self.budget.max_tokens {
+            if self.tokens + prompt > max {
+                return Err(BudgetExhausted::Tokens(max));
+            }
+        }
+        self.tokens += prompt;
+        Ok(())
+    }
+
+    pub fn charge_response(&mut self, response: &str) {
+        self.tokens += self.family.count(response);
+    }
+
+    /// Count one tool execution, or refuse if the cap is reached.
+    pub fn charge_tool_call(&mut self) -> Result<(), BudgetExhausted> {
+        if let Some(max) = self.budget.max_tool_calls {
+            if self.tool_calls >= max {
+                return Err(BudgetExhausted::ToolCalls(max));
+            }
+        }
+        self.tool_calls += 1;
+        Ok(())
+    }
+
+    /// Count one model call, or refuse if the cap is reached.
+    pub fn charge_iteration(&mut self) -> Result<(), BudgetExhausted> {
+        if let Some(max) = self.budget.max_iterations {
+            if self.iterations >= max {
+                return Err(BudgetExhausted::Iterations(max));
+            }
+        }
+        self.iterations += 1;
+        Ok(())
+    }
+
+    pub fn tokens(&self) -> u64 {
+        self.tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_can_only_lower_defaults() {
+        let defaults = TurnBudget {
+            max_tokens: Some(10_000),
+            max_tool_calls: Some(8),
+            max_seconds: None,
+            max_iterations: Some(20),
+        };
+        let request = TurnBudget {
+            max_tokens: Some(50_000),
+            max_tool_calls: Some(2),
+            max_seconds: Some(30),
+            max_iterations: None,
+        };
+        assert_eq!(
+            defaults.lowered_by(request),
+            TurnBudget {
+                max_tokens: Some(10_000),
+                max_tool_calls: Some(2),
+                max_seconds: Some(30),
+                max_iterations: Some(20),
+            }
+        );
+        assert_eq!(defaults.lowered_by(TurnBudget::default()), defaults);
+    }
+
+    #[test]
+    fn tracker_refuses_prompts_over_the_token_cap() {
+        let mut tracker = BudgetTracker::new(TurnBudget {
+            max_tokens: Some(10),
+            ..TurnBudget::default()
+        });
+        // 4 for the text, 3 message overhead, 3 reply priming
+        tracker
+            .charge_prompt(&[ChatMessage::user("x".repeat(20))])
+            .unwrap();
+        tracker.charge_response("abcd");
+        assert_eq!(tracker.tokens(), 11);
+        assert_eq!(
+            tracker.charge_prompt(&[ChatMessage::user("x".repeat(20))]),
+            Err(BudgetExhausted::Tokens(10))
+        );
+    }
+
+    #[test]
+    fn tracker_counts_tool_calls() {
+        let mut tracker = BudgetTracker::new(TurnBudget {
+            max_tool_calls: Some(1),
+            ..TurnBudget::default()
+        });
+        assert!(tracker.charge_tool_call().is_ok());
+        let err = tracker.charge_tool_call().unwrap_err();
+        assert_eq!(err.limit(), "max_tool_calls");
+        assert_eq!(
+            err.to_string(),
+            "Turn budget exhausted: max_tool_calls (1) reached"
+        );
+    }
+
+    #[test]
+    fn tracker_counts_iterations() {
+        let mut tracker = BudgetTracker::new(TurnBudget {
+            max_iterations: Some(2),
+            ..TurnBudget::default()
+        });
+        tracker.charge_iteration().unwrap();
+        tracker.charge_iteration().unwrap();
+        assert_eq!(
+            tracker.charge_iteration(),
+            Err(BudgetExhausted::Iterations(2))
+        );
+    }
+
+    #[test]
+    fn unlimited_budget_never_refuses() {
+        let mut tracker = BudgetTracker::new(TurnBudget::default());
+        for _ in 0..100 {
+            tracker.charge_tool_call().unwrap();
+        }
+        tracker
+            .charge_prompt(&[ChatMessage::user("x".repeat(100_000))])
+            .unwrap();
+    }
+}
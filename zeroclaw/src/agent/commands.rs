@@ -0,0 +1,413 @@
+//! Slash commands for interactive sessions.
+//!
+//! A line starting with `/` is handled by the session instead of being sent
+//! to the model: switch model, provider or profile, list and toggle tools, search
+//! memory, show or compact the history, save the transcript, attach a file
+//! to the next message, undo the last file change. Without them, changing anything meant restarting the
+//! session. A line opening a ```` ``` ```` block starts a multi-line message
+//! that runs to the closing fence.
+
+use crate::memory::Memory;
+use crate::providers::{ChatMessage, ImagePart, Provider};
+use crate::tokens::Family;
+use crate::tools::Tool;
+use crate::util::truncate_with_ellipsis;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Messages at the end of the history `/history compact` always keeps.
+const KEEP_RECENT_MESSAGES: usize = 2;
+
+/// Memories listed by `/memory`.
+const MEMORY_RESULTS: usize = 10;
+
+/// Largest text file `/attach` accepts.
+const MAX_ATTACHMENT_BYTES: usize = 256 * 1024;
+
+pub const HELP: &str = "Commands:
+  /model [name]           Show or switch the model
+  /provider [name]        Show or switch the provider
+  /profile [name]         Show or switch the config profile
+  /tools                  List tools and whether they are enabled
+  /tools enable <name>    Turn a tool back on
+  /tools disable <name>   Keep the model from using a tool
+  /memory <query>         Search memory
+  /history                Show the conversation so far
+  /history compact        Summarize older messages to free context
+  /save                   Save the conversation as a session
+  /attach <path>          Send a file (text or image) with the next message
+  /checkpoints            List workspace checkpoints taken before file changes
+  /undo                   Put files back as they were before the last change
+  /help                   Show this list
+  /quit                   Leave
+
+Start a line with ``` to type several lines; a line starting with ``` ends them.";
+
+const COMPACT_PROMPT: &str = "Summarize the conversation below for an assistant that will \
+continue it. Keep the user's goals, decisions made, facts learned, file paths and open \
+questions. Drop small talk. Be brief.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Quit,
+    Model(Option<String>),
+    Provider(Option<String>),
+    Profile(Option<String>),
+    Tools,
+    EnableTool(String),
+    DisableTool(String),
+    Memory(String),
+    History,
+    CompactHistory,
+    Save,
+    Attach(String),
+    Checkpoints,
+    Undo,
+}
+
+/// Parse `line` as a slash command. `None` for an ordinary message; `Err`
+/// says what is wrong with a malformed or unknown command.
+pub fn parse(line: &str) -> Option<Result<Command, String>> {
+    let line = line.trim();
+    let rest = line.strip_prefix('/')?;
+    let (name, arg) = match rest.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (rest, ""),
+    };
+    let arg = (!arg.is_empty()).then(|| arg.to_string());
+
+    let command = match (name, arg) {
+        ("help" | "?", _) => Ok(Command::Help),
+        ("quit" | "exit", _) => Ok(Command::Quit),
+        ("model", arg) => Ok(Command::Model(arg)),
+        ("provider", arg) => Ok(Command::Provider(arg)),
+        ("profile", arg) => Ok(Command::Profile(arg)),
+        ("tools", None) => Ok(Command::Tools),
+        ("tools", Some(arg)) => match arg.split_once(char::is_whitespace) {
+            Some(("enable", tool)) => Ok(Command::EnableTool(tool.trim().to_string())),
+            Some(("disable", tool)) => Ok(Command::DisableTool(tool.trim().to_string())),
+            _ => Err("Usage: /tools [enable|disable <name>]".to_string()),
+        },
+        ("memory", Some(query)) => Ok(Command::Memory(query)),
+        ("memory", None) => Err("Usage: /memory <query>".to_string()),
+        ("history", None) => Ok(Command::History),
+        ("history", Some(arg)) if arg == "compact" => Ok(Command::CompactHistory),
+        ("history", Some(_)) => Err("Usage: /history [compact]".to_string()),
+        ("save", _) => Ok(Command::Save),
+        ("attach", Some(path)) => Ok(Command::Attach(path)),
+        ("attach", None) => Err("Usage: /attach <path>".to_string()),
+        ("checkpoints", _) => Ok(Command::Checkpoints),
+        ("undo", _) => Ok(Command::Undo),
+        _ => Err(format!("Unknown command /{name}; /help lists commands")),
+    };
+    Some(command)
+}
+
+/// Tool names, enabled ones first.
+pub fn tool_list(enabled: &[Box<dyn Tool>], disabled: &[Box<dyn Tool>]) -> String {
+    let mut text = String::from("Tools:");
+    for tool in enabled {
+        let _ = write!(text, "\n  ✓ {}", tool.name());
+    }
+    for tool in disabled {
+        let _ = write!(text, "\n  ✗ {} (disabled)", tool.name());
+    }
+    text
+}
+
+/// Move the tool called `name` between `enabled` and `disabled`.
+pub fn set_tool_enabled(
+    enabled: &mut Vec<Box<dyn Tool>>,
+    disabled: &mut Vec<Box<dyn Tool>>,
+    name: &str,
+    enable: bool,
+) -> anyhow::Result<()> {
+    let (from, to) = if enable {
+        (disabled, enabled)
+    } else {
+        (enabled, disabled)
+    };
+    if let Some(index) = from.iter().position(|t| t.name() == name) {
+        to.push(from.remove(index));
+        return Ok(());
+    }
+    if to.iter().any(|t| t.name() == name) {
+        let state = if enable { "enabled" } else { "disabled" };
+        anyhow::bail!("`{name}` is already {state}");
+    }
+    anyhow::bail!("No tool named `{name}`; /tools lists them")
+}
+
+/// Memories matching `query`, one per line.
+pub async fn search_memory(memory: &dyn Memory, query: &str) -> anyhow::Result<String> {
+    let entries = memory.recall(query, MEMORY_RESULTS).await?;
+    if entries.is_empty() {
+        return Ok(format!("No memories match \"{query}\""));
+    }
+    let mut text = format!("Memories matching \"{query}\":");
+    for entry in &entries {
+        let _ = write!(
+            text,
+            "\n  - {}: {}",
+            entry.key,
+            truncate_with_ellipsis(&entry.content, 120)
+        );
+    }
+    Ok(text)
+}
+
+/// One line per message after the system prompt, with the estimated size.
+pub fn history_overview(history: &[ChatMessage], model: &str) -> String {
+    let family = Family::for_model(model);
+    let mut text = format!(
+        "History: {} messages, about {} tokens",
+        history.len(),
+        family.count_messages(history)
+    );
+    for (i, message) in history.iter().enumerate().skip(1) {
+        let line = message.content.lines().next().unwrap_or_default();
+        let _ = write!(
+            text,
+            "\n  {i:>3}. {}: {}",
+            message.role,
+            truncate_with_ellipsis(line, 80)
+        );
+    }
+    text
+}
+
+/// Replace all but the last few messages after the system prompt with a
+/// model-written summary. Returns how many messages were summarized.
+pub async fn compact_history(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    model: &str,
+) -> anyhow::Result<usize> {
+    let start = usize::from(history.first().is_some_and(|m| m.role == "system"));
+    // Keep whole turns: the kept part starts at a user message, not tool results
+    let cut = (start..=history.len().saturating_sub(KEEP_RECENT_MESSAGES))
+        .rev()
+        .find(|&i| history[i].role == "user" && !history[i].content.starts_with("[Tool results]"))
+        .unwrap_or(start);
+    if cut <= start + 1 {
+        anyhow::bail!("History is too short to compact");
+    }
+
+    let mut transcript = String::new();
+    for message in &history[start..cut] {
+        let _ = write!(transcript, "{}: {}\n\n", message.role, message.content);
+    }
+    let summary = provider
+        .chat_with_system(Some(COMPACT_PROMPT), &transcript, model, 0.0)
+        .await?;
+
+    history.splice(
+        start..cut,
+        [
+            ChatMessage::user(format!(
+                "[Summary of the earlier conversation]\n{}",
+                summary.trim()
+            )),
+            ChatMessage::assistant("Noted."),
+        ],
+    );
+    Ok(cut - start)
+}
+
+/// Whether `line` opens a ```` ``` ```` block that later lines continue.
+pub fn opens_block(line: &str) -> bool {
+    line.trim_start().starts_with("```") && line.matches("```").count() % 2 == 1
+}
+
+/// Whether `line` closes a block opened by [`opens_block`].
+pub fn closes_block(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// A file to send with the next message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attachment {
+    Text { name: String, content: String },
+    Image { name: String, image: ImagePart },
+}
+
+impl Attachment {
+    /// Read `path`: UTF-8 files are attached as text, anything else must be
+    /// an image.
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let name = path.display().to_string();
+        let bytes = std::fs::read(path)?;
+        match String::from_utf8(bytes) {
+            Ok(content) if content.len() > MAX_ATTACHMENT_BYTES => anyhow::bail!(
+                "{name} is too large to attach ({} KB, limit {} KB)",
+                content.len() / 1024,
+                MAX_ATTACHMENT_BYTES / 1024
+            ),
+            Ok(content) => Ok(Self::Text { name, content }),
+            Err(_) => match ImagePart::from_path(path) {
+                Ok(image) => Ok(Self::Image { name, image }),
+                Err(_) => anyhow::bail!("{name} is neither a text file nor a supported image"),
+            },
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Text { name, content } => {
+                format!("{name} ({} lines)", content.lines().count())
+            }
+            Self::Image { name, image } => format!("{name} ({})", image.mime_type),
+        }
+    }
+}
+
+/// Add `attachments` to `message`: text files after its content, images as
+/// image parts (described by the vision fallback for text-only models).
+pub fn attach(message: &mut ChatMessage, attachments: Vec<Attachment>) {
+    for attachment in attachments {
+        match attachment {
+            Attachment::Text { name, content } => {
+                let _ = write!(
+                    message.content,
+                    "\n\n[Attached file: {name}]\n```\n{}\n```",
+                    content.trim_end()
+                );
+            }
+            Attachment::Image { name, image } => {
+                let _ = write!(message.content, "\n\n[Attached image: {name}]");
+                message.images.push(image);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+    use crate::tools::CalculatorTool;
+
+    #[test]
+    fn parses_commands_and_leaves_messages_alone() {
+        assert_eq!(parse("what is 2+2?"), None);
+        assert_eq!(parse("/model"), Some(Ok(Command::Model(None))));
+        assert_eq!(
+            parse(" /model  gpt-4o "),
+            Some(Ok(Command::Model(Some("gpt-4o".into()))))
+        );
+        assert_eq!(
+            parse("/tools disable shell"),
+            Some(Ok(Command::DisableTool("shell".into())))
+        );
+        assert_eq!(
+            parse("/memory deploy keys"),
+            Some(Ok(Command::Memory("deploy keys".into())))
+        );
+        assert_eq!(parse("/history compact"), Some(Ok(Command::CompactHistory)));
+        assert_eq!(
+            parse("/profile work"),
+            Some(Ok(Command::Profile(Some("work".into()))))
+        );
+        assert_eq!(parse("/exit"), Some(Ok(Command::Quit)));
+        assert_eq!(parse("/undo"), Some(Ok(Command::Undo)));
+        assert_eq!(
+            parse("/attach notes/todo.md"),
+            Some(Ok(Command::Attach("notes/todo.md".into())))
+        );
+        assert!(matches!(parse("/attach"), Some(Err(_))));
+        assert!(matches!(parse("/memory"), Some(Err(_))));
+        assert!(matches!(parse("/tools toggle shell"), Some(Err(_))));
+        assert!(matches!(parse("/frobnicate"), Some(Err(e)) if e.contains("/help")));
+    }
+
+    #[test]
+    fn tools_move_between_enabled_and_disabled() {
+        let mut enabled: Vec<Box<dyn Tool>> = vec![Box::new(CalculatorTool::new())];
+        let mut disabled = Vec::new();
+
+        set_tool_enabled(&mut enabled, &mut disabled, "calculator", false).unwrap();
+        assert!(enabled.is_empty());
+        assert!(tool_list(&enabled, &disabled).contains("✗ calculator (disabled)"));
+        assert!(set_tool_enabled(&mut enabled, &mut disabled, "calculator", false).is_err());
+        assert!(set_tool_enabled(&mut enabled, &mut disabled, "nope", true).is_err());
+
+        set_tool_enabled(&mut enabled, &mut disabled, "calculator", true).unwrap();
+        assert_eq!(enabled.len(), 1);
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn fences_open_and_close_multi_line_blocks() {
+        assert!(opens_block("```"));
+        assert!(opens_block("```rust"));
+        assert!(!opens_block("```inline```"));
+        assert!(!opens_block("say ```hi"));
+        assert!(closes_block("  ```"));
+        assert!(!closes_block("let x = 1;"));
+    }
+
+    #[test]
+    fn text_and_image_files_are_attached() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = dir.path().join("notes.md");
+        std::fs::write(&text, "- buy milk\n- call Sam\n").unwrap();
+        let image = dir.path().join("shot.png");
+        std::fs::write(&image, [0x89, b'P', b'N', b'G', 0xff, 0x00]).unwrap();
+        let binary = dir.path().join("blob.bin");
+        std::fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+
+        let mut message = ChatMessage::user("What is on these?");
+        attach(
+            &mut message,
+            vec![
+                Attachment::read(&text).unwrap(),
+                Attachment::read(&image).unwrap(),
+            ],
+        );
+        assert!(message
+            .content
+            .contains("notes.md]\n```\n- buy milk\n- call Sam\n```"));
+        assert!(message.content.ends_with("shot.png]"));
+        assert_eq!(message.images.len(), 1);
+        assert_eq!(message.images[0].mime_type, "image/png");
+
+        assert!(Attachment::read(&binary).is_err());
+        assert!(Attachment::read(&dir.path().join("missing.txt")).is_err());
+    }
+
+    #[tokio::test]
+    async fn compaction_summarizes_older_turns_and_keeps_recent_ones() {
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("first question"),
+            ChatMessage::assistant("first answer"),
+            ChatMessage::user("second question"),
+            ChatMessage::assistant("<tool_call>...</tool_call>"),
+            ChatMessage::user("[Tool results]\nok"),
+            ChatMessage::assistant("second answer"),
+            ChatMessage::user("latest question"),
+            ChatMessage::assistant("latest answer"),
+        ];
+        let summarizer = ScriptedProvider::always("The user asked two questions.");
+        let summarized = compact_history(&summarizer, &mut history, "gpt-4o")
+            .await
+            .unwrap();
+
+        let request = summarizer.requests().pop().unwrap();
+        assert!(request.last().contains("user: first question"));
+        assert!(!request.last().contains("latest question"));
+        assert_eq!(summarized, 6);
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[0].content, "sys");
+        assert!(history[1]
+            .content
+            .ends_with("The user asked two questions."));
+        assert_eq!(history[3].content, "latest question");
+
+        let mut short = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+        assert!(compact_history(&summarizer, &mut short, "gpt-4o")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! Ctrl-C in interactive sessions.
+//!
+//! A press while a turn is running cancels just that turn (the provider call
+//! or tool in flight) and returns to the prompt. A second press within
+//! [`DOUBLE_PRESS`] of the first quits the session.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// How close together two presses must be to quit.
+pub const DOUBLE_PRESS: Duration = Duration::from_secs(2);
+
+/// Error returned by a turn cancelled through its token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TurnCancelled;
+
+impl fmt::Display for TurnCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("turn cancelled")
+    }
+}
+
+impl std::error::Error for TurnCancelled {}
+
+/// Tracks Ctrl-C presses to tell a cancel from a quit.
+#[derive(Debug, Default)]
+pub struct Interrupts {
+    last: Option<Instant>,
+}
+
+impl Interrupts {
+    /// Record a press; true when it follows the previous one closely enough
+    /// to mean "quit".
+    pub fn press(&mut self) -> bool {
+        self.press_at(Instant::now())
+    }
+
+    fn press_at(&mut self, now: Instant) -> bool {
+        let quit = self
+            .last
+            .is_some_and(|last| now.duration_since(last) <= DOUBLE_PRESS);
+        self.last = if quit { None } else { Some(now) };
+        quit
+    }
+}
+
+/// A token cancelled by the next Ctrl-C. Abort the returned task once the
+/// turn it guards is over.
+pub fn cancel_on_ctrl_c() -> (CancellationToken, JoinHandle<()>) {
+    let cancel = CancellationToken::new();
+    let watcher = tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancel.cancel();
+            }
+        }
+    });
+    (cancel, watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_quick_second_press_quits() {
+        let start = Instant::now();
+        let mut interrupts = Interrupts::default();
+        assert!(!interrupts.press_at(start));
+        assert!(!interrupts.press_at(start + DOUBLE_PRESS * 2));
+        assert!(interrupts.press_at(start + DOUBLE_PRESS * 2 + Duration::from_millis(300)));
+        // The quitting press is not counted towards the next pair
+        assert!(!interrupts.press_at(start + DOUBLE_PRESS * 2 + Duration::from_millis(600)));
+    }
+}
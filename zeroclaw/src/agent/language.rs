@@ -0,0 +1,368 @@
+//! Reply language enforcement.
+//!
+//! `[identity.persona] language` (or a channel override) names the language
+//! replies must be in, or `"auto"` for the language of the user's message.
+//! The persona prompt already asks for it; models still drift back to
+//! English after tool output or long English context, so each final answer
+//! is checked with a small script and stop-word detector and, when it is
+//! clearly in another language, the model is asked once to rewrite it.
+//! Answers too short or too mixed to classify are left alone.
+
+use crate::config::IdentityConfig;
+use crate::providers::{ChatMessage, Provider};
+
+/// Value of `language` that follows the user's message.
+pub const AUTO: &str = "auto";
+
+/// Prefix of the user message that carries tool output in the agent loop.
+const TOOL_RESULTS_PREFIX: &str = "[Tool results]";
+
+/// Latin-script text needs at least this many words to be classified.
+const MIN_WORDS: usize = 4;
+
+/// Languages the detector can tell apart: code, English name, native name.
+const LANGUAGES: &[(&str, &str, &str)] = &[
+    ("en", "English", "English"),
+    ("de", "German", "Deutsch"),
+    ("fr", "French", "Français"),
+    ("es", "Spanish", "Español"),
+    ("it", "Italian", "Italiano"),
+    ("pt", "Portuguese", "Português"),
+    ("nl", "Dutch", "Nederlands"),
+    ("ru", "Russian", "Русский"),
+    ("zh", "Chinese", "中文"),
+    ("ja", "Japanese", "日本語"),
+    ("ko", "Korean", "한국어"),
+    ("ar", "Arabic", "العربية"),
+    ("he", "Hebrew", "עברית"),
+    ("el", "Greek", "Ελληνικά"),
+    ("hi", "Hindi", "हिन्दी"),
+    ("th", "Thai", "ไทย"),
+];
+
+/// Common short words, per Latin-script language.
+const STOP_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "of", "to", "in", "that", "it", "you", "for", "with",
+            "this", "was", "have", "not", "be", "on", "can", "will",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "sie", "mit", "den", "ein", "eine",
+            "zu", "auf", "für", "sich", "auch", "es", "wir", "kann",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "des", "une", "un", "que", "pas", "pour", "dans",
+            "vous", "sur", "avec", "du", "ce", "qui", "je", "sont",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "y", "es", "que", "de", "en", "un", "una", "por", "para",
+            "con", "no", "del", "se", "su", "lo", "está",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "la", "di", "che", "e", "è", "un", "una", "per", "non", "con", "sono", "gli",
+            "del", "della", "le", "lo", "si", "questo", "anche",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "e", "é", "que", "de", "um", "uma", "não", "para", "com", "do",
+            "da", "em", "no", "na", "você", "são",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "van", "niet", "dat", "ik", "je", "met", "op", "zijn",
+            "voor", "er", "te", "maar", "ook", "wat", "dit",
+        ],
+    ),
+];
+
+const REWRITE_PROMPT: &str = "Your last reply was not in the required language. Rewrite it \
+in {language}, keeping its content, structure and formatting; leave code, commands, file \
+names and quoted text as they are. Output only the rewritten reply.";
+
+/// The `language` setting for `channel`, if any.
+pub fn configured(identity: &IdentityConfig, channel: &str) -> Option<String> {
+    identity
+        .for_channel(channel)
+        .persona
+        .language
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+}
+
+/// ISO 639-1 code of a language given by code or by English or native
+/// name, if the detector knows it.
+pub fn code_for(language: &str) -> Option<&'static str> {
+    let wanted = language.trim().to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(code, english, native)| {
+            wanted == *code || wanted == english.to_lowercase() || wanted == native.to_lowercase()
+        })
+        .map(|(code, _, _)| *code)
+}
+
+fn english_name(code: &str) -> &'static str {
+    LANGUAGES
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map_or("the required language", |(_, english, _)| english)
+}
+
+/// Best guess at the language of `text`, as an ISO 639-1 code. `None` when
+/// the text is too short or mixed to tell. Code blocks are ignored.
+pub fn detect(text: &str) -> Option<&'static str> {
+    let prose = strip_code(text);
+
+    let mut latin = 0usize;
+    let mut scripts = [
+        ("kana", 0usize),
+        ("zh", 0),
+        ("ko", 0),
+        ("ru", 0),
+        ("ar", 0),
+        ("he", 0),
+        ("el", 0),
+        ("hi", 0),
+        ("th", 0),
+    ];
+    for c in prose.chars().filter(|c| c.is_alphabetic()) {
+        let slot = match c as u32 {
+            0x3040..=0x30FF => 0,
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => 1,
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => 2,
+            0x0400..=0x04FF => 3,
+            0x0600..=0x06FF => 4,
+            0x0590..=0x05FF => 5,
+            0x0370..=0x03FF => 6,
+            0x0900..=0x097F => 7,
+            0x0E00..=0x0E7F => 8,
+            _ if c.is_ascii_alphabetic() || ('\u{C0}'..='\u{24F}').contains(&c) => {
+                latin += 1;
+                continue;
+            }
+            _ => continue,
+        };
+        scripts[slot].1 += 1;
+    }
+
+    let (script, count) = scripts
+        .iter()
+        .copied()
+        .max_by_key(|(_, count)| *count)
+        .unwrap_or(("kana", 0));
+    if count > latin {
+        // Japanese mixes kanji with kana; kanji alone reads as Chinese
+        return match script {
+            "kana" => Some("ja"),
+            "zh" if scripts[0].1 > 0 => Some("ja"),
+            other => Some(other),
+        };
+    }
+    detect_latin(&prose)
+}
+
+fn detect_latin(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < MIN_WORDS {
+        return None;
+    }
+    let mut scores: Vec<(&'static str, usize)> = STOP_WORDS
+        .iter()
+        .map(|(code, stop_words)| {
+            let hits = words
+                .iter()
+                .filter(|w| stop_words.contains(&w.as_str()))
+                .count();
+            (*code, hits)
+        })
+        .collect();
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+    let (best, best_hits) = scores[0];
+    let runner_up = scores.get(1).map_or(0, |s| s.1);
+    // Require a clear winner: languages share many short words
+    (best_hits >= 2 && best_hits * 2 > runner_up * 3).then_some(best)
+}
+
+/// Drop fenced code blocks and inline code, which are usually English
+/// whatever language the prose is in.
+fn strip_code(text: &str) -> String {
+    let mut prose = String::with_capacity(text.len());
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        for (i, part) in line.split('`').enumerate() {
+            if i % 2 == 0 {
+                prose.push_str(part);
+                prose.push(' ');
+            }
+        }
+        prose.push('\n');
+    }
+    prose
+}
+
+/// The language `answer` must be in under `setting`: the configured one, or
+/// the user's for `"auto"`. `None` when there is nothing to enforce.
+fn expected(setting: &str, history: &[ChatMessage]) -> Option<&'static str> {
+    if !setting.eq_ignore_ascii_case(AUTO) {
+        return code_for(setting);
+    }
+    let user = history
+        .iter()
+        .rev()
+        .find(|m| m.role == "user" && !m.content.starts_with(TOOL_RESULTS_PREFIX))?;
+    detect(&user.content)
+}
+
+/// Check that `answer`, the final reply of the turn in `history`, is in the
+/// language `setting` asks for, and have the model rewrite it once when it
+/// is not. The final assistant message in `history` is updated to match.
+/// Best-effort: any failure leaves the answer unchanged.
+pub async fn enforce(
+    provider: &dyn Provider,
+    history: &mut [ChatMessage],
+    answer: String,
+    model: &str,
+    setting: Option<&str>,
+) -> String {
+    let Some(expected) = setting.and_then(|s| expected(s, history)) else {
+        return answer;
+    };
+    let Some(actual) = detect(&answer) else {
+        return answer;
+    };
+    if actual == expected {
+        return answer;
+    }
+    tracing::info!(
+        expected,
+        actual,
+        "Reply language mismatch; asking for a rewrite"
+    );
+
+    let mut messages = history.to_vec();
+    if messages.last().is_none_or(|m| m.role != "assistant") {
+        messages.push(ChatMessage::assistant(answer.clone()));
+    }
+    messages.push(ChatMessage::user(
+        REWRITE_PROMPT.replace("{language}", english_name(expected)),
+    ));
+    let rewritten = match provider.chat_with_history(&messages, model, 0.0).await {
+        Ok(reply) if !reply.trim().is_empty() => reply,
+        Ok(_) => return answer,
+        Err(e) => {
+            tracing::warn!("Could not rewrite the reply in {expected}: {e}");
+            return answer;
+        }
+    };
+
+    if let Some(last) = history.iter_mut().rev().find(|m| m.role == "assistant") {
+        last.content.clone_from(&rewritten);
+    }
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+
+    #[test]
+    fn detects_scripts_and_common_latin_languages() {
+        assert_eq!(
+            detect("The build is green and the tests pass for this branch."),
+            Some("en")
+        );
+        assert_eq!(
+            detect("Der Build ist grün und die Tests laufen auf dem Branch."),
+            Some("de")
+        );
+        assert_eq!(
+            detect("Le build est vert et les tests passent pour cette branche."),
+            Some("fr")
+        );
+        assert_eq!(
+            detect("La compilación está bien y los tests pasan en la rama."),
+            Some("es")
+        );
+        assert_eq!(detect("Сборка прошла успешно, тесты зелёные."), Some("ru"));
+        assert_eq!(
+            detect("ビルドは成功しました。テストも通っています。"),
+            Some("ja")
+        );
+        assert_eq!(detect("构建成功，测试全部通过。"), Some("zh"));
+        assert_eq!(detect("빌드가 성공했습니다"), Some("ko"));
+        // Too short, or only code
+        assert_eq!(detect("OK"), None);
+        assert_eq!(detect("```\nthe and is are of to in\n```"), None);
+    }
+
+    #[test]
+    fn language_names_resolve_to_codes() {
+        assert_eq!(code_for("German"), Some("de"));
+        assert_eq!(code_for(" deutsch "), Some("de"));
+        assert_eq!(code_for("ja"), Some("ja"));
+        assert_eq!(code_for("Klingon"), None);
+    }
+
+    #[tokio::test]
+    async fn mismatched_replies_are_rewritten_once() {
+        let provider =
+            ScriptedProvider::always("Der Build ist grün und alle Tests sind bestanden.");
+        let english = "The build is green and all the tests have passed.";
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("Ist der Build für den Branch grün und sind die Tests ok?"),
+            ChatMessage::assistant(english),
+        ];
+
+        let answer = enforce(&provider, &mut history, english.into(), "m", Some("auto")).await;
+        assert!(answer.starts_with("Der Build"));
+        assert_eq!(history[2].content, answer);
+        assert!(provider.requests()[0].last().contains("in German"));
+
+        // Matching, unset or unknown settings leave the answer alone
+        let again = enforce(&provider, &mut history, answer.clone(), "m", Some("de")).await;
+        assert_eq!(again, answer);
+        let unset = enforce(&provider, &mut history, english.into(), "m", None).await;
+        assert_eq!(unset, english);
+        let unknown = enforce(
+            &provider,
+            &mut history,
+            english.into(),
+            "m",
+            Some("Klingon"),
+        )
+        .await;
+        assert_eq!(unknown, english);
+        assert_eq!(provider.calls(), 1);
+    }
+}
@@ -1,33 +1,78 @@
-use crate::config::Config;
+use super::budget::{BudgetExhausted, BudgetTracker, TurnBudget, TurnReply};
+use super::interrupt::{Interrupts, TurnCancelled};
+use crate::config::{Config, ToolSchemaFormat};
+use crate::guardrails::Guardrails;
+use crate::hooks;
+use crate::knowledge;
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::observability::{self, Observer, ObserverEvent};
-use crate::providers::{self, ChatMessage, Provider};
+use crate::providers::{self, ChatMessage, ImagePart, Provider, VisionFallback};
 use crate::runtime;
-use crate::security::SecurityPolicy;
+use crate::security::{ActionBlocked, SecurityPolicy};
+use crate::sessions::{self, Session, SessionStore};
+use crate::tokens;
+use crate::tools::ask_user::{ChannelPrompt, TerminalPrompt, UserPrompt};
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
+use futures_util::StreamExt;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Write;
-use std::io::Write as IoWrite;
+use std::io::{IsTerminal, Write as IoWrite};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 /// Maximum agentic tool-use iterations per user message to prevent runaway loops.
 const MAX_TOOL_ITERATIONS: usize = 1024;
 
+/// Failures in a row of the same call (tool and arguments) before the model
+/// is told to change approach. One more failure ends the turn.
+const REPEATED_FAILURE_LIMIT: u32 = 3;
+
 /// Maximum number of non-system messages to keep in history.
 /// When exceeded, the oldest messages are dropped (system prompt is always preserved).
 const MAX_HISTORY_MESSAGES: usize = 50;
 
+/// Estimated tokens of history kept before the oldest messages are dropped:
+/// three quarters of the model's context window (96k for most models).
+fn max_history_tokens(model: &str) -> u64 {
+    tokens::context_window(model) / 4 * 3
+}
+
 fn autosave_memory_key(prefix: &str) -> String {
     format!("{prefix}_{}", Uuid::new_v4())
 }
 
+/// Append one user/assistant exchange to the session transcript and persist it.
+/// The session is titled right after its first exchange.
+async fn record_session_turn(
+    store: Option<&SessionStore>,
+    session: &mut Session,
+    provider: &dyn Provider,
+    config: &Config,
+    model: &str,
+    user_message: &str,
+    response: &str,
+) {
+    // Kept without a store too, for the summary when the session ends
+    session.push(ChatMessage::user(user_message));
+    session.push(ChatMessage::assistant(response));
+    let Some(store) = store else {
+        return;
+    };
+    sessions::ensure_title(session, provider, &config.sessions, model).await;
+    if let Err(e) = store.save(session) {
+        tracing::warn!("Failed to save session {}: {e}", session.id());
+    }
+}
+
 /// Trim conversation history to prevent unbounded growth.
-/// Preserves the system prompt (first message if role=system) and the most recent messages.
-fn trim_history(history: &mut Vec<ChatMessage>) {
-    // Nothing to trim if within limit
+/// Preserves the system prompt (first message if role=system) and the most
+/// recent messages, keeping at most `MAX_HISTORY_MESSAGES` of them and
+/// dropping older ones while the history exceeds [`max_history_tokens`].
+pub(crate) fn trim_history(history: &mut Vec<ChatMessage>, model: &str) {
     let has_system = history.first().map_or(false, |m| m.role == "system");
     let non_system_count = if has_system {
         history.len() - 1
@@ -35,30 +80,40 @@ fn trim_history(history: &mut Vec<ChatMessage>) {
         history.len()
     };
 
-    if non_system_count <= MAX_HISTORY_MESSAGES {
-        return;
+    let start = if has_system { 1 } else { 0 };
+    let mut to_remove = non_system_count.saturating_sub(MAX_HISTORY_MESSAGES);
+
+    let family = tokens::Family::for_model(model);
+    let max_tokens = max_history_tokens(model);
+    let mut total = family.count_messages(&history[..start])
+        + history[start + to_remove..]
+            .iter()
+            .map(|m| family.count_message(m))
+            .sum::<u64>();
+    while total > max_tokens && non_system_count - to_remove > 1 {
+        total -= family.count_message(&history[start + to_remove]);
+        to_remove += 1;
     }
 
-    let start = if has_system { 1 } else { 0 };
-    let to_remove = non_system_count - MAX_HISTORY_MESSAGES;
     history.drain(start..start + to_remove);
 }
 
-/// Build context preamble by searching memory for relevant entries
-async fn build_context(mem: &dyn Memory, user_msg: &str) -> String {
-    let mut context = String::new();
-
-    // Pull relevant memories for this message
-    if let Ok(entries) = mem.recall(user_msg, 5).await {
-        if !entries.is_empty() {
-            context.push_str("[Memory context]\n");
-            for entry in &entries {
-                let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
-            }
-            context.push('\n');
-        }
-    }
-
+/// Build context preamble from the memories relevant enough to `user_msg`,
+/// the knowledge graph around what it mentions, then the knowledge base for
+/// up to `knowledge_chunks` passages
+async fn build_context(
+    mem: &dyn Memory,
+    user_msg: &str,
+    config: &Config,
+    knowledge_chunks: usize,
+) -> String {
+    let mut context = memory::recall::build_context(mem, user_msg, &config.memory.recall).await;
+    context.push_str(&memory::graph::build_context(
+        &config.memory,
+        &config.workspace_dir,
+        user_msg,
+    ));
+    context.push_str(&knowledge::build_context(mem, user_msg, knowledge_chunks).await);
     context
 }
 
@@ -79,31 +134,31 @@ fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn Tool>
 /// Also supports JSON with `tool_calls` array from OpenAI-format responses.
 fn fix_truncated_json(s: &str) -> String {
     let result = s.trim().to_string();
-    
+
     // If JSON is already valid, return as-is
     if serde_json::from_str::<serde_json::Value>(&result).is_ok() {
         return result;
     }
-    
+
     // For truncated tool_calls, try to extract and fix the arguments field
     // Pattern: {"name": "...", "arguments": "..."} where arguments may be truncated
-    
+
     // Try to extract arguments value from JSON string and fix it
     let mut fixed = result.clone();
-    
+
     // Find "arguments": " pattern and try to fix the truncated JSON string
     if let Some(args_start) = fixed.find("\"arguments\": \"") {
         let after_args = &fixed[args_start + 14..]; // Skip "arguments": "
-        
+
         // Find where the arguments string should end (before the closing brace)
         let target_end = after_args.len();
-        
+
         // Try to find incomplete JSON patterns and fix them
         let mut depth = 0;
         let mut in_string = false;
         let mut escape_next = false;
         let mut end_pos = 0;
-        
+
         for (i, c) in after_args.chars().enumerate() {
             if escape_next {
                 escape_next = false;
@@ -123,14 +178,14 @@ fn fix_truncated_json(s: &str) -> String {
                 _ => {}
             }
         }
-        
+
         if end_pos > 0 {
             // Extract and try to fix the arguments
             let args_str = &after_args[..end_pos];
             if serde_json::from_str::<serde_json::Value>(args_str).is_err() {
                 // Arguments are truncated, try to fix common patterns
                 let mut fixed_args = args_str.to_string();
-                
+
                 // Close any open braces/brackets
                 let mut opens = 0i32;
                 for c in fixed_args.chars() {
@@ -144,7 +199,7 @@ fn fix_truncated_json(s: &str) -> String {
                     fixed_args.push('}');
                     opens -= 1;
                 }
-                
+
                 // Replace the truncated arguments with fixed version
                 let before = &fixed[..args_start + 14];
                 let after = &after_args[end_pos..];
@@ -152,7 +207,7 @@ fn fix_truncated_json(s: &str) -> String {
             }
         }
     }
-    
+
     // Try to close any remaining open braces/brackets at top level
     let mut opens = 0i32;
     for c in fixed.chars() {
@@ -162,7 +217,7 @@ fn fix_truncated_json(s: &str) -> String {
             _ => {}
         }
     }
-    
+
     while opens > 0 {
         if fixed.ends_with('[') || fixed.ends_with('{') {
             fixed.push_str("null}");
@@ -171,13 +226,155 @@ fn fix_truncated_json(s: &str) -> String {
         }
         opens -= 1;
     }
-    
+
     // Remove trailing comma
     fixed = fixed.trim_end_matches(',').to_string();
-    
+
     fixed
 }
 
+/// Parse what sits between `<tool_call>` and `</tool_call>`: JSON with
+/// `name` and `arguments`, or the `<name>`/`<arguments>` tag form.
+fn parse_tool_call_body(inner: &str) -> Option<ParsedToolCall> {
+    // Try to parse as JSON first
+    if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(inner.trim()) {
+        let name = parsed
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        let arguments = parsed
+            .get("arguments")
+            .cloned()
+            .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+        if !name.is_empty() {
+            return Some(ParsedToolCall { name, arguments });
+        }
+    } else {
+        // Try to extract name and arguments from XML-like format
+        // <name>shell</name><arguments>{"command": "..."}</arguments>
+        let name = if let Some(n_start) = inner.find("<name>") {
+            if let Some(n_end) = inner[n_start..].find("</name>") {
+                let n = &inner[n_start + 6..n_start + n_end];
+                Some(n.trim().to_string())
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        let args = if let Some(a_start) = inner.find("<arguments>") {
+            if let Some(a_end) = inner[a_start..].find("</arguments>") {
+                let a = &inner[a_start + 11..a_start + a_end];
+                Some(a.trim().to_string())
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        if let (Some(n), Some(a)) = (name, args) {
+            if !n.is_empty() {
+                // Try to parse arguments as JSON
+                let arguments = serde_json::from_str(&a)
+                    .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+                return Some(ParsedToolCall { name: n, arguments });
+            }
+        }
+    }
+    None
+}
+
+tokio::task_local! {
+    static ON_DELTA: Arc<dyn Fn(&str) + Send + Sync>;
+}
+
+/// Run `turn` with `on_delta` receiving the model's text as it streams in,
+/// minus `<tool_call>` blocks. It sees every model reply in the turn, so
+/// text from before a tool call comes too; the turn's return value stays
+/// the answer of record, after verification and guardrails.
+pub async fn stream_deltas<F: std::future::Future>(
+    on_delta: Arc<dyn Fn(&str) + Send + Sync>,
+    turn: F,
+) -> F::Output {
+    ON_DELTA.scope(on_delta, turn).await
+}
+
+fn emit_delta(text: &str) {
+    if !text.is_empty() {
+        let _ = ON_DELTA.try_with(|on_delta| on_delta(text));
+    }
+}
+
+/// Finds complete `<tool_call>` blocks in a reply while it streams in, so
+/// each call can start as soon as its closing tag arrives.
+#[derive(Default)]
+struct ToolCallScanner {
+    buf: String,
+    /// Byte offset up to which `buf` holds no unfinished block
+    scanned: usize,
+    /// Byte offset up to which text outside blocks was handed out
+    shown: usize,
+    visible: String,
+}
+
+impl ToolCallScanner {
+    /// Append a delta; returns the calls it completed.
+    fn push(&mut self, delta: &str) -> Vec<ParsedToolCall> {
+        self.buf.push_str(delta);
+        let mut calls = Vec::new();
+        let mut open = None;
+        while let Some(offset) = self.buf[self.scanned..].find("<tool_call>") {
+            let start = self.scanned + offset;
+            let Some(end) = self.buf[start..].find("</tool_call>") else {
+                open = Some(start);
+                break;
+            };
+            calls.extend(parse_tool_call_body(&self.buf[start + 11..start + end]));
+            self.show_until(start);
+            self.scanned = start + end + 12;
+            self.shown = self.scanned;
+        }
+        // Hold back what may be the start of a tag still arriving
+        let safe = open.unwrap_or_else(|| {
+            let tail = &self.buf[self.shown..];
+            let held = (1..=tail.len().min(10))
+                .rev()
+                .find(|&n| {
+                    let start = tail.len() - n;
+                    tail.is_char_boundary(start) && "<tool_call>".starts_with(&tail[start..])
+                })
+                .unwrap_or(0);
+            self.buf.len() - held
+        });
+        self.show_until(safe);
+        calls
+    }
+
+    fn show_until(&mut self, end: usize) {
+        if end > self.shown {
+            self.visible.push_str(&self.buf[self.shown..end]);
+            self.shown = end;
+        }
+    }
+
+    /// Text outside blocks that arrived since the last call.
+    fn take_visible(&mut self) -> String {
+        std::mem::take(&mut self.visible)
+    }
+
+    /// The text held back at the end of the reply, unless a block is unfinished.
+    fn finish(&mut self) -> String {
+        if !self.buf[self.shown..].contains("<tool_call>") {
+            let end = self.buf.len();
+            self.show_until(end);
+        }
+        self.take_visible()
+    }
+}
+
 fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
     let mut text_parts = Vec::new();
     let mut calls = Vec::new();
@@ -226,9 +423,11 @@ fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
 
     // Fall back to XML-style <invoke> tag parsing (ZeroClaw's original format)
     // First, remove any <thinking> tags that might interfere with parsing
-    let cleaned_response = response.replace("<thinking>", "").replace("</thinking>", "");
+    let cleaned_response = response
+        .replace("<thinking>", "")
+        .replace("</thinking>", "");
     let mut remaining = cleaned_response.as_str();
-    
+
     while let Some(start) = remaining.find("<tool_call>") {
         // Everything before the tag is text
         let before = &remaining[..start];
@@ -238,45 +437,7 @@ fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
 
         if let Some(end) = remaining[start..].find("</tool_call>") {
             let inner = &remaining[start + 11..start + end];
-            // Try to parse as JSON first
-            if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(inner.trim()) {
-                let name = parsed
-                    .get("name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                let arguments = parsed
-                    .get("arguments")
-                    .cloned()
-                    .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
-                if !name.is_empty() {
-                    calls.push(ParsedToolCall { name, arguments });
-                }
-            } else {
-                // Try to extract name and arguments from XML-like format
-                // <name>shell</name><arguments>{"command": "..."}</arguments>
-                let name = if let Some(n_start) = inner.find("<name>") {
-                    if let Some(n_end) = inner[n_start..].find("</name>") {
-                        let n = &inner[n_start + 6..n_start + n_end];
-                        Some(n.trim().to_string())
-                    } else { None }
-                } else { None };
-                
-                let args = if let Some(a_start) = inner.find("<arguments>") {
-                    if let Some(a_end) = inner[a_start..].find("</arguments>") {
-                        let a = &inner[a_start + 11..a_start + a_end];
-                        Some(a.trim().to_string())
-                    } else { None }
-                } else { None };
-                
-                if let (Some(n), Some(a)) = (name, args) {
-                    if !n.is_empty() {
-                        // Try to parse arguments as JSON
-                        let arguments = serde_json::from_str(&a).unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
-                        calls.push(ParsedToolCall { name: n, arguments });
-                    }
-                }
-            }
+            calls.extend(parse_tool_call_body(inner));
             remaining = &remaining[start + end + 12..];
         } else {
             break;
@@ -291,7 +452,28 @@ fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
     (text_parts.join("\n"), calls)
 }
 
-#[derive(Debug)]
+/// Pull `data:image/...;base64,...` payloads (as emitted by `screenshot` and
+/// `image_info`) out of a tool result so they can be sent as real image parts.
+/// Each payload is replaced by a short placeholder in the text.
+fn extract_inline_images(output: &str) -> (String, Vec<ImagePart>) {
+    let mut images = Vec::new();
+    let mut text = String::with_capacity(output.len().min(4096));
+    for line in output.lines() {
+        if let Some(image) = ImagePart::from_data_uri(line) {
+            let _ = writeln!(text, "[image attached: {}]", image.mime_type);
+            images.push(image);
+        } else {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    if !output.ends_with('\n') {
+        text.pop();
+    }
+    (text, images)
+}
+
+#[derive(Debug, Clone)]
 struct ParsedToolCall {
     name: String,
     arguments: serde_json::Value,
@@ -307,10 +489,380 @@ pub async fn agent_turn(
     model: &str,
     temperature: f64,
 ) -> Result<String> {
+    agent_turn_with_vision(
+        provider,
+        history,
+        tools_registry,
+        observer,
+        model,
+        temperature,
+        None,
+    )
+    .await
+}
+
+/// Like [`agent_turn`], but when `model` cannot see images, any images in the
+/// conversation (user attachments or tool output) are described by
+/// `vision_fallback` and passed on as text instead of being dropped.
+pub async fn agent_turn_with_vision(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    model: &str,
+    temperature: f64,
+    vision_fallback: Option<&VisionFallback>,
+) -> Result<String> {
+    agent_turn_with_budget(
+        provider,
+        history,
+        tools_registry,
+        observer,
+        model,
+        temperature,
+        vision_fallback,
+        TurnBudget::default(),
+    )
+    .await
+    .and_then(TurnReply::into_result)
+}
+
+/// Like [`agent_turn_with_vision`], stopping once the turn would exceed
+/// `budget`. The wall-clock limit cancels the turn wherever it is, including
+/// mid-tool. A stopped turn still replies: with the best partial answer and
+/// the cap it hit, and the partial answer ends `history`.
+#[allow(clippy::too_many_arguments)]
+pub async fn agent_turn_with_budget(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    model: &str,
+    temperature: f64,
+    vision_fallback: Option<&VisionFallback>,
+    budget: TurnBudget,
+) -> Result<TurnReply> {
+    agent_turn_with_cancel(
+        provider,
+        history,
+        tools_registry,
+        observer,
+        model,
+        temperature,
+        vision_fallback,
+        budget,
+        &CancellationToken::new(),
+    )
+    .await
+}
+
+/// Like [`agent_turn_with_budget`], also stopping when `cancel` fires. A
+/// cancelled turn fails with [`TurnCancelled`] and takes back everything it
+/// added to `history`, so the conversation reads as if it never ran.
+#[allow(clippy::too_many_arguments)]
+pub async fn agent_turn_with_cancel(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    model: &str,
+    temperature: f64,
+    vision_fallback: Option<&VisionFallback>,
+    budget: TurnBudget,
+    cancel: &CancellationToken,
+) -> Result<TurnReply> {
+    let start = history.len();
+    let mut message = String::new();
+    if let Some(last) = history.last_mut().filter(|m| m.role == "user") {
+        match hooks::turn_start(&last.content).await {
+            Ok(Some(rewritten)) => last.content = rewritten,
+            Ok(None) => {}
+            Err(reason) => {
+                let message = format!("Action blocked by a turn_start hook: {reason}");
+                return Err(ActionBlocked(message).into());
+            }
+        }
+        message.clone_from(&last.content);
+    }
+    let mut tracker = BudgetTracker::new(budget).for_model(model);
+    let turn = observability::trace::turn(run_turn(
+        provider,
+        history,
+        tools_registry,
+        observer,
+        model,
+        temperature,
+        vision_fallback,
+        &mut tracker,
+    ));
+    let turn = async {
+        match budget.max_seconds {
+            Some(secs) => tokio::time::timeout(Duration::from_secs(secs), turn)
+                .await
+                .unwrap_or_else(|_| Err(BudgetExhausted::Seconds(secs).into())),
+            None => turn.await,
+        }
+    };
+    // Dropping the turn stops it wherever it is; tool processes die with it
+    let result = tokio::select! {
+        result = turn => Some(result),
+        () = cancel.cancelled() => None,
+    };
+    let Some(result) = result else {
+        tracing::info!("Turn cancelled");
+        history.truncate(start);
+        return Err(TurnCancelled.into());
+    };
+    let reply = match result {
+        Ok(answer) => TurnReply {
+            answer,
+            exhausted: None,
+        },
+        Err(e) => {
+            let Some(&exhausted) = e.downcast_ref::<BudgetExhausted>() else {
+                return Err(e);
+            };
+            tracing::info!(limit = exhausted.limit(), "Turn stopped by its budget");
+            let answer = partial_answer(&history[start.min(history.len())..]);
+            if history.last().is_none_or(|m| m.role != "assistant") {
+                history.push(ChatMessage::assistant(&answer));
+            }
+            TurnReply {
+                answer,
+                exhausted: Some(exhausted),
+            }
+        }
+    };
+    hooks::turn_end(&message, &reply.answer).await;
+    Ok(reply)
+}
+
+/// Longest excerpt of tool results used as a partial answer.
+const PARTIAL_RESULTS_CHARS: usize = 2_000;
+
+/// Best answer available from the messages a stopped turn added: the text
+/// of the model's latest reply, else its latest tool results.
+fn partial_answer(turn: &[ChatMessage]) -> String {
+    let text = turn
+        .iter()
+        .rev()
+        .filter(|m| m.role == "assistant")
+        .map(|m| parse_tool_calls(&m.content).0)
+        .find(|text| !text.trim().is_empty());
+    if let Some(text) = text {
+        return text.trim().to_string();
+    }
+    let results = turn.iter().rev().find_map(|m| {
+        (m.role == "user")
+            .then(|| m.content.strip_prefix("[Tool results]"))
+            .flatten()
+    });
+    match results {
+        Some(results) => format!(
+            "I ran out of budget before finishing. The latest tool results were:\n{}",
+            truncate_with_ellipsis(results.trim(), PARTIAL_RESULTS_CHARS)
+        ),
+        None => "I ran out of budget before I could answer.".to_string(),
+    }
+}
+
+/// Run one tool call and render its outcome as the text sent back to the
+/// model, with whether it succeeded.
+async fn execute_tool_call(
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    call: &ParsedToolCall,
+) -> (String, bool) {
+    tracing::info!(tool_name = %call.name, arguments = %call.arguments, "Executing tool");
+    let start = Instant::now();
+    let _run = crate::daemon::shutdown::begin_run();
+    let Some(tool) = find_tool(tools_registry, &call.name) else {
+        return (format!("Unknown tool: {}", call.name), false);
+    };
+    let arguments = match hooks::pre_tool_use(&call.name, call.arguments.clone()).await {
+        Ok(arguments) => arguments,
+        Err(reason) => {
+            return (
+                format!("Error: blocked by a pre_tool_use hook: {reason}"),
+                false,
+            )
+        }
+    };
+    let (output, success) = match tool.execute(arguments.clone()).await {
+        Ok(r) if r.success => (r.output, true),
+        Ok(r) => (
+            format!("Error: {}", r.error.unwrap_or_else(|| r.output)),
+            false,
+        ),
+        Err(e) => (format!("Error executing {}: {e}", call.name), false),
+    };
+    let duration = start.elapsed();
+    observer.record_event(&ObserverEvent::ToolCall {
+        tool: call.name.clone(),
+        duration,
+        success,
+    });
+    // Outside a turn (tests, one-off runs) no stats database is created
+    if let Some(settings) = super::settings::current() {
+        let workspace = &settings.workspace_dir;
+        observability::tool_stats::record(workspace, &call.name, duration, success, output.len());
+    }
+    hooks::post_tool_use(&call.name, &arguments, success, &output).await;
+    (output, success)
+}
+
+/// Failed calls in the current turn, by tool and arguments.
+#[derive(Default)]
+struct RepeatedFailures(HashMap<String, u32>);
+
+impl RepeatedFailures {
+    /// Record how `call` went and return how many times in a row it has now
+    /// failed (0 after a success).
+    fn record(&mut self, call: &ParsedToolCall, success: bool) -> u32 {
+        let key = format!("{}\n{}", call.name, call.arguments);
+        if success {
+            self.0.remove(&key);
+            return 0;
+        }
+        let failures = self.0.entry(key).or_default();
+        *failures += 1;
+        *failures
+    }
+}
+
+/// Stream the model's reply, running each `<tool_call>` block in order as
+/// soon as it is complete instead of waiting for the whole reply. Returns
+/// the reply and the `(call, output, success)` of the calls that ran
+/// meanwhile.
+#[allow(clippy::too_many_arguments)]
+async fn stream_reply(
+    provider: &dyn Provider,
+    history: &[ChatMessage],
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    model: &str,
+    temperature: f64,
+    tracker: &mut BudgetTracker,
+) -> Result<(String, Vec<(ParsedToolCall, String, bool)>)> {
+    let specs: Vec<tools::ToolSpec> = tools_registry.iter().map(|t| t.spec()).collect();
+    let mut deltas = provider
+        .chat_stream(history, &specs, model, temperature)
+        .await?;
+    let mut response = String::new();
+    let mut scanner = ToolCallScanner::default();
+    let mut ready = VecDeque::new();
+    let mut results = Vec::new();
+    let mut running = None;
+
+    loop {
+        if running.is_none() {
+            if let Some(call) = ready.pop_front() {
+                tracker.charge_tool_call()?;
+                running = Some(Box::pin(async move {
+                    let (output, success) =
+                        execute_tool_call(tools_registry, observer, &call).await;
+                    (call, output, success)
+                }));
+            }
+        }
+        tokio::select! {
+            delta = deltas.next() => {
+                let Some(delta) = delta else {
+                    emit_delta(&scanner.finish());
+                    break;
+                };
+                let delta = delta?;
+                response.push_str(&delta);
+                ready.extend(scanner.push(&delta));
+                emit_delta(&scanner.take_visible());
+            }
+            result = async { running.as_mut().unwrap().await }, if running.is_some() => {
+                running = None;
+                results.push(result);
+            }
+        }
+    }
+
+    // Let the call in flight finish; calls not yet started run after the
+    // reply is charged to the budget, like non-streamed ones
+    if let Some(run) = running {
+        results.push(run.await);
+    }
+    Ok((response, results))
+}
+
+/// Report one model call, with its estimated usage and cost.
+fn record_llm_call(
+    observer: &dyn Observer,
+    model: &str,
+    history: &[ChatMessage],
+    response: &str,
+    duration: Duration,
+) {
+    let input_tokens = tokens::count_messages(model, history);
+    let output_tokens = tokens::count(model, response);
+    let cost_usd = super::settings::current().and_then(|settings| {
+        tokens::cost_usd(&settings.model_profiles, model, input_tokens, output_tokens)
+    });
+    if let Some(cost) = cost_usd {
+        crate::health::record_spend(cost);
+    }
+    observer.record_event(&ObserverEvent::LlmCall {
+        model: model.to_string(),
+        duration,
+        input_tokens,
+        output_tokens,
+        cost_usd,
+        input: history
+            .last()
+            .map(|m| m.content.clone())
+            .unwrap_or_default(),
+        output: response.to_string(),
+    });
+}
+
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+async fn run_turn(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    model: &str,
+    temperature: f64,
+    vision_fallback: Option<&VisionFallback>,
+    tracker: &mut BudgetTracker,
+) -> Result<String> {
+    let vision = provider.supports_vision(model);
+    let describer = vision_fallback.filter(|_| !vision);
+    let mut failures = RepeatedFailures::default();
+
     for iteration in 0..MAX_TOOL_ITERATIONS {
-        let response = provider
-            .chat_with_history(history, model, temperature)
-            .await?;
+        tracker.charge_iteration()?;
+        if let Some(describer) = describer {
+            let rewritten = describer.describe_history_images(history).await;
+            if rewritten > 0 {
+                tracing::info!(
+                    model = describer.model(),
+                    messages = rewritten,
+                    "Routed images through vision fallback"
+                );
+            }
+        }
+
+        tracker.charge_prompt(history)?;
+        let started = Instant::now();
+        let (response, early_results) = stream_reply(
+            provider,
+            history,
+            tools_registry,
+            observer,
+            model,
+            temperature,
+            tracker,
+        )
+        .await?;
+        tracker.charge_response(&response);
+        record_llm_call(observer, model, history, &response, started.elapsed());
 
         let (text, tool_calls) = parse_tool_calls(&response);
 
@@ -321,9 +873,12 @@ pub async fn agent_turn(
             "Agent turn processing"
         );
 
-        if tool_calls.is_empty() {
+        if tool_calls.is_empty() && early_results.is_empty() {
             // No tool calls — this is the final response
-            tracing::info!(iteration = iteration, "Agent turn complete - no more tool calls");
+            tracing::info!(
+                iteration = iteration,
+                "Agent turn complete - no more tool calls"
+            );
             history.push(ChatMessage::assistant(&response));
             return Ok(if text.is_empty() { response } else { text });
         }
@@ -334,58 +889,81 @@ pub async fn agent_turn(
             let _ = std::io::stdout().flush();
         }
 
-        // Execute each tool call and build results
+        // Execute each tool call not already run while streaming, and build results
         let mut tool_results = String::new();
-        for call in &tool_calls {
-            tracing::info!(tool_name = %call.name, arguments = %call.arguments, "Executing tool");
-            let start = Instant::now();
-            let result = if let Some(tool) = find_tool(tools_registry, &call.name) {
-                match tool.execute(call.arguments.clone()).await {
-                    Ok(r) => {
-                        observer.record_event(&ObserverEvent::ToolCall {
-                            tool: call.name.clone(),
-                            duration: start.elapsed(),
-                            success: r.success,
-                        });
-                        if r.success {
-                            r.output
-                        } else {
-                            format!("Error: {}", r.error.unwrap_or_else(|| r.output))
-                        }
-                    }
-                    Err(e) => {
-                        observer.record_event(&ObserverEvent::ToolCall {
-                            tool: call.name.clone(),
-                            duration: start.elapsed(),
-                            success: false,
-                        });
-                        format!("Error executing {}: {e}", call.name)
-                    }
-                }
+        let mut tool_images = Vec::new();
+        let mut outputs = early_results;
+        for call in tool_calls.iter().skip(outputs.len()) {
+            tracker.charge_tool_call()?;
+            let (output, success) = execute_tool_call(tools_registry, observer, call).await;
+            outputs.push((call.clone(), output, success));
+        }
+        let mut critiques = Vec::new();
+        for (call, result, success) in outputs {
+            let name = call.name.as_str();
+            let failed = failures.record(&call, success);
+            if failed > REPEATED_FAILURE_LIMIT {
+                anyhow::bail!(
+                    "Agent stopped: `{name}` failed {failed} times in a row with the same arguments {}. Last error: {}",
+                    call.arguments,
+                    truncate_with_ellipsis(&result, 500)
+                );
+            }
+            if failed == REPEATED_FAILURE_LIMIT {
+                tracing::warn!(tool_name = %name, failures = failed, "Repeated tool failure");
+                critiques.push(format!(
+                    "[Repeated failure] `{name}` has failed {failed} times in a row with these \
+                     arguments. Calling it the same way again will end the turn. Work out why \
+                     it fails, then try a different approach, or stop and explain to the user \
+                     what is blocking you."
+                ));
+            }
+            let result = super::tool_output::shorten(provider, model, name, result).await;
+            let result = if vision || describer.is_some() {
+                let (text, images) = extract_inline_images(&result);
+                tool_images.extend(images);
+                text
             } else {
-                format!("Unknown tool: {}", call.name)
+                result
             };
 
             // Use a different tag name to avoid re-parsing as tool call
             let _ = writeln!(
                 tool_results,
-                "<tool_response name=\"{}\">\n{}\n</tool_response>",
-                call.name, result
+                "<tool_response name=\"{name}\">\n{result}\n</tool_response>"
             );
-            tracing::info!(tool_name = %call.name, success = result.len() < 1000, "Tool execution complete");
+            tracing::info!(tool_name = %name, success = result.len() < 1000, "Tool execution complete");
+        }
+        for critique in critiques {
+            let _ = writeln!(tool_results, "{critique}");
         }
 
         // Add assistant message with tool calls + tool results to history
         history.push(ChatMessage::assistant(&response));
-        history.push(ChatMessage::user(format!("[Tool results]\n{tool_results}")));
+        history.push(ChatMessage::user_with_images(
+            format!("[Tool results]\n{tool_results}"),
+            tool_images,
+        ));
     }
 
     anyhow::bail!("Agent exceeded maximum tool iterations ({MAX_TOOL_ITERATIONS})")
 }
 
+/// Longest tool description kept in compact tool instructions.
+const COMPACT_DESCRIPTION_CHARS: usize = 80;
+
 /// Build the tool instruction block for the system prompt so the LLM knows
 /// how to invoke tools.
 pub fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> String {
+    build_tool_instructions_with(tools_registry, ToolSchemaFormat::Json)
+}
+
+/// Like [`build_tool_instructions`], with parameters rendered in `format`.
+/// `Compact` uses TypeScript-style signatures instead of JSON Schema.
+pub fn build_tool_instructions_with(
+    tools_registry: &[Box<dyn Tool>],
+    format: ToolSchemaFormat,
+) -> String {
     let mut instructions = String::new();
     instructions.push_str("\n## Tool Use Protocol\n\n");
     instructions.push_str("CRITICAL: You MUST use <tool_call> tags to call tools. Never just describe what you will do!\n\n");
@@ -405,40 +983,199 @@ pub fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> String {
     instructions.push_str("You MUST include <tool_call> tags in your response. After execution, results appear in <tool_response> tags.\n\n");
     instructions.push_str("### Available Tools\n\n");
 
-    for tool in tools_registry {
-        let _ = writeln!(
-            instructions,
-            "**{}**: {}\nParameters: `{}`\n",
-            tool.name(),
-            tool.description(),
-            tool.parameters_schema()
-        );
+    match format {
+        ToolSchemaFormat::Json => {
+            for tool in tools_registry {
+                let _ = writeln!(
+                    instructions,
+                    "**{}**: {}\nParameters: `{}`\n",
+                    tool.name(),
+                    tool.description(),
+                    tool.parameters_schema()
+                );
+            }
+        }
+        ToolSchemaFormat::Compact => {
+            // Descriptions are cut to their first sentence: the full text
+            // (with "Use when" guidance) is already in the tool list above.
+            instructions.push_str("Signatures are TypeScript-style (`?` = optional); pass arguments as a JSON object.\n\n");
+            for tool in tools_registry {
+                let _ = writeln!(
+                    instructions,
+                    "- `{}`: {}",
+                    tools::signature::render_signature(tool.name(), &tool.parameters_schema()),
+                    tools::signature::first_sentence(tool.description(), COMPACT_DESCRIPTION_CHARS)
+                );
+            }
+        }
     }
 
     instructions
 }
 
+/// Prompt descriptions of the built-in tools, plus the optional ones
+/// `config` enables or `tools` contains.
 #[allow(clippy::too_many_lines)]
-pub async fn run(
-    config: Config,
-    message: Option<String>,
-    provider_override: Option<String>,
-    model_override: Option<String>,
-    temperature: f64,
-) -> Result<()> {
-    // ── Wire up agnostic subsystems ──────────────────────────────
-    let observer: Arc<dyn Observer> =
-        Arc::from(observability::create_observer(&config.observability));
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
+pub(crate) fn tool_descriptions(
+    config: &Config,
+    tools: &[Box<dyn Tool>],
+) -> Vec<(&'static str, &'static str)> {
+    let mut tool_descs: Vec<(&'static str, &'static str)> = vec![
+        (
+            "shell",
+            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
+        ),
+        (
+            "file_read",
+            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
+        ),
+        (
+            "file_write",
+            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain. The result lists changed line ranges and a content hash, so no read-back is needed.",
+        ),
+        (
+            "memory_store",
+            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
+        ),
+        (
+            "memory_recall",
+            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
+        ),
+        (
+            "memory_forget",
+            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
+        ),
+    ];
+    tool_descs.push((
+        "screenshot",
+        "Capture a screenshot of the current screen. Returns file path and base64-encoded PNG. Use when: visual verification, UI inspection, debugging displays.",
     ));
-
-    // ── Memory (the brain) ────────────────────────────────────────
-    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
-        &config.memory,
+    tool_descs.push((
+        "image_info",
+        "Read image file metadata (format, dimensions, size) and optionally base64-encode it. Use when: inspecting images, preparing visual data for analysis.",
+    ));
+    tool_descs.push((
+        "clipboard",
+        "Read or replace the system clipboard text. Use when: the user refers to what they just copied, or asks you to copy a result. Don't use when: the content was already given in the conversation.",
+    ));
+    tool_descs.push((
+        "calculator",
+        "Evaluate arithmetic exactly. Use when: an answer involves sums, percentages, ratios, or any computed figure. Don't use when: the number is quoted verbatim from a source.",
+    ));
+    tool_descs.push((
+        "fetch_full_output",
+        "Read lines of a tool output that was shortened to fit the context. Use when: a result says it was truncated or summarized and you need lines it left out. Don't use when: the shortened result already answers the question.",
+    ));
+    if config.browser.enabled {
+        tool_descs.push((
+            "browser_open",
+            "Open approved HTTPS URLs in Brave Browser (allowlist-only, no scraping)",
+        ));
+        tool_descs.push((
+            "browser_act",
+            "Drive a headless browser page (navigate, wait, click, fill, extract text, screenshot). Use when: a task needs interaction with an allowlisted site. Don't use when: web_search or a plain fetch is enough.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "transcribe") {
+        tool_descs.push((
+            "transcribe",
+            "Transcribe speech in an audio file to text. Use when: the user shares a voice note or recording. Don't use when: the file is not audio.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "speak") {
+        tool_descs.push((
+            "speak",
+            "Convert text to a voice note. Use when: the user asks to hear the answer or prefers audio. Don't use when: the reply contains code, tables, or long lists.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "web_search") {
+        tool_descs.push((
+            "web_search",
+            "Search the web for titles, URLs and snippets. Use when: the question depends on recent events or facts you may not know. Don't use when: memory or workspace files already answer it.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "calendar") {
+        tool_descs.push((
+            "calendar",
+            "List upcoming events or create events on the user's CalDAV calendars. Use when: the user asks about their schedule or wants something booked. Don't use when: the request concerns someone else's calendar.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "kb_search") {
+        tool_descs.push((
+            "kb_search",
+            "Search the indexed knowledge base documents. Use when: the answer may be in the user's notes, docs or manuals and the injected knowledge context is not enough. Don't use when: the question is about the conversation or saved preferences (use memory_recall).",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "memory_graph") {
+        tool_descs.push((
+            "memory_graph",
+            "Record or look up relations between people, companies, projects and places. Use when: the user states how two things are connected, or asks who or what is connected to something. Don't use when: the fact is about one thing only (use memory_store).",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "jobs") {
+        tool_descs.push((
+            "jobs",
+            "Queue a long-running task as a background job, or check on one. The result is sent to this chat when done. Use when: a task would take many steps or minutes. Don't use when: the answer can be given now.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "run_workflow") {
+        tool_descs.push((
+            "run_workflow",
+            "Run a predefined multi-step workflow by name. Use when: the user asks for an automation that exists as a workflow. Don't use when: no workflow fits.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "send_to_agent") {
+        tool_descs.push((
+            "send_to_agent",
+            "Send a message or task to another agent running in the daemon and wait for its answer. Use when: a task fits another agent's specialty. Don't use when: you can do it yourself.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "ask_user") {
+        tool_descs.push((
+            "ask_user",
+            "Ask the user a clarifying question and wait for the answer. Use when: the request is ambiguous and a wrong guess would waste work or cause side effects. Don't use when: a sensible default exists or the answer can be found with other tools.",
+        ));
+    }
+    if config.composio.enabled {
+        tool_descs.push((
+            "composio",
+            "Execute actions on 1000+ apps via Composio (Gmail, Notion, GitHub, Slack, etc.). Use action='list' to discover, 'execute' to run, 'connect' to OAuth.",
+        ));
+    }
+    tool_descs
+}
+
+#[allow(clippy::too_many_lines)]
+pub async fn run(
+    mut config: Config,
+    message: Option<String>,
+    provider_override: Option<String>,
+    model_override: Option<String>,
+    temperature: f64,
+    budget: TurnBudget,
+    skill: Option<String>,
+) -> Result<()> {
+    // ── Active skill (caps autonomy, narrows tools and prompt) ───
+    let active_skill = skill
+        .map(|name| {
+            crate::skills::sandbox::activate(&config.workspace_dir, &name, &mut config.autonomy)
+        })
+        .transpose()?;
+
+    // ── Wire up agnostic subsystems ──────────────────────────────
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+    let runtime: Arc<dyn runtime::RuntimeAdapter> =
+        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    crate::health::set_autonomy_limits(&config.autonomy);
+
+    // ── Memory (the brain) ────────────────────────────────────────
+    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
+        &config.memory,
         &config.workspace_dir,
         config.api_key.as_deref(),
     )?);
@@ -450,13 +1187,55 @@ pub async fn run(
     } else {
         None
     };
-    let tools_registry = tools::all_tools_with_runtime(
+    let mut tools_registry = tools::all_tools_with_runtime(
         &security,
         runtime,
         mem.clone(),
         composio_key,
         &config.browser,
     );
+    tools_registry.extend(tools::optional_tools(&config, &security));
+    tools::route_to_nodes(&mut tools_registry, &config.nodes, &security);
+
+    // ── Clarification questions (ask_user) ───────────────────────
+    // Interactive mode asks through the CLI channel and reads the answer from
+    // the same inbox as user messages; a one-shot run can still ask when
+    // stdin is a terminal. Otherwise nobody could answer, so no tool.
+    let (cli_tx, cli_inbox) = tokio::sync::mpsc::channel(32);
+    let cli_inbox = Arc::new(tokio::sync::Mutex::new(cli_inbox));
+    let user_prompt: Option<Arc<dyn UserPrompt>> = if message.is_none() {
+        Some(Arc::new(ChannelPrompt::new(
+            Arc::new(crate::channels::CliChannel::new()),
+            "user",
+            cli_inbox.clone(),
+        )))
+    } else if std::io::stdin().is_terminal() {
+        Some(Arc::new(TerminalPrompt))
+    } else {
+        None
+    };
+    if let Some(prompt) = &user_prompt {
+        tools_registry.push(Box::new(tools::AskUserTool::new(prompt.clone())));
+    }
+    let plans_first = config.autonomy.plans_first();
+
+    // ── Knowledge base (indexed in the background, searched per turn) ──
+    let knowledge_chunks = knowledge::context_limit(&config.knowledge);
+    if config.knowledge.enabled {
+        if knowledge::supported(mem.as_ref()) {
+            knowledge::spawn_sync(&config, mem.clone());
+            tools_registry.push(Box::new(tools::KbSearchTool::new(mem.clone())));
+        } else {
+            tracing::warn!(
+                backend = mem.name(),
+                "Knowledge base needs the sqlite memory backend; disabled"
+            );
+        }
+    }
+
+    if let Some(skill) = &active_skill {
+        crate::skills::sandbox::restrict_tools(skill, &mut tools_registry)?;
+    }
 
     // ── Resolve provider ─────────────────────────────────────────
     let provider_name = provider_override
@@ -468,6 +1247,8 @@ pub async fn run(
         .as_deref()
         .or(config.default_model.as_deref())
         .unwrap_or("anthropic/claude-sonnet-4-20250514");
+    // Applies to every turn; flags can only tighten the model profile's caps
+    let budget = config.turn_budget_for(model_name).lowered_by(budget);
 
     let provider: Box<dyn Provider> = providers::create_routed_provider(
         provider_name,
@@ -475,7 +1256,10 @@ pub async fn run(
         &config.reliability,
         &config.model_routes,
         model_name,
+        observer.clone(),
+        &providers::ProviderSettings::from_config(&config),
     )?;
+    let provider = providers::cache::wrap(provider, &config, provider_name);
 
     observer.record_event(&ObserverEvent::AgentStart {
         provider: provider_name.to_string(),
@@ -483,78 +1267,70 @@ pub async fn run(
     });
 
     // ── Build system prompt from workspace MD files (OpenClaw framework) ──
-    let skills = crate::skills::load_skills(&config.workspace_dir);
-    let mut tool_descs: Vec<(&str, &str)> = vec![
-        (
-            "shell",
-            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
-        ),
-        (
-            "file_read",
-            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
-        ),
-        (
-            "file_write",
-            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
-        ),
-        (
-            "memory_store",
-            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
-        ),
-        (
-            "memory_recall",
-            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
-        ),
-        (
-            "memory_forget",
-            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
-        ),
-    ];
-    tool_descs.push((
-        "screenshot",
-        "Capture a screenshot of the current screen. Returns file path and base64-encoded PNG. Use when: visual verification, UI inspection, debugging displays.",
-    ));
-    tool_descs.push((
-        "image_info",
-        "Read image file metadata (format, dimensions, size) and optionally base64-encode it. Use when: inspecting images, preparing visual data for analysis.",
-    ));
-    if config.browser.enabled {
-        tool_descs.push((
-            "browser_open",
-            "Open approved HTTPS URLs in Brave Browser (allowlist-only, no scraping)",
-        ));
+    let mut skills = match &active_skill {
+        Some(skill) => vec![skill.clone()],
+        None => crate::skills::load_skills(&config.workspace_dir),
+    };
+    let tool_names: Vec<&str> = tools_registry.iter().map(|t| t.name()).collect();
+    crate::skills::package::retain_usable(&mut skills, &tool_names);
+    let mut tool_descs = tool_descriptions(&config, &tools_registry);
+    if active_skill.is_some() {
+        tool_descs.retain(|(name, _)| tools_registry.iter().any(|t| t.name() == *name));
     }
-    if config.composio.enabled {
-        tool_descs.push((
-            "composio",
-            "Execute actions on 1000+ apps via Composio (Gmail, Notion, GitHub, Slack, etc.). Use action='list' to discover, 'execute' to run, 'connect' to OAuth.",
+    let language = super::language::configured(&config.identity, "cli");
+    let guardrails = Guardrails::from_config(&config.guardrails, &config.workspace_dir);
+    let build_prompt = || {
+        let mut system_prompt = crate::channels::build_system_prompt(
+            &config.workspace_dir,
+            model_name,
+            &tool_descs,
+            &skills,
+            Some(&crate::identity::readable_by(
+                config.identity.for_channel("cli"),
+                &security,
+            )),
+        );
+
+        // Append structured tool-use instructions with schemas
+        system_prompt.push_str(&build_tool_instructions_with(
+            &tools_registry,
+            config.tool_schema_for(model_name),
         ));
-    }
-    let mut system_prompt = crate::channels::build_system_prompt(
-        &config.workspace_dir,
-        model_name,
-        &tool_descs,
-        &skills,
-        Some(&config.identity),
-    );
+        system_prompt
+    };
+    let system_prompt = build_prompt();
+    let mut instructions =
+        crate::identity::InstructionWatcher::from_config(&config.identity, &security);
 
-    // Append structured tool-use instructions with schemas
-    system_prompt.push_str(&build_tool_instructions(&tools_registry));
+    // ── Session transcript ───────────────────────────────────────
+    let session_store = config
+        .sessions
+        .enabled
+        .then(|| SessionStore::new(&config.workspace_dir));
+    let mut session = Session::new("cli", model_name);
+
+    // Describes images for models without vision support
+    let vision_fallback = VisionFallback::from_config(&config);
 
     // ── Execute ──────────────────────────────────────────────────
     let start = Instant::now();
 
     if let Some(msg) = message {
         // Auto-save user message to memory
-        if config.memory.auto_save {
+        if config.memory.saves_raw_turns() {
             let user_key = autosave_memory_key("user_msg");
-            let _ = mem
-                .store(&user_key, &msg, MemoryCategory::Conversation)
-                .await;
+            let _ = memory::dedup::store(
+                mem.as_ref(),
+                &user_key,
+                &msg,
+                MemoryCategory::Conversation,
+                &config.memory.dedup,
+            )
+            .await;
         }
 
         // Inject memory context into user message
-        let context = build_context(mem.as_ref(), &msg).await;
+        let context = build_context(mem.as_ref(), &msg, &config, knowledge_chunks).await;
         let enriched = if context.is_empty() {
             msg.clone()
         } else {
@@ -566,90 +1342,313 @@ pub async fn run(
             ChatMessage::user(&enriched),
         ];
 
-        let response = agent_turn(
+        let rejected = if plans_first {
+            super::plan::review_plan(
+                provider.as_ref(),
+                &mut history,
+                observer.as_ref(),
+                user_prompt.as_deref(),
+                model_name,
+                temperature,
+            )
+            .await
+        } else {
+            None
+        };
+        let reply = match rejected {
+            Some(answer) => TurnReply {
+                answer,
+                exhausted: None,
+            },
+            None => {
+                observability::trace::session(
+                    session.id().to_string(),
+                    super::settings::scope(
+                        &config,
+                        hooks::scope(
+                            &config,
+                            tools::approval::scope(
+                                user_prompt.clone(),
+                                agent_turn_with_budget(
+                                    provider.as_ref(),
+                                    &mut history,
+                                    &tools_registry,
+                                    observer.as_ref(),
+                                    model_name,
+                                    temperature,
+                                    vision_fallback.as_ref(),
+                                    budget,
+                                ),
+                            ),
+                        ),
+                    ),
+                )
+                .await?
+            }
+        };
+        if let Some(exhausted) = reply.exhausted {
+            eprintln!("⚠️  {exhausted}; the answer below is partial");
+        }
+        let response = super::verify::verify_answer(
             provider.as_ref(),
             &mut history,
-            &tools_registry,
-            observer.as_ref(),
+            reply.answer,
             model_name,
-            temperature,
+            &config.verifier,
         )
-        .await?;
+        .await;
+        let response = super::language::enforce(
+            provider.as_ref(),
+            &mut history,
+            response,
+            model_name,
+            language.as_deref(),
+        )
+        .await;
+        let response = guardrails
+            .apply(provider.as_ref(), &mut history, response, model_name, "cli")
+            .await;
         println!("{response}");
 
+        record_session_turn(
+            session_store.as_ref(),
+            &mut session,
+            provider.as_ref(),
+            &config,
+            model_name,
+            &msg,
+            &response,
+        )
+        .await;
+
         // Auto-save assistant response to daily log
-        if config.memory.auto_save {
+        if config.memory.saves_raw_turns() {
             let summary = truncate_with_ellipsis(&response, 100);
             let response_key = autosave_memory_key("assistant_resp");
-            let _ = mem
-                .store(&response_key, &summary, MemoryCategory::Daily)
-                .await;
+            let _ = memory::dedup::store(
+                mem.as_ref(),
+                &response_key,
+                &summary,
+                MemoryCategory::Daily,
+                &config.memory.dedup,
+            )
+            .await;
         }
+        memory::extract::after_turn(
+            provider.as_ref(),
+            mem.as_ref(),
+            &config.memory,
+            model_name,
+            &msg,
+            &response,
+        )
+        .await;
     } else {
         println!("🦀 CClaw Interactive Mode");
-        println!("Type /quit to exit.\n");
+        println!("Type /quit to exit; Ctrl-C cancels a running turn.\n");
 
-        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
         let cli = crate::channels::CliChannel::new();
 
         // Spawn listener
         let listen_handle = tokio::spawn(async move {
-            let _ = crate::channels::Channel::listen(&cli, tx).await;
+            let _ = crate::channels::Channel::listen(&cli, cli_tx).await;
+        });
+
+        // Ctrl-C cancels the running turn; a quick second press quits
+        let (interrupt_tx, mut interrupt_rx) = tokio::sync::mpsc::unbounded_channel();
+        let ctrl_c_handle = tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if interrupt_tx.send(()).is_err() {
+                    break;
+                }
+            }
         });
+        let mut interrupts = Interrupts::default();
 
         // Persistent conversation history across turns
         let mut history = vec![ChatMessage::system(&system_prompt)];
 
-        while let Some(msg) = rx.recv().await {
+        // The inbox lock is only held while waiting, so ask_user can take
+        // the next line as its answer during a turn.
+        loop {
+            let msg = tokio::select! {
+                msg = async { cli_inbox.lock().await.recv().await } => msg,
+                Some(()) = interrupt_rx.recv() => {
+                    if interrupts.press() {
+                        break;
+                    }
+                    println!("\n(Press Ctrl-C again to quit)");
+                    continue;
+                }
+            };
+            let Some(msg) = msg else {
+                break;
+            };
+            // Edited AGENTS.md / SOUL.md take effect on the next turn
+            if instructions.changed() {
+                history[0] = ChatMessage::system(build_prompt());
+                println!("📝 Instruction files changed — system prompt reloaded");
+            }
             // Auto-save conversation turns
-            if config.memory.auto_save {
+            if config.memory.saves_raw_turns() {
                 let user_key = autosave_memory_key("user_msg");
-                let _ = mem
-                    .store(&user_key, &msg.content, MemoryCategory::Conversation)
-                    .await;
+                let _ = memory::dedup::store(
+                    mem.as_ref(),
+                    &user_key,
+                    &msg.content,
+                    MemoryCategory::Conversation,
+                    &config.memory.dedup,
+                )
+                .await;
             }
 
             // Inject memory context into user message
-            let context = build_context(mem.as_ref(), &msg.content).await;
+            let context =
+                build_context(mem.as_ref(), &msg.content, &config, knowledge_chunks).await;
             let enriched = if context.is_empty() {
                 msg.content.clone()
             } else {
                 format!("{context}{}", msg.content)
             };
 
+            let turn_start = history.len();
             history.push(ChatMessage::user(&enriched));
 
-            let response = match agent_turn(
-                provider.as_ref(),
-                &mut history,
-                &tools_registry,
-                observer.as_ref(),
-                model_name,
-                temperature,
-            )
-            .await
-            {
-                Ok(resp) => resp,
+            let rejected = if plans_first {
+                super::plan::review_plan(
+                    provider.as_ref(),
+                    &mut history,
+                    observer.as_ref(),
+                    user_prompt.as_deref(),
+                    model_name,
+                    temperature,
+                )
+                .await
+            } else {
+                None
+            };
+            let mut quit = false;
+            let turn = if let Some(answer) = rejected {
+                Ok(TurnReply {
+                    answer,
+                    exhausted: None,
+                })
+            } else {
+                let cancel = CancellationToken::new();
+                let turn = agent_turn_with_cancel(
+                    provider.as_ref(),
+                    &mut history,
+                    &tools_registry,
+                    observer.as_ref(),
+                    model_name,
+                    temperature,
+                    vision_fallback.as_ref(),
+                    budget,
+                    &cancel,
+                );
+                let turn = tools::approval::scope(user_prompt.clone(), turn);
+                let turn = hooks::scope(&config, turn);
+                let turn = super::settings::scope(&config, turn);
+                let turn = observability::trace::session(session.id().to_string(), turn);
+                tokio::pin!(turn);
+                loop {
+                    tokio::select! {
+                        result = &mut turn => break result,
+                        Some(()) = interrupt_rx.recv() => {
+                            quit = interrupts.press();
+                            cancel.cancel();
+                        }
+                    }
+                }
+            };
+            let reply = match turn {
+                Ok(reply) => reply,
+                Err(e) if e.is::<TurnCancelled>() => {
+                    // Forget the message too, as if it was never sent
+                    history.truncate(turn_start);
+                    println!("\n^C — turn cancelled (press Ctrl-C again to quit)\n");
+                    if quit {
+                        break;
+                    }
+                    continue;
+                }
                 Err(e) => {
                     eprintln!("\nError: {e}\n");
                     continue;
                 }
             };
-            println!("\n{response}\n");
+            if let Some(exhausted) = reply.exhausted {
+                eprintln!("\n⚠️  {exhausted}; the answer below is partial");
+            }
+            let response = super::verify::verify_answer(
+                provider.as_ref(),
+                &mut history,
+                reply.answer,
+                model_name,
+                &config.verifier,
+            )
+            .await;
+            let response = super::language::enforce(
+                provider.as_ref(),
+                &mut history,
+                response,
+                model_name,
+                language.as_deref(),
+            )
+            .await;
+            let response = guardrails
+                .apply(provider.as_ref(), &mut history, response, model_name, "cli")
+                .await;
+            println!("\n{}\n", crate::render::for_terminal(&response, &config.render));
+
+            record_session_turn(
+                session_store.as_ref(),
+                &mut session,
+                provider.as_ref(),
+                &config,
+                model_name,
+                &msg.content,
+                &response,
+            )
+            .await;
 
             // Prevent unbounded history growth in long interactive sessions
-            trim_history(&mut history);
+            trim_history(&mut history, model_name);
 
-            if config.memory.auto_save {
+            if config.memory.saves_raw_turns() {
                 let summary = truncate_with_ellipsis(&response, 100);
                 let response_key = autosave_memory_key("assistant_resp");
-                let _ = mem
-                    .store(&response_key, &summary, MemoryCategory::Daily)
-                    .await;
+                let _ = memory::dedup::store(
+                    mem.as_ref(),
+                    &response_key,
+                    &summary,
+                    MemoryCategory::Daily,
+                    &config.memory.dedup,
+                )
+                .await;
             }
+            memory::extract::after_turn(
+                provider.as_ref(),
+                mem.as_ref(),
+                &config.memory,
+                model_name,
+                &msg.content,
+                &response,
+            )
+            .await;
         }
 
         listen_handle.abort();
+        ctrl_c_handle.abort();
+        memory::summary::on_close(
+            provider.as_ref(),
+            mem.as_ref(),
+            &config.memory,
+            model_name,
+            session.id(),
+            &session.messages,
+        )
+        .await;
     }
 
     let duration = start.elapsed();
@@ -665,6 +1664,7 @@ pub async fn run(
 mod tests {
     use super::*;
     use crate::memory::{Memory, MemoryCategory, SqliteMemory};
+    use crate::providers::mock::ScriptedProvider;
     use tempfile::TempDir;
 
     #[test]
@@ -786,6 +1786,37 @@ After text."#;
         assert!(instructions.contains("file_write"));
     }
 
+    #[test]
+    fn compact_tool_instructions_halve_the_tool_section() {
+        use crate::security::SecurityPolicy;
+        let security = Arc::new(SecurityPolicy::from_config(
+            &crate::config::AutonomyConfig::default(),
+            std::path::Path::new("/tmp"),
+        ));
+        let tmp = TempDir::new().unwrap();
+        let mem: Arc<dyn Memory> = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+        let browser = crate::config::BrowserConfig {
+            enabled: true,
+            allowed_domains: vec!["example.com".into()],
+            ..crate::config::BrowserConfig::default()
+        };
+        let tools = tools::all_tools(&security, mem, None, &browser);
+
+        let section = |format| {
+            let text = build_tool_instructions_with(&tools, format);
+            let start = text.find("### Available Tools").unwrap();
+            text[start..].len()
+        };
+        let json = section(ToolSchemaFormat::Json);
+        let compact = section(ToolSchemaFormat::Compact);
+        assert!(compact * 100 <= json * 60, "json={json} compact={compact}");
+
+        let compact_text = build_tool_instructions_with(&tools, ToolSchemaFormat::Compact);
+        assert!(compact_text.contains("## Tool Use Protocol"));
+        assert!(compact_text.contains("`shell(command: string"));
+        assert!(!compact_text.contains("\"properties\""));
+    }
+
     #[test]
     fn trim_history_preserves_system_prompt() {
         let mut history = vec![ChatMessage::system("system prompt")];
@@ -795,7 +1826,7 @@ After text."#;
         let original_len = history.len();
         assert!(original_len > MAX_HISTORY_MESSAGES + 1);
 
-        trim_history(&mut history);
+        trim_history(&mut history, "gpt-4");
 
         // System prompt preserved
         assert_eq!(history[0].role, "system");
@@ -814,10 +1845,40 @@ After text."#;
             ChatMessage::user("hello"),
             ChatMessage::assistant("hi"),
         ];
-        trim_history(&mut history);
+        trim_history(&mut history, "gpt-4");
         assert_eq!(history.len(), 3);
     }
 
+    #[test]
+    fn trim_history_drops_oldest_messages_over_the_token_cap() {
+        let long = "word ".repeat(40_000);
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user(long.clone()),
+            ChatMessage::assistant(long.clone()),
+            ChatMessage::user(long),
+            ChatMessage::assistant("short"),
+        ];
+        trim_history(&mut history, "gpt-4");
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].role, "system");
+        assert_eq!(history[1].role, "assistant");
+        assert!(tokens::count_messages("gpt-4", &history) <= max_history_tokens("gpt-4"));
+    }
+
+    #[test]
+    fn trim_history_keeps_more_for_long_context_models() {
+        let long = "word ".repeat(40_000);
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user(long.clone()),
+            ChatMessage::assistant(long.clone()),
+            ChatMessage::user(long),
+        ];
+        trim_history(&mut history, "gemini-2.5-pro");
+        assert_eq!(history.len(), 4);
+    }
+
     #[test]
     fn autosave_memory_key_has_prefix_and_uniqueness() {
         let key1 = autosave_memory_key("user_msg");
@@ -848,4 +1909,451 @@ After text."#;
         let recalled = mem.recall("45", 5).await.unwrap();
         assert!(recalled.iter().any(|entry| entry.content.contains("45")));
     }
+
+    #[test]
+    fn extract_inline_images_replaces_data_uris() {
+        let output =
+            "Screenshot saved to: /tmp/a.png\nSize: 4 bytes\ndata:image/png;base64,iVBORw==";
+        let (text, images) = extract_inline_images(output);
+        assert_eq!(images, vec![ImagePart::new("image/png", "iVBORw==")]);
+        assert_eq!(
+            text,
+            "Screenshot saved to: /tmp/a.png\nSize: 4 bytes\n[image attached: image/png]"
+        );
+
+        let (plain, none) = extract_inline_images("no images here\n");
+        assert!(none.is_empty());
+        assert_eq!(plain, "no images here\n");
+    }
+
+    struct FakeScreenshotTool;
+
+    #[async_trait::async_trait]
+    impl Tool for FakeScreenshotTool {
+        fn name(&self) -> &str {
+            "screenshot"
+        }
+
+        fn description(&self) -> &str {
+            "fake"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<tools::ToolResult> {
+            Ok(tools::ToolResult {
+                success: true,
+                output: "Saved\ndata:image/png;base64,iVBORw==".into(),
+                error: None,
+            })
+        }
+    }
+
+    async fn run_screenshot_turn(vision: bool) -> Vec<Vec<ChatMessage>> {
+        run_screenshot_turn_with(vision, None).await
+    }
+
+    async fn run_screenshot_turn_with(
+        vision: bool,
+        fallback: Option<&VisionFallback>,
+    ) -> Vec<Vec<ChatMessage>> {
+        let provider = ScriptedProvider::new(&[
+            r#"<tool_call>{"name": "screenshot", "arguments": {}}</tool_call>"#,
+            "Looks fine",
+        ]);
+        let provider = if vision {
+            provider.with_vision()
+        } else {
+            provider
+        };
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(FakeScreenshotTool)];
+        let mut history = vec![ChatMessage::user("check the screen")];
+        let reply = agent_turn_with_vision(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &crate::observability::NoopObserver,
+            "m",
+            0.0,
+            fallback,
+        )
+        .await
+        .unwrap();
+        assert_eq!(reply, "Looks fine");
+        provider.requests().into_iter().map(|r| r.messages).collect()
+    }
+
+    #[tokio::test]
+    async fn agent_turn_sends_tool_images_to_vision_providers() {
+        let calls = run_screenshot_turn(true).await;
+        let tool_msg = calls[1].last().unwrap();
+        assert_eq!(tool_msg.images.len(), 1);
+        assert!(tool_msg.content.contains("[image attached: image/png]"));
+        assert!(!tool_msg.content.contains("base64"));
+    }
+
+    #[tokio::test]
+    async fn agent_turn_keeps_text_only_results_for_other_providers() {
+        let calls = run_screenshot_turn(false).await;
+        let tool_msg = calls[1].last().unwrap();
+        assert!(tool_msg.images.is_empty());
+        assert!(tool_msg.content.contains("data:image/png;base64,iVBORw=="));
+    }
+
+    #[tokio::test]
+    async fn agent_turn_describes_tool_images_via_vision_fallback() {
+        let describer =
+            ScriptedProvider::new(&["A dashboard with one failing check."]).with_vision();
+        let fallback = VisionFallback::new(Box::new(describer), "vision-model");
+
+        let calls = run_screenshot_turn_with(false, Some(&fallback)).await;
+        let tool_msg = calls[1].last().unwrap();
+        assert!(tool_msg.images.is_empty());
+        assert!(!tool_msg.content.contains("base64"));
+        assert!(tool_msg
+            .content
+            .contains("[Image description from vision-model"));
+        assert!(tool_msg.content.contains("one failing check"));
+    }
+
+    #[tokio::test]
+    async fn vision_fallback_is_unused_when_model_sees_images() {
+        let describer = ScriptedProvider::new(&[]).with_vision();
+        let fallback = VisionFallback::new(Box::new(describer), "vision-model");
+
+        let calls = run_screenshot_turn_with(true, Some(&fallback)).await;
+        assert_eq!(calls[1].last().unwrap().images.len(), 1);
+    }
+
+    struct FixedAnswer(&'static str);
+
+    #[async_trait::async_trait]
+    impl UserPrompt for FixedAnswer {
+        async fn ask(&self, _question: &str, _choices: &[String]) -> Result<Option<String>> {
+            Ok(Some(self.0.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn agent_turn_resumes_with_the_users_answer() {
+        let provider = ScriptedProvider::new(&[
+            r#"<tool_call>{"name": "ask_user", "arguments": {"question": "Which branch?"}}</tool_call>"#,
+            "Deployed main",
+        ]);
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(tools::AskUserTool::new(Arc::new(
+            FixedAnswer("main"),
+        )))];
+        let mut history = vec![ChatMessage::user("deploy it")];
+        let reply = agent_turn(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &crate::observability::NoopObserver,
+            "m",
+            0.0,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply, "Deployed main");
+        let calls = provider.requests();
+        let tool_msg = calls[1].messages.last().unwrap();
+        assert!(tool_msg.content.starts_with("[Tool results]"));
+        assert!(tool_msg.content.contains("User answered: main"));
+    }
+
+    #[tokio::test]
+    async fn agent_turn_stops_at_the_tool_call_budget() {
+        let ask =
+            r#"<tool_call>{"name": "ask_user", "arguments": {"question": "Which?"}}</tool_call>"#;
+        let provider = ScriptedProvider::new(&[ask, ask, "done"]);
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(tools::AskUserTool::new(Arc::new(
+            FixedAnswer("a"),
+        )))];
+        let mut history = vec![ChatMessage::user("go")];
+        let reply = agent_turn_with_budget(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &crate::observability::NoopObserver,
+            "m",
+            0.0,
+            None,
+            TurnBudget {
+                max_tool_calls: Some(1),
+                ..TurnBudget::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply.exhausted, Some(BudgetExhausted::ToolCalls(1)));
+        assert!(reply
+            .answer
+            .starts_with("I ran out of budget before finishing"));
+        assert!(reply.answer.contains("<tool_response name=\"ask_user\">"));
+        assert_eq!(history.last().unwrap().content, reply.answer);
+        assert_eq!(provider.calls(), 2);
+    }
+
+    /// Presses "Ctrl-C" as soon as it runs, then never finishes.
+    struct HangingTool(CancellationToken);
+
+    #[async_trait::async_trait]
+    impl Tool for HangingTool {
+        fn name(&self) -> &str {
+            "hang"
+        }
+
+        fn description(&self) -> &str {
+            "fake"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<tools::ToolResult> {
+            self.0.cancel();
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_turns_stop_mid_tool_and_leave_history_untouched() {
+        let provider =
+            ScriptedProvider::new(&[r#"<tool_call>{"name": "hang", "arguments": {}}</tool_call>"#]);
+        let cancel = CancellationToken::new();
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(HangingTool(cancel.clone()))];
+        let mut history = vec![ChatMessage::user("go")];
+        let err = agent_turn_with_cancel(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &crate::observability::NoopObserver,
+            "m",
+            0.0,
+            None,
+            TurnBudget::default(),
+            &cancel,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.is::<TurnCancelled>());
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "go");
+    }
+
+    #[tokio::test]
+    async fn agent_turn_returns_the_latest_text_at_the_iteration_cap() {
+        let ask = r#"Checking with you first. <tool_call>{"name": "ask_user", "arguments": {"question": "Which?"}}</tool_call>"#;
+        let provider = ScriptedProvider::new(&[ask, "done"]);
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(tools::AskUserTool::new(Arc::new(
+            FixedAnswer("a"),
+        )))];
+        let mut history = vec![ChatMessage::user("go")];
+        let reply = agent_turn_with_budget(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &crate::observability::NoopObserver,
+            "m",
+            0.0,
+            None,
+            TurnBudget {
+                max_iterations: Some(1),
+                ..TurnBudget::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply.exhausted, Some(BudgetExhausted::Iterations(1)));
+        assert_eq!(reply.answer, "Checking with you first.");
+    }
+
+    #[tokio::test]
+    async fn repeated_tool_failures_get_a_critique_then_end_the_turn() {
+        let call = r#"<tool_call>{"name": "deploy", "arguments": {"env": "prod"}}</tool_call>"#;
+        let provider = ScriptedProvider::new(&[call; 4]);
+        let mut history = vec![ChatMessage::user("ship it")];
+        let err = agent_turn(
+            &provider,
+            &mut history,
+            &[],
+            &crate::observability::NoopObserver,
+            "m",
+            0.0,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("`deploy` failed 4 times in a row"));
+        let seen = provider.requests();
+        let before_critique = seen[2].last();
+        assert!(!before_critique.contains("[Repeated failure]"));
+        let critique = seen[3].last();
+        assert!(critique.contains("[Repeated failure] `deploy` has failed 3 times"));
+    }
+
+    #[tokio::test]
+    async fn agent_turn_refuses_prompts_over_the_token_budget() {
+        let provider = ScriptedProvider::new(&["hi"]);
+        let mut history = vec![ChatMessage::user("x".repeat(400))];
+        let reply = agent_turn_with_budget(
+            &provider,
+            &mut history,
+            &[],
+            &crate::observability::NoopObserver,
+            "m",
+            0.0,
+            None,
+            TurnBudget {
+                max_tokens: Some(50),
+                ..TurnBudget::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply.exhausted, Some(BudgetExhausted::Tokens(50)));
+        assert_eq!(reply.answer, "I ran out of budget before I could answer.");
+        assert_eq!(provider.calls(), 0);
+    }
+
+    #[test]
+    fn tool_call_scanner_yields_each_block_once_complete() {
+        let mut scanner = ToolCallScanner::default();
+        assert!(scanner
+            .push("Checking. <tool_call>{\"name\": \"shell\", ")
+            .is_empty());
+        let calls = scanner.push("\"arguments\": {\"command\": \"ls\"}}</tool_call> and <tool_");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments["command"], "ls");
+
+        assert_eq!(scanner.take_visible(), "Checking.  and ");
+
+        let calls = scanner.push("call>{\"name\": \"file_read\", \"arguments\": {}}</tool_call>");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "file_read");
+        assert!(scanner.push(" done <").is_empty());
+        assert_eq!(scanner.take_visible(), " done ");
+        assert_eq!(scanner.finish(), "<");
+    }
+
+    /// Streams a tool call, then holds the rest of the reply until the tool runs.
+    struct StreamingProvider {
+        tool_ran: Arc<tokio::sync::Notify>,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for StreamingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("agent_turn should stream")
+        }
+
+        async fn chat_stream(
+            &self,
+            _messages: &[ChatMessage],
+            _tools: &[tools::ToolSpec],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<providers::traits::ChatStream> {
+            use futures_util::stream;
+            if self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) > 0 {
+                return Ok(stream::once(async { Ok("All set".to_string()) }).boxed());
+            }
+            let tool_ran = self.tool_ran.clone();
+            let head = r#"<tool_call>{"name": "probe", "arguments": {}}</tool_call>"#;
+            Ok(stream::once(async move { Ok(head.to_string()) })
+                .chain(stream::once(async move {
+                    tool_ran.notified().await;
+                    Ok(" waiting on the probe".to_string())
+                }))
+                .boxed())
+        }
+    }
+
+    struct ProbeTool {
+        ran: Arc<tokio::sync::Notify>,
+        runs: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Tool for ProbeTool {
+        fn name(&self) -> &str {
+            "probe"
+        }
+
+        fn description(&self) -> &str {
+            "fake"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<tools::ToolResult> {
+            self.runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.ran.notify_one();
+            Ok(tools::ToolResult {
+                success: true,
+                output: "probed".into(),
+                error: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn agent_turn_starts_tools_before_the_reply_finishes() {
+        let ran = Arc::new(tokio::sync::Notify::new());
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let provider = StreamingProvider {
+            tool_ran: ran.clone(),
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        };
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(ProbeTool {
+            ran,
+            runs: runs.clone(),
+        })];
+        let mut history = vec![ChatMessage::user("probe it")];
+        let streamed = Arc::new(std::sync::Mutex::new(String::new()));
+        let sink = streamed.clone();
+
+        // The first reply only ends once the probe has run
+        let reply = tokio::time::timeout(
+            Duration::from_secs(2),
+            stream_deltas(
+                Arc::new(move |delta: &str| sink.lock().unwrap().push_str(delta)),
+                agent_turn(
+                    &provider,
+                    &mut history,
+                    &tools_registry,
+                    &crate::observability::NoopObserver,
+                    "m",
+                    0.0,
+                ),
+            ),
+        )
+        .await
+        .expect("tool should run while the reply streams")
+        .unwrap();
+
+        assert_eq!(reply, "All set");
+        assert_eq!(*streamed.lock().unwrap(), " waiting on the probeAll set");
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(history[1].content.ends_with("waiting on the probe"));
+        assert!(history[2].content.contains("probed"));
+    }
 }
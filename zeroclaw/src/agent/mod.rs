@@ -1,3 +1,12 @@
+pub mod budget;
+pub mod commands;
+pub mod interrupt;
+pub mod language;
 pub mod loop_;
+pub mod plan;
+pub mod settings;
+pub mod tool_output;
+pub mod verify;
 
+pub use budget::TurnBudget;
 pub use loop_::run;
@@ -0,0 +1,286 @@
+//! Plan-then-act turns.
+//!
+//! At the autonomy levels listed in `[autonomy] plan_levels`, the model first
+//! writes a plan for the turn (steps, the tool each uses, risks) without
+//! calling any tool. The plan is reported to the observer and put to the
+//! user, who approves it, rejects it or asks for changes. Only an approved
+//! plan is carried out. A request that needs no tools gets an empty plan and
+//! runs as usual.
+
+use crate::observability::{Observer, ObserverEvent};
+use crate::providers::{ChatMessage, Provider};
+use crate::tools::ask_user::UserPrompt;
+use serde::Deserialize;
+use std::fmt::Write;
+
+/// Times the user may send a plan back with changes before it is dropped.
+const MAX_REVISIONS: usize = 3;
+
+const PLAN_PROMPT: &str = "Before doing anything, plan how you will handle the request above. \
+Do not call any tools yet. Respond with a JSON object only, no prose: {\"steps\": \
+[{\"action\": \"<what you will do>\", \"tool\": \"<tool name, or null>\"}], \"risks\": \
+[\"<anything that could lose data, cost money, or affect others>\"]}. If the request can be \
+answered without tools, respond with {\"steps\": [], \"risks\": []}.";
+
+#[derive(Debug, Default, Deserialize)]
+struct Plan {
+    #[serde(default)]
+    steps: Vec<PlanStep>,
+    #[serde(default)]
+    risks: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlanStep {
+    action: String,
+    #[serde(default)]
+    tool: Option<String>,
+}
+
+impl Plan {
+    fn step_lines(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .map(
+                |step| match step.tool.as_deref().filter(|t| !t.is_empty()) {
+                    Some(tool) => format!("{} ({tool})", step.action),
+                    None => step.action.clone(),
+                },
+            )
+            .collect()
+    }
+
+    fn render(&self) -> String {
+        let mut text = String::from("Plan:");
+        for (i, line) in self.step_lines().iter().enumerate() {
+            let _ = write!(text, "\n  {}. {line}", i + 1);
+        }
+        if !self.risks.is_empty() {
+            text.push_str("\nRisks:");
+            for risk in &self.risks {
+                let _ = write!(text, "\n  - {risk}");
+            }
+        }
+        text
+    }
+}
+
+enum Review {
+    Approve,
+    Reject,
+    Revise(String),
+}
+
+fn review(answer: Option<&str>) -> Review {
+    let Some(answer) = answer.map(str::trim) else {
+        return Review::Reject;
+    };
+    let word = answer.trim_end_matches(['.', '!']).to_ascii_lowercase();
+    match word.as_str() {
+        "y" | "yes" | "ok" | "okay" | "approve" | "approved" | "go" | "go ahead" | "proceed" => {
+            Review::Approve
+        }
+        "" | "n" | "no" | "reject" | "cancel" | "stop" => Review::Reject,
+        _ => Review::Revise(answer.to_string()),
+    }
+}
+
+/// Have the model plan the turn whose request ends `history`, and put the
+/// plan to the user before anything runs. An approved plan is added to
+/// `history` for the turn to follow and `None` is returned. A rejected plan
+/// ends the turn: the returned reply is also added to `history`.
+///
+/// Without `prompt` nobody can approve, so the plan is only reported and
+/// followed. Planning is best-effort: if the model produces no usable plan,
+/// the turn runs unplanned.
+pub async fn review_plan(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    observer: &dyn Observer,
+    prompt: Option<&dyn UserPrompt>,
+    model: &str,
+    temperature: f64,
+) -> Option<String> {
+    let mut messages = history.clone();
+    match messages.last_mut() {
+        Some(last) if last.role == "user" => {
+            last.content = format!("{}\n\n{PLAN_PROMPT}", last.content);
+        }
+        _ => messages.push(ChatMessage::user(PLAN_PROMPT)),
+    }
+
+    for revision in 0..=MAX_REVISIONS {
+        let plan = match draft(provider, &messages, model, temperature).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                tracing::warn!("Planning skipped: {e}");
+                return None;
+            }
+        };
+        if plan.steps.is_empty() {
+            return None;
+        }
+        observer.record_event(&ObserverEvent::PlanProposed {
+            steps: plan.step_lines(),
+            risks: plan.risks.clone(),
+        });
+        let rendered = plan.render();
+
+        let Some(prompt) = prompt else {
+            approve(history, rendered);
+            return None;
+        };
+        let question = if revision < MAX_REVISIONS {
+            format!("{rendered}\n\nCarry out this plan? Answer yes, no, or say what to change.")
+        } else {
+            format!("{rendered}\n\nCarry out this plan? Answer yes or no.")
+        };
+        let answer = match prompt.ask(&question, &[]).await {
+            Ok(answer) => answer,
+            Err(e) => {
+                tracing::warn!("Could not ask for plan approval: {e}");
+                None
+            }
+        };
+        match review(answer.as_deref()) {
+            Review::Approve => {
+                approve(history, rendered);
+                return None;
+            }
+            Review::Revise(changes) if revision < MAX_REVISIONS => {
+                tracing::info!(revision = revision + 1, "Plan sent back for changes");
+                messages.push(ChatMessage::assistant(rendered));
+                messages.push(ChatMessage::user(format!(
+                    "Revise the plan: {changes}\n\n{PLAN_PROMPT}"
+                )));
+            }
+            Review::Reject | Review::Revise(_) => break,
+        }
+    }
+
+    let reply = "Plan rejected, so nothing was done. Tell me how you'd like to proceed.";
+    history.push(ChatMessage::assistant(reply));
+    Some(reply.to_string())
+}
+
+fn approve(history: &mut Vec<ChatMessage>, rendered: String) {
+    history.push(ChatMessage::assistant(rendered));
+    history.push(ChatMessage::user(
+        "The plan is approved. Carry it out, step by step.",
+    ));
+}
+
+async fn draft(
+    provider: &dyn Provider,
+    messages: &[ChatMessage],
+    model: &str,
+    temperature: f64,
+) -> anyhow::Result<Plan> {
+    let reply = provider
+        .chat_with_history(messages, model, temperature)
+        .await?;
+    let span = reply.find('{').zip(reply.rfind('}'));
+    let Some((start, end)) = span.filter(|(start, end)| start < end) else {
+        anyhow::bail!("planner reply contained no JSON object");
+    };
+    Ok(serde_json::from_str(&reply[start..=end])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::NoopObserver;
+    use crate::providers::mock::ScriptedProvider;
+    use std::sync::Mutex;
+
+    struct Answers(Mutex<Vec<Option<&'static str>>>);
+
+    #[async_trait::async_trait]
+    impl UserPrompt for Answers {
+        async fn ask(
+            &self,
+            _question: &str,
+            _choices: &[String],
+        ) -> anyhow::Result<Option<String>> {
+            Ok(self.0.lock().unwrap().remove(0).map(str::to_string))
+        }
+    }
+
+    const CLEAN_PLAN: &str = "{\"steps\": [{\"action\": \"Delete build artifacts\", \"tool\": \
+        \"shell\"}, {\"action\": \"Report what was freed\", \"tool\": null}], \"risks\": \
+        [\"Removes target/\"]}";
+
+    fn history() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::system("You are an agent."),
+            ChatMessage::user("Free up disk space"),
+        ]
+    }
+
+    #[tokio::test]
+    async fn approved_plans_are_added_to_history() {
+        let provider = ScriptedProvider::new(&[CLEAN_PLAN]);
+        let answers = Answers(Mutex::new(vec![Some("yes")]));
+        let mut history = history();
+        let rejected = review_plan(
+            &provider,
+            &mut history,
+            &NoopObserver,
+            Some(&answers),
+            "m",
+            0.0,
+        )
+        .await;
+
+        assert!(rejected.is_none());
+        assert_eq!(history.len(), 4);
+        assert!(history[2]
+            .content
+            .contains("1. Delete build artifacts (shell)\n  2. Report what was freed\nRisks:"));
+        assert!(history[3].content.contains("approved"));
+        // The planning instruction is not kept in the conversation
+        assert!(!history[1].content.contains("JSON"));
+        assert!(provider.requests()[0].messages[1].content.contains("JSON"));
+    }
+
+    #[tokio::test]
+    async fn changes_are_sent_back_and_rejection_ends_the_turn() {
+        let provider = ScriptedProvider::new(&[CLEAN_PLAN, CLEAN_PLAN]);
+        let answers = Answers(Mutex::new(vec![Some("keep the caches"), Some("no")]));
+        let mut history = history();
+        let reply = review_plan(
+            &provider,
+            &mut history,
+            &NoopObserver,
+            Some(&answers),
+            "m",
+            0.0,
+        )
+        .await;
+
+        assert!(reply.unwrap().starts_with("Plan rejected"));
+        assert_eq!(history.len(), 3);
+        let revision = provider.requests().remove(1);
+        assert!(revision
+            .last()
+            .starts_with("Revise the plan: keep the caches"));
+    }
+
+    #[tokio::test]
+    async fn requests_without_steps_or_a_plan_run_unplanned() {
+        let mut history = history();
+        let provider = ScriptedProvider::new(&["{\"steps\": [], \"risks\": []}"]);
+        assert!(
+            review_plan(&provider, &mut history, &NoopObserver, None, "m", 0.0)
+                .await
+                .is_none()
+        );
+        let provider = ScriptedProvider::new(&["Sure, I'll clean up."]);
+        assert!(
+            review_plan(&provider, &mut history, &NoopObserver, None, "m", 0.0)
+                .await
+                .is_none()
+        );
+        assert_eq!(history.len(), 2);
+    }
+}
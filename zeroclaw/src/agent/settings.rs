@@ -0,0 +1,58 @@
+//! Settings a turn takes from the config it runs with.
+//!
+//! One process can hold several agents with their own configs (the CLI,
+//! channels, daemon jobs, FFI handles), so the loop and tools read these
+//! from the running turn rather than from process-wide state. Outside a
+//! turn there are none: no checkpoints are taken, tool calls are not
+//! counted, calls have no cost and tool outputs get the default limits.
+
+use crate::config::{CheckpointConfig, Config, ModelProfileConfig, ToolOutputConfig};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The parts of a config read mid-turn, away from where the turn started.
+#[derive(Debug, Clone)]
+pub struct TurnSettings {
+    pub tool_output: ToolOutputConfig,
+    pub checkpoints: CheckpointConfig,
+    /// Where tool calls are counted (`observability::tool_stats`)
+    pub workspace_dir: PathBuf,
+    /// `[[model_profiles]]`, for the prices model calls are charged at
+    pub model_profiles: Vec<ModelProfileConfig>,
+}
+
+impl TurnSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            tool_output: config.tool_output.clone(),
+            checkpoints: config.checkpoints.clone(),
+            workspace_dir: config.workspace_dir.clone(),
+            model_profiles: config.model_profiles.clone(),
+        }
+    }
+}
+
+tokio::task_local! {
+    static SETTINGS: Arc<TurnSettings>;
+}
+
+/// Run `turn` with the settings `config` sets.
+pub fn scope<F: Future>(config: &Config, turn: F) -> impl Future<Output = F::Output> {
+    scope_with(Arc::new(TurnSettings::from_config(config)), turn)
+}
+
+/// Run `turn` with `settings`, for callers that build them once for many
+/// turns.
+pub fn scope_with<F: Future>(
+    settings: Arc<TurnSettings>,
+    turn: F,
+) -> impl Future<Output = F::Output> {
+    // Boxed, so wrapping a turn does not grow the caller's future
+    SETTINGS.scope(settings, Box::pin(turn))
+}
+
+/// Settings of the running turn, if any.
+pub fn current() -> Option<Arc<TurnSettings>> {
+    SETTINGS.try_with(Arc::clone).ok()
+}
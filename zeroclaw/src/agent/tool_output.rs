@@ -0,0 +1,242 @@
+//! Shortening of tool outputs too large for the context window.
+//!
+//! An output over `[tool_output] max_tokens` is kept whole in the
+//! `fetch_full_output` store and replaced, for the model, by its first and
+//! last lines or by a summary from a cheaper model. The replacement names
+//! the stored copy so the model can page through the rest.
+
+use crate::config::{ToolOutputConfig, ToolOutputMode};
+use crate::providers::Provider;
+use crate::tokens::{self, Family};
+use crate::tools::fetch_full_output;
+
+/// `output` of `tool` as it should be shown to `model`, under the running
+/// turn's `[tool_output]`.
+pub async fn shorten(provider: &dyn Provider, model: &str, tool: &str, output: String) -> String {
+    let config = super::settings::current()
+        .map_or_else(ToolOutputConfig::default, |settings| settings.tool_output.clone());
+    shorten_with(&config, provider, model, tool, output).await
+}
+
+async fn shorten_with(
+    config: &ToolOutputConfig,
+    provider: &dyn Provider,
+    model: &str,
+    tool: &str,
+    output: String,
+) -> String {
+    // Pages of a stored output are already bounded
+    if config.max_tokens == 0 || tool == "fetch_full_output" {
+        return output;
+    }
+    let family = Family::for_model(model);
+    if family.count(&output) <= config.max_tokens {
+        return output;
+    }
+
+    let line_count = output.lines().count();
+    let truncated = truncate(&output, family, config.max_tokens);
+    let summary_model = config.summary_model.as_deref().unwrap_or(model);
+    // Leave the summarizer room for its own prompt and reply
+    let input = (config.mode == ToolOutputMode::Summarize).then(|| {
+        truncate(
+            &output,
+            Family::for_model(summary_model),
+            tokens::context_window(summary_model) / 2,
+        )
+    });
+    let id = fetch_full_output::store_output(output);
+
+    if let Some(input) = input {
+        let prompt = format!(
+            "Summarize this output of the `{tool}` tool for an agent that will act on it. \
+             Keep errors, warnings, failing tests, file paths and line numbers verbatim. \
+             Be brief."
+        );
+        match provider
+            .chat_with_system(Some(&prompt), &input.text, summary_model, 0.0)
+            .await
+        {
+            Ok(summary) => {
+                return format!(
+                    "[Summary of a {line_count}-line output. The full output is stored as {id}; \
+                     read it with fetch_full_output]\n{}",
+                    summary.trim()
+                );
+            }
+            Err(e) => {
+                tracing::warn!(
+                    tool,
+                    model = summary_model,
+                    "Tool output summary failed: {e}"
+                );
+            }
+        }
+    }
+
+    let note = format!(
+        "[{} of {line_count} lines omitted. The full output is stored as {id}; \
+         read them with fetch_full_output]",
+        line_count - truncated.kept
+    );
+    [truncated.head, note, truncated.tail]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+struct Truncated {
+    head: String,
+    tail: String,
+    /// Lines in `head` and `tail`
+    kept: usize,
+    text: String,
+}
+
+/// First and last lines of `output` within about `max_tokens`, half each.
+/// A single line longer than half the budget is left out.
+fn truncate(output: &str, family: Family, max_tokens: u64) -> Truncated {
+    let lines: Vec<&str> = output.lines().collect();
+    let half = max_tokens / 2;
+
+    let mut spent = 0;
+    let mut head_end = 0;
+    for line in &lines {
+        spent += family.count(line) + 1;
+        if spent > half {
+            break;
+        }
+        head_end += 1;
+    }
+
+    let mut spent = 0;
+    let mut tail_start = lines.len();
+    while tail_start > head_end {
+        spent += family.count(lines[tail_start - 1]) + 1;
+        if spent > half {
+            break;
+        }
+        tail_start -= 1;
+    }
+
+    let head = lines[..head_end].join("\n");
+    let tail = lines[tail_start..].join("\n");
+    let kept = head_end + lines.len() - tail_start;
+    let text = if kept == lines.len() {
+        output.to_string()
+    } else {
+        format!(
+            "{head}\n[... {} lines omitted ...]\n{tail}",
+            lines.len() - kept
+        )
+    };
+    Truncated {
+        head,
+        tail,
+        kept,
+        text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::settings;
+    use crate::config::Config;
+    use crate::providers::mock::ScriptedProvider;
+    use crate::tools::{FetchFullOutputTool, Tool};
+
+    fn summarizer(reply: Option<&'static str>) -> ScriptedProvider {
+        reply.map_or_else(
+            || ScriptedProvider::failing("summarizer down"),
+            ScriptedProvider::always,
+        )
+    }
+
+    fn build_log() -> String {
+        (0..5000)
+            .map(|i| format!("   Compiling crate-{i} v0.1.0"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn stored_id(shortened: &str) -> String {
+        let start = shortened.find("out-").unwrap();
+        shortened[start..]
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .next()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn small_outputs_pass_through() {
+        let config = ToolOutputConfig::default();
+        let output = shorten_with(&config, &summarizer(None), "gpt-4o", "shell", "ok".into()).await;
+        assert_eq!(output, "ok");
+    }
+
+    #[tokio::test]
+    async fn large_outputs_keep_head_and_tail_and_a_full_copy() {
+        let config = ToolOutputConfig {
+            max_tokens: 500,
+            ..ToolOutputConfig::default()
+        };
+        let log = build_log();
+        let output = shorten_with(&config, &summarizer(None), "gpt-4o", "shell", log).await;
+
+        assert!(tokens::count("gpt-4o", &output) < 600);
+        assert!(output.starts_with("   Compiling crate-0 v0.1.0"));
+        assert!(output.ends_with("   Compiling crate-4999 v0.1.0"));
+        assert!(output.contains("lines omitted"));
+
+        let page = FetchFullOutputTool::new()
+            .execute(serde_json::json!({"id": stored_id(&output), "offset": 2500, "limit": 1}))
+            .await
+            .unwrap();
+        assert!(page.output.contains("crate-2500 "));
+    }
+
+    #[tokio::test]
+    async fn summaries_use_the_summary_model_and_fall_back_to_truncation() {
+        let config = ToolOutputConfig {
+            max_tokens: 500,
+            mode: ToolOutputMode::Summarize,
+            summary_model: Some("gpt-4o-mini".into()),
+        };
+        let provider = summarizer(Some("5000 crates compiled, no errors."));
+        let output = shorten_with(&config, &provider, "gpt-4o", "shell", build_log()).await;
+        assert!(output.starts_with("[Summary of a 5000-line output"));
+        assert!(output.ends_with("5000 crates compiled, no errors."));
+        assert_eq!(provider.models(), ["gpt-4o-mini"]);
+
+        let output = shorten_with(&config, &summarizer(None), "gpt-4o", "shell", build_log()).await;
+        assert!(output.contains("lines omitted"));
+
+        let page = shorten_with(
+            &config,
+            &provider,
+            "gpt-4o",
+            "fetch_full_output",
+            build_log(),
+        )
+        .await;
+        assert_eq!(page, build_log());
+    }
+
+    #[tokio::test]
+    async fn each_turn_uses_the_settings_it_runs_with() {
+        let mut strict = Config::default();
+        strict.tool_output.max_tokens = 500;
+        let mut off = Config::default();
+        off.tool_output.max_tokens = 0;
+        let provider = summarizer(None);
+        let (shortened, whole) = tokio::join!(
+            settings::scope(&strict, shorten(&provider, "gpt-4o", "shell", build_log())),
+            settings::scope(&off, shorten(&provider, "gpt-4o", "shell", build_log())),
+        );
+        assert!(shortened.contains("lines omitted"));
+        assert_eq!(whole, build_log());
+    }
+}
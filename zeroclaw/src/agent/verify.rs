@@ -0,0 +1,443 @@
+//! Numeric answer verification.
+//!
+//! Tool-grounded answers can still carry arithmetic slips: a wrong total, a
+//! percentage computed against the wrong base. When `[verifier]` is enabled,
+//! the model lists every figure it derived from tool data together with the
+//! arithmetic behind it; each expression is recomputed with the calculator and
+//! mismatches are corrected before the answer is delivered.
+
+use crate::config::VerifierConfig;
+use crate::providers::{ChatMessage, Provider};
+use crate::tools::calculator::evaluate;
+use crate::util::truncate_with_ellipsis;
+use serde::Deserialize;
+use std::fmt::Write;
+
+/// Prefix of the user message that carries tool output in the agent loop.
+const TOOL_RESULTS_PREFIX: &str = "[Tool results]";
+const MAX_TOOL_CONTEXT_CHARS: usize = 12_000;
+
+const EXTRACT_PROMPT: &str = "You audit numbers in an assistant's answer. List every number in \
+the answer that was calculated (totals, differences, averages, percentages, growth rates, \
+conversions) rather than copied verbatim from the tool data. For each, give an arithmetic \
+expression using only numbers from the tool data that should produce it, in the same units \
+as written (for 12.5% the expression must evaluate to 12.5). Allowed: + - * / ^, parentheses, \
+round, abs, min, max, sum, avg, sqrt. Respond with a JSON array only, no prose: \
+[{\"claim\": \"<short quote from the answer>\", \"stated\": \"<the number exactly as written>\", \
+\"expression\": \"<expression>\"}]. Respond with [] when nothing was calculated.";
+
+const CORRECT_PROMPT: &str = "You fix arithmetic errors in an answer. Some figures were \
+recomputed exactly and differ from what the answer states. Rewrite the answer using the \
+corrected figures, also fixing any statement that depends on them. Change nothing else: keep \
+the wording, structure and formatting. Output only the revised answer.";
+
+/// A figure that did not survive recomputation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Correction {
+    pub claim: String,
+    pub stated: String,
+    pub expression: String,
+    pub correct: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Claim {
+    #[serde(default)]
+    claim: String,
+    stated: serde_json::Value,
+    expression: String,
+}
+
+/// Verify the figures in `answer` against this turn's tool results and
+/// return the (possibly corrected) answer. The final assistant message in
+/// `history` is updated to match. Verification is best-effort: any failure
+/// leaves the answer unchanged.
+pub async fn verify_answer(
+    provider: &dyn Provider,
+    history: &mut [ChatMessage],
+    answer: String,
+    model: &str,
+    config: &VerifierConfig,
+) -> String {
+    if !config.enabled || !answer.chars().any(|c| c.is_ascii_digit()) {
+        return answer;
+    }
+    let tool_data = turn_tool_results(history);
+    if tool_data.is_empty() {
+        return answer;
+    }
+    let model = config.model.as_deref().unwrap_or(model);
+
+    let corrections = match find_corrections(provider, &tool_data, &answer, model, config).await {
+        Ok(corrections) => corrections,
+        Err(e) => {
+            tracing::warn!("Numeric verification skipped: {e}");
+            return answer;
+        }
+    };
+    if corrections.is_empty() {
+        return answer;
+    }
+    for c in &corrections {
+        tracing::info!(
+            claim = %c.claim,
+            stated = %c.stated,
+            correct = %c.correct,
+            "Verifier corrected a figure"
+        );
+    }
+
+    let revised = match rewrite(provider, &answer, &corrections, model).await {
+        Ok(revised) if !revised.is_empty() => revised,
+        Ok(_) => append_corrections(&answer, &corrections),
+        Err(e) => {
+            tracing::warn!("Verifier could not rewrite the answer: {e}");
+            append_corrections(&answer, &corrections)
+        }
+    };
+
+    if let Some(last) = history.iter_mut().rev().find(|m| m.role == "assistant") {
+        last.content.clone_from(&revised);
+    }
+    revised
+}
+
+/// Tool output produced since the last real user message.
+fn turn_tool_results(history: &[ChatMessage]) -> String {
+    let start = history
+        .iter()
+        .rposition(|m| m.role == "user" && !m.content.starts_with(TOOL_RESULTS_PREFIX))
+        .map_or(0, |i| i + 1);
+    let mut data = String::new();
+    for message in &history[start..] {
+        if message.role == "user" && message.content.starts_with(TOOL_RESULTS_PREFIX) {
+            data.push_str(&message.content);
+            data.push('\n');
+        }
+    }
+    truncate_with_ellipsis(&data, MAX_TOOL_CONTEXT_CHARS)
+}
+
+async fn find_corrections(
+    provider: &dyn Provider,
+    tool_data: &str,
+    answer: &str,
+    model: &str,
+    config: &VerifierConfig,
+) -> anyhow::Result<Vec<Correction>> {
+    let messages = [
+        ChatMessage::system(EXTRACT_PROMPT),
+        ChatMessage::user(format!("Tool data:\n{tool_data}\n\nAnswer:\n{answer}")),
+    ];
+    let reply = provider.chat_with_history(&messages, model, 0.0).await?;
+    let claims = parse_claims(&reply)?;
+    Ok(check_claims(&claims, config.max_claims))
+}
+
+fn parse_claims(reply: &str) -> anyhow::Result<Vec<Claim>> {
+    let span = reply.find('[').zip(reply.rfind(']'));
+    let Some((start, end)) = span.filter(|(start, end)| start < end) else {
+        anyhow::bail!("verifier reply contained no JSON array");
+    };
+    Ok(serde_json::from_str(&reply[start..=end])?)
+}
+
+fn check_claims(claims: &[Claim], max_claims: usize) -> Vec<Correction> {
+    claims
+        .iter()
+        .take(max_claims)
+        .filter_map(|claim| {
+            let stated_raw = match &claim.stated {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            let stated = Figure::parse(&stated_raw)?;
+            let computed = match evaluate(&claim.expression) {
+                Ok(v) => v,
+                Err(e) => {
+                    tracing::debug!(expression = %claim.expression, "Unverifiable claim: {e}");
+                    return None;
+                }
+            };
+            if stated.matches(computed) {
+                return None;
+            }
+            Some(Correction {
+                claim: claim.claim.clone(),
+                stated: stated_raw.trim().to_string(),
+                expression: claim.expression.clone(),
+                correct: stated.render(computed),
+            })
+        })
+        .collect()
+}
+
+async fn rewrite(
+    provider: &dyn Provider,
+    answer: &str,
+    corrections: &[Correction],
+    model: &str,
+) -> anyhow::Result<String> {
+    let mut prompt = format!("Answer:\n{answer}\n\nCorrected figures:\n");
+    for c in corrections {
+        let _ = writeln!(
+            prompt,
+            "- \"{}\": stated {}, correct {} (= {})",
+            c.claim, c.stated, c.correct, c.expression
+        );
+    }
+    let messages = [
+        ChatMessage::system(CORRECT_PROMPT),
+        ChatMessage::user(prompt),
+    ];
+    Ok(provider
+        .chat_with_history(&messages, model, 0.0)
+        .await?
+        .trim()
+        .to_string())
+}
+
+/// Deterministic fallback when the model cannot rewrite the answer.
+fn append_corrections(answer: &str, corrections: &[Correction]) -> String {
+    let mut out = format!("{}\n\nCorrections (recomputed):", answer.trim_end());
+    for c in corrections {
+        let _ = write!(out, "\n- {}: {} (not {})", c.claim, c.correct, c.stated);
+    }
+    out
+}
+
+/// A number as written in prose: `$1,234.50`, `-3.2%`, `1.4M`, `2.5 billion`.
+#[derive(Debug, Clone, PartialEq)]
+struct Figure {
+    value: f64,
+    decimals: usize,
+    scale: f64,
+    prefix: String,
+    suffix: String,
+    grouped: bool,
+}
+
+impl Figure {
+    fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let first_digit = raw.find(|c: char| c.is_ascii_digit())?;
+        let (prefix, rest) = raw.split_at(first_digit);
+        let negative = prefix.contains(['-', '\u{2212}']);
+        let prefix: String = prefix
+            .chars()
+            .filter(|c| !matches!(c, '-' | '\u{2212}'))
+            .collect();
+
+        let core_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+            .unwrap_or(rest.len());
+        let core = rest[..core_len].trim_end_matches(['.', ',']);
+        let suffix = &rest[core.len()..];
+
+        let digits: String = core.chars().filter(|c| *c != ',').collect();
+        let number: f64 = digits.parse().ok()?;
+        let decimals = digits.split_once('.').map_or(0, |(_, frac)| frac.len());
+
+        let scale = match suffix.trim().to_ascii_lowercase().as_str() {
+            "k" | "thousand" => 1e3,
+            "m" | "mn" | "mm" | "million" => 1e6,
+            "b" | "bn" | "billion" => 1e9,
+            "t" | "tn" | "trillion" => 1e12,
+            _ => 1.0,
+        };
+        let sign = if negative { -1.0 } else { 1.0 };
+
+        Some(Self {
+            value: sign * number * scale,
+            decimals,
+            scale,
+            prefix,
+            suffix: suffix.to_string(),
+            grouped: core.contains(','),
+        })
+    }
+
+    /// Whether `computed` rounds to the stated figure at its written precision.
+    fn matches(&self, computed: f64) -> bool {
+        let half_unit = 0.5 * 10f64.powi(-i32::try_from(self.decimals).unwrap_or(15)) * self.scale;
+        let epsilon = 1e-9 * computed.abs().max(1.0);
+        (self.value - computed).abs() <= half_unit + epsilon
+    }
+
+    /// Format `computed` the way this figure was written.
+    fn render(&self, computed: f64) -> String {
+        let scaled = computed / self.scale;
+        let mut digits = format!("{:.*}", self.decimals, scaled.abs());
+        if self.grouped {
+            digits = group_thousands(&digits);
+        }
+        let sign = if scaled < 0.0 && digits.chars().any(|c| ('1'..='9').contains(&c)) {
+            "-"
+        } else {
+            ""
+        };
+        format!("{sign}{}{digits}{}", self.prefix, self.suffix)
+    }
+}
+
+fn group_thousands(digits: &str) -> String {
+    let (int, frac) = digits
+        .split_once('.')
+        .map_or((digits, None), |(i, f)| (i, Some(f)));
+    let mut grouped = String::new();
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    if let Some(frac) = frac {
+        grouped.push('.');
+        grouped.push_str(frac);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+
+    fn enabled() -> VerifierConfig {
+        VerifierConfig {
+            enabled: true,
+            ..VerifierConfig::default()
+        }
+    }
+
+    fn tool_turn(answer: &str) -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("What were total sales?"),
+            ChatMessage::assistant("<tool_call>...</tool_call>"),
+            ChatMessage::user("[Tool results]\nQ1: 1200.50\nQ2: 980.25"),
+            ChatMessage::assistant(answer),
+        ]
+    }
+
+    #[test]
+    fn figure_parse_handles_prose_formats() {
+        let f = Figure::parse("$1,234.50").unwrap();
+        assert!((f.value - 1234.5).abs() < 1e-9);
+        assert_eq!(f.decimals, 2);
+        assert_eq!(f.prefix, "$");
+        assert!(f.grouped);
+
+        let f = Figure::parse("-3.2%").unwrap();
+        assert!((f.value + 3.2).abs() < 1e-9);
+        assert_eq!(f.suffix, "%");
+        assert_eq!(Figure::parse("-$40").unwrap().render(-45.0), "-$45");
+
+        let f = Figure::parse("1.4M").unwrap();
+        assert!((f.value - 1_400_000.0).abs() < 1e-6);
+        assert!(f.matches(1_440_000.0));
+        assert!(!f.matches(1_460_000.0));
+
+        assert!(Figure::parse("n/a").is_none());
+    }
+
+    #[test]
+    fn figure_matches_at_written_precision() {
+        let f = Figure::parse("27.59").unwrap();
+        assert!(f.matches(27.591_836));
+        assert!(!f.matches(27.6));
+        let whole = Figure::parse("2,181").unwrap();
+        assert!(whole.matches(2180.75));
+        assert!(!whole.matches(2179.0));
+    }
+
+    #[test]
+    fn figure_render_mirrors_original_format() {
+        assert_eq!(
+            Figure::parse("$1,234.50").unwrap().render(2180.75),
+            "$2,180.75"
+        );
+        assert_eq!(Figure::parse("12.5%").unwrap().render(-8.333), "-8.3%");
+        assert_eq!(Figure::parse("1.4M").unwrap().render(1_560_000.0), "1.6M");
+        assert_eq!(group_thousands("1234567.8"), "1,234,567.8");
+    }
+
+    #[test]
+    fn check_claims_flags_only_mismatches() {
+        let claims = parse_claims(
+            r#"Here you go:
+            [{"claim": "total of $2,180.75", "stated": "$2,180.75", "expression": "1200.50 + 980.25"},
+             {"claim": "Q1 share 60%", "stated": "60%", "expression": "1200.50 / (1200.50 + 980.25) * 100"},
+             {"claim": "bad", "stated": 5, "expression": "foo"}]"#,
+        )
+        .unwrap();
+        let corrections = check_claims(&claims, 10);
+        assert_eq!(corrections.len(), 1);
+        assert_eq!(corrections[0].stated, "60%");
+        assert_eq!(corrections[0].correct, "55%");
+
+        assert!(check_claims(&claims, 1).is_empty());
+        assert!(parse_claims("no json").is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_answer_rewrites_wrong_figures() {
+        let answer = "Total sales were $2,280.75.";
+        let provider = ScriptedProvider::new(&[
+            r#"[{"claim": "Total sales", "stated": "$2,280.75", "expression": "1200.50 + 980.25"}]"#,
+            "Total sales were $2,180.75.",
+        ]);
+        let mut history = tool_turn(answer);
+
+        let revised = verify_answer(&provider, &mut history, answer.into(), "m", &enabled()).await;
+        assert_eq!(revised, "Total sales were $2,180.75.");
+        assert_eq!(history.last().unwrap().content, revised);
+    }
+
+    #[tokio::test]
+    async fn verify_answer_appends_corrections_when_rewrite_fails() {
+        let answer = "Total sales were $2,280.75.";
+        let provider = ScriptedProvider::new(&[
+            r#"[{"claim": "Total sales", "stated": "$2,280.75", "expression": "1200.50 + 980.25"}]"#,
+        ]);
+        let mut history = tool_turn(answer);
+
+        let revised = verify_answer(&provider, &mut history, answer.into(), "m", &enabled()).await;
+        assert!(revised.starts_with(answer));
+        assert!(revised.contains("- Total sales: $2,180.75 (not $2,280.75)"));
+    }
+
+    #[tokio::test]
+    async fn verify_answer_skips_turns_without_tool_data() {
+        let provider = ScriptedProvider::new(&[]);
+        let mut history = vec![
+            ChatMessage::user("What is 2 + 2?"),
+            ChatMessage::assistant("5"),
+        ];
+        let out = verify_answer(&provider, &mut history, "5".into(), "m", &enabled()).await;
+        assert_eq!(out, "5");
+
+        let mut history = tool_turn("No figures here.");
+        let out = verify_answer(
+            &provider,
+            &mut history,
+            "No figures here.".into(),
+            "m",
+            &enabled(),
+        )
+        .await;
+        assert_eq!(out, "No figures here.");
+
+        let mut history = tool_turn("Total 1");
+        let out = verify_answer(
+            &provider,
+            &mut history,
+            "Total 1".into(),
+            "m",
+            &VerifierConfig::default(),
+        )
+        .await;
+        assert_eq!(out, "Total 1");
+        assert_eq!(provider.calls(), 0);
+    }
+}
@@ -0,0 +1,225 @@
+//! The daemon side of the agent bus: one agent per profile, each answering
+//! its mailbox one message at a time.
+
+use super::{Envelope, MAIN_AGENT};
+use crate::agent::loop_;
+use crate::channels::build_system_prompt;
+use crate::config::Config;
+use crate::memory;
+use crate::observability::{self, Observer};
+use crate::providers::{self, ChatMessage, Provider};
+use crate::runtime;
+use crate::security::SecurityPolicy;
+use crate::tools::{self, Tool};
+use anyhow::{Context, Result};
+use futures_util::future::join_all;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// A profile running on the bus.
+struct BusAgent {
+    name: String,
+    config: Config,
+    provider: Box<dyn Provider>,
+    observer: Box<dyn Observer>,
+    tools: Vec<Box<dyn Tool>>,
+    system_prompt: String,
+    model: String,
+}
+
+/// Profiles to run: `[agent_bus] agents`, or every profile when empty.
+fn agent_names(config: &Config) -> Result<Vec<String>> {
+    let names: Vec<String> = if config.agent_bus.agents.is_empty() {
+        config.profiles.keys().cloned().collect()
+    } else {
+        config.agent_bus.agents.clone()
+    };
+    if names.is_empty() {
+        anyhow::bail!("[agent_bus] is enabled but no [profiles.<name>] sections are configured");
+    }
+    if names.iter().any(|name| name == MAIN_AGENT || name == "*") {
+        anyhow::bail!("\"{MAIN_AGENT}\" and \"*\" cannot be agent names");
+    }
+    Ok(names)
+}
+
+/// Agent loop, supervised by the daemon. Runs until the daemon stops.
+pub async fn run_agents(config: Config) -> Result<()> {
+    let names = agent_names(&config)?;
+    let mut agents = Vec::new();
+    for name in &names {
+        let agent = BusAgent::new(&config, name)
+            .with_context(|| format!("Could not start agent \"{name}\""))?;
+        agents.push(agent);
+    }
+
+    let bus = super::shared();
+    let mailboxes: Vec<_> = names.iter().map(|name| bus.register(name)).collect();
+    crate::health::mark_component_ok("agents");
+    tracing::info!("Agent bus running: {}", names.join(", "));
+
+    join_all(
+        agents
+            .iter()
+            .zip(mailboxes)
+            .map(|(agent, mut mailbox)| async move {
+                while let Some(envelope) = mailbox.recv().await {
+                    agent.handle(envelope).await;
+                }
+            }),
+    )
+    .await;
+    anyhow::bail!("Agent bus mailboxes closed")
+}
+
+impl BusAgent {
+    fn new(base: &Config, name: &str) -> Result<Self> {
+        let config = base.with_profile(name)?;
+        let provider = providers::create_resilient_provider(
+            config.default_provider.as_deref().unwrap_or("openrouter"),
+            config.api_key.as_deref(),
+            &config.reliability,
+            &providers::ProviderSettings::from_config(&config),
+        )?;
+        let model = config
+            .default_model
+            .clone()
+            .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
+
+        let workspace = config.workspace_dir.clone();
+        let mem: Arc<dyn memory::Memory> = Arc::from(memory::create_memory(
+            &config.memory,
+            &workspace,
+            config.api_key.as_deref(),
+        )?);
+        let security = Arc::new(SecurityPolicy::from_config(&config.autonomy, &workspace));
+        let runtime: Arc<dyn runtime::RuntimeAdapter> =
+            Arc::from(runtime::create_runtime(&config.runtime)?);
+        let composio_key = if config.composio.enabled {
+            config.composio.api_key.as_deref()
+        } else {
+            None
+        };
+        let mut tools = tools::all_tools_with_runtime(
+            &security,
+            runtime,
+            mem.clone(),
+            composio_key,
+            &config.browser,
+        );
+        tools.extend(tools::optional_tools(&config, &security));
+        tools::route_to_nodes(&mut tools, &config.nodes, &security);
+        if config.knowledge.enabled && crate::knowledge::supported(mem.as_ref()) {
+            tools.push(Box::new(tools::KbSearchTool::new(mem)) as Box<dyn Tool>);
+        }
+        // Nobody is there to answer a question
+        tools.retain(|t| t.name() != "ask_user");
+
+        let tool_descs = loop_::tool_descriptions(&config, &tools);
+        let skills = crate::skills::load_skills(&workspace);
+        let mut system_prompt = build_system_prompt(
+            &workspace,
+            &model,
+            &tool_descs,
+            &skills,
+            Some(&crate::identity::readable_by(config.identity.for_channel("agents"), &security)),
+        );
+        system_prompt.push_str(&loop_::build_tool_instructions_with(
+            &tools,
+            config.tool_schema_for(&model),
+        ));
+        let _ = write!(
+            system_prompt,
+            "\n\n## Agent bus\n\nYou are the agent \"{name}\". Your messages come from other \
+             agents, and your final answer is what the sender receives. Agents running: {}.\n",
+            agent_names(base)?.join(", ")
+        );
+
+        Ok(Self {
+            name: name.to_string(),
+            observer: observability::create_observer(&config.observability),
+            config,
+            provider,
+            tools,
+            system_prompt,
+            model,
+        })
+    }
+
+    /// Answer one message and send the answer back if the sender waits.
+    async fn handle(&self, envelope: Envelope) {
+        tracing::info!(
+            "Agent {} <- {}: {}",
+            self.name,
+            envelope.from,
+            envelope.message
+        );
+        let _run = crate::daemon::shutdown::begin_run();
+        let mut history = vec![
+            ChatMessage::system(self.system_prompt.as_str()),
+            ChatMessage::user(format!(
+                "Message from agent {}:\n{}",
+                envelope.from, envelope.message
+            )),
+        ];
+        let mut chain = envelope.chain;
+        chain.push(self.name.clone());
+        let outcome = super::scope(
+            chain,
+            crate::agent::settings::scope(
+                &self.config,
+                crate::hooks::scope(
+                    &self.config,
+                    loop_::agent_turn_with_budget(
+                        self.provider.as_ref(),
+                        &mut history,
+                        &self.tools,
+                        self.observer.as_ref(),
+                        &self.model,
+                        self.config.default_temperature,
+                        None,
+                        self.config.turn_budget_for(&self.model),
+                    ),
+                ),
+            ),
+        )
+        .await
+        .and_then(crate::agent::budget::TurnReply::into_result);
+
+        if let Err(e) = &outcome {
+            tracing::warn!(
+                "Agent {} failed on a message from {}: {e:#}",
+                self.name,
+                envelope.from
+            );
+        }
+        if let Some(reply) = envelope.reply {
+            // The sender may have stopped waiting
+            let _ = reply.send(outcome.map_err(|e| format!("{e:#}")));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::ProfileConfig;
+
+    #[test]
+    fn agents_default_to_every_profile() {
+        let mut config = Config::default();
+        assert!(agent_names(&config).is_err());
+
+        for name in ["coder", "lead"] {
+            config
+                .profiles
+                .insert(name.into(), ProfileConfig::default());
+        }
+        assert_eq!(agent_names(&config).unwrap(), ["coder", "lead"]);
+
+        config.agent_bus.agents = vec!["lead".into()];
+        assert_eq!(agent_names(&config).unwrap(), ["lead"]);
+        config.agent_bus.agents = vec!["main".into()];
+        assert!(agent_names(&config).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Agent bus — profiles run side by side in the daemon that message each
+//! other.
+//!
+//! Each agent is a `[profiles.<name>]` section run with its own model,
+//! memory namespace, identity and autonomy. The daemon gives every agent a
+//! mailbox on the shared [`AgentBus`]; the `send_to_agent` tool posts to it,
+//! either waiting for the answer (a supervisor delegating to a specialist)
+//! or not (handing a task off). Sending to `*` publishes to every other
+//! agent. A message carries the chain of agents it came through, so an
+//! agent is never asked by one it is waiting on, and chains stop at
+//! `[agent_bus] max_hops`.
+
+pub mod agents;
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::{mpsc, oneshot};
+
+pub use agents::run_agents;
+
+/// Messages an agent may have waiting before senders are turned away.
+const MAILBOX_CAPACITY: usize = 32;
+
+/// Sender name of turns that do not run on the bus (channels, CLI).
+pub const MAIN_AGENT: &str = "main";
+
+/// Where an agent's answer goes when the sender waits for it.
+pub type ReplyTo = oneshot::Sender<Result<String, String>>;
+
+/// One message on the bus.
+#[derive(Debug)]
+pub struct Envelope {
+    pub from: String,
+    pub to: String,
+    pub message: String,
+    /// Bus agents the message was handed on by, the sender last
+    pub chain: Vec<String>,
+    /// Unset when the sender does not wait for the answer
+    pub reply: Option<ReplyTo>,
+}
+
+/// Mailboxes of the running agents, by name.
+#[derive(Default)]
+pub struct AgentBus {
+    mailboxes: Mutex<BTreeMap<String, mpsc::Sender<Envelope>>>,
+}
+
+impl AgentBus {
+    fn mailboxes(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, mpsc::Sender<Envelope>>> {
+        self.mailboxes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Open the mailbox of agent `name`, replacing any earlier one.
+    pub fn register(&self, name: &str) -> mpsc::Receiver<Envelope> {
+        let (tx, rx) = mpsc::channel(MAILBOX_CAPACITY);
+        self.mailboxes().insert(name.to_string(), tx);
+        rx
+    }
+
+    pub fn unregister(&self, name: &str) {
+        self.mailboxes().remove(name);
+    }
+
+    /// Names of the running agents.
+    pub fn agents(&self) -> Vec<String> {
+        self.mailboxes().keys().cloned().collect()
+    }
+
+    /// Put `envelope` in its agent's mailbox.
+    pub fn send(&self, envelope: Envelope) -> Result<()> {
+        let Some(mailbox) = self.mailboxes().get(&envelope.to).cloned() else {
+            let running = self.agents();
+            anyhow::bail!(
+                "No agent named \"{}\" is running (running: {})",
+                envelope.to,
+                if running.is_empty() {
+                    "none".to_string()
+                } else {
+                    running.join(", ")
+                }
+            );
+        };
+        mailbox.try_send(envelope).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(envelope) => anyhow::anyhow!(
+                "Agent {} is busy ({MAILBOX_CAPACITY} messages waiting)",
+                envelope.to
+            ),
+            mpsc::error::TrySendError::Closed(envelope) => {
+                anyhow::anyhow!("Agent {} has stopped", envelope.to)
+            }
+        })
+    }
+
+    /// Send `message` to every agent except `from` and the ones in
+    /// `chain`, without waiting for answers. Returns the agents reached.
+    pub fn publish(&self, from: &str, message: &str, chain: &[String]) -> Vec<String> {
+        self.agents()
+            .into_iter()
+            .filter(|name| name != from && !chain.contains(name))
+            .filter(|name| {
+                self.send(Envelope {
+                    from: from.to_string(),
+                    to: name.clone(),
+                    message: message.to_string(),
+                    chain: chain.to_vec(),
+                    reply: None,
+                })
+                .map_err(|e| tracing::warn!("Could not publish to {name}: {e}"))
+                .is_ok()
+            })
+            .collect()
+    }
+}
+
+/// The bus shared by the daemon's agents and every `send_to_agent` tool.
+pub fn shared() -> &'static AgentBus {
+    static SHARED: OnceLock<AgentBus> = OnceLock::new();
+    SHARED.get_or_init(AgentBus::default)
+}
+
+tokio::task_local! {
+    static CHAIN: Vec<String>;
+}
+
+/// Run `turn` as the last agent of `chain`.
+pub async fn scope<F: Future>(chain: Vec<String>, turn: F) -> F::Output {
+    CHAIN.scope(chain, turn).await
+}
+
+/// Bus agents the running turn was handed on by, itself last; empty
+/// outside the bus.
+pub fn current_chain() -> Vec<String> {
+    CHAIN.try_with(Clone::clone).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn messages_reach_only_their_agent() {
+        let bus = AgentBus::default();
+        let mut coder = bus.register("coder");
+        let mut reviewer = bus.register("reviewer");
+
+        let (tx, rx) = oneshot::channel();
+        bus.send(Envelope {
+            from: MAIN_AGENT.into(),
+            to: "coder".into(),
+            message: "fix the parser".into(),
+            chain: Vec::new(),
+            reply: Some(tx),
+        })
+        .unwrap();
+        let envelope = coder.recv().await.unwrap();
+        assert_eq!(envelope.message, "fix the parser");
+        envelope.reply.unwrap().send(Ok("done".into())).unwrap();
+        assert_eq!(rx.await.unwrap(), Ok("done".to_string()));
+        assert!(reviewer.try_recv().is_err());
+
+        let err = bus
+            .send(Envelope {
+                from: MAIN_AGENT.into(),
+                to: "tester".into(),
+                message: String::new(),
+                chain: Vec::new(),
+                reply: None,
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No agent named \"tester\" is running (running: coder, reviewer)"
+        );
+
+        // Published messages skip the sender
+        assert_eq!(bus.publish("coder", "release is out", &[]), ["reviewer"]);
+        assert_eq!(reviewer.recv().await.unwrap().from, "coder");
+        assert!(coder.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn the_chain_is_visible_only_inside_the_scope() {
+        let chain = vec!["lead".to_string(), "coder".to_string()];
+        let seen = scope(chain.clone(), async { current_chain() }).await;
+        assert_eq!(seen, chain);
+        assert!(current_chain().is_empty());
+    }
+}
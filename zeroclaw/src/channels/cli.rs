@@ -42,6 +42,7 @@ impl Channel for CliChannel {
                 sender: "user".to_string(),
                 content: line,
                 channel: "cli".to_string(),
+                author: None,
                 timestamp: std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or_default()
@@ -92,6 +93,7 @@ mod tests {
             sender: "user".into(),
             content: "hello".into(),
             channel: "cli".into(),
+            author: None,
             timestamp: 1_234_567_890,
         };
         assert_eq!(msg.id, "test-id");
@@ -108,6 +110,7 @@ mod tests {
             sender: "s".into(),
             content: "c".into(),
             channel: "ch".into(),
+            author: None,
             timestamp: 0,
         };
         let cloned = msg.clone();
@@ -171,6 +171,43 @@ impl Channel for DiscordChannel {
         Ok(())
     }
 
+    async fn send_voice_note(
+        &self,
+        audio: Vec<u8>,
+        file_name: &str,
+        channel_id: &str,
+    ) -> anyhow::Result<()> {
+        let url = format!("https://discord.com/api/v10/channels/{channel_id}/messages");
+        let part = reqwest::multipart::Part::bytes(audio)
+            .file_name(file_name.to_string())
+            .mime_str("audio/ogg")?;
+        let form = reqwest::multipart::Form::new()
+            .text(
+                "payload_json",
+                json!({ "attachments": [{ "id": 0, "filename": file_name }] }).to_string(),
+            )
+            .part("files[0]", part);
+
+        let resp = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord voice upload failed ({status}): {err}");
+        }
+
+        Ok(())
+    }
+
     #[allow(clippy::too_many_lines)]
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let bot_user_id = Self::bot_user_id_from_token(&self.bot_token).unwrap_or_default();
@@ -343,6 +380,7 @@ impl Channel for DiscordChannel {
                         sender: channel_id,
                         content: content.to_string(),
                         channel: "discord".to_string(),
+                        author: Some(author_id.to_string()),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
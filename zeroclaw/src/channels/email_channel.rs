@@ -27,7 +27,7 @@ use uuid::Uuid;
 use super::traits::{Channel, ChannelMessage};
 
 /// Email channel configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct EmailConfig {
     /// IMAP server hostname
     pub imap_host: String,
@@ -57,6 +57,9 @@ pub struct EmailConfig {
     /// Allowed sender addresses/domains (empty = deny all, ["*"] = allow all)
     #[serde(default)]
     pub allowed_senders: Vec<String>,
+    /// Tools the agent may use on this channel; unset allows all
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 fn default_imap_port() -> u16 {
@@ -89,6 +92,7 @@ impl Default for EmailConfig {
             from_address: String::new(),
             poll_interval_secs: default_poll_interval(),
             allowed_senders: Vec::new(),
+            allowed_tools: None,
         }
     }
 }
@@ -415,6 +419,7 @@ impl Channel for EmailChannel {
                             sender,
                             content,
                             channel: "email".to_string(),
+                            author: None,
                             timestamp: ts,
                         };
                         if tx.send(msg).await.is_err() {
@@ -174,6 +174,7 @@ end tell"#
                             sender: sender.clone(),
                             content: text,
                             channel: "imessage".to_string(),
+                            author: None,
                             timestamp: std::time::SystemTime::now()
                                 .duration_since(std::time::UNIX_EPOCH)
                                 .unwrap_or_default()
@@ -557,6 +557,7 @@ impl Channel for IrcChannel {
                         sender: reply_to,
                         content,
                         channel: "irc".to_string(),
+                        author: Some(sender_nick.to_string()),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
@@ -937,6 +938,7 @@ mod tests {
             nickserv_password: Some("secret".into()),
             sasl_password: None,
             verify_tls: Some(true),
+            allowed_tools: None,
         };
 
         let toml_str = toml::to_string(&config).unwrap();
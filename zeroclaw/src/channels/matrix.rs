@@ -232,6 +232,7 @@ impl Channel for MatrixChannel {
                         sender: event.sender.clone(),
                         content: body.clone(),
                         channel: "matrix".to_string(),
+                        author: None,
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
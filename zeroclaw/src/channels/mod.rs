@@ -20,15 +20,26 @@ pub use telegram::TelegramChannel;
 pub use traits::Channel;
 pub use whatsapp::WhatsAppChannel;
 
-use crate::config::Config;
+use crate::agent::loop_;
+use crate::config::{Config, MemoryConfig, MemoryRecallConfig};
+use crate::guardrails::Guardrails;
 use crate::identity;
+use crate::knowledge;
 use crate::memory::{self, Memory};
-use crate::providers::{self, Provider};
+use crate::observability::{self, Observer};
+use crate::providers::{self, ChatMessage, Provider};
+use crate::runtime;
+use crate::security::SecurityPolicy;
+use crate::tools::{self, Tool};
+use crate::transcription::Transcriber;
+use crate::tts::Synthesizer;
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
 
 /// Maximum characters per injected workspace file (matches `OpenClaw` default).
 const BOOTSTRAP_MAX_CHARS: usize = 20_000;
@@ -38,23 +49,226 @@ const DEFAULT_CHANNEL_MAX_BACKOFF_SECS: u64 = 60;
 const CHANNEL_MESSAGE_TIMEOUT_SECS: u64 = 90;
 
 fn conversation_memory_key(msg: &traits::ChannelMessage) -> String {
-    format!("{}_{}_{}", msg.channel, msg.sender, msg.id)
+    format!("{}_{}_{}", msg.channel, msg.user(), msg.id)
 }
 
-async fn build_memory_context(mem: &dyn Memory, user_msg: &str) -> String {
-    let mut context = String::new();
+/// Drop every tool not in `allowed`; `None` keeps them all. Names that
+/// match no tool are logged, as they are most likely typos.
+fn restrict_tools(tools: &mut Vec<Box<dyn Tool>>, allowed: Option<&[String]>) {
+    let Some(allowed) = allowed else {
+        return;
+    };
+    for name in allowed {
+        if !tools.iter().any(|t| t.name() == name) {
+            tracing::warn!("allowed_tools names unknown tool {name}");
+        }
+    }
+    tools.retain(|t| allowed.iter().any(|name| name == t.name()));
+}
+
+/// Context from the sender's memories in `mem`, then up to
+/// `knowledge_chunks` knowledge passages, which are kept in the `shared` pool
+async fn build_memory_context(
+    mem: &dyn Memory,
+    shared: &dyn Memory,
+    user_msg: &str,
+    recall: &MemoryRecallConfig,
+    knowledge_chunks: usize,
+) -> String {
+    let mut context = memory::recall::build_context(mem, user_msg, recall).await;
+    context.push_str(&knowledge::build_context(shared, user_msg, knowledge_chunks).await);
+    context
+}
+
+/// A conversation with one user on a channel, kept for its summary until it
+/// has been quiet for `[memory.summary] idle_minutes`
+struct OpenConversation {
+    mem: Arc<dyn Memory>,
+    memory_config: MemoryConfig,
+    messages: Vec<ChatMessage>,
+    last_active: Instant,
+}
+
+/// Close the conversations quiet for `idle` or longer, summarizing each on
+/// `summaries` so the message loop does not wait for the model.
+fn close_conversations(
+    conversations: &mut HashMap<String, OpenConversation>,
+    idle: Duration,
+    provider: &Arc<dyn Provider>,
+    model: &str,
+    summaries: &mut JoinSet<()>,
+) {
+    let quiet: Vec<String> = conversations
+        .iter()
+        .filter(|(_, conversation)| conversation.last_active.elapsed() >= idle)
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in quiet {
+        if let Some(conversation) = conversations.remove(&key) {
+            let provider = provider.clone();
+            let model = model.to_string();
+            summaries.spawn(async move {
+                memory::summary::on_close(
+                    provider.as_ref(),
+                    conversation.mem.as_ref(),
+                    &conversation.memory_config,
+                    &model,
+                    &key,
+                    &conversation.messages,
+                )
+                .await;
+            });
+        }
+    }
+}
+
+/// Per-sender memory pools are dropped after this long without a message
+const USER_POOL_IDLE: Duration = Duration::from_secs(30 * 60);
+
+/// At most this many per-sender pools stay open; the least recently used go
+const MAX_USER_POOLS: usize = 256;
+
+/// A memory pool opened for one namespace, and when it last served a message
+struct UserPool {
+    mem: Arc<dyn Memory>,
+    last_used: Instant,
+}
+
+/// The memories of the sender `memory_config` is narrowed to (see
+/// `memory::user_config`), opened on their first message and then kept in
+/// `pools` by namespace.
+fn user_memory(
+    pools: &mut HashMap<String, UserPool>,
+    config: &Config,
+    memory_config: &MemoryConfig,
+) -> Result<Arc<dyn Memory>> {
+    let namespace = memory_config.namespace.clone().unwrap_or_default();
+    if let Some(pool) = pools.get_mut(&namespace) {
+        pool.last_used = Instant::now();
+        return Ok(pool.mem.clone());
+    }
+    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
+        memory_config,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )?);
+    pools.insert(
+        namespace,
+        UserPool {
+            mem: mem.clone(),
+            last_used: Instant::now(),
+        },
+    );
+    Ok(mem)
+}
+
+/// Drop the pools, and the tools built on them, idle for `idle` or longer,
+/// then the least recently used beyond `max`. The `shared` pool stays.
+fn evict_user_pools<T>(
+    pools: &mut HashMap<String, UserPool>,
+    tools: &mut HashMap<(String, String), T>,
+    shared: &str,
+    idle: Duration,
+    max: usize,
+) {
+    pools.retain(|namespace, pool| namespace == shared || pool.last_used.elapsed() < idle);
+    if pools.len() > max {
+        let mut by_use: Vec<(Instant, String)> = pools
+            .iter()
+            .filter(|(namespace, _)| namespace.as_str() != shared)
+            .map(|(namespace, pool)| (pool.last_used, namespace.clone()))
+            .collect();
+        by_use.sort();
+        for (_, namespace) in by_use.into_iter().take(pools.len() - max) {
+            pools.remove(&namespace);
+        }
+    }
+    tools.retain(|(_, namespace), _| pools.contains_key(namespace));
+}
+
+/// Split `[VOICE:<path>]` markers (emitted by the `speak` tool) out of a reply.
+fn extract_voice_markers(response: &str) -> (String, Vec<String>) {
+    let mut text = String::new();
+    let mut clips = Vec::new();
+    let mut rest = response;
+
+    while let Some(start) = rest.find("[VOICE:") {
+        let Some(len) = rest[start..].find(']') else {
+            break;
+        };
+        let path = rest[start + "[VOICE:".len()..start + len].trim();
+        if !path.is_empty() {
+            clips.push(path.to_string());
+        }
+        text.push_str(&rest[..start]);
+        rest = &rest[start + len + 1..];
+    }
+    text.push_str(rest);
 
-    if let Ok(entries) = mem.recall(user_msg, 5).await {
-        if !entries.is_empty() {
-            context.push_str("[Memory context]\n");
-            for entry in &entries {
-                let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
+    (text.trim().to_string(), clips)
+}
+
+/// Read a voice clip referenced by a reply, refusing paths outside the workspace.
+async fn read_voice_clip(workspace_dir: &std::path::Path, path: &str) -> Result<Vec<u8>> {
+    let workspace = tokio::fs::canonicalize(workspace_dir).await?;
+    let resolved = tokio::fs::canonicalize(workspace.join(path)).await?;
+    if !resolved.starts_with(&workspace) {
+        anyhow::bail!("Voice clip escapes workspace: {path}");
+    }
+    Ok(tokio::fs::read(resolved).await?)
+}
+
+/// Send a reply, as voice notes where requested.
+///
+/// Clips referenced with `[VOICE:...]` are always sent as voice notes. When
+/// `synthesizer` is set (the channel has `voice_replies` on), the remaining
+/// text is spoken as well; it falls back to plain text if synthesis or
+/// upload fails or the text is too long to speak.
+async fn deliver_reply(
+    channel: &dyn Channel,
+    response: &str,
+    recipient: &str,
+    workspace_dir: &std::path::Path,
+    synthesizer: Option<&Synthesizer>,
+) -> Result<()> {
+    let (text, clips) = extract_voice_markers(response);
+
+    for path in &clips {
+        let sent = match read_voice_clip(workspace_dir, path).await {
+            Ok(audio) => channel.send_voice_note(audio, "voice.ogg", recipient).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = sent {
+            tracing::warn!(
+                "Failed to send voice clip {path} on {}: {e}",
+                channel.name()
+            );
+        }
+    }
+
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(synth) = synthesizer.filter(|s| s.accepts(&text)) {
+        let spoken = match synth.synthesize(&text, None).await {
+            Ok(clip) => {
+                channel
+                    .send_voice_note(clip.bytes, &clip.file_name, recipient)
+                    .await
             }
-            context.push('\n');
+            Err(e) => Err(e),
+        };
+        match spoken {
+            Ok(()) => return Ok(()),
+            Err(e) => tracing::warn!(
+                "Voice reply on {} failed, sending text: {e}",
+                channel.name()
+            ),
         }
     }
 
-    context
+    channel.send(&text, recipient).await
 }
 
 fn spawn_supervised_listener(
@@ -98,18 +312,18 @@ fn spawn_supervised_listener(
 }
 
 /// Load OpenClaw format bootstrap files into the prompt.
-fn load_openclaw_bootstrap_files(prompt: &mut String, workspace_dir: &std::path::Path) {
+fn load_openclaw_bootstrap_files(
+    prompt: &mut String,
+    workspace_dir: &std::path::Path,
+    instructions: &[identity::InstructionFile],
+) {
     prompt
         .push_str("The following workspace files define your identity, behavior, and context.\n\n");
 
-    let bootstrap_files = [
-        "AGENTS.md",
-        "SOUL.md",
-        "TOOLS.md",
-        "IDENTITY.md",
-        "USER.md",
-        "HEARTBEAT.md",
-    ];
+    // Standing instructions (AGENTS.md, SOUL.md or `[identity.instruction_files]`)
+    inject_instruction_files(prompt, instructions, true);
+
+    let bootstrap_files = ["TOOLS.md", "IDENTITY.md", "USER.md", "HEARTBEAT.md"];
 
     for filename in &bootstrap_files {
         inject_workspace_file(prompt, workspace_dir, filename);
@@ -125,19 +339,48 @@ fn load_openclaw_bootstrap_files(prompt: &mut String, workspace_dir: &std::path:
     inject_workspace_file(prompt, workspace_dir, "MEMORY.md");
 }
 
+/// Inject instruction files within their token budgets. Missing files get
+/// the `OpenClaw` not-found marker when `mark_missing` is set, unless they are
+/// optional.
+fn inject_instruction_files(
+    prompt: &mut String,
+    instructions: &[identity::InstructionFile],
+    mark_missing: bool,
+) {
+    for file in instructions {
+        match identity::render_instruction_file(file) {
+            Ok(section) => prompt.push_str(&section),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && file.optional => {}
+            Err(_) if mark_missing => {
+                let _ = writeln!(
+                    prompt,
+                    "### {name}\n\n[File not found: {name}]\n",
+                    name = file.name
+                );
+            }
+            Err(e) => {
+                tracing::warn!("Instruction file {} unreadable: {e}", file.path.display());
+            }
+        }
+    }
+}
+
 /// Load workspace identity files and build a system prompt.
 ///
 /// Follows the `OpenClaw` framework structure by default:
 /// 1. Tooling — tool list + descriptions
 /// 2. Safety — guardrail reminder
 /// 3. Skills — compact list with paths (loaded on-demand)
-/// 4. Workspace — working directory
+/// 4. Workspace — working directory, and the detected project (see [`crate::context`])
 /// 5. Bootstrap files — AGENTS, SOUL, TOOLS, IDENTITY, USER, HEARTBEAT, BOOTSTRAP, MEMORY
-/// 6. Date & Time — timezone for cache stability
-/// 7. Runtime — host, OS, model
+/// 6. Persona — `[identity.persona]`, already merged with any channel
+///    overrides (see [`crate::config::IdentityConfig::for_channel`])
+/// 7. Date & Time — timezone for cache stability
+/// 8. Runtime — host, OS, model
 ///
 /// When `identity_config` is set to AIEOS format, the bootstrap files section
-/// is replaced with the AIEOS identity data loaded from file or inline JSON.
+/// is replaced with the AIEOS identity data loaded from file or inline JSON;
+/// explicitly configured `[identity.instruction_files]` still apply.
 ///
 /// Daily memory files (`memory/*.md`) are NOT injected — they are accessed
 /// on-demand via `memory_recall` / `memory_search` tools.
@@ -204,50 +447,53 @@ pub fn build_system_prompt(
         "## Workspace\n\nWorking directory: `{}`\n",
         workspace_dir.display()
     );
+    prompt.push_str(&crate::context::detect(workspace_dir).to_prompt());
 
     // ── 5. Bootstrap files (injected into context) ──────────────
     prompt.push_str("## Project Context\n\n");
 
-    // Check if AIEOS identity is configured
-    if let Some(config) = identity_config {
-        if identity::is_aieos_configured(config) {
-            // Load AIEOS identity
-            match identity::load_aieos_identity(config, workspace_dir) {
-                Ok(Some(aieos_identity)) => {
-                    let aieos_prompt = identity::aieos_to_system_prompt(&aieos_identity);
-                    if !aieos_prompt.is_empty() {
-                        prompt.push_str(&aieos_prompt);
-                        prompt.push_str("\n\n");
-                    }
+    let default_identity = crate::config::IdentityConfig::default();
+    let identity_config = identity_config.unwrap_or(&default_identity);
+    let instructions = identity::instruction_files(identity_config, workspace_dir);
+
+    if identity::is_aieos_configured(identity_config) {
+        // Load AIEOS identity
+        match identity::load_aieos_identity(identity_config, workspace_dir) {
+            Ok(Some(aieos_identity)) => {
+                let aieos_prompt = identity::aieos_to_system_prompt(&aieos_identity);
+                if !aieos_prompt.is_empty() {
+                    prompt.push_str(&aieos_prompt);
+                    prompt.push_str("\n\n");
                 }
-                Ok(None) => {
-                    // No AIEOS identity loaded (shouldn't happen if is_aieos_configured returned true)
-                    // Fall back to OpenClaw bootstrap files
-                    load_openclaw_bootstrap_files(&mut prompt, workspace_dir);
-                }
-                Err(e) => {
-                    // Log error but don't fail - fall back to OpenClaw
-                    eprintln!(
-                        "Warning: Failed to load AIEOS identity: {e}. Using OpenClaw format."
-                    );
-                    load_openclaw_bootstrap_files(&mut prompt, workspace_dir);
+                if !identity_config.instruction_files.is_empty() {
+                    inject_instruction_files(&mut prompt, &instructions, false);
                 }
             }
-        } else {
-            // OpenClaw format
-            load_openclaw_bootstrap_files(&mut prompt, workspace_dir);
+            Ok(None) => {
+                // No AIEOS identity loaded (shouldn't happen if is_aieos_configured returned true)
+                // Fall back to OpenClaw bootstrap files
+                load_openclaw_bootstrap_files(&mut prompt, workspace_dir, &instructions);
+            }
+            Err(e) => {
+                // Log error but don't fail - fall back to OpenClaw
+                eprintln!("Warning: Failed to load AIEOS identity: {e}. Using OpenClaw format.");
+                load_openclaw_bootstrap_files(&mut prompt, workspace_dir, &instructions);
+            }
         }
     } else {
-        // No identity config - use OpenClaw format
-        load_openclaw_bootstrap_files(&mut prompt, workspace_dir);
+        // OpenClaw format
+        load_openclaw_bootstrap_files(&mut prompt, workspace_dir, &instructions);
     }
 
-    // ── 6. Date & Time ──────────────────────────────────────────
+    // ── 6. Persona ──────────────────────────────────────────────
+    prompt.push_str(&identity::persona_prompt(&identity_config.persona));
+
+    // ── 7. Date & Time ──────────────────────────────────────────
     let now = chrono::Local::now();
     let tz = now.format("%Z").to_string();
     let _ = writeln!(prompt, "## Current Date & Time\n\nTimezone: {tz}\n");
 
-    // ── 7. Runtime ──────────────────────────────────────────────
+    // ── 8. Runtime ──────────────────────────────────────────────
     let host =
         hostname::get().map_or_else(|_| "unknown".into(), |h| h.to_string_lossy().to_string());
     let _ = writeln!(
@@ -363,6 +609,76 @@ fn classify_health_result(
     }
 }
 
+/// The configured channel called `name`, for sending outside the channel
+/// server (e.g. daemon jobs posting a result). `None` when it is not set up.
+pub fn configured_channel(config: &Config, name: &str) -> Option<Arc<dyn Channel>> {
+    let channels = &config.channels_config;
+    let channel: Arc<dyn Channel> = match name {
+        "telegram" => {
+            let tg = channels.telegram.as_ref()?;
+            Arc::new(
+                TelegramChannel::new(tg.bot_token.clone(), tg.allowed_users.clone())
+                    .with_markdown(config.render.markdown),
+            )
+        }
+        "discord" => {
+            let dc = channels.discord.as_ref()?;
+            Arc::new(DiscordChannel::new(
+                dc.bot_token.clone(),
+                dc.guild_id.clone(),
+                dc.allowed_users.clone(),
+            ))
+        }
+        "slack" => {
+            let sl = channels.slack.as_ref()?;
+            Arc::new(SlackChannel::new(
+                sl.bot_token.clone(),
+                sl.channel_id.clone(),
+                sl.allowed_users.clone(),
+            ))
+        }
+        "imessage" => Arc::new(IMessageChannel::new(
+            channels.imessage.as_ref()?.allowed_contacts.clone(),
+        )),
+        "matrix" => {
+            let mx = channels.matrix.as_ref()?;
+            Arc::new(MatrixChannel::new(
+                mx.homeserver.clone(),
+                mx.access_token.clone(),
+                mx.room_id.clone(),
+                mx.allowed_users.clone(),
+            ))
+        }
+        "whatsapp" => {
+            let wa = channels.whatsapp.as_ref()?;
+            Arc::new(WhatsAppChannel::new(
+                wa.access_token.clone(),
+                wa.phone_number_id.clone(),
+                wa.verify_token.clone(),
+                wa.allowed_numbers.clone(),
+            ))
+        }
+        "email" => Arc::new(EmailChannel::new(channels.email.clone()?)),
+        "irc" => {
+            let irc = channels.irc.as_ref()?;
+            Arc::new(IrcChannel::new(
+                irc.server.clone(),
+                irc.port,
+                irc.nickname.clone(),
+                irc.username.clone(),
+                irc.channels.clone(),
+                irc.allowed_users.clone(),
+                irc.server_password.clone(),
+                irc.nickserv_password.clone(),
+                irc.sasl_password.clone(),
+                irc.verify_tls.unwrap_or(true),
+            ))
+        }
+        _ => return None,
+    };
+    Some(channel)
+}
+
 /// Run health checks for configured channels.
 pub async fn doctor_channels(config: Config) -> Result<()> {
     let mut channels: Vec<(&'static str, Arc<dyn Channel>)> = Vec::new();
@@ -494,13 +810,27 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
 }
 
 /// Start all configured channels and route messages to the agent
-#[allow(clippy::too_many_lines)]
 pub async fn start_channels(config: Config) -> Result<()> {
-    let provider: Arc<dyn Provider> = Arc::from(providers::create_resilient_provider(
+    start_channels_until(config, std::future::pending()).await
+}
+
+/// Like [`start_channels`], but stop listening once `stop` completes.
+/// Messages already received are still answered before this returns.
+#[allow(clippy::too_many_lines)]
+pub async fn start_channels_until(
+    config: Config,
+    stop: impl std::future::Future<Output = ()>,
+) -> Result<()> {
+    let provider: Arc<dyn Provider> = Arc::from(providers::cache::wrap(
+        providers::create_resilient_provider(
+            config.default_provider.as_deref().unwrap_or("openrouter"),
+            config.api_key.as_deref(),
+            &config.reliability,
+            &providers::ProviderSettings::from_config(&config),
+        )?,
+        &config,
         config.default_provider.as_deref().unwrap_or("openrouter"),
-        config.api_key.as_deref(),
-        &config.reliability,
-    )?);
+    ));
 
     // Warm up the provider connection pool (TLS handshake, DNS, HTTP/2 setup)
     // so the first real message doesn't hit a cold-start timeout.
@@ -518,54 +848,85 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &config.workspace_dir,
         config.api_key.as_deref(),
     )?);
+    knowledge::spawn_sync(&config, mem.clone());
 
     // Build system prompt from workspace identity files + skills
     let workspace = config.workspace_dir.clone();
     let skills = crate::skills::load_skills(&workspace);
 
-    // Collect tool descriptions for the prompt
-    let mut tool_descs: Vec<(&str, &str)> = vec![
-        (
-            "shell",
-            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
-        ),
-        (
-            "file_read",
-            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
-        ),
-        (
-            "file_write",
-            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
-        ),
-        (
-            "memory_store",
-            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
-        ),
-        (
-            "memory_recall",
-            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
-        ),
-        (
-            "memory_forget",
-            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
-        ),
-    ];
-
-    if config.browser.enabled {
-        tool_descs.push((
-            "browser_open",
-            "Open approved HTTPS URLs in Brave Browser (allowlist-only, no scraping)",
-        ));
-    }
-
-    let system_prompt = build_system_prompt(
-        &workspace,
-        &model,
-        &tool_descs,
-        &skills,
-        Some(&config.identity),
+    // Each channel gets its own registry, narrowed to its `allowed_tools`;
+    // the loop can only run what is in the registry
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+    let runtime: Arc<dyn runtime::RuntimeAdapter> =
+        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let security = Arc::new(SecurityPolicy::from_config(&config.autonomy, &workspace));
+    let composio_key = if config.composio.enabled {
+        config.composio.api_key.as_deref()
+    } else {
+        None
+    };
+    // Memory tools reach the sender's own pool, knowledge the shared one
+    let tools_for = |channel: &str, user_mem: &Arc<dyn Memory>, memory_config: &MemoryConfig| {
+        let mut tools = tools::all_tools_with_runtime(
+            &security,
+            runtime.clone(),
+            user_mem.clone(),
+            composio_key,
+            &config.browser,
+        );
+        let scoped = Config {
+            memory: memory_config.clone(),
+            ..config.clone()
+        };
+        tools.extend(tools::optional_tools(&scoped, &security));
+        tools::route_to_nodes(&mut tools, &config.nodes, &security);
+        if config.knowledge.enabled && knowledge::supported(mem.as_ref()) {
+            tools.push(Box::new(tools::KbSearchTool::new(mem.clone())) as Box<dyn Tool>);
+        }
+        restrict_tools(&mut tools, config.channels_config.allowed_tools(channel));
+        tools
+    };
+    let mut channel_tools: HashMap<(String, String), Vec<Box<dyn Tool>>> = HashMap::new();
+    // Memory pools by namespace: the shared one, then one per sender on
+    // multi-user channels (`[memory.isolation]`)
+    let shared_namespace = config.memory.namespace.clone().unwrap_or_default();
+    let mut memory_pools: HashMap<String, UserPool> = HashMap::new();
+    memory_pools.insert(
+        shared_namespace.clone(),
+        UserPool {
+            mem: mem.clone(),
+            last_used: Instant::now(),
+        },
     );
 
+    // One prompt per channel, as each may override the persona and narrow
+    // the tools
+    let prompt_for = |channel: &str, tools: &[Box<dyn Tool>]| {
+        let mut tool_descs = loop_::tool_descriptions(&config, tools);
+        tool_descs.retain(|(name, _)| tools.iter().any(|t| t.name() == *name));
+        let tool_names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        let mut skills = skills.clone();
+        crate::skills::package::retain_usable(&mut skills, &tool_names);
+        let mut prompt = build_system_prompt(
+            &workspace,
+            &model,
+            &tool_descs,
+            &skills,
+            Some(&identity::readable_by(
+                config.identity.for_channel(channel),
+                &security,
+            )),
+        );
+        prompt.push_str(&loop_::build_tool_instructions_with(
+            tools,
+            config.tool_schema_for(&model),
+        ));
+        prompt
+    };
+    let mut system_prompts: HashMap<String, String> = HashMap::new();
+    let mut instructions = identity::InstructionWatcher::from_config(&config.identity, &security);
+
     if !skills.is_empty() {
         println!(
             "  🧩 Skills:   {}",
@@ -580,11 +941,47 @@ pub async fn start_channels(config: Config) -> Result<()> {
     // Collect active channels
     let mut channels: Vec<Arc<dyn Channel>> = Vec::new();
 
+    // Channels that speak their replies (`voice_replies = true` + `[tts]`)
+    let synthesizer = Synthesizer::from_app_config(&config);
+    let guardrails = Guardrails::from_config(&config.guardrails, &workspace);
+    // Answers "approve <run>" / "deny <run>" to paused workflows
+    let workflows = if config.workflows.enabled {
+        Some(Arc::new(crate::workflows::WorkflowEngine::with_provider(
+            &config,
+            provider.clone(),
+        )?))
+    } else {
+        None
+    };
+    let mut voice_reply_channels: Vec<&str> = Vec::new();
+    if config
+        .channels_config
+        .telegram
+        .as_ref()
+        .is_some_and(|tg| tg.voice_replies)
+    {
+        voice_reply_channels.push("telegram");
+    }
+    if config
+        .channels_config
+        .discord
+        .as_ref()
+        .is_some_and(|dc| dc.voice_replies)
+    {
+        voice_reply_channels.push("discord");
+    }
+    if synthesizer.is_none() && !voice_reply_channels.is_empty() {
+        tracing::warn!(
+            "voice_replies is set but [tts] is disabled or unconfigured; replying with text"
+        );
+    }
+
     if let Some(ref tg) = config.channels_config.telegram {
-        channels.push(Arc::new(TelegramChannel::new(
-            tg.bot_token.clone(),
-            tg.allowed_users.clone(),
-        )));
+        channels.push(Arc::new(
+            TelegramChannel::new(tg.bot_token.clone(), tg.allowed_users.clone())
+                .with_markdown(config.render.markdown)
+                .with_transcriber(Transcriber::from_app_config(&config).map(Arc::new)),
+        ));
     }
 
     if let Some(ref dc) = config.channels_config.discord {
@@ -664,6 +1061,11 @@ pub async fn start_channels(config: Config) -> Result<()> {
             .collect::<Vec<_>>()
             .join(", ")
     );
+    for ch in &channels {
+        if let Some(allowed) = config.channels_config.allowed_tools(ch.name()) {
+            println!("  🔧 {:<9} tools: {}", ch.name(), allowed.join(", "));
+        }
+    }
     println!();
     println!("  Listening for messages... (Ctrl+C to stop)");
     println!();
@@ -679,6 +1081,8 @@ pub async fn start_channels(config: Config) -> Result<()> {
         .channel_max_backoff_secs
         .max(DEFAULT_CHANNEL_MAX_BACKOFF_SECS);
 
+    crate::daemon::queue::shared().set_limits(&config.queue);
+
     // Single message bus — all channels send messages here
     let (tx, mut rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(100);
 
@@ -695,7 +1099,62 @@ pub async fn start_channels(config: Config) -> Result<()> {
     drop(tx); // Drop our copy so rx closes when all channels stop
 
     // Process incoming messages — call the LLM and reply
-    while let Some(msg) = rx.recv().await {
+    tokio::pin!(stop);
+    let mut stopping = false;
+    // Conversations to summarize once they go quiet, by channel and user
+    let mut conversations: HashMap<String, OpenConversation> = HashMap::new();
+    let mut summaries = JoinSet::new();
+    let idle_after = Duration::from_secs(config.memory.summary.idle_minutes.saturating_mul(60));
+    let mut idle_check = tokio::time::interval(Duration::from_secs(60));
+    loop {
+        let msg = tokio::select! {
+            msg = rx.recv() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            Some(_) = summaries.join_next(), if !summaries.is_empty() => continue,
+            _ = idle_check.tick() => {
+                close_conversations(
+                    &mut conversations,
+                    idle_after,
+                    &provider,
+                    &model,
+                    &mut summaries,
+                );
+                evict_user_pools(
+                    &mut memory_pools,
+                    &mut channel_tools,
+                    &shared_namespace,
+                    USER_POOL_IDLE,
+                    MAX_USER_POOLS,
+                );
+                continue;
+            }
+            () = &mut stop, if !stopping => {
+                // Dropping the listeners closes the bus once the queue drains
+                for h in &handles {
+                    h.abort();
+                }
+                stopping = true;
+                continue;
+            }
+        };
+        crate::health::set_queue_depth("channels", rx.len());
+        let target_channel = channels.iter().find(|ch| ch.name() == msg.channel);
+        let _permit = match crate::daemon::queue::shared()
+            .acquire(&format!("channel:{}", msg.channel))
+            .await
+        {
+            Ok(permit) => permit,
+            Err(busy) => {
+                if let Some(ch) = target_channel {
+                    let _ = ch.send(&format!("⚠️ {busy}"), &msg.sender).await;
+                }
+                continue;
+            }
+        };
+        // Held through autosave and reply so shutdown waits for both
+        let _run = crate::daemon::shutdown::begin_run();
         println!(
             "  💬 [{}] from {}: {}",
             msg.channel,
@@ -703,18 +1162,86 @@ pub async fn start_channels(config: Config) -> Result<()> {
             truncate_with_ellipsis(&msg.content, 80)
         );
 
-        let memory_context = build_memory_context(mem.as_ref(), &msg.content).await;
+        if let Some((engine, (decision, id))) = workflows
+            .as_ref()
+            .zip(crate::workflows::parse_decision(&msg.content))
+        {
+            let reply = match engine.decide(id, decision, Some((&msg.channel, &msg.sender))) {
+                Ok(run) => {
+                    let reply = format!(
+                        "✅ Workflow {} (run {}): noted",
+                        run.workflow,
+                        run.short_id()
+                    );
+                    if run.status == crate::workflows::RunStatus::Running {
+                        let engine = engine.clone();
+                        tokio::spawn(async move { engine.resume(run).await });
+                    }
+                    reply
+                }
+                Err(e) => format!("⚠️ {e:#}"),
+            };
+            if let Some(ch) = target_channel {
+                let _ = ch.send(&reply, &msg.sender).await;
+            }
+            continue;
+        }
+
+        // Pick up edits to AGENTS.md / SOUL.md without a restart
+        if instructions.changed() {
+            println!("  📝 Instruction files changed — reloading system prompt");
+            system_prompts.clear();
+        }
+        // Only the sender's own memories are read and written, unless the
+        // channel shares one pool
+        let memory_config = memory::user_config(&config.memory, &msg.channel, msg.user());
+        let user_mem = match user_memory(&mut memory_pools, &config, &memory_config) {
+            Ok(user_mem) => user_mem,
+            Err(e) => {
+                eprintln!("  ❌ Memory for {} unavailable: {e}", msg.user());
+                if let Some(ch) = target_channel {
+                    let _ = ch
+                        .send("⚠️ Your memory could not be opened.", &msg.sender)
+                        .await;
+                }
+                continue;
+            }
+        };
+        let tools = channel_tools
+            .entry((
+                msg.channel.clone(),
+                memory_config.namespace.clone().unwrap_or_default(),
+            ))
+            .or_insert_with(|| tools_for(&msg.channel, &user_mem, &memory_config));
+        let system_prompt = system_prompts
+            .entry(msg.channel.clone())
+            .or_insert_with(|| prompt_for(&msg.channel, tools));
+
+        let mut memory_context = build_memory_context(
+            user_mem.as_ref(),
+            mem.as_ref(),
+            &msg.content,
+            &memory_config.recall,
+            knowledge::context_limit(&config.knowledge),
+        )
+        .await;
+        memory_context.push_str(&memory::graph::build_context(
+            &memory_config,
+            &config.workspace_dir,
+            &msg.content,
+        ));
 
         // Auto-save to memory
-        if config.memory.auto_save {
+        if memory_config.saves_raw_turns() {
             let autosave_key = conversation_memory_key(&msg);
-            let _ = mem
-                .store(
-                    &autosave_key,
-                    &msg.content,
-                    crate::memory::MemoryCategory::Conversation,
-                )
-                .await;
+            let _ = memory::dedup::store(
+                user_mem.as_ref(),
+                &autosave_key,
+                &msg.content,
+                crate::memory::MemoryCategory::Conversation,
+                &memory_config.dedup,
+            )
+            .await;
         }
 
         let enriched_message = if memory_context.is_empty() {
@@ -723,8 +1250,6 @@ pub async fn start_channels(config: Config) -> Result<()> {
             format!("{memory_context}{}", msg.content)
         };
 
-        let target_channel = channels.iter().find(|ch| ch.name() == msg.channel);
-
         // Show typing indicator while processing
         if let Some(ch) = target_channel {
             if let Err(e) = ch.start_typing(&msg.sender).await {
@@ -736,9 +1261,37 @@ pub async fn start_channels(config: Config) -> Result<()> {
         println!("  ⏳ Processing message...");
         let started_at = Instant::now();
 
+        let mut history = vec![
+            ChatMessage::system(system_prompt.as_str()),
+            ChatMessage::user(enriched_message),
+        ];
+        // Jobs queued during the turn report back to this chat
+        let origin = crate::jobs::JobOrigin {
+            channel: msg.channel.clone(),
+            recipient: msg.sender.clone(),
+        };
         let llm_result = tokio::time::timeout(
             Duration::from_secs(CHANNEL_MESSAGE_TIMEOUT_SECS),
-            provider.chat_with_system(Some(&system_prompt), &enriched_message, &model, temperature),
+            crate::jobs::scope(
+                Some(origin),
+                crate::observability::trace::session(
+                    format!("{}:{}", msg.channel, msg.sender),
+                    crate::agent::settings::scope(
+                        &config,
+                        crate::hooks::scope(
+                            &config,
+                            loop_::agent_turn(
+                                provider.as_ref(),
+                                &mut history,
+                                tools,
+                                observer.as_ref(),
+                                &model,
+                                temperature,
+                            ),
+                        ),
+                    ),
+                ),
+            ),
         )
         .await;
 
@@ -751,16 +1304,64 @@ pub async fn start_channels(config: Config) -> Result<()> {
 
         match llm_result {
             Ok(Ok(response)) => {
+                let language = crate::agent::language::configured(&config.identity, &msg.channel);
+                let response = crate::agent::language::enforce(
+                    provider.as_ref(),
+                    &mut history,
+                    response,
+                    &model,
+                    language.as_deref(),
+                )
+                .await;
+                let response = guardrails
+                    .apply(
+                        provider.as_ref(),
+                        &mut history,
+                        response,
+                        &model,
+                        &msg.channel,
+                    )
+                    .await;
                 println!(
                     "  🤖 Reply ({}ms): {}",
                     started_at.elapsed().as_millis(),
                     truncate_with_ellipsis(&response, 80)
                 );
                 if let Some(ch) = target_channel {
-                    if let Err(e) = ch.send(&response, &msg.sender).await {
+                    let voice = synthesizer
+                        .as_ref()
+                        .filter(|_| voice_reply_channels.contains(&msg.channel.as_str()));
+                    if let Err(e) =
+                        deliver_reply(ch.as_ref(), &response, &msg.sender, &workspace, voice).await
+                    {
                         eprintln!("  ❌ Failed to reply on {}: {e}", ch.name());
                     }
                 }
+                memory::extract::after_turn(
+                    provider.as_ref(),
+                    user_mem.as_ref(),
+                    &memory_config,
+                    &model,
+                    &msg.content,
+                    &response,
+                )
+                .await;
+                if memory_config.summary.enabled {
+                    let conversation = conversations
+                        .entry(format!("{}:{}", msg.channel, msg.user()))
+                        .or_insert_with(|| OpenConversation {
+                            mem: user_mem.clone(),
+                            memory_config: memory_config.clone(),
+                            messages: Vec::new(),
+                            last_active: Instant::now(),
+                        });
+                    memory::summary::remember_exchange(
+                        &mut conversation.messages,
+                        &msg.content,
+                        &response,
+                    );
+                    conversation.last_active = Instant::now();
+                }
             }
             Ok(Err(e)) => {
                 eprintln!(
@@ -793,6 +1394,16 @@ pub async fn start_channels(config: Config) -> Result<()> {
         }
     }
 
+    // Conversations still open end with the channels
+    close_conversations(
+        &mut conversations,
+        Duration::ZERO,
+        &provider,
+        &model,
+        &mut summaries,
+    );
+    while summaries.join_next().await.is_some() {}
+
     // Wait for all channel tasks
     for h in handles {
         let _ = h.await;
@@ -904,6 +1515,45 @@ mod tests {
         assert!(prompt.contains("[File not found: SOUL.md]"));
         assert!(prompt.contains("[File not found: AGENTS.md]"));
         assert!(prompt.contains("[File not found: IDENTITY.md]"));
+        assert!(!prompt.contains(".zeroclaw/instructions.md"));
+    }
+
+    #[test]
+    fn prompt_puts_workspace_instructions_last() {
+        let ws = make_workspace();
+        std::fs::create_dir_all(ws.path().join(".zeroclaw")).unwrap();
+        std::fs::write(
+            ws.path().join(".zeroclaw/instructions.md"),
+            "Use 2-space indents.",
+        )
+        .unwrap();
+
+        let prompt = build_system_prompt(ws.path(), "model", &[], &[], None);
+        let agents = prompt.find("### AGENTS.md").unwrap();
+        let workspace = prompt.find("### .zeroclaw/instructions.md").unwrap();
+        assert!(agents < workspace);
+        assert!(prompt[workspace..].contains("Takes precedence"));
+        assert!(prompt[workspace..].contains("Use 2-space indents."));
+    }
+
+    #[test]
+    fn prompt_uses_configured_instruction_files() {
+        use crate::config::IdentityConfig;
+
+        let ws = make_workspace();
+        std::fs::create_dir_all(ws.path().join("docs")).unwrap();
+        std::fs::write(ws.path().join("docs/STYLE.md"), "Prefer tables.").unwrap();
+        let mut config = IdentityConfig::default();
+        config.instruction_files.insert("docs/STYLE.md".into(), 0);
+        config.instruction_files.insert("RULES.md".into(), 0);
+
+        let prompt = build_system_prompt(ws.path(), "model", &[], &[], Some(&config));
+        assert!(prompt.contains("### docs/STYLE.md\n\nPrefer tables."));
+        assert!(prompt.contains("[File not found: RULES.md]"));
+        // The configured list replaces the AGENTS.md / SOUL.md defaults
+        assert!(!prompt.contains("### AGENTS.md"));
+        assert!(!prompt.contains("### SOUL.md"));
+        assert!(prompt.contains("### TOOLS.md"));
     }
 
     #[test]
@@ -972,6 +1622,8 @@ mod tests {
             tags: vec![],
             tools: vec![],
             prompts: vec!["Long prompt content that should NOT appear in system prompt".into()],
+            requires: crate::skills::SkillRequirements::default(),
+            sandbox: crate::skills::SkillSandbox::default(),
             location: None,
         }];
 
@@ -1037,6 +1689,7 @@ mod tests {
             sender: "U123".into(),
             content: "hello".into(),
             channel: "slack".into(),
+            author: None,
             timestamp: 1,
         };
 
@@ -1050,6 +1703,7 @@ mod tests {
             sender: "U123".into(),
             content: "first".into(),
             channel: "slack".into(),
+            author: None,
             timestamp: 1,
         };
         let msg2 = traits::ChannelMessage {
@@ -1057,6 +1711,7 @@ mod tests {
             sender: "U123".into(),
             content: "second".into(),
             channel: "slack".into(),
+            author: None,
             timestamp: 2,
         };
 
@@ -1076,6 +1731,7 @@ mod tests {
             sender: "U123".into(),
             content: "I'm Paul".into(),
             channel: "slack".into(),
+            author: None,
             timestamp: 1,
         };
         let msg2 = traits::ChannelMessage {
@@ -1083,6 +1739,7 @@ mod tests {
             sender: "U123".into(),
             content: "I'm 45".into(),
             channel: "slack".into(),
+            author: None,
             timestamp: 2,
         };
 
@@ -1115,11 +1772,35 @@ mod tests {
             .await
             .unwrap();
 
-        let context = build_memory_context(&mem, "age").await;
+        let context = build_memory_context(&mem, &mem, "age", &MemoryRecallConfig::default(), 0).await;
         assert!(context.contains("[Memory context]"));
         assert!(context.contains("Age is 45"));
     }
 
+    #[tokio::test]
+    async fn build_memory_context_separates_knowledge_passages() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store("age_fact", "Age is 45", MemoryCategory::Conversation)
+            .await
+            .unwrap();
+        mem.store(
+            "kb:docs/faq.md#0",
+            "[docs/faq.md]\nMinimum age is 18",
+            knowledge::category(),
+        )
+        .await
+        .unwrap();
+
+        let context = build_memory_context(&mem, &mem, "age", &MemoryRecallConfig::default(), 0).await;
+        assert!(!context.contains("Minimum age"));
+
+        let context = build_memory_context(&mem, &mem, "age", &MemoryRecallConfig::default(), 2).await;
+        let (memory, kb) = context.split_once("[Knowledge context]").unwrap();
+        assert!(memory.contains("Age is 45") && !memory.contains("Minimum age"));
+        assert!(kb.contains("Minimum age is 18"));
+    }
+
     // ── AIEOS Identity Tests (Issue #168) ─────────────────────────
 
     #[test]
@@ -1153,6 +1834,7 @@ mod tests {
             format: "aieos".into(),
             aieos_path: Some("aieos_identity.json".into()),
             aieos_inline: None,
+            ..IdentityConfig::default()
         };
 
         let prompt = build_system_prompt(tmp.path(), "model", &[], &[], Some(&config));
@@ -1187,6 +1869,7 @@ mod tests {
             format: "aieos".into(),
             aieos_path: None,
             aieos_inline: Some(r#"{"identity":{"names":{"first":"Claw"}}}"#.into()),
+            ..IdentityConfig::default()
         };
 
         let prompt = build_system_prompt(
@@ -1209,6 +1892,7 @@ mod tests {
             format: "aieos".into(),
             aieos_path: Some("nonexistent.json".into()),
             aieos_inline: None,
+            ..IdentityConfig::default()
         };
 
         let ws = make_workspace();
@@ -1228,6 +1912,7 @@ mod tests {
             format: "aieos".into(),
             aieos_path: None,
             aieos_inline: None,
+            ..IdentityConfig::default()
         };
 
         let ws = make_workspace();
@@ -1246,6 +1931,7 @@ mod tests {
             format: "openclaw".into(),
             aieos_path: Some("identity.json".into()),
             aieos_inline: None,
+            ..IdentityConfig::default()
         };
 
         let ws = make_workspace();
@@ -1268,6 +1954,75 @@ mod tests {
         assert!(prompt.contains("Be helpful"));
     }
 
+    #[test]
+    fn persona_follows_channel_overrides() {
+        use crate::config::{IdentityConfig, PersonaConfig};
+
+        let ws = make_workspace();
+        let mut config = IdentityConfig::default();
+        config.persona.name = Some("Nova".into());
+        config.persona.verbosity = Some("thorough".into());
+        config.channels.insert(
+            "telegram".into(),
+            PersonaConfig {
+                verbosity: Some("one or two sentences".into()),
+                ..Default::default()
+            },
+        );
+
+        let ide = build_system_prompt(ws.path(), "model", &[], &[], Some(&config));
+        assert!(ide.contains("## Persona"));
+        assert!(ide.contains("- **Verbosity:** thorough"));
+
+        let telegram = config.for_channel("telegram");
+        let prompt = build_system_prompt(ws.path(), "model", &[], &[], Some(&telegram));
+        assert!(prompt.contains("- **Name:** Nova"));
+        assert!(prompt.contains("- **Verbosity:** one or two sentences"));
+        assert!(!prompt.contains("thorough"));
+
+        let plain = build_system_prompt(ws.path(), "model", &[], &[], None);
+        assert!(!plain.contains("## Persona"));
+    }
+
+    #[test]
+    fn channel_allowed_tools_narrow_the_registry() {
+        use crate::security::SecurityPolicy;
+        use crate::tools::{CalculatorTool, ImageInfoTool};
+
+        let config: crate::config::ChannelsConfig = toml::from_str(
+            r#"
+cli = true
+[telegram]
+bot_token = "t"
+allowed_users = ["*"]
+allowed_tools = ["calculator", "web_serach"]
+
+[discord]
+bot_token = "d"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.allowed_tools("discord"), None);
+        assert_eq!(config.allowed_tools("cli"), None);
+
+        let security = Arc::new(SecurityPolicy::default());
+        let all = || -> Vec<Box<dyn Tool>> {
+            vec![
+                Box::new(CalculatorTool::new()),
+                Box::new(ImageInfoTool::new(security.clone())),
+            ]
+        };
+
+        let mut telegram = all();
+        restrict_tools(&mut telegram, config.allowed_tools("telegram"));
+        let names: Vec<&str> = telegram.iter().map(|t| t.name()).collect();
+        assert_eq!(names, ["calculator"]);
+
+        let mut discord = all();
+        restrict_tools(&mut discord, config.allowed_tools("discord"));
+        assert_eq!(discord.len(), 2);
+    }
+
     #[test]
     fn classify_health_ok_true() {
         let state = classify_health_result(&Ok(true));
@@ -1341,4 +2096,138 @@ mod tests {
             .contains("listen boom"));
         assert!(calls.load(Ordering::SeqCst) >= 1);
     }
+
+    #[derive(Default)]
+    struct RecordingChannel {
+        texts: std::sync::Mutex<Vec<String>>,
+        voice_notes: std::sync::Mutex<Vec<Vec<u8>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, message: &str, _recipient: &str) -> anyhow::Result<()> {
+            self.texts.lock().unwrap().push(message.to_string());
+            Ok(())
+        }
+
+        async fn send_voice_note(
+            &self,
+            audio: Vec<u8>,
+            _file_name: &str,
+            _recipient: &str,
+        ) -> anyhow::Result<()> {
+            self.voice_notes.lock().unwrap().push(audio);
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn voice_markers_are_split_from_text() {
+        let (text, clips) =
+            extract_voice_markers("Here you go.\n[VOICE:audio/a.ogg]\nAnd [VOICE: audio/b.ogg ]");
+        assert_eq!(text, "Here you go.\n\nAnd");
+        assert_eq!(clips, vec!["audio/a.ogg", "audio/b.ogg"]);
+
+        let (text, clips) = extract_voice_markers("no markers, [VOICE:unterminated");
+        assert_eq!(text, "no markers, [VOICE:unterminated");
+        assert!(clips.is_empty());
+    }
+
+    #[tokio::test]
+    async fn deliver_reply_sends_marked_clips_as_voice_notes() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("audio")).unwrap();
+        std::fs::write(tmp.path().join("audio/a.ogg"), b"OggS").unwrap();
+        let ch = RecordingChannel::default();
+
+        deliver_reply(&ch, "Listen:\n[VOICE:audio/a.ogg]", "u", tmp.path(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(*ch.voice_notes.lock().unwrap(), vec![b"OggS".to_vec()]);
+        assert_eq!(*ch.texts.lock().unwrap(), vec!["Listen:".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn deliver_reply_refuses_clips_outside_workspace() {
+        let tmp = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        std::fs::write(outside.path().join("secret.ogg"), b"secret").unwrap();
+        let marker = format!("[VOICE:{}]", outside.path().join("secret.ogg").display());
+        let ch = RecordingChannel::default();
+
+        deliver_reply(&ch, &marker, "u", tmp.path(), None)
+            .await
+            .unwrap();
+
+        assert!(ch.voice_notes.lock().unwrap().is_empty());
+        assert!(ch.texts.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn deliver_reply_falls_back_to_text_when_too_long_to_speak() {
+        let tmp = TempDir::new().unwrap();
+        let tts = crate::config::TtsConfig {
+            enabled: true,
+            api_key: Some("sk-test".into()),
+            max_chars: 5,
+            ..crate::config::TtsConfig::default()
+        };
+        let synth = Synthesizer::from_config(&tts, None).unwrap();
+        let ch = RecordingChannel::default();
+
+        deliver_reply(
+            &ch,
+            "a reply longer than five",
+            "u",
+            tmp.path(),
+            Some(&synth),
+        )
+        .await
+        .unwrap();
+
+        assert!(ch.voice_notes.lock().unwrap().is_empty());
+        assert_eq!(
+            *ch.texts.lock().unwrap(),
+            vec!["a reply longer than five".to_string()]
+        );
+    }
+
+    #[test]
+    fn idle_and_least_recent_user_pools_are_evicted_with_their_tools() {
+        let tmp = TempDir::new().unwrap();
+        let mem: Arc<dyn Memory> = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+        let now = Instant::now();
+        let pool = |age: u64| UserPool {
+            mem: mem.clone(),
+            last_used: now.checked_sub(Duration::from_secs(age)).unwrap_or(now),
+        };
+        let mut pools = HashMap::from([
+            (String::new(), pool(7200)),
+            ("user.telegram.idle".to_string(), pool(3600)),
+            ("user.telegram.old".to_string(), pool(60)),
+            ("user.telegram.new".to_string(), pool(1)),
+        ]);
+        let mut tools: HashMap<(String, String), ()> = pools
+            .keys()
+            .map(|namespace| (("telegram".to_string(), namespace.clone()), ()))
+            .collect();
+
+        evict_user_pools(&mut pools, &mut tools, "", Duration::from_secs(1800), 2);
+        let mut kept: Vec<&str> = pools.keys().map(String::as_str).collect();
+        kept.sort_unstable();
+        assert_eq!(kept, ["", "user.telegram.new"]);
+        assert_eq!(tools.len(), 2);
+    }
 }
@@ -164,6 +164,7 @@ impl Channel for SlackChannel {
                         sender: channel_id.clone(),
                         content: text.to_string(),
                         channel: "slack".to_string(),
+                        author: Some(user.to_string()),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
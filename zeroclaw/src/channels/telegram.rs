@@ -1,7 +1,9 @@
 use super::traits::{Channel, ChannelMessage};
+use crate::transcription::Transcriber;
 use async_trait::async_trait;
 use reqwest::multipart::{Form, Part};
 use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Telegram channel — long-polls the Bot API for updates
@@ -9,6 +11,13 @@ pub struct TelegramChannel {
     bot_token: String,
     allowed_users: Vec<String>,
     client: reqwest::Client,
+    transcriber: Option<Arc<Transcriber>>,
+    markdown: bool,
+}
+
+/// Voice notes arrive as `voice`; uploaded audio files as `audio`.
+fn voice_attachment(message: &serde_json::Value) -> Option<&serde_json::Value> {
+    message.get("voice").or_else(|| message.get("audio"))
 }
 
 /// Find the best split point for a message chunk
@@ -40,13 +49,87 @@ impl TelegramChannel {
             bot_token,
             allowed_users,
             client: reqwest::Client::new(),
+            transcriber: None,
+            markdown: true,
         }
     }
 
+    /// Convert replies to Telegram HTML (`[render] markdown`). When off,
+    /// replies are sent as written with Telegram's own Markdown parse mode.
+    pub fn with_markdown(mut self, markdown: bool) -> Self {
+        self.markdown = markdown;
+        self
+    }
+
+    /// Transcribe incoming voice/audio messages so they reach the agent as text.
+    pub fn with_transcriber(mut self, transcriber: Option<Arc<Transcriber>>) -> Self {
+        self.transcriber = transcriber;
+        self
+    }
+
     fn api_url(&self, method: &str) -> String {
         format!("https://api.telegram.org/bot{}/{method}", self.bot_token)
     }
 
+    /// Download a file previously uploaded to Telegram by its `file_id`.
+    async fn download_file(&self, file_id: &str) -> anyhow::Result<(Vec<u8>, String)> {
+        let resp: serde_json::Value = self
+            .client
+            .post(self.api_url("getFile"))
+            .json(&serde_json::json!({ "file_id": file_id }))
+            .send()
+            .await?
+            .json()
+            .await?;
+        let file_path = resp
+            .get("result")
+            .and_then(|r| r.get("file_path"))
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Telegram getFile returned no file_path"))?;
+
+        let url = format!(
+            "https://api.telegram.org/file/bot{}/{file_path}",
+            self.bot_token
+        );
+        let bytes = self
+            .client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let file_name = file_path.rsplit('/').next().unwrap_or("voice.ogg");
+        Ok((bytes.to_vec(), file_name.to_string()))
+    }
+
+    /// Turn a voice or audio message into text. Returns `None` when the message
+    /// carries no audio or transcription is not configured; failures are
+    /// reported as a short notice so the user knows the message was seen.
+    async fn transcribe_voice(&self, message: &serde_json::Value) -> Option<String> {
+        let transcriber = self.transcriber.as_ref()?;
+        let audio = voice_attachment(message)?;
+
+        let file_id = audio.get("file_id").and_then(serde_json::Value::as_str)?;
+        if let Some(duration) = audio.get("duration").and_then(serde_json::Value::as_u64) {
+            if let Err(e) = transcriber.check_duration(duration) {
+                return Some(format!("[Voice message could not be transcribed: {e}]"));
+            }
+        }
+
+        let result = match self.download_file(file_id).await {
+            Ok((bytes, file_name)) => transcriber.transcribe_bytes(bytes, &file_name, None).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(text) => Some(format!("[Voice message] {text}")),
+            Err(e) => {
+                tracing::warn!("Telegram voice transcription failed: {e}");
+                Some(format!("[Voice message could not be transcribed: {e}]"))
+            }
+        }
+    }
+
     fn is_user_allowed(&self, username: &str) -> bool {
         self.allowed_users.iter().any(|u| u == "*" || u == username)
     }
@@ -321,6 +404,42 @@ impl TelegramChannel {
         Ok(())
     }
 
+    /// Send in-memory Ogg/Opus audio as a voice message
+    pub async fn send_voice_bytes(
+        &self,
+        chat_id: &str,
+        file_bytes: Vec<u8>,
+        file_name: &str,
+        caption: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let part = Part::bytes(file_bytes)
+            .file_name(file_name.to_string())
+            .mime_str("audio/ogg")?;
+
+        let mut form = Form::new()
+            .text("chat_id", chat_id.to_string())
+            .part("voice", part);
+
+        if let Some(cap) = caption {
+            form = form.text("caption", cap.to_string());
+        }
+
+        let resp = self
+            .client
+            .post(self.api_url("sendVoice"))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Telegram sendVoice failed: {err}");
+        }
+
+        tracing::info!("Telegram voice sent to {chat_id}: {file_name}");
+        Ok(())
+    }
+
     /// Send a file by URL (Telegram will download it)
     pub async fn send_document_by_url(
         &self,
@@ -387,10 +506,15 @@ impl TelegramChannel {
 
     /// Send a single message chunk (must be under Telegram's limit)
     async fn send_single_message(&self, message: &str, chat_id: &str) -> anyhow::Result<()> {
+        let (text, parse_mode) = if self.markdown {
+            (crate::render::telegram_html(message), "HTML")
+        } else {
+            (message.to_string(), "Markdown")
+        };
         let markdown_body = serde_json::json!({
             "chat_id": chat_id,
-            "text": message,
-            "parse_mode": "Markdown"
+            "text": text,
+            "parse_mode": parse_mode
         });
 
         let markdown_resp = self
@@ -408,7 +532,7 @@ impl TelegramChannel {
         let markdown_err = markdown_resp.text().await.unwrap_or_default();
         tracing::warn!(
             status = ?markdown_status,
-            "Telegram sendMessage with Markdown failed; retrying without parse_mode"
+            "Telegram sendMessage with {parse_mode} failed; retrying without parse_mode"
         );
 
         // Retry without parse_mode as a compatibility fallback.
@@ -509,6 +633,16 @@ impl Channel for TelegramChannel {
         Ok(())
     }
 
+    async fn send_voice_note(
+        &self,
+        audio: Vec<u8>,
+        file_name: &str,
+        chat_id: &str,
+    ) -> anyhow::Result<()> {
+        self.send_voice_bytes(chat_id, audio, file_name, None).await
+    }
+
+    #[allow(clippy::too_many_lines)]
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let mut offset: i64 = 0;
 
@@ -551,9 +685,12 @@ impl Channel for TelegramChannel {
                         continue;
                     };
 
-                    let Some(text) = message.get("text").and_then(serde_json::Value::as_str) else {
+                    let text = message.get("text").and_then(serde_json::Value::as_str);
+                    if text.is_none()
+                        && (self.transcriber.is_none() || voice_attachment(message).is_none())
+                    {
                         continue;
-                    };
+                    }
 
                     let username_opt = message
                         .get("from")
@@ -600,11 +737,20 @@ Allowlist Telegram @username or numeric user ID, then run `zeroclaw onboard --ch
                         .send()
                         .await; // Ignore errors for typing indicator
 
+                    let content = match text {
+                        Some(text) => text.to_string(),
+                        None => match self.transcribe_voice(message).await {
+                            Some(content) => content,
+                            None => continue,
+                        },
+                    };
+
                     let msg = ChannelMessage {
                         id: Uuid::new_v4().to_string(),
                         sender: chat_id,
-                        content: text.to_string(),
+                        content,
                         channel: "telegram".to_string(),
+                        author: user_id_str,
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
@@ -1024,4 +1170,38 @@ mod tests {
         assert_eq!(chunks[0].len(), 50);
         assert_eq!(chunks[1].len(), 50);
     }
+
+    #[test]
+    fn telegram_voice_attachment_detection() {
+        let voice = serde_json::json!({"voice": {"file_id": "v1", "duration": 4}});
+        let audio = serde_json::json!({"audio": {"file_id": "a1"}});
+        let text = serde_json::json!({"text": "hi"});
+        assert_eq!(voice_attachment(&voice).unwrap()["file_id"], "v1");
+        assert_eq!(voice_attachment(&audio).unwrap()["file_id"], "a1");
+        assert!(voice_attachment(&text).is_none());
+    }
+
+    #[tokio::test]
+    async fn telegram_voice_ignored_without_transcriber() {
+        let ch = TelegramChannel::new("fake-token".into(), vec!["*".into()]);
+        let msg = serde_json::json!({"voice": {"file_id": "v1", "duration": 4}});
+        assert!(ch.transcribe_voice(&msg).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn telegram_voice_over_duration_limit_is_reported() {
+        let cfg = crate::config::TranscriptionConfig {
+            enabled: true,
+            api_key: Some("sk-test".into()),
+            max_duration_secs: 30,
+            ..crate::config::TranscriptionConfig::default()
+        };
+        let transcriber = Transcriber::from_config(&cfg, None).map(Arc::new);
+        let ch = TelegramChannel::new("fake-token".into(), vec!["*".into()])
+            .with_transcriber(transcriber);
+        let msg = serde_json::json!({"voice": {"file_id": "v1", "duration": 31}});
+        let content = ch.transcribe_voice(&msg).await.unwrap();
+        assert!(content.contains("could not be transcribed"));
+        assert!(content.contains("31s"));
+    }
 }
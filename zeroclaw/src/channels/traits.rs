@@ -8,6 +8,16 @@ pub struct ChannelMessage {
     pub content: String,
     pub channel: String,
     pub timestamp: u64,
+    /// Who wrote it, when that is not `sender` (where replies go), e.g. one
+    /// member of a group chat
+    pub author: Option<String>,
+}
+
+impl ChannelMessage {
+    /// The user the message came from: its `author`, else its `sender`.
+    pub fn user(&self) -> &str {
+        self.author.as_deref().unwrap_or(&self.sender)
+    }
 }
 
 /// Core channel trait — implement for any messaging platform
@@ -22,6 +32,17 @@ pub trait Channel: Send + Sync {
     /// Start listening for incoming messages (long-running)
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()>;
 
+    /// Deliver an Ogg/Opus clip as a voice note. Channels without voice support
+    /// return an error so callers can fall back to text.
+    async fn send_voice_note(
+        &self,
+        _audio: Vec<u8>,
+        _file_name: &str,
+        _recipient: &str,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support voice messages", self.name())
+    }
+
     /// Check if channel is healthy
     async fn health_check(&self) -> bool {
         true
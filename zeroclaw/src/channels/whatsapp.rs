@@ -122,6 +122,7 @@ impl WhatsAppChannel {
                         sender: normalized_from,
                         content,
                         channel: "whatsapp".to_string(),
+                        author: None,
                         timestamp,
                     });
                 }
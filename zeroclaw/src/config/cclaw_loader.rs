@@ -120,6 +120,8 @@ pub fn load_cclaw_config() -> Result<Option<Config>> {
             config.channels_config.telegram = Some(TelegramConfig {
                 bot_token: telegram.bot_token,
                 allowed_users: telegram.allowed_users,
+                voice_replies: false,
+                allowed_tools: None,
             });
         }
     }
@@ -0,0 +1,310 @@
+//! Config files merged in layers.
+//!
+//! Shared defaults in `/etc/zeroclaw/config.toml`, the user's
+//! `~/.zeroclaw/config.toml` and a workspace's `.zeroclaw.toml` are read in
+//! that order. Tables merge key by key; any other value (arrays included) in
+//! a later file replaces the earlier one. Missing files are skipped.
+//!
+//! In every string value, `${VAR}` is replaced by the environment variable
+//! and `${VAR:-default}` falls back to `default` when it is unset or empty.
+//! `$${` keeps a literal `${`. This lets a shared file refer to keys that
+//! each user keeps in their own environment.
+//!
+//! The workspace file is untrusted: the agent's own file tools, or any
+//! cloned repository, can write it. It may only set [`WORKSPACE_KEYS`];
+//! other keys are dropped with a warning, and its strings are not
+//! interpolated, so it can neither widen autonomy, add hooks, plugins or
+//! providers, nor read the environment. File paths it sets must stay inside
+//! the workspace, so it cannot pull host files into the prompt either.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Shared defaults, read first.
+pub const SYSTEM_CONFIG: &str = "/etc/zeroclaw/config.toml";
+
+/// Per-workspace overrides, read last from the workspace directory.
+pub const WORKSPACE_CONFIG: &str = ".zeroclaw.toml";
+
+/// Keys the workspace file may set; `table.key` allows one key of a table.
+/// Everything here only shapes answers; nothing widens what the agent may
+/// run, read or reach.
+pub const WORKSPACE_KEYS: &[&str] = &[
+    "default_model",
+    "default_temperature",
+    "identity.format",
+    "identity.aieos_path",
+    "identity.aieos_inline",
+    "identity.instruction_files",
+    "identity.instruction_max_tokens",
+    "identity.persona",
+    "identity.channels",
+    "render",
+    "tool_output",
+];
+
+/// Keys in [`WORKSPACE_KEYS`] holding file paths (as the value, or as the
+/// keys of a table). The workspace file may only name workspace-relative
+/// paths that do not climb out with `..`.
+const WORKSPACE_PATH_KEYS: &[&str] = &["identity.aieos_path", "identity.instruction_files"];
+
+/// The trusted layers for a user config at `user_config`, in merge order.
+/// The workspace file is read separately by [`load_workspace`].
+pub fn paths(user_config: &Path) -> Vec<PathBuf> {
+    vec![PathBuf::from(SYSTEM_CONFIG), user_config.to_path_buf()]
+}
+
+/// Read and merge the files in `paths`, or `None` when none exists.
+pub fn load(paths: &[PathBuf]) -> Result<Option<toml::Table>> {
+    let mut merged: Option<toml::Table> = None;
+    for path in paths {
+        if !path.exists() {
+            continue;
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let mut layer: toml::Table = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+        for (_, value) in &mut layer {
+            interpolate(value, &|name| std::env::var(name).ok())
+                .with_context(|| format!("In config file {}", path.display()))?;
+        }
+        match &mut merged {
+            Some(base) => merge(base, layer),
+            None => merged = Some(layer),
+        }
+    }
+    Ok(merged)
+}
+
+/// Read `<workspace_dir>/.zeroclaw.toml`, keeping only [`WORKSPACE_KEYS`]
+/// and leaving `${...}` as written. `None` when the file does not exist.
+pub fn load_workspace(workspace_dir: &Path) -> Result<Option<toml::Table>> {
+    let path = workspace_dir.join(WORKSPACE_CONFIG);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let layer: toml::Table = toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+    Ok(Some(restrict_workspace(layer, &path)))
+}
+
+fn restrict_workspace(layer: toml::Table, path: &Path) -> toml::Table {
+    restrict(layer, "", path)
+}
+
+/// The entries of `table` (at `prefix`) that [`WORKSPACE_KEYS`] allows.
+fn restrict(table: toml::Table, prefix: &str, path: &Path) -> toml::Table {
+    let mut kept = toml::Table::new();
+    for (key, value) in table {
+        let name = format!("{prefix}{key}");
+        let nested = format!("{name}.");
+        if WORKSPACE_KEYS.contains(&name.as_str()) {
+            if WORKSPACE_PATH_KEYS.contains(&name.as_str()) && !stays_in_workspace(&value) {
+                tracing::warn!(
+                    "Ignoring `{name}` in {}: the workspace config may only name files inside the workspace",
+                    path.display()
+                );
+                continue;
+            }
+            kept.insert(key, value);
+            continue;
+        }
+        match value {
+            toml::Value::Table(inner) if WORKSPACE_KEYS.iter().any(|k| k.starts_with(&nested)) => {
+                kept.insert(key, toml::Value::Table(restrict(inner, &nested, path)));
+            }
+            _ => tracing::warn!(
+                "Ignoring `{name}` in {}: the workspace config may only set {}",
+                path.display(),
+                WORKSPACE_KEYS.join(", ")
+            ),
+        }
+    }
+    kept
+}
+
+/// Whether every path in `value` (a string, or a table's keys) is relative
+/// and stays under the directory it is resolved against.
+fn stays_in_workspace(value: &toml::Value) -> bool {
+    fn relative(path: &str) -> bool {
+        let path = Path::new(path);
+        !path.starts_with("~")
+            && path
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir))
+    }
+    match value {
+        toml::Value::String(path) => relative(path),
+        toml::Value::Table(table) => table.keys().all(|path| relative(path)),
+        _ => true,
+    }
+}
+
+/// Merge `layer` into `base`, `layer` winning.
+pub fn merge(base: &mut toml::Table, layer: toml::Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(layer)) => merge(base, layer),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn interpolate(value: &mut toml::Value, lookup: &dyn Fn(&str) -> Option<String>) -> Result<()> {
+    match value {
+        toml::Value::String(s) => *s = interpolate_str(s, lookup)?,
+        toml::Value::Array(items) => {
+            for item in items {
+                interpolate(item, lookup)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, item) in table {
+                interpolate(item, lookup)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn interpolate_str(s: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        if let Some(tail) = after.strip_prefix("${") {
+            out.push_str("${");
+            rest = tail;
+            continue;
+        }
+        let Some(body) = after.strip_prefix('{') else {
+            out.push('$');
+            rest = after;
+            continue;
+        };
+        let Some(end) = body.find('}') else {
+            anyhow::bail!("unclosed \"${{\" in \"{s}\"");
+        };
+        let (name, default) = match body[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&body[..end], None),
+        };
+        if name.is_empty() {
+            anyhow::bail!("empty variable name in \"{s}\"");
+        }
+        match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => out.push_str(default),
+            (Some(value), _) => out.push_str(&value),
+            (None, Some(default)) => out.push_str(default),
+            (None, None) => anyhow::bail!(
+                "environment variable {name} is not set (write ${{{name}:-default}} for a fallback)"
+            ),
+        }
+        rest = &body[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "TEAM_KEY" => Some("sk-team".into()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn interpolation_handles_defaults_escapes_and_missing_variables() {
+        let expand = |s: &str| interpolate_str(s, &env);
+        assert_eq!(expand("key=${TEAM_KEY}!").unwrap(), "key=sk-team!");
+        assert_eq!(expand("${UNSET:-gpt-4o} ${EMPTY:-x}").unwrap(), "gpt-4o x");
+        assert_eq!(
+            expand("cost $5, $${TEAM_KEY}").unwrap(),
+            "cost $5, ${TEAM_KEY}"
+        );
+        let err = expand("${UNSET}").unwrap_err().to_string();
+        assert!(err.contains("UNSET is not set"), "{err}");
+        assert!(expand("${TEAM_KEY").is_err());
+    }
+
+    #[test]
+    fn later_layers_win_key_by_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let system = dir.path().join("system.toml");
+        let user = dir.path().join("user.toml");
+        std::fs::write(
+            &system,
+            "default_model = \"shared\"\ndefault_temperature = 0.2\n\n[autonomy]\nlevel = \"supervised\"\nallowed_commands = [\"git\", \"ls\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &user,
+            "default_model = \"mine\"\n\n[autonomy]\nallowed_commands = [\"cargo\"]\n",
+        )
+        .unwrap();
+
+        let layers = [system, dir.path().join("missing.toml"), user];
+        let merged = load(&layers).unwrap().unwrap();
+        assert_eq!(merged["default_model"].as_str(), Some("mine"));
+        assert_eq!(merged["default_temperature"].as_float(), Some(0.2));
+        let autonomy = merged["autonomy"].as_table().unwrap();
+        assert_eq!(autonomy["level"].as_str(), Some("supervised"));
+        assert_eq!(autonomy["allowed_commands"].as_array().unwrap().len(), 1);
+        assert!(load(&layers[1..2]).unwrap().is_none());
+
+        // Partial layers over the defaults make a whole config
+        let mut table = toml::Table::try_from(crate::config::Config::default()).unwrap();
+        merge(&mut table, merged);
+        let config: crate::config::Config = toml::Value::Table(table).try_into().unwrap();
+        assert_eq!(config.default_model.as_deref(), Some("mine"));
+        assert_eq!(config.autonomy.allowed_commands, ["cargo"]);
+    }
+
+    #[test]
+    fn workspace_layer_keeps_only_allowed_keys_and_skips_interpolation() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_workspace(dir.path()).unwrap().is_none());
+        std::fs::write(
+            dir.path().join(WORKSPACE_CONFIG),
+            "default_model = \"${HOME}\"\napi_key = \"x\"\n\n[autonomy]\nlevel = \"full\"\n\n[hooks]\npre_tool = []\n\n[render]\nmarkdown = false\n\n[identity]\nformat = \"aieos\"\naieos_path = \"/etc/shadow\"\nunknown = 1\n\n[identity.instruction_files]\n\"AGENTS.md\" = 0\n\"../../.ssh/config\" = 0\n",
+        )
+        .unwrap();
+
+        let layer = load_workspace(dir.path()).unwrap().unwrap();
+        assert_eq!(layer["default_model"].as_str(), Some("${HOME}"));
+        assert!(layer["render"].is_table());
+        for key in ["api_key", "autonomy", "hooks"] {
+            assert!(!layer.contains_key(key), "{key} should be dropped");
+        }
+        let identity = layer["identity"].as_table().unwrap();
+        assert_eq!(identity["format"].as_str(), Some("aieos"));
+        for key in ["aieos_path", "instruction_files", "unknown"] {
+            assert!(!identity.contains_key(key), "identity.{key} should be dropped");
+        }
+
+        std::fs::write(
+            dir.path().join(WORKSPACE_CONFIG),
+            "[identity]\naieos_path = \"identity/aieos.json\"\n\n[identity.instruction_files]\n\"docs/AGENTS.md\" = 500\n",
+        )
+        .unwrap();
+        let layer = load_workspace(dir.path()).unwrap().unwrap();
+        let identity = layer["identity"].as_table().unwrap();
+        assert_eq!(identity["aieos_path"].as_str(), Some("identity/aieos.json"));
+        assert!(identity["instruction_files"]
+            .as_table()
+            .unwrap()
+            .contains_key("docs/AGENTS.md"));
+    }
+}
@@ -1,9 +1,22 @@
 pub mod cclaw_loader;
+pub mod layers;
+pub mod profiles;
 pub mod schema;
+pub mod validate;
 
 pub use schema::{
-    AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, DiscordConfig,
-    DockerRuntimeConfig, GatewayConfig, HeartbeatConfig, IMessageConfig, IdentityConfig,
-    MatrixConfig, MemoryConfig, ModelRouteConfig, ObservabilityConfig, ReliabilityConfig,
-    RuntimeConfig, SecretsConfig, SlackConfig, TelegramConfig, TunnelConfig, WebhookConfig,
+    A2aConfig, AgentBusConfig, AutonomyConfig, AzureAuth, AzureOpenAiConfig, BrowserConfig,
+    CalendarConfig, ChannelsConfig, CheckpointConfig, ComposioConfig, Config, DedupMerge,
+    DigestConfig, DiscordConfig, DockerRuntimeConfig, FsWatchRule, GatewayConfig, GeminiConfig,
+    GrpcConfig, GuardrailAction, GuardrailsConfig, HeartbeatConfig, HookConfig, HooksConfig,
+    IMessageConfig, IdentityConfig, JobsConfig, KnowledgeConfig, LangfuseSinkConfig, MatrixConfig,
+    MemoryConfig, MemoryDedupConfig, MemoryExtractConfig, MemoryGraphConfig, MemoryIsolationConfig,
+    MemoryRecallConfig, MemorySummaryConfig, ModelProfileConfig, ModelRouteConfig, NodesConfig,
+    OAuthClientConfig, ObservabilityConfig, ObservabilitySinksConfig, PersonaConfig, PluginsConfig,
+    ProviderConfig, QueueConfig, ReliabilityConfig, RemoteNodeConfig, RenderConfig,
+    ResponseCacheConfig, RouteMatch, RuntimeConfig, SecretsConfig, SessionsConfig,
+    SkillDistillConfig, SkillRegistryConfig, SlackConfig, TaskKind, TelegramConfig,
+    ToolOutputConfig, ToolOutputMode, ToolSchemaFormat, TranscriptionConfig, TriggersConfig,
+    TtsConfig, TunnelConfig, VerifierConfig, VisionConfig, WebSearchConfig, WebhookConfig,
+    WebhookSinkConfig, WorkflowsConfig,
 };
@@ -0,0 +1,131 @@
+//! Named profiles layered over the config.
+//!
+//! A `[profiles.<name>]` section overrides the provider, model, memory
+//! namespace, identity and autonomy settings at once, so switching between
+//! e.g. work and personal use needs no second config file. The profile is
+//! picked with `ZEROCLAW_PROFILE`, the `profile` argument of
+//! `zc_agent_init_profile`, or `/profile` in an interactive session.
+
+use super::schema::Config;
+use anyhow::{Context, Result};
+
+/// Environment variable naming the profile to apply on load.
+pub const PROFILE_ENV: &str = "ZEROCLAW_PROFILE";
+
+/// The profile named by [`PROFILE_ENV`], if set and not empty.
+pub fn from_env() -> Option<String> {
+    std::env::var(PROFILE_ENV)
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+}
+
+impl Config {
+    /// This config with the profile `name` applied. Call it on a config
+    /// without a profile: applying a second one does not undo the first.
+    pub fn with_profile(&self, name: &str) -> Result<Self> {
+        let Some(profile) = self.profiles.get(name) else {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            anyhow::bail!(
+                "No profile named \"{name}\" (configured: {})",
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            );
+        };
+
+        let mut overlay = toml::Table::new();
+        if let Some(provider) = &profile.provider {
+            overlay.insert("default_provider".into(), provider.clone().into());
+        }
+        if let Some(model) = &profile.model {
+            overlay.insert("default_model".into(), model.clone().into());
+        }
+        if let Some(namespace) = &profile.memory_namespace {
+            let memory =
+                toml::Table::from_iter([("namespace".to_string(), namespace.clone().into())]);
+            overlay.insert("memory".into(), memory.into());
+        }
+        if let Some(identity) = &profile.identity {
+            overlay.insert("identity".into(), identity.clone().into());
+        }
+        if let Some(autonomy) = &profile.autonomy {
+            overlay.insert("autonomy".into(), autonomy.clone().into());
+        }
+
+        let mut table = toml::Table::try_from(self).context("Failed to serialize config")?;
+        super::layers::merge(&mut table, overlay);
+        let mut config: Self = toml::Value::Table(table)
+            .try_into()
+            .with_context(|| format!("Invalid settings in profile \"{name}\""))?;
+        // Skipped during serialization
+        config.workspace_dir.clone_from(&self.workspace_dir);
+        config.config_path.clone_from(&self.config_path);
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::ProfileConfig;
+    use crate::security::AutonomyLevel;
+
+    fn config() -> Config {
+        let work: ProfileConfig = toml::from_str(
+            r#"
+            provider = "anthropic"
+            model = "claude-sonnet-4-20250514"
+            memory_namespace = "work"
+            identity = { instruction_files = { "WORK.md" = 0 } }
+            autonomy = { level = "full" }
+            "#,
+        )
+        .unwrap();
+        let mut config = Config {
+            default_model: Some("gpt-4o".into()),
+            workspace_dir: "/tmp/ws".into(),
+            ..Config::default()
+        };
+        config.autonomy.allowed_commands = vec!["git".into()];
+        config.profiles.insert("work".into(), work);
+        config
+            .profiles
+            .insert("personal".into(), ProfileConfig::default());
+        config
+    }
+
+    #[test]
+    fn profiles_override_only_what_they_set() {
+        let base = config();
+        let work = base.with_profile("work").unwrap();
+        assert_eq!(work.default_provider.as_deref(), Some("anthropic"));
+        assert_eq!(
+            work.default_model.as_deref(),
+            Some("claude-sonnet-4-20250514")
+        );
+        assert_eq!(work.memory.namespace.as_deref(), Some("work"));
+        assert!(work.identity.instruction_files.contains_key("WORK.md"));
+        assert_eq!(work.autonomy.level, AutonomyLevel::Full);
+        // Merged key by key, and paths survive the round trip
+        assert_eq!(work.autonomy.allowed_commands, ["git"]);
+        assert_eq!(work.workspace_dir, base.workspace_dir);
+
+        let personal = base.with_profile("personal").unwrap();
+        assert_eq!(personal.default_model.as_deref(), Some("gpt-4o"));
+        assert_eq!(personal.memory.namespace, None);
+    }
+
+    #[test]
+    fn unknown_profiles_and_bad_overrides_are_errors() {
+        let mut base = config();
+        let err = base.with_profile("home").unwrap_err().to_string();
+        assert!(err.contains("configured: personal, work"), "{err}");
+
+        base.profiles.get_mut("personal").unwrap().autonomy =
+            Some(toml::from_str("level = \"reckless\"").unwrap());
+        let err = format!("{:#}", base.with_profile("personal").unwrap_err());
+        assert!(err.contains("profile \"personal\""), "{err}");
+    }
+}
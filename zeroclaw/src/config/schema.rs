@@ -1,14 +1,18 @@
-use crate::security::AutonomyLevel;
+use crate::agent::TurnBudget;
+use crate::security::policy::DEFAULT_WRITE_PROTECTED;
+use crate::security::{AutonomyLevel, PathAccess, SecretStore};
 use anyhow::{Context, Result};
 use directories::UserDirs;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
 // ── Top-level config ──────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
     /// Workspace directory - computed from home, not serialized
     #[serde(skip)]
@@ -37,6 +41,10 @@ pub struct Config {
     #[serde(default)]
     pub model_routes: Vec<ModelRouteConfig>,
 
+    /// Per-model prompt settings, matched by model name (first match wins).
+    #[serde(default)]
+    pub model_profiles: Vec<ModelProfileConfig>,
+
     #[serde(default)]
     pub heartbeat: HeartbeatConfig,
 
@@ -63,11 +71,113 @@ pub struct Config {
 
     #[serde(default)]
     pub identity: IdentityConfig,
+
+    #[serde(default)]
+    pub sessions: SessionsConfig,
+
+    #[serde(default)]
+    pub transcription: TranscriptionConfig,
+
+    #[serde(default)]
+    pub skill_distill: SkillDistillConfig,
+
+    #[serde(default)]
+    pub digest: DigestConfig,
+
+    #[serde(default)]
+    pub jobs: JobsConfig,
+
+    #[serde(default)]
+    pub workflows: WorkflowsConfig,
+
+    #[serde(default)]
+    pub agent_bus: AgentBusConfig,
+
+    #[serde(default)]
+    pub a2a: A2aConfig,
+
+    #[serde(default)]
+    pub nodes: NodesConfig,
+
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+
+    #[serde(default)]
+    pub plugins: PluginsConfig,
+
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    #[serde(default)]
+    pub tts: TtsConfig,
+
+    #[serde(default)]
+    pub vision: VisionConfig,
+
+    #[serde(default)]
+    pub web_search: WebSearchConfig,
+
+    #[serde(default)]
+    pub verifier: VerifierConfig,
+
+    #[serde(default)]
+    pub guardrails: GuardrailsConfig,
+
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+
+    #[serde(default)]
+    pub triggers: TriggersConfig,
+
+    #[serde(default)]
+    pub knowledge: KnowledgeConfig,
+
+    #[serde(default)]
+    pub skill_registry: SkillRegistryConfig,
+
+    #[serde(default)]
+    pub queue: QueueConfig,
+
+    #[serde(default)]
+    pub response_cache: ResponseCacheConfig,
+
+    #[serde(default)]
+    pub azure_openai: AzureOpenAiConfig,
+
+    #[serde(default)]
+    pub gemini: GeminiConfig,
+
+    #[serde(default)]
+    pub tool_output: ToolOutputConfig,
+
+    #[serde(default)]
+    pub render: RenderConfig,
+
+    /// Credentials and endpoints per provider, keyed by provider name.
+    /// `api_key` above is the fallback for providers not listed here.
+    #[serde(default)]
+    pub providers: BTreeMap<String, ProviderConfig>,
+
+    /// Named overrides selected with `ZEROCLAW_PROFILE` or `/profile`.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+
+    /// OAuth apps for signing in to integrations, keyed by provider name.
+    #[serde(default)]
+    pub oauth: BTreeMap<String, OAuthClientConfig>,
+
+    /// Workspace directory (`~` is expanded). Unset means `~/.cclaw`;
+    /// `ZEROCLAW_WORKSPACE` overrides it.
+    #[serde(default)]
+    pub workspace: Option<String>,
+
+    #[serde(default)]
+    pub checkpoints: CheckpointConfig,
 }
 
 // ── Identity (AIEOS / OpenClaw format) ──────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct IdentityConfig {
     /// Identity format: "openclaw" (default) or "aieos"
     #[serde(default = "default_identity_format")]
@@ -78,528 +188,2552 @@ pub struct IdentityConfig {
     /// Inline AIEOS JSON (alternative to file path)
     #[serde(default)]
     pub aieos_inline: Option<String>,
+    /// Standing instruction files (relative to workspace) mapped to their
+    /// token budget; 0 uses `instruction_max_tokens`. Empty means
+    /// `AGENTS.md` and `SOUL.md`. Injected in path order, followed by
+    /// `.zeroclaw/instructions.md` when it exists.
+    #[serde(default)]
+    pub instruction_files: BTreeMap<String, usize>,
+    /// Default token budget for each instruction file
+    #[serde(default = "default_instruction_max_tokens")]
+    pub instruction_max_tokens: usize,
+    /// How the agent presents itself everywhere
+    #[serde(default)]
+    pub persona: PersonaConfig,
+    /// Persona fields overridden per channel, keyed by channel name
+    /// (`telegram`, `discord`, ..., or `cli`, `tui`, `ffi` for the agent
+    /// run from the terminal or an embedding app)
+    #[serde(default)]
+    pub channels: BTreeMap<String, PersonaConfig>,
+}
+
+impl IdentityConfig {
+    /// This identity as seen on `channel`: its `[identity.channels.<channel>]`
+    /// fields replace the matching `[identity.persona]` ones.
+    pub fn for_channel(&self, channel: &str) -> Self {
+        let mut identity = self.clone();
+        if let Some(overrides) = self.channels.get(channel) {
+            identity.persona = self.persona.merged_with(overrides);
+        }
+        identity
+    }
+}
+
+/// How the agent presents itself; unset fields leave the default behavior.
+///
+/// ```toml
+/// [identity.persona]
+/// name = "Nova"
+/// tone = "warm, a little dry"
+/// verbosity = "concise"
+///
+/// [identity.channels.telegram]
+/// verbosity = "one or two sentences"
+/// formatting = "plain text, no tables"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PersonaConfig {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// e.g. "she/her", "they/them"
+    #[serde(default)]
+    pub pronouns: Option<String>,
+    /// e.g. "friendly", "formal", "playful"
+    #[serde(default)]
+    pub tone: Option<String>,
+    /// e.g. "terse", "concise", "thorough"
+    #[serde(default)]
+    pub verbosity: Option<String>,
+    /// Language replies must be in, e.g. "German" or "de", or "auto" for
+    /// the language of the user's message. Replies detected in another
+    /// language are rewritten once; unset leaves the language to the model.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// e.g. "plain text only", "markdown with code blocks"
+    #[serde(default)]
+    pub formatting: Option<String>,
+    /// How to decline, e.g. "briefly, suggesting an alternative"
+    #[serde(default)]
+    pub refusal_style: Option<String>,
+}
+
+impl PersonaConfig {
+    /// `self` with every field set in `overrides` replaced.
+    pub fn merged_with(&self, overrides: &Self) -> Self {
+        let pick = |own: &Option<String>, other: &Option<String>| other.clone().or(own.clone());
+        Self {
+            name: pick(&self.name, &overrides.name),
+            pronouns: pick(&self.pronouns, &overrides.pronouns),
+            tone: pick(&self.tone, &overrides.tone),
+            verbosity: pick(&self.verbosity, &overrides.verbosity),
+            language: pick(&self.language, &overrides.language),
+            formatting: pick(&self.formatting, &overrides.formatting),
+            refusal_style: pick(&self.refusal_style, &overrides.refusal_style),
+        }
+    }
 }
 
 fn default_identity_format() -> String {
     "openclaw".into()
 }
 
+fn default_instruction_max_tokens() -> usize {
+    5_000
+}
+
 impl Default for IdentityConfig {
     fn default() -> Self {
         Self {
             format: default_identity_format(),
             aieos_path: None,
             aieos_inline: None,
+            instruction_files: BTreeMap::new(),
+            instruction_max_tokens: default_instruction_max_tokens(),
+            persona: PersonaConfig::default(),
+            channels: BTreeMap::new(),
         }
     }
 }
 
-// ── Gateway security ─────────────────────────────────────────────
+// ── Sessions (conversation transcripts) ─────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GatewayConfig {
-    /// Gateway port (default: 8080)
-    #[serde(default = "default_gateway_port")]
-    pub port: u16,
-    /// Gateway host (default: 127.0.0.1)
-    #[serde(default = "default_gateway_host")]
-    pub host: String,
-    /// Require pairing before accepting requests (default: true)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionsConfig {
+    /// Persist conversation transcripts under `<workspace>/sessions/`
     #[serde(default = "default_true")]
-    pub require_pairing: bool,
-    /// Allow binding to non-localhost without a tunnel (default: false)
+    pub enabled: bool,
+    /// Generate a short title after the first exchange (one extra, cheap model call)
+    #[serde(default = "default_true")]
+    pub auto_title: bool,
+    /// Model used for title generation (default: the session's own model)
     #[serde(default)]
-    pub allow_public_bind: bool,
-    /// Paired bearer tokens (managed automatically, not user-edited)
+    pub title_model: Option<String>,
+}
+
+impl Default for SessionsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            auto_title: true,
+            title_model: None,
+        }
+    }
+}
+
+// ── Text-to-speech ──────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TtsConfig {
+    /// Enable the `speak` tool and voice replies on channels with `voice_replies = true`
     #[serde(default)]
-    pub paired_tokens: Vec<String>,
+    pub enabled: bool,
+    /// "api" (`OpenAI`-compatible `/audio/speech`) | "command" (local engine such as piper)
+    #[serde(default = "default_tts_backend")]
+    pub backend: String,
+    /// Speech endpoint for the "api" backend
+    #[serde(default = "default_tts_api_url")]
+    pub api_url: String,
+    /// API key for the "api" backend (falls back to `OPENAI_API_KEY`)
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Model name for the "api" backend
+    #[serde(default = "default_tts_model")]
+    pub model: String,
+    /// Voice name for the "api" backend
+    #[serde(default = "default_tts_voice")]
+    pub voice: String,
+    /// Local command for the "command" backend; text arrives on stdin and the
+    /// command must write a WAV file to `{output}`,
+    /// e.g. `piper --model en_US-lessac-medium.onnx --output_file {output}`
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Longer replies are sent as text instead of audio
+    #[serde(default = "default_tts_max_chars")]
+    pub max_chars: usize,
+}
 
-    /// Max `/pair` requests per minute per client key.
-    #[serde(default = "default_pair_rate_limit")]
-    pub pair_rate_limit_per_minute: u32,
+fn default_tts_backend() -> String {
+    "api".into()
+}
 
-    /// Max `/webhook` requests per minute per client key.
-    #[serde(default = "default_webhook_rate_limit")]
-    pub webhook_rate_limit_per_minute: u32,
+fn default_tts_api_url() -> String {
+    "https://api.openai.com/v1/audio/speech".into()
+}
 
-    /// TTL for webhook idempotency keys.
-    #[serde(default = "default_idempotency_ttl_secs")]
-    pub idempotency_ttl_secs: u64,
+fn default_tts_model() -> String {
+    "tts-1".into()
 }
 
-fn default_gateway_port() -> u16 {
-    3000
+fn default_tts_voice() -> String {
+    "alloy".into()
 }
 
-fn default_gateway_host() -> String {
-    "127.0.0.1".into()
+fn default_tts_max_chars() -> usize {
+    1_500
 }
 
-fn default_pair_rate_limit() -> u32 {
-    10
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: default_tts_backend(),
+            api_url: default_tts_api_url(),
+            api_key: None,
+            model: default_tts_model(),
+            voice: default_tts_voice(),
+            command: None,
+            max_chars: default_tts_max_chars(),
+        }
+    }
 }
 
-fn default_webhook_rate_limit() -> u32 {
-    60
+// ── Skill distillation ──────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SkillDistillConfig {
+    /// Run distillation periodically from the daemon (`zeroclaw skills distill` always works)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hours between scheduled distillation runs
+    #[serde(default = "default_distill_interval_hours")]
+    pub interval_hours: u32,
+    /// Only analyze sessions updated within this many days
+    #[serde(default = "default_distill_lookback_days")]
+    pub lookback_days: u32,
+    /// A procedure must appear in at least this many sessions to become a draft
+    #[serde(default = "default_distill_min_occurrences")]
+    pub min_occurrences: usize,
+    /// Model used for analysis (defaults to `default_model`)
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
-fn default_idempotency_ttl_secs() -> u64 {
-    300
+fn default_distill_interval_hours() -> u32 {
+    24
 }
 
-fn default_true() -> bool {
-    true
+fn default_distill_lookback_days() -> u32 {
+    7
 }
 
-impl Default for GatewayConfig {
+fn default_distill_min_occurrences() -> usize {
+    2
+}
+
+impl Default for SkillDistillConfig {
     fn default() -> Self {
         Self {
-            port: default_gateway_port(),
-            host: default_gateway_host(),
-            require_pairing: true,
-            allow_public_bind: false,
-            paired_tokens: Vec::new(),
-            pair_rate_limit_per_minute: default_pair_rate_limit(),
-            webhook_rate_limit_per_minute: default_webhook_rate_limit(),
-            idempotency_ttl_secs: default_idempotency_ttl_secs(),
+            enabled: false,
+            interval_hours: default_distill_interval_hours(),
+            lookback_days: default_distill_lookback_days(),
+            min_occurrences: default_distill_min_occurrences(),
+            model: None,
         }
     }
 }
 
-// ── Composio (managed tool surface) ─────────────────────────────
+// ── Daily digest ────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ComposioConfig {
-    /// Enable Composio integration for 1000+ OAuth tools
+/// Summary of each completed day's conversation and daily memories,
+/// stored as a `daily` memory under `digest_<YYYY-MM-DD>`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DigestConfig {
+    /// Write a digest of each day from the daemon
     #[serde(default)]
     pub enabled: bool,
-    /// Composio API key (stored encrypted when secrets.encrypt = true)
+    /// Local hour (0-23) at which the previous day's digest is written
+    #[serde(default = "default_digest_hour")]
+    pub hour: u32,
+    /// Model used for summarizing (defaults to `default_model`)
     #[serde(default)]
-    pub api_key: Option<String>,
-    /// Default entity ID for multi-user setups
-    #[serde(default = "default_entity_id")]
-    pub entity_id: String,
+    pub model: Option<String>,
+    /// Channel the digest is also posted to, e.g. "telegram"
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Who receives it on that channel: chat ID, user, room or address
+    #[serde(default)]
+    pub recipient: Option<String>,
 }
 
-fn default_entity_id() -> String {
-    "default".into()
+fn default_digest_hour() -> u32 {
+    7
 }
 
-impl Default for ComposioConfig {
+impl Default for DigestConfig {
     fn default() -> Self {
         Self {
             enabled: false,
-            api_key: None,
-            entity_id: default_entity_id(),
+            hour: default_digest_hour(),
+            model: None,
+            channel: None,
+            recipient: None,
         }
     }
 }
 
-// ── Secrets (encrypted credential store) ────────────────────────
+// ── Background jobs ─────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SecretsConfig {
-    /// Enable encryption for API keys and tokens in config.toml
-    #[serde(default = "default_true")]
-    pub encrypt: bool,
+/// Long-running tasks queued with the `jobs` tool or `POST /jobs`, kept in
+/// `<workspace>/jobs` and run by the daemon.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JobsConfig {
+    /// Offer the `jobs` tool and run queued jobs in the daemon
+    #[serde(default)]
+    pub enabled: bool,
+    /// Jobs that may run at the same time
+    #[serde(default = "default_jobs_max_concurrent")]
+    pub max_concurrent: usize,
 }
 
-impl Default for SecretsConfig {
+fn default_jobs_max_concurrent() -> usize {
+    2
+}
+
+impl Default for JobsConfig {
     fn default() -> Self {
-        Self { encrypt: true }
+        Self {
+            enabled: false,
+            max_concurrent: default_jobs_max_concurrent(),
+        }
     }
 }
 
-// ── Browser (friendly-service browsing only) ───────────────────
+// ── Workflows ───────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct BrowserConfig {
-    /// Enable `browser_open` tool (opens URLs in Brave without scraping)
+/// Multi-step pipelines defined in TOML or YAML files, run with the
+/// `run_workflow` tool, `POST /workflows/:name/run`, or on a schedule.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowsConfig {
+    /// Offer the `run_workflow` tool and gateway routes, and run scheduled
+    /// workflows in the daemon
     #[serde(default)]
     pub enabled: bool,
-    /// Allowed domains for `browser_open` (exact or subdomain match)
+    /// Directory of workflow files, relative to the workspace
+    #[serde(default = "default_workflows_dir")]
+    pub dir: String,
+    /// Channel approval steps ask on when they name none, e.g. "telegram"
     #[serde(default)]
-    pub allowed_domains: Vec<String>,
-    /// Browser session name (for agent-browser automation)
+    pub approval_channel: Option<String>,
+    /// Chat, user or address asked there
     #[serde(default)]
-    pub session_name: Option<String>,
+    pub approval_recipient: Option<String>,
+    /// Minutes an approval step waits before its `on_timeout` decision
+    #[serde(default = "default_approval_timeout_minutes")]
+    pub approval_timeout_minutes: u64,
 }
 
-// ── Memory ───────────────────────────────────────────────────
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MemoryConfig {
-    /// "sqlite" | "markdown" | "none"
-    pub backend: String,
-    /// Auto-save conversation context to memory
-    pub auto_save: bool,
-    /// Run memory/session hygiene (archiving + retention cleanup)
-    #[serde(default = "default_hygiene_enabled")]
-    pub hygiene_enabled: bool,
-    /// Archive daily/session files older than this many days
-    #[serde(default = "default_archive_after_days")]
-    pub archive_after_days: u32,
-    /// Purge archived files older than this many days
-    #[serde(default = "default_purge_after_days")]
-    pub purge_after_days: u32,
-    /// For sqlite backend: prune conversation rows older than this many days
-    #[serde(default = "default_conversation_retention_days")]
-    pub conversation_retention_days: u32,
-    /// Embedding provider: "none" | "openai" | "custom:URL"
-    #[serde(default = "default_embedding_provider")]
-    pub embedding_provider: String,
-    /// Embedding model name (e.g. "text-embedding-3-small")
-    #[serde(default = "default_embedding_model")]
-    pub embedding_model: String,
-    /// Embedding vector dimensions
-    #[serde(default = "default_embedding_dims")]
-    pub embedding_dimensions: usize,
-    /// Weight for vector similarity in hybrid search (0.0–1.0)
-    #[serde(default = "default_vector_weight")]
-    pub vector_weight: f64,
-    /// Weight for keyword BM25 in hybrid search (0.0–1.0)
-    #[serde(default = "default_keyword_weight")]
-    pub keyword_weight: f64,
-    /// Max embedding cache entries before LRU eviction
-    #[serde(default = "default_cache_size")]
-    pub embedding_cache_size: usize,
-    /// Max tokens per chunk for document splitting
-    #[serde(default = "default_chunk_size")]
-    pub chunk_max_tokens: usize,
+fn default_workflows_dir() -> String {
+    "workflows".into()
 }
 
-fn default_embedding_provider() -> String {
-    "none".into()
+fn default_approval_timeout_minutes() -> u64 {
+    24 * 60
 }
-fn default_hygiene_enabled() -> bool {
-    true
-}
-fn default_archive_after_days() -> u32 {
-    7
-}
-fn default_purge_after_days() -> u32 {
-    30
+
+impl Default for WorkflowsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: default_workflows_dir(),
+            approval_channel: None,
+            approval_recipient: None,
+            approval_timeout_minutes: default_approval_timeout_minutes(),
+        }
+    }
 }
-fn default_conversation_retention_days() -> u32 {
-    30
+
+// ── Agent bus ───────────────────────────────────────────────────
+
+/// Profiles run side by side in the daemon as agents that message each
+/// other with the `send_to_agent` tool.
+///
+/// ```toml
+/// [agent_bus]
+/// enabled = true
+/// agents = ["researcher", "coder"]   # names of [profiles.<name>] sections
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AgentBusConfig {
+    /// Run the agents in the daemon and offer the `send_to_agent` tool
+    #[serde(default)]
+    pub enabled: bool,
+    /// Profiles to run as agents; empty runs every `[profiles.<name>]`
+    #[serde(default)]
+    pub agents: Vec<String>,
+    /// Seconds `send_to_agent` waits for a reply before giving up
+    #[serde(default = "default_agent_bus_reply_timeout_secs")]
+    pub reply_timeout_secs: u64,
+    /// Longest chain of agents handing a message on, to stop loops
+    #[serde(default = "default_agent_bus_max_hops")]
+    pub max_hops: usize,
 }
-fn default_embedding_model() -> String {
-    "text-embedding-3-small".into()
+
+fn default_agent_bus_reply_timeout_secs() -> u64 {
+    300
 }
-fn default_embedding_dims() -> usize {
-    1536
+
+fn default_agent_bus_max_hops() -> usize {
+    4
 }
-fn default_vector_weight() -> f64 {
-    0.7
+
+impl Default for AgentBusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            agents: Vec::new(),
+            reply_timeout_secs: default_agent_bus_reply_timeout_secs(),
+            max_hops: default_agent_bus_max_hops(),
+        }
+    }
 }
-fn default_keyword_weight() -> f64 {
-    0.3
+
+// ── A2A ─────────────────────────────────────────────────────────
+
+/// Agent-to-Agent protocol server on the gateway: an agent card at
+/// `/.well-known/agent.json` and JSON-RPC tasks at `POST /a2a`, so other
+/// A2A agents can discover and delegate to this one.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct A2aConfig {
+    /// Serve the agent card and the `/a2a` endpoint
+    #[serde(default)]
+    pub enabled: bool,
+    /// Name in the agent card
+    #[serde(default = "default_a2a_name")]
+    pub name: String,
+    /// Description in the agent card
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Base URL other agents reach the gateway at, e.g. the tunnel URL
+    /// (defaults to the `Host` of the card request)
+    #[serde(default)]
+    pub public_url: Option<String>,
+    /// Tasks kept for `tasks/get`; the oldest finished ones go first
+    #[serde(default = "default_a2a_max_tasks")]
+    pub max_tasks: usize,
 }
-fn default_cache_size() -> usize {
-    10_000
+
+fn default_a2a_name() -> String {
+    "ZeroClaw".into()
 }
-fn default_chunk_size() -> usize {
-    512
+
+fn default_a2a_max_tasks() -> usize {
+    200
 }
 
-impl Default for MemoryConfig {
+impl Default for A2aConfig {
     fn default() -> Self {
         Self {
-            backend: "sqlite".into(),
-            auto_save: true,
-            hygiene_enabled: default_hygiene_enabled(),
-            archive_after_days: default_archive_after_days(),
-            purge_after_days: default_purge_after_days(),
-            conversation_retention_days: default_conversation_retention_days(),
-            embedding_provider: default_embedding_provider(),
-            embedding_model: default_embedding_model(),
-            embedding_dimensions: default_embedding_dims(),
-            vector_weight: default_vector_weight(),
-            keyword_weight: default_keyword_weight(),
-            embedding_cache_size: default_cache_size(),
-            chunk_max_tokens: default_chunk_size(),
+            enabled: false,
+            name: default_a2a_name(),
+            description: None,
+            public_url: None,
+            max_tasks: default_a2a_max_tasks(),
         }
     }
 }
 
-// ── Observability ─────────────────────────────────────────────────
+// ── Remote tool nodes ───────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ObservabilityConfig {
-    /// "none" | "log" | "prometheus" | "otel"
-    pub backend: String,
+/// Remote tool execution. `zeroclaw node` serves this machine's shell and
+/// file tools over an authenticated WebSocket; `[nodes.remote.<name>]`
+/// entries let the agent run those tools on a node with `target: "<name>"`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NodesConfig {
+    /// Bearer token callers must present to `zeroclaw node` (required to serve)
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Address `zeroclaw node` binds to
+    #[serde(default = "default_nodes_host")]
+    pub host: String,
+    /// Allow `zeroclaw node` to bind a public address without a tunnel. The
+    /// link is plain `ws://`, so only do this behind a TLS proxy.
+    #[serde(default)]
+    pub allow_public_bind: bool,
+    /// Port `zeroclaw node` listens on
+    #[serde(default = "default_nodes_port")]
+    pub port: u16,
+    /// Tools `zeroclaw node` serves: any of shell, file_read, file_write, file_edit
+    #[serde(default = "default_nodes_tools")]
+    pub tools: Vec<String>,
+    /// How long a remote call may take before the caller gives up
+    #[serde(default = "default_nodes_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Nodes this instance sends tool calls to, by name
+    #[serde(default)]
+    pub remote: BTreeMap<String, RemoteNodeConfig>,
+}
 
-    /// OTLP endpoint (e.g. "http://localhost:4318"). Only used when backend = "otel".
+/// A node the agent can target.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RemoteNodeConfig {
+    /// `ws://` or `wss://` address of the node
+    pub url: String,
+    /// The node's `[nodes] token`
+    pub token: String,
+}
+
+fn default_nodes_host() -> String {
+    "127.0.0.1".into()
+}
+
+fn default_nodes_port() -> u16 {
+    7420
+}
+
+fn default_nodes_tools() -> Vec<String> {
+    ["shell", "file_read", "file_write", "file_edit"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_nodes_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for NodesConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            host: default_nodes_host(),
+            allow_public_bind: false,
+            port: default_nodes_port(),
+            tools: default_nodes_tools(),
+            timeout_secs: default_nodes_timeout_secs(),
+            remote: BTreeMap::new(),
+        }
+    }
+}
+
+// ── gRPC ────────────────────────────────────────────────────────
+
+/// gRPC API served next to the gateway, mirroring its REST routes
+/// (`proto/zeroclaw.proto`). Binds the gateway's host.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GrpcConfig {
+    /// Serve the gRPC services with the gateway
     #[serde(default)]
-    pub otel_endpoint: Option<String>,
+    pub enabled: bool,
+    /// Port for gRPC (HTTP/2), separate from the gateway's
+    #[serde(default = "default_grpc_port")]
+    pub port: u16,
+}
 
-    /// Service name reported to the OTel collector. Defaults to "zeroclaw".
+fn default_grpc_port() -> u16 {
+    50051
+}
+
+// ── Tool plugins ────────────────────────────────────────────────
+
+/// Native tool plugins: shared libraries (`.so`, `.dylib`, `.dll`) that
+/// export `zc_tool_descriptor` and `zc_tool_execute` from
+/// `include/zeroclaw_tool.h`. Each one found in `dir` becomes a tool.
+/// Plugins run in-process with the agent's privileges, so only enable
+/// this for libraries you trust.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PluginsConfig {
+    /// Load the plugins in `dir` as tools
     #[serde(default)]
-    pub otel_service_name: Option<String>,
+    pub enabled: bool,
+    /// Directory of plugin libraries, relative to `~/.zeroclaw`. It may not
+    /// be inside the workspace, which the agent can write to.
+    #[serde(default = "default_plugins_dir")]
+    pub dir: String,
 }
 
-impl Default for ObservabilityConfig {
+fn default_plugins_dir() -> String {
+    "~/.zeroclaw/plugins".into()
+}
+
+impl Default for PluginsConfig {
     fn default() -> Self {
         Self {
-            backend: "none".into(),
-            otel_endpoint: None,
-            otel_service_name: None,
+            enabled: false,
+            dir: default_plugins_dir(),
         }
     }
 }
 
-// ── Autonomy / Security ──────────────────────────────────────────
+// ── Lifecycle hooks ─────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AutonomyConfig {
-    pub level: AutonomyLevel,
-    pub workspace_only: bool,
-    pub allowed_commands: Vec<String>,
-    pub forbidden_paths: Vec<String>,
-    pub max_actions_per_hour: u32,
-    pub max_cost_per_day_cents: u32,
+/// Shell commands or webhooks run on agent lifecycle events. Each gets the
+/// event as JSON; `pre_tool_use` and `turn_start` hooks can answer with a
+/// veto or a rewrite.
+///
+/// ```toml
+/// [[hooks.pre_tool_use]]
+/// command = "scripts/tool-policy.sh"
+/// tools = ["shell"]
+///
+/// [[hooks.turn_end]]
+/// url = "https://audit.example.com/zeroclaw"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct HooksConfig {
+    /// Before a tool runs: may deny the call or rewrite its arguments
+    #[serde(default)]
+    pub pre_tool_use: Vec<HookConfig>,
+    /// After a tool ran, with its outcome
+    #[serde(default)]
+    pub post_tool_use: Vec<HookConfig>,
+    /// Before a turn starts: may deny it or rewrite the user's message
+    #[serde(default)]
+    pub turn_start: Vec<HookConfig>,
+    /// After a turn ended, with the reply
+    #[serde(default)]
+    pub turn_end: Vec<HookConfig>,
+}
 
-    /// Require explicit approval for medium-risk shell commands.
-    #[serde(default = "default_true")]
-    pub require_approval_for_medium_risk: bool,
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.pre_tool_use.is_empty()
+            && self.post_tool_use.is_empty()
+            && self.turn_start.is_empty()
+            && self.turn_end.is_empty()
+    }
+}
 
-    /// Block high-risk shell commands even if allowlisted.
-    #[serde(default = "default_true")]
-    pub block_high_risk_commands: bool,
+/// One hook: a shell command (run in the workspace, event JSON on stdin,
+/// answer on stdout) or a URL (event JSON sent by POST, answer in the body).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HookConfig {
+    /// Command run with `sh -c`
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Webhook URL, used when `command` is unset
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Extra headers sent to `url`, e.g. an Authorization token
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Tool events only: run for these tools (empty = all)
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// How long the hook may take; a pre hook that times out denies
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
 }
 
-impl Default for AutonomyConfig {
+fn default_hook_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for GrpcConfig {
     fn default() -> Self {
         Self {
-            level: AutonomyLevel::Supervised,
-            workspace_only: true,
-            allowed_commands: vec![
-                "git".into(),
-                "npm".into(),
-                "cargo".into(),
-                "ls".into(),
-                "cat".into(),
-                "grep".into(),
-                "find".into(),
-                "echo".into(),
-                "pwd".into(),
-                "wc".into(),
-                "head".into(),
-                "tail".into(),
-            ],
-            forbidden_paths: vec![
-                "/etc".into(),
-                "/root".into(),
-                "/home".into(),
-                "/usr".into(),
-                "/bin".into(),
-                "/sbin".into(),
-                "/lib".into(),
-                "/opt".into(),
-                "/boot".into(),
-                "/dev".into(),
-                "/proc".into(),
-                "/sys".into(),
-                "/var".into(),
-                "/tmp".into(),
-                "~/.ssh".into(),
-                "~/.gnupg".into(),
-                "~/.aws".into(),
-                "~/.config".into(),
-            ],
-            max_actions_per_hour: 10000,
-            max_cost_per_day_cents: 10000,
-            require_approval_for_medium_risk: true,
-            block_high_risk_commands: true,
+            enabled: false,
+            port: default_grpc_port(),
         }
     }
 }
 
-// ── Runtime ──────────────────────────────────────────────────────
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RuntimeConfig {
-    /// Runtime kind (`native` | `docker`).
-    #[serde(default = "default_runtime_kind")]
-    pub kind: String,
+// ── Transcription (speech-to-text) ──────────────────────────────
 
-    /// Docker runtime settings (used when `kind = "docker"`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TranscriptionConfig {
+    /// Enable the `transcribe` tool and automatic voice-message transcription
     #[serde(default)]
-    pub docker: DockerRuntimeConfig,
+    pub enabled: bool,
+    /// "api" (`OpenAI`-compatible `/audio/transcriptions`) | "`whisper_cpp`" (local)
+    #[serde(default = "default_transcription_backend")]
+    pub backend: String,
+    /// Transcription endpoint for the "api" backend
+    #[serde(default = "default_transcription_api_url")]
+    pub api_url: String,
+    /// API key for the "api" backend (falls back to `OPENAI_API_KEY`)
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Model name for the "api" backend
+    #[serde(default = "default_transcription_model")]
+    pub model: String,
+    /// `whisper.cpp` CLI binary for the local backend
+    #[serde(default = "default_whisper_cpp_bin")]
+    pub whisper_cpp_bin: String,
+    /// Path to the ggml model file for the local backend
+    #[serde(default)]
+    pub whisper_cpp_model: Option<String>,
+    /// Spoken-language hint as an ISO-639-1 code (e.g. "en", "de"); auto-detect when unset
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Reject recordings longer than this many seconds
+    #[serde(default = "default_max_audio_duration_secs")]
+    pub max_duration_secs: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DockerRuntimeConfig {
-    /// Runtime image used to execute shell commands.
-    #[serde(default = "default_docker_image")]
-    pub image: String,
+fn default_transcription_backend() -> String {
+    "api".into()
+}
 
-    /// Docker network mode (`none`, `bridge`, etc.).
-    #[serde(default = "default_docker_network")]
-    pub network: String,
+fn default_transcription_api_url() -> String {
+    "https://api.openai.com/v1/audio/transcriptions".into()
+}
 
-    /// Optional memory limit in MB (`None` = no explicit limit).
-    #[serde(default = "default_docker_memory_limit_mb")]
-    pub memory_limit_mb: Option<u64>,
+fn default_transcription_model() -> String {
+    "whisper-1".into()
+}
 
-    /// Optional CPU limit (`None` = no explicit limit).
-    #[serde(default = "default_docker_cpu_limit")]
-    pub cpu_limit: Option<f64>,
+fn default_whisper_cpp_bin() -> String {
+    "whisper-cli".into()
+}
 
-    /// Mount root filesystem as read-only.
-    #[serde(default = "default_true")]
-    pub read_only_rootfs: bool,
+fn default_max_audio_duration_secs() -> u64 {
+    300
+}
 
-    /// Mount configured workspace into `/workspace`.
+impl Default for TranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: default_transcription_backend(),
+            api_url: default_transcription_api_url(),
+            api_key: None,
+            model: default_transcription_model(),
+            whisper_cpp_bin: default_whisper_cpp_bin(),
+            whisper_cpp_model: None,
+            language: None,
+            max_duration_secs: default_max_audio_duration_secs(),
+        }
+    }
+}
+
+// ── Gateway security ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GatewayConfig {
+    /// Gateway port (default: 8080)
+    #[serde(default = "default_gateway_port")]
+    pub port: u16,
+    /// Gateway host (default: 127.0.0.1)
+    #[serde(default = "default_gateway_host")]
+    pub host: String,
+    /// Require pairing before accepting requests (default: true)
     #[serde(default = "default_true")]
-    pub mount_workspace: bool,
+    pub require_pairing: bool,
+    /// Allow binding to non-localhost without a tunnel (default: false)
+    #[serde(default)]
+    pub allow_public_bind: bool,
+    /// Paired bearer tokens (managed automatically, not user-edited)
+    #[serde(default)]
+    pub paired_tokens: Vec<String>,
+
+    /// Max `/pair` requests per minute per client key.
+    #[serde(default = "default_pair_rate_limit")]
+    pub pair_rate_limit_per_minute: u32,
+
+    /// Max `/webhook` requests per minute per client key.
+    #[serde(default = "default_webhook_rate_limit")]
+    pub webhook_rate_limit_per_minute: u32,
+
+    /// TTL for webhook idempotency keys.
+    #[serde(default = "default_idempotency_ttl_secs")]
+    pub idempotency_ttl_secs: u64,
+}
+
+fn default_gateway_port() -> u16 {
+    3000
+}
+
+fn default_gateway_host() -> String {
+    "127.0.0.1".into()
+}
+
+fn default_pair_rate_limit() -> u32 {
+    10
+}
+
+fn default_webhook_rate_limit() -> u32 {
+    60
+}
+
+fn default_idempotency_ttl_secs() -> u64 {
+    300
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        Self {
+            port: default_gateway_port(),
+            host: default_gateway_host(),
+            require_pairing: true,
+            allow_public_bind: false,
+            paired_tokens: Vec::new(),
+            pair_rate_limit_per_minute: default_pair_rate_limit(),
+            webhook_rate_limit_per_minute: default_webhook_rate_limit(),
+            idempotency_ttl_secs: default_idempotency_ttl_secs(),
+        }
+    }
+}
+
+// ── Composio (managed tool surface) ─────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ComposioConfig {
+    /// Enable Composio integration for 1000+ OAuth tools
+    #[serde(default)]
+    pub enabled: bool,
+    /// Composio API key (stored encrypted when secrets.encrypt = true)
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Default entity ID for multi-user setups
+    #[serde(default = "default_entity_id")]
+    pub entity_id: String,
+    /// Apps whose actions are offered to the model as tools of their own,
+    /// with typed parameters, e.g. `["github", "gmail"]`. Other actions stay
+    /// reachable through the generic `composio` tool.
+    #[serde(default)]
+    pub apps: Vec<String>,
+    /// Seconds before the cached action catalog is fetched again
+    #[serde(default = "default_composio_catalog_ttl_secs")]
+    pub catalog_ttl_secs: u64,
+}
+
+fn default_entity_id() -> String {
+    "default".into()
+}
+
+fn default_composio_catalog_ttl_secs() -> u64 {
+    86_400
+}
+
+impl Default for ComposioConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_key: None,
+            entity_id: default_entity_id(),
+            apps: Vec::new(),
+            catalog_ttl_secs: default_composio_catalog_ttl_secs(),
+        }
+    }
+}
+
+// ── OAuth (tool integrations) ───────────────────────────────────
+
+/// An OAuth app used to sign in to an integration from the CLI or an
+/// embedding app (`zeroclaw integrations login <name>`, `zc_oauth_login`).
+/// `github` and `google` know their endpoints; other names must set both
+/// URLs. The app must allow `http://127.0.0.1` redirects on any port.
+///
+/// ```toml
+/// [oauth.github]
+/// client_id = "Iv1.0123456789abcdef"
+/// client_secret = "enc2:..."
+/// scopes = ["repo", "read:user"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct OAuthClientConfig {
+    pub client_id: String,
+    /// Omit for public clients; `enc2:` values are decrypted with the secret store
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Authorization endpoint, for providers other than `github` and `google`
+    #[serde(default)]
+    pub authorize_url: Option<String>,
+    /// Token endpoint, for providers other than `github` and `google`
+    #[serde(default)]
+    pub token_url: Option<String>,
+}
+
+// ── Secrets (encrypted credential store) ────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SecretsConfig {
+    /// Enable encryption for API keys and tokens in config.toml
+    #[serde(default = "default_true")]
+    pub encrypt: bool,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self { encrypt: true }
+    }
+}
+
+// ── Browser (friendly-service browsing only) ───────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct BrowserConfig {
+    /// Enable `browser_open` tool (opens URLs in Brave without scraping)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Allowed domains for `browser_open` (exact or subdomain match)
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// Browser session name (for agent-browser automation)
+    #[serde(default)]
+    pub session_name: Option<String>,
+    /// Chromium/Chrome binary driven by `browser_act` (auto-detected when unset)
+    #[serde(default)]
+    pub chromium_path: Option<String>,
+    /// Attach `browser_act` to an already running `DevTools` endpoint
+    /// (e.g. `http://127.0.0.1:9222`) instead of launching Chromium
+    #[serde(default)]
+    pub cdp_url: Option<String>,
+}
+
+// ── Memory ───────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryConfig {
+    /// "sqlite" | "markdown" | "none"
+    pub backend: String,
+    /// Auto-save conversation context to memory
+    pub auto_save: bool,
+    /// Run memory/session hygiene (archiving + retention cleanup)
+    #[serde(default = "default_hygiene_enabled")]
+    pub hygiene_enabled: bool,
+    /// Archive daily/session files older than this many days
+    #[serde(default = "default_archive_after_days")]
+    pub archive_after_days: u32,
+    /// Purge archived files older than this many days
+    #[serde(default = "default_purge_after_days")]
+    pub purge_after_days: u32,
+    /// For sqlite backend: prune conversation rows older than this many days
+    #[serde(default = "default_conversation_retention_days")]
+    pub conversation_retention_days: u32,
+    /// Embedding provider: "none" | "openai" | "openrouter" | "ollama[:URL]" | "custom:URL"
+    #[serde(default = "default_embedding_provider")]
+    pub embedding_provider: String,
+    /// Embedding model name (e.g. "text-embedding-3-small")
+    #[serde(default = "default_embedding_model")]
+    pub embedding_model: String,
+    /// Embedding vector dimensions
+    #[serde(default = "default_embedding_dims")]
+    pub embedding_dimensions: usize,
+    /// Weight for vector similarity in hybrid search (0.0–1.0)
+    #[serde(default = "default_vector_weight")]
+    pub vector_weight: f64,
+    /// Weight for keyword BM25 in hybrid search (0.0–1.0)
+    #[serde(default = "default_keyword_weight")]
+    pub keyword_weight: f64,
+    /// Max embedding cache entries before LRU eviction
+    #[serde(default = "default_cache_size")]
+    pub embedding_cache_size: usize,
+    /// Max tokens per chunk for document splitting
+    #[serde(default = "default_chunk_size")]
+    pub chunk_max_tokens: usize,
+    /// Keep memories under `namespaces/<name>/` in the workspace instead of
+    /// sharing the workspace's own, e.g. one per profile
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Which recalled memories go into a turn's context
+    #[serde(default)]
+    pub recall: MemoryRecallConfig,
+    /// How auto-saved memories are kept from piling up duplicates
+    #[serde(default)]
+    pub dedup: MemoryDedupConfig,
+    /// Facts pulled out of each turn by a model, instead of raw messages
+    #[serde(default)]
+    pub extract: MemoryExtractConfig,
+    /// Entities and the relations between them, expanded from each message
+    #[serde(default)]
+    pub graph: MemoryGraphConfig,
+    /// Whose memories a message on a multi-user channel reaches
+    #[serde(default)]
+    pub isolation: MemoryIsolationConfig,
+    /// What a session leaves behind for the next one when it ends
+    #[serde(default)]
+    pub summary: MemorySummaryConfig,
+}
+
+impl MemoryConfig {
+    /// Whether raw user messages and answers are auto-saved; fact
+    /// extraction takes their place when it is enabled.
+    pub fn saves_raw_turns(&self) -> bool {
+        self.auto_save && !self.extract.enabled
+    }
+}
+
+/// Recall filtering for the `[Memory context]` block (`[memory.recall]`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryRecallConfig {
+    /// Most memories put in front of the model per turn
+    #[serde(default = "default_recall_limit")]
+    pub limit: usize,
+    /// Drop memories scoring below this relevance (0.0–1.0); 0 keeps every hit
+    #[serde(default = "default_recall_min_score")]
+    pub min_score: f64,
+    /// Cap on the estimated tokens of recalled memories; 0 = no cap
+    #[serde(default = "default_recall_max_context_tokens")]
+    pub max_context_tokens: usize,
+}
+
+fn default_recall_limit() -> usize {
+    5
+}
+fn default_recall_min_score() -> f64 {
+    0.2
+}
+fn default_recall_max_context_tokens() -> usize {
+    1000
+}
+
+impl Default for MemoryRecallConfig {
+    fn default() -> Self {
+        Self {
+            limit: default_recall_limit(),
+            min_score: default_recall_min_score(),
+            max_context_tokens: default_recall_max_context_tokens(),
+        }
+    }
+}
+
+/// Deduplication of auto-saved memories (`[memory.dedup]`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryDedupConfig {
+    /// Check auto-saves against similar memories before storing them
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Word-pair overlap (0.0–1.0) from which two memories count as the
+    /// same fact; identical text is always a duplicate
+    #[serde(default = "default_dedup_similarity")]
+    pub similarity: f64,
+    /// What happens to a near duplicate
+    #[serde(default)]
+    pub merge: DedupMerge,
+}
+
+/// What a near-duplicate auto-save does to the memory it resembles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupMerge {
+    /// The new text replaces the old under the old key: the fact was updated
+    #[default]
+    Replace,
+    /// The old memory stays and the new one is dropped
+    Keep,
+}
+
+fn default_dedup_similarity() -> f64 {
+    0.6
+}
+
+impl Default for MemoryDedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            similarity: default_dedup_similarity(),
+            merge: DedupMerge::default(),
+        }
+    }
+}
+
+/// Post-turn fact extraction (`[memory.extract]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryExtractConfig {
+    /// Store people, preferences, deadlines and decisions from each turn as
+    /// typed memories, instead of auto-saving the raw messages
+    #[serde(default)]
+    pub enabled: bool,
+    /// Model that extracts them, ideally a cheap one (defaults to the turn's)
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Knowledge graph memory (`[memory.graph]`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryGraphConfig {
+    /// Keep a graph of entities and relations, give the agent the
+    /// `memory_graph` tool and add the relations around entities a message
+    /// mentions to its context
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hops followed out from a mentioned entity
+    #[serde(default = "default_graph_depth")]
+    pub depth: usize,
+    /// Most relations added to one message's context
+    #[serde(default = "default_graph_max_relations")]
+    pub max_relations: usize,
+}
+
+fn default_graph_depth() -> usize {
+    2
+}
+fn default_graph_max_relations() -> usize {
+    20
+}
+
+impl Default for MemoryGraphConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            depth: default_graph_depth(),
+            max_relations: default_graph_max_relations(),
+        }
+    }
+}
+
+/// Per-user memory on multi-user channels (`[memory.isolation]`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryIsolationConfig {
+    /// Give each sender on a channel their own memory namespace, so one
+    /// user's memories never reach another's replies
+    #[serde(default = "default_true")]
+    pub per_user: bool,
+    /// Channels whose users knowingly share one memory pool, e.g. a team's
+    /// Slack
+    #[serde(default)]
+    pub shared_channels: Vec<String>,
+}
+
+impl Default for MemoryIsolationConfig {
+    fn default() -> Self {
+        Self {
+            per_user: true,
+            shared_channels: Vec::new(),
+        }
+    }
+}
+
+/// Session summaries (`[memory.summary]`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemorySummaryConfig {
+    /// When a session ends, store its goal, actions, outcomes and
+    /// follow-ups as a core memory the next session can pick up from
+    #[serde(default)]
+    pub enabled: bool,
+    /// Model that writes the summary (defaults to the session's)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Exchanges a session needs before it is worth summarizing
+    #[serde(default = "default_summary_min_turns")]
+    pub min_turns: usize,
+    /// Minutes without a message after which a channel conversation counts
+    /// as ended
+    #[serde(default = "default_summary_idle_minutes")]
+    pub idle_minutes: u64,
+}
+
+fn default_summary_min_turns() -> usize {
+    2
+}
+fn default_summary_idle_minutes() -> u64 {
+    30
+}
+
+impl Default for MemorySummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: None,
+            min_turns: default_summary_min_turns(),
+            idle_minutes: default_summary_idle_minutes(),
+        }
+    }
+}
+
+fn default_embedding_provider() -> String {
+    "none".into()
+}
+fn default_hygiene_enabled() -> bool {
+    true
+}
+fn default_archive_after_days() -> u32 {
+    7
+}
+fn default_purge_after_days() -> u32 {
+    30
+}
+fn default_conversation_retention_days() -> u32 {
+    30
+}
+fn default_embedding_model() -> String {
+    "text-embedding-3-small".into()
+}
+fn default_embedding_dims() -> usize {
+    1536
+}
+fn default_vector_weight() -> f64 {
+    0.7
+}
+fn default_keyword_weight() -> f64 {
+    0.3
+}
+fn default_cache_size() -> usize {
+    10_000
+}
+fn default_chunk_size() -> usize {
+    512
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            backend: "sqlite".into(),
+            auto_save: true,
+            hygiene_enabled: default_hygiene_enabled(),
+            archive_after_days: default_archive_after_days(),
+            purge_after_days: default_purge_after_days(),
+            conversation_retention_days: default_conversation_retention_days(),
+            embedding_provider: default_embedding_provider(),
+            embedding_model: default_embedding_model(),
+            embedding_dimensions: default_embedding_dims(),
+            vector_weight: default_vector_weight(),
+            keyword_weight: default_keyword_weight(),
+            embedding_cache_size: default_cache_size(),
+            chunk_max_tokens: default_chunk_size(),
+            namespace: None,
+            recall: MemoryRecallConfig::default(),
+            dedup: MemoryDedupConfig::default(),
+            extract: MemoryExtractConfig::default(),
+            graph: MemoryGraphConfig::default(),
+            isolation: MemoryIsolationConfig::default(),
+            summary: MemorySummaryConfig::default(),
+        }
+    }
+}
+
+// ── Observability ─────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ObservabilityConfig {
+    /// "none" | "log" | "prometheus" | "otel"
+    pub backend: String,
+
+    /// OTLP endpoint (e.g. "http://localhost:4318"). Only used when backend = "otel".
+    #[serde(default)]
+    pub otel_endpoint: Option<String>,
+
+    /// Service name reported to the OTel collector. Defaults to "zeroclaw".
+    #[serde(default)]
+    pub otel_service_name: Option<String>,
+
+    /// More destinations every event also goes to, alongside `backend`.
+    #[serde(default)]
+    pub sinks: ObservabilitySinksConfig,
+}
+
+impl Default for ObservabilityConfig {
+    fn default() -> Self {
+        Self {
+            backend: "none".into(),
+            otel_endpoint: None,
+            otel_service_name: None,
+            sinks: ObservabilitySinksConfig::default(),
+        }
+    }
+}
+
+/// `[observability.sinks]`: each sink that is set up receives every event
+/// and metric, so they can go to the console, a file, a collector and a
+/// webhook at once.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ObservabilitySinksConfig {
+    /// Log events to the console, like `backend = "log"`
+    #[serde(default)]
+    pub console: bool,
+
+    /// Append events to a JSON Lines file
+    #[serde(default)]
+    pub jsonl: Option<JsonlSinkConfig>,
+
+    /// Export traces and metrics to an OTLP collector
+    #[serde(default)]
+    pub otlp: Option<OtlpSinkConfig>,
+
+    /// POST events to a URL in batches
+    #[serde(default)]
+    pub webhook: Option<WebhookSinkConfig>,
+
+    /// Report model calls, tool spans and scores to Langfuse
+    #[serde(default)]
+    pub langfuse: Option<LangfuseSinkConfig>,
+}
+
+impl ObservabilitySinksConfig {
+    /// Whether no sink is set up.
+    pub fn is_empty(&self) -> bool {
+        !self.console
+            && self.jsonl.is_none()
+            && self.otlp.is_none()
+            && self.webhook.is_none()
+            && self.langfuse.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonlSinkConfig {
+    /// File to append to; `~` expands to the home directory
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OtlpSinkConfig {
+    /// Collector endpoint, `http://localhost:4318` when unset
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// Service name reported to the collector, "zeroclaw" when unset
+    #[serde(default)]
+    pub service_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebhookSinkConfig {
+    pub url: String,
+
+    /// Extra request headers, e.g. an `Authorization` token
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Most events sent in one request (a JSON array)
+    #[serde(default = "default_webhook_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_webhook_batch_size() -> usize {
+    50
+}
+
+/// A Langfuse project, or any server speaking its ingestion API. Each turn
+/// becomes a trace, grouped by session where the front end has one.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LangfuseSinkConfig {
+    #[serde(default = "default_langfuse_host")]
+    pub host: String,
+
+    /// Project public key; `LANGFUSE_PUBLIC_KEY` when unset
+    #[serde(default)]
+    pub public_key: Option<String>,
+
+    /// Project secret key; `LANGFUSE_SECRET_KEY` when unset
+    #[serde(default)]
+    pub secret_key: Option<String>,
+
+    /// Version label on every trace, to compare prompt or config changes;
+    /// "zeroclaw <version>" when unset
+    #[serde(default)]
+    pub release: Option<String>,
+
+    /// Most events sent in one request
+    #[serde(default = "default_webhook_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_langfuse_host() -> String {
+    "https://cloud.langfuse.com".into()
+}
+
+// ── Autonomy / Security ──────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AutonomyConfig {
+    pub level: AutonomyLevel,
+    pub workspace_only: bool,
+    pub allowed_commands: Vec<String>,
+    /// Paths the agent may neither read nor write: prefixes like `/etc`, or
+    /// globs like `**/*.pem` (`*` and `?` within a path segment, `**` across)
+    pub forbidden_paths: Vec<String>,
+    pub max_actions_per_hour: u32,
+    pub max_cost_per_day_cents: u32,
+
+    /// Require explicit approval for medium-risk shell commands.
+    #[serde(default = "default_true")]
+    pub require_approval_for_medium_risk: bool,
+
+    /// Block high-risk shell commands even if allowlisted.
+    #[serde(default = "default_true")]
+    pub block_high_risk_commands: bool,
+
+    /// Directories outside the workspace that path tools may use (e.g.
+    /// `~/docs`, a monorepo checkout). Read-only unless `access = "write"`.
+    #[serde(default)]
+    pub reference_roots: Vec<ReferenceRootConfig>,
+
+    /// Autonomy levels at which a turn starts with a plan (steps, tools,
+    /// risks) that the user approves before any tool runs, e.g.
+    /// `["supervised"]`.
+    #[serde(default)]
+    pub plan_levels: Vec<AutonomyLevel>,
+
+    /// Preview instead of act: tools that would change files, run commands
+    /// or act outside the agent report what they would have done.
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Show the diff of each `file_write` / `file_edit` change and wait for
+    /// the user to approve it
+    #[serde(default)]
+    pub approve_file_changes: bool,
+
+    /// Files the agent may read but never modify, in the workspace too:
+    /// globs like `**/.env`, or path prefixes
+    #[serde(default = "default_write_protected_paths")]
+    pub write_protected_paths: Vec<String>,
+
+    /// Let the agent read private keys (`**/id_rsa*`, `**/*.pem`, ...),
+    /// which are otherwise forbidden whatever `forbidden_paths` says
+    #[serde(default)]
+    pub allow_key_files: bool,
+}
+
+fn default_write_protected_paths() -> Vec<String> {
+    DEFAULT_WRITE_PROTECTED
+        .iter()
+        .map(|glob| (*glob).to_string())
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReferenceRootConfig {
+    /// Absolute path or `~/...`
+    pub path: String,
+    /// "read" (default) or "write"
+    #[serde(default)]
+    pub access: PathAccess,
+}
+
+impl Default for AutonomyConfig {
+    fn default() -> Self {
+        Self {
+            level: AutonomyLevel::Supervised,
+            workspace_only: true,
+            allowed_commands: vec![
+                "git".into(),
+                "npm".into(),
+                "cargo".into(),
+                "ls".into(),
+                "cat".into(),
+                "grep".into(),
+                "find".into(),
+                "echo".into(),
+                "pwd".into(),
+                "wc".into(),
+                "head".into(),
+                "tail".into(),
+            ],
+            forbidden_paths: vec![
+                "/etc".into(),
+                "/root".into(),
+                "/home".into(),
+                "/usr".into(),
+                "/bin".into(),
+                "/sbin".into(),
+                "/lib".into(),
+                "/opt".into(),
+                "/boot".into(),
+                "/dev".into(),
+                "/proc".into(),
+                "/sys".into(),
+                "/var".into(),
+                "/tmp".into(),
+                "~/.ssh".into(),
+                "~/.gnupg".into(),
+                "~/.aws".into(),
+                "~/.config".into(),
+            ],
+            max_actions_per_hour: 10000,
+            max_cost_per_day_cents: 10000,
+            require_approval_for_medium_risk: true,
+            block_high_risk_commands: true,
+            reference_roots: Vec::new(),
+            plan_levels: Vec::new(),
+            dry_run: false,
+            approve_file_changes: false,
+            write_protected_paths: default_write_protected_paths(),
+            allow_key_files: false,
+        }
+    }
+}
+
+impl AutonomyConfig {
+    /// Whether turns at the configured level are planned before they act.
+    pub fn plans_first(&self) -> bool {
+        self.plan_levels.contains(&self.level)
+    }
+}
+
+// ── Runtime ──────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RuntimeConfig {
+    /// Runtime kind (`native` | `docker`).
+    #[serde(default = "default_runtime_kind")]
+    pub kind: String,
+
+    /// Docker runtime settings (used when `kind = "docker"`).
+    #[serde(default)]
+    pub docker: DockerRuntimeConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DockerRuntimeConfig {
+    /// Runtime image used to execute shell commands.
+    #[serde(default = "default_docker_image")]
+    pub image: String,
+
+    /// Docker network mode (`none`, `bridge`, etc.).
+    #[serde(default = "default_docker_network")]
+    pub network: String,
+
+    /// Optional memory limit in MB (`None` = no explicit limit).
+    #[serde(default = "default_docker_memory_limit_mb")]
+    pub memory_limit_mb: Option<u64>,
+
+    /// Optional CPU limit (`None` = no explicit limit).
+    #[serde(default = "default_docker_cpu_limit")]
+    pub cpu_limit: Option<f64>,
+
+    /// Mount root filesystem as read-only.
+    #[serde(default = "default_true")]
+    pub read_only_rootfs: bool,
+
+    /// Mount configured workspace into `/workspace`.
+    #[serde(default = "default_true")]
+    pub mount_workspace: bool,
+
+    /// Optional workspace root allowlist for Docker mount validation.
+    #[serde(default)]
+    pub allowed_workspace_roots: Vec<String>,
+}
+
+fn default_runtime_kind() -> String {
+    "native".into()
+}
+
+fn default_docker_image() -> String {
+    "alpine:3.20".into()
+}
+
+fn default_docker_network() -> String {
+    "none".into()
+}
+
+fn default_docker_memory_limit_mb() -> Option<u64> {
+    Some(512)
+}
+
+fn default_docker_cpu_limit() -> Option<f64> {
+    Some(1.0)
+}
+
+impl Default for DockerRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            image: default_docker_image(),
+            network: default_docker_network(),
+            memory_limit_mb: default_docker_memory_limit_mb(),
+            cpu_limit: default_docker_cpu_limit(),
+            read_only_rootfs: true,
+            mount_workspace: true,
+            allowed_workspace_roots: Vec::new(),
+        }
+    }
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            kind: default_runtime_kind(),
+            docker: DockerRuntimeConfig::default(),
+        }
+    }
+}
+
+// ── Reliability / supervision ────────────────────────────────────
+
+/// Retry, fallback and restart policy.
+///
+/// ```toml
+/// [reliability]
+/// provider_retries = 2
+/// provider_backoff_ms = 500          # doubles per retry, with jitter
+/// provider_backoff_max_ms = 10000
+/// retry_status_codes = [408, 429, 500, 502, 503, 504]
+/// circuit_breaker_threshold = 5      # 0 disables the breaker
+/// circuit_breaker_cooldown_secs = 30
+/// fallback_providers = ["anthropic"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReliabilityConfig {
+    /// Retries per provider before failing over.
+    #[serde(default = "default_provider_retries")]
+    pub provider_retries: u32,
+    /// Base backoff (ms) for provider retry delay.
+    #[serde(default = "default_provider_backoff_ms")]
+    pub provider_backoff_ms: u64,
+    /// Upper bound (ms) on one provider retry delay.
+    #[serde(default = "default_provider_backoff_max_ms")]
+    pub provider_backoff_max_ms: u64,
+    /// HTTP statuses worth retrying; other statuses fail over at once.
+    /// Errors without a status (timeouts, resets) are always retried.
+    #[serde(default = "default_retry_status_codes")]
+    pub retry_status_codes: Vec<u16>,
+    /// Consecutive retryable failures after which a provider is skipped
+    /// until the cooldown passes; 0 disables the breaker.
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: u32,
+    /// Seconds an open circuit waits before letting a trial call through.
+    #[serde(default = "default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
+    /// Fallback provider chain (e.g. `["anthropic", "openai"]`).
+    #[serde(default)]
+    pub fallback_providers: Vec<String>,
+    /// Initial backoff for channel/daemon restarts.
+    #[serde(default = "default_channel_backoff_secs")]
+    pub channel_initial_backoff_secs: u64,
+    /// Max backoff for channel/daemon restarts.
+    #[serde(default = "default_channel_backoff_max_secs")]
+    pub channel_max_backoff_secs: u64,
+    /// Scheduler polling cadence in seconds.
+    #[serde(default = "default_scheduler_poll_secs")]
+    pub scheduler_poll_secs: u64,
+    /// Max retries for cron job execution attempts.
+    #[serde(default = "default_scheduler_retries")]
+    pub scheduler_retries: u32,
+}
+
+fn default_provider_retries() -> u32 {
+    2
+}
+
+fn default_provider_backoff_ms() -> u64 {
+    500
+}
+
+fn default_provider_backoff_max_ms() -> u64 {
+    10_000
+}
+
+fn default_retry_status_codes() -> Vec<u16> {
+    vec![408, 429, 500, 502, 503, 504]
+}
+
+fn default_circuit_breaker_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_breaker_cooldown_secs() -> u64 {
+    30
+}
+
+fn default_channel_backoff_secs() -> u64 {
+    2
+}
+
+fn default_channel_backoff_max_secs() -> u64 {
+    60
+}
+
+fn default_scheduler_poll_secs() -> u64 {
+    15
+}
+
+fn default_scheduler_retries() -> u32 {
+    2
+}
+
+impl Default for ReliabilityConfig {
+    fn default() -> Self {
+        Self {
+            provider_retries: default_provider_retries(),
+            provider_backoff_ms: default_provider_backoff_ms(),
+            provider_backoff_max_ms: default_provider_backoff_max_ms(),
+            retry_status_codes: default_retry_status_codes(),
+            circuit_breaker_threshold: default_circuit_breaker_threshold(),
+            circuit_breaker_cooldown_secs: default_circuit_breaker_cooldown_secs(),
+            fallback_providers: Vec::new(),
+            channel_initial_backoff_secs: default_channel_backoff_secs(),
+            channel_max_backoff_secs: default_channel_backoff_max_secs(),
+            scheduler_poll_secs: default_scheduler_poll_secs(),
+            scheduler_retries: default_scheduler_retries(),
+        }
+    }
+}
+
+// ── Providers ────────────────────────────────────────────────────
+
+/// Key and endpoint for one provider, so a config can use several at once.
+///
+/// ```toml
+/// [providers.openrouter]
+/// api_key = "sk-or-..."
+///
+/// [providers.anthropic]
+/// api_key = "${ANTHROPIC_API_KEY}"
+///
+/// [providers.ollama]
+/// base_url = "http://gpu-box:11434"
+/// ```
+///
+/// A key here wins over the top-level `api_key` and over environment
+/// variables. A `base_url` points a provider at another endpoint; under a
+/// name no provider has, it adds an OpenAI-compatible one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ProviderConfig {
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+// ── Profiles ─────────────────────────────────────────────────────
+
+/// Overrides applied together when a profile is selected, so one config
+/// can serve several contexts.
+///
+/// ```toml
+/// [profiles.work]
+/// provider = "anthropic"
+/// model = "claude-sonnet-4-20250514"
+/// memory_namespace = "work"
+/// identity = { instruction_files = { "WORK.md" = 0 } }
+/// autonomy = { level = "supervised", allowed_commands = ["git", "cargo"] }
+///
+/// [profiles.personal]
+/// model = "gpt-4o-mini"
+/// memory_namespace = "personal"
+/// ```
+///
+/// `identity` and `autonomy` are merged key by key over the `[identity]` and
+/// `[autonomy]` sections; unset fields keep the top-level values.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProfileConfig {
+    /// Replaces `default_provider`
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Replaces `default_model`
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Replaces `[memory] namespace`
+    #[serde(default)]
+    pub memory_namespace: Option<String>,
+    /// Keys of `[identity]` to override
+    #[serde(default)]
+    #[schemars(with = "Option<serde_json::Map<String, serde_json::Value>>")]
+    pub identity: Option<toml::Table>,
+    /// Keys of `[autonomy]` to override
+    #[serde(default)]
+    #[schemars(with = "Option<serde_json::Map<String, serde_json::Value>>")]
+    pub autonomy: Option<toml::Table>,
+}
+
+// ── Model routing ────────────────────────────────────────────────
+
+/// Route a task hint to a specific provider + model.
+///
+/// ```toml
+/// [[model_routes]]
+/// hint = "reasoning"
+/// provider = "openrouter"
+/// model = "anthropic/claude-opus-4-20250514"
+///
+/// [[model_routes]]
+/// hint = "fast"
+/// provider = "groq"
+/// model = "llama-3.3-70b-versatile"
+/// ```
+///
+/// Usage: pass `hint:reasoning` as the model parameter to route the request.
+///
+/// Routes with a `match` table are also picked automatically for requests
+/// on the default model (or `hint:auto`); the first route whose conditions
+/// all hold wins:
+///
+/// ```toml
+/// [[model_routes]]
+/// hint = "cheap"
+/// provider = "groq"
+/// model = "llama-3.1-8b-instant"
+/// match = { max_chars = 280, code = false, task = "chat" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelRouteConfig {
+    /// Task hint name (e.g. "reasoning", "fast", "code", "summarize")
+    pub hint: String,
+    /// Provider to route to (must match a known provider name)
+    pub provider: String,
+    /// Model to use with that provider
+    pub model: String,
+    /// Optional API key override for this route's provider
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Heuristics for automatic routing (None = only reachable by hint)
+    #[serde(default, rename = "match")]
+    pub matches: Option<RouteMatch>,
+}
+
+/// Conditions on the request for automatic routing; unset ones always hold.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RouteMatch {
+    /// The user's message has at least this many characters
+    #[serde(default)]
+    pub min_chars: Option<usize>,
+    /// The user's message has at most this many characters
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+    /// The user's message does (true) or does not (false) contain code
+    #[serde(default)]
+    pub code: Option<bool>,
+    /// Plain exchange or a step of a tool loop
+    #[serde(default)]
+    pub task: Option<TaskKind>,
+}
+
+/// What kind of request the model is answering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskKind {
+    /// Answering the user directly
+    Chat,
+    /// Continuing after tool results
+    Tools,
+}
+
+// ── Model profiles ───────────────────────────────────────────────
+
+/// How tool parameters are presented in the system prompt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolSchemaFormat {
+    /// Full JSON Schema per tool
+    #[default]
+    Json,
+    /// TypeScript-style signatures, roughly half the size
+    Compact,
+}
+
+/// Prompt settings and per-request spending caps for models matching `model`.
+///
+/// ```toml
+/// [[model_profiles]]
+/// model = "llama-3*"        # exact name, or prefix ending in `*`
+/// tool_schema = "compact"
+/// max_tool_calls = 20       # requests may lower these, never raise them
+/// input_price = 0.15        # USD per million tokens, for `zeroclaw tui`
+/// output_price = 0.6
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelProfileConfig {
+    pub model: String,
+    #[serde(default)]
+    pub tool_schema: ToolSchemaFormat,
+    /// Default cap on estimated tokens sent and received in one request
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+    /// Default cap on tool executions in one request
+    #[serde(default)]
+    pub max_tool_calls: Option<u32>,
+    /// Default wall-clock limit for one request, in seconds
+    #[serde(default)]
+    pub max_seconds: Option<u64>,
+    /// Default cap on model calls in one request
+    #[serde(default)]
+    pub max_iterations: Option<u32>,
+    /// USD per million input tokens, for the TUI cost meter
+    #[serde(default)]
+    pub input_price: Option<f64>,
+    /// USD per million output tokens
+    #[serde(default)]
+    pub output_price: Option<f64>,
+}
+
+impl ModelProfileConfig {
+    pub fn matches(&self, model: &str) -> bool {
+        match self.model.strip_suffix('*') {
+            Some(prefix) => model.starts_with(prefix),
+            None => self.model == model,
+        }
+    }
+}
+
+// ── Triggers (agent runs started by events) ─────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TriggersConfig {
+    #[serde(default)]
+    pub fswatch: FsWatchConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsWatchConfig {
+    /// Watch the workspace from the daemon and run the agent on matching changes
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between workspace scans
+    #[serde(default = "default_fswatch_poll_secs")]
+    pub poll_secs: u64,
+    /// Directory names never descended into
+    #[serde(default = "default_fswatch_ignore_dirs")]
+    pub ignore_dirs: Vec<String>,
+    /// Glob → prompt rules; every rule with a matching change fires once per batch
+    #[serde(default)]
+    pub rules: Vec<FsWatchRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FsWatchRule {
+    /// Workspace-relative glob (`*`, `?`, `**`), e.g. "docs/**/*.md"
+    pub glob: String,
+    /// Prompt for the agent run; the changed paths are appended
+    pub prompt: String,
+}
+
+fn default_fswatch_poll_secs() -> u64 {
+    2
+}
+
+fn default_fswatch_ignore_dirs() -> Vec<String> {
+    [".git", "target", "node_modules", "sessions"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+impl Default for FsWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_secs: default_fswatch_poll_secs(),
+            ignore_dirs: default_fswatch_ignore_dirs(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+// ── Knowledge base ──────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct KnowledgeConfig {
+    /// Index documents into memory, enable `kb_search` and inject matches
+    /// into each turn's context (requires the sqlite memory backend)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directories to index: absolute, `~/...`, or relative to the workspace
+    #[serde(default = "default_knowledge_dirs")]
+    pub dirs: Vec<String>,
+    /// File extensions (without the dot) that are indexed
+    #[serde(default = "default_knowledge_extensions")]
+    pub extensions: Vec<String>,
+    /// Files larger than this are skipped
+    #[serde(default = "default_knowledge_max_file_kb")]
+    pub max_file_kb: u64,
+    /// Knowledge chunks added to each message's context (0 = `kb_search` only)
+    #[serde(default = "default_knowledge_context_chunks")]
+    pub context_chunks: usize,
+    /// Minutes between re-index passes while the daemon runs
+    #[serde(default = "default_knowledge_reindex_minutes")]
+    pub reindex_interval_minutes: u32,
+}
+
+fn default_knowledge_dirs() -> Vec<String> {
+    vec!["knowledge".into()]
+}
+
+fn default_knowledge_extensions() -> Vec<String> {
+    ["md", "markdown", "txt", "rst", "org"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_knowledge_max_file_kb() -> u64 {
+    512
+}
+
+fn default_knowledge_context_chunks() -> usize {
+    3
+}
+
+fn default_knowledge_reindex_minutes() -> u32 {
+    15
+}
+
+impl Default for KnowledgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dirs: default_knowledge_dirs(),
+            extensions: default_knowledge_extensions(),
+            max_file_kb: default_knowledge_max_file_kb(),
+            context_chunks: default_knowledge_context_chunks(),
+            reindex_interval_minutes: default_knowledge_reindex_minutes(),
+        }
+    }
+}
+
+// ── Skill registry ──────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SkillRegistryConfig {
+    /// JSON index of installable skills; unset disables `skills search`
+    #[serde(default)]
+    pub index_url: Option<String>,
+    /// Hex Ed25519 public key. The index must come with a valid signature
+    /// at `<index_url>.sig` unless `allow_unsigned` is set.
+    #[serde(default)]
+    pub public_key: Option<String>,
+    #[serde(default)]
+    pub allow_unsigned: bool,
+}
+
+// ── Work queue ──────────────────────────────────────────────────
+
+/// Limits on agent turns running at once across the gateway and channels.
+///
+/// ```toml
+/// [queue]
+/// max_concurrent_turns = 4
+/// max_queued = 32
+/// ```
+///
+/// Waiting requests are served round-robin per channel, so one busy chat
+/// cannot starve the others. Past `max_queued`, the gateway answers 429
+/// with `Retry-After` and channels reply that the agent is busy.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueueConfig {
+    #[serde(default = "default_max_concurrent_turns")]
+    pub max_concurrent_turns: usize,
+    #[serde(default = "default_max_queued")]
+    pub max_queued: usize,
+    /// Seconds suggested in `Retry-After` when the queue is full
+    #[serde(default = "default_queue_retry_after_secs")]
+    pub retry_after_secs: u64,
+}
+
+fn default_max_concurrent_turns() -> usize {
+    4
+}
+
+fn default_max_queued() -> usize {
+    32
+}
+
+fn default_queue_retry_after_secs() -> u64 {
+    5
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_turns: default_max_concurrent_turns(),
+            max_queued: default_max_queued(),
+            retry_after_secs: default_queue_retry_after_secs(),
+        }
+    }
+}
+
+// ── Response cache ──────────────────────────────────────────────
+
+/// In-memory cache for provider answers to requests at temperature 0.
+///
+/// ```toml
+/// [response_cache]
+/// enabled = true
+/// ttl_secs = 3600
+/// max_entries = 256
+/// ```
+///
+/// Entries are keyed on the model and the exact messages, so a scheduled
+/// task or test sending the same prompt again gets the stored answer
+/// without a provider call. Requests at any other temperature always go
+/// to the provider.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ResponseCacheConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long an answer stays usable
+    #[serde(default = "default_response_cache_ttl_secs")]
+    pub ttl_secs: u64,
+    /// Oldest answers are dropped past this many
+    #[serde(default = "default_response_cache_max_entries")]
+    pub max_entries: usize,
+}
+
+fn default_response_cache_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_response_cache_max_entries() -> usize {
+    256
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: default_response_cache_ttl_secs(),
+            max_entries: default_response_cache_max_entries(),
+        }
+    }
+}
+
+// ── Azure OpenAI ────────────────────────────────────────────────
+
+/// Settings for `default_provider = "azure"`.
+///
+/// ```toml
+/// [azure_openai]
+/// endpoint = "https://my-resource.openai.azure.com"
+/// api_version = "2024-10-21"
+/// auth = "azure_ad"            # or "api_key" (default)
+/// tenant_id = "..."
+/// client_id = "..."
+/// client_secret = "..."        # or AZURE_CLIENT_SECRET
+///
+/// [azure_openai.deployments]
+/// "gpt-4o" = "prod-gpt4o"
+/// ```
+///
+/// Requests go to the deployment mapped from the model name; a model with
+/// no entry is taken to be a deployment name itself.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AzureOpenAiConfig {
+    /// Resource endpoint; `AZURE_OPENAI_ENDPOINT` when unset
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default = "default_azure_api_version")]
+    pub api_version: String,
+    /// Model name to deployment name
+    #[serde(default)]
+    pub deployments: BTreeMap<String, String>,
+    #[serde(default)]
+    pub auth: AzureAuth,
+    /// Entra ID app registration used when `auth = "azure_ad"` and no
+    /// `AZURE_OPENAI_AD_TOKEN` is set
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+}
+
+fn default_azure_api_version() -> String {
+    "2024-10-21".into()
+}
+
+impl Default for AzureOpenAiConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            api_version: default_azure_api_version(),
+            deployments: BTreeMap::new(),
+            auth: AzureAuth::default(),
+            tenant_id: None,
+            client_id: None,
+            client_secret: None,
+        }
+    }
+}
+
+/// How Azure `OpenAI` requests authenticate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AzureAuth {
+    /// `api-key` header with the configured API key or `AZURE_OPENAI_API_KEY`
+    #[default]
+    ApiKey,
+    /// Entra ID (Azure AD) bearer token
+    AzureAd,
+}
+
+// ── Gemini ──────────────────────────────────────────────────────
+
+/// Settings for `default_provider = "gemini"`.
+///
+/// ```toml
+/// [gemini]
+/// max_output_tokens = 8192
+///
+/// [gemini.safety_settings]      # harm category -> block threshold
+/// dangerous_content = "block_only_high"
+/// harassment = "block_none"
+/// ```
+///
+/// Categories and thresholds may be given in the API's own spelling
+/// (`HARM_CATEGORY_HARASSMENT`, `BLOCK_ONLY_HIGH`) or lowercase without the
+/// `HARM_CATEGORY_` prefix. Categories left out keep Google's defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GeminiConfig {
+    #[serde(default = "default_gemini_max_output_tokens")]
+    pub max_output_tokens: u32,
+    #[serde(default)]
+    pub safety_settings: BTreeMap<String, String>,
+}
+
+fn default_gemini_max_output_tokens() -> u32 {
+    8192
+}
+
+impl Default for GeminiConfig {
+    fn default() -> Self {
+        Self {
+            max_output_tokens: default_gemini_max_output_tokens(),
+            safety_settings: BTreeMap::new(),
+        }
+    }
+}
 
-    /// Optional workspace root allowlist for Docker mount validation.
+// ── Tool output ─────────────────────────────────────────────────
+
+/// What the agent does with tool outputs too large for the context window.
+///
+/// ```toml
+/// [tool_output]
+/// max_tokens = 4000
+/// mode = "summarize"               # or "truncate"
+/// summary_model = "openai/gpt-4o-mini"
+/// ```
+///
+/// Outputs over `max_tokens` are cut to their head and tail, or summarized
+/// by `summary_model`. Either way the full output is kept and the model can
+/// page through it with the `fetch_full_output` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolOutputConfig {
+    /// Largest tool output passed to the model as is (0 = no limit)
+    #[serde(default = "default_tool_output_max_tokens")]
+    pub max_tokens: u64,
     #[serde(default)]
-    pub allowed_workspace_roots: Vec<String>,
+    pub mode: ToolOutputMode,
+    /// Model that writes summaries (defaults to the turn's model)
+    #[serde(default)]
+    pub summary_model: Option<String>,
 }
 
-fn default_runtime_kind() -> String {
-    "native".into()
+fn default_tool_output_max_tokens() -> u64 {
+    4000
 }
 
-fn default_docker_image() -> String {
-    "alpine:3.20".into()
+impl Default for ToolOutputConfig {
+    fn default() -> Self {
+        Self {
+            max_tokens: default_tool_output_max_tokens(),
+            mode: ToolOutputMode::default(),
+            summary_model: None,
+        }
+    }
 }
 
-fn default_docker_network() -> String {
-    "none".into()
+/// How an oversized tool output is shortened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolOutputMode {
+    /// Keep the first and last lines
+    #[default]
+    Truncate,
+    /// Replace the output with a summary from `summary_model`
+    Summarize,
 }
 
-fn default_docker_memory_limit_mb() -> Option<u64> {
-    Some(512)
+// ── Reply rendering ─────────────────────────────────────────────
+
+/// How agent replies are displayed.
+///
+/// ```toml
+/// [render]
+/// markdown = true                  # false: print replies as plain text
+/// theme = "base16-ocean.dark"      # syntect theme for code blocks
+/// ```
+///
+/// With `markdown` on, the interactive terminal styles replies and
+/// highlights fenced code, and Telegram receives them as HTML.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RenderConfig {
+    #[serde(default = "default_true")]
+    pub markdown: bool,
+    #[serde(default = "default_render_theme")]
+    pub theme: String,
 }
 
-fn default_docker_cpu_limit() -> Option<f64> {
-    Some(1.0)
+fn default_render_theme() -> String {
+    "base16-ocean.dark".into()
 }
 
-impl Default for DockerRuntimeConfig {
+impl Default for RenderConfig {
     fn default() -> Self {
         Self {
-            image: default_docker_image(),
-            network: default_docker_network(),
-            memory_limit_mb: default_docker_memory_limit_mb(),
-            cpu_limit: default_docker_cpu_limit(),
-            read_only_rootfs: true,
-            mount_workspace: true,
-            allowed_workspace_roots: Vec::new(),
+            markdown: true,
+            theme: default_render_theme(),
         }
     }
 }
 
-impl Default for RuntimeConfig {
+// ── Checkpoints ─────────────────────────────────────────────────
+
+/// Workspace snapshots taken before tools change files.
+///
+/// ```toml
+/// [checkpoints]
+/// enabled = true                   # false: no snapshots, nothing to undo
+/// ```
+///
+/// `file_write`, `file_edit` and shell commands that are not read-only first
+/// snapshot the workspace into `~/.zeroclaw/checkpoints/<hash>` (needs git). The
+/// `checkpoints` tool and `/undo` roll the latest change back.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CheckpointConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+// ── Vision fallback ─────────────────────────────────────────────
+
+/// Vision-capable model used when the routed model cannot see images.
+///
+/// ```toml
+/// [vision]
+/// fallback_provider = "openrouter"
+/// fallback_model = "openai/gpt-4o-mini"
+/// ```
+///
+/// Images in a turn are described by this model and the description is
+/// passed to the main model as text.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct VisionConfig {
+    /// Provider for the fallback model (defaults to `default_provider`)
+    #[serde(default)]
+    pub fallback_provider: Option<String>,
+    /// Vision-capable model; fallback is disabled when unset
+    #[serde(default)]
+    pub fallback_model: Option<String>,
+    /// Optional API key override for the fallback provider
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+// ── Web search ──────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebSearchConfig {
+    /// Enable the `web_search` tool
+    #[serde(default)]
+    pub enabled: bool,
+    /// "duckduckgo" (no key needed) | "brave" | "searxng"
+    #[serde(default = "default_web_search_engine")]
+    pub engine: String,
+    /// Brave Search API key (falls back to `BRAVE_API_KEY`)
+    #[serde(default)]
+    pub brave_api_key: Option<String>,
+    /// Base URL of a `SearXNG` instance with the JSON format enabled
+    #[serde(default)]
+    pub searxng_url: Option<String>,
+    /// Upper bound on results returned to the agent
+    #[serde(default = "default_web_search_max_results")]
+    pub max_results: usize,
+    /// Request timeout in seconds
+    #[serde(default = "default_web_search_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_web_search_engine() -> String {
+    "duckduckgo".into()
+}
+
+fn default_web_search_max_results() -> usize {
+    5
+}
+
+fn default_web_search_timeout_secs() -> u64 {
+    15
+}
+
+impl Default for WebSearchConfig {
     fn default() -> Self {
         Self {
-            kind: default_runtime_kind(),
-            docker: DockerRuntimeConfig::default(),
+            enabled: false,
+            engine: default_web_search_engine(),
+            brave_api_key: None,
+            searxng_url: None,
+            max_results: default_web_search_max_results(),
+            timeout_secs: default_web_search_timeout_secs(),
         }
     }
 }
 
-// ── Reliability / supervision ────────────────────────────────────
+// ── Calendar (CalDAV) ───────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ReliabilityConfig {
-    /// Retries per provider before failing over.
-    #[serde(default = "default_provider_retries")]
-    pub provider_retries: u32,
-    /// Base backoff (ms) for provider retry delay.
-    #[serde(default = "default_provider_backoff_ms")]
-    pub provider_backoff_ms: u64,
-    /// Fallback provider chain (e.g. `["anthropic", "openai"]`).
+/// `CalDAV` accounts for the `calendar` tool.
+///
+/// ```toml
+/// [calendar]
+/// enabled = true
+///
+/// [[calendar.accounts]]
+/// name = "personal"
+/// url = "https://cloud.example.com/remote.php/dav/calendars/alice/personal/"
+/// username = "alice"
+/// password = "enc2:..."   # app password, encrypted with the secret store
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CalendarConfig {
+    /// Enable the `calendar` tool
     #[serde(default)]
-    pub fallback_providers: Vec<String>,
-    /// Initial backoff for channel/daemon restarts.
-    #[serde(default = "default_channel_backoff_secs")]
-    pub channel_initial_backoff_secs: u64,
-    /// Max backoff for channel/daemon restarts.
-    #[serde(default = "default_channel_backoff_max_secs")]
-    pub channel_max_backoff_secs: u64,
-    /// Scheduler polling cadence in seconds.
-    #[serde(default = "default_scheduler_poll_secs")]
-    pub scheduler_poll_secs: u64,
-    /// Max retries for cron job execution attempts.
-    #[serde(default = "default_scheduler_retries")]
-    pub scheduler_retries: u32,
+    pub enabled: bool,
+    /// Days ahead that `list` covers when no range is given
+    #[serde(default = "default_calendar_lookahead_days")]
+    pub lookahead_days: u32,
+    /// Request timeout in seconds
+    #[serde(default = "default_calendar_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Calendars to read and write; the first one is the default
+    #[serde(default)]
+    pub accounts: Vec<CalendarAccountConfig>,
 }
 
-fn default_provider_retries() -> u32 {
-    2
+/// One `CalDAV` calendar collection.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CalendarAccountConfig {
+    pub name: String,
+    /// Calendar collection URL (not the principal or server root)
+    pub url: String,
+    pub username: String,
+    /// Password or app token; `enc2:` values are decrypted with the secret store
+    #[serde(default)]
+    pub password: String,
 }
 
-fn default_provider_backoff_ms() -> u64 {
-    500
+fn default_calendar_lookahead_days() -> u32 {
+    7
 }
 
-fn default_channel_backoff_secs() -> u64 {
-    2
+fn default_calendar_timeout_secs() -> u64 {
+    20
 }
 
-fn default_channel_backoff_max_secs() -> u64 {
-    60
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lookahead_days: default_calendar_lookahead_days(),
+            timeout_secs: default_calendar_timeout_secs(),
+            accounts: Vec::new(),
+        }
+    }
 }
 
-fn default_scheduler_poll_secs() -> u64 {
-    15
+// ── Numeric answer verification ─────────────────────────────────
+
+/// Re-checks computed figures in tool-grounded answers before delivery.
+///
+/// After a turn that used tools, the model lists the numbers it derived
+/// together with the arithmetic behind them; each one is recomputed with the
+/// calculator and mismatches are corrected in the final answer.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VerifierConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Model used to extract and correct claims (defaults to the turn's model)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Maximum number of figures checked per answer
+    #[serde(default = "default_verifier_max_claims")]
+    pub max_claims: usize,
 }
 
-fn default_scheduler_retries() -> u32 {
-    2
+fn default_verifier_max_claims() -> usize {
+    10
 }
 
-impl Default for ReliabilityConfig {
+impl Default for VerifierConfig {
     fn default() -> Self {
         Self {
-            provider_retries: default_provider_retries(),
-            provider_backoff_ms: default_provider_backoff_ms(),
-            fallback_providers: Vec::new(),
-            channel_initial_backoff_secs: default_channel_backoff_secs(),
-            channel_max_backoff_secs: default_channel_backoff_max_secs(),
-            scheduler_poll_secs: default_scheduler_poll_secs(),
-            scheduler_retries: default_scheduler_retries(),
+            enabled: false,
+            model: None,
+            max_claims: default_verifier_max_claims(),
         }
     }
 }
 
-// ── Model routing ────────────────────────────────────────────────
+// ── Guardrails ───────────────────────────────────────────────────
 
-/// Route a task hint to a specific provider + model.
+/// Checks run on every final reply before it is delivered, on any channel.
 ///
 /// ```toml
-/// [[model_routes]]
-/// hint = "reasoning"
-/// provider = "openrouter"
-/// model = "anthropic/claude-opus-4-20250514"
+/// [guardrails]
+/// enabled = true
+/// judge_policy = "Never give medical dosage advice."
 ///
-/// [[model_routes]]
-/// hint = "fast"
-/// provider = "groq"
-/// model = "llama-3.3-70b-versatile"
-/// ```
+/// [[guardrails.rules]]
+/// name = "aws-keys"
+/// pattern = "AKIA[0-9A-Z]{16}"
+/// action = "redact"
 ///
-/// Usage: pass `hint:reasoning` as the model parameter to route the request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ModelRouteConfig {
-    /// Task hint name (e.g. "reasoning", "fast", "code", "summarize")
-    pub hint: String,
-    /// Provider to route to (must match a known provider name)
-    pub provider: String,
-    /// Model to use with that provider
-    pub model: String,
-    /// Optional API key override for this route's provider
+/// [[guardrails.rules]]
+/// name = "status-json"
+/// json_keys = ["status", "summary"]
+/// action = "regenerate"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GuardrailsConfig {
     #[serde(default)]
-    pub api_key: Option<String>,
+    pub enabled: bool,
+    #[serde(default)]
+    pub rules: Vec<GuardrailRule>,
+    /// Policy a model judges each reply against; unset skips the judge
+    #[serde(default)]
+    pub judge_policy: Option<String>,
+    /// Model used as the judge (defaults to the turn's model)
+    #[serde(default)]
+    pub judge_model: Option<String>,
+    /// What to do with replies the judge flags (`redact` acts as `block`)
+    #[serde(default)]
+    pub judge_action: GuardrailAction,
+    /// Sent instead of a blocked reply
+    #[serde(default = "default_guardrails_blocked_message")]
+    pub blocked_message: String,
+}
+
+/// One content check. Set `pattern`, or `json_keys` for replies that must
+/// be a JSON object with those keys.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GuardrailRule {
+    pub name: String,
+    /// Regular expression the reply must not match
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Keys the reply, parsed as a JSON object, must have
+    #[serde(default)]
+    pub json_keys: Vec<String>,
+    #[serde(default)]
+    pub action: GuardrailAction,
+}
+
+/// What happens to a reply that breaks a guardrail.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardrailAction {
+    /// Replace the reply with `blocked_message`
+    #[default]
+    Block,
+    /// Replace each `pattern` match with `[redacted]`; blocks for other checks
+    Redact,
+    /// Ask the model once for a reply that passes, blocking if it still fails
+    Regenerate,
+}
+
+fn default_guardrails_blocked_message() -> String {
+    "Sorry, I can't share that response.".into()
+}
+
+impl Default for GuardrailsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules: Vec::new(),
+            judge_policy: None,
+            judge_model: None,
+            judge_action: GuardrailAction::default(),
+            blocked_message: default_guardrails_blocked_message(),
+        }
+    }
 }
 
 // ── Heartbeat ────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HeartbeatConfig {
     pub enabled: bool,
     pub interval_minutes: u32,
@@ -616,7 +2750,7 @@ impl Default for HeartbeatConfig {
 
 // ── Tunnel ──────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TunnelConfig {
     /// "none", "cloudflare", "tailscale", "ngrok", "custom"
     pub provider: String,
@@ -646,13 +2780,13 @@ impl Default for TunnelConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CloudflareTunnelConfig {
     /// Cloudflare Tunnel token (from Zero Trust dashboard)
     pub token: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TailscaleTunnelConfig {
     /// Use Tailscale Funnel (public internet) vs Serve (tailnet only)
     #[serde(default)]
@@ -661,7 +2795,7 @@ pub struct TailscaleTunnelConfig {
     pub hostname: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NgrokTunnelConfig {
     /// ngrok auth token
     pub auth_token: String,
@@ -669,7 +2803,7 @@ pub struct NgrokTunnelConfig {
     pub domain: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CustomTunnelConfig {
     /// Command template to start the tunnel. Use {port} and {host} placeholders.
     /// Example: "bore local {port} --to bore.pub"
@@ -682,7 +2816,7 @@ pub struct CustomTunnelConfig {
 
 // ── Channels ─────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ChannelsConfig {
     pub cli: bool,
     pub telegram: Option<TelegramConfig>,
@@ -713,49 +2847,89 @@ impl Default for ChannelsConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ChannelsConfig {
+    /// The `allowed_tools` of the named channel; `None` when every tool is
+    /// allowed, including for unknown channels.
+    pub fn allowed_tools(&self, channel: &str) -> Option<&[String]> {
+        let allowed = match channel {
+            "telegram" => self.telegram.as_ref()?.allowed_tools.as_ref(),
+            "discord" => self.discord.as_ref()?.allowed_tools.as_ref(),
+            "slack" => self.slack.as_ref()?.allowed_tools.as_ref(),
+            "imessage" => self.imessage.as_ref()?.allowed_tools.as_ref(),
+            "matrix" => self.matrix.as_ref()?.allowed_tools.as_ref(),
+            "whatsapp" => self.whatsapp.as_ref()?.allowed_tools.as_ref(),
+            "email" => self.email.as_ref()?.allowed_tools.as_ref(),
+            "irc" => self.irc.as_ref()?.allowed_tools.as_ref(),
+            _ => None,
+        };
+        allowed.map(Vec::as_slice)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TelegramConfig {
     pub bot_token: String,
     pub allowed_users: Vec<String>,
+    /// Deliver replies as voice notes (requires `[tts]`)
+    #[serde(default)]
+    pub voice_replies: bool,
+    /// Tools the agent may use on this channel; unset allows all
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DiscordConfig {
     pub bot_token: String,
     pub guild_id: Option<String>,
     #[serde(default)]
     pub allowed_users: Vec<String>,
+    /// Deliver replies as voice notes (requires `[tts]`)
+    #[serde(default)]
+    pub voice_replies: bool,
+    /// Tools the agent may use on this channel; unset allows all
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SlackConfig {
     pub bot_token: String,
     pub app_token: Option<String>,
     pub channel_id: Option<String>,
     #[serde(default)]
     pub allowed_users: Vec<String>,
+    /// Tools the agent may use on this channel; unset allows all
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WebhookConfig {
     pub port: u16,
     pub secret: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct IMessageConfig {
     pub allowed_contacts: Vec<String>,
+    /// Tools the agent may use on this channel; unset allows all
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MatrixConfig {
     pub homeserver: String,
     pub access_token: String,
     pub room_id: String,
     pub allowed_users: Vec<String>,
+    /// Tools the agent may use on this channel; unset allows all
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WhatsAppConfig {
     /// Access token from Meta Business Suite
     pub access_token: String,
@@ -770,9 +2944,12 @@ pub struct WhatsAppConfig {
     /// Allowed phone numbers (E.164 format: +1234567890) or "*" for all
     #[serde(default)]
     pub allowed_numbers: Vec<String>,
+    /// Tools the agent may use on this channel; unset allows all
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct IrcConfig {
     /// IRC server hostname
     pub server: String,
@@ -797,6 +2974,9 @@ pub struct IrcConfig {
     pub sasl_password: Option<String>,
     /// Verify TLS certificate (default: true)
     pub verify_tls: Option<bool>,
+    /// Tools the agent may use on this channel; unset allows all
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 fn default_irc_port() -> u16 {
@@ -833,12 +3013,96 @@ impl Default for Config {
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             identity: IdentityConfig::default(),
+            triggers: TriggersConfig::default(),
+            knowledge: KnowledgeConfig::default(),
+            skill_registry: SkillRegistryConfig::default(),
+            queue: QueueConfig::default(),
+            response_cache: ResponseCacheConfig::default(),
+            azure_openai: AzureOpenAiConfig::default(),
+            gemini: GeminiConfig::default(),
+            tool_output: ToolOutputConfig::default(),
+            render: RenderConfig::default(),
+            providers: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            oauth: BTreeMap::new(),
+            workspace: None,
+            checkpoints: CheckpointConfig::default(),
+            calendar: CalendarConfig::default(),
+            model_profiles: Vec::new(),
+            verifier: VerifierConfig::default(),
+            guardrails: GuardrailsConfig::default(),
+            web_search: WebSearchConfig::default(),
+            vision: VisionConfig::default(),
+            tts: TtsConfig::default(),
+            skill_distill: SkillDistillConfig::default(),
+            digest: DigestConfig::default(),
+            jobs: JobsConfig::default(),
+            workflows: WorkflowsConfig::default(),
+            agent_bus: AgentBusConfig::default(),
+            a2a: A2aConfig::default(),
+            nodes: NodesConfig::default(),
+            grpc: GrpcConfig::default(),
+            plugins: PluginsConfig::default(),
+            hooks: HooksConfig::default(),
+            transcription: TranscriptionConfig::default(),
+            sessions: SessionsConfig::default(),
         }
     }
 }
 
 impl Config {
+    /// The first `[[model_profiles]]` entry matching `model`.
+    pub fn model_profile(&self, model: &str) -> Option<&ModelProfileConfig> {
+        self.model_profiles.iter().find(|p| p.matches(model))
+    }
+
+    /// Tool schema encoding to use in prompts for `model`.
+    pub fn tool_schema_for(&self, model: &str) -> ToolSchemaFormat {
+        self.model_profile(model)
+            .map(|p| p.tool_schema)
+            .unwrap_or_default()
+    }
+
+    /// USD per million input and output tokens for `model`, when its
+    /// profile sets both.
+    pub fn prices_for(&self, model: &str) -> Option<(f64, f64)> {
+        let profile = self.model_profile(model)?;
+        profile.input_price.zip(profile.output_price)
+    }
+
+    /// Default turn budget for `model`; callers may only tighten it.
+    pub fn turn_budget_for(&self, model: &str) -> TurnBudget {
+        self.model_profile(model)
+            .map(|p| TurnBudget {
+                max_tokens: p.max_tokens,
+                max_tool_calls: p.max_tool_calls,
+                max_seconds: p.max_seconds,
+                max_iterations: p.max_iterations,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Secret store for values encrypted into this config (`enc2:` prefix).
+    pub fn secret_store(&self) -> SecretStore {
+        let dir = self
+            .config_path
+            .parent()
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+        SecretStore::new(&dir, self.secrets.encrypt)
+    }
+
+    /// Load the config, creating a default one on first run, and apply the
+    /// profile named by `ZEROCLAW_PROFILE`.
     pub fn load_or_init() -> Result<Self> {
+        let config = Self::load_or_init_unprofiled()?;
+        match super::profiles::from_env() {
+            Some(profile) => config.with_profile(&profile),
+            None => Ok(config),
+        }
+    }
+
+    /// [`Config::load_or_init`] without applying a profile.
+    pub fn load_or_init_unprofiled() -> Result<Self> {
         let home = UserDirs::new()
             .map(|u| u.home_dir().to_path_buf())
             .context("Could not find home directory")?;
@@ -863,15 +3127,36 @@ impl Config {
             return Ok(config);
         }
 
-        // Priority 2: Load from ZeroClaw's config.toml
-        if zeroclaw_config_path.exists() {
-            let contents =
-                fs::read_to_string(&zeroclaw_config_path).context("Failed to read config file")?;
-            let mut config: Config =
-                toml::from_str(&contents).context("Failed to parse config file")?;
+        // Priority 2: ZeroClaw's config.toml, between the shared defaults in
+        // /etc/zeroclaw and the workspace's own .zeroclaw.toml
+        let workspace_dir = std::env::var_os("ZEROCLAW_WORKSPACE")
+            .filter(|w| !w.is_empty())
+            .map_or_else(|| cclaw_dir.clone(), PathBuf::from);
+        let layers = super::layers::paths(&zeroclaw_config_path);
+        let layered = match (
+            super::layers::load(&layers)?,
+            super::layers::load_workspace(&workspace_dir)?,
+        ) {
+            (Some(mut trusted), Some(workspace)) => {
+                super::layers::merge(&mut trusted, workspace);
+                Some(trusted)
+            }
+            (trusted, workspace) => trusted.or(workspace),
+        };
+        if let Some(layered) = layered {
+            // Layers may set only some keys; defaults fill in the rest
+            let mut table = toml::Table::try_from(Config::default())
+                .context("Failed to serialize default config")?;
+            super::layers::merge(&mut table, layered);
+            let mut config: Config = toml::Value::Table(table)
+                .try_into()
+                .context("Failed to parse config file")?;
             // Set computed paths that are skipped during serialization
             config.config_path = zeroclaw_config_path.clone();
-            config.workspace_dir = cclaw_dir;
+            config.workspace_dir = config.workspace.as_deref().map_or(cclaw_dir, |w| {
+                PathBuf::from(shellexpand::tilde(w).as_ref())
+            });
+            config.decrypt_keys()?;
             // Apply environment variable overrides
             config.apply_env_overrides();
             Ok(config)
@@ -881,10 +3166,31 @@ impl Config {
             config.config_path = zeroclaw_config_path.clone();
             config.workspace_dir = cclaw_dir;
             config.save()?;
+            tracing::warn!(
+                "No config found; wrote defaults to {}. Run `zeroclaw setup` to choose a \
+                 provider and API key.",
+                zeroclaw_config_path.display()
+            );
             Ok(config)
         }
     }
 
+    /// Decrypt `api_key` and the `[providers]` keys written by
+    /// [`Config::save_encrypted`]. Plaintext keys are left as they are.
+    fn decrypt_keys(&mut self) -> Result<()> {
+        let store = self.secret_store();
+        let keys = self
+            .api_key
+            .iter_mut()
+            .chain(self.providers.values_mut().filter_map(|p| p.api_key.as_mut()));
+        for key in keys {
+            if SecretStore::is_encrypted(key) {
+                *key = store.decrypt(key).context("Failed to decrypt API key in config")?;
+            }
+        }
+        Ok(())
+    }
+
     /// Apply environment variable overrides to config
     pub fn apply_env_overrides(&mut self) {
         // API Key: ZEROCLAW_API_KEY or API_KEY
@@ -944,6 +3250,26 @@ impl Config {
         }
     }
 
+    /// [`Config::save`], with `api_key` and the `[providers]` keys encrypted
+    /// by the secret store when `[secrets] encrypt` is on. Use it to write a
+    /// config that was loaded, since loading decrypts them.
+    pub fn save_encrypted(&self) -> Result<()> {
+        let mut config = self.clone();
+        let store = self.secret_store();
+        let keys = config
+            .api_key
+            .iter_mut()
+            .chain(config.providers.values_mut().filter_map(|p| p.api_key.as_mut()));
+        for key in keys {
+            if !SecretStore::is_encrypted(key) {
+                *key = store.encrypt(key)?;
+            }
+        }
+        config.save()
+    }
+
+    /// Write this config to `config_path`. Values that came from the system
+    /// or workspace layers, or from `${VAR}` interpolation, are written too.
     pub fn save(&self) -> Result<()> {
         let toml_str = toml::to_string_pretty(self).context("Failed to serialize config")?;
 
@@ -1126,6 +3452,12 @@ mod tests {
                 max_cost_per_day_cents: 10000,
                 require_approval_for_medium_risk: false,
                 block_high_risk_commands: true,
+                reference_roots: vec![],
+                plan_levels: vec![],
+                dry_run: false,
+                approve_file_changes: false,
+                write_protected_paths: vec![],
+                allow_key_files: false,
             },
             runtime: RuntimeConfig {
                 kind: "docker".into(),
@@ -1142,6 +3474,8 @@ mod tests {
                 telegram: Some(TelegramConfig {
                     bot_token: "123:ABC".into(),
                     allowed_users: vec!["user1".into()],
+                    voice_replies: false,
+                    allowed_tools: None,
                 }),
                 discord: None,
                 slack: None,
@@ -1159,6 +3493,7 @@ mod tests {
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             identity: IdentityConfig::default(),
+            ..Config::default()
         };
 
         let toml_str = toml::to_string_pretty(&config).unwrap();
@@ -1230,6 +3565,39 @@ default_temperature = 0.7
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             identity: IdentityConfig::default(),
+            triggers: TriggersConfig::default(),
+            knowledge: KnowledgeConfig::default(),
+            skill_registry: SkillRegistryConfig::default(),
+            queue: QueueConfig::default(),
+            response_cache: ResponseCacheConfig::default(),
+            azure_openai: AzureOpenAiConfig::default(),
+            gemini: GeminiConfig::default(),
+            tool_output: ToolOutputConfig::default(),
+            render: RenderConfig::default(),
+            providers: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            oauth: BTreeMap::new(),
+            workspace: None,
+            checkpoints: CheckpointConfig::default(),
+            calendar: CalendarConfig::default(),
+            model_profiles: Vec::new(),
+            verifier: VerifierConfig::default(),
+            guardrails: GuardrailsConfig::default(),
+            web_search: WebSearchConfig::default(),
+            vision: VisionConfig::default(),
+            tts: TtsConfig::default(),
+            skill_distill: SkillDistillConfig::default(),
+            digest: DigestConfig::default(),
+            jobs: JobsConfig::default(),
+            workflows: WorkflowsConfig::default(),
+            agent_bus: AgentBusConfig::default(),
+            a2a: A2aConfig::default(),
+            nodes: NodesConfig::default(),
+            grpc: GrpcConfig::default(),
+            plugins: PluginsConfig::default(),
+            hooks: HooksConfig::default(),
+            transcription: TranscriptionConfig::default(),
+            sessions: SessionsConfig::default(),
         };
 
         config.save().unwrap();
@@ -1244,6 +3612,32 @@ default_temperature = 0.7
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn saved_keys_are_encrypted_and_decrypted_on_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config {
+            config_path: dir.path().join("config.toml"),
+            api_key: Some("sk-secret".into()),
+            ..Config::default()
+        };
+        config.providers.insert(
+            "anthropic".into(),
+            ProviderConfig {
+                api_key: Some("sk-ant".into()),
+                base_url: None,
+            },
+        );
+        config.save_encrypted().unwrap();
+
+        let contents = fs::read_to_string(&config.config_path).unwrap();
+        assert!(!contents.contains("sk-secret") && !contents.contains("sk-ant"));
+        let mut loaded: Config = toml::from_str(&contents).unwrap();
+        loaded.config_path.clone_from(&config.config_path);
+        loaded.decrypt_keys().unwrap();
+        assert_eq!(loaded.api_key.as_deref(), Some("sk-secret"));
+        assert_eq!(loaded.providers["anthropic"].api_key.as_deref(), Some("sk-ant"));
+    }
+
     #[test]
     fn config_save_atomic_cleanup() {
         let dir =
@@ -1282,6 +3676,8 @@ default_temperature = 0.7
         let tc = TelegramConfig {
             bot_token: "123:XYZ".into(),
             allowed_users: vec!["alice".into(), "bob".into()],
+            voice_replies: false,
+            allowed_tools: None,
         };
         let json = serde_json::to_string(&tc).unwrap();
         let parsed: TelegramConfig = serde_json::from_str(&json).unwrap();
@@ -1295,6 +3691,8 @@ default_temperature = 0.7
             bot_token: "discord-token".into(),
             guild_id: Some("12345".into()),
             allowed_users: vec![],
+            voice_replies: false,
+            allowed_tools: None,
         };
         let json = serde_json::to_string(&dc).unwrap();
         let parsed: DiscordConfig = serde_json::from_str(&json).unwrap();
@@ -1308,6 +3706,8 @@ default_temperature = 0.7
             bot_token: "tok".into(),
             guild_id: None,
             allowed_users: vec![],
+            voice_replies: false,
+            allowed_tools: None,
         };
         let json = serde_json::to_string(&dc).unwrap();
         let parsed: DiscordConfig = serde_json::from_str(&json).unwrap();
@@ -1320,6 +3720,7 @@ default_temperature = 0.7
     fn imessage_config_serde() {
         let ic = IMessageConfig {
             allowed_contacts: vec!["+1234567890".into(), "user@icloud.com".into()],
+            allowed_tools: None,
         };
         let json = serde_json::to_string(&ic).unwrap();
         let parsed: IMessageConfig = serde_json::from_str(&json).unwrap();
@@ -1331,6 +3732,7 @@ default_temperature = 0.7
     fn imessage_config_empty_contacts() {
         let ic = IMessageConfig {
             allowed_contacts: vec![],
+            allowed_tools: None,
         };
         let json = serde_json::to_string(&ic).unwrap();
         let parsed: IMessageConfig = serde_json::from_str(&json).unwrap();
@@ -1341,6 +3743,7 @@ default_temperature = 0.7
     fn imessage_config_wildcard() {
         let ic = IMessageConfig {
             allowed_contacts: vec!["*".into()],
+            allowed_tools: None,
         };
         let toml_str = toml::to_string(&ic).unwrap();
         let parsed: IMessageConfig = toml::from_str(&toml_str).unwrap();
@@ -1354,6 +3757,7 @@ default_temperature = 0.7
             access_token: "syt_token_abc".into(),
             room_id: "!room123:matrix.org".into(),
             allowed_users: vec!["@user:matrix.org".into()],
+            allowed_tools: None,
         };
         let json = serde_json::to_string(&mc).unwrap();
         let parsed: MatrixConfig = serde_json::from_str(&json).unwrap();
@@ -1370,6 +3774,7 @@ default_temperature = 0.7
             access_token: "tok".into(),
             room_id: "!abc:synapse.local".into(),
             allowed_users: vec!["@admin:synapse.local".into(), "*".into()],
+            allowed_tools: None,
         };
         let toml_str = toml::to_string(&mc).unwrap();
         let parsed: MatrixConfig = toml::from_str(&toml_str).unwrap();
@@ -1387,12 +3792,14 @@ default_temperature = 0.7
             webhook: None,
             imessage: Some(IMessageConfig {
                 allowed_contacts: vec!["+1".into()],
+                allowed_tools: None,
             }),
             matrix: Some(MatrixConfig {
                 homeserver: "https://m.org".into(),
                 access_token: "tok".into(),
                 room_id: "!r:m".into(),
                 allowed_users: vec!["@u:m".into()],
+                allowed_tools: None,
             }),
             whatsapp: None,
             email: None,
@@ -1491,6 +3898,7 @@ channel_id = "C123"
             verify_token: "my-verify-token".into(),
             app_secret: None,
             allowed_numbers: vec!["+1234567890".into(), "+9876543210".into()],
+            allowed_tools: None,
         };
         let json = serde_json::to_string(&wc).unwrap();
         let parsed: WhatsAppConfig = serde_json::from_str(&json).unwrap();
@@ -1508,6 +3916,7 @@ channel_id = "C123"
             verify_token: "verify".into(),
             app_secret: Some("secret123".into()),
             allowed_numbers: vec!["+1".into()],
+            allowed_tools: None,
         };
         let toml_str = toml::to_string(&wc).unwrap();
         let parsed: WhatsAppConfig = toml::from_str(&toml_str).unwrap();
@@ -1530,6 +3939,7 @@ channel_id = "C123"
             verify_token: "ver".into(),
             app_secret: None,
             allowed_numbers: vec!["*".into()],
+            allowed_tools: None,
         };
         let toml_str = toml::to_string(&wc).unwrap();
         let parsed: WhatsAppConfig = toml::from_str(&toml_str).unwrap();
@@ -1552,6 +3962,7 @@ channel_id = "C123"
                 verify_token: "ver".into(),
                 app_secret: None,
                 allowed_numbers: vec!["+1".into()],
+                allowed_tools: None,
             }),
             email: None,
             irc: None,
@@ -1693,6 +4104,7 @@ default_temperature = 0.7
             enabled: true,
             api_key: Some("comp-key-123".into()),
             entity_id: "user42".into(),
+            ..ComposioConfig::default()
         };
         let toml_str = toml::to_string(&c).unwrap();
         let parsed: ComposioConfig = toml::from_str(&toml_str).unwrap();
@@ -1782,6 +4194,7 @@ default_temperature = 0.7
             enabled: true,
             allowed_domains: vec!["example.com".into(), "docs.example.com".into()],
             session_name: None,
+            ..BrowserConfig::default()
         };
         let toml_str = toml::to_string(&b).unwrap();
         let parsed: BrowserConfig = toml::from_str(&toml_str).unwrap();
@@ -1984,4 +4397,107 @@ default_temperature = 0.7
         assert!(!g.allow_public_bind);
         assert!(g.paired_tokens.is_empty());
     }
+
+    #[test]
+    fn model_profiles_select_tool_schema_format() {
+        let config: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[[model_profiles]]
+model = "llama3.2:1b"
+
+[[model_profiles]]
+model = "llama3*"
+tool_schema = "compact"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.model_profiles.len(), 2);
+        // First match wins, and an omitted tool_schema means JSON
+        assert_eq!(
+            config.tool_schema_for("llama3.2:1b"),
+            ToolSchemaFormat::Json
+        );
+        assert_eq!(
+            config.tool_schema_for("llama3.1:8b"),
+            ToolSchemaFormat::Compact
+        );
+        assert_eq!(config.tool_schema_for("gpt-4o"), ToolSchemaFormat::Json);
+        assert!(config.model_profile("llama").is_none());
+    }
+
+    #[test]
+    fn model_profiles_set_turn_budget_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[[model_profiles]]
+model = "gpt-4o*"
+max_tokens = 20000
+max_tool_calls = 10
+input_price = 0.15
+output_price = 0.6
+"#,
+        )
+        .unwrap();
+        let budget = config.turn_budget_for("gpt-4o-mini");
+        assert_eq!(budget.max_tokens, Some(20_000));
+        assert_eq!(budget.max_tool_calls, Some(10));
+        assert_eq!(budget.max_seconds, None);
+        assert_eq!(config.turn_budget_for("claude"), TurnBudget::default());
+        assert_eq!(config.prices_for("gpt-4o-mini"), Some((0.15, 0.6)));
+        assert_eq!(config.prices_for("claude"), None);
+    }
+
+    #[test]
+    fn identity_instruction_files_parse_with_budgets() {
+        let config: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[identity.instruction_files]
+"AGENTS.md" = 0
+"docs/STYLE.md" = 1500
+"#,
+        )
+        .unwrap();
+        let files: Vec<_> = config.identity.instruction_files.into_iter().collect();
+        assert_eq!(
+            files,
+            vec![("AGENTS.md".into(), 0), ("docs/STYLE.md".into(), 1500)]
+        );
+        assert_eq!(config.identity.format, "openclaw");
+        assert_eq!(config.identity.instruction_max_tokens, 5_000);
+    }
+
+    #[test]
+    fn autonomy_reference_roots_default_to_read_access() {
+        let config: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[autonomy]
+level = "supervised"
+workspace_only = true
+allowed_commands = []
+forbidden_paths = []
+max_actions_per_hour = 20
+max_cost_per_day_cents = 500
+
+[[autonomy.reference_roots]]
+path = "~/docs"
+
+[[autonomy.reference_roots]]
+path = "/srv/scratch"
+access = "write"
+"#,
+        )
+        .unwrap();
+        let roots = &config.autonomy.reference_roots;
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].access, PathAccess::Read);
+        assert_eq!(roots[1].access, PathAccess::Write);
+    }
 }
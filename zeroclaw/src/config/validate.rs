@@ -0,0 +1,415 @@
+//! Pre-flight checks for a config.
+//!
+//! [`Config::validate`] looks for settings that parse fine but will not work
+//! as intended: provider names nothing knows, a provider or channel without
+//! credentials, autonomy settings that cancel each other out. Each warning
+//! names the field and says how to fix it. [`json_schema`] describes every
+//! field, for editors and for hosts that check configs before loading them.
+
+use super::schema::Config;
+use crate::providers;
+use crate::security::{AutonomyLevel, PathAccess};
+use serde::Serialize;
+use std::fmt;
+
+/// A setting that will not work as intended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigWarning {
+    /// Dotted path of the setting, e.g. `channels_config.telegram.bot_token`
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl Config {
+    /// Settings in this config that will not work as intended. An empty list
+    /// means nothing was found, not that every credential is valid: nothing
+    /// here goes over the network.
+    pub fn validate(&self) -> Vec<ConfigWarning> {
+        let mut warnings = Vec::new();
+        let mut warn = |field: &str, message: String| {
+            warnings.push(ConfigWarning {
+                field: field.to_string(),
+                message,
+            });
+        };
+        self.check_providers(&mut warn);
+        self.check_profiles(&mut warn);
+        self.check_channels(&mut warn);
+        self.check_autonomy(&mut warn);
+        self.check_hooks(&mut warn);
+        warnings
+    }
+
+    fn check_providers(&self, warn: &mut impl FnMut(&str, String)) {
+        let default_provider = self.default_provider.as_deref().unwrap_or("openrouter");
+        let mut named = vec![("default_provider".to_string(), default_provider)];
+        for (i, name) in self.reliability.fallback_providers.iter().enumerate() {
+            named.push((format!("reliability.fallback_providers[{i}]"), name));
+        }
+        for (i, route) in self.model_routes.iter().enumerate() {
+            named.push((format!("model_routes[{i}].provider"), &route.provider));
+        }
+        if let Some(name) = &self.vision.fallback_provider {
+            named.push(("vision.fallback_provider".to_string(), name));
+        }
+        for name in self.providers.keys() {
+            named.push((format!("providers.{name}"), name));
+        }
+        for (field, name) in &named {
+            if !self.is_known_provider(name) {
+                warn(
+                    field,
+                    format!(
+                        "unknown provider \"{name}\"; use a name from the README, or \
+                         \"custom:<url>\" for an OpenAI-compatible endpoint"
+                    ),
+                );
+            }
+        }
+        let section = self.providers.get(default_provider);
+        let key = section
+            .and_then(|s| s.api_key.as_deref())
+            .or(self.api_key.as_deref());
+        if self.is_known_provider(default_provider)
+            && section.is_none_or(|s| s.base_url.is_none())
+            && needs_api_key(default_provider)
+            && providers::resolve_api_key(default_provider, key).is_none()
+        {
+            warn(
+                "api_key",
+                format!(
+                    "no API key for provider \"{default_provider}\"; set \
+                     [providers.{default_provider}] api_key, api_key, or the provider's \
+                     environment variable (e.g. {})",
+                    key_variable(default_provider)
+                ),
+            );
+        }
+    }
+
+    /// Names the factory knows, and names given a `base_url` under
+    /// `[providers]`.
+    fn is_known_provider(&self, name: &str) -> bool {
+        if self
+            .providers
+            .get(name)
+            .is_some_and(|s| s.base_url.is_some())
+        {
+            return true;
+        }
+        match providers::create_provider(
+            name,
+            Some("validate"),
+            &providers::ProviderSettings::from_config(self),
+        ) {
+            Ok(_) => true,
+            Err(e) => !e.to_string().starts_with("Unknown provider"),
+        }
+    }
+
+    fn check_profiles(&self, warn: &mut impl FnMut(&str, String)) {
+        for (name, profile) in &self.profiles {
+            if let Err(e) = self.with_profile(name) {
+                warn(&format!("profiles.{name}"), format!("{e:#}"));
+                continue;
+            }
+            if let Some(provider) = &profile.provider {
+                if !self.is_known_provider(provider) {
+                    warn(
+                        &format!("profiles.{name}.provider"),
+                        format!("unknown provider \"{provider}\""),
+                    );
+                }
+            }
+            let namespace = crate::config::MemoryConfig {
+                namespace: profile.memory_namespace.clone(),
+                ..self.memory.clone()
+            };
+            if let Err(e) = crate::memory::memory_root(&namespace, &self.workspace_dir) {
+                warn(&format!("profiles.{name}.memory_namespace"), e.to_string());
+            }
+        }
+    }
+
+    fn check_channels(&self, warn: &mut impl FnMut(&str, String)) {
+        let channels = &self.channels_config;
+        let mut credentials = Vec::new();
+        if let Some(telegram) = &channels.telegram {
+            credentials.push(("telegram.bot_token", &telegram.bot_token));
+        }
+        if let Some(discord) = &channels.discord {
+            credentials.push(("discord.bot_token", &discord.bot_token));
+        }
+        if let Some(slack) = &channels.slack {
+            credentials.push(("slack.bot_token", &slack.bot_token));
+        }
+        if let Some(matrix) = &channels.matrix {
+            credentials.push(("matrix.access_token", &matrix.access_token));
+        }
+        if let Some(whatsapp) = &channels.whatsapp {
+            credentials.push(("whatsapp.access_token", &whatsapp.access_token));
+        }
+        for (field, value) in credentials {
+            if value.trim().is_empty() {
+                let channel = field.split('.').next().unwrap_or(field);
+                warn(
+                    &format!("channels_config.{field}"),
+                    format!(
+                        "the {channel} channel is enabled but has no credential; set it, \
+                         or remove [channels_config.{channel}]"
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_autonomy(&self, warn: &mut impl FnMut(&str, String)) {
+        let autonomy = &self.autonomy;
+        let level = format!("{:?}", autonomy.level).to_lowercase();
+        if !autonomy.plan_levels.is_empty() && !autonomy.plans_first() {
+            warn(
+                "autonomy.plan_levels",
+                format!(
+                    "does not include the configured level \"{level}\", so no turn is \
+                     planned first; add \"{level}\" or clear plan_levels"
+                ),
+            );
+        }
+        if autonomy.level != AutonomyLevel::ReadOnly && autonomy.max_actions_per_hour == 0 {
+            warn(
+                "autonomy.max_actions_per_hour",
+                format!(
+                    "is 0, so the agent cannot act at level \"{level}\"; raise it, or set \
+                     level = \"readonly\""
+                ),
+            );
+        }
+        if autonomy.level == AutonomyLevel::ReadOnly {
+            for (i, root) in autonomy.reference_roots.iter().enumerate() {
+                if root.access == PathAccess::Write {
+                    warn(
+                        &format!("autonomy.reference_roots[{i}].access"),
+                        format!(
+                            "\"{}\" is writable but level is \"readonly\", which allows no \
+                             writes; use access = \"read\" or raise the level",
+                            root.path
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_hooks(&self, warn: &mut impl FnMut(&str, String)) {
+        let events = [
+            ("pre_tool_use", &self.hooks.pre_tool_use),
+            ("post_tool_use", &self.hooks.post_tool_use),
+            ("turn_start", &self.hooks.turn_start),
+            ("turn_end", &self.hooks.turn_end),
+        ];
+        for (event, hooks) in events {
+            for (i, hook) in hooks.iter().enumerate() {
+                if hook.command.is_none() && hook.url.is_none() {
+                    let fails = if matches!(event, "pre_tool_use" | "turn_start") {
+                        "denies everything"
+                    } else {
+                        "never runs"
+                    };
+                    warn(
+                        &format!("hooks.{event}[{i}]"),
+                        format!("sets neither command nor url, so it {fails}; set one"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Local and self-hosted providers, and Azure (which can also authenticate
+/// with Entra ID), work without a key.
+fn needs_api_key(name: &str) -> bool {
+    !(name == "ollama"
+        || name == "mock"
+        || name.starts_with("mock:")
+        || name.starts_with("custom:")
+        || name.starts_with("anthropic-custom:")
+        || name.starts_with("azure"))
+}
+
+fn key_variable(provider: &str) -> String {
+    let name = provider.split(['-', '.', ':']).next().unwrap_or(provider);
+    format!("{}_API_KEY", name.to_uppercase())
+}
+
+/// JSON Schema (draft 2020-12) for the config file, as JSON.
+pub fn json_schema() -> serde_json::Value {
+    schemars::schema_for!(Config).to_value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::{ProfileConfig, ReferenceRootConfig};
+    use crate::config::{HookConfig, ProviderConfig, TelegramConfig};
+
+    fn fields(config: &Config) -> Vec<String> {
+        config.validate().into_iter().map(|w| w.field).collect()
+    }
+
+    #[test]
+    fn defaults_with_a_key_raise_nothing() {
+        let config = Config {
+            api_key: Some("sk-test".into()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_empty(), "{:?}", config.validate());
+    }
+
+    #[test]
+    fn unknown_providers_and_empty_channel_tokens_are_reported() {
+        let mut config = Config {
+            api_key: Some("sk-test".into()),
+            default_provider: Some("opnai".into()),
+            ..Config::default()
+        };
+        config.reliability.fallback_providers = vec!["anthropic".into(), "gork".into()];
+        config.channels_config.telegram = Some(TelegramConfig {
+            bot_token: " ".into(),
+            allowed_users: vec![],
+            voice_replies: false,
+            allowed_tools: None,
+        });
+
+        assert_eq!(
+            fields(&config),
+            [
+                "default_provider",
+                "reliability.fallback_providers[1]",
+                "channels_config.telegram.bot_token",
+            ]
+        );
+        let warning = &config.validate()[0];
+        assert!(warning.to_string().contains("unknown provider \"opnai\""));
+    }
+
+    #[test]
+    fn provider_sections_supply_keys_and_custom_endpoints() {
+        let mut config = Config {
+            default_provider: Some("team-gateway".into()),
+            ..Config::default()
+        };
+        assert_eq!(fields(&config), ["default_provider"]);
+
+        config.providers.insert(
+            "team-gateway".into(),
+            ProviderConfig {
+                api_key: None,
+                base_url: Some("https://llm.internal.example/v1".into()),
+            },
+        );
+        config
+            .providers
+            .insert("anthropc".into(), ProviderConfig::default());
+        assert_eq!(fields(&config), ["providers.anthropc"]);
+
+        config.providers.remove("anthropc");
+        config.default_provider = Some("groq".into());
+        config.providers.insert(
+            "groq".into(),
+            ProviderConfig {
+                api_key: Some("gsk-test".into()),
+                base_url: None,
+            },
+        );
+        assert!(config.validate().is_empty(), "{:?}", config.validate());
+    }
+
+    #[test]
+    fn broken_profiles_are_reported() {
+        let mut config = Config {
+            api_key: Some("sk-test".into()),
+            ..Config::default()
+        };
+        config.profiles.insert(
+            "work".into(),
+            ProfileConfig {
+                provider: Some("anthropik".into()),
+                memory_namespace: Some("../work".into()),
+                ..ProfileConfig::default()
+            },
+        );
+        config.profiles.insert(
+            "home".into(),
+            ProfileConfig {
+                autonomy: Some(toml::from_str("level = \"reckless\"").unwrap()),
+                ..ProfileConfig::default()
+            },
+        );
+        assert_eq!(
+            fields(&config),
+            [
+                "profiles.home",
+                "profiles.work.provider",
+                "profiles.work.memory_namespace"
+            ]
+        );
+    }
+
+    #[test]
+    fn contradictory_autonomy_settings_are_reported() {
+        let mut config = Config {
+            api_key: Some("sk-test".into()),
+            ..Config::default()
+        };
+        config.autonomy.plan_levels = vec![AutonomyLevel::Full];
+        config.autonomy.max_actions_per_hour = 0;
+        assert_eq!(
+            fields(&config),
+            ["autonomy.plan_levels", "autonomy.max_actions_per_hour"]
+        );
+
+        config.autonomy.level = AutonomyLevel::ReadOnly;
+        config.autonomy.plan_levels.clear();
+        config.autonomy.reference_roots = vec![ReferenceRootConfig {
+            path: "~/docs".into(),
+            access: PathAccess::Write,
+        }];
+        assert_eq!(fields(&config), ["autonomy.reference_roots[0].access"]);
+    }
+
+    #[test]
+    fn hooks_without_a_target_are_reported() {
+        let mut config = Config {
+            api_key: Some("sk-test".into()),
+            ..Config::default()
+        };
+        let hook = HookConfig {
+            command: None,
+            url: None,
+            headers: Default::default(),
+            tools: Vec::new(),
+            timeout_secs: 10,
+        };
+        config.hooks.post_tool_use = vec![HookConfig {
+            command: Some("logger".into()),
+            ..hook.clone()
+        }];
+        config.hooks.turn_start = vec![hook];
+        assert_eq!(fields(&config), ["hooks.turn_start[0]"]);
+    }
+
+    #[test]
+    fn schema_describes_config_sections() {
+        let schema = json_schema();
+        let properties = &schema["properties"];
+        for section in ["default_provider", "autonomy", "channels_config", "render"] {
+            assert!(properties.get(section).is_some(), "missing {section}");
+        }
+    }
+}
@@ -214,7 +214,7 @@ pub fn reschedule_after_run(
     })
 }
 
-fn next_run_for(expression: &str, from: DateTime<Utc>) -> Result<DateTime<Utc>> {
+pub(crate) fn next_run_for(expression: &str, from: DateTime<Utc>) -> Result<DateTime<Utc>> {
     let normalized = normalize_expression(expression)?;
     let schedule = Schedule::from_str(&normalized)
         .with_context(|| format!("Invalid cron expression: {expression}"))?;
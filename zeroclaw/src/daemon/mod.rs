@@ -1,14 +1,55 @@
+pub mod queue;
+pub mod reload;
+pub mod service;
+pub mod shutdown;
+
 use crate::config::Config;
 use anyhow::Result;
 use chrono::Utc;
 use std::future::Future;
 use std::path::PathBuf;
+use tokio::net::TcpListener;
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
 const STATUS_FLUSH_SECONDS: u64 = 5;
 
 pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
+    run_with_listener(config, host, port, None).await
+}
+
+/// Like [`run`], but the gateway first serves on `listener`, bound by the
+/// caller so bind errors and the real port are known before the daemon
+/// starts. Gateway restarts rebind the same port.
+pub async fn run_with_listener(
+    config: Config,
+    host: String,
+    port: u16,
+    listener: Option<TcpListener>,
+) -> Result<()> {
+    run_until(config, host, port, listener, async {
+        if let Err(e) = service::shutdown_signal().await {
+            tracing::warn!("Shutdown signal handler failed: {e}");
+        }
+    })
+    .await
+}
+
+/// Like [`run_with_listener`], but stop when `shutdown` completes instead
+/// of on Ctrl+C/SIGTERM. Active agent runs get up to
+/// [`shutdown::DRAIN_TIMEOUT_SECS`] to finish before components are stopped.
+#[allow(clippy::too_many_lines)]
+pub async fn run_until(
+    config: Config,
+    host: String,
+    port: u16,
+    listener: Option<TcpListener>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    let port = match &listener {
+        Some(listener) => listener.local_addr()?.port(),
+        None => port,
+    };
     let initial_backoff = config.reliability.channel_initial_backoff_secs.max(1);
     let max_backoff = config
         .reliability
@@ -16,6 +57,8 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         .max(initial_backoff);
 
     crate::health::mark_component_ok("daemon");
+    crate::health::set_autonomy_limits(&config.autonomy);
+    shutdown::set_shutting_down(false);
 
     if config.heartbeat.enabled {
         let _ =
@@ -25,31 +68,46 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
 
     let mut handles: Vec<JoinHandle<()>> = vec![spawn_state_writer(config.clone())];
 
+    let (reload, config_rx, reload_task) = reload::spawn(config.clone());
+    handles.push(reload_task);
+    handles.extend(reload::spawn_sighup_listener(reload.clone()));
+
     {
-        let gateway_cfg = config.clone();
+        let gateway_rx = config_rx.clone();
         let gateway_host = host.clone();
+        let mut listener = listener;
         handles.push(spawn_component_supervisor(
             "gateway",
             initial_backoff,
             max_backoff,
             move || {
-                let cfg = gateway_cfg.clone();
+                let config = gateway_rx.clone();
+                let reload = reload.clone();
                 let host = gateway_host.clone();
-                async move { crate::gateway::run_gateway(&host, port, cfg).await }
+                let listener = listener.take();
+                async move {
+                    let listener = if let Some(listener) = listener {
+                        listener
+                    } else {
+                        let cfg = config.borrow().clone();
+                        crate::gateway::bind_gateway(&host, port, &cfg).await?
+                    };
+                    crate::gateway::serve_gateway_live(listener, &host, config, Some(reload)).await
+                }
             },
         ));
     }
 
     {
         if has_supervised_channels(&config) {
-            let channels_cfg = config.clone();
+            let channels_rx = config_rx.clone();
             handles.push(spawn_component_supervisor(
                 "channels",
                 initial_backoff,
                 max_backoff,
                 move || {
-                    let cfg = channels_cfg.clone();
-                    async move { crate::channels::start_channels(cfg).await }
+                    let config = channels_rx.clone();
+                    async move { run_channels_with_reload(config).await }
                 },
             ));
         } else {
@@ -59,18 +117,119 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     }
 
     if config.heartbeat.enabled {
-        let heartbeat_cfg = config.clone();
+        let heartbeat_rx = config_rx.clone();
         handles.push(spawn_component_supervisor(
             "heartbeat",
             initial_backoff,
             max_backoff,
             move || {
-                let cfg = heartbeat_cfg.clone();
-                async move { run_heartbeat_worker(cfg).await }
+                let config = heartbeat_rx.clone();
+                async move { run_heartbeat_worker(config).await }
+            },
+        ));
+    }
+
+    if config.skill_distill.enabled {
+        let distill_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "distill",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = distill_cfg.clone();
+                async move { crate::skills::distill::run_worker(cfg).await }
+            },
+        ));
+    }
+
+    if config.digest.enabled {
+        let digest_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "digest",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = digest_cfg.clone();
+                async move { crate::memory::digest::run_worker(cfg).await }
+            },
+        ));
+    }
+
+    if config.jobs.enabled {
+        let jobs_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "jobs",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = jobs_cfg.clone();
+                async move { crate::jobs::run_worker(cfg).await }
+            },
+        ));
+    }
+
+    if config.workflows.enabled {
+        let workflows_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "workflows",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = workflows_cfg.clone();
+                async move { crate::workflows::run_scheduler(cfg).await }
+            },
+        ));
+    }
+
+    if config.agent_bus.enabled {
+        let agents_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "agents",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = agents_cfg.clone();
+                async move { crate::bus::run_agents(cfg).await }
             },
         ));
     }
 
+    if config.triggers.fswatch.enabled {
+        if config.triggers.fswatch.rules.is_empty() {
+            tracing::warn!("[triggers.fswatch] is enabled but has no rules; file watcher disabled");
+        } else {
+            let fswatch_cfg = config.clone();
+            handles.push(spawn_component_supervisor(
+                "fswatch",
+                initial_backoff,
+                max_backoff,
+                move || {
+                    let cfg = fswatch_cfg.clone();
+                    async move { crate::triggers::fswatch::run(cfg).await }
+                },
+            ));
+        }
+    }
+
+    if config.knowledge.enabled {
+        if config.memory.backend == "sqlite" {
+            let knowledge_cfg = config.clone();
+            handles.push(spawn_component_supervisor(
+                "knowledge",
+                initial_backoff,
+                max_backoff,
+                move || {
+                    let cfg = knowledge_cfg.clone();
+                    async move { crate::knowledge::run(cfg).await }
+                },
+            ));
+        } else {
+            tracing::warn!(
+                "[knowledge] needs memory.backend = \"sqlite\"; knowledge indexer disabled"
+            );
+        }
+    }
+
     {
         let scheduler_cfg = config.clone();
         handles.push(spawn_component_supervisor(
@@ -89,8 +248,24 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     println!("   Components: gateway, channels, heartbeat, scheduler");
     println!("   Ctrl+C to stop");
 
-    tokio::signal::ctrl_c().await?;
+    handles.extend(service::spawn_watchdog());
+    service::notify("READY=1");
+
+    shutdown.await;
+    shutdown::set_shutting_down(true);
     crate::health::mark_component_error("daemon", "shutdown requested");
+    service::notify("STOPPING=1");
+    let active = shutdown::active_runs();
+    if active > 0 {
+        println!("⏳ Waiting for {active} active agent run(s) to finish...");
+        if !shutdown::drain(Duration::from_secs(shutdown::DRAIN_TIMEOUT_SECS)).await {
+            tracing::warn!(
+                "Stopping with {} agent run(s) still active",
+                shutdown::active_runs()
+            );
+        }
+    }
+    write_state_snapshot(&state_file_path(&config)).await;
 
     for handle in &handles {
         handle.abort();
@@ -120,19 +295,23 @@ fn spawn_state_writer(config: Config) -> JoinHandle<()> {
         let mut interval = tokio::time::interval(Duration::from_secs(STATUS_FLUSH_SECONDS));
         loop {
             interval.tick().await;
-            let mut json = crate::health::snapshot_json();
-            if let Some(obj) = json.as_object_mut() {
-                obj.insert(
-                    "written_at".into(),
-                    serde_json::json!(Utc::now().to_rfc3339()),
-                );
-            }
-            let data = serde_json::to_vec_pretty(&json).unwrap_or_else(|_| b"{}".to_vec());
-            let _ = tokio::fs::write(&path, data).await;
+            write_state_snapshot(&path).await;
         }
     })
 }
 
+async fn write_state_snapshot(path: &std::path::Path) {
+    let mut json = crate::health::snapshot_json();
+    if let Some(obj) = json.as_object_mut() {
+        obj.insert(
+            "written_at".into(),
+            serde_json::json!(Utc::now().to_rfc3339()),
+        );
+    }
+    let data = serde_json::to_vec_pretty(&json).unwrap_or_else(|_| b"{}".to_vec());
+    let _ = tokio::fs::write(path, data).await;
+}
+
 fn spawn_component_supervisor<F, Fut>(
     name: &'static str,
     initial_backoff_secs: u64,
@@ -170,7 +349,8 @@ where
     })
 }
 
-async fn run_heartbeat_worker(config: Config) -> Result<()> {
+async fn run_heartbeat_worker(live: reload::ConfigWatch) -> Result<()> {
+    let config = live.borrow().clone();
     let observer: std::sync::Arc<dyn crate::observability::Observer> =
         std::sync::Arc::from(crate::observability::create_observer(&config.observability));
     let engine = crate::heartbeat::engine::HeartbeatEngine::new(
@@ -190,10 +370,23 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
             continue;
         }
 
+        // Tasks pick up reloaded providers and autonomy; the schedule
+        // and heartbeat file keep their startup values
+        let config = live.borrow().clone();
         for task in tasks {
             let prompt = format!("[Heartbeat Task] {task}");
             let temp = config.default_temperature;
-            if let Err(e) = crate::agent::run(config.clone(), Some(prompt), None, None, temp).await
+            let _run = shutdown::begin_run();
+            if let Err(e) = crate::agent::run(
+                config.as_ref().clone(),
+                Some(prompt),
+                None,
+                None,
+                temp,
+                crate::agent::TurnBudget::default(),
+                None,
+            )
+            .await
             {
                 crate::health::mark_component_error("heartbeat", e.to_string());
                 tracing::warn!("Heartbeat task failed: {e}");
@@ -204,6 +397,20 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
     }
 }
 
+/// Run channels on the current config, restarting them between messages
+/// whenever it is reloaded.
+async fn run_channels_with_reload(mut config: reload::ConfigWatch) -> Result<()> {
+    loop {
+        let cfg = config.borrow_and_update().as_ref().clone();
+        let mut next = config.clone();
+        crate::channels::start_channels_until(cfg, async move {
+            let _ = next.changed().await;
+        })
+        .await?;
+        tracing::info!("Restarting channels with reloaded config");
+    }
+}
+
 fn has_supervised_channels(config: &Config) -> bool {
     config.channels_config.telegram.is_some()
         || config.channels_config.discord.is_some()
@@ -288,6 +495,8 @@ mod tests {
         config.channels_config.telegram = Some(crate::config::TelegramConfig {
             bot_token: "token".into(),
             allowed_users: vec![],
+            voice_replies: false,
+            allowed_tools: None,
         });
         assert!(has_supervised_channels(&config));
     }
@@ -0,0 +1,342 @@
+//! Bounded work queue for agent turns.
+//!
+//! Gateway requests and channel messages take a [`TurnPermit`] before
+//! calling the provider. At most `[queue] max_concurrent_turns` permits are
+//! out at once; further callers wait, served round-robin by source (one
+//! source per channel or gateway route), up to `max_queued` waiters. Past
+//! that, [`WorkQueue::acquire`] fails fast with [`Saturated`].
+
+use crate::config::QueueConfig;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::oneshot;
+
+/// The queue is full; retry after `retry_after_secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Saturated {
+    pub retry_after_secs: u64,
+}
+
+impl std::fmt::Display for Saturated {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Agent is busy; retry in {}s",
+            self.retry_after_secs.max(1)
+        )
+    }
+}
+
+impl std::error::Error for Saturated {}
+
+pub struct WorkQueue {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    state: Mutex<State>,
+}
+
+struct State {
+    limits: QueueConfig,
+    running: usize,
+    queued: usize,
+    waiters: HashMap<String, VecDeque<Waiter>>,
+    /// Sources with waiters, in the order they get the next free slot
+    turn_order: VecDeque<String>,
+    next_waiter: u64,
+}
+
+struct Waiter {
+    id: u64,
+    sender: oneshot::Sender<TurnPermit>,
+}
+
+/// A caller in [`WorkQueue::acquire`]; leaves the queue if dropped before
+/// it is served.
+struct Waiting {
+    inner: Arc<Inner>,
+    source: String,
+    id: u64,
+    receiver: oneshot::Receiver<TurnPermit>,
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        // The receiver drops after this, outside the lock: a permit sent to
+        // it in the meantime is released for the next waiter
+        let Ok(mut state) = self.inner.state.lock() else {
+            return;
+        };
+        let Some(waiting) = state.waiters.get_mut(&self.source) else {
+            return;
+        };
+        let Some(position) = waiting.iter().position(|w| w.id == self.id) else {
+            return;
+        };
+        waiting.remove(position);
+        if waiting.is_empty() {
+            state.waiters.remove(&self.source);
+            state.turn_order.retain(|source| *source != self.source);
+        }
+        state.queued -= 1;
+        publish_depth(&state);
+    }
+}
+
+/// One running agent turn; frees its slot for the next waiter on drop.
+pub struct TurnPermit {
+    queue: Option<Arc<Inner>>,
+}
+
+impl Drop for TurnPermit {
+    fn drop(&mut self) {
+        if let Some(inner) = self.queue.take() {
+            release(&inner);
+        }
+    }
+}
+
+/// Hand the slot to the next live waiter, or free it.
+fn release(inner: &Arc<Inner>) {
+    let Ok(mut state) = inner.state.lock() else {
+        return;
+    };
+    if !hand_off(inner, &mut state) {
+        state.running = state.running.saturating_sub(1);
+    }
+    publish_depth(&state);
+}
+
+/// Give a slot to the next live waiter; false when nobody is waiting.
+fn hand_off(inner: &Arc<Inner>, state: &mut State) -> bool {
+    while let Some(source) = state.turn_order.pop_front() {
+        let Some(waiting) = state.waiters.get_mut(&source) else {
+            continue;
+        };
+        let next = waiting.pop_front();
+        if waiting.is_empty() {
+            state.waiters.remove(&source);
+        } else {
+            state.turn_order.push_back(source);
+        }
+        let Some(next) = next else {
+            continue;
+        };
+        state.queued -= 1;
+        let permit = TurnPermit {
+            queue: Some(inner.clone()),
+        };
+        match next.sender.send(permit) {
+            Ok(()) => return true,
+            // The waiter gave up; disarm the permit and try the next one
+            Err(mut permit) => permit.queue = None,
+        }
+    }
+    false
+}
+
+fn publish_depth(state: &State) {
+    crate::health::set_queue_depth("agent_turns", state.queued);
+}
+
+impl WorkQueue {
+    pub fn new(limits: QueueConfig) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    limits,
+                    running: 0,
+                    queued: 0,
+                    waiters: HashMap::new(),
+                    turn_order: VecDeque::new(),
+                    next_waiter: 0,
+                }),
+            }),
+        }
+    }
+
+    /// Apply new limits; running turns keep their slots, and waiters get
+    /// any slots a higher `max_concurrent_turns` frees.
+    pub fn set_limits(&self, limits: &QueueConfig) {
+        let Ok(mut state) = self.inner.state.lock() else {
+            return;
+        };
+        state.limits = limits.clone();
+        let max_concurrent = state.limits.max_concurrent_turns.max(1);
+        while state.running < max_concurrent && hand_off(&self.inner, &mut state) {
+            state.running += 1;
+        }
+        publish_depth(&state);
+    }
+
+    /// Wait for a slot for a turn from `source`. Fails at once when
+    /// `max_queued` callers are already waiting.
+    pub async fn acquire(&self, source: &str) -> Result<TurnPermit, Saturated> {
+        let mut waiting = {
+            let Ok(mut state) = self.inner.state.lock() else {
+                return Ok(TurnPermit { queue: None });
+            };
+            let max_concurrent = state.limits.max_concurrent_turns.max(1);
+            if state.running < max_concurrent && state.queued == 0 {
+                state.running += 1;
+                return Ok(TurnPermit {
+                    queue: Some(self.inner.clone()),
+                });
+            }
+            if state.queued >= state.limits.max_queued {
+                return Err(Saturated {
+                    retry_after_secs: state.limits.retry_after_secs,
+                });
+            }
+            let (sender, receiver) = oneshot::channel();
+            if !state.waiters.contains_key(source) {
+                state.turn_order.push_back(source.to_string());
+            }
+            let id = state.next_waiter;
+            state.next_waiter += 1;
+            state
+                .waiters
+                .entry(source.to_string())
+                .or_default()
+                .push_back(Waiter { id, sender });
+            state.queued += 1;
+            publish_depth(&state);
+            Waiting {
+                inner: self.inner.clone(),
+                source: source.to_string(),
+                id,
+                receiver,
+            }
+        };
+        (&mut waiting.receiver).await.map_err(|_| Saturated {
+            retry_after_secs: 1,
+        })
+    }
+}
+
+/// The queue shared by the gateway and channels, sized by the daemon from
+/// `[queue]` (defaults until then).
+pub fn shared() -> &'static WorkQueue {
+    static SHARED: OnceLock<WorkQueue> = OnceLock::new();
+    SHARED.get_or_init(|| WorkQueue::new(QueueConfig::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn queue(max_concurrent_turns: usize, max_queued: usize) -> WorkQueue {
+        WorkQueue::new(QueueConfig {
+            max_concurrent_turns,
+            max_queued,
+            retry_after_secs: 7,
+        })
+    }
+
+    async fn next(
+        receiver: &mut tokio::sync::mpsc::UnboundedReceiver<&'static str>,
+    ) -> &'static str {
+        tokio::time::timeout(Duration::from_secs(1), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn rejects_past_max_queued() {
+        let queue = Arc::new(queue(1, 1));
+        let running = queue.acquire("a").await.unwrap();
+
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.acquire("a").await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let err = queue.acquire("b").await.err().unwrap();
+        assert_eq!(err.retry_after_secs, 7);
+
+        drop(running);
+        assert!(waiter.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn waiters_are_served_round_robin_by_source() {
+        let queue = Arc::new(queue(1, 10));
+        let running = queue.acquire("setup").await.unwrap();
+        let (done, mut order) = tokio::sync::mpsc::unbounded_channel();
+
+        for source in ["telegram", "telegram", "telegram", "slack"] {
+            let queue = queue.clone();
+            let done = done.clone();
+            tokio::spawn(async move {
+                let _permit = queue.acquire(source).await.unwrap();
+                done.send(source).unwrap();
+            });
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        drop(running);
+        assert_eq!(next(&mut order).await, "telegram");
+        assert_eq!(next(&mut order).await, "slack");
+        assert_eq!(next(&mut order).await, "telegram");
+        assert_eq!(next(&mut order).await, "telegram");
+    }
+
+    #[tokio::test]
+    async fn abandoned_waiters_do_not_leak_slots() {
+        let queue = Arc::new(queue(1, 10));
+        let running = queue.acquire("a").await.unwrap();
+        let abandoned = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.acquire("b").await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        abandoned.abort();
+        let _ = abandoned.await;
+
+        drop(running);
+        let permit = tokio::time::timeout(Duration::from_secs(1), queue.acquire("c"))
+            .await
+            .unwrap();
+        assert!(permit.is_ok());
+    }
+
+    #[tokio::test]
+    async fn abandoned_waiters_leave_the_queue() {
+        let queue = Arc::new(queue(1, 1));
+        let running = queue.acquire("a").await.unwrap();
+        let abandoned = tokio::time::timeout(Duration::from_millis(20), queue.acquire("b")).await;
+        assert!(abandoned.is_err());
+
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.acquire("c").await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(running);
+        assert!(waiter.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn raising_max_concurrent_serves_waiters() {
+        let queue = Arc::new(queue(1, 10));
+        let _running = queue.acquire("a").await.unwrap();
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.acquire("b").await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        queue.set_limits(&QueueConfig {
+            max_concurrent_turns: 2,
+            max_queued: 10,
+            retry_after_secs: 7,
+        });
+        let served = tokio::time::timeout(Duration::from_secs(1), waiter).await;
+        assert!(served.unwrap().unwrap().is_ok());
+    }
+}
@@ -0,0 +1,139 @@
+//! Live config reload for the daemon.
+//!
+//! SIGHUP or `POST /admin/reload` re-reads the config from the same files
+//! as startup and publishes it on a watch channel. The gateway swaps its
+//! state between requests, channels restart between messages, and heartbeat
+//! tasks use the new config on their next run; work already in flight
+//! finishes on the config it started with. Host, port, and turning whole
+//! components on or off still need a restart.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::task::JoinHandle;
+
+/// The daemon's current config, updated on every successful reload.
+pub type ConfigWatch = watch::Receiver<Arc<Config>>;
+
+type ReloadReply = oneshot::Sender<Result<(), String>>;
+
+/// Requests a reload; cheap to clone.
+#[derive(Clone)]
+pub struct ReloadHandle {
+    requests: mpsc::Sender<ReloadReply>,
+}
+
+impl ReloadHandle {
+    /// Re-read the config and publish it. Fails, leaving the running
+    /// config in place, when the file cannot be read or parsed.
+    pub async fn reload(&self) -> Result<()> {
+        let (reply, result) = oneshot::channel();
+        self.requests
+            .send(reply)
+            .await
+            .map_err(|_| anyhow::anyhow!("Config reloader is not running"))?;
+        result
+            .await
+            .context("Config reloader stopped")?
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+/// Start the reloader with `initial` as the current config.
+pub fn spawn(initial: Config) -> (ReloadHandle, ConfigWatch, JoinHandle<()>) {
+    spawn_with(initial, Config::load_or_init)
+}
+
+fn spawn_with<F>(initial: Config, load: F) -> (ReloadHandle, ConfigWatch, JoinHandle<()>)
+where
+    F: Fn() -> Result<Config> + Send + Sync + 'static,
+{
+    let load = Arc::new(load);
+    let (config_tx, config_rx) = watch::channel(Arc::new(initial));
+    let (requests, mut pending) = mpsc::channel::<ReloadReply>(4);
+
+    let task = tokio::spawn(async move {
+        while let Some(reply) = pending.recv().await {
+            let load = load.clone();
+            let loaded = tokio::task::spawn_blocking(move || load())
+                .await
+                .unwrap_or_else(|e| Err(e.into()));
+            let result = match loaded {
+                Ok(config) => {
+                    crate::health::set_autonomy_limits(&config.autonomy);
+                    config_tx.send_replace(Arc::new(config));
+                    tracing::info!("Config reloaded");
+                    Ok(())
+                }
+                Err(e) => {
+                    tracing::warn!("Config reload failed, keeping current config: {e:#}");
+                    Err(format!("{e:#}"))
+                }
+            };
+            let _ = reply.send(result);
+        }
+    });
+
+    (ReloadHandle { requests }, config_rx, task)
+}
+
+/// Reload on SIGHUP.
+#[cfg(unix)]
+pub fn spawn_sighup_listener(handle: ReloadHandle) -> Option<JoinHandle<()>> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::warn!("SIGHUP reload unavailable: {e}");
+            return None;
+        }
+    };
+    Some(tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            tracing::info!("SIGHUP received, reloading config");
+            let _ = handle.reload().await;
+        }
+    }))
+}
+
+#[cfg(not(unix))]
+pub fn spawn_sighup_listener(_handle: ReloadHandle) -> Option<JoinHandle<()>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn reload_publishes_new_config_and_keeps_old_on_error() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let (handle, mut config, task) = spawn_with(Config::default(), move || {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                Ok(Config {
+                    default_model: Some("reloaded-model".into()),
+                    ..Config::default()
+                })
+            } else {
+                anyhow::bail!("bad toml")
+            }
+        });
+
+        handle.reload().await.unwrap();
+        assert!(config.has_changed().unwrap());
+        assert_eq!(
+            config.borrow_and_update().default_model.as_deref(),
+            Some("reloaded-model")
+        );
+
+        let err = handle.reload().await.unwrap_err();
+        assert!(err.to_string().contains("bad toml"));
+        assert!(!config.has_changed().unwrap());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        task.abort();
+    }
+}
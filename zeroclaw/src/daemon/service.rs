@@ -0,0 +1,257 @@
+//! Running the daemon under a service manager.
+//!
+//! Under systemd (`Type=notify`) the daemon reports readiness and
+//! shutdown on `$NOTIFY_SOCKET` and pings the watchdog when `WATCHDOG_USEC`
+//! is set. On Windows, `zeroclaw daemon --service` hands the process to the
+//! service control manager. Either way, stopping goes through
+//! [`super::shutdown`], so agent runs in flight finish first.
+
+use anyhow::Result;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Resolves on Ctrl+C, or SIGTERM on unix (what systemd and launchd send).
+pub async fn shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = terminate.recv() => {}
+        }
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await?;
+        Ok(())
+    }
+}
+
+/// Send a state update (`READY=1`, `STOPPING=1`, ...) to systemd. Does
+/// nothing unless started by systemd with `$NOTIFY_SOCKET`.
+pub fn notify(state: &str) {
+    let Some(socket) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+    if let Err(e) = send_notify(&socket, state) {
+        tracing::debug!("systemd notify failed: {e}");
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_notify(socket: &std::ffi::OsStr, state: &str) -> std::io::Result<()> {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::net::{SocketAddr, UnixDatagram};
+
+    let sender = UnixDatagram::unbound()?;
+    let path = socket.as_bytes();
+    let addr = match path.strip_prefix(b"@") {
+        Some(name) => SocketAddr::from_abstract_name(name)?,
+        None => SocketAddr::from_pathname(socket)?,
+    };
+    sender.send_to_addr(state.as_bytes(), &addr)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn send_notify(_socket: &std::ffi::OsStr, _state: &str) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Ping the systemd watchdog at half of `WATCHDOG_USEC`, when it is set.
+pub fn spawn_watchdog() -> Option<JoinHandle<()>> {
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    let period = Duration::from_micros(usec / 2).max(Duration::from_millis(500));
+    Some(tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            notify("WATCHDOG=1");
+        }
+    }))
+}
+
+/// Run the daemon under the Windows service control manager. Blocks until
+/// the service is stopped.
+#[cfg(windows)]
+pub fn run_windows_service(
+    runtime: tokio::runtime::Handle,
+    config: crate::config::Config,
+    host: String,
+    port: u16,
+) -> Result<()> {
+    windows::run(runtime, config, host, port)
+}
+
+#[cfg(windows)]
+mod windows {
+    use crate::config::Config;
+    use crate::daemon::shutdown::{StopSignal, DRAIN_TIMEOUT_SECS};
+    use anyhow::Result;
+    use std::ffi::c_void;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Mutex, OnceLock};
+    use windows_sys::core::PWSTR;
+    use windows_sys::Win32::Foundation::{ERROR_CALL_NOT_IMPLEMENTED, NO_ERROR};
+    use windows_sys::Win32::System::Services::{
+        RegisterServiceCtrlHandlerExW, SetServiceStatus, StartServiceCtrlDispatcherW,
+        SERVICE_ACCEPT_SHUTDOWN, SERVICE_ACCEPT_STOP, SERVICE_CONTROL_INTERROGATE,
+        SERVICE_CONTROL_SHUTDOWN, SERVICE_CONTROL_STOP, SERVICE_RUNNING, SERVICE_STATUS,
+        SERVICE_STATUS_HANDLE, SERVICE_STOPPED, SERVICE_STOP_PENDING, SERVICE_TABLE_ENTRYW,
+        SERVICE_WIN32_OWN_PROCESS,
+    };
+
+    struct Launch {
+        runtime: tokio::runtime::Handle,
+        config: Config,
+        host: String,
+        port: u16,
+    }
+
+    // The SCM calls back on its own threads with no way to pass Rust state
+    static LAUNCH: Mutex<Option<Launch>> = Mutex::new(None);
+    static STOP: OnceLock<StopSignal> = OnceLock::new();
+    static STATUS_HANDLE: AtomicUsize = AtomicUsize::new(0);
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    pub fn run(
+        runtime: tokio::runtime::Handle,
+        config: Config,
+        host: String,
+        port: u16,
+    ) -> Result<()> {
+        if let Ok(mut launch) = LAUNCH.lock() {
+            *launch = Some(Launch {
+                runtime,
+                config,
+                host,
+                port,
+            });
+        }
+        let mut name = wide(crate::service::WINDOWS_SERVICE_NAME);
+        let table = [
+            SERVICE_TABLE_ENTRYW {
+                lpServiceName: name.as_mut_ptr(),
+                lpServiceProc: Some(service_main),
+            },
+            SERVICE_TABLE_ENTRYW {
+                lpServiceName: std::ptr::null_mut(),
+                lpServiceProc: None,
+            },
+        ];
+        // SAFETY: `table` is null-terminated and outlives the dispatcher,
+        // which returns only after the service has stopped.
+        if unsafe { StartServiceCtrlDispatcherW(table.as_ptr()) } == 0 {
+            anyhow::bail!(
+                "Not started by the Windows service manager ({}); run `zeroclaw daemon` without --service",
+                std::io::Error::last_os_error()
+            );
+        }
+        Ok(())
+    }
+
+    unsafe extern "system" fn service_main(_argc: u32, _argv: *mut PWSTR) {
+        let Some(launch) = LAUNCH.lock().ok().and_then(|mut l| l.take()) else {
+            return;
+        };
+        let stop = STOP.get_or_init(StopSignal::new);
+
+        let name = wide(crate::service::WINDOWS_SERVICE_NAME);
+        let handle =
+            RegisterServiceCtrlHandlerExW(name.as_ptr(), Some(control_handler), std::ptr::null());
+        if handle.is_null() {
+            return;
+        }
+        STATUS_HANDLE.store(handle as usize, Ordering::SeqCst);
+        set_status(
+            SERVICE_RUNNING,
+            SERVICE_ACCEPT_STOP | SERVICE_ACCEPT_SHUTDOWN,
+            NO_ERROR,
+        );
+
+        let result = launch.runtime.block_on(crate::daemon::run_until(
+            launch.config,
+            launch.host,
+            launch.port,
+            None,
+            stop.stopped(),
+        ));
+        let exit_code = match result {
+            Ok(()) => NO_ERROR,
+            Err(e) => {
+                tracing::error!("Daemon service failed: {e:#}");
+                1
+            }
+        };
+        set_status(SERVICE_STOPPED, 0, exit_code);
+    }
+
+    unsafe extern "system" fn control_handler(
+        control: u32,
+        _event_type: u32,
+        _event_data: *mut c_void,
+        _context: *mut c_void,
+    ) -> u32 {
+        match control {
+            SERVICE_CONTROL_STOP | SERVICE_CONTROL_SHUTDOWN => {
+                set_status(SERVICE_STOP_PENDING, 0, NO_ERROR);
+                if let Some(stop) = STOP.get() {
+                    stop.stop();
+                }
+                NO_ERROR
+            }
+            SERVICE_CONTROL_INTERROGATE => NO_ERROR,
+            _ => ERROR_CALL_NOT_IMPLEMENTED,
+        }
+    }
+
+    fn set_status(state: u32, controls_accepted: u32, exit_code: u32) {
+        let handle = STATUS_HANDLE.load(Ordering::SeqCst) as SERVICE_STATUS_HANDLE;
+        if handle.is_null() {
+            return;
+        }
+        let wait_hint = if state == SERVICE_STOP_PENDING {
+            u32::try_from((DRAIN_TIMEOUT_SECS + 5) * 1000).unwrap_or(u32::MAX)
+        } else {
+            0
+        };
+        let status = SERVICE_STATUS {
+            dwServiceType: SERVICE_WIN32_OWN_PROCESS,
+            dwCurrentState: state,
+            dwControlsAccepted: controls_accepted,
+            dwWin32ExitCode: exit_code,
+            dwServiceSpecificExitCode: 0,
+            dwCheckPoint: 0,
+            dwWaitHint: wait_hint,
+        };
+        // SAFETY: `handle` came from RegisterServiceCtrlHandlerExW
+        unsafe {
+            SetServiceStatus(handle, &status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn notify_sends_state_to_socket() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("notify.sock");
+        let receiver = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+
+        send_notify(path.as_os_str(), "READY=1").unwrap();
+        let mut buf = [0u8; 64];
+        let n = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+    }
+}
@@ -0,0 +1,119 @@
+//! Coordinated daemon shutdown.
+//!
+//! Agent turns and tool executions hold an [`ActiveRun`] guard while they
+//! work, memory autosaves and session writes included. When the daemon is
+//! told to stop (signal, Windows service control, or `zc_daemon_stop`) it
+//! turns away new gateway requests, waits up to [`DRAIN_TIMEOUT_SECS`] for
+//! active runs, writes a final state snapshot, and only then stops its
+//! components.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// How long shutdown waits for active agent runs
+pub const DRAIN_TIMEOUT_SECS: u64 = 30;
+
+static ACTIVE_RUNS: AtomicUsize = AtomicUsize::new(0);
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Marks one agent turn or tool execution as in flight until dropped.
+#[must_use = "the run counts as active only while the guard is held"]
+pub struct ActiveRun(());
+
+impl Drop for ActiveRun {
+    fn drop(&mut self) {
+        ACTIVE_RUNS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Count an agent turn or tool execution as active so shutdown waits for it.
+pub fn begin_run() -> ActiveRun {
+    ACTIVE_RUNS.fetch_add(1, Ordering::SeqCst);
+    ActiveRun(())
+}
+
+pub fn active_runs() -> usize {
+    ACTIVE_RUNS.load(Ordering::SeqCst)
+}
+
+/// True while the daemon is draining; new work should be refused.
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+pub(crate) fn set_shutting_down(value: bool) {
+    SHUTTING_DOWN.store(value, Ordering::SeqCst);
+}
+
+/// Wait until no runs are active. Returns false if `timeout` passed first.
+pub async fn drain(timeout: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while active_runs() > 0 {
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    true
+}
+
+/// Asks a daemon started with [`super::run_until`] to stop; cheap to clone.
+#[derive(Clone)]
+pub struct StopSignal {
+    sender: watch::Sender<bool>,
+}
+
+impl Default for StopSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StopSignal {
+    pub fn new() -> Self {
+        Self {
+            sender: watch::channel(false).0,
+        }
+    }
+
+    pub fn stop(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Resolves once [`stop`](Self::stop) has been called.
+    pub fn stopped(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut receiver = self.sender.subscribe();
+        async move {
+            let _ = receiver.wait_for(|stop| *stop).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn drain_waits_for_active_runs() {
+        let run = begin_run();
+        assert!(!drain(Duration::from_millis(150)).await);
+
+        let waiter = tokio::spawn(drain(Duration::from_secs(5)));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(run);
+        assert!(waiter.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn stop_signal_wakes_waiters_started_before_and_after() {
+        let signal = StopSignal::new();
+        let early = tokio::spawn(signal.stopped());
+        signal.clone().stop();
+        early.await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), signal.stopped())
+            .await
+            .unwrap();
+    }
+}
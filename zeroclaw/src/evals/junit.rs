@@ -0,0 +1,117 @@
+//! `JUnit` XML for eval results: one `<testsuite>` per suite and target, one
+//! `<testcase>` per case, so CI can show which cases regressed.
+
+use super::CaseResult;
+use std::fmt::Write;
+
+/// Render `results` as a `JUnit` XML report.
+pub fn render(results: &[CaseResult]) -> String {
+    let mut groups: Vec<(&str, &str, Vec<&CaseResult>)> = Vec::new();
+    for result in results {
+        match groups
+            .iter_mut()
+            .find(|(suite, target, _)| *suite == result.suite && *target == result.target)
+        {
+            Some((_, _, cases)) => cases.push(result),
+            None => groups.push((&result.suite, &result.target, vec![result])),
+        }
+    }
+
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"zeroclaw evals\" tests=\"{}\" failures=\"{failed}\" time=\"{:.3}\">",
+        results.len(),
+        total_seconds(results.iter())
+    );
+    for (suite, target, cases) in &groups {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">",
+            escape(&format!("{suite} [{target}]")),
+            cases.len(),
+            cases.iter().filter(|r| !r.passed()).count(),
+            total_seconds(cases.iter().copied())
+        );
+        for case in cases {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape(&case.case),
+                escape(&format!("{suite}.{target}")),
+                case.duration.as_secs_f64()
+            );
+            if case.passed() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(">\n");
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\">{}</failure>",
+                escape(&case.failures.join("; ")),
+                escape(&case.answer)
+            );
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn total_seconds<'a>(results: impl Iterator<Item = &'a CaseResult>) -> f64 {
+    results.map(|r| r.duration.as_secs_f64()).sum()
+}
+
+/// Escape text for XML content and attributes, dropping characters XML 1.0
+/// cannot hold.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\r' | '\t' => escaped.push(c),
+            c if c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn failures_carry_their_reasons_and_answer() {
+        let result = |case: &str, failures: Vec<String>| CaseResult {
+            suite: "refunds".into(),
+            case: case.into(),
+            target: "openrouter/gpt-4o".into(),
+            failures,
+            answer: "Returns take <30> days & more".into(),
+            duration: Duration::from_millis(1500),
+        };
+        let xml = render(&[
+            result("policy", Vec::new()),
+            result("tone", vec!["does not match /sorry/".into()]),
+        ]);
+        assert!(xml.contains("<testsuites name=\"zeroclaw evals\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains(
+            "<testsuite name=\"refunds [openrouter/gpt-4o]\" tests=\"2\" failures=\"1\""
+        ));
+        assert!(xml.contains(
+            "<testcase name=\"policy\" classname=\"refunds.openrouter/gpt-4o\" time=\"1.500\"/>"
+        ));
+        assert!(xml.contains(
+            "<failure message=\"does not match /sorry/\">Returns take &lt;30&gt; days &amp; more</failure>"
+        ));
+    }
+}
@@ -0,0 +1,406 @@
+//! Evals — regression tests for prompts and skills.
+//!
+//! Each `<workspace>/evals/<name>.yaml` (or `.yml` / `.toml`) is a suite of
+//! cases: an `input` sent as a user message, and assertions on the agent's
+//! answer. An assertion is a `regex` the answer must match, a `not_regex`
+//! it must not, a `json_schema` the JSON in the answer must conform to, or
+//! a `judge` question another model call answers PASS or FAIL.
+//!
+//! `zeroclaw eval` runs every suite against each combination of the
+//! providers, models and skills it is given, prints which cases passed,
+//! and writes `JUnit` XML with `--junit` for CI. Each case's outcome is also
+//! recorded as an observer score named `eval:<suite>/<case>`.
+
+pub mod junit;
+pub mod runner;
+pub mod schema;
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+pub use runner::{CaseResult, Runner};
+
+const EXTENSIONS: [&str; 3] = ["yaml", "yml", "toml"];
+
+/// An eval file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Suite {
+    /// File name without extension; set when loaded
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Skill the cases run scoped to, unless the run names skills
+    #[serde(default)]
+    pub skill: Option<String>,
+    /// Model the cases run on, unless the run names models
+    #[serde(default)]
+    pub model: Option<String>,
+    pub cases: Vec<Case>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Case {
+    pub name: String,
+    /// The user message
+    pub input: String,
+    /// Every one must hold for the case to pass
+    #[serde(rename = "assert")]
+    pub assertions: Vec<Assertion>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Assertion {
+    /// Pattern the answer must match
+    #[serde(default)]
+    pub regex: Option<String>,
+    /// Pattern the answer must not match
+    #[serde(default)]
+    pub not_regex: Option<String>,
+    /// Schema the JSON in the answer must conform to
+    #[serde(default)]
+    pub json_schema: Option<Value>,
+    /// Criterion a judge model checks the answer against
+    #[serde(default)]
+    pub judge: Option<String>,
+}
+
+/// What an assertion checks, once validated.
+#[derive(Debug, Clone, Copy)]
+pub enum AssertionKind<'a> {
+    Regex(&'a str),
+    NotRegex(&'a str),
+    JsonSchema(&'a Value),
+    Judge(&'a str),
+}
+
+impl Assertion {
+    pub fn kind(&self) -> Result<AssertionKind<'_>> {
+        match (&self.regex, &self.not_regex, &self.json_schema, &self.judge) {
+            (Some(re), None, None, None) => Ok(AssertionKind::Regex(re)),
+            (None, Some(re), None, None) => Ok(AssertionKind::NotRegex(re)),
+            (None, None, Some(schema), None) => Ok(AssertionKind::JsonSchema(schema)),
+            (None, None, None, Some(criterion)) => Ok(AssertionKind::Judge(criterion)),
+            _ => anyhow::bail!(
+                "An assertion needs exactly one of regex, not_regex, json_schema or judge"
+            ),
+        }
+    }
+}
+
+impl AssertionKind<'_> {
+    /// Check `answer` without a model call; None for judge assertions.
+    /// `Some(Err(reason))` when the answer fails.
+    pub fn check_locally(&self, answer: &str) -> Option<Result<(), String>> {
+        let outcome = match self {
+            Self::Regex(pattern) => match regex::Regex::new(pattern) {
+                Ok(re) if re.is_match(answer) => Ok(()),
+                Ok(_) => Err(format!("does not match /{pattern}/")),
+                Err(e) => Err(format!("invalid regex /{pattern}/: {e}")),
+            },
+            Self::NotRegex(pattern) => match regex::Regex::new(pattern) {
+                Ok(re) if re.is_match(answer) => Err(format!("matches /{pattern}/")),
+                Ok(_) => Ok(()),
+                Err(e) => Err(format!("invalid regex /{pattern}/: {e}")),
+            },
+            Self::JsonSchema(expected) => match extract_json(answer) {
+                Some(value) => {
+                    let errors = schema::validate(expected, &value);
+                    if errors.is_empty() {
+                        Ok(())
+                    } else {
+                        Err(format!(
+                            "JSON does not fit the schema: {}",
+                            errors.join("; ")
+                        ))
+                    }
+                }
+                None => Err("no JSON in the answer".into()),
+            },
+            Self::Judge(_) => return None,
+        };
+        Some(outcome)
+    }
+}
+
+/// The JSON in a model answer: the whole answer, a fenced block, or the
+/// first object or array in the text.
+pub fn extract_json(answer: &str) -> Option<Value> {
+    let answer = answer.trim();
+    if let Ok(value) = serde_json::from_str(answer) {
+        return Some(value);
+    }
+    if let Some(start) = answer.find("```") {
+        let fenced = &answer[start + 3..];
+        let body = fenced.split_once('\n').map_or("", |(_, rest)| rest);
+        if let Some(end) = body.find("```") {
+            if let Ok(value) = serde_json::from_str(body[..end].trim()) {
+                return Some(value);
+            }
+        }
+    }
+    let start = answer.find(['{', '['])?;
+    serde_json::Deserializer::from_str(&answer[start..])
+        .into_iter::<Value>()
+        .next()?
+        .ok()
+}
+
+impl Suite {
+    /// Parse an eval file; the format follows the extension.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read eval suite: {}", path.display()))?;
+        let mut suite: Suite = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&raw)
+                .with_context(|| format!("Invalid eval suite TOML: {}", path.display()))?,
+            _ => serde_yaml::from_str(&raw)
+                .with_context(|| format!("Invalid eval suite YAML: {}", path.display()))?,
+        };
+        suite.name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        suite
+            .validate()
+            .with_context(|| format!("Invalid eval suite: {}", path.display()))?;
+        Ok(suite)
+    }
+
+    /// Check case names and assertions.
+    pub fn validate(&self) -> Result<()> {
+        if self.cases.is_empty() {
+            anyhow::bail!("A suite needs at least one case");
+        }
+        let mut names = HashSet::new();
+        for case in &self.cases {
+            if case.name.trim().is_empty() {
+                anyhow::bail!("Every case needs a name");
+            }
+            if !names.insert(case.name.as_str()) {
+                anyhow::bail!("Duplicate case name '{}'", case.name);
+            }
+            if case.assertions.is_empty() {
+                anyhow::bail!("Case '{}' asserts nothing", case.name);
+            }
+            for assertion in &case.assertions {
+                let kind = assertion
+                    .kind()
+                    .with_context(|| format!("Case '{}'", case.name))?;
+                if let AssertionKind::Regex(pattern) | AssertionKind::NotRegex(pattern) = kind {
+                    regex::Regex::new(pattern).with_context(|| {
+                        format!("Case '{}' has an invalid regex /{pattern}/", case.name)
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Where suites live when no paths are given.
+pub fn evals_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("evals")
+}
+
+/// The suites in `paths`: files as they are, directories for every eval
+/// file in them, by name. Unlike workflows, an invalid file is an error,
+/// so a broken suite cannot pass by going missing.
+pub fn load(paths: &[PathBuf]) -> Result<Vec<Suite>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut found: Vec<PathBuf> = std::fs::read_dir(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| {
+                    p.extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| EXTENSIONS.contains(&e))
+                })
+                .collect();
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files.iter().map(|path| Suite::from_file(path)).collect()
+}
+
+/// What `zeroclaw eval` was asked to run.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Suite files or directories (default: `<workspace>/evals`)
+    pub paths: Vec<PathBuf>,
+    /// Each provider, model and skill is tried in every combination
+    pub providers: Vec<String>,
+    pub models: Vec<String>,
+    pub skills: Vec<String>,
+    pub judge_model: Option<String>,
+    /// Where to write a `JUnit` XML report
+    pub junit: Option<PathBuf>,
+}
+
+/// Run the suites in `options`, print each case's outcome and fail when any
+/// case failed.
+pub async fn run_command(config: &Config, options: RunOptions) -> Result<()> {
+    let paths = if options.paths.is_empty() {
+        vec![evals_dir(&config.workspace_dir)]
+    } else {
+        options.paths
+    };
+    let suites = load(&paths)?;
+    if suites.is_empty() {
+        println!("No eval suites in {}.", paths[0].display());
+        return Ok(());
+    }
+    let providers = if options.providers.is_empty() {
+        vec![config
+            .default_provider
+            .clone()
+            .unwrap_or_else(|| "openrouter".into())]
+    } else {
+        options.providers
+    };
+    let models: Vec<Option<&str>> = if options.models.is_empty() {
+        vec![None]
+    } else {
+        options.models.iter().map(|m| Some(m.as_str())).collect()
+    };
+    let skills: Vec<Option<&str>> = if options.skills.is_empty() {
+        vec![None]
+    } else {
+        options.skills.iter().map(|s| Some(s.as_str())).collect()
+    };
+
+    let mut results = Vec::new();
+    for provider in &providers {
+        let runner =
+            Runner::from_config(config, provider)?.with_judge_model(options.judge_model.clone());
+        for suite in &suites {
+            for &model in &models {
+                for &skill in &skills {
+                    let target = runner.target(suite, model, skill);
+                    println!("🧪 {} [{target}]", suite.name);
+                    for result in runner.run(suite, &target).await {
+                        if result.passed() {
+                            println!(
+                                "  ✅ {} ({:.1}s)",
+                                result.case,
+                                result.duration.as_secs_f64()
+                            );
+                        } else {
+                            println!("  ❌ {}", result.case);
+                            for failure in &result.failures {
+                                println!("     {failure}");
+                            }
+                        }
+                        results.push(result);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(path) = &options.junit {
+        std::fs::write(path, junit::render(&results))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    println!(
+        "\n{} of {} cases passed",
+        results.len() - failed,
+        results.len()
+    );
+    if failed > 0 {
+        anyhow::bail!("{failed} eval case(s) failed");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suites_load_from_yaml_and_reject_ambiguous_assertions() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("refunds.yaml"),
+            r#"
+skill: support
+cases:
+  - name: policy
+    input: How long do I have to return shoes?
+    assert:
+      - regex: "(?i)30 days"
+      - judge: Mentions that the shoes must be unworn
+"#,
+        )
+        .unwrap();
+        std::fs::write(tmp.path().join("notes.txt"), "not a suite").unwrap();
+        let suites = load(&[tmp.path().to_path_buf()]).unwrap();
+        assert_eq!(suites.len(), 1);
+        assert_eq!(suites[0].name, "refunds");
+        assert_eq!(suites[0].skill.as_deref(), Some("support"));
+        assert_eq!(suites[0].cases[0].assertions.len(), 2);
+
+        let both = tmp.path().join("both.yml");
+        std::fs::write(
+            &both,
+            "cases:\n  - name: x\n    input: hi\n    assert:\n      - {regex: a, judge: b}\n",
+        )
+        .unwrap();
+        let err = format!("{:#}", Suite::from_file(&both).unwrap_err());
+        assert!(err.contains("exactly one of"), "{err}");
+    }
+
+    #[test]
+    fn local_assertions_check_text_and_json() {
+        let answer = "Here you go:\n```json\n{\"days\": 30}\n```";
+        let json_schema = serde_json::json!({"type": "object", "required": ["days"]});
+        let pass = |assertion: Assertion| assertion.kind().unwrap().check_locally(answer);
+
+        assert_eq!(
+            pass(Assertion {
+                regex: Some(r"\d+".into()),
+                ..Assertion::default()
+            }),
+            Some(Ok(()))
+        );
+        assert_eq!(
+            pass(Assertion {
+                not_regex: Some("days".into()),
+                ..Assertion::default()
+            }),
+            Some(Err("matches /days/".into()))
+        );
+        assert_eq!(
+            pass(Assertion {
+                json_schema: Some(json_schema),
+                ..Assertion::default()
+            }),
+            Some(Ok(()))
+        );
+        assert_eq!(
+            pass(Assertion {
+                judge: Some("polite".into()),
+                ..Assertion::default()
+            }),
+            None
+        );
+        assert_eq!(
+            extract_json("The list is [1, 2] as asked."),
+            Some(serde_json::json!([1, 2]))
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! Runs eval suites: each case as one agent turn, then its assertions.
+
+use super::{AssertionKind, Case, Suite};
+use crate::agent::loop_;
+use crate::channels::build_system_prompt;
+use crate::config::Config;
+use crate::memory::{self, Memory};
+use crate::observability::{self, trace, Observer, ObserverEvent};
+use crate::providers::{self, ChatMessage, Provider};
+use crate::runtime::{self, RuntimeAdapter};
+use crate::security::SecurityPolicy;
+use crate::tools::{self, Tool};
+use anyhow::Result;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const DEFAULT_MODEL: &str = "anthropic/claude-sonnet-4-20250514";
+
+const JUDGE_PROMPT: &str = "You grade an AI assistant's answer against one criterion. \
+Reply with PASS or FAIL on the first line, then one sentence saying why.";
+
+/// The provider, model and skill a suite runs against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    pub provider: String,
+    pub model: String,
+    pub skill: Option<String>,
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.provider, self.model)?;
+        if let Some(skill) = &self.skill {
+            write!(f, " +{skill}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of one case against one target.
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    pub suite: String,
+    pub case: String,
+    pub target: String,
+    /// Why the case failed, one entry per failed assertion; empty on a pass
+    pub failures: Vec<String>,
+    pub answer: String,
+    pub duration: Duration,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// What every case of a suite runs with for one target.
+struct Prepared {
+    config: Config,
+    tools: Vec<Box<dyn Tool>>,
+    system_prompt: String,
+}
+
+/// Runs suites on one provider, with the workspace's tools and memory.
+pub struct Runner {
+    config: Config,
+    provider_name: String,
+    provider: Arc<dyn Provider>,
+    mem: Arc<dyn Memory>,
+    runtime: Arc<dyn RuntimeAdapter>,
+    observer: Arc<dyn Observer>,
+    /// Model judge assertions ask (defaults to the target's model)
+    judge_model: Option<String>,
+}
+
+impl Runner {
+    pub fn from_config(config: &Config, provider_name: &str) -> Result<Self> {
+        let provider = providers::create_resilient_provider(
+            provider_name,
+            config.api_key.as_deref(),
+            &config.reliability,
+            &providers::ProviderSettings::from_config(config),
+        )?;
+        Self::with_provider(config, provider_name, Arc::from(provider))
+    }
+
+    pub fn with_provider(
+        config: &Config,
+        provider_name: &str,
+        provider: Arc<dyn Provider>,
+    ) -> Result<Self> {
+        let workspace = &config.workspace_dir;
+        Ok(Self {
+            mem: Arc::from(memory::create_memory(
+                &config.memory,
+                workspace,
+                config.api_key.as_deref(),
+            )?),
+            runtime: Arc::from(runtime::create_runtime(&config.runtime)?),
+            observer: Arc::from(observability::create_observer(&config.observability)),
+            config: config.clone(),
+            provider_name: provider_name.to_string(),
+            provider,
+            judge_model: None,
+        })
+    }
+
+    /// Ask judge assertions on `model` instead of the target's model.
+    #[must_use]
+    pub fn with_judge_model(mut self, model: Option<String>) -> Self {
+        self.judge_model = model;
+        self
+    }
+
+    /// The target `suite` runs against with these overrides.
+    pub fn target(&self, suite: &Suite, model: Option<&str>, skill: Option<&str>) -> Target {
+        Target {
+            provider: self.provider_name.clone(),
+            model: model
+                .or(suite.model.as_deref())
+                .or(self.config.default_model.as_deref())
+                .unwrap_or(DEFAULT_MODEL)
+                .to_string(),
+            skill: skill.or(suite.skill.as_deref()).map(str::to_string),
+        }
+    }
+
+    /// Run every case of `suite` against `target`. Never fails as a whole:
+    /// a skill that cannot run fails each case.
+    pub async fn run(&self, suite: &Suite, target: &Target) -> Vec<CaseResult> {
+        let label = target.to_string();
+        let prepared = match self.prepare(target) {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                return suite
+                    .cases
+                    .iter()
+                    .map(|case| CaseResult {
+                        suite: suite.name.clone(),
+                        case: case.name.clone(),
+                        target: label.clone(),
+                        failures: vec![format!("{e:#}")],
+                        answer: String::new(),
+                        duration: Duration::ZERO,
+                    })
+                    .collect();
+            }
+        };
+
+        let mut results = Vec::with_capacity(suite.cases.len());
+        for case in &suite.cases {
+            let started = Instant::now();
+            let (answer, failures) = trace::session(
+                format!("eval:{}", suite.name),
+                trace::turn(async {
+                    let outcome = self.run_case(&prepared, case, &target.model).await;
+                    self.observer.record_event(&ObserverEvent::Score {
+                        name: format!("eval:{}/{}", suite.name, case.name),
+                        value: if outcome.1.is_empty() { 1.0 } else { 0.0 },
+                        comment: (!outcome.1.is_empty()).then(|| outcome.1.join("; ")),
+                    });
+                    outcome
+                }),
+            )
+            .await;
+            results.push(CaseResult {
+                suite: suite.name.clone(),
+                case: case.name.clone(),
+                target: label.clone(),
+                failures,
+                answer,
+                duration: started.elapsed(),
+            });
+        }
+        self.observer.flush();
+        results
+    }
+
+    /// The config, tools and system prompt for `target`, scoped to its
+    /// skill the way `zeroclaw agent --skill` is.
+    fn prepare(&self, target: &Target) -> Result<Prepared> {
+        let mut config = self.config.clone();
+        let workspace = config.workspace_dir.clone();
+        let skill = target
+            .skill
+            .as_deref()
+            .map(|name| crate::skills::sandbox::activate(&workspace, name, &mut config.autonomy))
+            .transpose()?;
+        let security = Arc::new(SecurityPolicy::from_config(&config.autonomy, &workspace));
+        let composio_key = if config.composio.enabled {
+            config.composio.api_key.as_deref()
+        } else {
+            None
+        };
+        let mut tools = tools::all_tools_with_runtime(
+            &security,
+            self.runtime.clone(),
+            self.mem.clone(),
+            composio_key,
+            &config.browser,
+        );
+        tools.extend(tools::optional_tools(&config, &security));
+        tools::route_to_nodes(&mut tools, &config.nodes, &security);
+
+        let mut skills = match &skill {
+            Some(skill) => {
+                crate::skills::sandbox::restrict_tools(skill, &mut tools)?;
+                vec![skill.clone()]
+            }
+            None => crate::skills::load_skills(&workspace),
+        };
+        let tool_names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        crate::skills::package::retain_usable(&mut skills, &tool_names);
+        let mut tool_descs = loop_::tool_descriptions(&config, &tools);
+        if skill.is_some() {
+            tool_descs.retain(|(name, _)| tools.iter().any(|t| t.name() == *name));
+        }
+        let mut system_prompt = build_system_prompt(
+            &workspace,
+            &target.model,
+            &tool_descs,
+            &skills,
+            Some(&crate::identity::readable_by(config.identity.for_channel("evals"), &security)),
+        );
+        system_prompt.push_str(&loop_::build_tool_instructions_with(
+            &tools,
+            config.tool_schema_for(&target.model),
+        ));
+        Ok(Prepared {
+            config,
+            tools,
+            system_prompt,
+        })
+    }
+
+    /// The answer to `case` and its failed assertions.
+    async fn run_case(
+        &self,
+        prepared: &Prepared,
+        case: &Case,
+        model: &str,
+    ) -> (String, Vec<String>) {
+        let Prepared {
+            config,
+            tools,
+            system_prompt,
+        } = prepared;
+        let mut history = vec![
+            ChatMessage::system(system_prompt.as_str()),
+            ChatMessage::user(&case.input),
+        ];
+        let reply = crate::agent::settings::scope(
+            config,
+            loop_::agent_turn_with_budget(
+                self.provider.as_ref(),
+                &mut history,
+                tools,
+                self.observer.as_ref(),
+                model,
+                config.default_temperature,
+                None,
+                config.turn_budget_for(model),
+            ),
+        )
+        .await
+        .and_then(crate::agent::budget::TurnReply::into_result);
+        let answer = match reply {
+            Ok(answer) => answer,
+            Err(e) => return (String::new(), vec![format!("turn failed: {e:#}")]),
+        };
+
+        let mut failures = Vec::new();
+        for assertion in &case.assertions {
+            let outcome = match assertion.kind() {
+                Ok(AssertionKind::Judge(criterion)) => {
+                    self.judge(criterion, &case.input, &answer, model).await
+                }
+                Ok(kind) => kind.check_locally(&answer).unwrap_or(Ok(())),
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(reason) = outcome {
+                failures.push(reason);
+            }
+        }
+        (answer, failures)
+    }
+
+    /// Ask the judge model whether `answer` meets `criterion`.
+    async fn judge(
+        &self,
+        criterion: &str,
+        input: &str,
+        answer: &str,
+        model: &str,
+    ) -> Result<(), String> {
+        let question = format!(
+            "User message:\n{input}\n\nAssistant answer:\n{answer}\n\nCriterion: {criterion}"
+        );
+        let verdict = self
+            .provider
+            .chat_with_history(
+                &[
+                    ChatMessage::system(JUDGE_PROMPT),
+                    ChatMessage::user(question),
+                ],
+                self.judge_model.as_deref().unwrap_or(model),
+                0.0,
+            )
+            .await
+            .map_err(|e| format!("judge failed: {e:#}"))?;
+        let verdict = verdict.trim();
+        let (first, reason) = verdict.split_once('\n').unwrap_or((verdict, ""));
+        let first = first
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_uppercase();
+        if first.starts_with("PASS") {
+            Ok(())
+        } else if first.starts_with("FAIL") {
+            Err(format!("judge ({criterion}): {}", reason.trim()))
+        } else {
+            Err(format!("judge gave no verdict: {verdict}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+
+    #[tokio::test]
+    async fn cases_pass_or_fail_on_their_assertions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            memory: crate::config::MemoryConfig {
+                backend: "none".into(),
+                ..crate::config::MemoryConfig::default()
+            },
+            ..Config::default()
+        };
+        let provider = Arc::new(ScriptedProvider::new(&[
+            "You have 30 days to return them.",
+            "PASS\nIt names the window.",
+            "{\"days\": \"thirty\"}",
+        ]));
+        let runner = Runner::with_provider(&config, "scripted", provider.clone())
+            .unwrap()
+            .with_judge_model(Some("judge-model".into()));
+        let suite: Suite = serde_yaml::from_str(
+            r#"
+name: refunds
+cases:
+  - name: window
+    input: How long do I have?
+    assert:
+      - regex: "30 days"
+      - judge: States the return window
+  - name: structured
+    input: Reply with JSON
+    assert:
+      - json_schema: {type: object, properties: {days: {type: integer}}}
+"#,
+        )
+        .unwrap();
+
+        let target = runner.target(&suite, Some("test-model"), None);
+        assert_eq!(target.to_string(), "scripted/test-model");
+        let results = runner.run(&suite, &target).await;
+        assert!(results[0].passed(), "{:?}", results[0].failures);
+        assert_eq!(
+            results[1].failures,
+            ["JSON does not fit the schema: $.days: expected integer, got string"]
+        );
+        assert_eq!(
+            provider.models(),
+            ["test-model", "judge-model", "test-model"]
+        );
+    }
+}
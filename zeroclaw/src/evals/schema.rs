@@ -0,0 +1,216 @@
+//! The JSON Schema keywords eval cases check answers against: `type`,
+//! `enum`, `const`, `properties`, `required`, `additionalProperties`,
+//! `items`, `minItems`/`maxItems`, `minLength`/`maxLength`, `pattern`,
+//! `minimum`/`maximum`, and `anyOf`/`allOf`. Other keywords are ignored.
+
+use serde_json::{Map, Number, Value};
+
+/// Where `value` breaks `schema`, one message per problem; empty when it
+/// conforms.
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check(schema, value, "$", &mut errors);
+    errors
+}
+
+fn check(schema: &Value, value: &Value, at: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        // `true` allows anything, `false` nothing
+        if schema == &Value::Bool(false) {
+            errors.push(format!("{at}: no value is allowed here"));
+        }
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            errors.push(format!(
+                "{at}: expected {}, got {}",
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            errors.push(format!(
+                "{at}: {value} is not one of {}",
+                Value::from(allowed.clone())
+            ));
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            errors.push(format!("{at}: expected {constant}, got {value}"));
+        }
+    }
+
+    match value {
+        Value::Object(object) => check_object(schema, object, at, errors),
+        Value::Array(items) => check_array(schema, items, at, errors),
+        Value::String(text) => check_string(schema, text, at, errors),
+        Value::Number(number) => check_number(schema, number, at, errors),
+        _ => {}
+    }
+
+    for sub in schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        check(sub, value, at, errors);
+    }
+    if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+        if !options.iter().any(|sub| validate(sub, value).is_empty()) {
+            errors.push(format!("{at}: matches none of the anyOf schemas"));
+        }
+    }
+}
+
+fn check_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    at: &str,
+    errors: &mut Vec<String>,
+) {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for name in schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+    {
+        if !object.contains_key(name) {
+            errors.push(format!("{at}: missing required property \"{name}\""));
+        }
+    }
+    for (name, field) in object {
+        let path = format!("{at}.{name}");
+        match properties.and_then(|p| p.get(name)) {
+            Some(field_schema) => check(field_schema, field, &path, errors),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    errors.push(format!("{at}: unexpected property \"{name}\""));
+                }
+                Some(extra) => check(extra, field, &path, errors),
+                None => {}
+            },
+        }
+    }
+}
+
+fn check_array(schema: &Map<String, Value>, items: &[Value], at: &str, errors: &mut Vec<String>) {
+    let count = items.len() as u64;
+    if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+        if count < min {
+            errors.push(format!("{at}: {count} items, at least {min} needed"));
+        }
+    }
+    if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+        if count > max {
+            errors.push(format!("{at}: {count} items, at most {max} allowed"));
+        }
+    }
+    if let Some(item_schema) = schema.get("items") {
+        for (i, item) in items.iter().enumerate() {
+            check(item_schema, item, &format!("{at}[{i}]"), errors);
+        }
+    }
+}
+
+fn check_string(schema: &Map<String, Value>, text: &str, at: &str, errors: &mut Vec<String>) {
+    let length = text.chars().count() as u64;
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        if length < min {
+            errors.push(format!("{at}: shorter than {min} characters"));
+        }
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        if length > max {
+            errors.push(format!("{at}: longer than {max} characters"));
+        }
+    }
+    if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+        match regex::Regex::new(pattern) {
+            Ok(re) if re.is_match(text) => {}
+            Ok(_) => errors.push(format!("{at}: does not match /{pattern}/")),
+            Err(e) => errors.push(format!("{at}: invalid pattern /{pattern}/: {e}")),
+        }
+    }
+}
+
+fn check_number(schema: &Map<String, Value>, number: &Number, at: &str, errors: &mut Vec<String>) {
+    let n = number.as_f64().unwrap_or_default();
+    if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+        if n < min {
+            errors.push(format!("{at}: {number} is below the minimum {min}"));
+        }
+    }
+    if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+        if n > max {
+            errors.push(format!("{at}: {number} is above the maximum {max}"));
+        }
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "integer" => value.as_i64().is_some() || value.as_u64().is_some(),
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_each_violation_with_its_path() {
+        let schema = json!({
+            "type": "object",
+            "required": ["days", "currency"],
+            "additionalProperties": false,
+            "properties": {
+                "days": {"type": "integer", "minimum": 1},
+                "currency": {"type": "string", "pattern": "^[A-Z]{3}$"},
+                "tags": {"type": "array", "items": {"enum": ["refund", "return"]}},
+            }
+        });
+        assert!(validate(&schema, &json!({"days": 30, "currency": "EUR"})).is_empty());
+
+        let errors = validate(&schema, &json!({"days": 0, "tags": ["refund", "gift"]}));
+        assert_eq!(
+            errors,
+            [
+                "$: missing required property \"currency\"",
+                "$.days: 0 is below the minimum 1",
+                "$.tags[1]: \"gift\" is not one of [\"refund\",\"return\"]",
+            ]
+        );
+        assert_eq!(
+            validate(&schema, &json!([1])),
+            ["$: expected object, got array"]
+        );
+    }
+}
@@ -3,19 +3,32 @@
 
 use std::ffi::{CStr, CString};
 use std::io::Write;
-use std::os::raw::{c_char, c_double};
+use std::os::raw::{c_char, c_double, c_void};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
+use anyhow::Context;
 use serde::Deserialize;
 
 use crate::agent;
+use crate::agent::budget::{BudgetExhausted, TurnBudget, TurnReply};
 use crate::config::Config;
+use crate::guardrails::Guardrails;
+use crate::integrations;
+use crate::knowledge;
+use crate::memory::browse::{self, BrowseFilter};
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::observability::{self, Observer, ObserverEvent};
+use crate::prompts::PromptLibrary;
+use crate::providers::reliable::AllProvidersFailed;
 use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
-use crate::security::{SecurityPolicy, AutonomyLevel};
+use crate::security::{ActionBlocked, SecurityPolicy, AutonomyLevel};
+use crate::sessions::feedback::{self, Feedback};
+use crate::sessions::{Rating, Session, SessionStore};
+use crate::skills::registry::RegistryClient;
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 
@@ -31,16 +44,22 @@ struct FfiConfig {
     autonomy: Option<FfiAutonomyConfig>,
     browser: Option<FfiBrowserConfig>,
     composio: Option<FfiComposioConfig>,
+    skill_registry: Option<crate::config::SkillRegistryConfig>,
+    profiles: Option<std::collections::BTreeMap<String, crate::config::schema::ProfileConfig>>,
 }
 
 #[derive(Debug, Deserialize)]
 struct FfiMemoryConfig {
     backend: String,
+    #[serde(default)]
+    recall: Option<crate::config::MemoryRecallConfig>,
 }
 
 #[derive(Debug, Deserialize)]
 struct FfiAutonomyConfig {
     level: i32,
+    #[serde(default)]
+    dry_run: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -75,6 +94,9 @@ impl FfiConfig {
         }
         if let Some(memory) = self.memory {
             config.memory.backend = memory.backend;
+            if let Some(recall) = memory.recall {
+                config.memory.recall = recall;
+            }
         }
         if let Some(autonomy) = self.autonomy {
             config.autonomy.level = match autonomy.level {
@@ -83,6 +105,7 @@ impl FfiConfig {
                 2 => AutonomyLevel::Full,
                 _ => AutonomyLevel::Supervised,
             };
+            config.autonomy.dry_run = autonomy.dry_run;
         }
         if let Some(browser) = self.browser {
             config.browser.enabled = browser.enabled;
@@ -90,6 +113,12 @@ impl FfiConfig {
         if let Some(composio) = self.composio {
             config.composio.enabled = composio.enabled;
         }
+        if let Some(registry) = self.skill_registry {
+            config.skill_registry = registry;
+        }
+        if let Some(profiles) = self.profiles {
+            config.profiles = profiles;
+        }
 
         config
     }
@@ -98,9 +127,28 @@ impl FfiConfig {
 /// Opaque handle to agent runtime
 pub struct AgentRuntime {
     config: Config,
+    /// `config` before its profile was applied, for `/profile`
+    base_config: Config,
+    /// Name of the applied profile
+    profile: Option<String>,
     security: Arc<SecurityPolicy>,
     memory: Arc<dyn Memory>,
     tools: Vec<Box<dyn Tool>>,
+    /// Handler set with `zc_agent_set_ask_user`, also asked to approve plans
+    user_prompt: Option<Arc<dyn tools::ask_user::UserPrompt>>,
+    /// Open `zc_session_*` conversations by id
+    sessions: Mutex<HashMap<u64, FfiSession>>,
+    next_session_id: AtomicU64,
+}
+
+type FfiSession = Arc<Mutex<SessionState>>;
+
+/// What a `zc_session_*` conversation keeps between calls
+struct SessionState {
+    /// Model-facing history, system prompt first
+    history: Vec<ChatMessage>,
+    /// The caller's messages and the replies, as indexed by `zc_session_branch`
+    transcript: Vec<ChatMessage>,
 }
 
 /// Result codes
@@ -112,11 +160,163 @@ pub enum ZcResult {
     InvalidArg = -2,
     NotInitialized = -3,
     OutOfMemory = -4,
+    /// The request hit one of its turn budget caps
+    BudgetExhausted = -5,
+    /// The model provider failed after retries and fallbacks
+    ProviderError = -6,
+    /// The security policy refused the action
+    ToolDenied = -7,
+    /// A provider or the action budget is rate limiting
+    RateLimited = -8,
+    /// The conversation no longer fits the model's context window
+    ContextTooLong = -9,
+    /// The daemon's gateway could not listen on the requested address
+    BindError = -10,
+}
+
+/// Which turn budget cap stopped a request, for `zc_agent_run_single_budget`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ZcBudgetLimit {
+    None = 0,
+    Tokens = 1,
+    ToolCalls = 2,
+    Seconds = 3,
+    Iterations = 4,
+}
+
+/// Per-request caps for `zc_agent_run_single_budget` (0 = no cap of its
+/// own). Caps can only lower the model profile's defaults.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ZcTurnBudget {
+    pub max_tokens: u64,
+    pub max_tool_calls: u32,
+    pub max_seconds: u64,
+    /// Model calls in the turn
+    pub max_iterations: u32,
+    /// When a cap stops the turn, still hand back the best partial answer
+    pub return_partial: bool,
+    /// Set on return to the cap that stopped the turn, `None` otherwise
+    pub exhausted: ZcBudgetLimit,
+}
+
+impl ZcTurnBudget {
+    fn caps(&self) -> TurnBudget {
+        TurnBudget {
+            max_tokens: (self.max_tokens > 0).then_some(self.max_tokens),
+            max_tool_calls: (self.max_tool_calls > 0).then_some(self.max_tool_calls),
+            max_seconds: (self.max_seconds > 0).then_some(self.max_seconds),
+            max_iterations: (self.max_iterations > 0).then_some(self.max_iterations),
+        }
+    }
+}
+
+impl From<BudgetExhausted> for ZcBudgetLimit {
+    fn from(exhausted: BudgetExhausted) -> Self {
+        match exhausted {
+            BudgetExhausted::Tokens(_) => Self::Tokens,
+            BudgetExhausted::ToolCalls(_) => Self::ToolCalls,
+            BudgetExhausted::Seconds(_) => Self::Seconds,
+            BudgetExhausted::Iterations(_) => Self::Iterations,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Record why a call failed for `zc_last_error_message` and echo it to stderr.
+fn fail(code: ZcResult, message: impl std::fmt::Display) -> ZcResult {
+    let message = message.to_string();
+    eprintln!("{message}");
+    let message = CString::new(message.replace('\0', "")).ok();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    code
+}
+
+/// Fail with the most specific code for `err`.
+fn fail_with(context: &str, err: &anyhow::Error) -> ZcResult {
+    fail(classify_error(err), format!("{context}: {err:#}"))
+}
+
+/// Map an agent error to a result code by the typed errors in its chain.
+fn classify_error(err: &anyhow::Error) -> ZcResult {
+    if err.is::<BudgetExhausted>() {
+        return ZcResult::BudgetExhausted;
+    }
+    if err.is::<ActionBlocked>() {
+        return ZcResult::ToolDenied;
+    }
+    let api_error = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<providers::ApiError>());
+    match api_error {
+        Some(api_error) if api_error.is_context_overflow() => ZcResult::ContextTooLong,
+        Some(api_error) if api_error.is_rate_limited() => ZcResult::RateLimited,
+        Some(_) => ZcResult::ProviderError,
+        None if err.is::<AllProvidersFailed>() || err.is::<reqwest::Error>() => {
+            ZcResult::ProviderError
+        }
+        None => ZcResult::Error,
+    }
+}
+
+/// Message describing the last failed call on this thread, or NULL if none
+/// has failed. The string stays valid until the next failing call on the
+/// same thread; do not free it.
+#[no_mangle]
+pub extern "C" fn zc_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Worker threads for the shared runtime; 0 = one per core
+static RUNTIME_THREADS: AtomicUsize = AtomicUsize::new(0);
+static SHARED_RUNTIME: OnceCell<Runtime> = OnceCell::new();
+
+/// Runtime driving every blocking agent call, built on first use
+fn shared_runtime() -> Option<&'static Runtime> {
+    SHARED_RUNTIME
+        .get_or_try_init(|| {
+            let mut builder = tokio::runtime::Builder::new_multi_thread();
+            let threads = RUNTIME_THREADS.load(Ordering::SeqCst);
+            if threads > 0 {
+                builder.worker_threads(threads);
+            }
+            builder.enable_all().build()
+        })
+        .map_err(|e| fail(ZcResult::Error, format!("Failed to create tokio runtime: {e}")))
+        .ok()
+}
+
+/// Set the worker thread count of the runtime shared by agent calls
+/// (0 = one per core). Takes effect only before the first call that needs
+/// the runtime; returns `Error` once it exists.
+#[no_mangle]
+pub extern "C" fn zc_runtime_configure(threads: u32) -> ZcResult {
+    if SHARED_RUNTIME.get().is_some() {
+        return ZcResult::Error;
+    }
+    RUNTIME_THREADS.store(threads as usize, Ordering::SeqCst);
+    ZcResult::Ok
 }
 
 /// Build system prompt with tool instructions
+/// The `ffi` identity, without files the agent's policy would not let it read.
+fn prompt_identity(config: &Config) -> crate::config::IdentityConfig {
+    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+    crate::identity::readable_by(config.identity.for_channel("ffi"), &security)
+}
+
 fn build_system_prompt(config: &Config, tools: &[Box<dyn Tool>]) -> String {
-    let skills = crate::skills::load_skills(&config.workspace_dir);
+    let mut skills = crate::skills::load_skills(&config.workspace_dir);
+    let tool_names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+    crate::skills::package::retain_usable(&mut skills, &tool_names);
     let mut tool_descs: Vec<(&str, &str)> = vec![
         (
             "shell",
@@ -128,11 +328,11 @@ fn build_system_prompt(config: &Config, tools: &[Box<dyn Tool>]) -> String {
         ),
         (
             "file_write",
-            "Write file contents. Use when: creating new files or completely replacing existing files. Don't use when: side effects are unclear or file ownership is uncertain.",
+            "Write file contents. Use when: creating new files or completely replacing existing files. Don't use when: side effects are unclear or file ownership is uncertain. The result lists changed line ranges and a content hash, so no read-back is needed.",
         ),
         (
             "file_edit",
-            "Edit existing files: insert lines, delete lines, or replace content at specific line numbers. Use when: modifying specific parts of a file without rewriting the entire file. Parameters: path, operation (insert/delete/replace), line (1-based), content, end_line (optional).",
+            "Edit existing files: insert lines, delete lines, or replace content at specific line numbers. Use when: modifying specific parts of a file without rewriting the entire file. Parameters: path, operation (insert/delete/replace), line (1-based), content, end_line (optional). The result lists changed line ranges and a content hash.",
         ),
         (
             "memory_store",
@@ -155,11 +355,45 @@ fn build_system_prompt(config: &Config, tools: &[Box<dyn Tool>]) -> String {
         "image_info",
         "Read image file metadata (format, dimensions, size) and optionally base64-encode it. Use when: inspecting images, preparing visual data for analysis.",
     ));
+    tool_descs.push((
+        "clipboard",
+        "Read or replace the system clipboard text. Use when: the user refers to what they just copied, or asks you to copy a result. Don't use when: the content was already given in the conversation.",
+    ));
+    tool_descs.push((
+        "calculator",
+        "Evaluate arithmetic exactly. Use when: an answer involves sums, percentages, ratios, or any computed figure. Don't use when: the number is quoted verbatim from a source.",
+    ));
+    tool_descs.push((
+        "fetch_full_output",
+        "Read lines of a tool output that was shortened to fit the context. Use when: a result says it was truncated or summarized and you need lines it left out. Don't use when: the shortened result already answers the question.",
+    ));
     if config.browser.enabled {
         tool_descs.push((
             "browser_open",
             "Open approved HTTPS URLs in Brave Browser (allowlist-only, no scraping)",
         ));
+        tool_descs.push((
+            "browser_act",
+            "Drive a headless browser page (navigate, wait, click, fill, extract text, screenshot). Use when: a task needs interaction with an allowlisted site. Don't use when: web_search or a plain fetch is enough.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "calendar") {
+        tool_descs.push((
+            "calendar",
+            "List upcoming events or create events on the user's CalDAV calendars. Use when: the user asks about their schedule or wants something booked. Don't use when: the request concerns someone else's calendar.",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "kb_search") {
+        tool_descs.push((
+            "kb_search",
+            "Search the indexed knowledge base documents. Use when: the answer may be in the user's notes, docs or manuals and the injected knowledge context is not enough. Don't use when: the question is about the conversation or saved preferences (use memory_recall).",
+        ));
+    }
+    if tools.iter().any(|t| t.name() == "ask_user") {
+        tool_descs.push((
+            "ask_user",
+            "Ask the user a clarifying question and wait for the answer. Use when: the request is ambiguous and a wrong guess would waste work or cause side effects. Don't use when: a sensible default exists or the answer can be found with other tools.",
+        ));
     }
     if config.composio.enabled {
         tool_descs.push((
@@ -173,11 +407,14 @@ fn build_system_prompt(config: &Config, tools: &[Box<dyn Tool>]) -> String {
         config.default_model.as_deref().unwrap_or("unknown"),
         &tool_descs,
         &skills,
-        Some(&config.identity),
+        Some(&prompt_identity(config)),
     );
 
     // Append structured tool-use instructions with schemas
-    system_prompt.push_str(&agent::loop_::build_tool_instructions(tools));
+    system_prompt.push_str(&agent::loop_::build_tool_instructions_with(
+        tools,
+        config.tool_schema_for(config.default_model.as_deref().unwrap_or("unknown")),
+    ));
     system_prompt
 }
 
@@ -190,14 +427,31 @@ pub unsafe extern "C" fn zc_agent_init(
     config_json: *const c_char,
     workspace_dir: *const c_char,
     out_handle: *mut *mut AgentRuntime,
+) -> ZcResult {
+    zc_agent_init_profile(config_json, workspace_dir, std::ptr::null(), out_handle)
+}
+
+/// Initialize the agent runtime with a config profile applied. A NULL
+/// `profile` uses `ZEROCLAW_PROFILE`, if set. Profiles come from the
+/// "profiles" key of `config_json`, or else from the config file.
+///
+/// # Safety
+/// Caller must ensure `config_json`, `workspace_dir` and `profile` are
+/// valid null-terminated UTF-8 strings or NULL
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_init_profile(
+    config_json: *const c_char,
+    workspace_dir: *const c_char,
+    profile: *const c_char,
+    out_handle: *mut *mut AgentRuntime,
 ) -> ZcResult {
     if out_handle.is_null() {
         return ZcResult::InvalidArg;
     }
 
     // Load or create config
-    let mut config: Config = if config_json.is_null() {
-        Config::load_or_init().unwrap_or_default()
+    let mut base: Config = if config_json.is_null() {
+        Config::load_or_init_unprofiled().unwrap_or_default()
     } else {
         let json_str = match CStr::from_ptr(config_json).to_str() {
             Ok(s) => s,
@@ -207,8 +461,7 @@ pub unsafe extern "C" fn zc_agent_init(
         match serde_json::from_str::<FfiConfig>(json_str) {
             Ok(ffi_cfg) => ffi_cfg.to_config(),
             Err(e) => {
-                eprintln!("Failed to parse FFI config: {}", e);
-                return ZcResult::InvalidArg;
+                return fail(ZcResult::InvalidArg, format!("Failed to parse FFI config: {e}"));
             }
         }
     };
@@ -219,14 +472,60 @@ pub unsafe extern "C" fn zc_agent_init(
             Ok(s) => s,
             Err(_) => return ZcResult::InvalidArg,
         };
-        config.workspace_dir = PathBuf::from(ws);
+        base.workspace_dir = PathBuf::from(ws);
     }
 
-    // Ensure workspace directory exists
-    if let Err(e) = std::fs::create_dir_all(&config.workspace_dir) {
-        eprintln!("Failed to create workspace directory: {}", e);
-        return ZcResult::Error;
+    let profile = if profile.is_null() {
+        crate::config::profiles::from_env()
+    } else {
+        match CStr::from_ptr(profile).to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(_) => return ZcResult::InvalidArg,
+        }
+    };
+    if profile.is_some() && base.profiles.is_empty() && !config_json.is_null() {
+        if let Ok(file) = Config::load_or_init_unprofiled() {
+            base.profiles = file.profiles;
+        }
     }
+    let mut config = match &profile {
+        Some(name) => match base.with_profile(name) {
+            Ok(config) => config,
+            Err(e) => return fail(ZcResult::InvalidArg, format!("{e:#}")),
+        },
+        None => base.clone(),
+    };
+
+    let (security, memory, tools) = match runtime_parts(&mut config) {
+        Ok(parts) => parts,
+        Err(e) => return fail_with("Failed to initialize agent", &e),
+    };
+
+    let agent = Box::new(AgentRuntime {
+        config,
+        base_config: base,
+        profile,
+        security,
+        memory,
+        tools,
+        user_prompt: None,
+        sessions: Mutex::new(HashMap::new()),
+        next_session_id: AtomicU64::new(1),
+    });
+
+    *out_handle = Box::into_raw(agent);
+    ZcResult::Ok
+}
+
+/// What an [`AgentRuntime`] builds from its config.
+type RuntimeParts = (Arc<SecurityPolicy>, Arc<dyn Memory>, Vec<Box<dyn Tool>>);
+
+/// Security policy, memory and tools for `config`, whose autonomy settings
+/// are relaxed for the levels the C side runs at.
+fn runtime_parts(config: &mut Config) -> anyhow::Result<RuntimeParts> {
+    // Ensure workspace directory exists
+    std::fs::create_dir_all(&config.workspace_dir)
+        .context("Failed to create workspace directory")?;
 
     // Force Full autonomy mode to bypass all security restrictions
     // This ensures agent-browser and other skills can run without blocking
@@ -244,65 +543,233 @@ pub unsafe extern "C" fn zc_agent_init(
         &config.autonomy,
         &config.workspace_dir,
     ));
+    crate::health::set_autonomy_limits(&config.autonomy);
 
-    let memory: Arc<dyn Memory> = match memory::create_memory(
-        &config.memory,
-        &config.workspace_dir,
-        config.api_key.as_deref(),
-    ) {
-        Ok(m) => Arc::from(m),
-        Err(_) => return ZcResult::Error,
-    };
+    let memory: Arc<dyn Memory> = Arc::from(
+        memory::create_memory(&config.memory, &config.workspace_dir, config.api_key.as_deref())
+            .context("Failed to create memory backend")?,
+    );
 
     // Create tool registry
-    let tools = tools::all_tools_with_runtime(
+    let mut tools = tools::all_tools_with_runtime(
         &security,
         Arc::new(runtime::NativeRuntime::new()),
         memory.clone(),
         config.composio.api_key.as_deref().filter(|k| config.composio.enabled && !k.is_empty()),
         &config.browser,
     );
+    tools.extend(tools::optional_tools(config, &security));
+    tools::route_to_nodes(&mut tools, &config.nodes, &security);
+    if config.knowledge.enabled && knowledge::supported(memory.as_ref()) {
+        knowledge::spawn_sync(config, memory.clone());
+        tools.push(Box::new(tools::KbSearchTool::new(memory.clone())));
+    }
+    Ok((security, memory, tools))
+}
 
-    let agent = Box::new(AgentRuntime {
-        config,
-        security,
-        memory,
-        tools,
-    });
+/// Callback answering `ask_user` questions on behalf of the embedding app,
+/// which with `[autonomy] approve_file_changes` also get file change
+/// approvals: the diff, answered with "approve" or "reject". It must write a NUL-terminated answer of at most `answer_len` bytes into
+/// `answer` and return true, or return false when the user gives no answer.
+/// Called on a worker thread while the agent turn is paused.
+pub type ZcAskUserFn = Option<
+    unsafe extern "C" fn(
+        user_data: *mut c_void,
+        question: *const c_char,
+        answer: *mut c_char,
+        answer_len: usize,
+    ) -> bool,
+>;
+
+/// Size of the answer buffer handed to [`ZcAskUserFn`].
+const ASK_USER_ANSWER_BYTES: usize = 4096;
+
+/// Routes `ask_user` questions to a C callback.
+struct FfiUserPrompt {
+    callback: ZcAskUserFn,
+    user_data: *mut c_void,
+}
 
-    *out_handle = Box::into_raw(agent);
+// SAFETY: `zc_agent_set_ask_user` requires the callback and its user data to
+// be usable from any thread; Rust never dereferences `user_data` itself.
+unsafe impl Send for FfiUserPrompt {}
+unsafe impl Sync for FfiUserPrompt {}
+
+#[async_trait::async_trait]
+impl tools::ask_user::UserPrompt for FfiUserPrompt {
+    async fn ask(&self, question: &str, choices: &[String]) -> anyhow::Result<Option<String>> {
+        let question = CString::new(tools::ask_user::format_question(question, choices))?;
+        let (Some(callback), user_data) = (self.callback, self.user_data as usize) else {
+            return Ok(None);
+        };
+        tokio::task::spawn_blocking(move || {
+            let mut answer = vec![0u8; ASK_USER_ANSWER_BYTES];
+            // SAFETY: the buffer outlives the call and its length is passed
+            let answered = unsafe {
+                callback(
+                    user_data as *mut c_void,
+                    question.as_ptr(),
+                    answer.as_mut_ptr().cast::<c_char>(),
+                    answer.len(),
+                )
+            };
+            if !answered {
+                return None;
+            }
+            // Guard against callbacks that fill the buffer without a terminator
+            answer[ASK_USER_ANSWER_BYTES - 1] = 0;
+            let answer = CStr::from_bytes_until_nul(&answer)
+                .map(|s| s.to_string_lossy().trim().to_string())
+                .unwrap_or_default();
+            (!answer.is_empty()).then_some(answer)
+        })
+        .await
+        .map_err(Into::into)
+    }
+}
+
+/// Register (or with a NULL callback, remove) the handler for the `ask_user`
+/// tool. Without one, the agent cannot ask clarifying questions in
+/// `zc_agent_run_single`.
+///
+/// # Safety
+/// Caller must ensure handle is valid, and that `callback` and `user_data`
+/// stay usable from any thread until replaced or the runtime is shut down
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_set_ask_user(
+    handle: *mut AgentRuntime,
+    callback: ZcAskUserFn,
+    user_data: *mut c_void,
+) -> ZcResult {
+    if handle.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &mut *handle;
+
+    agent.tools.retain(|t| t.name() != "ask_user");
+    agent.user_prompt = callback.is_some().then(|| {
+        Arc::new(FfiUserPrompt {
+            callback,
+            user_data,
+        }) as Arc<dyn tools::ask_user::UserPrompt>
+    });
+    if let Some(prompt) = &agent.user_prompt {
+        agent.tools.push(Box::new(tools::AskUserTool::new(prompt.clone())));
+    }
     ZcResult::Ok
 }
 
-/// Shutdown and free agent runtime
+/// Callback receiving a turn's text as the model writes it, for the
+/// `*_stream` calls. `delta` is NUL-terminated UTF-8, valid only during the
+/// call. Called on a worker thread while the turn runs, so it should return
+/// quickly.
+pub type ZcDeltaFn = Option<unsafe extern "C" fn(user_data: *mut c_void, delta: *const c_char)>;
+
+/// Hands streamed text to a C callback.
+#[derive(Clone, Copy)]
+struct FfiDeltas {
+    callback: ZcDeltaFn,
+    user_data: *mut c_void,
+}
+
+// SAFETY: the `*_stream` calls require the callback and its user data to be
+// usable from any thread for the duration of the call.
+unsafe impl Send for FfiDeltas {}
+unsafe impl Sync for FfiDeltas {}
+
+impl FfiDeltas {
+    fn new(callback: ZcDeltaFn, user_data: *mut c_void) -> Option<Self> {
+        callback.is_some().then_some(Self { callback, user_data })
+    }
+
+    /// Run `turn`, streaming its text to the callback.
+    async fn stream<F: std::future::Future>(deltas: Option<Self>, turn: F) -> F::Output {
+        let Some(deltas) = deltas else {
+            return turn.await;
+        };
+        agent::loop_::stream_deltas(Arc::new(move |delta: &str| deltas.send(delta)), turn).await
+    }
+
+    fn send(&self, delta: &str) {
+        if let (Some(callback), Ok(delta)) = (self.callback, CString::new(delta.replace('\0', ""))) {
+            // SAFETY: the string outlives the call
+            unsafe { callback(self.user_data, delta.as_ptr()) };
+        }
+    }
+}
+
+/// Shutdown and free agent runtime. Sessions still open are closed, and
+/// summarized first with `[memory.summary]` enabled.
 ///
 /// # Safety
 /// Caller must ensure handle is a valid pointer returned by zc_agent_init
 #[no_mangle]
 pub unsafe extern "C" fn zc_agent_shutdown(handle: *mut AgentRuntime) {
     if !handle.is_null() {
-        let _ = Box::from_raw(handle);
+        let agent = Box::from_raw(handle);
+        let sessions = agent
+            .sessions
+            .lock()
+            .map(|mut sessions| sessions.drain().collect())
+            .unwrap_or_default();
+        summarize_sessions(&agent, sessions);
     }
 }
 
-/// Build context by searching memory for relevant entries
-async fn build_context(mem: &dyn Memory, user_msg: &str) -> String {
-    let mut context = String::new();
-
-    // Pull relevant memories for this message
-    if let Ok(entries) = mem.recall(user_msg, 5).await {
-        if !entries.is_empty() {
-            context.push_str("[Memory context]\n");
-            for entry in &entries {
-                let _ = std::fmt::Write::write_fmt(
-                    &mut context,
-                    format_args!("- {}: {}\n", entry.key, entry.content),
-                );
-            }
-            context.push('\n');
+/// Store `[memory.summary]` summaries of `zc_session_*` sessions being
+/// closed, with the default provider and model. A session still sending is
+/// waited for.
+fn summarize_sessions(agent: &AgentRuntime, sessions: Vec<(u64, FfiSession)>) {
+    let config = &agent.config;
+    if !config.memory.summary.enabled || sessions.is_empty() {
+        return;
+    }
+    let Some(rt) = shared_runtime() else {
+        return;
+    };
+    let provider = match providers::create_resilient_provider(
+        config.default_provider.as_deref().unwrap_or("openrouter"),
+        config.api_key.as_deref(),
+        &config.reliability,
+        &providers::ProviderSettings::from_config(config),
+    ) {
+        Ok(provider) => provider,
+        Err(e) => {
+            tracing::warn!("Session summaries skipped: {e}");
+            return;
         }
+    };
+    let model = config
+        .default_model
+        .as_deref()
+        .unwrap_or("anthropic/claude-sonnet-4-20250514");
+    for (id, session) in sessions {
+        let Ok(session) = session.lock() else {
+            continue;
+        };
+        rt.block_on(memory::summary::on_close(
+            provider.as_ref(),
+            agent.memory.as_ref(),
+            &config.memory,
+            model,
+            &format!("ffi-{id}"),
+            &session.transcript,
+        ));
     }
+}
 
+/// Build context from the memories relevant enough to `user_msg`, the
+/// knowledge graph around what it mentions, then the knowledge base when it
+/// is enabled
+async fn build_context(mem: &dyn Memory, user_msg: &str, config: &Config) -> String {
+    let mut context = memory::recall::build_context(mem, user_msg, &config.memory.recall).await;
+    context.push_str(&memory::graph::build_context(
+        &config.memory,
+        &config.workspace_dir,
+        user_msg,
+    ));
+    let limit = knowledge::context_limit(&config.knowledge);
+    context.push_str(&knowledge::build_context(mem, user_msg, limit).await);
     context
 }
 
@@ -319,15 +786,118 @@ pub unsafe extern "C" fn zc_agent_run_single(
     temperature: c_double,
     out_response: *mut *mut c_char,
 ) -> ZcResult {
-    if handle.is_null() || message.is_null() || out_response.is_null() {
+    zc_agent_run_single_budget(
+        handle,
+        message,
+        provider,
+        model,
+        temperature,
+        std::ptr::null_mut(),
+        out_response,
+    )
+}
+
+/// Like `zc_agent_run_single`, within the caps of `budget` (NULL = none of
+/// its own). Returns `BudgetExhausted` when a cap is hit and records it in
+/// `budget->exhausted`; with `budget->return_partial` set, `out_response`
+/// then holds the best partial answer (free it as usual).
+///
+/// # Safety
+/// Same requirements as `zc_agent_run_single`; `budget` must be NULL or
+/// valid for reads and writes
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_run_single_budget(
+    handle: *mut AgentRuntime,
+    message: *const c_char,
+    provider: *const c_char,
+    model: *const c_char,
+    temperature: c_double,
+    budget: *mut ZcTurnBudget,
+    out_response: *mut *mut c_char,
+) -> ZcResult {
+    if out_response.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let mut budget = budget.as_mut();
+    let caps = budget.as_deref().map_or_else(TurnBudget::default, ZcTurnBudget::caps);
+    if let Some(budget) = budget.as_deref_mut() {
+        budget.exhausted = ZcBudgetLimit::None;
+    }
+    let result = match run_single(handle, message, provider, model, temperature, caps, None) {
+        Ok(result) => result,
+        Err(code) => return code,
+    };
+    match (result, budget) {
+        (
+            Ok(TurnReply {
+                answer,
+                exhausted: Some(exhausted),
+            }),
+            Some(budget),
+        ) => {
+            budget.exhausted = exhausted.into();
+            if budget.return_partial {
+                let code = write_response(Ok(answer), out_response);
+                if !matches!(code, ZcResult::Ok) {
+                    return code;
+                }
+            }
+            fail(ZcResult::BudgetExhausted, exhausted)
+        }
+        (result, _) => write_response(result.and_then(TurnReply::into_result), out_response),
+    }
+}
+
+/// Run `message` like `zc_agent_run_single`, handing the reply's text to
+/// `on_delta` (with `user_data`) while the model writes it, tool calls left
+/// out. The text of every model reply in the turn is streamed, including
+/// what it says before using a tool; `out_response` gets the final answer,
+/// which may differ once verification and guardrails have run.
+///
+/// # Safety
+/// Same requirements as `zc_agent_run_single`; `on_delta` and `user_data`
+/// must be usable from another thread until this returns
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn zc_agent_run_stream(
+    handle: *mut AgentRuntime,
+    message: *const c_char,
+    provider: *const c_char,
+    model: *const c_char,
+    temperature: c_double,
+    on_delta: ZcDeltaFn,
+    user_data: *mut c_void,
+    out_response: *mut *mut c_char,
+) -> ZcResult {
+    if out_response.is_null() {
         return ZcResult::InvalidArg;
     }
+    let deltas = FfiDeltas::new(on_delta, user_data);
+    match run_single(handle, message, provider, model, temperature, TurnBudget::default(), deltas) {
+        Ok(result) => write_response(result.and_then(TurnReply::into_result), out_response),
+        Err(code) => code,
+    }
+}
+
+/// Parse the arguments shared by the `zc_agent_run_single*` calls and run
+/// the turn in a fresh conversation. `Err` is an argument or setup failure.
+unsafe fn run_single(
+    handle: *mut AgentRuntime,
+    message: *const c_char,
+    provider: *const c_char,
+    model: *const c_char,
+    temperature: c_double,
+    request_budget: TurnBudget,
+    deltas: Option<FfiDeltas>,
+) -> Result<anyhow::Result<TurnReply>, ZcResult> {
+    if handle.is_null() || message.is_null() {
+        return Err(ZcResult::InvalidArg);
+    }
 
     let agent = &*handle;
 
-    let msg = match CStr::from_ptr(message).to_str() {
-        Ok(s) => s,
-        Err(_) => return ZcResult::InvalidArg,
+    let Ok(msg) = CStr::from_ptr(message).to_str() else {
+        return Err(ZcResult::InvalidArg);
     };
 
     let provider_override = if provider.is_null() {
@@ -335,7 +905,7 @@ pub unsafe extern "C" fn zc_agent_run_single(
     } else {
         match CStr::from_ptr(provider).to_str() {
             Ok(s) => Some(s.to_string()),
-            Err(_) => return ZcResult::InvalidArg,
+            Err(_) => return Err(ZcResult::InvalidArg),
         }
     };
 
@@ -344,82 +914,160 @@ pub unsafe extern "C" fn zc_agent_run_single(
     } else {
         match CStr::from_ptr(model).to_str() {
             Ok(s) => Some(s.to_string()),
-            Err(_) => return ZcResult::InvalidArg,
+            Err(_) => return Err(ZcResult::InvalidArg),
         }
     };
 
-    // Create tokio runtime for this call
-    let rt = match tokio::runtime::Runtime::new() {
-        Ok(r) => r,
-        Err(_) => return ZcResult::Error,
+    let Some(rt) = shared_runtime() else {
+        return Err(ZcResult::Error);
     };
 
     // Run the agent with tool support
     let result = rt.block_on(async {
-        let config = &agent.config;
-
-        // Wire up agnostic subsystems
-        let observer: Arc<dyn Observer> =
-            Arc::from(observability::create_observer(&config.observability));
-
-        // Resolve provider
-        let provider_name = provider_override
-            .as_deref()
-            .or(config.default_provider.as_deref())
-            .unwrap_or("openrouter");
-
-        let model_name = model_override
-            .as_deref()
-            .or(config.default_model.as_deref())
-            .unwrap_or("anthropic/claude-sonnet-4-20250514");
-
-        let provider: Box<dyn Provider> = providers::create_routed_provider(
-            provider_name,
-            config.api_key.as_deref(),
-            &config.reliability,
-            &config.model_routes,
-            model_name,
-        )?;
-
-        // Build system prompt with tool instructions
-        let system_prompt = build_system_prompt(config, &agent.tools);
-
-        // Inject memory context into user message
-        let context = build_context(agent.memory.as_ref(), msg).await;
-        let enriched = if context.is_empty() {
-            msg.to_string()
-        } else {
-            format!("{context}{msg}")
-        };
+        let mut history = vec![ChatMessage::system(build_system_prompt(
+            &agent.config,
+            &agent.tools,
+        ))];
+        FfiDeltas::stream(
+            deltas,
+            run_turn(
+                agent,
+                &mut history,
+                msg,
+                provider_override.as_deref(),
+                model_override.as_deref(),
+                temperature,
+                request_budget,
+            ),
+        )
+        .await
+    });
+    Ok(result)
+}
 
-        let mut history = vec![
-            ChatMessage::system(&system_prompt),
-            ChatMessage::user(&enriched),
-        ];
+/// One user turn against `history`, which must start with the system prompt.
+/// Appends the user message, tool traffic and the final (or partial) answer.
+async fn run_turn(
+    agent: &AgentRuntime,
+    history: &mut Vec<ChatMessage>,
+    msg: &str,
+    provider_override: Option<&str>,
+    model_override: Option<&str>,
+    temperature: f64,
+    request_budget: TurnBudget,
+) -> anyhow::Result<TurnReply> {
+    let config = &agent.config;
+    let _run = crate::daemon::shutdown::begin_run();
+
+    // Wire up agnostic subsystems
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+
+    // Resolve provider
+    let provider_name = provider_override
+        .or(config.default_provider.as_deref())
+        .unwrap_or("openrouter");
+
+    let model_name = model_override
+        .or(config.default_model.as_deref())
+        .unwrap_or("anthropic/claude-sonnet-4-20250514");
+
+    let provider: Box<dyn Provider> = providers::create_routed_provider(
+        provider_name,
+        config.api_key.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        model_name,
+        observer.clone(),
+        &providers::ProviderSettings::from_config(config),
+    )?;
+    let provider = providers::cache::wrap(provider, config, provider_name);
+
+    // Inject memory context into user message
+    let context = build_context(agent.memory.as_ref(), msg, config).await;
+    let enriched = if context.is_empty() {
+        msg.to_string()
+    } else {
+        format!("{context}{msg}")
+    };
+    history.push(ChatMessage::user(&enriched));
 
-        // Run agent turn with tools
-        let response = agent::loop_::agent_turn(
+    // Run agent turn with tools, once its plan is approved where required
+    let vision_fallback = crate::providers::VisionFallback::from_config(config);
+    let temperature = if temperature == 0.0 { config.default_temperature } else { temperature };
+    let rejected = if config.autonomy.plans_first() {
+        agent::plan::review_plan(
             provider.as_ref(),
-            &mut history,
-            &agent.tools,
+            history,
             observer.as_ref(),
+            agent.user_prompt.as_deref(),
             model_name,
-            if temperature == 0.0 { config.default_temperature } else { temperature },
-        ).await?;
-
-        // Auto-save to memory
-        if config.memory.auto_save {
-            use uuid::Uuid;
-            let user_key = format!("user_msg_{}", Uuid::new_v4());
-            let _ = agent.memory.store(&user_key, msg, MemoryCategory::Conversation).await;
-            let summary = truncate_with_ellipsis(&response, 100);
-            let response_key = format!("assistant_resp_{}", Uuid::new_v4());
-            let _ = agent.memory.store(&response_key, &summary, MemoryCategory::Daily).await;
-        }
+            temperature,
+        ).await
+    } else {
+        None
+    };
+    let reply = match rejected {
+        Some(answer) => TurnReply { answer, exhausted: None },
+        None => tools::approval::scope(
+            agent.user_prompt.clone(),
+            agent::settings::scope(config, crate::hooks::scope(
+                config,
+                agent::loop_::agent_turn_with_budget(
+                    provider.as_ref(),
+                    history,
+                    &agent.tools,
+                    observer.as_ref(),
+                    model_name,
+                    temperature,
+                    vision_fallback.as_ref(),
+                    config.turn_budget_for(model_name).lowered_by(request_budget),
+                ),
+            )),
+        ).await?,
+    };
+    let response = if reply.exhausted.is_some() {
+        reply.answer
+    } else {
+        let answer = agent::verify::verify_answer(
+            provider.as_ref(),
+            history,
+            reply.answer,
+            model_name,
+            &config.verifier,
+        ).await;
+        agent::language::enforce(
+            provider.as_ref(),
+            history,
+            answer,
+            model_name,
+            agent::language::configured(&config.identity, "ffi").as_deref(),
+        ).await
+    };
+    let response = Guardrails::from_config(&config.guardrails, &config.workspace_dir)
+        .apply(provider.as_ref(), history, response, model_name, "ffi")
+        .await;
+
+    // Auto-save to memory
+    if config.memory.saves_raw_turns() {
+        use uuid::Uuid;
+        let user_key = format!("user_msg_{}", Uuid::new_v4());
+        let dedup = &config.memory.dedup;
+        let _ = memory::dedup::store(agent.memory.as_ref(), &user_key, msg, MemoryCategory::Conversation, dedup).await;
+        let summary = truncate_with_ellipsis(&response, 100);
+        let response_key = format!("assistant_resp_{}", Uuid::new_v4());
+        let _ = memory::dedup::store(agent.memory.as_ref(), &response_key, &summary, MemoryCategory::Daily, dedup).await;
+    }
+    memory::extract::after_turn(provider.as_ref(), agent.memory.as_ref(), &config.memory, model_name, msg, &response).await;
 
-        Ok::<String, anyhow::Error>(response)
-    });
+    Ok(TurnReply {
+        answer: response,
+        exhausted: reply.exhausted,
+    })
+}
 
+/// Hand a turn's answer to C, or the most specific code for its failure
+unsafe fn write_response(result: anyhow::Result<String>, out_response: *mut *mut c_char) -> ZcResult {
     match result {
         Ok(response) => {
             // Return the response to C code
@@ -430,220 +1078,1746 @@ pub unsafe extern "C" fn zc_agent_run_single(
             *out_response = cstr.into_raw();
             ZcResult::Ok
         }
-        Err(e) => {
-            eprintln!("Agent error: {}", e);
-            ZcResult::Error
-        }
+        Err(e) => fail_with("Agent error", &e),
     }
 }
 
-/// Run interactive agent loop with proper tool support
+/// Start a conversation whose history is kept between `zc_session_send`
+/// calls, so each message costs only its own tokens plus the transcript
+/// instead of a fresh context. Skills and tools are fixed at creation.
 ///
 /// # Safety
-/// Caller must ensure handle is valid
+/// Caller must ensure handle is valid and `out_session_id` can be written to
 #[no_mangle]
-pub unsafe extern "C" fn zc_agent_run_interactive(
+pub unsafe extern "C" fn zc_session_create(
     handle: *mut AgentRuntime,
-    provider: *const c_char,
-    model: *const c_char,
-    temperature: c_double,
+    out_session_id: *mut u64,
 ) -> ZcResult {
-    if handle.is_null() {
+    if handle.is_null() || out_session_id.is_null() {
         return ZcResult::InvalidArg;
     }
-
     let agent = &*handle;
 
-    let provider_override = if provider.is_null() {
-        None
-    } else {
-        match CStr::from_ptr(provider).to_str() {
-            Ok(s) => Some(s.to_string()),
-            Err(_) => return ZcResult::InvalidArg,
-        }
+    let history = vec![ChatMessage::system(build_system_prompt(
+        &agent.config,
+        &agent.tools,
+    ))];
+    let id = agent.next_session_id.fetch_add(1, Ordering::Relaxed);
+    let Ok(mut sessions) = agent.sessions.lock() else {
+        return ZcResult::Error;
+    };
+    sessions.insert(
+        id,
+        Arc::new(Mutex::new(SessionState {
+            history,
+            transcript: Vec::new(),
+        })),
+    );
+    *out_session_id = id;
+    ZcResult::Ok
+}
+
+/// Send `message` in an open session with the default provider, model and
+/// temperature. A failed turn leaves the history as it was. Returns
+/// `InvalidArg` for an unknown session id.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `message` is null-terminated UTF-8,
+/// and `out_response` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_session_send(
+    handle: *mut AgentRuntime,
+    session_id: u64,
+    message: *const c_char,
+    out_response: *mut *mut c_char,
+) -> ZcResult {
+    session_send(handle, session_id, message, None, out_response)
+}
+
+/// `zc_session_send`, streaming the reply's text to `on_delta` as in
+/// `zc_agent_run_stream`.
+///
+/// # Safety
+/// Same requirements as `zc_session_send`; `on_delta` and `user_data` must
+/// be usable from another thread until this returns
+#[no_mangle]
+pub unsafe extern "C" fn zc_session_send_stream(
+    handle: *mut AgentRuntime,
+    session_id: u64,
+    message: *const c_char,
+    on_delta: ZcDeltaFn,
+    user_data: *mut c_void,
+    out_response: *mut *mut c_char,
+) -> ZcResult {
+    let deltas = FfiDeltas::new(on_delta, user_data);
+    session_send(handle, session_id, message, deltas, out_response)
+}
+
+unsafe fn session_send(
+    handle: *mut AgentRuntime,
+    session_id: u64,
+    message: *const c_char,
+    deltas: Option<FfiDeltas>,
+    out_response: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || message.is_null() || out_response.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
+    let Ok(msg) = CStr::from_ptr(message).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+
+    let session = match agent.sessions.lock() {
+        Ok(sessions) => sessions.get(&session_id).cloned(),
+        Err(_) => return ZcResult::Error,
+    };
+    let Some(session) = session else {
+        return ZcResult::InvalidArg;
+    };
+    // Concurrent sends to one session run one after the other
+    let Ok(mut session) = session.lock() else {
+        return ZcResult::Error;
     };
 
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    let turn_start = session.history.len();
+    let result = rt.block_on(FfiDeltas::stream(
+        deltas,
+        observability::trace::session(
+            format!("ffi-{session_id}"),
+            run_turn(
+                agent,
+                &mut session.history,
+                msg,
+                None,
+                None,
+                0.0,
+                TurnBudget::default(),
+            ),
+        ),
+    )).and_then(TurnReply::into_result);
+    match &result {
+        Ok(response) => {
+            let model = agent.config.default_model.as_deref().unwrap_or_default();
+            agent::loop_::trim_history(&mut session.history, model);
+            session.transcript.push(ChatMessage::user(msg));
+            session.transcript.push(ChatMessage::assistant(response));
+        }
+        Err(_) => session.history.truncate(turn_start),
+    }
+
+    write_response(result, out_response)
+}
+
+/// Fork a session at `message_index` and rerun the turn containing it,
+/// optionally with another model or temperature (NULL / 0.0 for the
+/// defaults). Messages are indexed as the caller saw them: 0 is the first
+/// message sent, 1 its reply, and so on. The branch is a new session whose
+/// id goes to `out_session_id`; the original is left as it was. Tool calls
+/// from before the fork are not carried over, only the messages and replies.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `model` is NULL or null-terminated
+/// UTF-8, and `out_session_id` and `out_response` can be written to
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn zc_session_branch(
+    handle: *mut AgentRuntime,
+    session_id: u64,
+    message_index: u64,
+    model: *const c_char,
+    temperature: c_double,
+    out_session_id: *mut u64,
+    out_response: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || out_session_id.is_null() || out_response.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
     let model_override = if model.is_null() {
         None
     } else {
         match CStr::from_ptr(model).to_str() {
-            Ok(s) => Some(s.to_string()),
+            Ok(s) => Some(s),
             Err(_) => return ZcResult::InvalidArg,
         }
     };
 
-    println!("\x1b[2J\x1b[H"); // Clear screen
-    println!("\x1b[1m");
-    println!("╔══════════════════════════════════════════════════════════╗");
-    println!("║               CClaw Agent                                 ");
-    println!("╠══════════════════════════════════════════════════════════╣");
-    println!("║  Type /quit to exit  |  ZeroClaw v{}", env!("CARGO_PKG_VERSION"));
-    println!("╚══════════════════════════════════════════════════════════╝");
-    println!("\x1b[0m\n");
+    let session = match agent.sessions.lock() {
+        Ok(sessions) => sessions.get(&session_id).cloned(),
+        Err(_) => return ZcResult::Error,
+    };
+    let Some(session) = session else {
+        return ZcResult::InvalidArg;
+    };
+    let (mut transcript, msg) = {
+        let Ok(session) = session.lock() else {
+            return ZcResult::Error;
+        };
+        let index = usize::try_from(message_index).unwrap_or(usize::MAX);
+        let turn = match crate::sessions::branch_point(&session.transcript, index) {
+            Ok(turn) => turn,
+            Err(e) => return fail(ZcResult::InvalidArg, format!("Cannot branch session: {e}")),
+        };
+        (
+            session.transcript[..turn].to_vec(),
+            session.transcript[turn].content.clone(),
+        )
+    };
 
-    // Create tokio runtime
-    let rt = match tokio::runtime::Runtime::new() {
-        Ok(r) => r,
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    let mut history = vec![ChatMessage::system(build_system_prompt(
+        &agent.config,
+        &agent.tools,
+    ))];
+    history.extend(transcript.iter().cloned());
+    let result = rt.block_on(run_turn(
+        agent,
+        &mut history,
+        &msg,
+        None,
+        model_override,
+        temperature,
+        TurnBudget::default(),
+    )).and_then(TurnReply::into_result);
+
+    if let Ok(response) = &result {
+        let model = model_override
+            .or(agent.config.default_model.as_deref())
+            .unwrap_or_default();
+        agent::loop_::trim_history(&mut history, model);
+        transcript.push(ChatMessage::user(&msg));
+        transcript.push(ChatMessage::assistant(response));
+        let id = agent.next_session_id.fetch_add(1, Ordering::Relaxed);
+        let Ok(mut sessions) = agent.sessions.lock() else {
+            return ZcResult::Error;
+        };
+        sessions.insert(
+            id,
+            Arc::new(Mutex::new(SessionState {
+                history,
+                transcript,
+            })),
+        );
+        *out_session_id = id;
+    }
+
+    write_response(result, out_response)
+}
+
+/// Close a session and drop its history, after storing a summary of it
+/// when `[memory.summary]` is enabled. Returns `InvalidArg` for an unknown
+/// session id.
+///
+/// # Safety
+/// Caller must ensure handle is valid
+#[no_mangle]
+pub unsafe extern "C" fn zc_session_destroy(handle: *mut AgentRuntime, session_id: u64) -> ZcResult {
+    if handle.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
+    let removed = match agent.sessions.lock() {
+        Ok(mut sessions) => sessions.remove(&session_id),
         Err(_) => return ZcResult::Error,
     };
+    let Some(session) = removed else {
+        return ZcResult::InvalidArg;
+    };
+    summarize_sessions(agent, vec![(session_id, session)]);
+    ZcResult::Ok
+}
+
+/// List the agent's tools. `out_json` receives a JSON array of
+/// `{name, description, parameters}` with each tool's JSON schema.
+///
+/// # Safety
+/// Caller must ensure handle is valid and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_tool_list(handle: *mut AgentRuntime, out_json: *mut *mut c_char) -> ZcResult {
+    if handle.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
+    let specs: Vec<tools::ToolSpec> = agent.tools.iter().map(|t| t.spec()).collect();
+    match serde_json::to_string(&specs) {
+        Ok(json) => write_out_string(json, out_json),
+        Err(e) => fail(ZcResult::Error, format!("Tool list error: {e}")),
+    }
+}
+
+/// Run one tool directly, under the same security policy the agent uses.
+/// `args_json` is the tool's argument object (NULL = `{}`); `out_json`
+/// receives `{success, output, error}` whenever the tool ran. A tool that
+/// reports failure returns `ToolDenied`, `RateLimited` or `Error`, and an
+/// unknown tool name returns `InvalidArg`.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `name` and `args_json` (if non-NULL)
+/// are null-terminated UTF-8, and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_tool_execute(
+    handle: *mut AgentRuntime,
+    name: *const c_char,
+    args_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || name.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let Ok(name) = CStr::from_ptr(name).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+    let args = if args_json.is_null() {
+        serde_json::json!({})
+    } else {
+        match CStr::from_ptr(args_json)
+            .to_str()
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+        {
+            Some(v) if v.is_object() => v,
+            _ => return fail(ZcResult::InvalidArg, "Tool arguments must be a JSON object"),
+        }
+    };
+
+    let agent = &*handle;
+    let Some(tool) = agent.tools.iter().find(|t| t.name() == name) else {
+        return fail(ZcResult::InvalidArg, format!("Unknown tool: {name}"));
+    };
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    let _run = crate::daemon::shutdown::begin_run();
+    let start = std::time::Instant::now();
+    let result = rt.block_on(agent::settings::scope(&agent.config, tool.execute(args)));
+    let (success, output_bytes) = match &result {
+        Ok(r) => (r.success, r.output.len() + r.error.as_ref().map_or(0, String::len)),
+        Err(e) => (false, e.to_string().len()),
+    };
+    let workspace = &agent.config.workspace_dir;
+    observability::tool_stats::record(workspace, name, start.elapsed(), success, output_bytes);
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => return fail_with("Tool error", &e),
+    };
+
+    let code = if result.success {
+        ZcResult::Ok
+    } else {
+        let error = result.error.clone().unwrap_or_else(|| result.output.clone());
+        fail_with(&format!("Tool {name} failed"), &anyhow::anyhow!(error))
+    };
+    match serde_json::to_string(&result) {
+        Ok(json) => match write_out_string(json, out_json) {
+            ZcResult::Ok => code,
+            other => other,
+        },
+        Err(e) => fail(ZcResult::Error, format!("Tool result error: {e}")),
+    }
+}
+
+/// Usage statistics of the tools run in this agent's workspace, across
+/// sessions. `out_json` receives a JSON array of `{tool, invocations,
+/// failures, success_rate, mean_latency_ms, output_bytes, last_used}`, most
+/// used first.
+///
+/// # Safety
+/// Caller must ensure handle is valid and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_get_stats(
+    handle: *mut AgentRuntime,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
+    let stats = observability::tool_stats::summary(&agent.config.workspace_dir)
+        .and_then(|stats| Ok(serde_json::to_string(&stats)?));
+    write_response(stats, out_json)
+}
+
+/// Rate an answer: thumbs up when `rating` is positive, down when negative,
+/// with an optional `comment` (NULL for none). `session_id` is a saved
+/// session's id, or `ffi-<id>` for a session from `zc_session_create`;
+/// `message_index` counts messages as `zc_session_branch` does. Feedback
+/// goes to the workspace's `.zeroclaw/feedback.jsonl` as labels for eval
+/// and fine-tuning pipelines. Returns `InvalidArg` when the message is not
+/// an answer.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `session_id` is null-terminated
+/// UTF-8 and `comment` is NULL or null-terminated UTF-8
+#[no_mangle]
+pub unsafe extern "C" fn zc_feedback_record(
+    handle: *mut AgentRuntime,
+    session_id: *const c_char,
+    message_index: u64,
+    rating: i32,
+    comment: *const c_char,
+) -> ZcResult {
+    if handle.is_null() || session_id.is_null() || rating == 0 {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
+    let Ok(session_id) = CStr::from_ptr(session_id).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+    let comment = if comment.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(comment).to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(_) => return ZcResult::InvalidArg,
+        }
+    };
+    let index = usize::try_from(message_index).unwrap_or(usize::MAX);
+
+    // Live sessions are checked against their transcript here; saved ones
+    // are checked by `feedback::record`
+    let live = session_id
+        .strip_prefix("ffi-")
+        .and_then(|id| id.parse::<u64>().ok())
+        .and_then(|id| agent.sessions.lock().ok()?.get(&id).cloned());
+    if let Some(session) = live {
+        let Ok(session) = session.lock() else {
+            return ZcResult::Error;
+        };
+        if session.transcript.get(index).is_none_or(|m| m.role != "assistant") {
+            return fail(
+                ZcResult::InvalidArg,
+                format!("Message {index} of {session_id} is not an answer"),
+            );
+        }
+    }
+
+    let rating = if rating > 0 { Rating::Up } else { Rating::Down };
+    let entry = Feedback::new(session_id, index, rating, "ffi").with_comment(comment);
+    match feedback::record(&agent.config.workspace_dir, &entry) {
+        Ok(()) => ZcResult::Ok,
+        Err(e) => fail(ZcResult::InvalidArg, format!("Cannot record feedback: {e:#}")),
+    }
+}
+
+/// Totals of the feedback given in this agent's workspace. `out_json`
+/// receives `{total, up, down, approval_rate, recent}`, with the latest ten
+/// entries in `recent`, newest first.
+///
+/// # Safety
+/// Caller must ensure handle is valid and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_feedback_stats(
+    handle: *mut AgentRuntime,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
+    let stats = feedback::stats(&agent.config.workspace_dir)
+        .and_then(|stats| Ok(serde_json::to_string(&stats)?));
+    write_response(stats, out_json)
+}
+
+/// List the workspace checkpoints taken before tools changed files.
+/// `out_json` receives a JSON array of `{id, created_at, label}`, newest
+/// first and at most `limit` long (0 = all).
+///
+/// # Safety
+/// Caller must ensure handle is valid and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_list_checkpoints(
+    handle: *mut AgentRuntime,
+    limit: u32,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
+    let limit = if limit == 0 { usize::MAX } else { limit as usize };
+    let listed = tools::checkpoints::list(&agent.config.workspace_dir, limit)
+        .and_then(|checkpoints| Ok(serde_json::to_string(&checkpoints)?));
+    write_response(listed, out_json)
+}
+
+/// Put the workspace files back as they were at the newest checkpoint and
+/// drop it. `out_json` receives `{checkpoint, files, remaining}`, or `null`
+/// when there was nothing to undo.
+///
+/// # Safety
+/// Caller must ensure handle is valid and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_undo_last(handle: *mut AgentRuntime, out_json: *mut *mut c_char) -> ZcResult {
+    if handle.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
+    let undone = tools::checkpoints::undo_last(&agent.config.workspace_dir)
+        .and_then(|undone| Ok(serde_json::to_string(&undone)?));
+    write_response(undone, out_json)
+}
+
+fn parse_category(raw: &str) -> MemoryCategory {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "core" | "" => MemoryCategory::Core,
+        "daily" => MemoryCategory::Daily,
+        "conversation" => MemoryCategory::Conversation,
+        other => MemoryCategory::Custom(other.to_string()),
+    }
+}
+
+/// Store `content` under `key` in the agent's memory, replacing any entry
+/// with that key. `category` is "core" (default when NULL), "daily",
+/// "conversation" or a custom name.
+///
+/// # Safety
+/// Caller must ensure handle is valid and `key`, `content` and `category`
+/// (if non-NULL) are null-terminated UTF-8
+#[no_mangle]
+pub unsafe extern "C" fn zc_memory_store(
+    handle: *mut AgentRuntime,
+    key: *const c_char,
+    content: *const c_char,
+    category: *const c_char,
+) -> ZcResult {
+    if handle.is_null() || key.is_null() || content.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let (Ok(key), Ok(content)) = (CStr::from_ptr(key).to_str(), CStr::from_ptr(content).to_str())
+    else {
+        return ZcResult::InvalidArg;
+    };
+    let category = if category.is_null() {
+        MemoryCategory::Core
+    } else {
+        match CStr::from_ptr(category).to_str() {
+            Ok(s) => parse_category(s),
+            Err(_) => return ZcResult::InvalidArg,
+        }
+    };
+
+    let agent = &*handle;
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    match rt.block_on(agent.memory.store(key, content, category)) {
+        Ok(()) => ZcResult::Ok,
+        Err(e) => fail_with("Memory store error", &e),
+    }
+}
+
+/// Search memory for `query`. `out_json` receives a JSON array of entries
+/// (`key`, `content`, `category`, `timestamp`, `score`), best match first.
+/// `limit` of 0 returns up to 5.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `query` is null-terminated UTF-8,
+/// and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_memory_recall(
+    handle: *mut AgentRuntime,
+    query: *const c_char,
+    limit: u32,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || query.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let Ok(query) = CStr::from_ptr(query).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+    let limit = if limit == 0 { 5 } else { limit as usize };
+
+    let agent = &*handle;
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    let result = rt.block_on(agent.memory.recall(query, limit));
+    match result.and_then(|entries| Ok(serde_json::to_string(&entries)?)) {
+        Ok(json) => write_out_string(json, out_json),
+        Err(e) => fail_with("Memory recall error", &e),
+    }
+}
+
+/// Delete the entry stored under `key`. `out_removed` (may be NULL) is set to
+/// whether an entry existed.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `key` is null-terminated UTF-8, and
+/// `out_removed` (if non-NULL) can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_memory_forget(
+    handle: *mut AgentRuntime,
+    key: *const c_char,
+    out_removed: *mut bool,
+) -> ZcResult {
+    if handle.is_null() || key.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let Ok(key) = CStr::from_ptr(key).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+
+    let agent = &*handle;
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    match rt.block_on(agent.memory.forget(key)) {
+        Ok(removed) => {
+            if !out_removed.is_null() {
+                *out_removed = removed;
+            }
+            ZcResult::Ok
+        }
+        Err(e) => fail_with("Memory forget error", &e),
+    }
+}
+
+/// Number of entries in the agent's memory.
+///
+/// # Safety
+/// Caller must ensure handle is valid and `out_count` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_memory_count(handle: *mut AgentRuntime, out_count: *mut u64) -> ZcResult {
+    if handle.is_null() || out_count.is_null() {
+        return ZcResult::InvalidArg;
+    }
+
+    let agent = &*handle;
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    match rt.block_on(agent.memory.count()) {
+        Ok(count) => {
+            *out_count = count as u64;
+            ZcResult::Ok
+        }
+        Err(e) => fail_with("Memory count error", &e),
+    }
+}
+
+/// Parse the optional browse filter in `filter_json`; NULL = no filter.
+unsafe fn parse_browse_filter(filter_json: *const c_char) -> Option<BrowseFilter> {
+    if filter_json.is_null() {
+        return Some(BrowseFilter::default());
+    }
+    let filter: BrowseFilter = CStr::from_ptr(filter_json)
+        .to_str()
+        .ok()
+        .and_then(|s| serde_json::from_str(s).ok())?;
+    filter.check().is_ok().then_some(filter)
+}
+
+/// The agent's own memory, or the one of the namespace `filter` names.
+fn browsed_memory(agent: &AgentRuntime, filter: &BrowseFilter) -> anyhow::Result<Arc<dyn Memory>> {
+    match filter.namespace.as_deref() {
+        Some(name) if agent.config.memory.namespace.as_deref() != Some(name) => {
+            let memory = browse::open_namespace(
+                &agent.config.memory,
+                &agent.config.workspace_dir,
+                name,
+            )?;
+            Ok(Arc::from(memory))
+        }
+        _ => Ok(agent.memory.clone()),
+    }
+}
+
+/// One page of what the agent remembers, like `GET /memory`. `filter_json`
+/// (NULL = everything) is an object with any of `category`, `namespace`,
+/// `since`, `until` (a date or RFC 3339 time), `key_prefix`, `offset` and
+/// `limit` (default 50, at most 500). `out_json` receives
+/// `{namespace, total, offset, limit, next_offset, entries, namespaces}`.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `filter_json` (if non-NULL) is
+/// null-terminated UTF-8, and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_memory_browse(
+    handle: *mut AgentRuntime,
+    filter_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let Some(filter) = parse_browse_filter(filter_json) else {
+        return ZcResult::InvalidArg;
+    };
+
+    let agent = &*handle;
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    let page = rt.block_on(async {
+        let memory = browsed_memory(agent, &filter)?;
+        let page = browse::browse(memory.as_ref(), &filter).await?;
+        let mut json = serde_json::to_value(&page)?;
+        json["namespaces"] = serde_json::json!(browse::namespaces(&agent.config.workspace_dir));
+        Ok::<_, anyhow::Error>(json.to_string())
+    });
+    match page {
+        Ok(json) => write_out_string(json, out_json),
+        Err(e) => fail_with("Memory browse error", &e),
+    }
+}
+
+/// Forget every memory `filter_json` matches, like `DELETE /memory`; the
+/// filter must name a category, dates or a key prefix. `out_json` receives
+/// `{count, removed}` with the removed keys.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `filter_json` is null-terminated
+/// UTF-8, and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_memory_forget_matching(
+    handle: *mut AgentRuntime,
+    filter_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || filter_json.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let Some(filter) = parse_browse_filter(filter_json) else {
+        return ZcResult::InvalidArg;
+    };
+    if filter.is_empty() {
+        return fail(
+            ZcResult::InvalidArg,
+            "Give category, since, until or key_prefix to choose what to forget",
+        );
+    }
+
+    let agent = &*handle;
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    let removed = rt.block_on(async {
+        let memory = browsed_memory(agent, &filter)?;
+        browse::forget_matching(memory.as_ref(), &filter).await
+    });
+    match removed {
+        Ok(removed) => write_out_string(
+            serde_json::json!({"count": removed.len(), "removed": removed}).to_string(),
+            out_json,
+        ),
+        Err(e) => fail_with("Memory forget error", &e),
+    }
+}
 
-    // Setup agent components
+/// Render the prompt template `name` from `~/.zeroclaw/prompts/` with the
+/// variables in `vars_json` (a JSON object; NULL = none) and run it like
+/// `zc_agent_run_single` with the default provider, model and temperature.
+/// Returns `InvalidArg` for an unknown template or a missing variable.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `name` and `vars_json` (if non-NULL)
+/// are null-terminated UTF-8, and `out_response` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_run_template(
+    handle: *mut AgentRuntime,
+    name: *const c_char,
+    vars_json: *const c_char,
+    out_response: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || name.is_null() || out_response.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let Ok(name) = CStr::from_ptr(name).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+    let vars = if vars_json.is_null() {
+        serde_json::json!({})
+    } else {
+        match CStr::from_ptr(vars_json)
+            .to_str()
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+        {
+            Some(v) if v.is_object() => v,
+            _ => return ZcResult::InvalidArg,
+        }
+    };
+
+    let agent = &*handle;
+    let prompt = match PromptLibrary::from_config(&agent.config).render(name, &vars) {
+        Ok(p) => p,
+        Err(e) => return fail(ZcResult::InvalidArg, format!("Template error: {e}")),
+    };
+    let Ok(prompt) = CString::new(prompt) else {
+        return ZcResult::InvalidArg;
+    };
+    zc_agent_run_single(
+        handle,
+        prompt.as_ptr(),
+        std::ptr::null(),
+        std::ptr::null(),
+        0.0,
+        out_response,
+    )
+}
+
+/// Search the configured skill registry. `query` may be NULL to list every
+/// entry; `out_json` receives a JSON array of registry entries.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `query` (if non-NULL) is a
+/// null-terminated UTF-8 string, and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_skills_search(
+    handle: *mut AgentRuntime,
+    query: *const c_char,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let query = if query.is_null() {
+        ""
+    } else {
+        match CStr::from_ptr(query).to_str() {
+            Ok(s) => s,
+            Err(_) => return ZcResult::InvalidArg,
+        }
+    };
+
+    let agent = &*handle;
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
     let result = rt.block_on(async {
-        let config = &agent.config;
-
-        // Wire up agnostic subsystems
-        let observer: Arc<dyn Observer> =
-            Arc::from(observability::create_observer(&config.observability));
-
-        // Resolve provider
-        let provider_name = provider_override
-            .as_deref()
-            .or(config.default_provider.as_deref())
-            .unwrap_or("openrouter");
-
-        let model_name = model_override
-            .as_deref()
-            .or(config.default_model.as_deref())
-            .unwrap_or("anthropic/claude-sonnet-4-20250514");
-
-        let provider: Box<dyn Provider> = providers::create_routed_provider(
-            provider_name,
-            config.api_key.as_deref(),
-            &config.reliability,
-            &config.model_routes,
-            model_name,
-        )?;
+        let client = RegistryClient::from_config(&agent.config.skill_registry)?;
+        client.search(query).await
+    });
+    match result.and_then(|entries| Ok(serde_json::to_string(&entries)?)) {
+        Ok(json) => write_out_string(json, out_json),
+        Err(e) => fail_with("Skill registry error", &e),
+    }
+}
 
-        // Build system prompt with tool instructions
-        let system_prompt = build_system_prompt(config, &agent.tools);
+/// Accounts connected through Composio for the configured entity.
+/// `out_json` receives a JSON array of `{id, appName, status, createdAt}`.
+///
+/// # Safety
+/// Caller must ensure handle is valid and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_composio_connections(
+    handle: *mut AgentRuntime,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &*handle;
+    let Some(composio) = tools::ComposioAccounts::from_config(&agent.config) else {
+        return fail(ZcResult::NotInitialized, "Composio is not enabled");
+    };
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    match rt
+        .block_on(composio.list())
+        .and_then(|connections| Ok(serde_json::to_string(&connections)?))
+    {
+        Ok(json) => write_out_string(json, out_json),
+        Err(e) => fail_with("Composio error", &e),
+    }
+}
 
-        Ok::<(Box<dyn Provider>, Arc<dyn Observer>, String, String), anyhow::Error>(
-            (provider, observer, model_name.to_string(), system_prompt)
-        )
+/// Start a Composio OAuth flow for `app` (e.g. "github"). `out_url`
+/// receives the URL the user must open to finish connecting.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `app` is a null-terminated UTF-8
+/// string, and `out_url` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_composio_connect(
+    handle: *mut AgentRuntime,
+    app: *const c_char,
+    out_url: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || app.is_null() || out_url.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let Ok(app) = CStr::from_ptr(app).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+    let agent = &*handle;
+    let Some(composio) = tools::ComposioAccounts::from_config(&agent.config) else {
+        return fail(ZcResult::NotInitialized, "Composio is not enabled");
+    };
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    match rt.block_on(composio.connect(app.trim())) {
+        Ok(url) => write_out_string(url, out_url),
+        Err(e) => fail_with("Composio error", &e),
+    }
+}
+
+/// Revoke the Composio connection `connection_id`, as listed by
+/// `zc_composio_connections`.
+///
+/// # Safety
+/// Caller must ensure handle is valid and `connection_id` is a
+/// null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn zc_composio_disconnect(
+    handle: *mut AgentRuntime,
+    connection_id: *const c_char,
+) -> ZcResult {
+    if handle.is_null() || connection_id.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let Ok(connection_id) = CStr::from_ptr(connection_id).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+    let agent = &*handle;
+    let Some(composio) = tools::ComposioAccounts::from_config(&agent.config) else {
+        return fail(ZcResult::NotInitialized, "Composio is not enabled");
+    };
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    match rt.block_on(composio.revoke(connection_id)) {
+        Ok(()) => ZcResult::Ok,
+        Err(e) => fail_with("Composio error", &e),
+    }
+}
+
+/// Callback handed the URL of a sign-in page, to open in a browser or show
+/// to the user. Called once, on the calling thread, before the sign-in
+/// waits for the browser.
+pub type ZcOpenUrlFn = Option<unsafe extern "C" fn(user_data: *mut c_void, url: *const c_char)>;
+
+/// Hand `url` to the host's callback.
+fn open_with(callback: ZcOpenUrlFn, user_data: *mut c_void) -> impl FnOnce(&str) {
+    move |url| {
+        if let (Some(callback), Ok(url)) = (callback, CString::new(url)) {
+            // SAFETY: the string outlives the call; the caller vouches for the callback
+            unsafe { callback(user_data, url.as_ptr()) };
+        }
+    }
+}
+
+fn sign_in_timeout(timeout_secs: u32) -> std::time::Duration {
+    if timeout_secs == 0 {
+        integrations::oauth::DEFAULT_TIMEOUT
+    } else {
+        std::time::Duration::from_secs(u64::from(timeout_secs))
+    }
+}
+
+/// Connect `app` through Composio and block until the browser returns to
+/// a local callback server, or `timeout_secs` pass (0 = five minutes).
+/// `out_connection_id` receives the new connection's id, or an empty
+/// string when Composio does not report one.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `app` is a null-terminated UTF-8
+/// string, `open_url` is safe to call with `user_data`, and
+/// `out_connection_id` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_composio_connect_wait(
+    handle: *mut AgentRuntime,
+    app: *const c_char,
+    open_url: ZcOpenUrlFn,
+    user_data: *mut c_void,
+    timeout_secs: u32,
+    out_connection_id: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || app.is_null() || out_connection_id.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let (Ok(app), true) = (CStr::from_ptr(app).to_str(), open_url.is_some()) else {
+        return ZcResult::InvalidArg;
+    };
+    let agent = &*handle;
+    let Some(composio) = tools::ComposioAccounts::from_config(&agent.config) else {
+        return fail(ZcResult::NotInitialized, "Composio is not enabled");
+    };
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    let connect = composio.connect_and_wait(
+        app.trim(),
+        open_with(open_url, user_data),
+        sign_in_timeout(timeout_secs),
+    );
+    match rt.block_on(connect) {
+        Ok(id) => write_out_string(id.unwrap_or_default(), out_connection_id),
+        Err(e) => fail_with("Composio error", &e),
+    }
+}
+
+/// Sign in to the OAuth app configured under `[oauth.<provider>]` (e.g.
+/// "github", "google"): `open_url` receives the consent page, and the call
+/// blocks until the browser returns to a local callback server or
+/// `timeout_secs` pass (0 = five minutes). The tokens are stored encrypted
+/// with the secret store; `out_json` receives
+/// `{provider, scope, expires_at, refreshable}`.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `provider` is a null-terminated
+/// UTF-8 string, `open_url` is safe to call with `user_data`, and
+/// `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_oauth_login(
+    handle: *mut AgentRuntime,
+    provider: *const c_char,
+    open_url: ZcOpenUrlFn,
+    user_data: *mut c_void,
+    timeout_secs: u32,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || provider.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let (Ok(provider), true) = (CStr::from_ptr(provider).to_str(), open_url.is_some()) else {
+        return ZcResult::InvalidArg;
+    };
+    let provider = provider.trim().to_lowercase();
+    let agent = &*handle;
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    let login = integrations::oauth::login(
+        &agent.config,
+        &provider,
+        open_with(open_url, user_data),
+        sign_in_timeout(timeout_secs),
+    );
+    match rt
+        .block_on(login)
+        .and_then(|summary| Ok(serde_json::to_string(&summary)?))
+    {
+        Ok(json) => write_out_string(json, out_json),
+        Err(e) => fail_with("OAuth error", &e),
+    }
+}
+
+/// Install the registry package `name` into the workspace. `out_json`
+/// receives the installed skill's metadata. Skills are loaded when an agent
+/// is initialized, so the new skill is visible to the next `zc_agent_init`.
+///
+/// # Safety
+/// Caller must ensure handle is valid, `name` is a null-terminated UTF-8
+/// string, and `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_skills_install(
+    handle: *mut AgentRuntime,
+    name: *const c_char,
+    out_json: *mut *mut c_char,
+) -> ZcResult {
+    if handle.is_null() || name.is_null() || out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let Ok(name) = CStr::from_ptr(name).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+
+    let agent = &*handle;
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+    let result = rt.block_on(async {
+        let client = RegistryClient::from_config(&agent.config.skill_registry)?;
+        client.install(&agent.config.workspace_dir, name).await
     });
+    match result.and_then(|skill| Ok(serde_json::to_string(&skill)?)) {
+        Ok(json) => write_out_string(json, out_json),
+        Err(e) => fail_with("Skill install error", &e),
+    }
+}
+
+unsafe fn write_out_string(s: String, out: *mut *mut c_char) -> ZcResult {
+    match CString::new(s) {
+        Ok(cstr) => {
+            *out = cstr.into_raw();
+            ZcResult::Ok
+        }
+        Err(_) => ZcResult::Error,
+    }
+}
+
+/// Run interactive agent loop with proper tool support
+///
+/// # Safety
+/// Caller must ensure handle is valid
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_run_interactive(
+    handle: *mut AgentRuntime,
+    provider: *const c_char,
+    model: *const c_char,
+    temperature: c_double,
+) -> ZcResult {
+    if handle.is_null() {
+        return ZcResult::InvalidArg;
+    }
 
-    let (provider, observer, model_name, system_prompt): (Box<dyn Provider>, Arc<dyn Observer>, String, String) = match result {
-        Ok(t) => t,
+    let agent = &mut *handle;
+
+    let provider_override = if provider.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(provider).to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(_) => return ZcResult::InvalidArg,
+        }
+    };
+
+    let model_override = if model.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(model).to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(_) => return ZcResult::InvalidArg,
+        }
+    };
+
+    print_banner();
+
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+
+    // Questions from ask_user go to the terminal unless the app set a callback
+    let terminal_ask_user = !agent.tools.iter().any(|t| t.name() == "ask_user");
+    if terminal_ask_user {
+        agent.tools.push(Box::new(tools::AskUserTool::new(Arc::new(
+            tools::ask_user::TerminalPrompt,
+        ))));
+    }
+
+    let result = interactive_session(
+        agent,
+        rt,
+        provider_override.as_deref(),
+        model_override.as_deref(),
+        temperature,
+    );
+
+    if terminal_ask_user {
+        agent.tools.retain(|t| t.name() != "ask_user");
+    }
+    match result {
+        Ok(()) => ZcResult::Ok,
         Err(e) => {
-            eprintln!("Failed to initialize: {}", e);
-            return ZcResult::Error;
+            eprintln!("{e:#}");
+            ZcResult::Error
         }
-    };
+    }
+}
 
-    // Interactive mode using rustyline for proper line editing
-    use rustyline::Editor;
-    use rustyline::history::DefaultHistory;
-    
+fn print_banner() {
     println!("\x1b[2J\x1b[H"); // Clear screen
     println!("\x1b[1m");
     println!("╔══════════════════════════════════════════════════════════╗");
     println!("║                     CClaw Agent                           ");
     println!("╠══════════════════════════════════════════════════════════╣");
-    println!("║  Type /quit to exit  |  ZeroClaw v{}", env!("CARGO_PKG_VERSION"));
+    println!("║  /help lists commands  |  ZeroClaw v{}", env!("CARGO_PKG_VERSION"));
     println!("╚══════════════════════════════════════════════════════════╝");
     println!("\x1b[0m\n");
+}
 
-    // Create rustyline editor
-    let mut rl = match Editor::<(), DefaultHistory>::new() {
-        Ok(editor) => editor,
-        Err(e) => {
-            eprintln!("Failed to create editor: {}", e);
-            return ZcResult::Error;
-        }
-    };
+/// The prompt loop of `zc_agent_run_interactive`, until `/quit`, end of
+/// input or a second Ctrl-C.
+fn interactive_session(
+    agent: &mut AgentRuntime,
+    rt: &Runtime,
+    provider_override: Option<&str>,
+    model_override: Option<&str>,
+    temperature: f64,
+) -> anyhow::Result<()> {
+    // Interactive mode using rustyline for proper line editing
+    use rustyline::error::ReadlineError;
+    use rustyline::history::DefaultHistory;
+    use rustyline::Editor;
+
+    let mut state = rt
+        .block_on(async { interactive_state(agent, provider_override, model_override) })
+        .context("Failed to initialize")?;
+
+    print_banner();
+
+    let mut rl = Editor::<(), DefaultHistory>::new().context("Failed to create editor")?;
     rl.load_history(&std::path::Path::new(".zeroclaw_history")).ok();
 
-    // Persistent conversation history across turns
-    let mut history: Vec<ChatMessage> = vec![ChatMessage::system(&system_prompt)];
+    let plan_prompt: Arc<dyn tools::ask_user::UserPrompt> = agent
+        .user_prompt
+        .clone()
+        .unwrap_or_else(|| Arc::new(tools::ask_user::TerminalPrompt));
+    let vision_fallback = crate::providers::VisionFallback::from_config(&agent.config);
+    let mut instructions =
+        crate::identity::InstructionWatcher::from_config(&agent.config.identity, &agent.security);
 
+    let mut interrupts = agent::interrupt::Interrupts::default();
     loop {
-        let readline = rl.readline("> ");
-        match readline {
-            Ok(line) => {
-                rl.add_history_entry(&line);
-                let line = line.trim();
-                if line.is_empty() {
-                    continue;
-                }
-                if line == "/quit" || line == "/exit" {
+        let line = match rl.readline("> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                if interrupts.press() {
                     break;
                 }
-
-                // Process message through agent with tools
-                let msg = line.to_string();
-                let config = &agent.config;
-                let temp = if temperature == 0.0 { config.default_temperature } else { temperature };
-
-                let result = rt.block_on(async {
-                    // Inject memory context
-                    let context = build_context(agent.memory.as_ref(), &msg).await;
-                    let enriched = if context.is_empty() {
-                        msg.clone()
-                    } else {
-                        format!("{context}{msg}")
-                    };
-
-                    // Add user message to history
-                    history.push(ChatMessage::user(&enriched));
-
-                    // Run agent turn with tools
-                    let response = agent::loop_::agent_turn(
-                        provider.as_ref(),
-                        &mut history,
-                        &agent.tools,
-                        observer.as_ref(),
-                        &model_name,
-                        temp,
-                    ).await;
-
-                    // Auto-save to memory
-                    if config.memory.auto_save {
-                        use uuid::Uuid;
-                        let user_key = format!("user_msg_{}", Uuid::new_v4());
-                        let _ = agent.memory.store(&user_key, &msg, MemoryCategory::Conversation).await;
-                    }
-
-                    response
-                });
-
-                match result {
-                    Ok(resp) => {
-                        println!("\n{}\n", resp);
-
-                        // Auto-save response
-                        if agent.config.memory.auto_save {
-                            let summary = truncate_with_ellipsis(&resp, 100);
-                            rt.block_on(async {
-                                use uuid::Uuid;
-                                let response_key = format!("assistant_resp_{}", Uuid::new_v4());
-                                let _ = agent.memory.store(&response_key, &summary, MemoryCategory::Daily).await;
-                            });
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("\nError: {}\n", e);
-                    }
-                }
+                println!("^C (press Ctrl-C again to quit)");
+                continue;
             }
-            Err(rustyline::error::ReadlineError::Interrupted) => {
-                println!("^C");
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("Error: {err:?}");
                 break;
             }
-            Err(rustyline::error::ReadlineError::Eof) => {
-                break;
+        };
+        let _ = rl.add_history_entry(&line);
+        let line = if agent::commands::opens_block(&line) {
+            match read_block(&mut rl, line) {
+                Ok(block) => block,
+                Err(ReadlineError::Interrupted) => {
+                    println!("^C (message discarded)");
+                    continue;
+                }
+                Err(_) => break,
             }
-            Err(err) => {
-                eprintln!("Error: {:?}", err);
-                break;
+        } else {
+            line
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match agent::commands::parse(line) {
+            Some(Ok(agent::commands::Command::Quit)) => break,
+            Some(Ok(command)) => {
+                run_command(agent, rt, &mut state, command);
+                continue;
+            }
+            Some(Err(usage)) => {
+                eprintln!("{usage}");
+                continue;
+            }
+            None => {}
+        }
+
+        // Edited AGENTS.md / SOUL.md take effect on the next turn
+        if instructions.changed() {
+            state.history[0] = ChatMessage::system(build_system_prompt(&agent.config, &agent.tools));
+            println!("📝 Instruction files changed — system prompt reloaded");
+        }
+
+        // Process message through agent with tools
+        let msg = line.to_string();
+        let temp = if temperature == 0.0 { agent.config.default_temperature } else { temperature };
+        let turn = interactive_turn(agent, &mut state, &msg, temp, &plan_prompt, vision_fallback.as_ref());
+        match rt.block_on(turn) {
+            Ok(resp) => after_interactive_reply(agent, rt, &mut state, &msg, &resp),
+            Err(e) if e.is::<agent::interrupt::TurnCancelled>() => {
+                interrupts.press();
+                println!("\n^C — turn cancelled (press Ctrl-C again to quit)\n");
+            }
+            Err(e) => {
+                eprintln!("\nError: {e}\n");
             }
         }
     }
 
+    rt.block_on(memory::summary::on_close(
+        state.provider.as_ref(),
+        agent.memory.as_ref(),
+        &agent.config.memory,
+        &state.model_name,
+        state.session.id(),
+        &state.session.messages,
+    ));
+
     // Save history (if supported)
     let history_path = std::path::Path::new(".zeroclaw_history");
     let _ = rl.save_history(history_path);
-    
-    ZcResult::Ok
+
+    // Tools switched off with /tools stay registered for later calls
+    agent.tools.append(&mut state.disabled_tools);
+    Ok(())
+}
+
+/// Provider and a history holding just the system prompt, for a new
+/// interactive session.
+fn interactive_state(
+    agent: &AgentRuntime,
+    provider_override: Option<&str>,
+    model_override: Option<&str>,
+) -> anyhow::Result<InteractiveState> {
+    let config = &agent.config;
+
+    // Wire up agnostic subsystems
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+
+    // Resolve provider
+    let provider_name = provider_override
+        .or(config.default_provider.as_deref())
+        .unwrap_or("openrouter");
+
+    let model_name = model_override
+        .or(config.default_model.as_deref())
+        .unwrap_or("anthropic/claude-sonnet-4-20250514");
+
+    let provider = interactive_provider(config, provider_name, model_name, &observer)?;
+
+    // Build system prompt with tool instructions
+    let system_prompt = build_system_prompt(config, &agent.tools);
+
+    Ok(InteractiveState {
+        provider,
+        observer,
+        provider_name: provider_name.to_string(),
+        model_name: model_name.to_string(),
+        history: vec![ChatMessage::system(&system_prompt)],
+        disabled_tools: Vec::new(),
+        session: Session::new("cli", model_name),
+        attachments: Vec::new(),
+    })
+}
+
+/// One interactive turn: the answer to `msg` once its plan (where required),
+/// tools, verification and guardrails have run. A cancelled turn leaves no
+/// trace in the history.
+async fn interactive_turn(
+    agent: &AgentRuntime,
+    state: &mut InteractiveState,
+    msg: &str,
+    temp: f64,
+    plan_prompt: &Arc<dyn tools::ask_user::UserPrompt>,
+    vision_fallback: Option<&crate::providers::VisionFallback>,
+) -> anyhow::Result<String> {
+    let config = &agent.config;
+    let InteractiveState { provider, observer, model_name, history, attachments, .. } = state;
+
+    // Inject memory context
+    let context = build_context(agent.memory.as_ref(), msg, config).await;
+    let enriched = if context.is_empty() {
+        msg.to_string()
+    } else {
+        format!("{context}{msg}")
+    };
+
+    // Add user message, with any /attach files, to history
+    let turn_start = history.len();
+    let mut message = ChatMessage::user(&enriched);
+    agent::commands::attach(&mut message, std::mem::take(attachments));
+    history.push(message);
+
+    // Run agent turn with tools, once its plan is approved where required
+    let rejected = if config.autonomy.plans_first() {
+        agent::plan::review_plan(
+            provider.as_ref(),
+            history,
+            observer.as_ref(),
+            Some(plan_prompt.as_ref()),
+            model_name,
+            temp,
+        ).await
+    } else {
+        None
+    };
+    let response = if let Some(reply) = rejected {
+        Ok(reply)
+    } else {
+        // Ctrl-C cancels this turn only
+        let (cancel, watcher) = agent::interrupt::cancel_on_ctrl_c();
+        let reply = tools::approval::scope(
+            Some(plan_prompt.clone()),
+            agent::settings::scope(config, crate::hooks::scope(
+                config,
+                agent::loop_::agent_turn_with_cancel(
+                    provider.as_ref(),
+                    history,
+                    &agent.tools,
+                    observer.as_ref(),
+                    model_name,
+                    temp,
+                    vision_fallback,
+                    TurnBudget::default(),
+                    &cancel,
+                ),
+            )),
+        ).await;
+        watcher.abort();
+        if reply.as_ref().is_err_and(anyhow::Error::is::<agent::interrupt::TurnCancelled>) {
+            history.truncate(turn_start);
+        }
+        reply.and_then(TurnReply::into_result)
+    };
+    let response = match response {
+        Ok(answer) => {
+            let answer = agent::verify::verify_answer(
+                provider.as_ref(),
+                history,
+                answer,
+                model_name,
+                &config.verifier,
+            ).await;
+            let answer = agent::language::enforce(
+                provider.as_ref(),
+                history,
+                answer,
+                model_name,
+                agent::language::configured(&config.identity, "ffi").as_deref(),
+            ).await;
+            Ok(Guardrails::from_config(&config.guardrails, &config.workspace_dir)
+                .apply(provider.as_ref(), history, answer, model_name, "ffi")
+                .await)
+        }
+        Err(e) => Err(e),
+    };
+
+    // Auto-save to memory
+    if config.memory.saves_raw_turns() {
+        use uuid::Uuid;
+        let user_key = format!("user_msg_{}", Uuid::new_v4());
+        let dedup = &config.memory.dedup;
+        let _ = memory::dedup::store(agent.memory.as_ref(), &user_key, msg, MemoryCategory::Conversation, dedup).await;
+    }
+
+    response
+}
+
+/// Show an interactive answer and record it in the session and memory.
+fn after_interactive_reply(
+    agent: &AgentRuntime,
+    rt: &Runtime,
+    state: &mut InteractiveState,
+    msg: &str,
+    resp: &str,
+) {
+    println!("\n{}\n", crate::render::for_terminal(resp, &agent.config.render));
+    state.session.push(ChatMessage::user(msg));
+    state.session.push(ChatMessage::assistant(resp));
+
+    // Auto-save response
+    if agent.config.memory.saves_raw_turns() {
+        let summary = truncate_with_ellipsis(resp, 100);
+        rt.block_on(async {
+            use uuid::Uuid;
+            let response_key = format!("assistant_resp_{}", Uuid::new_v4());
+            let dedup = &agent.config.memory.dedup;
+            let _ = memory::dedup::store(agent.memory.as_ref(), &response_key, &summary, MemoryCategory::Daily, dedup).await;
+        });
+    }
+    rt.block_on(memory::extract::after_turn(
+        state.provider.as_ref(),
+        agent.memory.as_ref(),
+        &agent.config.memory,
+        &state.model_name,
+        msg,
+        resp,
+    ));
+}
+
+/// What slash commands can change in `zc_agent_run_interactive`.
+struct InteractiveState {
+    provider: Box<dyn Provider>,
+    observer: Arc<dyn Observer>,
+    provider_name: String,
+    model_name: String,
+    history: Vec<ChatMessage>,
+    /// Tools switched off with `/tools disable`, kept out of `agent.tools`
+    disabled_tools: Vec<Box<dyn Tool>>,
+    /// User/assistant exchanges for `/save`
+    session: Session,
+    /// Files from `/attach` waiting for the next message
+    attachments: Vec<agent::commands::Attachment>,
+}
+
+/// Read the lines of a message opened with ```` ``` ```` up to the closing
+/// fence; the fences are kept so the model sees a code block.
+fn read_block(
+    rl: &mut rustyline::Editor<(), rustyline::history::DefaultHistory>,
+    first: String,
+) -> Result<String, rustyline::error::ReadlineError> {
+    let mut block = first;
+    loop {
+        let line = rl.readline("... ")?;
+        block.push('\n');
+        block.push_str(&line);
+        if agent::commands::closes_block(&line) {
+            return Ok(block);
+        }
+    }
+}
+
+fn interactive_provider(
+    config: &Config,
+    provider_name: &str,
+    model_name: &str,
+    observer: &Arc<dyn Observer>,
+) -> anyhow::Result<Box<dyn Provider>> {
+    let provider = providers::create_routed_provider(
+        provider_name,
+        config.api_key.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        model_name,
+        observer.clone(),
+        &providers::ProviderSettings::from_config(config),
+    )?;
+    Ok(providers::cache::wrap(provider, config, provider_name))
+}
+
+/// Carry out a slash command from the interactive loop, printing the result.
+fn run_command(
+    agent: &mut AgentRuntime,
+    rt: &tokio::runtime::Runtime,
+    state: &mut InteractiveState,
+    command: agent::commands::Command,
+) {
+    use agent::commands::{self, Command};
+
+    let result = match command {
+        Command::Help | Command::Quit => Ok(commands::HELP.to_string()),
+        Command::Model(None) => Ok(format!("Model: {}", state.model_name)),
+        Command::Provider(None) => Ok(format!("Provider: {}", state.provider_name)),
+        Command::Model(Some(model)) => {
+            interactive_provider(&agent.config, &state.provider_name, &model, &state.observer)
+                .map(|provider| {
+                    state.provider = provider;
+                    state.model_name = model;
+                    format!("Model switched to {}", state.model_name)
+                })
+        }
+        Command::Provider(Some(name)) => {
+            interactive_provider(&agent.config, &name, &state.model_name, &state.observer)
+                .map(|provider| {
+                    state.provider = provider;
+                    state.provider_name = name;
+                    format!("Provider switched to {}", state.provider_name)
+                })
+        }
+        Command::Profile(None) => Ok(profile_overview(agent)),
+        Command::Profile(Some(name)) => switch_profile(agent, state, &name),
+        Command::Tools => Ok(commands::tool_list(&agent.tools, &state.disabled_tools)),
+        Command::EnableTool(name) => toggle_tool(agent, state, &name, true),
+        Command::DisableTool(name) => toggle_tool(agent, state, &name, false),
+        Command::Memory(query) => {
+            rt.block_on(commands::search_memory(agent.memory.as_ref(), &query))
+        }
+        Command::History => Ok(commands::history_overview(&state.history, &state.model_name)),
+        Command::CompactHistory => rt
+            .block_on(commands::compact_history(
+                state.provider.as_ref(),
+                &mut state.history,
+                &state.model_name,
+            ))
+            .map(|n| format!("Compacted {n} messages into a summary")),
+        Command::Save => save_interactive_session(agent, rt, state),
+        Command::Attach(path) => commands::Attachment::read(std::path::Path::new(&path)).map(
+            |attachment| {
+                let text = format!("📎 {} goes with your next message", attachment.describe());
+                state.attachments.push(attachment);
+                text
+            },
+        ),
+        Command::Checkpoints => tools::checkpoints::list(&agent.config.workspace_dir, 20)
+            .map(|checkpoints| tools::checkpoints::describe_list(&checkpoints)),
+        Command::Undo => tools::checkpoints::undo_last(&agent.config.workspace_dir)
+            .map(|undone| tools::checkpoints::describe_undo(undone.as_ref())),
+    };
+    match result {
+        Ok(text) => println!("{text}\n"),
+        Err(e) => eprintln!("{e}\n"),
+    }
+}
+
+/// `/profile`: the applied profile and the configured ones.
+fn profile_overview(agent: &AgentRuntime) -> String {
+    let current = agent.profile.as_deref().unwrap_or("none");
+    let configured: Vec<&str> = agent.base_config.profiles.keys().map(String::as_str).collect();
+    if configured.is_empty() {
+        return format!("Profile: {current} (no [profiles] in the config)");
+    }
+    format!("Profile: {current}\nConfigured: {}", configured.join(", "))
+}
+
+/// `/profile <name>`: rebuild memory, tools and provider from the config with
+/// `name` applied. The conversation carries on under the new settings.
+fn switch_profile(
+    agent: &mut AgentRuntime,
+    state: &mut InteractiveState,
+    name: &str,
+) -> anyhow::Result<String> {
+    let mut config = agent.base_config.with_profile(name)?;
+    let profile = &agent.base_config.profiles[name];
+    let provider_name = profile.provider.clone().unwrap_or_else(|| state.provider_name.clone());
+    let model_name = profile.model.clone().unwrap_or_else(|| state.model_name.clone());
+    let (security, memory, mut tools) = runtime_parts(&mut config)?;
+    let provider = interactive_provider(&config, &provider_name, &model_name, &state.observer)?;
+
+    // Keep the ask_user handler, and the tools switched off with /tools
+    if let Some(i) = agent.tools.iter().position(|t| t.name() == "ask_user") {
+        tools.push(agent.tools.swap_remove(i));
+    }
+    let disabled: Vec<String> =
+        state.disabled_tools.drain(..).map(|t| t.name().to_string()).collect();
+    for tool in &disabled {
+        let _ = agent::commands::set_tool_enabled(&mut tools, &mut state.disabled_tools, tool, false);
+    }
+
+    agent.config = config;
+    agent.security = security;
+    agent.memory = memory;
+    agent.tools = tools;
+    agent.profile = Some(name.to_string());
+    state.provider = provider;
+    state.provider_name = provider_name;
+    state.model_name = model_name;
+    state.history[0] = ChatMessage::system(build_system_prompt(&agent.config, &agent.tools));
+    Ok(format!(
+        "Profile switched to {name} ({} / {})",
+        state.provider_name, state.model_name
+    ))
+}
+
+/// `/tools enable|disable`; the system prompt is rebuilt for the new tool set.
+fn toggle_tool(
+    agent: &mut AgentRuntime,
+    state: &mut InteractiveState,
+    name: &str,
+    enable: bool,
+) -> anyhow::Result<String> {
+    agent::commands::set_tool_enabled(&mut agent.tools, &mut state.disabled_tools, name, enable)?;
+    state.history[0] = ChatMessage::system(build_system_prompt(&agent.config, &agent.tools));
+    let verb = if enable { "enabled" } else { "disabled" };
+    Ok(format!("`{name}` {verb}"))
+}
+
+/// `/save`: write the exchanges so far to the workspace session store.
+fn save_interactive_session(
+    agent: &AgentRuntime,
+    rt: &tokio::runtime::Runtime,
+    state: &mut InteractiveState,
+) -> anyhow::Result<String> {
+    if state.session.messages.is_empty() {
+        anyhow::bail!("Nothing to save yet");
+    }
+    state.session.meta.model.clone_from(&state.model_name);
+    rt.block_on(crate::sessions::ensure_title(
+        &mut state.session,
+        state.provider.as_ref(),
+        &agent.config.sessions,
+        &state.model_name,
+    ));
+    SessionStore::new(&agent.config.workspace_dir).save(&state.session)?;
+    Ok(format!(
+        "Saved session \"{}\" ({})",
+        state.session.meta.display_title(),
+        state.session.id()
+    ))
+}
+
+/// Run the full-screen terminal UI (conversation, tool, memory and cost
+/// panes) until the user quits. Tool switches from `zc_agent_run_interactive`
+/// do not apply here.
+///
+/// # Safety
+/// Caller must ensure handle is valid and `provider` / `model` (if non-NULL)
+/// are null-terminated UTF-8 strings
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_run_tui(
+    handle: *mut AgentRuntime,
+    provider: *const c_char,
+    model: *const c_char,
+    temperature: c_double,
+) -> ZcResult {
+    if handle.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let agent = &mut *handle;
+
+    let provider_override = if provider.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(provider).to_str() {
+            Ok(s) => Some(s),
+            Err(_) => return ZcResult::InvalidArg,
+        }
+    };
+
+    let model_override = if model.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(model).to_str() {
+            Ok(s) => Some(s),
+            Err(_) => return ZcResult::InvalidArg,
+        }
+    };
+
+    run_tui(agent, provider_override, model_override, temperature)
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_tui(_: &mut AgentRuntime, _: Option<&str>, _: Option<&str>, _: f64) -> ZcResult {
+    fail(ZcResult::Error, "This build has no terminal UI; rebuild with the `tui` feature")
+}
+
+#[cfg(feature = "tui")]
+fn run_tui(
+    agent: &mut AgentRuntime,
+    provider_override: Option<&str>,
+    model_override: Option<&str>,
+    temperature: f64,
+) -> ZcResult {
+    let Some(rt) = shared_runtime() else {
+        return ZcResult::Error;
+    };
+
+    let config = &agent.config;
+    let provider_name = provider_override
+        .or(config.default_provider.as_deref())
+        .unwrap_or("openrouter");
+    let model_name = model_override
+        .or(config.default_model.as_deref())
+        .unwrap_or("anthropic/claude-sonnet-4-20250514")
+        .to_string();
+    let temperature = if temperature == 0.0 { config.default_temperature } else { temperature };
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+    let provider = match interactive_provider(config, provider_name, &model_name, &observer) {
+        Ok(provider) => provider,
+        Err(e) => return fail(ZcResult::ProviderError, e),
+    };
+
+    let tui_agent = crate::tui::TuiAgent {
+        provider,
+        system_prompt: build_system_prompt(config, &agent.tools),
+        tools: std::mem::take(&mut agent.tools),
+        memory: agent.memory.clone(),
+        observer,
+        vision_fallback: crate::providers::VisionFallback::from_config(config),
+        budget: config.turn_budget_for(&model_name),
+        prices: config.prices_for(&model_name),
+        model: model_name,
+        temperature,
+        verifier: config.verifier.clone(),
+        language: agent::language::configured(&config.identity, "ffi"),
+        guardrails: Guardrails::from_config(&config.guardrails, &config.workspace_dir),
+        auto_save: config.memory.saves_raw_turns(),
+        memory_config: config.memory.clone(),
+        workspace_dir: config.workspace_dir.clone(),
+        hooks: crate::hooks::Hooks::from_config(config),
+        settings: Arc::new(agent::settings::TurnSettings::from_config(config)),
+    };
+    match rt.block_on(crate::tui::run(tui_agent)) {
+        Ok(tools) => {
+            agent.tools = tools;
+            ZcResult::Ok
+        }
+        Err(e) => fail(ZcResult::Error, e),
+    }
 }
 
 /// Free a string returned by ZeroClaw
@@ -657,6 +2831,104 @@ pub unsafe extern "C" fn zc_free_string(s: *mut c_char) {
     }
 }
 
+/// Estimated tokens in `text` for `model` (NULL for the `cl100k` default),
+/// counted with the model family's tokenizer rules.
+///
+/// # Safety
+/// Caller must ensure `model` (if non-NULL) and `text` are null-terminated
+/// UTF-8 strings and `out_count` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_count_tokens(
+    model: *const c_char,
+    text: *const c_char,
+    out_count: *mut u64,
+) -> ZcResult {
+    if text.is_null() || out_count.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let model = if model.is_null() {
+        ""
+    } else {
+        match CStr::from_ptr(model).to_str() {
+            Ok(s) => s,
+            Err(_) => return ZcResult::InvalidArg,
+        }
+    };
+    let Ok(text) = CStr::from_ptr(text).to_str() else {
+        return ZcResult::InvalidArg;
+    };
+    *out_count = crate::tokens::count(model, text);
+    ZcResult::Ok
+}
+
+/// Check a config before `zc_agent_init`. `config_json` takes the same JSON
+/// as `zc_agent_init` (NULL checks the config file it would load instead).
+/// `out_warnings_json` receives a JSON array of `{"field", "message"}`
+/// objects, empty when nothing was found; free it with `zc_free_string`.
+///
+/// # Safety
+/// Caller must ensure `config_json` (if non-NULL) is a null-terminated UTF-8
+/// string and `out_warnings_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_config_validate(
+    config_json: *const c_char,
+    out_warnings_json: *mut *mut c_char,
+) -> ZcResult {
+    if out_warnings_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let config = if config_json.is_null() {
+        match Config::load_or_init() {
+            Ok(config) => config,
+            Err(e) => return fail_with("Failed to load config", &e),
+        }
+    } else {
+        let Ok(json_str) = CStr::from_ptr(config_json).to_str() else {
+            return ZcResult::InvalidArg;
+        };
+        match serde_json::from_str::<FfiConfig>(json_str) {
+            Ok(ffi_cfg) => ffi_cfg.to_config(),
+            Err(e) => {
+                return fail(ZcResult::InvalidArg, format!("Failed to parse FFI config: {e}"));
+            }
+        }
+    };
+    let warnings = serde_json::to_string(&config.validate()).map_err(anyhow::Error::from);
+    write_response(warnings, out_warnings_json)
+}
+
+/// JSON Schema of the config file, for hosts that check or edit configs.
+/// Free `out_json` with `zc_free_string`.
+///
+/// # Safety
+/// Caller must ensure `out_json` can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_config_schema(out_json: *mut *mut c_char) -> ZcResult {
+    if out_json.is_null() {
+        return ZcResult::InvalidArg;
+    }
+    let schema = crate::config::validate::json_schema().to_string();
+    write_response(Ok(schema), out_json)
+}
+
+/// Run the guided first-run setup in the terminal: provider, API key
+/// (stored encrypted), workspace and autonomy level. The config is checked
+/// and written to `~/.zeroclaw/config.toml`, whose path goes to
+/// `out_config_path` unless it is NULL (free it with `zc_free_string`).
+///
+/// # Safety
+/// Caller must ensure `out_config_path` is NULL or can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_agent_setup_interactive(out_config_path: *mut *mut c_char) -> ZcResult {
+    match crate::onboard::run_setup() {
+        Ok(config) if !out_config_path.is_null() => {
+            write_out_string(config.config_path.display().to_string(), out_config_path)
+        }
+        Ok(_) => ZcResult::Ok,
+        Err(e) => fail_with("Setup failed", &e),
+    }
+}
+
 /// Get ZeroClaw version string
 ///
 /// # Safety
@@ -667,27 +2939,95 @@ pub extern "C" fn zc_version() -> *const c_char {
     VERSION.as_ptr() as *const c_char
 }
 
+/// Bumped when a function, type or constant of this interface changes or
+/// goes away; a header with another major version cannot talk to this library.
+pub const ZC_ABI_VERSION_MAJOR: u32 = 1;
+/// Bumped when functions or values are added; newer libraries keep serving
+/// headers with a lower minor version.
+pub const ZC_ABI_VERSION_MINOR: u32 = 0;
+/// The ABI version as `major << 16 | minor`, what `zc_abi_version` returns.
+pub const ZC_ABI_VERSION: u32 = ZC_ABI_VERSION_MAJOR << 16 | ZC_ABI_VERSION_MINOR;
+
+/// ABI version of the loaded library as `major << 16 | minor`. Compare it
+/// with the `ZC_ABI_VERSION` of the header you built against, or let
+/// `zc_abi_check` do it.
+#[no_mangle]
+pub extern "C" fn zc_abi_version() -> u32 {
+    ZC_ABI_VERSION
+}
+
+/// Check that this library serves callers built against ABI version
+/// `header_version` (pass `ZC_ABI_VERSION`): the major versions must match
+/// and the library's minor version must be at least the header's.
+/// Returns `ZC_RESULT_INVALID_ARG`, with the reason in
+/// `zc_last_error_message`, when they cannot talk.
+#[no_mangle]
+pub extern "C" fn zc_abi_check(header_version: u32) -> ZcResult {
+    let (major, minor) = (header_version >> 16, header_version & 0xffff);
+    if major != ZC_ABI_VERSION_MAJOR || minor > ZC_ABI_VERSION_MINOR {
+        return fail(
+            ZcResult::InvalidArg,
+            format!(
+                "Header ABI {major}.{minor} does not match libzeroclaw ABI {ZC_ABI_VERSION_MAJOR}.{ZC_ABI_VERSION_MINOR}"
+            ),
+        );
+    }
+    ZcResult::Ok
+}
+
 // Re-export for daemon FFI
 pub use crate::health::snapshot_json as health_snapshot_json;
 pub use crate::daemon::state_file_path;
 
-use std::sync::atomic::{AtomicBool, Ordering};
-use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use once_cell::sync::{Lazy, OnceCell};
 use tokio::runtime::Runtime;
 
 static DAEMON_RUNNING: AtomicBool = AtomicBool::new(false);
-static DAEMON_RUNTIME: Lazy<Arc<std::sync::Mutex<Option<Runtime>>>> =
+static DAEMON_RUNTIME: Lazy<Arc<std::sync::Mutex<Option<RunningDaemon>>>> =
     Lazy::new(|| Arc::new(std::sync::Mutex::new(None)));
 
+struct RunningDaemon {
+    runtime: Runtime,
+    stop: crate::daemon::shutdown::StopSignal,
+    task: tokio::task::JoinHandle<()>,
+}
+
+/// How long `zc_daemon_start` waits for the gateway to bind.
+const DAEMON_BIND_TIMEOUT_MS: u32 = 5000;
+
+/// Start the daemon once its gateway is listening; see
+/// `zc_daemon_start_ready`.
+///
+/// # Safety
+/// Same requirements as `zc_daemon_start_ready`
 #[no_mangle]
 pub unsafe extern "C" fn zc_daemon_start(
     config_toml: *const c_char,
     host: *const c_char,
     port: u16,
+) -> ZcResult {
+    zc_daemon_start_ready(config_toml, host, port, 0, std::ptr::null_mut())
+}
+
+/// Bind the gateway, then start the daemon in the background. Returns
+/// `BindError` if the port cannot be bound within `timeout_ms` (0 = 5s),
+/// so success means the gateway accepts connections. `out_port` (may be
+/// NULL) receives the bound port, useful with `port = 0`.
+///
+/// # Safety
+/// Caller must ensure `config_toml` and `host` (if non-NULL) are
+/// null-terminated UTF-8 and `out_port` (if non-NULL) can be written to
+#[no_mangle]
+pub unsafe extern "C" fn zc_daemon_start_ready(
+    config_toml: *const c_char,
+    host: *const c_char,
+    port: u16,
+    timeout_ms: u32,
+    out_port: *mut u16,
 ) -> ZcResult {
     if DAEMON_RUNNING.load(Ordering::SeqCst) {
-        eprintln!("Daemon is already running");
-        return ZcResult::Error;
+        return fail(ZcResult::Error, "Daemon is already running");
     }
 
     let toml_str = if config_toml.is_null() {
@@ -716,8 +3056,7 @@ pub unsafe extern "C" fn zc_daemon_start(
         match Config::load_or_init() {
             Ok(c) => c,
             Err(e) => {
-                eprintln!("Failed to load config: {}", e);
-                return ZcResult::InvalidArg;
+                return fail(ZcResult::InvalidArg, format!("Failed to load config: {e}"));
             }
         }
     } else {
@@ -728,8 +3067,7 @@ pub unsafe extern "C" fn zc_daemon_start(
                 c
             }
             Err(e) => {
-                eprintln!("Failed to parse config TOML: {}", e);
-                return ZcResult::InvalidArg;
+                return fail(ZcResult::InvalidArg, format!("Failed to parse config TOML: {e}"));
             }
         }
     };
@@ -740,41 +3078,82 @@ pub unsafe extern "C" fn zc_daemon_start(
     {
         Ok(r) => r,
         Err(e) => {
-            eprintln!("Failed to create tokio runtime: {}", e);
-            return ZcResult::Error;
+            return fail(ZcResult::Error, format!("Failed to create tokio runtime: {e}"));
         }
     };
 
+    let timeout_ms = if timeout_ms == 0 { DAEMON_BIND_TIMEOUT_MS } else { timeout_ms };
+    let bound = runtime.block_on(tokio::time::timeout(
+        std::time::Duration::from_millis(timeout_ms.into()),
+        crate::gateway::bind_gateway(&host_str, port, &config),
+    ));
+    let listener = match bound {
+        Ok(Ok(listener)) => listener,
+        Ok(Err(e)) => return fail(ZcResult::BindError, format!("Failed to bind gateway: {e:#}")),
+        Err(_) => return fail(ZcResult::BindError, "Timed out binding gateway"),
+    };
+    let bound_port = match listener.local_addr() {
+        Ok(addr) => addr.port(),
+        Err(e) => return fail(ZcResult::BindError, format!("Failed to bind gateway: {e}")),
+    };
+
     let host_clone = host_str.clone();
     let config_clone = config.clone();
-
-    runtime.spawn(async move {
-        if let Err(e) = crate::daemon::run(config_clone, host_clone, port).await {
+    let stop = crate::daemon::shutdown::StopSignal::new();
+    let stopped = stop.stopped();
+
+    let task = runtime.spawn(async move {
+        if let Err(e) = crate::daemon::run_until(
+            config_clone,
+            host_clone,
+            bound_port,
+            Some(listener),
+            stopped,
+        )
+        .await
+        {
             eprintln!("Daemon error: {}", e);
         }
     });
+    if !out_port.is_null() {
+        *out_port = bound_port;
+    }
 
     DAEMON_RUNNING.store(true, Ordering::SeqCst);
 
     if let Ok(mut guard) = DAEMON_RUNTIME.lock() {
-        *guard = Some(runtime);
+        *guard = Some(RunningDaemon {
+            runtime,
+            stop,
+            task,
+        });
     }
 
     println!("ZeroClaw daemon started");
     ZcResult::Ok
 }
 
+/// Stop the daemon, first waiting up to 30s for active agent turns and
+/// tool executions to finish and the final state snapshot to be written.
 #[no_mangle]
 pub extern "C" fn zc_daemon_stop() -> ZcResult {
     if !DAEMON_RUNNING.load(Ordering::SeqCst) {
-        eprintln!("Daemon is not running");
-        return ZcResult::Error;
+        return fail(ZcResult::Error, "Daemon is not running");
     }
 
-    if let Ok(mut guard) = DAEMON_RUNTIME.lock() {
-        if let Some(runtime) = guard.take() {
-            runtime.shutdown_background();
+    let running = DAEMON_RUNTIME.lock().ok().and_then(|mut guard| guard.take());
+    if let Some(daemon) = running {
+        daemon.stop.stop();
+        let grace =
+            std::time::Duration::from_secs(crate::daemon::shutdown::DRAIN_TIMEOUT_SECS + 5);
+        if daemon
+            .runtime
+            .block_on(tokio::time::timeout(grace, daemon.task))
+            .is_err()
+        {
+            eprintln!("Daemon did not stop within {}s; forcing shutdown", grace.as_secs());
         }
+        daemon.runtime.shutdown_timeout(std::time::Duration::from_secs(1));
     }
 
     DAEMON_RUNNING.store(false, Ordering::SeqCst);
@@ -805,3 +3184,146 @@ pub unsafe extern "C" fn zc_daemon_status(state_json: *mut *mut c_char) -> ZcRes
 pub extern "C" fn zc_daemon_is_running() -> bool {
     DAEMON_RUNNING.load(Ordering::SeqCst)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_error_picks_specific_codes() {
+        let code = |e: anyhow::Error| classify_error(&e) as i32;
+        let api_error = |status: u16, body: &str| {
+            let status = reqwest::StatusCode::from_u16(status).unwrap();
+            anyhow::Error::new(providers::ApiError::new("OpenAI", status, body))
+        };
+        assert_eq!(
+            code(BudgetExhausted::ToolCalls(3).into()),
+            ZcResult::BudgetExhausted as i32
+        );
+        assert_eq!(
+            code(api_error(400, r#"{"error": {"code": "context_length_exceeded"}}"#)),
+            ZcResult::ContextTooLong as i32
+        );
+        assert_eq!(
+            code(
+                AllProvidersFailed {
+                    attempts: vec!["openai attempt 1/1: 429".into()],
+                    last: Some(api_error(429, "slow down")),
+                }
+                .into()
+            ),
+            ZcResult::RateLimited as i32
+        );
+        assert_eq!(
+            code(api_error(500, "overloaded").context("Agent turn failed")),
+            ZcResult::ProviderError as i32
+        );
+        assert_eq!(
+            code(ActionBlocked("Action blocked by a turn_start hook: no".into()).into()),
+            ZcResult::ToolDenied as i32
+        );
+        // Only the types count, not what the message happens to say
+        assert_eq!(
+            code(anyhow::anyhow!("rate limit of the security policy: 429")),
+            ZcResult::Error as i32
+        );
+        assert_eq!(code(anyhow::anyhow!("disk full")), ZcResult::Error as i32);
+    }
+
+    #[test]
+    fn turn_budget_zero_means_no_cap_of_its_own() {
+        let budget = ZcTurnBudget {
+            max_tokens: 0,
+            max_tool_calls: 4,
+            max_seconds: 0,
+            max_iterations: 2,
+            return_partial: true,
+            exhausted: ZcBudgetLimit::None,
+        };
+        let caps = budget.caps();
+        assert_eq!(caps.max_tokens, None);
+        assert_eq!(caps.max_tool_calls, Some(4));
+        assert_eq!(caps.max_seconds, None);
+        assert_eq!(caps.max_iterations, Some(2));
+    }
+
+    #[test]
+    fn abi_check_accepts_older_minor_versions_only() {
+        let version = |major: u32, minor: u32| major << 16 | minor;
+        assert_eq!(zc_abi_version(), ZC_ABI_VERSION);
+        assert!(matches!(zc_abi_check(ZC_ABI_VERSION), ZcResult::Ok));
+        assert!(matches!(
+            zc_abi_check(version(ZC_ABI_VERSION_MAJOR, 0)),
+            ZcResult::Ok
+        ));
+        assert!(matches!(
+            zc_abi_check(version(ZC_ABI_VERSION_MAJOR, ZC_ABI_VERSION_MINOR + 1)),
+            ZcResult::InvalidArg
+        ));
+        assert!(matches!(
+            zc_abi_check(version(ZC_ABI_VERSION_MAJOR + 1, 0)),
+            ZcResult::InvalidArg
+        ));
+    }
+
+    #[test]
+    fn tool_execute_runs_tools_without_a_model() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = CString::new(r#"{"memory": {"backend": "markdown"}}"#).unwrap();
+        let workspace = CString::new(tmp.path().to_str().unwrap()).unwrap();
+        let mut handle = std::ptr::null_mut();
+        unsafe {
+            assert!(matches!(
+                zc_agent_init(config.as_ptr(), workspace.as_ptr(), &mut handle),
+                ZcResult::Ok
+            ));
+
+            let name = CString::new("calculator").unwrap();
+            let args = CString::new(r#"{"expression": "6 * 7"}"#).unwrap();
+            let mut out = std::ptr::null_mut();
+            let code = zc_tool_execute(handle, name.as_ptr(), args.as_ptr(), &mut out);
+            assert!(matches!(code, ZcResult::Ok));
+            let result: serde_json::Value =
+                serde_json::from_str(CStr::from_ptr(out).to_str().unwrap()).unwrap();
+            assert_eq!(result["success"], true);
+            assert!(result["output"].as_str().unwrap().contains("42"));
+            zc_free_string(out);
+
+            let unknown = CString::new("teleport").unwrap();
+            let code = zc_tool_execute(handle, unknown.as_ptr(), std::ptr::null(), &mut out);
+            assert!(matches!(code, ZcResult::InvalidArg));
+
+            zc_agent_shutdown(handle);
+        }
+    }
+
+    #[test]
+    fn last_error_message_is_per_thread() {
+        fail(ZcResult::Error, "first failure");
+        let message = unsafe { CStr::from_ptr(zc_last_error_message()) };
+        assert_eq!(message.to_str().unwrap(), "first failure");
+
+        let other = std::thread::spawn(|| zc_last_error_message().is_null())
+            .join()
+            .unwrap();
+        assert!(other);
+    }
+
+    #[test]
+    fn count_tokens_uses_the_model_family() {
+        let text = CString::new("The quick brown fox jumps over the lazy dog.").unwrap();
+        let claude = CString::new("claude-sonnet-4").unwrap();
+        let mut count = 0;
+        unsafe {
+            let code = zc_count_tokens(std::ptr::null(), text.as_ptr(), &mut count);
+            assert!(matches!(code, ZcResult::Ok));
+            assert_eq!(count, 10);
+
+            zc_count_tokens(claude.as_ptr(), text.as_ptr(), &mut count);
+            assert_eq!(count, 12);
+
+            let code = zc_count_tokens(std::ptr::null(), std::ptr::null(), &mut count);
+            assert!(matches!(code, ZcResult::InvalidArg));
+        }
+    }
+}
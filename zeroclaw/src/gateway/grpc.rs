@@ -0,0 +1,641 @@
+//! gRPC API on `[grpc] port`, generated from `proto/zeroclaw.proto`.
+//!
+//! The services mirror the REST routes: `Chat` is `POST /webhook` (with a
+//! streaming variant), `Sessions` is `/sessions`, `Tools.Stats` is `/stats`
+//! and `Health` is `/health`; `Memory` reads and writes the agent's memory.
+//! Calls authenticate like their REST counterparts, with the bearer token
+//! in the `authorization` metadata, and run against the gateway's current
+//! state, so reloaded config applies to them too.
+
+use super::{
+    chat_within_budget, client_key_from_headers, has_valid_webhook_secret, is_bearer_authorized,
+    spawn_fact_extraction, webhook_memory_key, AppState, PROVIDER_PING_INTERVAL_SECS,
+};
+use crate::agent::budget::{BudgetExhausted, TurnBudget};
+use crate::daemon::queue::{self, TurnPermit};
+use crate::daemon::reload::ConfigWatch;
+use crate::daemon::shutdown::ActiveRun;
+use crate::health::selftest;
+use crate::memory::{self, MemoryCategory};
+use crate::providers::{self, ChatMessage};
+use crate::security::SecurityPolicy;
+use crate::tools;
+use anyhow::Result;
+use futures_util::{stream, Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Request, Response, Status};
+
+/// Messages and service stubs generated from the proto file.
+#[allow(clippy::all, clippy::pedantic)]
+pub mod pb {
+    tonic::include_proto!("zeroclaw.v1");
+}
+
+use pb::chat_server::{Chat, ChatServer};
+use pb::health_server::{Health, HealthServer};
+use pb::memory_server::{Memory, MemoryServer};
+use pb::sessions_server::{Sessions, SessionsServer};
+use pb::tools_server::{Tools, ToolsServer};
+
+type ReplyStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Shared by every service: the gateway's live state and config.
+#[derive(Clone)]
+pub struct GrpcApi {
+    state: watch::Receiver<AppState>,
+    config: ConfigWatch,
+}
+
+impl GrpcApi {
+    pub fn new(state: watch::Receiver<AppState>, config: ConfigWatch) -> Self {
+        Self { state, config }
+    }
+
+    fn state(&self) -> AppState {
+        self.state.borrow().clone()
+    }
+
+    /// The state, once the call carries a paired bearer token.
+    fn authorized<T>(&self, request: &Request<T>) -> Result<AppState, Status> {
+        let state = self.state();
+        let headers = request.metadata().clone().into_headers();
+        if is_bearer_authorized(&state, &headers) {
+            Ok(state)
+        } else {
+            Err(Status::unauthenticated(
+                "Unauthorized — pair first via POST /pair",
+            ))
+        }
+    }
+
+    /// [`GrpcApi::authorized`] plus the webhook rate limit and secret, as
+    /// for `POST /webhook`.
+    fn authorized_chat<T>(&self, request: &Request<T>) -> Result<AppState, Status> {
+        let state = self.state();
+        let headers = request.metadata().clone().into_headers();
+        let client_key = client_key_from_headers(&headers);
+        if !state.rate_limiter.allow_webhook(&client_key) {
+            tracing::warn!("gRPC chat rate limit exceeded for key: {client_key}");
+            return Err(Status::resource_exhausted(
+                "Too many chat requests. Please retry later.",
+            ));
+        }
+        if !is_bearer_authorized(&state, &headers) {
+            return Err(Status::unauthenticated(
+                "Unauthorized — pair first via POST /pair, then send authorization: Bearer <token>",
+            ));
+        }
+        if !has_valid_webhook_secret(&state, &headers) {
+            return Err(Status::unauthenticated(
+                "Unauthorized — invalid or missing x-webhook-secret",
+            ));
+        }
+        Ok(state)
+    }
+}
+
+/// Serve every service on `listener` until it fails.
+pub async fn serve(listener: TcpListener, api: GrpcApi) -> Result<()> {
+    tonic::transport::Server::builder()
+        .add_service(ChatServer::new(api.clone()))
+        .add_service(SessionsServer::new(api.clone()))
+        .add_service(MemoryServer::new(api.clone()))
+        .add_service(ToolsServer::new(api.clone()))
+        .add_service(HealthServer::new(api))
+        .serve_with_incoming(TcpIncoming::from(listener))
+        .await?;
+    Ok(())
+}
+
+/// Slot for one agent turn, as `admit_turn` grants it to REST calls.
+async fn admit_turn(source: &str) -> Result<(ActiveRun, TurnPermit), Status> {
+    if crate::daemon::shutdown::is_shutting_down() {
+        return Err(Status::unavailable(
+            "Shutting down — retry once the daemon is back",
+        ));
+    }
+    match queue::shared().acquire(source).await {
+        Ok(permit) => Ok((crate::daemon::shutdown::begin_run(), permit)),
+        Err(busy) => Err(Status::resource_exhausted(busy.to_string())),
+    }
+}
+
+fn chat_status(e: &anyhow::Error) -> Status {
+    if let Some(exhausted) = e.downcast_ref::<BudgetExhausted>() {
+        return Status::failed_precondition(exhausted.to_string());
+    }
+    tracing::error!(
+        "gRPC chat provider error: {}",
+        providers::sanitize_api_error(&e.to_string())
+    );
+    Status::internal("LLM request failed")
+}
+
+fn turn_budget(budget: Option<pb::Budget>) -> TurnBudget {
+    let budget = budget.unwrap_or_default();
+    TurnBudget {
+        max_tokens: budget.max_tokens,
+        max_tool_calls: budget.max_tool_calls,
+        max_seconds: budget.max_seconds,
+        max_iterations: budget.max_iterations,
+    }
+}
+
+async fn save_message(state: &AppState, message: &str) {
+    if state.auto_save {
+        let _ = memory::dedup::store(
+            state.mem.as_ref(),
+            &webhook_memory_key(),
+            message,
+            MemoryCategory::Conversation,
+            &state.memory_config.dedup,
+        )
+        .await;
+    }
+}
+
+#[tonic::async_trait]
+impl Chat for GrpcApi {
+    async fn send(
+        &self,
+        request: Request<pb::ChatRequest>,
+    ) -> Result<Response<pb::ChatReply>, Status> {
+        let state = self.authorized_chat(&request)?;
+        let request = request.into_inner();
+        let _turn = admit_turn("gateway:grpc").await?;
+        save_message(&state, &request.message).await;
+
+        let response = chat_within_budget(&state, &request.message, turn_budget(request.budget))
+            .await
+            .map_err(|e| chat_status(&e))?;
+        spawn_fact_extraction(&state, &request.message, &response);
+        Ok(Response::new(pb::ChatReply {
+            response,
+            model: state.model.clone(),
+        }))
+    }
+
+    type StreamStream = ReplyStream<pb::ChatChunk>;
+
+    async fn stream(
+        &self,
+        request: Request<pb::ChatRequest>,
+    ) -> Result<Response<Self::StreamStream>, Status> {
+        let state = self.authorized_chat(&request)?;
+        let request = request.into_inner();
+        let turn = admit_turn("gateway:grpc").await?;
+        save_message(&state, &request.message).await;
+
+        let budget = state.turn_budget.lowered_by(turn_budget(request.budget));
+        let messages = [ChatMessage::user(request.message)];
+        crate::agent::budget::BudgetTracker::new(budget)
+            .for_model(&state.model)
+            .charge_prompt(&messages)
+            .map_err(|e| Status::failed_precondition(e.to_string()))?;
+        let deltas = state
+            .provider
+            .chat_stream(&messages, &[], &state.model, state.temperature)
+            .await
+            .map_err(|e| chat_status(&e))?;
+
+        // The turn stays admitted until the last chunk is sent
+        let deltas = deltas
+            .map(|delta| {
+                delta
+                    .map(|delta| pb::ChatChunk { delta, done: false })
+                    .map_err(|e| chat_status(&e))
+            })
+            .chain(stream::once(async move {
+                drop(turn);
+                Ok(pb::ChatChunk {
+                    delta: String::new(),
+                    done: true,
+                })
+            }));
+        let deltas: Self::StreamStream = match budget.max_seconds {
+            Some(secs) => Box::pin(with_deadline(deltas, Duration::from_secs(secs))),
+            None => Box::pin(deltas),
+        };
+        Ok(Response::new(deltas))
+    }
+}
+
+/// `stream`, cut off with a budget error once `limit` has passed.
+fn with_deadline<S>(
+    stream: S,
+    limit: Duration,
+) -> impl Stream<Item = Result<pb::ChatChunk, Status>> + Send
+where
+    S: Stream<Item = Result<pb::ChatChunk, Status>> + Send + 'static,
+{
+    let deadline = tokio::time::Instant::now() + limit;
+    stream::unfold(Some(Box::pin(stream)), move |stream| async move {
+        let mut stream = stream?;
+        match tokio::time::timeout_at(deadline, stream.next()).await {
+            Ok(Some(item)) => Some((item, Some(stream))),
+            Ok(None) => None,
+            Err(_) => Some((
+                Err(Status::failed_precondition(
+                    BudgetExhausted::Seconds(limit.as_secs()).to_string(),
+                )),
+                None,
+            )),
+        }
+    })
+}
+
+fn session_meta(meta: crate::sessions::SessionMeta) -> pb::SessionMeta {
+    pb::SessionMeta {
+        id: meta.id,
+        title: meta.title.unwrap_or_default(),
+        channel: meta.channel,
+        model: meta.model,
+        created_at: meta.created_at.to_rfc3339(),
+        updated_at: meta.updated_at.to_rfc3339(),
+        message_count: meta.message_count as u64,
+    }
+}
+
+#[tonic::async_trait]
+impl Sessions for GrpcApi {
+    async fn list(
+        &self,
+        request: Request<pb::ListSessionsRequest>,
+    ) -> Result<Response<pb::ListSessionsReply>, Status> {
+        let state = self.authorized(&request)?;
+        let sessions = state.sessions.list().map_err(|e| {
+            tracing::error!("Failed to list sessions: {e}");
+            Status::internal("Failed to list sessions")
+        })?;
+        Ok(Response::new(pb::ListSessionsReply {
+            sessions: sessions.into_iter().map(session_meta).collect(),
+        }))
+    }
+
+    async fn search(
+        &self,
+        request: Request<pb::SearchSessionsRequest>,
+    ) -> Result<Response<pb::SearchSessionsReply>, Status> {
+        let state = self.authorized(&request)?;
+        let query = request.into_inner();
+        let limit = match query.limit {
+            0 => 10,
+            n => (n as usize).clamp(1, 100),
+        };
+        let result = match (query.semantic, state.session_search.as_deref()) {
+            (true, Some(semantic)) => {
+                state
+                    .sessions
+                    .search_semantic(&query.query, semantic, limit)
+                    .await
+            }
+            (true, None) => {
+                return Err(Status::failed_precondition(
+                    "Semantic search unavailable — no embedding provider configured",
+                ))
+            }
+            (false, _) => state.sessions.search(&query.query, limit),
+        };
+        let hits = result.map_err(|e| {
+            tracing::error!(
+                "Session search failed: {}",
+                providers::sanitize_api_error(&e.to_string())
+            );
+            Status::internal("Session search failed")
+        })?;
+        Ok(Response::new(pb::SearchSessionsReply {
+            results: hits
+                .into_iter()
+                .map(|hit| pb::SessionHit {
+                    meta: Some(session_meta(hit.meta)),
+                    score: hit.score,
+                    snippets: hit.snippets,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get(
+        &self,
+        request: Request<pb::GetSessionRequest>,
+    ) -> Result<Response<pb::Session>, Status> {
+        let state = self.authorized(&request)?;
+        let session = state
+            .sessions
+            .load(&request.into_inner().id)
+            .map_err(|e| Status::not_found(e.to_string()))?;
+        Ok(Response::new(pb::Session {
+            meta: Some(session_meta(session.meta)),
+            messages: session
+                .messages
+                .into_iter()
+                .map(|m| pb::ChatMessage {
+                    role: m.role,
+                    content: m.content,
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn parse_category(raw: &str) -> MemoryCategory {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "core" | "" => MemoryCategory::Core,
+        "daily" => MemoryCategory::Daily,
+        "conversation" => MemoryCategory::Conversation,
+        other => MemoryCategory::Custom(other.to_string()),
+    }
+}
+
+fn memory_entry(entry: crate::memory::MemoryEntry) -> pb::MemoryEntry {
+    pb::MemoryEntry {
+        id: entry.id,
+        key: entry.key,
+        content: entry.content,
+        category: entry.category.to_string(),
+        timestamp: entry.timestamp,
+        score: entry.score,
+    }
+}
+
+fn memory_status(e: &anyhow::Error) -> Status {
+    tracing::error!("gRPC memory call failed: {e:#}");
+    Status::internal("Memory operation failed")
+}
+
+#[tonic::async_trait]
+impl Memory for GrpcApi {
+    async fn store(
+        &self,
+        request: Request<pb::StoreMemoryRequest>,
+    ) -> Result<Response<pb::StoreMemoryReply>, Status> {
+        let state = self.authorized(&request)?;
+        let request = request.into_inner();
+        if request.key.trim().is_empty() {
+            return Err(Status::invalid_argument("key must not be empty"));
+        }
+        state
+            .mem
+            .store(
+                &request.key,
+                &request.content,
+                parse_category(&request.category),
+            )
+            .await
+            .map_err(|e| memory_status(&e))?;
+        Ok(Response::new(pb::StoreMemoryReply {}))
+    }
+
+    async fn recall(
+        &self,
+        request: Request<pb::RecallMemoryRequest>,
+    ) -> Result<Response<pb::RecallMemoryReply>, Status> {
+        let state = self.authorized(&request)?;
+        let request = request.into_inner();
+        let limit = match request.limit {
+            0 => 5,
+            n => (n as usize).min(100),
+        };
+        let entries = state
+            .mem
+            .recall(&request.query, limit)
+            .await
+            .map_err(|e| memory_status(&e))?;
+        Ok(Response::new(pb::RecallMemoryReply {
+            entries: entries.into_iter().map(memory_entry).collect(),
+        }))
+    }
+
+    async fn get(
+        &self,
+        request: Request<pb::GetMemoryRequest>,
+    ) -> Result<Response<pb::MemoryEntry>, Status> {
+        let state = self.authorized(&request)?;
+        let key = request.into_inner().key;
+        match state.mem.get(&key).await.map_err(|e| memory_status(&e))? {
+            Some(entry) => Ok(Response::new(memory_entry(entry))),
+            None => Err(Status::not_found(format!("No memory with key {key}"))),
+        }
+    }
+
+    async fn forget(
+        &self,
+        request: Request<pb::ForgetMemoryRequest>,
+    ) -> Result<Response<pb::ForgetMemoryReply>, Status> {
+        let state = self.authorized(&request)?;
+        let forgotten = state
+            .mem
+            .forget(&request.into_inner().key)
+            .await
+            .map_err(|e| memory_status(&e))?;
+        Ok(Response::new(pb::ForgetMemoryReply { forgotten }))
+    }
+}
+
+#[tonic::async_trait]
+impl Tools for GrpcApi {
+    async fn list(
+        &self,
+        request: Request<pb::ListToolsRequest>,
+    ) -> Result<Response<pb::ListToolsReply>, Status> {
+        let state = self.authorized(&request)?;
+        let config = self.config.borrow().clone();
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+        let composio_key = if config.composio.enabled {
+            config.composio.api_key.as_deref()
+        } else {
+            None
+        };
+        let mut registry =
+            tools::all_tools(&security, state.mem.clone(), composio_key, &config.browser);
+        registry.extend(tools::optional_tools(&config, &security));
+        tools::route_to_nodes(&mut registry, &config.nodes, &security);
+        Ok(Response::new(pb::ListToolsReply {
+            tools: registry
+                .iter()
+                .map(|tool| {
+                    let spec = tool.spec();
+                    pb::ToolSpec {
+                        name: spec.name,
+                        description: spec.description,
+                        parameters_json: spec.parameters.to_string(),
+                    }
+                })
+                .collect(),
+        }))
+    }
+
+    async fn stats(
+        &self,
+        request: Request<pb::ToolStatsRequest>,
+    ) -> Result<Response<pb::ToolStatsReply>, Status> {
+        let state = self.authorized(&request)?;
+        let summary =
+            crate::observability::tool_stats::summary(&state.workspace_dir).map_err(|e| {
+                tracing::error!("Failed to read tool stats: {e}");
+                Status::internal("Failed to read tool stats")
+            })?;
+        Ok(Response::new(pb::ToolStatsReply {
+            tools: summary
+                .into_iter()
+                .map(|s| pb::ToolStats {
+                    tool: s.tool,
+                    invocations: s.invocations,
+                    failures: s.failures,
+                    success_rate: s.success_rate,
+                    mean_latency_ms: s.mean_latency_ms,
+                    output_bytes: s.output_bytes,
+                    last_used: s.last_used,
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn health_reply(state: &AppState) -> pb::HealthReply {
+    let snapshot = crate::health::snapshot();
+    pb::HealthReply {
+        status: "ok".into(),
+        paired: state.pairing.is_paired(),
+        uptime_seconds: snapshot.uptime_seconds,
+        components: snapshot
+            .components
+            .into_iter()
+            .map(|(name, c)| {
+                let component = pb::ComponentHealth {
+                    status: c.status,
+                    updated_at: c.updated_at,
+                    last_error: c.last_error.unwrap_or_default(),
+                    restart_count: c.restart_count,
+                };
+                (name, component)
+            })
+            .collect(),
+        queues: snapshot
+            .queues
+            .into_iter()
+            .map(|(name, depth)| (name, depth as u64))
+            .collect(),
+        circuits: snapshot.circuits.into_iter().collect(),
+        active_runs: snapshot.active_runs as u64,
+    }
+}
+
+#[tonic::async_trait]
+impl Health for GrpcApi {
+    async fn check(
+        &self,
+        _request: Request<pb::HealthRequest>,
+    ) -> Result<Response<pb::HealthReply>, Status> {
+        let state = self.state();
+        selftest::refresh_provider(
+            state.provider.clone(),
+            Duration::from_secs(PROVIDER_PING_INTERVAL_SECS),
+        );
+        selftest::check_memory(state.mem.as_ref()).await;
+        Ok(Response::new(health_reply(&state)))
+    }
+
+    type WatchStream = ReplyStream<pb::HealthReply>;
+
+    async fn watch(
+        &self,
+        request: Request<pb::WatchHealthRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let interval = match request.into_inner().interval_secs {
+            0 => 5,
+            n => u64::from(n),
+        };
+        let mut ticks = tokio::time::interval(Duration::from_secs(interval));
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let api = self.clone();
+        let replies = stream::unfold((ticks, api), |(mut ticks, api)| async move {
+            ticks.tick().await;
+            let reply = health_reply(&api.state());
+            Some((Ok(reply), (ticks, api)))
+        });
+        Ok(Response::new(Box::pin(replies)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use pb::health_client::HealthClient;
+    use pb::memory_client::MemoryClient;
+    use pb::tools_client::ToolsClient;
+
+    fn with_token<T>(message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer zc_grpc_test".parse().unwrap());
+        request
+    }
+
+    #[tokio::test]
+    async fn services_answer_paired_callers_over_http2() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        config.gateway.paired_tokens = vec!["zc_grpc_test".into()];
+        let state = super::super::build_state(&config, None, None).unwrap();
+        let (_state_tx, state_rx) = watch::channel(state);
+        let (_config_tx, config_rx) = watch::channel(Arc::new(config));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener, GrpcApi::new(state_rx, config_rx)));
+
+        let mut memory = MemoryClient::connect(url.clone()).await.unwrap();
+        let store = pb::StoreMemoryRequest {
+            key: "editor".into(),
+            content: "The user prefers helix".into(),
+            category: String::new(),
+        };
+        let refused = memory.store(Request::new(store.clone())).await.unwrap_err();
+        assert_eq!(refused.code(), tonic::Code::Unauthenticated);
+        memory.store(with_token(store)).await.unwrap();
+
+        let entry = memory
+            .get(with_token(pb::GetMemoryRequest {
+                key: "editor".into(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(entry.content, "The user prefers helix");
+        assert_eq!(entry.category, "core");
+
+        let tools = ToolsClient::connect(url.clone())
+            .await
+            .unwrap()
+            .list(with_token(pb::ListToolsRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(tools.tools.iter().any(|tool| tool.name == "shell"));
+
+        // Health is public, as GET /health is
+        let health = HealthClient::connect(url)
+            .await
+            .unwrap()
+            .check(pb::HealthRequest {})
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(health.status, "ok");
+        assert!(health.paired);
+    }
+}
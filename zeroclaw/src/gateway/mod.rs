@@ -7,35 +7,64 @@
 //! - Request timeouts (30s) to prevent slow-loris attacks
 //! - Header sanitization (handled by axum/hyper)
 
+use crate::a2a::{self, A2aServer};
+use crate::agent::budget::{BudgetExhausted, BudgetTracker, TurnBudget};
 use crate::channels::{Channel, WhatsAppChannel};
-use crate::config::Config;
+use crate::config::{Config, MemoryConfig};
+use crate::daemon::queue::{self, TurnPermit};
+use crate::daemon::reload::{ConfigWatch, ReloadHandle};
+use crate::daemon::shutdown::ActiveRun;
+use crate::health::selftest;
+use crate::jobs::JobStore;
+use crate::memory::browse::{self, BrowseFilter};
 use crate::memory::{self, Memory, MemoryCategory};
-use crate::providers::{self, Provider};
+use crate::prompts::{self, PromptError, PromptLibrary};
+use crate::providers::{self, ChatMessage, Provider};
 use crate::security::pairing::{constant_time_eq, is_public_bind, PairingGuard};
+use crate::sessions::feedback::{self, Feedback};
+use crate::sessions::{SemanticSearch, SessionStore};
+use crate::skills::package;
+use crate::skills::registry::RegistryClient;
+use crate::tools::ComposioAccounts;
 use crate::util::truncate_with_ellipsis;
+use crate::workflows::{Decision, RunStatus, WorkflowEngine};
 use anyhow::Result;
 use axum::{
     body::Bytes,
-    extract::{Query, State},
+    extract::{Path, Query, Request, State},
     http::{header, HeaderMap, StatusCode},
-    response::{IntoResponse, Json},
-    routing::{get, post},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::{delete, get, post},
     Router,
 };
+use futures_util::StreamExt;
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tower::ServiceExt;
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::timeout::TimeoutLayer;
 use uuid::Uuid;
 
+#[cfg(feature = "grpc")]
+pub mod grpc;
+
 /// Maximum request body size (64KB) — prevents memory exhaustion
 pub const MAX_BODY_SIZE: usize = 65_536;
 /// Request timeout (30s) — prevents slow-loris attacks
 pub const REQUEST_TIMEOUT_SECS: u64 = 30;
 /// Sliding window used by gateway rate limiting.
 pub const RATE_LIMIT_WINDOW_SECS: u64 = 60;
+/// Minimum gap between provider reachability pings triggered by `/health`
+const PROVIDER_PING_INTERVAL_SECS: u64 = 60;
 
 fn webhook_memory_key() -> String {
     format!("webhook_msg_{}", Uuid::new_v4())
@@ -162,7 +191,10 @@ pub struct AppState {
     pub model: String,
     pub temperature: f64,
     pub mem: Arc<dyn Memory>,
+    /// Auto-save raw messages (off when facts are extracted instead)
     pub auto_save: bool,
+    /// `[memory]`: dedup and fact extraction for what turns leave behind
+    pub memory_config: MemoryConfig,
     pub webhook_secret: Option<Arc<str>>,
     pub pairing: Arc<PairingGuard>,
     pub rate_limiter: Arc<GatewayRateLimiter>,
@@ -170,11 +202,62 @@ pub struct AppState {
     pub whatsapp: Option<Arc<WhatsAppChannel>>,
     /// `WhatsApp` app secret for webhook signature verification (`X-Hub-Signature-256`)
     pub whatsapp_app_secret: Option<Arc<str>>,
+    /// Saved conversation transcripts exposed via `/sessions`
+    pub sessions: Arc<SessionStore>,
+    /// Embedding setup for `/sessions/search?semantic=true` (None = keyword only)
+    pub session_search: Option<Arc<SemanticSearch>>,
+    /// Model profile caps for `/webhook`; request bodies may only lower them
+    pub turn_budget: TurnBudget,
+    /// Templates served by `/prompts`
+    pub prompts: Arc<PromptLibrary>,
+    /// Workspace whose skills `/skills` manages
+    pub workspace_dir: PathBuf,
+    /// Backs `/skills/registry` (None when no registry is configured)
+    pub skill_registry: Option<Arc<RegistryClient>>,
+    /// Backs `/composio/connections` (None unless Composio is enabled)
+    pub composio: Option<Arc<ComposioAccounts>>,
+    /// Backs `/jobs` (None unless `[jobs]` is enabled)
+    pub jobs: Option<Arc<JobStore>>,
+    /// Backs `/workflows` (None unless `[workflows]` is enabled)
+    pub workflows: Option<Arc<WorkflowEngine>>,
+    /// Backs `/a2a` and the agent card (None unless `[a2a]` is enabled)
+    pub a2a: Option<Arc<A2aServer>>,
+    /// Backs `/admin/reload` (None outside the daemon)
+    pub reload: Option<ReloadHandle>,
+}
+
+/// Bearer-token check shared by authenticated read-only endpoints.
+fn is_bearer_authorized(state: &AppState, headers: &HeaderMap) -> bool {
+    if !state.pairing.require_pairing() {
+        return true;
+    }
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "))
+        .unwrap_or("");
+    state.pairing.is_authenticated(token)
+}
+
+/// `X-Webhook-Secret` check; passes when no secret is configured.
+fn has_valid_webhook_secret(state: &AppState, headers: &HeaderMap) -> bool {
+    state.webhook_secret.as_ref().is_none_or(|secret| {
+        headers
+            .get("X-Webhook-Secret")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|val| constant_time_eq(val, secret.as_ref()))
+    })
 }
 
 /// Run the HTTP gateway using axum with proper HTTP/1.1 compliance.
-#[allow(clippy::too_many_lines)]
 pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
+    crate::health::set_autonomy_limits(&config.autonomy);
+    let listener = bind_gateway(host, port, &config).await?;
+    serve_gateway(listener, host, config).await
+}
+
+/// Bind the gateway's listener (port 0 picks a free port).
+pub async fn bind_gateway(host: &str, port: u16, config: &Config) -> Result<TcpListener> {
     // ── Security: refuse public bind without tunnel or explicit opt-in ──
     if is_public_bind(host) && config.tunnel.provider == "none" && !config.gateway.allow_public_bind
     {
@@ -186,15 +269,187 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     }
 
     let addr: SocketAddr = format!("{host}:{port}").parse()?;
-    let listener = tokio::net::TcpListener::bind(addr).await?;
+    Ok(TcpListener::bind(addr).await?)
+}
+
+/// Serve the gateway on a listener from [`bind_gateway`].
+pub async fn serve_gateway(listener: TcpListener, host: &str, config: Config) -> Result<()> {
+    let (_config_tx, config) = watch::channel(Arc::new(config));
+    serve_gateway_live(listener, host, config, None).await
+}
+
+/// Serve the gateway, rebuilding its state whenever `config` changes.
+/// Pairings, rate limits and idempotency keys carry over; requests in
+/// flight finish on the state they started with. With `reload`,
+/// `POST /admin/reload` re-reads the daemon config.
+pub async fn serve_gateway_live(
+    listener: TcpListener,
+    host: &str,
+    mut config: ConfigWatch,
+    reload: Option<ReloadHandle>,
+) -> Result<()> {
     let actual_port = listener.local_addr()?.port();
     let display_addr = format!("{host}:{actual_port}");
+    let initial = config.borrow_and_update().clone();
+    let state = build_state(&initial, None, reload.clone())?;
+    // gRPC calls read the state current when they arrive
+    let (state_tx, state_rx) = watch::channel(state.clone());
+
+    // ── Tunnel ────────────────────────────────────────────────
+    let tunnel = crate::tunnel::create_tunnel(&initial.tunnel)?;
+    let mut tunnel_url: Option<String> = None;
+
+    if let Some(ref tun) = tunnel {
+        println!("🔗 Starting {} tunnel...", tun.name());
+        match tun.start(host, actual_port).await {
+            Ok(url) => {
+                println!("🌐 Tunnel active: {url}");
+                tunnel_url = Some(url);
+            }
+            Err(e) => {
+                println!("⚠️  Tunnel failed to start: {e}");
+                println!("   Falling back to local-only mode.");
+            }
+        }
+    }
+
+    println!("🦀 ZeroClaw Gateway listening on http://{display_addr}");
+    if let Some(ref url) = tunnel_url {
+        println!("  🌐 Public URL: {url}");
+    }
+    println!("  POST /pair      — pair a new client (X-Pairing-Code header)");
+    println!("  POST /webhook   — {{\"message\": \"your prompt\"}}");
+    if state.whatsapp.is_some() {
+        println!("  GET  /whatsapp  — Meta webhook verification");
+        println!("  POST /whatsapp  — WhatsApp message webhook");
+    }
+    println!("  GET  /sessions  — list saved conversations (titles, ids)");
+    println!("  GET  /sessions/search?q=...  — search past conversations");
+    println!("  POST /sessions/:id/branch  — rerun a turn as a new branch");
+    println!("  POST /sessions/:id/feedback  — rate an answer (up/down, comment)");
+    println!("  GET  /stats     — per-tool calls, failures and latency");
+    if state.composio.is_some() {
+        println!("  GET  /composio/connections  — connected Composio accounts");
+    }
+    if state.jobs.is_some() {
+        println!("  POST /jobs      — {{\"prompt\": \"long-running task\"}}");
+        println!("  GET  /jobs/:id  — job status, progress and result");
+    }
+    if state.workflows.is_some() {
+        println!("  GET  /workflows — workflow definitions");
+        println!("  POST /workflows/:name/run  — {{\"input\": \"...\"}}");
+        println!("  GET  /workflows/runs — recent workflow runs");
+        println!("  POST /workflows/runs/:id/approve|deny — answer an approval step");
+    }
+    if state.a2a.is_some() {
+        println!("  GET  /.well-known/agent.json — A2A agent card");
+        println!("  POST /a2a       — A2A JSON-RPC (message/send, message/stream, tasks/*)");
+    }
+    println!("  GET  /health    — health check");
+    #[cfg(not(feature = "grpc"))]
+    {
+        if initial.grpc.enabled {
+            tracing::warn!(
+                "[grpc] is enabled, but this build has no gRPC API (the `grpc` feature)"
+            );
+        }
+        drop(state_rx);
+    }
+    #[cfg(feature = "grpc")]
+    let grpc = if initial.grpc.enabled {
+        let listener = TcpListener::bind((host, initial.grpc.port)).await?;
+        println!(
+            "  gRPC {host}:{} — Chat, Sessions, Memory, Tools, Health (proto/zeroclaw.proto)",
+            listener.local_addr()?.port()
+        );
+        let api = grpc::GrpcApi::new(state_rx, config.clone());
+        Some(tokio::spawn(async move {
+            if let Err(e) = grpc::serve(listener, api).await {
+                tracing::error!("gRPC server stopped: {e:#}");
+            }
+        }))
+    } else {
+        None
+    };
+    if let Some(code) = state.pairing.pairing_code() {
+        println!();
+        println!("  🔐 PAIRING REQUIRED — use this one-time code:");
+        println!("     ┌──────────────┐");
+        println!("     │  {code}  │");
+        println!("     └──────────────┘");
+        println!("     Send: POST /pair with header X-Pairing-Code: {code}");
+    } else if state.pairing.require_pairing() {
+        println!("  🔒 Pairing: ACTIVE (bearer token required)");
+    } else {
+        println!("  ⚠️  Pairing: DISABLED (all requests accepted)");
+    }
+    if state.webhook_secret.is_some() {
+        println!("  🔒 Webhook secret: ENABLED");
+    }
+    println!("  Press Ctrl+C to stop.\n");
+
+    crate::health::mark_component_ok("gateway");
+
+    // Each request is routed by the router current when it arrives
+    let router = Arc::new(std::sync::RwLock::new(build_router(state.clone())));
+    let live_router = router.clone();
+    let reloader = tokio::spawn(async move {
+        let mut state = state;
+        while config.changed().await.is_ok() {
+            let next = config.borrow_and_update().clone();
+            match build_state(&next, Some(&state), state.reload.clone()) {
+                Ok(rebuilt) => {
+                    if let Ok(mut router) = live_router.write() {
+                        *router = build_router(rebuilt.clone());
+                    }
+                    state_tx.send_replace(rebuilt.clone());
+                    state = rebuilt;
+                    tracing::info!("Gateway applied reloaded config");
+                }
+                Err(e) => tracing::warn!("Gateway kept its previous config: {e:#}"),
+            }
+        }
+    });
+    let app = Router::new().fallback_service(tower::service_fn(move |req: Request| {
+        let current = router.read().ok().map(|r| r.clone());
+        async move {
+            match current {
+                Some(router) => router.oneshot(req).await,
+                None => Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+            }
+        }
+    }));
 
-    let provider: Arc<dyn Provider> = Arc::from(providers::create_resilient_provider(
+    // Run the server
+    let served = axum::serve(listener, app).await;
+    reloader.abort();
+    #[cfg(feature = "grpc")]
+    if let Some(grpc) = grpc {
+        grpc.abort();
+    }
+    served?;
+
+    Ok(())
+}
+
+/// Build the request state from `config`. `previous` supplies the pairing
+/// guard, rate limiter and idempotency store when reloading.
+#[allow(clippy::too_many_lines)]
+fn build_state(
+    config: &Config,
+    previous: Option<&AppState>,
+    reload: Option<ReloadHandle>,
+) -> Result<AppState> {
+    let provider: Arc<dyn Provider> = Arc::from(providers::cache::wrap(
+        providers::create_resilient_provider(
+            config.default_provider.as_deref().unwrap_or("openrouter"),
+            config.api_key.as_deref(),
+            &config.reliability,
+            &providers::ProviderSettings::from_config(config),
+        )?,
+        config,
         config.default_provider.as_deref().unwrap_or("openrouter"),
-        config.api_key.as_deref(),
-        &config.reliability,
-    )?);
+    ));
     let model = config
         .default_model
         .clone()
@@ -245,99 +500,123 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .map(Arc::from);
 
     // ── Pairing guard ──────────────────────────────────────
-    let pairing = Arc::new(PairingGuard::new(
-        config.gateway.require_pairing,
-        &config.gateway.paired_tokens,
-    ));
-    let rate_limiter = Arc::new(GatewayRateLimiter::new(
-        config.gateway.pair_rate_limit_per_minute,
-        config.gateway.webhook_rate_limit_per_minute,
-    ));
-    let idempotency_store = Arc::new(IdempotencyStore::new(Duration::from_secs(
-        config.gateway.idempotency_ttl_secs.max(1),
-    )));
-
-    // ── Tunnel ────────────────────────────────────────────────
-    let tunnel = crate::tunnel::create_tunnel(&config.tunnel)?;
-    let mut tunnel_url: Option<String> = None;
+    let (pairing, rate_limiter, idempotency_store) = match previous {
+        Some(prev) => (
+            prev.pairing.clone(),
+            prev.rate_limiter.clone(),
+            prev.idempotency_store.clone(),
+        ),
+        None => (
+            Arc::new(PairingGuard::new(
+                config.gateway.require_pairing,
+                &config.gateway.paired_tokens,
+            )),
+            Arc::new(GatewayRateLimiter::new(
+                config.gateway.pair_rate_limit_per_minute,
+                config.gateway.webhook_rate_limit_per_minute,
+            )),
+            Arc::new(IdempotencyStore::new(Duration::from_secs(
+                config.gateway.idempotency_ttl_secs.max(1),
+            ))),
+        ),
+    };
 
-    if let Some(ref tun) = tunnel {
-        println!("🔗 Starting {} tunnel...", tun.name());
-        match tun.start(host, actual_port).await {
-            Ok(url) => {
-                println!("🌐 Tunnel active: {url}");
-                tunnel_url = Some(url);
-            }
-            Err(e) => {
-                println!("⚠️  Tunnel failed to start: {e}");
-                println!("   Falling back to local-only mode.");
+    let skill_registry = match RegistryClient::from_config(&config.skill_registry) {
+        Ok(client) => Some(Arc::new(client)),
+        Err(e) => {
+            if config.skill_registry.index_url.is_some() {
+                tracing::warn!("Skill registry disabled: {e}");
             }
+            None
         }
-    }
-
-    println!("🦀 ZeroClaw Gateway listening on http://{display_addr}");
-    if let Some(ref url) = tunnel_url {
-        println!("  🌐 Public URL: {url}");
-    }
-    println!("  POST /pair      — pair a new client (X-Pairing-Code header)");
-    println!("  POST /webhook   — {{\"message\": \"your prompt\"}}");
-    if whatsapp_channel.is_some() {
-        println!("  GET  /whatsapp  — Meta webhook verification");
-        println!("  POST /whatsapp  — WhatsApp message webhook");
-    }
-    println!("  GET  /health    — health check");
-    if let Some(code) = pairing.pairing_code() {
-        println!();
-        println!("  🔐 PAIRING REQUIRED — use this one-time code:");
-        println!("     ┌──────────────┐");
-        println!("     │  {code}  │");
-        println!("     └──────────────┘");
-        println!("     Send: POST /pair with header X-Pairing-Code: {code}");
-    } else if pairing.require_pairing() {
-        println!("  🔒 Pairing: ACTIVE (bearer token required)");
-    } else {
-        println!("  ⚠️  Pairing: DISABLED (all requests accepted)");
-    }
-    if webhook_secret.is_some() {
-        println!("  🔒 Webhook secret: ENABLED");
-    }
-    println!("  Press Ctrl+C to stop.\n");
-
-    crate::health::mark_component_ok("gateway");
+    };
 
-    // Build shared state
-    let state = AppState {
+    queue::shared().set_limits(&config.queue);
+    let turn_budget = config.turn_budget_for(&model);
+    Ok(AppState {
         provider,
         model,
         temperature,
         mem,
-        auto_save: config.memory.auto_save,
+        auto_save: config.memory.saves_raw_turns(),
+        memory_config: config.memory.clone(),
         webhook_secret,
         pairing,
         rate_limiter,
         idempotency_store,
         whatsapp: whatsapp_channel,
         whatsapp_app_secret,
-    };
+        sessions: Arc::new(SessionStore::new(&config.workspace_dir)),
+        session_search: SemanticSearch::from_config(config).map(Arc::new),
+        turn_budget,
+        prompts: Arc::new(PromptLibrary::from_config(config)),
+        workspace_dir: config.workspace_dir.clone(),
+        skill_registry,
+        composio: ComposioAccounts::from_config(config).map(Arc::new),
+        jobs: config
+            .jobs
+            .enabled
+            .then(|| Arc::new(JobStore::new(&config.workspace_dir))),
+        workflows: if config.workflows.enabled {
+            Some(Arc::new(WorkflowEngine::from_config(config)?))
+        } else {
+            None
+        },
+        a2a: if config.a2a.enabled {
+            Some(Arc::new(A2aServer::from_config(config)?))
+        } else {
+            None
+        },
+        reload,
+    })
+}
 
-    // Build router with middleware
-    let app = Router::new()
+fn build_router(state: AppState) -> Router {
+    Router::new()
         .route("/health", get(handle_health))
+        .route("/health/selftest", post(handle_selftest))
         .route("/pair", post(handle_pair))
         .route("/webhook", post(handle_webhook))
+        .route("/admin/reload", post(handle_admin_reload))
+        .route("/prompts", get(handle_prompts_list))
+        .route("/prompts/:name", post(handle_prompt_run))
+        .route("/skills", get(handle_skills_list))
+        .route("/skills/install", post(handle_skill_install))
+        .route("/skills/registry", get(handle_registry_search))
+        .route("/skills/registry/:name", post(handle_registry_install))
+        .route("/skills/:name", delete(handle_skill_remove))
         .route("/whatsapp", get(handle_whatsapp_verify))
         .route("/whatsapp", post(handle_whatsapp_message))
+        .route("/sessions", get(handle_sessions_list))
+        .route("/sessions/search", get(handle_sessions_search))
+        .route("/sessions/:id", get(handle_session_get))
+        .route("/sessions/:id/branch", post(handle_session_branch))
+        .route("/sessions/:id/feedback", post(handle_session_feedback))
+        .route("/stats", get(handle_stats))
+        .route("/memory", get(handle_memory_browse))
+        .route("/memory", delete(handle_memory_forget_matching))
+        .route("/memory/:key", delete(handle_memory_forget))
+        .route("/composio/connections", get(handle_composio_connections))
+        .route("/composio/connections", post(handle_composio_connect))
+        .route("/composio/connections/:id", delete(handle_composio_revoke))
+        .route("/jobs", get(handle_jobs_list))
+        .route("/jobs", post(handle_job_enqueue))
+        .route("/jobs/:id", get(handle_job_get))
+        .route("/workflows", get(handle_workflows_list))
+        .route("/workflows/:name/run", post(handle_workflow_run))
+        .route("/workflows/runs", get(handle_workflow_runs_list))
+        .route("/workflows/runs/:id", get(handle_workflow_run_get))
+        .route("/workflows/runs/:id/approve", post(handle_workflow_approve))
+        .route("/workflows/runs/:id/deny", post(handle_workflow_deny))
+        .route("/.well-known/agent.json", get(handle_a2a_card))
+        .route("/.well-known/agent-card.json", get(handle_a2a_card))
+        .route("/a2a", post(handle_a2a))
         .with_state(state)
         .layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE))
         .layer(TimeoutLayer::with_status_code(
             StatusCode::REQUEST_TIMEOUT,
             Duration::from_secs(REQUEST_TIMEOUT_SECS),
-        ));
-
-    // Run the server
-    axum::serve(listener, app).await?;
-
-    Ok(())
+        ))
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -346,6 +625,11 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
 
 /// GET /health — always public (no secrets leaked)
 async fn handle_health(State(state): State<AppState>) -> impl IntoResponse {
+    selftest::refresh_provider(
+        state.provider.clone(),
+        Duration::from_secs(PROVIDER_PING_INTERVAL_SECS),
+    );
+    selftest::check_memory(state.mem.as_ref()).await;
     let body = serde_json::json!({
         "status": "ok",
         "paired": state.pairing.is_paired(),
@@ -354,6 +638,21 @@ async fn handle_health(State(state): State<AppState>) -> impl IntoResponse {
     Json(body)
 }
 
+/// POST /health/selftest — canary agent turn plus provider and memory checks
+async fn handle_selftest(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) || !has_valid_webhook_secret(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let report = selftest::run(state.provider.as_ref(), state.mem.as_ref()).await;
+    let status = if report.ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(serde_json::json!(report)))
+}
+
 /// POST /pair — exchange one-time code for bearer token
 async fn handle_pair(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
     let client_key = client_key_from_headers(&headers);
@@ -379,134 +678,1271 @@ async fn handle_pair(State(state): State<AppState>, headers: HeaderMap) -> impl
                 "token": token,
                 "message": "Save this token — use it as Authorization: Bearer <token>"
             });
-            (StatusCode::OK, Json(body))
+            (StatusCode::OK, Json(body))
+        }
+        Ok(None) => {
+            tracing::warn!("🔐 Pairing attempt with invalid code");
+            let err = serde_json::json!({"error": "Invalid pairing code"});
+            (StatusCode::FORBIDDEN, Json(err))
+        }
+        Err(lockout_secs) => {
+            tracing::warn!(
+                "🔐 Pairing locked out — too many failed attempts ({lockout_secs}s remaining)"
+            );
+            let err = serde_json::json!({
+                "error": format!("Too many failed attempts. Try again in {lockout_secs}s."),
+                "retry_after": lockout_secs
+            });
+            (StatusCode::TOO_MANY_REQUESTS, Json(err))
+        }
+    }
+}
+
+/// Webhook request body
+#[derive(serde::Deserialize)]
+pub struct WebhookBody {
+    pub message: String,
+    /// Optional `max_tokens` / `max_tool_calls` / `max_seconds` caps
+    #[serde(flatten)]
+    pub budget: TurnBudget,
+}
+
+/// POST /webhook — main webhook endpoint
+async fn handle_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Result<Json<WebhookBody>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    let client_key = client_key_from_headers(&headers);
+    if !state.rate_limiter.allow_webhook(&client_key) {
+        tracing::warn!("/webhook rate limit exceeded for key: {client_key}");
+        let err = serde_json::json!({
+            "error": "Too many webhook requests. Please retry later.",
+            "retry_after": RATE_LIMIT_WINDOW_SECS,
+        });
+        return (StatusCode::TOO_MANY_REQUESTS, Json(err)).into_response();
+    }
+
+    // ── Bearer token auth (pairing) ──
+    if state.pairing.require_pairing() {
+        let auth = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        let token = auth.strip_prefix("Bearer ").unwrap_or("");
+        if !state.pairing.is_authenticated(token) {
+            tracing::warn!("Webhook: rejected — not paired / invalid bearer token");
+            let err = serde_json::json!({
+                "error": "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
+            });
+            return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+        }
+    }
+
+    // ── Webhook secret auth (optional, additional layer) ──
+    if !has_valid_webhook_secret(&state, &headers) {
+        tracing::warn!("Webhook: rejected request — invalid or missing X-Webhook-Secret");
+        let err = serde_json::json!({"error": "Unauthorized — invalid or missing X-Webhook-Secret header"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+
+    // ── Parse body ──
+    let Json(webhook_body) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            let err = serde_json::json!({
+                "error": format!("Invalid JSON: {e}. Expected: {{\"message\": \"...\"}}")
+            });
+            return (StatusCode::BAD_REQUEST, Json(err)).into_response();
+        }
+    };
+
+    // ── Idempotency (optional) ──
+    if let Some(idempotency_key) = headers
+        .get("X-Idempotency-Key")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        if !state.idempotency_store.record_if_new(idempotency_key) {
+            tracing::info!("Webhook duplicate ignored (idempotency key: {idempotency_key})");
+            let body = serde_json::json!({
+                "status": "duplicate",
+                "idempotent": true,
+                "message": "Request already processed for this idempotency key"
+            });
+            return (StatusCode::OK, Json(body)).into_response();
+        }
+    }
+
+    let message = &webhook_body.message;
+
+    let _turn = match admit_turn("gateway:webhook").await {
+        Ok(turn) => turn,
+        Err(response) => return response,
+    };
+
+    if state.auto_save {
+        let key = webhook_memory_key();
+        let _ = memory::dedup::store(
+            state.mem.as_ref(),
+            &key,
+            message,
+            MemoryCategory::Conversation,
+            &state.memory_config.dedup,
+        )
+        .await;
+    }
+
+    match chat_within_budget(&state, message, webhook_body.budget).await {
+        Ok(response) => {
+            spawn_fact_extraction(&state, message, &response);
+            let body = serde_json::json!({"response": response, "model": state.model});
+            (StatusCode::OK, Json(body))
+        }
+        Err(e) => chat_error_response(&e, "Webhook"),
+    }
+    .into_response()
+}
+
+/// Slot for one agent turn: 503 while the daemon drains for shutdown,
+/// 429 with `Retry-After` when the work queue is full.
+async fn admit_turn(source: &str) -> Result<(ActiveRun, TurnPermit), Response> {
+    if crate::daemon::shutdown::is_shutting_down() {
+        let err = serde_json::json!({"error": "Shutting down — retry once the daemon is back"});
+        return Err((StatusCode::SERVICE_UNAVAILABLE, Json(err)).into_response());
+    }
+    match queue::shared().acquire(source).await {
+        Ok(permit) => Ok((crate::daemon::shutdown::begin_run(), permit)),
+        Err(busy) => {
+            let err = serde_json::json!({
+                "error": busy.to_string(),
+                "retry_after": busy.retry_after_secs,
+            });
+            Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, busy.retry_after_secs.to_string())],
+                Json(err),
+            )
+                .into_response())
+        }
+    }
+}
+
+/// 422 naming the exhausted limit for budget errors, else a generic 500.
+fn chat_error_response(e: &anyhow::Error, source: &str) -> (StatusCode, Json<serde_json::Value>) {
+    if let Some(exhausted) = e.downcast_ref::<BudgetExhausted>() {
+        let err = serde_json::json!({"error": exhausted.to_string(), "limit": exhausted.limit()});
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(err));
+    }
+    tracing::error!(
+        "{source} provider error: {}",
+        providers::sanitize_api_error(&e.to_string())
+    );
+    let err = serde_json::json!({"error": "LLM request failed"});
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
+}
+
+/// One provider call, refused up front when the message alone exceeds the
+/// token cap and cancelled when it runs past the time cap.
+async fn chat_within_budget(
+    state: &AppState,
+    message: &str,
+    request: TurnBudget,
+) -> anyhow::Result<String> {
+    let budget = state.turn_budget.lowered_by(request);
+    BudgetTracker::new(budget)
+        .for_model(&state.model)
+        .charge_prompt(&[ChatMessage::user(message)])?;
+    let chat = state
+        .provider
+        .chat(message, &state.model, state.temperature);
+    match budget.max_seconds {
+        Some(secs) => tokio::time::timeout(Duration::from_secs(secs), chat)
+            .await
+            .map_err(|_| BudgetExhausted::Seconds(secs))?,
+        None => chat.await,
+    }
+}
+
+/// Run `[memory.extract]` over a finished exchange in the background, so the
+/// reply is not held up by the extra model call.
+fn spawn_fact_extraction(state: &AppState, user: &str, answer: &str) {
+    if !state.memory_config.extract.enabled {
+        return;
+    }
+    let (provider, mem) = (state.provider.clone(), state.mem.clone());
+    let (config, model) = (state.memory_config.clone(), state.model.clone());
+    let (user, answer) = (user.to_string(), answer.to_string());
+    tokio::spawn(async move {
+        memory::extract::after_turn(
+            provider.as_ref(),
+            mem.as_ref(),
+            &config,
+            &model,
+            &user,
+            &answer,
+        )
+        .await;
+    });
+}
+
+/// POST /admin/reload — re-read the daemon config and apply it live
+async fn handle_admin_reload(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) || !has_valid_webhook_secret(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Some(reload) = state.reload.as_ref() else {
+        let err =
+            serde_json::json!({"error": "Config reload is only available under `zeroclaw daemon`"});
+        return (StatusCode::NOT_FOUND, Json(err));
+    };
+
+    match reload.reload().await {
+        Ok(()) => (StatusCode::OK, Json(serde_json::json!({"reloaded": true}))),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("{e:#}")})),
+        ),
+    }
+}
+
+/// GET /prompts — template names and the variables each one expects
+async fn handle_prompts_list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+
+    let templates: Vec<serde_json::Value> = state
+        .prompts
+        .list()
+        .into_iter()
+        .map(|name| {
+            let variables = state
+                .prompts
+                .load(&name)
+                .map(|t| prompts::template_variables(&t))
+                .unwrap_or_default();
+            serde_json::json!({"name": name, "variables": variables})
+        })
+        .collect();
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({"prompts": templates})),
+    )
+}
+
+/// `/prompts/:name` request body
+#[derive(serde::Deserialize)]
+pub struct PromptRunBody {
+    /// Values for the template's `{{var}}` placeholders
+    #[serde(default)]
+    pub vars: serde_json::Map<String, serde_json::Value>,
+    #[serde(flatten)]
+    pub budget: TurnBudget,
+}
+
+/// POST /prompts/:name — render a template and send it like `/webhook`
+async fn handle_prompt_run(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    body: Result<Json<PromptRunBody>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    let client_key = client_key_from_headers(&headers);
+    if !state.rate_limiter.allow_webhook(&client_key) {
+        let err = serde_json::json!({
+            "error": "Too many webhook requests. Please retry later.",
+            "retry_after": RATE_LIMIT_WINDOW_SECS,
+        });
+        return (StatusCode::TOO_MANY_REQUESTS, Json(err)).into_response();
+    }
+    if !is_bearer_authorized(&state, &headers) || !has_valid_webhook_secret(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+    let Json(body) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            let err = serde_json::json!({
+                "error": format!("Invalid JSON: {e}. Expected: {{\"vars\": {{...}}}}")
+            });
+            return (StatusCode::BAD_REQUEST, Json(err)).into_response();
+        }
+    };
+
+    let message = match state
+        .prompts
+        .render(&name, &serde_json::Value::Object(body.vars))
+    {
+        Ok(message) => message,
+        Err(e) => {
+            let status = match e {
+                PromptError::NotFound(_) => StatusCode::NOT_FOUND,
+                PromptError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                _ => StatusCode::BAD_REQUEST,
+            };
+            return (status, Json(serde_json::json!({"error": e.to_string()}))).into_response();
+        }
+    };
+
+    let _turn = match admit_turn("gateway:prompts").await {
+        Ok(turn) => turn,
+        Err(response) => return response,
+    };
+    match chat_within_budget(&state, &message, body.budget).await {
+        Ok(response) => {
+            let body =
+                serde_json::json!({"response": response, "model": state.model, "prompt": name});
+            (StatusCode::OK, Json(body))
+        }
+        Err(e) => chat_error_response(&e, "Prompt template"),
+    }
+    .into_response()
+}
+
+/// GET /skills — installed skill packages with versions and sources
+async fn handle_skills_list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+
+    let skills = package::list_installed(&state.workspace_dir);
+    (StatusCode::OK, Json(serde_json::json!({"skills": skills})))
+}
+
+/// GET /stats — per-tool invocations, failures, latency and output size,
+/// and the feedback given on answers
+async fn handle_stats(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+
+    let feedback = feedback::stats(&state.workspace_dir).unwrap_or_else(|e| {
+        tracing::warn!("Failed to read feedback: {e:#}");
+        feedback::FeedbackStats::default()
+    });
+    match crate::observability::tool_stats::summary(&state.workspace_dir) {
+        Ok(tools) => (
+            StatusCode::OK,
+            Json(serde_json::json!({"tools": tools, "feedback": feedback})),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to read tool stats: {e}");
+            let err = serde_json::json!({"error": "Failed to read tool stats"});
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
+        }
+    }
+}
+
+/// `/memory/:key` query params
+#[derive(serde::Deserialize)]
+pub struct MemoryNamespaceQuery {
+    pub namespace: Option<String>,
+}
+
+/// The memory store a request is about: the gateway's own, or the
+/// namespace it names.
+fn memory_for(state: &AppState, namespace: Option<&str>) -> Result<Arc<dyn Memory>> {
+    match namespace {
+        Some(name) if state.memory_config.namespace.as_deref() != Some(name) => {
+            let mem = browse::open_namespace(&state.memory_config, &state.workspace_dir, name)?;
+            Ok(Arc::from(mem))
+        }
+        _ => Ok(state.mem.clone()),
+    }
+}
+
+/// GET /memory — what the agent remembers, a page at a time; filtered by
+/// `category`, `namespace`, `since`, `until` and `key_prefix`, paged by
+/// `offset` and `limit`
+async fn handle_memory_browse(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(filter): Query<BrowseFilter>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let mem = match filter
+        .check()
+        .and_then(|()| memory_for(&state, filter.namespace.as_deref()))
+    {
+        Ok(mem) => mem,
+        Err(e) => {
+            let err = serde_json::json!({"error": e.to_string()});
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
+
+    match browse::browse(mem.as_ref(), &filter).await {
+        Ok(page) => {
+            let mut body = serde_json::to_value(&page).unwrap_or_default();
+            body["namespaces"] = serde_json::json!(browse::namespaces(&state.workspace_dir));
+            (StatusCode::OK, Json(body))
+        }
+        Err(e) => {
+            tracing::error!("Failed to list memories: {e}");
+            let err = serde_json::json!({"error": "Failed to list memories"});
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
+        }
+    }
+}
+
+/// DELETE /memory — forget every memory the filters match (at least one
+/// of `category`, `since`, `until`, `key_prefix` is required)
+async fn handle_memory_forget_matching(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(filter): Query<BrowseFilter>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    if filter.is_empty() {
+        let err = serde_json::json!({
+            "error": "Give category, since, until or key_prefix to choose what to forget"
+        });
+        return (StatusCode::BAD_REQUEST, Json(err));
+    }
+    let mem = match filter
+        .check()
+        .and_then(|()| memory_for(&state, filter.namespace.as_deref()))
+    {
+        Ok(mem) => mem,
+        Err(e) => {
+            let err = serde_json::json!({"error": e.to_string()});
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
+
+    match browse::forget_matching(mem.as_ref(), &filter).await {
+        Ok(removed) => (
+            StatusCode::OK,
+            Json(serde_json::json!({"count": removed.len(), "removed": removed})),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to forget memories: {e}");
+            let err = serde_json::json!({"error": "Failed to forget memories"});
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
+        }
+    }
+}
+
+/// DELETE /memory/:key — forget one memory, from `?namespace=` if given
+async fn handle_memory_forget(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+    Query(query): Query<MemoryNamespaceQuery>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let mem = match memory_for(&state, query.namespace.as_deref()) {
+        Ok(mem) => mem,
+        Err(e) => {
+            let err = serde_json::json!({"error": e.to_string()});
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
+
+    match mem.forget(&key).await {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({"removed": key}))),
+        Ok(false) => {
+            let err = serde_json::json!({"error": format!("No memory stored under '{key}'")});
+            (StatusCode::NOT_FOUND, Json(err))
+        }
+        Err(e) => {
+            tracing::error!("Failed to forget memory {key}: {e}");
+            let err = serde_json::json!({"error": "Failed to forget memory"});
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
+        }
+    }
+}
+
+fn composio_unavailable() -> (StatusCode, Json<serde_json::Value>) {
+    let err = serde_json::json!({"error": "Composio is not enabled"});
+    (StatusCode::NOT_FOUND, Json(err))
+}
+
+/// GET /composio/connections — accounts connected through Composio
+async fn handle_composio_connections(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Some(composio) = state.composio.as_ref() else {
+        return composio_unavailable();
+    };
+
+    match composio.list().await {
+        Ok(connections) => (
+            StatusCode::OK,
+            Json(serde_json::json!({"connections": connections})),
+        ),
+        Err(e) => (
+            StatusCode::BAD_GATEWAY,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+fn jobs_unavailable() -> (StatusCode, Json<serde_json::Value>) {
+    let err = serde_json::json!({"error": "Jobs are not enabled"});
+    (StatusCode::NOT_FOUND, Json(err))
+}
+
+/// GET /jobs — queued, running and finished jobs, newest first
+async fn handle_jobs_list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Some(jobs) = state.jobs.as_ref() else {
+        return jobs_unavailable();
+    };
+
+    match jobs.list() {
+        Ok(jobs) => (StatusCode::OK, Json(serde_json::json!({"jobs": jobs}))),
+        Err(e) => {
+            tracing::error!("Failed to list jobs: {e}");
+            let err = serde_json::json!({"error": "Failed to list jobs"});
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
+        }
+    }
+}
+
+/// `/jobs` request body
+#[derive(serde::Deserialize)]
+pub struct JobEnqueueBody {
+    pub prompt: String,
+}
+
+/// POST /jobs — queue a job for the daemon; poll `GET /jobs/:id` for the result
+async fn handle_job_enqueue(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Result<Json<JobEnqueueBody>, axum::extract::rejection::JsonRejection>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Some(jobs) = state.jobs.as_ref() else {
+        return jobs_unavailable();
+    };
+    let Json(body) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            let err = serde_json::json!({
+                "error": format!("Invalid JSON: {e}. Expected: {{\"prompt\": \"...\"}}")
+            });
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
+
+    match jobs.enqueue(&body.prompt, None) {
+        Ok(job) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({"id": job.id, "status": job.status})),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+/// GET /jobs/:id — one job with its progress and result
+async fn handle_job_get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Some(jobs) = state.jobs.as_ref() else {
+        return jobs_unavailable();
+    };
+
+    match jobs.load(&id) {
+        Ok(job) => (StatusCode::OK, Json(serde_json::json!(job))),
+        Err(e) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+fn workflows_unavailable() -> Response {
+    let err = serde_json::json!({"error": "Workflows are not enabled"});
+    (StatusCode::NOT_FOUND, Json(err)).into_response()
+}
+
+/// GET /workflows — valid workflow definitions, by name
+async fn handle_workflows_list(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+    let Some(engine) = state.workflows.as_ref() else {
+        return workflows_unavailable();
+    };
+    let workflows = engine.list();
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({"workflows": workflows})),
+    )
+        .into_response()
+}
+
+/// `/workflows/:name/run` request body
+#[derive(serde::Deserialize, Default)]
+pub struct WorkflowRunBody {
+    #[serde(default)]
+    pub input: String,
+}
+
+/// POST /workflows/:name/run — run a workflow and return every step's outcome
+async fn handle_workflow_run(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    body: Option<Json<WorkflowRunBody>>,
+) -> Response {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+    let Some(engine) = state.workflows.as_ref() else {
+        return workflows_unavailable();
+    };
+    let workflow = match engine.load(&name) {
+        Ok(workflow) => workflow,
+        Err(e) => {
+            let err = serde_json::json!({"error": format!("{e:#}")});
+            return (StatusCode::NOT_FOUND, Json(err)).into_response();
+        }
+    };
+    let body = body.map(|Json(b)| b).unwrap_or_default();
+
+    let _turn = match admit_turn("gateway:workflows").await {
+        Ok(turn) => turn,
+        Err(response) => return response,
+    };
+    let run = engine.run(&workflow, &body.input).await;
+    (StatusCode::OK, Json(serde_json::json!(run))).into_response()
+}
+
+/// GET /workflows/runs — saved runs, most recent first
+async fn handle_workflow_runs_list(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+    let Some(engine) = state.workflows.as_ref() else {
+        return workflows_unavailable();
+    };
+    match engine.runs().list() {
+        Ok(runs) => (StatusCode::OK, Json(serde_json::json!({"runs": runs}))).into_response(),
+        Err(e) => {
+            let err = serde_json::json!({"error": format!("{e:#}")});
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err)).into_response()
+        }
+    }
+}
+
+/// GET /workflows/runs/:id — one run, by id or id prefix
+async fn handle_workflow_run_get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+    let Some(engine) = state.workflows.as_ref() else {
+        return workflows_unavailable();
+    };
+    match engine.runs().load(&id) {
+        Ok(run) => (StatusCode::OK, Json(serde_json::json!(run))).into_response(),
+        Err(e) => {
+            let err = serde_json::json!({"error": format!("{e:#}")});
+            (StatusCode::NOT_FOUND, Json(err)).into_response()
+        }
+    }
+}
+
+/// POST /workflows/runs/:id/approve
+async fn handle_workflow_approve(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    decide_workflow_run(&state, &headers, &id, Decision::Approve)
+}
+
+/// POST /workflows/runs/:id/deny
+async fn handle_workflow_deny(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    decide_workflow_run(&state, &headers, &id, Decision::Deny)
+}
+
+/// Answer every approval step a paused run waits on, then let it go on in
+/// the background. Returns the run as decided.
+fn decide_workflow_run(
+    state: &AppState,
+    headers: &HeaderMap,
+    id: &str,
+    decision: Decision,
+) -> Response {
+    if !is_bearer_authorized(state, headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+    let Some(engine) = state.workflows.as_ref() else {
+        return workflows_unavailable();
+    };
+    if let Err(e) = engine.runs().load(id) {
+        let err = serde_json::json!({"error": format!("{e:#}")});
+        return (StatusCode::NOT_FOUND, Json(err)).into_response();
+    }
+    match engine.decide(id, decision, None) {
+        Ok(run) => {
+            if run.status == RunStatus::Running {
+                let engine = engine.clone();
+                let resumed = run.clone();
+                tokio::spawn(async move { engine.resume(resumed).await });
+            }
+            (StatusCode::ACCEPTED, Json(serde_json::json!(run))).into_response()
+        }
+        Err(e) => {
+            let err = serde_json::json!({"error": format!("{e:#}")});
+            (StatusCode::CONFLICT, Json(err)).into_response()
+        }
+    }
+}
+
+fn a2a_unavailable() -> Response {
+    let err = serde_json::json!({"error": "A2A is not enabled"});
+    (StatusCode::NOT_FOUND, Json(err)).into_response()
+}
+
+/// GET /.well-known/agent.json — A2A agent card; public, for discovery
+async fn handle_a2a_card(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let Some(server) = state.a2a.as_ref() else {
+        return a2a_unavailable();
+    };
+    let host = headers.get(header::HOST).and_then(|v| v.to_str().ok());
+    let card = server.card(host, state.pairing.require_pairing());
+    (StatusCode::OK, Json(card)).into_response()
+}
+
+/// POST /a2a — A2A JSON-RPC; `message/stream` and `tasks/resubscribe`
+/// answer with server-sent events
+async fn handle_a2a(State(state): State<AppState>, headers: HeaderMap, body: Bytes) -> Response {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+    let Some(server) = state.a2a.clone() else {
+        return a2a_unavailable();
+    };
+    // Only messages start agent turns; task lookups skip the queue
+    let starts_turn = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|call| call["method"].as_str().map(|m| m.starts_with("message/")))
+        .unwrap_or(false);
+    let turn = if starts_turn {
+        match admit_turn("gateway:a2a").await {
+            Ok(turn) => Some(turn),
+            Err(response) => return response,
+        }
+    } else {
+        None
+    };
+
+    match a2a::rpc::dispatch(&server, &body, turn).await {
+        a2a::rpc::Reply::Json(reply) => (StatusCode::OK, Json(reply)).into_response(),
+        a2a::rpc::Reply::Stream { id, first, events } => {
+            let events = a2a::rpc::stream_responses(id, &first, events)
+                .map(|reply| Ok::<_, Infallible>(Event::default().data(reply.to_string())));
+            Sse::new(events)
+                .keep_alive(KeepAlive::default())
+                .into_response()
+        }
+    }
+}
+
+/// `/composio/connections` request body
+#[derive(serde::Deserialize)]
+pub struct ComposioConnectBody {
+    /// App to connect, e.g. "github"
+    pub app: String,
+}
+
+/// POST /composio/connections — start an OAuth flow, returning its URL
+async fn handle_composio_connect(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Result<Json<ComposioConnectBody>, axum::extract::rejection::JsonRejection>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) || !has_valid_webhook_secret(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Some(composio) = state.composio.as_ref() else {
+        return composio_unavailable();
+    };
+    let Json(body) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            let err = serde_json::json!({
+                "error": format!("Invalid JSON: {e}. Expected: {{\"app\": \"github\"}}")
+            });
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
+
+    match composio.connect(body.app.trim()).await {
+        Ok(url) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({"app": body.app.trim(), "redirect_url": url})),
+        ),
+        Err(e) => (
+            StatusCode::BAD_GATEWAY,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+/// DELETE /composio/connections/:id — revoke a connected account
+async fn handle_composio_revoke(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) || !has_valid_webhook_secret(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Some(composio) = state.composio.as_ref() else {
+        return composio_unavailable();
+    };
+
+    match composio.revoke(&id).await {
+        Ok(()) => (StatusCode::OK, Json(serde_json::json!({"revoked": id}))),
+        Err(e) => (
+            StatusCode::BAD_GATEWAY,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+/// `/skills/install` request body
+#[derive(serde::Deserialize)]
+pub struct SkillInstallBody {
+    /// Git URL; local paths are CLI-only
+    pub source: String,
+    /// Branch or tag to check out
+    #[serde(default, rename = "ref")]
+    pub git_ref: Option<String>,
+}
+
+/// POST /skills/install — clone a skill package into the workspace
+async fn handle_skill_install(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Result<Json<SkillInstallBody>, axum::extract::rejection::JsonRejection>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) || !has_valid_webhook_secret(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Json(body) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            let err = serde_json::json!({
+                "error": format!("Invalid JSON: {e}. Expected: {{\"source\": \"https://...\"}}")
+            });
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
+
+    let workspace_dir = state.workspace_dir.clone();
+    let installed = tokio::task::spawn_blocking(move || {
+        package::install_from_git(&workspace_dir, &body.source, body.git_ref.as_deref())
+    })
+    .await;
+    match installed {
+        Ok(Ok(skill)) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({"skill": skill})),
+        ),
+        Ok(Err(e)) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+        Err(e) => {
+            tracing::error!("Skill install task failed: {e}");
+            let err = serde_json::json!({"error": "Skill install failed"});
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
+        }
+    }
+}
+
+/// `/skills/registry` query params
+#[derive(serde::Deserialize)]
+pub struct RegistrySearchQuery {
+    #[serde(default)]
+    pub q: String,
+}
+
+fn registry_unavailable() -> (StatusCode, Json<serde_json::Value>) {
+    let err = serde_json::json!({"error": "No skill registry configured"});
+    (StatusCode::NOT_FOUND, Json(err))
+}
+
+/// GET /skills/registry?q=... — search the signed skill index
+async fn handle_registry_search(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<RegistrySearchQuery>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Some(registry) = state.skill_registry.as_ref() else {
+        return registry_unavailable();
+    };
+
+    match registry.search(&query.q).await {
+        Ok(skills) => (StatusCode::OK, Json(serde_json::json!({"skills": skills}))),
+        Err(e) => (
+            StatusCode::BAD_GATEWAY,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+/// POST /skills/registry/:name — install a package listed in the registry
+async fn handle_registry_install(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) || !has_valid_webhook_secret(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+    let Some(registry) = state.skill_registry.as_ref() else {
+        return registry_unavailable();
+    };
+
+    match registry.install(&state.workspace_dir, &name).await {
+        Ok(skill) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({"skill": skill})),
+        ),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+/// DELETE /skills/:name — uninstall a skill
+async fn handle_skill_remove(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) || !has_valid_webhook_secret(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
+    }
+
+    match package::uninstall(&state.workspace_dir, &name) {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({"removed": name}))),
+        Ok(false) => {
+            let err = serde_json::json!({"error": format!("Skill not found: {name}")});
+            (StatusCode::NOT_FOUND, Json(err))
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ),
+    }
+}
+
+/// `POST /sessions/:id/branch` body
+#[derive(serde::Deserialize)]
+pub struct SessionBranchBody {
+    /// Message to branch at; the turn containing it is rerun
+    pub index: usize,
+    /// Defaults to the gateway's model
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Defaults to the gateway's temperature
+    #[serde(default)]
+    pub temperature: Option<f64>,
+}
+
+/// POST /sessions/:id/branch — fork a saved session at a message and rerun
+/// that turn; the branch is saved as a new session next to the original
+async fn handle_session_branch(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    body: Result<Json<SessionBranchBody>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+    let Json(body) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            let err = serde_json::json!({
+                "error": format!("Invalid JSON: {e}. Expected: {{\"index\": 2}}")
+            });
+            return (StatusCode::BAD_REQUEST, Json(err)).into_response();
+        }
+    };
+    let session = match state.sessions.load(&id) {
+        Ok(session) => session,
+        Err(e) => {
+            let err = serde_json::json!({"error": e.to_string()});
+            return (StatusCode::NOT_FOUND, Json(err)).into_response();
+        }
+    };
+
+    let model = body.model.unwrap_or_else(|| state.model.clone());
+    let temperature = body.temperature.unwrap_or(state.temperature);
+    let (mut branch, message) = match session.branch(body.index, &model) {
+        Ok(fork) => fork,
+        Err(e) => {
+            let err = serde_json::json!({"error": e.to_string()});
+            return (StatusCode::BAD_REQUEST, Json(err)).into_response();
+        }
+    };
+
+    let _turn = match admit_turn("gateway:sessions").await {
+        Ok(turn) => turn,
+        Err(response) => return response,
+    };
+    let mut history = branch.messages.clone();
+    history.push(ChatMessage::user(&message));
+    let response = match state
+        .provider
+        .chat_with_history(&history, &model, temperature)
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => return chat_error_response(&e, "Session branch").into_response(),
+    };
+
+    branch.push(ChatMessage::user(&message));
+    branch.push(ChatMessage::assistant(&response));
+    if let Err(e) = state.sessions.save(&branch) {
+        tracing::error!("Failed to save session branch: {e}");
+        let err = serde_json::json!({"error": "Failed to save session branch"});
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(err)).into_response();
+    }
+    let body = serde_json::json!({"session": branch.meta, "response": response});
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+/// `POST /sessions/:id/feedback` body
+#[derive(serde::Deserialize)]
+pub struct SessionFeedbackBody {
+    /// The rated answer's position in the session
+    pub message_index: usize,
+    pub rating: crate::sessions::Rating,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// POST /sessions/:id/feedback — thumbs up or down on one answer
+async fn handle_session_feedback(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    body: Result<Json<SessionFeedbackBody>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+    }
+    let Json(body) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            let err = serde_json::json!({
+                "error": format!(
+                    "Invalid JSON: {e}. Expected: {{\"message_index\": 1, \"rating\": \"up\"}}"
+                )
+            });
+            return (StatusCode::BAD_REQUEST, Json(err)).into_response();
         }
-        Ok(None) => {
-            tracing::warn!("🔐 Pairing attempt with invalid code");
-            let err = serde_json::json!({"error": "Invalid pairing code"});
-            (StatusCode::FORBIDDEN, Json(err))
+    };
+    let feedback = Feedback::new(&id, body.message_index, body.rating, "gateway")
+        .with_comment(body.comment);
+    match feedback::record(&state.workspace_dir, &feedback) {
+        Ok(()) => {
+            let body = serde_json::json!({"feedback": feedback});
+            (StatusCode::OK, Json(body)).into_response()
         }
-        Err(lockout_secs) => {
-            tracing::warn!(
-                "🔐 Pairing locked out — too many failed attempts ({lockout_secs}s remaining)"
-            );
-            let err = serde_json::json!({
-                "error": format!("Too many failed attempts. Try again in {lockout_secs}s."),
-                "retry_after": lockout_secs
-            });
-            (StatusCode::TOO_MANY_REQUESTS, Json(err))
+        Err(e) => {
+            let err = serde_json::json!({"error": e.to_string()});
+            (StatusCode::BAD_REQUEST, Json(err)).into_response()
         }
     }
 }
 
-/// Webhook request body
+/// `/sessions/:id` query params
 #[derive(serde::Deserialize)]
-pub struct WebhookBody {
-    pub message: String,
+pub struct SessionExportQuery {
+    /// "json" (default) or "markdown"
+    pub format: Option<String>,
 }
 
-/// POST /webhook — main webhook endpoint
-async fn handle_webhook(
+/// GET /sessions — session metadata (id, title, timestamps), newest first
+async fn handle_sessions_list(
     State(state): State<AppState>,
     headers: HeaderMap,
-    body: Result<Json<WebhookBody>, axum::extract::rejection::JsonRejection>,
 ) -> impl IntoResponse {
-    let client_key = client_key_from_headers(&headers);
-    if !state.rate_limiter.allow_webhook(&client_key) {
-        tracing::warn!("/webhook rate limit exceeded for key: {client_key}");
-        let err = serde_json::json!({
-            "error": "Too many webhook requests. Please retry later.",
-            "retry_after": RATE_LIMIT_WINDOW_SECS,
-        });
-        return (StatusCode::TOO_MANY_REQUESTS, Json(err));
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
     }
 
-    // ── Bearer token auth (pairing) ──
-    if state.pairing.require_pairing() {
-        let auth = headers
-            .get(header::AUTHORIZATION)
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-        let token = auth.strip_prefix("Bearer ").unwrap_or("");
-        if !state.pairing.is_authenticated(token) {
-            tracing::warn!("Webhook: rejected — not paired / invalid bearer token");
-            let err = serde_json::json!({
-                "error": "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
-            });
-            return (StatusCode::UNAUTHORIZED, Json(err));
+    match state.sessions.list() {
+        Ok(sessions) => (
+            StatusCode::OK,
+            Json(serde_json::json!({"sessions": sessions})),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to list sessions: {e}");
+            let err = serde_json::json!({"error": "Failed to list sessions"});
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
         }
     }
+}
 
-    // ── Webhook secret auth (optional, additional layer) ──
-    if let Some(ref secret) = state.webhook_secret {
-        let header_val = headers
-            .get("X-Webhook-Secret")
-            .and_then(|v| v.to_str().ok());
-        match header_val {
-            Some(val) if constant_time_eq(val, secret.as_ref()) => {}
-            _ => {
-                tracing::warn!("Webhook: rejected request — invalid or missing X-Webhook-Secret");
-                let err = serde_json::json!({"error": "Unauthorized — invalid or missing X-Webhook-Secret header"});
-                return (StatusCode::UNAUTHORIZED, Json(err));
-            }
-        }
+/// `/sessions/search` query params
+#[derive(serde::Deserialize)]
+pub struct SessionSearchQuery {
+    pub q: String,
+    pub limit: Option<usize>,
+    /// Blend in embedding similarity when an embedding provider is configured
+    #[serde(default)]
+    pub semantic: bool,
+}
+
+/// GET /sessions/search?q=... — matching sessions with snippets, best first
+async fn handle_sessions_search(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<SessionSearchQuery>,
+) -> impl IntoResponse {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err));
     }
 
-    // ── Parse body ──
-    let Json(webhook_body) = match body {
-        Ok(b) => b,
-        Err(e) => {
+    let limit = query.limit.unwrap_or(10).clamp(1, 100);
+    let result = match (query.semantic, state.session_search.as_deref()) {
+        (true, Some(semantic)) => {
+            state
+                .sessions
+                .search_semantic(&query.q, semantic, limit)
+                .await
+        }
+        (true, None) => {
             let err = serde_json::json!({
-                "error": format!("Invalid JSON: {e}. Expected: {{\"message\": \"...\"}}")
+                "error": "Semantic search unavailable — no embedding provider configured"
             });
             return (StatusCode::BAD_REQUEST, Json(err));
         }
+        (false, _) => state.sessions.search(&query.q, limit),
     };
 
-    // ── Idempotency (optional) ──
-    if let Some(idempotency_key) = headers
-        .get("X-Idempotency-Key")
-        .and_then(|v| v.to_str().ok())
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-    {
-        if !state.idempotency_store.record_if_new(idempotency_key) {
-            tracing::info!("Webhook duplicate ignored (idempotency key: {idempotency_key})");
-            let body = serde_json::json!({
-                "status": "duplicate",
-                "idempotent": true,
-                "message": "Request already processed for this idempotency key"
-            });
-            return (StatusCode::OK, Json(body));
+    match result {
+        Ok(hits) => (StatusCode::OK, Json(serde_json::json!({"results": hits}))),
+        Err(e) => {
+            tracing::error!(
+                "Session search failed: {}",
+                providers::sanitize_api_error(&e.to_string())
+            );
+            let err = serde_json::json!({"error": "Session search failed"});
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
         }
     }
+}
 
-    let message = &webhook_body.message;
-
-    if state.auto_save {
-        let key = webhook_memory_key();
-        let _ = state
-            .mem
-            .store(&key, message, MemoryCategory::Conversation)
-            .await;
+/// GET /sessions/:id — full transcript as JSON, or Markdown with `?format=markdown`
+async fn handle_session_get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(query): Query<SessionExportQuery>,
+) -> axum::response::Response {
+    if !is_bearer_authorized(&state, &headers) {
+        let err = serde_json::json!({"error": "Unauthorized — pair first via POST /pair"});
+        return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
     }
 
-    match state
-        .provider
-        .chat(message, &state.model, state.temperature)
-        .await
-    {
-        Ok(response) => {
-            let body = serde_json::json!({"response": response, "model": state.model});
-            (StatusCode::OK, Json(body))
-        }
+    let session = match state.sessions.load(&id) {
+        Ok(session) => session,
         Err(e) => {
-            tracing::error!(
-                "Webhook provider error: {}",
-                providers::sanitize_api_error(&e.to_string())
-            );
-            let err = serde_json::json!({"error": "LLM request failed"});
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
+            let err = serde_json::json!({"error": e.to_string()});
+            return (StatusCode::NOT_FOUND, Json(err)).into_response();
+        }
+    };
+
+    match query.format.as_deref() {
+        Some("markdown" | "md") => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+            session.to_markdown(),
+        )
+            .into_response(),
+        Some("json") | None => (StatusCode::OK, Json(session)).into_response(),
+        Some(other) => {
+            let err = serde_json::json!({
+                "error": format!("Unknown format '{other}' (use json or markdown)")
+            });
+            (StatusCode::BAD_REQUEST, Json(err)).into_response()
         }
     }
 }
@@ -638,13 +2074,25 @@ async fn handle_whatsapp_message(
         // Auto-save to memory
         if state.auto_save {
             let key = whatsapp_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation)
-                .await;
+            let _ = memory::dedup::store(
+                state.mem.as_ref(),
+                &key,
+                &msg.content,
+                MemoryCategory::Conversation,
+                &state.memory_config.dedup,
+            )
+            .await;
         }
 
         // Call the LLM
+        let _permit = match queue::shared().acquire("whatsapp").await {
+            Ok(permit) => permit,
+            Err(busy) => {
+                let _ = wa.send(&format!("⚠️ {busy}"), &msg.sender).await;
+                continue;
+            }
+        };
+        let _run = crate::daemon::shutdown::begin_run();
         match state
             .provider
             .chat(&msg.content, &state.model, state.temperature)
@@ -655,6 +2103,7 @@ async fn handle_whatsapp_message(
                 if let Err(e) = wa.send(&response, &msg.sender).await {
                     tracing::error!("Failed to send WhatsApp reply: {e}");
                 }
+                spawn_fact_extraction(&state, &msg.content, &response);
             }
             Err(e) => {
                 tracing::error!("LLM error for WhatsApp message: {e:#}");
@@ -756,6 +2205,7 @@ mod tests {
             sender: "+1234567890".into(),
             content: "hello".into(),
             channel: "whatsapp".into(),
+            author: None,
             timestamp: 1,
         };
 
@@ -885,6 +2335,16 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn bind_gateway_reports_port_and_refuses_public_hosts() {
+        let config = Config::default();
+        let listener = bind_gateway("127.0.0.1", 0, &config).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert_ne!(port, 0);
+        assert!(bind_gateway("127.0.0.1", port, &config).await.is_err());
+        assert!(bind_gateway("0.0.0.0", 0, &config).await.is_err());
+    }
+
     #[tokio::test]
     async fn webhook_idempotency_skips_duplicate_provider_calls() {
         let provider_impl = Arc::new(MockProvider::default());
@@ -897,12 +2357,24 @@ mod tests {
             temperature: 0.0,
             mem: memory,
             auto_save: false,
+            memory_config: MemoryConfig::default(),
             webhook_secret: None,
             pairing: Arc::new(PairingGuard::new(false, &[])),
             rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
             idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
             whatsapp: None,
             whatsapp_app_secret: None,
+            sessions: Arc::new(SessionStore::new(std::path::Path::new("."))),
+            session_search: None,
+            turn_budget: TurnBudget::default(),
+            prompts: Arc::new(PromptLibrary::new("prompts")),
+            workspace_dir: PathBuf::from("workspace"),
+            skill_registry: None,
+            composio: None,
+            jobs: None,
+            workflows: None,
+            a2a: None,
+            reload: None,
         };
 
         let mut headers = HeaderMap::new();
@@ -910,6 +2382,7 @@ mod tests {
 
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
+            budget: TurnBudget::default(),
         }));
         let first = handle_webhook(State(state.clone()), headers.clone(), body)
             .await
@@ -918,6 +2391,7 @@ mod tests {
 
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
+            budget: TurnBudget::default(),
         }));
         let second = handle_webhook(State(state), headers, body)
             .await
@@ -931,6 +2405,216 @@ mod tests {
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn webhook_enforces_request_turn_budget() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let state = AppState {
+            provider: provider_impl.clone(),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            memory_config: MemoryConfig::default(),
+            webhook_secret: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            sessions: Arc::new(SessionStore::new(std::path::Path::new("."))),
+            session_search: None,
+            turn_budget: TurnBudget {
+                max_tokens: Some(1_000),
+                ..TurnBudget::default()
+            },
+            prompts: Arc::new(PromptLibrary::new("prompts")),
+            workspace_dir: PathBuf::from("workspace"),
+            skill_registry: None,
+            composio: None,
+            jobs: None,
+            workflows: None,
+            a2a: None,
+            reload: None,
+        };
+
+        // The body can lower the profile cap below what the message needs
+        let body: WebhookBody =
+            serde_json::from_str(r#"{"message": "summarize this report", "max_tokens": 2}"#)
+                .unwrap();
+        assert_eq!(body.budget.max_tokens, Some(2));
+        let response = handle_webhook(State(state.clone()), HeaderMap::new(), Ok(Json(body)))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let payload = response.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["limit"], "max_tokens");
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+
+        // ...but not raise it
+        let body: WebhookBody =
+            serde_json::from_str(r#"{"message": "hi", "max_tokens": 1000000}"#).unwrap();
+        let response = handle_webhook(State(state), HeaderMap::new(), Ok(Json(body)))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn prompt_route_renders_templates() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("triage.md"), "Triage ticket {{id}}").unwrap();
+        let provider_impl = Arc::new(MockProvider::default());
+        let state = AppState {
+            provider: provider_impl.clone(),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            memory_config: MemoryConfig::default(),
+            webhook_secret: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            sessions: Arc::new(SessionStore::new(std::path::Path::new("."))),
+            session_search: None,
+            turn_budget: TurnBudget::default(),
+            prompts: Arc::new(PromptLibrary::new(tmp.path())),
+            workspace_dir: tmp.path().to_path_buf(),
+            skill_registry: None,
+            composio: None,
+            jobs: None,
+            workflows: None,
+            a2a: None,
+            reload: None,
+        };
+        let run = |name: &str, body: &str| {
+            let body: PromptRunBody = serde_json::from_str(body).unwrap();
+            handle_prompt_run(
+                State(state.clone()),
+                HeaderMap::new(),
+                Path(name.to_string()),
+                Ok(Json(body)),
+            )
+        };
+
+        let ok = run("triage", r#"{"vars": {"id": 42}}"#)
+            .await
+            .into_response();
+        assert_eq!(ok.status(), StatusCode::OK);
+        let missing = run("triage", "{}").await.into_response();
+        assert_eq!(missing.status(), StatusCode::BAD_REQUEST);
+        let unknown = run("nope", "{}").await.into_response();
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
+
+        let listed = handle_prompts_list(State(state.clone()), HeaderMap::new())
+            .await
+            .into_response();
+        let payload = listed.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(
+            parsed["prompts"],
+            serde_json::json!([{"name": "triage", "variables": ["id"]}])
+        );
+    }
+
+    #[tokio::test]
+    async fn skill_routes_list_install_and_remove() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let skill_dir = tmp.path().join("skills/notes");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.toml"),
+            "[skill]\nname = \"notes\"\ndescription = \"Take notes\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        let state = AppState {
+            provider: Arc::new(MockProvider::default()),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            memory_config: MemoryConfig::default(),
+            webhook_secret: Some(Arc::from("s3cret")),
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            sessions: Arc::new(SessionStore::new(std::path::Path::new("."))),
+            session_search: None,
+            turn_budget: TurnBudget::default(),
+            prompts: Arc::new(PromptLibrary::new("prompts")),
+            workspace_dir: tmp.path().to_path_buf(),
+            skill_registry: None,
+            composio: None,
+            jobs: None,
+            workflows: None,
+            a2a: None,
+            reload: None,
+        };
+        let mut secret = HeaderMap::new();
+        secret.insert("X-Webhook-Secret", HeaderValue::from_static("s3cret"));
+
+        let listed = handle_skills_list(State(state.clone()), HeaderMap::new())
+            .await
+            .into_response();
+        let payload = listed.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["skills"][0]["name"], "notes");
+        assert_eq!(parsed["skills"][0]["version"], "1.0.0");
+
+        let body = SkillInstallBody {
+            source: tmp.path().display().to_string(),
+            git_ref: None,
+        };
+        let local = handle_skill_install(State(state.clone()), secret.clone(), Ok(Json(body)))
+            .await
+            .into_response();
+        assert_eq!(local.status(), StatusCode::BAD_REQUEST);
+
+        let remove = |headers: HeaderMap, name: &str| {
+            handle_skill_remove(State(state.clone()), headers, Path(name.to_string()))
+        };
+        let unauthorized = remove(HeaderMap::new(), "notes").await.into_response();
+        assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+        let removed = remove(secret.clone(), "notes").await.into_response();
+        assert_eq!(removed.status(), StatusCode::OK);
+        assert!(!skill_dir.exists());
+        let missing = remove(secret.clone(), "notes").await.into_response();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+        let no_registry =
+            handle_registry_install(State(state.clone()), secret.clone(), Path("x".into()))
+                .await
+                .into_response();
+        assert_eq!(no_registry.status(), StatusCode::NOT_FOUND);
+
+        let reload = handle_admin_reload(State(state.clone()), HeaderMap::new())
+            .await
+            .into_response();
+        assert_eq!(reload.status(), StatusCode::UNAUTHORIZED);
+        let outside_daemon = handle_admin_reload(State(state.clone()), secret.clone())
+            .await
+            .into_response();
+        assert_eq!(outside_daemon.status(), StatusCode::NOT_FOUND);
+
+        let selftest = handle_selftest(State(state.clone()), HeaderMap::new())
+            .await
+            .into_response();
+        assert_eq!(selftest.status(), StatusCode::UNAUTHORIZED);
+        let selftest = handle_selftest(State(state), secret).await.into_response();
+        assert_eq!(selftest.status(), StatusCode::OK);
+        let payload = selftest.into_body().collect().await.unwrap().to_bytes();
+        let report: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(report["checks"][0]["name"], "canary_turn");
+        assert_eq!(report["ok"], true);
+    }
+
     #[tokio::test]
     async fn webhook_autosave_stores_distinct_keys_per_request() {
         let provider_impl = Arc::new(MockProvider::default());
@@ -945,18 +2629,31 @@ mod tests {
             temperature: 0.0,
             mem: memory,
             auto_save: true,
+            memory_config: MemoryConfig::default(),
             webhook_secret: None,
             pairing: Arc::new(PairingGuard::new(false, &[])),
             rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
             idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
             whatsapp: None,
             whatsapp_app_secret: None,
+            sessions: Arc::new(SessionStore::new(std::path::Path::new("."))),
+            session_search: None,
+            turn_budget: TurnBudget::default(),
+            prompts: Arc::new(PromptLibrary::new("prompts")),
+            workspace_dir: PathBuf::from("workspace"),
+            skill_registry: None,
+            composio: None,
+            jobs: None,
+            workflows: None,
+            a2a: None,
+            reload: None,
         };
 
         let headers = HeaderMap::new();
 
         let body1 = Ok(Json(WebhookBody {
             message: "hello one".into(),
+            budget: TurnBudget::default(),
         }));
         let first = handle_webhook(State(state.clone()), headers.clone(), body1)
             .await
@@ -965,6 +2662,7 @@ mod tests {
 
         let body2 = Ok(Json(WebhookBody {
             message: "hello two".into(),
+            budget: TurnBudget::default(),
         }));
         let second = handle_webhook(State(state), headers, body2)
             .await
@@ -983,6 +2681,275 @@ mod tests {
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 2);
     }
 
+    #[tokio::test]
+    async fn memory_endpoints_page_filter_and_forget() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mem = crate::memory::SqliteMemory::new(tmp.path()).unwrap();
+        for key in ["pref:editor", "pref:lang", "user_msg_1"] {
+            mem.store(key, "remembered", MemoryCategory::Core)
+                .await
+                .unwrap();
+        }
+        let state = AppState {
+            provider: Arc::new(MockProvider::default()),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(mem),
+            auto_save: false,
+            memory_config: MemoryConfig::default(),
+            webhook_secret: None,
+            pairing: Arc::new(PairingGuard::new(true, &["secret-token".into()])),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            sessions: Arc::new(SessionStore::new(tmp.path())),
+            session_search: None,
+            turn_budget: TurnBudget::default(),
+            prompts: Arc::new(PromptLibrary::new("prompts")),
+            workspace_dir: tmp.path().to_path_buf(),
+            skill_registry: None,
+            composio: None,
+            jobs: None,
+            workflows: None,
+            a2a: None,
+            reload: None,
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret-token"),
+        );
+        let prefs = BrowseFilter {
+            key_prefix: Some("pref:".into()),
+            limit: Some(1),
+            ..BrowseFilter::default()
+        };
+
+        let denied = handle_memory_browse(
+            State(state.clone()),
+            HeaderMap::new(),
+            Query(prefs.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(denied.status(), StatusCode::UNAUTHORIZED);
+        let page = handle_memory_browse(State(state.clone()), headers.clone(), Query(prefs.clone()))
+            .await
+            .into_response();
+        assert_eq!(page.status(), StatusCode::OK);
+        let payload = page.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["total"], 2);
+        assert_eq!(parsed["next_offset"], 1);
+        assert_eq!(parsed["entries"].as_array().unwrap().len(), 1);
+
+        let everything = handle_memory_forget_matching(
+            State(state.clone()),
+            headers.clone(),
+            Query(BrowseFilter::default()),
+        )
+        .await
+        .into_response();
+        assert_eq!(everything.status(), StatusCode::BAD_REQUEST);
+        let forgotten =
+            handle_memory_forget_matching(State(state.clone()), headers.clone(), Query(prefs))
+                .await
+                .into_response();
+        let payload = forgotten.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["count"], 2);
+
+        let forget = |key: &str| {
+            handle_memory_forget(
+                State(state.clone()),
+                headers.clone(),
+                Path(key.to_string()),
+                Query(MemoryNamespaceQuery { namespace: None }),
+            )
+        };
+        assert_eq!(
+            forget("user_msg_1").await.into_response().status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            forget("user_msg_1").await.into_response().status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(state.mem.count().await.unwrap(), 0);
+    }
+
+    /// Gateway state over a session store holding one titled session, with
+    /// the bearer headers it accepts.
+    fn sessions_state(
+        workspace: &std::path::Path,
+    ) -> (AppState, crate::sessions::Session, HeaderMap) {
+        let store = SessionStore::new(workspace);
+        let mut session = crate::sessions::Session::new("cli", "test-model");
+        session.meta.title = Some("Nginx log rotation".into());
+        session.push(crate::providers::ChatMessage::user("rotate logs?"));
+        store.save(&session).unwrap();
+
+        let state = AppState {
+            provider: Arc::new(MockProvider::default()),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            memory_config: MemoryConfig::default(),
+            webhook_secret: None,
+            pairing: Arc::new(PairingGuard::new(true, &["secret-token".into()])),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            sessions: Arc::new(store),
+            session_search: None,
+            turn_budget: TurnBudget::default(),
+            prompts: Arc::new(PromptLibrary::new("prompts")),
+            workspace_dir: workspace.to_path_buf(),
+            skill_registry: None,
+            composio: None,
+            jobs: None,
+            workflows: None,
+            a2a: None,
+            reload: None,
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret-token"),
+        );
+        (state, session, headers)
+    }
+
+    #[tokio::test]
+    async fn sessions_endpoints_require_bearer_and_return_titles() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (state, session, headers) = sessions_state(tmp.path());
+
+        let denied = handle_sessions_list(State(state.clone()), HeaderMap::new())
+            .await
+            .into_response();
+        assert_eq!(denied.status(), StatusCode::UNAUTHORIZED);
+
+        let listed = handle_sessions_list(State(state.clone()), headers.clone())
+            .await
+            .into_response();
+        assert_eq!(listed.status(), StatusCode::OK);
+        let payload = listed.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["sessions"][0]["title"], "Nginx log rotation");
+
+        let markdown = handle_session_get(
+            State(state.clone()),
+            headers.clone(),
+            Path(session.id().to_string()),
+            Query(SessionExportQuery {
+                format: Some("markdown".into()),
+            }),
+        )
+        .await;
+        assert_eq!(markdown.status(), StatusCode::OK);
+        let payload = markdown.into_body().collect().await.unwrap().to_bytes();
+        assert!(String::from_utf8_lossy(&payload).starts_with("# Nginx log rotation"));
+
+        let found = handle_sessions_search(
+            State(state.clone()),
+            headers.clone(),
+            Query(SessionSearchQuery {
+                q: "ROTATE".into(),
+                limit: None,
+                semantic: false,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(found.status(), StatusCode::OK);
+        let payload = found.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["results"][0]["id"], session.id());
+        assert_eq!(parsed["results"][0]["snippets"][0], "rotate logs?");
+
+        let missing = handle_session_get(
+            State(state),
+            headers,
+            Path("nope".into()),
+            Query(SessionExportQuery { format: None }),
+        )
+        .await;
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn session_branch_and_feedback_endpoints() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (state, session, headers) = sessions_state(tmp.path());
+
+        let branched = handle_session_branch(
+            State(state.clone()),
+            headers.clone(),
+            Path(session.id().to_string()),
+            Ok(Json(SessionBranchBody {
+                index: 0,
+                model: Some("other-model".into()),
+                temperature: None,
+            })),
+        )
+        .await;
+        assert_eq!(branched.status(), StatusCode::OK);
+        let payload = branched.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["response"], "ok");
+        assert_eq!(parsed["session"]["branch_of"]["session_id"], session.id());
+        let branch = state
+            .sessions
+            .load(parsed["session"]["id"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(branch.meta.model, "other-model");
+        assert_eq!(branch.messages.len(), 2);
+        assert_eq!(state.sessions.load(session.id()).unwrap().messages.len(), 1);
+
+        let out_of_range = handle_session_branch(
+            State(state.clone()),
+            headers.clone(),
+            Path(session.id().to_string()),
+            Ok(Json(SessionBranchBody {
+                index: 5,
+                model: None,
+                temperature: None,
+            })),
+        )
+        .await;
+        assert_eq!(out_of_range.status(), StatusCode::BAD_REQUEST);
+
+        let rate = |index| {
+            handle_session_feedback(
+                State(state.clone()),
+                headers.clone(),
+                Path(branch.meta.id.clone()),
+                Ok(Json(SessionFeedbackBody {
+                    message_index: index,
+                    rating: crate::sessions::Rating::Up,
+                    comment: Some("Exactly right".into()),
+                })),
+            )
+        };
+        assert_eq!(rate(1).await.status(), StatusCode::OK);
+        // The user's own message is not an answer
+        assert_eq!(rate(0).await.status(), StatusCode::BAD_REQUEST);
+        let rated = state.sessions.load(&branch.meta.id).unwrap();
+        assert_eq!(rated.meta.rating, Some(crate::sessions::Rating::Up));
+        let stats = handle_stats(State(state.clone()), headers.clone())
+            .await
+            .into_response();
+        let payload = stats.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["feedback"]["up"], 1);
+        assert_eq!(parsed["feedback"]["recent"][0]["comment"], "Exactly right");
+    }
+
     // ══════════════════════════════════════════════════════════
     // WhatsApp Signature Verification Tests (CWE-345 Prevention)
     // ══════════════════════════════════════════════════════════
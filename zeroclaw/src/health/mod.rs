@@ -1,15 +1,19 @@
-use chrono::Utc;
+pub mod selftest;
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::{Mutex, OnceLock};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ComponentHealth {
     pub status: String,
     pub updated_at: String,
     pub last_ok: Option<String>,
+    /// Most recent error, kept after the component recovers
     pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
     pub restart_count: u64,
 }
 
@@ -19,11 +23,84 @@ pub struct HealthSnapshot {
     pub updated_at: String,
     pub uptime_seconds: u64,
     pub components: BTreeMap<String, ComponentHealth>,
+    /// Messages waiting in each work queue
+    pub queues: BTreeMap<String, usize>,
+    /// Circuit breaker state per provider: closed, open or `half_open`
+    pub circuits: BTreeMap<String, String>,
+    /// Agent turns and tool executions in flight
+    pub active_runs: usize,
+    /// How much of the `[autonomy]` budget is used
+    pub autonomy: AutonomyUsage,
+}
+
+/// Live autonomy counters against the `[autonomy]` limits, across agents.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AutonomyUsage {
+    /// Rate-limited actions (medium and high risk) in the last hour
+    pub actions_this_hour: usize,
+    /// None until a security policy is set up from config
+    pub max_actions_per_hour: Option<u32>,
+    /// Estimated model spend since local midnight; only models whose
+    /// `[[model_profiles]]` set prices are counted
+    pub spend_today_cents: f64,
+    pub max_cost_per_day_cents: Option<u32>,
+    /// High-risk commands refused by `block_high_risk_commands` since start
+    pub high_risk_blocked: u64,
+}
+
+/// What [`AutonomyUsage`] is computed from.
+#[derive(Default)]
+struct AutonomyCounters {
+    actions: VecDeque<Instant>,
+    limits: Option<(u32, u32)>,
+    spend_day: Option<NaiveDate>,
+    spend_cents: f64,
+    high_risk_blocked: u64,
+}
+
+const ACTION_WINDOW: Duration = Duration::from_secs(3600);
+
+impl AutonomyCounters {
+    fn expire_actions(&mut self, now: Instant) {
+        while self
+            .actions
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= ACTION_WINDOW)
+        {
+            self.actions.pop_front();
+        }
+    }
+
+    fn add_spend(&mut self, cents: f64, today: NaiveDate) {
+        if self.spend_day != Some(today) {
+            self.spend_day = Some(today);
+            self.spend_cents = 0.0;
+        }
+        self.spend_cents += cents;
+    }
+
+    fn usage(&mut self, now: Instant, today: NaiveDate) -> AutonomyUsage {
+        self.expire_actions(now);
+        AutonomyUsage {
+            actions_this_hour: self.actions.len(),
+            max_actions_per_hour: self.limits.map(|(actions, _)| actions),
+            spend_today_cents: if self.spend_day == Some(today) {
+                self.spend_cents
+            } else {
+                0.0
+            },
+            max_cost_per_day_cents: self.limits.map(|(_, cents)| cents),
+            high_risk_blocked: self.high_risk_blocked,
+        }
+    }
 }
 
 struct HealthRegistry {
     started_at: Instant,
     components: Mutex<BTreeMap<String, ComponentHealth>>,
+    queues: Mutex<BTreeMap<String, usize>>,
+    circuits: Mutex<BTreeMap<String, String>>,
+    autonomy: Mutex<AutonomyCounters>,
 }
 
 static REGISTRY: OnceLock<HealthRegistry> = OnceLock::new();
@@ -32,6 +109,9 @@ fn registry() -> &'static HealthRegistry {
     REGISTRY.get_or_init(|| HealthRegistry {
         started_at: Instant::now(),
         components: Mutex::new(BTreeMap::new()),
+        queues: Mutex::new(BTreeMap::new()),
+        circuits: Mutex::new(BTreeMap::new()),
+        autonomy: Mutex::new(AutonomyCounters::default()),
     })
 }
 
@@ -52,6 +132,7 @@ where
                 updated_at: now.clone(),
                 last_ok: None,
                 last_error: None,
+                last_error_at: None,
                 restart_count: 0,
             });
         update(entry);
@@ -63,7 +144,6 @@ pub fn mark_component_ok(component: &str) {
     upsert_component(component, |entry| {
         entry.status = "ok".into();
         entry.last_ok = Some(now_rfc3339());
-        entry.last_error = None;
     });
 }
 
@@ -73,9 +153,71 @@ pub fn mark_component_error(component: &str, error: impl ToString) {
     upsert_component(component, move |entry| {
         entry.status = "error".into();
         entry.last_error = Some(err);
+        entry.last_error_at = Some(now_rfc3339());
     });
 }
 
+/// Record how many items are waiting in the queue `name`.
+pub fn set_queue_depth(name: &str, depth: usize) {
+    if let Ok(mut queues) = registry().queues.lock() {
+        queues.insert(name.to_string(), depth);
+    }
+}
+
+/// Record the circuit breaker state of `provider`.
+pub fn set_circuit_state(provider: &str, state: &str) {
+    if let Ok(mut circuits) = registry().circuits.lock() {
+        circuits.insert(provider.to_string(), state.to_string());
+    }
+}
+
+fn update_autonomy(update: impl FnOnce(&mut AutonomyCounters)) {
+    if let Ok(mut counters) = registry().autonomy.lock() {
+        update(&mut counters);
+    }
+}
+
+/// Record the `[autonomy]` limits the usage counters are reported against.
+/// Called by the daemon, the gateway and agents when they start, and on
+/// config reloads.
+pub fn set_autonomy_limits(autonomy: &crate::config::AutonomyConfig) {
+    let limits = (autonomy.max_actions_per_hour, autonomy.max_cost_per_day_cents);
+    update_autonomy(|c| c.limits = Some(limits));
+}
+
+/// Count one rate-limited action.
+pub fn record_autonomy_action() {
+    update_autonomy(|c| {
+        let now = Instant::now();
+        c.expire_actions(now);
+        c.actions.push_back(now);
+    });
+}
+
+/// Add the estimated cost of a model call to today's spend.
+pub fn record_spend(cost_usd: f64) {
+    update_autonomy(|c| c.add_spend(cost_usd * 100.0, Local::now().date_naive()));
+}
+
+/// Count a high-risk command refused by policy.
+pub fn record_high_risk_blocked() {
+    update_autonomy(|c| c.high_risk_blocked = c.high_risk_blocked.saturating_add(1));
+}
+
+/// True when `component` has not been updated within `max_age` (or ever).
+pub fn is_stale(component: &str, max_age: Duration) -> bool {
+    let updated_at = registry()
+        .components
+        .lock()
+        .ok()
+        .and_then(|map| map.get(component).map(|c| c.updated_at.clone()));
+    let Some(updated_at) = updated_at.and_then(|t| DateTime::parse_from_rfc3339(&t).ok()) else {
+        return true;
+    };
+    let age = Utc::now().signed_duration_since(updated_at);
+    age.to_std().is_ok_and(|age| age > max_age)
+}
+
 pub fn bump_component_restart(component: &str) {
     upsert_component(component, |entry| {
         entry.restart_count = entry.restart_count.saturating_add(1);
@@ -88,11 +230,29 @@ pub fn snapshot() -> HealthSnapshot {
         .lock()
         .map_or_else(|_| BTreeMap::new(), |map| map.clone());
 
+    let queues = registry()
+        .queues
+        .lock()
+        .map_or_else(|_| BTreeMap::new(), |map| map.clone());
+
+    let circuits = registry()
+        .circuits
+        .lock()
+        .map_or_else(|_| BTreeMap::new(), |map| map.clone());
+
     HealthSnapshot {
         pid: std::process::id(),
         updated_at: now_rfc3339(),
         uptime_seconds: registry().started_at.elapsed().as_secs(),
         components,
+        queues,
+        circuits,
+        active_runs: crate::daemon::shutdown::active_runs(),
+        autonomy: registry()
+            .autonomy
+            .lock()
+            .map(|mut c| c.usage(Instant::now(), Local::now().date_naive()))
+            .unwrap_or_default(),
     }
 }
 
@@ -104,3 +264,39 @@ pub fn snapshot_json() -> serde_json::Value {
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn autonomy_counters_roll_over_by_hour_and_day() {
+        let mut counters = AutonomyCounters::default();
+        let start = Instant::now();
+        let today = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        counters
+            .actions
+            .extend([start, start + Duration::from_secs(1800)]);
+        counters.add_spend(12.5, today);
+        counters.add_spend(2.5, today);
+        counters.high_risk_blocked = 1;
+
+        let usage = counters.usage(start + Duration::from_secs(1800), today);
+        assert_eq!(usage.actions_this_hour, 2);
+        assert!((usage.spend_today_cents - 15.0).abs() < 1e-9);
+        assert_eq!(usage.max_actions_per_hour, None);
+
+        counters.limits = Some((20, 500));
+        let tomorrow = today.succ_opt().unwrap();
+        let usage = counters.usage(start + Duration::from_secs(3600), tomorrow);
+        assert_eq!(usage.actions_this_hour, 1);
+        assert!(usage.spend_today_cents.abs() < 1e-9);
+        assert_eq!(
+            (usage.max_actions_per_hour, usage.max_cost_per_day_cents),
+            (Some(20), Some(500))
+        );
+        counters.add_spend(1.0, tomorrow);
+        assert!((counters.spend_cents - 1.0).abs() < 1e-9);
+        assert_eq!(usage.high_risk_blocked, 1);
+    }
+}
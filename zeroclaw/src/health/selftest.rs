@@ -0,0 +1,190 @@
+//! On-demand self-test behind `POST /health/selftest`.
+//!
+//! The canary turn drives the real agent loop and tool dispatch against a
+//! scripted provider, so it needs no network or API key. The provider and
+//! memory checks hit the configured backends and record their result in
+//! the health registry as the `provider` and `memory` components.
+
+use crate::agent::loop_::agent_turn;
+use crate::memory::Memory;
+use crate::observability::NoopObserver;
+use crate::providers::{ChatMessage, Provider};
+use crate::tools::{CalculatorTool, Tool};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const PROVIDER_PING_TIMEOUT_SECS: u64 = 10;
+
+static PROVIDER_PING_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub ok: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+fn finish(name: &'static str, started: Instant, result: Result<String>) -> CheckResult {
+    let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    match result {
+        Ok(detail) => CheckResult {
+            name,
+            ok: true,
+            detail,
+            duration_ms,
+        },
+        Err(e) => CheckResult {
+            name,
+            ok: false,
+            detail: format!("{e:#}"),
+            duration_ms,
+        },
+    }
+}
+
+/// Run every check.
+pub async fn run(provider: &dyn Provider, mem: &dyn Memory) -> SelfTestReport {
+    let checks = vec![
+        canary_turn().await,
+        check_provider(provider).await,
+        check_memory(mem).await,
+    ];
+    SelfTestReport {
+        ok: checks.iter().all(|c| c.ok),
+        checks,
+    }
+}
+
+/// Answers with a calculator call, then with the tool's result.
+struct CanaryProvider;
+
+#[async_trait]
+impl Provider for CanaryProvider {
+    async fn chat_with_system(
+        &self,
+        _system_prompt: Option<&str>,
+        message: &str,
+        _model: &str,
+        _temperature: f64,
+    ) -> Result<String> {
+        Ok(match message.split_once("<tool_response name=\"calculator\">") {
+            Some((_, rest)) => format!(
+                "Canary result: {}",
+                rest.lines().nth(1).unwrap_or_default().trim()
+            ),
+            None => "<tool_call>\n{\"name\": \"calculator\", \"arguments\": {\"expression\": \"6 * 7\"}}\n</tool_call>".into(),
+        })
+    }
+}
+
+/// One full agent turn with a tool call, against [`CanaryProvider`].
+pub async fn canary_turn() -> CheckResult {
+    let started = Instant::now();
+    let tools: Vec<Box<dyn Tool>> = vec![Box::new(CalculatorTool::new())];
+    let mut history = vec![
+        ChatMessage::system("You are a health check."),
+        ChatMessage::user("What is 6 * 7?"),
+    ];
+    let result = agent_turn(
+        &CanaryProvider,
+        &mut history,
+        &tools,
+        &NoopObserver,
+        "canary",
+        0.0,
+    )
+    .await
+    .and_then(|answer| {
+        if answer.contains("42") {
+            Ok(answer)
+        } else {
+            anyhow::bail!("Unexpected canary answer: {answer}")
+        }
+    });
+    finish("canary_turn", started, result)
+}
+
+/// Cheap reachability ping (connection warmup) of the configured provider.
+pub async fn check_provider(provider: &dyn Provider) -> CheckResult {
+    let started = Instant::now();
+    let result = tokio::time::timeout(
+        Duration::from_secs(PROVIDER_PING_TIMEOUT_SECS),
+        provider.warmup(),
+    )
+    .await
+    .unwrap_or_else(|_| {
+        Err(anyhow::anyhow!(
+            "No response within {PROVIDER_PING_TIMEOUT_SECS}s"
+        ))
+    })
+    .map(|()| "reachable".to_string());
+    let check = finish("provider", started, result);
+    record(&check);
+    check
+}
+
+/// Re-ping the provider in the background when the recorded result is
+/// older than `max_age`, so `/health` never waits on the network.
+pub fn refresh_provider(provider: Arc<dyn Provider>, max_age: Duration) {
+    if !super::is_stale("provider", max_age) || PROVIDER_PING_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(async move {
+        check_provider(provider.as_ref()).await;
+        PROVIDER_PING_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+pub async fn check_memory(mem: &dyn Memory) -> CheckResult {
+    let started = Instant::now();
+    let result = if mem.health_check().await {
+        Ok(format!("{} backend healthy", mem.name()))
+    } else {
+        Err(anyhow::anyhow!("{} backend unhealthy", mem.name()))
+    };
+    let check = finish("memory", started, result);
+    record(&check);
+    check
+}
+
+fn record(check: &CheckResult) {
+    if check.ok {
+        super::mark_component_ok(check.name);
+    } else {
+        super::mark_component_error(check.name, &check.detail);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn canary_turn_runs_a_tool_call() {
+        let check = canary_turn().await;
+        assert!(check.ok, "{}", check.detail);
+        assert_eq!(check.detail, "Canary result: 6 * 7 = 42");
+    }
+
+    #[test]
+    fn failed_checks_keep_their_error() {
+        let check = finish(
+            "provider",
+            Instant::now(),
+            Err(anyhow::anyhow!("connection refused")),
+        );
+        assert!(!check.ok);
+        assert_eq!(check.detail, "connection refused");
+    }
+}
@@ -0,0 +1,363 @@
+//! Lifecycle hooks.
+//!
+//! `[hooks]` maps agent events to shell commands or webhooks, so an
+//! organisation can add its own policy without patching the agent:
+//! `pre_tool_use` and `post_tool_use` run around every tool call, and
+//! `turn_start` and `turn_end` around every turn run inside [`scope`].
+//!
+//! Each hook gets the event as a JSON object, e.g.
+//! `{"event": "pre_tool_use", "tool": "shell", "arguments": {...}}` —
+//! on stdin for a command, as the POST body for a webhook. Pre hooks may
+//! answer with JSON on stdout or in the response body:
+//! `{"decision": "deny", "reason": "..."}` stops the action,
+//! `{"arguments": {...}}` (tools) or `{"message": "..."}` (turns) rewrites
+//! it, and an empty answer lets it through. Hooks run in order, each seeing
+//! the previous one's rewrite.
+//!
+//! Pre hooks fail closed: one that exits non-zero, times out or answers
+//! unreadably denies, and every denial goes to the audit log. Post hooks
+//! only observe; their failures are logged and otherwise ignored.
+
+use crate::config::{Config, HookConfig, HooksConfig};
+use crate::observability::audit;
+use anyhow::{anyhow, bail, Context};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+tokio::task_local! {
+    static HOOKS: Option<Arc<Hooks>>;
+}
+
+/// Run `turn` with the hooks `config` sets up.
+pub fn scope<F: Future>(config: &Config, turn: F) -> impl Future<Output = F::Output> {
+    scope_with(Hooks::from_config(config), turn)
+}
+
+/// Run `turn` with `hooks`, for callers that set them up once for many turns.
+pub fn scope_with<F: Future>(
+    hooks: Option<Arc<Hooks>>,
+    turn: F,
+) -> impl Future<Output = F::Output> {
+    // Boxed, so wrapping a turn does not grow the caller's future
+    HOOKS.scope(hooks, Box::pin(turn))
+}
+
+fn current() -> Option<Arc<Hooks>> {
+    HOOKS.try_with(Clone::clone).ok().flatten()
+}
+
+/// Run the `pre_tool_use` hooks for a call to `tool`. Returns the arguments
+/// to run it with, or why it must not run.
+pub async fn pre_tool_use(tool: &str, mut arguments: Value) -> Result<Value, String> {
+    let Some(hooks) = current() else {
+        return Ok(arguments);
+    };
+    for hook in for_tool(&hooks.config.pre_tool_use, tool) {
+        let event = json!({"event": "pre_tool_use", "tool": tool, "arguments": arguments});
+        if let Some(rewritten) = hooks.decide(hook, &event).await?.arguments {
+            arguments = rewritten;
+        }
+    }
+    Ok(arguments)
+}
+
+/// Run the `post_tool_use` hooks for a call to `tool` that has finished.
+pub async fn post_tool_use(tool: &str, arguments: &Value, success: bool, output: &str) {
+    let Some(hooks) = current() else {
+        return;
+    };
+    for hook in for_tool(&hooks.config.post_tool_use, tool) {
+        let event = json!({
+            "event": "post_tool_use",
+            "tool": tool,
+            "arguments": arguments,
+            "success": success,
+            "output": output,
+        });
+        hooks.notify(hook, &event).await;
+    }
+}
+
+/// Run the `turn_start` hooks for a turn answering `message`. Returns the
+/// message rewritten by a hook, if one did, or why the turn must not run.
+pub async fn turn_start(message: &str) -> Result<Option<String>, String> {
+    let Some(hooks) = current() else {
+        return Ok(None);
+    };
+    let mut rewritten = None;
+    for hook in &hooks.config.turn_start {
+        let current = rewritten.as_deref().unwrap_or(message);
+        let event = json!({"event": "turn_start", "message": current});
+        if let Some(message) = hooks.decide(hook, &event).await?.message {
+            rewritten = Some(message);
+        }
+    }
+    Ok(rewritten)
+}
+
+/// Run the `turn_end` hooks for a turn that answered `message` with `reply`.
+pub async fn turn_end(message: &str, reply: &str) {
+    let Some(hooks) = current() else {
+        return;
+    };
+    for hook in &hooks.config.turn_end {
+        let event = json!({"event": "turn_end", "message": message, "reply": reply});
+        hooks.notify(hook, &event).await;
+    }
+}
+
+fn for_tool<'a>(hooks: &'a [HookConfig], tool: &'a str) -> impl Iterator<Item = &'a HookConfig> {
+    hooks
+        .iter()
+        .filter(move |hook| hook.tools.is_empty() || hook.tools.iter().any(|t| t == tool))
+}
+
+/// What a pre hook answered.
+#[derive(Debug, Default, Deserialize)]
+struct Answer {
+    #[serde(default)]
+    decision: Option<String>,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    arguments: Option<Value>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// The hooks of a config.
+pub struct Hooks {
+    config: HooksConfig,
+    workspace_dir: PathBuf,
+}
+
+impl Hooks {
+    /// The hooks `config` sets up, or None when it has none.
+    pub fn from_config(config: &Config) -> Option<Arc<Self>> {
+        (!config.hooks.is_empty()).then(|| {
+            Arc::new(Self {
+                config: config.hooks.clone(),
+                workspace_dir: config.workspace_dir.clone(),
+            })
+        })
+    }
+
+    /// Run a pre hook. `Err` holds why the action must not go ahead.
+    async fn decide(&self, hook: &HookConfig, event: &Value) -> Result<Answer, String> {
+        let answer = self.run(hook, event).await.and_then(|output| {
+            if output.trim().is_empty() {
+                return Ok(Answer::default());
+            }
+            serde_json::from_str::<Answer>(&output).context("the hook answered with invalid JSON")
+        });
+        let denied = match answer {
+            Ok(answer) if answer.decision.as_deref().is_some_and(is_denial) => answer
+                .reason
+                .filter(|reason| !reason.trim().is_empty())
+                .unwrap_or_else(|| "no reason given".into()),
+            Ok(answer) => return Ok(answer),
+            Err(e) => format!("the hook failed: {e:#}"),
+        };
+        tracing::info!(hook = %label(hook), event = %event["event"], "Hook denied: {denied}");
+        audit::record(
+            &self.workspace_dir,
+            "hook",
+            json!({"hook": label(hook), "event": event["event"], "tool": event["tool"], "reason": denied}),
+        );
+        Err(denied)
+    }
+
+    /// Run a post hook, logging a failure.
+    async fn notify(&self, hook: &HookConfig, event: &Value) {
+        if let Err(e) = self.run(hook, event).await {
+            tracing::warn!(hook = %label(hook), event = %event["event"], "Hook failed: {e:#}");
+        }
+    }
+
+    /// Run `hook` on `event` and return its answer.
+    async fn run(&self, hook: &HookConfig, event: &Value) -> anyhow::Result<String> {
+        let run = async {
+            match (&hook.command, &hook.url) {
+                (Some(command), _) => self.run_command(command, event).await,
+                (None, Some(url)) => post(url, hook, event).await,
+                (None, None) => bail!("the hook sets neither command nor url"),
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(hook.timeout_secs), run)
+            .await
+            .map_err(|_| anyhow!("timed out after {}s", hook.timeout_secs))?
+    }
+
+    async fn run_command(&self, command: &str, event: &Value) -> anyhow::Result<String> {
+        let mut process = tokio::process::Command::new("sh");
+        process
+            .arg("-c")
+            .arg(command)
+            .env(
+                "ZEROCLAW_HOOK_EVENT",
+                event["event"].as_str().unwrap_or_default(),
+            )
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if self.workspace_dir.is_dir() {
+            process.current_dir(&self.workspace_dir);
+        }
+        let mut child = process.spawn().context("could not start the command")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that ignores its input may exit before reading it
+            let _ = stdin.write_all(event.to_string().as_bytes()).await;
+        }
+        let output = child.wait_with_output().await?;
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let detail = if stderr.trim().is_empty() {
+                &stdout
+            } else {
+                &*stderr
+            };
+            bail!(
+                "{} exited with {}: {}",
+                command,
+                output.status,
+                detail.trim()
+            );
+        }
+        Ok(stdout)
+    }
+}
+
+async fn post(url: &str, hook: &HookConfig, event: &Value) -> anyhow::Result<String> {
+    let mut request = reqwest::Client::new().post(url).json(event);
+    for (name, value) in &hook.headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        bail!("{url} answered {status}: {}", body.trim());
+    }
+    Ok(body)
+}
+
+fn is_denial(decision: &str) -> bool {
+    matches!(
+        decision.trim().to_lowercase().as_str(),
+        "deny" | "block" | "reject"
+    )
+}
+
+fn label(hook: &HookConfig) -> &str {
+    hook.command
+        .as_deref()
+        .or(hook.url.as_deref())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn command(command: &str) -> HookConfig {
+        HookConfig {
+            command: Some(command.into()),
+            url: None,
+            headers: BTreeMap::new(),
+            tools: Vec::new(),
+            timeout_secs: 5,
+        }
+    }
+
+    fn config(workspace: &std::path::Path, hooks: HooksConfig) -> Config {
+        Config {
+            workspace_dir: workspace.to_path_buf(),
+            hooks,
+            ..Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn pre_tool_hooks_deny_or_rewrite_in_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let hooks = HooksConfig {
+            pre_tool_use: vec![
+                HookConfig {
+                    tools: vec!["shell".into()],
+                    ..command(r#"echo '{"decision": "deny", "reason": "no shell here"}'"#)
+                },
+                command(r#"echo '{"arguments": {"path": "safe.txt"}}'"#),
+            ],
+            ..HooksConfig::default()
+        };
+        let config = config(tmp.path(), hooks);
+
+        scope(&config, async {
+            let denied = pre_tool_use("shell", json!({"command": "ls"})).await;
+            assert_eq!(denied.unwrap_err(), "no shell here");
+
+            let rewritten = pre_tool_use("file_read", json!({"path": "secret.txt"})).await;
+            assert_eq!(rewritten.unwrap(), json!({"path": "safe.txt"}));
+        })
+        .await;
+
+        let log = std::fs::read_to_string(tmp.path().join(audit::AUDIT_LOG)).unwrap();
+        assert!(log.contains("no shell here"));
+        // Outside a scope nothing runs
+        let args = pre_tool_use("shell", json!({"command": "ls"})).await;
+        assert_eq!(args.unwrap(), json!({"command": "ls"}));
+    }
+
+    #[tokio::test]
+    async fn failing_pre_hooks_deny_and_post_hooks_observe() {
+        let tmp = tempfile::tempdir().unwrap();
+        let hooks = HooksConfig {
+            turn_start: vec![command("echo 'policy service down' >&2; exit 3")],
+            post_tool_use: vec![command("cat > post.json")],
+            turn_end: vec![command("exit 1")],
+            ..HooksConfig::default()
+        };
+        let config = config(tmp.path(), hooks);
+
+        scope(&config, async {
+            let denied = turn_start("hello").await.unwrap_err();
+            assert!(denied.contains("policy service down"), "{denied}");
+
+            post_tool_use("calculator", &json!({"expression": "6*7"}), true, "42").await;
+            // A failing post hook is only logged
+            turn_end("hello", "hi").await;
+        })
+        .await;
+
+        let event: Value =
+            serde_json::from_str(&std::fs::read_to_string(tmp.path().join("post.json")).unwrap())
+                .unwrap();
+        assert_eq!(event["event"], "post_tool_use");
+        assert_eq!(event["output"], "42");
+        assert_eq!(event["success"], true);
+    }
+
+    #[tokio::test]
+    async fn turn_start_hooks_rewrite_the_message() {
+        let tmp = tempfile::tempdir().unwrap();
+        let hooks = HooksConfig {
+            turn_start: vec![
+                command(r#"sed 's/.*"message":"\(.*\)"}/{"message": "[internal] \1"}/'"#),
+                command("true"),
+            ],
+            ..HooksConfig::default()
+        };
+        let config = config(tmp.path(), hooks);
+        let rewritten = scope(&config, turn_start("deploy it")).await;
+        assert_eq!(rewritten.unwrap().as_deref(), Some("[internal] deploy it"));
+    }
+}
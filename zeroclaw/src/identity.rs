@@ -4,7 +4,8 @@
 //! portable AI identity. This module handles loading and converting AIEOS v1.1
 //! JSON to ZeroClaw's system prompt format.
 
-use crate::config::IdentityConfig;
+use crate::config::{IdentityConfig, PersonaConfig};
+use crate::security::{PathAccess, SecurityPolicy};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -472,6 +473,216 @@ pub fn is_aieos_configured(config: &IdentityConfig) -> bool {
     config.format == "aieos" && (config.aieos_path.is_some() || config.aieos_inline.is_some())
 }
 
+// ── Persona ─────────────────────────────────────────────────────
+
+/// Render `[identity.persona]` as a `## Persona` prompt section, or an empty
+/// string when nothing is set.
+pub fn persona_prompt(persona: &PersonaConfig) -> String {
+    use std::fmt::Write as _;
+    let language = persona.language.as_ref().map(|l| {
+        if l.trim().eq_ignore_ascii_case(crate::agent::language::AUTO) {
+            "reply in the language of the user's message".to_string()
+        } else {
+            l.clone()
+        }
+    });
+    let fields = [
+        ("Name", &persona.name),
+        ("Pronouns", &persona.pronouns),
+        ("Tone", &persona.tone),
+        ("Verbosity", &persona.verbosity),
+        ("Language", &language),
+        ("Formatting", &persona.formatting),
+        ("When declining", &persona.refusal_style),
+    ];
+    let mut section = String::new();
+    for (label, value) in fields {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            let _ = writeln!(section, "- **{label}:** {value}");
+        }
+    }
+    if section.is_empty() {
+        return section;
+    }
+    format!(
+        "## Persona\n\nPresent yourself this way; it takes precedence over the \
+         style notes in the files above.\n\n{section}\n"
+    )
+}
+
+// ── Standing instruction files ──────────────────────────────────
+
+/// Instruction files read when `[identity.instruction_files]` is empty.
+pub const DEFAULT_INSTRUCTION_FILES: [&str; 2] = ["AGENTS.md", "SOUL.md"];
+
+/// ZeroClaw-specific instructions kept in the workspace, read after the other
+/// instruction files whenever it exists. It comes last so it wins where it
+/// conflicts with them, e.g. with an `AGENTS.md` shared with other tools.
+pub const WORKSPACE_INSTRUCTION_FILE: &str = ".zeroclaw/instructions.md";
+
+/// Rough characters-per-token ratio used to turn a token budget into a cut-off.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// A standing instruction document and how much of the prompt it may use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionFile {
+    /// Path as configured, used as the prompt heading
+    pub name: String,
+    pub path: PathBuf,
+    pub max_tokens: usize,
+    /// Skipped silently when missing, instead of marked as not found
+    pub optional: bool,
+}
+
+/// Resolve the instruction files for a workspace: the configured table, or
+/// `AGENTS.md` and `SOUL.md` when none are configured, followed by
+/// [`WORKSPACE_INSTRUCTION_FILE`].
+pub fn instruction_files(config: &IdentityConfig, workspace_dir: &Path) -> Vec<InstructionFile> {
+    let resolve = |name: &str, max_tokens: usize| {
+        let path = if Path::new(name).is_absolute() {
+            PathBuf::from(name)
+        } else {
+            workspace_dir.join(name)
+        };
+        InstructionFile {
+            name: name.to_string(),
+            path,
+            max_tokens: if max_tokens == 0 {
+                config.instruction_max_tokens
+            } else {
+                max_tokens
+            },
+            optional: false,
+        }
+    };
+
+    let mut files: Vec<InstructionFile> = if config.instruction_files.is_empty() {
+        DEFAULT_INSTRUCTION_FILES
+            .iter()
+            .map(|name| resolve(name, 0))
+            .collect()
+    } else {
+        config
+            .instruction_files
+            .iter()
+            .filter(|(name, _)| name.as_str() != WORKSPACE_INSTRUCTION_FILE)
+            .map(|(name, max_tokens)| resolve(name, *max_tokens))
+            .collect()
+    };
+    let budget = config
+        .instruction_files
+        .get(WORKSPACE_INSTRUCTION_FILE)
+        .copied()
+        .unwrap_or(0);
+    files.push(InstructionFile {
+        optional: true,
+        ..resolve(WORKSPACE_INSTRUCTION_FILE, budget)
+    });
+    files
+}
+
+/// `config` without the instruction files and AIEOS file that `security`
+/// does not let the agent read. Configured paths go through `workspace_only`,
+/// `forbidden_paths` and reference roots like a `file_read` of them would, so
+/// a config cannot pull host files into the prompt.
+pub fn readable_by(mut config: IdentityConfig, security: &SecurityPolicy) -> IdentityConfig {
+    let readable = |name: &str| {
+        let allowed = security.is_path_allowed_for(name, PathAccess::Read)
+            && security
+                .workspace_dir
+                .join(name)
+                .canonicalize()
+                .map_or(true, |resolved| {
+                    security.is_resolved_path_allowed_for(&resolved, PathAccess::Read)
+                });
+        if !allowed {
+            tracing::warn!("Skipping identity file {name}: not readable under the security policy");
+        }
+        allowed
+    };
+    config.instruction_files.retain(|name, _| readable(name));
+    if config.aieos_path.as_deref().is_some_and(|path| !readable(path)) {
+        config.aieos_path = None;
+    }
+    config
+}
+
+/// Render an instruction file as a `### name` prompt section, cut off at its
+/// token budget. Blank files render as an empty string.
+pub fn render_instruction_file(file: &InstructionFile) -> std::io::Result<String> {
+    use std::fmt::Write as _;
+    let content = std::fs::read_to_string(&file.path)?;
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return Ok(String::new());
+    }
+
+    let max_chars = file.max_tokens.saturating_mul(CHARS_PER_TOKEN);
+    let mut section = format!("### {}\n\n", file.name);
+    if file.name == WORKSPACE_INSTRUCTION_FILE {
+        section.push_str("_Takes precedence over the instructions above where they conflict._\n\n");
+    }
+    if let Some((idx, _)) = trimmed.char_indices().nth(max_chars) {
+        section.push_str(&trimmed[..idx]);
+        let _ = write!(
+            section,
+            "\n\n[... truncated at ~{} tokens — use `read` for full file]\n\n",
+            file.max_tokens
+        );
+    } else {
+        section.push_str(trimmed);
+        section.push_str("\n\n");
+    }
+    Ok(section)
+}
+
+/// Notices edits to instruction files between turns, so long-lived sessions
+/// can rebuild their system prompt instead of running on stale instructions.
+pub struct InstructionWatcher {
+    files: Vec<(PathBuf, Option<u64>)>,
+}
+
+impl InstructionWatcher {
+    pub fn new(files: &[InstructionFile]) -> Self {
+        Self {
+            files: files
+                .iter()
+                .map(|f| (f.path.clone(), fingerprint(&f.path)))
+                .collect(),
+        }
+    }
+
+    /// Watch the instruction files of `config` that `security` lets the
+    /// agent read.
+    pub fn from_config(config: &IdentityConfig, security: &SecurityPolicy) -> Self {
+        let config = readable_by(config.clone(), security);
+        Self::new(&instruction_files(&config, &security.workspace_dir))
+    }
+
+    /// True when any file was created, edited or removed since the last call.
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, seen) in &mut self.files {
+            let current = fingerprint(path);
+            if current != *seen {
+                *seen = current;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Content hash of a file, or `None` if it can't be read. Hashing instead of
+/// comparing mtimes catches edits within the filesystem's timestamp precision.
+fn fingerprint(path: &Path) -> Option<u64> {
+    use std::hash::{Hash, Hasher};
+    let bytes = std::fs::read(path).ok()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -730,6 +941,7 @@ mod tests {
             format: "aieos".into(),
             aieos_path: Some("identity.json".into()),
             aieos_inline: None,
+            ..IdentityConfig::default()
         };
         assert!(is_aieos_configured(&config));
     }
@@ -740,6 +952,7 @@ mod tests {
             format: "aieos".into(),
             aieos_path: None,
             aieos_inline: Some("{\"identity\":{}}".into()),
+            ..IdentityConfig::default()
         };
         assert!(is_aieos_configured(&config));
     }
@@ -750,6 +963,7 @@ mod tests {
             format: "openclaw".into(),
             aieos_path: Some("identity.json".into()),
             aieos_inline: None,
+            ..IdentityConfig::default()
         };
         assert!(!is_aieos_configured(&config));
     }
@@ -760,6 +974,7 @@ mod tests {
             format: "aieos".into(),
             aieos_path: None,
             aieos_inline: None,
+            ..IdentityConfig::default()
         };
         assert!(!is_aieos_configured(&config));
     }
@@ -780,4 +995,129 @@ mod tests {
         assert!(identity.identity.is_none());
         assert!(identity.psychology.is_none());
     }
+
+    #[test]
+    fn instruction_files_default_to_agents_and_soul() {
+        let ws = Path::new("/ws");
+        let files = instruction_files(&IdentityConfig::default(), ws);
+        let names: Vec<_> = files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["AGENTS.md", "SOUL.md", WORKSPACE_INSTRUCTION_FILE]);
+        assert_eq!(files[0].path, ws.join("AGENTS.md"));
+        assert_eq!(files[0].max_tokens, 5_000);
+        assert!(!files[0].optional);
+        assert_eq!(files[2].path, ws.join(".zeroclaw/instructions.md"));
+        assert!(files[2].optional);
+    }
+
+    #[test]
+    fn instruction_files_use_configured_paths_and_budgets() {
+        let mut config = IdentityConfig {
+            instruction_max_tokens: 800,
+            ..IdentityConfig::default()
+        };
+        config.instruction_files.insert("docs/STYLE.md".into(), 0);
+        config
+            .instruction_files
+            .insert("/etc/agent/RULES.md".into(), 200);
+
+        let files = instruction_files(&config, Path::new("/ws"));
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, PathBuf::from("/etc/agent/RULES.md"));
+        assert_eq!(files[0].max_tokens, 200);
+        assert_eq!(files[1].path, PathBuf::from("/ws/docs/STYLE.md"));
+        assert_eq!(files[1].max_tokens, 800);
+        // The workspace file is always read last, with its configured budget
+        config
+            .instruction_files
+            .insert(WORKSPACE_INSTRUCTION_FILE.into(), 300);
+        let files = instruction_files(&config, Path::new("/ws"));
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[2].name, WORKSPACE_INSTRUCTION_FILE);
+        assert_eq!(files[2].max_tokens, 300);
+    }
+
+    #[test]
+    fn readable_by_drops_paths_the_policy_forbids() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("STYLE.md"), "Be terse.").unwrap();
+        let mut config = IdentityConfig {
+            format: "aieos".into(),
+            aieos_path: Some("/etc/passwd".into()),
+            ..IdentityConfig::default()
+        };
+        config.instruction_files.insert("STYLE.md".into(), 0);
+        config.instruction_files.insert("/etc/hostname".into(), 0);
+        config.instruction_files.insert("keys/id_rsa".into(), 0);
+        let security = SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            workspace_only: true,
+            forbidden_paths: vec!["**/id_rsa*".into()],
+            ..SecurityPolicy::default()
+        };
+
+        let config = readable_by(config, &security);
+        let names: Vec<_> = config.instruction_files.keys().map(String::as_str).collect();
+        assert_eq!(names, ["STYLE.md"]);
+        assert!(config.aieos_path.is_none());
+
+        #[cfg(unix)]
+        {
+            let outside = tempfile::TempDir::new().unwrap();
+            std::fs::write(outside.path().join("notes.md"), "private").unwrap();
+            std::os::unix::fs::symlink(outside.path().join("notes.md"), tmp.path().join("LINK.md"))
+                .unwrap();
+            let mut linked = IdentityConfig::default();
+            linked.instruction_files.insert("LINK.md".into(), 0);
+            assert!(readable_by(linked, &security).instruction_files.is_empty());
+        }
+    }
+
+    #[test]
+    fn render_instruction_file_truncates_at_token_budget() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("AGENTS.md");
+        std::fs::write(&path, format!("{}tail", "x".repeat(40))).unwrap();
+        let mut file = InstructionFile {
+            name: "AGENTS.md".into(),
+            path,
+            max_tokens: 10,
+            optional: false,
+        };
+
+        let section = render_instruction_file(&file).unwrap();
+        assert!(section.starts_with(&format!("### AGENTS.md\n\n{}\n", "x".repeat(40))));
+        assert!(section.contains("truncated at ~10 tokens"));
+        assert!(!section.contains("tail"));
+
+        file.max_tokens = 11;
+        let section = render_instruction_file(&file).unwrap();
+        assert!(section.contains("tail"));
+        assert!(!section.contains("truncated"));
+
+        file.path = tmp.path().join("missing.md");
+        assert!(render_instruction_file(&file).is_err());
+    }
+
+    #[test]
+    fn instruction_watcher_detects_edits_and_removal() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = IdentityConfig::default();
+        let security = SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        };
+        let mut watcher = InstructionWatcher::from_config(&config, &security);
+        assert!(!watcher.changed());
+
+        std::fs::write(tmp.path().join("SOUL.md"), "Be terse.").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        // Same length, different content
+        std::fs::write(tmp.path().join("SOUL.md"), "Be brief.").unwrap();
+        assert!(watcher.changed());
+
+        std::fs::remove_file(tmp.path().join("SOUL.md")).unwrap();
+        assert!(watcher.changed());
+    }
 }
@@ -1,3 +1,4 @@
+pub mod oauth;
 pub mod registry;
 
 use crate::config::Config;
@@ -67,12 +68,54 @@ pub struct IntegrationEntry {
 }
 
 /// Handle the `integrations` CLI command
-pub fn handle_command(command: crate::IntegrationCommands, config: &Config) -> Result<()> {
+pub async fn handle_command(command: crate::IntegrationCommands, config: &Config) -> Result<()> {
     match command {
         crate::IntegrationCommands::Info { name } => show_integration_info(config, &name),
+        crate::IntegrationCommands::Login { name } => login(config, &name.to_lowercase()).await,
+        crate::IntegrationCommands::Logout { name } => {
+            let name = name.to_lowercase();
+            if oauth::remove_token(config, &name)? {
+                println!("Signed out of {name}");
+            } else {
+                println!("Not signed in to {name}");
+            }
+            Ok(())
+        }
+        crate::IntegrationCommands::Connect { app } => connect_composio(config, &app).await,
     }
 }
 
+fn show_sign_in_url(url: &str) {
+    println!("Open this URL in your browser to continue:");
+    println!();
+    println!("  {url}");
+    println!();
+    println!("Waiting for the browser to come back (Ctrl-C to cancel)...");
+}
+
+async fn login(config: &Config, name: &str) -> Result<()> {
+    let summary = oauth::login(config, name, show_sign_in_url, oauth::DEFAULT_TIMEOUT).await?;
+    println!("✅ Signed in to {name}");
+    if let Some(scope) = summary.scope.filter(|s| !s.is_empty()) {
+        println!("   Scopes: {scope}");
+    }
+    Ok(())
+}
+
+async fn connect_composio(config: &Config, app: &str) -> Result<()> {
+    let Some(composio) = crate::tools::ComposioAccounts::from_config(config) else {
+        anyhow::bail!("Composio is not enabled; set [composio] enabled and api_key first");
+    };
+    let connection = composio
+        .connect_and_wait(app, show_sign_in_url, oauth::DEFAULT_TIMEOUT)
+        .await?;
+    match connection {
+        Some(id) => println!("✅ Connected {app} (connection {id})"),
+        None => println!("✅ Connected {app}"),
+    }
+    Ok(())
+}
+
 fn show_integration_info(config: &Config, name: &str) -> Result<()> {
     let entries = registry::all_integrations();
     let name_lower = name.to_lowercase();
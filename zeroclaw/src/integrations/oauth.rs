@@ -0,0 +1,617 @@
+//! Sign-in to tool integrations through a local OAuth callback.
+//!
+//! Instead of pasting tokens into the config, `zeroclaw integrations login
+//! <name>` (or `zc_oauth_login` from C) starts a one-shot HTTP server on
+//! `127.0.0.1`, opens the provider's consent page with that server as the
+//! redirect URI, and waits for the browser to come back with a code. The
+//! code is exchanged for tokens (with PKCE), which are encrypted with the
+//! secret store into `oauth_tokens.json` next to the config file.
+//!
+//! Composio runs its own OAuth, so [`crate::tools::ComposioAccounts`] only
+//! uses the [`CallbackServer`] to learn when the user has finished.
+
+use crate::config::{Config, OAuthClientConfig};
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Token file, next to the config file.
+pub const TOKENS_FILE: &str = "oauth_tokens.json";
+
+/// How long a sign-in waits for the browser by default.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_mins(5);
+
+/// Tokens within this many seconds of expiry are refreshed before use.
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// Largest callback request read; redirects carry a short query string.
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+
+/// Authorization and token endpoints of the providers known by name.
+fn known_endpoints(provider: &str) -> Option<(&'static str, &'static str)> {
+    match provider {
+        "github" => Some((
+            "https://github.com/login/oauth/authorize",
+            "https://github.com/login/oauth/access_token",
+        )),
+        "google" => Some((
+            "https://accounts.google.com/o/oauth2/v2/auth",
+            "https://oauth2.googleapis.com/token",
+        )),
+        _ => None,
+    }
+}
+
+/// Tokens from a completed sign-in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthToken {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Scopes granted, space separated as the provider reports them
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Unix time the access token expires, when the provider says
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+impl OAuthToken {
+    fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|at| at - EXPIRY_MARGIN_SECS <= chrono::Utc::now().timestamp())
+    }
+}
+
+/// What a sign-in reports back: everything but the tokens themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoginSummary {
+    pub provider: String,
+    pub scope: Option<String>,
+    pub expires_at: Option<i64>,
+    pub refreshable: bool,
+}
+
+impl LoginSummary {
+    fn new(provider: &str, token: &OAuthToken) -> Self {
+        Self {
+            provider: provider.to_string(),
+            scope: token.scope.clone(),
+            expires_at: token.expires_at,
+            refreshable: token.refresh_token.is_some(),
+        }
+    }
+}
+
+// ── Callback server ─────────────────────────────────────────────
+
+/// A one-shot HTTP server on `127.0.0.1` that receives an OAuth redirect.
+pub struct CallbackServer {
+    listener: TcpListener,
+    port: u16,
+}
+
+impl CallbackServer {
+    /// Listen on a free port of the loopback interface.
+    pub async fn bind() -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .context("Failed to start the OAuth callback server")?;
+        let port = listener.local_addr()?.port();
+        Ok(Self { listener, port })
+    }
+
+    /// Where the provider should send the browser back to.
+    pub fn redirect_uri(&self) -> String {
+        format!("http://127.0.0.1:{}/callback", self.port)
+    }
+
+    /// Wait for the browser to reach `/callback` and return its query
+    /// parameters. With `state` set, requests carrying another state are
+    /// answered with an error page and ignored, so a stray or forged request
+    /// cannot complete the sign-in. Fails on timeout or when the provider
+    /// reports an `error`.
+    pub async fn wait(
+        self,
+        state: Option<&str>,
+        timeout: Duration,
+    ) -> Result<HashMap<String, String>> {
+        tokio::time::timeout(timeout, self.accept_callback(state))
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Timed out after {}s waiting for the browser to finish signing in",
+                    timeout.as_secs()
+                )
+            })?
+    }
+
+    async fn accept_callback(&self, state: Option<&str>) -> Result<HashMap<String, String>> {
+        loop {
+            let (mut stream, _) = self.listener.accept().await?;
+            let Some((path, params)) = read_request(&mut stream).await else {
+                respond(&mut stream, "400 Bad Request", "Malformed request").await;
+                continue;
+            };
+            if path != "/callback" {
+                respond(&mut stream, "404 Not Found", "Not found").await;
+                continue;
+            }
+            if state.is_some_and(|s| params.get("state").map(String::as_str) != Some(s)) {
+                respond(
+                    &mut stream,
+                    "400 Bad Request",
+                    "This sign-in link is stale; start again from ZeroClaw.",
+                )
+                .await;
+                continue;
+            }
+            if let Some(error) = params.get("error") {
+                let description = params.get("error_description").unwrap_or(error);
+                respond(
+                    &mut stream,
+                    "200 OK",
+                    "Sign-in was not completed. You can close this tab.",
+                )
+                .await;
+                anyhow::bail!("Sign-in failed: {description}");
+            }
+            respond(
+                &mut stream,
+                "200 OK",
+                "Signed in to ZeroClaw. You can close this tab.",
+            )
+            .await;
+            return Ok(params);
+        }
+    }
+}
+
+/// Path and decoded query parameters of an HTTP request line.
+async fn read_request(stream: &mut TcpStream) -> Option<(String, HashMap<String, String>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 2048];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    parse_request_line(String::from_utf8_lossy(&buf).lines().next()?)
+}
+
+fn parse_request_line(line: &str) -> Option<(String, HashMap<String, String>)> {
+    let mut parts = line.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    let url = reqwest::Url::parse(&format!("http://127.0.0.1{}", parts.next()?)).ok()?;
+    let params = url.query_pairs().into_owned().collect();
+    Some((url.path().to_string(), params))
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"><title>ZeroClaw</title></head>\
+         <body style=\"font-family:sans-serif;margin:4em\"><p>{message}</p></body></html>"
+    );
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+// ── Sign-in ─────────────────────────────────────────────────────
+
+/// An `[oauth.<name>]` app with its endpoints resolved.
+struct Client {
+    client_id: String,
+    client_secret: Option<String>,
+    scopes: Vec<String>,
+    authorize_url: String,
+    token_url: String,
+}
+
+impl Client {
+    fn from_config(config: &Config, provider: &str) -> Result<Self> {
+        let app: &OAuthClientConfig = config.oauth.get(provider).with_context(|| {
+            format!("No [oauth.{provider}] app is configured; add its client_id to the config")
+        })?;
+        anyhow::ensure!(
+            !app.client_id.is_empty(),
+            "[oauth.{provider}] client_id is empty"
+        );
+        let known = known_endpoints(provider);
+        let authorize_url = app
+            .authorize_url
+            .clone()
+            .or_else(|| known.map(|(url, _)| url.to_string()))
+            .with_context(|| format!("[oauth.{provider}] needs authorize_url"))?;
+        let token_url = app
+            .token_url
+            .clone()
+            .or_else(|| known.map(|(_, url)| url.to_string()))
+            .with_context(|| format!("[oauth.{provider}] needs token_url"))?;
+        let client_secret =
+            match app.client_secret.as_deref().filter(|s| !s.is_empty()) {
+                Some(secret) => Some(config.secret_store().decrypt(secret).with_context(|| {
+                    format!("Failed to decrypt [oauth.{provider}] client_secret")
+                })?),
+                None => None,
+            };
+        Ok(Self {
+            client_id: app.client_id.clone(),
+            client_secret,
+            scopes: app.scopes.clone(),
+            authorize_url,
+            token_url,
+        })
+    }
+
+    fn authorization_url(
+        &self,
+        provider: &str,
+        redirect_uri: &str,
+        state: &str,
+        challenge: &str,
+    ) -> Result<String> {
+        let scope = self.scopes.join(" ");
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", self.client_id.as_str()),
+            ("redirect_uri", redirect_uri),
+            ("state", state),
+            ("code_challenge", challenge),
+            ("code_challenge_method", "S256"),
+        ];
+        if !scope.is_empty() {
+            params.push(("scope", scope.as_str()));
+        }
+        if provider == "google" {
+            // Without these Google issues no refresh token
+            params.extend([("access_type", "offline"), ("prompt", "consent")]);
+        }
+        let url = reqwest::Url::parse_with_params(&self.authorize_url, &params)
+            .with_context(|| format!("Invalid authorize_url for {provider}"))?;
+        Ok(url.into())
+    }
+
+    async fn request_token(&self, grant: &[(&str, &str)]) -> Result<OAuthToken> {
+        let mut form = vec![("client_id", self.client_id.as_str())];
+        if let Some(secret) = &self.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+        form.extend_from_slice(grant);
+
+        let resp = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?
+            .post(&self.token_url)
+            .header("Accept", "application/json")
+            .form(&form)
+            .send()
+            .await
+            .context("Token request failed")?;
+        let status = resp.status();
+        let body: serde_json::Value = resp
+            .json()
+            .await
+            .with_context(|| format!("Unexpected token response ({status})"))?;
+        // GitHub reports errors with 200 OK
+        if let Some(error) = body.get("error").and_then(|e| e.as_str()) {
+            let description = body
+                .get("error_description")
+                .and_then(|d| d.as_str())
+                .unwrap_or(error);
+            anyhow::bail!("Token request rejected: {description}");
+        }
+        anyhow::ensure!(status.is_success(), "Token request failed ({status})");
+
+        let access_token = body
+            .get("access_token")
+            .and_then(|t| t.as_str())
+            .context("No access_token in token response")?;
+        Ok(OAuthToken {
+            access_token: access_token.to_string(),
+            refresh_token: body
+                .get("refresh_token")
+                .and_then(|t| t.as_str())
+                .map(String::from),
+            scope: body.get("scope").and_then(|s| s.as_str()).map(String::from),
+            expires_at: body
+                .get("expires_in")
+                .and_then(serde_json::Value::as_i64)
+                .map(|secs| chrono::Utc::now().timestamp() + secs),
+        })
+    }
+}
+
+/// PKCE verifier and its S256 challenge.
+fn pkce_pair() -> (String, String) {
+    let verifier = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(Sha256::digest(verifier.as_bytes()));
+    (verifier, challenge)
+}
+
+/// Sign in to `provider` as configured under `[oauth.<provider>]`.
+/// `open` receives the consent page URL, to launch a browser or show it to
+/// the user; the tokens are stored once the browser comes back.
+pub async fn login(
+    config: &Config,
+    provider: &str,
+    open: impl FnOnce(&str),
+    timeout: Duration,
+) -> Result<LoginSummary> {
+    let client = Client::from_config(config, provider)?;
+    let server = CallbackServer::bind().await?;
+    let redirect_uri = server.redirect_uri();
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let (verifier, challenge) = pkce_pair();
+
+    open(&client.authorization_url(provider, &redirect_uri, &state, &challenge)?);
+    let params = server.wait(Some(&state), timeout).await?;
+    let code = params
+        .get("code")
+        .context("The provider did not return an authorization code")?;
+
+    let token = client
+        .request_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("code_verifier", verifier.as_str()),
+        ])
+        .await?;
+    store_token(config, provider, &token)?;
+    Ok(LoginSummary::new(provider, &token))
+}
+
+/// A usable access token for `provider`, refreshed first when it has
+/// expired. `None` when the user has not signed in.
+pub async fn access_token(config: &Config, provider: &str) -> Result<Option<String>> {
+    let Some(token) = load_token(config, provider)? else {
+        return Ok(None);
+    };
+    if !token.is_expired() {
+        return Ok(Some(token.access_token));
+    }
+    let refresh_token = token.refresh_token.as_deref().with_context(|| {
+        format!("The {provider} sign-in has expired; run `zeroclaw integrations login {provider}`")
+    })?;
+    let client = Client::from_config(config, provider)?;
+    let mut refreshed = client
+        .request_token(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ])
+        .await?;
+    // Providers may omit the refresh token when it does not rotate
+    if refreshed.refresh_token.is_none() {
+        refreshed.refresh_token = token.refresh_token;
+    }
+    store_token(config, provider, &refreshed)?;
+    Ok(Some(refreshed.access_token))
+}
+
+// ── Token storage ───────────────────────────────────────────────
+
+fn tokens_path(config: &Config) -> PathBuf {
+    config
+        .config_path
+        .parent()
+        .map_or_else(|| PathBuf::from("."), std::path::Path::to_path_buf)
+        .join(TOKENS_FILE)
+}
+
+fn read_tokens(config: &Config) -> Result<BTreeMap<String, OAuthToken>> {
+    let path = tokens_path(config);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn write_tokens(config: &Config, tokens: &BTreeMap<String, OAuthToken>) -> Result<()> {
+    let path = tokens_path(config);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(tokens)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// Save `token` for `provider`, encrypting it with the secret store.
+pub fn store_token(config: &Config, provider: &str, token: &OAuthToken) -> Result<()> {
+    let store = config.secret_store();
+    let mut tokens = read_tokens(config)?;
+    tokens.insert(
+        provider.to_string(),
+        OAuthToken {
+            access_token: store.encrypt(&token.access_token)?,
+            refresh_token: token
+                .refresh_token
+                .as_deref()
+                .map(|t| store.encrypt(t))
+                .transpose()?,
+            ..token.clone()
+        },
+    );
+    write_tokens(config, &tokens)
+}
+
+/// The stored token for `provider`, decrypted.
+pub fn load_token(config: &Config, provider: &str) -> Result<Option<OAuthToken>> {
+    let Some(token) = read_tokens(config)?.remove(provider) else {
+        return Ok(None);
+    };
+    let store = config.secret_store();
+    Ok(Some(OAuthToken {
+        access_token: store.decrypt(&token.access_token)?,
+        refresh_token: token
+            .refresh_token
+            .as_deref()
+            .map(|t| store.decrypt(t))
+            .transpose()?,
+        ..token
+    }))
+}
+
+/// Forget the stored token for `provider`. Returns whether there was one.
+/// The grant itself stays valid until revoked with the provider.
+pub fn remove_token(config: &Config, provider: &str) -> Result<bool> {
+    let mut tokens = read_tokens(config)?;
+    let removed = tokens.remove(provider).is_some();
+    if removed {
+        write_tokens(config, &tokens)?;
+    }
+    Ok(removed)
+}
+
+/// Names of the providers with a stored token.
+pub fn signed_in(config: &Config) -> Result<Vec<String>> {
+    Ok(read_tokens(config)?.into_keys().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(dir: &std::path::Path) -> Config {
+        Config {
+            config_path: dir.join("config.toml"),
+            ..Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn callback_server_returns_the_matching_redirect() {
+        let server = CallbackServer::bind().await.unwrap();
+        let base = server.redirect_uri();
+        assert!(base.starts_with("http://127.0.0.1:"));
+
+        let browser = tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let stale = client
+                .get(format!("{base}?code=old&state=other"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(stale.status(), 400);
+            let other = client.get(base.replace("/callback", "/favicon.ico"));
+            assert_eq!(other.send().await.unwrap().status(), 404);
+            let ok = client
+                .get(format!("{base}?code=a%2Fb&state=s1"))
+                .send()
+                .await
+                .unwrap();
+            assert!(ok.text().await.unwrap().contains("Signed in"));
+        });
+
+        let params = server
+            .wait(Some("s1"), Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(params["code"], "a/b");
+        browser.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn callback_server_reports_provider_errors_and_timeouts() {
+        let server = CallbackServer::bind().await.unwrap();
+        let url = format!(
+            "{}?error=access_denied&error_description=User+denied",
+            server.redirect_uri()
+        );
+        let browser = tokio::spawn(async move { reqwest::get(url).await.unwrap() });
+        let err = server
+            .wait(None, Duration::from_secs(10))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("User denied"), "{err}");
+        browser.await.unwrap();
+
+        let server = CallbackServer::bind().await.unwrap();
+        let err = server
+            .wait(None, Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Timed out"));
+    }
+
+    #[test]
+    fn authorization_url_uses_known_endpoints_and_pkce() {
+        let mut config = Config::default();
+        config.oauth.insert(
+            "google".into(),
+            OAuthClientConfig {
+                client_id: "abc".into(),
+                scopes: vec!["openid".into(), "email".into()],
+                ..OAuthClientConfig::default()
+            },
+        );
+        let client = Client::from_config(&config, "google").unwrap();
+        let (_, challenge) = pkce_pair();
+        let url = client
+            .authorization_url("google", "http://127.0.0.1:1/callback", "st", &challenge)
+            .unwrap();
+        assert!(url.starts_with("https://accounts.google.com/o/oauth2/v2/auth?"));
+        assert!(url.contains("scope=openid+email"));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains("access_type=offline"));
+
+        config.oauth.insert(
+            "acme".into(),
+            OAuthClientConfig {
+                client_id: "abc".into(),
+                ..OAuthClientConfig::default()
+            },
+        );
+        assert!(Client::from_config(&config, "acme").is_err());
+        assert!(Client::from_config(&config, "missing").is_err());
+    }
+
+    #[test]
+    fn tokens_are_stored_encrypted() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let token = OAuthToken {
+            access_token: "gho_secret".into(),
+            refresh_token: Some("refresh_secret".into()),
+            scope: Some("repo".into()),
+            expires_at: None,
+        };
+        store_token(&config, "github", &token).unwrap();
+
+        let raw = std::fs::read_to_string(dir.path().join(TOKENS_FILE)).unwrap();
+        assert!(!raw.contains("gho_secret") && !raw.contains("refresh_secret"));
+        assert_eq!(load_token(&config, "github").unwrap(), Some(token));
+        assert_eq!(signed_in(&config).unwrap(), ["github"]);
+
+        assert!(remove_token(&config, "github").unwrap());
+        assert!(load_token(&config, "github").unwrap().is_none());
+        assert!(!remove_token(&config, "github").unwrap());
+    }
+}
@@ -0,0 +1,284 @@
+//! Background jobs — long-running tasks run by the daemon.
+//!
+//! The agent queues a job with the `jobs` tool ("refactor module X",
+//! "research topic Y"), or an API caller with `POST /jobs`. Each job lives
+//! in `<workspace>/jobs/<uuid>.json`, so the queue survives restarts: a job
+//! that was running when the daemon stopped is queued again on the next
+//! start. The daemon runs up to `[jobs] max_concurrent` jobs at a time as
+//! headless agent turns, records their tool calls as progress, and sends
+//! the result to the channel and chat the job was queued from.
+
+pub mod worker;
+
+use crate::util::json_dir::{JsonDir, Record};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::Path;
+use uuid::Uuid;
+
+pub use worker::run_worker;
+
+/// Progress entries kept per job; older ones are dropped first.
+const MAX_PROGRESS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Done => "done",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Where the result of a job is delivered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobOrigin {
+    /// Channel name, e.g. "telegram"
+    pub channel: String,
+    /// Chat ID, user, room or address the job was queued from
+    pub recipient: String,
+}
+
+/// One progress event of a running job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobProgress {
+    pub at: DateTime<Utc>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    /// The task, given to the agent as the user message
+    pub prompt: String,
+    pub status: JobStatus,
+    /// Unset for jobs queued over the API, which poll `GET /jobs/{id}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<JobOrigin>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Times the job was started; above 1 after a restart interrupted it
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub progress: Vec<JobProgress>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Job {
+    pub fn new(prompt: &str, origin: Option<JobOrigin>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            prompt: prompt.to_string(),
+            status: JobStatus::Queued,
+            origin,
+            created_at: now,
+            updated_at: now,
+            attempts: 0,
+            progress: Vec::new(),
+            result: None,
+            error: None,
+        }
+    }
+
+    /// First 8 characters of the id, enough to look the job up.
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(8)]
+    }
+
+    pub fn push_progress(&mut self, message: impl Into<String>) {
+        self.progress.push(JobProgress {
+            at: Utc::now(),
+            message: message.into(),
+        });
+        if self.progress.len() > MAX_PROGRESS {
+            let excess = self.progress.len() - MAX_PROGRESS;
+            self.progress.drain(..excess);
+        }
+        self.updated_at = Utc::now();
+    }
+}
+
+/// File-backed job queue rooted at `<workspace>/jobs`.
+#[derive(Debug, Clone)]
+pub struct JobStore {
+    records: JsonDir<Job>,
+}
+
+impl Record for Job {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl JobStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            records: JsonDir::new(workspace_dir.join("jobs"), "job"),
+        }
+    }
+
+    /// Queue `prompt` as a new job.
+    pub fn enqueue(&self, prompt: &str, origin: Option<JobOrigin>) -> Result<Job> {
+        if prompt.trim().is_empty() {
+            anyhow::bail!("A job needs a non-empty prompt");
+        }
+        let job = Job::new(prompt.trim(), origin);
+        self.save(&job)?;
+        Ok(job)
+    }
+
+    /// Write the job atomically (temp file + rename).
+    pub fn save(&self, job: &Job) -> Result<()> {
+        self.records.save(job)
+    }
+
+    /// Load a job by full id or unambiguous id prefix.
+    pub fn load(&self, id: &str) -> Result<Job> {
+        self.records.load(id)
+    }
+
+    /// Load job `id`, apply `change` and save it.
+    pub fn update(&self, id: &str, change: impl FnOnce(&mut Job)) -> Result<Job> {
+        let mut job = self.load(id)?;
+        change(&mut job);
+        job.updated_at = Utc::now();
+        self.save(&job)?;
+        Ok(job)
+    }
+
+    /// Every readable job, most recently queued first.
+    pub fn list(&self) -> Result<Vec<Job>> {
+        let mut jobs = self.records.load_all()?;
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.created_at));
+        Ok(jobs)
+    }
+
+    /// Jobs waiting to run.
+    pub fn queued(&self) -> Result<usize> {
+        Ok(self
+            .list()?
+            .iter()
+            .filter(|job| job.status == JobStatus::Queued)
+            .count())
+    }
+
+    /// Mark the oldest queued job as running and return it.
+    pub fn claim_next(&self) -> Result<Option<Job>> {
+        let Some(mut job) = self
+            .list()?
+            .into_iter()
+            .rev()
+            .find(|job| job.status == JobStatus::Queued)
+        else {
+            return Ok(None);
+        };
+        job.status = JobStatus::Running;
+        job.attempts += 1;
+        job.push_progress(if job.attempts > 1 {
+            format!("Restarted (attempt {})", job.attempts)
+        } else {
+            "Started".to_string()
+        });
+        self.save(&job)?;
+        Ok(Some(job))
+    }
+
+    /// Queue again the jobs left running by a daemon that stopped. Call once
+    /// at startup, before any job is claimed. Returns how many were queued.
+    pub fn requeue_interrupted(&self) -> Result<usize> {
+        let mut count = 0;
+        for mut job in self.list()? {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Queued;
+                job.push_progress("Interrupted by a restart; queued again");
+                self.save(&job)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+tokio::task_local! {
+    static ORIGIN: Option<JobOrigin>;
+}
+
+/// Run `turn` with jobs queued during it delivering their results to `origin`.
+pub async fn scope<F: Future>(origin: Option<JobOrigin>, turn: F) -> F::Output {
+    ORIGIN.scope(origin, turn).await
+}
+
+/// Channel and chat of the running turn, if it came from a channel.
+pub fn current_origin() -> Option<JobOrigin> {
+    ORIGIN.try_with(Clone::clone).ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn jobs_are_claimed_oldest_first_and_survive_a_restart() {
+        let tmp = TempDir::new().unwrap();
+        let store = JobStore::new(tmp.path());
+        let first = store.enqueue("refactor the parser", None).unwrap();
+        let mut second = store.enqueue("research WAL tuning", None).unwrap();
+        second.created_at = first.created_at + chrono::Duration::seconds(1);
+        store.save(&second).unwrap();
+        assert!(store.enqueue("  ", None).is_err());
+        assert_eq!(store.queued().unwrap(), 2);
+
+        let claimed = store.claim_next().unwrap().unwrap();
+        assert_eq!(claimed.id, first.id);
+        assert_eq!(claimed.status, JobStatus::Running);
+        assert_eq!(claimed.attempts, 1);
+
+        // The daemon stopped mid-run: the job goes back to the queue
+        let restarted = JobStore::new(tmp.path());
+        assert_eq!(restarted.requeue_interrupted().unwrap(), 1);
+        assert_eq!(restarted.queued().unwrap(), 2);
+        let again = restarted.claim_next().unwrap().unwrap();
+        assert_eq!(again.id, first.id);
+        assert_eq!(again.attempts, 2);
+        assert_eq!(
+            again.progress.last().unwrap().message,
+            "Restarted (attempt 2)"
+        );
+
+        assert_eq!(
+            restarted.load(&second.id[..8]).unwrap().prompt,
+            second.prompt
+        );
+        assert!(restarted.load("../x").is_err());
+    }
+
+    #[tokio::test]
+    async fn origin_is_visible_only_inside_the_scope() {
+        let origin = JobOrigin {
+            channel: "telegram".into(),
+            recipient: "42".into(),
+        };
+        let seen = scope(Some(origin.clone()), async { current_origin() }).await;
+        assert_eq!(seen, Some(origin));
+        assert_eq!(current_origin(), None);
+    }
+}
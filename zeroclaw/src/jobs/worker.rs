@@ -0,0 +1,360 @@
+//! The daemon side of the job queue: claims queued jobs, runs each as a
+//! headless agent turn and delivers the result.
+
+use super::{Job, JobStatus, JobStore};
+use crate::agent::loop_;
+use crate::channels::build_system_prompt;
+use crate::config::Config;
+use crate::guardrails::Guardrails;
+use crate::memory;
+use crate::observability::traits::ObserverMetric;
+use crate::observability::{self, Observer, ObserverEvent};
+use crate::providers::{self, ChatMessage, Provider};
+use crate::runtime;
+use crate::security::SecurityPolicy;
+use crate::tools::{self, Tool};
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// How often the queue is checked for new jobs.
+const POLL_INTERVAL_SECS: u64 = 5;
+
+/// Results longer than this are cut when sent to a channel; the full
+/// result stays in the job file.
+const DELIVERY_MAX_CHARS: usize = 3_500;
+
+/// Everything a job run needs, shared by the jobs running at once.
+struct Runner {
+    config: Config,
+    store: JobStore,
+    provider: Box<dyn Provider>,
+    observer: Arc<dyn Observer>,
+    tools: Vec<Box<dyn Tool>>,
+    system_prompt: String,
+    model: String,
+    guardrails: Guardrails,
+}
+
+/// Records the tool calls of one job as its progress, and passes every
+/// event on to the configured observer.
+struct ProgressObserver {
+    inner: Arc<dyn Observer>,
+    store: JobStore,
+    job_id: String,
+}
+
+impl Observer for ProgressObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        let message = match event {
+            ObserverEvent::ToolCall {
+                tool,
+                duration,
+                success,
+            } => Some(format!(
+                "{tool} {} ({}ms)",
+                if *success { "ran" } else { "failed" },
+                duration.as_millis()
+            )),
+            ObserverEvent::PlanProposed { steps, .. } => {
+                Some(format!("Planned {} step(s)", steps.len()))
+            }
+            _ => None,
+        };
+        if let Some(message) = message {
+            if let Err(e) = self
+                .store
+                .update(&self.job_id, |job| job.push_progress(message))
+            {
+                tracing::debug!("Could not record job progress: {e}");
+            }
+        }
+        self.inner.record_event(event);
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        self.inner.record_metric(metric);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+
+    fn name(&self) -> &str {
+        "jobs"
+    }
+}
+
+/// Job loop, supervised by the daemon. Jobs left running by the previous
+/// daemon are queued again first.
+pub async fn run_worker(config: Config) -> Result<()> {
+    let store = JobStore::new(&config.workspace_dir);
+    let requeued = store.requeue_interrupted()?;
+    if requeued > 0 {
+        tracing::info!("Queued {requeued} job(s) again that a restart interrupted");
+    }
+
+    let runner = Arc::new(Runner::new(config, store.clone())?);
+    let slots = Arc::new(Semaphore::new(runner.config.jobs.max_concurrent.max(1)));
+    let mut interval = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+        crate::health::mark_component_ok("jobs");
+        if crate::daemon::shutdown::is_shutting_down() {
+            continue;
+        }
+        while let Ok(slot) = slots.clone().try_acquire_owned() {
+            let job = match store.claim_next() {
+                Ok(Some(job)) => job,
+                Ok(None) => break,
+                Err(e) => {
+                    crate::health::mark_component_error("jobs", e.to_string());
+                    tracing::warn!("Could not claim a job: {e}");
+                    break;
+                }
+            };
+            let runner = runner.clone();
+            tokio::spawn(async move {
+                let _slot = slot;
+                runner.run(job).await;
+            });
+        }
+        if let Ok(depth) = store.queued() {
+            crate::health::set_queue_depth("jobs", depth);
+        }
+    }
+}
+
+impl Runner {
+    fn new(config: Config, store: JobStore) -> Result<Self> {
+        let provider = providers::create_resilient_provider(
+            config.default_provider.as_deref().unwrap_or("openrouter"),
+            config.api_key.as_deref(),
+            &config.reliability,
+            &providers::ProviderSettings::from_config(&config),
+        )?;
+        let model = config
+            .default_model
+            .clone()
+            .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
+
+        let workspace = config.workspace_dir.clone();
+        let mem: Arc<dyn memory::Memory> = Arc::from(memory::create_memory(
+            &config.memory,
+            &workspace,
+            config.api_key.as_deref(),
+        )?);
+        let security = Arc::new(SecurityPolicy::from_config(&config.autonomy, &workspace));
+        let runtime: Arc<dyn runtime::RuntimeAdapter> =
+            Arc::from(runtime::create_runtime(&config.runtime)?);
+        let composio_key = if config.composio.enabled {
+            config.composio.api_key.as_deref()
+        } else {
+            None
+        };
+        let mut tools = tools::all_tools_with_runtime(
+            &security,
+            runtime,
+            mem.clone(),
+            composio_key,
+            &config.browser,
+        );
+        tools.extend(tools::optional_tools(&config, &security));
+        tools::route_to_nodes(&mut tools, &config.nodes, &security);
+        if config.knowledge.enabled && crate::knowledge::supported(mem.as_ref()) {
+            tools.push(Box::new(tools::KbSearchTool::new(mem)) as Box<dyn Tool>);
+        }
+        // A job may not queue more jobs, nor wait on a user who is not there
+        tools.retain(|t| !matches!(t.name(), "jobs" | "ask_user"));
+
+        let tool_descs = loop_::tool_descriptions(&config, &tools);
+        let skills = crate::skills::load_skills(&workspace);
+        let mut system_prompt = build_system_prompt(
+            &workspace,
+            &model,
+            &tool_descs,
+            &skills,
+            Some(&crate::identity::readable_by(config.identity.for_channel("jobs"), &security)),
+        );
+        system_prompt.push_str(&loop_::build_tool_instructions_with(
+            &tools,
+            config.tool_schema_for(&model),
+        ));
+
+        Ok(Self {
+            observer: Arc::from(observability::create_observer(&config.observability)),
+            guardrails: Guardrails::from_config(&config.guardrails, &workspace),
+            config,
+            store,
+            provider,
+            tools,
+            system_prompt,
+            model,
+        })
+    }
+
+    /// Run a claimed job to completion, save the outcome and deliver it.
+    async fn run(&self, job: Job) {
+        tracing::info!("Job {} started: {}", job.short_id(), job.prompt);
+        let channel = job
+            .origin
+            .as_ref()
+            .map_or("jobs", |origin| origin.channel.as_str());
+        let observer = ProgressObserver {
+            inner: self.observer.clone(),
+            store: self.store.clone(),
+            job_id: job.id.clone(),
+        };
+        let mut history = vec![
+            ChatMessage::system(self.system_prompt.as_str()),
+            ChatMessage::user(job.prompt.as_str()),
+        ];
+        let outcome = crate::agent::settings::scope(
+            &self.config,
+            crate::hooks::scope(
+                &self.config,
+                loop_::agent_turn_with_budget(
+                    self.provider.as_ref(),
+                    &mut history,
+                    &self.tools,
+                    &observer,
+                    &self.model,
+                    self.config.default_temperature,
+                    None,
+                    self.config.turn_budget_for(&self.model),
+                ),
+            ),
+        )
+        .await;
+        let outcome = match outcome {
+            Ok(reply) => Ok((
+                self.guardrails
+                    .apply(
+                        self.provider.as_ref(),
+                        &mut history,
+                        reply.answer,
+                        &self.model,
+                        channel,
+                    )
+                    .await,
+                reply.exhausted,
+            )),
+            Err(e) => Err(e),
+        };
+
+        // Reload so the progress recorded during the run is kept
+        let saved = self.store.update(&job.id, |job| match outcome {
+            Ok((answer, exhausted)) => {
+                if let Some(exhausted) = exhausted {
+                    job.push_progress(format!("Stopped early: {exhausted}"));
+                }
+                job.push_progress("Finished");
+                job.status = JobStatus::Done;
+                job.result = Some(answer);
+            }
+            Err(e) => {
+                job.push_progress("Failed");
+                job.status = JobStatus::Failed;
+                job.error = Some(format!("{e:#}"));
+            }
+        });
+        match saved {
+            Ok(job) => {
+                tracing::info!("Job {} {}", job.short_id(), job.status.as_str());
+                deliver(&self.config, &job).await;
+            }
+            Err(e) => {
+                crate::health::mark_component_error("jobs", e.to_string());
+                tracing::warn!("Could not save job {}: {e}", job.short_id());
+            }
+        }
+    }
+}
+
+/// Text sent to the originating chat when a job finishes.
+fn completion_message(job: &Job) -> String {
+    match job.status {
+        JobStatus::Done => format!(
+            "✅ Job {} finished: {}\n\n{}",
+            job.short_id(),
+            truncate_with_ellipsis(&job.prompt, 80),
+            truncate_with_ellipsis(job.result.as_deref().unwrap_or(""), DELIVERY_MAX_CHARS)
+        ),
+        _ => format!(
+            "❌ Job {} failed: {}\n\n{}",
+            job.short_id(),
+            truncate_with_ellipsis(&job.prompt, 80),
+            job.error.as_deref().unwrap_or("unknown error")
+        ),
+    }
+}
+
+/// Send the outcome of `job` to the channel it was queued from.
+async fn deliver(config: &Config, job: &Job) {
+    let Some(origin) = &job.origin else {
+        return;
+    };
+    let Some(channel) = crate::channels::configured_channel(config, &origin.channel) else {
+        tracing::warn!(
+            "Job {}: channel {} is not configured; result not delivered",
+            job.short_id(),
+            origin.channel
+        );
+        return;
+    };
+    if let Err(e) = channel
+        .send(&completion_message(job), &origin.recipient)
+        .await
+    {
+        tracing::warn!(
+            "Could not deliver job {} to {}: {e}",
+            job.short_id(),
+            origin.channel
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn progress_observer_records_tool_calls_on_the_job() {
+        let tmp = TempDir::new().unwrap();
+        let store = JobStore::new(tmp.path());
+        let job = store.enqueue("audit dependencies", None).unwrap();
+        let observer = ProgressObserver {
+            inner: Arc::new(observability::NoopObserver),
+            store: store.clone(),
+            job_id: job.id.clone(),
+        };
+        observer.record_event(&ObserverEvent::ToolCall {
+            tool: "shell".into(),
+            duration: Duration::from_millis(12),
+            success: true,
+        });
+        observer.record_event(&ObserverEvent::HeartbeatTick);
+
+        let progress = store.load(&job.id).unwrap().progress;
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress[0].message, "shell ran (12ms)");
+    }
+
+    #[test]
+    fn completion_message_reports_result_or_error() {
+        let mut job = Job::new("summarize the changelog", None);
+        job.status = JobStatus::Done;
+        job.result = Some("Three fixes, one feature.".into());
+        let done = completion_message(&job);
+        assert!(done.starts_with(&format!("✅ Job {} finished", job.short_id())));
+        assert!(done.ends_with("Three fixes, one feature."));
+
+        job.status = JobStatus::Failed;
+        job.error = Some("provider timed out".into());
+        assert!(completion_message(&job).ends_with("provider timed out"));
+    }
+}
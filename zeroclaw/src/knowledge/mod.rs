@@ -0,0 +1,460 @@
+//! Knowledge base: documents from configured directories, chunked and stored
+//! in memory under the `knowledge` category so they share the sqlite
+//! backend's embeddings and hybrid search.
+//!
+//! A manifest (`memory/knowledge_index.json`) records a content hash and the
+//! chunk count per file. A sync only re-embeds files whose hash changed and
+//! forgets the chunks of files that shrank or disappeared.
+
+use crate::config::{Config, KnowledgeConfig};
+use crate::memory::{self, chunker, Memory, MemoryCategory, MemoryEntry};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Memory category every knowledge chunk is stored under.
+pub const CATEGORY: &str = "knowledge";
+
+const MANIFEST_FILE: &str = "knowledge_index.json";
+
+/// Recall is not filtered by category, so ask for this many candidates per
+/// wanted knowledge hit and drop the rest.
+const RECALL_OVERSAMPLE: usize = 4;
+
+/// One sync at a time per process; the agent and daemon share the manifest.
+static SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+pub fn category() -> MemoryCategory {
+    MemoryCategory::Custom(CATEGORY.into())
+}
+
+pub fn is_knowledge(entry: &MemoryEntry) -> bool {
+    matches!(&entry.category, MemoryCategory::Custom(c) if c == CATEGORY)
+}
+
+fn chunk_key(source: &str, index: usize) -> String {
+    format!("kb:{source}#{index}")
+}
+
+/// Indexed state of one document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexedFile {
+    sha256: String,
+    chunks: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    files: BTreeMap<String, IndexedFile>,
+}
+
+/// What a sync pass changed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SyncStats {
+    pub indexed_files: usize,
+    pub chunks: usize,
+    pub removed_files: usize,
+}
+
+impl SyncStats {
+    pub fn is_empty(&self) -> bool {
+        self.indexed_files == 0 && self.removed_files == 0
+    }
+}
+
+/// The configured document set and where its manifest lives.
+pub struct KnowledgeIndex {
+    dirs: Vec<PathBuf>,
+    extensions: Vec<String>,
+    max_file_bytes: u64,
+    chunk_tokens: usize,
+    workspace_dir: PathBuf,
+    manifest_path: PathBuf,
+}
+
+impl KnowledgeIndex {
+    pub fn new(config: &KnowledgeConfig, workspace_dir: &Path, chunk_tokens: usize) -> Self {
+        let dirs = config
+            .dirs
+            .iter()
+            .map(|d| {
+                let expanded = PathBuf::from(shellexpand::tilde(d).as_ref());
+                if expanded.is_absolute() {
+                    expanded
+                } else {
+                    workspace_dir.join(expanded)
+                }
+            })
+            .collect();
+        Self {
+            dirs,
+            extensions: config
+                .extensions
+                .iter()
+                .map(|e| e.trim_start_matches('.').to_ascii_lowercase())
+                .collect(),
+            max_file_bytes: config.max_file_kb.saturating_mul(1024),
+            chunk_tokens: chunk_tokens.max(1),
+            workspace_dir: workspace_dir.to_path_buf(),
+            manifest_path: workspace_dir.join("memory").join(MANIFEST_FILE),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            &config.knowledge,
+            &config.workspace_dir,
+            config.memory.chunk_max_tokens,
+        )
+    }
+
+    /// Bring the memory index in line with the documents on disk.
+    pub async fn sync(&self, mem: &dyn Memory) -> Result<SyncStats> {
+        let _guard = SYNC_LOCK.lock().await;
+        let mut manifest = self.load_manifest();
+        let mut stats = SyncStats::default();
+        let mut seen = HashSet::new();
+
+        for (source, path) in self.collect_files() {
+            seen.insert(source.clone());
+            // Non-UTF-8 files are not text we can chunk
+            let Ok(text) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+            let sha256 = hex::encode(Sha256::digest(text.as_bytes()));
+            let previous = manifest.files.get(&source).cloned();
+            if previous.as_ref().is_some_and(|f| f.sha256 == sha256) {
+                continue;
+            }
+
+            let chunks = chunker::chunk_markdown(&text, self.chunk_tokens);
+            for chunk in &chunks {
+                let content = format!("[{source}]\n{}", chunk.content);
+                mem.store(&chunk_key(&source, chunk.index), &content, category())
+                    .await?;
+            }
+            for index in chunks.len()..previous.map_or(0, |f| f.chunks) {
+                mem.forget(&chunk_key(&source, index)).await?;
+            }
+
+            stats.indexed_files += 1;
+            stats.chunks += chunks.len();
+            manifest.files.insert(
+                source,
+                IndexedFile {
+                    sha256,
+                    chunks: chunks.len(),
+                },
+            );
+            // Saved per file so an interrupted first index resumes, not restarts
+            self.save_manifest(&manifest)?;
+        }
+
+        let removed: Vec<String> = manifest
+            .files
+            .keys()
+            .filter(|source| !seen.contains(*source))
+            .cloned()
+            .collect();
+        for source in removed {
+            if let Some(file) = manifest.files.remove(&source) {
+                for index in 0..file.chunks {
+                    mem.forget(&chunk_key(&source, index)).await?;
+                }
+                stats.removed_files += 1;
+            }
+        }
+        self.save_manifest(&manifest)?;
+
+        Ok(stats)
+    }
+
+    /// Indexable files as `(source label, path)`. Files under the workspace
+    /// are labelled relative to it, others by absolute path.
+    fn collect_files(&self) -> Vec<(String, PathBuf)> {
+        let mut files = Vec::new();
+        for dir in &self.dirs {
+            self.walk(dir, &mut files);
+        }
+        files.sort();
+        files.dedup();
+        files
+            .into_iter()
+            .map(|path| {
+                let label = path.strip_prefix(&self.workspace_dir).unwrap_or(&path);
+                (label.to_string_lossy().replace('\\', "/"), path)
+            })
+            .collect()
+    }
+
+    fn walk(&self, dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if file_type.is_dir() {
+                if !hidden {
+                    self.walk(&path, files);
+                }
+            } else if file_type.is_file() && !hidden && self.wants(&path, &entry) {
+                files.push(path);
+            }
+        }
+    }
+
+    fn wants(&self, path: &Path, entry: &std::fs::DirEntry) -> bool {
+        let extension_ok = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| self.extensions.contains(&e.to_ascii_lowercase()));
+        extension_ok
+            && entry
+                .metadata()
+                .is_ok_and(|m| m.len() <= self.max_file_bytes)
+    }
+
+    fn load_manifest(&self) -> Manifest {
+        std::fs::read_to_string(&self.manifest_path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_manifest(&self, manifest: &Manifest) -> Result<()> {
+        if let Some(parent) = self.manifest_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.manifest_path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(manifest)?)?;
+        std::fs::rename(&tmp, &self.manifest_path)?;
+        Ok(())
+    }
+}
+
+/// The knowledge base only works on a backend with ranked recall.
+pub fn supported(mem: &dyn Memory) -> bool {
+    mem.name() == "sqlite"
+}
+
+/// Chunks to inject per turn; 0 when the knowledge base is off.
+pub fn context_limit(config: &KnowledgeConfig) -> usize {
+    if config.enabled {
+        config.context_chunks
+    } else {
+        0
+    }
+}
+
+/// Knowledge chunks most relevant to `query`, best first.
+pub async fn search(mem: &dyn Memory, query: &str, limit: usize) -> Result<Vec<MemoryEntry>> {
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+    let mut entries = mem
+        .recall(query, limit.saturating_mul(RECALL_OVERSAMPLE))
+        .await?;
+    entries.retain(is_knowledge);
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// `[Knowledge context]` block for the turn preamble; empty when nothing
+/// matches or `limit` is 0.
+pub async fn build_context(mem: &dyn Memory, query: &str, limit: usize) -> String {
+    let mut context = String::new();
+    if let Ok(entries) = search(mem, query, limit).await {
+        if !entries.is_empty() {
+            context.push_str("[Knowledge context]\n");
+            for entry in &entries {
+                let _ = writeln!(context, "- {}", entry.content.replace('\n', "\n  "));
+            }
+            context.push('\n');
+        }
+    }
+    context
+}
+
+/// Index once in the background, e.g. when an agent session starts. Callers
+/// without a long-lived runtime (the FFI) get a dedicated thread instead.
+pub fn spawn_sync(config: &Config, mem: Arc<dyn Memory>) {
+    if !config.knowledge.enabled || !supported(mem.as_ref()) {
+        return;
+    }
+    let index = KnowledgeIndex::from_config(config);
+    let task = async move {
+        match index.sync(mem.as_ref()).await {
+            Ok(stats) => log_stats(stats),
+            Err(e) => tracing::warn!("Knowledge base sync failed: {e}"),
+        }
+    };
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        handle.spawn(task);
+    } else {
+        std::thread::spawn(move || {
+            match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt.block_on(task),
+                Err(e) => tracing::warn!("Knowledge base sync not started: {e}"),
+            }
+        });
+    }
+}
+
+fn log_stats(stats: SyncStats) {
+    if !stats.is_empty() {
+        tracing::info!(
+            files = stats.indexed_files,
+            chunks = stats.chunks,
+            removed = stats.removed_files,
+            "Knowledge base updated"
+        );
+    }
+}
+
+/// Daemon worker: keep the index current for as long as the daemon runs.
+pub async fn run(config: Config) -> Result<()> {
+    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
+        &config.memory,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )?);
+    if !supported(mem.as_ref()) {
+        anyhow::bail!(
+            "knowledge base needs the sqlite memory backend (current: {})",
+            mem.name()
+        );
+    }
+    let index = KnowledgeIndex::from_config(&config);
+    let interval = u64::from(config.knowledge.reindex_interval_minutes.max(1)) * 60;
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+
+    loop {
+        ticker.tick().await;
+        log_stats(index.sync(mem.as_ref()).await?);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use tempfile::TempDir;
+
+    fn index_for(workspace: &Path) -> KnowledgeIndex {
+        KnowledgeIndex::new(&KnowledgeConfig::default(), workspace, 512)
+    }
+
+    async fn knowledge_keys(mem: &dyn Memory) -> Vec<String> {
+        let mut keys: Vec<String> = mem
+            .list(Some(&category()))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.key)
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[tokio::test]
+    async fn sync_indexes_only_changed_files() {
+        let tmp = TempDir::new().unwrap();
+        let docs = tmp.path().join("knowledge");
+        std::fs::create_dir_all(docs.join("ops")).unwrap();
+        std::fs::write(docs.join("ops/deploy.md"), "# Deploy\nRun make release.").unwrap();
+        std::fs::write(docs.join("notes.txt"), "Staging lives on host b7.").unwrap();
+        std::fs::write(docs.join("image.png"), "not text").unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let index = index_for(tmp.path());
+
+        let stats = index.sync(&mem).await.unwrap();
+        assert_eq!(stats.indexed_files, 2);
+        assert_eq!(
+            knowledge_keys(&mem).await,
+            vec![
+                "kb:knowledge/notes.txt#0".to_string(),
+                "kb:knowledge/ops/deploy.md#0".to_string()
+            ]
+        );
+
+        // Nothing changed: nothing re-embedded
+        assert!(index.sync(&mem).await.unwrap().is_empty());
+
+        std::fs::write(docs.join("notes.txt"), "Staging moved to host c3.").unwrap();
+        let stats = index.sync(&mem).await.unwrap();
+        assert_eq!(stats.indexed_files, 1);
+        let entry = mem.get("kb:knowledge/notes.txt#0").await.unwrap().unwrap();
+        assert!(entry.content.contains("host c3"));
+    }
+
+    #[tokio::test]
+    async fn sync_forgets_removed_and_shrunk_documents() {
+        let tmp = TempDir::new().unwrap();
+        let docs = tmp.path().join("knowledge");
+        std::fs::create_dir_all(&docs).unwrap();
+        let long = "# A\nalpha\n\n# B\nbeta\n\n# C\ngamma";
+        std::fs::write(docs.join("guide.md"), long).unwrap();
+        std::fs::write(docs.join("old.md"), "obsolete").unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let index = KnowledgeIndex::new(&KnowledgeConfig::default(), tmp.path(), 4);
+
+        index.sync(&mem).await.unwrap();
+        assert_eq!(knowledge_keys(&mem).await.len(), 4);
+
+        std::fs::write(docs.join("guide.md"), "# A\nalpha").unwrap();
+        std::fs::remove_file(docs.join("old.md")).unwrap();
+        let stats = index.sync(&mem).await.unwrap();
+        assert_eq!(stats.removed_files, 1);
+        assert_eq!(
+            knowledge_keys(&mem).await,
+            vec!["kb:knowledge/guide.md#0".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_returns_only_knowledge_chunks() {
+        let tmp = TempDir::new().unwrap();
+        let docs = tmp.path().join("knowledge");
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(docs.join("vpn.md"), "The VPN gateway is vpn.example.org").unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store("vpn_pref", "User dislikes the VPN", MemoryCategory::Core)
+            .await
+            .unwrap();
+        index_for(tmp.path()).sync(&mem).await.unwrap();
+
+        let hits = search(&mem, "VPN", 5).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].key, "kb:knowledge/vpn.md#0");
+
+        let context = build_context(&mem, "VPN", 3).await;
+        assert!(context.starts_with("[Knowledge context]\n- [knowledge/vpn.md]"));
+        assert!(build_context(&mem, "VPN", 0).await.is_empty());
+    }
+
+    #[test]
+    fn dirs_resolve_against_workspace() {
+        let config = KnowledgeConfig {
+            dirs: vec!["docs".into(), "/srv/wiki".into()],
+            ..KnowledgeConfig::default()
+        };
+        let index = KnowledgeIndex::new(&config, Path::new("/ws"), 512);
+        assert_eq!(
+            index.dirs,
+            vec![PathBuf::from("/ws/docs"), PathBuf::from("/srv/wiki")]
+        );
+    }
+}
@@ -14,30 +14,49 @@
 use clap::Subcommand;
 use serde::{Deserialize, Serialize};
 
+pub mod a2a;
 pub mod agent;
+pub mod bus;
 pub mod channels;
 pub mod config;
+pub mod context;
 pub mod cron;
 pub mod daemon;
 pub mod doctor;
+pub mod evals;
 pub mod ffi;
 pub mod gateway;
+pub mod guardrails;
 pub mod health;
 pub mod heartbeat;
+pub mod hooks;
 pub mod identity;
 pub mod integrations;
+pub mod jobs;
+pub mod knowledge;
 pub mod memory;
 pub mod migration;
+pub mod nodes;
 pub mod observability;
 pub mod onboard;
+pub mod prompts;
 pub mod providers;
+pub mod render;
 pub mod runtime;
 pub mod security;
 pub mod service;
+pub mod sessions;
 pub mod skills;
+pub mod tokens;
 pub mod tools;
+pub mod transcription;
+pub mod triggers;
+pub mod tts;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod tunnel;
 pub mod util;
+pub mod workflows;
 
 pub use config::Config;
 
@@ -88,12 +107,40 @@ pub enum SkillCommands {
     Install {
         /// Source URL or local path
         source: String,
+        /// Branch or tag to check out (git sources only)
+        #[arg(long = "ref")]
+        git_ref: Option<String>,
+        /// Treat the source as a package name in the configured skill registry
+        #[arg(long, conflicts_with = "git_ref")]
+        registry: bool,
+    },
+    /// Search the configured skill registry
+    Search {
+        /// Matches names, descriptions and tags; empty lists everything
+        #[arg(default_value = "")]
+        query: String,
     },
     /// Remove an installed skill
     Remove {
         /// Skill name to remove
         name: String,
     },
+    /// Draft new skills from recurring procedures in recent sessions
+    Distill {
+        /// Only analyze sessions updated within this many days
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+        /// Show proposed drafts without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List skill drafts awaiting review
+    Drafts,
+    /// Move a reviewed draft into the skills directory
+    Approve {
+        /// Draft name
+        name: String,
+    },
 }
 
 /// Migration subcommands
@@ -138,4 +185,105 @@ pub enum IntegrationCommands {
         /// Integration name
         name: String,
     },
+    /// Sign in to an OAuth integration configured under [oauth.<name>]
+    Login {
+        /// Provider name, e.g. github or google
+        name: String,
+    },
+    /// Forget the stored sign-in for an OAuth integration
+    Logout {
+        /// Provider name
+        name: String,
+    },
+    /// Connect an app through Composio and wait for the browser to finish
+    Connect {
+        /// Composio app name, e.g. gmail
+        app: String,
+    },
+}
+
+/// Session subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionCommands {
+    /// List saved sessions, most recent first
+    List {
+        /// Maximum number of sessions to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+    /// Search past sessions by keyword (or meaning, with --semantic)
+    Search {
+        /// Search query
+        query: String,
+        /// Maximum number of matching sessions
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+        /// Blend in embedding similarity (uses the memory embedding provider)
+        #[arg(long)]
+        semantic: bool,
+    },
+    /// Print a session transcript
+    Show {
+        /// Session ID (or unique prefix)
+        id: String,
+    },
+    /// Export a session transcript
+    Export {
+        /// Session ID (or unique prefix)
+        id: String,
+        /// Output format (markdown, json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Add or remove labels on a session
+    Tag {
+        /// Session ID (or unique prefix)
+        id: String,
+        /// Tags to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Remove the tags instead
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Rate an answer in a session (up or down), as feedback
+    Rate {
+        /// Session ID (or unique prefix)
+        id: String,
+        /// up or down
+        rating: String,
+        /// Index of the rated message (default: the last answer)
+        #[arg(long)]
+        message: Option<usize>,
+        /// Why
+        #[arg(long)]
+        comment: Option<String>,
+    },
+    /// Export sessions as a fine-tuning dataset (JSONL), secrets redacted
+    Dataset {
+        /// Dataset format (openai, sharegpt)
+        #[arg(short, long, default_value = "openai")]
+        format: String,
+        /// Only sessions started on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only sessions started before this date
+        #[arg(long)]
+        until: Option<String>,
+        /// Only sessions with this tag; repeat to require several
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Only sessions rated up or down
+        #[arg(long)]
+        rating: Option<String>,
+        /// Leave emails, phone and card numbers and IP addresses in
+        #[arg(long)]
+        keep_pii: bool,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
 }
@@ -10,33 +10,53 @@
 
 use anyhow::{bail, Result};
 use clap::{Parser, Subcommand};
+use std::io::IsTerminal;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
+mod a2a;
 mod agent;
+mod bus;
 mod channels;
 mod config;
+mod context;
 mod cron;
 mod daemon;
 mod doctor;
+mod evals;
 mod gateway;
+mod guardrails;
 mod health;
 mod heartbeat;
+mod hooks;
 mod identity;
 mod integrations;
+mod jobs;
+mod knowledge;
 mod memory;
 mod migration;
+mod nodes;
 mod observability;
 mod onboard;
+mod prompts;
 mod providers;
+mod render;
 mod runtime;
 mod security;
 mod service;
+mod sessions;
 mod skillforge;
 mod skills;
+mod tokens;
 mod tools;
+mod transcription;
+mod triggers;
+mod tts;
+#[cfg(feature = "tui")]
+mod tui;
 mod tunnel;
 mod util;
+mod workflows;
 
 use config::Config;
 
@@ -90,6 +110,20 @@ enum Commands {
         memory: Option<String>,
     },
 
+    /// Guided first-run setup: provider, API key, workspace and autonomy
+    Setup,
+
+    /// Scaffold the workspace from a persona template (coding, assistant, ops)
+    Init {
+        /// Template name: coding, assistant or ops
+        #[arg(long)]
+        template: String,
+
+        /// Skip the template's example cron jobs
+        #[arg(long)]
+        no_schedules: bool,
+    },
+
     /// Start the AI agent loop
     Agent {
         /// Single message mode (don't enter interactive mode)
@@ -107,6 +141,45 @@ enum Commands {
         /// Temperature (0.0 - 2.0)
         #[arg(short, long, default_value = "0.7")]
         temperature: f64,
+
+        /// Cap estimated tokens per request (can only lower the model profile's cap)
+        #[arg(long)]
+        max_tokens: Option<u64>,
+
+        /// Cap tool executions per request
+        #[arg(long)]
+        max_tool_calls: Option<u32>,
+
+        /// Cap wall-clock seconds per request
+        #[arg(long)]
+        max_seconds: Option<u64>,
+
+        /// Cap model calls per request
+        #[arg(long)]
+        max_iterations: Option<u32>,
+
+        /// Run scoped to one skill: only its declared tools are available
+        #[arg(long)]
+        skill: Option<String>,
+
+        /// Preview what tools would change instead of changing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Chat in a full-screen terminal UI with tool, memory and cost panes
+    Tui {
+        /// Provider to use (openrouter, anthropic, openai)
+        #[arg(short, long)]
+        provider: Option<String>,
+
+        /// Model to use
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Temperature (0.0 - 2.0)
+        #[arg(short, long, default_value = "0.7")]
+        temperature: f64,
     },
 
     /// Start the gateway server (webhooks, websockets)
@@ -120,6 +193,17 @@ enum Commands {
         host: String,
     },
 
+    /// Serve this machine's shell and file tools to other instances
+    Node {
+        /// Port to listen on (default: [nodes] port)
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// Host to bind to (default: [nodes] host)
+        #[arg(long)]
+        host: Option<String>,
+    },
+
     /// Start long-running autonomous runtime (gateway + channels + heartbeat + scheduler)
     Daemon {
         /// Port to listen on (use 0 for random available port)
@@ -129,9 +213,13 @@ enum Commands {
         /// Host to bind to
         #[arg(long, default_value = "127.0.0.1")]
         host: String,
+
+        /// Run under the Windows service control manager (set by `service install`)
+        #[arg(long)]
+        service: bool,
     },
 
-    /// Manage OS service lifecycle (launchd/systemd user service)
+    /// Manage OS service lifecycle (launchd/systemd user service, Windows service)
     Service {
         #[command(subcommand)]
         service_command: ServiceCommands,
@@ -172,6 +260,53 @@ enum Commands {
         #[command(subcommand)]
         migrate_command: MigrateCommands,
     },
+
+    /// Browse and export past conversations
+    #[command(alias = "session")]
+    Sessions {
+        #[command(subcommand)]
+        session_command: SessionCommands,
+    },
+
+    /// Run eval suites against providers, models and skills
+    Eval {
+        /// Suite files or directories (default: <workspace>/evals)
+        paths: Vec<std::path::PathBuf>,
+
+        /// Provider to run on; repeat to compare providers
+        #[arg(short, long)]
+        provider: Vec<String>,
+
+        /// Model to run on; repeat to compare models
+        #[arg(long)]
+        model: Vec<String>,
+
+        /// Skill to scope the cases to; repeat to compare skills
+        #[arg(long)]
+        skill: Vec<String>,
+
+        /// Model that answers judge assertions (default: the model under test)
+        #[arg(long)]
+        judge_model: Option<String>,
+
+        /// Write a JUnit XML report to this file
+        #[arg(long)]
+        junit: Option<std::path::PathBuf>,
+    },
+
+    /// Check the config or print its JSON Schema
+    Config {
+        #[command(subcommand)]
+        config_command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Warn about settings that will not work as intended
+    Validate,
+    /// Print the JSON Schema of the config file
+    Schema,
 }
 
 #[derive(Subcommand, Debug)]
@@ -236,12 +371,125 @@ enum SkillCommands {
     Install {
         /// GitHub URL or local path
         source: String,
+        /// Branch or tag to check out (git sources only)
+        #[arg(long = "ref")]
+        git_ref: Option<String>,
+        /// Treat the source as a package name in the configured skill registry
+        #[arg(long, conflicts_with = "git_ref")]
+        registry: bool,
+    },
+    /// Search the configured skill registry
+    Search {
+        /// Matches names, descriptions and tags; empty lists everything
+        #[arg(default_value = "")]
+        query: String,
     },
     /// Remove an installed skill
     Remove {
         /// Skill name
         name: String,
     },
+    /// Draft new skills from recurring procedures in recent sessions
+    Distill {
+        /// Only analyze sessions updated within this many days
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+        /// Show proposed drafts without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List skill drafts awaiting review
+    Drafts,
+    /// Move a reviewed draft into the skills directory
+    Approve {
+        /// Draft name
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SessionCommands {
+    /// List saved sessions, most recent first
+    List {
+        /// Maximum number of sessions to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+    /// Search past sessions by keyword (or meaning, with --semantic)
+    Search {
+        /// Search query
+        query: String,
+        /// Maximum number of matching sessions
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+        /// Blend in embedding similarity (uses the memory embedding provider)
+        #[arg(long)]
+        semantic: bool,
+    },
+    /// Print a session transcript
+    Show {
+        /// Session ID (or unique prefix)
+        id: String,
+    },
+    /// Export a session transcript
+    Export {
+        /// Session ID (or unique prefix)
+        id: String,
+        /// Output format (markdown, json)
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Add or remove labels on a session
+    Tag {
+        /// Session ID (or unique prefix)
+        id: String,
+        /// Tags to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Remove the tags instead
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Rate an answer in a session (up or down), as feedback
+    Rate {
+        /// Session ID (or unique prefix)
+        id: String,
+        /// up or down
+        rating: String,
+        /// Index of the rated message (default: the last answer)
+        #[arg(long)]
+        message: Option<usize>,
+        /// Why
+        #[arg(long)]
+        comment: Option<String>,
+    },
+    /// Export sessions as a fine-tuning dataset (JSONL), secrets redacted
+    Dataset {
+        /// Dataset format (openai, sharegpt)
+        #[arg(short, long, default_value = "openai")]
+        format: String,
+        /// Only sessions started on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only sessions started before this date
+        #[arg(long)]
+        until: Option<String>,
+        /// Only sessions with this tag; repeat to require several
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Only sessions rated up or down
+        #[arg(long)]
+        rating: Option<String>,
+        /// Leave emails, phone and card numbers and IP addresses in
+        #[arg(long)]
+        keep_pii: bool,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -251,6 +499,21 @@ enum IntegrationCommands {
         /// Integration name
         name: String,
     },
+    /// Sign in to an OAuth integration configured under [oauth.<name>]
+    Login {
+        /// Provider name, e.g. github or google
+        name: String,
+    },
+    /// Forget the stored sign-in for an OAuth integration
+    Logout {
+        /// Provider name
+        name: String,
+    },
+    /// Connect an app through Composio and wait for the browser to finish
+    Connect {
+        /// Composio app name, e.g. gmail
+        app: String,
+    },
 }
 
 #[tokio::main]
@@ -302,18 +565,63 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    if matches!(cli.command, Commands::Setup) {
+        onboard::run_setup()?;
+        return Ok(());
+    }
+    // A first interactive session starts with setup instead of silent defaults
+    let interactive = matches!(
+        cli.command,
+        Commands::Agent { message: None, .. } | Commands::Tui { .. }
+    );
+    if interactive && onboard::needs_setup() && std::io::stdin().is_terminal() {
+        onboard::run_setup()?;
+    }
+
     // All other commands need config loaded first
     let config = Config::load_or_init()?;
 
     match cli.command {
-        Commands::Onboard { .. } => unreachable!(),
+        Commands::Onboard { .. } | Commands::Setup => unreachable!(),
 
         Commands::Agent {
             message,
             provider,
             model,
             temperature,
-        } => agent::run(config, message, provider, model, temperature).await,
+            max_tokens,
+            max_tool_calls,
+            max_seconds,
+            max_iterations,
+            skill,
+            dry_run,
+        } => {
+            let mut config = config;
+            config.autonomy.dry_run |= dry_run;
+            let budget = agent::TurnBudget {
+                max_tokens,
+                max_tool_calls,
+                max_seconds,
+                max_iterations,
+            };
+            agent::run(config, message, provider, model, temperature, budget, skill).await
+        }
+
+        #[cfg(feature = "tui")]
+        Commands::Tui {
+            provider,
+            model,
+            temperature,
+        } => tui::run_from_config(config, provider, model, temperature).await,
+        #[cfg(not(feature = "tui"))]
+        Commands::Tui { .. } => {
+            anyhow::bail!("This build has no terminal UI; rebuild with the `tui` feature")
+        }
+
+        Commands::Init {
+            template,
+            no_schedules,
+        } => onboard::templates::init_workspace(config, &template, !no_schedules),
 
         Commands::Gateway { port, host } => {
             if port == 0 {
@@ -324,7 +632,16 @@ async fn main() -> Result<()> {
             gateway::run_gateway(&host, port, config).await
         }
 
-        Commands::Daemon { port, host } => {
+        Commands::Node { port, host } => nodes::run_node(config, host, port).await,
+
+        Commands::Daemon {
+            port,
+            host,
+            service,
+        } => {
+            if service {
+                return run_daemon_service(config, host, port).await;
+            }
             if port == 0 {
                 info!("🧠 Starting ZeroClaw Daemon on {host} (random port)");
             } else {
@@ -416,18 +733,99 @@ async fn main() -> Result<()> {
 
         Commands::Integrations {
             integration_command,
-        } => integrations::handle_command(integration_command, &config),
+        } => integrations::handle_command(integration_command, &config).await,
 
-        Commands::Skills { skill_command } => {
-            skills::handle_command(skill_command, &config.workspace_dir)
-        }
+        Commands::Skills { skill_command } => match skill_command {
+            SkillCommands::Distill { days, dry_run } => {
+                skills::distill::handle_distill(&config, days, dry_run).await
+            }
+            SkillCommands::Search { query } => {
+                skills::registry::handle_search(&config.skill_registry, &query).await
+            }
+            SkillCommands::Install {
+                source,
+                registry: true,
+                ..
+            } => {
+                skills::registry::handle_install(
+                    &config.skill_registry,
+                    &config.workspace_dir,
+                    &source,
+                )
+                .await
+            }
+            other => skills::handle_command(other, &config.workspace_dir),
+        },
 
         Commands::Migrate { migrate_command } => {
             migration::handle_command(migrate_command, &config).await
         }
+
+        Commands::Sessions { session_command } => {
+            sessions::handle_command(session_command, &config).await
+        }
+
+        Commands::Eval {
+            paths,
+            provider,
+            model,
+            skill,
+            judge_model,
+            junit,
+        } => {
+            let options = evals::RunOptions {
+                paths,
+                providers: provider,
+                models: model,
+                skills: skill,
+                judge_model,
+                junit,
+            };
+            evals::run_command(&config, options).await
+        }
+
+        Commands::Config { config_command } => match config_command {
+            ConfigCommands::Validate => {
+                let warnings = config.validate();
+                if warnings.is_empty() {
+                    println!("✅ {} looks good", config.config_path.display());
+                    return Ok(());
+                }
+                for warning in &warnings {
+                    println!("⚠️  {warning}");
+                }
+                bail!(
+                    "{} warning(s) in {}",
+                    warnings.len(),
+                    config.config_path.display()
+                )
+            }
+            ConfigCommands::Schema => {
+                let schema = config::validate::json_schema();
+                println!("{}", serde_json::to_string_pretty(&schema)?);
+                Ok(())
+            }
+        },
     }
 }
 
+#[cfg(windows)]
+async fn run_daemon_service(config: Config, host: String, port: u16) -> Result<()> {
+    let runtime = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        daemon::service::run_windows_service(runtime, config, host, port)
+    })
+    .await?
+}
+
+#[cfg(not(windows))]
+#[allow(clippy::unused_async)]
+async fn run_daemon_service(_config: Config, _host: String, _port: u16) -> Result<()> {
+    bail!(
+        "--service is only for the Windows service manager; systemd and launchd run `zeroclaw daemon` directly"
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
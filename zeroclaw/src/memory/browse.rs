@@ -0,0 +1,259 @@
+//! Browsing what the agent remembers.
+//!
+//! `GET /memory` and `zc_memory_browse` list a memory store page by page,
+//! filtered by category, key prefix and date, so a UI can show users what
+//! is kept about them; `DELETE /memory` forgets everything a filter matches.
+//! Each namespace (`[memory] namespace`) is its own store, opened by name.
+
+use super::{create_memory, Memory, MemoryCategory, MemoryEntry};
+use crate::config::MemoryConfig;
+use crate::sessions::dataset::parse_date;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Entries per page unless `limit` says otherwise.
+pub const DEFAULT_PAGE: usize = 50;
+
+/// Most entries one page may hold.
+pub const MAX_PAGE: usize = 500;
+
+/// Which memories to list. Unset fields let every entry through.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BrowseFilter {
+    /// "core", "daily", "conversation" or a custom category
+    pub category: Option<String>,
+    /// Namespace to browse instead of the agent's own memories
+    pub namespace: Option<String>,
+    /// Stored on or after: a date (`2026-01-31`) or RFC 3339 timestamp
+    pub since: Option<String>,
+    /// Stored before
+    pub until: Option<String>,
+    pub key_prefix: Option<String>,
+    /// Matching entries to skip, for the pages before this one
+    pub offset: usize,
+    /// Page size; 0 or unset = [`DEFAULT_PAGE`], at most [`MAX_PAGE`]
+    pub limit: Option<usize>,
+}
+
+impl BrowseFilter {
+    /// Whether no field narrows the selection (paging aside).
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.key_prefix.as_deref().is_none_or(str::is_empty)
+    }
+
+    /// Reject dates that cannot be read, before any store is opened.
+    pub fn check(&self) -> Result<()> {
+        for date in [&self.since, &self.until].into_iter().flatten() {
+            parse_date(date)?;
+        }
+        Ok(())
+    }
+
+    fn page_size(&self) -> usize {
+        match self.limit {
+            None | Some(0) => DEFAULT_PAGE,
+            Some(limit) => limit.min(MAX_PAGE),
+        }
+    }
+
+    fn category(&self) -> Option<MemoryCategory> {
+        self.category.as_deref().map(|name| match name {
+            "core" => MemoryCategory::Core,
+            "daily" => MemoryCategory::Daily,
+            "conversation" => MemoryCategory::Conversation,
+            other => MemoryCategory::Custom(other.to_string()),
+        })
+    }
+
+    /// Entries of `mem` that match, newest first as the backend lists them.
+    async fn matching(&self, mem: &dyn Memory) -> Result<Vec<MemoryEntry>> {
+        let since = self.since.as_deref().map(parse_date).transpose()?;
+        let until = self.until.as_deref().map(parse_date).transpose()?;
+        let prefix = self.key_prefix.as_deref().unwrap_or_default();
+        let mut entries = mem.list(self.category().as_ref()).await?;
+        entries.retain(|entry| {
+            let time = stored_at(&entry.timestamp);
+            entry.key.starts_with(prefix)
+                && since.is_none_or(|since| time.is_some_and(|t| t >= since))
+                && until.is_none_or(|until| time.is_some_and(|t| t < until))
+        });
+        Ok(entries)
+    }
+}
+
+/// When an entry was stored: its RFC 3339 timestamp, or the day that
+/// starts it (markdown entries are stamped with their file's date).
+fn stored_at(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| parse_date(timestamp.get(..10)?).ok())
+}
+
+/// One page of matching memories.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryPage {
+    pub namespace: Option<String>,
+    /// Entries matching the filter, across all pages
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    /// `offset` of the next page; None on the last one
+    pub next_offset: Option<usize>,
+    pub entries: Vec<MemoryEntry>,
+}
+
+/// The page of `mem` that `filter` selects.
+pub async fn browse(mem: &dyn Memory, filter: &BrowseFilter) -> Result<MemoryPage> {
+    let entries = filter.matching(mem).await?;
+    let total = entries.len();
+    let limit = filter.page_size();
+    let end = filter.offset.saturating_add(limit).min(total);
+    Ok(MemoryPage {
+        namespace: filter.namespace.clone(),
+        total,
+        offset: filter.offset,
+        limit,
+        next_offset: (end < total).then_some(end),
+        entries: entries
+            .into_iter()
+            .skip(filter.offset)
+            .take(limit)
+            .collect(),
+    })
+}
+
+/// Forget every entry of `mem` that `filter` matches, ignoring paging.
+/// Returns the keys removed.
+pub async fn forget_matching(mem: &dyn Memory, filter: &BrowseFilter) -> Result<Vec<String>> {
+    let mut removed = Vec::new();
+    for entry in filter.matching(mem).await? {
+        if mem.forget(&entry.key).await? {
+            removed.push(entry.key);
+        }
+    }
+    Ok(removed)
+}
+
+/// The store for `namespace` in `workspace_dir`, with `config`'s backend.
+/// Embeddings are not needed to list or forget, so none are set up.
+pub fn open_namespace(
+    config: &MemoryConfig,
+    workspace_dir: &Path,
+    namespace: &str,
+) -> Result<Box<dyn Memory>> {
+    let config = MemoryConfig {
+        namespace: Some(namespace.to_string()),
+        embedding_provider: "none".into(),
+        ..config.clone()
+    };
+    create_memory(&config, workspace_dir, None)
+}
+
+/// Names of the namespaces that hold memories in `workspace_dir`.
+pub fn namespaces(workspace_dir: &Path) -> Vec<String> {
+    let Ok(dir) = std::fs::read_dir(workspace_dir.join("namespaces")) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = dir
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+
+    #[tokio::test]
+    async fn pages_follow_filters() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        for i in 0..5 {
+            mem.store(&format!("pref:{i}"), "likes tea", MemoryCategory::Core)
+                .await
+                .unwrap();
+        }
+        mem.store("user_msg_1", "hello", MemoryCategory::Conversation)
+            .await
+            .unwrap();
+
+        let filter = BrowseFilter {
+            key_prefix: Some("pref:".into()),
+            limit: Some(2),
+            ..BrowseFilter::default()
+        };
+        let first = browse(&mem, &filter).await.unwrap();
+        assert_eq!((first.total, first.entries.len()), (5, 2));
+        assert_eq!(first.next_offset, Some(2));
+        let last = browse(
+            &mem,
+            &BrowseFilter {
+                offset: 4,
+                ..filter.clone()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!((last.entries.len(), last.next_offset), (1, None));
+
+        let conversation = BrowseFilter {
+            category: Some("conversation".into()),
+            ..BrowseFilter::default()
+        };
+        assert_eq!(browse(&mem, &conversation).await.unwrap().total, 1);
+        let future = BrowseFilter {
+            since: Some("2999-01-01".into()),
+            ..BrowseFilter::default()
+        };
+        assert_eq!(browse(&mem, &future).await.unwrap().total, 0);
+        let bad_date = BrowseFilter {
+            until: Some("soon".into()),
+            ..BrowseFilter::default()
+        };
+        assert!(bad_date.check().is_err());
+        assert!(browse(&mem, &bad_date).await.is_err());
+
+        assert!(BrowseFilter::default().is_empty());
+        assert_eq!(
+            forget_matching(&mem, &filter).await.unwrap().len(),
+            5,
+            "paging does not limit deletion"
+        );
+        assert_eq!(mem.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn namespaces_are_browsed_by_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = MemoryConfig {
+            backend: "sqlite".into(),
+            ..MemoryConfig::default()
+        };
+        let work = open_namespace(&config, tmp.path(), "work").unwrap();
+        work.store("k", "in the work namespace", MemoryCategory::Core)
+            .await
+            .unwrap();
+        assert_eq!(namespaces(tmp.path()), ["work"]);
+        assert!(open_namespace(&config, tmp.path(), "../x").is_err());
+        let page = browse(
+            open_namespace(&config, tmp.path(), "work")
+                .unwrap()
+                .as_ref(),
+            &BrowseFilter::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.entries[0].content, "in the work namespace");
+    }
+}
@@ -0,0 +1,175 @@
+//! Deduplication of auto-saved memories.
+//!
+//! Auto-save stores every user message and a summary of every answer, so
+//! the same fact arrives again and again. Before an auto-save is stored, the
+//! memories recall finds for it are compared with it: identical text (after
+//! normalizing case, punctuation and spacing) is dropped, and text whose
+//! shingles (consecutive word pairs) overlap by `[memory.dedup] similarity` or
+//! more counts as the same fact, merged as `merge` says.
+
+use super::{Memory, MemoryCategory};
+use crate::config::{DedupMerge, MemoryDedupConfig};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+/// Memories compared against each auto-save.
+const CANDIDATES: usize = 5;
+
+/// Words per shingle. Pairs keep a one-word edit in a short fact above
+/// the default similarity.
+const SHINGLE: usize = 2;
+
+/// What [`store`] did with an auto-save.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Saved {
+    /// Stored under its own key
+    New(String),
+    /// Already stored, word for word, under this key
+    Duplicate(String),
+    /// A near duplicate of this key: its text now replaces the old one
+    Replaced(String),
+    /// A near duplicate of this key, which was kept instead
+    Kept(String),
+}
+
+/// Lowercased words of `text`, punctuation dropped.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Hash of `text` with case, punctuation and spacing normalized away.
+pub fn content_hash(text: &str) -> String {
+    let digest = Sha256::digest(words(text).join(" ").as_bytes());
+    hex::encode(digest)
+}
+
+/// Runs of [`SHINGLE`] consecutive words; shorter texts are one shingle.
+fn shingles(text: &str) -> HashSet<String> {
+    let words = words(text);
+    if words.len() <= SHINGLE {
+        return std::iter::once(words.join(" "))
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+    words.windows(SHINGLE).map(|w| w.join(" ")).collect()
+}
+
+/// Jaccard overlap of the two texts' shingles, 0.0–1.0.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (shingles(a), shingles(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let overlap = a.intersection(&b).count() as f64 / union as f64;
+    overlap
+}
+
+/// Store an auto-save of `content` under `key`, unless `mem` already holds
+/// it: see the module docs. With dedup disabled it is always stored.
+pub async fn store(
+    mem: &dyn Memory,
+    key: &str,
+    content: &str,
+    category: MemoryCategory,
+    config: &MemoryDedupConfig,
+) -> anyhow::Result<Saved> {
+    if config.enabled {
+        let hash = content_hash(content);
+        let candidates = mem.recall(content, CANDIDATES).await.unwrap_or_default();
+        let mut closest: Option<(f64, String)> = None;
+        for entry in candidates.into_iter().filter(|e| e.category == category) {
+            if content_hash(&entry.content) == hash {
+                return Ok(Saved::Duplicate(entry.key));
+            }
+            let score = similarity(content, &entry.content);
+            if score >= config.similarity && closest.as_ref().is_none_or(|(best, _)| score > *best)
+            {
+                closest = Some((score, entry.key));
+            }
+        }
+        if let Some((_, existing)) = closest {
+            return match config.merge {
+                DedupMerge::Replace => {
+                    mem.store(&existing, content, category).await?;
+                    Ok(Saved::Replaced(existing))
+                }
+                DedupMerge::Keep => Ok(Saved::Kept(existing)),
+            };
+        }
+    }
+    mem.store(key, content, category).await?;
+    Ok(Saved::New(key.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+
+    #[test]
+    fn near_duplicates_share_most_shingles() {
+        assert_eq!(
+            content_hash("My name is Paul."),
+            content_hash("my name  is paul")
+        );
+        let old = "The staging database runs on port 5432 behind the VPN";
+        assert!(similarity(old, "The staging database runs on port 5433 behind the VPN") > 0.6);
+        assert!(similarity(old, "Lunch is at noon on Fridays") < 0.1);
+        assert!((similarity("hello one", "hello one") - 1.0).abs() < 1e-9);
+        assert!(similarity("hello one", "hello two") < 0.1);
+    }
+
+    #[tokio::test]
+    async fn duplicates_are_dropped_and_updates_merged() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let config = MemoryDedupConfig::default();
+        let fact = "The deploy script lives in tools/deploy.sh and needs the prod profile";
+        let save = |key: &'static str, content: &'static str, config: MemoryDedupConfig| {
+            let mem = &mem;
+            async move {
+                store(mem, key, content, MemoryCategory::Conversation, &config)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        assert_eq!(
+            save("a", fact, config.clone()).await,
+            Saved::New("a".into())
+        );
+        assert_eq!(
+            save(
+                "b",
+                "the deploy script lives in tools/deploy.sh, and needs the PROD profile!",
+                config.clone()
+            )
+            .await,
+            Saved::Duplicate("a".into())
+        );
+        let updated = "The deploy script lives in tools/deploy.sh and needs the staging profile";
+        let keep = MemoryDedupConfig {
+            merge: DedupMerge::Keep,
+            ..config.clone()
+        };
+        assert_eq!(save("c", updated, keep).await, Saved::Kept("a".into()));
+        assert_eq!(
+            save("d", updated, config.clone()).await,
+            Saved::Replaced("a".into())
+        );
+        assert_eq!(mem.count().await.unwrap(), 1);
+        assert_eq!(mem.get("a").await.unwrap().unwrap().content, updated);
+
+        let off = MemoryDedupConfig {
+            enabled: false,
+            ..config
+        };
+        assert_eq!(save("e", updated, off).await, Saved::New("e".into()));
+        assert_eq!(mem.count().await.unwrap(), 2);
+    }
+}
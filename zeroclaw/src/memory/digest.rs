@@ -0,0 +1,283 @@
+//! Daily digests of conversation memory.
+//!
+//! With `[digest]` enabled, the daemon summarizes each day's `conversation`
+//! and `daily` memories once that day is over and the configured hour of
+//! the next has passed, and stores the summary as a `daily` memory keyed
+//! `digest_<YYYY-MM-DD>`: the journal entry the `daily` category is named
+//! for. The digest can also be posted to a channel. Only completed days are
+//! summarized, so nothing said late in the day is left out, and a day that
+//! already has a digest is never summarized again.
+//! With `[memory.isolation] per_user`, each sender's namespace gets its own
+//! digest, stored in their memories, next to the shared pool's.
+
+use super::{Memory, MemoryCategory, MemoryEntry};
+use crate::config::{Config, MemoryConfig};
+use crate::providers::{self, ChatMessage, Provider};
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate, Timelike};
+use std::fmt::Write;
+
+const KEY_PREFIX: &str = "digest_";
+
+/// Entries per digest; the earliest of a busier day are kept
+const MAX_ENTRIES: usize = 200;
+const ENTRY_MAX_CHARS: usize = 400;
+
+const DIGEST_PROMPT: &str = "You write a short daily journal entry from an assistant's \
+memories of one day. Summarize what the user asked for, what was decided or learned, and \
+anything left open, as a few bullet points. Do not invent details. Output only the bullets.";
+
+/// Memory key of the digest for `date`.
+pub fn digest_key(date: NaiveDate) -> String {
+    format!("{KEY_PREFIX}{date}")
+}
+
+/// The entries that go into the digest of `date`: conversation and daily
+/// memories stored that day, oldest first, without earlier digests.
+pub fn entries_for_day(entries: Vec<MemoryEntry>, date: NaiveDate) -> Vec<MemoryEntry> {
+    let day = date.to_string();
+    let mut entries: Vec<MemoryEntry> = entries
+        .into_iter()
+        .filter(|e| {
+            matches!(
+                e.category,
+                MemoryCategory::Conversation | MemoryCategory::Daily
+            )
+        })
+        .filter(|e| e.timestamp.starts_with(&day))
+        // Markdown memory keeps the key in the content: "**digest_...**: ..."
+        .filter(|e| {
+            !e.key.starts_with(KEY_PREFIX) && !e.content.starts_with(&format!("**{KEY_PREFIX}"))
+        })
+        .collect();
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    entries.truncate(MAX_ENTRIES);
+    entries
+}
+
+/// Summarize `date` with `model` and store the digest. `None` when the day
+/// has no memories or already has a digest.
+pub async fn write_digest(
+    mem: &dyn Memory,
+    provider: &dyn Provider,
+    model: &str,
+    date: NaiveDate,
+) -> Result<Option<String>> {
+    let key = digest_key(date);
+    if mem.get(&key).await?.is_some() {
+        return Ok(None);
+    }
+    let mut entries = mem.list(Some(&MemoryCategory::Conversation)).await?;
+    entries.extend(mem.list(Some(&MemoryCategory::Daily)).await?);
+    let entries = entries_for_day(entries, date);
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let mut notes = String::new();
+    for entry in &entries {
+        let _ = writeln!(
+            notes,
+            "- {}",
+            truncate_with_ellipsis(&entry.content, ENTRY_MAX_CHARS)
+        );
+    }
+    let messages = [
+        ChatMessage::system(DIGEST_PROMPT),
+        ChatMessage::user(format!("Memories from {date}:\n{notes}")),
+    ];
+    let summary = provider.chat_with_history(&messages, model, 0.2).await?;
+    let summary = summary.trim();
+    if summary.is_empty() {
+        anyhow::bail!("The model returned an empty digest for {date}");
+    }
+
+    let digest = format!("Digest for {date}:\n{summary}");
+    mem.store(&key, &digest, MemoryCategory::Daily).await?;
+    Ok(Some(digest))
+}
+
+/// Post `digest` to `[digest] channel`, when one is set.
+async fn post(config: &Config, namespace: Option<&str>, digest: &str) {
+    let Some(name) = config.digest.channel.as_deref() else {
+        return;
+    };
+    let Some(recipient) = config.digest.recipient.as_deref() else {
+        tracing::warn!("[digest] channel is set without a recipient; digest not posted");
+        return;
+    };
+    let Some(channel) = crate::channels::configured_channel(config, name) else {
+        tracing::warn!("[digest] channel {name} is not configured; digest not posted");
+        return;
+    };
+    let digest = match namespace {
+        Some(namespace) => format!("[{namespace}] {digest}"),
+        None => digest.to_string(),
+    };
+    if let Err(e) = channel.send(&digest, recipient).await {
+        tracing::warn!("Could not post the digest to {name}: {e}");
+    }
+}
+
+/// The day whose digest is due at `now`: yesterday, once `hour` has passed.
+fn due_day(now: DateTime<Local>, hour: u32) -> Option<NaiveDate> {
+    (now.hour() >= hour).then(|| now.date_naive() - Duration::days(1))
+}
+
+/// Scheduled digest loop, supervised by the daemon. Checks at the top of
+/// every hour whether yesterday's digest is due.
+pub async fn run_worker(config: Config) -> Result<()> {
+    let provider = providers::create_resilient_provider(
+        config.default_provider.as_deref().unwrap_or("openrouter"),
+        config.api_key.as_deref(),
+        &config.reliability,
+        &providers::ProviderSettings::from_config(&config),
+    )?;
+    let model = config
+        .digest
+        .model
+        .as_deref()
+        .or(config.default_model.as_deref())
+        .unwrap_or("anthropic/claude-sonnet-4-20250514");
+    let hour = config.digest.hour.min(23);
+
+    loop {
+        let now = Local::now();
+        let days: Vec<NaiveDate> = due_day(now, hour).into_iter().collect();
+        // The shared pool, then every sender's own
+        let namespaces = std::iter::once(None).chain(
+            super::user_namespaces(&config.memory, &config.workspace_dir)
+                .into_iter()
+                .map(Some),
+        );
+        for namespace in namespaces {
+            let memory_config = MemoryConfig {
+                namespace: namespace.clone().or_else(|| config.memory.namespace.clone()),
+                ..config.memory.clone()
+            };
+            let mem = match super::create_memory(
+                &memory_config,
+                &config.workspace_dir,
+                config.api_key.as_deref(),
+            ) {
+                Ok(mem) => mem,
+                Err(e) => {
+                    crate::health::mark_component_error("digest", e.to_string());
+                    tracing::warn!("Daily digest could not open memory: {e}");
+                    continue;
+                }
+            };
+            for &date in &days {
+                match write_digest(mem.as_ref(), provider.as_ref(), model, date).await {
+                    Ok(Some(digest)) => {
+                        crate::health::mark_component_ok("digest");
+                        tracing::info!("Wrote the digest for {date}");
+                        post(&config, namespace.as_deref(), &digest).await;
+                    }
+                    Ok(None) => crate::health::mark_component_ok("digest"),
+                    Err(e) => {
+                        crate::health::mark_component_error("digest", e.to_string());
+                        tracing::warn!("Daily digest failed: {e}");
+                    }
+                }
+            }
+        }
+
+        let into_hour = u64::from(now.minute() * 60 + now.second());
+        tokio::time::sleep(std::time::Duration::from_secs(3600 - into_hour.min(3599))).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+    use crate::memory::SqliteMemory;
+    use chrono::TimeZone;
+
+    fn entry(key: &str, category: MemoryCategory, timestamp: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: format!("{key} content"),
+            category,
+            timestamp: timestamp.into(),
+            session_id: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn day_entries_skip_other_days_core_and_digests() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+        let entries = entries_for_day(
+            vec![
+                entry("late", MemoryCategory::Daily, "2026-03-14T22:00:00+01:00"),
+                entry(
+                    "early",
+                    MemoryCategory::Conversation,
+                    "2026-03-14T08:00:00+01:00",
+                ),
+                entry(
+                    "before",
+                    MemoryCategory::Conversation,
+                    "2026-03-13T23:59:00+01:00",
+                ),
+                entry("fact", MemoryCategory::Core, "2026-03-14T09:00:00+01:00"),
+                entry(
+                    "digest_2026-03-13",
+                    MemoryCategory::Daily,
+                    "2026-03-14T00:00:00+01:00",
+                ),
+                entry("log", MemoryCategory::Daily, "2026-03-14"),
+            ],
+            date,
+        );
+        let keys: Vec<&str> = entries.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, ["log", "early", "late"]);
+    }
+
+    #[test]
+    fn only_yesterday_is_due_once_the_hour_has_passed() {
+        let at = |hour| Local.with_ymd_and_hms(2026, 3, 15, hour, 0, 0).unwrap();
+        assert_eq!(due_day(at(6), 7), None);
+        assert_eq!(due_day(at(7), 7), NaiveDate::from_ymd_opt(2026, 3, 14));
+        assert_eq!(due_day(at(23), 7), NaiveDate::from_ymd_opt(2026, 3, 14));
+    }
+
+    #[tokio::test]
+    async fn digest_is_written_once_per_day() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store(
+            "telegram_1",
+            "Book flights to Lisbon",
+            MemoryCategory::Conversation,
+        )
+        .await
+        .unwrap();
+        let today = Local::now().date_naive();
+        let provider = ScriptedProvider::always("- Planned the trip to Lisbon");
+
+        let digest = write_digest(&mem, &provider, "m", today).await.unwrap();
+        assert_eq!(
+            digest.as_deref(),
+            Some(format!("Digest for {today}:\n- Planned the trip to Lisbon").as_str())
+        );
+        let prompt = provider.requests().pop().unwrap();
+        assert!(prompt.last().contains("Book flights to Lisbon"));
+        let stored = mem.get(&digest_key(today)).await.unwrap().unwrap();
+        assert_eq!(stored.category, MemoryCategory::Daily);
+
+        assert!(write_digest(&mem, &provider, "m", today)
+            .await
+            .unwrap()
+            .is_none());
+        let yesterday = today - Duration::days(1);
+        assert!(write_digest(&mem, &provider, "m", yesterday)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
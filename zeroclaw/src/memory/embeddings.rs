@@ -1,3 +1,6 @@
+use crate::providers::ollama::OllamaProvider;
+use crate::providers::openrouter::OpenRouterProvider;
+use crate::providers::Provider;
 use async_trait::async_trait;
 
 /// Trait for embedding providers — convert text to vectors
@@ -123,6 +126,52 @@ impl EmbeddingProvider for OpenAiEmbedding {
     }
 }
 
+// ── Chat provider embeddings ─────────────────────────────────
+
+/// Embeds through a chat provider's [`Provider::embed`] (`OpenRouter`, Ollama).
+pub struct ProviderEmbedding {
+    name: String,
+    provider: Box<dyn Provider>,
+    model: String,
+    dims: usize,
+}
+
+impl ProviderEmbedding {
+    pub fn new(name: &str, provider: Box<dyn Provider>, model: &str, dims: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            provider,
+            model: model.to_string(),
+            dims,
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for ProviderEmbedding {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let embeddings = self.provider.embed(texts, &self.model).await?;
+        tracing::debug!(
+            provider = self.name.as_str(),
+            texts = texts.len(),
+            tokens = embeddings.tokens,
+            "embeddings"
+        );
+        Ok(embeddings.vectors)
+    }
+}
+
 // ── Factory ──────────────────────────────────────────────────
 
 pub fn create_embedding_provider(
@@ -141,6 +190,21 @@ pub fn create_embedding_provider(
                 dims,
             ))
         }
+        "openrouter" => Box::new(ProviderEmbedding::new(
+            "openrouter",
+            Box::new(OpenRouterProvider::new(api_key)),
+            model,
+            dims,
+        )),
+        name if name == "ollama" || name.starts_with("ollama:") => {
+            let base_url = name.strip_prefix("ollama:");
+            Box::new(ProviderEmbedding::new(
+                "ollama",
+                Box::new(OllamaProvider::new(base_url)),
+                model,
+                dims,
+            ))
+        }
         name if name.starts_with("custom:") => {
             let base_url = name.strip_prefix("custom:").unwrap_or("");
             let key = api_key.unwrap_or("");
@@ -181,6 +245,26 @@ mod tests {
         assert_eq!(p.dimensions(), 1536);
     }
 
+    #[test]
+    fn factory_chat_providers() {
+        let p = create_embedding_provider("ollama", None, "nomic-embed-text", 768);
+        assert_eq!(p.name(), "ollama");
+        assert_eq!(p.dimensions(), 768);
+        let p = create_embedding_provider("ollama:http://gpu-box:11434", None, "m", 768);
+        assert_eq!(p.name(), "ollama");
+        let p = create_embedding_provider("openrouter", Some("key"), "m", 1536);
+        assert_eq!(p.name(), "openrouter");
+    }
+
+    #[tokio::test]
+    async fn provider_embedding_surfaces_unsupported_providers() {
+        let chat_only = crate::providers::mock::ScriptedProvider::always("");
+        let p = ProviderEmbedding::new("chat-only", Box::new(chat_only), "m", 8);
+        assert!(p.embed(&[]).await.unwrap().is_empty());
+        let err = p.embed(&["hello"]).await.unwrap_err();
+        assert!(err.to_string().contains("does not support embeddings"));
+    }
+
     #[test]
     fn factory_custom_url() {
         let p = create_embedding_provider("custom:http://localhost:1234", None, "model", 768);
@@ -0,0 +1,239 @@
+//! Fact extraction from finished turns.
+//!
+//! With `[memory.extract] enabled`, each exchange is shown to a model, ideally
+//! a cheap one, once the answer is out. It lists the people, preferences,
+//! deadlines and decisions worth remembering, and each becomes a typed memory:
+//! category `person`, `preference`, `deadline` or `decision`, key
+//! `<kind>:<subject>`. A later fact about the same subject therefore replaces
+//! the earlier one. Extracted facts take the place of auto-saving raw
+//! messages.
+
+use super::{dedup, Memory, MemoryCategory};
+use crate::config::MemoryConfig;
+use crate::providers::Provider;
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Most facts kept from one exchange.
+const MAX_FACTS: usize = 10;
+
+/// Longest subject slug in a fact's key.
+const MAX_SUBJECT: usize = 48;
+
+const EXTRACT_PROMPT: &str = "You extract facts worth remembering from one exchange \
+between a user and an AI assistant. Only keep what will still matter in later \
+conversations: people (who someone is, their role), the user's preferences, deadlines, \
+and decisions that were made. Skip small talk, questions, and anything only about this \
+exchange. Reply with a JSON array and nothing else, e.g.\n\
+[{\"type\": \"preference\", \"subject\": \"editor\", \"fact\": \"The user prefers Helix over Vim\"},\n \
+{\"type\": \"deadline\", \"subject\": \"tax return\", \"fact\": \"The tax return is due\", \"due\": \"2026-05-01\"}]\n\
+type is one of person, preference, deadline, decision. subject names what the fact is \
+about in a few words. Reply with [] when there is nothing to keep.";
+
+/// What a fact is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FactKind {
+    Person,
+    Preference,
+    Deadline,
+    Decision,
+}
+
+impl fmt::Display for FactKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Person => "person",
+            Self::Preference => "preference",
+            Self::Deadline => "deadline",
+            Self::Decision => "decision",
+        })
+    }
+}
+
+/// One extracted fact.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Fact {
+    #[serde(rename = "type")]
+    pub kind: FactKind,
+    pub subject: String,
+    pub fact: String,
+    /// When a deadline is due, as the model wrote it
+    #[serde(default)]
+    pub due: Option<String>,
+}
+
+impl Fact {
+    /// `<kind>:<subject>`, with the subject lowercased and hyphenated.
+    pub fn key(&self) -> String {
+        let slug: Vec<String> = self
+            .subject
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        let slug: String = slug.join("-").chars().take(MAX_SUBJECT).collect();
+        format!("{}:{slug}", self.kind)
+    }
+
+    /// The memory text: the fact, with its due date when it has one.
+    pub fn content(&self) -> String {
+        match self.due.as_deref().map(str::trim) {
+            Some(due) if !due.is_empty() => format!("{} (due {due})", self.fact.trim()),
+            _ => self.fact.trim().to_string(),
+        }
+    }
+
+    pub fn category(&self) -> MemoryCategory {
+        MemoryCategory::Custom(self.kind.to_string())
+    }
+}
+
+/// Facts in a model reply: a JSON array, or an object with a `facts`
+/// array. Malformed or empty entries are skipped.
+pub fn parse(reply: &str) -> Vec<Fact> {
+    let items = match crate::evals::extract_json(reply) {
+        Some(Value::Array(items)) => items,
+        Some(Value::Object(mut object)) => match object.remove("facts") {
+            Some(Value::Array(items)) => items,
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    items
+        .into_iter()
+        .filter_map(|item| serde_json::from_value::<Fact>(item).ok())
+        .filter(|fact| !fact.fact.trim().is_empty() && !fact.key().ends_with(':'))
+        .take(MAX_FACTS)
+        .collect()
+}
+
+/// Ask `model` for the facts in one exchange.
+pub async fn extract(
+    provider: &dyn Provider,
+    model: &str,
+    user: &str,
+    answer: &str,
+) -> Result<Vec<Fact>> {
+    let exchange = format!("User:\n{user}\n\nAssistant:\n{answer}");
+    let reply = provider
+        .chat_with_system(Some(EXTRACT_PROMPT), &exchange, model, 0.0)
+        .await?;
+    Ok(parse(&reply))
+}
+
+/// The post-turn pass: store the facts in this exchange as typed memories,
+/// through `[memory.dedup]`. Returns how many were new or updated; failures
+/// are logged, as they must not fail the turn.
+pub async fn after_turn(
+    provider: &dyn Provider,
+    mem: &dyn Memory,
+    config: &MemoryConfig,
+    default_model: &str,
+    user: &str,
+    answer: &str,
+) -> usize {
+    if !config.extract.enabled {
+        return 0;
+    }
+    let model = config.extract.model.as_deref().unwrap_or(default_model);
+    let facts = match extract(provider, model, user, answer).await {
+        Ok(facts) => facts,
+        Err(e) => {
+            tracing::warn!(
+                "Fact extraction failed: {}",
+                crate::providers::sanitize_api_error(&e.to_string())
+            );
+            return 0;
+        }
+    };
+    let mut stored = 0;
+    for fact in facts {
+        match dedup::store(
+            mem,
+            &fact.key(),
+            &fact.content(),
+            fact.category(),
+            &config.dedup,
+        )
+        .await
+        {
+            Ok(dedup::Saved::New(_) | dedup::Saved::Replaced(_)) => stored += 1,
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to store fact {}: {e}", fact.key()),
+        }
+    }
+    stored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MemoryExtractConfig;
+    use crate::memory::SqliteMemory;
+    use crate::providers::mock::ScriptedProvider;
+
+    #[test]
+    fn replies_become_typed_facts() {
+        let facts = parse(
+            "Here you go:\n```json\n[\
+             {\"type\": \"deadline\", \"subject\": \"Q3 Report!\", \"fact\": \"Q3 report due\", \"due\": \"2026-11-01\"},\
+             {\"type\": \"mood\", \"subject\": \"x\", \"fact\": \"unknown kind\"},\
+             {\"type\": \"person\", \"subject\": \"...\", \"fact\": \"no subject\"}\
+             ]\n```",
+        );
+        assert_eq!(facts.len(), 1);
+        assert_eq!(facts[0].key(), "deadline:q3-report");
+        assert_eq!(facts[0].content(), "Q3 report due (due 2026-11-01)");
+        assert_eq!(
+            facts[0].category(),
+            MemoryCategory::Custom("deadline".into())
+        );
+        assert!(parse("{\"facts\": []}").is_empty());
+        assert!(parse("Nothing worth keeping.").is_empty());
+    }
+
+    #[tokio::test]
+    async fn facts_replace_earlier_ones_about_the_same_subject() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let mut config = MemoryConfig {
+            extract: MemoryExtractConfig {
+                enabled: true,
+                model: Some("cheap-model".into()),
+            },
+            ..MemoryConfig::default()
+        };
+        let vim = ScriptedProvider::always(
+            r#"[{"type": "preference", "subject": "editor", "fact": "The user prefers Vim"}]"#,
+        );
+        let helix = ScriptedProvider::always(
+            r#"[{"type": "preference", "subject": "Editor", "fact": "The user switched to Helix"}]"#,
+        );
+
+        assert_eq!(
+            after_turn(&vim, &mem, &config, "m", "I use vim", "Noted").await,
+            1
+        );
+        assert_eq!(
+            after_turn(&helix, &mem, &config, "m", "Helix now", "OK").await,
+            1
+        );
+        let request = helix.requests().pop().unwrap();
+        assert_eq!(request.model, "cheap-model");
+        assert_eq!(request.messages[0].content, EXTRACT_PROMPT);
+        assert!(request.last().starts_with("User:\n"));
+        let entry = mem.get("preference:editor").await.unwrap().unwrap();
+        assert_eq!(entry.content, "The user switched to Helix");
+        assert_eq!(mem.count().await.unwrap(), 1);
+
+        config.extract.enabled = false;
+        assert_eq!(
+            after_turn(&vim, &mem, &config, "m", "I use vim", "").await,
+            0
+        );
+        assert_eq!(vim.calls(), 1);
+    }
+}
@@ -0,0 +1,304 @@
+//! Knowledge graph memory.
+//!
+//! Vector and keyword recall find memories that read like the message, but
+//! not who is connected to what. With `[memory.graph] enabled`, the agent
+//! records entities and the relations between them through the
+//! `memory_graph` tool, in `memory/graph.db` next to the other memories.
+//! Each message is then searched for entity names: [`KnowledgeGraph::graph_query`]
+//! follows relations out from the ones it mentions, `depth` hops deep, and
+//! what it finds goes into a `[Knowledge graph]` context block.
+
+use super::memory_root;
+use crate::config::{MemoryConfig, MemoryGraphConfig};
+use anyhow::Result;
+use chrono::Local;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// One edge: `source` —`relation`→ `target`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Relation {
+    pub source: String,
+    pub relation: String,
+    pub target: String,
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} —{}→ {}", self.source, self.relation, self.target)
+    }
+}
+
+/// Entities and relations, stored in sqlite.
+pub struct KnowledgeGraph {
+    conn: Mutex<Connection>,
+}
+
+/// Lowercased words of `text`, punctuation dropped, joined by spaces.
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl KnowledgeGraph {
+    /// The graph in `<dir>/memory/graph.db`, created when missing.
+    pub fn open(dir: &Path) -> Result<Self> {
+        let db_path = dir.join("memory").join("graph.db");
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(&db_path)?;
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+            CREATE TABLE IF NOT EXISTS entities (
+                id          INTEGER PRIMARY KEY,
+                name        TEXT NOT NULL,
+                norm        TEXT NOT NULL UNIQUE,
+                created_at  TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS relations (
+                source      INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+                relation    TEXT NOT NULL,
+                target      INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+                updated_at  TEXT NOT NULL,
+                PRIMARY KEY (source, relation, target)
+            );
+            CREATE INDEX IF NOT EXISTS idx_relations_target ON relations(target);",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// The graph for `config`'s memories, which honours `namespace`.
+    pub fn for_config(config: &MemoryConfig, workspace_dir: &Path) -> Result<Self> {
+        Self::open(&memory_root(config, workspace_dir)?)
+    }
+
+    /// Id of the entity named `name` (any case or punctuation), added when new.
+    fn entity(conn: &Connection, name: &str) -> Result<i64> {
+        let norm = normalize(name);
+        if norm.is_empty() {
+            anyhow::bail!("Entity name {name:?} has no letters or digits");
+        }
+        conn.execute(
+            "INSERT OR IGNORE INTO entities (name, norm, created_at) VALUES (?1, ?2, ?3)",
+            params![name.trim(), norm, Local::now().to_rfc3339()],
+        )?;
+        Ok(conn.query_row(
+            "SELECT id FROM entities WHERE norm = ?1",
+            params![norm],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Record `source` —`relation`→ `target`, adding either entity when new.
+    pub fn relate(&self, source: &str, relation: &str, target: &str) -> Result<Relation> {
+        let relation = relation.trim();
+        if relation.is_empty() {
+            anyhow::bail!("Relation must not be empty");
+        }
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        let (source_id, target_id) = (Self::entity(&conn, source)?, Self::entity(&conn, target)?);
+        conn.execute(
+            "INSERT OR REPLACE INTO relations (source, relation, target, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![source_id, relation, target_id, Local::now().to_rfc3339()],
+        )?;
+        Ok(Relation {
+            source: source.trim().to_string(),
+            relation: relation.to_string(),
+            target: target.trim().to_string(),
+        })
+    }
+
+    /// Remove a relation; returns whether it existed. Entities stay.
+    pub fn unrelate(&self, source: &str, relation: &str, target: &str) -> Result<bool> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        let removed = conn.execute(
+            "DELETE FROM relations WHERE relation = ?2
+               AND source = (SELECT id FROM entities WHERE norm = ?1)
+               AND target = (SELECT id FROM entities WHERE norm = ?3)",
+            params![normalize(source), relation.trim(), normalize(target)],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Ids of the entities whose whole name appears in `text`, as words:
+    /// "Al" is not found in "Alice".
+    fn mentioned(conn: &Connection, text: &str) -> Result<Vec<i64>> {
+        let text = format!(" {} ", normalize(text));
+        let mut stmt = conn.prepare("SELECT id, norm FROM entities ORDER BY length(norm) DESC")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut ids = Vec::new();
+        for row in rows {
+            let (id, norm) = row?;
+            if text.contains(&format!(" {norm} ")) {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Relations within `depth` hops of the entities `text` mentions,
+    /// nearest first and most recently recorded first within a hop; at most
+    /// `limit`.
+    pub fn graph_query(&self, text: &str, depth: usize, limit: usize) -> Result<Vec<Relation>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        let mut frontier = Self::mentioned(&conn, text)?;
+        let mut visited: HashSet<i64> = frontier.iter().copied().collect();
+        let mut seen = HashSet::new();
+        let mut found = Vec::new();
+        let mut stmt = conn.prepare(
+            "SELECT s.name, r.relation, t.name, r.source, r.target
+             FROM relations r
+             JOIN entities s ON s.id = r.source
+             JOIN entities t ON t.id = r.target
+             WHERE r.source = ?1 OR r.target = ?1
+             ORDER BY r.updated_at DESC",
+        )?;
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for id in frontier {
+                let rows = stmt.query_map(params![id], |row| {
+                    Ok((
+                        Relation {
+                            source: row.get(0)?,
+                            relation: row.get(1)?,
+                            target: row.get(2)?,
+                        },
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                    ))
+                })?;
+                for row in rows {
+                    let (relation, source, target) = row?;
+                    let other = if source == id { target } else { source };
+                    if visited.insert(other) {
+                        next.push(other);
+                    }
+                    if seen.insert(relation.clone()) {
+                        found.push(relation);
+                        if found.len() >= limit {
+                            return Ok(found);
+                        }
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        Ok(found)
+    }
+}
+
+/// `[Knowledge graph]` block for `relations`; empty when there are none.
+pub fn context_block(relations: &[Relation]) -> String {
+    if relations.is_empty() {
+        return String::new();
+    }
+    let mut context = String::from("[Knowledge graph]\n");
+    for relation in relations {
+        let _ = writeln!(context, "- {relation}");
+    }
+    context.push('\n');
+    context
+}
+
+/// [`context_block`] for what `[memory.graph]` finds around `user_msg`;
+/// empty when the graph is disabled or cannot be read.
+pub fn build_context(config: &MemoryConfig, workspace_dir: &Path, user_msg: &str) -> String {
+    let MemoryGraphConfig {
+        enabled,
+        depth,
+        max_relations,
+    } = config.graph;
+    if !enabled || depth == 0 || max_relations == 0 {
+        return String::new();
+    }
+    let relations = KnowledgeGraph::for_config(config, workspace_dir)
+        .and_then(|graph| graph.graph_query(user_msg, depth, max_relations));
+    match relations {
+        Ok(relations) => context_block(&relations),
+        Err(e) => {
+            tracing::warn!("Knowledge graph query failed: {e}");
+            String::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_expand_from_mentioned_entities() {
+        let tmp = tempfile::tempdir().unwrap();
+        let graph = KnowledgeGraph::open(tmp.path()).unwrap();
+        graph.relate("Alice", "works at", "Acme Labs").unwrap();
+        graph.relate("Acme Labs", "is owned by", "Globex").unwrap();
+        graph.relate("Globex", "is based in", "Berlin").unwrap();
+        graph.relate("Bob", "manages", "alice").unwrap();
+        graph.relate("Carol", "likes", "Jazz").unwrap();
+
+        let names = |relations: Vec<Relation>| -> Vec<String> {
+            relations.iter().map(ToString::to_string).collect()
+        };
+        let one_hop = graph.graph_query("Who is ALICE?", 1, 10).unwrap();
+        assert_eq!(
+            names(one_hop),
+            ["Bob —manages→ Alice", "Alice —works at→ Acme Labs"]
+        );
+        let two_hops = names(graph.graph_query("Where does Alice work?", 2, 10).unwrap());
+        assert_eq!(two_hops.len(), 3);
+        assert!(two_hops.contains(&"Acme Labs —is owned by→ Globex".to_string()));
+        assert_eq!(graph.graph_query("Alice", 3, 2).unwrap().len(), 2);
+        // Names match whole words only
+        assert!(graph
+            .graph_query("Malice and jazzy tunes", 2, 10)
+            .unwrap()
+            .is_empty());
+
+        assert!(graph.unrelate("bob", "manages", "Alice").unwrap());
+        assert!(!graph.unrelate("bob", "manages", "Alice").unwrap());
+        assert!(graph.graph_query("Bob", 2, 10).unwrap().is_empty());
+        assert!(graph.relate("!!", "knows", "Bob").is_err());
+    }
+
+    #[test]
+    fn context_is_built_only_when_enabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = MemoryConfig::default();
+        KnowledgeGraph::for_config(&config, tmp.path())
+            .unwrap()
+            .relate("Dana", "reports to", "Eve")
+            .unwrap();
+        assert!(build_context(&config, tmp.path(), "Ask Dana").is_empty());
+
+        config.graph.enabled = true;
+        assert_eq!(
+            build_context(&config, tmp.path(), "Ask Dana"),
+            "[Knowledge graph]\n- Dana —reports to→ Eve\n\n"
+        );
+        assert!(build_context(&config, tmp.path(), "Ask Frank").is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use super::recall::keyword_coverage;
 use super::traits::{Memory, MemoryCategory, MemoryEntry};
 use async_trait::async_trait;
 use chrono::Local;
@@ -154,20 +155,12 @@ impl Memory for MarkdownMemory {
 
     async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>> {
         let all = self.read_all_entries().await?;
-        let query_lower = query.to_lowercase();
-        let keywords: Vec<&str> = query_lower.split_whitespace().collect();
 
         let mut scored: Vec<MemoryEntry> = all
             .into_iter()
             .filter_map(|mut entry| {
-                let content_lower = entry.content.to_lowercase();
-                let matched = keywords
-                    .iter()
-                    .filter(|kw| content_lower.contains(**kw))
-                    .count();
-                if matched > 0 {
-                    #[allow(clippy::cast_precision_loss)]
-                    let score = matched as f64 / keywords.len() as f64;
+                let score = keyword_coverage(query, &entry.content);
+                if score > 0.0 {
                     entry.score = Some(score);
                     Some(entry)
                 } else {
@@ -1,8 +1,15 @@
+pub mod browse;
 pub mod chunker;
+pub mod dedup;
+pub mod digest;
 pub mod embeddings;
+pub mod extract;
+pub mod graph;
 pub mod hygiene;
 pub mod markdown;
+pub mod recall;
 pub mod sqlite;
+pub mod summary;
 pub mod traits;
 pub mod vector;
 
@@ -13,15 +20,98 @@ pub use traits::Memory;
 pub use traits::{MemoryCategory, MemoryEntry};
 
 use crate::config::MemoryConfig;
-use std::path::Path;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+/// Where memories for `config` live: the workspace itself, or
+/// `namespaces/<name>/` in it when a namespace is set.
+pub fn memory_root(config: &MemoryConfig, workspace_dir: &Path) -> anyhow::Result<PathBuf> {
+    let Some(namespace) = config.namespace.as_deref() else {
+        return Ok(workspace_dir.to_path_buf());
+    };
+    let valid = !namespace.is_empty()
+        && !namespace.starts_with('.')
+        && namespace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        anyhow::bail!(
+            "Invalid memory namespace \"{namespace}\": use letters, digits, '-', '_' or '.'"
+        );
+    }
+    Ok(workspace_dir.join("namespaces").join(namespace))
+}
+
+/// `text` with every character a namespace cannot hold, `_` included,
+/// written as `_xx` per byte, so distinct ids never share a namespace.
+fn escape_namespace(text: &str) -> String {
+    let mut escaped = String::new();
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.') {
+            escaped.push(char::from(byte));
+        } else {
+            let _ = write!(escaped, "_{byte:02x}");
+        }
+    }
+    escaped
+}
+
+/// Namespace of `sender`'s own memories on `channel` under
+/// `[memory.isolation]`: `<namespace or "user">.<channel>.<sender>`. None
+/// when the channel's users share one pool: on the CLI, on
+/// `shared_channels`, or with `per_user` off.
+pub fn user_namespace(config: &MemoryConfig, channel: &str, sender: &str) -> Option<String> {
+    let isolation = &config.isolation;
+    if !isolation.per_user
+        || channel == "cli"
+        || isolation.shared_channels.iter().any(|c| c == channel)
+    {
+        return None;
+    }
+    let base = config.namespace.as_deref().unwrap_or("user");
+    Some(format!(
+        "{base}.{}.{}",
+        escape_namespace(channel),
+        escape_namespace(sender)
+    ))
+}
+
+/// The per-sender namespaces [`user_namespace`] has created under
+/// `workspace_dir` so far, sorted.
+pub fn user_namespaces(config: &MemoryConfig, workspace_dir: &Path) -> Vec<String> {
+    let prefix = format!("{}.", config.namespace.as_deref().unwrap_or("user"));
+    let Ok(entries) = std::fs::read_dir(workspace_dir.join("namespaces")) else {
+        return Vec::new();
+    };
+    let mut namespaces: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with(&prefix))
+        .collect();
+    namespaces.sort();
+    namespaces
+}
+
+/// `config` narrowed to `sender`'s memories on `channel`; see
+/// [`user_namespace`].
+pub fn user_config(config: &MemoryConfig, channel: &str, sender: &str) -> MemoryConfig {
+    MemoryConfig {
+        namespace: user_namespace(config, channel, sender).or_else(|| config.namespace.clone()),
+        ..config.clone()
+    }
+}
+
 /// Factory: create the right memory backend from config
 pub fn create_memory(
     config: &MemoryConfig,
     workspace_dir: &Path,
     api_key: Option<&str>,
 ) -> anyhow::Result<Box<dyn Memory>> {
+    let root = memory_root(config, workspace_dir)?;
+    let workspace_dir = root.as_path();
+
     // Best-effort memory hygiene/retention pass (throttled by state file).
     if let Err(e) = hygiene::run_if_due(config, workspace_dir) {
         tracing::warn!("memory hygiene skipped: {e}");
@@ -71,6 +161,67 @@ mod tests {
         assert_eq!(mem.name(), "sqlite");
     }
 
+    #[test]
+    fn namespaces_keep_memories_apart() {
+        let tmp = TempDir::new().unwrap();
+        let mut cfg = MemoryConfig {
+            backend: "markdown".into(),
+            ..MemoryConfig::default()
+        };
+        assert_eq!(memory_root(&cfg, tmp.path()).unwrap(), tmp.path());
+
+        cfg.namespace = Some("work".into());
+        assert_eq!(
+            memory_root(&cfg, tmp.path()).unwrap(),
+            tmp.path().join("namespaces").join("work")
+        );
+        for bad in ["", "..", "../home", "a/b"] {
+            cfg.namespace = Some(bad.into());
+            assert!(memory_root(&cfg, tmp.path()).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn channel_users_get_their_own_namespace() {
+        let tmp = TempDir::new().unwrap();
+        let mut cfg = MemoryConfig::default();
+        let alice = user_config(&cfg, "telegram", "alice");
+        assert_eq!(alice.namespace.as_deref(), Some("user.telegram.alice"));
+        // Escaping keeps distinct senders apart and the namespace valid
+        let a = user_namespace(&cfg, "email", "a@b.com").unwrap();
+        let b = user_namespace(&cfg, "email", "a_40b.com").unwrap();
+        assert_ne!(a, b);
+        assert!(memory_root(&user_config(&cfg, "email", "../../etc"), tmp.path()).is_ok());
+        assert_eq!(user_namespace(&cfg, "cli", "user"), None);
+
+        cfg.namespace = Some("work".into());
+        cfg.isolation.shared_channels = vec!["slack".into()];
+        assert_eq!(
+            user_namespace(&cfg, "discord", "42").as_deref(),
+            Some("work.discord.42")
+        );
+        assert_eq!(
+            user_config(&cfg, "slack", "U1").namespace.as_deref(),
+            Some("work")
+        );
+        cfg.isolation.per_user = false;
+        assert_eq!(user_namespace(&cfg, "discord", "42"), None);
+    }
+
+    #[test]
+    fn user_namespaces_lists_the_senders_seen_so_far() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = MemoryConfig::default();
+        assert!(user_namespaces(&cfg, tmp.path()).is_empty());
+        for namespace in ["user.telegram.bob", "user.discord.42", "work"] {
+            std::fs::create_dir_all(tmp.path().join("namespaces").join(namespace)).unwrap();
+        }
+        assert_eq!(
+            user_namespaces(&cfg, tmp.path()),
+            ["user.discord.42", "user.telegram.bob"]
+        );
+    }
+
     #[test]
     fn factory_markdown() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,175 @@
+//! Which recalled memories reach the prompt.
+//!
+//! Backends score each hit from 0.0 to 1.0: the share of query words an
+//! entry contains for keyword search, the weighted vector/keyword blend for
+//! hybrid search. Hits below `[memory.recall] min_score` are dropped, and the
+//! rest go into the `[Memory context]` block best first until
+//! `max_context_tokens` is spent.
+
+use super::{Memory, MemoryEntry};
+use crate::config::MemoryRecallConfig;
+use crate::knowledge;
+use crate::tokens::Family;
+use std::fmt::Write;
+
+/// Share of the distinct words in `query` that appear in `text`, ignoring
+/// case and surrounding punctuation; 0.0 for an empty query.
+pub fn keyword_coverage(query: &str, text: &str) -> f64 {
+    let text = text.to_lowercase();
+    let mut words: Vec<String> = query
+        .split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect();
+    words.sort_unstable();
+    words.dedup();
+    if words.is_empty() {
+        return 0.0;
+    }
+    let matched = words.iter().filter(|w| text.contains(w.as_str())).count();
+    #[allow(clippy::cast_precision_loss)]
+    let coverage = matched as f64 / words.len() as f64;
+    coverage
+}
+
+/// Score `entries` by [`keyword_coverage`] of their key and content, then
+/// sort them best first; the sort is stable, so ties keep their order.
+pub fn rank_by_coverage(query: &str, entries: &mut [MemoryEntry]) {
+    for entry in entries.iter_mut() {
+        entry.score = Some(keyword_coverage(
+            query,
+            &format!("{} {}", entry.key, entry.content),
+        ));
+    }
+    entries.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+/// Keep the `entries` worth injecting, in order: at most `limit`, none below
+/// `min_score`, within `max_context_tokens`. Unscored entries are kept, as
+/// their backend cannot tell relevance apart.
+pub fn select(entries: Vec<MemoryEntry>, config: &MemoryRecallConfig) -> Vec<MemoryEntry> {
+    let family = Family::default();
+    let mut spent = 0;
+    let mut kept = Vec::new();
+    for entry in entries {
+        if kept.len() >= config.limit {
+            break;
+        }
+        if entry.score.is_some_and(|score| score < config.min_score) {
+            continue;
+        }
+        let cost = usize::try_from(family.count(&line(&entry))).unwrap_or(usize::MAX);
+        if config.max_context_tokens > 0 && spent + cost > config.max_context_tokens {
+            // A shorter, less relevant memory may still fit
+            continue;
+        }
+        spent += cost;
+        kept.push(entry);
+    }
+    kept
+}
+
+/// Memories relevant to `query`, knowledge passages excluded (they get
+/// their own block), filtered by `config`.
+pub async fn relevant(
+    mem: &dyn Memory,
+    query: &str,
+    config: &MemoryRecallConfig,
+) -> Vec<MemoryEntry> {
+    if config.limit == 0 {
+        return Vec::new();
+    }
+    let Ok(mut entries) = mem.recall(query, config.limit.saturating_mul(2)).await else {
+        return Vec::new();
+    };
+    entries.retain(|e| !knowledge::is_knowledge(e));
+    select(entries, config)
+}
+
+/// `[Memory context]` block for `entries`; empty when there are none.
+pub fn context_block(entries: &[MemoryEntry]) -> String {
+    if entries.is_empty() {
+        return String::new();
+    }
+    let mut context = String::from("[Memory context]\n");
+    for entry in entries {
+        context.push_str(&line(entry));
+    }
+    context.push('\n');
+    context
+}
+
+/// [`context_block`] for the memories [`relevant`] finds.
+pub async fn build_context(mem: &dyn Memory, query: &str, config: &MemoryRecallConfig) -> String {
+    context_block(&relevant(mem, query, config).await)
+}
+
+fn line(entry: &MemoryEntry) -> String {
+    let mut line = String::new();
+    let _ = writeln!(line, "- {}: {}", entry.key, entry.content);
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryCategory;
+
+    fn entry(key: &str, content: &str, score: Option<f64>) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: content.into(),
+            category: MemoryCategory::Core,
+            timestamp: String::new(),
+            session_id: None,
+            score,
+        }
+    }
+
+    #[test]
+    fn coverage_counts_distinct_query_words() {
+        assert!((keyword_coverage("What is my age?", "User's age is 30") - 0.5).abs() < 1e-9);
+        assert!((keyword_coverage("age AGE", "age: 30") - 1.0).abs() < 1e-9);
+        assert!(keyword_coverage("?!", "anything").abs() < 1e-9);
+    }
+
+    #[test]
+    fn low_scores_and_overflow_are_left_out() {
+        let entries = vec![
+            entry("age", "User is 30", Some(0.9)),
+            entry("weather", "It rained on Tuesday", Some(0.1)),
+            entry("essay", &"long text ".repeat(200), Some(0.8)),
+            entry("city", "User lives in Lisbon", Some(0.6)),
+            entry("legacy", "Unscored backend hit", None),
+        ];
+        let config = MemoryRecallConfig {
+            limit: 5,
+            min_score: 0.2,
+            max_context_tokens: 100,
+        };
+        let keys: Vec<String> = select(entries.clone(), &config)
+            .into_iter()
+            .map(|e| e.key)
+            .collect();
+        assert_eq!(keys, ["age", "city", "legacy"]);
+
+        let uncapped = MemoryRecallConfig {
+            limit: 2,
+            min_score: 0.0,
+            max_context_tokens: 0,
+        };
+        let keys: Vec<String> = select(entries, &uncapped)
+            .into_iter()
+            .map(|e| e.key)
+            .collect();
+        assert_eq!(keys, ["age", "weather"]);
+    }
+}
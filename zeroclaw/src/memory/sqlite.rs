@@ -1,4 +1,5 @@
 use super::embeddings::EmbeddingProvider;
+use super::recall::rank_by_coverage;
 use super::traits::{Memory, MemoryCategory, MemoryEntry};
 use super::vector;
 use async_trait::async_trait;
@@ -252,6 +253,56 @@ impl SqliteMemory {
         Ok(results)
     }
 
+    /// Substring search over keys and content, for queries FTS5 cannot
+    /// match (e.g. partial words), scored by keyword coverage
+    fn like_search(
+        conn: &Connection,
+        query: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let keywords: Vec<String> = query.split_whitespace().map(|w| format!("%{w}%")).collect();
+        if keywords.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conditions: Vec<String> = keywords
+            .iter()
+            .enumerate()
+            .map(|(i, _)| format!("(content LIKE ?{} OR key LIKE ?{})", i * 2 + 1, i * 2 + 2))
+            .collect();
+        let where_clause = conditions.join(" OR ");
+        let sql = format!(
+            "SELECT id, key, content, category, created_at FROM memories
+             WHERE {where_clause}
+             ORDER BY updated_at DESC
+             LIMIT ?{}",
+            keywords.len() * 2 + 1
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        for kw in &keywords {
+            param_values.push(Box::new(kw.clone()));
+            param_values.push(Box::new(kw.clone()));
+        }
+        #[allow(clippy::cast_possible_wrap)]
+        param_values.push(Box::new(limit as i64));
+        let params_ref: Vec<&dyn rusqlite::types::ToSql> =
+            param_values.iter().map(AsRef::as_ref).collect();
+        let rows = stmt.query_map(params_ref.as_slice(), |row| {
+            Ok(MemoryEntry {
+                id: row.get(0)?,
+                key: row.get(1)?,
+                content: row.get(2)?,
+                category: Self::str_to_category(&row.get::<_, String>(3)?),
+                timestamp: row.get(4)?,
+                session_id: None,
+                score: None,
+            })
+        })?;
+        let mut results = rows.collect::<Result<Vec<_>, _>>()?;
+        rank_by_coverage(query, &mut results);
+        Ok(results)
+    }
+
     /// Vector similarity search: scan embeddings and compute cosine similarity
     fn vector_search(
         conn: &Connection,
@@ -398,7 +449,8 @@ impl Memory for SqliteMemory {
         };
 
         // Hybrid merge
-        let merged = if vector_results.is_empty() {
+        let keyword_only = vector_results.is_empty();
+        let merged = if keyword_only {
             // No embeddings — use keyword results only
             keyword_results
                 .iter()
@@ -439,52 +491,15 @@ impl Memory for SqliteMemory {
                 results.push(entry);
             }
         }
+        // Raw BM25 has no fixed scale, so keyword hits are scored by how much
+        // of the query they contain; BM25 still orders equal scores
+        if keyword_only {
+            rank_by_coverage(query, &mut results);
+        }
 
         // If hybrid returned nothing, fall back to LIKE search
         if results.is_empty() {
-            let keywords: Vec<String> =
-                query.split_whitespace().map(|w| format!("%{w}%")).collect();
-            if !keywords.is_empty() {
-                let conditions: Vec<String> = keywords
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| {
-                        format!("(content LIKE ?{} OR key LIKE ?{})", i * 2 + 1, i * 2 + 2)
-                    })
-                    .collect();
-                let where_clause = conditions.join(" OR ");
-                let sql = format!(
-                    "SELECT id, key, content, category, created_at FROM memories
-                     WHERE {where_clause}
-                     ORDER BY updated_at DESC
-                     LIMIT ?{}",
-                    keywords.len() * 2 + 1
-                );
-                let mut stmt = conn.prepare(&sql)?;
-                let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-                for kw in &keywords {
-                    param_values.push(Box::new(kw.clone()));
-                    param_values.push(Box::new(kw.clone()));
-                }
-                #[allow(clippy::cast_possible_wrap)]
-                param_values.push(Box::new(limit as i64));
-                let params_ref: Vec<&dyn rusqlite::types::ToSql> =
-                    param_values.iter().map(AsRef::as_ref).collect();
-                let rows = stmt.query_map(params_ref.as_slice(), |row| {
-                    Ok(MemoryEntry {
-                        id: row.get(0)?,
-                        key: row.get(1)?,
-                        content: row.get(2)?,
-                        category: Self::str_to_category(&row.get::<_, String>(3)?),
-                        timestamp: row.get(4)?,
-                        session_id: None,
-                        score: Some(1.0),
-                    })
-                })?;
-                for row in rows {
-                    results.push(row?);
-                }
-            }
+            results = Self::like_search(&conn, query, limit)?;
         }
 
         results.truncate(limit);
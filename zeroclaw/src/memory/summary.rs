@@ -0,0 +1,211 @@
+//! Session summaries.
+//!
+//! With `[memory.summary] enabled`, a session that ends is summarized by a
+//! model into its goal, the actions taken, the outcomes and the follow-ups
+//! left open. Sessions end on `/quit`, when the TUI closes, on
+//! `zc_session_destroy` and `zc_agent_shutdown`, and on a channel once the
+//! conversation has been quiet for `idle_minutes`. The summary is stored as
+//! a `core` memory keyed `session_summary_<session>_<time>`, so recall
+//! brings it back when a later session returns to the same work.
+
+use super::{Memory, MemoryCategory};
+use crate::config::MemoryConfig;
+use crate::providers::{ChatMessage, Provider};
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use chrono::Local;
+use std::fmt::Write;
+
+const KEY_PREFIX: &str = "session_summary_";
+
+/// Messages a still open conversation keeps for its summary; older ones
+/// are dropped first.
+const KEEP_MESSAGES: usize = 100;
+
+const MESSAGE_MAX_CHARS: usize = 1_000;
+
+const SUMMARY_PROMPT: &str = "You summarize a finished conversation between a user and an AI \
+assistant, so that the next conversation can pick up where this one left off. Reply with \
+these four sections and nothing else, each a few short bullet points, or \"- none\":\n\
+Goal:\nActions taken:\nOutcomes:\nFollow-ups:\n\
+Only use what the conversation says; do not invent details.";
+
+/// Memory key for a summary of `session` written now.
+pub fn summary_key(session: &str) -> String {
+    format!(
+        "{KEY_PREFIX}{session}_{}",
+        Local::now().format("%Y-%m-%dT%H:%M:%S")
+    )
+}
+
+/// Add one exchange to an open conversation's `messages`, keeping at most
+/// the latest [`KEEP_MESSAGES`].
+pub fn remember_exchange(messages: &mut Vec<ChatMessage>, user: &str, answer: &str) {
+    messages.push(ChatMessage::user(user));
+    messages.push(ChatMessage::assistant(answer));
+    let excess = messages.len().saturating_sub(KEEP_MESSAGES);
+    messages.drain(..excess);
+}
+
+/// User messages in `messages` that got an answer.
+fn exchanges(messages: &[ChatMessage]) -> usize {
+    messages
+        .windows(2)
+        .filter(|pair| pair[0].role == "user" && pair[1].role == "assistant")
+        .count()
+}
+
+/// The user and assistant messages of a session, each shortened, without
+/// the system prompt or tool traffic.
+pub fn transcript(messages: &[ChatMessage]) -> String {
+    let mut transcript = String::new();
+    for message in messages {
+        let speaker = match message.role.as_str() {
+            "user" => "User",
+            "assistant" => "Assistant",
+            _ => continue,
+        };
+        let _ = writeln!(
+            transcript,
+            "{speaker}: {}\n",
+            truncate_with_ellipsis(message.content.trim(), MESSAGE_MAX_CHARS)
+        );
+    }
+    transcript
+}
+
+/// Ask `model` to summarize the session in `messages`.
+pub async fn summarize(
+    provider: &dyn Provider,
+    model: &str,
+    messages: &[ChatMessage],
+) -> Result<String> {
+    let request = [
+        ChatMessage::system(SUMMARY_PROMPT),
+        ChatMessage::user(format!("Conversation:\n\n{}", transcript(messages))),
+    ];
+    let summary = provider.chat_with_history(&request, model, 0.2).await?;
+    let summary = summary.trim();
+    if summary.is_empty() {
+        anyhow::bail!("The model returned an empty session summary");
+    }
+    Ok(summary.to_string())
+}
+
+/// The end-of-session pass: summarize `session` and store the summary as a
+/// core memory in `mem`. Returns it, or None when summaries are disabled,
+/// the session has fewer than `min_turns` exchanges, or writing it failed;
+/// failures are logged, as they must not hold up closing the session.
+pub async fn on_close(
+    provider: &dyn Provider,
+    mem: &dyn Memory,
+    config: &MemoryConfig,
+    default_model: &str,
+    session: &str,
+    messages: &[ChatMessage],
+) -> Option<String> {
+    let settings = &config.summary;
+    if !settings.enabled || exchanges(messages) < settings.min_turns.max(1) {
+        return None;
+    }
+    let model = settings.model.as_deref().unwrap_or(default_model);
+    let summary = match summarize(provider, model, messages).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            tracing::warn!(
+                "Summarizing session {session} failed: {}",
+                crate::providers::sanitize_api_error(&e.to_string())
+            );
+            return None;
+        }
+    };
+    let content = format!(
+        "Summary of session {session} ({}):\n{summary}",
+        Local::now().format("%Y-%m-%d %H:%M")
+    );
+    if let Err(e) = mem
+        .store(&summary_key(session), &content, MemoryCategory::Core)
+        .await
+    {
+        tracing::warn!("Failed to store the summary of session {session}: {e}");
+        return None;
+    }
+    Some(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MemorySummaryConfig;
+    use crate::providers::mock::ScriptedProvider;
+    use crate::memory::SqliteMemory;
+
+    fn session() -> Vec<ChatMessage> {
+        let mut messages = vec![ChatMessage::system("You are ZeroClaw")];
+        remember_exchange(&mut messages, "Help me plan Lisbon", "Sure, when?");
+        messages.push(ChatMessage {
+            role: "tool".into(),
+            ..ChatMessage::user("{\"flights\": []}")
+        });
+        remember_exchange(&mut messages, "In May", "Flights are booked");
+        messages
+    }
+
+    #[test]
+    fn transcripts_keep_only_the_conversation() {
+        let messages = session();
+        assert_eq!(exchanges(&messages), 2);
+        let text = transcript(&messages);
+        assert!(text.starts_with("User: Help me plan Lisbon\n"));
+        assert!(text.contains("Assistant: Flights are booked"));
+        assert!(!text.contains("ZeroClaw") && !text.contains("flights"));
+
+        let mut long = Vec::new();
+        for i in 0..KEEP_MESSAGES {
+            remember_exchange(&mut long, &format!("q{i}"), "a");
+        }
+        assert_eq!(long.len(), KEEP_MESSAGES);
+        assert_eq!(long[0].role, "user");
+        assert_eq!(long.last().unwrap().content, "a");
+    }
+
+    #[tokio::test]
+    async fn closed_sessions_leave_a_core_summary() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let provider = ScriptedProvider::always(
+            "Goal:\n- Plan the Lisbon trip\nFollow-ups:\n- Book the hotel",
+        );
+        let mut config = MemoryConfig::default();
+        let close = |config: MemoryConfig, messages: Vec<ChatMessage>| {
+            let (provider, mem) = (&provider, &mem);
+            async move { on_close(provider, mem, &config, "m", "cli-1", &messages).await }
+        };
+
+        assert!(close(config.clone(), session()).await.is_none(), "disabled");
+        config.summary = MemorySummaryConfig {
+            enabled: true,
+            model: Some("cheap-model".into()),
+            min_turns: 3,
+            ..MemorySummaryConfig::default()
+        };
+        assert!(
+            close(config.clone(), session()).await.is_none(),
+            "too short"
+        );
+
+        config.summary.min_turns = 2;
+        let summary = close(config, session()).await.unwrap();
+        assert!(summary.starts_with("Summary of session cli-1 ("));
+        assert!(summary.ends_with("- Book the hotel"));
+        let request = provider.requests().pop().unwrap();
+        assert_eq!(request.model, "cheap-model");
+        let request = request.messages;
+        assert_eq!(request[0].content, SUMMARY_PROMPT);
+        assert!(request[1].content.contains("User: In May"));
+        let stored = mem.list(Some(&MemoryCategory::Core)).await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert!(stored[0].key.starts_with("session_summary_cli-1_"));
+        assert_eq!(stored[0].content, summary);
+    }
+}
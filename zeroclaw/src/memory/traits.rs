@@ -10,6 +10,7 @@ pub struct MemoryEntry {
     pub category: MemoryCategory,
     pub timestamp: String,
     pub session_id: Option<String>,
+    /// Relevance to the recall query, 0.0–1.0; None outside recall
     pub score: Option<f64>,
 }
 
@@ -48,7 +49,8 @@ pub trait Memory: Send + Sync {
     async fn store(&self, key: &str, content: &str, category: MemoryCategory)
         -> anyhow::Result<()>;
 
-    /// Recall memories matching a query (keyword search)
+    /// Recall memories matching a query, best first, each with its
+    /// relevance `score` (see `memory::recall`)
     async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>>;
 
     /// Get a specific memory by key
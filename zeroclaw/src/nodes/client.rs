@@ -0,0 +1,222 @@
+//! The calling side: one connection per remote node, opened on first use
+//! and reopened after it drops.
+
+use super::Frame;
+use crate::config::RemoteNodeConfig;
+use crate::tools::traits::ToolResult;
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+
+/// How long to wait for the node's `hello` after connecting.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<ToolResult>>>>;
+
+fn lock(pending: &Pending) -> MutexGuard<'_, HashMap<u64, oneshot::Sender<ToolResult>>> {
+    pending.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// An open connection: frames to send, and the calls waiting on results.
+#[derive(Clone)]
+struct Link {
+    outgoing: mpsc::UnboundedSender<String>,
+    pending: Pending,
+    tools: Arc<Vec<String>>,
+}
+
+pub struct NodeClient {
+    name: String,
+    config: RemoteNodeConfig,
+    timeout: Duration,
+    link: tokio::sync::Mutex<Option<Link>>,
+    next_id: AtomicU64,
+}
+
+impl NodeClient {
+    pub fn new(name: &str, config: &RemoteNodeConfig, timeout: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            config: config.clone(),
+            timeout,
+            link: tokio::sync::Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Run `tool` on the node. `Err` means the node could not be reached or
+    /// did not answer; a failing tool comes back as an unsuccessful result.
+    pub async fn call(&self, tool: &str, args: serde_json::Value) -> Result<ToolResult> {
+        let link = self.link().await?;
+        if !link.tools.iter().any(|served| served == tool) {
+            anyhow::bail!("Node {} does not serve {tool}", self.name);
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        lock(&link.pending).insert(id, tx);
+        let frame = Frame::Call {
+            id,
+            tool: tool.to_string(),
+            args,
+        };
+        if link.outgoing.send(frame.to_text()).is_err() {
+            lock(&link.pending).remove(&id);
+            anyhow::bail!("Lost the connection to node {}", self.name);
+        }
+
+        match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => anyhow::bail!("Node {} disconnected during the call", self.name),
+            Err(_) => {
+                lock(&link.pending).remove(&id);
+                anyhow::bail!(
+                    "Node {} did not answer within {}s",
+                    self.name,
+                    self.timeout.as_secs()
+                )
+            }
+        }
+    }
+
+    /// The open connection, connecting first when there is none.
+    async fn link(&self) -> Result<Link> {
+        let mut slot = self.link.lock().await;
+        if let Some(link) = slot.as_ref().filter(|link| !link.outgoing.is_closed()) {
+            return Ok(link.clone());
+        }
+        let link = self.connect().await.with_context(|| {
+            format!("Could not reach node {} at {}", self.name, self.config.url)
+        })?;
+        *slot = Some(link.clone());
+        Ok(link)
+    }
+
+    async fn connect(&self) -> Result<Link> {
+        let mut request = self.config.url.as_str().into_client_request()?;
+        request.headers_mut().insert(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {}", self.config.token))?,
+        );
+        let (ws, _) = tokio_tungstenite::connect_async(request).await?;
+        let (mut sink, mut incoming) = ws.split();
+
+        let hello = tokio::time::timeout(HELLO_TIMEOUT, incoming.next())
+            .await
+            .context("The node did not say hello")?;
+        let tools = match hello {
+            Some(Ok(Message::Text(text))) => match serde_json::from_str(&text) {
+                Ok(Frame::Hello { tools, .. }) => tools,
+                _ => anyhow::bail!("The node opened with an unexpected frame"),
+            },
+            Some(Err(e)) => return Err(e.into()),
+            _ => anyhow::bail!("The node closed the connection"),
+        };
+
+        let (outgoing, mut frames) = mpsc::unbounded_channel::<String>();
+        let pending: Pending = Arc::default();
+        let name = self.name.clone();
+        let waiting = pending.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    frame = frames.recv() => match frame {
+                        Some(frame) => {
+                            if sink.send(Message::Text(frame)).await.is_err() {
+                                break;
+                            }
+                        }
+                        None => break,
+                    },
+                    message = incoming.next() => match message {
+                        Some(Ok(Message::Text(text))) => {
+                            if let Ok(Frame::Result { id, result }) = serde_json::from_str(&text) {
+                                if let Some(tx) = lock(&waiting).remove(&id) {
+                                    let _ = tx.send(result);
+                                }
+                            }
+                        }
+                        Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                        Some(Ok(_)) => {}
+                    },
+                }
+            }
+            tracing::info!("Connection to node {name} closed");
+            // Dropping the senders fails the calls still waiting
+            lock(&waiting).clear();
+        });
+
+        Ok(Link {
+            outgoing,
+            pending,
+            tools: Arc::new(tools),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::nodes::server;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn calls_run_on_the_node_with_the_right_token() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("notes.txt"), "built on buildbox").unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        config.nodes.tools = vec!["file_read".into()];
+        let tools = Arc::new(server::node_tools(&config).unwrap());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(server::serve(listener, "s3cret".into(), tools));
+
+        let node = |token: &str| {
+            let config = RemoteNodeConfig {
+                url: url.clone(),
+                token: token.into(),
+            };
+            NodeClient::new("buildbox", &config, Duration::from_secs(10))
+        };
+
+        let client = node("s3cret");
+        let result = client
+            .call("file_read", json!({"path": "notes.txt"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("built on buildbox"));
+
+        let missing = client
+            .call("file_read", json!({"path": "missing.txt"}))
+            .await
+            .unwrap();
+        assert!(!missing.success);
+
+        let unserved = client.call("shell", json!({"command": "ls"})).await;
+        assert!(unserved
+            .unwrap_err()
+            .to_string()
+            .contains("does not serve shell"));
+
+        let refused = node("wrong")
+            .call("file_read", json!({"path": "notes.txt"}))
+            .await;
+        assert!(refused
+            .unwrap_err()
+            .to_string()
+            .starts_with("Could not reach node buildbox"));
+    }
+}
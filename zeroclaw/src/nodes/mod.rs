@@ -0,0 +1,85 @@
+//! Remote tool execution nodes.
+//!
+//! `zeroclaw node` turns a machine into a worker: it serves its shell and
+//! file tools (and nothing else — no model, no channels) over a WebSocket
+//! that callers open with `Authorization: Bearer <[nodes] token>`. The node
+//! runs every call under its own `[autonomy]` policy and workspace.
+//!
+//! On the calling side, each `[nodes.remote.<name>]` entry adds a `target`
+//! parameter to the same tools; a call with `target: "buildbox"` runs on
+//! that node instead of locally.
+//!
+//! Frames are JSON text messages: the node opens with `hello` listing the
+//! tools it serves, then answers each `call` with a `result` of the same id.
+//! Calls on one connection may run concurrently.
+
+pub mod client;
+pub mod server;
+
+use crate::tools::traits::ToolResult;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub use client::NodeClient;
+pub use server::run_node;
+
+/// Tools a node can serve and a caller can route to one.
+pub const ROUTABLE_TOOLS: [&str; 4] = ["shell", "file_read", "file_write", "file_edit"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Frame {
+    Hello {
+        version: String,
+        tools: Vec<String>,
+    },
+    Call {
+        id: u64,
+        tool: String,
+        args: Value,
+    },
+    Result {
+        id: u64,
+        #[serde(flatten)]
+        result: ToolResult,
+    },
+}
+
+impl Frame {
+    pub fn to_text(&self) -> String {
+        // Frames hold only strings, numbers and JSON values
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn frames_are_tagged_json() {
+        let call: Frame = serde_json::from_value(json!({
+            "type": "call",
+            "id": 3,
+            "tool": "shell",
+            "args": {"command": "uname -a"}
+        }))
+        .unwrap();
+        assert!(matches!(call, Frame::Call { id: 3, ref tool, .. } if tool == "shell"));
+
+        let result = Frame::Result {
+            id: 3,
+            result: ToolResult {
+                success: true,
+                output: "Linux".into(),
+                error: None,
+            },
+        };
+        let value: Value = serde_json::from_str(&result.to_text()).unwrap();
+        assert_eq!(
+            value,
+            json!({"type": "result", "id": 3, "success": true, "output": "Linux", "error": null})
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! The worker side: `zeroclaw node`.
+
+use super::{Frame, ROUTABLE_TOOLS};
+use crate::config::Config;
+use crate::runtime;
+use crate::security::pairing::{constant_time_eq, is_public_bind};
+use crate::security::SecurityPolicy;
+use crate::tools::{self, Tool};
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+/// The tools in `[nodes] tools`, under this machine's autonomy policy.
+pub(crate) fn node_tools(config: &Config) -> Result<Vec<Box<dyn Tool>>> {
+    if let Some(unknown) = config
+        .nodes
+        .tools
+        .iter()
+        .find(|name| !ROUTABLE_TOOLS.contains(&name.as_str()))
+    {
+        anyhow::bail!(
+            "[nodes] tools: \"{unknown}\" cannot be served; use any of {}",
+            ROUTABLE_TOOLS.join(", ")
+        );
+    }
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    let runtime: Arc<dyn runtime::RuntimeAdapter> =
+        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let mut tools = tools::default_tools_with_runtime(security, runtime);
+    tools.retain(|tool| config.nodes.tools.iter().any(|name| name == tool.name()));
+    Ok(tools)
+}
+
+/// Whether the upgrade request carries the node token.
+fn is_authorized(request: &Request, token: &str) -> bool {
+    request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.trim(), token))
+}
+
+/// Refuse a public `host` unless a tunnel is configured or `[nodes]
+/// allow_public_bind` is set: the node runs commands for anyone holding the
+/// token, and plain `ws://` sends that token in cleartext.
+fn check_bind(host: &str, config: &Config) -> Result<()> {
+    if is_public_bind(host) && config.tunnel.provider == "none" && !config.nodes.allow_public_bind
+    {
+        anyhow::bail!(
+            "🛑 Refusing to bind to {host} — the node would serve its tools over plain ws://.\n\
+             Fix: use --host 127.0.0.1 (default), configure a tunnel, or put a TLS proxy in\n\
+             front and set [nodes] allow_public_bind = true in config.toml."
+        );
+    }
+    Ok(())
+}
+
+/// Serve the node until the process stops. `host`/`port` override `[nodes]`.
+pub async fn run_node(config: Config, host: Option<String>, port: Option<u16>) -> Result<()> {
+    let token = config
+        .nodes
+        .token
+        .clone()
+        .filter(|token| !token.trim().is_empty())
+        .context("`zeroclaw node` needs a [nodes] token that callers present")?;
+    let tools = Arc::new(node_tools(&config)?);
+    if tools.is_empty() {
+        anyhow::bail!("[nodes] tools is empty; the node would serve nothing");
+    }
+
+    let host = host.unwrap_or_else(|| config.nodes.host.clone());
+    let port = port.unwrap_or(config.nodes.port);
+    check_bind(&host, &config)?;
+    let listener = TcpListener::bind((host.as_str(), port))
+        .await
+        .with_context(|| format!("Could not listen on {host}:{port}"))?;
+    let names: Vec<&str> = tools.iter().map(|tool| tool.name()).collect();
+    println!(
+        "🛰️  ZeroClaw node listening on ws://{}",
+        listener.local_addr()?
+    );
+    println!("   Serving: {}", names.join(", "));
+    println!("   Workspace: {}", config.workspace_dir.display());
+
+    serve(listener, token.into(), tools).await
+}
+
+/// Accept connections on `listener` until it fails.
+pub(crate) async fn serve(
+    listener: TcpListener,
+    token: Arc<str>,
+    tools: Arc<Vec<Box<dyn Tool>>>,
+) -> Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let tools = tools.clone();
+        let token = token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(stream, peer, &token, tools).await {
+                tracing::warn!("Node connection from {peer} ended: {e:#}");
+            }
+        });
+    }
+}
+
+async fn serve_connection(
+    stream: TcpStream,
+    peer: SocketAddr,
+    token: &str,
+    tools: Arc<Vec<Box<dyn Tool>>>,
+) -> Result<()> {
+    let ws = tokio_tungstenite::accept_hdr_async(
+        stream,
+        |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+            if is_authorized(request, token) {
+                Ok(response)
+            } else {
+                let mut refusal = ErrorResponse::new(Some("Unauthorized".into()));
+                *refusal.status_mut() = StatusCode::UNAUTHORIZED;
+                Err(refusal)
+            }
+        },
+    )
+    .await
+    .context("WebSocket handshake failed")?;
+    tracing::info!("Node connection from {peer}");
+
+    let (mut sink, mut incoming) = ws.split();
+    let hello = Frame::Hello {
+        version: env!("CARGO_PKG_VERSION").into(),
+        tools: tools.iter().map(|tool| tool.name().to_string()).collect(),
+    };
+    sink.send(Message::Text(hello.to_text())).await?;
+
+    let (results_tx, mut results) = mpsc::unbounded_channel::<Frame>();
+    loop {
+        tokio::select! {
+            Some(frame) = results.recv() => {
+                sink.send(Message::Text(frame.to_text())).await?;
+            }
+            message = incoming.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let Ok(Frame::Call { id, tool, args }) = serde_json::from_str(&text) else {
+                        tracing::warn!("Node ignored a frame from {peer} that is not a call");
+                        continue;
+                    };
+                    tracing::info!("Node call from {peer}: {tool}");
+                    let tools = tools.clone();
+                    let results_tx = results_tx.clone();
+                    tokio::spawn(async move {
+                        let result = execute(&tools, &tool, args).await;
+                        let _ = results_tx.send(Frame::Result { id, result });
+                    });
+                }
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e.into()),
+            },
+        }
+    }
+}
+
+async fn execute(
+    tools: &[Box<dyn Tool>],
+    name: &str,
+    args: serde_json::Value,
+) -> crate::tools::traits::ToolResult {
+    let failure = |error: String| crate::tools::traits::ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+    };
+    let Some(tool) = tools.iter().find(|tool| tool.name() == name) else {
+        return failure(format!("This node does not serve {name}"));
+    };
+    match tool.execute(args).await {
+        Ok(result) => result,
+        Err(e) => failure(format!("{e:#}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_only_the_configured_routable_tools() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        config.nodes.tools = vec!["file_read".into(), "shell".into()];
+        let names: Vec<String> = node_tools(&config)
+            .unwrap()
+            .iter()
+            .map(|tool| tool.name().to_string())
+            .collect();
+        assert_eq!(names, ["shell", "file_read"]);
+
+        config.nodes.tools = vec!["memory_forget".into()];
+        assert!(node_tools(&config).is_err());
+    }
+
+    #[test]
+    fn public_bind_needs_a_tunnel_or_opt_in() {
+        let mut config = Config::default();
+        assert!(check_bind("127.0.0.1", &config).is_ok());
+        let err = check_bind("0.0.0.0", &config).unwrap_err().to_string();
+        assert!(err.contains("allow_public_bind"), "{err}");
+
+        config.nodes.allow_public_bind = true;
+        assert!(check_bind("0.0.0.0", &config).is_ok());
+        config.nodes.allow_public_bind = false;
+        config.tunnel.provider = "cloudflare".into();
+        assert!(check_bind("0.0.0.0", &config).is_ok());
+    }
+}
@@ -0,0 +1,42 @@
+//! Audit trail of policy decisions.
+//!
+//! Events that an operator may need to account for later, such as a reply
+//! blocked by a guardrail, are appended as JSON lines to
+//! `<workspace>/.zeroclaw/audit.jsonl`. Unlike tracing output, the file is
+//! kept across runs and is not affected by the log level.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::Path;
+
+/// Audit log, relative to the workspace.
+pub const AUDIT_LOG: &str = ".zeroclaw/audit.jsonl";
+
+/// Append a `kind` event with `detail` fields to the audit log of
+/// `workspace_dir`. Failing to write is logged, never fatal.
+pub fn record(workspace_dir: &Path, kind: &str, detail: Value) {
+    if let Err(e) = append(workspace_dir, kind, detail) {
+        tracing::warn!("Could not write the audit log: {e:#}");
+    }
+}
+
+fn append(workspace_dir: &Path, kind: &str, detail: Value) -> Result<()> {
+    let path = workspace_dir.join(AUDIT_LOG);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut entry = json!({ "time": Utc::now().to_rfc3339(), "kind": kind });
+    if let (Some(entry), Value::Object(detail)) = (entry.as_object_mut(), detail) {
+        entry.extend(detail);
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{entry}")?;
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use super::trace;
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// JSON Lines observer — appends one timestamped object per event or
+/// metric to a file
+pub struct JsonlObserver {
+    path: PathBuf,
+    file: Mutex<BufWriter<File>>,
+}
+
+impl JsonlObserver {
+    /// Open `path` for appending, creating it and its directory if needed.
+    pub fn new(path: &Path) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    fn write(&self, mut record: serde_json::Value) {
+        record["ts"] = chrono::Utc::now().to_rfc3339().into();
+        trace::annotate(&mut record);
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // Events come one at a time, so flush each to keep the file current
+        let written = writeln!(file, "{record}").and_then(|()| file.flush());
+        if let Err(e) = written {
+            tracing::warn!("Failed to write to {}: {e}", self.path.display());
+        }
+    }
+}
+
+impl Observer for JsonlObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        self.write(event.to_json());
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        self.write(metric.to_json());
+    }
+
+    fn flush(&self) {
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let _ = file.flush();
+    }
+
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn events_and_metrics_are_appended_as_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("logs/events.jsonl");
+        let observer = JsonlObserver::new(&path).unwrap();
+        observer.record_event(&ObserverEvent::ToolCall {
+            tool: "shell".into(),
+            duration: Duration::from_millis(12),
+            success: true,
+        });
+        observer.record_metric(&ObserverMetric::TokensUsed(42));
+        // A second observer on the same file appends
+        JsonlObserver::new(&path)
+            .unwrap()
+            .record_event(&ObserverEvent::HeartbeatTick);
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["event"], "tool.call");
+        assert_eq!(lines[0]["duration_ms"], 12);
+        assert!(lines[0]["ts"].is_string());
+        assert_eq!(lines[1]["metric"], "tokens_used");
+        assert_eq!(lines[1]["value"], 42);
+        assert_eq!(lines[2]["event"], "heartbeat.tick");
+    }
+}
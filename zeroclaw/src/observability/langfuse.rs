@@ -0,0 +1,279 @@
+use super::trace::{self, TraceContext};
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use super::webhook::{http_client, spawn_batcher};
+use crate::config::LangfuseSinkConfig;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Langfuse observer — reports each turn as a trace holding its model calls
+/// (generations, with usage and cost), tool calls (spans), errors and
+/// scores, through the batch ingestion API
+pub struct LangfuseObserver {
+    release: String,
+    queue: Sender<Value>,
+    /// Trace last created, so a turn's trace is sent once per run of events
+    last_trace: Mutex<String>,
+}
+
+impl LangfuseObserver {
+    pub fn new(config: &LangfuseSinkConfig) -> Result<Self, String> {
+        let key = |value: &Option<String>, var: &str| {
+            let set = |key: &String| !key.trim().is_empty();
+            value
+                .clone()
+                .filter(set)
+                .or_else(|| std::env::var(var).ok().filter(set))
+                .ok_or_else(|| format!("set a Langfuse key in the config or {var}"))
+        };
+        let public_key = key(&config.public_key, "LANGFUSE_PUBLIC_KEY")?;
+        let secret_key = key(&config.secret_key, "LANGFUSE_SECRET_KEY")?;
+        let url = format!("{}/api/public/ingestion", config.host.trim_end_matches('/'));
+        let mut client = None;
+        let queue = spawn_batcher("observer-langfuse", config.batch_size, move |batch| {
+            let client = client.get_or_insert_with(http_client);
+            let sent = client
+                .post(&url)
+                .basic_auth(&public_key, Some(&secret_key))
+                .json(&json!({ "batch": batch }))
+                .send()
+                .and_then(reqwest::blocking::Response::error_for_status);
+            if let Err(e) = sent {
+                tracing::warn!("Dropped {} Langfuse events: {e}", batch.len());
+            }
+        });
+        Ok(Self {
+            release: config
+                .release
+                .clone()
+                .unwrap_or_else(|| format!("zeroclaw {}", env!("CARGO_PKG_VERSION"))),
+            queue,
+            last_trace: Mutex::new(String::new()),
+        })
+    }
+
+    /// Queue an ingestion event of `kind`, creating the trace it belongs to
+    /// first if it is new.
+    fn send(&self, kind: &str, mut body: Value) {
+        let context = trace::current().unwrap_or_else(|| TraceContext {
+            trace_id: Uuid::new_v4().to_string(),
+            session_id: None,
+        });
+        let now = Utc::now();
+        let new_trace = {
+            let mut last = self
+                .last_trace
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if *last == context.trace_id {
+                false
+            } else {
+                last.clone_from(&context.trace_id);
+                true
+            }
+        };
+        if new_trace {
+            // Langfuse merges repeated trace-create events into one trace
+            self.enqueue(
+                "trace-create",
+                now,
+                &json!({
+                    "id": context.trace_id,
+                    "name": "turn",
+                    "sessionId": context.session_id,
+                    "release": self.release,
+                }),
+            );
+        }
+        body["id"] = Uuid::new_v4().to_string().into();
+        body["traceId"] = context.trace_id.into();
+        self.enqueue(kind, now, &body);
+    }
+
+    fn enqueue(&self, kind: &str, timestamp: DateTime<Utc>, body: &Value) {
+        let event = json!({
+            "id": Uuid::new_v4().to_string(),
+            "type": kind,
+            "timestamp": timestamp.to_rfc3339(),
+            "body": body,
+        });
+        // The thread only stops once this observer is dropped
+        let _ = self.queue.send(event);
+    }
+}
+
+/// Start and end times of something that just took `duration`.
+fn times(duration: Duration) -> (String, String) {
+    let end = Utc::now();
+    let start = end - chrono::Duration::from_std(duration).unwrap_or_default();
+    (start.to_rfc3339(), end.to_rfc3339())
+}
+
+impl Observer for LangfuseObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        match event {
+            ObserverEvent::LlmCall {
+                model,
+                duration,
+                input_tokens,
+                output_tokens,
+                cost_usd,
+                input,
+                output,
+            } => {
+                let (start, end) = times(*duration);
+                let mut body = json!({
+                    "name": "llm.call",
+                    "model": model,
+                    "startTime": start,
+                    "endTime": end,
+                    "input": input,
+                    "output": output,
+                    "usageDetails": {"input": input_tokens, "output": output_tokens},
+                });
+                if let Some(cost) = cost_usd {
+                    body["costDetails"] = json!({"total": cost});
+                }
+                self.send("generation-create", body);
+            }
+            ObserverEvent::ToolCall {
+                tool,
+                duration,
+                success,
+            } => {
+                let (start, end) = times(*duration);
+                let level = if *success { "DEFAULT" } else { "ERROR" };
+                self.send(
+                    "span-create",
+                    json!({"name": tool, "startTime": start, "endTime": end, "level": level}),
+                );
+            }
+            ObserverEvent::Score {
+                name,
+                value,
+                comment,
+            } => self.send(
+                "score-create",
+                json!({"name": name, "value": value, "comment": comment}),
+            ),
+            ObserverEvent::Error { component, message } => self.send(
+                "event-create",
+                json!({"name": component, "level": "ERROR", "statusMessage": message}),
+            ),
+            _ => {}
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn name(&self) -> &str {
+        "langfuse"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Accept one request and return its headers and body.
+    fn receive_one(listener: &TcpListener) -> (String, Value) {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut headers = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            headers.push_str(&line.to_lowercase());
+        }
+        let length: usize = headers
+            .lines()
+            .find_map(|l| l.strip_prefix("content-length:"))
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        (&stream)
+            .write_all(b"HTTP/1.1 207 Multi-Status\r\ncontent-length: 0\r\n\r\n")
+            .unwrap();
+        (headers, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn turns_become_traces_with_generations_and_spans() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let observer = LangfuseObserver::new(&LangfuseSinkConfig {
+            host: format!("http://{}/", listener.local_addr().unwrap()),
+            public_key: Some("pk-lf-test".into()),
+            secret_key: Some("sk-lf-test".into()),
+            release: Some("prompt-v2".into()),
+            batch_size: 10,
+        })
+        .unwrap();
+
+        trace::session(
+            "chat-7".into(),
+            trace::turn(async {
+                observer.record_event(&ObserverEvent::LlmCall {
+                    model: "gpt-4o".into(),
+                    duration: Duration::from_millis(800),
+                    input_tokens: 120,
+                    output_tokens: 30,
+                    cost_usd: Some(0.0005),
+                    input: "What is 6*7?".into(),
+                    output: "<tool_call>calculator</tool_call>".into(),
+                });
+                observer.record_event(&ObserverEvent::ToolCall {
+                    tool: "calculator".into(),
+                    duration: Duration::from_millis(2),
+                    success: false,
+                });
+            }),
+        )
+        .await;
+
+        let mut events = Vec::new();
+        while events.len() < 3 {
+            let (headers, body) = receive_one(&listener);
+            // Basic auth with the project keys
+            assert!(headers.contains("authorization: basic "));
+            events.extend(body["batch"].as_array().unwrap().clone());
+        }
+        let kinds: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["trace-create", "generation-create", "span-create"]);
+
+        let trace = &events[0]["body"];
+        assert_eq!(trace["sessionId"], "chat-7");
+        assert_eq!(trace["release"], "prompt-v2");
+        let generation = &events[1]["body"];
+        assert_eq!(generation["traceId"], trace["id"]);
+        assert_eq!(generation["usageDetails"]["input"], 120);
+        assert_eq!(generation["costDetails"]["total"], 0.0005);
+        let span = &events[2]["body"];
+        assert_eq!(span["traceId"], trace["id"]);
+        assert_eq!(span["level"], "ERROR");
+    }
+
+    #[test]
+    fn keys_are_required() {
+        let config = LangfuseSinkConfig {
+            host: "http://127.0.0.1:9".into(),
+            public_key: Some("pk-lf-test".into()),
+            secret_key: Some(String::new()),
+            release: None,
+            batch_size: 10,
+        };
+        if std::env::var("LANGFUSE_SECRET_KEY").is_err() {
+            assert!(LangfuseObserver::new(&config).is_err());
+        }
+    }
+}
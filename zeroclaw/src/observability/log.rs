@@ -37,6 +37,35 @@ impl Observer for LogObserver {
             ObserverEvent::HeartbeatTick => {
                 info!("heartbeat.tick");
             }
+            ObserverEvent::ModelRouted {
+                hint,
+                provider,
+                model,
+                reason,
+            } => {
+                info!(hint = %hint, provider = %provider, model = %model, reason = %reason, "model.route");
+            }
+            ObserverEvent::PlanProposed { steps, risks } => {
+                info!(steps = ?steps, risks = ?risks, "agent.plan");
+            }
+            ObserverEvent::LlmCall {
+                model,
+                duration,
+                input_tokens,
+                output_tokens,
+                cost_usd,
+                ..
+            } => {
+                let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+                info!(model = %model, duration_ms = ms, input_tokens, output_tokens, cost_usd = ?cost_usd, "llm.call");
+            }
+            ObserverEvent::Score {
+                name,
+                value,
+                comment,
+            } => {
+                info!(name = %name, value, comment = ?comment, "score");
+            }
             ObserverEvent::Error { component, message } => {
                 info!(component = %component, error = %message, "error");
             }
@@ -101,6 +130,16 @@ mod tests {
             direction: "outbound".into(),
         });
         obs.record_event(&ObserverEvent::HeartbeatTick);
+        obs.record_event(&ObserverEvent::ModelRouted {
+            hint: "cheap".into(),
+            provider: "groq".into(),
+            model: "llama-3.1-8b-instant".into(),
+            reason: "12 chars, chat".into(),
+        });
+        obs.record_event(&ObserverEvent::PlanProposed {
+            steps: vec!["Run the tests (shell)".into()],
+            risks: vec![],
+        });
         obs.record_event(&ObserverEvent::Error {
             component: "provider".into(),
             message: "timeout".into(),
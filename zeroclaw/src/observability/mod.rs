@@ -1,41 +1,50 @@
+pub mod audit;
+pub mod jsonl;
+pub mod langfuse;
 pub mod log;
 pub mod multi;
 pub mod noop;
 pub mod otel;
+pub mod tool_stats;
+pub mod trace;
 pub mod traits;
+pub mod webhook;
 
 pub use self::log::LogObserver;
+pub use jsonl::JsonlObserver;
+pub use langfuse::LangfuseObserver;
+pub use multi::MultiObserver;
 pub use noop::NoopObserver;
 pub use otel::OtelObserver;
 pub use traits::{Observer, ObserverEvent};
+pub use webhook::WebhookObserver;
 
-use crate::config::ObservabilityConfig;
+use crate::config::{ObservabilityConfig, ObservabilitySinksConfig};
+use std::path::PathBuf;
 
-/// Factory: create the right observer from config
+/// Factory: create the right observer from config. With
+/// `[observability.sinks]` set up, events fan out to `backend` and every
+/// sink.
 pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
+    let backend = create_backend(config);
+    if config.sinks.is_empty() {
+        return backend;
+    }
+    let mut observers = create_sinks(&config.sinks);
+    if backend.name() != "noop" {
+        observers.insert(0, backend);
+    }
+    Box::new(MultiObserver::new(observers))
+}
+
+fn create_backend(config: &ObservabilityConfig) -> Box<dyn Observer> {
     match config.backend.as_str() {
         "log" => Box::new(LogObserver::new()),
-        "otel" | "opentelemetry" | "otlp" => {
-            match OtelObserver::new(
-                config.otel_endpoint.as_deref(),
-                config.otel_service_name.as_deref(),
-            ) {
-                Ok(obs) => {
-                    tracing::info!(
-                        endpoint = config
-                            .otel_endpoint
-                            .as_deref()
-                            .unwrap_or("http://localhost:4318"),
-                        "OpenTelemetry observer initialized"
-                    );
-                    Box::new(obs)
-                }
-                Err(e) => {
-                    tracing::error!("Failed to create OTel observer: {e}. Falling back to noop.");
-                    Box::new(NoopObserver)
-                }
-            }
-        }
+        "otel" | "opentelemetry" | "otlp" => otel(
+            config.otel_endpoint.as_deref(),
+            config.otel_service_name.as_deref(),
+        )
+        .unwrap_or_else(|| Box::new(NoopObserver)),
         "none" | "noop" => Box::new(NoopObserver),
         _ => {
             tracing::warn!(
@@ -47,6 +56,51 @@ pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
     }
 }
 
+/// The sinks in `[observability.sinks]`. One that cannot start is left out
+/// with an error in the log.
+fn create_sinks(sinks: &ObservabilitySinksConfig) -> Vec<Box<dyn Observer>> {
+    let mut observers: Vec<Box<dyn Observer>> = Vec::new();
+    if sinks.console {
+        observers.push(Box::new(LogObserver::new()));
+    }
+    if let Some(jsonl) = &sinks.jsonl {
+        let path = PathBuf::from(shellexpand::tilde(&jsonl.path).as_ref());
+        match JsonlObserver::new(&path) {
+            Ok(obs) => observers.push(Box::new(obs)),
+            Err(e) => tracing::error!("Failed to open {} for events: {e}", path.display()),
+        }
+    }
+    if let Some(otlp) = &sinks.otlp {
+        observers.extend(otel(otlp.endpoint.as_deref(), otlp.service_name.as_deref()));
+    }
+    if let Some(webhook) = &sinks.webhook {
+        observers.push(Box::new(WebhookObserver::new(webhook)));
+    }
+    if let Some(langfuse) = &sinks.langfuse {
+        match LangfuseObserver::new(langfuse) {
+            Ok(obs) => observers.push(Box::new(obs)),
+            Err(e) => tracing::error!("Failed to create Langfuse observer: {e}"),
+        }
+    }
+    observers
+}
+
+fn otel(endpoint: Option<&str>, service_name: Option<&str>) -> Option<Box<dyn Observer>> {
+    match OtelObserver::new(endpoint, service_name) {
+        Ok(obs) => {
+            tracing::info!(
+                endpoint = endpoint.unwrap_or("http://localhost:4318"),
+                "OpenTelemetry observer initialized"
+            );
+            Some(Box::new(obs))
+        }
+        Err(e) => {
+            tracing::error!("Failed to create OTel observer: {e}. Falling back to noop.");
+            None
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,6 +138,7 @@ mod tests {
             backend: "otel".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
@@ -94,6 +149,7 @@ mod tests {
             backend: "opentelemetry".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
@@ -104,6 +160,7 @@ mod tests {
             backend: "otlp".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
@@ -134,4 +191,32 @@ mod tests {
         };
         assert_eq!(create_observer(&cfg).name(), "noop");
     }
+
+    #[test]
+    fn sinks_fan_out_alongside_the_backend() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let mut cfg = ObservabilityConfig {
+            backend: "log".into(),
+            ..ObservabilityConfig::default()
+        };
+        cfg.sinks.console = true;
+        cfg.sinks.jsonl = Some(crate::config::schema::JsonlSinkConfig {
+            path: path.display().to_string(),
+        });
+        let observer = create_observer(&cfg);
+        assert_eq!(observer.name(), "multi");
+        observer.record_event(&ObserverEvent::HeartbeatTick);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("heartbeat.tick"));
+
+        // A noop backend is left out, and a sink that fails to start too
+        cfg.backend = "none".into();
+        cfg.sinks.console = false;
+        cfg.sinks.jsonl = Some(crate::config::schema::JsonlSinkConfig {
+            path: path.join("not-a-dir").display().to_string(),
+        });
+        assert_eq!(create_observer(&cfg).name(), "multi");
+    }
 }
@@ -155,6 +155,7 @@ impl OtelObserver {
 }
 
 impl Observer for OtelObserver {
+    #[allow(clippy::too_many_lines)]
     fn record_event(&self, event: &ObserverEvent) {
         let tracer = global::tracer("zeroclaw");
 
@@ -242,6 +243,84 @@ impl Observer for OtelObserver {
             ObserverEvent::HeartbeatTick => {
                 self.heartbeat_ticks.add(1, &[]);
             }
+            ObserverEvent::ModelRouted {
+                hint,
+                provider,
+                model,
+                reason,
+            } => {
+                let mut span = tracer.build(
+                    opentelemetry::trace::SpanBuilder::from_name("model.route")
+                        .with_kind(SpanKind::Internal)
+                        .with_attributes(vec![
+                            KeyValue::new("route.hint", hint.clone()),
+                            KeyValue::new("route.provider", provider.clone()),
+                            KeyValue::new("route.model", model.clone()),
+                            KeyValue::new("route.reason", reason.clone()),
+                        ]),
+                );
+                span.end();
+            }
+            ObserverEvent::PlanProposed { steps, risks } => {
+                let mut span = tracer.build(
+                    opentelemetry::trace::SpanBuilder::from_name("agent.plan")
+                        .with_kind(SpanKind::Internal)
+                        .with_attributes(vec![
+                            KeyValue::new("plan.steps", steps.join("\n")),
+                            KeyValue::new("plan.risks", risks.join("\n")),
+                        ]),
+                );
+                span.end();
+            }
+            ObserverEvent::LlmCall {
+                model,
+                duration,
+                input_tokens,
+                output_tokens,
+                cost_usd,
+                ..
+            } => {
+                let start_time = SystemTime::now()
+                    .checked_sub(*duration)
+                    .unwrap_or(SystemTime::now());
+                let mut attributes = vec![
+                    KeyValue::new("gen_ai.request.model", model.clone()),
+                    KeyValue::new(
+                        "gen_ai.usage.input_tokens",
+                        i64::try_from(*input_tokens).unwrap_or(i64::MAX),
+                    ),
+                    KeyValue::new(
+                        "gen_ai.usage.output_tokens",
+                        i64::try_from(*output_tokens).unwrap_or(i64::MAX),
+                    ),
+                ];
+                if let Some(cost) = cost_usd {
+                    attributes.push(KeyValue::new("cost_usd", *cost));
+                }
+                let mut span = tracer.build(
+                    opentelemetry::trace::SpanBuilder::from_name("llm.call")
+                        .with_kind(SpanKind::Client)
+                        .with_start_time(start_time)
+                        .with_attributes(attributes),
+                );
+                span.end();
+            }
+            ObserverEvent::Score {
+                name,
+                value,
+                comment,
+            } => {
+                let mut span = tracer.build(
+                    opentelemetry::trace::SpanBuilder::from_name("score")
+                        .with_kind(SpanKind::Internal)
+                        .with_attributes(vec![
+                            KeyValue::new("score.name", name.clone()),
+                            KeyValue::new("score.value", *value),
+                            KeyValue::new("score.comment", comment.clone().unwrap_or_default()),
+                        ]),
+                );
+                span.end();
+            }
             ObserverEvent::Error { component, message } => {
                 // Create an error span for visibility in trace backends
                 let mut span = tracer.build(
@@ -0,0 +1,158 @@
+//! Per-tool usage statistics.
+//!
+//! Every tool call the agent makes is counted in
+//! `<workspace>/.zeroclaw/tool_stats.db`: invocations, failures, time spent
+//! and output produced, kept across sessions. The gateway serves them at
+//! `GET /stats` and C hosts read them with `zc_agent_get_stats`, to see which
+//! tools earn their place and which mostly fail.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Statistics database, relative to the workspace.
+pub const STATS_DB: &str = ".zeroclaw/tool_stats.db";
+
+/// Totals for one tool.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub invocations: u64,
+    pub failures: u64,
+    /// Share of invocations that succeeded, 0.0-1.0
+    pub success_rate: f64,
+    pub mean_latency_ms: f64,
+    /// Bytes of output (or error text) returned to the model
+    pub output_bytes: u64,
+    /// RFC 3339 time of the latest call
+    pub last_used: String,
+}
+
+/// Count one call of `tool` in `workspace_dir`. Failing to record is
+/// logged, never fatal.
+pub fn record(
+    workspace_dir: &Path,
+    tool: &str,
+    duration: Duration,
+    success: bool,
+    output_bytes: usize,
+) {
+    if let Err(e) = record_in(workspace_dir, tool, duration, success, output_bytes) {
+        tracing::warn!("Could not record tool stats for {tool}: {e:#}");
+    }
+}
+
+fn record_in(
+    workspace_dir: &Path,
+    tool: &str,
+    duration: Duration,
+    success: bool,
+    output_bytes: usize,
+) -> Result<()> {
+    with_connection(workspace_dir, |conn| {
+        conn.execute(
+            "INSERT INTO tool_stats
+                (tool, invocations, failures, total_latency_ms, output_bytes, last_used)
+             VALUES (?1, 1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(tool) DO UPDATE SET
+                invocations = invocations + 1,
+                failures = failures + excluded.failures,
+                total_latency_ms = total_latency_ms + excluded.total_latency_ms,
+                output_bytes = output_bytes + excluded.output_bytes,
+                last_used = excluded.last_used",
+            params![
+                tool,
+                i64::from(!success),
+                i64::try_from(duration.as_millis()).unwrap_or(i64::MAX),
+                i64::try_from(output_bytes).unwrap_or(i64::MAX),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    })
+}
+
+/// Totals for every tool used in `workspace_dir`, most used first.
+#[allow(clippy::cast_precision_loss)]
+pub fn summary(workspace_dir: &Path) -> Result<Vec<ToolStats>> {
+    if !workspace_dir.join(STATS_DB).exists() {
+        return Ok(Vec::new());
+    }
+    with_connection(workspace_dir, |conn| {
+        let mut stmt = conn.prepare(
+            "SELECT tool, invocations, failures, total_latency_ms, output_bytes, last_used
+             FROM tool_stats ORDER BY invocations DESC, tool",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let invocations: i64 = row.get(1)?;
+            let failures: i64 = row.get(2)?;
+            let total_latency_ms: i64 = row.get(3)?;
+            let calls = invocations.max(1) as f64;
+            Ok(ToolStats {
+                tool: row.get(0)?,
+                invocations: invocations.unsigned_abs(),
+                failures: failures.unsigned_abs(),
+                success_rate: (invocations - failures) as f64 / calls,
+                mean_latency_ms: total_latency_ms as f64 / calls,
+                output_bytes: row.get::<_, i64>(4)?.unsigned_abs(),
+                last_used: row.get(5)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    })
+}
+
+fn with_connection<T>(workspace_dir: &Path, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    let db_path = workspace_dir.join(STATS_DB);
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let conn = Connection::open(&db_path)
+        .with_context(|| format!("Failed to open tool stats DB: {}", db_path.display()))?;
+    conn.busy_timeout(Duration::from_secs(2))?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tool_stats (
+            tool             TEXT PRIMARY KEY,
+            invocations      INTEGER NOT NULL,
+            failures         INTEGER NOT NULL,
+            total_latency_ms INTEGER NOT NULL,
+            output_bytes     INTEGER NOT NULL,
+            last_used        TEXT NOT NULL
+        );",
+    )
+    .context("Failed to initialize tool stats schema")?;
+    f(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn calls_are_aggregated_per_tool() {
+        let tmp = TempDir::new().unwrap();
+        assert!(summary(tmp.path()).unwrap().is_empty());
+
+        let record = |tool, ms, success, bytes| {
+            record_in(tmp.path(), tool, Duration::from_millis(ms), success, bytes).unwrap();
+        };
+        record("shell", 100, true, 40);
+        record("shell", 300, false, 10);
+        record("file_read", 5, true, 2_000);
+
+        let stats = summary(tmp.path()).unwrap();
+        assert_eq!(stats.len(), 2);
+        let shell = &stats[0];
+        assert_eq!(shell.tool, "shell");
+        assert_eq!((shell.invocations, shell.failures), (2, 1));
+        assert!((shell.success_rate - 0.5).abs() < f64::EPSILON);
+        assert!((shell.mean_latency_ms - 200.0).abs() < f64::EPSILON);
+        assert_eq!(shell.output_bytes, 50);
+        assert_eq!(stats[1].tool, "file_read");
+    }
+}
@@ -0,0 +1,82 @@
+//! Which turn and session an event belongs to.
+//!
+//! Every agent turn runs inside [`turn`], which gives it a fresh trace id;
+//! front ends that keep conversations wrap their turns in [`session`] too.
+//! Sinks that group events (JSONL, webhooks, Langfuse) read both with
+//! [`current`] when an event is recorded.
+
+use std::future::Future;
+use uuid::Uuid;
+
+tokio::task_local! {
+    static SESSION: String;
+    static TRACE: String;
+}
+
+/// The trace and session of the running turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: String,
+    pub session_id: Option<String>,
+}
+
+/// Run `turns` as part of the conversation `session_id`.
+pub fn session<F: Future>(session_id: String, turns: F) -> impl Future<Output = F::Output> {
+    SESSION.scope(session_id, Box::pin(turns))
+}
+
+/// Run `turn` as a trace of its own, or as part of the enclosing trace
+/// when nested in one (a sub-agent, or an eval case that scores the turn).
+pub fn turn<F: Future>(turn: F) -> impl Future<Output = F::Output> {
+    let trace_id = TRACE
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| Uuid::new_v4().to_string());
+    TRACE.scope(trace_id, Box::pin(turn))
+}
+
+/// The running turn's trace, or None outside a turn.
+pub fn current() -> Option<TraceContext> {
+    let trace_id = TRACE.try_with(Clone::clone).ok()?;
+    Some(TraceContext {
+        trace_id,
+        session_id: SESSION.try_with(Clone::clone).ok(),
+    })
+}
+
+/// Add `trace_id` and `session_id` to an event record, inside a turn.
+pub fn annotate(record: &mut serde_json::Value) {
+    if let Some(context) = current() {
+        record["trace_id"] = context.trace_id.into();
+        if let Some(session_id) = context.session_id {
+            record["session_id"] = session_id.into();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn turns_get_their_own_trace_within_a_session() {
+        assert_eq!(current(), None);
+        let (first, second) = session("chat-1".into(), async {
+            assert_eq!(current(), None);
+            (
+                turn(async { current() }).await,
+                turn(async { current() }).await,
+            )
+        })
+        .await;
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_ne!(first.trace_id, second.trace_id);
+        assert_eq!(first.session_id.as_deref(), Some("chat-1"));
+        let (outer, inner) = turn(async { (current(), turn(async { current() }).await) }).await;
+        assert_eq!(outer, inner);
+
+        let mut record = serde_json::json!({"event": "tool.call"});
+        turn(async { annotate(&mut record) }).await;
+        assert!(record["trace_id"].is_string());
+        assert!(record.get("session_id").is_none());
+    }
+}
@@ -1,3 +1,4 @@
+use serde_json::json;
 use std::time::Duration;
 
 /// Events the observer can record
@@ -21,6 +22,39 @@ pub enum ObserverEvent {
         direction: String,
     },
     HeartbeatTick,
+    /// The model router picked a route from the request's heuristics
+    ModelRouted {
+        hint: String,
+        provider: String,
+        model: String,
+        /// What the request looked like, e.g. "1200 chars, code, chat"
+        reason: String,
+    },
+    /// The agent proposed a plan for a turn before acting on it
+    PlanProposed {
+        /// One line per step, naming its tool when it uses one
+        steps: Vec<String>,
+        risks: Vec<String>,
+    },
+    /// One model call within a turn
+    LlmCall {
+        model: String,
+        duration: Duration,
+        /// Estimated tokens sent and received
+        input_tokens: u64,
+        output_tokens: u64,
+        /// USD, when the model's profile sets prices
+        cost_usd: Option<f64>,
+        /// The last message sent: the user's, or the tool results
+        input: String,
+        output: String,
+    },
+    /// A judgement of the turn's answer, e.g. user feedback or an eval check
+    Score {
+        name: String,
+        value: f64,
+        comment: Option<String>,
+    },
     Error {
         component: String,
         message: String,
@@ -36,6 +70,96 @@ pub enum ObserverMetric {
     QueueDepth(u64),
 }
 
+impl ObserverEvent {
+    /// The event as a JSON object tagged with its `event` name, for sinks
+    /// that write or send events as JSON.
+    pub fn to_json(&self) -> serde_json::Value {
+        let millis = |d: &Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+        match self {
+            Self::AgentStart { provider, model } => {
+                json!({"event": "agent.start", "provider": provider, "model": model})
+            }
+            Self::AgentEnd {
+                duration,
+                tokens_used,
+            } => {
+                json!({"event": "agent.end", "duration_ms": millis(duration), "tokens": tokens_used})
+            }
+            Self::ToolCall {
+                tool,
+                duration,
+                success,
+            } => json!({
+                "event": "tool.call",
+                "tool": tool,
+                "duration_ms": millis(duration),
+                "success": success,
+            }),
+            Self::ChannelMessage { channel, direction } => {
+                json!({"event": "channel.message", "channel": channel, "direction": direction})
+            }
+            Self::HeartbeatTick => json!({"event": "heartbeat.tick"}),
+            Self::ModelRouted {
+                hint,
+                provider,
+                model,
+                reason,
+            } => json!({
+                "event": "model.route",
+                "hint": hint,
+                "provider": provider,
+                "model": model,
+                "reason": reason,
+            }),
+            Self::PlanProposed { steps, risks } => {
+                json!({"event": "agent.plan", "steps": steps, "risks": risks})
+            }
+            Self::LlmCall {
+                model,
+                duration,
+                input_tokens,
+                output_tokens,
+                cost_usd,
+                input,
+                output,
+            } => json!({
+                "event": "llm.call",
+                "model": model,
+                "duration_ms": millis(duration),
+                "input_tokens": input_tokens,
+                "output_tokens": output_tokens,
+                "cost_usd": cost_usd,
+                "input": input,
+                "output": output,
+            }),
+            Self::Score {
+                name,
+                value,
+                comment,
+            } => json!({"event": "score", "name": name, "value": value, "comment": comment}),
+            Self::Error { component, message } => {
+                json!({"event": "error", "component": component, "error": message})
+            }
+        }
+    }
+}
+
+impl ObserverMetric {
+    /// The metric as a JSON object with its `metric` name and `value`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let (metric, value) = match self {
+            Self::RequestLatency(d) => (
+                "request_latency_ms",
+                u64::try_from(d.as_millis()).unwrap_or(u64::MAX),
+            ),
+            Self::TokensUsed(n) => ("tokens_used", *n),
+            Self::ActiveSessions(n) => ("active_sessions", *n),
+            Self::QueueDepth(n) => ("queue_depth", *n),
+        };
+        json!({"metric": metric, "value": value})
+    }
+}
+
 /// Core observability trait — implement for any backend
 pub trait Observer: Send + Sync + 'static {
     /// Record a discrete event
@@ -0,0 +1,140 @@
+use super::trace;
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use crate::config::WebhookSinkConfig;
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+/// Webhook observer — POSTs events to a URL as JSON arrays from a
+/// background thread, so recording never waits on the network
+pub struct WebhookObserver {
+    queue: Sender<serde_json::Value>,
+}
+
+impl WebhookObserver {
+    pub fn new(config: &WebhookSinkConfig) -> Self {
+        let config = config.clone();
+        let mut client = None;
+        let queue = spawn_batcher("observer-webhook", config.batch_size, move |batch| {
+            let client = client.get_or_insert_with(http_client);
+            let mut request = client.post(&config.url).json(&batch);
+            for (name, value) in &config.headers {
+                request = request.header(name, value);
+            }
+            let sent = request
+                .send()
+                .and_then(reqwest::blocking::Response::error_for_status);
+            if let Err(e) = sent {
+                tracing::warn!("Dropped {} observer events: {e}", batch.len());
+            }
+        });
+        Self { queue }
+    }
+
+    fn send(&self, mut record: serde_json::Value) {
+        record["ts"] = chrono::Utc::now().to_rfc3339().into();
+        trace::annotate(&mut record);
+        // The thread only stops once this observer is dropped
+        let _ = self.queue.send(record);
+    }
+}
+
+/// Start a thread that hands the records sent on the returned queue to
+/// `send`, each with whatever queued up behind it (up to `batch_size` in
+/// all), until the queue is dropped. Blocking clients must be created on
+/// that thread, inside `send`.
+pub(super) fn spawn_batcher(
+    name: &str,
+    batch_size: usize,
+    mut send: impl FnMut(Vec<serde_json::Value>) + Send + 'static,
+) -> Sender<serde_json::Value> {
+    let (queue, pending) = mpsc::channel::<serde_json::Value>();
+    std::thread::Builder::new()
+        .name(name.into())
+        .spawn(move || {
+            while let Ok(first) = pending.recv() {
+                let mut batch = vec![first];
+                batch.extend(pending.try_iter().take(batch_size.max(1) - 1));
+                send(batch);
+            }
+        })
+        .expect("failed to spawn an observer thread");
+    queue
+}
+
+pub(super) fn http_client() -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default()
+}
+
+impl Observer for WebhookObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        self.send(event.to_json());
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        self.send(metric.to_json());
+    }
+
+    fn name(&self) -> &str {
+        "webhook"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// Accept one request and return its headers and body.
+    fn receive_one(listener: &TcpListener) -> (String, serde_json::Value) {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut headers = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            headers.push_str(&line.to_lowercase());
+        }
+        let length: usize = headers
+            .lines()
+            .find_map(|l| l.strip_prefix("content-length:"))
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        (&stream)
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .unwrap();
+        (headers, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[test]
+    fn events_are_posted_as_json_arrays() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = WebhookSinkConfig {
+            url: format!("http://{}/events", listener.local_addr().unwrap()),
+            headers: BTreeMap::from([("Authorization".into(), "Bearer t0ken".into())]),
+            batch_size: 10,
+        };
+        let observer = WebhookObserver::new(&config);
+        observer.record_event(&ObserverEvent::Error {
+            component: "gateway".into(),
+            message: "boom".into(),
+        });
+
+        let (headers, body) = receive_one(&listener);
+        assert!(headers.contains("authorization: bearer t0ken"));
+        assert_eq!(body[0]["event"], "error");
+        assert_eq!(body[0]["component"], "gateway");
+        assert!(body[0]["ts"].is_string());
+    }
+}
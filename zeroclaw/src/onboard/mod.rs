@@ -1,3 +1,6 @@
+pub mod setup;
+pub mod templates;
 pub mod wizard;
 
+pub use setup::{needs_setup, run_setup};
 pub use wizard::{run_channels_repair_wizard, run_quick_setup, run_wizard};
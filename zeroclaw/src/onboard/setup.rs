@@ -0,0 +1,160 @@
+//! First-run setup.
+//!
+//! A short guided flow for new users: pick a provider and model, enter the
+//! API key (stored encrypted by the secret store), choose the workspace and
+//! an autonomy level, then write a config that has passed
+//! [`Config::validate`]. Without it, the first run silently wrote defaults
+//! with no provider key. `onboard --interactive` covers channels, tunnels
+//! and the rest.
+
+use super::wizard::{print_bullet, print_step, setup_provider};
+use crate::config::Config;
+use crate::security::AutonomyLevel;
+use anyhow::{Context, Result};
+use console::style;
+use dialoguer::{Confirm, Input, Select};
+use std::path::{Path, PathBuf};
+
+/// Whether no config exists yet: no `~/.zeroclaw/config.toml`, no
+/// `~/.cclaw/config.json` and no shared `/etc` defaults.
+pub fn needs_setup() -> bool {
+    let Some(home) = directories::UserDirs::new().map(|u| u.home_dir().to_path_buf()) else {
+        return false;
+    };
+    !home.join(".zeroclaw").join("config.toml").exists()
+        && !home.join(".cclaw").join("config.json").exists()
+        && !Path::new(crate::config::layers::SYSTEM_CONFIG).exists()
+}
+
+/// Run the guided setup and save the config to `~/.zeroclaw/config.toml`.
+pub fn run_setup() -> Result<Config> {
+    let home = directories::UserDirs::new()
+        .map(|u| u.home_dir().to_path_buf())
+        .context("Could not find home directory")?;
+
+    println!();
+    println!("  {}", style("ZeroClaw setup").white().bold());
+    println!(
+        "  {}",
+        style("Five steps; rerun `zeroclaw setup` at any time to change them.").dim()
+    );
+
+    print_step(1, 5, "Provider, API key & model");
+    let (provider, api_key, model) = setup_provider()?;
+
+    print_step(2, 5, "Workspace");
+    let default_workspace = home.join(".cclaw");
+    let workspace_dir = choose_workspace(&default_workspace)?;
+
+    print_step(3, 5, "Autonomy");
+    let level = choose_autonomy()?;
+
+    let mut config = Config {
+        config_path: home.join(".zeroclaw").join("config.toml"),
+        workspace: (workspace_dir != default_workspace)
+            .then(|| workspace_dir.display().to_string()),
+        workspace_dir,
+        api_key: (!api_key.is_empty()).then_some(api_key),
+        default_provider: Some(provider),
+        default_model: Some(model),
+        ..Config::default()
+    };
+    config.autonomy.level = level;
+
+    print_step(4, 5, "Check");
+    let warnings = config.validate();
+    if warnings.is_empty() {
+        println!("  {} No problems found", style("✓").green().bold());
+    } else {
+        for warning in &warnings {
+            println!("  {} {warning}", style("!").yellow().bold());
+        }
+        let save = Confirm::new()
+            .with_prompt("  Save this config anyway?")
+            .default(false)
+            .interact()?;
+        if !save {
+            anyhow::bail!("Setup cancelled; nothing was written");
+        }
+    }
+
+    print_step(5, 5, "Save");
+    std::fs::create_dir_all(&config.workspace_dir)
+        .context("Failed to create workspace directory")?;
+    config.save_encrypted()?;
+    println!(
+        "  {} Saved {}",
+        style("✓").green().bold(),
+        style(config.config_path.display()).green()
+    );
+    if config.api_key.is_some() && config.secrets.encrypt {
+        print_bullet("The API key is stored encrypted; the key file sits next to the config.");
+    }
+    if home.join(".cclaw").join("config.json").exists() {
+        print_bullet(&format!(
+            "{} also exists and takes precedence; remove it to use this config.",
+            style("~/.cclaw/config.json").yellow()
+        ));
+    }
+    Ok(config)
+}
+
+fn choose_workspace(default: &Path) -> Result<PathBuf> {
+    print_bullet("The workspace holds memory, sessions, skills and instruction files.");
+    let current = std::env::current_dir().ok();
+    let mut choices = vec![format!("{} (default)", default.display())];
+    if let Some(dir) = &current {
+        choices.push(format!("{} (current directory)", dir.display()));
+    }
+    choices.push("Somewhere else…".to_string());
+
+    let choice = Select::new()
+        .with_prompt("  Workspace")
+        .items(&choices)
+        .default(0)
+        .interact()?;
+    let dir = match (choice, &current) {
+        (0, _) => default.to_path_buf(),
+        (1, Some(dir)) => dir.clone(),
+        _ => {
+            let path: String = Input::new()
+                .with_prompt("  Workspace path")
+                .interact_text()?;
+            PathBuf::from(shellexpand::tilde(path.trim()).as_ref())
+        }
+    };
+    println!(
+        "  {} Workspace: {}",
+        style("✓").green().bold(),
+        style(dir.display()).green()
+    );
+    Ok(dir)
+}
+
+/// Each level, with what it means in practice.
+const AUTONOMY_LEVELS: [(AutonomyLevel, &str); 3] = [
+    (
+        AutonomyLevel::ReadOnly,
+        "Read-only — reads files and answers; never writes, runs commands or acts",
+    ),
+    (
+        AutonomyLevel::Supervised,
+        "Supervised — acts, but asks before risky commands (recommended)",
+    ),
+    (
+        AutonomyLevel::Full,
+        "Full — acts on its own within the command and path allowlists",
+    ),
+];
+
+fn choose_autonomy() -> Result<AutonomyLevel> {
+    print_bullet("How much the agent may do without asking you first.");
+    print_bullet("Change it later under [autonomy] in the config.");
+    let labels: Vec<&str> = AUTONOMY_LEVELS.iter().map(|(_, label)| *label).collect();
+    let choice = Select::new()
+        .with_prompt("  Autonomy level")
+        .items(&labels)
+        .default(1)
+        .interact()?;
+    Ok(AUTONOMY_LEVELS[choice].0)
+}
@@ -0,0 +1,371 @@
+//! Persona templates for `zeroclaw init --template <name>`.
+//!
+//! A template lays the usual workspace files down (see the onboarding
+//! scaffold), then adds persona instructions in `PERSONA.md`, a few starter
+//! skills, a policy preset merged into `[autonomy]`, and example cron jobs.
+//! Existing files are never overwritten, so `init` can be re-run safely.
+
+use super::wizard::{scaffold_workspace, ProjectContext};
+use crate::config::Config;
+use crate::security::AutonomyLevel;
+use anyhow::Result;
+use console::style;
+use std::fs;
+use std::path::PathBuf;
+
+const PERSONA_FILE: &str = "PERSONA.md";
+
+pub struct WorkspaceTemplate {
+    pub name: &'static str,
+    pub summary: &'static str,
+    instructions: &'static str,
+    /// (directory name, SKILL.md contents)
+    skills: &'static [(&'static str, &'static str)],
+    policy: PolicyPreset,
+    /// (cron expression, command)
+    schedules: &'static [(&'static str, &'static str)],
+}
+
+struct PolicyPreset {
+    level: AutonomyLevel,
+    /// Added to `allowed_commands`; existing entries are kept
+    commands: &'static [&'static str],
+    max_actions_per_hour: u32,
+}
+
+pub const TEMPLATES: [WorkspaceTemplate; 3] = [
+    WorkspaceTemplate {
+        name: "coding",
+        summary: "Pair programmer: reviews diffs, runs tests, fixes failures",
+        instructions: "# PERSONA.md — Coding Partner\n\n\
+            You work inside software projects. Favour small, reviewable changes.\n\n\
+            - Read the surrounding code before editing; match its style.\n\
+            - Run the project's build and tests after every change and report the result.\n\
+            - Never force-push, rewrite history, or touch files outside the task.\n\
+            - When a fix is uncertain, explain the trade-off instead of guessing.\n",
+        skills: &[
+            (
+                "code-review",
+                "---\ndescription: Review a diff for bugs, missing tests and style drift\nversion: 0.1.0\ntags: [coding, review]\n---\n\
+                 # Code Review\n\n\
+                 1. Run `git diff` (or `git diff --staged`) to see the change.\n\
+                 2. Check each hunk for logic errors, unhandled errors and edge cases.\n\
+                 3. Note missing or weakened tests.\n\
+                 4. Flag naming or structure that differs from neighbouring code.\n\
+                 5. Reply with findings ordered by severity, each with file:line.\n",
+            ),
+            (
+                "test-fix-loop",
+                "---\ndescription: Run the test suite, fix the first failure, repeat\nversion: 0.1.0\ntags: [coding, testing]\n---\n\
+                 # Test-Fix Loop\n\n\
+                 1. Detect the test command (`cargo test`, `npm test`, `pytest`, `make test`).\n\
+                 2. Run it and pick the first failure.\n\
+                 3. Read the failing test and the code under test; make the smallest fix.\n\
+                 4. Re-run. Stop after five rounds or when everything passes, and summarize.\n",
+            ),
+        ],
+        policy: PolicyPreset {
+            level: AutonomyLevel::Supervised,
+            commands: &[
+                "git", "cargo", "npm", "npx", "node", "python3", "pytest", "make", "rg", "diff",
+                "zeroclaw",
+            ],
+            max_actions_per_hour: 500,
+        },
+        schedules: &[(
+            "0 9 * * 1-5",
+            "zeroclaw agent -m \"Summarize uncommitted changes and failing tests in the workspace projects\"",
+        )],
+    },
+    WorkspaceTemplate {
+        name: "assistant",
+        summary: "Personal assistant: briefings, reminders, inbox triage",
+        instructions: "# PERSONA.md — Personal Assistant\n\n\
+            You help one person stay on top of their day.\n\n\
+            - Keep answers short; lead with what needs action.\n\
+            - Store preferences and recurring facts in memory as you learn them.\n\
+            - Ask before sending anything on the user's behalf.\n\
+            - Never share personal details with third parties.\n",
+        skills: &[
+            (
+                "daily-briefing",
+                "---\ndescription: Morning briefing from calendar, reminders and memory\nversion: 0.1.0\ntags: [assistant, planning]\n---\n\
+                 # Daily Briefing\n\n\
+                 1. Check today's calendar events, if a calendar is configured.\n\
+                 2. Recall open reminders and follow-ups from memory.\n\
+                 3. List at most five priorities for the day, most urgent first.\n\
+                 4. End with anything that needs a decision today.\n",
+            ),
+            (
+                "inbox-triage",
+                "---\ndescription: Sort incoming messages into act, delegate, read later, archive\nversion: 0.1.0\ntags: [assistant, email]\n---\n\
+                 # Inbox Triage\n\n\
+                 1. Group new messages by sender and thread.\n\
+                 2. Label each: act now, reply later, read later, or archive.\n\
+                 3. Draft short replies for \"act now\" items but do not send them.\n\
+                 4. Report counts per label and the drafts for review.\n",
+            ),
+        ],
+        policy: PolicyPreset {
+            level: AutonomyLevel::Supervised,
+            commands: &["date", "cal", "zeroclaw"],
+            max_actions_per_hour: 200,
+        },
+        schedules: &[(
+            "0 8 * * *",
+            "zeroclaw agent -m \"Prepare my daily briefing\"",
+        )],
+    },
+    WorkspaceTemplate {
+        name: "ops",
+        summary: "Operations: health checks, incident triage, log digging",
+        instructions: "# PERSONA.md — Operations\n\n\
+            You watch over servers and services.\n\n\
+            - Diagnose with read-only commands first (status, logs, metrics).\n\
+            - Propose restarts, deploys or config changes; never apply them unasked.\n\
+            - Quote exact command output when reporting.\n\
+            - During an incident, keep a timeline of what was checked and found.\n",
+        skills: &[
+            (
+                "incident-triage",
+                "---\ndescription: First response to an alert or outage report\nversion: 0.1.0\ntags: [ops, incident]\n---\n\
+                 # Incident Triage\n\n\
+                 1. Restate the symptom and when it started.\n\
+                 2. Check service status, recent logs and resource usage on the affected host.\n\
+                 3. Compare against recent deploys or config changes.\n\
+                 4. Report the most likely cause, the evidence, and a proposed next step.\n",
+            ),
+            (
+                "health-check",
+                "---\ndescription: Routine host health report\nversion: 0.1.0\ntags: [ops, monitoring]\n---\n\
+                 # Health Check\n\n\
+                 1. Disk: `df -h`; flag filesystems above 85%.\n\
+                 2. Memory and load: `free -m`, `uptime`.\n\
+                 3. Failed units: `systemctl --failed` where systemd is available.\n\
+                 4. Summarize in one line per finding; say \"all clear\" when nothing is wrong.\n",
+            ),
+        ],
+        policy: PolicyPreset {
+            level: AutonomyLevel::Supervised,
+            commands: &[
+                "df", "du", "free", "uptime", "ps", "systemctl", "journalctl", "docker", "kubectl",
+                "curl", "ping", "zeroclaw",
+            ],
+            max_actions_per_hour: 300,
+        },
+        schedules: &[(
+            "0 */6 * * *",
+            "zeroclaw agent -m \"Run the health-check skill and report anything abnormal\"",
+        )],
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static WorkspaceTemplate> {
+    TEMPLATES.iter().find(|t| t.name == name)
+}
+
+/// What `apply` did.
+#[derive(Debug, Default)]
+pub struct InitReport {
+    pub created: Vec<PathBuf>,
+    /// Already present, left untouched
+    pub kept: Vec<PathBuf>,
+    pub commands_added: Vec<String>,
+    /// (job id, expression, command)
+    pub schedules: Vec<(String, String, String)>,
+}
+
+/// Lay the template down in `config.workspace_dir` and save the updated
+/// config. Schedules already present (same expression and command) are not
+/// added twice.
+pub fn apply(
+    config: &mut Config,
+    template: &WorkspaceTemplate,
+    with_schedules: bool,
+) -> Result<InitReport> {
+    let workspace = config.workspace_dir.clone();
+    fs::create_dir_all(&workspace)?;
+    scaffold_workspace(
+        &workspace,
+        &ProjectContext {
+            user_name: std::env::var("USER").unwrap_or_else(|_| "User".into()),
+            timezone: "UTC".into(),
+            agent_name: "ZeroClaw".into(),
+            communication_style: String::new(),
+        },
+    )?;
+
+    let mut report = InitReport::default();
+    let mut files: Vec<(PathBuf, &str)> =
+        vec![(workspace.join(PERSONA_FILE), template.instructions)];
+    for (name, body) in template.skills {
+        files.push((
+            crate::skills::skills_dir(&workspace)
+                .join(name)
+                .join("SKILL.md"),
+            body,
+        ));
+    }
+    for (path, body) in files {
+        if path.exists() {
+            report.kept.push(path);
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, body)?;
+        report.created.push(path);
+    }
+
+    // PERSONA.md joins the default instruction files
+    let instructions = &mut config.identity.instruction_files;
+    if instructions.is_empty() {
+        for file in crate::identity::DEFAULT_INSTRUCTION_FILES {
+            instructions.insert(file.to_string(), 0);
+        }
+    }
+    instructions.entry(PERSONA_FILE.to_string()).or_insert(0);
+
+    let autonomy = &mut config.autonomy;
+    autonomy.level = template.policy.level;
+    autonomy.max_actions_per_hour = template.policy.max_actions_per_hour;
+    for command in template.policy.commands {
+        if !autonomy.allowed_commands.iter().any(|c| c == command) {
+            autonomy.allowed_commands.push((*command).to_string());
+            report.commands_added.push((*command).to_string());
+        }
+    }
+    config.save_encrypted()?;
+
+    if with_schedules {
+        let existing = crate::cron::list_jobs(config)?;
+        for (expression, command) in template.schedules {
+            if existing
+                .iter()
+                .any(|j| j.expression == *expression && j.command == *command)
+            {
+                continue;
+            }
+            let job = crate::cron::add_job(config, expression, command)?;
+            report.schedules.push((job.id, job.expression, job.command));
+        }
+    }
+
+    Ok(report)
+}
+
+/// `zeroclaw init --template <name>`
+pub fn init_workspace(mut config: Config, name: &str, with_schedules: bool) -> Result<()> {
+    let Some(template) = find(name) else {
+        let names: Vec<&str> = TEMPLATES.iter().map(|t| t.name).collect();
+        anyhow::bail!(
+            "Unknown template '{name}' (available: {})",
+            names.join(", ")
+        );
+    };
+
+    println!(
+        "Initializing {} with the {} template — {}",
+        config.workspace_dir.display(),
+        style(template.name).white().bold(),
+        template.summary
+    );
+    let report = apply(&mut config, template, with_schedules)?;
+
+    for path in &report.created {
+        println!("  {} {}", style("✓").green().bold(), path.display());
+    }
+    for path in &report.kept {
+        println!("  {} {} (exists, kept)", style("·").dim(), path.display());
+    }
+    println!(
+        "  {} Autonomy: {:?}, {} actions/hour",
+        style("✓").green().bold(),
+        config.autonomy.level,
+        config.autonomy.max_actions_per_hour
+    );
+    if !report.commands_added.is_empty() {
+        println!(
+            "  {} Allowed commands added: {}",
+            style("✓").green().bold(),
+            report.commands_added.join(", ")
+        );
+    }
+    for (id, expression, command) in &report.schedules {
+        println!(
+            "  {} Scheduled [{expression}] {command}\n      remove with: zeroclaw cron remove {id}",
+            style("✓").green().bold()
+        );
+    }
+    println!();
+    println!("  Edit {PERSONA_FILE} to adjust the persona, then run `zeroclaw daemon`.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_config(tmp: &TempDir) -> Config {
+        Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn every_template_applies_cleanly() {
+        for template in &TEMPLATES {
+            let tmp = TempDir::new().unwrap();
+            let mut config = test_config(&tmp);
+            let report = apply(&mut config, template, true).unwrap();
+
+            assert!(config.workspace_dir.join("AGENTS.md").exists());
+            assert!(config.workspace_dir.join(PERSONA_FILE).exists());
+            assert_eq!(report.schedules.len(), template.schedules.len());
+            let skills = crate::skills::load_skills(&config.workspace_dir);
+            for (name, _) in template.skills {
+                assert!(
+                    skills.iter().any(|s| s.name == *name),
+                    "{name} not loadable"
+                );
+            }
+            assert!(config.identity.instruction_files.contains_key("SOUL.md"));
+            assert!(config.identity.instruction_files.contains_key(PERSONA_FILE));
+            assert!(config.config_path.exists());
+        }
+    }
+
+    #[test]
+    fn reapplying_keeps_edits_and_does_not_duplicate() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        let template = find("ops").unwrap();
+        apply(&mut config, template, true).unwrap();
+        let persona = config.workspace_dir.join(PERSONA_FILE);
+        fs::write(&persona, "custom").unwrap();
+
+        let report = apply(&mut config, template, true).unwrap();
+        assert!(report.created.is_empty());
+        assert!(report.commands_added.is_empty());
+        assert!(report.schedules.is_empty());
+        assert_eq!(fs::read_to_string(&persona).unwrap(), "custom");
+        assert_eq!(crate::cron::list_jobs(&config).unwrap().len(), 1);
+        let df = config
+            .autonomy
+            .allowed_commands
+            .iter()
+            .filter(|c| *c == "df");
+        assert_eq!(df.count(), 1);
+    }
+
+    #[test]
+    fn unknown_template_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let err = init_workspace(test_config(&tmp), "gaming", true).unwrap_err();
+        assert!(err.to_string().contains("coding, assistant, ops"));
+    }
+}
@@ -1,8 +1,10 @@
 use crate::config::schema::{IrcConfig, WhatsAppConfig};
 use crate::config::{
     AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, DiscordConfig,
-    HeartbeatConfig, IMessageConfig, MatrixConfig, MemoryConfig, ObservabilityConfig,
-    RuntimeConfig, SecretsConfig, SlackConfig, TelegramConfig, WebhookConfig,
+    HeartbeatConfig, IMessageConfig, MatrixConfig, MemoryConfig, MemoryDedupConfig,
+    MemoryExtractConfig, MemoryGraphConfig, MemoryIsolationConfig, MemoryRecallConfig,
+    MemorySummaryConfig, ObservabilityConfig, RuntimeConfig, SecretsConfig, SlackConfig,
+    TelegramConfig, WebhookConfig,
 };
 use anyhow::{Context, Result};
 use console::style;
@@ -40,6 +42,7 @@ const BANNER: &str = r"
 
 // ── Main wizard entry point ──────────────────────────────────────
 
+#[allow(clippy::too_many_lines)]
 pub fn run_wizard() -> Result<Config> {
     println!("{}", style(BANNER).cyan().bold());
 
@@ -106,6 +109,39 @@ pub fn run_wizard() -> Result<Config> {
         secrets: secrets_config,
         browser: BrowserConfig::default(),
         identity: crate::config::IdentityConfig::default(),
+        triggers: crate::config::TriggersConfig::default(),
+        knowledge: crate::config::KnowledgeConfig::default(),
+        skill_registry: crate::config::SkillRegistryConfig::default(),
+        queue: crate::config::QueueConfig::default(),
+        response_cache: crate::config::ResponseCacheConfig::default(),
+        azure_openai: crate::config::AzureOpenAiConfig::default(),
+        gemini: crate::config::GeminiConfig::default(),
+        tool_output: crate::config::ToolOutputConfig::default(),
+        render: crate::config::RenderConfig::default(),
+        providers: std::collections::BTreeMap::new(),
+        profiles: std::collections::BTreeMap::new(),
+        oauth: std::collections::BTreeMap::new(),
+        workspace: None,
+        checkpoints: crate::config::CheckpointConfig::default(),
+        calendar: crate::config::CalendarConfig::default(),
+        model_profiles: Vec::new(),
+        verifier: crate::config::VerifierConfig::default(),
+        guardrails: crate::config::GuardrailsConfig::default(),
+        web_search: crate::config::WebSearchConfig::default(),
+        vision: crate::config::VisionConfig::default(),
+        tts: crate::config::TtsConfig::default(),
+        skill_distill: crate::config::SkillDistillConfig::default(),
+        digest: crate::config::DigestConfig::default(),
+        jobs: crate::config::JobsConfig::default(),
+        workflows: crate::config::WorkflowsConfig::default(),
+        agent_bus: crate::config::AgentBusConfig::default(),
+        a2a: crate::config::A2aConfig::default(),
+        nodes: crate::config::NodesConfig::default(),
+        grpc: crate::config::GrpcConfig::default(),
+        plugins: crate::config::PluginsConfig::default(),
+        hooks: crate::config::HooksConfig::default(),
+        transcription: crate::config::TranscriptionConfig::default(),
+        sessions: crate::config::SessionsConfig::default(),
     };
 
     println!(
@@ -173,7 +209,7 @@ pub fn run_channels_repair_wizard() -> Result<Config> {
 
     print_step(1, 1, "Channels (How You Talk to ZeroClaw)");
     config.channels_config = setup_channels()?;
-    config.save()?;
+    config.save_encrypted()?;
 
     println!();
     println!(
@@ -274,6 +310,13 @@ pub fn run_quick_setup(
             0
         },
         chunk_max_tokens: 512,
+        namespace: None,
+        recall: MemoryRecallConfig::default(),
+        dedup: MemoryDedupConfig::default(),
+        extract: MemoryExtractConfig::default(),
+        graph: MemoryGraphConfig::default(),
+        isolation: MemoryIsolationConfig::default(),
+        summary: MemorySummaryConfig::default(),
     };
 
     let config = Config {
@@ -297,6 +340,39 @@ pub fn run_quick_setup(
         secrets: SecretsConfig::default(),
         browser: BrowserConfig::default(),
         identity: crate::config::IdentityConfig::default(),
+        triggers: crate::config::TriggersConfig::default(),
+        knowledge: crate::config::KnowledgeConfig::default(),
+        skill_registry: crate::config::SkillRegistryConfig::default(),
+        queue: crate::config::QueueConfig::default(),
+        response_cache: crate::config::ResponseCacheConfig::default(),
+        azure_openai: crate::config::AzureOpenAiConfig::default(),
+        gemini: crate::config::GeminiConfig::default(),
+        tool_output: crate::config::ToolOutputConfig::default(),
+        render: crate::config::RenderConfig::default(),
+        providers: std::collections::BTreeMap::new(),
+        profiles: std::collections::BTreeMap::new(),
+        oauth: std::collections::BTreeMap::new(),
+        workspace: None,
+        checkpoints: crate::config::CheckpointConfig::default(),
+        calendar: crate::config::CalendarConfig::default(),
+        model_profiles: Vec::new(),
+        verifier: crate::config::VerifierConfig::default(),
+        guardrails: crate::config::GuardrailsConfig::default(),
+        web_search: crate::config::WebSearchConfig::default(),
+        vision: crate::config::VisionConfig::default(),
+        tts: crate::config::TtsConfig::default(),
+        skill_distill: crate::config::SkillDistillConfig::default(),
+        digest: crate::config::DigestConfig::default(),
+        jobs: crate::config::JobsConfig::default(),
+        workflows: crate::config::WorkflowsConfig::default(),
+        agent_bus: crate::config::AgentBusConfig::default(),
+        a2a: crate::config::A2aConfig::default(),
+        nodes: crate::config::NodesConfig::default(),
+        grpc: crate::config::GrpcConfig::default(),
+        plugins: crate::config::PluginsConfig::default(),
+        hooks: crate::config::HooksConfig::default(),
+        transcription: crate::config::TranscriptionConfig::default(),
+        sessions: crate::config::SessionsConfig::default(),
     };
 
     config.save()?;
@@ -410,7 +486,7 @@ fn default_model_for_provider(provider: &str) -> String {
 
 // ── Step helpers ─────────────────────────────────────────────────
 
-fn print_step(current: u8, total: u8, title: &str) {
+pub(super) fn print_step(current: u8, total: u8, title: &str) {
     println!();
     println!(
         "  {} {}",
@@ -420,7 +496,7 @@ fn print_step(current: u8, total: u8, title: &str) {
     println!("  {}", style("─".repeat(50)).dim());
 }
 
-fn print_bullet(text: &str) {
+pub(super) fn print_bullet(text: &str) {
     println!("  {} {}", style("›").cyan(), text);
 }
 
@@ -469,7 +545,7 @@ fn setup_workspace() -> Result<(PathBuf, PathBuf)> {
 // ── Step 2: Provider & API Key ───────────────────────────────────
 
 #[allow(clippy::too_many_lines)]
-fn setup_provider() -> Result<(String, String, String)> {
+pub(super) fn setup_provider() -> Result<(String, String, String)> {
     // ── Tier selection ──
     let tiers = vec![
         "⭐ Recommended (OpenRouter, Venice, Anthropic, OpenAI, Gemini)",
@@ -798,6 +874,14 @@ fn setup_provider() -> Result<(String, String, String)> {
         ],
         "gemini" | "google" | "google-gemini" => vec![
             ("gemini-2.0-flash", "Gemini 2.0 Flash (fast, recommended)"),
+            (
+                "gemini-2.5-pro",
+                "Gemini 2.5 Pro (1M context, best quality)",
+            ),
+            (
+                "gemini-2.5-flash",
+                "Gemini 2.5 Flash (1M context, balanced)",
+            ),
             (
                 "gemini-2.0-flash-lite",
                 "Gemini 2.0 Flash Lite (fastest, cheapest)",
@@ -1102,6 +1186,13 @@ fn setup_memory() -> Result<MemoryConfig> {
         keyword_weight: 0.3,
         embedding_cache_size: if backend == "sqlite" { 10000 } else { 0 },
         chunk_max_tokens: 512,
+        namespace: None,
+        recall: MemoryRecallConfig::default(),
+        dedup: MemoryDedupConfig::default(),
+        extract: MemoryExtractConfig::default(),
+        graph: MemoryGraphConfig::default(),
+        isolation: MemoryIsolationConfig::default(),
+        summary: MemorySummaryConfig::default(),
     })
 }
 
@@ -1285,6 +1376,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
                 config.telegram = Some(TelegramConfig {
                     bot_token: token,
                     allowed_users,
+                    voice_replies: false,
+                    allowed_tools: None,
                 });
             }
             1 => {
@@ -1375,6 +1468,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     bot_token: token,
                     guild_id: if guild.is_empty() { None } else { Some(guild) },
                     allowed_users,
+                    voice_replies: false,
+                    allowed_tools: None,
                 });
             }
             2 => {
@@ -1493,6 +1588,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         Some(channel)
                     },
                     allowed_users,
+                    allowed_tools: None,
                 });
             }
             3 => {
@@ -1532,7 +1628,10 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         .collect()
                 };
 
-                config.imessage = Some(IMessageConfig { allowed_contacts });
+                config.imessage = Some(IMessageConfig {
+                    allowed_contacts,
+                    allowed_tools: None,
+                });
                 println!(
                     "  {} iMessage configured (contacts: {})",
                     style("✅").green().bold(),
@@ -1617,6 +1716,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     access_token,
                     room_id,
                     allowed_users,
+                    allowed_tools: None,
                 });
             }
             5 => {
@@ -1702,6 +1802,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     verify_token: verify_token.trim().to_string(),
                     app_secret: None, // Can be set via ZEROCLAW_WHATSAPP_APP_SECRET env var
                     allowed_numbers,
+                    allowed_tools: None,
                 });
             }
             6 => {
@@ -1841,6 +1942,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         Some(sasl_password.trim().to_string())
                     },
                     verify_tls: Some(verify_tls),
+                    allowed_tools: None,
                 });
             }
             7 => {
@@ -2076,7 +2178,7 @@ fn setup_tunnel() -> Result<crate::config::TunnelConfig> {
 // ── Step 6: Scaffold workspace files ─────────────────────────────
 
 #[allow(clippy::too_many_lines)]
-fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Result<()> {
+pub(super) fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Result<()> {
     let agent = if ctx.agent_name.is_empty() {
         "ZeroClaw"
     } else {
@@ -0,0 +1,238 @@
+//! Named prompt templates with Handlebars-style variables.
+//!
+//! Templates live in `~/.zeroclaw/prompts/<name>.md` (or `.txt`). `{{var}}`
+//! and `{{ var }}` are replaced with values from a JSON object; dotted paths
+//! (`{{ticket.id}}`) reach into nested objects and `\{{` emits a literal
+//! `{{`. Strings are inserted as-is, other JSON values as JSON text.
+
+use crate::config::Config;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+const EXTENSIONS: [&str; 2] = ["md", "txt"];
+
+/// Why a template could not be rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptError {
+    InvalidName(String),
+    NotFound(String),
+    MissingVariables(Vec<String>),
+    Unclosed,
+    Io(String),
+}
+
+impl std::fmt::Display for PromptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidName(name) => write!(
+                f,
+                "Invalid template name '{name}' (use letters, digits, '-' and '_')"
+            ),
+            Self::NotFound(name) => write!(f, "Unknown prompt template '{name}'"),
+            Self::MissingVariables(vars) => {
+                write!(f, "Missing template variables: {}", vars.join(", "))
+            }
+            Self::Unclosed => write!(f, "Unclosed '{{{{' in template"),
+            Self::Io(e) => write!(f, "Failed to read template: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PromptError {}
+
+/// A directory of prompt templates.
+pub struct PromptLibrary {
+    dir: PathBuf,
+}
+
+impl PromptLibrary {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `prompts/` next to the config file, i.e. `~/.zeroclaw/prompts`.
+    pub fn from_config(config: &Config) -> Self {
+        let config_dir = config
+            .config_path
+            .parent()
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+        Self::new(config_dir.join("prompts"))
+    }
+
+    /// Template names, sorted.
+    pub fn list(&self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                p.is_file()
+                    && p.extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| EXTENSIONS.contains(&e))
+            })
+            .filter_map(|p| p.file_stem()?.to_str().map(String::from))
+            .filter(|name| is_valid_name(name))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Raw template text.
+    pub fn load(&self, name: &str) -> Result<String, PromptError> {
+        if !is_valid_name(name) {
+            return Err(PromptError::InvalidName(name.to_string()));
+        }
+        let path = EXTENSIONS
+            .iter()
+            .map(|ext| self.dir.join(format!("{name}.{ext}")))
+            .find(|p| p.is_file())
+            .ok_or_else(|| PromptError::NotFound(name.to_string()))?;
+        std::fs::read_to_string(path).map_err(|e| PromptError::Io(e.to_string()))
+    }
+
+    pub fn render(&self, name: &str, vars: &Value) -> Result<String, PromptError> {
+        render_template(&self.load(name)?, vars)
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Substitute `{{var}}` placeholders from `vars`. Every missing variable is
+/// reported at once rather than one per attempt.
+pub fn render_template(template: &str, vars: &Value) -> Result<String, PromptError> {
+    let mut out = String::with_capacity(template.len());
+    let mut missing: Vec<String> = Vec::new();
+
+    for part in parse(template)? {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Var(path) => match lookup(vars, path) {
+                Some(Value::String(s)) => out.push_str(s),
+                Some(value) => out.push_str(&value.to_string()),
+                None => {
+                    if !missing.iter().any(|m| m == path) {
+                        missing.push(path.to_string());
+                    }
+                }
+            },
+        }
+    }
+
+    if missing.is_empty() {
+        Ok(out)
+    } else {
+        Err(PromptError::MissingVariables(missing))
+    }
+}
+
+/// Variable names used by `template`, in order of first use.
+pub fn template_variables(template: &str) -> Vec<String> {
+    let mut vars: Vec<String> = Vec::new();
+    for part in parse(template).unwrap_or_default() {
+        if let Part::Var(path) = part {
+            if !vars.iter().any(|v| v == path) {
+                vars.push(path.to_string());
+            }
+        }
+    }
+    vars
+}
+
+enum Part<'a> {
+    Text(&'a str),
+    Var(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, PromptError> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            parts.push(Part::Text(&rest[..start - 1]));
+            parts.push(Part::Text("{{"));
+            rest = &rest[start + 2..];
+            continue;
+        }
+        parts.push(Part::Text(&rest[..start]));
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or(PromptError::Unclosed)?;
+        parts.push(Part::Var(after[..end].trim()));
+        rest = &after[end + 2..];
+    }
+    parts.push(Part::Text(rest));
+    Ok(parts)
+}
+
+fn lookup<'a>(vars: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return None;
+    }
+    path.split('.')
+        .try_fold(vars, |value, key| value.get(key))
+        .filter(|v| !v.is_null())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn renders_variables_and_nested_paths() {
+        let out = render_template(
+            "Triage {{ ticket.id }} for {{user}} ({{count}} open) \\{{literal}}",
+            &json!({"user": "ana", "count": 3, "ticket": {"id": "T-9"}}),
+        )
+        .unwrap();
+        assert_eq!(out, "Triage T-9 for ana (3 open) {{literal}}");
+    }
+
+    #[test]
+    fn reports_every_missing_variable() {
+        let err = render_template("{{a}} {{b}} {{a}} {{c}}", &json!({"b": 1})).unwrap_err();
+        assert_eq!(
+            err,
+            PromptError::MissingVariables(vec!["a".into(), "c".into()])
+        );
+        assert_eq!(
+            render_template("{{a", &json!({})).unwrap_err(),
+            PromptError::Unclosed
+        );
+    }
+
+    #[test]
+    fn library_lists_loads_and_rejects_traversal() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("summarize.md"), "Summarize {{text}}").unwrap();
+        std::fs::write(tmp.path().join("triage.txt"), "Triage {{id}}").unwrap();
+        std::fs::write(tmp.path().join("notes.json"), "{}").unwrap();
+        let library = PromptLibrary::new(tmp.path());
+
+        assert_eq!(library.list(), vec!["summarize", "triage"]);
+        assert_eq!(
+            library
+                .render("summarize", &json!({"text": "the log"}))
+                .unwrap(),
+            "Summarize the log"
+        );
+        assert_eq!(
+            library.load("missing").unwrap_err(),
+            PromptError::NotFound("missing".into())
+        );
+        assert!(matches!(
+            library.load("../secrets"),
+            Err(PromptError::InvalidName(_))
+        ));
+        assert_eq!(template_variables("{{id}} {{ id }} {{x.y}}"), ["id", "x.y"]);
+    }
+}
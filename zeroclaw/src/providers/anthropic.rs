@@ -1,4 +1,4 @@
-use crate::providers::traits::Provider;
+use crate::providers::traits::{ChatMessage, Provider};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -22,7 +22,62 @@ struct ChatRequest {
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
-    content: String,
+    content: MessageContent,
+}
+
+/// Anthropic message content: plain text, or content blocks when images are attached.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Blocks(Vec<RequestBlock>),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RequestBlock {
+    Text { text: String },
+    Image { source: ImageSource },
+}
+
+#[derive(Debug, Serialize)]
+struct ImageSource {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    media_type: String,
+    data: String,
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&ChatMessage> for MessageContent {
+    fn from(msg: &ChatMessage) -> Self {
+        if msg.images.is_empty() {
+            return Self::Text(msg.content.clone());
+        }
+        // Anthropic recommends images before the accompanying text.
+        let mut blocks: Vec<RequestBlock> = msg
+            .images
+            .iter()
+            .map(|image| RequestBlock::Image {
+                source: ImageSource {
+                    kind: "base64",
+                    media_type: image.mime_type.clone(),
+                    data: image.data.clone(),
+                },
+            })
+            .collect();
+        if !msg.content.is_empty() {
+            blocks.push(RequestBlock::Text {
+                text: msg.content.clone(),
+            });
+        }
+        Self::Blocks(blocks)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -62,6 +117,36 @@ impl AnthropicProvider {
     fn is_setup_token(token: &str) -> bool {
         token.starts_with("sk-ant-oat01-")
     }
+
+    async fn send(&self, credential: &str, request: &ChatRequest) -> anyhow::Result<String> {
+        let mut request = self
+            .client
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(request);
+
+        if Self::is_setup_token(credential) {
+            request = request.header("Authorization", format!("Bearer {credential}"));
+        } else {
+            request = request.header("x-api-key", credential);
+        }
+
+        let response = request.send().await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("Anthropic", response).await);
+        }
+
+        let chat_response: ChatResponse = response.json().await?;
+
+        chat_response
+            .content
+            .into_iter()
+            .next()
+            .map(|c| c.text)
+            .ok_or_else(|| anyhow::anyhow!("No response from Anthropic"))
+    }
 }
 
 #[async_trait]
@@ -85,38 +170,54 @@ impl Provider for AnthropicProvider {
             system: system_prompt.map(ToString::to_string),
             messages: vec![Message {
                 role: "user".to_string(),
-                content: message.to_string(),
+                content: message.to_string().into(),
             }],
             temperature,
         };
 
-        let mut request = self
-            .client
-            .post(format!("{}/v1/messages", self.base_url))
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request);
+        self.send(credential, &request).await
+    }
 
-        if Self::is_setup_token(credential) {
-            request = request.header("Authorization", format!("Bearer {credential}"));
-        } else {
-            request = request.header("x-api-key", credential);
-        }
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let credential = self.credential.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
+            )
+        })?;
 
-        let response = request.send().await?;
+        // Anthropic takes the system prompt as a top-level field, not a message.
+        let system: Vec<&str> = messages
+            .iter()
+            .filter(|m| m.role == "system")
+            .map(|m| m.content.as_str())
+            .collect();
+        let api_messages = messages
+            .iter()
+            .filter(|m| m.role != "system")
+            .map(|m| Message {
+                role: m.role.clone(),
+                content: m.into(),
+            })
+            .collect();
 
-        if !response.status().is_success() {
-            return Err(super::api_error("Anthropic", response).await);
-        }
+        let request = ChatRequest {
+            model: model.to_string(),
+            max_tokens: 4096,
+            system: (!system.is_empty()).then(|| system.join("\n\n")),
+            messages: api_messages,
+            temperature,
+        };
 
-        let chat_response: ChatResponse = response.json().await?;
+        self.send(credential, &request).await
+    }
 
-        chat_response
-            .content
-            .into_iter()
-            .next()
-            .map(|c| c.text)
-            .ok_or_else(|| anyhow::anyhow!("No response from Anthropic"))
+    fn supports_vision(&self, _model: &str) -> bool {
+        true
     }
 }
 
@@ -209,7 +310,7 @@ mod tests {
             system: None,
             messages: vec![Message {
                 role: "user".to_string(),
-                content: "hello".to_string(),
+                content: "hello".to_string().into(),
             }],
             temperature: 0.7,
         };
@@ -230,7 +331,7 @@ mod tests {
             system: Some("You are ZeroClaw".to_string()),
             messages: vec![Message {
                 role: "user".to_string(),
-                content: "hello".to_string(),
+                content: "hello".to_string().into(),
             }],
             temperature: 0.7,
         };
@@ -238,6 +339,25 @@ mod tests {
         assert!(json.contains("\"system\":\"You are ZeroClaw\""));
     }
 
+    #[test]
+    fn image_messages_serialize_as_base64_blocks() {
+        let msg = ChatMessage::user_with_images(
+            "describe",
+            vec![crate::providers::ImagePart::new("image/jpeg", "/9j/4AAQ")],
+        );
+        let json = serde_json::to_value(Message {
+            role: "user".into(),
+            content: (&msg).into(),
+        })
+        .unwrap();
+        assert_eq!(json["content"][0]["type"], "image");
+        assert_eq!(json["content"][0]["source"]["type"], "base64");
+        assert_eq!(json["content"][0]["source"]["media_type"], "image/jpeg");
+        assert_eq!(json["content"][0]["source"]["data"], "/9j/4AAQ");
+        assert_eq!(json["content"][1]["type"], "text");
+        assert_eq!(json["content"][1]["text"], "describe");
+    }
+
     #[test]
     fn chat_response_deserializes() {
         let json = r#"{"content":[{"type":"text","text":"Hello there!"}]}"#;
@@ -0,0 +1,340 @@
+//! Azure `OpenAI` (`default_provider = "azure"`), configured by `[azure_openai]`.
+//!
+//! Each model is sent to its deployment under the resource endpoint:
+//! `{endpoint}/openai/deployments/{deployment}/chat/completions?api-version=...`.
+//! Requests authenticate with an `api-key` header, or with an Entra ID
+//! (Azure AD) bearer token: `AZURE_OPENAI_AD_TOKEN` when set, otherwise one
+//! obtained with the app registration's client secret and refreshed before
+//! it expires.
+
+use crate::config::{AzureAuth, AzureOpenAiConfig};
+use crate::providers::compatible::MessageContent;
+use crate::providers::traits::{ChatMessage, ChatStream, Embeddings, Provider};
+use crate::providers::{embed, stream};
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Scope of Entra ID tokens for Azure `OpenAI`.
+const TOKEN_SCOPE: &str = "https://cognitiveservices.azure.com/.default";
+
+/// Tokens are refreshed this long before they expire.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_mins(5);
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+enum Credential {
+    ApiKey(Option<String>),
+    /// Fixed bearer token from `AZURE_OPENAI_AD_TOKEN`
+    Token(String),
+    ClientSecret {
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+        cached: Mutex<Option<(String, Instant)>>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: u64,
+}
+
+pub struct AzureOpenAiProvider {
+    endpoint: String,
+    api_version: String,
+    deployments: BTreeMap<String, String>,
+    credential: Credential,
+    client: Client,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    messages: Vec<Message>,
+    temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct Message {
+    role: String,
+    content: MessageContent,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: ResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+impl AzureOpenAiProvider {
+    pub fn new(config: &AzureOpenAiConfig, api_key: Option<&str>) -> anyhow::Result<Self> {
+        let endpoint = config
+            .endpoint
+            .clone()
+            .filter(|e| !e.trim().is_empty())
+            .or_else(|| env("AZURE_OPENAI_ENDPOINT"))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Azure OpenAI endpoint not set. Set [azure_openai] endpoint or AZURE_OPENAI_ENDPOINT."
+                )
+            })?;
+
+        let credential = match config.auth {
+            AzureAuth::ApiKey => Credential::ApiKey(api_key.map(str::to_string)),
+            AzureAuth::AzureAd => {
+                if let Some(token) = env("AZURE_OPENAI_AD_TOKEN") {
+                    Credential::Token(token)
+                } else {
+                    let setting = |value: &Option<String>, var: &str| {
+                        value
+                            .clone()
+                            .filter(|v| !v.trim().is_empty())
+                            .or_else(|| env(var))
+                            .ok_or_else(|| {
+                                anyhow::anyhow!(
+                                    "Azure AD auth needs AZURE_OPENAI_AD_TOKEN or [azure_openai] \
+                                     tenant_id, client_id and client_secret ({var} is missing)"
+                                )
+                            })
+                    };
+                    Credential::ClientSecret {
+                        tenant_id: setting(&config.tenant_id, "AZURE_TENANT_ID")?,
+                        client_id: setting(&config.client_id, "AZURE_CLIENT_ID")?,
+                        client_secret: setting(&config.client_secret, "AZURE_CLIENT_SECRET")?,
+                        cached: Mutex::new(None),
+                    }
+                }
+            }
+        };
+
+        Ok(Self {
+            endpoint: endpoint.trim().trim_end_matches('/').to_string(),
+            api_version: config.api_version.clone(),
+            deployments: config.deployments.clone(),
+            credential,
+            client: Client::builder()
+                .timeout(Duration::from_mins(2))
+                .connect_timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+        })
+    }
+
+    /// Deployment serving `model`; unmapped models name their deployment.
+    fn deployment<'a>(&'a self, model: &'a str) -> &'a str {
+        self.deployments.get(model).map_or(model, String::as_str)
+    }
+
+    fn url(&self, model: &str, operation: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/{operation}?api-version={}",
+            self.endpoint,
+            self.deployment(model),
+            self.api_version
+        )
+    }
+
+    async fn authorize(&self, request: RequestBuilder) -> anyhow::Result<RequestBuilder> {
+        match &self.credential {
+            Credential::ApiKey(Some(key)) => Ok(request.header("api-key", key)),
+            Credential::ApiKey(None) => anyhow::bail!(
+                "Azure OpenAI API key not set. Set AZURE_OPENAI_API_KEY or edit config.toml."
+            ),
+            Credential::Token(token) => Ok(request.bearer_auth(token)),
+            Credential::ClientSecret {
+                tenant_id,
+                client_id,
+                client_secret,
+                cached,
+            } => {
+                let mut cached = cached.lock().await;
+                if let Some((token, expires)) = cached.as_ref() {
+                    if Instant::now() + TOKEN_REFRESH_MARGIN < *expires {
+                        return Ok(request.bearer_auth(token));
+                    }
+                }
+                let response = self
+                    .client
+                    .post(format!(
+                        "https://login.microsoftonline.com/{tenant_id}/oauth2/v2.0/token"
+                    ))
+                    .form(&[
+                        ("grant_type", "client_credentials"),
+                        ("client_id", client_id),
+                        ("client_secret", client_secret),
+                        ("scope", TOKEN_SCOPE),
+                    ])
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    return Err(super::api_error("Azure AD", response).await);
+                }
+                let token: TokenResponse = response.json().await?;
+                let expires = Instant::now() + Duration::from_secs(token.expires_in);
+                let request = request.bearer_auth(&token.access_token);
+                *cached = Some((token.access_token, expires));
+                Ok(request)
+            }
+        }
+    }
+
+    fn request(messages: &[ChatMessage], temperature: f64, stream: Option<bool>) -> ChatRequest {
+        ChatRequest {
+            messages: messages
+                .iter()
+                .map(|m| Message {
+                    role: m.role.clone(),
+                    content: m.into(),
+                })
+                .collect(),
+            temperature,
+            stream,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for AzureOpenAiProvider {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut messages: Vec<ChatMessage> =
+            system_prompt.map(ChatMessage::system).into_iter().collect();
+        messages.push(ChatMessage::user(message));
+        self.chat_with_history(&messages, model, temperature).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let request = self
+            .client
+            .post(self.url(model, "chat/completions"))
+            .json(&Self::request(messages, temperature, None));
+        let response = self.authorize(request).await?.send().await?;
+        if !response.status().is_success() {
+            return Err(super::api_error("Azure OpenAI", response).await);
+        }
+
+        let chat_response: ChatResponse = response.json().await?;
+        chat_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message.content.unwrap_or_default())
+            .ok_or_else(|| anyhow::anyhow!("No response from Azure OpenAI"))
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let request = self
+            .client
+            .post(self.url(model, "chat/completions"))
+            .json(&Self::request(messages, temperature, Some(true)));
+        stream::openai_compatible("Azure OpenAI", self.authorize(request).await?).await
+    }
+
+    async fn embed(&self, texts: &[&str], model: &str) -> anyhow::Result<Embeddings> {
+        let url = self.url(model, "embeddings");
+        embed::in_batches(texts, embed::EMBED_BATCH_SIZE, |batch| {
+            let request = self.client.post(&url);
+            async move {
+                let request = self.authorize(request).await?;
+                embed::openai_compatible("Azure OpenAI", request, model, batch).await
+            }
+        })
+        .await
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        super::model_supports_vision(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AzureOpenAiConfig {
+        AzureOpenAiConfig {
+            endpoint: Some("https://contoso.openai.azure.com/".into()),
+            deployments: BTreeMap::from([("gpt-4o".into(), "prod-gpt4o".into())]),
+            ..AzureOpenAiConfig::default()
+        }
+    }
+
+    #[test]
+    fn models_route_to_their_deployments() {
+        let provider = AzureOpenAiProvider::new(&config(), Some("key")).unwrap();
+        assert_eq!(
+            provider.url("gpt-4o", "chat/completions"),
+            "https://contoso.openai.azure.com/openai/deployments/prod-gpt4o/chat/completions?api-version=2024-10-21"
+        );
+        assert_eq!(
+            provider.url("text-embedding-3-small", "embeddings"),
+            "https://contoso.openai.azure.com/openai/deployments/text-embedding-3-small/embeddings?api-version=2024-10-21"
+        );
+    }
+
+    #[tokio::test]
+    async fn api_key_is_required_at_call_time() {
+        let provider = AzureOpenAiProvider::new(&config(), None).unwrap();
+        let err = provider.chat("hi", "gpt-4o", 0.0).await.unwrap_err();
+        assert!(err.to_string().contains("API key not set"));
+    }
+
+    #[test]
+    fn azure_ad_needs_an_app_registration() {
+        let mut config = config();
+        config.auth = AzureAuth::AzureAd;
+        config.tenant_id = Some("tenant".into());
+        config.client_id = Some("client".into());
+        config.client_secret = Some("secret".into());
+        let provider = AzureOpenAiProvider::new(&config, None).unwrap();
+        assert!(matches!(
+            provider.credential,
+            Credential::ClientSecret { .. } | Credential::Token(_)
+        ));
+
+        let request: ChatRequest =
+            AzureOpenAiProvider::request(&[ChatMessage::user("hi")], 0.5, None);
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("model").is_none());
+        assert!(json.get("stream").is_none());
+    }
+}
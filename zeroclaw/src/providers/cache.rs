@@ -0,0 +1,376 @@
+//! Response cache for deterministic provider calls (`[response_cache]`).
+//!
+//! Answers to requests at temperature 0 are stored under a hash of the
+//! provider endpoint, the model and the exact messages. The store is shared
+//! by every cached provider in the process, so it outlives the per-turn
+//! providers built by the agent loop, FFI and channels. Agents there may use
+//! different providers or servers under the same model name, hence the
+//! endpoint in the key.
+
+use super::traits::{ChatMessage, ChatStream, Embeddings};
+use super::Provider;
+use crate::config::{Config, ResponseCacheConfig};
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Cached answers, oldest first in `order`.
+#[derive(Default)]
+pub struct ResponseStore {
+    entries: HashMap<String, (String, Instant)>,
+    order: VecDeque<String>,
+}
+
+impl ResponseStore {
+    fn get(&mut self, key: &str, ttl: Duration) -> Option<String> {
+        let (response, stored_at) = self.entries.get(key)?;
+        if stored_at.elapsed() < ttl {
+            return Some(response.clone());
+        }
+        self.entries.remove(key);
+        self.order.retain(|k| k != key);
+        None
+    }
+
+    fn insert(&mut self, key: String, response: String, max_entries: usize) {
+        if self.entries.contains_key(&key) {
+            self.order.retain(|k| k != &key);
+        }
+        while self.order.len() >= max_entries {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        self.order.push_back(key.clone());
+        self.entries.insert(key, (response, Instant::now()));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The process-wide store used by [`wrap`].
+pub fn shared_store() -> Arc<Mutex<ResponseStore>> {
+    static SHARED: OnceLock<Arc<Mutex<ResponseStore>>> = OnceLock::new();
+    SHARED.get_or_init(Arc::default).clone()
+}
+
+/// Wrap `provider`, built as `provider_name` from `config`, in the shared
+/// cache when `[response_cache]` is enabled.
+pub fn wrap(
+    provider: Box<dyn Provider>,
+    config: &Config,
+    provider_name: &str,
+) -> Box<dyn Provider> {
+    let cache = &config.response_cache;
+    if !cache.enabled || cache.max_entries == 0 {
+        return provider;
+    }
+    let endpoint = endpoint(config, provider_name);
+    Box::new(CachedProvider::new(provider, endpoint, cache, shared_store()))
+}
+
+/// Where `provider_name` sends requests under `config`: its name and base
+/// URL, then those of each `[[model_routes]]` target.
+fn endpoint(config: &Config, provider_name: &str) -> String {
+    let base_url = |name: &str| match name {
+        "azure" | "azure-openai" | "azure_openai" => config.azure_openai.endpoint.clone(),
+        _ => config.providers.get(name).and_then(|p| p.base_url.clone()),
+    };
+    let mut endpoint = format!("{provider_name} {}", base_url(provider_name).unwrap_or_default());
+    for route in &config.model_routes {
+        let _ = write!(
+            endpoint,
+            "\n{} -> {} {} {}",
+            route.hint,
+            route.provider,
+            base_url(&route.provider).unwrap_or_default(),
+            route.model
+        );
+    }
+    endpoint
+}
+
+/// Cache key: endpoint and model plus every message's role, text and
+/// images, and the declared tools.
+fn cache_key(
+    endpoint: &str,
+    model: &str,
+    messages: &[ChatMessage],
+    tools: &[ToolSpec],
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(endpoint.as_bytes());
+    hasher.update([0]);
+    hasher.update(model.as_bytes());
+    for message in messages {
+        hasher.update([0]);
+        hasher.update(message.role.as_bytes());
+        hasher.update([0]);
+        hasher.update(message.content.as_bytes());
+        for image in &message.images {
+            hasher.update([0]);
+            hasher.update(image.mime_type.as_bytes());
+            hasher.update(image.data.as_bytes());
+        }
+    }
+    for tool in tools {
+        hasher.update([1]);
+        hasher.update(tool.name.as_bytes());
+        hasher.update(tool.parameters.to_string().as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+pub struct CachedProvider {
+    inner: Box<dyn Provider>,
+    endpoint: String,
+    ttl: Duration,
+    max_entries: usize,
+    store: Arc<Mutex<ResponseStore>>,
+}
+
+impl CachedProvider {
+    pub fn new(
+        inner: Box<dyn Provider>,
+        endpoint: String,
+        config: &ResponseCacheConfig,
+        store: Arc<Mutex<ResponseStore>>,
+    ) -> Self {
+        Self {
+            inner,
+            endpoint,
+            ttl: Duration::from_secs(config.ttl_secs),
+            max_entries: config.max_entries.max(1),
+            store,
+        }
+    }
+
+    fn lookup(&self, key: &str) -> Option<String> {
+        let hit = self.store.lock().ok()?.get(key, self.ttl);
+        if hit.is_some() {
+            tracing::debug!("Response cache hit");
+        }
+        hit
+    }
+
+    fn remember(&self, key: String, response: &str) {
+        if let Ok(mut store) = self.store.lock() {
+            store.insert(key, response.to_string(), self.max_entries);
+        }
+    }
+}
+
+/// Only deterministic requests are worth replaying.
+fn cacheable(temperature: f64) -> bool {
+    temperature.abs() < f64::EPSILON
+}
+
+#[async_trait]
+impl Provider for CachedProvider {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        if !cacheable(temperature) {
+            return self
+                .inner
+                .chat_with_system(system_prompt, message, model, temperature)
+                .await;
+        }
+        let mut messages: Vec<ChatMessage> =
+            system_prompt.map(ChatMessage::system).into_iter().collect();
+        messages.push(ChatMessage::user(message));
+        let key = cache_key(&self.endpoint, model, &messages, &[]);
+        if let Some(response) = self.lookup(&key) {
+            return Ok(response);
+        }
+        let response = self
+            .inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await?;
+        self.remember(key, &response);
+        Ok(response)
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        if !cacheable(temperature) {
+            return self
+                .inner
+                .chat_with_history(messages, model, temperature)
+                .await;
+        }
+        let key = cache_key(&self.endpoint, model, messages, &[]);
+        if let Some(response) = self.lookup(&key) {
+            return Ok(response);
+        }
+        let response = self
+            .inner
+            .chat_with_history(messages, model, temperature)
+            .await?;
+        self.remember(key, &response);
+        Ok(response)
+    }
+
+    /// Cacheable requests are answered whole, from the cache when possible.
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        if !cacheable(temperature) {
+            return self
+                .inner
+                .chat_stream(messages, tools, model, temperature)
+                .await;
+        }
+        let key = cache_key(&self.endpoint, model, messages, tools);
+        let response = if let Some(response) = self.lookup(&key) {
+            response
+        } else {
+            let mut deltas = self
+                .inner
+                .chat_stream(messages, tools, model, temperature)
+                .await?;
+            let mut response = String::new();
+            while let Some(delta) = deltas.next().await {
+                response.push_str(&delta?);
+            }
+            self.remember(key, &response);
+            response
+        };
+        Ok(futures_util::stream::once(async { Ok(response) }).boxed())
+    }
+
+    async fn embed(&self, texts: &[&str], model: &str) -> anyhow::Result<Embeddings> {
+        self.inner.embed(texts, model).await
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        self.inner.supports_vision(model)
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+
+    fn cached(ttl_secs: u64, max_entries: usize) -> (CachedProvider, Arc<ScriptedProvider>) {
+        cached_in("openrouter ", ttl_secs, max_entries, Arc::default())
+    }
+
+    fn cached_in(
+        endpoint: &str,
+        ttl_secs: u64,
+        max_entries: usize,
+        store: Arc<Mutex<ResponseStore>>,
+    ) -> (CachedProvider, Arc<ScriptedProvider>) {
+        let upstream = Arc::new(ScriptedProvider::always("pong"));
+        let provider = CachedProvider::new(
+            Box::new(upstream.clone()),
+            endpoint.to_string(),
+            &ResponseCacheConfig {
+                enabled: true,
+                ttl_secs,
+                max_entries,
+            },
+            store,
+        );
+        (provider, upstream)
+    }
+
+    #[tokio::test]
+    async fn repeats_at_temperature_zero_are_served_from_cache() {
+        let (provider, upstream) = cached(60, 8);
+
+        let first = provider.chat("ping", "m", 0.0).await.unwrap();
+        assert_eq!(provider.chat("ping", "m", 0.0).await.unwrap(), first);
+        assert_eq!(upstream.calls(), 1);
+
+        provider.chat("ping", "other-model", 0.0).await.unwrap();
+        provider.chat("ping", "m", 0.7).await.unwrap();
+        provider.chat("ping", "m", 0.7).await.unwrap();
+        assert_eq!(upstream.calls(), 4);
+
+        let history = [ChatMessage::system("be brief"), ChatMessage::user("ping")];
+        provider
+            .chat_with_history(&history, "m", 0.0)
+            .await
+            .unwrap();
+        provider
+            .chat_with_history(&history, "m", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(upstream.calls(), 5);
+    }
+
+    #[tokio::test]
+    async fn entries_expire_and_oldest_are_evicted() {
+        let (provider, upstream) = cached(0, 8);
+        provider.chat("a", "m", 0.0).await.unwrap();
+        provider.chat("a", "m", 0.0).await.unwrap();
+        assert_eq!(upstream.calls(), 2);
+
+        let (provider, upstream) = cached(60, 2);
+        for message in ["a", "b", "c", "a"] {
+            provider.chat(message, "m", 0.0).await.unwrap();
+        }
+        assert_eq!(upstream.calls(), 4);
+        assert_eq!(provider.store.lock().unwrap().len(), 2);
+        provider.chat("c", "m", 0.0).await.unwrap();
+        assert_eq!(upstream.calls(), 4);
+    }
+
+    #[tokio::test]
+    async fn answers_are_not_shared_across_endpoints() {
+        let mut config = Config::default();
+        let local = endpoint(&config, "ollama");
+        config.providers.insert(
+            "ollama".into(),
+            crate::config::ProviderConfig {
+                api_key: None,
+                base_url: Some("http://gpu-box:11434".into()),
+            },
+        );
+        let remote = endpoint(&config, "ollama");
+        assert_ne!(local, remote);
+        assert_ne!(endpoint(&config, "openai"), endpoint(&config, "anthropic"));
+
+        let store = Arc::default();
+        let (first, first_upstream) = cached_in(&local, 60, 8, Arc::clone(&store));
+        let (second, second_upstream) = cached_in(&remote, 60, 8, Arc::clone(&store));
+        let (again, again_upstream) = cached_in(&local, 60, 8, store);
+        first.chat("ping", "llama3", 0.0).await.unwrap();
+        second.chat("ping", "llama3", 0.0).await.unwrap();
+        again.chat("ping", "llama3", 0.0).await.unwrap();
+        assert_eq!(first_upstream.calls(), 1);
+        assert_eq!(second_upstream.calls(), 1);
+        assert_eq!(again_upstream.calls(), 0);
+    }
+}
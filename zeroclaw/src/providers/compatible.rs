@@ -2,8 +2,11 @@
 //! Most LLM APIs follow the same `/v1/chat/completions` format.
 //! This module provides a single implementation that works for all of them.
 
-use crate::providers::traits::{ChatMessage, Provider};
+use crate::providers::stream;
+use crate::providers::traits::{ChatMessage, ChatStream, Provider};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -72,12 +75,61 @@ struct ChatRequest {
     model: String,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
-    content: String,
+    content: MessageContent,
+}
+
+/// OpenAI-style message content: a plain string, or typed parts once images are attached.
+/// Shared by the OpenAI-format providers (`openai`, `openrouter`).
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub(crate) enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ImageUrl {
+    url: String,
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&ChatMessage> for MessageContent {
+    fn from(msg: &ChatMessage) -> Self {
+        if msg.images.is_empty() {
+            return Self::Text(msg.content.clone());
+        }
+        let mut parts = Vec::with_capacity(msg.images.len() + 1);
+        if !msg.content.is_empty() {
+            parts.push(ContentPart::Text {
+                text: msg.content.clone(),
+            });
+        }
+        parts.extend(msg.images.iter().map(|image| ContentPart::ImageUrl {
+            image_url: ImageUrl {
+                url: image.to_data_uri(),
+            },
+        }));
+        Self::Parts(parts)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -236,6 +288,10 @@ impl OpenAiCompatibleProvider {
 
 #[async_trait]
 impl Provider for OpenAiCompatibleProvider {
+    fn supports_vision(&self, model: &str) -> bool {
+        super::model_supports_vision(model)
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -255,19 +311,20 @@ impl Provider for OpenAiCompatibleProvider {
         if let Some(sys) = system_prompt {
             messages.push(Message {
                 role: "system".to_string(),
-                content: sys.to_string(),
+                content: sys.to_string().into(),
             });
         }
 
         messages.push(Message {
             role: "user".to_string(),
-            content: message.to_string(),
+            content: message.to_string().into(),
         });
 
         let request = ChatRequest {
             model: model.to_string(),
             messages,
             temperature,
+            stream: None,
         };
 
         let url = self.chat_completions_url();
@@ -294,7 +351,7 @@ impl Provider for OpenAiCompatibleProvider {
                     });
             }
 
-            anyhow::bail!("{} API error ({status}): {sanitized}", self.name);
+            return Err(super::ApiError::new(&self.name, status, &error).into());
         }
 
         let chat_response: ApiChatResponse = response.json().await?;
@@ -339,7 +396,7 @@ impl Provider for OpenAiCompatibleProvider {
             .iter()
             .map(|m| Message {
                 role: m.role.clone(),
-                content: m.content.clone(),
+                content: m.into(),
             })
             .collect();
 
@@ -347,6 +404,7 @@ impl Provider for OpenAiCompatibleProvider {
             model: model.to_string(),
             messages: api_messages,
             temperature,
+            stream: None,
         };
 
         let url = self.chat_completions_url();
@@ -408,6 +466,50 @@ impl Provider for OpenAiCompatibleProvider {
             })
             .ok_or_else(|| anyhow::anyhow!("No response from {}", self.name))
     }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
+                self.name
+            )
+        })?;
+
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages: messages
+                .iter()
+                .map(|m| Message {
+                    role: m.role.clone(),
+                    content: m.into(),
+                })
+                .collect(),
+            temperature,
+            stream: Some(true),
+        };
+
+        let url = self.chat_completions_url();
+        let response = self
+            .apply_auth_header(self.client.post(&url).json(&request), api_key)
+            .send()
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            // Responses API providers: answer in one piece via the fallback
+            let text = self.chat_with_history(messages, model, temperature).await?;
+            return Ok(futures_util::stream::once(async { Ok(text) }).boxed());
+        }
+        if !response.status().is_success() {
+            return Err(super::api_error(&self.name, response).await);
+        }
+        Ok(stream::sse_deltas(response.bytes_stream()))
+    }
 }
 
 #[cfg(test)]
@@ -451,6 +553,33 @@ mod tests {
             .contains("Venice API key not set"));
     }
 
+    #[test]
+    fn image_messages_serialize_as_content_parts() {
+        let msg = ChatMessage::user_with_images(
+            "What is on screen?",
+            vec![crate::providers::ImagePart::new("image/png", "iVBORw==")],
+        );
+        let json = serde_json::to_value(Message {
+            role: msg.role.clone(),
+            content: (&msg).into(),
+        })
+        .unwrap();
+        assert_eq!(json["content"][0]["type"], "text");
+        assert_eq!(json["content"][0]["text"], "What is on screen?");
+        assert_eq!(json["content"][1]["type"], "image_url");
+        assert_eq!(
+            json["content"][1]["image_url"]["url"],
+            "data:image/png;base64,iVBORw=="
+        );
+
+        let plain = serde_json::to_value(Message {
+            role: "user".into(),
+            content: (&ChatMessage::user("hi")).into(),
+        })
+        .unwrap();
+        assert_eq!(plain["content"], "hi");
+    }
+
     #[test]
     fn request_serializes_correctly() {
         let req = ChatRequest {
@@ -458,14 +587,15 @@ mod tests {
             messages: vec![
                 Message {
                     role: "system".to_string(),
-                    content: "You are ZeroClaw".to_string(),
+                    content: "You are ZeroClaw".to_string().into(),
                 },
                 Message {
                     role: "user".to_string(),
-                    content: "hello".to_string(),
+                    content: "hello".to_string().into(),
                 },
             ],
             temperature: 0.7,
+            stream: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("llama-3.3-70b"));
@@ -0,0 +1,153 @@
+//! Shared plumbing for [`Provider::embed`](super::Provider::embed):
+//! batching and the `OpenAI`-style `/embeddings` request, which `OpenAI` and
+//! `OpenRouter` both speak.
+
+use super::traits::Embeddings;
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+use std::future::Future;
+
+/// Texts sent per embedding request.
+pub const EMBED_BATCH_SIZE: usize = 96;
+
+/// Embed `texts` with one `call` per batch of at most `batch_size`,
+/// concatenating the vectors in order and summing the token counts.
+pub async fn in_batches<'a, F, Fut>(
+    texts: &'a [&'a str],
+    batch_size: usize,
+    mut call: F,
+) -> anyhow::Result<Embeddings>
+where
+    F: FnMut(&'a [&'a str]) -> Fut,
+    Fut: Future<Output = anyhow::Result<Embeddings>>,
+{
+    let mut all = Embeddings::default();
+    for batch in texts.chunks(batch_size.max(1)) {
+        let part = call(batch).await?;
+        if part.vectors.len() != batch.len() {
+            anyhow::bail!(
+                "Embedding response has {} vectors for {} texts",
+                part.vectors.len(),
+                batch.len()
+            );
+        }
+        all.vectors.extend(part.vectors);
+        all.tokens += part.tokens;
+    }
+    Ok(all)
+}
+
+/// Estimated input tokens, for providers that report no usage.
+pub fn estimate_tokens(batch: &[&str]) -> u64 {
+    batch
+        .iter()
+        .map(|text| crate::tokens::Family::Cl100k.count(text))
+        .sum()
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbedding>,
+    #[serde(default)]
+    usage: Option<OpenAiUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbedding {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiUsage {
+    prompt_tokens: u64,
+}
+
+/// Send one batch to an `OpenAI`-style embeddings endpoint; `request` is
+/// the authenticated POST to it.
+pub async fn openai_compatible(
+    provider: &str,
+    request: RequestBuilder,
+    model: &str,
+    batch: &[&str],
+) -> anyhow::Result<Embeddings> {
+    let response = request
+        .json(&serde_json::json!({"model": model, "input": batch}))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(super::api_error(provider, response).await);
+    }
+    let parsed: OpenAiEmbeddingResponse = response.json().await?;
+    Ok(parse_openai(parsed, batch))
+}
+
+fn parse_openai(mut parsed: OpenAiEmbeddingResponse, batch: &[&str]) -> Embeddings {
+    parsed.data.sort_by_key(|item| item.index);
+    Embeddings {
+        vectors: parsed.data.into_iter().map(|item| item.embedding).collect(),
+        tokens: parsed
+            .usage
+            .map_or_else(|| estimate_tokens(batch), |usage| usage.prompt_tokens),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn batches_keep_order_and_sum_tokens() {
+        let texts = ["a", "bb", "ccc", "dddd", "eeeee"];
+        let mut calls = 0;
+        let result = in_batches(&texts, 2, |batch| {
+            calls += 1;
+            let vectors = batch.iter().map(|t| vec![t.len() as f32]).collect();
+            async move {
+                Ok(Embeddings {
+                    vectors,
+                    tokens: 10,
+                })
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(calls, 3);
+        assert_eq!(
+            result.vectors,
+            vec![vec![1.0], vec![2.0], vec![3.0], vec![4.0], vec![5.0]]
+        );
+        assert_eq!(result.tokens, 30);
+    }
+
+    #[tokio::test]
+    async fn short_batches_are_an_error() {
+        let texts = ["a", "b"];
+        let err = in_batches(&texts, 8, |_| async {
+            Ok(Embeddings {
+                vectors: vec![vec![0.0]],
+                tokens: 1,
+            })
+        })
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("1 vectors for 2 texts"));
+    }
+
+    #[test]
+    fn openai_response_is_reordered_by_index() {
+        let parsed: OpenAiEmbeddingResponse = serde_json::from_str(
+            r#"{"data":[{"index":1,"embedding":[0.5]},{"index":0,"embedding":[0.25]}],
+                "usage":{"prompt_tokens":7,"total_tokens":7}}"#,
+        )
+        .unwrap();
+        let embeddings = parse_openai(parsed, &["x", "y"]);
+        assert_eq!(embeddings.vectors, vec![vec![0.25], vec![0.5]]);
+        assert_eq!(embeddings.tokens, 7);
+
+        let parsed: OpenAiEmbeddingResponse =
+            serde_json::from_str(r#"{"data":[{"index":0,"embedding":[1.0]}]}"#).unwrap();
+        assert_eq!(parse_openai(parsed, &["12345678"]).tokens, 3);
+    }
+}
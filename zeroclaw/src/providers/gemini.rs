@@ -1,19 +1,29 @@
-//! Google Gemini provider with support for:
+//! Google Gemini provider (Generative Language API) with support for:
 //! - Direct API key (`GEMINI_API_KEY` env var or config)
 //! - Gemini CLI OAuth tokens (reuse existing ~/.gemini/ authentication)
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
-
-use crate::providers::traits::Provider;
+//!
+//! Tools are sent as native `functionDeclarations`; the `functionCall`
+//! parts of a reply come back as `<tool_call>` blocks for the agent loop.
+//! Output length and safety thresholds come from `[gemini]`.
+
+use crate::config::GeminiConfig;
+use crate::providers::traits::{ChatMessage, ChatStream, Provider};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use directories::UserDirs;
+use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::fmt::Write;
 use std::path::PathBuf;
 
 /// Gemini provider supporting multiple authentication methods.
 pub struct GeminiProvider {
     auth: Option<GeminiAuth>,
     client: Client,
+    /// `[gemini]` as it was when the provider was built
+    settings: GeminiConfig,
 }
 
 /// Resolved credential — the variant determines both the HTTP auth method
@@ -61,6 +71,10 @@ struct GenerateContentRequest {
     system_instruction: Option<Content>,
     #[serde(rename = "generationConfig")]
     generation_config: GenerationConfig,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<ToolDeclarations>,
+    #[serde(rename = "safetySettings", skip_serializing_if = "Vec::is_empty")]
+    safety_settings: Vec<SafetySetting>,
 }
 
 #[derive(Debug, Serialize)]
@@ -71,8 +85,42 @@ struct Content {
 }
 
 #[derive(Debug, Serialize)]
-struct Part {
-    text: String,
+#[serde(untagged)]
+enum Part {
+    Text {
+        text: String,
+    },
+    InlineData {
+        #[serde(rename = "inlineData")]
+        inline_data: InlineData,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct InlineData {
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ToolDeclarations {
+    #[serde(rename = "functionDeclarations")]
+    function_declarations: Vec<FunctionDeclaration>,
+}
+
+#[derive(Debug, Serialize)]
+struct FunctionDeclaration {
+    name: String,
+    description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parameters: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct SafetySetting {
+    category: String,
+    threshold: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -86,21 +134,43 @@ struct GenerationConfig {
 struct GenerateContentResponse {
     candidates: Option<Vec<Candidate>>,
     error: Option<ApiError>,
+    #[serde(rename = "promptFeedback", default)]
+    prompt_feedback: Option<PromptFeedback>,
 }
 
 #[derive(Debug, Deserialize)]
 struct Candidate {
+    /// Missing when the candidate was blocked
+    #[serde(default)]
     content: CandidateContent,
+    #[serde(rename = "finishReason", default)]
+    finish_reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct CandidateContent {
+    #[serde(default)]
     parts: Vec<ResponsePart>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ResponsePart {
     text: Option<String>,
+    #[serde(rename = "functionCall", default)]
+    function_call: Option<FunctionCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptFeedback {
+    #[serde(rename = "blockReason", default)]
+    block_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -128,6 +198,12 @@ impl GeminiProvider {
     /// 3. `GOOGLE_API_KEY` environment variable
     /// 4. Gemini CLI OAuth tokens (`~/.gemini/oauth_creds.json`)
     pub fn new(api_key: Option<&str>) -> Self {
+        Self::with_settings(api_key, GeminiConfig::default())
+    }
+
+    /// [`GeminiProvider::new`] with output length and safety thresholds
+    /// from `[gemini]`.
+    pub fn with_settings(api_key: Option<&str>, settings: GeminiConfig) -> Self {
         let resolved_auth = api_key
             .and_then(Self::normalize_non_empty)
             .map(GeminiAuth::ExplicitKey)
@@ -142,6 +218,7 @@ impl GeminiProvider {
                 .connect_timeout(std::time::Duration::from_secs(10))
                 .build()
                 .unwrap_or_else(|_| Client::new()),
+            settings,
         }
     }
 
@@ -250,14 +327,116 @@ impl GeminiProvider {
             _ => req,
         }
     }
-}
 
-#[async_trait]
-impl Provider for GeminiProvider {
-    async fn chat_with_system(
+    /// Split chat messages into the system instruction and Gemini contents.
+    /// Assistant turns become `model` turns, images become inline data, and
+    /// consecutive messages from the same side are merged into one turn.
+    fn build_contents(messages: &[ChatMessage]) -> (Option<Content>, Vec<Content>) {
+        let system: Vec<&str> = messages
+            .iter()
+            .filter(|m| m.role == "system")
+            .map(|m| m.content.as_str())
+            .collect();
+        let system_instruction = (!system.is_empty()).then(|| Content {
+            role: None,
+            parts: vec![Part::Text {
+                text: system.join("\n\n"),
+            }],
+        });
+
+        let mut contents: Vec<Content> = Vec::new();
+        for message in messages.iter().filter(|m| m.role != "system") {
+            let role = if message.role == "assistant" {
+                "model"
+            } else {
+                "user"
+            };
+            let mut parts = Vec::new();
+            if !message.content.is_empty() || message.images.is_empty() {
+                parts.push(Part::Text {
+                    text: message.content.clone(),
+                });
+            }
+            parts.extend(message.images.iter().map(|image| Part::InlineData {
+                inline_data: InlineData {
+                    mime_type: image.mime_type.clone(),
+                    data: image.data.clone(),
+                },
+            }));
+            match contents.last_mut() {
+                Some(last) if last.role.as_deref() == Some(role) => last.parts.extend(parts),
+                _ => contents.push(Content {
+                    role: Some(role.into()),
+                    parts,
+                }),
+            }
+        }
+        (system_instruction, contents)
+    }
+
+    fn function_declarations(tools: &[ToolSpec]) -> Vec<ToolDeclarations> {
+        if tools.is_empty() {
+            return Vec::new();
+        }
+        vec![ToolDeclarations {
+            function_declarations: tools
+                .iter()
+                .map(|tool| FunctionDeclaration {
+                    name: tool.name.clone(),
+                    description: tool.description.clone(),
+                    parameters: gemini_schema(&tool.parameters),
+                })
+                .collect(),
+        }]
+    }
+
+    /// The reply text, with each `functionCall` part as a `<tool_call>` block.
+    fn reply_text(result: GenerateContentResponse) -> anyhow::Result<String> {
+        if let Some(err) = result.error {
+            anyhow::bail!("Gemini API error: {}", err.message);
+        }
+        if let Some(reason) = result.prompt_feedback.and_then(|f| f.block_reason) {
+            anyhow::bail!("Gemini blocked the prompt ({reason})");
+        }
+        let candidate = result
+            .candidates
+            .and_then(|c| c.into_iter().next())
+            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))?;
+
+        let mut reply = String::new();
+        for part in candidate.content.parts {
+            if let Some(text) = part.text {
+                reply.push_str(&text);
+            }
+            if let Some(call) = part.function_call {
+                if !reply.is_empty() && !reply.ends_with('\n') {
+                    reply.push('\n');
+                }
+                let arguments = if call.args.is_null() {
+                    serde_json::json!({})
+                } else {
+                    call.args
+                };
+                let _ = write!(
+                    reply,
+                    "<tool_call>\n{}\n</tool_call>",
+                    serde_json::json!({"name": call.name, "arguments": arguments})
+                );
+            }
+        }
+        if reply.is_empty() {
+            match candidate.finish_reason {
+                Some(reason) => anyhow::bail!("Gemini returned no content ({reason})"),
+                None => anyhow::bail!("No response from Gemini"),
+            }
+        }
+        Ok(reply)
+    }
+
+    async fn generate(
         &self,
-        system_prompt: Option<&str>,
-        message: &str,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
@@ -271,26 +450,17 @@ impl Provider for GeminiProvider {
             )
         })?;
 
-        // Build request
-        let system_instruction = system_prompt.map(|sys| Content {
-            role: None,
-            parts: vec![Part {
-                text: sys.to_string(),
-            }],
-        });
-
+        let settings = &self.settings;
+        let (system_instruction, contents) = Self::build_contents(messages);
         let request = GenerateContentRequest {
-            contents: vec![Content {
-                role: Some("user".to_string()),
-                parts: vec![Part {
-                    text: message.to_string(),
-                }],
-            }],
+            contents,
             system_instruction,
             generation_config: GenerationConfig {
                 temperature,
-                max_output_tokens: 8192,
+                max_output_tokens: settings.max_output_tokens,
             },
+            tools: Self::function_declarations(tools),
+            safety_settings: safety_settings(settings),
         };
 
         let url = Self::build_generate_content_url(model, auth);
@@ -303,23 +473,109 @@ impl Provider for GeminiProvider {
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Gemini API error ({status}): {error_text}");
+            return Err(super::ApiError::new("Gemini", status, &error_text).into());
         }
 
-        let result: GenerateContentResponse = response.json().await?;
+        Self::reply_text(response.json().await?)
+    }
+}
+
+/// JSON Schema keywords outside the API's `OpenAPI` schema subset.
+const UNSUPPORTED_SCHEMA_KEYS: [&str; 4] = ["$schema", "$id", "additionalProperties", "examples"];
+
+/// A tool's parameter schema in the form `functionDeclarations` accept;
+/// `None` for tools without parameters, since empty objects are rejected.
+fn gemini_schema(schema: &serde_json::Value) -> Option<serde_json::Value> {
+    let has_properties = schema
+        .get("properties")
+        .and_then(serde_json::Value::as_object)
+        .is_some_and(|p| !p.is_empty());
+    if !has_properties {
+        return None;
+    }
+    let mut schema = schema.clone();
+    strip_unsupported(&mut schema);
+    Some(schema)
+}
 
-        // Check for API error in response body
-        if let Some(err) = result.error {
-            anyhow::bail!("Gemini API error: {}", err.message);
+fn strip_unsupported(schema: &mut serde_json::Value) {
+    let Some(map) = schema.as_object_mut() else {
+        return;
+    };
+    for key in UNSUPPORTED_SCHEMA_KEYS {
+        map.remove(key);
+    }
+    // Property names may collide with keywords; only their schemas are cleaned
+    if let Some(serde_json::Value::Object(properties)) = map.get_mut("properties") {
+        properties.values_mut().for_each(strip_unsupported);
+    }
+    if let Some(items) = map.get_mut("items") {
+        strip_unsupported(items);
+    }
+    for key in ["anyOf", "oneOf", "allOf"] {
+        if let Some(serde_json::Value::Array(variants)) = map.get_mut(key) {
+            variants.iter_mut().for_each(strip_unsupported);
         }
+    }
+}
 
-        // Extract text from response
-        result
-            .candidates
-            .and_then(|c| c.into_iter().next())
-            .and_then(|c| c.content.parts.into_iter().next())
-            .and_then(|p| p.text)
-            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
+/// `[gemini.safety_settings]` in the API's spelling.
+fn safety_settings(config: &GeminiConfig) -> Vec<SafetySetting> {
+    config
+        .safety_settings
+        .iter()
+        .map(|(category, threshold)| {
+            let category = category.trim().to_ascii_uppercase();
+            SafetySetting {
+                category: if category.starts_with("HARM_CATEGORY_") {
+                    category
+                } else {
+                    format!("HARM_CATEGORY_{category}")
+                },
+                threshold: threshold.trim().to_ascii_uppercase(),
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Provider for GeminiProvider {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut messages: Vec<ChatMessage> =
+            system_prompt.map(ChatMessage::system).into_iter().collect();
+        messages.push(ChatMessage::user(message));
+        self.generate(&messages, &[], model, temperature).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.generate(messages, &[], model, temperature).await
+    }
+
+    /// One delta holding the whole reply, generated with `tools` declared.
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let response = self.generate(messages, tools, model, temperature).await?;
+        Ok(futures_util::stream::once(async { Ok(response) }).boxed())
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        super::model_supports_vision(model)
     }
 }
 
@@ -375,6 +631,7 @@ mod tests {
         let provider = GeminiProvider {
             auth: Some(GeminiAuth::ExplicitKey("key".into())),
             client: Client::new(),
+            settings: GeminiConfig::default(),
         };
         assert_eq!(provider.auth_source(), "config");
     }
@@ -384,6 +641,7 @@ mod tests {
         let provider = GeminiProvider {
             auth: None,
             client: Client::new(),
+            settings: GeminiConfig::default(),
         };
         assert_eq!(provider.auth_source(), "none");
     }
@@ -393,6 +651,7 @@ mod tests {
         let provider = GeminiProvider {
             auth: Some(GeminiAuth::OAuthToken("ya29.mock".into())),
             client: Client::new(),
+            settings: GeminiConfig::default(),
         };
         assert_eq!(provider.auth_source(), "Gemini CLI OAuth");
     }
@@ -429,13 +688,14 @@ mod tests {
         let provider = GeminiProvider {
             auth: Some(GeminiAuth::OAuthToken("ya29.mock-token".into())),
             client: Client::new(),
+            settings: GeminiConfig::default(),
         };
         let auth = GeminiAuth::OAuthToken("ya29.mock-token".into());
         let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "hello".into(),
                 }],
             }],
@@ -444,6 +704,8 @@ mod tests {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: Vec::new(),
+            safety_settings: Vec::new(),
         };
 
         let request = provider
@@ -465,13 +727,14 @@ mod tests {
         let provider = GeminiProvider {
             auth: Some(GeminiAuth::ExplicitKey("api-key-123".into())),
             client: Client::new(),
+            settings: GeminiConfig::default(),
         };
         let auth = GeminiAuth::ExplicitKey("api-key-123".into());
         let url = GeminiProvider::build_generate_content_url("gemini-2.0-flash", &auth);
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "hello".into(),
                 }],
             }],
@@ -480,6 +743,8 @@ mod tests {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: Vec::new(),
+            safety_settings: Vec::new(),
         };
 
         let request = provider
@@ -495,13 +760,13 @@ mod tests {
         let request = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".to_string()),
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "Hello".to_string(),
                 }],
             }],
             system_instruction: Some(Content {
                 role: None,
-                parts: vec![Part {
+                parts: vec![Part::Text {
                     text: "You are helpful".to_string(),
                 }],
             }),
@@ -509,6 +774,8 @@ mod tests {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: Vec::new(),
+            safety_settings: Vec::new(),
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -557,4 +824,125 @@ mod tests {
         assert!(response.error.is_some());
         assert_eq!(response.error.unwrap().message, "Invalid API key");
     }
+
+    #[test]
+    fn history_maps_to_alternating_turns() {
+        use crate::providers::ImagePart;
+        let messages = [
+            ChatMessage::system("Be brief."),
+            ChatMessage::user("hi"),
+            ChatMessage::assistant("hello"),
+            ChatMessage::user("[Tool results]"),
+            ChatMessage::user_with_images(
+                "what is this?",
+                vec![ImagePart::new("image/png", "AAAA")],
+            ),
+        ];
+        let (system, contents) = GeminiProvider::build_contents(&messages);
+        let json = serde_json::to_value(&contents).unwrap();
+
+        assert!(system.is_some());
+        assert_eq!(contents.len(), 3);
+        assert_eq!(json[1]["role"], "model");
+        assert_eq!(json[2]["parts"].as_array().unwrap().len(), 3);
+        assert_eq!(json[2]["parts"][2]["inlineData"]["mimeType"], "image/png");
+    }
+
+    #[test]
+    fn tools_become_function_declarations() {
+        let tools = [
+            ToolSpec {
+                name: "file_read".into(),
+                description: "Read a file".into(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "path": {"type": "string"},
+                        "default": {"type": "string", "examples": ["x"]}
+                    },
+                    "required": ["path"]
+                }),
+            },
+            ToolSpec {
+                name: "now".into(),
+                description: "Current time".into(),
+                parameters: serde_json::json!({"type": "object", "properties": {}}),
+            },
+        ];
+        let json = serde_json::to_value(GeminiProvider::function_declarations(&tools)).unwrap();
+        let declarations = &json[0]["functionDeclarations"];
+
+        assert_eq!(declarations[0]["name"], "file_read");
+        let parameters = &declarations[0]["parameters"];
+        assert!(parameters.get("additionalProperties").is_none());
+        assert!(parameters["properties"]["default"]
+            .get("examples")
+            .is_none());
+        assert_eq!(parameters["required"][0], "path");
+        assert!(declarations[1].get("parameters").is_none());
+        assert!(GeminiProvider::function_declarations(&[]).is_empty());
+    }
+
+    #[test]
+    fn function_calls_become_tool_call_blocks() {
+        let json = r#"{
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        {"text": "Checking."},
+                        {"functionCall": {"name": "shell", "args": {"command": "ls"}}}
+                    ]
+                },
+                "finishReason": "STOP"
+            }]
+        }"#;
+        let reply = GeminiProvider::reply_text(serde_json::from_str(json).unwrap()).unwrap();
+        assert!(reply.starts_with("Checking.\n<tool_call>\n"));
+        assert!(reply.contains(r#""arguments":{"command":"ls"}"#));
+        assert!(reply.ends_with("</tool_call>"));
+    }
+
+    #[test]
+    fn blocked_responses_report_the_reason() {
+        let prompt = r#"{"promptFeedback": {"blockReason": "SAFETY"}}"#;
+        let err = GeminiProvider::reply_text(serde_json::from_str(prompt).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("SAFETY"));
+
+        let candidate = r#"{"candidates": [{"finishReason": "SAFETY"}]}"#;
+        let err = GeminiProvider::reply_text(serde_json::from_str(candidate).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("SAFETY"));
+    }
+
+    #[test]
+    fn safety_settings_accept_short_names() {
+        let config = GeminiConfig {
+            safety_settings: [
+                ("harassment".to_string(), "block_none".to_string()),
+                (
+                    "HARM_CATEGORY_DANGEROUS_CONTENT".to_string(),
+                    "BLOCK_ONLY_HIGH".to_string(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            ..GeminiConfig::default()
+        };
+        let json = serde_json::to_value(safety_settings(&config)).unwrap();
+        assert_eq!(json[0]["category"], "HARM_CATEGORY_DANGEROUS_CONTENT");
+        assert_eq!(json[1]["category"], "HARM_CATEGORY_HARASSMENT");
+        assert_eq!(json[1]["threshold"], "BLOCK_NONE");
+    }
+
+    #[test]
+    fn settings_are_kept_per_provider() {
+        let config = GeminiConfig {
+            max_output_tokens: 256,
+            ..GeminiConfig::default()
+        };
+        let provider = GeminiProvider::with_settings(Some("key"), config);
+        assert_eq!(provider.settings.max_output_tokens, 256);
+        assert_eq!(GeminiProvider::new(Some("key")).settings.max_output_tokens, 8192);
+    }
 }
@@ -0,0 +1,415 @@
+//! Fixture-backed provider for tests without API keys.
+//!
+//! `default_provider = "mock"` replays answers from `$ZEROCLAW_MOCK_DIR`
+//! (default `./fixtures`); `"mock:<dir>"` names the directory directly. Each
+//! fixture is a `<hash>.json` file holding the conversation it answers and
+//! the response. System messages are left out of the hash because the
+//! system prompt changes with the workspace, tools and date.
+//!
+//! With `ZEROCLAW_MOCK_RECORD=<provider>` set, requests go to that real
+//! provider instead and every answer is written as a fixture, so a test
+//! run once against the real API can be replayed offline afterwards.
+
+use super::traits::ChatMessage;
+use super::Provider;
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Fixture directory when neither `mock:<dir>` nor `$ZEROCLAW_MOCK_DIR` is given
+const DEFAULT_FIXTURE_DIR: &str = "fixtures";
+
+/// One recorded exchange.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Fixture {
+    /// The conversation without system messages, for readers of the file
+    #[serde(default)]
+    pub messages: Vec<ChatMessage>,
+    pub response: String,
+}
+
+pub struct MockProvider {
+    dir: PathBuf,
+    /// Real provider to record from; replay only when None
+    upstream: Option<Box<dyn Provider>>,
+}
+
+impl MockProvider {
+    /// Replay fixtures from `dir`.
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            upstream: None,
+        }
+    }
+
+    /// Answer with `upstream` and save each answer as a fixture in `dir`.
+    pub fn recording(dir: impl Into<PathBuf>, upstream: Box<dyn Provider>) -> Self {
+        Self {
+            dir: dir.into(),
+            upstream: Some(upstream),
+        }
+    }
+
+    /// Build from a `mock` / `mock:<dir>` provider name and the environment.
+    /// A recording upstream is built with `settings`.
+    pub fn from_name(
+        name: &str,
+        api_key: Option<&str>,
+        settings: &super::ProviderSettings,
+    ) -> anyhow::Result<Self> {
+        let dir = match name.strip_prefix("mock:").filter(|d| !d.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::var_os("ZEROCLAW_MOCK_DIR")
+                .map_or_else(|| PathBuf::from(DEFAULT_FIXTURE_DIR), PathBuf::from),
+        };
+        match std::env::var("ZEROCLAW_MOCK_RECORD") {
+            Ok(upstream) if !upstream.trim().is_empty() => {
+                let upstream = upstream.trim();
+                if upstream == "mock" || upstream.starts_with("mock:") {
+                    anyhow::bail!("ZEROCLAW_MOCK_RECORD must name a real provider");
+                }
+                Ok(Self::recording(
+                    dir,
+                    super::create_provider(upstream, api_key, settings)?,
+                ))
+            }
+            _ => Ok(Self::replay(dir)),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn fixture_path(&self, messages: &[ChatMessage]) -> PathBuf {
+        self.dir.join(format!("{}.json", fixture_key(messages)))
+    }
+
+    async fn answer(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let path = self.fixture_path(messages);
+        let Some(upstream) = &self.upstream else {
+            return load_fixture(&path, messages);
+        };
+
+        let response = upstream
+            .chat_with_history(messages, model, temperature)
+            .await?;
+        let fixture = Fixture {
+            messages: conversation(messages).cloned().collect(),
+            response,
+        };
+        std::fs::create_dir_all(&self.dir).with_context(|| {
+            format!("Failed to create fixture directory: {}", self.dir.display())
+        })?;
+        std::fs::write(&path, serde_json::to_string_pretty(&fixture)?)
+            .with_context(|| format!("Failed to write fixture: {}", path.display()))?;
+        Ok(fixture.response)
+    }
+}
+
+fn conversation(messages: &[ChatMessage]) -> impl Iterator<Item = &ChatMessage> {
+    messages.iter().filter(|m| m.role != "system")
+}
+
+/// Stable name for the fixture answering `messages`.
+pub fn fixture_key(messages: &[ChatMessage]) -> String {
+    let mut hasher = Sha256::new();
+    for message in conversation(messages) {
+        hasher.update(message.role.as_bytes());
+        hasher.update([0]);
+        hasher.update(message.content.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(&hasher.finalize()[..8])
+}
+
+fn load_fixture(path: &Path, messages: &[ChatMessage]) -> anyhow::Result<String> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let last = messages
+                .iter()
+                .rfind(|m| m.role == "user")
+                .map_or("", |m| m.content.as_str());
+            anyhow::bail!(
+                "No mock fixture {} for {:?}; record one with ZEROCLAW_MOCK_RECORD=<provider>",
+                path.display(),
+                crate::util::truncate_with_ellipsis(last, 80)
+            );
+        }
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read fixture: {}", path.display()))
+        }
+    };
+    let fixture: Fixture = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse fixture: {}", path.display()))?;
+    Ok(fixture.response)
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut messages: Vec<ChatMessage> =
+            system_prompt.map(ChatMessage::system).into_iter().collect();
+        messages.push(ChatMessage::user(message));
+        self.answer(&messages, model, temperature).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.answer(messages, model, temperature).await
+    }
+}
+
+/// A request [`ScriptedProvider`] was sent.
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub model: String,
+    /// The whole conversation, system prompt included
+    pub messages: Vec<ChatMessage>,
+}
+
+#[cfg(test)]
+impl Request {
+    /// Content of the last message, usually the prompt being answered.
+    pub fn last(&self) -> &str {
+        self.messages.last().map_or("", |m| m.content.as_str())
+    }
+}
+
+#[cfg(test)]
+type Answer = Box<dyn Fn(&[ChatMessage]) -> String + Send + Sync>;
+
+#[cfg(test)]
+enum Script {
+    /// Replies in order; an error once they run out
+    Replies(std::collections::VecDeque<Result<String, String>>),
+    Always(Result<String, String>),
+    Answering(Answer),
+}
+
+/// Stand-in provider for unit tests that need answers without fixtures on
+/// disk. Answers from a script and keeps every request it was sent.
+#[cfg(test)]
+pub struct ScriptedProvider {
+    script: std::sync::Mutex<Script>,
+    requests: std::sync::Mutex<Vec<Request>>,
+    vision: bool,
+}
+
+#[cfg(test)]
+impl ScriptedProvider {
+    fn with_script(script: Script) -> Self {
+        Self {
+            script: std::sync::Mutex::new(script),
+            requests: std::sync::Mutex::new(Vec::new()),
+            vision: false,
+        }
+    }
+
+    /// Answer with `replies` in order, then fail.
+    pub fn new(replies: &[&str]) -> Self {
+        let replies = replies.iter().map(|r| Ok((*r).to_string())).collect();
+        Self::with_script(Script::Replies(replies))
+    }
+
+    /// Answer every request with `reply`.
+    pub fn always(reply: impl Into<String>) -> Self {
+        Self::with_script(Script::Always(Ok(reply.into())))
+    }
+
+    /// Fail every request with `error`.
+    pub fn failing(error: impl Into<String>) -> Self {
+        Self::with_script(Script::Always(Err(error.into())))
+    }
+
+    /// Answer with whatever `answer` makes of the conversation.
+    pub fn answering(
+        answer: impl Fn(&[ChatMessage]) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self::with_script(Script::Answering(Box::new(answer)))
+    }
+
+    /// Claim vision support for every model.
+    pub fn with_vision(mut self) -> Self {
+        self.vision = true;
+        self
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn calls(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+
+    pub fn models(&self) -> Vec<String> {
+        self.requests().into_iter().map(|r| r.model).collect()
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Provider for ScriptedProvider {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut messages: Vec<ChatMessage> =
+            system_prompt.map(ChatMessage::system).into_iter().collect();
+        messages.push(ChatMessage::user(message));
+        self.chat_with_history(&messages, model, temperature).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        _temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.requests.lock().unwrap().push(Request {
+            model: model.to_string(),
+            messages: messages.to_vec(),
+        });
+        let reply = match &mut *self.script.lock().unwrap() {
+            Script::Replies(replies) => replies
+                .pop_front()
+                .unwrap_or_else(|| Err("no scripted reply left".into())),
+            Script::Always(reply) => reply.clone(),
+            Script::Answering(answer) => Ok(answer(messages)),
+        };
+        reply.map_err(|e| anyhow::anyhow!(e))
+    }
+
+    fn supports_vision(&self, _model: &str) -> bool {
+        self.vision
+    }
+}
+
+/// So a test can hand the provider over and still read its requests.
+#[cfg(test)]
+#[async_trait]
+impl Provider for std::sync::Arc<ScriptedProvider> {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        (**self)
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        (**self)
+            .chat_with_history(messages, model, temperature)
+            .await
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        (**self).supports_vision(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn scripted_replies_run_in_order_then_fail() {
+        let provider = ScriptedProvider::new(&["one", "two"]);
+        assert_eq!(provider.chat("a", "m1", 0.0).await.unwrap(), "one");
+        assert_eq!(provider.chat("b", "m2", 0.0).await.unwrap(), "two");
+        assert!(provider.chat("c", "m3", 0.0).await.is_err());
+        assert_eq!(provider.models(), ["m1", "m2", "m3"]);
+        assert_eq!(provider.requests()[1].last(), "b");
+    }
+
+    #[tokio::test]
+    async fn recorded_answers_replay_without_upstream() {
+        let tmp = tempfile::tempdir().unwrap();
+        let echo = ScriptedProvider::answering(|m| format!("echo: {}", m[m.len() - 1].content));
+        let recorder = MockProvider::recording(tmp.path(), Box::new(echo));
+        let answer = recorder
+            .chat_with_system(Some("prompt at 09:00"), "hello", "m", 0.7)
+            .await
+            .unwrap();
+        assert_eq!(answer, "echo: hello");
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 1);
+
+        // System prompts may differ between runs; the model does not matter
+        let replay = MockProvider::replay(tmp.path());
+        let replayed = replay
+            .chat_with_system(Some("prompt at 10:00"), "hello", "other", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(replayed, "echo: hello");
+
+        let history = [ChatMessage::system("s"), ChatMessage::user("hello")];
+        assert_eq!(
+            replay.chat_with_history(&history, "m", 0.0).await.unwrap(),
+            "echo: hello"
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_fixture_names_the_file_and_message() {
+        let tmp = tempfile::tempdir().unwrap();
+        let replay = MockProvider::replay(tmp.path());
+        let err = replay.chat("never recorded", "m", 0.0).await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("never recorded"));
+        assert!(message.contains(&fixture_key(&[ChatMessage::user("never recorded")])));
+    }
+
+    #[tokio::test]
+    async fn hand_written_fixtures_only_need_a_response() {
+        let tmp = tempfile::tempdir().unwrap();
+        let key = fixture_key(&[ChatMessage::user("status?")]);
+        std::fs::write(
+            tmp.path().join(format!("{key}.json")),
+            r#"{"response": "all good"}"#,
+        )
+        .unwrap();
+        let provider = super::super::create_provider(
+            &format!("mock:{}", tmp.path().display()),
+            None,
+            &super::super::ProviderSettings::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            provider.chat("status?", "m", 0.0).await.unwrap(),
+            "all good"
+        );
+    }
+}
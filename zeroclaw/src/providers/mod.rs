@@ -1,17 +1,26 @@
 pub mod anthropic;
+pub mod azure_openai;
+pub mod cache;
 pub mod compatible;
+pub mod embed;
 pub mod gemini;
+pub mod mock;
 pub mod ollama;
 pub mod openai;
 pub mod openrouter;
 pub mod reliable;
 pub mod router;
+pub mod stream;
 pub mod traits;
+pub mod vision;
 
-pub use traits::{ChatMessage, Provider};
+pub use traits::{ChatMessage, ImagePart, Provider};
+pub use vision::VisionFallback;
 
+use crate::config::ProviderConfig;
 use compatible::{AuthStyle, OpenAiCompatibleProvider};
-use reliable::ReliableProvider;
+use reliable::{ReliableProvider, RetryPolicy};
+use std::collections::BTreeMap;
 
 const MAX_API_ERROR_CHARS: usize = 200;
 
@@ -80,6 +89,55 @@ pub fn sanitize_api_error(input: &str) -> String {
     format!("{}...", &scrubbed[..end])
 }
 
+/// Best-effort check whether a model name refers to an image-capable model.
+///
+/// Used by gateways that serve many model families (`OpenRouter`, OpenAI-compatible
+/// endpoints) where vision support depends on the model, not the provider.
+pub fn model_supports_vision(model: &str) -> bool {
+    const VISION_HINTS: [&str; 12] = [
+        "gpt-4o",
+        "gpt-4.1",
+        "gpt-4-turbo",
+        "gpt-5",
+        "claude-3",
+        "claude-sonnet-4",
+        "claude-opus-4",
+        "gemini",
+        "vision",
+        "-vl",
+        "llava",
+        "pixtral",
+    ];
+    let model = model.to_ascii_lowercase();
+    VISION_HINTS.iter().any(|hint| model.contains(hint))
+}
+
+/// The `[providers.<name>]`, `[azure_openai]` and `[gemini]` sections of one
+/// config. The factories take them from their caller, so FFI handles and
+/// profiles in one process each build providers from their own keys and
+/// endpoints.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderSettings {
+    pub sections: BTreeMap<String, ProviderConfig>,
+    pub azure_openai: crate::config::AzureOpenAiConfig,
+    pub gemini: crate::config::GeminiConfig,
+}
+
+impl ProviderSettings {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            sections: config.providers.clone(),
+            azure_openai: config.azure_openai.clone(),
+            gemini: config.gemini.clone(),
+        }
+    }
+
+    /// The `[providers.<name>]` section for `name`, empty when there is none.
+    fn section(&self, name: &str) -> ProviderConfig {
+        self.sections.get(name).cloned().unwrap_or_default()
+    }
+}
+
 /// Build a sanitized provider error from a failed HTTP response.
 pub async fn api_error(provider: &str, response: reqwest::Response) -> anyhow::Error {
     let status = response.status();
@@ -87,10 +145,57 @@ pub async fn api_error(provider: &str, response: reqwest::Response) -> anyhow::E
         .text()
         .await
         .unwrap_or_else(|_| "<failed to read provider error body>".to_string());
-    let sanitized = sanitize_api_error(&body);
-    anyhow::anyhow!("{provider} API error ({status}): {sanitized}")
+    ApiError::new(provider, status, &body).into()
 }
 
+/// A provider's failed HTTP response, with its body sanitized.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub provider: String,
+    pub status: reqwest::StatusCode,
+    pub body: String,
+}
+
+impl ApiError {
+    pub fn new(provider: &str, status: reqwest::StatusCode, body: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            status,
+            body: sanitize_api_error(body),
+        }
+    }
+
+    /// The provider is throttling us.
+    pub fn is_rate_limited(&self) -> bool {
+        self.status == reqwest::StatusCode::TOO_MANY_REQUESTS
+    }
+
+    /// The request did not fit the model's context window. Providers answer
+    /// with a 400 or 413 and say so only in the body, in their own words.
+    pub fn is_context_overflow(&self) -> bool {
+        const MARKERS: [&str; 5] = [
+            "context_length_exceeded",
+            "context length",
+            "context window",
+            "prompt is too long",
+            "too many tokens",
+        ];
+        if !matches!(self.status.as_u16(), 400 | 413) {
+            return false;
+        }
+        let body = self.body.to_ascii_lowercase();
+        MARKERS.iter().any(|marker| body.contains(marker))
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} API error ({}): {}", self.provider, self.status, self.body)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
 /// Resolve API key for a provider from config and environment variables.
 ///
 /// Resolution order:
@@ -100,7 +205,7 @@ pub async fn api_error(provider: &str, response: reqwest::Response) -> anyhow::E
 ///
 /// For Anthropic, the provider-specific env var is `ANTHROPIC_OAUTH_TOKEN` (for setup-tokens)
 /// followed by `ANTHROPIC_API_KEY` (for regular API keys).
-fn resolve_api_key(name: &str, api_key: Option<&str>) -> Option<String> {
+pub(crate) fn resolve_api_key(name: &str, api_key: Option<&str>) -> Option<String> {
     if let Some(key) = api_key.map(str::trim).filter(|k| !k.is_empty()) {
         return Some(key.to_string());
     }
@@ -109,6 +214,7 @@ fn resolve_api_key(name: &str, api_key: Option<&str>) -> Option<String> {
         "anthropic" => vec!["ANTHROPIC_OAUTH_TOKEN", "ANTHROPIC_API_KEY"],
         "openrouter" => vec!["OPENROUTER_API_KEY"],
         "openai" => vec!["OPENAI_API_KEY"],
+        "azure" | "azure-openai" | "azure_openai" => vec!["AZURE_OPENAI_API_KEY"],
         "venice" => vec!["VENICE_API_KEY"],
         "groq" => vec!["GROQ_API_KEY"],
         "mistral" => vec!["MISTRAL_API_KEY"],
@@ -151,11 +257,42 @@ fn resolve_api_key(name: &str, api_key: Option<&str>) -> Option<String> {
     None
 }
 
-/// Factory: create the right provider from config
+/// Factory: create the right provider from config. A key or `base_url` in
+/// the provider's `[providers.<name>]` section wins over `api_key`.
 #[allow(clippy::too_many_lines)]
-pub fn create_provider(name: &str, api_key: Option<&str>) -> anyhow::Result<Box<dyn Provider>> {
-    let resolved_key = resolve_api_key(name, api_key);
+pub fn create_provider(
+    name: &str,
+    api_key: Option<&str>,
+    settings: &ProviderSettings,
+) -> anyhow::Result<Box<dyn Provider>> {
+    let section = &settings.section(name);
+    let resolved_key = resolve_api_key(name, section.api_key.as_deref().or(api_key));
     let key = resolved_key.as_deref();
+    if let Some(base_url) = section.base_url.as_deref().filter(|url| !url.is_empty()) {
+        match name {
+            "ollama" => return Ok(Box::new(ollama::OllamaProvider::new(Some(base_url)))),
+            "anthropic" => {
+                return Ok(Box::new(anthropic::AnthropicProvider::with_base_url(
+                    key,
+                    Some(base_url),
+                )))
+            }
+            "gemini" | "google" | "google-gemini" | "azure" | "azure-openai" | "azure_openai" => {
+                tracing::warn!(
+                    provider = name,
+                    "base_url is not supported for this provider; configure its own section"
+                );
+            }
+            _ => {
+                return Ok(Box::new(OpenAiCompatibleProvider::new(
+                    name,
+                    base_url,
+                    key,
+                    AuthStyle::Bearer,
+                )))
+            }
+        }
+    }
     match name {
         // ── Primary providers (custom implementations) ───────
         "openrouter" => Ok(Box::new(openrouter::OpenRouterProvider::new(key))),
@@ -165,8 +302,15 @@ pub fn create_provider(name: &str, api_key: Option<&str>) -> anyhow::Result<Box<
         // The api_key parameter is ignored to avoid it being misinterpreted as a base_url.
         "ollama" => Ok(Box::new(ollama::OllamaProvider::new(None))),
         "gemini" | "google" | "google-gemini" => {
-            Ok(Box::new(gemini::GeminiProvider::new(key)))
+            Ok(Box::new(gemini::GeminiProvider::with_settings(
+                key,
+                settings.gemini.clone(),
+            )))
         }
+        // Deployments, endpoint and auth mode come from [azure_openai]
+        "azure" | "azure-openai" | "azure_openai" => Ok(Box::new(
+            azure_openai::AzureOpenAiProvider::new(&settings.azure_openai, key)?,
+        )),
 
         // ── OpenAI-compatible providers ──────────────────────
         "venice" => Ok(Box::new(OpenAiCompatibleProvider::new(
@@ -239,6 +383,11 @@ pub fn create_provider(name: &str, api_key: Option<&str>) -> anyhow::Result<Box<
         ))),
 
         // ── Bring Your Own Provider (custom URL) ───────────
+        // Fixture replay for tests: "mock" or "mock:path/to/fixtures"
+        name if name == "mock" || name.starts_with("mock:") => {
+            Ok(Box::new(mock::MockProvider::from_name(name, api_key, settings)?))
+        }
+
         // Format: "custom:https://your-api.com" or "custom:http://localhost:1234"
         name if name.starts_with("custom:") => {
             let base_url = name.strip_prefix("custom:").unwrap_or("");
@@ -278,12 +427,13 @@ pub fn create_resilient_provider(
     primary_name: &str,
     api_key: Option<&str>,
     reliability: &crate::config::ReliabilityConfig,
+    settings: &ProviderSettings,
 ) -> anyhow::Result<Box<dyn Provider>> {
     let mut providers: Vec<(String, Box<dyn Provider>)> = Vec::new();
 
     providers.push((
         primary_name.to_string(),
-        create_provider(primary_name, api_key)?,
+        create_provider(primary_name, api_key, settings)?,
     ));
 
     for fallback in &reliability.fallback_providers {
@@ -291,7 +441,7 @@ pub fn create_resilient_provider(
             continue;
         }
 
-        if api_key.is_some() && fallback != "ollama" {
+        if api_key.is_some() && fallback != "ollama" && settings.section(fallback).api_key.is_none() {
             tracing::warn!(
                 fallback_provider = fallback,
                 primary_provider = primary_name,
@@ -300,7 +450,7 @@ pub fn create_resilient_provider(
             );
         }
 
-        match create_provider(fallback, api_key) {
+        match create_provider(fallback, api_key, settings) {
             Ok(provider) => providers.push((fallback.clone(), provider)),
             Err(e) => {
                 tracing::warn!(
@@ -311,10 +461,10 @@ pub fn create_resilient_provider(
         }
     }
 
-    Ok(Box::new(ReliableProvider::new(
+    Ok(Box::new(ReliableProvider::with_policy(
         providers,
-        reliability.provider_retries,
-        reliability.provider_backoff_ms,
+        RetryPolicy::from_config(reliability),
+        reliable::shared_breakers(),
     )))
 }
 
@@ -327,9 +477,11 @@ pub fn create_routed_provider(
     reliability: &crate::config::ReliabilityConfig,
     model_routes: &[crate::config::ModelRouteConfig],
     default_model: &str,
+    observer: std::sync::Arc<dyn crate::observability::Observer>,
+    settings: &ProviderSettings,
 ) -> anyhow::Result<Box<dyn Provider>> {
     if model_routes.is_empty() {
-        return create_resilient_provider(primary_name, api_key, reliability);
+        return create_resilient_provider(primary_name, api_key, reliability, settings);
     }
 
     // Collect unique provider names needed
@@ -348,7 +500,7 @@ pub fn create_routed_provider(
             .find(|r| &r.provider == name)
             .and_then(|r| r.api_key.as_deref())
             .or(api_key);
-        match create_resilient_provider(name, key, reliability) {
+        match create_resilient_provider(name, key, reliability, settings) {
             Ok(provider) => providers.push((name.clone(), provider)),
             Err(e) => {
                 if name == primary_name {
@@ -371,196 +523,242 @@ pub fn create_routed_provider(
                 router::Route {
                     provider_name: r.provider.clone(),
                     model: r.model.clone(),
+                    matches: r.matches.clone(),
                 },
             )
         })
         .collect();
 
-    Ok(Box::new(router::RouterProvider::new(
-        providers,
-        routes,
-        default_model.to_string(),
-    )))
+    Ok(Box::new(
+        router::RouterProvider::new(providers, routes, default_model.to_string())
+            .with_observer(observer),
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// [`create_provider`] without any `[providers.<name>]` sections.
+    fn create(name: &str, api_key: Option<&str>) -> anyhow::Result<Box<dyn Provider>> {
+        create_provider(name, api_key, &ProviderSettings::default())
+    }
+
+    #[test]
+    fn model_supports_vision_matches_known_families() {
+        assert!(model_supports_vision("openai/gpt-4o-mini"));
+        assert!(model_supports_vision("anthropic/claude-sonnet-4-20250514"));
+        assert!(model_supports_vision("qwen/qwen2.5-VL-72b-instruct"));
+        assert!(model_supports_vision("llama-3.2-11b-vision-preview"));
+        assert!(!model_supports_vision("deepseek-chat"));
+        assert!(!model_supports_vision("gpt-3.5-turbo"));
+        assert!(!model_supports_vision("llama-3.3-70b-versatile"));
+    }
+
     // ── Primary providers ────────────────────────────────────
 
     #[test]
     fn factory_openrouter() {
-        assert!(create_provider("openrouter", Some("sk-test")).is_ok());
-        assert!(create_provider("openrouter", None).is_ok());
+        assert!(create("openrouter", Some("sk-test")).is_ok());
+        assert!(create("openrouter", None).is_ok());
+    }
+
+    #[test]
+    fn provider_sections_add_endpoints_and_keys() {
+        let gateway = ProviderConfig {
+            api_key: Some("sk-team".into()),
+            base_url: Some("https://llm.internal.example/v1".into()),
+        };
+        let settings = ProviderSettings {
+            sections: BTreeMap::from([("team-gateway".to_string(), gateway)]),
+            ..ProviderSettings::default()
+        };
+        assert!(create("team-gateway", None).is_err());
+        assert!(create_provider("team-gateway", None, &settings).is_ok());
+        let local = ProviderConfig {
+            api_key: None,
+            base_url: Some("http://gpu-box:11434".into()),
+        };
+        let settings = ProviderSettings {
+            sections: BTreeMap::from([("ollama".to_string(), local)]),
+            ..ProviderSettings::default()
+        };
+        assert!(create_provider("ollama", None, &settings).is_ok());
+    }
+
+    #[test]
+    fn azure_reads_its_section_from_the_callers_settings() {
+        let mut settings = ProviderSettings::default();
+        settings.azure_openai.endpoint = Some("https://team.openai.azure.com".into());
+        assert!(create_provider("azure", Some("key"), &settings).is_ok());
     }
 
     #[test]
     fn factory_anthropic() {
-        assert!(create_provider("anthropic", Some("sk-test")).is_ok());
+        assert!(create("anthropic", Some("sk-test")).is_ok());
     }
 
     #[test]
     fn factory_openai() {
-        assert!(create_provider("openai", Some("sk-test")).is_ok());
+        assert!(create("openai", Some("sk-test")).is_ok());
     }
 
     #[test]
     fn factory_ollama() {
-        assert!(create_provider("ollama", None).is_ok());
+        assert!(create("ollama", None).is_ok());
         // Ollama ignores the api_key parameter since it's a local service
-        assert!(create_provider("ollama", Some("dummy")).is_ok());
-        assert!(create_provider("ollama", Some("any-value-here")).is_ok());
+        assert!(create("ollama", Some("dummy")).is_ok());
+        assert!(create("ollama", Some("any-value-here")).is_ok());
     }
 
     #[test]
     fn factory_gemini() {
-        assert!(create_provider("gemini", Some("test-key")).is_ok());
-        assert!(create_provider("google", Some("test-key")).is_ok());
-        assert!(create_provider("google-gemini", Some("test-key")).is_ok());
+        assert!(create("gemini", Some("test-key")).is_ok());
+        assert!(create("google", Some("test-key")).is_ok());
+        assert!(create("google-gemini", Some("test-key")).is_ok());
         // Should also work without key (will try CLI auth)
-        assert!(create_provider("gemini", None).is_ok());
+        assert!(create("gemini", None).is_ok());
     }
 
     // ── OpenAI-compatible providers ──────────────────────────
 
     #[test]
     fn factory_venice() {
-        assert!(create_provider("venice", Some("vn-key")).is_ok());
+        assert!(create("venice", Some("vn-key")).is_ok());
     }
 
     #[test]
     fn factory_vercel() {
-        assert!(create_provider("vercel", Some("key")).is_ok());
-        assert!(create_provider("vercel-ai", Some("key")).is_ok());
+        assert!(create("vercel", Some("key")).is_ok());
+        assert!(create("vercel-ai", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_cloudflare() {
-        assert!(create_provider("cloudflare", Some("key")).is_ok());
-        assert!(create_provider("cloudflare-ai", Some("key")).is_ok());
+        assert!(create("cloudflare", Some("key")).is_ok());
+        assert!(create("cloudflare-ai", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_moonshot() {
-        assert!(create_provider("moonshot", Some("key")).is_ok());
-        assert!(create_provider("kimi", Some("key")).is_ok());
+        assert!(create("moonshot", Some("key")).is_ok());
+        assert!(create("kimi", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_synthetic() {
-        assert!(create_provider("synthetic", Some("key")).is_ok());
+        assert!(create("synthetic", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_opencode() {
-        assert!(create_provider("opencode", Some("key")).is_ok());
-        assert!(create_provider("opencode-zen", Some("key")).is_ok());
+        assert!(create("opencode", Some("key")).is_ok());
+        assert!(create("opencode-zen", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_zai() {
-        assert!(create_provider("zai", Some("key")).is_ok());
-        assert!(create_provider("z.ai", Some("key")).is_ok());
+        assert!(create("zai", Some("key")).is_ok());
+        assert!(create("z.ai", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_glm() {
-        assert!(create_provider("glm", Some("key")).is_ok());
-        assert!(create_provider("zhipu", Some("key")).is_ok());
+        assert!(create("glm", Some("key")).is_ok());
+        assert!(create("zhipu", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_minimax() {
-        assert!(create_provider("minimax", Some("key")).is_ok());
+        assert!(create("minimax", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_bedrock() {
-        assert!(create_provider("bedrock", Some("key")).is_ok());
-        assert!(create_provider("aws-bedrock", Some("key")).is_ok());
+        assert!(create("bedrock", Some("key")).is_ok());
+        assert!(create("aws-bedrock", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_qianfan() {
-        assert!(create_provider("qianfan", Some("key")).is_ok());
-        assert!(create_provider("baidu", Some("key")).is_ok());
+        assert!(create("qianfan", Some("key")).is_ok());
+        assert!(create("baidu", Some("key")).is_ok());
     }
 
     // ── Extended ecosystem ───────────────────────────────────
 
     #[test]
     fn factory_groq() {
-        assert!(create_provider("groq", Some("key")).is_ok());
+        assert!(create("groq", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_mistral() {
-        assert!(create_provider("mistral", Some("key")).is_ok());
+        assert!(create("mistral", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_xai() {
-        assert!(create_provider("xai", Some("key")).is_ok());
-        assert!(create_provider("grok", Some("key")).is_ok());
+        assert!(create("xai", Some("key")).is_ok());
+        assert!(create("grok", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_deepseek() {
-        assert!(create_provider("deepseek", Some("key")).is_ok());
+        assert!(create("deepseek", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_together() {
-        assert!(create_provider("together", Some("key")).is_ok());
-        assert!(create_provider("together-ai", Some("key")).is_ok());
+        assert!(create("together", Some("key")).is_ok());
+        assert!(create("together-ai", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_fireworks() {
-        assert!(create_provider("fireworks", Some("key")).is_ok());
-        assert!(create_provider("fireworks-ai", Some("key")).is_ok());
+        assert!(create("fireworks", Some("key")).is_ok());
+        assert!(create("fireworks-ai", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_perplexity() {
-        assert!(create_provider("perplexity", Some("key")).is_ok());
+        assert!(create("perplexity", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_cohere() {
-        assert!(create_provider("cohere", Some("key")).is_ok());
+        assert!(create("cohere", Some("key")).is_ok());
     }
 
     #[test]
     fn factory_copilot() {
-        assert!(create_provider("copilot", Some("key")).is_ok());
-        assert!(create_provider("github-copilot", Some("key")).is_ok());
+        assert!(create("copilot", Some("key")).is_ok());
+        assert!(create("github-copilot", Some("key")).is_ok());
     }
 
     // ── Custom / BYOP provider ─────────────────────────────
 
     #[test]
     fn factory_custom_url() {
-        let p = create_provider("custom:https://my-llm.example.com", Some("key"));
+        let p = create("custom:https://my-llm.example.com", Some("key"));
         assert!(p.is_ok());
     }
 
     #[test]
     fn factory_custom_localhost() {
-        let p = create_provider("custom:http://localhost:1234", Some("key"));
+        let p = create("custom:http://localhost:1234", Some("key"));
         assert!(p.is_ok());
     }
 
     #[test]
     fn factory_custom_no_key() {
-        let p = create_provider("custom:https://my-llm.example.com", None);
+        let p = create("custom:https://my-llm.example.com", None);
         assert!(p.is_ok());
     }
 
     #[test]
     fn factory_custom_empty_url_errors() {
-        match create_provider("custom:", None) {
+        match create("custom:", None) {
             Err(e) => assert!(
                 e.to_string().contains("requires a URL"),
                 "Expected 'requires a URL', got: {e}"
@@ -573,25 +771,25 @@ mod tests {
 
     #[test]
     fn factory_anthropic_custom_url() {
-        let p = create_provider("anthropic-custom:https://api.example.com", Some("key"));
+        let p = create("anthropic-custom:https://api.example.com", Some("key"));
         assert!(p.is_ok());
     }
 
     #[test]
     fn factory_anthropic_custom_trailing_slash() {
-        let p = create_provider("anthropic-custom:https://api.example.com/", Some("key"));
+        let p = create("anthropic-custom:https://api.example.com/", Some("key"));
         assert!(p.is_ok());
     }
 
     #[test]
     fn factory_anthropic_custom_no_key() {
-        let p = create_provider("anthropic-custom:https://api.example.com", None);
+        let p = create("anthropic-custom:https://api.example.com", None);
         assert!(p.is_ok());
     }
 
     #[test]
     fn factory_anthropic_custom_empty_url_errors() {
-        match create_provider("anthropic-custom:", None) {
+        match create("anthropic-custom:", None) {
             Err(e) => assert!(
                 e.to_string().contains("requires a URL"),
                 "Expected 'requires a URL', got: {e}"
@@ -604,7 +802,7 @@ mod tests {
 
     #[test]
     fn factory_unknown_provider_errors() {
-        let p = create_provider("nonexistent", None);
+        let p = create("nonexistent", None);
         assert!(p.is_err());
         let msg = p.err().unwrap().to_string();
         assert!(msg.contains("Unknown provider"));
@@ -613,7 +811,7 @@ mod tests {
 
     #[test]
     fn factory_empty_name_errors() {
-        assert!(create_provider("", None).is_err());
+        assert!(create("", None).is_err());
     }
 
     #[test]
@@ -627,20 +825,27 @@ mod tests {
                 "openai".into(),
                 "openai".into(),
             ],
-            channel_initial_backoff_secs: 2,
-            channel_max_backoff_secs: 60,
-            scheduler_poll_secs: 15,
-            scheduler_retries: 2,
+            ..crate::config::ReliabilityConfig::default()
         };
 
-        let provider = create_resilient_provider("openrouter", Some("sk-test"), &reliability);
+        let provider = create_resilient_provider(
+            "openrouter",
+            Some("sk-test"),
+            &reliability,
+            &ProviderSettings::default(),
+        );
         assert!(provider.is_ok());
     }
 
     #[test]
     fn resilient_provider_errors_for_invalid_primary() {
         let reliability = crate::config::ReliabilityConfig::default();
-        let provider = create_resilient_provider("totally-invalid", Some("sk-test"), &reliability);
+        let provider = create_resilient_provider(
+            "totally-invalid",
+            Some("sk-test"),
+            &reliability,
+            &ProviderSettings::default(),
+        );
         assert!(provider.is_err());
     }
 
@@ -675,7 +880,7 @@ mod tests {
         ];
         for name in providers {
             assert!(
-                create_provider(name, Some("test-key")).is_ok(),
+                create(name, Some("test-key")).is_ok(),
                 "Provider '{name}' should create successfully"
             );
         }
@@ -1,4 +1,5 @@
-use crate::providers::traits::Provider;
+use crate::providers::embed;
+use crate::providers::traits::{Embeddings, Provider};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -37,6 +38,19 @@ struct ResponseMessage {
     content: String,
 }
 
+#[derive(Debug, Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [&'a str],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+}
+
 impl OllamaProvider {
     pub fn new(base_url: Option<&str>) -> Self {
         Self {
@@ -95,6 +109,33 @@ impl Provider for OllamaProvider {
         let chat_response: ChatResponse = response.json().await?;
         Ok(chat_response.message.content)
     }
+
+    async fn embed(&self, texts: &[&str], model: &str) -> anyhow::Result<Embeddings> {
+        let url = format!("{}/api/embed", self.base_url);
+        embed::in_batches(texts, embed::EMBED_BATCH_SIZE, |batch| {
+            let request = self.client.post(&url).json(&EmbedRequest {
+                model,
+                input: batch,
+            });
+            async move {
+                let response = request.send().await?;
+                if !response.status().is_success() {
+                    let err = super::api_error("Ollama", response).await;
+                    anyhow::bail!(
+                        "{err}. Is Ollama running? (brew install ollama && ollama serve)"
+                    );
+                }
+                let parsed: EmbedResponse = response.json().await?;
+                Ok(Embeddings {
+                    tokens: parsed
+                        .prompt_eval_count
+                        .unwrap_or_else(|| embed::estimate_tokens(batch)),
+                    vectors: parsed.embeddings,
+                })
+            }
+        })
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -165,6 +206,14 @@ mod tests {
         assert!(json.contains("mistral"));
     }
 
+    #[test]
+    fn embed_response_deserializes() {
+        let json = r#"{"model":"nomic-embed-text","embeddings":[[0.1,0.2],[0.3,0.4]],"prompt_eval_count":6}"#;
+        let resp: EmbedResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.embeddings.len(), 2);
+        assert_eq!(resp.prompt_eval_count, Some(6));
+    }
+
     #[test]
     fn response_deserializes() {
         let json = r#"{"message":{"role":"assistant","content":"Hello from Ollama!"}}"#;
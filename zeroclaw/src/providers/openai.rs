@@ -1,4 +1,7 @@
-use crate::providers::traits::Provider;
+use crate::providers::compatible::MessageContent;
+use crate::providers::traits::{ChatMessage, ChatStream, Embeddings, Provider};
+use crate::providers::{embed, stream};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -13,12 +16,14 @@ struct ChatRequest {
     model: String,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
-    content: String,
+    content: MessageContent,
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,6 +52,42 @@ impl OpenAiProvider {
                 .unwrap_or_else(|_| Client::new()),
         }
     }
+
+    async fn send(
+        &self,
+        api_key: &str,
+        messages: Vec<Message>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages,
+            temperature,
+            stream: None,
+        };
+
+        let response = self
+            .client
+            .post("https://api.openai.com/v1/chat/completions")
+            .header("Authorization", format!("Bearer {api_key}"))
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenAI", response).await);
+        }
+
+        let chat_response: ChatResponse = response.json().await?;
+
+        chat_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message.content)
+            .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))
+    }
 }
 
 #[async_trait]
@@ -67,41 +108,86 @@ impl Provider for OpenAiProvider {
         if let Some(sys) = system_prompt {
             messages.push(Message {
                 role: "system".to_string(),
-                content: sys.to_string(),
+                content: sys.to_string().into(),
             });
         }
 
         messages.push(Message {
             role: "user".to_string(),
-            content: message.to_string(),
+            content: message.to_string().into(),
         });
 
+        self.send(api_key, messages, model, temperature).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+        })?;
+
+        let api_messages = messages
+            .iter()
+            .map(|m| Message {
+                role: m.role.clone(),
+                content: m.into(),
+            })
+            .collect();
+
+        self.send(api_key, api_messages, model, temperature).await
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+        })?;
+
         let request = ChatRequest {
             model: model.to_string(),
-            messages,
+            messages: messages
+                .iter()
+                .map(|m| Message {
+                    role: m.role.clone(),
+                    content: m.into(),
+                })
+                .collect(),
             temperature,
+            stream: Some(true),
         };
-
-        let response = self
+        let request = self
             .client
             .post("https://api.openai.com/v1/chat/completions")
             .header("Authorization", format!("Bearer {api_key}"))
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(super::api_error("OpenAI", response).await);
-        }
+            .json(&request);
+        stream::openai_compatible("OpenAI", request).await
+    }
 
-        let chat_response: ChatResponse = response.json().await?;
+    fn supports_vision(&self, model: &str) -> bool {
+        super::model_supports_vision(model)
+    }
 
-        chat_response
-            .choices
-            .into_iter()
-            .next()
-            .map(|c| c.message.content)
-            .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))
+    async fn embed(&self, texts: &[&str], model: &str) -> anyhow::Result<Embeddings> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+        })?;
+        embed::in_batches(texts, embed::EMBED_BATCH_SIZE, |batch| {
+            let request = self
+                .client
+                .post("https://api.openai.com/v1/embeddings")
+                .header("Authorization", format!("Bearer {api_key}"));
+            embed::openai_compatible("OpenAI", request, model, batch)
+        })
+        .await
     }
 }
 
@@ -135,6 +221,16 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("API key not set"));
     }
 
+    #[tokio::test]
+    async fn embed_fails_without_key() {
+        let p = OpenAiProvider::new(None);
+        let err = p
+            .embed(&["hello"], "text-embedding-3-small")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("API key not set"));
+    }
+
     #[tokio::test]
     async fn chat_with_system_fails_without_key() {
         let p = OpenAiProvider::new(None);
@@ -151,14 +247,15 @@ mod tests {
             messages: vec![
                 Message {
                     role: "system".to_string(),
-                    content: "You are ZeroClaw".to_string(),
+                    content: "You are ZeroClaw".to_string().into(),
                 },
                 Message {
                     role: "user".to_string(),
-                    content: "hello".to_string(),
+                    content: "hello".to_string().into(),
                 },
             ],
             temperature: 0.7,
+            stream: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"role\":\"system\""));
@@ -172,9 +269,10 @@ mod tests {
             model: "gpt-4o".to_string(),
             messages: vec![Message {
                 role: "user".to_string(),
-                content: "hello".to_string(),
+                content: "hello".to_string().into(),
             }],
             temperature: 0.0,
+            stream: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(!json.contains("system"));
@@ -1,4 +1,7 @@
-use crate::providers::traits::{ChatMessage, Provider};
+use crate::providers::compatible::MessageContent;
+use crate::providers::traits::{ChatMessage, ChatStream, Embeddings, Provider};
+use crate::providers::{embed, stream};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -13,12 +16,14 @@ struct ChatRequest {
     model: String,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
-    content: String,
+    content: MessageContent,
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,6 +56,10 @@ impl OpenRouterProvider {
 
 #[async_trait]
 impl Provider for OpenRouterProvider {
+    fn supports_vision(&self, model: &str) -> bool {
+        super::model_supports_vision(model)
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         // Hit a lightweight endpoint to establish TLS + HTTP/2 connection pool.
         // This prevents the first real chat request from timing out on cold start.
@@ -80,19 +89,20 @@ impl Provider for OpenRouterProvider {
         if let Some(sys) = system_prompt {
             messages.push(Message {
                 role: "system".to_string(),
-                content: sys.to_string(),
+                content: sys.to_string().into(),
             });
         }
 
         messages.push(Message {
             role: "user".to_string(),
-            content: message.to_string(),
+            content: message.to_string().into(),
         });
 
         let request = ChatRequest {
             model: model.to_string(),
             messages,
             temperature,
+            stream: None,
         };
 
         let response = self
@@ -135,7 +145,7 @@ impl Provider for OpenRouterProvider {
             .iter()
             .map(|m| Message {
                 role: m.role.clone(),
-                content: m.content.clone(),
+                content: m.into(),
             })
             .collect();
 
@@ -143,6 +153,7 @@ impl Provider for OpenRouterProvider {
             model: model.to_string(),
             messages: api_messages,
             temperature,
+            stream: None,
         };
 
         let response = self
@@ -171,4 +182,57 @@ impl Provider for OpenRouterProvider {
             .map(|c| c.message.content)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenRouter"))
     }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let api_key = self.api_key.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("OpenRouter API key not set. Run `zeroclaw onboard` or set OPENROUTER_API_KEY env var."))?;
+
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages: messages
+                .iter()
+                .map(|m| Message {
+                    role: m.role.clone(),
+                    content: m.into(),
+                })
+                .collect(),
+            temperature,
+            stream: Some(true),
+        };
+        let request = self
+            .client
+            .post("https://openrouter.ai/api/v1/chat/completions")
+            .header("Authorization", format!("Bearer {api_key}"))
+            .header(
+                "HTTP-Referer",
+                "https://github.com/theonlyhennygod/zeroclaw",
+            )
+            .header("X-Title", "ZeroClaw")
+            .json(&request);
+        stream::openai_compatible("OpenRouter", request).await
+    }
+
+    async fn embed(&self, texts: &[&str], model: &str) -> anyhow::Result<Embeddings> {
+        let api_key = self.api_key.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("OpenRouter API key not set. Run `zeroclaw onboard` or set OPENROUTER_API_KEY env var."))?;
+        embed::in_batches(texts, embed::EMBED_BATCH_SIZE, |batch| {
+            let request = self
+                .client
+                .post("https://openrouter.ai/api/v1/embeddings")
+                .header("Authorization", format!("Bearer {api_key}"))
+                .header(
+                    "HTTP-Referer",
+                    "https://github.com/theonlyhennygod/zeroclaw",
+                )
+                .header("X-Title", "ZeroClaw");
+            embed::openai_compatible("OpenRouter", request, model, batch)
+        })
+        .await
+    }
 }
@@ -1,82 +1,255 @@
-use super::traits::ChatMessage;
+use super::traits::{ChatMessage, ChatStream, Embeddings};
 use super::Provider;
+use crate::config::ReliabilityConfig;
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
-use std::time::Duration;
-
-/// Check if an error is non-retryable (client errors that won't resolve with retries).
-fn is_non_retryable(err: &anyhow::Error) -> bool {
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// HTTP status carried by a provider error, if any.
+fn status_code(err: &anyhow::Error) -> Option<u16> {
+    if let Some(api_err) = err.downcast_ref::<super::ApiError>() {
+        return Some(api_err.status.as_u16());
+    }
     // Check for reqwest status errors (returned by .error_for_status())
     if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>() {
         if let Some(status) = reqwest_err.status() {
-            let code = status.as_u16();
-            // 4xx client errors are non-retryable, except:
-            // - 429 Too Many Requests (rate limiting, transient)
-            // - 408 Request Timeout (transient)
-            return status.is_client_error() && code != 429 && code != 408;
+            return Some(status.as_u16());
         }
     }
-    // String fallback: scan for any 4xx status code in error message
+    // String fallback: provider errors lead with the status, e.g.
+    // "OpenAI API error (503 Service Unavailable): ..."
     let msg = err.to_string();
-    for word in msg.split(|c: char| !c.is_ascii_digit()) {
-        if let Ok(code) = word.parse::<u16>() {
-            if (400..500).contains(&code) {
-                return code != 429 && code != 408;
+    msg.split(|c: char| !c.is_ascii_digit())
+        .filter(|word| word.len() == 3)
+        .filter_map(|word| word.parse::<u16>().ok())
+        .find(|code| (400..600).contains(code))
+}
+
+/// Error when every provider failed or was skipped. Its source is the last
+/// provider error, so callers can still tell e.g. a rate limit apart.
+#[derive(Debug)]
+pub struct AllProvidersFailed {
+    pub attempts: Vec<String>,
+    pub last: Option<anyhow::Error>,
+}
+
+impl std::fmt::Display for AllProvidersFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "All providers failed. Attempts:\n{}", self.attempts.join("\n"))
+    }
+}
+
+impl std::error::Error for AllProvidersFailed {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.last
+            .as_ref()
+            .map(|err| err.as_ref() as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// When and how often a failed provider call is retried, and when a
+/// provider that keeps failing is taken out of rotation.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Statuses worth retrying; errors without a status always are
+    pub retry_status_codes: Vec<u16>,
+    /// Consecutive retryable failures that open a provider's circuit; 0 never
+    pub breaker_threshold: u32,
+    pub breaker_cooldown: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from_config(&ReliabilityConfig::default())
+    }
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &ReliabilityConfig) -> Self {
+        Self {
+            max_retries: config.provider_retries,
+            base_backoff_ms: config.provider_backoff_ms.max(50),
+            max_backoff_ms: config.provider_backoff_max_ms,
+            retry_status_codes: config.retry_status_codes.clone(),
+            breaker_threshold: config.circuit_breaker_threshold,
+            breaker_cooldown: Duration::from_secs(config.circuit_breaker_cooldown_secs),
+        }
+    }
+
+    /// Whether `err` may go away on retry: a listed status, or a transport
+    /// error (timeout, reset) that carries none.
+    pub fn is_retryable(&self, err: &anyhow::Error) -> bool {
+        status_code(err).is_none_or(|code| self.retry_status_codes.contains(&code))
+    }
+
+    /// Delay before retry number `retry` (from 0): the base doubled per
+    /// retry up to the cap, of which a random half is jitter so clients
+    /// that failed together don't retry together.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let ceiling = self
+            .base_backoff_ms
+            .saturating_mul(1u64 << retry.min(32))
+            .min(self.max_backoff_ms.max(1));
+        let fixed = ceiling / 2;
+        let jitter = RandomState::new().build_hasher().finish() % (ceiling - fixed + 1);
+        Duration::from_millis(fixed + jitter)
+    }
+}
+
+/// Consecutive failures of one provider and when its circuit opened.
+#[derive(Debug, Default)]
+struct Circuit {
+    failures: u32,
+    opened_at: Option<Instant>,
+}
+
+/// Circuit breaker state per provider name.
+#[derive(Debug, Default)]
+pub struct Breakers {
+    circuits: HashMap<String, Circuit>,
+}
+
+impl Breakers {
+    /// Whether `provider` may be called: its circuit is closed, or open
+    /// for longer than the cooldown (half-open, one trial at a time is
+    /// not enforced).
+    fn allows(&mut self, provider: &str, policy: &RetryPolicy) -> bool {
+        let Some(opened_at) = self.circuits.get(provider).and_then(|c| c.opened_at) else {
+            return true;
+        };
+        if opened_at.elapsed() < policy.breaker_cooldown {
+            return false;
+        }
+        crate::health::set_circuit_state(provider, "half_open");
+        true
+    }
+
+    fn record_success(&mut self, provider: &str) {
+        if let Some(circuit) = self.circuits.remove(provider) {
+            if circuit.opened_at.is_some() {
+                tracing::info!(provider, "Provider recovered, circuit closed");
+                crate::health::set_circuit_state(provider, "closed");
+                crate::health::mark_component_ok(&format!("provider:{provider}"));
             }
         }
     }
-    false
+
+    fn record_failure(&mut self, provider: &str, policy: &RetryPolicy) {
+        if policy.breaker_threshold == 0 {
+            return;
+        }
+        let circuit = self.circuits.entry(provider.to_string()).or_default();
+        circuit.failures = circuit.failures.saturating_add(1);
+        if circuit.failures >= policy.breaker_threshold {
+            if circuit.opened_at.is_none() {
+                tracing::warn!(
+                    provider,
+                    failures = circuit.failures,
+                    "Provider keeps failing, circuit opened"
+                );
+                crate::health::mark_component_error(
+                    &format!("provider:{provider}"),
+                    format!(
+                        "circuit open after {} consecutive failures",
+                        circuit.failures
+                    ),
+                );
+            }
+            // A failed trial call re-opens the circuit for another cooldown
+            circuit.opened_at = Some(Instant::now());
+            crate::health::set_circuit_state(provider, "open");
+        }
+    }
 }
 
-/// Provider wrapper with retry + fallback behavior.
+/// The process-wide breakers used by [`super::create_resilient_provider`],
+/// so failures count across the per-turn providers built for each request.
+pub fn shared_breakers() -> Arc<Mutex<Breakers>> {
+    static SHARED: OnceLock<Arc<Mutex<Breakers>>> = OnceLock::new();
+    SHARED.get_or_init(Arc::default).clone()
+}
+
+/// Provider wrapper with retry, circuit breaker and fallback behavior.
 pub struct ReliableProvider {
     providers: Vec<(String, Box<dyn Provider>)>,
-    max_retries: u32,
-    base_backoff_ms: u64,
+    policy: RetryPolicy,
+    breakers: Arc<Mutex<Breakers>>,
 }
 
 impl ReliableProvider {
+    /// Default policy with the given retries and base backoff, and breakers
+    /// of its own.
     pub fn new(
         providers: Vec<(String, Box<dyn Provider>)>,
         max_retries: u32,
         base_backoff_ms: u64,
     ) -> Self {
-        Self {
-            providers,
+        let policy = RetryPolicy {
             max_retries,
             base_backoff_ms: base_backoff_ms.max(50),
-        }
+            ..RetryPolicy::default()
+        };
+        Self::with_policy(providers, policy, Arc::default())
     }
-}
 
-#[async_trait]
-impl Provider for ReliableProvider {
-    async fn warmup(&self) -> anyhow::Result<()> {
-        for (name, provider) in &self.providers {
-            tracing::info!(provider = name, "Warming up provider connection pool");
-            if let Err(e) = provider.warmup().await {
-                tracing::warn!(provider = name, "Warmup failed (non-fatal): {e}");
-            }
+    pub fn with_policy(
+        providers: Vec<(String, Box<dyn Provider>)>,
+        policy: RetryPolicy,
+        breakers: Arc<Mutex<Breakers>>,
+    ) -> Self {
+        Self {
+            providers,
+            policy,
+            breakers,
         }
-        Ok(())
     }
 
-    async fn chat_with_system(
-        &self,
-        system_prompt: Option<&str>,
-        message: &str,
-        model: &str,
-        temperature: f64,
-    ) -> anyhow::Result<String> {
+    fn breakers(&self) -> std::sync::MutexGuard<'_, Breakers> {
+        self.breakers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Run `call` against each provider in turn (only the primary unless
+    /// `fallbacks`), retrying retryable errors with backoff, until one
+    /// succeeds. `what` names the call in logs.
+    async fn call<'s, T, F, Fut>(
+        &'s self,
+        what: &str,
+        fallbacks: bool,
+        call: F,
+    ) -> anyhow::Result<T>
+    where
+        F: Fn(&'s dyn Provider) -> Fut + Send + Sync,
+        Fut: Future<Output = anyhow::Result<T>> + Send,
+        T: Send,
+    {
+        let policy = &self.policy;
+        let providers = if fallbacks {
+            &self.providers[..]
+        } else {
+            &self.providers[..self.providers.len().min(1)]
+        };
         let mut failures = Vec::new();
+        let mut last_error = None;
 
-        for (provider_name, provider) in &self.providers {
-            let mut backoff_ms = self.base_backoff_ms;
+        for (provider_name, provider) in providers {
+            if !self.breakers().allows(provider_name, policy) {
+                tracing::warn!(provider = provider_name, "Circuit open, skipping provider");
+                failures.push(format!("{provider_name}: circuit open, skipped"));
+                continue;
+            }
 
-            for attempt in 0..=self.max_retries {
-                match provider
-                    .chat_with_system(system_prompt, message, model, temperature)
-                    .await
-                {
+            for attempt in 0..=policy.max_retries {
+                match call(provider.as_ref()).await {
                     Ok(resp) => {
                         if attempt > 0 {
                             tracing::info!(
@@ -85,17 +258,19 @@ impl Provider for ReliableProvider {
                                 "Provider recovered after retries"
                             );
                         }
+                        self.breakers().record_success(provider_name);
                         return Ok(resp);
                     }
                     Err(e) => {
-                        let non_retryable = is_non_retryable(&e);
+                        let retryable = policy.is_retryable(&e);
                         failures.push(format!(
                             "{provider_name} attempt {}/{}: {e}",
                             attempt + 1,
-                            self.max_retries + 1
+                            policy.max_retries + 1
                         ));
+                        last_error = Some(e);
 
-                        if non_retryable {
+                        if !retryable {
                             tracing::warn!(
                                 provider = provider_name,
                                 "Non-retryable error, switching provider"
@@ -103,24 +278,85 @@ impl Provider for ReliableProvider {
                             break;
                         }
 
-                        if attempt < self.max_retries {
+                        let circuit_open = {
+                            let mut breakers = self.breakers();
+                            breakers.record_failure(provider_name, policy);
+                            !breakers.allows(provider_name, policy)
+                        };
+                        if circuit_open {
+                            break;
+                        }
+
+                        if attempt < policy.max_retries {
                             tracing::warn!(
                                 provider = provider_name,
                                 attempt = attempt + 1,
-                                max_retries = self.max_retries,
-                                "Provider call failed, retrying"
+                                max_retries = policy.max_retries,
+                                "{what} failed, retrying"
                             );
-                            tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
-                            backoff_ms = (backoff_ms.saturating_mul(2)).min(10_000);
+                            tokio::time::sleep(policy.backoff(attempt)).await;
                         }
                     }
                 }
             }
 
-            tracing::warn!(provider = provider_name, "Switching to fallback provider");
+            if fallbacks {
+                tracing::warn!(provider = provider_name, "Switching to fallback provider");
+            }
+        }
+
+        match last_error {
+            // Keep the provider's own error when there was nothing to fall back to
+            Some(e) if !fallbacks => Err(e),
+            _ if providers.is_empty() => anyhow::bail!("No provider configured"),
+            last => Err(AllProvidersFailed {
+                attempts: failures,
+                last,
+            }
+            .into()),
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for ReliableProvider {
+    /// Vision support follows the primary provider; fallbacks are best-effort.
+    fn supports_vision(&self, model: &str) -> bool {
+        self.providers
+            .first()
+            .is_some_and(|(_, provider)| provider.supports_vision(model))
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        for (name, provider) in &self.providers {
+            tracing::info!(provider = name, "Warming up provider connection pool");
+            if let Err(e) = provider.warmup().await {
+                tracing::warn!(provider = name, "Warmup failed (non-fatal): {e}");
+            }
         }
+        Ok(())
+    }
+
+    /// Retries on the primary provider only: a fallback's embedding model
+    /// would produce vectors that don't compare with stored ones.
+    async fn embed(&self, texts: &[&str], model: &str) -> anyhow::Result<Embeddings> {
+        self.call("Embedding call", false, |provider| {
+            provider.embed(texts, model)
+        })
+        .await
+    }
 
-        anyhow::bail!("All providers failed. Attempts:\n{}", failures.join("\n"))
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.call("Provider call", true, |provider| {
+            provider.chat_with_system(system_prompt, message, model, temperature)
+        })
+        .await
     }
 
     async fn chat_with_history(
@@ -129,60 +365,25 @@ impl Provider for ReliableProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let mut failures = Vec::new();
-
-        for (provider_name, provider) in &self.providers {
-            let mut backoff_ms = self.base_backoff_ms;
-
-            for attempt in 0..=self.max_retries {
-                match provider
-                    .chat_with_history(messages, model, temperature)
-                    .await
-                {
-                    Ok(resp) => {
-                        if attempt > 0 {
-                            tracing::info!(
-                                provider = provider_name,
-                                attempt,
-                                "Provider recovered after retries"
-                            );
-                        }
-                        return Ok(resp);
-                    }
-                    Err(e) => {
-                        let non_retryable = is_non_retryable(&e);
-                        failures.push(format!(
-                            "{provider_name} attempt {}/{}: {e}",
-                            attempt + 1,
-                            self.max_retries + 1
-                        ));
-
-                        if non_retryable {
-                            tracing::warn!(
-                                provider = provider_name,
-                                "Non-retryable error, switching provider"
-                            );
-                            break;
-                        }
-
-                        if attempt < self.max_retries {
-                            tracing::warn!(
-                                provider = provider_name,
-                                attempt = attempt + 1,
-                                max_retries = self.max_retries,
-                                "Provider call failed, retrying"
-                            );
-                            tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
-                            backoff_ms = (backoff_ms.saturating_mul(2)).min(10_000);
-                        }
-                    }
-                }
-            }
-
-            tracing::warn!(provider = provider_name, "Switching to fallback provider");
-        }
+        self.call("Provider call", true, |provider| {
+            provider.chat_with_history(messages, model, temperature)
+        })
+        .await
+    }
 
-        anyhow::bail!("All providers failed. Attempts:\n{}", failures.join("\n"))
+    /// Retries and fallbacks cover opening the stream; an error after the
+    /// first delta is passed through.
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        self.call("Provider stream", true, |provider| {
+            provider.chat_stream(messages, tools, model, temperature)
+        })
+        .await
     }
 }
 
@@ -348,26 +549,157 @@ mod tests {
 
     #[test]
     fn non_retryable_detects_common_patterns() {
+        let policy = RetryPolicy::default();
+        let is_non_retryable = |err: anyhow::Error| !policy.is_retryable(&err);
         // Non-retryable 4xx errors
-        assert!(is_non_retryable(&anyhow::anyhow!("400 Bad Request")));
-        assert!(is_non_retryable(&anyhow::anyhow!("401 Unauthorized")));
-        assert!(is_non_retryable(&anyhow::anyhow!("403 Forbidden")));
-        assert!(is_non_retryable(&anyhow::anyhow!("404 Not Found")));
-        assert!(is_non_retryable(&anyhow::anyhow!(
+        assert!(is_non_retryable(anyhow::anyhow!("400 Bad Request")));
+        assert!(is_non_retryable(anyhow::anyhow!("401 Unauthorized")));
+        assert!(is_non_retryable(anyhow::anyhow!("403 Forbidden")));
+        assert!(is_non_retryable(anyhow::anyhow!("404 Not Found")));
+        assert!(is_non_retryable(anyhow::anyhow!(
             "API error with 400 Bad Request"
         )));
         // Retryable: 429 Too Many Requests
-        assert!(!is_non_retryable(&anyhow::anyhow!("429 Too Many Requests")));
+        assert!(!is_non_retryable(anyhow::anyhow!("429 Too Many Requests")));
         // Retryable: 408 Request Timeout
-        assert!(!is_non_retryable(&anyhow::anyhow!("408 Request Timeout")));
+        assert!(!is_non_retryable(anyhow::anyhow!("408 Request Timeout")));
         // Retryable: 5xx server errors
-        assert!(!is_non_retryable(&anyhow::anyhow!(
+        assert!(!is_non_retryable(anyhow::anyhow!(
             "500 Internal Server Error"
         )));
-        assert!(!is_non_retryable(&anyhow::anyhow!("502 Bad Gateway")));
+        assert!(!is_non_retryable(anyhow::anyhow!("502 Bad Gateway")));
         // Retryable: transient errors
-        assert!(!is_non_retryable(&anyhow::anyhow!("timeout")));
-        assert!(!is_non_retryable(&anyhow::anyhow!("connection reset")));
+        assert!(!is_non_retryable(anyhow::anyhow!("timeout")));
+        assert!(!is_non_retryable(anyhow::anyhow!("connection reset")));
+    }
+
+    #[test]
+    fn retry_statuses_are_configurable() {
+        let policy = RetryPolicy {
+            retry_status_codes: vec![503],
+            ..RetryPolicy::default()
+        };
+        assert!(policy.is_retryable(&anyhow::anyhow!(
+            "OpenAI API error (503 Service Unavailable): busy"
+        )));
+        assert!(!policy.is_retryable(&anyhow::anyhow!("429 Too Many Requests")));
+        assert!(!policy.is_retryable(&anyhow::anyhow!("500 Internal Server Error")));
+    }
+
+    #[test]
+    fn backoff_grows_with_jitter_up_to_the_cap() {
+        let policy = RetryPolicy {
+            base_backoff_ms: 100,
+            max_backoff_ms: 1_000,
+            ..RetryPolicy::default()
+        };
+        for _ in 0..50 {
+            let first = policy.backoff(0).as_millis();
+            assert!((50..=100).contains(&first), "first={first}");
+            let third = policy.backoff(2).as_millis();
+            assert!((200..=400).contains(&third), "third={third}");
+            let late = policy.backoff(40).as_millis();
+            assert!((500..=1_000).contains(&late), "late={late}");
+        }
+    }
+
+    #[tokio::test]
+    async fn circuit_opens_after_consecutive_failures_and_skips_provider() {
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let fallback_calls = Arc::new(AtomicUsize::new(0));
+        let policy = RetryPolicy {
+            max_retries: 1,
+            base_backoff_ms: 1,
+            breaker_threshold: 3,
+            breaker_cooldown: Duration::from_secs(60),
+            ..RetryPolicy::default()
+        };
+        let breakers: Arc<Mutex<Breakers>> = Arc::default();
+        let build = || {
+            ReliableProvider::with_policy(
+                vec![
+                    (
+                        "flaky-primary".into(),
+                        Box::new(MockProvider {
+                            calls: Arc::clone(&primary_calls),
+                            fail_until_attempt: usize::MAX,
+                            response: "never",
+                            error: "503 Service Unavailable",
+                        }) as Box<dyn Provider>,
+                    ),
+                    (
+                        "fallback".into(),
+                        Box::new(MockProvider {
+                            calls: Arc::clone(&fallback_calls),
+                            fail_until_attempt: 0,
+                            response: "from fallback",
+                            error: "unused",
+                        }),
+                    ),
+                ],
+                policy.clone(),
+                Arc::clone(&breakers),
+            )
+        };
+
+        // Two failures, then the third opens the circuit mid-retries
+        assert_eq!(build().chat("a", "m", 0.0).await.unwrap(), "from fallback");
+        assert_eq!(build().chat("b", "m", 0.0).await.unwrap(), "from fallback");
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 3);
+
+        // Breakers are shared, so a new provider chain skips the primary
+        let err = ReliableProvider::with_policy(
+            vec![(
+                "flaky-primary".into(),
+                Box::new(MockProvider {
+                    calls: Arc::clone(&primary_calls),
+                    fail_until_attempt: 0,
+                    response: "ok",
+                    error: "unused",
+                }),
+            )],
+            policy.clone(),
+            Arc::clone(&breakers),
+        )
+        .chat("c", "m", 0.0)
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("circuit open"));
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            crate::health::snapshot()
+                .circuits
+                .get("flaky-primary")
+                .map(String::as_str),
+            Some("open")
+        );
+    }
+
+    #[tokio::test]
+    async fn open_circuit_lets_a_trial_call_through_after_cooldown() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::with_policy(
+            vec![(
+                "recovering".into(),
+                Box::new(MockProvider {
+                    calls: Arc::clone(&calls),
+                    fail_until_attempt: 1,
+                    response: "back",
+                    error: "timeout",
+                }),
+            )],
+            RetryPolicy {
+                max_retries: 0,
+                breaker_threshold: 1,
+                breaker_cooldown: Duration::ZERO,
+                ..RetryPolicy::default()
+            },
+            Arc::default(),
+        );
+
+        assert!(provider.chat("a", "m", 0.0).await.is_err());
+        assert_eq!(provider.chat("b", "m", 0.0).await.unwrap(), "back");
+        assert!(provider.breakers().circuits.is_empty());
     }
 
     #[tokio::test]
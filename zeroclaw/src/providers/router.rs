@@ -1,13 +1,110 @@
-use super::traits::ChatMessage;
+use super::traits::{ChatMessage, ChatStream, Embeddings};
 use super::Provider;
+use crate::config::{RouteMatch, TaskKind};
+use crate::observability::{Observer, ObserverEvent};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
 
 /// A single route: maps a task hint to a provider + model combo.
 #[derive(Debug, Clone)]
 pub struct Route {
     pub provider_name: String,
     pub model: String,
+    /// Heuristics for picking this route automatically
+    pub matches: Option<RouteMatch>,
+}
+
+/// Model parameter that always asks for automatic routing.
+pub const AUTO_HINT: &str = "hint:auto";
+
+/// Prefix the agent loop puts on the message carrying tool results.
+const TOOL_RESULTS_PREFIX: &str = "[Tool results]";
+
+/// What the router looks at when picking a route automatically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskProfile {
+    /// Characters in the user's latest message (tool results excluded)
+    pub chars: usize,
+    pub code: bool,
+    pub task: TaskKind,
+}
+
+impl TaskProfile {
+    pub fn of(messages: &[ChatMessage]) -> Self {
+        let mut user_messages = messages.iter().rev().filter(|m| m.role == "user");
+        let latest = user_messages.next().map_or("", |m| m.content.as_str());
+        let task = if latest.starts_with(TOOL_RESULTS_PREFIX) {
+            TaskKind::Tools
+        } else {
+            TaskKind::Chat
+        };
+        let request = if task == TaskKind::Tools {
+            user_messages
+                .find(|m| !m.content.starts_with(TOOL_RESULTS_PREFIX))
+                .map_or("", |m| m.content.as_str())
+        } else {
+            latest
+        };
+        Self {
+            chars: request.chars().count(),
+            code: looks_like_code(request),
+            task,
+        }
+    }
+
+    pub fn matches(&self, conditions: &RouteMatch) -> bool {
+        conditions.min_chars.is_none_or(|min| self.chars >= min)
+            && conditions.max_chars.is_none_or(|max| self.chars <= max)
+            && conditions.code.is_none_or(|code| self.code == code)
+            && conditions.task.is_none_or(|task| self.task == task)
+    }
+
+    fn describe(&self) -> String {
+        let mut out = format!("{} chars", self.chars);
+        if self.code {
+            out.push_str(", code");
+        }
+        let task = match self.task {
+            TaskKind::Chat => "chat",
+            TaskKind::Tools => "tools",
+        };
+        let _ = write!(out, ", {task}");
+        out
+    }
+}
+
+/// Fenced blocks, or at least two lines that read like source code.
+fn looks_like_code(text: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "fn ",
+        "def ",
+        "class ",
+        "import ",
+        "#include",
+        "function ",
+        "const ",
+        "let ",
+        "pub ",
+        "return ",
+        "SELECT ",
+    ];
+    if text.contains("```") {
+        return true;
+    }
+    text.lines()
+        .map(str::trim)
+        .filter(|line| {
+            KEYWORDS.iter().any(|k| line.starts_with(k))
+                || line.ends_with(';')
+                || line.ends_with('{')
+                || *line == "}"
+        })
+        .take(2)
+        .count()
+        == 2
 }
 
 /// Multi-model router — routes requests to different provider+model combos
@@ -18,11 +115,17 @@ pub struct Route {
 /// - A hint-prefixed string (e.g. "hint:reasoning") → resolves via route table
 ///
 /// This wraps multiple pre-created providers and selects the right one per request.
+///
+/// Requests on the default model (or [`AUTO_HINT`]) go to the first route
+/// whose `match` conditions fit the request's [`TaskProfile`].
 pub struct RouterProvider {
     routes: HashMap<String, (usize, String)>, // hint → (provider_index, model)
+    /// Routes with `match` heuristics, in config order
+    auto_routes: Vec<(String, usize, String, RouteMatch)>, // (hint, provider_index, model, conditions)
     providers: Vec<(String, Box<dyn Provider>)>,
     default_index: usize,
     default_model: String,
+    observer: Option<Arc<dyn Observer>>,
 }
 
 impl RouterProvider {
@@ -43,29 +146,69 @@ impl RouterProvider {
             .collect();
 
         // Resolve routes to provider indices
-        let resolved_routes: HashMap<String, (usize, String)> = routes
-            .into_iter()
-            .filter_map(|(hint, route)| {
-                let index = name_to_index.get(route.provider_name.as_str()).copied();
-                match index {
-                    Some(i) => Some((hint, (i, route.model))),
-                    None => {
-                        tracing::warn!(
-                            hint = hint,
-                            provider = route.provider_name,
-                            "Route references unknown provider, skipping"
-                        );
-                        None
-                    }
-                }
-            })
-            .collect();
+        let mut resolved_routes: HashMap<String, (usize, String)> = HashMap::new();
+        let mut auto_routes = Vec::new();
+        for (hint, route) in routes {
+            let Some(i) = name_to_index.get(route.provider_name.as_str()).copied() else {
+                tracing::warn!(
+                    hint = hint,
+                    provider = route.provider_name,
+                    "Route references unknown provider, skipping"
+                );
+                continue;
+            };
+            if let Some(conditions) = route.matches {
+                auto_routes.push((hint.clone(), i, route.model.clone(), conditions));
+            }
+            resolved_routes.insert(hint, (i, route.model));
+        }
 
         Self {
             routes: resolved_routes,
+            auto_routes,
             providers,
             default_index: 0,
             default_model,
+            observer: None,
+        }
+    }
+
+    /// Report automatic routing decisions to `observer`.
+    #[must_use]
+    pub fn with_observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Like [`resolve`](Self::resolve), but requests on the default model or
+    /// [`AUTO_HINT`] are classified and may be routed automatically.
+    fn route(&self, model: &str, messages: &[ChatMessage]) -> (usize, String) {
+        let auto = model == AUTO_HINT;
+        if !auto && model != self.default_model {
+            return self.resolve(model);
+        }
+        if !self.auto_routes.is_empty() {
+            let profile = TaskProfile::of(messages);
+            if let Some((hint, idx, resolved_model, _)) = self
+                .auto_routes
+                .iter()
+                .find(|(_, _, _, conditions)| profile.matches(conditions))
+            {
+                if let Some(observer) = &self.observer {
+                    observer.record_event(&ObserverEvent::ModelRouted {
+                        hint: hint.clone(),
+                        provider: self.providers[*idx].0.clone(),
+                        model: resolved_model.clone(),
+                        reason: profile.describe(),
+                    });
+                }
+                return (*idx, resolved_model.clone());
+            }
+        }
+        if auto {
+            (self.default_index, self.default_model.clone())
+        } else {
+            self.resolve(model)
         }
     }
 
@@ -99,7 +242,7 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let (provider_idx, resolved_model) = self.route(model, &[ChatMessage::user(message)]);
 
         let (provider_name, provider) = &self.providers[provider_idx];
         tracing::info!(
@@ -119,13 +262,42 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let (provider_idx, resolved_model) = self.route(model, messages);
         let (_, provider) = &self.providers[provider_idx];
         provider
             .chat_with_history(messages, &resolved_model, temperature)
             .await
     }
 
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let (provider_idx, resolved_model) = self.route(model, messages);
+        let (_, provider) = &self.providers[provider_idx];
+        provider
+            .chat_stream(messages, tools, &resolved_model, temperature)
+            .await
+    }
+
+    async fn embed(&self, texts: &[&str], model: &str) -> anyhow::Result<Embeddings> {
+        let (provider_idx, resolved_model) = self.resolve(model);
+        self.providers[provider_idx]
+            .1
+            .embed(texts, &resolved_model)
+            .await
+    }
+
+    fn supports_vision(&self, model: &str) -> bool {
+        let (provider_idx, resolved_model) = self.resolve(model);
+        self.providers[provider_idx]
+            .1
+            .supports_vision(&resolved_model)
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         for (name, provider) in &self.providers {
             tracing::info!(provider = name, "Warming up routed provider");
@@ -210,6 +382,7 @@ mod tests {
                     Route {
                         provider_name: provider_name.to_string(),
                         model: model.to_string(),
+                        matches: None,
                     },
                 )
             })
@@ -331,6 +504,135 @@ mod tests {
         assert!(!router.routes.contains_key("broken"));
     }
 
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl Observer for RecordingObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            if let ObserverEvent::ModelRouted {
+                hint,
+                model,
+                reason,
+                ..
+            } = event
+            {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push(format!("{hint} {model} ({reason})"));
+            }
+        }
+
+        fn record_metric(&self, _metric: &crate::observability::traits::ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+    }
+
+    fn make_auto_router() -> (
+        RouterProvider,
+        Vec<Arc<MockProvider>>,
+        Arc<RecordingObserver>,
+    ) {
+        let mocks = vec![
+            Arc::new(MockProvider::new("frontier")),
+            Arc::new(MockProvider::new("small")),
+        ];
+        let providers = vec![
+            (
+                "frontier".to_string(),
+                Box::new(Arc::clone(&mocks[0])) as Box<dyn Provider>,
+            ),
+            (
+                "small".to_string(),
+                Box::new(Arc::clone(&mocks[1])) as Box<dyn Provider>,
+            ),
+        ];
+        let routes = vec![(
+            "cheap".to_string(),
+            Route {
+                provider_name: "small".into(),
+                model: "tiny-model".into(),
+                matches: Some(RouteMatch {
+                    max_chars: Some(100),
+                    code: Some(false),
+                    task: Some(TaskKind::Chat),
+                    ..RouteMatch::default()
+                }),
+            },
+        )];
+        let observer = Arc::new(RecordingObserver::default());
+        let router = RouterProvider::new(providers, routes, "default-model".into())
+            .with_observer(observer.clone());
+        (router, mocks, observer)
+    }
+
+    #[tokio::test]
+    async fn auto_routes_short_chat_to_the_cheap_model() {
+        let (router, mocks, observer) = make_auto_router();
+
+        let result = router.chat("hi there", "default-model", 0.5).await.unwrap();
+        assert_eq!(result, "small");
+        assert_eq!(mocks[1].last_model(), "tiny-model");
+        assert_eq!(
+            observer.events.lock().unwrap().as_slice(),
+            ["cheap tiny-model (8 chars, chat)"]
+        );
+
+        let code = "Why does this fail?\nfn main() {\n    let x = 1;\n}";
+        let result = router.chat(code, "default-model", 0.5).await.unwrap();
+        assert_eq!(result, "frontier");
+        assert_eq!(mocks[0].last_model(), "default-model");
+
+        let long = "word ".repeat(50);
+        assert_eq!(
+            router.chat(&long, "hint:auto", 0.5).await.unwrap(),
+            "frontier"
+        );
+        assert_eq!(mocks[0].last_model(), "default-model");
+    }
+
+    #[tokio::test]
+    async fn auto_routing_skips_explicit_models_and_tool_steps() {
+        let (router, mocks, observer) = make_auto_router();
+
+        router.chat("hi", "some/other-model", 0.5).await.unwrap();
+        assert_eq!(mocks[0].last_model(), "some/other-model");
+
+        let history = vec![
+            ChatMessage::user("list files"),
+            ChatMessage::assistant("<tool_call>...</tool_call>"),
+            ChatMessage::user(
+                "[Tool results]\n<tool_response name=\"shell\">a.txt</tool_response>",
+            ),
+        ];
+        assert_eq!(
+            TaskProfile::of(&history),
+            TaskProfile {
+                chars: 10,
+                code: false,
+                task: TaskKind::Tools,
+            }
+        );
+        router
+            .chat_with_history(&history, "default-model", 0.5)
+            .await
+            .unwrap();
+        assert_eq!(mocks[1].call_count(), 0);
+        assert!(observer.events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn code_detection_needs_fences_or_code_lines() {
+        assert!(looks_like_code("```\nls\n```"));
+        assert!(looks_like_code("def f(x):\n    return x"));
+        assert!(!looks_like_code("What should I cook tonight?"));
+        assert!(!looks_like_code("Remind me: call mom; buy milk"));
+    }
+
     #[tokio::test]
     async fn warmup_calls_all_providers() {
         let (router, _) = make_router(vec![("a", "ok"), ("b", "ok")], vec![]);
@@ -0,0 +1,241 @@
+//! Shared plumbing for [`Provider::chat_stream`](super::Provider::chat_stream):
+//! decoding `OpenAI`-style server-sent events (`stream: true` on
+//! `/chat/completions`) into text deltas.
+
+use super::traits::ChatStream;
+use futures_util::stream::{self, Stream, StreamExt};
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+use std::collections::VecDeque;
+
+#[derive(Debug, Deserialize)]
+struct Chunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: Delta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Delta {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCallDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCallDelta {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    function: Option<FunctionDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionDelta {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<String>,
+}
+
+/// A native tool call whose name and arguments are still arriving.
+#[derive(Debug, Default)]
+struct PendingCall {
+    index: usize,
+    name: String,
+    arguments: String,
+}
+
+impl PendingCall {
+    /// The call in the `<tool_call>` form the agent loop parses.
+    fn into_block(self) -> String {
+        let arguments = serde_json::from_str::<serde_json::Value>(&self.arguments)
+            .unwrap_or_else(|_| serde_json::json!({}));
+        format!(
+            "<tool_call>\n{}\n</tool_call>",
+            serde_json::json!({"name": self.name, "arguments": arguments})
+        )
+    }
+}
+
+/// Incremental SSE decoder. Bytes go in as they arrive; text deltas come
+/// out. Native tool-call fragments are buffered and emitted as one
+/// `<tool_call>` block once the next call starts or the stream ends.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buf: Vec<u8>,
+    call: Option<PendingCall>,
+    done: bool,
+}
+
+impl SseDecoder {
+    /// Feed received bytes; returns the deltas completed by them.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buf.extend_from_slice(bytes);
+        let mut deltas = Vec::new();
+        while let Some(newline) = self.buf.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            self.line(line.trim(), &mut deltas);
+        }
+        deltas
+    }
+
+    /// End of input: flush any trailing line and pending tool call.
+    pub fn finish(&mut self) -> Vec<String> {
+        let mut deltas = Vec::new();
+        let rest = std::mem::take(&mut self.buf);
+        self.line(String::from_utf8_lossy(&rest).trim(), &mut deltas);
+        deltas.extend(self.call.take().map(PendingCall::into_block));
+        self.done = true;
+        deltas
+    }
+
+    /// Whether `[DONE]` or the end of input was seen.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    fn line(&mut self, line: &str, deltas: &mut Vec<String>) {
+        if self.done {
+            return;
+        }
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return;
+        };
+        if data == "[DONE]" {
+            deltas.extend(self.call.take().map(PendingCall::into_block));
+            self.done = true;
+            return;
+        }
+        let Ok(chunk) = serde_json::from_str::<Chunk>(data) else {
+            tracing::debug!("Skipping unparsable stream event");
+            return;
+        };
+        let Some(choice) = chunk.choices.into_iter().next() else {
+            return;
+        };
+        if let Some(content) = choice.delta.content.filter(|c| !c.is_empty()) {
+            deltas.push(content);
+        }
+        for fragment in choice.delta.tool_calls {
+            if self
+                .call
+                .as_ref()
+                .is_some_and(|c| c.index != fragment.index)
+            {
+                deltas.extend(self.call.take().map(PendingCall::into_block));
+            }
+            let call = self.call.get_or_insert_with(|| PendingCall {
+                index: fragment.index,
+                ..PendingCall::default()
+            });
+            if let Some(function) = fragment.function {
+                call.name
+                    .push_str(function.name.as_deref().unwrap_or_default());
+                call.arguments
+                    .push_str(function.arguments.as_deref().unwrap_or_default());
+            }
+        }
+    }
+}
+
+/// Decode an SSE byte stream into a [`ChatStream`].
+pub fn sse_deltas<S, B>(bytes: S) -> ChatStream
+where
+    S: Stream<Item = reqwest::Result<B>> + Send + 'static,
+    B: AsRef<[u8]>,
+{
+    let state = (Box::pin(bytes), SseDecoder::default(), VecDeque::new());
+    stream::unfold(state, |(mut bytes, mut decoder, mut pending)| async move {
+        loop {
+            if let Some(delta) = pending.pop_front() {
+                return Some((Ok(delta), (bytes, decoder, pending)));
+            }
+            if decoder.is_done() {
+                return None;
+            }
+            match bytes.next().await {
+                Some(Ok(chunk)) => pending.extend(decoder.feed(chunk.as_ref())),
+                Some(Err(e)) => {
+                    decoder.done = true;
+                    return Some((Err(e.into()), (bytes, decoder, pending)));
+                }
+                None => pending.extend(decoder.finish()),
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Send a streaming `/chat/completions` request; `request` is the
+/// authenticated POST with `"stream": true` in its body.
+pub async fn openai_compatible(
+    provider: &str,
+    request: RequestBuilder,
+) -> anyhow::Result<ChatStream> {
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        return Err(super::api_error(provider, response).await);
+    }
+    Ok(sse_deltas(response.bytes_stream()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_deltas_survive_split_lines() {
+        let mut decoder = SseDecoder::default();
+        let mut deltas = decoder
+            .feed(b"data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\ndata: {\"cho");
+        deltas
+            .extend(decoder.feed(b"ices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n: keep-alive\n"));
+        deltas.extend(decoder.feed(b"data: [DONE]\n\n"));
+        assert_eq!(deltas, vec!["Hel", "lo"]);
+        assert!(decoder.is_done());
+    }
+
+    #[test]
+    fn native_tool_calls_become_blocks_as_each_completes() {
+        let mut decoder = SseDecoder::default();
+        let events = [
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"name":"shell","arguments":""}}]}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"command\":"}}]}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"ls\"}"}}]}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":1,"function":{"name":"file_read","arguments":"{}"}}]}}]}"#,
+        ];
+        let mut deltas = Vec::new();
+        for event in events {
+            deltas.extend(decoder.feed(format!("data: {event}\n\n").as_bytes()));
+        }
+        assert_eq!(deltas.len(), 1);
+        assert!(deltas[0].contains(r#""name":"shell""#));
+        assert!(deltas[0].contains(r#""command":"ls""#));
+
+        deltas = decoder.finish();
+        assert_eq!(deltas.len(), 1);
+        assert!(deltas[0].starts_with("<tool_call>"));
+        assert!(deltas[0].contains("file_read"));
+    }
+
+    #[tokio::test]
+    async fn byte_stream_is_decoded_in_order() {
+        let chunks: Vec<reqwest::Result<Vec<u8>>> = vec![
+            Ok(b"data: {\"choices\":[{\"delta\":{\"content\":\"a\"}}]}\n".to_vec()),
+            Ok(b"data: {\"choices\":[{\"delta\":{\"content\":\"b\"}}]}".to_vec()),
+        ];
+        let deltas: Vec<String> = sse_deltas(stream::iter(chunks))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(deltas, vec!["a", "b"]);
+    }
+}
@@ -1,4 +1,6 @@
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::stream::{self, BoxStream, StreamExt};
 use serde::{Deserialize, Serialize};
 
 /// A single message in a conversation.
@@ -6,6 +8,9 @@ use serde::{Deserialize, Serialize};
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    /// Images attached to this message (sent only to vision-capable providers).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImagePart>,
 }
 
 impl ChatMessage {
@@ -13,6 +18,7 @@ impl ChatMessage {
         Self {
             role: "system".into(),
             content: content.into(),
+            images: Vec::new(),
         }
     }
 
@@ -20,6 +26,7 @@ impl ChatMessage {
         Self {
             role: "user".into(),
             content: content.into(),
+            images: Vec::new(),
         }
     }
 
@@ -27,10 +34,74 @@ impl ChatMessage {
         Self {
             role: "assistant".into(),
             content: content.into(),
+            images: Vec::new(),
+        }
+    }
+
+    /// User message carrying text plus image parts.
+    pub fn user_with_images(content: impl Into<String>, images: Vec<ImagePart>) -> Self {
+        Self {
+            role: "user".into(),
+            content: content.into(),
+            images,
         }
     }
 }
 
+/// Image content attached to a message: MIME type plus base64-encoded bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImagePart {
+    pub mime_type: String,
+    /// Base64 payload without the `data:` URI prefix.
+    pub data: String,
+}
+
+impl ImagePart {
+    pub fn new(mime_type: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            mime_type: mime_type.into(),
+            data: data.into(),
+        }
+    }
+
+    /// Parse a `data:image/<type>;base64,<payload>` URI.
+    pub fn from_data_uri(uri: &str) -> Option<Self> {
+        let rest = uri.trim().strip_prefix("data:")?;
+        let (mime_type, data) = rest.split_once(";base64,")?;
+        if !mime_type.starts_with("image/") || data.is_empty() {
+            return None;
+        }
+        Some(Self::new(mime_type, data))
+    }
+
+    /// Read and base64-encode an image file; the MIME type comes from the extension.
+    pub fn from_path(path: &std::path::Path) -> anyhow::Result<Self> {
+        use base64::Engine;
+        let mime_type = match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("png") => "image/png",
+            Some("jpg" | "jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            Some("bmp") => "image/bmp",
+            _ => anyhow::bail!("Unsupported image type: {}", path.display()),
+        };
+        let bytes = std::fs::read(path)?;
+        Ok(Self::new(
+            mime_type,
+            base64::engine::general_purpose::STANDARD.encode(bytes),
+        ))
+    }
+
+    pub fn to_data_uri(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.data)
+    }
+}
+
 /// A tool call requested by the LLM.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCall {
@@ -82,6 +153,19 @@ pub enum ConversationMessage {
     ToolResult(ToolResultMessage),
 }
 
+/// Result of [`Provider::embed`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Embeddings {
+    /// One vector per input text, in input order
+    pub vectors: Vec<Vec<f32>>,
+    /// Input tokens billed across all batches, as reported by the provider
+    /// (estimated from the text when it reports none)
+    pub tokens: u64,
+}
+
+/// Text deltas of a streamed reply, in order; see [`Provider::chat_stream`].
+pub type ChatStream = BoxStream<'static, anyhow::Result<String>>;
+
 #[async_trait]
 pub trait Provider: Send + Sync {
     async fn chat(&self, message: &str, model: &str, temperature: f64) -> anyhow::Result<String> {
@@ -118,6 +202,35 @@ pub trait Provider: Send + Sync {
             .await
     }
 
+    /// Like `chat_with_history`, but yields the reply as it is generated.
+    /// `tools` are the tools the model may call: providers with native
+    /// function calling declare them to the API, the rest rely on the tool
+    /// instructions in the system prompt. Native tool calls arrive as
+    /// complete `<tool_call>` blocks. The default yields the whole
+    /// `chat_with_history` reply as a single delta.
+    async fn chat_stream(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let response = self.chat_with_history(messages, model, temperature).await?;
+        Ok(stream::once(async { Ok(response) }).boxed())
+    }
+
+    /// Embed `texts` with the embedding `model`, batching large inputs.
+    /// Providers without an embeddings API keep the default, which fails.
+    async fn embed(&self, _texts: &[&str], _model: &str) -> anyhow::Result<Embeddings> {
+        anyhow::bail!("This provider does not support embeddings")
+    }
+
+    /// Whether `model` accepts image parts in `chat_with_history`.
+    /// Providers that drop or reject images keep the default `false`.
+    fn supports_vision(&self, _model: &str) -> bool {
+        false
+    }
+
     /// Warm up the HTTP connection pool (TLS handshake, DNS, HTTP/2 setup).
     /// Default implementation is a no-op; providers with HTTP clients should override.
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -142,6 +255,43 @@ mod tests {
         assert_eq!(asst.role, "assistant");
     }
 
+    #[test]
+    fn chat_message_images_roundtrip_and_skip_when_empty() {
+        let plain = serde_json::to_string(&ChatMessage::user("hi")).unwrap();
+        assert!(!plain.contains("images"));
+
+        let msg = ChatMessage::user_with_images("look", vec![ImagePart::new("image/png", "AAAA")]);
+        let json = serde_json::to_string(&msg).unwrap();
+        let back: ChatMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.images, msg.images);
+    }
+
+    #[test]
+    fn image_part_data_uri_parsing() {
+        let part = ImagePart::from_data_uri("data:image/jpeg;base64,/9j/4AAQ").unwrap();
+        assert_eq!(part.mime_type, "image/jpeg");
+        assert_eq!(part.data, "/9j/4AAQ");
+        assert_eq!(part.to_data_uri(), "data:image/jpeg;base64,/9j/4AAQ");
+
+        assert!(ImagePart::from_data_uri("data:text/plain;base64,aGk=").is_none());
+        assert!(ImagePart::from_data_uri("data:image/png;base64,").is_none());
+        assert!(ImagePart::from_data_uri("not a uri").is_none());
+    }
+
+    #[test]
+    fn image_part_from_path_detects_mime() {
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("shot.PNG");
+        std::fs::write(&png, [0x89, b'P', b'N', b'G']).unwrap();
+        let part = ImagePart::from_path(&png).unwrap();
+        assert_eq!(part.mime_type, "image/png");
+        assert_eq!(part.data, "iVBORw==");
+
+        let txt = dir.path().join("notes.txt");
+        std::fs::write(&txt, "hi").unwrap();
+        assert!(ImagePart::from_path(&txt).is_err());
+    }
+
     #[test]
     fn chat_response_helpers() {
         let empty = ChatResponse {
@@ -0,0 +1,195 @@
+//! Vision fallback — lets text-only models work with images by asking a
+//! vision-capable model to describe them first.
+
+use super::traits::{ChatMessage, ImagePart, Provider};
+use crate::config::Config;
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+
+const DESCRIBE_SYSTEM_PROMPT: &str = "You describe images for an assistant that cannot see them. \
+Describe each image factually and concisely: layout, visible text (transcribed verbatim), UI \
+elements, charts, errors, and anything relevant to the context provided. Number the \
+descriptions when there is more than one image. Do not speculate beyond what is visible.";
+
+/// Text sent alongside the images so the description focuses on what matters.
+const CONTEXT_MAX_CHARS: usize = 2_000;
+
+/// A vision-capable provider/model pair used to describe images for text-only models.
+pub struct VisionFallback {
+    provider: Box<dyn Provider>,
+    model: String,
+}
+
+impl VisionFallback {
+    pub fn new(provider: Box<dyn Provider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+        }
+    }
+
+    /// Build from `[vision]`; `None` when no fallback model is configured or
+    /// the provider cannot be created.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let model = config.vision.fallback_model.as_deref()?.trim();
+        if model.is_empty() {
+            return None;
+        }
+        let provider_name = config
+            .vision
+            .fallback_provider
+            .as_deref()
+            .or(config.default_provider.as_deref())
+            .unwrap_or("openrouter");
+        let api_key = config
+            .vision
+            .api_key
+            .as_deref()
+            .or(config.api_key.as_deref());
+
+        let settings = super::ProviderSettings::from_config(config);
+        match super::create_resilient_provider(
+            provider_name,
+            api_key,
+            &config.reliability,
+            &settings,
+        ) {
+            Ok(provider) => Some(Self::new(provider, model)),
+            Err(e) => {
+                tracing::warn!("Vision fallback provider '{provider_name}' unavailable: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Describe `images`, using `context` (the text they arrived with) to
+    /// decide what is relevant.
+    pub async fn describe(&self, images: &[ImagePart], context: &str) -> Result<String> {
+        let prompt = format!(
+            "Context the images arrived with:\n{}\n\nDescribe the {} attached image(s).",
+            truncate_with_ellipsis(context.trim(), CONTEXT_MAX_CHARS),
+            images.len()
+        );
+        let messages = [
+            ChatMessage::system(DESCRIBE_SYSTEM_PROMPT),
+            ChatMessage::user_with_images(prompt, images.to_vec()),
+        ];
+        let description = self
+            .provider
+            .chat_with_history(&messages, &self.model, 0.0)
+            .await?;
+        Ok(description.trim().to_string())
+    }
+
+    /// Replace image parts in `history` with text descriptions so a model
+    /// without vision still learns what the images show. Returns the number
+    /// of messages rewritten.
+    pub async fn describe_history_images(&self, history: &mut [ChatMessage]) -> usize {
+        let mut rewritten = 0;
+        for message in history.iter_mut().filter(|m| !m.images.is_empty()) {
+            let images = std::mem::take(&mut message.images);
+            let note = match self.describe(&images, &message.content).await {
+                Ok(description) => format!(
+                    "[Image description from {} ({} image(s))]\n{description}",
+                    self.model,
+                    images.len()
+                ),
+                Err(e) => {
+                    tracing::warn!("Vision fallback failed: {e}");
+                    format!(
+                        "[{} image(s) could not be described: {}]",
+                        images.len(),
+                        super::sanitize_api_error(&e.to_string())
+                    )
+                }
+            };
+            message.content.push_str("\n\n");
+            message.content.push_str(&note);
+            rewritten += 1;
+        }
+        rewritten
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+    use std::sync::Arc;
+
+    fn fallback(fail: bool) -> (VisionFallback, Arc<ScriptedProvider>) {
+        let provider = if fail {
+            ScriptedProvider::failing("upstream unavailable")
+        } else {
+            ScriptedProvider::always("A login form with a red error banner.\n")
+        };
+        let provider = Arc::new(provider.with_vision());
+        (
+            VisionFallback::new(Box::new(provider.clone()), "vision-model"),
+            provider,
+        )
+    }
+
+    /// Images sent with each request to the vision model
+    fn images_seen(provider: &ScriptedProvider) -> Vec<usize> {
+        provider
+            .requests()
+            .iter()
+            .map(|r| r.messages.iter().map(|m| m.images.len()).sum())
+            .collect()
+    }
+
+    fn image() -> ImagePart {
+        ImagePart::new("image/png", "iVBORw0KGgo=")
+    }
+
+    #[test]
+    fn from_config_requires_fallback_model() {
+        let config = Config::default();
+        assert!(VisionFallback::from_config(&config).is_none());
+
+        let mut config = Config::default();
+        config.vision.fallback_model = Some("  ".into());
+        assert!(VisionFallback::from_config(&config).is_none());
+    }
+
+    #[tokio::test]
+    async fn describe_history_images_replaces_images_with_text() {
+        let (fallback, provider) = fallback(false);
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("plain"),
+            ChatMessage::user_with_images("[Tool results]\nscreenshot", vec![image(), image()]),
+        ];
+
+        assert_eq!(fallback.describe_history_images(&mut history).await, 1);
+        assert_eq!(images_seen(&provider), vec![2]);
+        assert!(history.iter().all(|m| m.images.is_empty()));
+        assert_eq!(history[1].content, "plain");
+        assert!(history[2].content.starts_with("[Tool results]\nscreenshot"));
+        assert!(history[2]
+            .content
+            .contains("[Image description from vision-model (2 image(s))]"));
+        assert!(history[2].content.ends_with("red error banner."));
+
+        // Already-described messages are not sent again
+        assert_eq!(fallback.describe_history_images(&mut history).await, 0);
+        assert_eq!(provider.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn describe_failure_is_reported_not_dropped() {
+        let (fallback, _) = fallback(true);
+        let mut history = vec![ChatMessage::user_with_images("look", vec![image()])];
+
+        fallback.describe_history_images(&mut history).await;
+        assert!(history[0].images.is_empty());
+        assert!(history[0]
+            .content
+            .contains("1 image(s) could not be described"));
+    }
+}
@@ -0,0 +1,248 @@
+//! Display of agent replies, which the model writes as markdown.
+//!
+//! In a terminal, prose is styled with termimad and fenced code blocks are
+//! highlighted with syntect. For Telegram, replies are converted to the HTML
+//! subset its `parse_mode = "HTML"` accepts. Both are switched off with
+//! `[render] markdown = false`, which leaves replies as plain text.
+//!
+//! Rendering needs the `markdown` Cargo feature (on by default). Without it
+//! terminal replies are printed as written and Telegram gets them escaped.
+
+use crate::config::RenderConfig;
+#[cfg(feature = "markdown")]
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+#[cfg(feature = "markdown")]
+use std::fmt::Write;
+#[cfg(feature = "markdown")]
+use std::io::IsTerminal;
+#[cfg(feature = "markdown")]
+use std::sync::OnceLock;
+#[cfg(feature = "markdown")]
+use syntect::easy::HighlightLines;
+#[cfg(feature = "markdown")]
+use syntect::highlighting::{Theme, ThemeSet};
+#[cfg(feature = "markdown")]
+use syntect::parsing::SyntaxSet;
+#[cfg(feature = "markdown")]
+use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+
+#[cfg(not(feature = "markdown"))]
+pub fn for_terminal(reply: &str, _config: &RenderConfig) -> String {
+    reply.to_string()
+}
+
+#[cfg(not(feature = "markdown"))]
+pub fn telegram_html(markdown: &str) -> String {
+    escape(markdown)
+}
+
+/// `reply` as it should be printed to stdout: rendered when stdout is a
+/// terminal, unchanged when it is piped or rendering is off.
+#[cfg(feature = "markdown")]
+pub fn for_terminal(reply: &str, config: &RenderConfig) -> String {
+    if !config.markdown || !std::io::stdout().is_terminal() {
+        return reply.to_string();
+    }
+    let width = usize::from(termimad::terminal_size().0).max(20);
+    terminal(reply, &config.theme, width)
+}
+
+/// Render `markdown` with ANSI styles for a terminal `width` columns wide.
+#[cfg(feature = "markdown")]
+pub fn terminal(markdown: &str, theme: &str, width: usize) -> String {
+    let skin = termimad::MadSkin::default();
+    let mut out = String::new();
+    let mut prose = String::new();
+    let mut code: Option<(String, String)> = None;
+
+    for line in LinesWithEndings::from(markdown) {
+        let fence = line.trim_start().strip_prefix("```");
+        match (&mut code, fence) {
+            (None, Some(lang)) => {
+                out.push_str(&skin.text(&prose, Some(width)).to_string());
+                prose.clear();
+                code = Some((lang.trim().to_string(), String::new()));
+            }
+            (Some((lang, body)), Some(_)) => {
+                out.push_str(&highlight(body, lang, theme));
+                code = None;
+            }
+            (Some((_, body)), None) => body.push_str(line),
+            (None, None) => prose.push_str(line),
+        }
+    }
+    // An unclosed fence still shows its code
+    if let Some((lang, body)) = code {
+        out.push_str(&highlight(&body, &lang, theme));
+    }
+    out.push_str(&skin.text(&prose, Some(width)).to_string());
+    out.trim_end().to_string()
+}
+
+#[cfg(feature = "markdown")]
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+#[cfg(feature = "markdown")]
+fn theme(name: &str) -> &'static Theme {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    let themes = THEMES.get_or_init(ThemeSet::load_defaults);
+    themes
+        .themes
+        .get(name)
+        .or_else(|| themes.themes.get(RenderConfig::default().theme.as_str()))
+        .or_else(|| themes.themes.values().next())
+        .expect("syntect ships default themes")
+}
+
+#[cfg(feature = "markdown")]
+fn highlight(code: &str, lang: &str, theme_name: &str) -> String {
+    let syntaxes = syntaxes();
+    let syntax = syntaxes
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, theme(theme_name));
+    let mut out = String::new();
+    for line in LinesWithEndings::from(code) {
+        match highlighter.highlight_line(line, syntaxes) {
+            Ok(ranges) => out.push_str(&as_24_bit_terminal_escaped(&ranges, false)),
+            Err(_) => out.push_str(line),
+        }
+    }
+    out.push_str("\x1b[0m\n");
+    out
+}
+
+/// Convert `markdown` to Telegram HTML: bold, italic, strikethrough, code,
+/// pre, links and blockquotes. Headings become bold lines and lists are
+/// written out with bullets or numbers, since Telegram has neither.
+#[cfg(feature = "markdown")]
+pub fn telegram_html(markdown: &str) -> String {
+    let mut html = String::new();
+    // Next number of each open list; `None` for bullet lists
+    let mut lists: Vec<Option<u64>> = Vec::new();
+
+    for event in Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH) {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Heading { .. } | Tag::Strong => html.push_str("<b>"),
+                Tag::Emphasis => html.push_str("<i>"),
+                Tag::Strikethrough => html.push_str("<s>"),
+                Tag::BlockQuote(_) => html.push_str("<blockquote>"),
+                Tag::CodeBlock(CodeBlockKind::Fenced(lang)) if !lang.is_empty() => {
+                    html.push_str("<pre><code class=\"language-");
+                    html.push_str(&escape(&lang));
+                    html.push_str("\">");
+                }
+                Tag::CodeBlock(_) => html.push_str("<pre><code>"),
+                Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. } => {
+                    html.push_str("<a href=\"");
+                    html.push_str(&escape(&dest_url));
+                    html.push_str("\">");
+                }
+                Tag::List(start) => {
+                    if !lists.is_empty() && !html.ends_with('\n') {
+                        html.push('\n');
+                    }
+                    lists.push(start);
+                }
+                Tag::Item => {
+                    html.push_str(&"  ".repeat(lists.len().saturating_sub(1)));
+                    match lists.last_mut() {
+                        Some(Some(n)) => {
+                            let _ = write!(html, "{n}. ");
+                            *n += 1;
+                        }
+                        _ => html.push_str("• "),
+                    }
+                }
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                TagEnd::Heading(_) => html.push_str("</b>\n\n"),
+                TagEnd::Strong => html.push_str("</b>"),
+                TagEnd::Emphasis => html.push_str("</i>"),
+                TagEnd::Strikethrough => html.push_str("</s>"),
+                TagEnd::BlockQuote(_) => {
+                    let trimmed = html.trim_end().len();
+                    html.truncate(trimmed);
+                    html.push_str("</blockquote>\n\n");
+                }
+                TagEnd::CodeBlock => {
+                    if html.ends_with('\n') {
+                        html.pop();
+                    }
+                    html.push_str("</code></pre>\n\n");
+                }
+                TagEnd::Link | TagEnd::Image => html.push_str("</a>"),
+                TagEnd::Paragraph if lists.is_empty() => html.push_str("\n\n"),
+                TagEnd::Paragraph | TagEnd::Item if !html.ends_with('\n') => html.push('\n'),
+                TagEnd::List(_) => {
+                    lists.pop();
+                    if lists.is_empty() {
+                        html.push('\n');
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                html.push_str(&escape(&text));
+            }
+            Event::Code(code) => {
+                html.push_str("<code>");
+                html.push_str(&escape(&code));
+                html.push_str("</code>");
+            }
+            Event::SoftBreak | Event::HardBreak => html.push('\n'),
+            Event::Rule => html.push_str("――――――――\n\n"),
+            _ => {}
+        }
+    }
+    html.trim_end().to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(all(test, feature = "markdown"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn telegram_html_covers_inline_blocks_and_lists() {
+        let html = telegram_html(
+            "# Result\n\nIt **worked**, _mostly_ ~~not~~. See [docs](https://x.dev/?a=1&b=2).\n\n\
+             1. `cargo build`\n2. ship\n\n- a < b\n  - nested\n\n```rust\nfn main() {}\n```\n",
+        );
+        assert_eq!(
+            html,
+            "<b>Result</b>\n\n\
+             It <b>worked</b>, <i>mostly</i> <s>not</s>. See \
+             <a href=\"https://x.dev/?a=1&amp;b=2\">docs</a>.\n\n\
+             1. <code>cargo build</code>\n2. ship\n\n\
+             • a &lt; b\n  • nested\n\n\
+             <pre><code class=\"language-rust\">fn main() {}</code></pre>"
+        );
+    }
+
+    #[test]
+    fn terminal_output_highlights_code_and_keeps_text() {
+        let out = terminal(
+            "Run this:\n\n```sh\necho hi\n```\nDone.",
+            "base16-ocean.dark",
+            60,
+        );
+        assert!(out.contains("Run this:"));
+        assert!(out.contains("Done."));
+        // Code is colored, not shown with its fences
+        assert!(out.contains("\x1b[38;2;"));
+        assert!(out.contains("echo"));
+        assert!(!out.contains("```"));
+    }
+}
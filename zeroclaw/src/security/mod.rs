@@ -4,6 +4,6 @@ pub mod secrets;
 
 #[allow(unused_imports)]
 pub use pairing::PairingGuard;
-pub use policy::{AutonomyLevel, CommandRiskLevel, SecurityPolicy};
+pub use policy::{ActionBlocked, AutonomyLevel, CommandRiskLevel, PathAccess, SecurityPolicy};
 #[allow(unused_imports)]
 pub use secrets::SecretStore;
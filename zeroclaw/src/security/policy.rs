@@ -3,8 +3,8 @@ use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::time::Instant;
 
-/// How much autonomy the agent has
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// How much autonomy the agent has, ordered from least to most
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum AutonomyLevel {
     /// Read-only: can observe but not act
@@ -16,6 +16,68 @@ pub enum AutonomyLevel {
     Full,
 }
 
+/// Error for a turn or action that a policy or hook refused to run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionBlocked(pub String);
+
+impl std::fmt::Display for ActionBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ActionBlocked {}
+
+/// What the agent may do with files under a path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PathAccess {
+    /// Read files only
+    #[default]
+    Read,
+    /// Read and modify files
+    Write,
+}
+
+/// A directory outside the workspace the agent may use, up to `access`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceRoot {
+    pub path: PathBuf,
+    pub access: PathAccess,
+}
+
+/// Key files the agent may not even read, wherever they are. Added to any
+/// configured `forbidden_paths` unless `[autonomy] allow_key_files` is set.
+pub const DEFAULT_FORBIDDEN_GLOBS: &[&str] =
+    &["**/id_rsa*", "**/id_ed25519*", "**/*.pem", "**/*.p12"];
+
+/// Secrets and config files the agent may read but not modify, even in the
+/// workspace.
+pub const DEFAULT_WRITE_PROTECTED: &[&str] = &[
+    "**/.env",
+    "**/.env.*",
+    "**/*.key",
+    "**/.netrc",
+    "**/.npmrc",
+    "**/.zeroclaw.toml",
+];
+
+/// Whether a `forbidden_paths` / `write_protected_paths` entry is a glob
+/// (`*`, `?`, `**`) rather than a path prefix.
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Glob match of `path` against `pattern`, `~/` expanded; see
+/// [`crate::util::glob::matches`].
+fn glob_matches_path(pattern: &str, path: &Path) -> bool {
+    let pattern = expand_tilde(pattern);
+    crate::util::glob::matches(
+        &pattern.to_string_lossy(),
+        &path.to_string_lossy().replace('\\', "/"),
+    )
+}
+
 /// Risk score for shell command execution.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CommandRiskLevel {
@@ -80,16 +142,27 @@ impl Clone for ActionTracker {
 
 /// Security policy enforced on all tool executions
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct SecurityPolicy {
     pub autonomy: AutonomyLevel,
     pub workspace_dir: PathBuf,
     pub workspace_only: bool,
     pub allowed_commands: Vec<String>,
+    /// Never read or written: path prefixes, or globs like `**/*.pem`
     pub forbidden_paths: Vec<String>,
+    /// Readable but never written, in the workspace too: globs like
+    /// `**/.env`, or path prefixes
+    pub write_protected_paths: Vec<String>,
+    /// Directories outside the workspace reachable by path tools
+    pub reference_roots: Vec<ReferenceRoot>,
     pub max_actions_per_hour: u32,
     pub max_cost_per_day_cents: u32,
     pub require_approval_for_medium_risk: bool,
     pub block_high_risk_commands: bool,
+    /// Tools report what they would do instead of doing it
+    pub dry_run: bool,
+    /// File tools ask the user to approve each change, showing its diff
+    pub approve_file_changes: bool,
     pub tracker: ActionTracker,
 }
 
@@ -114,16 +187,74 @@ impl Default for SecurityPolicy {
                 "~/.ssh".into(),
                 "~/.gnupg".into(),
                 "~/.aws/credentials".into(),
-            ],
+            ]
+            .into_iter()
+            .chain(DEFAULT_FORBIDDEN_GLOBS.iter().map(|glob| (*glob).to_string()))
+            .collect(),
+            write_protected_paths: DEFAULT_WRITE_PROTECTED
+                .iter()
+                .map(|glob| (*glob).to_string())
+                .collect(),
+            reference_roots: vec![],
             max_actions_per_hour: 1000,
             max_cost_per_day_cents: 500,
             require_approval_for_medium_risk: false,
             block_high_risk_commands: false,
+            dry_run: false,
+            approve_file_changes: false,
             tracker: ActionTracker::new(),
         }
     }
 }
 
+/// The configured `forbidden_paths` plus [`DEFAULT_FORBIDDEN_GLOBS`] and the
+/// checkpoint repositories, so that replacing the list in a config file does
+/// not expose private keys or let tools plant git config.
+fn forbidden_paths(autonomy_config: &crate::config::AutonomyConfig) -> Vec<String> {
+    let mut paths = autonomy_config.forbidden_paths.clone();
+    let checkpoints = crate::tools::checkpoints::CHECKPOINT_ROOT;
+    if !paths.iter().any(|path| path == checkpoints) {
+        paths.push(checkpoints.to_string());
+    }
+    if !autonomy_config.allow_key_files {
+        for glob in DEFAULT_FORBIDDEN_GLOBS {
+            if !paths.iter().any(|path| path == glob) {
+                paths.push((*glob).to_string());
+            }
+        }
+    }
+    paths
+}
+
+/// Expand a leading `~/` to `$HOME`.
+fn expand_tilde(path: &str) -> PathBuf {
+    if let Some(stripped) = path.strip_prefix("~/") {
+        if let Some(home) = std::env::var("HOME").ok().map(PathBuf::from) {
+            return home.join(stripped);
+        }
+    }
+    PathBuf::from(path)
+}
+
+/// `path` with `.` and `..` folded away lexically, so `./config/app.toml`
+/// and `src/../config/app.toml` match a `config/` prefix.
+fn normalize(path: &Path) -> PathBuf {
+    use std::path::Component;
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(normalized.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
 /// Skip leading environment variable assignments (e.g. `FOO=bar cmd args`).
 /// Returns the remainder starting at the first non-assignment word.
 fn skip_env_assignments(s: &str) -> &str {
@@ -273,6 +404,7 @@ impl SecurityPolicy {
 
         if risk == CommandRiskLevel::High {
             if self.block_high_risk_commands {
+                crate::health::record_high_risk_blocked();
                 return Err("Command blocked: high-risk command is disallowed by policy".into());
             }
             if self.autonomy == AutonomyLevel::Supervised && !approved {
@@ -373,8 +505,14 @@ impl SecurityPolicy {
         has_cmd
     }
 
-    /// Check if a file path is allowed (no path traversal, within workspace)
+    /// Check if a file path may be written (no path traversal, within workspace)
     pub fn is_path_allowed(&self, path: &str) -> bool {
+        self.is_path_allowed_for(path, PathAccess::Write)
+    }
+
+    /// Check if a file path is allowed for `access`. Absolute paths outside
+    /// the workspace are only allowed under a reference root granting it.
+    pub fn is_path_allowed_for(&self, path: &str, access: PathAccess) -> bool {
         // Block null bytes (can truncate paths in C-backed syscalls)
         if path.contains('\0') {
             return false;
@@ -395,58 +533,106 @@ impl SecurityPolicy {
         }
 
         // Expand tilde for comparison
-        let expanded = if let Some(stripped) = path.strip_prefix("~/") {
-            if let Some(home) = std::env::var("HOME").ok().map(PathBuf::from) {
-                home.join(stripped).to_string_lossy().to_string()
-            } else {
-                path.to_string()
-            }
-        } else {
-            path.to_string()
-        };
+        let expanded = expand_tilde(path);
+        let root = self.reference_root_for(&expanded, access, Path::to_path_buf);
 
         // Block absolute paths when workspace_only is set
-        if self.workspace_only && Path::new(&expanded).is_absolute() {
+        if self.workspace_only && expanded.is_absolute() && root.is_none() {
             return false;
         }
 
-        // Block forbidden paths using path-component-aware matching
-        let expanded_path = Path::new(&expanded);
-        for forbidden in &self.forbidden_paths {
-            let forbidden_expanded = if let Some(stripped) = forbidden.strip_prefix("~/") {
-                if let Some(home) = std::env::var("HOME").ok().map(PathBuf::from) {
-                    home.join(stripped).to_string_lossy().to_string()
-                } else {
-                    forbidden.clone()
-                }
+        if access == PathAccess::Write && self.is_write_protected(&expanded) {
+            return false;
+        }
+        !self.is_forbidden(&expanded, root.as_deref())
+    }
+
+    /// Forbidden paths use path-component-aware matching. A reference root
+    /// nested inside a forbidden prefix (e.g. `~/docs` under `/home`) is an
+    /// explicit exception to it; forbidden paths inside the root still apply.
+    /// Globs apply everywhere, reference roots included.
+    fn is_forbidden(&self, path: &Path, root: Option<&Path>) -> bool {
+        let path = normalize(path);
+        self.forbidden_paths.iter().any(|forbidden| {
+            if is_glob(forbidden) {
+                return glob_matches_path(forbidden, &path);
+            }
+            let forbidden = normalize(&expand_tilde(forbidden));
+            path.starts_with(&forbidden)
+                && !root.is_some_and(|root| root != forbidden && root.starts_with(&forbidden))
+        })
+    }
+
+    /// Whether `path` may be read but not written.
+    fn is_write_protected(&self, path: &Path) -> bool {
+        let path = normalize(path);
+        self.write_protected_paths.iter().any(|protected| {
+            if is_glob(protected) {
+                glob_matches_path(protected, &path)
             } else {
-                forbidden.clone()
-            };
-            let forbidden_path = Path::new(&forbidden_expanded);
-            if expanded_path.starts_with(forbidden_path) {
-                return false;
+                path.starts_with(normalize(&expand_tilde(protected)))
             }
-        }
-
-        true
+        })
     }
 
-    /// Validate that a resolved path is still inside the workspace.
+    /// The most specific reference root containing `path` that grants
+    /// `access`, with root paths passed through `normalize`.
+    fn reference_root_for(
+        &self,
+        path: &Path,
+        access: PathAccess,
+        normalize: impl Fn(&Path) -> PathBuf,
+    ) -> Option<PathBuf> {
+        self.reference_roots
+            .iter()
+            .filter(|root| root.access >= access)
+            .map(|root| normalize(&root.path))
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+    }
+
+    /// Validate that a resolved path may be written: still inside the
+    /// workspace, or under a writable reference root.
     /// Call this AFTER joining `workspace_dir` + relative path and canonicalizing.
     pub fn is_resolved_path_allowed(&self, resolved: &Path) -> bool {
-        // If workspace_only is disabled, allow all paths
-        if !self.workspace_only {
-            return true;
-        }
+        self.is_resolved_path_allowed_for(resolved, PathAccess::Write)
+    }
 
-        // Must be under workspace_dir (prevents symlink escapes).
+    /// Validate a resolved path for `access`: inside the workspace, or under a
+    /// reference root granting it.
+    pub fn is_resolved_path_allowed_for(&self, resolved: &Path, access: PathAccess) -> bool {
         // Prefer canonical workspace root so `/a/../b` style config paths don't
         // cause false positives or negatives.
         let workspace_root = self
             .workspace_dir
             .canonicalize()
             .unwrap_or_else(|_| self.workspace_dir.clone());
-        resolved.starts_with(workspace_root)
+        let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        let root = self.reference_root_for(resolved, access, canonical);
+        let relative = resolved.strip_prefix(&workspace_root).ok();
+
+        // Forbidden and protected entries are checked again on the real path,
+        // so a symlink can't lead to them. Like a reference root, the
+        // workspace is an exception to forbidden prefixes above it (`/home`);
+        // workspace-relative entries (`config/`, `secrets/**`) are matched
+        // against the path below the workspace root.
+        let blocked = |path: &Path, exempt: Option<&Path>| {
+            self.is_forbidden(path, exempt)
+                || (access == PathAccess::Write && self.is_write_protected(path))
+        };
+        let exempt = if relative.is_some() {
+            Some(workspace_root.as_path())
+        } else {
+            root.as_deref()
+        };
+        if blocked(resolved, exempt) || relative.is_some_and(|relative| blocked(relative, None)) {
+            return false;
+        }
+
+        // If workspace_only is disabled, allow all other paths; otherwise the
+        // path must be under workspace_dir (prevents symlink escapes) or a
+        // reference root granting `access`
+        !self.workspace_only || relative.is_some() || root.is_some()
     }
 
     /// Check if autonomy level permits any action at all
@@ -458,6 +644,7 @@ impl SecurityPolicy {
     /// Returns `true` if the action is allowed, `false` if rate-limited.
     /// Only records for write actions (medium/high risk).
     pub fn record_action(&self) -> bool {
+        crate::health::record_autonomy_action();
         let count = self.tracker.record();
         count <= self.max_actions_per_hour as usize
     }
@@ -484,11 +671,22 @@ impl SecurityPolicy {
             workspace_dir: workspace_dir.to_path_buf(),
             workspace_only: autonomy_config.workspace_only,
             allowed_commands: autonomy_config.allowed_commands.clone(),
-            forbidden_paths: autonomy_config.forbidden_paths.clone(),
+            forbidden_paths: forbidden_paths(autonomy_config),
+            write_protected_paths: autonomy_config.write_protected_paths.clone(),
+            reference_roots: autonomy_config
+                .reference_roots
+                .iter()
+                .map(|root| ReferenceRoot {
+                    path: expand_tilde(&root.path),
+                    access: root.access,
+                })
+                .collect(),
             max_actions_per_hour: autonomy_config.max_actions_per_hour,
             max_cost_per_day_cents: autonomy_config.max_cost_per_day_cents,
             require_approval_for_medium_risk: autonomy_config.require_approval_for_medium_risk,
             block_high_risk_commands: autonomy_config.block_high_risk_commands,
+            dry_run: autonomy_config.dry_run,
+            approve_file_changes: autonomy_config.approve_file_changes,
             tracker: ActionTracker::new(),
         }
     }
@@ -757,7 +955,9 @@ mod tests {
     fn dotfile_in_workspace_allowed() {
         let p = default_policy();
         assert!(p.is_path_allowed(".gitignore"));
-        assert!(p.is_path_allowed(".env"));
+        // Secrets dotfiles can be read, not written
+        assert!(p.is_path_allowed_for(".env", PathAccess::Read));
+        assert!(!p.is_path_allowed(".env"));
     }
 
     // ── from_config ─────────────────────────────────────────
@@ -773,6 +973,12 @@ mod tests {
             max_cost_per_day_cents: 1000,
             require_approval_for_medium_risk: false,
             block_high_risk_commands: false,
+            reference_roots: vec![],
+            plan_levels: vec![],
+            dry_run: false,
+            approve_file_changes: false,
+            write_protected_paths: vec!["**/.env".into()],
+            allow_key_files: true,
         };
         let workspace = PathBuf::from("/tmp/test-workspace");
         let policy = SecurityPolicy::from_config(&autonomy_config, &workspace);
@@ -780,7 +986,11 @@ mod tests {
         assert_eq!(policy.autonomy, AutonomyLevel::Full);
         assert!(!policy.workspace_only);
         assert_eq!(policy.allowed_commands, vec!["docker"]);
-        assert_eq!(policy.forbidden_paths, vec!["/secret"]);
+        assert_eq!(
+            policy.forbidden_paths,
+            vec!["/secret", crate::tools::checkpoints::CHECKPOINT_ROOT]
+        );
+        assert_eq!(policy.write_protected_paths, vec!["**/.env"]);
         assert_eq!(policy.max_actions_per_hour, 100);
         assert_eq!(policy.max_cost_per_day_cents, 1000);
         assert!(!policy.require_approval_for_medium_risk);
@@ -1086,6 +1296,12 @@ mod tests {
             max_cost_per_day_cents: 100,
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
+            reference_roots: vec![],
+            plan_levels: vec![],
+            dry_run: false,
+            approve_file_changes: false,
+            write_protected_paths: vec![],
+            allow_key_files: false,
         };
         let workspace = PathBuf::from("/tmp/test");
         let policy = SecurityPolicy::from_config(&autonomy_config, &workspace);
@@ -1208,4 +1424,182 @@ mod tests {
             );
         }
     }
+
+    // ── Reference roots ─────────────────────────────────────
+
+    fn policy_with_roots(roots: Vec<ReferenceRoot>) -> SecurityPolicy {
+        SecurityPolicy {
+            workspace_only: true,
+            forbidden_paths: vec!["/home".into(), "/home/me/docs/private".into()],
+            reference_roots: roots,
+            ..SecurityPolicy::default()
+        }
+    }
+
+    fn root(path: &str, access: PathAccess) -> ReferenceRoot {
+        ReferenceRoot {
+            path: PathBuf::from(path),
+            access,
+        }
+    }
+
+    #[test]
+    fn reference_root_allows_reads_but_not_writes() {
+        let p = policy_with_roots(vec![root("/home/me/docs", PathAccess::Read)]);
+        assert!(p.is_path_allowed_for("/home/me/docs/guide.md", PathAccess::Read));
+        assert!(!p.is_path_allowed("/home/me/docs/guide.md"));
+        // Outside the root the workspace_only rule still applies
+        assert!(!p.is_path_allowed_for("/home/me/notes.md", PathAccess::Read));
+        assert!(!p.is_path_allowed_for("/opt/other.md", PathAccess::Read));
+        assert!(!p.is_path_allowed_for("/home/me/docs/../.ssh/id", PathAccess::Read));
+        // Relative paths stay inside the workspace
+        assert!(p.is_path_allowed("src/main.rs"));
+    }
+
+    #[test]
+    fn writable_reference_root_allows_writes() {
+        let p = policy_with_roots(vec![root("/home/me/scratch", PathAccess::Write)]);
+        assert!(p.is_path_allowed("/home/me/scratch/out.txt"));
+        assert!(p.is_path_allowed_for("/home/me/scratch/out.txt", PathAccess::Read));
+    }
+
+    #[test]
+    fn forbidden_paths_inside_reference_root_stay_blocked() {
+        let p = policy_with_roots(vec![root("/home/me/docs", PathAccess::Read)]);
+        // The root is an exception to the broader `/home` prefix only
+        assert!(p.is_path_allowed_for("/home/me/docs/a.md", PathAccess::Read));
+        assert!(!p.is_path_allowed_for("/home/me/docs/private/key.txt", PathAccess::Read));
+
+        // A root that *is* a forbidden path doesn't unlock it
+        let p = policy_with_roots(vec![root("/home", PathAccess::Read)]);
+        assert!(!p.is_path_allowed_for("/home/me/a.md", PathAccess::Read));
+    }
+
+    #[test]
+    fn resolved_paths_under_reference_roots() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let docs = tempfile::TempDir::new().unwrap();
+        let other = tempfile::TempDir::new().unwrap();
+        let docs_root = docs.path().canonicalize().unwrap();
+        let p = SecurityPolicy {
+            workspace_dir: workspace.path().to_path_buf(),
+            workspace_only: true,
+            forbidden_paths: vec![docs_root.join("secret").to_string_lossy().into_owned()],
+            reference_roots: vec![root(docs.path().to_str().unwrap(), PathAccess::Read)],
+            ..SecurityPolicy::default()
+        };
+
+        assert!(p.is_resolved_path_allowed_for(&docs_root.join("a.md"), PathAccess::Read));
+        assert!(!p.is_resolved_path_allowed(&docs_root.join("a.md")));
+        assert!(!p.is_resolved_path_allowed_for(&docs_root.join("secret/k"), PathAccess::Read));
+        let other = other.path().canonicalize().unwrap();
+        assert!(!p.is_resolved_path_allowed_for(&other.join("a.md"), PathAccess::Read));
+    }
+
+    #[test]
+    fn glob_patterns_guard_secrets_inside_the_workspace() {
+        let p = SecurityPolicy::default();
+        assert!(!p.is_path_allowed_for("deploy/server.pem", PathAccess::Read));
+        assert!(!p.is_path_allowed_for(".ssh/id_rsa.pub", PathAccess::Read));
+        assert!(p.is_path_allowed_for(".env", PathAccess::Read));
+        assert!(!p.is_path_allowed(".env"));
+        assert!(!p.is_path_allowed("services/api/.env.production"));
+        assert!(p.is_path_allowed("src/env.rs"));
+        assert!(p.is_path_allowed("docs/pem-format.md"));
+
+        let p = SecurityPolicy {
+            workspace_only: false,
+            forbidden_paths: vec!["~/notes/*.secret".into()],
+            write_protected_paths: vec!["config/".into()],
+            ..SecurityPolicy::default()
+        };
+        assert!(!p.is_path_allowed_for("~/notes/bank.secret", PathAccess::Read));
+        assert!(p.is_path_allowed_for("~/notes/deep/bank.secret", PathAccess::Read));
+        assert!(!p.is_path_allowed("config/app.toml"));
+        assert!(p.is_path_allowed_for("config/app.toml", PathAccess::Read));
+    }
+
+    #[test]
+    fn write_protected_globs_apply_to_resolved_paths() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let root = workspace.path().canonicalize().unwrap();
+        let p = SecurityPolicy {
+            workspace_dir: workspace.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        };
+        assert!(p.is_resolved_path_allowed_for(&root.join(".env"), PathAccess::Read));
+        assert!(!p.is_resolved_path_allowed(&root.join(".env")));
+        assert!(!p.is_resolved_path_allowed_for(&root.join("certs/ca.pem"), PathAccess::Read));
+        assert!(p.is_resolved_path_allowed(&root.join("src/main.rs")));
+    }
+
+    #[test]
+    fn prefixes_are_normalized_and_apply_to_resolved_paths() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let root = workspace.path().canonicalize().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        let outside = outside.path().canonicalize().unwrap();
+        let p = SecurityPolicy {
+            workspace_dir: workspace.path().to_path_buf(),
+            workspace_only: false,
+            forbidden_paths: vec![
+                "/tmp".into(),
+                outside.to_string_lossy().into_owned(),
+                "private".into(),
+                "secrets/**".into(),
+            ],
+            write_protected_paths: vec!["config/".into()],
+            ..SecurityPolicy::default()
+        };
+        assert!(!p.is_path_allowed("./config/app.toml"));
+        assert!(!p.is_path_allowed_for("./private/notes.md", PathAccess::Read));
+
+        // A prefix above the workspace does not hide the workspace itself
+        assert!(p.is_resolved_path_allowed(&root.join("src/main.rs")));
+        assert!(!p.is_resolved_path_allowed(&root.join("config/app.toml")));
+        assert!(p.is_resolved_path_allowed_for(&root.join("config/app.toml"), PathAccess::Read));
+        assert!(!p.is_resolved_path_allowed_for(&root.join("private/notes.md"), PathAccess::Read));
+        assert!(!p.is_resolved_path_allowed_for(&root.join("secrets/db/key"), PathAccess::Read));
+        assert!(!p.is_resolved_path_allowed_for(&outside.join("a.md"), PathAccess::Read));
+    }
+
+    #[test]
+    fn from_config_always_forbids_key_files() {
+        let autonomy_config = crate::config::AutonomyConfig {
+            forbidden_paths: vec!["/secret".into(), "**/*.pem".into()],
+            ..crate::config::AutonomyConfig::default()
+        };
+        let policy = SecurityPolicy::from_config(&autonomy_config, Path::new("/ws"));
+        assert_eq!(policy.forbidden_paths[..2], ["/secret", "**/*.pem"]);
+        assert_eq!(policy.forbidden_paths.len(), 2 + DEFAULT_FORBIDDEN_GLOBS.len());
+        assert!(!policy.is_path_allowed_for("keys/id_rsa", PathAccess::Read));
+        let shadow_config = expand_tilde("~/.zeroclaw/checkpoints/0123abcd/config");
+        assert!(!policy.is_path_allowed_for(&shadow_config.to_string_lossy(), PathAccess::Write));
+
+        let opted_out = crate::config::AutonomyConfig {
+            allow_key_files: true,
+            ..autonomy_config
+        };
+        let policy = SecurityPolicy::from_config(&opted_out, Path::new("/ws"));
+        assert_eq!(
+            policy.forbidden_paths,
+            ["/secret", "**/*.pem", crate::tools::checkpoints::CHECKPOINT_ROOT]
+        );
+        assert!(policy.is_path_allowed_for("keys/id_rsa", PathAccess::Read));
+    }
+
+    #[test]
+    fn from_config_expands_reference_roots() {
+        let autonomy_config = crate::config::AutonomyConfig {
+            reference_roots: vec![crate::config::schema::ReferenceRootConfig {
+                path: "~/docs".into(),
+                access: PathAccess::Read,
+            }],
+            ..crate::config::AutonomyConfig::default()
+        };
+        let policy = SecurityPolicy::from_config(&autonomy_config, Path::new("/ws"));
+        assert_eq!(policy.reference_roots.len(), 1);
+        assert!(!policy.reference_roots[0].path.starts_with("~"));
+        assert!(policy.reference_roots[0].path.ends_with("docs"));
+    }
 }
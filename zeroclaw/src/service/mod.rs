@@ -1,10 +1,13 @@
 use crate::config::Config;
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 const SERVICE_LABEL: &str = "com.zeroclaw.daemon";
+/// Name registered with the Windows service control manager
+pub const WINDOWS_SERVICE_NAME: &str = "ZeroClaw";
+const UNSUPPORTED: &str = "Service management is supported on macOS, Linux and Windows only";
 
 pub fn handle_command(command: &crate::ServiceCommands, config: &Config) -> Result<()> {
     match command {
@@ -21,8 +24,10 @@ fn install(config: &Config) -> Result<()> {
         install_macos(config)
     } else if cfg!(target_os = "linux") {
         install_linux(config)
+    } else if cfg!(windows) {
+        install_windows()
     } else {
-        anyhow::bail!("Service management is supported on macOS and Linux only");
+        anyhow::bail!(UNSUPPORTED);
     }
 }
 
@@ -38,9 +43,13 @@ fn start(config: &Config) -> Result<()> {
         run_checked(Command::new("systemctl").args(["--user", "start", "zeroclaw.service"]))?;
         println!("✅ Service started");
         Ok(())
+    } else if cfg!(windows) {
+        run_checked(Command::new("sc.exe").args(["start", WINDOWS_SERVICE_NAME]))?;
+        println!("✅ Service started");
+        Ok(())
     } else {
         let _ = config;
-        anyhow::bail!("Service management is supported on macOS and Linux only")
+        anyhow::bail!(UNSUPPORTED)
     }
 }
 
@@ -60,9 +69,13 @@ fn stop(config: &Config) -> Result<()> {
         let _ = run_checked(Command::new("systemctl").args(["--user", "stop", "zeroclaw.service"]));
         println!("✅ Service stopped");
         Ok(())
+    } else if cfg!(windows) {
+        let _ = run_checked(Command::new("sc.exe").args(["stop", WINDOWS_SERVICE_NAME]));
+        println!("✅ Service stopped");
+        Ok(())
     } else {
         let _ = config;
-        anyhow::bail!("Service management is supported on macOS and Linux only")
+        anyhow::bail!(UNSUPPORTED)
     }
 }
 
@@ -94,7 +107,19 @@ fn status(config: &Config) -> Result<()> {
         return Ok(());
     }
 
-    anyhow::bail!("Service management is supported on macOS and Linux only")
+    if cfg!(windows) {
+        let out = run_capture(Command::new("sc.exe").args(["query", WINDOWS_SERVICE_NAME]))?;
+        let state = out
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("STATE"))
+            .and_then(|rest| rest.split_whitespace().nth(2))
+            .unwrap_or("not installed");
+        println!("Service state: {state}");
+        println!("Service name: {WINDOWS_SERVICE_NAME}");
+        return Ok(());
+    }
+
+    anyhow::bail!(UNSUPPORTED)
 }
 
 fn uninstall(config: &Config) -> Result<()> {
@@ -121,7 +146,13 @@ fn uninstall(config: &Config) -> Result<()> {
         return Ok(());
     }
 
-    anyhow::bail!("Service management is supported on macOS and Linux only")
+    if cfg!(windows) {
+        run_checked(Command::new("sc.exe").args(["delete", WINDOWS_SERVICE_NAME]))?;
+        println!("✅ Service uninstalled ({WINDOWS_SERVICE_NAME})");
+        return Ok(());
+    }
+
+    anyhow::bail!(UNSUPPORTED)
 }
 
 fn install_macos(config: &Config) -> Result<()> {
@@ -183,12 +214,7 @@ fn install_linux(config: &Config) -> Result<()> {
     }
 
     let exe = std::env::current_exe().context("Failed to resolve current executable")?;
-    let unit = format!(
-        "[Unit]\nDescription=ZeroClaw daemon\nAfter=network.target\n\n[Service]\nType=simple\nExecStart={} daemon\nRestart=always\nRestartSec=3\n\n[Install]\nWantedBy=default.target\n",
-        exe.display()
-    );
-
-    fs::write(&file, unit)?;
+    fs::write(&file, linux_unit(&exe))?;
     let _ = run_checked(Command::new("systemctl").args(["--user", "daemon-reload"]));
     let _ = run_checked(Command::new("systemctl").args(["--user", "enable", "zeroclaw.service"]));
     println!("✅ Installed systemd user service: {}", file.display());
@@ -196,6 +222,44 @@ fn install_linux(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// systemd user unit. `Type=notify` waits for the daemon's READY=1, reload
+/// sends SIGHUP, and the stop timeout leaves room to drain active runs.
+fn linux_unit(exe: &Path) -> String {
+    format!(
+        "[Unit]\nDescription=ZeroClaw daemon\nAfter=network.target\n\n[Service]\nType=notify\nNotifyAccess=main\nExecStart={} daemon\nExecReload=/bin/kill -HUP $MAINPID\nRestart=always\nRestartSec=3\nTimeoutStopSec={}\nWatchdogSec=60\n\n[Install]\nWantedBy=default.target\n",
+        exe.display(),
+        crate::daemon::shutdown::DRAIN_TIMEOUT_SECS + 15
+    )
+}
+
+fn install_windows() -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to resolve current executable")?;
+    run_checked(Command::new("sc.exe").args(windows_create_args(&exe)))?;
+    let _ = run_checked(Command::new("sc.exe").args([
+        "description",
+        WINDOWS_SERVICE_NAME,
+        "ZeroClaw autonomous agent daemon",
+    ]));
+    println!("✅ Installed Windows service: {WINDOWS_SERVICE_NAME}");
+    println!("   Start with: zeroclaw service start");
+    Ok(())
+}
+
+/// `sc.exe create` arguments; sc expects each `key=` and its value as
+/// separate arguments.
+fn windows_create_args(exe: &Path) -> Vec<String> {
+    vec![
+        "create".into(),
+        WINDOWS_SERVICE_NAME.into(),
+        "binPath=".into(),
+        format!("\"{}\" daemon --service", exe.display()),
+        "start=".into(),
+        "auto".into(),
+        "DisplayName=".into(),
+        "ZeroClaw daemon".into(),
+    ]
+}
+
 fn macos_service_file() -> Result<PathBuf> {
     let home = directories::UserDirs::new()
         .map(|u| u.home_dir().to_path_buf())
@@ -275,6 +339,23 @@ mod tests {
         assert!(err.to_string().contains("Command failed"));
     }
 
+    #[test]
+    fn linux_unit_uses_notify_and_sighup_reload() {
+        let unit = linux_unit(Path::new("/usr/local/bin/zeroclaw"));
+        assert!(unit.contains("Type=notify\n"));
+        assert!(unit.contains("ExecStart=/usr/local/bin/zeroclaw daemon\n"));
+        assert!(unit.contains("ExecReload=/bin/kill -HUP $MAINPID\n"));
+        assert!(unit.contains("TimeoutStopSec=45\n"));
+    }
+
+    #[test]
+    fn windows_create_args_run_daemon_as_service() {
+        let args = windows_create_args(Path::new("C:\\Tools\\zeroclaw.exe"));
+        assert_eq!(args[1], WINDOWS_SERVICE_NAME);
+        assert_eq!(args[2], "binPath=");
+        assert_eq!(args[3], "\"C:\\Tools\\zeroclaw.exe\" daemon --service");
+    }
+
     #[test]
     fn linux_service_file_has_expected_suffix() {
         let file = linux_service_file(&Config::default()).unwrap();
@@ -0,0 +1,180 @@
+//! User feedback on answers.
+//!
+//! A thumbs up or down, with an optional comment, on one message of a
+//! session is appended to `<workspace>/.zeroclaw/feedback.jsonl`, next to
+//! the audit log, so eval and fine-tuning pipelines have labels to work
+//! from. Feedback on a saved session also becomes that session's rating.
+//! It arrives from `zeroclaw sessions rate`, `POST /sessions/:id/feedback`
+//! or `zc_feedback_record`, and is summarized in `GET /stats`.
+
+use super::{Rating, SessionStore};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+/// Feedback log, relative to the workspace.
+pub const FEEDBACK_LOG: &str = ".zeroclaw/feedback.jsonl";
+
+/// Entries listed in [`FeedbackStats::recent`].
+const RECENT: usize = 10;
+
+/// One rating of one message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Feedback {
+    pub time: DateTime<Utc>,
+    pub session_id: String,
+    /// Position of the rated message in the session, from 0
+    pub message_index: usize,
+    pub rating: Rating,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Where it was given ("cli", "gateway", "ffi")
+    pub source: String,
+}
+
+impl Feedback {
+    pub fn new(session_id: &str, message_index: usize, rating: Rating, source: &str) -> Self {
+        Self {
+            time: Utc::now(),
+            session_id: session_id.trim().to_string(),
+            message_index,
+            rating,
+            comment: None,
+            source: source.to_string(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_comment(mut self, comment: Option<String>) -> Self {
+        self.comment = comment.filter(|c| !c.trim().is_empty());
+        self
+    }
+}
+
+/// Totals for `GET /stats` and `zc_feedback_stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FeedbackStats {
+    pub total: usize,
+    pub up: usize,
+    pub down: usize,
+    /// Share of ratings that are thumbs up; None before any feedback
+    pub approval_rate: Option<f64>,
+    /// Latest entries, newest first
+    pub recent: Vec<Feedback>,
+}
+
+/// Store `feedback`. When its session is saved in the workspace, the
+/// message must exist and be an answer, and the session takes the rating.
+/// Other ids, such as live FFI sessions (`ffi-<id>`), are recorded as given.
+pub fn record(workspace_dir: &Path, feedback: &Feedback) -> Result<()> {
+    let id = &feedback.session_id;
+    if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+        anyhow::bail!("Invalid session id: {id:?}");
+    }
+    let store = SessionStore::new(workspace_dir);
+    if store.path_for(id).is_file() {
+        let mut session = store.load(id)?;
+        match session.messages.get(feedback.message_index) {
+            Some(message) if message.role == "assistant" => {}
+            Some(message) => anyhow::bail!(
+                "Message {} of session {id} is a {} message, not an answer",
+                feedback.message_index,
+                message.role
+            ),
+            None => anyhow::bail!(
+                "Session {id} has no message {} (it has {})",
+                feedback.message_index,
+                session.messages.len()
+            ),
+        }
+        session.meta.rating = Some(feedback.rating);
+        store.save(&session)?;
+    }
+
+    let path = workspace_dir.join(FEEDBACK_LOG);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(feedback)?)?;
+    Ok(())
+}
+
+/// Every stored entry, oldest first. Unreadable lines are skipped.
+pub fn load(workspace_dir: &Path) -> Result<Vec<Feedback>> {
+    let path = workspace_dir.join(FEEDBACK_LOG);
+    let raw = match std::fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+pub fn stats(workspace_dir: &Path) -> Result<FeedbackStats> {
+    let entries = load(workspace_dir)?;
+    let up = entries.iter().filter(|f| f.rating == Rating::Up).count();
+    let total = entries.len();
+    #[allow(clippy::cast_precision_loss)]
+    let approval_rate = (total > 0).then(|| up as f64 / total as f64);
+    Ok(FeedbackStats {
+        total,
+        up,
+        down: total - up,
+        approval_rate,
+        recent: entries.into_iter().rev().take(RECENT).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ChatMessage;
+    use crate::sessions::Session;
+
+    #[test]
+    fn feedback_is_logged_and_rates_saved_sessions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(tmp.path());
+        let mut session = Session::new("cli", "test-model");
+        session.push(ChatMessage::user("Summarize the report"));
+        session.push(ChatMessage::assistant("It says revenue grew."));
+        store.save(&session).unwrap();
+
+        let down = Feedback::new(session.id(), 1, Rating::Down, "gateway")
+            .with_comment(Some("Missed the losses".into()));
+        record(tmp.path(), &down).unwrap();
+        assert_eq!(
+            store.load(session.id()).unwrap().meta.rating,
+            Some(Rating::Down)
+        );
+        // Only answers that exist can be rated
+        let rate = |index| {
+            record(
+                tmp.path(),
+                &Feedback::new(session.id(), index, Rating::Up, "cli"),
+            )
+        };
+        assert!(rate(0).is_err());
+        assert!(rate(5).is_err());
+        assert!(record(tmp.path(), &Feedback::new("../x", 0, Rating::Up, "cli")).is_err());
+        // Live sessions are not saved, so their feedback is taken as given
+        record(tmp.path(), &Feedback::new("ffi-3", 7, Rating::Up, "ffi")).unwrap();
+
+        let stats = stats(tmp.path()).unwrap();
+        assert_eq!((stats.total, stats.up, stats.down), (2, 1, 1));
+        assert_eq!(stats.approval_rate, Some(0.5));
+        assert_eq!(stats.recent[0].session_id, "ffi-3");
+        assert_eq!(stats.recent[1], down);
+    }
+}
@@ -0,0 +1,1018 @@
+//! Conversation sessions — persisted transcripts with short, human-readable titles.
+//!
+//! Each session lives in `<workspace>/sessions/<uuid>.json`. After the first
+//! user/assistant exchange a title is generated with one small model call so
+//! `zeroclaw sessions list`, the gateway `/sessions` API and transcript exports
+//! show something better than a bare UUID.
+//!
+//! Sessions can be tagged and rated, answers can get feedback (see
+//! [`feedback`]), and sessions can be exported as fine-tuning datasets (see
+//! [`dataset`]).
+
+pub mod dataset;
+pub mod feedback;
+
+use crate::config::{Config, SessionsConfig};
+use crate::memory::embeddings::{create_embedding_provider, EmbeddingProvider};
+use crate::memory::vector::{cosine_similarity, hybrid_merge};
+use crate::providers::{ChatMessage, Provider};
+use crate::util::json_dir::{JsonDir, Record};
+use crate::util::truncate_with_ellipsis;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Upper bound for generated titles (characters).
+pub const TITLE_MAX_CHARS: usize = 60;
+
+const TITLE_SYSTEM_PROMPT: &str = "You label chat conversations. \
+Reply with a concise title of at most 6 words describing the topic of the exchange. \
+Reply with the title only: no quotes, no trailing punctuation, no preamble.";
+
+/// Input given to the title model is capped so a huge first message stays cheap.
+const TITLE_INPUT_MAX_CHARS: usize = 1_500;
+
+/// Search ranking weights and snippet shape.
+const TITLE_MATCH_WEIGHT: f32 = 3.0;
+const PHRASE_MATCH_WEIGHT: f32 = 5.0;
+const MAX_SNIPPETS: usize = 3;
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+/// Semantic search embeds at most this many recent sessions per query.
+const SEMANTIC_MAX_SESSIONS: usize = 200;
+const EMBEDDING_DOC_MAX_CHARS: usize = 2_000;
+
+/// Session metadata — everything except the transcript itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMeta {
+    pub id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Where the conversation happened ("cli", "gateway", ...)
+    pub channel: String,
+    pub model: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub message_count: usize,
+    /// Set on sessions created by [`Session::branch`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_of: Option<BranchOrigin>,
+    /// Labels for picking sessions, e.g. when exporting a dataset
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// The user's verdict on the conversation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<Rating>,
+}
+
+/// Thumbs up or down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rating {
+    Up,
+    Down,
+}
+
+impl std::str::FromStr for Rating {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "up" | "good" | "+1" => Ok(Self::Up),
+            "down" | "bad" | "-1" => Ok(Self::Down),
+            other => anyhow::bail!("Unknown rating '{other}' (use up or down)"),
+        }
+    }
+}
+
+/// Where a branched session was forked from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchOrigin {
+    pub session_id: String,
+    /// Message index passed to [`Session::branch`]
+    pub message_index: usize,
+}
+
+impl SessionMeta {
+    /// Title if one was generated, otherwise the session id.
+    pub fn display_title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// A persisted conversation: metadata plus user/assistant messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(flatten)]
+    pub meta: SessionMeta,
+    #[serde(default)]
+    pub messages: Vec<ChatMessage>,
+}
+
+impl Session {
+    pub fn new(channel: &str, model: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            meta: SessionMeta {
+                id: Uuid::new_v4().to_string(),
+                title: None,
+                channel: channel.to_string(),
+                model: model.to_string(),
+                created_at: now,
+                updated_at: now,
+                message_count: 0,
+                branch_of: None,
+                tags: Vec::new(),
+                rating: None,
+            },
+            messages: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.meta.id
+    }
+
+    pub fn push(&mut self, message: ChatMessage) {
+        self.messages.push(message);
+        self.meta.message_count = self.messages.len();
+        self.meta.updated_at = Utc::now();
+    }
+
+    /// First user message and the assistant reply that followed it.
+    pub fn first_exchange(&self) -> Option<(&str, &str)> {
+        let user_idx = self.messages.iter().position(|m| m.role == "user")?;
+        let reply = self.messages[user_idx + 1..]
+            .iter()
+            .find(|m| m.role == "assistant")?;
+        Some((&self.messages[user_idx].content, &reply.content))
+    }
+
+    /// Fork the conversation at `index` to rerun the turn containing it,
+    /// e.g. with another model. Returns a new session holding the messages
+    /// before that turn, and the turn's user message to send again. The
+    /// original session is left untouched.
+    pub fn branch(&self, index: usize, model: &str) -> Result<(Session, String)> {
+        let turn = branch_point(&self.messages, index)?;
+        let mut branch = Session::new(&self.meta.channel, model);
+        branch.meta.title.clone_from(&self.meta.title);
+        branch.meta.branch_of = Some(BranchOrigin {
+            session_id: self.meta.id.clone(),
+            message_index: index,
+        });
+        for message in &self.messages[..turn] {
+            branch.push(message.clone());
+        }
+        Ok((branch, self.messages[turn].content.clone()))
+    }
+
+    /// Render the transcript as Markdown, headed by the session title.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.meta.display_title());
+        let _ = writeln!(out, "- Session: `{}`", self.meta.id);
+        let _ = writeln!(out, "- Channel: {}", self.meta.channel);
+        let _ = writeln!(out, "- Model: {}", self.meta.model);
+        if let Some(origin) = &self.meta.branch_of {
+            let _ = writeln!(
+                out,
+                "- Branched from: `{}` at message {}",
+                origin.session_id, origin.message_index
+            );
+        }
+        let _ = writeln!(out, "- Started: {}", self.meta.created_at.to_rfc3339());
+        let _ = writeln!(out, "- Updated: {}", self.meta.updated_at.to_rfc3339());
+
+        for msg in &self.messages {
+            let speaker = match msg.role.as_str() {
+                "user" => "User",
+                "assistant" => "Assistant",
+                "system" => "System",
+                other => other,
+            };
+            let _ = write!(out, "\n## {speaker}\n\n{}\n", msg.content.trim_end());
+        }
+        out
+    }
+}
+
+/// Index of the user message that starts the turn containing `index`.
+pub fn branch_point(messages: &[ChatMessage], index: usize) -> Result<usize> {
+    if index >= messages.len() {
+        anyhow::bail!(
+            "Message index {index} is out of range (the session has {} messages)",
+            messages.len()
+        );
+    }
+    messages[..=index]
+        .iter()
+        .rposition(|m| m.role == "user")
+        .with_context(|| format!("No user message at or before index {index}"))
+}
+
+/// File-backed session store rooted at `<workspace>/sessions`.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    records: JsonDir<Session>,
+}
+
+impl Record for Session {
+    fn id(&self) -> &str {
+        Session::id(self)
+    }
+}
+
+impl SessionStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            records: JsonDir::new(workspace_dir.join("sessions"), "session"),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        self.records.dir()
+    }
+
+    fn path_for(&self, id: &str) -> PathBuf {
+        self.records.path_for(id)
+    }
+
+    /// Write the session atomically (temp file + rename).
+    pub fn save(&self, session: &Session) -> Result<()> {
+        self.records.save(session)
+    }
+
+    /// Load a session by full id or unambiguous id prefix.
+    pub fn load(&self, id: &str) -> Result<Session> {
+        self.records.load(id)
+    }
+
+    /// Change a stored session's metadata, e.g. its tags or rating, without
+    /// counting as activity.
+    pub fn update_meta(
+        &self,
+        id: &str,
+        change: impl FnOnce(&mut SessionMeta),
+    ) -> Result<SessionMeta> {
+        let mut session = self.load(id)?;
+        change(&mut session.meta);
+        self.save(&session)?;
+        Ok(session.meta)
+    }
+
+    /// Metadata for every stored session, most recently updated first.
+    pub fn list(&self) -> Result<Vec<SessionMeta>> {
+        Ok(self
+            .load_all()?
+            .into_iter()
+            .map(|session| session.meta)
+            .collect())
+    }
+
+    /// Sessions updated at or after `cutoff`, most recently updated first.
+    pub fn updated_since(&self, cutoff: DateTime<Utc>) -> Result<Vec<Session>> {
+        Ok(self
+            .load_all()?
+            .into_iter()
+            .take_while(|session| session.meta.updated_at >= cutoff)
+            .collect())
+    }
+
+    /// Every readable session, most recently updated first.
+    fn load_all(&self) -> Result<Vec<Session>> {
+        let mut sessions = self.records.load_all()?;
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.meta.updated_at));
+        Ok(sessions)
+    }
+
+    /// Case-insensitive full-text search over titles and transcripts.
+    ///
+    /// Sessions matching any query term are ranked by term frequency, with
+    /// title hits and exact phrase matches weighted higher.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SessionSearchHit>> {
+        let terms = search_terms(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut hits: Vec<SessionSearchHit> = self
+            .load_all()?
+            .iter()
+            .filter_map(|session| keyword_hit(session, query, &terms))
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// Hybrid search: keyword hits fused with embedding similarity, so
+    /// sessions about the same topic surface even without shared words.
+    pub async fn search_semantic(
+        &self,
+        query: &str,
+        semantic: &SemanticSearch,
+        limit: usize,
+    ) -> Result<Vec<SessionSearchHit>> {
+        let terms = search_terms(query);
+        let mut sessions = self.load_all()?;
+        sessions.truncate(SEMANTIC_MAX_SESSIONS);
+        if sessions.is_empty() || query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let docs: Vec<String> = sessions.iter().map(embedding_document).collect();
+        let doc_refs: Vec<&str> = docs.iter().map(String::as_str).collect();
+        let doc_vectors = semantic.embedder.embed(&doc_refs).await?;
+        let query_vector = semantic.embedder.embed_one(query).await?;
+
+        let vector_results: Vec<(String, f32)> = sessions
+            .iter()
+            .zip(&doc_vectors)
+            .map(|(session, vector)| {
+                (
+                    session.meta.id.clone(),
+                    cosine_similarity(&query_vector, vector),
+                )
+            })
+            .collect();
+        let keyword_hits: Vec<SessionSearchHit> = sessions
+            .iter()
+            .filter_map(|session| keyword_hit(session, query, &terms))
+            .collect();
+        let keyword_results: Vec<(String, f32)> = keyword_hits
+            .iter()
+            .map(|hit| (hit.meta.id.clone(), hit.score))
+            .collect();
+
+        let merged = hybrid_merge(
+            &vector_results,
+            &keyword_results,
+            semantic.vector_weight,
+            semantic.keyword_weight,
+            limit,
+        );
+
+        Ok(merged
+            .into_iter()
+            .filter_map(|scored| {
+                let snippets = keyword_hits
+                    .iter()
+                    .find(|hit| hit.meta.id == scored.id)
+                    .map(|hit| hit.snippets.clone());
+                let session = sessions.iter().find(|s| s.meta.id == scored.id)?;
+                Some(SessionSearchHit {
+                    meta: session.meta.clone(),
+                    score: scored.final_score,
+                    snippets: snippets.unwrap_or_else(|| opening_snippet(session)),
+                })
+            })
+            .collect())
+    }
+}
+
+/// Embedding setup for semantic session search (reuses the memory embedding config).
+pub struct SemanticSearch {
+    pub embedder: Arc<dyn EmbeddingProvider>,
+    pub vector_weight: f32,
+    pub keyword_weight: f32,
+}
+
+impl SemanticSearch {
+    /// `None` when no embedding provider is configured.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let embedder: Arc<dyn EmbeddingProvider> = Arc::from(create_embedding_provider(
+            &config.memory.embedding_provider,
+            config.api_key.as_deref(),
+            &config.memory.embedding_model,
+            config.memory.embedding_dimensions,
+        ));
+        if embedder.dimensions() == 0 {
+            return None;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        Some(Self {
+            embedder,
+            vector_weight: config.memory.vector_weight as f32,
+            keyword_weight: config.memory.keyword_weight as f32,
+        })
+    }
+}
+
+/// A session matching a search query, with the best-matching excerpts.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSearchHit {
+    #[serde(flatten)]
+    pub meta: SessionMeta,
+    pub score: f32,
+    pub snippets: Vec<String>,
+}
+
+fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split(|c: char| !c.is_alphanumeric() && c != '-' && c != '_')
+        .filter(|t| t.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn keyword_hit(session: &Session, query: &str, terms: &[String]) -> Option<SessionSearchHit> {
+    let phrase = query.trim().to_lowercase();
+    let title = session
+        .meta
+        .title
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let mut score = 0.0_f32;
+    for term in terms {
+        score += TITLE_MATCH_WEIGHT * title.matches(term.as_str()).count() as f32;
+    }
+    if terms.len() > 1 && title.contains(&phrase) {
+        score += PHRASE_MATCH_WEIGHT;
+    }
+
+    let mut snippets = Vec::new();
+    for msg in &session.messages {
+        let lower = msg.content.to_lowercase();
+        let mut first_match = None;
+        for term in terms {
+            let count = lower.matches(term.as_str()).count();
+            if count > 0 {
+                score += count as f32;
+                first_match = first_match.or_else(|| lower.find(term.as_str()));
+            }
+        }
+        if terms.len() > 1 && lower.contains(&phrase) {
+            score += PHRASE_MATCH_WEIGHT;
+            first_match = lower.find(&phrase);
+        }
+        if let Some(pos) = first_match {
+            if snippets.len() < MAX_SNIPPETS {
+                snippets.push(snippet_around(&msg.content, &lower, pos));
+            }
+        }
+    }
+
+    (score > 0.0).then(|| SessionSearchHit {
+        meta: session.meta.clone(),
+        score,
+        snippets,
+    })
+}
+
+/// Excerpt of `content` centred on byte offset `pos` of its lowercase form.
+fn snippet_around(content: &str, lower: &str, pos: usize) -> String {
+    // Lowercasing can change byte lengths; map the offset via char count.
+    let chars: Vec<char> = content.chars().collect();
+    let char_pos = lower[..pos].chars().count().min(chars.len());
+    let start = char_pos.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (char_pos + SNIPPET_CONTEXT_CHARS).min(chars.len());
+    let body: String = chars[start..end].iter().collect();
+    let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!(
+        "{}{body}{}",
+        if start > 0 { "…" } else { "" },
+        if end < chars.len() { "…" } else { "" }
+    )
+}
+
+fn opening_snippet(session: &Session) -> Vec<String> {
+    session
+        .messages
+        .iter()
+        .find(|m| m.role == "user")
+        .map(|m| {
+            let line = m.content.split_whitespace().collect::<Vec<_>>().join(" ");
+            vec![truncate_with_ellipsis(&line, SNIPPET_CONTEXT_CHARS * 2)]
+        })
+        .unwrap_or_default()
+}
+
+/// Text embedded for semantic search: title plus what the user asked.
+fn embedding_document(session: &Session) -> String {
+    let mut doc = session.meta.title.clone().unwrap_or_default();
+    for msg in session.messages.iter().filter(|m| m.role == "user") {
+        doc.push('\n');
+        doc.push_str(&msg.content);
+    }
+    truncate_with_ellipsis(&doc, EMBEDDING_DOC_MAX_CHARS)
+}
+
+/// Normalize a model-produced title: first line, no quotes/labels/trailing punctuation.
+pub fn clean_title(raw: &str) -> Option<String> {
+    let line = raw.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = ["Title:", "title:", "TITLE:"]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+        .unwrap_or(line);
+    let line = line
+        .trim()
+        .trim_matches(|c| matches!(c, '"' | '\'' | '`' | '*' | '#'))
+        .trim_end_matches(['.', '!', '?', ':', ';', ','])
+        .trim();
+    if line.is_empty() {
+        return None;
+    }
+    Some(truncate_with_ellipsis(line, TITLE_MAX_CHARS))
+}
+
+/// Title derived from the first user message, used when the model call fails.
+pub fn fallback_title(first_user_message: &str) -> String {
+    clean_title(first_user_message).unwrap_or_else(|| "Untitled session".into())
+}
+
+/// Ask the model for a short topic label for the first exchange.
+pub async fn generate_title(
+    provider: &dyn Provider,
+    model: &str,
+    user_message: &str,
+    assistant_reply: &str,
+) -> Result<String> {
+    let prompt = format!(
+        "User: {}\n\nAssistant: {}",
+        truncate_with_ellipsis(user_message, TITLE_INPUT_MAX_CHARS),
+        truncate_with_ellipsis(assistant_reply, TITLE_INPUT_MAX_CHARS)
+    );
+    let raw = provider
+        .chat_with_system(Some(TITLE_SYSTEM_PROMPT), &prompt, model, 0.0)
+        .await?;
+    clean_title(&raw).context("Title model returned an empty response")
+}
+
+/// Fill in the session title once the first exchange is available.
+///
+/// Returns `true` when a title was set. Model failures fall back to a title
+/// derived from the first user message so sessions never stay unlabeled.
+pub async fn ensure_title(
+    session: &mut Session,
+    provider: &dyn Provider,
+    config: &SessionsConfig,
+    default_model: &str,
+) -> bool {
+    if !config.auto_title || session.meta.title.is_some() {
+        return false;
+    }
+    let Some((user, assistant)) = session.first_exchange() else {
+        return false;
+    };
+
+    let model = config.title_model.as_deref().unwrap_or(default_model);
+    let title = match generate_title(provider, model, user, assistant).await {
+        Ok(title) => title,
+        Err(e) => {
+            tracing::warn!(
+                "Session title generation failed: {}",
+                crate::providers::sanitize_api_error(&e.to_string())
+            );
+            fallback_title(user)
+        }
+    };
+    session.meta.title = Some(title);
+    true
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::too_many_lines)]
+pub async fn handle_command(command: crate::SessionCommands, config: &Config) -> Result<()> {
+    let store = SessionStore::new(&config.workspace_dir);
+    match command {
+        crate::SessionCommands::List { limit } => {
+            let sessions = store.list()?;
+            if sessions.is_empty() {
+                println!("No sessions yet.");
+                println!("\nStart one with:");
+                println!("  zeroclaw agent");
+                return Ok(());
+            }
+
+            println!("💬 Sessions ({}):", sessions.len());
+            for meta in sessions.iter().take(limit) {
+                println!(
+                    "- {} | {} | {} msgs | {}\n    {}",
+                    meta.id,
+                    meta.updated_at.format("%Y-%m-%d %H:%M"),
+                    meta.message_count,
+                    meta.channel,
+                    meta.display_title()
+                );
+            }
+            if sessions.len() > limit {
+                println!("  … {} more (use --limit)", sessions.len() - limit);
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Search {
+            query,
+            limit,
+            semantic,
+        } => {
+            let hits = if semantic {
+                let Some(semantic) = SemanticSearch::from_config(config) else {
+                    anyhow::bail!(
+                        "Semantic search needs an embedding provider (set [memory].embedding_provider)"
+                    );
+                };
+                store.search_semantic(&query, &semantic, limit).await?
+            } else {
+                store.search(&query, limit)?
+            };
+
+            if hits.is_empty() {
+                println!("No sessions match \"{query}\".");
+                return Ok(());
+            }
+            println!("🔎 {} matching session(s):", hits.len());
+            for hit in hits {
+                println!(
+                    "- {} | {} | {}",
+                    hit.meta.id,
+                    hit.meta.updated_at.format("%Y-%m-%d %H:%M"),
+                    hit.meta.display_title()
+                );
+                for snippet in &hit.snippets {
+                    println!("    › {snippet}");
+                }
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Show { id } => {
+            let session = store.load(&id)?;
+            println!("{}", session.to_markdown());
+            Ok(())
+        }
+        crate::SessionCommands::Export { id, format, output } => {
+            let session = store.load(&id)?;
+            let rendered = match format.as_str() {
+                "markdown" | "md" => session.to_markdown(),
+                "json" => serde_json::to_string_pretty(&session)?,
+                other => anyhow::bail!("Unknown export format '{other}' (use markdown or json)"),
+            };
+            match output {
+                Some(path) => {
+                    fs::write(&path, rendered)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!(
+                        "✅ Exported \"{}\" to {}",
+                        session.meta.display_title(),
+                        path.display()
+                    );
+                }
+                None => println!("{rendered}"),
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Tag { id, tags, remove } => {
+            let meta = store.update_meta(&id, |meta| {
+                for tag in tags {
+                    if remove {
+                        meta.tags.retain(|t| *t != tag);
+                    } else if !meta.tags.contains(&tag) {
+                        meta.tags.push(tag);
+                    }
+                }
+            })?;
+            println!(
+                "🏷️  \"{}\": {}",
+                meta.display_title(),
+                if meta.tags.is_empty() {
+                    "no tags".to_string()
+                } else {
+                    meta.tags.join(", ")
+                }
+            );
+            Ok(())
+        }
+        crate::SessionCommands::Rate {
+            id,
+            rating,
+            message,
+            comment,
+        } => {
+            let rating: Rating = rating.parse()?;
+            let session = store.load(&id)?;
+            let index = match message {
+                Some(index) => index,
+                None => session
+                    .messages
+                    .iter()
+                    .rposition(|m| m.role == "assistant")
+                    .context("The session has no answer to rate")?,
+            };
+            let entry =
+                feedback::Feedback::new(session.id(), index, rating, "cli").with_comment(comment);
+            feedback::record(&config.workspace_dir, &entry)?;
+            let thumb = if rating == Rating::Up { "👍" } else { "👎" };
+            println!(
+                "{thumb} Rated message {index} of \"{}\"",
+                session.meta.display_title()
+            );
+            Ok(())
+        }
+        crate::SessionCommands::Dataset {
+            format,
+            since,
+            until,
+            tag,
+            rating,
+            keep_pii,
+            output,
+        } => {
+            let format: dataset::DatasetFormat = format.parse()?;
+            let filter = dataset::DatasetFilter {
+                since: since.as_deref().map(dataset::parse_date).transpose()?,
+                until: until.as_deref().map(dataset::parse_date).transpose()?,
+                tags: tag,
+                rating: rating.as_deref().map(str::parse).transpose()?,
+            };
+            let redactor = dataset::Redactor::from_config(config, !keep_pii);
+            let (jsonl, count) = dataset::export(&store.load_all()?, &filter, format, &redactor);
+            match output {
+                Some(path) => {
+                    fs::write(&path, jsonl)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!("✅ Exported {count} session(s) to {}", path.display());
+                }
+                None => print!("{jsonl}"),
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+    use async_trait::async_trait;
+    use tempfile::TempDir;
+
+    fn session_with_exchange() -> Session {
+        let mut session = Session::new("cli", "test-model");
+        session.push(ChatMessage::user("How do I rotate nginx logs daily?"));
+        session.push(ChatMessage::assistant("Use logrotate with a daily stanza."));
+        session
+    }
+
+    #[test]
+    fn clean_title_strips_labels_quotes_and_punctuation() {
+        assert_eq!(
+            clean_title("Title: \"Nginx log rotation.\"\nextra").as_deref(),
+            Some("Nginx log rotation")
+        );
+        assert_eq!(clean_title("  \n  "), None);
+        assert_eq!(
+            clean_title("**Rust lifetimes**").as_deref(),
+            Some("Rust lifetimes")
+        );
+    }
+
+    #[test]
+    fn clean_title_truncates_long_output() {
+        let long = "word ".repeat(40);
+        let title = clean_title(&long).unwrap();
+        assert!(title.chars().count() <= TITLE_MAX_CHARS + 3);
+    }
+
+    #[test]
+    fn first_exchange_requires_assistant_reply() {
+        let mut session = Session::new("cli", "m");
+        session.push(ChatMessage::user("hi"));
+        assert!(session.first_exchange().is_none());
+        session.push(ChatMessage::assistant("hello"));
+        assert_eq!(session.first_exchange(), Some(("hi", "hello")));
+    }
+
+    #[test]
+    fn branch_reruns_the_turn_containing_the_index() {
+        let mut session = session_with_exchange();
+        session.push(ChatMessage::user("And weekly?"));
+        session.push(ChatMessage::assistant("Use weekly instead."));
+
+        let (branch, rerun) = session.branch(3, "other-model").unwrap();
+        assert_eq!(rerun, "And weekly?");
+        assert_eq!(branch.messages.len(), 2);
+        assert_eq!(branch.meta.model, "other-model");
+        assert_ne!(branch.id(), session.id());
+        assert_eq!(
+            branch.meta.branch_of,
+            Some(BranchOrigin {
+                session_id: session.meta.id.clone(),
+                message_index: 3,
+            })
+        );
+        assert_eq!(session.messages.len(), 4);
+
+        let (branch, rerun) = session.branch(0, "m").unwrap();
+        assert_eq!(rerun, "How do I rotate nginx logs daily?");
+        assert!(branch.messages.is_empty());
+        assert!(session.branch(4, "m").is_err());
+    }
+
+    #[test]
+    fn store_roundtrip_and_list_order() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+
+        let mut older = session_with_exchange();
+        older.meta.updated_at = Utc::now() - chrono::Duration::hours(1);
+        let mut newer = Session::new("gateway", "m");
+        newer.meta.title = Some("Newer".into());
+        store.save(&older).unwrap();
+        store.save(&newer).unwrap();
+
+        let listed = store.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].id, newer.meta.id);
+        assert_eq!(listed[0].display_title(), "Newer");
+
+        let loaded = store.load(older.id()).unwrap();
+        assert_eq!(loaded.messages.len(), 2);
+        assert_eq!(loaded.meta.message_count, 2);
+    }
+
+    #[test]
+    fn load_accepts_unique_prefix_and_rejects_traversal() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        let session = session_with_exchange();
+        store.save(&session).unwrap();
+
+        let loaded = store.load(&session.id()[..8]).unwrap();
+        assert_eq!(loaded.id(), session.id());
+        assert!(store.load("../config").is_err());
+        assert!(store.load("does-not-exist").is_err());
+    }
+
+    #[test]
+    fn list_ignores_foreign_files() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        fs::create_dir_all(store.dir()).unwrap();
+        fs::write(store.dir().join("2024-01-01.md"), "notes").unwrap();
+        fs::write(store.dir().join("broken.json"), "{").unwrap();
+        assert!(store.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn markdown_export_uses_title() {
+        let mut session = session_with_exchange();
+        session.meta.title = Some("Nginx log rotation".into());
+        let md = session.to_markdown();
+        assert!(md.starts_with("# Nginx log rotation\n"));
+        assert!(md.contains("## User\n\nHow do I rotate nginx logs daily?"));
+        assert!(md.contains("## Assistant\n\nUse logrotate"));
+    }
+
+    #[tokio::test]
+    async fn ensure_title_uses_model_reply() {
+        let provider = ScriptedProvider::always("\"Nginx Log Rotation\"");
+        let mut session = session_with_exchange();
+        let set = ensure_title(&mut session, &provider, &SessionsConfig::default(), "m").await;
+        assert!(set);
+        assert_eq!(session.meta.title.as_deref(), Some("Nginx Log Rotation"));
+
+        // Titles are generated once.
+        let again = ensure_title(&mut session, &provider, &SessionsConfig::default(), "m").await;
+        assert!(!again);
+    }
+
+    #[tokio::test]
+    async fn ensure_title_falls_back_on_provider_error() {
+        let provider = ScriptedProvider::failing("boom");
+        let mut session = session_with_exchange();
+        ensure_title(&mut session, &provider, &SessionsConfig::default(), "m").await;
+        assert_eq!(
+            session.meta.title.as_deref(),
+            Some("How do I rotate nginx logs daily")
+        );
+    }
+
+    #[tokio::test]
+    async fn ensure_title_respects_config_toggle() {
+        let provider = ScriptedProvider::always("Ignored");
+        let config = SessionsConfig {
+            auto_title: false,
+            ..SessionsConfig::default()
+        };
+        let mut session = session_with_exchange();
+        assert!(!ensure_title(&mut session, &provider, &config, "m").await);
+        assert!(session.meta.title.is_none());
+    }
+
+    fn save_session(store: &SessionStore, title: &str, user: &str, reply: &str) -> Session {
+        let mut session = Session::new("cli", "m");
+        session.meta.title = Some(title.into());
+        session.push(ChatMessage::user(user));
+        session.push(ChatMessage::assistant(reply));
+        store.save(&session).unwrap();
+        session
+    }
+
+    #[test]
+    fn search_ranks_title_and_phrase_matches_first() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        let weak = save_session(
+            &store,
+            "Cluster upgrade",
+            "The ingress pod restarted during the upgrade",
+            "Check the kubernetes events.",
+        );
+        let strong = save_session(
+            &store,
+            "Kubernetes ingress TLS",
+            "My kubernetes ingress returns 404",
+            "Your ingress rules need a host.",
+        );
+        save_session(
+            &store,
+            "Pasta recipe",
+            "How long to boil?",
+            "About 9 minutes.",
+        );
+
+        let hits = store.search("Kubernetes ingress", 10).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].meta.id, strong.meta.id);
+        assert_eq!(hits[1].meta.id, weak.meta.id);
+        assert!(hits[0].score > hits[1].score);
+        assert_eq!(hits[0].snippets[0], "My kubernetes ingress returns 404");
+    }
+
+    #[test]
+    fn search_ignores_empty_queries_and_respects_limit() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        for i in 0..3 {
+            save_session(&store, &format!("Docker {i}"), "docker build", "ok");
+        }
+        assert!(store.search("  ", 10).unwrap().is_empty());
+        assert_eq!(store.search("docker", 2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn snippet_is_centred_and_marked_when_clipped() {
+        let content = format!("{} needle {}", "a ".repeat(100), "b ".repeat(100));
+        let lower = content.to_lowercase();
+        let snippet = snippet_around(&content, &lower, lower.find("needle").unwrap());
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+        assert!(snippet.chars().count() <= SNIPPET_CONTEXT_CHARS * 2 + 2);
+    }
+
+    /// Embeds text as [mentions of "cat", mentions of "dog"].
+    struct PetEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for PetEmbedding {
+        fn name(&self) -> &str {
+            "pets"
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    let t = t.to_lowercase();
+                    let cats = (t.matches("cat").count() + t.matches("kitten").count()) as f32;
+                    vec![cats + 0.01, t.matches("dog").count() as f32 + 0.01]
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn semantic_search_finds_related_sessions_without_shared_terms() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path());
+        let cat = save_session(&store, "Adopting a kitten", "kitten food?", "Wet food.");
+        save_session(
+            &store,
+            "Dog training",
+            "dog barks at night",
+            "Try crate training.",
+        );
+
+        let semantic = SemanticSearch {
+            embedder: Arc::new(PetEmbedding),
+            vector_weight: 0.7,
+            keyword_weight: 0.3,
+        };
+        assert!(store.search("cat", 10).unwrap().is_empty());
+        let hits = store.search_semantic("cat", &semantic, 1).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].meta.id, cat.meta.id);
+        assert_eq!(hits[0].snippets, vec!["kitten food?".to_string()]);
+    }
+}
@@ -0,0 +1,608 @@
+//! Skill distillation — mine saved session transcripts for procedures the
+//! agent keeps working out from scratch and draft them as `SKILL.md` files.
+//!
+//! Drafts land in `<workspace>/skill-drafts/<name>/SKILL.md`, outside the
+//! directory skills are loaded from, so nothing reaches the system prompt
+//! until a human runs `zeroclaw skills approve <name>`.
+
+use super::{load_skills, parse_frontmatter_list, skills_dir, split_frontmatter};
+use crate::config::Config;
+use crate::providers::{self, Provider};
+use crate::sessions::{Session, SessionStore};
+use crate::util::truncate_with_ellipsis;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Review directory for drafted skills, relative to the workspace.
+pub const DRAFTS_DIR: &str = "skill-drafts";
+
+/// Tracks which session revisions were already analyzed.
+const STATE_FILE: &str = ".distilled.json";
+
+/// Keep the analysis prompt bounded regardless of how much history exists.
+const MAX_SESSIONS_PER_RUN: usize = 40;
+const TRANSCRIPT_MAX_CHARS: usize = 3_000;
+const MESSAGE_MAX_CHARS: usize = 600;
+
+/// Final replies starting with one of these mark a session as unsuccessful.
+const FAILURE_PREFIXES: &[&str] = &[
+    "error",
+    "⚠",
+    "sorry, i couldn",
+    "i wasn't able",
+    "i was unable",
+];
+
+const DISTILL_SYSTEM_PROMPT: &str = "You review an AI agent's past conversations and find \
+procedures it carried out successfully more than once. For each recurring procedure, write a \
+reusable skill the agent can follow next time. Reply with a JSON array only, no prose. Each \
+element: {\"name\": kebab-case string, \"description\": one sentence, \"when_to_use\": one \
+sentence, \"steps\": [imperative strings], \"tags\": [strings], \"sessions\": [ids of the \
+sessions the procedure appears in]}. Skip one-off tasks, small talk, and anything covered by an \
+existing skill. Reply with [] when nothing qualifies.";
+
+/// A skill proposed by the distillation model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DraftSkill {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub when_to_use: String,
+    #[serde(default)]
+    pub steps: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Session ids the procedure was observed in
+    #[serde(default)]
+    pub sessions: Vec<String>,
+}
+
+impl DraftSkill {
+    /// Render as a SKILL.md with frontmatter the skill loader understands.
+    pub fn to_skill_md(&self) -> String {
+        let mut tags = vec!["distilled".to_string()];
+        tags.extend(self.tags.iter().filter(|t| *t != "distilled").cloned());
+
+        let mut md = String::from("---\n");
+        let _ = writeln!(md, "name: {}", self.name);
+        let _ = writeln!(md, "description: {}", self.description);
+        md.push_str("version: 0.1.0\n");
+        let _ = writeln!(md, "tags: [{}]", tags.join(", "));
+        let _ = writeln!(md, "sources: [{}]", self.sessions.join(", "));
+        md.push_str("status: draft\n---\n");
+        let _ = writeln!(md, "# {}\n\n{}\n", self.name, self.description);
+        if !self.when_to_use.is_empty() {
+            let _ = writeln!(md, "## When to use\n\n{}\n", self.when_to_use);
+        }
+        md.push_str("## Steps\n\n");
+        for (i, step) in self.steps.iter().enumerate() {
+            let _ = writeln!(md, "{}. {step}", i + 1);
+        }
+        md
+    }
+}
+
+/// Outcome of one distillation pass.
+#[derive(Debug, Default)]
+pub struct DistillReport {
+    pub sessions_analyzed: usize,
+    pub drafts: Vec<DraftSkill>,
+    pub written: Vec<PathBuf>,
+}
+
+pub fn drafts_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join(DRAFTS_DIR)
+}
+
+/// A session is worth learning from when it ends with a real answer.
+pub fn is_successful(session: &Session) -> bool {
+    let has_user = session.messages.iter().any(|m| m.role == "user");
+    let Some(last) = session.messages.last() else {
+        return false;
+    };
+    if !has_user || last.role != "assistant" || last.content.trim().is_empty() {
+        return false;
+    }
+    let reply = last.content.trim_start().to_lowercase();
+    !FAILURE_PREFIXES.iter().any(|p| reply.starts_with(p))
+}
+
+/// Reduce kebab-case names to a safe directory name.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for ch in name.trim().chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').chars().take(64).collect()
+}
+
+fn render_transcripts(sessions: &[Session]) -> String {
+    let mut out = String::new();
+    for session in sessions {
+        let mut transcript = String::new();
+        for message in &session.messages {
+            let _ = writeln!(
+                transcript,
+                "{}: {}",
+                message.role,
+                truncate_with_ellipsis(message.content.trim(), MESSAGE_MAX_CHARS)
+            );
+        }
+        let _ = writeln!(
+            out,
+            "### Session {} — {}\n{}\n",
+            session.id(),
+            session.meta.display_title(),
+            truncate_with_ellipsis(&transcript, TRANSCRIPT_MAX_CHARS)
+        );
+    }
+    out
+}
+
+/// Extract the JSON array from a model reply, tolerating code fences and preamble.
+pub fn parse_drafts(raw: &str) -> Result<Vec<DraftSkill>> {
+    let start = raw
+        .find('[')
+        .context("Distillation reply contains no JSON array")?;
+    let end = raw
+        .rfind(']')
+        .context("Distillation reply contains no JSON array")?;
+    anyhow::ensure!(end > start, "Distillation reply contains no JSON array");
+    serde_json::from_str(&raw[start..=end]).context("Distillation reply is not valid JSON")
+}
+
+/// Keep drafts that are well-formed, new, and backed by enough real sessions.
+fn validate_drafts(
+    drafts: Vec<DraftSkill>,
+    known_sessions: &HashSet<&str>,
+    existing_names: &HashSet<String>,
+    min_occurrences: usize,
+) -> Vec<DraftSkill> {
+    let mut seen = HashSet::new();
+    drafts
+        .into_iter()
+        .filter_map(|mut draft| {
+            draft.name = slugify(&draft.name);
+            draft
+                .sessions
+                .retain(|id| known_sessions.contains(id.as_str()));
+            draft.sessions.sort();
+            draft.sessions.dedup();
+            draft.steps.retain(|s| !s.trim().is_empty());
+
+            let valid = !draft.name.is_empty()
+                && !draft.steps.is_empty()
+                && draft.sessions.len() >= min_occurrences.max(1)
+                && !existing_names.contains(&draft.name)
+                && seen.insert(draft.name.clone());
+            valid.then_some(draft)
+        })
+        .collect()
+}
+
+/// Ask the model which procedures recur across `sessions`.
+async fn distill_sessions(
+    provider: &dyn Provider,
+    model: &str,
+    sessions: &[Session],
+    existing_names: &HashSet<String>,
+    min_occurrences: usize,
+) -> Result<Vec<DraftSkill>> {
+    if sessions.len() < min_occurrences.max(1) {
+        return Ok(Vec::new());
+    }
+
+    let mut existing: Vec<&str> = existing_names.iter().map(String::as_str).collect();
+    existing.sort_unstable();
+    let prompt = format!(
+        "A procedure must appear in at least {min_occurrences} sessions.\n\
+         Existing skills: {}\n\n{}",
+        if existing.is_empty() {
+            "(none)".to_string()
+        } else {
+            existing.join(", ")
+        },
+        render_transcripts(sessions)
+    );
+
+    let raw = provider
+        .chat_with_system(Some(DISTILL_SYSTEM_PROMPT), &prompt, model, 0.2)
+        .await?;
+    let known: HashSet<&str> = sessions.iter().map(Session::id).collect();
+    Ok(validate_drafts(
+        parse_drafts(&raw)?,
+        &known,
+        existing_names,
+        min_occurrences,
+    ))
+}
+
+/// Write drafts to the review directory, never overwriting an existing draft.
+pub fn write_drafts(workspace_dir: &Path, drafts: &[DraftSkill]) -> Result<Vec<PathBuf>> {
+    let dir = drafts_dir(workspace_dir);
+    let mut written = Vec::new();
+    for draft in drafts {
+        let skill_dir = dir.join(&draft.name);
+        if skill_dir.exists() {
+            continue;
+        }
+        std::fs::create_dir_all(&skill_dir)?;
+        let path = skill_dir.join("SKILL.md");
+        std::fs::write(&path, draft.to_skill_md())?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Names of installed skills and pending drafts.
+fn existing_skill_names(workspace_dir: &Path) -> HashSet<String> {
+    let mut names: HashSet<String> = load_skills(workspace_dir)
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    if let Ok(entries) = std::fs::read_dir(drafts_dir(workspace_dir)) {
+        names.extend(
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string)),
+        );
+    }
+    names
+}
+
+fn state_path(workspace_dir: &Path) -> PathBuf {
+    drafts_dir(workspace_dir).join(STATE_FILE)
+}
+
+/// Session id → `updated_at` of the revision last analyzed.
+fn load_state(workspace_dir: &Path) -> BTreeMap<String, DateTime<Utc>> {
+    std::fs::read_to_string(state_path(workspace_dir))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(workspace_dir: &Path, state: &BTreeMap<String, DateTime<Utc>>) -> Result<()> {
+    std::fs::create_dir_all(drafts_dir(workspace_dir))?;
+    std::fs::write(
+        state_path(workspace_dir),
+        serde_json::to_string_pretty(state)?,
+    )?;
+    Ok(())
+}
+
+/// Analyze recent successful sessions that changed since the last run and
+/// write any new drafts. With `dry_run`, nothing is written and the analysis
+/// state is left untouched.
+pub async fn run(config: &Config, lookback_days: u32, dry_run: bool) -> Result<DistillReport> {
+    let store = SessionStore::new(&config.workspace_dir);
+    let cutoff = Utc::now() - Duration::days(i64::from(lookback_days));
+    let mut state = load_state(&config.workspace_dir);
+
+    let sessions: Vec<Session> = store
+        .updated_since(cutoff)?
+        .into_iter()
+        .filter(|s| state.get(s.id()) != Some(&s.meta.updated_at))
+        .filter(is_successful)
+        .take(MAX_SESSIONS_PER_RUN)
+        .collect();
+
+    let mut report = DistillReport {
+        sessions_analyzed: sessions.len(),
+        ..DistillReport::default()
+    };
+    let min_occurrences = config.skill_distill.min_occurrences;
+    if sessions.len() < min_occurrences.max(1) {
+        return Ok(report);
+    }
+
+    let provider = providers::create_resilient_provider(
+        config.default_provider.as_deref().unwrap_or("openrouter"),
+        config.api_key.as_deref(),
+        &config.reliability,
+        &providers::ProviderSettings::from_config(config),
+    )?;
+    let model = config
+        .skill_distill
+        .model
+        .as_deref()
+        .or(config.default_model.as_deref())
+        .unwrap_or("anthropic/claude-sonnet-4-20250514");
+
+    let existing = existing_skill_names(&config.workspace_dir);
+    report.drafts = distill_sessions(
+        provider.as_ref(),
+        model,
+        &sessions,
+        &existing,
+        min_occurrences,
+    )
+    .await?;
+
+    if !dry_run {
+        report.written = write_drafts(&config.workspace_dir, &report.drafts)?;
+        for session in &sessions {
+            state.insert(session.id().to_string(), session.meta.updated_at);
+        }
+        save_state(&config.workspace_dir, &state)?;
+    }
+
+    Ok(report)
+}
+
+/// Handle `zeroclaw skills distill`.
+pub async fn handle_distill(config: &Config, lookback_days: u32, dry_run: bool) -> Result<()> {
+    let report = run(config, lookback_days, dry_run).await?;
+    println!(
+        "Analyzed {} new successful session(s) from the last {lookback_days} day(s).",
+        report.sessions_analyzed
+    );
+    if report.drafts.is_empty() {
+        println!("No recurring procedures found.");
+        return Ok(());
+    }
+
+    println!();
+    for draft in &report.drafts {
+        println!(
+            "  {} — {} {}",
+            console::style(&draft.name).white().bold(),
+            draft.description,
+            console::style(format!("({} sessions)", draft.sessions.len())).dim()
+        );
+    }
+    println!();
+    if dry_run {
+        println!("Dry run: no drafts written.");
+    } else {
+        println!(
+            "  {} {} draft(s) written to {}",
+            console::style("✓").green().bold(),
+            report.written.len(),
+            drafts_dir(&config.workspace_dir).display()
+        );
+        println!("  Review, then: zeroclaw skills approve <name>");
+    }
+    Ok(())
+}
+
+/// Scheduled distillation loop, supervised by the daemon.
+pub async fn run_worker(config: Config) -> Result<()> {
+    let hours = u64::from(config.skill_distill.interval_hours.max(1));
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(hours * 3600));
+
+    loop {
+        interval.tick().await;
+        match run(&config, config.skill_distill.lookback_days, false).await {
+            Ok(report) => {
+                crate::health::mark_component_ok("distill");
+                if !report.written.is_empty() {
+                    tracing::info!(
+                        "Drafted {} skill(s) from {} session(s) into {}",
+                        report.written.len(),
+                        report.sessions_analyzed,
+                        drafts_dir(&config.workspace_dir).display()
+                    );
+                }
+            }
+            Err(e) => {
+                crate::health::mark_component_error("distill", e.to_string());
+                tracing::warn!("Skill distillation failed: {e}");
+            }
+        }
+    }
+}
+
+/// Move a reviewed draft into the skills directory so it starts loading.
+pub fn approve_draft(workspace_dir: &Path, name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.contains("..") || name.contains('/') || name.contains('\\') {
+        anyhow::bail!("Invalid draft name: {name}");
+    }
+    let src = drafts_dir(workspace_dir).join(name);
+    if !src.join("SKILL.md").is_file() {
+        anyhow::bail!("Draft not found: {name}");
+    }
+    let dest = skills_dir(workspace_dir).join(name);
+    if dest.exists() {
+        anyhow::bail!("A skill named '{name}' is already installed");
+    }
+
+    std::fs::create_dir_all(skills_dir(workspace_dir))?;
+    std::fs::rename(&src, &dest)?;
+
+    let md_path = dest.join("SKILL.md");
+    let content = std::fs::read_to_string(&md_path)?;
+    let mut approved = String::new();
+    for line in content
+        .lines()
+        .filter(|line| line.trim() != "status: draft")
+    {
+        approved.push_str(line);
+        approved.push('\n');
+    }
+    std::fs::write(&md_path, approved)?;
+    Ok(dest)
+}
+
+/// Pending drafts as (name, description, source session count).
+pub fn list_drafts(workspace_dir: &Path) -> Vec<(String, String, usize)> {
+    let Ok(entries) = std::fs::read_dir(drafts_dir(workspace_dir)) else {
+        return Vec::new();
+    };
+    let mut drafts: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let content = std::fs::read_to_string(entry.path().join("SKILL.md")).ok()?;
+            let (fields, _) = split_frontmatter(&content);
+            let name = entry.file_name().to_str()?.to_string();
+            let description = fields.get("description").cloned().unwrap_or_default();
+            let sources = fields
+                .get("sources")
+                .map(|raw| parse_frontmatter_list(raw).len())
+                .unwrap_or_default();
+            Some((name, description, sources))
+        })
+        .collect();
+    drafts.sort();
+    drafts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+    use crate::providers::ChatMessage;
+
+    fn session(user: &str, reply: &str) -> Session {
+        let mut s = Session::new("cli", "test-model");
+        s.push(ChatMessage::user(user));
+        s.push(ChatMessage::assistant(reply));
+        s
+    }
+
+    fn draft_json(name: &str, sessions: &[&str]) -> String {
+        serde_json::json!([{
+            "name": name,
+            "description": "Deploy the app to staging",
+            "when_to_use": "The user asks for a staging deploy",
+            "steps": ["Run tests", "Build image", "Push to staging"],
+            "tags": ["deploy"],
+            "sessions": sessions,
+        }])
+        .to_string()
+    }
+
+    #[test]
+    fn successful_sessions_end_with_a_real_reply() {
+        assert!(is_successful(&session("deploy", "Deployed to staging.")));
+        assert!(!is_successful(&session("deploy", "Error: build failed")));
+        assert!(!is_successful(&session("deploy", "  ")));
+
+        let mut unanswered = Session::new("cli", "m");
+        unanswered.push(ChatMessage::user("hello?"));
+        assert!(!is_successful(&unanswered));
+    }
+
+    #[test]
+    fn slugify_produces_directory_safe_names() {
+        assert_eq!(slugify("Deploy To Staging!"), "deploy-to-staging");
+        assert_eq!(slugify("../../etc"), "etc");
+        assert_eq!(slugify("---"), "");
+    }
+
+    #[test]
+    fn parse_drafts_tolerates_code_fences() {
+        let raw = format!(
+            "Here you go:\n```json\n{}\n```",
+            draft_json("deploy", &["a"])
+        );
+        let drafts = parse_drafts(&raw).unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].steps.len(), 3);
+        assert!(parse_drafts("nothing to report").is_err());
+    }
+
+    #[test]
+    fn skill_md_round_trips_through_loader_frontmatter() {
+        let draft = parse_drafts(&draft_json("deploy-staging", &["s1", "s2"]))
+            .unwrap()
+            .remove(0);
+        let md = draft.to_skill_md();
+        let (fields, body) = split_frontmatter(&md);
+        assert_eq!(fields["description"], "Deploy the app to staging");
+        assert_eq!(fields["status"], "draft");
+        assert_eq!(
+            parse_frontmatter_list(&fields["tags"]),
+            vec!["distilled", "deploy"]
+        );
+        assert!(body.contains("## Steps\n\n1. Run tests\n2. Build image"));
+    }
+
+    #[tokio::test]
+    async fn drafts_need_enough_real_sessions() {
+        let sessions = vec![session("deploy", "done"), session("deploy again", "done")];
+        let ids: Vec<&str> = sessions.iter().map(Session::id).collect();
+
+        // Backed by both sessions → kept
+        let provider = ScriptedProvider::always(draft_json("Deploy Staging", &ids));
+        let drafts = distill_sessions(&provider, "m", &sessions, &HashSet::new(), 2)
+            .await
+            .unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].name, "deploy-staging");
+
+        // Hallucinated session ids don't count toward the threshold
+        let provider = ScriptedProvider::always(draft_json("deploy", &[ids[0], "made-up"]));
+        let drafts = distill_sessions(&provider, "m", &sessions, &HashSet::new(), 2)
+            .await
+            .unwrap();
+        assert!(drafts.is_empty());
+
+        // Existing skills are not re-drafted
+        let existing: HashSet<String> = ["deploy-staging".to_string()].into();
+        let provider = ScriptedProvider::always(draft_json("deploy-staging", &ids));
+        let drafts = distill_sessions(&provider, "m", &sessions, &existing, 2)
+            .await
+            .unwrap();
+        assert!(drafts.is_empty());
+    }
+
+    #[test]
+    fn write_list_and_approve_drafts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let draft = parse_drafts(&draft_json("deploy-staging", &["s1", "s2"]))
+            .unwrap()
+            .remove(0);
+
+        let written = write_drafts(tmp.path(), std::slice::from_ref(&draft)).unwrap();
+        assert_eq!(written.len(), 1);
+        // Existing drafts are never overwritten
+        assert!(write_drafts(tmp.path(), &[draft]).unwrap().is_empty());
+        // Drafts are not loaded as skills until approved
+        assert!(load_skills(tmp.path()).is_empty());
+
+        let drafts = list_drafts(tmp.path());
+        assert_eq!(
+            drafts,
+            vec![(
+                "deploy-staging".to_string(),
+                "Deploy the app to staging".to_string(),
+                2
+            )]
+        );
+
+        let dest = approve_draft(tmp.path(), "deploy-staging").unwrap();
+        assert!(dest.join("SKILL.md").is_file());
+        assert!(list_drafts(tmp.path()).is_empty());
+        let skills = load_skills(tmp.path());
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].description, "Deploy the app to staging");
+        assert!(!std::fs::read_to_string(dest.join("SKILL.md"))
+            .unwrap()
+            .contains("status: draft"));
+
+        assert!(approve_draft(tmp.path(), "../escape").is_err());
+        assert!(approve_draft(tmp.path(), "missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn run_without_sessions_is_a_no_op() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let report = run(&config, 7, false).await.unwrap();
+        assert_eq!(report.sessions_analyzed, 0);
+        assert!(report.written.is_empty());
+    }
+}
@@ -6,6 +6,13 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::{Duration, SystemTime};
 
+pub mod distill;
+pub mod package;
+pub mod registry;
+pub mod sandbox;
+
+pub use sandbox::SkillSandbox;
+
 const OPEN_SKILLS_REPO_URL: &str = "https://github.com/besoeasy/open-skills";
 const OPEN_SKILLS_SYNC_MARKER: &str = ".zeroclaw-open-skills-sync";
 const OPEN_SKILLS_SYNC_INTERVAL_SECS: u64 = 60 * 60 * 24 * 7;
@@ -26,10 +33,26 @@ pub struct Skill {
     pub tools: Vec<SkillTool>,
     #[serde(default)]
     pub prompts: Vec<String>,
+    #[serde(default)]
+    pub requires: SkillRequirements,
+    #[serde(default)]
+    pub sandbox: SkillSandbox,
     #[serde(skip)]
     pub location: Option<PathBuf>,
 }
 
+/// What a skill needs from the host, declared under `[requires]` in SKILL.toml.
+/// Skills with unmet requirements are left out of the system prompt.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillRequirements {
+    /// Agent tools the skill relies on (e.g. "shell", "browser")
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Environment variables that must be set, typically API keys
+    #[serde(default)]
+    pub env: Vec<String>,
+}
+
 /// A tool defined by a skill (shell command, HTTP call, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillTool {
@@ -51,6 +74,10 @@ struct SkillManifest {
     tools: Vec<SkillTool>,
     #[serde(default)]
     prompts: Vec<String>,
+    #[serde(default)]
+    requires: SkillRequirements,
+    #[serde(default)]
+    sandbox: SkillSandbox,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,7 +126,8 @@ fn load_skills_from_directory(skills_dir: &Path) -> Vec<Skill> {
 
     for entry in entries.flatten() {
         let path = entry.path();
-        if !path.is_dir() {
+        // Hidden directories include in-progress package installs
+        if !path.is_dir() || entry.file_name().to_string_lossy().starts_with('.') {
             continue;
         }
 
@@ -300,6 +328,8 @@ fn load_skill_toml(path: &Path) -> Result<Skill> {
         tags: manifest.skill.tags,
         tools: manifest.tools,
         prompts: manifest.prompts,
+        requires: manifest.requires,
+        sandbox: manifest.sandbox,
         location: Some(path.to_path_buf()),
     })
 }
@@ -313,18 +343,64 @@ fn load_skill_md(path: &Path, dir: &Path) -> Result<Skill> {
         .unwrap_or("unknown")
         .to_string();
 
+    let (frontmatter, body) = split_frontmatter(&content);
+
     Ok(Skill {
         name,
-        description: extract_description(&content),
-        version: "0.1.0".to_string(),
-        author: None,
-        tags: Vec::new(),
+        description: frontmatter
+            .get("description")
+            .cloned()
+            .unwrap_or_else(|| extract_description(body)),
+        version: frontmatter
+            .get("version")
+            .cloned()
+            .unwrap_or_else(default_version),
+        author: frontmatter.get("author").cloned(),
+        tags: frontmatter
+            .get("tags")
+            .map(|raw| parse_frontmatter_list(raw))
+            .unwrap_or_default(),
         tools: Vec::new(),
-        prompts: vec![content],
+        prompts: vec![body.to_string()],
+        requires: SkillRequirements::default(),
+        sandbox: SkillSandbox::default(),
         location: Some(path.to_path_buf()),
     })
 }
 
+/// Split optional `---` delimited `key: value` frontmatter from a SKILL.md body.
+pub(crate) fn split_frontmatter(content: &str) -> (HashMap<String, String>, &str) {
+    let mut fields = HashMap::new();
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return (fields, content);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (fields, content);
+    };
+
+    for line in rest[..end].lines() {
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim().trim_matches('"');
+            if !value.is_empty() {
+                fields.insert(key.trim().to_string(), value.to_string());
+            }
+        }
+    }
+
+    let body = rest[end + 4..].trim_start_matches(['\r', '\n']);
+    (fields, body)
+}
+
+/// Parse an inline frontmatter list such as `[a, b]` or `a, b`.
+pub(crate) fn parse_frontmatter_list(raw: &str) -> Vec<String> {
+    raw.trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|item| item.trim().trim_matches('"').to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
 fn load_open_skill_md(path: &Path) -> Result<Skill> {
     let content = std::fs::read_to_string(path)?;
     let name = path
@@ -341,6 +417,8 @@ fn load_open_skill_md(path: &Path) -> Result<Skill> {
         tags: vec!["open-skills".to_string()],
         tools: Vec::new(),
         prompts: vec![content],
+        requires: SkillRequirements::default(),
+        sandbox: SkillSandbox::default(),
         location: Some(path.to_path_buf()),
     })
 }
@@ -414,6 +492,9 @@ pub fn init_skills_dir(workspace_dir: &Path) -> Result<()> {
              version = \"0.1.0\"\n\
              author = \"your-name\"\n\
              tags = [\"productivity\", \"automation\"]\n\n\
+             [requires]\n\
+             tools = [\"shell\"]\n\
+             env = [\"MY_API_KEY\"]\n\n\
              [[tools]]\n\
              name = \"my_tool\"\n\
              description = \"What this tool does\"\n\
@@ -425,7 +506,7 @@ pub fn init_skills_dir(workspace_dir: &Path) -> Result<()> {
              The agent will read it and follow the instructions.\n\n\
              ## Installing community skills\n\n\
              ```bash\n\
-             zeroclaw skills install <github-url>\n\
+             zeroclaw skills install <github-url> [--ref v1.2.0]\n\
              zeroclaw skills list\n\
              ```\n",
         )?;
@@ -488,125 +569,93 @@ pub fn handle_command(command: crate::SkillCommands, workspace_dir: &Path) -> Re
                     if !skill.tags.is_empty() {
                         println!("    Tags:  {}", skill.tags.join(", "));
                     }
+                    if !skill.requires.tools.is_empty() || !skill.requires.env.is_empty() {
+                        let needs: Vec<String> = skill
+                            .requires
+                            .tools
+                            .iter()
+                            .map(|t| format!("tool {t}"))
+                            .chain(skill.requires.env.iter().map(|e| format!("env {e}")))
+                            .collect();
+                        println!("    Needs: {}", needs.join(", "));
+                    }
+                    if let Some(level) = skill.sandbox.max_autonomy {
+                        println!("    Caps autonomy at: {level:?}");
+                    }
                 }
             }
             println!();
             Ok(())
         }
-        crate::SkillCommands::Install { source } => {
+        crate::SkillCommands::Search { .. }
+        | crate::SkillCommands::Install { registry: true, .. } => {
+            anyhow::bail!("Registry commands must be handled in main.rs (requires async runtime)")
+        }
+        crate::SkillCommands::Install {
+            source, git_ref, ..
+        } => {
             println!("Installing skill from: {source}");
-
-            let skills_path = skills_dir(workspace_dir);
-            std::fs::create_dir_all(&skills_path)?;
-
-            if source.starts_with("https://") || source.starts_with("http://") {
-                // Git clone
-                let output = std::process::Command::new("git")
-                    .args(["clone", "--depth", "1", &source])
-                    .current_dir(&skills_path)
-                    .output()?;
-
-                if output.status.success() {
-                    println!(
-                        "  {} Skill installed successfully!",
-                        console::style("✓").green().bold()
-                    );
-                    println!("  Restart `zeroclaw channel start` to activate.");
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    anyhow::bail!("Git clone failed: {stderr}");
-                }
+            let skill = package::install(workspace_dir, &source, git_ref.as_deref())?;
+            println!(
+                "  {} Skill '{}' v{} {}",
+                console::style("✓").green().bold(),
+                skill.name,
+                skill.version,
+                if skill.linked { "linked" } else { "installed" }
+            );
+            if !skill.missing_env.is_empty() {
+                println!(
+                    "  {} Set {} before use; the skill stays disabled until then.",
+                    console::style("!").yellow().bold(),
+                    skill.missing_env.join(", ")
+                );
+            }
+            println!("  Restart `zeroclaw channel start` to activate.");
+            Ok(())
+        }
+        crate::SkillCommands::Distill { .. } => {
+            anyhow::bail!("Distill must be handled in main.rs (requires async runtime)")
+        }
+        crate::SkillCommands::Drafts => {
+            let drafts = distill::list_drafts(workspace_dir);
+            if drafts.is_empty() {
+                println!("No skill drafts awaiting review.");
+                println!();
+                println!("  Generate some: zeroclaw skills distill");
             } else {
-                // Local path — symlink or copy
-                let src = PathBuf::from(&source);
-                if !src.exists() {
-                    anyhow::bail!("Source path does not exist: {source}");
-                }
-                let name = src.file_name().unwrap_or_default();
-                let dest = skills_path.join(name);
-
-                #[cfg(unix)]
-                {
-                    std::os::unix::fs::symlink(&src, &dest)?;
-                    println!(
-                        "  {} Skill linked: {}",
-                        console::style("✓").green().bold(),
-                        dest.display()
-                    );
-                }
-                #[cfg(windows)]
-                {
-                    // On Windows, try symlink first (requires admin or developer mode),
-                    // fall back to directory junction, then copy
-                    use std::os::windows::fs::symlink_dir;
-                    if symlink_dir(&src, &dest).is_ok() {
-                        println!(
-                            "  {} Skill linked: {}",
-                            console::style("✓").green().bold(),
-                            dest.display()
-                        );
-                    } else {
-                        // Try junction as fallback (works without admin)
-                        let junction_result = std::process::Command::new("cmd")
-                            .args(["/C", "mklink", "/J"])
-                            .arg(&dest)
-                            .arg(&src)
-                            .output();
-
-                        if junction_result.is_ok() && junction_result.unwrap().status.success() {
-                            println!(
-                                "  {} Skill linked (junction): {}",
-                                console::style("✓").green().bold(),
-                                dest.display()
-                            );
-                        } else {
-                            // Final fallback: copy the directory
-                            copy_dir_recursive(&src, &dest)?;
-                            println!(
-                                "  {} Skill copied: {}",
-                                console::style("✓").green().bold(),
-                                dest.display()
-                            );
-                        }
-                    }
-                }
-                #[cfg(not(any(unix, windows)))]
-                {
-                    // On other platforms, copy the directory
-                    copy_dir_recursive(&src, &dest)?;
+                println!(
+                    "Skill drafts awaiting review ({}) in {}:",
+                    drafts.len(),
+                    distill::drafts_dir(workspace_dir).display()
+                );
+                println!();
+                for (name, description, sources) in &drafts {
                     println!(
-                        "  {} Skill copied: {}",
-                        console::style("✓").green().bold(),
-                        dest.display()
+                        "  {} — {description} {}",
+                        console::style(name).white().bold(),
+                        console::style(format!("({sources} sessions)")).dim()
                     );
                 }
+                println!();
+                println!("  Approve with: zeroclaw skills approve <name>");
             }
-
+            println!();
+            Ok(())
+        }
+        crate::SkillCommands::Approve { name } => {
+            let dest = distill::approve_draft(workspace_dir, &name)?;
+            println!(
+                "  {} Skill '{}' approved: {}",
+                console::style("✓").green().bold(),
+                name,
+                dest.display()
+            );
             Ok(())
         }
         crate::SkillCommands::Remove { name } => {
-            // Reject path traversal attempts
-            if name.contains("..") || name.contains('/') || name.contains('\\') {
-                anyhow::bail!("Invalid skill name: {name}");
-            }
-
-            let skill_path = skills_dir(workspace_dir).join(&name);
-
-            // Verify the resolved path is actually inside the skills directory
-            let canonical_skills = skills_dir(workspace_dir)
-                .canonicalize()
-                .unwrap_or_else(|_| skills_dir(workspace_dir));
-            if let Ok(canonical_skill) = skill_path.canonicalize() {
-                if !canonical_skill.starts_with(&canonical_skills) {
-                    anyhow::bail!("Skill path escapes skills directory: {name}");
-                }
-            }
-
-            if !skill_path.exists() {
+            if !package::uninstall(workspace_dir, &name)? {
                 anyhow::bail!("Skill not found: {name}");
             }
-
-            std::fs::remove_dir_all(&skill_path)?;
             println!(
                 "  {} Skill '{}' removed.",
                 console::style("✓").green().bold(),
@@ -697,6 +746,8 @@ command = "echo hello"
             tags: vec![],
             tools: vec![],
             prompts: vec!["Do the thing.".to_string()],
+            requires: SkillRequirements::default(),
+            sandbox: SkillSandbox::default(),
             location: None,
         }];
         let prompt = skills_to_prompt(&skills);
@@ -871,6 +922,38 @@ description = "Bare minimum"
         assert_eq!(skills[0].description, "No description");
     }
 
+    #[test]
+    fn md_skill_reads_frontmatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_dir = dir.path().join("skills").join("deploy");
+        fs::create_dir_all(&skill_dir).unwrap();
+
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\ndescription: Deploy to staging\nversion: 0.2.0\ntags: [ops, deploy]\n---\n# Deploy\n\n- step one\n",
+        )
+        .unwrap();
+
+        let skills = load_skills(dir.path());
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].description, "Deploy to staging");
+        assert_eq!(skills[0].version, "0.2.0");
+        assert_eq!(skills[0].tags, vec!["ops", "deploy"]);
+        assert!(skills[0].prompts[0].starts_with("# Deploy"));
+        assert!(skills[0].prompts[0].contains("- step one"));
+    }
+
+    #[test]
+    fn frontmatter_absent_leaves_content_untouched() {
+        let (fields, body) = split_frontmatter("# Title\nbody\n");
+        assert!(fields.is_empty());
+        assert_eq!(body, "# Title\nbody\n");
+
+        let (fields, body) = split_frontmatter("---\nunterminated: yes\n");
+        assert!(fields.is_empty());
+        assert!(body.starts_with("---"));
+    }
+
     #[test]
     fn skills_to_prompt_includes_tools() {
         let skills = vec![Skill {
@@ -887,6 +970,8 @@ description = "Bare minimum"
                 args: HashMap::new(),
             }],
             prompts: vec![],
+            requires: SkillRequirements::default(),
+            sandbox: SkillSandbox::default(),
             location: None,
         }];
         let prompt = skills_to_prompt(&skills);
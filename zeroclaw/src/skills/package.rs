@@ -0,0 +1,511 @@
+//! Installing, listing and removing skill packages.
+//!
+//! A package is a directory, usually a git repository, with a `SKILL.toml`
+//! or `SKILL.md` at its root. `SKILL.toml` can declare what the skill needs
+//! from the host:
+//!
+//! ```toml
+//! [skill]
+//! name = "github-triage"
+//! version = "1.2.0"
+//!
+//! [requires]
+//! tools = ["shell"]
+//! env = ["GITHUB_TOKEN"]
+//! ```
+//!
+//! Git installs record their source in `.zeroclaw-package.json` inside the
+//! skill directory; installing again from the same source upgrades in place.
+
+use super::{load_skill_md, load_skill_toml, skills_dir, Skill, SkillRequirements};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+const INSTALL_RECORD: &str = ".zeroclaw-package.json";
+
+/// Where a git-installed package came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallRecord {
+    pub source: String,
+    #[serde(default)]
+    pub git_ref: Option<String>,
+    /// Commit checked out at install time
+    #[serde(default)]
+    pub commit: Option<String>,
+    pub installed_at: String,
+}
+
+/// A skill in the workspace skills directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledSkill {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub requires: SkillRequirements,
+    /// Required environment variables that are currently unset
+    pub missing_env: Vec<String>,
+    /// Git URL, or the target of a linked local package
+    pub source: Option<String>,
+    pub commit: Option<String>,
+    pub installed_at: Option<String>,
+    /// Symlinked from a local path instead of copied in
+    pub linked: bool,
+}
+
+/// Install from a git URL (`https://`, `http://`, `git@`) or a local path.
+pub fn install(
+    workspace_dir: &Path,
+    source: &str,
+    git_ref: Option<&str>,
+) -> Result<InstalledSkill> {
+    if is_git_url(source) {
+        install_from_git(workspace_dir, source, git_ref)
+    } else if git_ref.is_some() {
+        anyhow::bail!("--ref only applies to git sources")
+    } else {
+        install_from_path(workspace_dir, Path::new(source))
+    }
+}
+
+/// Shallow-clone `url` (at `git_ref` when given) and install the package
+/// at its root. Reinstalling from the same URL replaces the old copy.
+pub fn install_from_git(
+    workspace_dir: &Path,
+    url: &str,
+    git_ref: Option<&str>,
+) -> Result<InstalledSkill> {
+    if !is_git_url(url) {
+        anyhow::bail!("Not a git URL: {url} (expected https://, http:// or git@)");
+    }
+    install_git(workspace_dir, url, git_ref)
+}
+
+fn install_git(workspace_dir: &Path, url: &str, git_ref: Option<&str>) -> Result<InstalledSkill> {
+    if git_ref.is_some_and(|r| r.is_empty() || r.starts_with('-')) {
+        anyhow::bail!("Invalid git ref: {}", git_ref.unwrap_or_default());
+    }
+
+    let skills_path = skills_dir(workspace_dir);
+    std::fs::create_dir_all(&skills_path)?;
+    // Hidden, so skill loading never picks up a half-installed package
+    let staging = skills_path.join(format!(".install-{}", uuid::Uuid::new_v4()));
+    let result = clone_into(&staging, url, git_ref).and_then(|commit| {
+        let skill = read_package(&staging, &repo_name(url))?;
+        let _ = std::fs::remove_dir_all(staging.join(".git"));
+        let record = InstallRecord {
+            source: url.to_string(),
+            git_ref: git_ref.map(String::from),
+            commit,
+            installed_at: Utc::now().to_rfc3339(),
+        };
+        let dest = skills_path.join(&skill.name);
+        if dest.symlink_metadata().is_ok() {
+            match read_record(&dest) {
+                Some(existing) if existing.source == record.source => remove_entry(&dest)?,
+                _ => anyhow::bail!(
+                    "Skill '{}' is already installed from another source; remove it first",
+                    skill.name
+                ),
+            }
+        }
+        std::fs::write(
+            staging.join(INSTALL_RECORD),
+            serde_json::to_vec_pretty(&record)?,
+        )?;
+        std::fs::rename(&staging, &dest)?;
+        describe(&dest, &skill.name)
+    });
+    if staging.exists() {
+        let _ = std::fs::remove_dir_all(&staging);
+    }
+    result
+}
+
+/// Link (or, where symlinks are unavailable, copy) a local package into the
+/// skills directory.
+pub fn install_from_path(workspace_dir: &Path, src: &Path) -> Result<InstalledSkill> {
+    let src = src
+        .canonicalize()
+        .with_context(|| format!("Source path does not exist: {}", src.display()))?;
+    let dir_name = src
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let skill = read_package(&src, &dir_name)?;
+
+    let skills_path = skills_dir(workspace_dir);
+    std::fs::create_dir_all(&skills_path)?;
+    let dest = skills_path.join(&skill.name);
+    if dest.symlink_metadata().is_ok() {
+        anyhow::bail!(
+            "Skill '{}' is already installed; remove it first",
+            skill.name
+        );
+    }
+    link_dir(&src, &dest)?;
+    describe(&dest, &skill.name)
+}
+
+/// Remove an installed skill; `false` if there was none by that name.
+/// Linked packages lose only the link.
+pub fn uninstall(workspace_dir: &Path, name: &str) -> Result<bool> {
+    if !is_valid_name(name) {
+        anyhow::bail!("Invalid skill name: {name}");
+    }
+    let path = skills_dir(workspace_dir).join(name);
+    if path.symlink_metadata().is_err() {
+        return Ok(false);
+    }
+    remove_entry(&path)?;
+    Ok(true)
+}
+
+/// Skills in the workspace skills directory, sorted by name. Open-skills
+/// entries are not installed packages and are not listed.
+pub fn list_installed(workspace_dir: &Path) -> Vec<InstalledSkill> {
+    let Ok(entries) = std::fs::read_dir(skills_dir(workspace_dir)) else {
+        return Vec::new();
+    };
+    let mut skills: Vec<InstalledSkill> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| {
+            let name = e.file_name().to_str()?.to_string();
+            if name.starts_with('.') {
+                return None;
+            }
+            describe(&e.path(), &name).ok()
+        })
+        .collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    skills
+}
+
+/// Requirements this process cannot meet: tools absent from `tool_names`
+/// and unset environment variables.
+pub fn unmet_requirements(skill: &Skill, tool_names: &[&str]) -> Vec<String> {
+    skill
+        .requires
+        .tools
+        .iter()
+        .filter(|t| !tool_names.contains(&t.as_str()))
+        .map(|t| format!("tool '{t}'"))
+        .chain(
+            missing_env(&skill.requires)
+                .into_iter()
+                .map(|e| format!("env {e}")),
+        )
+        .collect()
+}
+
+/// Drop skills whose requirements are unmet, logging what each is missing.
+pub fn retain_usable(skills: &mut Vec<Skill>, tool_names: &[&str]) {
+    skills.retain(|skill| {
+        let unmet = unmet_requirements(skill, tool_names);
+        if !unmet.is_empty() {
+            tracing::warn!(
+                skill = %skill.name,
+                "Skill disabled, missing {}",
+                unmet.join(", ")
+            );
+        }
+        unmet.is_empty()
+    });
+}
+
+fn missing_env(requires: &SkillRequirements) -> Vec<String> {
+    requires
+        .env
+        .iter()
+        .filter(|var| std::env::var_os(var).is_none_or(|v| v.is_empty()))
+        .cloned()
+        .collect()
+}
+
+fn is_git_url(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://") || source.starts_with("git@")
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Last path segment of a git URL without `.git`, naming `SKILL.md`-only
+/// packages.
+fn repo_name(url: &str) -> String {
+    let trimmed = url.trim_end_matches('/');
+    let last = trimmed.rsplit(['/', ':']).next().unwrap_or(trimmed);
+    last.trim_end_matches(".git").to_string()
+}
+
+/// Clone into `dest`; returns the checked-out commit when git reports it.
+fn clone_into(dest: &Path, url: &str, git_ref: Option<&str>) -> Result<Option<String>> {
+    let mut cmd = Command::new("git");
+    cmd.args(["clone", "--depth", "1"]);
+    if let Some(git_ref) = git_ref {
+        cmd.args(["--branch", git_ref]);
+    }
+    let output = cmd
+        .arg("--")
+        .arg(url)
+        .arg(dest)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Git clone failed: {}", stderr.trim());
+    }
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dest)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+    Ok(commit)
+}
+
+/// Parse the package manifest at the root of `dir`. `SKILL.md` packages
+/// take `fallback_name`; `SKILL.toml` packages name themselves.
+fn read_package(dir: &Path, fallback_name: &str) -> Result<Skill> {
+    let toml_path = dir.join("SKILL.toml");
+    let md_path = dir.join("SKILL.md");
+    let skill = if toml_path.is_file() {
+        load_skill_toml(&toml_path).context("Invalid SKILL.toml")?
+    } else if md_path.is_file() {
+        Skill {
+            name: fallback_name.to_string(),
+            ..load_skill_md(&md_path, dir)?
+        }
+    } else {
+        anyhow::bail!(
+            "Not a skill package: no SKILL.toml or SKILL.md in {}",
+            dir.display()
+        );
+    };
+    if !is_valid_name(&skill.name) {
+        anyhow::bail!(
+            "Invalid skill name '{}' (use letters, digits, '-', '_' and '.')",
+            skill.name
+        );
+    }
+    Ok(skill)
+}
+
+fn read_record(dir: &Path) -> Option<InstallRecord> {
+    let data = std::fs::read(dir.join(INSTALL_RECORD)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+fn describe(path: &Path, name: &str) -> Result<InstalledSkill> {
+    let skill = read_package(path, name)?;
+    let record = read_record(path);
+    let link_target = std::fs::read_link(path).ok();
+    Ok(InstalledSkill {
+        missing_env: missing_env(&skill.requires),
+        name: name.to_string(),
+        version: skill.version,
+        description: skill.description,
+        requires: skill.requires,
+        source: record
+            .as_ref()
+            .map(|r| r.source.clone())
+            .or_else(|| link_target.as_ref().map(|t| t.display().to_string())),
+        commit: record.as_ref().and_then(|r| r.commit.clone()),
+        installed_at: record.map(|r| r.installed_at),
+        linked: link_target.is_some(),
+    })
+}
+
+/// Remove a skill directory, or just the link for linked packages.
+fn remove_entry(path: &Path) -> Result<()> {
+    if path.symlink_metadata()?.file_type().is_symlink() {
+        std::fs::remove_file(path).or_else(|_| std::fs::remove_dir(path))?;
+    } else {
+        std::fs::remove_dir_all(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn link_dir(src: &Path, dest: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(src, dest)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn link_dir(src: &Path, dest: &Path) -> Result<()> {
+    // Symlinks need admin or developer mode; junctions work without either
+    if std::os::windows::fs::symlink_dir(src, dest).is_ok() {
+        return Ok(());
+    }
+    let junction = Command::new("cmd")
+        .args(["/C", "mklink", "/J"])
+        .arg(dest)
+        .arg(src)
+        .output();
+    if junction.is_ok_and(|o| o.status.success()) {
+        return Ok(());
+    }
+    super::copy_dir_recursive(src, dest)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn link_dir(src: &Path, dest: &Path) -> Result<()> {
+    super::copy_dir_recursive(src, dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_package(dir: &Path, name: &str, version: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("SKILL.toml"),
+            format!(
+                "[skill]\nname = \"{name}\"\ndescription = \"Test\"\nversion = \"{version}\"\n\n\
+                 [requires]\ntools = [\"shell\"]\nenv = [\"ZEROCLAW_TEST_UNSET_SKILL_VAR\"]\n"
+            ),
+        )
+        .unwrap();
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "-c",
+                "commit.gpgsign=false",
+            ])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(status.status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn git_install_records_source_and_upgrades_in_place() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        write_package(&repo, "triage", "1.0.0");
+        git(&repo, &["init", "-q"]);
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-qm", "v1"]);
+        let workspace = tmp.path().join("workspace");
+        // Public entry points only take network URLs
+        let url = format!("file://{}", repo.display());
+        assert!(install_from_git(&workspace, &url, None).is_err());
+
+        let installed = install_git(&workspace, &url, None).unwrap();
+        assert_eq!(
+            (installed.name.as_str(), installed.version.as_str()),
+            ("triage", "1.0.0")
+        );
+        assert_eq!(installed.source.as_deref(), Some(url.as_str()));
+        assert_eq!(installed.commit.as_deref().map(str::len), Some(40));
+        assert_eq!(installed.missing_env, ["ZEROCLAW_TEST_UNSET_SKILL_VAR"]);
+        assert!(!skills_dir(&workspace).join("triage/.git").exists());
+
+        write_package(&repo, "triage", "1.1.0");
+        git(&repo, &["commit", "-qam", "v2"]);
+        let upgraded = install_git(&workspace, &url, None).unwrap();
+        assert_eq!(upgraded.version, "1.1.0");
+        assert_eq!(list_installed(&workspace).len(), 1);
+
+        // A different source may not replace it, and nothing is left staged
+        let other = tmp.path().join("other");
+        write_package(&other, "triage", "9.0.0");
+        assert!(install_from_path(&workspace, &other).is_err());
+        let entries: Vec<_> = fs::read_dir(skills_dir(&workspace)).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn local_packages_are_linked_and_unlinked() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src-pkg");
+        write_package(&src, "local-tool", "0.2.0");
+        let workspace = tmp.path().join("workspace");
+
+        let installed = install_from_path(&workspace, &src).unwrap();
+        assert_eq!(installed.name, "local-tool");
+        assert_eq!(installed.linked, cfg!(unix));
+        assert!(install_from_path(&workspace, &src).is_err());
+
+        assert!(uninstall(&workspace, "local-tool").unwrap());
+        assert!(src.join("SKILL.toml").exists(), "removal keeps the source");
+        assert!(list_installed(&workspace).is_empty());
+    }
+
+    #[test]
+    fn lists_packages_with_install_records() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+        let dir = skills_dir(workspace).join("deploy");
+        write_package(&dir, "deploy", "2.1.0");
+        fs::write(
+            dir.join(INSTALL_RECORD),
+            r#"{"source":"https://example.com/deploy.git","commit":"abc","installed_at":"2026-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(skills_dir(workspace).join(".install-partial")).unwrap();
+
+        let listed = list_installed(workspace);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].version, "2.1.0");
+        assert_eq!(
+            listed[0].source.as_deref(),
+            Some("https://example.com/deploy.git")
+        );
+        assert_eq!(listed[0].requires.tools, ["shell"]);
+        assert!(!listed[0].linked);
+
+        assert!(uninstall(workspace, "../deploy").is_err());
+        assert!(uninstall(workspace, "deploy").unwrap());
+        assert!(!uninstall(workspace, "deploy").unwrap());
+    }
+
+    #[test]
+    fn unmet_requirements_name_tools_and_env() {
+        let tmp = TempDir::new().unwrap();
+        write_package(tmp.path(), "needs", "0.1.0");
+        let skill = read_package(tmp.path(), "unused").unwrap();
+
+        assert_eq!(
+            unmet_requirements(&skill, &["file_read"]),
+            ["tool 'shell'", "env ZEROCLAW_TEST_UNSET_SKILL_VAR"]
+        );
+        let mut skills = vec![skill];
+        retain_usable(&mut skills, &["shell"]);
+        assert!(skills.is_empty());
+    }
+
+    #[test]
+    fn repo_names_and_sources() {
+        assert_eq!(repo_name("https://github.com/a/pdf-tools.git"), "pdf-tools");
+        assert_eq!(repo_name("git@github.com:a/notes/"), "notes");
+        assert!(is_git_url("git@github.com:a/b"));
+        assert!(!is_git_url("./local/skill"));
+        assert!(!is_valid_name(".hidden"));
+        assert!(!is_valid_name("a/b"));
+    }
+}
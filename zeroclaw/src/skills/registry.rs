@@ -0,0 +1,290 @@
+//! Skill registry client: search and install packages from a signed index.
+//!
+//! `[skill_registry] index_url` points at a JSON document:
+//!
+//! ```json
+//! {"skills": [{"name": "pdf-tools", "version": "1.2.0", "description": "...",
+//!              "source": "https://github.com/org/pdf-tools", "ref": "v1.2.0",
+//!              "commit": "3f2a...", "tags": ["documents"]}]}
+//! ```
+//!
+//! A detached Ed25519 signature over the exact index bytes (hex or base64)
+//! is fetched from `<index_url>.sig` and checked against `public_key` before
+//! the index is parsed. An entry's `commit`, when present, must match what
+//! the clone checks out, so a signed index pins package contents too.
+
+use super::package::{self, InstalledSkill};
+use crate::config::SkillRegistryConfig;
+use anyhow::{Context, Result};
+use base64::Engine;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+const FETCH_TIMEOUT_SECS: u64 = 30;
+
+/// One installable package in the index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Git URL of the package
+    pub source: String,
+    #[serde(default, rename = "ref")]
+    pub git_ref: Option<String>,
+    /// Expected commit after checking out `ref`
+    #[serde(default)]
+    pub commit: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RegistryIndex {
+    skills: Vec<RegistryEntry>,
+}
+
+pub struct RegistryClient {
+    index_url: String,
+    /// None only with `allow_unsigned`
+    public_key: Option<Vec<u8>>,
+    client: reqwest::Client,
+}
+
+impl RegistryClient {
+    pub fn from_config(config: &SkillRegistryConfig) -> Result<Self> {
+        let index_url = config
+            .index_url
+            .clone()
+            .filter(|u| !u.trim().is_empty())
+            .context("No skill registry configured (set [skill_registry] index_url)")?;
+        let public_key = match config.public_key.as_deref() {
+            Some(key) => Some(
+                hex::decode(key.trim())
+                    .ok()
+                    .filter(|k| k.len() == 32)
+                    .context("[skill_registry] public_key must be a 32-byte hex Ed25519 key")?,
+            ),
+            None if config.allow_unsigned => None,
+            None => anyhow::bail!(
+                "[skill_registry] public_key is required (or set allow_unsigned = true)"
+            ),
+        };
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+            .build()?;
+        Ok(Self {
+            index_url,
+            public_key,
+            client,
+        })
+    }
+
+    /// Download and verify the index.
+    pub async fn fetch_index(&self) -> Result<Vec<RegistryEntry>> {
+        let index = self.get(&self.index_url).await?;
+        if let Some(key) = &self.public_key {
+            let signature = self.get(&format!("{}.sig", self.index_url)).await?;
+            verify_index(&index, &String::from_utf8_lossy(&signature), key)?;
+        } else {
+            tracing::warn!("Skill registry index is not signature-checked");
+        }
+        parse_index(&index)
+    }
+
+    /// Entries whose name, description or tags contain `query`
+    /// (case-insensitive); an empty query lists everything.
+    pub async fn search(&self, query: &str) -> Result<Vec<RegistryEntry>> {
+        Ok(filter_entries(self.fetch_index().await?, query))
+    }
+
+    /// Install the package named `name` into the workspace.
+    pub async fn install(&self, workspace_dir: &Path, name: &str) -> Result<InstalledSkill> {
+        let entry = self
+            .fetch_index()
+            .await?
+            .into_iter()
+            .find(|e| e.name == name)
+            .with_context(|| format!("Skill '{name}' is not in the registry"))?;
+        let workspace_dir = workspace_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || install_entry(&workspace_dir, &entry)).await?
+    }
+
+    async fn get(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {url}"))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Fetching {url} failed: HTTP {}", response.status());
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+/// Check a detached Ed25519 signature (hex or base64 text) over `index`.
+pub fn verify_index(index: &[u8], signature: &str, public_key: &[u8]) -> Result<()> {
+    let signature = signature.trim();
+    let bytes = hex::decode(signature)
+        .or_else(|_| base64::engine::general_purpose::STANDARD.decode(signature))
+        .context("Registry signature is neither hex nor base64")?;
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(index, &bytes)
+        .map_err(|_| anyhow::anyhow!("Skill registry index signature is invalid"))
+}
+
+fn parse_index(bytes: &[u8]) -> Result<Vec<RegistryEntry>> {
+    let index: RegistryIndex =
+        serde_json::from_slice(bytes).context("Skill registry index is not valid JSON")?;
+    Ok(index.skills)
+}
+
+fn filter_entries(entries: Vec<RegistryEntry>, query: &str) -> Vec<RegistryEntry> {
+    let query = query.trim().to_lowercase();
+    entries
+        .into_iter()
+        .filter(|e| {
+            query.is_empty()
+                || e.name.to_lowercase().contains(&query)
+                || e.description.to_lowercase().contains(&query)
+                || e.tags.iter().any(|t| t.to_lowercase() == query)
+        })
+        .collect()
+}
+
+/// Clone an entry and reject it (removing the install) if the package
+/// is not what the index promised.
+fn install_entry(workspace_dir: &Path, entry: &RegistryEntry) -> Result<InstalledSkill> {
+    let installed =
+        package::install_from_git(workspace_dir, &entry.source, entry.git_ref.as_deref())?;
+    let mismatch = if installed.name != entry.name {
+        Some(format!("package is named '{}'", installed.name))
+    } else if entry
+        .commit
+        .as_ref()
+        .is_some_and(|c| installed.commit.as_ref() != Some(c))
+    {
+        Some(format!(
+            "checked out {} instead of the pinned commit",
+            installed.commit.as_deref().unwrap_or("an unknown commit")
+        ))
+    } else {
+        None
+    };
+    if let Some(reason) = mismatch {
+        let _ = package::uninstall(workspace_dir, &installed.name);
+        anyhow::bail!("Registry entry '{}' rejected: {reason}", entry.name);
+    }
+    Ok(installed)
+}
+
+/// `zeroclaw skills search <query>`
+pub async fn handle_search(config: &SkillRegistryConfig, query: &str) -> Result<()> {
+    let entries = RegistryClient::from_config(config)?.search(query).await?;
+    if entries.is_empty() {
+        println!("No registry skills match '{query}'.");
+        return Ok(());
+    }
+    for entry in &entries {
+        println!(
+            "  {} {} — {}",
+            console::style(&entry.name).white().bold(),
+            console::style(format!("v{}", entry.version)).dim(),
+            entry.description
+        );
+    }
+    println!();
+    println!("  Install with: zeroclaw skills install --registry <name>");
+    Ok(())
+}
+
+/// `zeroclaw skills install --registry <name>`
+pub async fn handle_install(
+    config: &SkillRegistryConfig,
+    workspace_dir: &Path,
+    name: &str,
+) -> Result<()> {
+    let skill = RegistryClient::from_config(config)?
+        .install(workspace_dir, name)
+        .await?;
+    println!(
+        "  {} Skill '{}' v{} installed from the registry",
+        console::style("✓").green().bold(),
+        skill.name,
+        skill.version
+    );
+    if !skill.missing_env.is_empty() {
+        println!(
+            "  {} Set {} before use; the skill stays disabled until then.",
+            console::style("!").yellow().bold(),
+            skill.missing_env.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    const INDEX: &str = r#"{"skills": [
+        {"name": "pdf-tools", "version": "1.2.0", "description": "Split and merge PDFs",
+         "source": "https://example.com/pdf-tools.git", "ref": "v1.2.0", "tags": ["documents"]},
+        {"name": "deploy", "version": "0.3.0", "source": "https://example.com/deploy.git"}
+    ]}"#;
+
+    #[test]
+    fn verifies_signatures_in_hex_and_base64() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public = pair.public_key().as_ref();
+        let signature = pair.sign(INDEX.as_bytes());
+
+        verify_index(INDEX.as_bytes(), &hex::encode(signature.as_ref()), public).unwrap();
+        let b64 = base64::engine::general_purpose::STANDARD.encode(signature.as_ref());
+        verify_index(INDEX.as_bytes(), &format!("{b64}\n"), public).unwrap();
+
+        let tampered = INDEX.replace("1.2.0", "6.6.6");
+        assert!(verify_index(tampered.as_bytes(), &b64, public).is_err());
+        assert!(verify_index(INDEX.as_bytes(), "not a signature!", public).is_err());
+    }
+
+    #[test]
+    fn search_matches_names_descriptions_and_tags() {
+        let entries = parse_index(INDEX.as_bytes()).unwrap();
+        assert_eq!(entries[0].git_ref.as_deref(), Some("v1.2.0"));
+        let names = |q: &str| -> Vec<String> {
+            filter_entries(entries.clone(), q)
+                .into_iter()
+                .map(|e| e.name)
+                .collect()
+        };
+        assert_eq!(names("PDF"), ["pdf-tools"]);
+        assert_eq!(names("merge"), ["pdf-tools"]);
+        assert_eq!(names("documents"), ["pdf-tools"]);
+        assert_eq!(names("").len(), 2);
+        assert!(names("kubernetes").is_empty());
+    }
+
+    #[test]
+    fn config_requires_url_and_key() {
+        let mut config = SkillRegistryConfig::default();
+        assert!(RegistryClient::from_config(&config).is_err());
+        config.index_url = Some("https://example.com/index.json".into());
+        assert!(RegistryClient::from_config(&config).is_err());
+        config.public_key = Some("abcd".into());
+        assert!(RegistryClient::from_config(&config).is_err());
+        config.public_key = Some("11".repeat(32));
+        assert!(RegistryClient::from_config(&config).is_ok());
+        config.public_key = None;
+        config.allow_unsigned = true;
+        assert!(RegistryClient::from_config(&config).is_ok());
+    }
+}
@@ -0,0 +1,148 @@
+//! Scope an agent run to a single skill.
+//!
+//! `zeroclaw agent --skill <name>` activates one skill: only its prompt is
+//! injected, the tool registry shrinks to the tools listed under
+//! `[requires] tools`, and `[sandbox] max_autonomy` caps the autonomy level:
+//!
+//! ```toml
+//! [requires]
+//! tools = ["shell", "file_read"]
+//!
+//! [sandbox]
+//! max_autonomy = "supervised"
+//! ```
+//!
+//! A skill that declares no tools runs with none.
+
+use super::{load_skills, package, Skill};
+use crate::config::AutonomyConfig;
+use crate::security::AutonomyLevel;
+use crate::tools::Tool;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Limits applied while the skill is active, from `[sandbox]` in SKILL.toml.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillSandbox {
+    /// Highest autonomy level the skill may run with
+    #[serde(default)]
+    pub max_autonomy: Option<AutonomyLevel>,
+}
+
+/// Load the skill `name` and lower `autonomy` to its sandbox cap.
+pub fn activate(workspace_dir: &Path, name: &str, autonomy: &mut AutonomyConfig) -> Result<Skill> {
+    let skill = load_skills(workspace_dir)
+        .into_iter()
+        .find(|s| s.name == name)
+        .with_context(|| format!("Skill '{name}' is not installed"))?;
+    if let Some(cap) = skill.sandbox.max_autonomy {
+        autonomy.level = autonomy.level.min(cap);
+    }
+    Ok(skill)
+}
+
+/// Drop every tool the skill does not declare. Fails when a declared tool
+/// is unavailable or a required env var is unset.
+pub fn restrict_tools(skill: &Skill, tools: &mut Vec<Box<dyn Tool>>) -> Result<()> {
+    tools.retain(|t| skill.requires.tools.iter().any(|name| name == t.name()));
+    let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+    let unmet = package::unmet_requirements(skill, &names);
+    if !unmet.is_empty() {
+        anyhow::bail!(
+            "Skill '{}' cannot run, missing {}",
+            skill.name,
+            unmet.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::SecurityPolicy;
+    use crate::tools::{CalculatorTool, ImageInfoTool};
+    use std::sync::Arc;
+
+    fn write_skill(workspace: &Path, manifest: &str) {
+        let dir = workspace.join("skills").join("reader");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("SKILL.toml"), manifest).unwrap();
+    }
+
+    #[test]
+    fn activate_caps_autonomy() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(
+            tmp.path(),
+            "[skill]\nname = \"reader\"\ndescription = \"Reads\"\n\n\
+             [requires]\ntools = [\"calculator\"]\n\n[sandbox]\nmax_autonomy = \"readonly\"\n",
+        );
+
+        let mut autonomy = AutonomyConfig {
+            level: AutonomyLevel::Full,
+            ..AutonomyConfig::default()
+        };
+        let skill = activate(tmp.path(), "reader", &mut autonomy).unwrap();
+        assert_eq!(skill.requires.tools, ["calculator"]);
+        assert_eq!(autonomy.level, AutonomyLevel::ReadOnly);
+
+        assert!(activate(tmp.path(), "writer", &mut autonomy).is_err());
+    }
+
+    #[test]
+    fn cap_never_raises_autonomy() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_skill(
+            tmp.path(),
+            "[skill]\nname = \"reader\"\ndescription = \"Reads\"\n\n[sandbox]\nmax_autonomy = \"full\"\n",
+        );
+
+        let mut autonomy = AutonomyConfig {
+            level: AutonomyLevel::Supervised,
+            ..AutonomyConfig::default()
+        };
+        activate(tmp.path(), "reader", &mut autonomy).unwrap();
+        assert_eq!(autonomy.level, AutonomyLevel::Supervised);
+    }
+
+    #[test]
+    fn restrict_tools_keeps_only_declared_tools() {
+        let security = Arc::new(SecurityPolicy::default());
+        let all = || -> Vec<Box<dyn Tool>> {
+            vec![
+                Box::new(CalculatorTool::new()),
+                Box::new(ImageInfoTool::new(security.clone())),
+            ]
+        };
+        let mut skill = crate::skills::Skill {
+            name: "math".into(),
+            description: String::new(),
+            version: "0.1.0".into(),
+            author: None,
+            tags: vec![],
+            tools: vec![],
+            prompts: vec![],
+            requires: crate::skills::SkillRequirements {
+                tools: vec!["calculator".into()],
+                env: vec![],
+            },
+            sandbox: SkillSandbox::default(),
+            location: None,
+        };
+
+        let mut tools = all();
+        restrict_tools(&skill, &mut tools).unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, ["calculator"]);
+
+        skill.requires.tools.push("shell".into());
+        assert!(restrict_tools(&skill, &mut all()).is_err());
+
+        skill.requires.tools.clear();
+        let mut tools = all();
+        restrict_tools(&skill, &mut tools).unwrap();
+        assert!(tools.is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+//! Token counts per model family.
+//!
+//! No vocabulary files are bundled, so counts are estimates. The tiktoken
+//! families split text the way tiktoken's pre-tokenizer does (contractions,
+//! words with their leading space or punctuation, digit groups of up to
+//! three, punctuation runs, whitespace) and charge each piece by its length,
+//! which is what a BPE merge over a large vocabulary mostly comes down to.
+//! Claude's tokenizer is not public; it is approximated from the `cl100k`
+//! count. Unlike a characters/4 rule this stays close on code, numbers and
+//! non-Latin scripts.
+
+use crate::config::ModelProfileConfig;
+use crate::providers::ChatMessage;
+
+/// Tokens added per message for role and separators, as in `OpenAI`'s
+/// chat format accounting.
+const MESSAGE_OVERHEAD: u64 = 3;
+
+/// Tokens that prime the assistant's reply.
+const REPLY_PRIMING: u64 = 3;
+
+/// Context window assumed for models not known to have a larger one.
+const DEFAULT_CONTEXT_WINDOW: u64 = 128_000;
+
+/// Tokenizer family a model belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Family {
+    /// `cl100k_base`: GPT-4, GPT-3.5 and the default for unknown models
+    #[default]
+    Cl100k,
+    /// `o200k_base`: GPT-4o, GPT-4.1, GPT-5 and the o-series
+    O200k,
+    /// Anthropic Claude models
+    Claude,
+}
+
+impl Family {
+    /// Family for a model name, with or without a `provider/` prefix.
+    pub fn for_model(model: &str) -> Self {
+        let model = model.to_ascii_lowercase();
+        let name = model.rsplit('/').next().unwrap_or_default();
+        if name.contains("claude") {
+            Self::Claude
+        } else if name.starts_with("gpt-4o")
+            || name.starts_with("gpt-4.1")
+            || name.starts_with("gpt-5")
+            || name.starts_with("chatgpt-4o")
+            || ["o1", "o3", "o4"].iter().any(|o| {
+                name.strip_prefix(o)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+            })
+        {
+            Self::O200k
+        } else {
+            Self::Cl100k
+        }
+    }
+
+    /// Estimated tokens in `text`.
+    pub fn count(self, text: &str) -> u64 {
+        match self {
+            Self::Cl100k => count_pieces(text, 6, 1),
+            Self::O200k => count_pieces(text, 7, 2),
+            // Claude's vocabulary yields roughly 15% more tokens than cl100k
+            Self::Claude => (count_pieces(text, 6, 1) * 23).div_ceil(20),
+        }
+    }
+
+    /// Estimated tokens one message adds to a prompt.
+    pub fn count_message(self, message: &ChatMessage) -> u64 {
+        self.count(&message.content) + MESSAGE_OVERHEAD
+    }
+
+    /// Estimated prompt tokens for a conversation, including per-message
+    /// overhead.
+    pub fn count_messages(self, messages: &[ChatMessage]) -> u64 {
+        if messages.is_empty() {
+            return 0;
+        }
+        messages.iter().map(|m| self.count_message(m)).sum::<u64>() + REPLY_PRIMING
+    }
+}
+
+/// Estimated tokens in `text` for `model`.
+pub fn count(model: &str, text: &str) -> u64 {
+    Family::for_model(model).count(text)
+}
+
+/// Estimated prompt tokens for `messages` sent to `model`.
+pub fn count_messages(model: &str, messages: &[ChatMessage]) -> u64 {
+    Family::for_model(model).count_messages(messages)
+}
+
+/// Cost in USD of sending `input` and receiving `output` tokens from
+/// `model`, when its profile in `profiles` sets prices (USD per million
+/// tokens).
+#[allow(clippy::cast_precision_loss)]
+pub fn cost_usd(
+    profiles: &[ModelProfileConfig],
+    model: &str,
+    input: u64,
+    output: u64,
+) -> Option<f64> {
+    let profile = profiles.iter().find(|p| p.matches(model))?;
+    let (input_price, output_price) = profile.input_price.zip(profile.output_price)?;
+    Some((input as f64 * input_price + output as f64 * output_price) / 1_000_000.0)
+}
+
+/// Context window of `model` in tokens. Only models known to exceed the
+/// default are listed.
+pub fn context_window(model: &str) -> u64 {
+    let model = model.to_ascii_lowercase();
+    let name = model.rsplit('/').next().unwrap_or_default();
+    if name.starts_with("gemini-1.5-pro") {
+        2_097_152
+    } else if name.starts_with("gemini-1.5") || name.starts_with("gemini-2") {
+        1_048_576
+    } else {
+        DEFAULT_CONTEXT_WINDOW
+    }
+}
+
+/// Walk `text` in tiktoken pre-tokenizer pieces. Words cost one token per
+/// `letters_per_token` ASCII letters (at least one); other letters (CJK,
+/// Cyrillic, ...) cost one per `wide_per_token`.
+fn count_pieces(text: &str, letters_per_token: u64, wide_per_token: u64) -> u64 {
+    let chars: Vec<char> = text.chars().collect();
+    let is_letter = |i: usize| chars.get(i).is_some_and(|c| c.is_alphabetic());
+    let is_symbol = |i: usize| {
+        chars
+            .get(i)
+            .is_some_and(|c| !c.is_whitespace() && !c.is_alphanumeric())
+    };
+    let mut tokens = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        // 's 't 're 've 'm 'll 'd
+        if c == '\'' {
+            let next: String = chars[i + 1..chars.len().min(i + 3)]
+                .iter()
+                .collect::<String>()
+                .to_ascii_lowercase();
+            let len = if ["re", "ve", "ll"].iter().any(|s| next.starts_with(s)) {
+                3
+            } else if next.starts_with(['s', 't', 'm', 'd']) {
+                2
+            } else {
+                0
+            };
+            if len > 0 {
+                tokens += 1;
+                i += len;
+                continue;
+            }
+        }
+
+        // A word, with at most one leading non-letter (usually a space)
+        if c.is_alphabetic() || (!c.is_alphanumeric() && c != '\n' && c != '\r' && is_letter(i + 1))
+        {
+            let mut j = if c.is_alphabetic() { i } else { i + 1 };
+            let (mut ascii, mut wide) = (0, 0);
+            while j < chars.len() && chars[j].is_alphabetic() {
+                if chars[j].is_ascii() {
+                    ascii += 1;
+                } else {
+                    wide += 1;
+                }
+                j += 1;
+            }
+            let ascii_tokens = if ascii > 0 {
+                1 + (ascii - 1) / letters_per_token
+            } else {
+                0
+            };
+            tokens += (ascii_tokens + u64::div_ceil(wide, wide_per_token)).max(1);
+            i = j;
+            continue;
+        }
+
+        // Digits in groups of up to three
+        if c.is_numeric() {
+            let mut j = i;
+            while j < chars.len() && chars[j].is_numeric() {
+                j += 1;
+            }
+            tokens += ((j - i) as u64).div_ceil(3);
+            i = j;
+            continue;
+        }
+
+        // Punctuation runs, with an optional leading space and trailing newlines
+        if is_symbol(i) || (c == ' ' && is_symbol(i + 1)) {
+            let mut j = if c == ' ' { i + 1 } else { i };
+            let (mut ascii, mut other) = (0, 0);
+            while is_symbol(j) {
+                if chars[j].is_ascii() {
+                    ascii += 1;
+                } else {
+                    other += 1;
+                }
+                j += 1;
+            }
+            while j < chars.len() && matches!(chars[j], '\n' | '\r') {
+                j += 1;
+            }
+            tokens += u64::div_ceil(ascii, 2) + other;
+            i = j;
+            continue;
+        }
+
+        // Whitespace; a final space before a word or symbol belongs to it
+        let mut j = i;
+        while j < chars.len() && chars[j].is_whitespace() {
+            j += 1;
+        }
+        tokens += 1;
+        i = if j - i > 1 && j < chars.len() && chars[j - 1] == ' ' && !chars[j].is_numeric() {
+            j - 1
+        } else {
+            j
+        };
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn models_map_to_families() {
+        assert_eq!(Family::for_model("gpt-4"), Family::Cl100k);
+        assert_eq!(Family::for_model("openai/gpt-4o-mini"), Family::O200k);
+        assert_eq!(Family::for_model("o3-mini"), Family::O200k);
+        assert_eq!(Family::for_model("ollama-model"), Family::Cl100k);
+        assert_eq!(
+            Family::for_model("anthropic/claude-sonnet-4-20250514"),
+            Family::Claude
+        );
+        assert_eq!(Family::for_model("llama3.1:8b"), Family::Cl100k);
+    }
+
+    #[test]
+    fn english_prose_matches_tiktoken_counts() {
+        // cl100k_base gives 10 tokens for this sentence
+        let text = "The quick brown fox jumps over the lazy dog.";
+        assert_eq!(Family::Cl100k.count(text), 10);
+        assert_eq!(Family::Cl100k.count("Hello, world!"), 4);
+        assert_eq!(Family::Cl100k.count("I'm sure it's fine"), 6);
+        assert_eq!(Family::Cl100k.count(""), 0);
+    }
+
+    #[test]
+    fn code_and_numbers_cost_more_than_a_length_rule_suggests() {
+        let code = "fn main() {\n    println!(\"{}\", 1234567);\n}\n";
+        let tokens = Family::Cl100k.count(code);
+        let by_length = (code.chars().count() as u64).div_ceil(4);
+        assert!(tokens > by_length, "tokens={tokens} by_length={by_length}");
+        assert_eq!(Family::Cl100k.count("1234567"), 3);
+    }
+
+    #[test]
+    fn wide_scripts_and_families_scale() {
+        assert_eq!(Family::Cl100k.count("你好世界"), 4);
+        assert_eq!(Family::O200k.count("你好世界"), 2);
+        let text = "A longer paragraph about tokenization and its discontents.";
+        assert!(Family::Claude.count(text) > Family::Cl100k.count(text));
+    }
+
+    #[test]
+    fn long_context_models_have_larger_windows() {
+        assert_eq!(context_window("gemini-2.5-pro"), 1_048_576);
+        assert_eq!(context_window("models/gemini-1.5-pro-002"), 2_097_152);
+        assert_eq!(context_window("google/gemini-2.0-flash-001"), 1_048_576);
+        assert_eq!(context_window("gpt-4o"), DEFAULT_CONTEXT_WINDOW);
+    }
+
+    #[test]
+    fn messages_include_overhead() {
+        let messages = [ChatMessage::system("Be brief."), ChatMessage::user("Hi")];
+        let content = Family::Cl100k.count("Be brief.") + Family::Cl100k.count("Hi");
+        assert_eq!(
+            count_messages("gpt-4", &messages),
+            content + 2 * MESSAGE_OVERHEAD + REPLY_PRIMING
+        );
+        assert_eq!(count_messages("gpt-4", &[]), 0);
+    }
+
+    #[test]
+    fn costs_follow_model_profile_prices() {
+        let profile: ModelProfileConfig = toml::from_str(
+            r#"
+model = "cost-test-model*"
+input_price = 2.0
+output_price = 8.0
+"#,
+        )
+        .unwrap();
+        let profiles = [profile];
+        let cost = cost_usd(&profiles, "cost-test-model-mini", 1_000_000, 500_000).unwrap();
+        assert!((cost - 6.0).abs() < 1e-9);
+        assert_eq!(cost_usd(&profiles, "unpriced-model", 10, 10), None);
+    }
+}
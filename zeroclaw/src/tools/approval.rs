@@ -0,0 +1,78 @@
+//! Human approval of file changes.
+//!
+//! With `[autonomy] approve_file_changes = true`, `file_write` and
+//! `file_edit` show the unified diff of the pending change and wait for the
+//! user to approve it. Questions go to whoever answers `ask_user` for the
+//! running turn — the terminal, or the embedding app's callback. A turn
+//! with nobody to ask (a TUI turn, a one-shot run without a terminal) has
+//! its file changes refused.
+
+use super::ask_user::UserPrompt;
+use std::future::Future;
+use std::sync::Arc;
+
+tokio::task_local! {
+    static APPROVER: Option<Arc<dyn UserPrompt>>;
+}
+
+/// Run `turn` with `prompt` answering the approval requests made during it.
+pub async fn scope<F: Future>(prompt: Option<Arc<dyn UserPrompt>>, turn: F) -> F::Output {
+    APPROVER.scope(prompt, turn).await
+}
+
+/// Ask the user to approve `change`, e.g. "`file_edit` wants to change
+/// notes.md" followed by its diff. `Err` holds the reason to report back to
+/// the model when the change must not be made.
+pub async fn request(change: &str) -> Result<(), String> {
+    let prompt = APPROVER.try_with(Clone::clone).ok().flatten();
+    let Some(prompt) = prompt else {
+        return Err("This change needs approval, but nobody can approve it here".into());
+    };
+    let choices = ["approve".to_string(), "reject".to_string()];
+    match prompt.ask(change, &choices).await {
+        Ok(Some(answer)) if is_approval(&answer) => Ok(()),
+        Ok(Some(answer)) => Err(format!("The user rejected this change: {answer}")),
+        Ok(None) => Err("The user did not approve this change".into()),
+        Err(e) => Err(format!("Could not ask for approval: {e}")),
+    }
+}
+
+fn is_approval(answer: &str) -> bool {
+    matches!(
+        answer.trim().to_lowercase().as_str(),
+        "1" | "y" | "yes" | "ok" | "approve" | "approved"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct Reply(Option<&'static str>);
+
+    #[async_trait]
+    impl UserPrompt for Reply {
+        async fn ask(&self, question: &str, choices: &[String]) -> anyhow::Result<Option<String>> {
+            assert!(question.starts_with("file_write wants to change"));
+            assert_eq!(choices, ["approve", "reject"]);
+            Ok(self.0.map(String::from))
+        }
+    }
+
+    #[tokio::test]
+    async fn only_an_approving_answer_allows_the_change() {
+        let ask = |reply| {
+            scope(
+                Some(Arc::new(Reply(reply))),
+                request("file_write wants to change a.md"),
+            )
+        };
+        assert!(ask(Some("1")).await.is_ok());
+        assert!(ask(Some(" Yes ")).await.is_ok());
+        assert!(ask(Some("reject")).await.unwrap_err().contains("rejected"));
+        assert!(ask(None).await.is_err());
+        assert!(request("file_write wants to change a.md").await.is_err());
+        assert!(scope(None, request("x")).await.is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use super::traits::{Tool, ToolResult};
+use crate::channels::traits::{Channel, ChannelMessage};
+use async_trait::async_trait;
+use serde_json::json;
+use std::io::{BufRead, Write};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+/// Longest question the model may put to the user.
+const MAX_QUESTION_CHARS: usize = 2_000;
+
+/// Somewhere a clarification question can be put to the user — the terminal,
+/// an embedding application's callback, ...
+#[async_trait]
+pub trait UserPrompt: Send + Sync {
+    /// Show `question` (with optional suggested `choices`) and wait for the
+    /// reply. `Ok(None)` means the user dismissed it without answering.
+    async fn ask(&self, question: &str, choices: &[String]) -> anyhow::Result<Option<String>>;
+}
+
+/// Ask on stdout and read one line from stdin.
+pub struct TerminalPrompt;
+
+#[async_trait]
+impl UserPrompt for TerminalPrompt {
+    async fn ask(&self, question: &str, choices: &[String]) -> anyhow::Result<Option<String>> {
+        let text = format_question(question, choices);
+        tokio::task::spawn_blocking(move || {
+            let mut stdout = std::io::stdout();
+            writeln!(stdout, "\n❓ {text}")?;
+            write!(stdout, "answer> ")?;
+            stdout.flush()?;
+
+            let mut line = String::new();
+            if std::io::stdin().lock().read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let answer = line.trim();
+            Ok((!answer.is_empty()).then(|| answer.to_string()))
+        })
+        .await?
+    }
+}
+
+/// Send the question through a channel and take the next inbound message as
+/// the answer. The inbox is shared with the loop that feeds the agent, which
+/// is blocked on the current turn while the question is open.
+pub struct ChannelPrompt {
+    channel: Arc<dyn Channel>,
+    recipient: String,
+    inbox: Arc<Mutex<mpsc::Receiver<ChannelMessage>>>,
+}
+
+impl ChannelPrompt {
+    pub fn new(
+        channel: Arc<dyn Channel>,
+        recipient: impl Into<String>,
+        inbox: Arc<Mutex<mpsc::Receiver<ChannelMessage>>>,
+    ) -> Self {
+        Self {
+            channel,
+            recipient: recipient.into(),
+            inbox,
+        }
+    }
+}
+
+#[async_trait]
+impl UserPrompt for ChannelPrompt {
+    async fn ask(&self, question: &str, choices: &[String]) -> anyhow::Result<Option<String>> {
+        self.channel
+            .send(
+                &format!("❓ {}", format_question(question, choices)),
+                &self.recipient,
+            )
+            .await?;
+        let reply = self.inbox.lock().await.recv().await;
+        Ok(reply
+            .map(|msg| msg.content.trim().to_string())
+            .filter(|answer| !answer.is_empty()))
+    }
+}
+
+/// Render a question and its choices as one block of plain text.
+pub fn format_question(question: &str, choices: &[String]) -> String {
+    use std::fmt::Write as _;
+    if choices.is_empty() {
+        return question.to_string();
+    }
+    let mut text = question.to_string();
+    for (i, choice) in choices.iter().enumerate() {
+        let _ = write!(text, "\n  {}. {choice}", i + 1);
+    }
+    text
+}
+
+/// Pause the agent loop to ask the user a clarifying question. The loop waits
+/// on the tool call, so the answer arrives as an ordinary tool result and the
+/// turn continues. Only registered where someone can actually answer.
+pub struct AskUserTool {
+    prompt: Arc<dyn UserPrompt>,
+}
+
+impl AskUserTool {
+    pub fn new(prompt: Arc<dyn UserPrompt>) -> Self {
+        Self { prompt }
+    }
+}
+
+#[async_trait]
+impl Tool for AskUserTool {
+    fn name(&self) -> &str {
+        "ask_user"
+    }
+
+    fn description(&self) -> &str {
+        "Ask the user a clarifying question and wait for the answer. Offer choices when the options are known."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "question": {
+                    "type": "string",
+                    "description": "The question to ask, self-contained"
+                },
+                "choices": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Suggested answers the user can pick from"
+                }
+            },
+            "required": ["question"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let question = args
+            .get("question")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'question' parameter"))?;
+        if question.is_empty() || question.chars().count() > MAX_QUESTION_CHARS {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Question must be 1-{MAX_QUESTION_CHARS} characters"
+                )),
+            });
+        }
+        let choices: Vec<String> = args
+            .get("choices")
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|c| c.as_str())
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        match self.prompt.ask(question, &choices).await {
+            Ok(Some(answer)) => Ok(ToolResult {
+                success: true,
+                output: format!("User answered: {answer}"),
+                error: None,
+            }),
+            Ok(None) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(
+                    "The user did not answer. Continue with your best judgement and state the assumption you made."
+                        .into(),
+                ),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Could not ask the user: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replies with a fixed answer and records what was asked.
+    struct ScriptedPrompt {
+        answer: Option<String>,
+        asked: std::sync::Mutex<Vec<(String, Vec<String>)>>,
+    }
+
+    #[async_trait]
+    impl UserPrompt for ScriptedPrompt {
+        async fn ask(&self, question: &str, choices: &[String]) -> anyhow::Result<Option<String>> {
+            self.asked
+                .lock()
+                .unwrap()
+                .push((question.to_string(), choices.to_vec()));
+            Ok(self.answer.clone())
+        }
+    }
+
+    fn scripted(answer: Option<&str>) -> Arc<ScriptedPrompt> {
+        Arc::new(ScriptedPrompt {
+            answer: answer.map(String::from),
+            asked: std::sync::Mutex::new(Vec::new()),
+        })
+    }
+
+    #[tokio::test]
+    async fn ask_user_returns_the_answer() {
+        let prompt = scripted(Some("staging"));
+        let tool = AskUserTool::new(prompt.clone());
+        let result = tool
+            .execute(json!({
+                "question": "Which environment?",
+                "choices": ["staging", " production ", ""]
+            }))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "User answered: staging");
+
+        let asked = prompt.asked.lock().unwrap();
+        assert_eq!(asked[0].0, "Which environment?");
+        assert_eq!(asked[0].1, vec!["staging", "production"]);
+    }
+
+    #[tokio::test]
+    async fn ask_user_reports_unanswered_questions() {
+        let tool = AskUserTool::new(scripted(None));
+        let result = tool.execute(json!({"question": "Proceed?"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("did not answer"));
+    }
+
+    #[tokio::test]
+    async fn ask_user_validates_question() {
+        let tool = AskUserTool::new(scripted(Some("x")));
+        assert!(tool.execute(json!({})).await.is_err());
+        let result = tool.execute(json!({"question": "  "})).await.unwrap();
+        assert!(!result.success);
+    }
+
+    /// Records what was sent instead of delivering it.
+    struct RecordingChannel {
+        sent: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, message: &str, _recipient: &str) -> anyhow::Result<()> {
+            self.sent.lock().await.push(message.to_string());
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn channel_prompt_takes_the_next_message_as_answer() {
+        let channel = Arc::new(RecordingChannel {
+            sent: Mutex::new(Vec::new()),
+        });
+        let (tx, rx) = mpsc::channel(4);
+        let prompt = ChannelPrompt::new(channel.clone(), "alice", Arc::new(Mutex::new(rx)));
+
+        tx.send(ChannelMessage {
+            id: "1".into(),
+            sender: "alice".into(),
+            content: " yes \n".into(),
+            channel: "recording".into(),
+            author: None,
+            timestamp: 0,
+        })
+        .await
+        .unwrap();
+        let answer = prompt.ask("Deploy now?", &[]).await.unwrap();
+        assert_eq!(answer.as_deref(), Some("yes"));
+        assert_eq!(channel.sent.lock().await[0], "❓ Deploy now?");
+
+        // A closed inbox means nobody is left to answer
+        drop(tx);
+        assert!(prompt.ask("Still there?", &[]).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn format_question_numbers_choices() {
+        assert_eq!(format_question("Why?", &[]), "Why?");
+        assert_eq!(
+            format_question("Pick", &["a".into(), "b".into()]),
+            "Pick\n  1. a\n  2. b"
+        );
+    }
+}
@@ -4,6 +4,7 @@
 //! It supports semantic element selection, accessibility snapshots, and JSON output
 //! for efficient LLM integration.
 
+use super::dry_run;
 use super::traits::{Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -472,6 +473,12 @@ impl Tool for BrowserTool {
             });
         }
 
+        if self.security.dry_run {
+            let action = args.get("action").and_then(Value::as_str).unwrap_or("?");
+            let action = format!("run the browser action \"{action}\"");
+            return Ok(dry_run::preview(self.name(), &action, &args));
+        }
+
         if !self.security.record_action() {
             return Ok(ToolResult {
                 success: false,
@@ -660,7 +667,7 @@ impl Tool for BrowserTool {
 
 // ── Helper functions ─────────────────────────────────────────────
 
-fn normalize_domains(domains: Vec<String>) -> Vec<String> {
+pub(super) fn normalize_domains(domains: Vec<String>) -> Vec<String> {
     domains
         .into_iter()
         .map(|d| d.trim().to_lowercase())
@@ -695,7 +702,7 @@ fn extract_host(url_str: &str) -> anyhow::Result<String> {
     Ok(host.to_lowercase())
 }
 
-fn is_private_host(host: &str) -> bool {
+pub(super) fn is_private_host(host: &str) -> bool {
     // Strip brackets from IPv6 addresses like [::1]
     let bare = host
         .strip_prefix('[')
@@ -747,7 +754,7 @@ fn is_private_host(host: &str) -> bool {
     string_patterns.iter().any(|p| bare.starts_with(p))
 }
 
-fn host_matches_allowlist(host: &str, allowed: &[String]) -> bool {
+pub(super) fn host_matches_allowlist(host: &str, allowed: &[String]) -> bool {
     allowed.iter().any(|pattern| {
         if pattern == "*" {
             return true;
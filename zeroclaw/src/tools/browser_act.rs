@@ -0,0 +1,803 @@
+//! Browser automation over the Chrome `DevTools` Protocol (CDP).
+//!
+//! `browser_act` drives a single headless Chromium page: navigate, wait, click,
+//! fill, extract text and take screenshots. Chromium is launched on first use
+//! (or `[browser].cdp_url` is attached to) and kept alive between calls so
+//! multi-step flows share cookies and page state.
+//!
+//! Every page the browser lands on — not just explicit navigations — is checked
+//! against `[browser].allowed_domains`; a click that leaves the allowlist is
+//! reverted to `about:blank`.
+
+use super::browser::{host_matches_allowlist, is_private_host, normalize_domains};
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::config::BrowserConfig;
+use crate::security::SecurityPolicy;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// Per-command response timeout.
+const COMMAND_TIMEOUT_SECS: u64 = 30;
+/// How long to wait for a freshly launched Chromium to print its `DevTools` URL.
+const LAUNCH_TIMEOUT_SECS: u64 = 20;
+const DEFAULT_WAIT_MS: u64 = 10_000;
+const MAX_WAIT_MS: u64 = 30_000;
+const POLL_INTERVAL_MS: u64 = 200;
+const MAX_TEXT_CHARS: usize = 20_000;
+/// Screenshots larger than this are saved but not inlined.
+const MAX_INLINE_SCREENSHOT_BYTES: usize = 1_572_864;
+/// Workspace-relative directory for screenshots.
+const SCREENSHOT_DIR: &str = "browser";
+
+const CHROMIUM_CANDIDATES: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+    "microsoft-edge",
+];
+
+#[cfg(target_os = "macos")]
+const CHROMIUM_APP_PATHS: &[&str] = &[
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+];
+#[cfg(not(target_os = "macos"))]
+const CHROMIUM_APP_PATHS: &[&str] = &[];
+
+type CdpSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// One page target attached over a flattened CDP session.
+struct CdpPage {
+    ws: CdpSocket,
+    session_id: String,
+    target_id: String,
+    next_id: u64,
+    /// Chromium we launched ourselves (killed on drop); `None` when attached.
+    _child: Option<Child>,
+    profile_dir: Option<PathBuf>,
+}
+
+impl CdpPage {
+    async fn launch(binary: &Path) -> Result<Self> {
+        let profile_dir =
+            std::env::temp_dir().join(format!("zeroclaw-chromium-{}", uuid::Uuid::new_v4()));
+        let mut child = Command::new(binary)
+            .args([
+                "--headless=new",
+                "--remote-debugging-port=0",
+                "--no-first-run",
+                "--no-default-browser-check",
+                "--disable-extensions",
+                "--disable-gpu",
+            ])
+            .arg(format!("--user-data-dir={}", profile_dir.display()))
+            .arg("about:blank")
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to launch {}", binary.display()))?;
+
+        let stderr = child.stderr.take().context("Chromium stderr unavailable")?;
+        let mut lines = BufReader::new(stderr).lines();
+        let ws_url = tokio::time::timeout(Duration::from_secs(LAUNCH_TIMEOUT_SECS), async {
+            while let Some(line) = lines.next_line().await? {
+                if let Some(url) = parse_devtools_line(&line) {
+                    return Ok(url);
+                }
+            }
+            bail!("Chromium exited before exposing a DevTools endpoint")
+        })
+        .await
+        .context("Timed out waiting for Chromium to start")??;
+
+        // Keep draining stderr so Chromium never blocks on a full pipe
+        tokio::spawn(async move { while let Ok(Some(_)) = lines.next_line().await {} });
+
+        Self::open(&ws_url, Some(child), Some(profile_dir)).await
+    }
+
+    async fn attach(cdp_url: &str) -> Result<Self> {
+        let version: Value = reqwest::Client::new()
+            .get(format!("{}/json/version", cdp_url.trim_end_matches('/')))
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .with_context(|| format!("Cannot reach DevTools endpoint {cdp_url}"))?
+            .json()
+            .await?;
+        let ws_url = version["webSocketDebuggerUrl"]
+            .as_str()
+            .context("DevTools endpoint returned no webSocketDebuggerUrl")?;
+        Self::open(ws_url, None, None).await
+    }
+
+    async fn open(
+        ws_url: &str,
+        child: Option<Child>,
+        profile_dir: Option<PathBuf>,
+    ) -> Result<Self> {
+        let (ws, _) = tokio_tungstenite::connect_async(ws_url)
+            .await
+            .context("Failed to connect to DevTools WebSocket")?;
+        let mut page = Self {
+            ws,
+            session_id: String::new(),
+            target_id: String::new(),
+            next_id: 0,
+            _child: child,
+            profile_dir,
+        };
+
+        let target = page
+            .send(None, "Target.createTarget", json!({"url": "about:blank"}))
+            .await?;
+        page.target_id = target["targetId"]
+            .as_str()
+            .context("Target.createTarget returned no targetId")?
+            .to_string();
+        let attached = page
+            .send(
+                None,
+                "Target.attachToTarget",
+                json!({"targetId": page.target_id, "flatten": true}),
+            )
+            .await?;
+        page.session_id = attached["sessionId"]
+            .as_str()
+            .context("Target.attachToTarget returned no sessionId")?
+            .to_string();
+        Ok(page)
+    }
+
+    /// Send a command and wait for its response, skipping unrelated events.
+    async fn send(&mut self, session: Option<&str>, method: &str, params: Value) -> Result<Value> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let mut request = json!({"id": id, "method": method, "params": params});
+        if let Some(session) = session {
+            request["sessionId"] = json!(session);
+        }
+        self.ws.send(Message::Text(request.to_string())).await?;
+
+        let deadline = Instant::now() + Duration::from_secs(COMMAND_TIMEOUT_SECS);
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let msg = tokio::time::timeout(remaining, self.ws.next())
+                .await
+                .with_context(|| format!("{method} timed out"))?
+                .context("DevTools connection closed")??;
+            let Message::Text(text) = msg else {
+                continue;
+            };
+            let reply: Value = serde_json::from_str(&text)?;
+            if reply["id"].as_u64() != Some(id) {
+                continue;
+            }
+            if let Some(err) = reply.get("error") {
+                bail!(
+                    "{method} failed: {}",
+                    err["message"].as_str().unwrap_or("unknown error")
+                );
+            }
+            return Ok(reply["result"].clone());
+        }
+    }
+
+    async fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let session = self.session_id.clone();
+        self.send(Some(&session), method, params).await
+    }
+
+    /// Evaluate `expression` in the page and return its JSON value.
+    async fn evaluate(&mut self, expression: &str) -> Result<Value> {
+        let result = self
+            .call(
+                "Runtime.evaluate",
+                json!({"expression": expression, "returnByValue": true, "awaitPromise": true}),
+            )
+            .await?;
+        if let Some(details) = result.get("exceptionDetails") {
+            let text = details["exception"]["description"]
+                .as_str()
+                .or_else(|| details["text"].as_str())
+                .unwrap_or("script error");
+            bail!("Page script failed: {text}");
+        }
+        Ok(result["result"]["value"].clone())
+    }
+
+    async fn location(&mut self) -> Result<String> {
+        Ok(self
+            .evaluate("location.href")
+            .await?
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// Poll `condition` until it evaluates truthy or `timeout_ms` elapses.
+    async fn wait_until(&mut self, condition: &str, timeout_ms: u64) -> Result<bool> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        loop {
+            if truthy(&self.evaluate(condition).await?) {
+                return Ok(true);
+            }
+            if Instant::now() >= deadline {
+                return Ok(false);
+            }
+            tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+        }
+    }
+
+    async fn close(mut self) {
+        let target_id = self.target_id.clone();
+        let _ = self
+            .send(None, "Target.closeTarget", json!({"targetId": target_id}))
+            .await;
+        let _ = self.ws.close(None).await;
+    }
+}
+
+impl Drop for CdpPage {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.profile_dir {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Parsed `browser_act` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Action {
+    Navigate {
+        url: String,
+    },
+    Wait {
+        selector: Option<String>,
+        text: Option<String>,
+        ms: Option<u64>,
+    },
+    Click {
+        selector: String,
+    },
+    Fill {
+        selector: String,
+        value: String,
+    },
+    Extract {
+        selector: Option<String>,
+    },
+    Screenshot {
+        full_page: bool,
+    },
+    Close,
+}
+
+impl Action {
+    fn parse(args: &Value) -> Result<Self> {
+        let str_arg = |name: &str| args.get(name).and_then(Value::as_str).map(str::to_string);
+        let required = |name: &str, action: &str| {
+            str_arg(name)
+                .filter(|v| !v.trim().is_empty())
+                .with_context(|| format!("Missing '{name}' for {action}"))
+        };
+
+        let action = args
+            .get("action")
+            .and_then(Value::as_str)
+            .context("Missing 'action' parameter")?;
+        Ok(match action {
+            "navigate" => Self::Navigate {
+                url: required("url", action)?,
+            },
+            "wait" => Self::Wait {
+                selector: str_arg("selector"),
+                text: str_arg("text"),
+                ms: args.get("ms").and_then(Value::as_u64),
+            },
+            "click" => Self::Click {
+                selector: required("selector", action)?,
+            },
+            "fill" => Self::Fill {
+                selector: required("selector", action)?,
+                value: str_arg("value").context("Missing 'value' for fill")?,
+            },
+            "extract" => Self::Extract {
+                selector: str_arg("selector"),
+            },
+            "screenshot" => Self::Screenshot {
+                full_page: args
+                    .get("full_page")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            },
+            "close" => Self::Close,
+            other => bail!("Unknown browser_act action '{other}'"),
+        })
+    }
+}
+
+/// Headless Chromium automation via CDP, restricted to allowlisted domains.
+pub struct BrowserActTool {
+    security: Arc<SecurityPolicy>,
+    allowed_domains: Vec<String>,
+    chromium_path: Option<String>,
+    cdp_url: Option<String>,
+    page: Mutex<Option<CdpPage>>,
+}
+
+impl BrowserActTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: &BrowserConfig) -> Self {
+        Self {
+            security,
+            allowed_domains: normalize_domains(config.allowed_domains.clone()),
+            chromium_path: config.chromium_path.clone(),
+            cdp_url: config.cdp_url.clone().filter(|u| !u.trim().is_empty()),
+            page: Mutex::new(None),
+        }
+    }
+
+    /// Check a URL the page is about to visit (or has landed on).
+    fn validate_url(&self, raw: &str) -> Result<()> {
+        let url = Url::parse(raw.trim()).with_context(|| format!("Invalid URL: {raw}"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("Only http:// and https:// URLs are allowed");
+        }
+        if !url.username().is_empty() || url.password().is_some() {
+            bail!("URL userinfo is not allowed");
+        }
+        if self.allowed_domains.is_empty() {
+            bail!(
+                "browser_act is enabled but no allowed_domains are configured. \
+                 Add [browser].allowed_domains in config.toml"
+            );
+        }
+        let host = url
+            .host_str()
+            .context("URL must include a host")?
+            .to_lowercase();
+        if is_private_host(&host) {
+            bail!("Blocked local/private host: {host}");
+        }
+        if !host_matches_allowlist(&host, &self.allowed_domains) {
+            bail!("Host '{host}' is not in browser.allowed_domains");
+        }
+        Ok(())
+    }
+
+    async fn connect(&self) -> Result<CdpPage> {
+        if let Some(cdp_url) = &self.cdp_url {
+            return CdpPage::attach(cdp_url).await;
+        }
+        let binary = find_chromium(self.chromium_path.as_deref()).context(
+            "Chromium not found. Install chromium or google-chrome, or set [browser].chromium_path",
+        )?;
+        CdpPage::launch(&binary).await
+    }
+
+    /// After an action, make sure the page has not wandered off the allowlist.
+    async fn enforce_location(&self, page: &mut CdpPage) -> Result<String> {
+        let href = page.location().await?;
+        if href == "about:blank" {
+            return Ok(href);
+        }
+        if let Err(e) = self.validate_url(&href) {
+            let _ = page
+                .call("Page.navigate", json!({"url": "about:blank"}))
+                .await;
+            bail!("Page left the allowlist ({href}): {e}");
+        }
+        Ok(href)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    async fn run(&self, page: &mut CdpPage, action: Action) -> Result<String> {
+        match action {
+            Action::Navigate { url } => {
+                self.validate_url(&url)?;
+                let nav = page.call("Page.navigate", json!({"url": url})).await?;
+                if let Some(err) = nav["errorText"].as_str().filter(|e| !e.is_empty()) {
+                    bail!("Navigation failed: {err}");
+                }
+                page.wait_until("document.readyState === 'complete'", DEFAULT_WAIT_MS)
+                    .await?;
+                let href = self.enforce_location(page).await?;
+                let title = page.evaluate("document.title").await?;
+                Ok(format!(
+                    "Navigated to {href}\nTitle: {}",
+                    title.as_str().unwrap_or_default()
+                ))
+            }
+            Action::Wait { selector, text, ms } => {
+                let timeout = ms.unwrap_or(DEFAULT_WAIT_MS).min(MAX_WAIT_MS);
+                let (condition, what) = match (selector, text) {
+                    (Some(sel), _) => (
+                        format!("!!document.querySelector({})", js_string(&sel)),
+                        format!("selector {sel}"),
+                    ),
+                    (None, Some(text)) => (
+                        format!(
+                            "!!document.body && document.body.innerText.includes({})",
+                            js_string(&text)
+                        ),
+                        format!("text \"{text}\""),
+                    ),
+                    (None, None) => {
+                        tokio::time::sleep(Duration::from_millis(timeout)).await;
+                        return Ok(format!("Waited {timeout} ms"));
+                    }
+                };
+                if page.wait_until(&condition, timeout).await? {
+                    Ok(format!("Found {what}"))
+                } else {
+                    bail!("Timed out after {timeout} ms waiting for {what}")
+                }
+            }
+            Action::Click { selector } => {
+                let script = format!(
+                    "(() => {{ const el = document.querySelector({}); if (!el) return false; \
+                     el.scrollIntoView({{block: 'center'}}); el.click(); return true; }})()",
+                    js_string(&selector)
+                );
+                if !truthy(&page.evaluate(&script).await?) {
+                    bail!("No element matches selector {selector}");
+                }
+                // Give click-triggered navigations a moment to start, then settle
+                tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+                page.wait_until("document.readyState === 'complete'", DEFAULT_WAIT_MS)
+                    .await?;
+                let href = self.enforce_location(page).await?;
+                Ok(format!("Clicked {selector}\nURL: {href}"))
+            }
+            Action::Fill { selector, value } => {
+                // Use the native value setter so framework-controlled inputs see the change
+                let script = format!(
+                    "(() => {{ const el = document.querySelector({}); if (!el) return false; \
+                     el.focus(); \
+                     const desc = Object.getOwnPropertyDescriptor(Object.getPrototypeOf(el), 'value'); \
+                     if (desc && desc.set) desc.set.call(el, {value}); else el.value = {value}; \
+                     el.dispatchEvent(new Event('input', {{bubbles: true}})); \
+                     el.dispatchEvent(new Event('change', {{bubbles: true}})); \
+                     return true; }})()",
+                    js_string(&selector),
+                    value = js_string(&value)
+                );
+                if !truthy(&page.evaluate(&script).await?) {
+                    bail!("No element matches selector {selector}");
+                }
+                Ok(format!("Filled {selector}"))
+            }
+            Action::Extract { selector } => {
+                let target = selector.as_deref().map_or_else(
+                    || "document.body".to_string(),
+                    |sel| format!("document.querySelector({})", js_string(sel)),
+                );
+                let script = format!(
+                    "(() => {{ const el = {target}; return el ? el.innerText : null; }})()"
+                );
+                let Some(text) = page.evaluate(&script).await?.as_str().map(str::to_string) else {
+                    bail!(
+                        "No element matches selector {}",
+                        selector.as_deref().unwrap_or("body")
+                    );
+                };
+                Ok(truncate_with_ellipsis(text.trim(), MAX_TEXT_CHARS))
+            }
+            Action::Screenshot { full_page } => {
+                let shot = page
+                    .call(
+                        "Page.captureScreenshot",
+                        json!({"format": "png", "captureBeyondViewport": full_page}),
+                    )
+                    .await?;
+                let encoded = shot["data"]
+                    .as_str()
+                    .context("Page.captureScreenshot returned no data")?;
+                self.save_screenshot(encoded).await
+            }
+            Action::Close => unreachable!("close is handled before connecting"),
+        }
+    }
+
+    async fn save_screenshot(&self, encoded: &str) -> Result<String> {
+        use base64::Engine;
+        let bytes = base64::engine::general_purpose::STANDARD.decode(encoded)?;
+        let relative = format!(
+            "{SCREENSHOT_DIR}/page_{}.png",
+            chrono::Local::now().format("%Y%m%d_%H%M%S_%3f")
+        );
+        let full_path = self.security.workspace_dir.join(&relative);
+        if let Some(parent) = full_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&full_path, &bytes).await?;
+
+        let mut output = format!(
+            "Screenshot saved to: {relative}\nSize: {} bytes",
+            bytes.len()
+        );
+        if bytes.len() <= MAX_INLINE_SCREENSHOT_BYTES {
+            output.push_str("\ndata:image/png;base64,");
+            output.push_str(encoded);
+        } else {
+            output.push_str(" (too large to inline)");
+        }
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl Tool for BrowserActTool {
+    fn name(&self) -> &str {
+        "browser_act"
+    }
+
+    fn description(&self) -> &str {
+        "Drive a headless Chromium page: navigate, wait for selectors/text, click, fill \
+         form fields, extract visible text and take screenshots. The page persists between \
+         calls. Allowed domains only."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["navigate", "wait", "click", "fill", "extract", "screenshot", "close"],
+                    "description": "Browser action to perform"
+                },
+                "url": {
+                    "type": "string",
+                    "description": "URL to open (for 'navigate')"
+                },
+                "selector": {
+                    "type": "string",
+                    "description": "CSS selector (for 'click', 'fill', 'wait', 'extract')"
+                },
+                "value": {
+                    "type": "string",
+                    "description": "Value to enter (for 'fill')"
+                },
+                "text": {
+                    "type": "string",
+                    "description": "Text to wait for on the page (for 'wait')"
+                },
+                "ms": {
+                    "type": "integer",
+                    "description": "Timeout, or plain delay without selector/text, in milliseconds (for 'wait', max 30000)"
+                },
+                "full_page": {
+                    "type": "boolean",
+                    "description": "Capture the full scrollable page (for 'screenshot')"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        let action = Action::parse(&args)?;
+
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+
+        if self.security.dry_run {
+            let action = args.get("action").and_then(Value::as_str).unwrap_or("?");
+            let action = format!("run the browser action \"{action}\"");
+            return Ok(dry_run::preview(self.name(), &action, &args));
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: rate limit exceeded".into()),
+            });
+        }
+
+        let mut guard = self.page.lock().await;
+
+        if action == Action::Close {
+            let closed = match guard.take() {
+                Some(page) => {
+                    page.close().await;
+                    true
+                }
+                None => false,
+            };
+            return Ok(ToolResult {
+                success: true,
+                output: if closed {
+                    "Browser closed".into()
+                } else {
+                    "Browser was not running".into()
+                },
+                error: None,
+            });
+        }
+
+        if guard.is_none() {
+            match self.connect().await {
+                Ok(page) => *guard = Some(page),
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Failed to start browser: {e}")),
+                    });
+                }
+            }
+        }
+        let Some(page) = guard.as_mut() else {
+            unreachable!("page was just connected");
+        };
+
+        match self.run(page, action).await {
+            Ok(output) => Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+            }),
+            Err(e) => {
+                // A dead connection is dropped so the next call starts fresh
+                if e.to_string().contains("DevTools connection closed") {
+                    *guard = None;
+                }
+                Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                })
+            }
+        }
+    }
+}
+
+/// Extract the WebSocket URL from Chromium's startup banner.
+fn parse_devtools_line(line: &str) -> Option<String> {
+    line.split_once("DevTools listening on ")
+        .map(|(_, url)| url.trim().to_string())
+        .filter(|url| url.starts_with("ws://"))
+}
+
+fn find_chromium(configured: Option<&str>) -> Option<PathBuf> {
+    if let Some(path) = configured.map(str::trim).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var)
+        .flat_map(|dir| CHROMIUM_CANDIDATES.iter().map(move |name| dir.join(name)))
+        .chain(CHROMIUM_APP_PATHS.iter().map(PathBuf::from))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Quote `value` as a JavaScript string literal.
+fn js_string(value: &str) -> String {
+    Value::String(value.to_string()).to_string()
+}
+
+fn truthy(value: &Value) -> bool {
+    value.as_bool().unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    fn tool_with(domains: &[&str], autonomy: AutonomyLevel) -> BrowserActTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy,
+            ..SecurityPolicy::default()
+        });
+        let config = BrowserConfig {
+            enabled: true,
+            allowed_domains: domains.iter().map(|d| (*d).to_string()).collect(),
+            ..BrowserConfig::default()
+        };
+        BrowserActTool::new(security, &config)
+    }
+
+    #[test]
+    fn validate_url_enforces_allowlist_and_blocks_private_hosts() {
+        let tool = tool_with(&["example.com"], AutonomyLevel::Supervised);
+        assert!(tool.validate_url("https://example.com/login").is_ok());
+        assert!(tool.validate_url("http://docs.example.com").is_ok());
+        assert!(tool.validate_url("https://other.com").is_err());
+        assert!(tool.validate_url("https://user:pw@example.com").is_err());
+        assert!(tool.validate_url("file:///etc/passwd").is_err());
+        assert!(tool.validate_url("javascript:alert(1)").is_err());
+
+        let open = tool_with(&["*"], AutonomyLevel::Supervised);
+        assert!(open.validate_url("http://127.0.0.1:9222").is_err());
+        assert!(open.validate_url("http://[::1]/").is_err());
+        assert!(open.validate_url("http://localhost/").is_err());
+
+        let empty = tool_with(&[], AutonomyLevel::Supervised);
+        assert!(empty.validate_url("https://example.com").is_err());
+    }
+
+    #[test]
+    fn action_parse_requires_per_action_fields() {
+        assert_eq!(
+            Action::parse(&json!({"action": "navigate", "url": "https://example.com"})).unwrap(),
+            Action::Navigate {
+                url: "https://example.com".into()
+            }
+        );
+        assert!(Action::parse(&json!({"action": "click"})).is_err());
+        assert!(Action::parse(&json!({"action": "fill", "selector": "#q"})).is_err());
+        assert!(Action::parse(&json!({"action": "teleport"})).is_err());
+        assert!(Action::parse(&json!({})).is_err());
+        assert_eq!(
+            Action::parse(&json!({"action": "screenshot"})).unwrap(),
+            Action::Screenshot { full_page: false }
+        );
+    }
+
+    #[test]
+    fn js_string_escapes_quotes_and_newlines() {
+        assert_eq!(js_string(r#"a[name="q"]"#), r#""a[name=\"q\"]""#);
+        assert_eq!(js_string("line\n'x'"), r#""line\n'x'""#);
+    }
+
+    #[test]
+    fn parse_devtools_line_extracts_ws_url() {
+        assert_eq!(
+            parse_devtools_line("DevTools listening on ws://127.0.0.1:40123/devtools/browser/abc"),
+            Some("ws://127.0.0.1:40123/devtools/browser/abc".into())
+        );
+        assert_eq!(parse_devtools_line("[WARNING] something else"), None);
+    }
+
+    #[test]
+    fn find_chromium_prefers_configured_path() {
+        assert_eq!(
+            find_chromium(Some("/opt/chrome/chrome")),
+            Some(PathBuf::from("/opt/chrome/chrome"))
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_blocked_in_read_only_mode() {
+        let tool = tool_with(&["example.com"], AutonomyLevel::ReadOnly);
+        let result = tool
+            .execute(json!({"action": "navigate", "url": "https://example.com"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn close_without_browser_is_a_no_op() {
+        let tool = tool_with(&["example.com"], AutonomyLevel::Supervised);
+        let result = tool.execute(json!({"action": "close"})).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "Browser was not running");
+    }
+}
@@ -1,3 +1,4 @@
+use super::dry_run;
 use super::traits::{Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -90,6 +91,10 @@ impl Tool for BrowserOpenTool {
             });
         }
 
+        if self.security.dry_run {
+            return Ok(dry_run::preview(self.name(), &format!("open {url}"), &args));
+        }
+
         if !self.security.record_action() {
             return Ok(ToolResult {
                 success: false,
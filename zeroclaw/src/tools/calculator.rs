@@ -0,0 +1,492 @@
+use super::traits::{Tool, ToolResult};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Longest expression accepted, to keep parsing cheap and bounded.
+const MAX_EXPRESSION_CHARS: usize = 2_000;
+/// Maximum parenthesis/function nesting.
+const MAX_DEPTH: usize = 64;
+
+/// Exact arithmetic for the agent, so numbers in answers come from
+/// computation rather than mental math.
+pub struct CalculatorTool;
+
+impl CalculatorTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CalculatorTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for CalculatorTool {
+    fn name(&self) -> &str {
+        "calculator"
+    }
+
+    fn description(&self) -> &str {
+        "Evaluate an arithmetic expression: + - * / % ^, parentheses, and functions \
+         abs, sqrt, round(x, digits), floor, ceil, min, max, sum, avg, pow, ln, log10, exp; \
+         constants pi and e. Use for every calculation instead of computing in your head."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "expression": {
+                    "type": "string",
+                    "description": "Expression to evaluate, e.g. 'round((1250.40 - 980) / 980 * 100, 2)'"
+                }
+            },
+            "required": ["expression"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let expression = args
+            .get("expression")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'expression' parameter"))?;
+
+        match evaluate(expression) {
+            Ok(value) => Ok(ToolResult {
+                success: true,
+                output: format!("{} = {}", expression.trim(), format_number(value)),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Cannot evaluate '{}': {e}", expression.trim())),
+            }),
+        }
+    }
+}
+
+/// Evaluate an arithmetic expression.
+pub fn evaluate(expression: &str) -> Result<f64> {
+    if expression.chars().count() > MAX_EXPRESSION_CHARS {
+        bail!("Expression longer than {MAX_EXPRESSION_CHARS} characters");
+    }
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        bail!("Empty expression");
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.expr()?;
+    if let Some(token) = parser.peek() {
+        bail!("Unexpected {token}");
+    }
+    if !value.is_finite() {
+        bail!("Result is not a finite number");
+    }
+    Ok(value)
+}
+
+/// Render a result without float noise: integers plainly, fractions with at
+/// most 10 decimals and no trailing zeros.
+pub fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{value:.0}");
+    }
+    let fixed = format!("{value:.10}");
+    let trimmed = fixed.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" {
+        "0".into()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Num(n) => write!(f, "number {n}"),
+            Self::Ident(name) => write!(f, "'{name}'"),
+            Self::Op(op) => write!(f, "'{op}'"),
+            Self::LParen => f.write_str("'('"),
+            Self::RParen => f.write_str("')'"),
+            Self::Comma => f.write_str("','"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit() || matches!(chars[i], '.' | '_'))
+                {
+                    i += 1;
+                }
+                // Scientific notation: 1.5e3, 2E-4
+                if i + 1 < chars.len()
+                    && matches!(chars[i], 'e' | 'E')
+                    && (chars[i + 1].is_ascii_digit()
+                        || (matches!(chars[i + 1], '+' | '-')
+                            && chars.get(i + 2).is_some_and(char::is_ascii_digit)))
+                {
+                    i += 2;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+                let literal: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+                let value = literal
+                    .parse::<f64>()
+                    .with_context(|| format!("Invalid number '{literal}'"))?;
+                tokens.push(Token::Num(value));
+            }
+            c if c.is_alphabetic() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let name: String = chars[start..i].iter().collect();
+                tokens.push(Token::Ident(name.to_lowercase()));
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                tokens.push(Token::Op('^'));
+                i += 2;
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '×' => {
+                tokens.push(Token::Op('*'));
+                i += 1;
+            }
+            '÷' => {
+                tokens.push(Token::Op('/'));
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            other => bail!("Unexpected character '{other}'"),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<()> {
+        match self.next() {
+            Some(ref token) if token == expected => Ok(()),
+            Some(token) => bail!("Expected {expected}, found {token}"),
+            None => bail!("Expected {expected} at end of expression"),
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat(&Token::Op('+')) {
+                value += self.term()?;
+            } else if self.eat(&Token::Op('-')) {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat(&Token::Op('*')) {
+                value *= self.unary()?;
+            } else if self.eat(&Token::Op('/')) {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    bail!("Division by zero");
+                }
+                value /= divisor;
+            } else if self.eat(&Token::Op('%')) {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    bail!("Modulo by zero");
+                }
+                value %= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Result<f64> {
+        if self.eat(&Token::Op('-')) {
+            return Ok(-self.nested(Self::unary)?);
+        }
+        if self.eat(&Token::Op('+')) {
+            return self.nested(Self::unary);
+        }
+        self.power()
+    }
+
+    // power := primary ('^' unary)?   (right-associative)
+    fn power(&mut self) -> Result<f64> {
+        let base = self.primary()?;
+        if self.eat(&Token::Op('^')) {
+            let exponent = self.nested(Self::unary)?;
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<f64> {
+        match self.next() {
+            Some(Token::Num(n)) => Ok(n),
+            Some(Token::LParen) => {
+                let value = self.nested(Self::expr)?;
+                self.expect(&Token::RParen)?;
+                Ok(value)
+            }
+            Some(Token::Ident(name)) => {
+                if self.eat(&Token::LParen) {
+                    let args = self.nested(Self::arguments)?;
+                    call_function(&name, &args)
+                } else {
+                    match name.as_str() {
+                        "pi" => Ok(std::f64::consts::PI),
+                        "e" => Ok(std::f64::consts::E),
+                        _ => bail!("Unknown constant '{name}'"),
+                    }
+                }
+            }
+            Some(token) => bail!("Unexpected {token}"),
+            None => bail!("Unexpected end of expression"),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<Vec<f64>> {
+        let mut args = Vec::new();
+        if self.eat(&Token::RParen) {
+            return Ok(args);
+        }
+        loop {
+            args.push(self.expr()?);
+            if self.eat(&Token::Comma) {
+                continue;
+            }
+            self.expect(&Token::RParen)?;
+            return Ok(args);
+        }
+    }
+
+    fn nested<T>(&mut self, parse: fn(&mut Self) -> Result<T>) -> Result<T> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            bail!("Expression nested too deeply");
+        }
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+}
+
+fn call_function(name: &str, args: &[f64]) -> Result<f64> {
+    let one = || -> Result<f64> {
+        match args {
+            [x] => Ok(*x),
+            _ => bail!("{name}() takes exactly one argument"),
+        }
+    };
+    let non_empty = || -> Result<&[f64]> {
+        if args.is_empty() {
+            bail!("{name}() needs at least one argument");
+        }
+        Ok(args)
+    };
+
+    Ok(match name {
+        "abs" => one()?.abs(),
+        "sqrt" => {
+            let x = one()?;
+            if x < 0.0 {
+                bail!("sqrt() of a negative number");
+            }
+            x.sqrt()
+        }
+        "floor" => one()?.floor(),
+        "ceil" => one()?.ceil(),
+        "exp" => one()?.exp(),
+        "ln" => {
+            let x = one()?;
+            if x <= 0.0 {
+                bail!("ln() of a non-positive number");
+            }
+            x.ln()
+        }
+        "log10" | "log" => {
+            let x = one()?;
+            if x <= 0.0 {
+                bail!("{name}() of a non-positive number");
+            }
+            x.log10()
+        }
+        "round" => match args {
+            [x] => x.round(),
+            [x, digits] => {
+                #[allow(clippy::cast_possible_truncation)]
+                let factor = 10f64.powi(digits.round().clamp(-15.0, 15.0) as i32);
+                (x * factor).round() / factor
+            }
+            _ => bail!("round() takes one or two arguments"),
+        },
+        "pow" => match args {
+            [base, exponent] => base.powf(*exponent),
+            _ => bail!("pow() takes exactly two arguments"),
+        },
+        "min" => non_empty()?.iter().copied().fold(f64::INFINITY, f64::min),
+        "max" => non_empty()?
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max),
+        "sum" => args.iter().sum(),
+        "avg" | "mean" => {
+            let values = non_empty()?;
+            #[allow(clippy::cast_precision_loss)]
+            let count = values.len() as f64;
+            values.iter().sum::<f64>() / count
+        }
+        _ => bail!("Unknown function '{name}'"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expr: &str) -> f64 {
+        evaluate(expr).unwrap()
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+        assert_eq!(eval("2 ** 10"), 1024.0);
+        assert_eq!(eval("-2 ^ 2"), -4.0);
+        assert_eq!(eval("10 - 4 - 3"), 3.0);
+        assert_eq!(eval("7 % 4"), 3.0);
+        assert_eq!(eval("6 × 7 ÷ 2"), 21.0);
+    }
+
+    #[test]
+    fn numbers_functions_and_constants() {
+        assert_eq!(eval("1_000_000 / 4"), 250_000.0);
+        assert_eq!(eval("1.5e3 + 2E-1"), 1500.2);
+        assert_eq!(eval("round(2 / 3, 4)"), 0.6667);
+        assert_eq!(eval("sum(1, 2, 3.5)"), 6.5);
+        assert_eq!(eval("avg(2, 4, 9)"), 5.0);
+        assert_eq!(eval("max(3, -1, 8) - min(3, -1, 8)"), 9.0);
+        assert_eq!(eval("sqrt(16) + abs(-2) + pow(2, 3)"), 14.0);
+        assert!((eval("pi") - std::f64::consts::PI).abs() < 1e-12);
+        assert_eq!(eval("ROUND(1234.5678, -2)"), 1200.0);
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for bad in [
+            "", "1 +", "(1 + 2", "1 / 0", "sqrt(-1)", "foo(1)", "x + 1", "2 $ 3", "min()", "1 2",
+        ] {
+            assert!(evaluate(bad).is_err(), "expected error for {bad:?}");
+        }
+        let deep = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+        assert!(evaluate(&deep).is_err());
+    }
+
+    #[test]
+    fn format_number_trims_float_noise() {
+        assert_eq!(format_number(42.0), "42");
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(-1.25), "-1.25");
+        assert_eq!(format_number(1e20), "100000000000000000000");
+    }
+
+    #[tokio::test]
+    async fn execute_reports_result_and_errors() {
+        let tool = CalculatorTool::new();
+        let ok = tool
+            .execute(json!({"expression": "(1250.40 - 980) / 980 * 100"}))
+            .await
+            .unwrap();
+        assert!(ok.success);
+        assert_eq!(ok.output, "(1250.40 - 980) / 980 * 100 = 27.5918367347");
+
+        let err = tool.execute(json!({"expression": "1 / 0"})).await.unwrap();
+        assert!(!err.success);
+        assert!(err.error.unwrap().contains("Division by zero"));
+
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
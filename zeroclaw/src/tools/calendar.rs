@@ -0,0 +1,1006 @@
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use reqwest::{Client, Method, StatusCode};
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Longest range `list` will query.
+const MAX_LOOKAHEAD_DAYS: u32 = 90;
+/// Most events returned by one `list` call.
+const MAX_LISTED_EVENTS: usize = 50;
+
+/// A configured calendar with its password already decrypted.
+struct Account {
+    name: String,
+    /// Collection URL, always ending in `/`
+    url: String,
+    username: String,
+    password: String,
+}
+
+/// List and create events on `CalDAV` calendars (Nextcloud, iCloud, Fastmail,
+/// Radicale, ...). Creating events requires an autonomy level that can act.
+pub struct CalendarTool {
+    security: Arc<SecurityPolicy>,
+    accounts: Vec<Account>,
+    lookahead_days: u32,
+    client: Client,
+}
+
+impl CalendarTool {
+    /// Build from `[calendar]`. Returns `None` when disabled or when no
+    /// account is usable. Encrypted passwords are read via the secret store.
+    pub fn from_config(config: &Config, security: Arc<SecurityPolicy>) -> Option<Self> {
+        let cal = &config.calendar;
+        if !cal.enabled {
+            return None;
+        }
+
+        let store = config.secret_store();
+        let accounts: Vec<Account> = cal
+            .accounts
+            .iter()
+            .filter_map(|acct| {
+                let url = acct.url.trim();
+                if !(url.starts_with("https://") || url.starts_with("http://")) {
+                    tracing::warn!("calendar '{}': url must be http(s)", acct.name);
+                    return None;
+                }
+                let password = match store.decrypt(&acct.password) {
+                    Ok(p) => p,
+                    Err(e) => {
+                        tracing::warn!("calendar '{}': cannot decrypt password: {e}", acct.name);
+                        return None;
+                    }
+                };
+                if config.secrets.encrypt
+                    && !password.is_empty()
+                    && !crate::security::SecretStore::is_encrypted(&acct.password)
+                {
+                    tracing::warn!(
+                        "calendar '{}': password is stored in plaintext; store it encrypted (enc2:)",
+                        acct.name
+                    );
+                }
+                Some(Account {
+                    name: acct.name.clone(),
+                    url: format!("{}/", url.trim_end_matches('/')),
+                    username: acct.username.clone(),
+                    password,
+                })
+            })
+            .collect();
+        if accounts.is_empty() {
+            tracing::warn!("calendar: enabled but no usable accounts configured");
+            return None;
+        }
+
+        Some(Self {
+            security,
+            accounts,
+            lookahead_days: cal.lookahead_days.clamp(1, MAX_LOOKAHEAD_DAYS),
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(cal.timeout_secs.max(1)))
+                .connect_timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+        })
+    }
+
+    fn account(&self, name: Option<&str>) -> anyhow::Result<&Account> {
+        match name {
+            None => Ok(&self.accounts[0]),
+            Some(name) => self
+                .accounts
+                .iter()
+                .find(|a| a.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    let known: Vec<&str> = self.accounts.iter().map(|a| a.name.as_str()).collect();
+                    anyhow::anyhow!("Unknown calendar '{name}'. Available: {}", known.join(", "))
+                }),
+        }
+    }
+
+    /// Events overlapping `[start, end)`, with recurring events expanded by
+    /// the server.
+    async fn list_events(
+        &self,
+        account: &Account,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<Event>> {
+        let resp = self
+            .client
+            .request(Method::from_bytes(b"REPORT")?, &account.url)
+            .basic_auth(&account.username, Some(&account.password))
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(calendar_query(start, end))
+            .send()
+            .await?;
+        let status = resp.status();
+        if status != StatusCode::MULTI_STATUS && !status.is_success() {
+            anyhow::bail!("CalDAV REPORT failed ({status})");
+        }
+        let body = resp.text().await?;
+
+        let mut events: Vec<Event> = calendar_data_blocks(&body)
+            .iter()
+            .flat_map(|ics| parse_events(ics))
+            .collect();
+        events.sort_by_key(|e| e.start.sort_key());
+        Ok(events)
+    }
+
+    async fn create_event(&self, account: &Account, event: &NewEvent) -> anyhow::Result<String> {
+        let uid = format!("{}@zeroclaw", uuid::Uuid::new_v4());
+        let resp = self
+            .client
+            .put(format!("{}{}.ics", account.url, uid))
+            .basic_auth(&account.username, Some(&account.password))
+            .header("Content-Type", "text/calendar; charset=utf-8")
+            .header("If-None-Match", "*")
+            .body(event.to_ics(&uid, Utc::now()))
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!(
+                "CalDAV PUT failed ({status}): {}",
+                crate::util::truncate_with_ellipsis(body.trim(), 200)
+            );
+        }
+        Ok(uid)
+    }
+
+    async fn run_list(&self, args: &serde_json::Value) -> anyhow::Result<ToolResult> {
+        let account = self.account(args.get("calendar").and_then(|v| v.as_str()))?;
+        #[allow(clippy::cast_possible_truncation)]
+        let days = args
+            .get("days")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(self.lookahead_days, |d| {
+                d.min(u64::from(MAX_LOOKAHEAD_DAYS)) as u32
+            })
+            .max(1);
+        let start = Utc::now();
+        let end = start + Duration::days(i64::from(days));
+
+        match self.list_events(account, start, end).await {
+            Ok(events) => Ok(ToolResult {
+                success: true,
+                output: format_events(&account.name, days, &events),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to list events: {e}")),
+            }),
+        }
+    }
+
+    async fn run_create(&self, args: &serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+        let account = self.account(args.get("calendar").and_then(|v| v.as_str()))?;
+        let field = |name: &str| {
+            args.get(name)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+        };
+        let title = field("title").ok_or_else(|| anyhow::anyhow!("Missing 'title' for create"))?;
+        let start = field("start").ok_or_else(|| anyhow::anyhow!("Missing 'start' for create"))?;
+
+        let event = match NewEvent::parse(
+            title,
+            start,
+            field("end"),
+            field("location"),
+            field("description"),
+        ) {
+            Ok(event) => event,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                })
+            }
+        };
+
+        if self.security.dry_run {
+            let action = format!("create the event \"{title}\" starting {start}");
+            return Ok(dry_run::preview(self.name(), &action, args));
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: rate limit exceeded".into()),
+            });
+        }
+
+        match self.create_event(account, &event).await {
+            Ok(uid) => Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Created \"{}\" on {} in calendar '{}' (uid {uid})",
+                    event.title,
+                    event.start.display(),
+                    account.name
+                ),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to create event: {e}")),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for CalendarTool {
+    fn name(&self) -> &str {
+        "calendar"
+    }
+
+    fn description(&self) -> &str {
+        "List upcoming events or create an event on the user's CalDAV calendars. Times without an offset are local time; a plain date makes an all-day event."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "create"],
+                    "description": "'list' upcoming events or 'create' one"
+                },
+                "calendar": {
+                    "type": "string",
+                    "description": "Calendar name (defaults to the first configured)"
+                },
+                "days": {
+                    "type": "integer",
+                    "description": "For list: how many days ahead to look"
+                },
+                "title": {"type": "string", "description": "For create: event title"},
+                "start": {
+                    "type": "string",
+                    "description": "For create: '2026-03-14T15:00', RFC 3339, or '2026-03-14' for all-day"
+                },
+                "end": {
+                    "type": "string",
+                    "description": "For create: end time (default: 1 hour after start)"
+                },
+                "location": {"type": "string", "description": "For create: location"},
+                "description": {"type": "string", "description": "For create: notes"}
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+
+        match action {
+            "list" => self.run_list(&args).await,
+            "create" => self.run_create(&args).await,
+            other => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Unknown action '{other}'. Use 'list' or 'create'")),
+            }),
+        }
+    }
+}
+
+// ── Event times ─────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EventTime {
+    AllDay(NaiveDate),
+    Utc(DateTime<Utc>),
+    /// Floating or `TZID`-qualified wall-clock time, shown as written
+    Local(NaiveDateTime),
+}
+
+impl EventTime {
+    /// Parse an iCalendar `DTSTART`/`DTEND` value.
+    fn from_ics(value: &str, params: &str) -> Option<Self> {
+        let value = value.trim();
+        let is_date = value.len() == 8
+            || params
+                .split(';')
+                .any(|p| p.eq_ignore_ascii_case("VALUE=DATE"));
+        if is_date {
+            return NaiveDate::parse_from_str(value, "%Y%m%d")
+                .ok()
+                .map(Self::AllDay);
+        }
+        if let Some(utc) = value.strip_suffix('Z') {
+            return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+                .ok()
+                .map(|t| Self::Utc(Utc.from_utc_datetime(&t)));
+        }
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .ok()
+            .map(Self::Local)
+    }
+
+    /// Parse a time given by the model: RFC 3339, local `YYYY-MM-DDTHH:MM`,
+    /// or a plain date.
+    fn from_input(input: &str) -> anyhow::Result<Self> {
+        let input = input.trim();
+        if let Ok(t) = DateTime::parse_from_rfc3339(input) {
+            return Ok(Self::Utc(t.with_timezone(&Utc)));
+        }
+        if let Ok(d) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+            return Ok(Self::AllDay(d));
+        }
+        let naive = [
+            "%Y-%m-%dT%H:%M:%S",
+            "%Y-%m-%dT%H:%M",
+            "%Y-%m-%d %H:%M:%S",
+            "%Y-%m-%d %H:%M",
+        ]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(input, fmt).ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unrecognized time '{input}'. Use '2026-03-14T15:00', RFC 3339, or '2026-03-14'"
+            )
+        })?;
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|t| Self::Utc(t.with_timezone(&Utc)))
+            .ok_or_else(|| anyhow::anyhow!("'{input}' does not exist in the local time zone"))
+    }
+
+    fn sort_key(&self) -> NaiveDateTime {
+        match self {
+            Self::AllDay(d) => d.and_hms_opt(0, 0, 0).unwrap_or_default(),
+            Self::Utc(t) => t.with_timezone(&Local).naive_local(),
+            Self::Local(t) => *t,
+        }
+    }
+
+    fn display(&self) -> String {
+        match self {
+            Self::AllDay(d) => format!("{} (all day)", d.format("%a %Y-%m-%d")),
+            Self::Utc(t) => t
+                .with_timezone(&Local)
+                .format("%a %Y-%m-%d %H:%M")
+                .to_string(),
+            Self::Local(t) => t.format("%a %Y-%m-%d %H:%M").to_string(),
+        }
+    }
+
+    /// `DTSTART`/`DTEND` property line for `name`.
+    fn to_ics(&self, name: &str) -> String {
+        match self {
+            Self::AllDay(d) => format!("{name};VALUE=DATE:{}", d.format("%Y%m%d")),
+            Self::Utc(t) => format!("{name}:{}", t.format("%Y%m%dT%H%M%SZ")),
+            Self::Local(t) => format!("{name}:{}", t.format("%Y%m%dT%H%M%S")),
+        }
+    }
+}
+
+// ── Reading events ──────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Event {
+    summary: String,
+    start: EventTime,
+    end: Option<EventTime>,
+    location: Option<String>,
+}
+
+fn calendar_query(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    let (start, end) = (start.format("%Y%m%dT%H%M%SZ"), end.format("%Y%m%dT%H%M%SZ"));
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <C:calendar-data><C:expand start="{start}" end="{end}"/></C:calendar-data>
+  </D:prop>
+  <C:filter>
+    <C:comp-filter name="VCALENDAR">
+      <C:comp-filter name="VEVENT">
+        <C:time-range start="{start}" end="{end}"/>
+      </C:comp-filter>
+    </C:comp-filter>
+  </C:filter>
+</C:calendar-query>"#
+    )
+}
+
+/// Contents of every `calendar-data` element in a multistatus response,
+/// whatever namespace prefix the server chose.
+fn calendar_data_blocks(xml: &str) -> Vec<String> {
+    const NAME: &str = "calendar-data";
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = xml[pos..].find(NAME) {
+        let name_at = pos + found;
+        pos = name_at + NAME.len();
+
+        // Opening tag: `<` + optional `prefix:` directly before the name
+        let Some(lt) = xml[..name_at].rfind('<') else {
+            continue;
+        };
+        let prefix = &xml[lt + 1..name_at];
+        let is_open_tag = prefix.is_empty()
+            || (prefix.ends_with(':')
+                && prefix[..prefix.len() - 1]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'));
+        if !is_open_tag || !xml[pos..].starts_with(['>', ' ', '\t', '\r', '\n']) {
+            continue;
+        }
+        let Some(gt) = xml[pos..].find('>') else {
+            break;
+        };
+        let content_start = pos + gt + 1;
+        if xml[..content_start].ends_with("/>") {
+            continue;
+        }
+        let close = format!("</{prefix}{NAME}");
+        let Some(content_len) = xml[content_start..].find(&close) else {
+            break;
+        };
+        let raw = xml[content_start..content_start + content_len].trim();
+        let text = match raw
+            .strip_prefix("<![CDATA[")
+            .and_then(|r| r.strip_suffix("]]>"))
+        {
+            Some(cdata) => cdata.to_string(),
+            None => decode_xml_entities(raw),
+        };
+        if !text.trim().is_empty() {
+            blocks.push(text);
+        }
+        pos = content_start + content_len;
+    }
+    blocks
+}
+
+fn decode_xml_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#13;", "\r")
+        .replace("&#xD;", "\r")
+        .replace("&#10;", "\n")
+        .replace("&#xA;", "\n")
+        .replace("&amp;", "&")
+}
+
+/// Parse the `VEVENT`s of an iCalendar document. Nested components such as
+/// `VALARM` are skipped.
+fn parse_events(ics: &str) -> Vec<Event> {
+    /// Properties of the `VEVENT` being read.
+    #[derive(Default)]
+    struct Partial {
+        summary: Option<String>,
+        start: Option<EventTime>,
+        end: Option<EventTime>,
+        location: Option<String>,
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<Partial> = None;
+    let mut nested = 0usize;
+
+    for line in unfold(ics) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        if let ("BEGIN", "VEVENT") = (name.as_str(), value) {
+            current = Some(Partial::default());
+            nested = 0;
+            continue;
+        }
+        let Some(event) = current.as_mut() else {
+            continue;
+        };
+        match (name.as_str(), value) {
+            ("END", "VEVENT") => {
+                let done = current.take().unwrap_or_default();
+                if let Some(start) = done.start {
+                    events.push(Event {
+                        summary: done.summary.unwrap_or_else(|| "(no title)".into()),
+                        start,
+                        end: done.end,
+                        location: done.location,
+                    });
+                }
+            }
+            ("BEGIN", _) => nested += 1,
+            ("END", _) => nested = nested.saturating_sub(1),
+            _ if nested > 0 => {}
+            ("SUMMARY", v) => event.summary = Some(unescape_text(v)),
+            ("DTSTART", v) => event.start = EventTime::from_ics(v, params),
+            ("DTEND", v) => event.end = EventTime::from_ics(v, params),
+            ("LOCATION", v) => event.location = Some(unescape_text(v)).filter(|l| !l.is_empty()),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Join folded content lines (continuations start with a space or tab).
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(cont), Some(last)) => last.push_str(cont),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// Split `NAME;PARAMS:VALUE`, ignoring colons inside quoted parameters.
+fn split_property(line: &str) -> Option<(String, &str, &str)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.trim().to_ascii_uppercase(), params, value))
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => {}
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out.trim().to_string()
+}
+
+fn format_events(calendar: &str, days: u32, events: &[Event]) -> String {
+    if events.is_empty() {
+        return format!("No events in calendar '{calendar}' in the next {days} day(s).");
+    }
+    let mut out = format!("Events in calendar '{calendar}' (next {days} day(s)):\n");
+    for event in events.iter().take(MAX_LISTED_EVENTS) {
+        let _ = write!(out, "- {}", event.start.display());
+        if let Some(end) = &event.end {
+            if !matches!(end, EventTime::AllDay(_)) {
+                let _ = write!(out, " – {}", end.display());
+            }
+        }
+        let _ = write!(out, ": {}", event.summary);
+        if let Some(location) = &event.location {
+            let _ = write!(out, " @ {location}");
+        }
+        out.push('\n');
+    }
+    if events.len() > MAX_LISTED_EVENTS {
+        let _ = writeln!(out, "... and {} more", events.len() - MAX_LISTED_EVENTS);
+    }
+    out
+}
+
+// ── Creating events ─────────────────────────────────────────────
+
+#[derive(Debug)]
+struct NewEvent {
+    title: String,
+    start: EventTime,
+    end: EventTime,
+    location: Option<String>,
+    description: Option<String>,
+}
+
+impl NewEvent {
+    fn parse(
+        title: &str,
+        start: &str,
+        end: Option<&str>,
+        location: Option<&str>,
+        description: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let start = EventTime::from_input(start)?;
+        let end = match end {
+            Some(end) => EventTime::from_input(end)?,
+            None => match &start {
+                EventTime::AllDay(d) => EventTime::AllDay(*d + Duration::days(1)),
+                EventTime::Utc(t) => EventTime::Utc(*t + Duration::hours(1)),
+                EventTime::Local(t) => EventTime::Local(*t + Duration::hours(1)),
+            },
+        };
+        let end = match (&start, end) {
+            // An all-day end date is inclusive for people, exclusive in iCalendar
+            (EventTime::AllDay(s), EventTime::AllDay(e)) if e == *s => {
+                EventTime::AllDay(e + Duration::days(1))
+            }
+            (EventTime::AllDay(_), EventTime::AllDay(e)) => EventTime::AllDay(e),
+            (EventTime::AllDay(_), _) | (_, EventTime::AllDay(_)) => {
+                anyhow::bail!("Start and end must both be dates or both be times")
+            }
+            (_, end) => end,
+        };
+        anyhow::ensure!(end.sort_key() > start.sort_key(), "End must be after start");
+
+        Ok(Self {
+            title: title.to_string(),
+            start,
+            end,
+            location: location.map(String::from),
+            description: description.map(String::from),
+        })
+    }
+
+    fn to_ics(&self, uid: &str, now: DateTime<Utc>) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//ZeroClaw//calendar tool//EN".to_string(),
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{uid}"),
+            format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+            self.start.to_ics("DTSTART"),
+            self.end.to_ics("DTEND"),
+            format!("SUMMARY:{}", escape_text(&self.title)),
+        ];
+        if let Some(location) = &self.location {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+        if let Some(description) = &self.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        lines.push("END:VEVENT".into());
+        lines.push("END:VCALENDAR".into());
+
+        let mut out = String::new();
+        for line in lines {
+            out.push_str(&fold(&line));
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets without splitting UTF-8 sequences.
+fn fold(line: &str) -> String {
+    const LIMIT: usize = 75;
+    let mut out = String::with_capacity(line.len() + line.len() / LIMIT * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::CalendarAccountConfig;
+    use tempfile::TempDir;
+
+    const SAMPLE_ICS: &str = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VEVENT\r\nUID:1\r\nSUMMARY:Team sync\\, weekly\r\nDTSTART:20261019T140000Z\r\nDTEND:20261019T143000Z\r\nLOCATION:Room 4\r\nBEGIN:VALARM\r\nSUMMARY:Alarm text\r\nDTSTART:20000101T000000Z\r\nEND:VALARM\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nSUMMARY:Offsite pl\r\n anning\r\nDTSTART;VALUE=DATE:20261021\r\nDTEND;VALUE=DATE:20261022\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nSUMMARY:Dentist\r\nDTSTART;TZID=\"Europe/Berlin\":20261020T090000\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+
+    fn config_with(accounts: Vec<CalendarAccountConfig>, dir: &TempDir) -> Config {
+        let mut config = Config::default();
+        config.config_path = dir.path().join("config.toml");
+        config.calendar.enabled = true;
+        config.calendar.accounts = accounts;
+        config
+    }
+
+    fn account(name: &str, password: &str) -> CalendarAccountConfig {
+        CalendarAccountConfig {
+            name: name.into(),
+            url: "https://dav.example.com/cal/alice/personal".into(),
+            username: "alice".into(),
+            password: password.into(),
+        }
+    }
+
+    #[test]
+    fn parse_events_reads_vevents_and_skips_alarms() {
+        let events = parse_events(SAMPLE_ICS);
+        assert_eq!(events.len(), 3);
+
+        assert_eq!(events[0].summary, "Team sync, weekly");
+        assert_eq!(events[0].location.as_deref(), Some("Room 4"));
+        assert_eq!(
+            events[0].start,
+            EventTime::Utc(Utc.with_ymd_and_hms(2026, 10, 19, 14, 0, 0).unwrap())
+        );
+
+        assert_eq!(events[1].summary, "Offsite planning");
+        assert_eq!(
+            events[1].start,
+            EventTime::AllDay(NaiveDate::from_ymd_opt(2026, 10, 21).unwrap())
+        );
+
+        assert_eq!(events[2].summary, "Dentist");
+        assert!(matches!(events[2].start, EventTime::Local(_)));
+        assert!(events[2].end.is_none());
+    }
+
+    #[test]
+    fn calendar_data_blocks_handle_prefixes_entities_and_cdata() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response><d:propstat><d:prop>
+    <cal:calendar-data>BEGIN:VCALENDAR&#13;
+SUMMARY:R&amp;D review&#13;
+END:VCALENDAR</cal:calendar-data>
+  </d:prop></d:propstat></d:response>
+  <d:response><d:propstat><d:prop>
+    <calendar-data xmlns="urn:ietf:params:xml:ns:caldav"><![CDATA[BEGIN:VCALENDAR
+END:VCALENDAR]]></calendar-data>
+  </d:prop></d:propstat></d:response>
+  <d:response><d:propstat><d:prop><cal:calendar-data/></d:prop></d:propstat></d:response>
+</d:multistatus>"#;
+        let blocks = calendar_data_blocks(xml);
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[0].contains("SUMMARY:R&D review\r"));
+        assert_eq!(blocks[1], "BEGIN:VCALENDAR\nEND:VCALENDAR");
+    }
+
+    #[test]
+    fn calendar_query_filters_by_time_range() {
+        let start = Utc.with_ymd_and_hms(2026, 10, 16, 8, 0, 0).unwrap();
+        let query = calendar_query(start, start + Duration::days(7));
+        assert!(
+            query.contains(r#"<C:time-range start="20261016T080000Z" end="20261023T080000Z"/>"#)
+        );
+        assert!(query.contains("<C:expand "));
+    }
+
+    #[test]
+    fn new_event_defaults_and_validation() {
+        let event = NewEvent::parse("Call", "2026-10-20T15:00:00Z", None, None, None).unwrap();
+        assert_eq!(
+            event.end,
+            EventTime::Utc(Utc.with_ymd_and_hms(2026, 10, 20, 16, 0, 0).unwrap())
+        );
+
+        let all_day =
+            NewEvent::parse("Trip", "2026-10-20", Some("2026-10-20"), None, None).unwrap();
+        assert_eq!(
+            all_day.end,
+            EventTime::AllDay(NaiveDate::from_ymd_opt(2026, 10, 21).unwrap())
+        );
+
+        assert!(NewEvent::parse(
+            "x",
+            "2026-10-20T15:00Z",
+            Some("2026-10-20T14:00Z"),
+            None,
+            None
+        )
+        .is_err());
+        assert!(NewEvent::parse("x", "2026-10-20", Some("2026-10-21T10:00"), None, None).is_err());
+        assert!(NewEvent::parse("x", "next tuesday", None, None, None).is_err());
+        assert!(matches!(
+            EventTime::from_input("2026-10-20T15:00").unwrap(),
+            EventTime::Utc(_)
+        ));
+    }
+
+    #[test]
+    fn new_event_serializes_escaped_and_folded_ics() {
+        let event = NewEvent::parse(
+            "Review; Q4, budget",
+            "2026-10-20T15:00:00Z",
+            None,
+            Some("HQ"),
+            Some(&"line one\nline two ".repeat(8)),
+        )
+        .unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+        let ics = event.to_ics("abc@zeroclaw", now);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:abc@zeroclaw\r\n"));
+        assert!(ics.contains("DTSTART:20261020T150000Z\r\n"));
+        assert!(ics.contains("DTEND:20261020T160000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Review\\; Q4\\, budget\r\n"));
+        assert!(ics.contains("LOCATION:HQ\r\n"));
+        assert!(ics.lines().all(|l| l.trim_end_matches('\r').len() <= 75));
+
+        // Round-trips through our own parser
+        let parsed = parse_events(&ics);
+        assert_eq!(parsed[0].summary, "Review; Q4, budget");
+    }
+
+    #[test]
+    fn format_events_lists_times_and_locations() {
+        let out = format_events("work", 7, &parse_events(SAMPLE_ICS));
+        assert!(out.starts_with("Events in calendar 'work' (next 7 day(s)):"));
+        assert!(out.contains(": Team sync, weekly @ Room 4\n"));
+        assert!(out.contains("Wed 2026-10-21 (all day): Offsite planning\n"));
+        assert_eq!(
+            format_events("work", 3, &[]),
+            "No events in calendar 'work' in the next 3 day(s)."
+        );
+    }
+
+    #[test]
+    fn from_config_decrypts_passwords_and_requires_accounts() {
+        let dir = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy::default());
+
+        let mut config = config_with(vec![], &dir);
+        assert!(CalendarTool::from_config(&config, security.clone()).is_none());
+        config.calendar.enabled = false;
+        assert!(CalendarTool::from_config(&config, security.clone()).is_none());
+
+        let encrypted = config.secret_store().encrypt("app-password").unwrap();
+        assert!(encrypted.starts_with("enc2:"));
+        let config = config_with(
+            vec![
+                account("personal", &encrypted),
+                CalendarAccountConfig {
+                    url: "ftp://nope".into(),
+                    ..account("broken", "x")
+                },
+            ],
+            &dir,
+        );
+        let tool = CalendarTool::from_config(&config, security).unwrap();
+        assert_eq!(tool.accounts.len(), 1);
+        assert_eq!(tool.accounts[0].password, "app-password");
+        assert_eq!(
+            tool.accounts[0].url,
+            "https://dav.example.com/cal/alice/personal/"
+        );
+        assert!(tool.account(Some("PERSONAL")).is_ok());
+        assert!(tool.account(Some("work")).is_err());
+    }
+
+    /// Serve one HTTP response and hand back the raw request.
+    async fn serve_once(
+        status: &'static str,
+        body: String,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/cal/alice/personal/",
+            listener.local_addr().unwrap()
+        );
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(head_end) = text.find("\r\n\r\n") {
+                    let length = text[..head_end]
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= head_end + 4 + length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn list_sends_calendar_query_report() {
+        let xml = format!(
+            "<d:multistatus xmlns:d=\"DAV:\" xmlns:c=\"urn:ietf:params:xml:ns:caldav\"><d:response><d:propstat><d:prop><c:calendar-data>{}</c:calendar-data></d:prop></d:propstat></d:response></d:multistatus>",
+            SAMPLE_ICS.replace('\r', "&#13;")
+        );
+        let (url, server) = serve_once("207 Multi-Status", xml).await;
+        let dir = TempDir::new().unwrap();
+        let config = config_with(
+            vec![CalendarAccountConfig {
+                url,
+                ..account("personal", "pw")
+            }],
+            &dir,
+        );
+        let tool = CalendarTool::from_config(&config, Arc::new(SecurityPolicy::default())).unwrap();
+
+        let result = tool
+            .execute(json!({"action": "list", "days": 3}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("Team sync, weekly @ Room 4"));
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("REPORT /cal/alice/personal/ HTTP/1.1"));
+        let lower = request.to_ascii_lowercase();
+        assert!(lower.contains("depth: 1"));
+        // alice:pw
+        assert!(lower.contains("authorization: basic ywxpy2u6chc="));
+        assert!(request.contains("<C:comp-filter name=\"VEVENT\">"));
+    }
+
+    #[tokio::test]
+    async fn create_is_blocked_in_read_only_mode() {
+        let dir = TempDir::new().unwrap();
+        let config = config_with(vec![account("personal", "pw")], &dir);
+        let security = Arc::new(SecurityPolicy {
+            autonomy: crate::security::AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tool = CalendarTool::from_config(&config, security).unwrap();
+        let result = tool
+            .execute(json!({"action": "create", "title": "x", "start": "2026-10-20"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+}
@@ -0,0 +1,568 @@
+//! Checkpoints of the workspace taken before tools change it.
+//!
+//! `file_write`, `file_edit` and shell commands that are not read-only
+//! snapshot the workspace into a shadow git repository first, so a bad edit
+//! can be rolled back even when the workspace has no version control of its
+//! own. Git only stores what changed, and the workspace's own `.git` and
+//! ignore rules are left alone. Memory, sessions and other agent state are
+//! not snapshotted.
+//!
+//! The shadow repository lives under [`CHECKPOINT_ROOT`], outside the
+//! workspace, so tools cannot plant git config (`core.fsmonitor`, filter
+//! drivers, hooks) that would run on the next checkpoint. Git is also run
+//! with hooks, fsmonitor, system/global config and attribute filters off, so
+//! a `.gitattributes` in the workspace cannot run anything either.
+//!
+//! [`undo_last`] puts the workspace files back as they were at the newest
+//! checkpoint and drops it; calling it again steps further back.
+
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+
+/// Shadow repositories, one per workspace. Always in `forbidden_paths`.
+pub const CHECKPOINT_ROOT: &str = "~/.zeroclaw/checkpoints";
+
+/// Never snapshotted: agent state and build output.
+const EXCLUDES: &[&str] = &[
+    "/memory/",
+    "/namespaces/",
+    "/sessions/",
+    "/state/",
+    "/cron/",
+    "*.db",
+    "*.db-shm",
+    "*.db-wal",
+    "target/",
+    "node_modules/",
+];
+
+/// Checkpoints listed by the tool.
+const LIST_LIMIT: usize = 20;
+
+/// Serializes git runs, which share the shadow repository's index.
+static GIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// A snapshot of the workspace, taken before a tool changed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Checkpoint {
+    /// Abbreviated commit hash in the shadow repository
+    pub id: String,
+    /// Unix time the checkpoint was taken
+    pub created_at: i64,
+    /// What was about to run, e.g. `file_edit src/main.rs`
+    pub label: String,
+}
+
+/// The result of [`undo_last`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Undone {
+    pub checkpoint: Checkpoint,
+    /// Workspace paths that were restored, recreated or removed
+    pub files: Vec<String>,
+    /// Checkpoints still available
+    pub remaining: usize,
+}
+
+/// Attributes that keep `.gitattributes` in the workspace from selecting
+/// filter drivers or rewriting content. `info/attributes` wins over them.
+const ATTRIBUTES: &str = "* -filter -ident -text -diff -merge -working-tree-encoding\n";
+
+/// The shadow repository of one workspace.
+struct Shadow {
+    git_dir: PathBuf,
+    workspace: PathBuf,
+}
+
+impl Shadow {
+    /// The repository for `workspace` under [`CHECKPOINT_ROOT`].
+    fn for_workspace(workspace: &Path) -> Self {
+        let root = match CHECKPOINT_ROOT.strip_prefix("~/") {
+            Some(rest) => directories::UserDirs::new().map_or_else(
+                || PathBuf::from(".zeroclaw/checkpoints"),
+                |u| u.home_dir().join(rest),
+            ),
+            None => PathBuf::from(CHECKPOINT_ROOT),
+        };
+        Self::in_root(&root, workspace)
+    }
+
+    /// The repository for `workspace` under `root`, named by a hash of the
+    /// workspace's canonical path.
+    fn in_root(root: &Path, workspace: &Path) -> Self {
+        let canonical = workspace
+            .canonicalize()
+            .unwrap_or_else(|_| workspace.to_path_buf());
+        let hash = hex::encode(Sha256::digest(canonical.to_string_lossy().as_bytes()));
+        Self {
+            git_dir: root.join(&hash[..16]),
+            workspace: workspace.to_path_buf(),
+        }
+    }
+
+    fn exists(&self) -> bool {
+        self.git_dir.join("HEAD").exists()
+    }
+}
+
+fn git(shadow: &Shadow) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.arg("--git-dir")
+        .arg(&shadow.git_dir)
+        .arg("--work-tree")
+        .arg(&shadow.workspace)
+        .args([
+            "-c",
+            "user.name=ZeroClaw",
+            "-c",
+            "user.email=checkpoints@zeroclaw.local",
+            "-c",
+            "commit.gpgsign=false",
+            "-c",
+            "core.autocrlf=false",
+            "-c",
+            "core.fsmonitor=false",
+            "-c",
+            "core.hooksPath=/dev/null",
+            "-c",
+            "core.attributesFile=/dev/null",
+        ])
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .env("GIT_ATTR_NOSYSTEM", "1")
+        .env_remove("GIT_INDEX_FILE")
+        .env_remove("GIT_DIR")
+        .env_remove("GIT_WORK_TREE")
+        .env_remove("GIT_CONFIG_PARAMETERS");
+    cmd
+}
+
+/// Run git against `shadow` and return its stdout.
+fn run(shadow: &Shadow, args: &[&str]) -> Result<String> {
+    let output = git(shadow)
+        .args(args)
+        .output()
+        .context("Failed to run git; checkpoints need git installed")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn head(shadow: &Shadow) -> Option<String> {
+    run(shadow, &["rev-parse", "--verify", "--quiet", "HEAD"])
+        .ok()
+        .map(|hash| hash.trim().to_string())
+}
+
+fn init(shadow: &Shadow) -> Result<()> {
+    let dir = &shadow.git_dir;
+    if shadow.exists() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let status = Command::new("git")
+        .args(["-c", "core.hooksPath=/dev/null", "init", "--quiet", "--bare"])
+        .arg(dir)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .status()
+        .context("Failed to run git; checkpoints need git installed")?;
+    anyhow::ensure!(status.success(), "git init failed in {}", dir.display());
+    std::fs::create_dir_all(dir.join("info"))?;
+    std::fs::write(dir.join("info").join("exclude"), EXCLUDES.join("\n") + "\n")?;
+    std::fs::write(dir.join("info").join("attributes"), ATTRIBUTES)?;
+    Ok(())
+}
+
+/// Snapshot `workspace` as a checkpoint named `label`.
+pub fn create(workspace: &Path, label: &str) -> Result<Checkpoint> {
+    create_in(&Shadow::for_workspace(workspace), label)
+}
+
+fn create_in(shadow: &Shadow, label: &str) -> Result<Checkpoint> {
+    let _lock = GIT_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    init(shadow)?;
+    run(shadow, &["add", "--all"])?;
+    run(
+        shadow,
+        &[
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "--no-verify",
+            "-m",
+            label,
+        ],
+    )?;
+    list_locked(shadow, 1)?
+        .pop()
+        .context("Checkpoint was not recorded")
+}
+
+/// Take a checkpoint before a tool changes `workspace`, when the running
+/// turn has checkpoints on. Outside a turn none are taken, so tools run on
+/// their own do not snapshot arbitrary directories. Failures are logged
+/// rather than stopping the tool.
+pub async fn before_change(workspace: &Path, label: String) {
+    let enabled = crate::agent::settings::current().is_some_and(|s| s.checkpoints.enabled);
+    if !enabled {
+        return;
+    }
+    let workspace = workspace.to_path_buf();
+    let result = tokio::task::spawn_blocking(move || create(&workspace, &label)).await;
+    match result {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => tracing::warn!("Checkpoint skipped: {e:#}"),
+        Err(e) => tracing::warn!("Checkpoint skipped: {e}"),
+    }
+}
+
+/// Up to `limit` checkpoints in `workspace`, newest first.
+pub fn list(workspace: &Path, limit: usize) -> Result<Vec<Checkpoint>> {
+    list_in(&Shadow::for_workspace(workspace), limit)
+}
+
+fn list_in(shadow: &Shadow, limit: usize) -> Result<Vec<Checkpoint>> {
+    let _lock = GIT_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    list_locked(shadow, limit)
+}
+
+fn list_locked(shadow: &Shadow, limit: usize) -> Result<Vec<Checkpoint>> {
+    if !shadow.exists() || head(shadow).is_none() {
+        return Ok(Vec::new());
+    }
+    let limit = format!("--max-count={limit}");
+    let log = run(shadow, &["log", &limit, "--format=%h%x1f%ct%x1f%s"])?;
+    Ok(log
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\u{1f}');
+            Some(Checkpoint {
+                id: fields.next()?.to_string(),
+                created_at: fields.next()?.parse().ok()?,
+                label: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+fn count(shadow: &Shadow) -> Result<usize> {
+    if head(shadow).is_none() {
+        return Ok(0);
+    }
+    let count = run(shadow, &["rev-list", "--count", "HEAD"])?;
+    count
+        .trim()
+        .parse()
+        .context("Unexpected git rev-list output")
+}
+
+/// Restore the workspace files to the newest checkpoint and drop it. Files
+/// created since are removed. `None` when there is no checkpoint.
+pub fn undo_last(workspace: &Path) -> Result<Option<Undone>> {
+    undo_last_in(&Shadow::for_workspace(workspace))
+}
+
+fn undo_last_in(shadow: &Shadow) -> Result<Option<Undone>> {
+    let _lock = GIT_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let Some(checkpoint) = list_locked(shadow, 1)?.pop() else {
+        return Ok(None);
+    };
+
+    run(shadow, &["add", "--all"])?;
+    let files: Vec<String> = run(
+        shadow,
+        &["diff", "--cached", "--no-ext-diff", "--name-only", "HEAD"],
+    )?
+    .lines()
+    .map(str::to_string)
+    .collect();
+    run(shadow, &["read-tree", "-u", "--reset", "HEAD"])?;
+
+    if run(shadow, &["rev-parse", "--verify", "--quiet", "HEAD~1"]).is_ok() {
+        run(shadow, &["update-ref", "HEAD", "HEAD~1"])?;
+    } else {
+        run(shadow, &["update-ref", "-d", "HEAD"])?;
+    }
+    Ok(Some(Undone {
+        checkpoint,
+        files,
+        remaining: count(shadow)?,
+    }))
+}
+
+/// One line per checkpoint, for people and for the model.
+pub fn describe_list(checkpoints: &[Checkpoint]) -> String {
+    if checkpoints.is_empty() {
+        return "No checkpoints yet".to_string();
+    }
+    let mut text = String::from("Checkpoints (newest first):");
+    for checkpoint in checkpoints {
+        let when = chrono::DateTime::from_timestamp(checkpoint.created_at, 0).map_or_else(
+            || checkpoint.created_at.to_string(),
+            |t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            },
+        );
+        let _ = write!(
+            text,
+            "\n  {} {when}  before {}",
+            checkpoint.id, checkpoint.label
+        );
+    }
+    text
+}
+
+/// What [`undo_last`] did, or that there was nothing to undo.
+pub fn describe_undo(undone: Option<&Undone>) -> String {
+    let Some(undone) = undone else {
+        return "No checkpoints to undo".to_string();
+    };
+    let mut text = format!(
+        "Restored the workspace to before {}",
+        undone.checkpoint.label
+    );
+    if undone.files.is_empty() {
+        text.push_str("; no files had changed since");
+    } else {
+        let _ = write!(
+            text,
+            "; {} files: {}",
+            undone.files.len(),
+            undone.files.join(", ")
+        );
+    }
+    let _ = write!(text, " ({} checkpoints left)", undone.remaining);
+    text
+}
+
+/// List checkpoints and undo the last change made by a tool
+pub struct CheckpointsTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl CheckpointsTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+
+    fn workspace(&self) -> PathBuf {
+        self.security.workspace_dir.clone()
+    }
+}
+
+#[async_trait]
+impl Tool for CheckpointsTool {
+    fn name(&self) -> &str {
+        "checkpoints"
+    }
+
+    fn description(&self) -> &str {
+        "List workspace checkpoints taken before file_write, file_edit and shell changes, or undo the latest change. Use when: an edit or command went wrong and its files should go back to how they were. Don't use when: the change was intended."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "undo"],
+                    "description": "list: show recent checkpoints; undo: restore files to the newest checkpoint and drop it"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+        let workspace = self.workspace();
+
+        let output = match action {
+            "list" => {
+                let checkpoints =
+                    tokio::task::spawn_blocking(move || list(&workspace, LIST_LIMIT)).await??;
+                describe_list(&checkpoints)
+            }
+            "undo" => {
+                if !self.security.can_act() {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("Undo is not allowed in read-only mode".into()),
+                    });
+                }
+                if self.security.dry_run {
+                    return Ok(dry_run::preview(
+                        self.name(),
+                        "restore the files to the newest checkpoint",
+                        &args,
+                    ));
+                }
+                if !self.security.record_action() {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("Rate limit exceeded: action budget exhausted".into()),
+                    });
+                }
+                let undone = tokio::task::spawn_blocking(move || undo_last(&workspace)).await??;
+                describe_undo(undone.as_ref())
+            }
+            other => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Unknown action: {other}. Use 'list' or 'undo'")),
+                });
+            }
+        };
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_available() -> bool {
+        Command::new("git").arg("--version").output().is_ok()
+    }
+
+    #[test]
+    fn undo_restores_edits_and_removes_new_files() {
+        if !git_available() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let root = tempfile::tempdir().unwrap();
+        let ws = dir.path();
+        let shadow = Shadow::in_root(root.path(), ws);
+        std::fs::write(ws.join("notes.txt"), "v1").unwrap();
+        std::fs::create_dir(ws.join("memory")).unwrap();
+        std::fs::write(ws.join("memory").join("today.md"), "kept").unwrap();
+
+        create_in(&shadow, "file_write notes.txt").unwrap();
+        assert!(!ws.join(".zeroclaw").exists());
+        std::fs::write(ws.join("notes.txt"), "v2").unwrap();
+        create_in(&shadow, "shell: make").unwrap();
+        std::fs::write(ws.join("notes.txt"), "v3").unwrap();
+        std::fs::write(ws.join("build.log"), "noise").unwrap();
+        std::fs::write(ws.join("memory").join("today.md"), "changed").unwrap();
+
+        let labels: Vec<String> = list_in(&shadow, 10)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        assert_eq!(labels, ["shell: make", "file_write notes.txt"]);
+
+        let undone = undo_last_in(&shadow).unwrap().unwrap();
+        assert_eq!(undone.checkpoint.label, "shell: make");
+        assert_eq!(undone.files, ["build.log", "notes.txt"]);
+        assert_eq!(undone.remaining, 1);
+        assert_eq!(std::fs::read_to_string(ws.join("notes.txt")).unwrap(), "v2");
+        assert!(!ws.join("build.log").exists());
+        // Agent state is left alone
+        assert_eq!(
+            std::fs::read_to_string(ws.join("memory").join("today.md")).unwrap(),
+            "changed"
+        );
+
+        undo_last_in(&shadow).unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(ws.join("notes.txt")).unwrap(), "v1");
+        assert!(undo_last_in(&shadow).unwrap().is_none());
+        assert!(list_in(&shadow, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn planted_fsmonitor_and_filters_are_not_run() {
+        if !git_available() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let root = tempfile::tempdir().unwrap();
+        let ws = dir.path();
+        let shadow = Shadow::in_root(root.path(), ws);
+        std::fs::write(ws.join("notes.txt"), "v1").unwrap();
+        create_in(&shadow, "first").unwrap();
+
+        let fsmonitor = ws.join("fsmonitor-ran");
+        let filter = ws.join("filter-ran");
+        let mut config = std::fs::read_to_string(shadow.git_dir.join("config")).unwrap();
+        let _ = write!(
+            config,
+            "[core]\n\tfsmonitor = touch {}\n[filter \"x\"]\n\tclean = touch {f}\n\tsmudge = touch {f}\n",
+            fsmonitor.display(),
+            f = filter.display()
+        );
+        std::fs::write(shadow.git_dir.join("config"), config).unwrap();
+        std::fs::write(ws.join(".gitattributes"), "* filter=x\n").unwrap();
+        std::fs::write(ws.join("notes.txt"), "v2").unwrap();
+
+        create_in(&shadow, "second").unwrap();
+        assert!(!fsmonitor.exists());
+        assert!(!filter.exists());
+        std::fs::write(ws.join("notes.txt"), "v3").unwrap();
+        undo_last_in(&shadow).unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(ws.join("notes.txt")).unwrap(), "v2");
+        assert!(!fsmonitor.exists());
+        assert!(!filter.exists());
+    }
+
+    #[test]
+    fn shadow_repository_is_outside_the_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let shadow = Shadow::for_workspace(dir.path());
+        assert!(!shadow.git_dir.starts_with(dir.path()));
+        let other = tempfile::tempdir().unwrap();
+        assert_ne!(shadow.git_dir, Shadow::for_workspace(other.path()).git_dir);
+    }
+
+    #[tokio::test]
+    async fn tool_lists_and_refuses_undo_when_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: crate::security::AutonomyLevel::ReadOnly,
+            workspace_dir: dir.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let tool = CheckpointsTool::new(security);
+
+        let listed = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert_eq!(listed.output, "No checkpoints yet");
+        let undo = tool.execute(json!({"action": "undo"})).await.unwrap();
+        assert!(!undo.success);
+        assert!(undo.error.unwrap().contains("read-only"));
+    }
+}
@@ -0,0 +1,321 @@
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Maximum clipboard text read or written, in bytes.
+const MAX_CLIPBOARD_BYTES: usize = 65_536;
+/// Maximum time to wait for a clipboard helper.
+const CLIPBOARD_TIMEOUT_SECS: u64 = 5;
+
+/// Read and write the system clipboard (plain text) using platform commands.
+///
+/// macOS: `pbpaste` / `pbcopy`
+/// Linux: `wl-paste` / `wl-copy` on Wayland, otherwise `xclip` or `xsel`
+/// Windows: `PowerShell` `Get-Clipboard` / `Set-Clipboard`
+pub struct ClipboardTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl ClipboardTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+
+    /// Candidate commands for reading the clipboard, in preference order.
+    fn read_commands() -> Vec<Vec<&'static str>> {
+        if cfg!(target_os = "macos") {
+            vec![vec!["pbpaste"]]
+        } else if cfg!(target_os = "windows") {
+            vec![vec![
+                "powershell",
+                "-NoProfile",
+                "-Command",
+                "Get-Clipboard -Raw",
+            ]]
+        } else {
+            let mut cmds = Vec::new();
+            if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+                cmds.push(vec!["wl-paste", "--no-newline"]);
+            }
+            cmds.push(vec!["xclip", "-selection", "clipboard", "-o"]);
+            cmds.push(vec!["xsel", "--clipboard", "--output"]);
+            cmds
+        }
+    }
+
+    /// Candidate commands for writing the clipboard (text arrives on stdin).
+    fn write_commands() -> Vec<Vec<&'static str>> {
+        if cfg!(target_os = "macos") {
+            vec![vec!["pbcopy"]]
+        } else if cfg!(target_os = "windows") {
+            vec![vec![
+                "powershell",
+                "-NoProfile",
+                "-Command",
+                "$input | Set-Clipboard",
+            ]]
+        } else {
+            let mut cmds = Vec::new();
+            if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+                cmds.push(vec!["wl-copy"]);
+            }
+            cmds.push(vec!["xclip", "-selection", "clipboard", "-i"]);
+            cmds.push(vec!["xsel", "--clipboard", "--input"]);
+            cmds
+        }
+    }
+
+    async fn read_clipboard() -> anyhow::Result<String> {
+        let mut last_error = String::from("no clipboard command available");
+        for argv in Self::read_commands() {
+            let output = tokio::time::timeout(
+                Duration::from_secs(CLIPBOARD_TIMEOUT_SECS),
+                Command::new(argv[0])
+                    .args(&argv[1..])
+                    .stdin(Stdio::null())
+                    .output(),
+            )
+            .await;
+            match output {
+                Ok(Ok(out)) if out.status.success() => {
+                    return Ok(String::from_utf8_lossy(&out.stdout).into_owned());
+                }
+                Ok(Ok(out)) => {
+                    last_error = format!(
+                        "{} failed: {}",
+                        argv[0],
+                        String::from_utf8_lossy(&out.stderr).trim()
+                    );
+                }
+                Ok(Err(e)) => last_error = format!("{} not runnable: {e}", argv[0]),
+                Err(_) => last_error = format!("{} timed out", argv[0]),
+            }
+        }
+        anyhow::bail!("{last_error}")
+    }
+
+    async fn write_clipboard(text: &str) -> anyhow::Result<()> {
+        let mut last_error = String::from("no clipboard command available");
+        for argv in Self::write_commands() {
+            let child = Command::new(argv[0])
+                .args(&argv[1..])
+                .stdin(Stdio::piped())
+                // xclip/wl-copy fork a process that keeps serving the selection;
+                // it must not inherit pipes we wait on.
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            let mut child = match child {
+                Ok(child) => child,
+                Err(e) => {
+                    last_error = format!("{} not runnable: {e}", argv[0]);
+                    continue;
+                }
+            };
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(text.as_bytes()).await?;
+                // Dropping stdin closes the pipe so the helper sees EOF
+            }
+            match tokio::time::timeout(Duration::from_secs(CLIPBOARD_TIMEOUT_SECS), child.wait())
+                .await
+            {
+                Ok(Ok(status)) if status.success() => return Ok(()),
+                Ok(Ok(status)) => last_error = format!("{} exited with {status}", argv[0]),
+                Ok(Err(e)) => last_error = format!("{} failed: {e}", argv[0]),
+                Err(_) => last_error = format!("{} timed out", argv[0]),
+            }
+        }
+        anyhow::bail!("{last_error}")
+    }
+}
+
+#[async_trait]
+impl Tool for ClipboardTool {
+    fn name(&self) -> &str {
+        "clipboard"
+    }
+
+    fn description(&self) -> &str {
+        "Read ('get') or replace ('set') the text on the system clipboard. Text is limited to 64 KB."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["get", "set"],
+                    "description": "'get' returns the clipboard text, 'set' replaces it"
+                },
+                "text": {
+                    "type": "string",
+                    "description": "Text to copy (for 'set')"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+
+        // The clipboard may hold anything the user copied, so even reading
+        // requires an autonomy level that can act.
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+
+        match action {
+            "get" => match Self::read_clipboard().await {
+                Ok(mut text) => {
+                    let total = text.len();
+                    let output = if text.is_empty() {
+                        "Clipboard is empty".to_string()
+                    } else if total > MAX_CLIPBOARD_BYTES {
+                        text.truncate(text.floor_char_boundary(MAX_CLIPBOARD_BYTES));
+                        format!("{text}\n[clipboard truncated: {total} bytes total]")
+                    } else {
+                        text
+                    };
+                    Ok(ToolResult {
+                        success: true,
+                        output,
+                        error: None,
+                    })
+                }
+                Err(e) => Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Failed to read clipboard: {e}")),
+                }),
+            },
+            "set" => {
+                let text = args
+                    .get("text")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'text' for set"))?;
+                if text.len() > MAX_CLIPBOARD_BYTES {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!(
+                            "Text too large for clipboard: {} bytes (limit: {MAX_CLIPBOARD_BYTES})",
+                            text.len()
+                        )),
+                    });
+                }
+                if self.security.dry_run {
+                    let action = format!("copy {} bytes to the clipboard", text.len());
+                    return Ok(dry_run::preview(self.name(), &action, &args));
+                }
+                if !self.security.record_action() {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("Action blocked: rate limit exceeded".into()),
+                    });
+                }
+                match Self::write_clipboard(text).await {
+                    Ok(()) => Ok(ToolResult {
+                        success: true,
+                        output: format!(
+                            "Copied {} characters to the clipboard",
+                            text.chars().count()
+                        ),
+                        error: None,
+                    }),
+                    Err(e) => Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Failed to write clipboard: {e}")),
+                    }),
+                }
+            }
+            other => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Unknown action '{other}'. Use 'get' or 'set'")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    fn tool(autonomy: AutonomyLevel) -> ClipboardTool {
+        ClipboardTool::new(Arc::new(SecurityPolicy {
+            autonomy,
+            ..SecurityPolicy::default()
+        }))
+    }
+
+    #[test]
+    fn clipboard_name_and_schema() {
+        let tool = tool(AutonomyLevel::Supervised);
+        assert_eq!(tool.name(), "clipboard");
+        let schema = tool.parameters_schema();
+        assert_eq!(
+            schema["properties"]["action"]["enum"],
+            json!(["get", "set"])
+        );
+        assert_eq!(schema["required"], json!(["action"]));
+    }
+
+    #[test]
+    fn clipboard_has_platform_commands() {
+        assert!(!ClipboardTool::read_commands().is_empty());
+        assert!(!ClipboardTool::write_commands().is_empty());
+    }
+
+    #[tokio::test]
+    async fn clipboard_blocked_in_read_only_mode() {
+        let tool = tool(AutonomyLevel::ReadOnly);
+        for args in [
+            json!({"action": "get"}),
+            json!({"action": "set", "text": "x"}),
+        ] {
+            let result = tool.execute(args).await.unwrap();
+            assert!(!result.success);
+            assert!(result.error.unwrap().contains("read-only"));
+        }
+    }
+
+    #[tokio::test]
+    async fn clipboard_set_rejects_oversized_text() {
+        let tool = tool(AutonomyLevel::Supervised);
+        let text = "a".repeat(MAX_CLIPBOARD_BYTES + 1);
+        let result = tool
+            .execute(json!({"action": "set", "text": text}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("too large"));
+    }
+
+    #[tokio::test]
+    async fn clipboard_validates_arguments() {
+        let tool = tool(AutonomyLevel::Supervised);
+        assert!(tool.execute(json!({})).await.is_err());
+        assert!(tool.execute(json!({"action": "set"})).await.is_err());
+        let result = tool.execute(json!({"action": "cut"})).await.unwrap();
+        assert!(!result.success);
+    }
+}
@@ -5,23 +5,46 @@
 //
 // This is opt-in. Users who prefer sovereign/local-only mode skip this entirely.
 // The Composio API key is stored in the encrypted secret store.
+//
+// Actions of the apps listed in `[composio] apps` are also offered as tools
+// of their own with typed parameters, so the model can call them without a
+// discovery round-trip. Their catalog is cached in the workspace and fetched
+// again in the background once older than `catalog_ttl_secs`.
 
+use super::dry_run;
 use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 const COMPOSIO_API_BASE: &str = "https://backend.composio.dev/api/v2";
 
+/// Cached action catalog, relative to the workspace.
+const CATALOG_FILE: &str = ".zeroclaw/composio_catalog.json";
+
+/// Most actions offered as tools of their own; every tool spec costs prompt
+/// tokens on each request.
+const MAX_ACTION_TOOLS: usize = 64;
+
+/// Set while a catalog refresh is running, so only one runs at a time.
+static REFRESHING: AtomicBool = AtomicBool::new(false);
+
 /// A tool that proxies actions to the Composio managed tool platform.
+#[derive(Clone)]
 pub struct ComposioTool {
     api_key: String,
     client: Client,
+    security: Arc<SecurityPolicy>,
 }
 
 impl ComposioTool {
-    pub fn new(api_key: &str) -> Self {
+    pub fn new(api_key: &str, security: Arc<SecurityPolicy>) -> Self {
         Self {
             api_key: api_key.to_string(),
             client: Client::builder()
@@ -29,6 +52,7 @@ impl ComposioTool {
                 .connect_timeout(std::time::Duration::from_secs(10))
                 .build()
                 .unwrap_or_else(|_| Client::new()),
+            security,
         }
     }
 
@@ -97,13 +121,17 @@ impl ComposioTool {
         &self,
         app_name: &str,
         entity_id: &str,
+        redirect_uri: Option<&str>,
     ) -> anyhow::Result<String> {
         let url = format!("{COMPOSIO_API_BASE}/connectedAccounts");
 
-        let body = json!({
+        let mut body = json!({
             "integrationId": app_name,
             "entityId": entity_id,
         });
+        if let Some(redirect_uri) = redirect_uri {
+            body["redirectUri"] = json!(redirect_uri);
+        }
 
         let resp = self
             .client
@@ -125,6 +153,115 @@ impl ComposioTool {
             .map(String::from)
             .ok_or_else(|| anyhow::anyhow!("No redirect URL in response"))
     }
+
+    /// Accounts `entity_id` has connected through OAuth.
+    pub async fn list_connections(
+        &self,
+        entity_id: &str,
+    ) -> anyhow::Result<Vec<ComposioConnection>> {
+        let resp = self
+            .client
+            .get(format!("{COMPOSIO_API_BASE}/connectedAccounts"))
+            .query(&[("user_uuid", entity_id)])
+            .header("x-api-key", &self.api_key)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to list connections: {err}");
+        }
+
+        let body: ComposioConnectionsResponse = resp.json().await?;
+        Ok(body.items)
+    }
+
+    /// Revoke the connected account `connection_id`.
+    pub async fn revoke_connection(&self, connection_id: &str) -> anyhow::Result<()> {
+        if connection_id.is_empty()
+            || !connection_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            anyhow::bail!("Invalid connection id: {connection_id}");
+        }
+        let resp = self
+            .client
+            .delete(format!(
+                "{COMPOSIO_API_BASE}/connectedAccounts/{connection_id}"
+            ))
+            .header("x-api-key", &self.api_key)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to revoke connection: {err}");
+        }
+        Ok(())
+    }
+}
+
+/// Connected accounts of the configured `[composio] entity_id`, managed
+/// outside the chat loop: the gateway's `/composio/connections` routes and
+/// the `zc_composio_*` FFI calls.
+pub struct ComposioAccounts {
+    client: ComposioTool,
+    entity_id: String,
+}
+
+impl ComposioAccounts {
+    /// `None` unless Composio is enabled with an API key.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let composio = &config.composio;
+        let api_key = composio.api_key.as_deref().filter(|k| !k.is_empty())?;
+        if !composio.enabled {
+            return None;
+        }
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+        Some(Self {
+            client: ComposioTool::new(api_key, Arc::new(security)),
+            entity_id: composio.entity_id.clone(),
+        })
+    }
+
+    pub async fn list(&self) -> anyhow::Result<Vec<ComposioConnection>> {
+        self.client.list_connections(&self.entity_id).await
+    }
+
+    /// Start an OAuth flow for `app`; returns the URL the user must open.
+    pub async fn connect(&self, app: &str) -> anyhow::Result<String> {
+        self.client
+            .get_connection_url(app, &self.entity_id, None)
+            .await
+    }
+
+    /// Connect `app` without leaving the CLI or app: `open` receives the
+    /// URL to show the user, and this returns once Composio sends the
+    /// browser back to a local callback server. Returns the new connection
+    /// id when Composio reports one.
+    pub async fn connect_and_wait(
+        &self,
+        app: &str,
+        open: impl FnOnce(&str),
+        timeout: std::time::Duration,
+    ) -> anyhow::Result<Option<String>> {
+        let server = crate::integrations::oauth::CallbackServer::bind().await?;
+        let url = self
+            .client
+            .get_connection_url(app, &self.entity_id, Some(&server.redirect_uri()))
+            .await?;
+        open(&url);
+        let params = server.wait(None, timeout).await?;
+        if let Some(status) = params.get("status").filter(|s| *s != "success") {
+            anyhow::bail!("Connecting {app} did not complete: {status}");
+        }
+        Ok(params.get("connectedAccountId").cloned())
+    }
+
+    pub async fn revoke(&self, connection_id: &str) -> anyhow::Result<()> {
+        self.client.revoke_connection(connection_id).await
+    }
 }
 
 #[async_trait]
@@ -168,6 +305,7 @@ impl Tool for ComposioTool {
         })
     }
 
+    #[allow(clippy::too_many_lines)]
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let action = args
             .get("action")
@@ -226,6 +364,11 @@ impl Tool for ComposioTool {
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("Missing 'action_name' for execute"))?;
 
+                if self.security.dry_run {
+                    let action = format!("run the Composio action {action_name}");
+                    return Ok(dry_run::preview(self.name(), &action, &args));
+                }
+
                 let params = args.get("params").cloned().unwrap_or(json!({}));
 
                 match self
@@ -255,7 +398,7 @@ impl Tool for ComposioTool {
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("Missing 'app' for connect"))?;
 
-                match self.get_connection_url(app, entity_id).await {
+                match self.get_connection_url(app, entity_id, None).await {
                     Ok(url) => Ok(ToolResult {
                         success: true,
                         output: format!("Open this URL to connect {app}:\n{url}"),
@@ -280,8 +423,213 @@ impl Tool for ComposioTool {
     }
 }
 
+// ── Typed action tools ──────────────────────────────────────────
+
+/// Actions of `apps` fetched at `fetched_at` (Unix time).
+#[derive(Debug, Serialize, Deserialize)]
+struct Catalog {
+    fetched_at: i64,
+    apps: Vec<String>,
+    actions: Vec<ComposioAction>,
+}
+
+impl Catalog {
+    fn read(path: &Path) -> Option<Self> {
+        let raw = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    fn is_fresh(&self, apps: &[String], ttl_secs: u64) -> bool {
+        let age = chrono::Utc::now().timestamp() - self.fetched_at;
+        self.apps == apps && u64::try_from(age).is_ok_and(|age| age < ttl_secs)
+    }
+}
+
+fn catalog_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join(CATALOG_FILE)
+}
+
+/// One tool per cached action of the `[composio] apps`. Only the cache is
+/// read here; a missing or stale one is fetched in the background, for the
+/// next session to use.
+pub fn action_tools(config: &Config, security: &Arc<SecurityPolicy>) -> Vec<Box<dyn Tool>> {
+    let composio = &config.composio;
+    let Some(api_key) = composio.api_key.as_deref().filter(|k| !k.is_empty()) else {
+        return Vec::new();
+    };
+    if !composio.enabled || composio.apps.is_empty() {
+        return Vec::new();
+    }
+    let client = ComposioTool::new(api_key, security.clone());
+    let path = catalog_path(&security.workspace_dir);
+    let catalog = Catalog::read(&path);
+    if !catalog
+        .as_ref()
+        .is_some_and(|c| c.is_fresh(&composio.apps, composio.catalog_ttl_secs))
+    {
+        spawn_refresh(client.clone(), path, composio.apps.clone());
+    }
+    let Some(catalog) = catalog.filter(|c| c.apps == composio.apps) else {
+        return Vec::new();
+    };
+
+    if catalog.actions.len() > MAX_ACTION_TOOLS {
+        tracing::warn!(
+            "Composio apps have {} actions; offering the first {MAX_ACTION_TOOLS} as tools",
+            catalog.actions.len()
+        );
+    }
+    catalog
+        .actions
+        .into_iter()
+        .take(MAX_ACTION_TOOLS)
+        .map(|action| {
+            Box::new(ComposioActionTool::new(
+                client.clone(),
+                action,
+                &composio.entity_id,
+            )) as Box<dyn Tool>
+        })
+        .collect()
+}
+
+/// Fetch the actions of `apps` and cache them at `path`, off the caller's
+/// path: on the current runtime when there is one, else on a thread.
+fn spawn_refresh(client: ComposioTool, path: PathBuf, apps: Vec<String>) {
+    if REFRESHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let refresh = async move {
+        match client.list_actions(Some(&apps.join(","))).await {
+            Ok(actions) => {
+                let catalog = Catalog {
+                    fetched_at: chrono::Utc::now().timestamp(),
+                    apps,
+                    actions,
+                };
+                let written = path
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|()| {
+                        std::fs::write(&path, serde_json::to_vec(&catalog).unwrap_or_default())
+                    });
+                if let Err(e) = written {
+                    tracing::warn!("Could not cache the Composio catalog: {e}");
+                }
+            }
+            Err(e) => tracing::warn!("Could not refresh the Composio catalog: {e}"),
+        }
+        REFRESHING.store(false, Ordering::SeqCst);
+    };
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        handle.spawn(refresh);
+    } else {
+        std::thread::spawn(move || {
+            match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt.block_on(refresh),
+                Err(_) => REFRESHING.store(false, Ordering::SeqCst),
+            }
+        });
+    }
+}
+
+/// One Composio action as a tool: the action's own parameter schema, run
+/// directly without going through `composio`.
+pub struct ComposioActionTool {
+    composio: ComposioTool,
+    /// `composio_` plus the lowercased action name
+    name: String,
+    description: String,
+    action: ComposioAction,
+    entity_id: String,
+}
+
+impl ComposioActionTool {
+    fn new(composio: ComposioTool, action: ComposioAction, entity_id: &str) -> Self {
+        let mut name = format!("composio_{}", action.name.to_lowercase());
+        name.truncate(64);
+        let description = format!(
+            "{} (Composio, {})",
+            action.description.as_deref().unwrap_or(&action.name),
+            action.app_name.as_deref().unwrap_or("?")
+        );
+        Self {
+            composio,
+            name,
+            description,
+            action,
+            entity_id: entity_id.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ComposioActionTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        if self.action.parameters.is_object() {
+            self.action.parameters.clone()
+        } else {
+            json!({"type": "object", "properties": {}})
+        }
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if self.composio.security.dry_run {
+            let action = format!("run the Composio action {}", self.action.name);
+            return Ok(dry_run::preview(self.name(), &action, &args));
+        }
+        match self
+            .composio
+            .execute_action(&self.action.name, args, Some(&self.entity_id))
+            .await
+        {
+            Ok(result) => Ok(ToolResult {
+                success: true,
+                output: serde_json::to_string_pretty(&result)
+                    .unwrap_or_else(|_| format!("{result:?}")),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Action execution failed: {e}")),
+            }),
+        }
+    }
+}
+
 // ── API response types ──────────────────────────────────────────
 
+#[derive(Debug, Deserialize)]
+struct ComposioConnectionsResponse {
+    #[serde(default)]
+    items: Vec<ComposioConnection>,
+}
+
+/// An account connected through Composio OAuth.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComposioConnection {
+    pub id: String,
+    #[serde(rename = "appName", default)]
+    pub app_name: Option<String>,
+    /// "ACTIVE", "INITIATED", "FAILED", ...
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(rename = "createdAt", default)]
+    pub created_at: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ComposioActionsResponse {
     #[serde(default)]
@@ -296,30 +644,37 @@ pub struct ComposioAction {
     pub description: Option<String>,
     #[serde(default)]
     pub enabled: bool,
+    /// JSON schema of the action's input
+    #[serde(default)]
+    pub parameters: serde_json::Value,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn test_tool() -> ComposioTool {
+        ComposioTool::new("test-key", Arc::new(SecurityPolicy::default()))
+    }
+
     // ── Constructor ───────────────────────────────────────────
 
     #[test]
     fn composio_tool_has_correct_name() {
-        let tool = ComposioTool::new("test-key");
+        let tool = test_tool();
         assert_eq!(tool.name(), "composio");
     }
 
     #[test]
     fn composio_tool_has_description() {
-        let tool = ComposioTool::new("test-key");
+        let tool = test_tool();
         assert!(!tool.description().is_empty());
         assert!(tool.description().contains("1000+"));
     }
 
     #[test]
     fn composio_tool_schema_has_required_fields() {
-        let tool = ComposioTool::new("test-key");
+        let tool = test_tool();
         let schema = tool.parameters_schema();
         assert!(schema["properties"]["action"].is_object());
         assert!(schema["properties"]["action_name"].is_object());
@@ -331,7 +686,7 @@ mod tests {
 
     #[test]
     fn composio_tool_spec_roundtrip() {
-        let tool = ComposioTool::new("test-key");
+        let tool = test_tool();
         let spec = tool.spec();
         assert_eq!(spec.name, "composio");
         assert!(spec.parameters.is_object());
@@ -341,14 +696,14 @@ mod tests {
 
     #[tokio::test]
     async fn execute_missing_action_returns_error() {
-        let tool = ComposioTool::new("test-key");
+        let tool = test_tool();
         let result = tool.execute(json!({})).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn execute_unknown_action_returns_error() {
-        let tool = ComposioTool::new("test-key");
+        let tool = test_tool();
         let result = tool.execute(json!({"action": "unknown"})).await.unwrap();
         assert!(!result.success);
         assert!(result.error.as_ref().unwrap().contains("Unknown action"));
@@ -356,14 +711,14 @@ mod tests {
 
     #[tokio::test]
     async fn execute_without_action_name_returns_error() {
-        let tool = ComposioTool::new("test-key");
+        let tool = test_tool();
         let result = tool.execute(json!({"action": "execute"})).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn connect_without_app_returns_error() {
-        let tool = ComposioTool::new("test-key");
+        let tool = test_tool();
         let result = tool.execute(json!({"action": "connect"})).await;
         assert!(result.is_err());
     }
@@ -394,6 +749,92 @@ mod tests {
         assert!(resp.items.is_empty());
     }
 
+    #[test]
+    fn composio_connections_response_deserializes() {
+        let json_str = r#"{"items": [{"id": "ca_1", "appName": "github", "status": "ACTIVE", "createdAt": "2026-01-02T03:04:05Z", "integrationId": "x"}]}"#;
+        let resp: ComposioConnectionsResponse = serde_json::from_str(json_str).unwrap();
+        assert_eq!(resp.items[0].id, "ca_1");
+        assert_eq!(resp.items[0].app_name.as_deref(), Some("github"));
+        assert_eq!(resp.items[0].status.as_deref(), Some("ACTIVE"));
+    }
+
+    #[tokio::test]
+    async fn revoke_rejects_ids_that_are_not_plain() {
+        let err = test_tool()
+            .revoke_connection("../actions")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid connection id"));
+    }
+
+    #[test]
+    fn accounts_need_composio_enabled_with_a_key() {
+        let mut config = Config::default();
+        config.composio.api_key = Some("test-key".into());
+        assert!(ComposioAccounts::from_config(&config).is_none());
+        config.composio.enabled = true;
+        assert!(ComposioAccounts::from_config(&config).is_some());
+    }
+
+    // ── Typed action tools ────────────────────────────────────
+
+    #[test]
+    fn cached_actions_become_typed_tools() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let mut config = Config::default();
+        config.composio.enabled = true;
+        config.composio.api_key = Some("test-key".into());
+        config.composio.apps = vec!["github".into()];
+
+        let catalog = json!({
+            "fetched_at": chrono::Utc::now().timestamp(),
+            "apps": ["github"],
+            "actions": [{
+                "name": "GITHUB_STAR_A_REPOSITORY",
+                "appName": "github",
+                "description": "Star a repository",
+                "parameters": {
+                    "type": "object",
+                    "properties": {"owner": {"type": "string"}, "repo": {"type": "string"}},
+                    "required": ["owner", "repo"]
+                }
+            }]
+        });
+        let path = catalog_path(tmp.path());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, catalog.to_string()).unwrap();
+
+        let tools = action_tools(&config, &security);
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name(), "composio_github_star_a_repository");
+        assert_eq!(
+            tools[0].description(),
+            "Star a repository (Composio, github)"
+        );
+        assert_eq!(
+            tools[0].parameters_schema()["required"],
+            json!(["owner", "repo"])
+        );
+
+        // Another app list needs another catalog
+        let catalog = Catalog::read(&path).unwrap();
+        assert!(catalog.is_fresh(&config.composio.apps, 60));
+        assert!(!catalog.is_fresh(&["gmail".to_string()], 60));
+        assert!(!catalog.is_fresh(&config.composio.apps, 0));
+    }
+
+    #[test]
+    fn action_tools_need_apps() {
+        let mut config = Config::default();
+        config.composio.enabled = true;
+        config.composio.api_key = Some("test-key".into());
+        assert!(action_tools(&config, &Arc::new(SecurityPolicy::default())).is_empty());
+    }
+
     #[test]
     fn composio_actions_response_missing_items_defaults() {
         let json_str = r"{}";
@@ -0,0 +1,47 @@
+//! Previews for dry runs.
+//!
+//! With `[autonomy] dry_run = true`, tools that would change files, run a
+//! command that is not read-only, or act outside the agent (browser,
+//! clipboard, calendar, Composio, plugins, jobs, workflows, speech, other
+//! agents on the bus, remote nodes) report what they would have done, with
+//! every argument, instead of doing it. Reads still run, so the model plans
+//! from real data; memory tools still work, as they only touch the agent's
+//! own notes. This lets a prompt meant for Full autonomy be checked first.
+
+use super::traits::ToolResult;
+use std::fmt::Write;
+
+/// The result a tool returns in a dry run instead of acting. `action` says
+/// what would have happened, e.g. "write 12 bytes to notes.md", and may go
+/// on with detail lines.
+pub fn preview(tool: &str, action: &str, args: &serde_json::Value) -> ToolResult {
+    let mut output = format!("[dry run] Nothing was changed; {tool} would {action}");
+    if let Ok(args) = serde_json::to_string_pretty(args) {
+        let _ = write!(output, "\nArguments:\n{args}");
+    }
+    ToolResult {
+        success: true,
+        output,
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn preview_names_the_action_and_every_argument() {
+        let result = preview(
+            "shell",
+            "run `rm -rf build`",
+            &json!({"command": "rm -rf build", "approved": true}),
+        );
+        assert!(result.success);
+        assert!(result
+            .output
+            .starts_with("[dry run] Nothing was changed; shell would run `rm -rf build`\n"));
+        assert!(result.output.contains("\"approved\": true"));
+    }
+}
@@ -0,0 +1,321 @@
+//! Compact record of what a file write changed, appended to `file_write` and
+//! `file_edit` results so the model can check an edit landed where intended
+//! without reading the whole file back.
+//!
+//! ```text
+//! lines: 120 -> 123 (+4 -1)
+//! changed: @@ -10,2 +10,3 @@ @@ -40,0 +41,2 @@
+//! sha256: 3f2a9c1b0e7d4a55
+//! ```
+
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// Hunks listed before the rest are only counted.
+const MAX_HUNKS: usize = 8;
+
+/// Above this many line comparisons the changed region is reported as one
+/// hunk instead of being split precisely.
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+/// One changed region in unified-diff terms: 1-based starts, line counts.
+/// A zero-length side sits after line `start` (as in `@@ -40,0 +41,2 @@`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Hunk {
+    old_start: usize,
+    old_len: usize,
+    new_start: usize,
+    new_len: usize,
+}
+
+/// Summarize the change from `before` (`None` for a new file) to `after`.
+pub fn summarize(before: Option<&str>, after: &str) -> String {
+    let new_lines: Vec<&str> = after.lines().collect();
+    let mut out = String::new();
+
+    match before {
+        None => {
+            let _ = writeln!(out, "lines: {} (new file)", new_lines.len());
+        }
+        Some(before) => {
+            let old_lines: Vec<&str> = before.lines().collect();
+            let hunks = diff_lines(&old_lines, &new_lines);
+            let added: usize = hunks.iter().map(|h| h.new_len).sum();
+            let removed: usize = hunks.iter().map(|h| h.old_len).sum();
+            let _ = writeln!(
+                out,
+                "lines: {} -> {} (+{added} -{removed})",
+                old_lines.len(),
+                new_lines.len()
+            );
+            if hunks.is_empty() {
+                out.push_str("changed: nothing (content identical)\n");
+            } else {
+                out.push_str("changed:");
+                for h in hunks.iter().take(MAX_HUNKS) {
+                    let _ = write!(
+                        out,
+                        " @@ -{},{} +{},{} @@",
+                        h.old_start, h.old_len, h.new_start, h.new_len
+                    );
+                }
+                if hunks.len() > MAX_HUNKS {
+                    let _ = write!(out, " (+{} more)", hunks.len() - MAX_HUNKS);
+                }
+                out.push('\n');
+            }
+        }
+    }
+
+    let digest = hex::encode(Sha256::digest(after.as_bytes()));
+    let _ = write!(out, "sha256: {}", &digest[..16]);
+    out
+}
+
+/// Unchanged lines shown around each change in [`unified_diff`].
+const DIFF_CONTEXT: usize = 3;
+
+/// Lines of [`unified_diff`] output shown before the rest are only counted.
+const MAX_DIFF_OUTPUT_LINES: usize = 200;
+
+/// The change from `before` (`None` for a new file) to `after` as a unified
+/// diff of `path`, for showing a pending change to a person.
+pub fn unified_diff(path: &str, before: Option<&str>, after: &str) -> String {
+    let old: Vec<&str> = before.map(|b| b.lines().collect()).unwrap_or_default();
+    let new: Vec<&str> = after.lines().collect();
+    // 0-based offsets: a zero-length side starts at its insertion point
+    let offset = |start: usize, len: usize| if len == 0 { start } else { start - 1 };
+    let hunks: Vec<Hunk> = diff_lines(&old, &new)
+        .into_iter()
+        .map(|h| Hunk {
+            old_start: offset(h.old_start, h.old_len),
+            new_start: offset(h.new_start, h.new_len),
+            ..h
+        })
+        .collect();
+
+    let mut lines = vec![
+        match before {
+            Some(_) => format!("--- a/{path}"),
+            None => "--- /dev/null".to_string(),
+        },
+        format!("+++ b/{path}"),
+    ];
+    if hunks.is_empty() {
+        lines.push("(no changes)".to_string());
+    }
+
+    // Hunks whose context would overlap are shown as one block
+    let mut groups: Vec<Vec<Hunk>> = Vec::new();
+    for h in hunks {
+        match groups.last_mut() {
+            Some(group)
+                if group.last().is_some_and(|p| {
+                    h.old_start - (p.old_start + p.old_len) <= 2 * DIFF_CONTEXT
+                }) =>
+            {
+                group.push(h);
+            }
+            _ => groups.push(vec![h]),
+        }
+    }
+
+    for group in groups {
+        let (first, last) = (group[0], group[group.len() - 1]);
+        let old_from = first.old_start.saturating_sub(DIFF_CONTEXT);
+        let new_from = first.new_start - (first.old_start - old_from);
+        let old_to = (last.old_start + last.old_len + DIFF_CONTEXT).min(old.len());
+        let new_to = last.new_start + last.new_len + (old_to - (last.old_start + last.old_len));
+        let range = |from: usize, to: usize| {
+            let len = to - from;
+            format!("{},{len}", if len == 0 { from } else { from + 1 })
+        };
+        lines.push(format!(
+            "@@ -{} +{} @@",
+            range(old_from, old_to),
+            range(new_from, new_to)
+        ));
+        let mut pos = old_from;
+        for h in &group {
+            lines.extend(old[pos..h.old_start].iter().map(|l| format!(" {l}")));
+            lines.extend(
+                old[h.old_start..h.old_start + h.old_len]
+                    .iter()
+                    .map(|l| format!("-{l}")),
+            );
+            lines.extend(
+                new[h.new_start..h.new_start + h.new_len]
+                    .iter()
+                    .map(|l| format!("+{l}")),
+            );
+            pos = h.old_start + h.old_len;
+        }
+        lines.extend(old[pos..old_to].iter().map(|l| format!(" {l}")));
+    }
+
+    if lines.len() > MAX_DIFF_OUTPUT_LINES {
+        let more = lines.len() - MAX_DIFF_OUTPUT_LINES;
+        lines.truncate(MAX_DIFF_OUTPUT_LINES);
+        lines.push(format!("... ({more} more lines)"));
+    }
+    lines.join("\n")
+}
+
+/// Changed regions between two line lists. Common leading and trailing lines
+/// are trimmed first, so typical edits only diff a handful of lines.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Hunk> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    if old_mid.is_empty() && new_mid.is_empty() {
+        return Vec::new();
+    }
+    if old_mid.is_empty()
+        || new_mid.is_empty()
+        || old_mid.len().saturating_mul(new_mid.len()) > MAX_DIFF_CELLS
+    {
+        return vec![hunk(prefix, old_mid.len(), prefix, new_mid.len())];
+    }
+
+    lcs_hunks(old_mid, new_mid)
+        .into_iter()
+        .map(|h| {
+            hunk(
+                prefix + h.old_start,
+                h.old_len,
+                prefix + h.new_start,
+                h.new_len,
+            )
+        })
+        .collect()
+}
+
+/// Hunk from 0-based offsets, converted to unified-diff numbering.
+fn hunk(old_offset: usize, old_len: usize, new_offset: usize, new_len: usize) -> Hunk {
+    let start = |offset: usize, len: usize| if len == 0 { offset } else { offset + 1 };
+    Hunk {
+        old_start: start(old_offset, old_len),
+        old_len,
+        new_start: start(new_offset, new_len),
+        new_len,
+    }
+}
+
+/// Longest-common-subsequence diff; returned hunks use 0-based offsets in
+/// the `*_start` fields.
+fn lcs_hunks(old: &[&str], new: &[&str]) -> Vec<Hunk> {
+    let (n, m) = (old.len(), new.len());
+    // table[i][j] = LCS length of old[i..] and new[j..]
+    let mut table = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if old[i] == new[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut open: Option<Hunk> = None;
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            hunks.extend(open.take());
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let current = open.get_or_insert(Hunk {
+            old_start: i,
+            old_len: 0,
+            new_start: j,
+            new_len: 0,
+        });
+        if j < m && (i == n || table[i][j + 1] >= table[i + 1][j]) {
+            current.new_len += 1;
+            j += 1;
+        } else {
+            current.old_len += 1;
+            i += 1;
+        }
+    }
+    hunks.extend(open);
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_separate_hunks_with_counts() {
+        let before = "a\nb\nc\nd\ne\nf\n";
+        let after = "a\nB\nB2\nc\nd\nf\ng\n";
+        let summary = summarize(Some(before), after);
+        let mut lines = summary.lines();
+        assert_eq!(lines.next(), Some("lines: 6 -> 7 (+3 -2)"));
+        assert_eq!(
+            lines.next(),
+            Some("changed: @@ -2,1 +2,2 @@ @@ -5,1 +5,0 @@ @@ -6,0 +7,1 @@")
+        );
+        assert!(lines.next().unwrap().starts_with("sha256: "));
+    }
+
+    #[test]
+    fn unified_diff_shows_context_around_changes() {
+        let before = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n";
+        let after = before.replacen("2\n", "two\n", 1).replace("15\n", "");
+        assert_eq!(
+            unified_diff("n.txt", Some(before), &after),
+            "--- a/n.txt\n+++ b/n.txt\n\
+             @@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n\
+             @@ -12,5 +12,4 @@\n 12\n 13\n 14\n-15\n 16"
+        );
+        assert_eq!(
+            unified_diff("new.txt", None, "a\nb"),
+            "--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1,2 @@\n+a\n+b"
+        );
+    }
+
+    #[test]
+    fn new_and_identical_files() {
+        assert!(summarize(None, "x\ny").starts_with("lines: 2 (new file)\nsha256: "));
+        assert!(summarize(Some("same"), "same").contains("changed: nothing"));
+        // Same content always hashes the same
+        assert_eq!(
+            summarize(None, "x").lines().last(),
+            summarize(Some("y"), "x").lines().last()
+        );
+    }
+
+    #[test]
+    fn pure_insertions_and_deletions() {
+        assert_eq!(
+            diff_lines(&["a", "b"], &["a", "x", "y", "b"]),
+            vec![Hunk {
+                old_start: 1,
+                old_len: 0,
+                new_start: 2,
+                new_len: 2
+            }]
+        );
+        assert_eq!(
+            diff_lines(&["a", "b", "c"], &["a"]),
+            vec![Hunk {
+                old_start: 2,
+                old_len: 2,
+                new_start: 1,
+                new_len: 0
+            }]
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use super::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+
+/// Full copies kept of shortened tool outputs; older ones are dropped.
+const MAX_STORED_OUTPUTS: usize = 32;
+
+/// Lines returned when the call gives no `limit`.
+const DEFAULT_PAGE_LINES: usize = 200;
+
+/// Most lines returned by one call.
+const MAX_PAGE_LINES: usize = 500;
+
+#[derive(Default)]
+struct OutputStore {
+    next_id: u64,
+    outputs: VecDeque<(String, String)>,
+}
+
+fn output_store() -> &'static Mutex<OutputStore> {
+    static STORE: OnceLock<Mutex<OutputStore>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(OutputStore::default()))
+}
+
+/// Keep the full text of a tool output that was shortened for the model and
+/// return the id `fetch_full_output` reads it back by.
+pub fn store_output(output: String) -> String {
+    let mut store = output_store()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    store.next_id += 1;
+    let id = format!("out-{}", store.next_id);
+    if store.outputs.len() == MAX_STORED_OUTPUTS {
+        store.outputs.pop_front();
+    }
+    store.outputs.push_back((id.clone(), output));
+    id
+}
+
+fn stored_output(id: &str) -> Option<String> {
+    output_store()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .outputs
+        .iter()
+        .find(|(stored, _)| stored == id)
+        .map(|(_, output)| output.clone())
+}
+
+/// Page through a tool output that was truncated or summarized
+pub struct FetchFullOutputTool;
+
+impl FetchFullOutputTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for FetchFullOutputTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for FetchFullOutputTool {
+    fn name(&self) -> &str {
+        "fetch_full_output"
+    }
+
+    fn description(&self) -> &str {
+        "Read lines of a tool output that was shortened to fit the context. Use when: a result says it was truncated or summarized and you need lines it left out. Don't use when: the shortened result already answers the question."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Output id from the shortened result, e.g. out-3"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "First line to return, counting from 0 (default 0)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Number of lines to return (default 200, at most 500)"
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let id = args
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'id' parameter"))?;
+        let offset = args
+            .get("offset")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(0, |v| usize::try_from(v).unwrap_or(usize::MAX));
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_PAGE_LINES, |v| {
+                usize::try_from(v).unwrap_or(MAX_PAGE_LINES)
+            })
+            .clamp(1, MAX_PAGE_LINES);
+
+        let Some(output) = stored_output(id) else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "No stored output with id {id} (it may have expired)"
+                )),
+            });
+        };
+
+        let lines: Vec<&str> = output.lines().collect();
+        let start = offset.min(lines.len());
+        let end = start.saturating_add(limit).min(lines.len());
+        let mut page = format!("Lines {start}-{end} of {} in {id}:\n", lines.len());
+        page.push_str(&lines[start..end].join("\n"));
+        if end < lines.len() {
+            let _ = write!(page, "\n[more lines follow; next offset is {end}]");
+        }
+        Ok(ToolResult {
+            success: true,
+            output: page,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn pages_through_a_stored_output() {
+        let output: Vec<String> = (0..1000).map(|i| format!("line {i}")).collect();
+        let id = store_output(output.join("\n"));
+        let tool = FetchFullOutputTool::new();
+
+        let first = tool.execute(json!({"id": id})).await.unwrap();
+        assert!(first.success);
+        assert!(first
+            .output
+            .starts_with(&format!("Lines 0-200 of 1000 in {id}")));
+        assert!(first
+            .output
+            .contains("line 199\n[more lines follow; next offset is 200]"));
+
+        let last = tool
+            .execute(json!({"id": id, "offset": 990, "limit": 50}))
+            .await
+            .unwrap();
+        assert!(last.output.ends_with("line 999"));
+    }
+
+    #[tokio::test]
+    async fn unknown_ids_are_reported() {
+        let result = FetchFullOutputTool::new()
+            .execute(json!({"id": "out-0"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("out-0"));
+    }
+}
@@ -1,3 +1,7 @@
+use super::approval;
+use super::checkpoints;
+use super::dry_run;
+use super::edit_summary;
 use super::traits::{Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -12,6 +16,86 @@ impl FileEditTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
         Self { security }
     }
+
+    fn failure(error: String) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+        }
+    }
+}
+
+/// `original` with `operation` applied at the 1-based `line` (through
+/// `end_line` for ranges), or why it cannot be.
+fn edit_lines(
+    original: &str,
+    operation: &str,
+    line: usize,
+    end_line: Option<usize>,
+    content: &str,
+) -> Result<String, String> {
+    let lines: Vec<&str> = original.lines().collect();
+    let total_lines = lines.len();
+    let out_of_range = || format!("Line {line} out of range (file has {total_lines} lines)");
+
+    match operation {
+        "insert" => {
+            let insert_pos = line.saturating_sub(1).min(total_lines);
+
+            let mut new_lines: Vec<&str> = Vec::new();
+            for (i, l) in lines.iter().enumerate() {
+                if i == insert_pos {
+                    new_lines.push(content);
+                }
+                new_lines.push(l);
+            }
+            // Handle case where inserting at the end
+            if insert_pos >= total_lines {
+                new_lines.push(content);
+            }
+            Ok(new_lines.join("\n"))
+        }
+        "delete" => {
+            let end = end_line.unwrap_or(line).saturating_sub(1).min(total_lines.saturating_sub(1));
+            let start = line.saturating_sub(1).min(end);
+
+            if start >= total_lines {
+                return Err(out_of_range());
+            }
+
+            let mut new_lines: Vec<&str> = Vec::new();
+            for (i, l) in lines.iter().enumerate() {
+                if i < start || i > end {
+                    new_lines.push(l);
+                }
+            }
+            Ok(new_lines.join("\n"))
+        }
+        "replace" => {
+            let replace_pos = line.saturating_sub(1).min(total_lines.saturating_sub(1));
+            let end = end_line.unwrap_or(line).saturating_sub(1).min(total_lines.saturating_sub(1));
+
+            if replace_pos >= total_lines {
+                return Err(out_of_range());
+            }
+
+            let mut new_lines: Vec<&str> = Vec::new();
+            for (i, l) in lines.iter().enumerate() {
+                if i < replace_pos {
+                    new_lines.push(l);
+                } else if i == replace_pos {
+                    new_lines.push(content);
+                } else if i > end {
+                    new_lines.push(l);
+                }
+            }
+            Ok(new_lines.join("\n"))
+        }
+        _ => Err(format!(
+            "Unknown operation: {operation}. Use 'insert', 'delete', or 'replace'"
+        )),
+    }
 }
 
 #[async_trait]
@@ -70,10 +154,7 @@ impl Tool for FileEditTool {
             .and_then(|v| v.as_i64())
             .ok_or_else(|| anyhow::anyhow!("Missing 'line' parameter"))? as usize;
 
-        let content = args
-            .get("content")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string());
+        let content = args.get("content").and_then(|v| v.as_str());
 
         let end_line = args
             .get("end_line")
@@ -82,111 +163,70 @@ impl Tool for FileEditTool {
 
         // Security check: validate path is within workspace
         if !self.security.is_path_allowed(path) {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!("Path not allowed by security policy: {path}")),
-            });
+            return Ok(Self::failure(format!("Path not allowed by security policy: {path}")));
         }
 
         let full_path = self.security.workspace_dir.join(path);
 
         // Check if file exists
         if !full_path.exists() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!("File not found: {path}")),
-            });
+            return Ok(Self::failure(format!("File not found: {path}")));
+        }
+
+        // Resolve the file to block symlink escapes and protected files
+        // reached through a symlink
+        let full_path = match tokio::fs::canonicalize(&full_path).await {
+            Ok(resolved) => resolved,
+            Err(e) => return Ok(Self::failure(format!("Failed to resolve file path: {e}"))),
+        };
+        if !self.security.is_resolved_path_allowed(&full_path) {
+            return Ok(Self::failure(format!(
+                "Resolved path escapes workspace: {}",
+                full_path.display()
+            )));
         }
 
         // Read existing content
         let original_content = match tokio::fs::read_to_string(&full_path).await {
             Ok(c) => c,
-            Err(e) => {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(format!("Failed to read file: {e}")),
-                });
-            }
+            Err(e) => return Ok(Self::failure(format!("Failed to read file: {e}"))),
         };
 
-        let lines: Vec<&str> = original_content.lines().collect();
-        let total_lines = lines.len();
-
-        let new_content = match operation {
-            "insert" => {
-                let content = content.ok_or_else(|| anyhow::anyhow!("Missing 'content' for insert"))?;
-                let insert_pos = line.saturating_sub(1).min(total_lines);
-                
-                let mut new_lines: Vec<String> = Vec::new();
-                for (i, l) in lines.iter().enumerate() {
-                    if i == insert_pos {
-                        new_lines.push(content.clone());
-                    }
-                    new_lines.push(l.to_string());
-                }
-                // Handle case where inserting at the end
-                if insert_pos >= total_lines {
-                    new_lines.push(content);
-                }
-                new_lines.join("\n")
-            }
-            "delete" => {
-                let end = end_line.unwrap_or(line).saturating_sub(1).min(total_lines.saturating_sub(1));
-                let start = line.saturating_sub(1).min(end);
-                
-                if start >= total_lines {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some(format!("Line {} out of range (file has {} lines)", line, total_lines)),
-                    });
-                }
-                
-                let mut new_lines: Vec<String> = Vec::new();
-                for (i, l) in lines.iter().enumerate() {
-                    if i < start || i > end {
-                        new_lines.push(l.to_string());
-                    }
-                }
-                new_lines.join("\n")
-            }
-            "replace" => {
-                let content = content.ok_or_else(|| anyhow::anyhow!("Missing 'content' for replace"))?;
-                let replace_pos = line.saturating_sub(1).min(total_lines.saturating_sub(1));
-                let end = end_line.unwrap_or(line).saturating_sub(1).min(total_lines.saturating_sub(1));
-                
-                if replace_pos >= total_lines {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some(format!("Line {} out of range (file has {} lines)", line, total_lines)),
-                    });
-                }
-                
-                let mut new_lines: Vec<String> = Vec::new();
-                for (i, l) in lines.iter().enumerate() {
-                    if i < replace_pos {
-                        new_lines.push(l.to_string());
-                    } else if i == replace_pos {
-                        new_lines.push(content.clone());
-                    } else if i > end {
-                        new_lines.push(l.to_string());
-                    }
-                }
-                new_lines.join("\n")
-            }
-            _ => {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(format!("Unknown operation: {}. Use 'insert', 'delete', or 'replace'", operation)),
-                });
-            }
+        if content.is_none() && matches!(operation, "insert" | "replace") {
+            anyhow::bail!("Missing 'content' for {operation}");
+        }
+        let new_content = match edit_lines(
+            &original_content,
+            operation,
+            line,
+            end_line,
+            content.unwrap_or_default(),
+        ) {
+            Ok(new_content) => new_content,
+            Err(error) => return Ok(Self::failure(error)),
         };
 
+        if self.security.dry_run {
+            let action = format!(
+                "{operation} at line {line} of {path}\n{}",
+                edit_summary::summarize(Some(&original_content), &new_content)
+            );
+            return Ok(dry_run::preview(self.name(), &action, &args));
+        }
+
+        if self.security.approve_file_changes {
+            let change = format!(
+                "file_edit wants to change {path}:\n{}",
+                edit_summary::unified_diff(path, Some(&original_content), &new_content)
+            );
+            if let Err(reason) = approval::request(&change).await {
+                return Ok(Self::failure(reason));
+            }
+        }
+
+        let label = format!("file_edit {path}");
+        checkpoints::before_change(&self.security.workspace_dir, label).await;
+
         // Write the modified content
         match tokio::fs::write(&full_path, &new_content).await {
             Ok(()) => {
@@ -198,15 +238,57 @@ impl Tool for FileEditTool {
                 };
                 Ok(ToolResult {
                     success: true,
-                    output: format!("{} operation completed. {} lines changed in {}", operation, lines_changed, path),
+                    output: format!(
+                        "{} operation completed. {} lines changed in {}\n{}",
+                        operation,
+                        lines_changed,
+                        path,
+                        edit_summary::summarize(Some(&original_content), &new_content)
+                    ),
                     error: None,
                 })
             }
-            Err(e) => Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!("Failed to write file: {e}")),
-            }),
+            Err(e) => Ok(Self::failure(format!("Failed to write file: {e}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn file_edit_checks_the_file_behind_a_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("workspace");
+        let outside = dir.path().join("outside");
+        tokio::fs::create_dir_all(&workspace).await.unwrap();
+        tokio::fs::create_dir_all(&outside).await.unwrap();
+        tokio::fs::write(outside.join("notes.txt"), "a\n").await.unwrap();
+        tokio::fs::write(workspace.join(".env"), "KEY=1\n").await.unwrap();
+        std::os::unix::fs::symlink(outside.join("notes.txt"), workspace.join("notes.txt")).unwrap();
+        std::os::unix::fs::symlink(workspace.join(".env"), workspace.join("env.txt")).unwrap();
+
+        let tool = FileEditTool::new(Arc::new(SecurityPolicy {
+            workspace_dir: workspace.clone(),
+            workspace_only: true,
+            ..SecurityPolicy::default()
+        }));
+        for path in ["notes.txt", "env.txt"] {
+            let result = tool
+                .execute(json!({"path": path, "operation": "insert", "line": 1, "content": "x"}))
+                .await
+                .unwrap();
+            assert!(!result.success, "{path} should be refused");
         }
+        assert_eq!(
+            tokio::fs::read_to_string(outside.join("notes.txt")).await.unwrap(),
+            "a\n"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(workspace.join(".env")).await.unwrap(),
+            "KEY=1\n"
+        );
     }
 }
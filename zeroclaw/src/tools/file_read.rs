@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use crate::security::{PathAccess, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
@@ -31,7 +31,7 @@ impl Tool for FileReadTool {
             "properties": {
                 "path": {
                     "type": "string",
-                    "description": "Relative path to the file within the workspace"
+                    "description": "Path relative to the workspace, or absolute under a reference root"
                 }
             },
             "required": ["path"]
@@ -45,7 +45,7 @@ impl Tool for FileReadTool {
             .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
 
         // Security check: validate path is within workspace
-        if !self.security.is_path_allowed(path) {
+        if !self.security.is_path_allowed_for(path, PathAccess::Read) {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -67,7 +67,10 @@ impl Tool for FileReadTool {
             }
         };
 
-        if !self.security.is_resolved_path_allowed(&resolved_path) {
+        if !self
+            .security
+            .is_resolved_path_allowed_for(&resolved_path, PathAccess::Read)
+        {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -280,6 +283,40 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&root).await;
     }
 
+    #[tokio::test]
+    async fn file_read_allows_reference_roots() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let docs = tempfile::TempDir::new().unwrap();
+        tokio::fs::write(docs.path().join("spec.md"), "reference")
+            .await
+            .unwrap();
+
+        let tool = FileReadTool::new(Arc::new(SecurityPolicy {
+            workspace_dir: workspace.path().to_path_buf(),
+            workspace_only: true,
+            forbidden_paths: vec![],
+            reference_roots: vec![crate::security::policy::ReferenceRoot {
+                path: docs.path().to_path_buf(),
+                access: PathAccess::Read,
+            }],
+            ..SecurityPolicy::default()
+        }));
+        let path = docs.path().join("spec.md");
+        let result = tool
+            .execute(json!({"path": path.to_str().unwrap()}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "reference");
+
+        let outside = workspace.path().parent().unwrap().join("elsewhere.md");
+        let result = tool
+            .execute(json!({"path": outside.to_str().unwrap()}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+
     #[tokio::test]
     async fn file_read_rejects_oversized_file() {
         let dir = std::env::temp_dir().join("zeroclaw_test_file_read_large");
@@ -1,3 +1,7 @@
+use super::approval;
+use super::checkpoints;
+use super::dry_run;
+use super::edit_summary;
 use super::traits::{Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -42,6 +46,7 @@ impl Tool for FileWriteTool {
         })
     }
 
+    #[allow(clippy::too_many_lines)]
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let path = args
             .get("path")
@@ -64,6 +69,31 @@ impl Tool for FileWriteTool {
 
         let full_path = self.security.workspace_dir.join(path);
 
+        if self.security.dry_run {
+            let before = tokio::fs::read_to_string(&full_path).await.ok();
+            let action = format!(
+                "write {} bytes to {path}\n{}",
+                content.len(),
+                edit_summary::summarize(before.as_deref(), content)
+            );
+            return Ok(dry_run::preview(self.name(), &action, &args));
+        }
+
+        if self.security.approve_file_changes {
+            let before = tokio::fs::read_to_string(&full_path).await.ok();
+            let change = format!(
+                "file_write wants to write {path}:\n{}",
+                edit_summary::unified_diff(path, before.as_deref(), content)
+            );
+            if let Err(reason) = approval::request(&change).await {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(reason),
+                });
+            }
+        }
+
         let Some(parent) = full_path.parent() else {
             return Ok(ToolResult {
                 success: false,
@@ -87,17 +117,6 @@ impl Tool for FileWriteTool {
             }
         };
 
-        if !self.security.is_resolved_path_allowed(&resolved_parent) {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!(
-                    "Resolved path escapes workspace: {}",
-                    resolved_parent.display()
-                )),
-            });
-        }
-
         let Some(file_name) = full_path.file_name() else {
             return Ok(ToolResult {
                 success: false,
@@ -106,7 +125,19 @@ impl Tool for FileWriteTool {
             });
         };
 
+        // Check the file itself, so protected names reached through a
+        // symlinked directory are caught too
         let resolved_target = resolved_parent.join(file_name);
+        if !self.security.is_resolved_path_allowed(&resolved_target) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Resolved path escapes workspace: {}",
+                    resolved_target.display()
+                )),
+            });
+        }
 
         // If the target already exists and is a symlink, refuse to follow it
         if let Ok(meta) = tokio::fs::symlink_metadata(&resolved_target).await {
@@ -122,10 +153,19 @@ impl Tool for FileWriteTool {
             }
         }
 
+        // Previous content, for the change summary (None for new or non-UTF-8 files)
+        let before = tokio::fs::read_to_string(&resolved_target).await.ok();
+        let label = format!("file_write {path}");
+        checkpoints::before_change(&self.security.workspace_dir, label).await;
+
         match tokio::fs::write(&resolved_target, content).await {
             Ok(()) => Ok(ToolResult {
                 success: true,
-                output: format!("Written {} bytes to {path}", content.len()),
+                output: format!(
+                    "Written {} bytes to {path}\n{}",
+                    content.len(),
+                    edit_summary::summarize(before.as_deref(), content)
+                ),
                 error: None,
             }),
             Err(e) => Ok(ToolResult {
@@ -189,6 +229,74 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
 
+    #[tokio::test]
+    async fn file_write_dry_run_only_previews() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_write_dry_run");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let tool = FileWriteTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: dir.clone(),
+            dry_run: true,
+            ..SecurityPolicy::default()
+        }));
+        let result = tool
+            .execute(json!({"path": "out.txt", "content": "written!"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.starts_with("[dry run]"));
+        assert!(result.output.contains("write 8 bytes to out.txt"));
+        assert!(!dir.join("out.txt").exists());
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn file_write_waits_for_approval_when_required() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_write_approval");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let tool = FileWriteTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: dir.clone(),
+            approve_file_changes: true,
+            ..SecurityPolicy::default()
+        }));
+        // Nobody to ask: refused
+        let result = tool
+            .execute(json!({"path": "out.txt", "content": "written!"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(!dir.join("out.txt").exists());
+
+        let yes: Arc<dyn crate::tools::ask_user::UserPrompt> = Arc::new(Approve);
+        let result = approval::scope(
+            Some(yes),
+            tool.execute(json!({"path": "out.txt", "content": "written!"})),
+        )
+        .await
+        .unwrap();
+        assert!(result.success);
+        assert!(dir.join("out.txt").exists());
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    /// Approves every change after checking it shows the diff.
+    struct Approve;
+
+    #[async_trait]
+    impl crate::tools::ask_user::UserPrompt for Approve {
+        async fn ask(&self, question: &str, _: &[String]) -> anyhow::Result<Option<String>> {
+            assert!(question.contains("+++ b/out.txt\n@@ -0,0 +1,1 @@\n+written!"));
+            Ok(Some("approve".into()))
+        }
+    }
+
     #[tokio::test]
     async fn file_write_creates_parent_dirs() {
         let dir = std::env::temp_dir().join("zeroclaw_test_file_write_nested");
@@ -230,6 +338,8 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(content, "new");
+        assert!(result.output.contains("lines: 1 -> 1 (+1 -1)"));
+        assert!(result.output.contains("changed: @@ -1,1 +1,1 @@"));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -324,4 +434,36 @@ mod tests {
 
         let _ = tokio::fs::remove_dir_all(&root).await;
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn file_write_checks_the_file_behind_a_symlinked_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().to_path_buf();
+        tokio::fs::create_dir_all(workspace.join("config")).await.unwrap();
+        tokio::fs::write(workspace.join("config/app.toml"), "old").await.unwrap();
+        std::os::unix::fs::symlink(workspace.join("config"), workspace.join("alias")).unwrap();
+
+        let tool = FileWriteTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace.clone(),
+            write_protected_paths: vec!["config/app.toml".into()],
+            ..SecurityPolicy::default()
+        }));
+        let result = tool
+            .execute(json!({"path": "alias/app.toml", "content": "new"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            tokio::fs::read_to_string(workspace.join("config/app.toml")).await.unwrap(),
+            "old"
+        );
+
+        let result = tool
+            .execute(json!({"path": "alias/other.toml", "content": "new"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+    }
 }
@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use crate::security::{PathAccess, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::fmt::Write;
@@ -11,9 +11,9 @@ const MAX_IMAGE_BYTES: u64 = 5_242_880;
 
 /// Tool to read image metadata and optionally return base64-encoded data.
 ///
-/// Since providers are currently text-only, this tool extracts what it can
-/// (file size, format, dimensions from header bytes) and provides base64
-/// data for future multimodal provider support.
+/// Extracts what it can from header bytes (file size, format, dimensions).
+/// With `include_base64`, the image is appended as a `data:` URI line, which the
+/// agent loop forwards as an image part to vision-capable providers.
 pub struct ImageInfoTool {
     security: Arc<SecurityPolicy>,
 }
@@ -159,7 +159,10 @@ impl Tool for ImageInfoTool {
         let path = Path::new(path_str);
 
         // Restrict reads to workspace directory to prevent arbitrary file exfiltration
-        if !self.security.is_path_allowed(path_str) {
+        if !self
+            .security
+            .is_path_allowed_for(path_str, PathAccess::Read)
+        {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -0,0 +1,228 @@
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::jobs::{self, Job, JobStore};
+use crate::security::SecurityPolicy;
+use crate::util::truncate_with_ellipsis;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Jobs listed by the tool.
+const LIST_LIMIT: usize = 10;
+
+/// Let the agent hand long-running work to the daemon's job queue
+pub struct JobsTool {
+    security: Arc<SecurityPolicy>,
+    store: JobStore,
+}
+
+impl JobsTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        let store = JobStore::new(&security.workspace_dir);
+        Self { security, store }
+    }
+}
+
+fn describe(job: &Job) -> String {
+    let mut out = format!(
+        "Job {} [{}]: {}",
+        job.short_id(),
+        job.status.as_str(),
+        truncate_with_ellipsis(&job.prompt, 80)
+    );
+    if let Some(progress) = job.progress.last() {
+        let _ = write!(out, "\nLast progress: {}", progress.message);
+    }
+    if let Some(result) = &job.result {
+        let _ = write!(out, "\nResult:\n{result}");
+    }
+    if let Some(error) = &job.error {
+        let _ = write!(out, "\nError: {error}");
+    }
+    out
+}
+
+#[async_trait]
+impl Tool for JobsTool {
+    fn name(&self) -> &str {
+        "jobs"
+    }
+
+    fn description(&self) -> &str {
+        "Queue a long-running task as a background job, or check on queued jobs. The result is sent to this chat when the job is done. Use when: a task would take many steps or minutes (refactoring a module, researching a topic). Don't use when: the answer can be given now."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["enqueue", "status", "list"],
+                    "description": "enqueue: queue a new job; status: show one job; list: show recent jobs"
+                },
+                "prompt": {
+                    "type": "string",
+                    "description": "For enqueue: the full task, written so it can be done without this conversation"
+                },
+                "id": {
+                    "type": "string",
+                    "description": "For status: job id or its first characters"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+
+        let output = match action {
+            "enqueue" => {
+                let prompt = args
+                    .get("prompt")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'prompt' parameter"))?;
+                if !self.security.can_act() {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("Jobs cannot be queued in read-only mode".into()),
+                    });
+                }
+                if self.security.dry_run {
+                    return Ok(dry_run::preview(
+                        self.name(),
+                        "queue a background job",
+                        &args,
+                    ));
+                }
+                if !self.security.record_action() {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some("Rate limit exceeded: action budget exhausted".into()),
+                    });
+                }
+                let job = self.store.enqueue(prompt, jobs::current_origin())?;
+                let delivery = match &job.origin {
+                    Some(origin) => format!("the result will be sent to {}", origin.channel),
+                    None => format!("check on it with status and id {}", job.short_id()),
+                };
+                format!("Queued job {}; {delivery}", job.short_id())
+            }
+            "status" => {
+                let id = args
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'id' parameter"))?;
+                match self.store.load(id) {
+                    Ok(job) => describe(&job),
+                    Err(e) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e.to_string()),
+                        });
+                    }
+                }
+            }
+            "list" => {
+                let jobs = self.store.list()?;
+                if jobs.is_empty() {
+                    "No jobs yet".to_string()
+                } else {
+                    jobs.iter()
+                        .take(LIST_LIMIT)
+                        .map(|job| {
+                            format!(
+                                "{} [{}] {}",
+                                job.short_id(),
+                                job.status.as_str(),
+                                truncate_with_ellipsis(&job.prompt, 60)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }
+            other => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!(
+                        "Unknown action: {other}. Use 'enqueue', 'status' or 'list'"
+                    )),
+                });
+            }
+        };
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::{JobOrigin, JobStatus};
+
+    fn tool_in(dir: &std::path::Path) -> JobsTool {
+        JobsTool::new(Arc::new(SecurityPolicy {
+            workspace_dir: dir.to_path_buf(),
+            ..SecurityPolicy::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn enqueue_remembers_the_originating_chat() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = tool_in(dir.path());
+        let origin = JobOrigin {
+            channel: "telegram".into(),
+            recipient: "42".into(),
+        };
+        let queued = jobs::scope(
+            Some(origin.clone()),
+            tool.execute(json!({"action": "enqueue", "prompt": "research WAL tuning"})),
+        )
+        .await
+        .unwrap();
+        assert!(queued.success);
+        assert!(queued.output.contains("sent to telegram"));
+
+        let job = &JobStore::new(dir.path()).list().unwrap()[0];
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.origin.as_ref(), Some(&origin));
+
+        let status = tool
+            .execute(json!({"action": "status", "id": job.short_id()}))
+            .await
+            .unwrap();
+        assert!(status.output.contains("[queued]: research WAL tuning"));
+        let listed = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert!(listed.output.contains(job.short_id()));
+    }
+
+    #[tokio::test]
+    async fn enqueue_is_refused_when_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = JobsTool::new(Arc::new(SecurityPolicy {
+            autonomy: crate::security::AutonomyLevel::ReadOnly,
+            workspace_dir: dir.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        }));
+        let result = tool
+            .execute(json!({"action": "enqueue", "prompt": "x"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(JobStore::new(dir.path()).list().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+use super::traits::{Tool, ToolResult};
+use crate::knowledge;
+use crate::memory::Memory;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Most chunks one search may return.
+const MAX_RESULTS: usize = 20;
+
+/// Search the indexed knowledge base (documents from `[knowledge] dirs`).
+pub struct KbSearchTool {
+    memory: Arc<dyn Memory>,
+}
+
+impl KbSearchTool {
+    pub fn new(memory: Arc<dyn Memory>) -> Self {
+        Self { memory }
+    }
+}
+
+#[async_trait]
+impl Tool for KbSearchTool {
+    fn name(&self) -> &str {
+        "kb_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the knowledge base of indexed documents. Returns matching passages with their source file."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Question or keywords to look up"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max passages to return (default: 5)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(5, |v| (v as usize).clamp(1, MAX_RESULTS));
+
+        match knowledge::search(self.memory.as_ref(), query, limit).await {
+            Ok(entries) if entries.is_empty() => Ok(ToolResult {
+                success: true,
+                output: "No knowledge base passages match that query.".into(),
+                error: None,
+            }),
+            Ok(entries) => {
+                let mut output = format!("Found {} passages:\n", entries.len());
+                for entry in &entries {
+                    let score = entry
+                        .score
+                        .map_or_else(String::new, |s| format!(" [{s:.0}%]"));
+                    let _ = writeln!(output, "\n---{score}\n{}", entry.content);
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Knowledge base search failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryCategory, SqliteMemory};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn kb_search_ignores_other_memories() {
+        let tmp = TempDir::new().unwrap();
+        let mem: Arc<dyn Memory> = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+        mem.store("pref", "User likes Postgres", MemoryCategory::Core)
+            .await
+            .unwrap();
+        let tool = KbSearchTool::new(mem.clone());
+
+        let result = tool.execute(json!({"query": "Postgres"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("No knowledge base passages"));
+
+        mem.store(
+            "kb:docs/db.md#0",
+            "[docs/db.md]\nPostgres runs on port 5433",
+            knowledge::category(),
+        )
+        .await
+        .unwrap();
+        let result = tool.execute(json!({"query": "Postgres"})).await.unwrap();
+        assert!(result.output.contains("Found 1 passages"));
+        assert!(result.output.contains("port 5433"));
+        assert!(!result.output.contains("User likes"));
+    }
+
+    #[tokio::test]
+    async fn kb_search_requires_query() {
+        let tmp = TempDir::new().unwrap();
+        let tool = KbSearchTool::new(Arc::new(SqliteMemory::new(tmp.path()).unwrap()));
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
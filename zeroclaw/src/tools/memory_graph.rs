@@ -0,0 +1,171 @@
+use super::traits::{Tool, ToolResult};
+use crate::memory::graph::KnowledgeGraph;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Most relations one query may return.
+const MAX_RESULTS: usize = 50;
+
+/// Record and look up who is connected to what (`[memory.graph]`).
+pub struct MemoryGraphTool {
+    graph: Arc<KnowledgeGraph>,
+    depth: usize,
+}
+
+impl MemoryGraphTool {
+    pub fn new(graph: Arc<KnowledgeGraph>, depth: usize) -> Self {
+        Self { graph, depth }
+    }
+}
+
+fn arg<'a>(args: &'a serde_json::Value, name: &str) -> anyhow::Result<&'a str> {
+    args.get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing '{name}' parameter"))
+}
+
+#[async_trait]
+impl Tool for MemoryGraphTool {
+    fn name(&self) -> &str {
+        "memory_graph"
+    }
+
+    fn description(&self) -> &str {
+        "Record a relation between two entities (people, companies, projects, places) in the knowledge graph, remove one, or look up everything connected to an entity."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["relate", "unrelate", "query"],
+                    "description": "relate: record source -relation-> target; unrelate: remove it; query: relations around the entities named in 'query'"
+                },
+                "source": {
+                    "type": "string",
+                    "description": "Entity the relation starts from (e.g. 'Alice')"
+                },
+                "relation": {
+                    "type": "string",
+                    "description": "How they are connected, as a short verb phrase (e.g. 'works at')"
+                },
+                "target": {
+                    "type": "string",
+                    "description": "Entity the relation points to (e.g. 'Acme')"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Text naming the entities to look up"
+                },
+                "depth": {
+                    "type": "integer",
+                    "description": "Hops to follow out from those entities (default: [memory.graph] depth)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let graph = &self.graph;
+        let output = match arg(&args, "action")? {
+            "relate" => {
+                let relation = graph.relate(
+                    arg(&args, "source")?,
+                    arg(&args, "relation")?,
+                    arg(&args, "target")?,
+                )?;
+                format!("Recorded: {relation}")
+            }
+            "unrelate" => {
+                let (source, relation, target) = (
+                    arg(&args, "source")?,
+                    arg(&args, "relation")?,
+                    arg(&args, "target")?,
+                );
+                if graph.unrelate(source, relation, target)? {
+                    format!("Removed: {source} —{relation}→ {target}")
+                } else {
+                    format!("No relation found: {source} —{relation}→ {target}")
+                }
+            }
+            "query" => {
+                #[allow(clippy::cast_possible_truncation)]
+                let depth = args
+                    .get("depth")
+                    .and_then(serde_json::Value::as_u64)
+                    .map_or(self.depth, |v| v as usize)
+                    .clamp(1, 5);
+                let relations = graph.graph_query(arg(&args, "query")?, depth, MAX_RESULTS)?;
+                if relations.is_empty() {
+                    "No known entities in that query, or none with relations.".to_string()
+                } else {
+                    let mut output = format!("Found {} relations:\n", relations.len());
+                    for relation in &relations {
+                        let _ = writeln!(output, "- {relation}");
+                    }
+                    output
+                }
+            }
+            other => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!(
+                        "Unknown action: {other}. Use 'relate', 'unrelate' or 'query'"
+                    )),
+                });
+            }
+        };
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn relations_are_recorded_and_queried() {
+        let tmp = TempDir::new().unwrap();
+        let graph = Arc::new(KnowledgeGraph::open(tmp.path()).unwrap());
+        let tool = MemoryGraphTool::new(graph, 2);
+
+        let result = tool
+            .execute(json!({"action": "relate", "source": "Alice", "relation": "works at", "target": "Acme"}))
+            .await
+            .unwrap();
+        assert_eq!(result.output, "Recorded: Alice —works at→ Acme");
+        let result = tool
+            .execute(json!({"action": "query", "query": "Who works at acme?"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 1 relations"));
+
+        let result = tool
+            .execute(json!({"action": "unrelate", "source": "alice", "relation": "works at", "target": "ACME"}))
+            .await
+            .unwrap();
+        assert!(result.output.starts_with("Removed"));
+        assert!(tool
+            .execute(json!({"action": "relate", "source": "Alice"}))
+            .await
+            .is_err());
+        assert!(
+            !tool
+                .execute(json!({"action": "drop"}))
+                .await
+                .unwrap()
+                .success
+        );
+    }
+}
@@ -1,32 +1,70 @@
+pub mod approval;
+pub mod ask_user;
 pub mod browser;
+pub mod browser_act;
 pub mod browser_open;
+pub mod calculator;
+pub mod calendar;
+pub mod checkpoints;
+pub mod clipboard;
 pub mod composio;
+pub mod dry_run;
+pub mod edit_summary;
+pub mod fetch_full_output;
 pub mod file_edit;
 pub mod file_read;
 pub mod file_write;
 pub mod image_info;
+pub mod jobs;
+pub mod kb_search;
 pub mod memory_forget;
+pub mod memory_graph;
 pub mod memory_recall;
 pub mod memory_store;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+pub mod remote_node;
+pub mod run_workflow;
 pub mod screenshot;
+pub mod send_to_agent;
 pub mod shell;
+pub mod signature;
+pub mod speak;
 pub mod traits;
+pub mod transcribe;
+pub mod web_search;
 
+pub use ask_user::AskUserTool;
 pub use browser::BrowserTool;
+pub use browser_act::BrowserActTool;
 pub use browser_open::BrowserOpenTool;
-pub use composio::ComposioTool;
+pub use calculator::CalculatorTool;
+pub use calendar::CalendarTool;
+pub use checkpoints::CheckpointsTool;
+pub use clipboard::ClipboardTool;
+pub use composio::{ComposioAccounts, ComposioTool};
+pub use fetch_full_output::FetchFullOutputTool;
 pub use file_edit::FileEditTool;
 pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
 pub use image_info::ImageInfoTool;
+pub use jobs::JobsTool;
+pub use kb_search::KbSearchTool;
 pub use memory_forget::MemoryForgetTool;
+pub use memory_graph::MemoryGraphTool;
 pub use memory_recall::MemoryRecallTool;
 pub use memory_store::MemoryStoreTool;
+pub use remote_node::route_to_nodes;
+pub use run_workflow::RunWorkflowTool;
 pub use screenshot::ScreenshotTool;
+pub use send_to_agent::SendToAgentTool;
 pub use shell::ShellTool;
+pub use speak::SpeakTool;
 pub use traits::Tool;
 #[allow(unused_imports)]
 pub use traits::{ToolResult, ToolSpec};
+pub use transcribe::TranscribeTool;
+pub use web_search::WebSearchTool;
 
 use crate::memory::Memory;
 use crate::runtime::{NativeRuntime, RuntimeAdapter};
@@ -80,6 +118,7 @@ pub fn all_tools_with_runtime(
         Box::new(FileReadTool::new(security.clone())),
         Box::new(FileWriteTool::new(security.clone())),
         Box::new(FileEditTool::new(security.clone())),
+        Box::new(CheckpointsTool::new(security.clone())),
         Box::new(MemoryStoreTool::new(memory.clone())),
         Box::new(MemoryRecallTool::new(memory.clone())),
         Box::new(MemoryForgetTool::new(memory)),
@@ -97,21 +136,107 @@ pub fn all_tools_with_runtime(
             browser_config.allowed_domains.clone(),
             browser_config.session_name.clone(),
         )));
+        // CDP-driven automation (headless Chromium)
+        tools.push(Box::new(BrowserActTool::new(
+            security.clone(),
+            browser_config,
+        )));
     }
 
     // Vision tools are always available
     tools.push(Box::new(ScreenshotTool::new(security.clone())));
     tools.push(Box::new(ImageInfoTool::new(security.clone())));
 
+    // Desktop clipboard (get/set), refused in read-only mode
+    tools.push(Box::new(ClipboardTool::new(security.clone())));
+
+    // Exact arithmetic, also used by the numeric answer verifier
+    tools.push(Box::new(CalculatorTool::new()));
+
+    // Reads back tool outputs shortened to fit the context
+    tools.push(Box::new(FetchFullOutputTool::new()));
+
     if let Some(key) = composio_key {
         if !key.is_empty() {
-            tools.push(Box::new(ComposioTool::new(key)));
+            tools.push(Box::new(ComposioTool::new(key, security.clone())));
         }
     }
 
     tools
 }
 
+/// Create tools that are only available when enabled and configured in `config`.
+pub fn optional_tools(
+    config: &crate::config::Config,
+    security: &Arc<SecurityPolicy>,
+) -> Vec<Box<dyn Tool>> {
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+
+    if let Some(transcriber) = crate::transcription::Transcriber::from_app_config(config) {
+        tools.push(Box::new(TranscribeTool::new(
+            security.clone(),
+            Arc::new(transcriber),
+        )));
+    }
+
+    if let Some(synthesizer) = crate::tts::Synthesizer::from_app_config(config) {
+        tools.push(Box::new(SpeakTool::new(
+            security.clone(),
+            Arc::new(synthesizer),
+        )));
+    }
+
+    if let Some(web_search) = WebSearchTool::from_config(&config.web_search) {
+        tools.push(Box::new(web_search));
+    }
+
+    if let Some(calendar) = CalendarTool::from_config(config, security.clone()) {
+        tools.push(Box::new(calendar));
+    }
+
+    if config.memory.graph.enabled {
+        let graph = crate::memory::graph::KnowledgeGraph::for_config(
+            &config.memory,
+            &config.workspace_dir,
+        );
+        match graph {
+            Ok(graph) => tools.push(Box::new(MemoryGraphTool::new(
+                Arc::new(graph),
+                config.memory.graph.depth,
+            ))),
+            Err(e) => tracing::warn!("Knowledge graph unavailable: {e}"),
+        }
+    }
+
+    if config.jobs.enabled {
+        tools.push(Box::new(JobsTool::new(security.clone())));
+    }
+
+    if config.workflows.enabled {
+        tools.push(Box::new(RunWorkflowTool::new(config, security.clone())));
+    }
+
+    if config.agent_bus.enabled {
+        tools.push(Box::new(SendToAgentTool::new(
+            &config.agent_bus,
+            security.clone(),
+        )));
+    }
+
+    tools.extend(composio::action_tools(config, security));
+
+    #[cfg(feature = "plugins")]
+    tools.extend(plugin::load_plugins(config, security));
+    #[cfg(not(feature = "plugins"))]
+    if config.plugins.enabled {
+        tracing::warn!(
+            "[plugins] is enabled, but this build cannot load plugins (the `plugins` feature)"
+        );
+    }
+
+    tools
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,6 +265,7 @@ mod tests {
             enabled: false,
             allowed_domains: vec!["example.com".into()],
             session_name: None,
+            ..BrowserConfig::default()
         };
 
         let tools = all_tools(&security, mem, None, &browser);
@@ -162,11 +288,37 @@ mod tests {
             enabled: true,
             allowed_domains: vec!["example.com".into()],
             session_name: None,
+            ..BrowserConfig::default()
         };
 
         let tools = all_tools(&security, mem, None, &browser);
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"browser_open"));
+        assert!(names.contains(&"browser_act"));
+    }
+
+    #[test]
+    fn optional_tools_gated_by_config() {
+        let security = Arc::new(SecurityPolicy::default());
+        let mut config = crate::config::Config::default();
+        assert!(optional_tools(&config, &security).is_empty());
+
+        config.transcription.enabled = true;
+        config.transcription.api_key = Some("sk-test".into());
+        let tools = optional_tools(&config, &security);
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["transcribe"]);
+
+        config.tts.enabled = true;
+        config.tts.api_key = Some("sk-test".into());
+        let tools = optional_tools(&config, &security);
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["transcribe", "speak"]);
+
+        config.web_search.enabled = true;
+        let tools = optional_tools(&config, &security);
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["transcribe", "speak", "web_search"]);
     }
 
     #[test]
@@ -0,0 +1,366 @@
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashSet;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Version of the tool plugin interface. A plugin puts the version it was
+/// built against in its descriptor; others are not loaded.
+pub const ZC_TOOL_ABI_VERSION: u32 = 1;
+
+/// What `zc_tool_descriptor` returns. The descriptor and its strings must
+/// stay valid while the library is loaded.
+#[repr(C)]
+pub struct ZcToolDescriptor {
+    /// `ZC_TOOL_ABI_VERSION`
+    pub abi_version: u32,
+    /// Tool name the model calls, e.g. `word_count`
+    pub name: *const c_char,
+    /// What the tool does, shown to the model
+    pub description: *const c_char,
+    /// JSON schema of the arguments, or NULL for none
+    pub parameters_schema: *const c_char,
+}
+
+/// Sink `zc_tool_execute` writes its output to: pass `sink` back with
+/// NUL-terminated UTF-8 text, as often as needed. The pieces are joined.
+pub type ZcToolOutputFn = Option<unsafe extern "C" fn(sink: *mut c_void, text: *const c_char)>;
+
+type DescriptorFn = unsafe extern "C" fn() -> *const ZcToolDescriptor;
+type ExecuteFn = unsafe extern "C" fn(
+    args_json: *const c_char,
+    output: ZcToolOutputFn,
+    sink: *mut c_void,
+) -> i32;
+
+/// A tool served by a plugin library.
+pub struct PluginTool {
+    name: String,
+    description: String,
+    schema: serde_json::Value,
+    execute: ExecuteFn,
+    security: Arc<SecurityPolicy>,
+    /// Keeps `execute` loaded
+    library: Arc<libloading::Library>,
+}
+
+impl PluginTool {
+    /// Load the plugin at `path` and read its descriptor.
+    pub fn load(path: &Path, security: Arc<SecurityPolicy>) -> anyhow::Result<Self> {
+        // SAFETY: loading runs the library's initializers; enabling plugins
+        // means trusting the libraries in the plugins directory.
+        let library = unsafe { libloading::Library::new(path) }?;
+        // SAFETY: the symbols have the types zeroclaw_tool.h declares, and
+        // `library` outlives every use of them.
+        let (descriptor, execute) = unsafe {
+            let describe = library.get::<DescriptorFn>(b"zc_tool_descriptor\0")?;
+            let execute = *library.get::<ExecuteFn>(b"zc_tool_execute\0")?;
+            (describe().as_ref(), execute)
+        };
+        let Some(descriptor) = descriptor else {
+            bail!("zc_tool_descriptor returned NULL");
+        };
+        if descriptor.abi_version != ZC_TOOL_ABI_VERSION {
+            bail!(
+                "built for tool ABI {}, this build loads version {ZC_TOOL_ABI_VERSION}",
+                descriptor.abi_version
+            );
+        }
+        // SAFETY: the descriptor's strings are NUL-terminated or NULL
+        let text = |ptr: *const c_char| (!ptr.is_null()).then(|| unsafe { CStr::from_ptr(ptr) });
+        let name = text(descriptor.name)
+            .map(|name| name.to_string_lossy().trim().to_string())
+            .filter(|name| !name.is_empty())
+            .context("the descriptor has no name")?;
+        let description = text(descriptor.description)
+            .map(|d| d.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let schema = match text(descriptor.parameters_schema) {
+            Some(schema) => serde_json::from_slice(schema.to_bytes())
+                .context("parameters_schema is not valid JSON")?,
+            None => json!({"type": "object", "properties": {}}),
+        };
+
+        Ok(Self {
+            name,
+            description,
+            schema,
+            execute,
+            security,
+            library: Arc::new(library),
+        })
+    }
+}
+
+/// Where `[plugins] dir` points: `~` expanded, relative to the directory of
+/// the config file unless absolute.
+pub fn plugins_dir(config: &Config) -> PathBuf {
+    let dir = PathBuf::from(shellexpand::tilde(&config.plugins.dir).as_ref());
+    if dir.is_absolute() {
+        dir
+    } else {
+        config
+            .config_path
+            .parent()
+            .map_or_else(|| dir.clone(), |base| base.join(&dir))
+    }
+}
+
+/// Whether `dir` resolves to the workspace or somewhere below it, where the
+/// agent's file tools could plant a library.
+fn inside_workspace(dir: &Path, workspace_dir: &Path) -> bool {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let workspace = workspace_dir
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_dir.to_path_buf());
+    dir.starts_with(workspace)
+}
+
+/// Tools from the plugin libraries in `[plugins] dir`, in file name order,
+/// when `[plugins] enabled`. Libraries that fail to load, or repeat a name
+/// an earlier plugin took, are skipped with a warning. A directory inside
+/// the workspace is refused.
+pub fn load_plugins(config: &Config, security: &Arc<SecurityPolicy>) -> Vec<Box<dyn Tool>> {
+    if !config.plugins.enabled {
+        return Vec::new();
+    }
+    let dir = plugins_dir(config);
+    if inside_workspace(&dir, &config.workspace_dir) {
+        tracing::warn!(
+            "Not loading plugins from {}: it is inside the workspace, which the agent can write to",
+            dir.display()
+        );
+        return Vec::new();
+    }
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        tracing::warn!("Plugins directory {} is not readable", dir.display());
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == std::env::consts::DLL_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+
+    let mut names = HashSet::new();
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    for path in paths {
+        match PluginTool::load(&path, security.clone()) {
+            Ok(tool) if !names.insert(tool.name.clone()) => tracing::warn!(
+                "Skipping tool plugin {}: another plugin is named {}",
+                path.display(),
+                tool.name
+            ),
+            Ok(tool) => tools.push(Box::new(tool)),
+            Err(e) => tracing::warn!("Skipping tool plugin {}: {e:#}", path.display()),
+        }
+    }
+    tools
+}
+
+/// Appends what a plugin writes to the `Vec<u8>` behind `sink`.
+unsafe extern "C" fn append_output(sink: *mut c_void, text: *const c_char) {
+    if let (Some(output), false) = (sink.cast::<Vec<u8>>().as_mut(), text.is_null()) {
+        output.extend_from_slice(CStr::from_ptr(text).to_bytes());
+    }
+}
+
+#[async_trait]
+impl Tool for PluginTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.schema.clone()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        // Plugins can do anything, so they count as acting
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+
+        if self.security.dry_run {
+            return Ok(dry_run::preview(&self.name, "run this plugin", &args));
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: rate limit exceeded".into()),
+            });
+        }
+
+        let args = CString::new(args.to_string())?;
+        let (execute, library) = (self.execute, self.library.clone());
+        let (code, output) = tokio::task::spawn_blocking(move || {
+            let _library = library;
+            let mut output = Vec::<u8>::new();
+            // SAFETY: `args` and `output` outlive the call, and the library
+            // stays loaded until it returns
+            let code = unsafe {
+                execute(
+                    args.as_ptr(),
+                    Some(append_output),
+                    std::ptr::addr_of_mut!(output).cast(),
+                )
+            };
+            (code, String::from_utf8_lossy(&output).into_owned())
+        })
+        .await?;
+
+        if code == 0 {
+            Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+            })
+        } else {
+            let error = if output.trim().is_empty() {
+                format!("{} failed with code {code}", self.name)
+            } else {
+                output
+            };
+            Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use std::process::Command;
+
+    /// Build `examples/tool_plugin.c` into `dir`, or None without a C compiler.
+    fn build_example(dir: &Path) -> Option<PathBuf> {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let library = dir.join(format!("word_count.{}", std::env::consts::DLL_EXTENSION));
+        let built = Command::new("cc")
+            .args(["-shared", "-fPIC", "-I"])
+            .arg(crate_dir.join("include"))
+            .arg(crate_dir.join("examples/tool_plugin.c"))
+            .arg("-o")
+            .arg(&library)
+            .status()
+            .is_ok_and(|status| status.success());
+        built.then_some(library)
+    }
+
+    /// Workspace in `root/workspace`, plugins in `root/plugins`.
+    fn config(root: &Path) -> Config {
+        let mut config = Config {
+            workspace_dir: root.join("workspace"),
+            ..Config::default()
+        };
+        config.plugins.enabled = true;
+        config.plugins.dir = root.join("plugins").to_string_lossy().into_owned();
+        config
+    }
+
+    #[tokio::test]
+    async fn plugins_load_as_tools_and_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("plugins");
+        std::fs::create_dir(&dir).unwrap();
+        let Some(_) = build_example(&dir) else {
+            eprintln!("no C compiler; skipping");
+            return;
+        };
+        // Not libraries: ignored, or skipped with a warning
+        std::fs::write(dir.join("notes.txt"), "not a plugin").unwrap();
+        std::fs::write(
+            dir.join(format!("broken.{}", std::env::consts::DLL_EXTENSION)),
+            "not a library",
+        )
+        .unwrap();
+
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            ..SecurityPolicy::default()
+        });
+        let tools = load_plugins(&config(tmp.path()), &security);
+        assert_eq!(tools.len(), 1);
+        let tool = &tools[0];
+        assert_eq!(tool.name(), "word_count");
+        assert_eq!(tool.parameters_schema()["required"][0], "text");
+
+        let result = tool
+            .execute(json!({"text": "one two  three"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{result:?}");
+        assert_eq!(result.output, "3 words");
+
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("Missing \"text\" argument"));
+
+        let read_only = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tools = load_plugins(&config(tmp.path()), &read_only);
+        let result = tools[0].execute(json!({"text": "hi"})).await.unwrap();
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+
+    #[test]
+    fn nothing_loads_unless_enabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = config(tmp.path());
+        let security = Arc::new(SecurityPolicy::default());
+        // A missing directory loads nothing
+        assert!(load_plugins(&config, &security).is_empty());
+
+        config.plugins.enabled = false;
+        config.plugins.dir = "/nonexistent".into();
+        assert!(load_plugins(&config, &security).is_empty());
+        assert_eq!(plugins_dir(&config), PathBuf::from("/nonexistent"));
+
+        config.config_path = tmp.path().join("config.toml");
+        config.plugins.dir = "plugins".into();
+        assert_eq!(plugins_dir(&config), tmp.path().join("plugins"));
+    }
+
+    #[test]
+    fn plugins_inside_the_workspace_are_refused() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = config(tmp.path());
+        let dir = config.workspace_dir.join("plugins");
+        std::fs::create_dir_all(&dir).unwrap();
+        config.plugins.dir = dir.to_string_lossy().into_owned();
+        assert!(inside_workspace(&dir, &config.workspace_dir));
+        assert!(!inside_workspace(&tmp.path().join("plugins"), &config.workspace_dir));
+        let Some(_) = build_example(&dir) else {
+            eprintln!("no C compiler; skipping");
+            return;
+        };
+        let security = Arc::new(SecurityPolicy::default());
+        assert!(load_plugins(&config, &security).is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::config::NodesConfig;
+use crate::nodes::{NodeClient, ROUTABLE_TOOLS};
+use crate::security::{CommandRiskLevel, SecurityPolicy};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A shell or file tool that also runs on remote nodes: with `target` set
+/// the call goes to that node, otherwise it runs here as before.
+pub struct RemoteNodeTool {
+    inner: Box<dyn Tool>,
+    nodes: Arc<BTreeMap<String, NodeClient>>,
+    security: Arc<SecurityPolicy>,
+    description: String,
+}
+
+impl RemoteNodeTool {
+    pub fn new(
+        inner: Box<dyn Tool>,
+        nodes: Arc<BTreeMap<String, NodeClient>>,
+        security: Arc<SecurityPolicy>,
+    ) -> Self {
+        let names: Vec<&str> = nodes.keys().map(String::as_str).collect();
+        let description = format!(
+            "{} Set `target` to run it on a remote node instead of here: {}.",
+            inner.description(),
+            names.join(", ")
+        );
+        Self {
+            inner,
+            nodes,
+            security,
+            description,
+        }
+    }
+
+    /// Whether a remote call with `args` only reads, and so still runs in a
+    /// dry run.
+    fn reads_only(&self, args: &serde_json::Value) -> bool {
+        match self.inner.name() {
+            "file_read" => true,
+            "shell" => args
+                .get("command")
+                .and_then(|v| v.as_str())
+                .is_some_and(|command| {
+                    self.security.command_risk_level(command) == CommandRiskLevel::Low
+                }),
+            _ => false,
+        }
+    }
+}
+
+/// Give the shell and file tools in `tools` a `target` parameter when
+/// `[nodes.remote]` names any nodes. The tools share one connection per node;
+/// in a dry run under `security`, remote calls that change something are
+/// only previewed.
+pub fn route_to_nodes(
+    tools: &mut Vec<Box<dyn Tool>>,
+    config: &NodesConfig,
+    security: &Arc<SecurityPolicy>,
+) {
+    if config.remote.is_empty() {
+        return;
+    }
+    let timeout = Duration::from_secs(config.timeout_secs);
+    let nodes: Arc<BTreeMap<String, NodeClient>> = Arc::new(
+        config
+            .remote
+            .iter()
+            .map(|(name, node)| (name.clone(), NodeClient::new(name, node, timeout)))
+            .collect(),
+    );
+    *tools = std::mem::take(tools)
+        .into_iter()
+        .map(|tool| {
+            if ROUTABLE_TOOLS.contains(&tool.name()) {
+                Box::new(RemoteNodeTool::new(tool, nodes.clone(), security.clone()))
+                    as Box<dyn Tool>
+            } else {
+                tool
+            }
+        })
+        .collect();
+}
+
+#[async_trait]
+impl Tool for RemoteNodeTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let mut schema = self.inner.parameters_schema();
+        if let Some(properties) = schema
+            .get_mut("properties")
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            properties.insert(
+                "target".into(),
+                json!({
+                    "type": "string",
+                    "enum": self.nodes.keys().collect::<Vec<_>>(),
+                    "description": "Remote node to run on (omit to run here)"
+                }),
+            );
+        }
+        schema
+    }
+
+    async fn execute(&self, mut args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let target = args
+            .as_object_mut()
+            .and_then(|args| args.remove("target"))
+            .and_then(|target| target.as_str().map(str::to_string))
+            .filter(|target| !target.is_empty());
+        let Some(target) = target else {
+            return self.inner.execute(args).await;
+        };
+        let Some(node) = self.nodes.get(&target) else {
+            let known: Vec<&str> = self.nodes.keys().map(String::as_str).collect();
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Unknown node \"{target}\"; configured nodes: {}",
+                    known.join(", ")
+                )),
+            });
+        };
+        if self.security.dry_run && !self.reads_only(&args) {
+            let action = format!("run {} on node {target}", self.inner.name());
+            return Ok(dry_run::preview(self.name(), &action, &args));
+        }
+        match node.call(self.inner.name(), args).await {
+            Ok(result) => Ok(result),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("{e:#}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RemoteNodeConfig;
+    use crate::security::SecurityPolicy;
+    use crate::tools::{CalculatorTool, FileReadTool};
+
+    #[tokio::test]
+    async fn only_routable_tools_gain_a_target_and_local_calls_stay_local() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "local file").unwrap();
+        let security = Arc::new(SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let mut tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(FileReadTool::new(security.clone())),
+            Box::new(CalculatorTool::new()),
+        ];
+        let mut config = NodesConfig::default();
+        route_to_nodes(&mut tools, &config, &security);
+        assert!(tools[0].parameters_schema()["properties"]["target"].is_null());
+
+        config.remote.insert(
+            "buildbox".into(),
+            RemoteNodeConfig {
+                url: "ws://127.0.0.1:9".into(),
+                token: "t".into(),
+            },
+        );
+        route_to_nodes(&mut tools, &config, &security);
+        let schema = tools[0].parameters_schema();
+        assert_eq!(schema["properties"]["target"]["enum"], json!(["buildbox"]));
+        assert!(tools[0]
+            .description()
+            .ends_with("instead of here: buildbox."));
+        assert!(tools[1].parameters_schema()["properties"]["target"].is_null());
+
+        let local = tools[0].execute(json!({"path": "a.txt"})).await.unwrap();
+        assert!(local.output.contains("local file"));
+
+        let unknown = tools[0]
+            .execute(json!({"path": "a.txt", "target": "nas"}))
+            .await
+            .unwrap();
+        assert!(unknown.error.unwrap().starts_with("Unknown node \"nas\""));
+    }
+
+    #[tokio::test]
+    async fn dry_run_previews_remote_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            dry_run: true,
+            ..SecurityPolicy::default()
+        });
+        let mut tools: Vec<Box<dyn Tool>> =
+            vec![Box::new(crate::tools::FileWriteTool::new(security.clone()))];
+        let mut config = NodesConfig::default();
+        config.remote.insert(
+            "buildbox".into(),
+            RemoteNodeConfig {
+                url: "ws://127.0.0.1:9".into(),
+                token: "t".into(),
+            },
+        );
+        route_to_nodes(&mut tools, &config, &security);
+
+        let result = tools[0]
+            .execute(json!({"path": "a.txt", "content": "x", "target": "buildbox"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result
+            .output
+            .starts_with("[dry run] Nothing was changed; file_write would run file_write on node buildbox"));
+    }
+}
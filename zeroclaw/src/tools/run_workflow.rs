@@ -0,0 +1,157 @@
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::security::SecurityPolicy;
+use crate::workflows::WorkflowEngine;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// Let the agent run a workflow from `[workflows] dir`
+pub struct RunWorkflowTool {
+    config: Config,
+    security: Arc<SecurityPolicy>,
+    /// Built on first use, so registries that never run a workflow stay cheap
+    engine: OnceCell<WorkflowEngine>,
+}
+
+impl RunWorkflowTool {
+    pub fn new(config: &Config, security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            config: config.clone(),
+            security,
+            engine: OnceCell::new(),
+        }
+    }
+
+    fn failure(error: String) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for RunWorkflowTool {
+    fn name(&self) -> &str {
+        "run_workflow"
+    }
+
+    fn description(&self) -> &str {
+        "Run a predefined multi-step workflow by name and return each step's outcome and the final output. Use when: the user asks for an automation that exists as a workflow. Don't use when: no workflow fits; do the steps yourself."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Workflow name (its file name without extension)"
+                },
+                "input": {
+                    "type": "string",
+                    "description": "Text available to the steps as {{input}}"
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'name' parameter"))?;
+        let input = args.get("input").and_then(|v| v.as_str()).unwrap_or("");
+
+        if self.security.dry_run {
+            return Ok(dry_run::preview(
+                self.name(),
+                &format!("run workflow {name}"),
+                &args,
+            ));
+        }
+        if !self.security.record_action() {
+            return Ok(Self::failure(
+                "Rate limit exceeded: action budget exhausted".into(),
+            ));
+        }
+        let engine = self
+            .engine
+            .get_or_try_init(|| async { WorkflowEngine::from_config(&self.config) })
+            .await?;
+        let workflow = match engine.load(name) {
+            Ok(workflow) => workflow,
+            Err(e) => {
+                let available: Vec<String> = engine.list().into_iter().map(|w| w.name).collect();
+                return Ok(Self::failure(format!(
+                    "{e:#}. Available workflows: {}",
+                    if available.is_empty() {
+                        "none".to_string()
+                    } else {
+                        available.join(", ")
+                    }
+                )));
+            }
+        };
+
+        let run = engine.run(&workflow, input).await;
+        let mut output = run.summary();
+        if run.status == crate::workflows::RunStatus::Paused {
+            let _ = write!(
+                output,
+                "\n\nThe run continues once approved (run id {}).",
+                run.short_id()
+            );
+        }
+        if let Some(result) = run.final_output() {
+            output.push_str("\n\nOutput:\n");
+            output.push_str(result);
+        }
+        Ok(ToolResult {
+            success: matches!(
+                run.status,
+                crate::workflows::RunStatus::Succeeded | crate::workflows::RunStatus::Paused
+            ),
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn unknown_workflows_list_the_available_ones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("workflows");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("standup.toml"),
+            "[[steps]]\nid = \"a\"\nprompt = \"Summarize yesterday\"",
+        )
+        .unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            memory: crate::config::MemoryConfig {
+                backend: "none".into(),
+                ..crate::config::MemoryConfig::default()
+            },
+            ..Config::default()
+        };
+        let tool = RunWorkflowTool::new(&config, Arc::new(SecurityPolicy::default()));
+
+        let result = tool.execute(json!({"name": "deploy"})).await.unwrap();
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(error.contains("Workflow not found: deploy"), "{error}");
+        assert!(error.ends_with("Available workflows: standup"), "{error}");
+    }
+}
@@ -0,0 +1,232 @@
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::bus::{self, Envelope, MAIN_AGENT};
+use crate::config::AgentBusConfig;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Message another agent on the daemon's agent bus
+pub struct SendToAgentTool {
+    security: Arc<SecurityPolicy>,
+    reply_timeout: Duration,
+    max_hops: usize,
+}
+
+impl SendToAgentTool {
+    pub fn new(config: &AgentBusConfig, security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            security,
+            reply_timeout: Duration::from_secs(config.reply_timeout_secs),
+            max_hops: config.max_hops,
+        }
+    }
+
+    fn failure(error: String) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for SendToAgentTool {
+    fn name(&self) -> &str {
+        "send_to_agent"
+    }
+
+    fn description(&self) -> &str {
+        "Send a message or task to another agent running in the daemon and, by default, wait for its answer. Use \"*\" as the agent to tell every other agent. Use when: a task fits another agent's specialty. Don't use when: you can do it yourself."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "agent": {
+                    "type": "string",
+                    "description": "Agent (profile) name, or \"*\" for every other agent"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "What to tell or ask the agent"
+                },
+                "wait": {
+                    "type": "boolean",
+                    "description": "Wait for the agent's answer (default true; ignored for \"*\")"
+                }
+            },
+            "required": ["agent", "message"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let agent = args
+            .get("agent")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'agent' parameter"))?;
+        let message = args
+            .get("message")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'message' parameter"))?;
+        let wait = args
+            .get("wait")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(true);
+
+        let chain = bus::current_chain();
+        let from = chain.last().map_or(MAIN_AGENT, String::as_str).to_string();
+        if agent == from {
+            return Ok(Self::failure("An agent cannot message itself".into()));
+        }
+        if chain.iter().any(|name| name == agent) {
+            return Ok(Self::failure(format!(
+                "Agent {agent} is waiting on your answer; reply to it instead"
+            )));
+        }
+        if chain.len() >= self.max_hops {
+            return Ok(Self::failure(format!(
+                "This message already passed through {} agents (max_hops = {}); answer it yourself",
+                chain.len(),
+                self.max_hops
+            )));
+        }
+        if self.security.dry_run {
+            let action = if agent == "*" {
+                "broadcast a message to every other agent".to_string()
+            } else {
+                format!("send a message to agent {agent}")
+            };
+            return Ok(dry_run::preview(self.name(), &action, &args));
+        }
+        if !self.security.record_action() {
+            return Ok(Self::failure(
+                "Rate limit exceeded: action budget exhausted".into(),
+            ));
+        }
+
+        if agent == "*" {
+            let reached = bus::shared().publish(&from, message, &chain);
+            return Ok(if reached.is_empty() {
+                Self::failure("No other agent is running".into())
+            } else {
+                ToolResult {
+                    success: true,
+                    output: format!("Sent to {}", reached.join(", ")),
+                    error: None,
+                }
+            });
+        }
+
+        let (reply, answer) = if wait {
+            let (tx, rx) = oneshot::channel();
+            (Some(tx), Some(rx))
+        } else {
+            (None, None)
+        };
+        let envelope = Envelope {
+            from,
+            to: agent.to_string(),
+            message: message.to_string(),
+            chain,
+            reply,
+        };
+        if let Err(e) = bus::shared().send(envelope) {
+            return Ok(Self::failure(e.to_string()));
+        }
+        let Some(answer) = answer else {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("Sent to {agent}; it works on it without you waiting"),
+                error: None,
+            });
+        };
+
+        let answer = tokio::time::timeout(self.reply_timeout, answer).await;
+        Ok(match answer {
+            Ok(Ok(Ok(text))) => ToolResult {
+                success: true,
+                output: text,
+                error: None,
+            },
+            Ok(Ok(Err(e))) => Self::failure(format!("Agent {agent} failed: {e}")),
+            Ok(Err(_)) => Self::failure(format!("Agent {agent} stopped before answering")),
+            Err(_) => Self::failure(format!(
+                "Agent {agent} did not answer within {}s",
+                self.reply_timeout.as_secs()
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn waits_for_the_answer_and_refuses_loops() {
+        let mut mailbox = bus::shared().register("send-to-agent-test");
+        tokio::spawn(async move {
+            while let Some(envelope) = mailbox.recv().await {
+                let answer = format!("{} asked: {}", envelope.from, envelope.message);
+                let _ = envelope.reply.unwrap().send(Ok(answer));
+            }
+        });
+        let tool = SendToAgentTool::new(
+            &AgentBusConfig::default(),
+            Arc::new(SecurityPolicy::default()),
+        );
+
+        let result = tool
+            .execute(json!({"agent": "send-to-agent-test", "message": "status?"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "main asked: status?");
+
+        let result = tool
+            .execute(json!({"agent": "nobody", "message": "hi"}))
+            .await
+            .unwrap();
+        assert!(result
+            .error
+            .unwrap()
+            .starts_with("No agent named \"nobody\" is running"));
+
+        // Asking back an agent that waits on this turn would deadlock
+        let chain = vec!["send-to-agent-test".to_string(), "coder".to_string()];
+        let result = bus::scope(
+            chain,
+            tool.execute(json!({"agent": "send-to-agent-test", "message": "hi"})),
+        )
+        .await
+        .unwrap();
+        assert!(result.error.unwrap().contains("waiting on your answer"));
+        bus::shared().unregister("send-to-agent-test");
+    }
+
+    #[tokio::test]
+    async fn dry_run_does_not_send() {
+        let tool = SendToAgentTool::new(
+            &AgentBusConfig::default(),
+            Arc::new(SecurityPolicy {
+                dry_run: true,
+                ..SecurityPolicy::default()
+            }),
+        );
+        let result = tool
+            .execute(json!({"agent": "nobody", "message": "hi"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result
+            .output
+            .starts_with("[dry run] Nothing was changed; send_to_agent would send a message to agent nobody"));
+    }
+}
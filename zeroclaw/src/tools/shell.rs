@@ -1,3 +1,5 @@
+use super::checkpoints;
+use super::dry_run;
 use super::traits::{Tool, ToolResult};
 use crate::runtime::RuntimeAdapter;
 use crate::security::{CommandRiskLevel, SecurityPolicy};
@@ -56,6 +58,7 @@ impl Tool for ShellTool {
         })
     }
 
+    #[allow(clippy::too_many_lines)]
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let command = args
             .get("command")
@@ -86,6 +89,9 @@ impl Tool for ShellTool {
         }
 
         let risk_level = self.security.command_risk_level(command);
+        if self.security.dry_run && risk_level != CommandRiskLevel::Low {
+            return Ok(dry_run::preview(self.name(), &format!("run `{command}`"), &args));
+        }
         let action_allowed = match risk_level {
             CommandRiskLevel::Low => {
                 self.security.record_read_only_action()
@@ -101,6 +107,15 @@ impl Tool for ShellTool {
             });
         }
 
+        // Read-only commands leave the workspace as it is
+        if risk_level != CommandRiskLevel::Low {
+            let label = format!(
+                "shell: {}",
+                crate::util::truncate_with_ellipsis(command, 60)
+            );
+            checkpoints::before_change(&self.security.workspace_dir, label).await;
+        }
+
         // Execute with timeout to prevent hanging commands.
         // Clear the environment to prevent leaking API keys and other secrets
         // (CWE-200), then re-add only safe, functional variables.
@@ -125,6 +140,9 @@ impl Tool for ShellTool {
             }
         }
 
+        // A timed-out or cancelled turn must not leave the command running
+        cmd.kill_on_drop(true);
+
         let result =
             tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output()).await;
 
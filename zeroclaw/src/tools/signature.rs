@@ -0,0 +1,248 @@
+//! Compact, TypeScript-style rendering of tool parameter schemas.
+//!
+//! A full JSON Schema spends most of its bytes on structure (`"type"`,
+//! `"properties"`, quoting). Models that read tools from the system prompt
+//! understand signatures just as well, e.g.
+//!
+//! ```text
+//! shell(command: string /* Shell command to run */, approved?: boolean)
+//! ```
+
+use crate::util::truncate_with_ellipsis;
+use serde_json::Value;
+
+/// Longest inline parameter note; the tool description carries the detail.
+const MAX_NOTE_CHARS: usize = 48;
+
+/// Render `name(params...)` from a JSON Schema object. Required parameters
+/// come first in declaration order; optional ones are suffixed with `?`.
+/// Top-level parameter descriptions are kept as short inline comments.
+pub fn render_signature(name: &str, schema: &Value) -> String {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return format!("{name}()");
+    };
+    let required = required_names(schema);
+
+    let mut ordered: Vec<&String> = required
+        .iter()
+        .filter_map(|r| properties.get_key_value(*r).map(|(k, _)| k))
+        .collect();
+    ordered.extend(
+        properties
+            .keys()
+            .filter(|k| !required.contains(&k.as_str())),
+    );
+
+    let params: Vec<String> = ordered
+        .into_iter()
+        .map(|key| {
+            let prop = &properties[key];
+            let optional = if required.contains(&key.as_str()) {
+                ""
+            } else {
+                "?"
+            };
+            let mut param = format!("{key}{optional}: {}", ts_type(prop));
+            if let Some(note) = prop
+                .get("description")
+                .and_then(Value::as_str)
+                .map(|d| first_sentence(d, MAX_NOTE_CHARS))
+                .filter(|d| !d.is_empty())
+            {
+                param.push_str(" /* ");
+                param.push_str(&note.replace("*/", "* /"));
+                param.push_str(" */");
+            }
+            param
+        })
+        .collect();
+
+    format!("{name}({})", params.join(", "))
+}
+
+/// First sentence of `description`, capped at `max_chars`.
+pub fn first_sentence(description: &str, max_chars: usize) -> String {
+    let description = description.trim();
+    // A sentence ends at ". " followed by a capital (not "e.g. 'x'")
+    let first = description
+        .match_indices(". ")
+        .find(|(i, _)| {
+            description[i + 2..]
+                .chars()
+                .next()
+                .is_some_and(char::is_uppercase)
+        })
+        .map_or(description, |(end, _)| &description[..end]);
+    truncate_with_ellipsis(first.trim_end_matches('.'), max_chars)
+}
+
+fn required_names(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Map a JSON Schema fragment to a TypeScript type expression.
+fn ts_type(schema: &Value) -> String {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        if !values.is_empty() {
+            return values
+                .iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>()
+                .join(" | ");
+        }
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+            return union(variants.iter().map(ts_type));
+        }
+    }
+
+    match schema.get("type") {
+        Some(Value::String(t)) => named_type(t, schema),
+        Some(Value::Array(types)) => union(
+            types
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|t| named_type(t, schema)),
+        ),
+        _ => "any".into(),
+    }
+}
+
+fn named_type(name: &str, schema: &Value) -> String {
+    match name {
+        "string" | "boolean" | "null" => name.into(),
+        "integer" | "number" => "number".into(),
+        "array" => {
+            let item = schema.get("items").map_or_else(|| "any".into(), ts_type);
+            if item.contains(' ') && !item.starts_with('{') {
+                format!("({item})[]")
+            } else {
+                format!("{item}[]")
+            }
+        }
+        "object" => object_type(schema),
+        _ => "any".into(),
+    }
+}
+
+/// Inline object type; nested descriptions are dropped to keep it short.
+fn object_type(schema: &Value) -> String {
+    let Some(properties) = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .filter(|p| !p.is_empty())
+    else {
+        return "object".into();
+    };
+    let required = required_names(schema);
+    let fields: Vec<String> = properties
+        .iter()
+        .map(|(key, prop)| {
+            let optional = if required.contains(&key.as_str()) {
+                ""
+            } else {
+                "?"
+            };
+            format!("{key}{optional}: {}", ts_type(prop))
+        })
+        .collect();
+    format!("{{ {} }}", fields.join("; "))
+}
+
+fn union(types: impl Iterator<Item = String>) -> String {
+    let mut seen: Vec<String> = Vec::new();
+    for t in types {
+        if !seen.contains(&t) {
+            seen.push(t);
+        }
+    }
+    if seen.is_empty() {
+        "any".into()
+    } else {
+        seen.join(" | ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn required_params_first_and_optional_marked() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "approved": {"type": "boolean"},
+                "command": {"type": "string", "description": "Shell command to run"}
+            },
+            "required": ["command"]
+        });
+        assert_eq!(
+            render_signature("shell", &schema),
+            "shell(command: string /* Shell command to run */, approved?: boolean)"
+        );
+    }
+
+    #[test]
+    fn maps_json_schema_types() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "action": {"type": "string", "enum": ["get", "set"]},
+                "limit": {"type": "integer"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "modes": {"type": "array", "items": {"enum": ["a", "b"]}},
+                "point": {
+                    "type": "object",
+                    "properties": {"x": {"type": "number"}, "y": {"type": "number"}},
+                    "required": ["x"]
+                },
+                "value": {"type": ["string", "null"]},
+                "raw": {}
+            },
+            "required": ["action"]
+        });
+        let sig = render_signature("t", &schema);
+        assert!(sig.starts_with("t(action: \"get\" | \"set\", "));
+        assert!(sig.contains("limit?: number"));
+        assert!(sig.contains("tags?: string[]"));
+        assert!(sig.contains("modes?: (\"a\" | \"b\")[]"));
+        assert!(sig.contains("point?: { x: number; y?: number }"));
+        assert!(sig.contains("value?: string | null"));
+        assert!(sig.contains("raw?: any"));
+    }
+
+    #[test]
+    fn first_sentence_stops_at_sentence_end() {
+        assert_eq!(
+            first_sentence("Line number (1-based). For insert: after this line.", 80),
+            "Line number (1-based)"
+        );
+        assert_eq!(
+            first_sentence("Expression, e.g. 'round(x, 2)'. More.", 80),
+            "Expression, e.g. 'round(x, 2)'"
+        );
+        assert_eq!(first_sentence("Remove a memory by key", 8), "Remove a...");
+    }
+
+    #[test]
+    fn handles_missing_properties_and_comment_terminators() {
+        assert_eq!(
+            render_signature("noop", &json!({"type": "object"})),
+            "noop()"
+        );
+        let schema = json!({
+            "properties": {"glob": {"type": "string", "description": "e.g. src/**/*.rs"}}
+        });
+        assert_eq!(
+            render_signature("find", &schema),
+            "find(glob?: string /* e.g. src/** /*.rs */)"
+        );
+    }
+}
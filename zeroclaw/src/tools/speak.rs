@@ -0,0 +1,178 @@
+use super::dry_run;
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use crate::tts::Synthesizer;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Workspace-relative directory for synthesized speech.
+const AUDIO_DIR: &str = "audio";
+
+/// Turn text into a voice note. The returned `[VOICE:<path>]` marker tells
+/// channels to deliver the clip as a voice message.
+pub struct SpeakTool {
+    security: Arc<SecurityPolicy>,
+    synthesizer: Arc<Synthesizer>,
+}
+
+impl SpeakTool {
+    pub fn new(security: Arc<SecurityPolicy>, synthesizer: Arc<Synthesizer>) -> Self {
+        Self {
+            security,
+            synthesizer,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for SpeakTool {
+    fn name(&self) -> &str {
+        "speak"
+    }
+
+    fn description(&self) -> &str {
+        "Convert text to speech and save it as a voice note (Ogg/Opus); include the returned [VOICE:...] marker in your reply to send it"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "What to say"
+                },
+                "voice": {
+                    "type": "string",
+                    "description": "Optional voice name overriding the configured default"
+                }
+            },
+            "required": ["text"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let text = args
+            .get("text")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'text' parameter"))?;
+        let voice = args.get("voice").and_then(|v| v.as_str());
+
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+        if self.security.dry_run {
+            let action = format!("synthesize speech and save it under {AUDIO_DIR}/");
+            return Ok(dry_run::preview(self.name(), &action, &args));
+        }
+
+        let clip = match self.synthesizer.synthesize(text, voice).await {
+            Ok(clip) => clip,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Speech synthesis failed: {e}")),
+                });
+            }
+        };
+
+        let relative = format!(
+            "{AUDIO_DIR}/speech_{}.ogg",
+            chrono::Local::now().format("%Y%m%d_%H%M%S_%3f")
+        );
+        let full_path = self.security.workspace_dir.join(&relative);
+        if let Some(parent) = full_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&full_path, &clip.bytes).await?;
+
+        Ok(ToolResult {
+            success: true,
+            output: format!(
+                "Saved {} bytes of speech to {relative}\n[VOICE:{relative}]",
+                clip.bytes.len()
+            ),
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TtsConfig;
+    use crate::security::AutonomyLevel;
+
+    fn test_tool(autonomy: AutonomyLevel) -> SpeakTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: std::env::temp_dir(),
+            ..SecurityPolicy::default()
+        });
+        let cfg = TtsConfig {
+            enabled: true,
+            api_key: Some("sk-test".into()),
+            max_chars: 20,
+            ..TtsConfig::default()
+        };
+        SpeakTool::new(
+            security,
+            Arc::new(Synthesizer::from_config(&cfg, None).unwrap()),
+        )
+    }
+
+    #[test]
+    fn speak_name_and_schema() {
+        let tool = test_tool(AutonomyLevel::Supervised);
+        assert_eq!(tool.name(), "speak");
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["text"].is_object());
+        assert_eq!(schema["required"], json!(["text"]));
+    }
+
+    #[tokio::test]
+    async fn speak_blocked_in_read_only_mode() {
+        let tool = test_tool(AutonomyLevel::ReadOnly);
+        let result = tool.execute(json!({"text": "hello"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn speak_reports_text_over_limit() {
+        let tool = test_tool(AutonomyLevel::Supervised);
+        let result = tool
+            .execute(json!({"text": "this sentence is longer than twenty characters"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("too long"));
+    }
+
+    #[tokio::test]
+    async fn speak_dry_run_only_previews() {
+        let tool = SpeakTool {
+            security: Arc::new(SecurityPolicy {
+                dry_run: true,
+                workspace_dir: std::env::temp_dir(),
+                ..SecurityPolicy::default()
+            }),
+            ..test_tool(AutonomyLevel::Supervised)
+        };
+        let result = tool.execute(json!({"text": "hello"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.starts_with("[dry run] Nothing was changed; speak would"));
+    }
+
+    #[tokio::test]
+    async fn speak_missing_text_param() {
+        let tool = test_tool(AutonomyLevel::Supervised);
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::{PathAccess, SecurityPolicy};
+use crate::transcription::Transcriber;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Transcribe an audio file in the workspace to text
+pub struct TranscribeTool {
+    security: Arc<SecurityPolicy>,
+    transcriber: Arc<Transcriber>,
+}
+
+impl TranscribeTool {
+    pub fn new(security: Arc<SecurityPolicy>, transcriber: Arc<Transcriber>) -> Self {
+        Self {
+            security,
+            transcriber,
+        }
+    }
+
+    fn failure(error: String) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for TranscribeTool {
+    fn name(&self) -> &str {
+        "transcribe"
+    }
+
+    fn description(&self) -> &str {
+        "Transcribe speech in an audio file (ogg, mp3, wav, m4a, webm) to text"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Relative path to the audio file within the workspace"
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Optional ISO-639-1 language hint (e.g. 'en'); overrides the configured default"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        let language = args.get("language").and_then(|v| v.as_str());
+
+        if !self.security.is_path_allowed_for(path, PathAccess::Read) {
+            return Ok(Self::failure(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+
+        let full_path = self.security.workspace_dir.join(path);
+        let resolved_path = match tokio::fs::canonicalize(&full_path).await {
+            Ok(p) => p,
+            Err(e) => return Ok(Self::failure(format!("Failed to resolve file path: {e}"))),
+        };
+
+        if !self
+            .security
+            .is_resolved_path_allowed_for(&resolved_path, PathAccess::Read)
+        {
+            return Ok(Self::failure(format!(
+                "Resolved path escapes workspace: {}",
+                resolved_path.display()
+            )));
+        }
+
+        match self
+            .transcriber
+            .transcribe_file(&resolved_path, language)
+            .await
+        {
+            Ok(text) if text.is_empty() => Ok(Self::failure("No speech detected".into())),
+            Ok(text) => Ok(ToolResult {
+                success: true,
+                output: text,
+                error: None,
+            }),
+            Err(e) => Ok(Self::failure(format!("Transcription failed: {e}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TranscriptionConfig;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+
+    fn test_tool(workspace: std::path::PathBuf) -> TranscribeTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        });
+        let cfg = TranscriptionConfig {
+            enabled: true,
+            api_key: Some("sk-test".into()),
+            ..TranscriptionConfig::default()
+        };
+        let transcriber = Transcriber::from_config(&cfg, None).unwrap();
+        TranscribeTool::new(security, Arc::new(transcriber))
+    }
+
+    #[test]
+    fn transcribe_name_and_schema() {
+        let tool = test_tool(std::env::temp_dir());
+        assert_eq!(tool.name(), "transcribe");
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["path"].is_object());
+        assert!(schema["properties"]["language"].is_object());
+        assert_eq!(schema["required"], json!(["path"]));
+    }
+
+    #[tokio::test]
+    async fn transcribe_blocks_path_traversal() {
+        let tool = test_tool(std::env::temp_dir());
+        let result = tool
+            .execute(json!({"path": "../../etc/passwd"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn transcribe_missing_file() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_transcribe_missing");
+        let _ = tokio::fs::create_dir_all(&dir).await;
+        let tool = test_tool(dir.clone());
+        let result = tool.execute(json!({"path": "nope.ogg"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Failed to resolve"));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn transcribe_empty_file_reports_error() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_transcribe_empty");
+        let _ = tokio::fs::create_dir_all(&dir).await;
+        tokio::fs::write(dir.join("silence.ogg"), b"")
+            .await
+            .unwrap();
+        let tool = test_tool(dir.clone());
+        let result = tool.execute(json!({"path": "silence.ogg"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("empty"));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn transcribe_missing_path_param() {
+        let tool = test_tool(std::env::temp_dir());
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
@@ -0,0 +1,492 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::WebSearchConfig;
+use crate::util::truncate_with_ellipsis;
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::time::Duration;
+
+const BRAVE_API_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+const DUCKDUCKGO_HTML_URL: &str = "https://html.duckduckgo.com/html/";
+const USER_AGENT: &str = "Mozilla/5.0 (compatible; ZeroClaw web_search)";
+const MAX_SNIPPET_CHARS: usize = 300;
+
+/// A single search hit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Engine {
+    DuckDuckGo,
+    Brave { api_key: String },
+    Searxng { base_url: String },
+}
+
+impl Engine {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::DuckDuckGo => "duckduckgo",
+            Self::Brave { .. } => "brave",
+            Self::Searxng { .. } => "searxng",
+        }
+    }
+}
+
+/// Search the web through a configurable engine and return titles, URLs and snippets.
+pub struct WebSearchTool {
+    engine: Engine,
+    max_results: usize,
+    client: Client,
+}
+
+impl WebSearchTool {
+    /// Build from `[web_search]`. Returns `None` when disabled or when the
+    /// selected engine is missing its key/URL.
+    pub fn from_config(config: &WebSearchConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let engine = match config.engine.trim().to_ascii_lowercase().as_str() {
+            "duckduckgo" | "ddg" => Engine::DuckDuckGo,
+            "brave" => {
+                let api_key = config
+                    .brave_api_key
+                    .clone()
+                    .or_else(|| std::env::var("BRAVE_API_KEY").ok())
+                    .filter(|k| !k.trim().is_empty());
+                let Some(api_key) = api_key else {
+                    tracing::warn!("web_search: brave engine has no API key configured");
+                    return None;
+                };
+                Engine::Brave { api_key }
+            }
+            "searxng" => {
+                let Some(base_url) = config
+                    .searxng_url
+                    .as_deref()
+                    .map(|u| u.trim().trim_end_matches('/'))
+                    .filter(|u| u.starts_with("http://") || u.starts_with("https://"))
+                else {
+                    tracing::warn!("web_search: searxng engine needs an http(s) `searxng_url`");
+                    return None;
+                };
+                Engine::Searxng {
+                    base_url: base_url.to_string(),
+                }
+            }
+            other => {
+                tracing::warn!("web_search: unknown engine '{other}'");
+                return None;
+            }
+        };
+
+        Some(Self {
+            engine,
+            max_results: config.max_results.max(1),
+            client: Client::builder()
+                .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+                .connect_timeout(Duration::from_secs(10))
+                .user_agent(USER_AGENT)
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+        })
+    }
+
+    /// Run `query` against the configured engine, returning at most `limit` results.
+    pub async fn search(&self, query: &str, limit: usize) -> anyhow::Result<Vec<SearchResult>> {
+        let mut results = match &self.engine {
+            Engine::DuckDuckGo => {
+                let resp = self
+                    .client
+                    .get(DUCKDUCKGO_HTML_URL)
+                    .query(&[("q", query)])
+                    .send()
+                    .await?;
+                if !resp.status().is_success() {
+                    anyhow::bail!("DuckDuckGo returned {}", resp.status());
+                }
+                parse_duckduckgo_html(&resp.text().await?)
+            }
+            Engine::Brave { api_key } => {
+                let resp = self
+                    .client
+                    .get(BRAVE_API_URL)
+                    .header("X-Subscription-Token", api_key)
+                    .header("Accept", "application/json")
+                    .query(&[("q", query), ("count", &limit.min(20).to_string())])
+                    .send()
+                    .await?;
+                if !resp.status().is_success() {
+                    let status = resp.status();
+                    let err = resp.text().await.unwrap_or_default();
+                    anyhow::bail!("Brave Search API error ({status}): {err}");
+                }
+                parse_brave(&resp.json().await?)
+            }
+            Engine::Searxng { base_url } => {
+                let resp = self
+                    .client
+                    .get(format!("{base_url}/search"))
+                    .query(&[("q", query), ("format", "json")])
+                    .send()
+                    .await?;
+                if !resp.status().is_success() {
+                    anyhow::bail!(
+                        "SearXNG returned {} (is the json format enabled in settings.yml?)",
+                        resp.status()
+                    );
+                }
+                parse_searxng(&resp.json().await?)
+            }
+        };
+
+        results.truncate(limit);
+        Ok(results)
+    }
+}
+
+#[async_trait]
+impl Tool for WebSearchTool {
+    fn name(&self) -> &str {
+        "web_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the web for current information. Returns titles, URLs and snippets of the top results."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Search query"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Number of results to return (capped by configuration)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+        if query.is_empty() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Query cannot be empty".into()),
+            });
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("max_results")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(self.max_results, |n| n as usize)
+            .clamp(1, self.max_results);
+
+        match self.search(query, limit).await {
+            Ok(results) => Ok(ToolResult {
+                success: true,
+                output: format_results(query, self.engine.name(), &results),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Web search failed: {e}")),
+            }),
+        }
+    }
+}
+
+fn format_results(query: &str, engine: &str, results: &[SearchResult]) -> String {
+    if results.is_empty() {
+        return format!("No results for \"{query}\" (via {engine}).");
+    }
+    let mut out = format!("Results for \"{query}\" (via {engine}):\n");
+    for (i, r) in results.iter().enumerate() {
+        let _ = write!(out, "\n{}. {}\n   {}\n", i + 1, r.title, r.url);
+        if !r.snippet.is_empty() {
+            let _ = writeln!(out, "   {}", r.snippet);
+        }
+    }
+    out
+}
+
+fn result_from(title: &str, url: &str, snippet: &str) -> Option<SearchResult> {
+    let title = collapse_whitespace(title);
+    let url = url.trim();
+    if title.is_empty() || !(url.starts_with("http://") || url.starts_with("https://")) {
+        return None;
+    }
+    Some(SearchResult {
+        title,
+        url: url.to_string(),
+        snippet: truncate_with_ellipsis(&collapse_whitespace(snippet), MAX_SNIPPET_CHARS),
+    })
+}
+
+fn parse_brave(body: &Value) -> Vec<SearchResult> {
+    body["web"]["results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|r| {
+            result_from(
+                &strip_tags(r["title"].as_str().unwrap_or_default()),
+                r["url"].as_str().unwrap_or_default(),
+                &strip_tags(r["description"].as_str().unwrap_or_default()),
+            )
+        })
+        .collect()
+}
+
+fn parse_searxng(body: &Value) -> Vec<SearchResult> {
+    body["results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|r| {
+            result_from(
+                r["title"].as_str().unwrap_or_default(),
+                r["url"].as_str().unwrap_or_default(),
+                r["content"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+/// Extract results from the `DuckDuckGo` HTML endpoint (`html.duckduckgo.com`).
+fn parse_duckduckgo_html(html: &str) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    let mut rest = html;
+
+    while let Some((tag, title, after)) = next_anchor(rest, "result__a") {
+        // The snippet belongs to this result only if it appears before the next title
+        let next_title = after.find("class=\"result__a\"").unwrap_or(after.len());
+        let snippet = next_anchor(&after[..next_title], "result__snippet")
+            .map(|(_, text, _)| text)
+            .unwrap_or_default();
+
+        let url = attr(tag, "href")
+            .map(|href| decode_duckduckgo_href(&decode_entities(href)))
+            .unwrap_or_default();
+        if !url.contains("duckduckgo.com/y.js") {
+            if let Some(r) = result_from(&strip_tags(title), &url, &strip_tags(snippet)) {
+                results.push(r);
+            }
+        }
+        rest = after;
+    }
+    results
+}
+
+/// Find the next `<a>` (or other element) carrying `class`, returning its
+/// opening tag, inner HTML and the remainder of the document.
+fn next_anchor<'a>(html: &'a str, class: &str) -> Option<(&'a str, &'a str, &'a str)> {
+    let marker = format!("class=\"{class}\"");
+    let pos = html.find(&marker)?;
+    let tag_start = html[..pos].rfind('<')?;
+    let tag_end = pos + html[pos..].find('>')?;
+    let inner_start = tag_end + 1;
+    let close = html[inner_start..]
+        .find("</a>")
+        .map_or(html.len(), |i| inner_start + i);
+    Some((
+        &html[tag_start..tag_end],
+        &html[inner_start..close],
+        &html[close..],
+    ))
+}
+
+fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let marker = format!("{name}=\"");
+    let start = tag.find(&marker)? + marker.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+/// `DuckDuckGo` wraps result links as `//duckduckgo.com/l/?uddg=<encoded target>`.
+fn decode_duckduckgo_href(href: &str) -> String {
+    let absolute = if href.starts_with("//") {
+        format!("https:{href}")
+    } else {
+        href.to_string()
+    };
+    Url::parse(&absolute)
+        .ok()
+        .filter(|u| u.path() == "/l/")
+        .and_then(|u| {
+            u.query_pairs()
+                .find(|(k, _)| k == "uddg")
+                .map(|(_, v)| v.into_owned())
+        })
+        .unwrap_or(absolute)
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(&out)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(engine: &str) -> WebSearchConfig {
+        WebSearchConfig {
+            enabled: true,
+            engine: engine.into(),
+            ..WebSearchConfig::default()
+        }
+    }
+
+    #[test]
+    fn from_config_respects_enabled_and_engine_requirements() {
+        assert!(WebSearchTool::from_config(&WebSearchConfig::default()).is_none());
+        assert!(WebSearchTool::from_config(&enabled("bing")).is_none());
+        assert!(WebSearchTool::from_config(&enabled("searxng")).is_none());
+
+        let tool = WebSearchTool::from_config(&enabled("duckduckgo")).unwrap();
+        assert_eq!(tool.engine, Engine::DuckDuckGo);
+
+        let cfg = WebSearchConfig {
+            searxng_url: Some("https://search.example.org/".into()),
+            max_results: 0,
+            ..enabled("searxng")
+        };
+        let tool = WebSearchTool::from_config(&cfg).unwrap();
+        assert_eq!(
+            tool.engine,
+            Engine::Searxng {
+                base_url: "https://search.example.org".into()
+            }
+        );
+        assert_eq!(tool.max_results, 1);
+
+        let cfg = WebSearchConfig {
+            brave_api_key: Some("bk-test".into()),
+            ..enabled("brave")
+        };
+        assert!(matches!(
+            WebSearchTool::from_config(&cfg).unwrap().engine,
+            Engine::Brave { .. }
+        ));
+    }
+
+    #[test]
+    fn parse_brave_results() {
+        let body = json!({"web": {"results": [
+            {"title": "Rust <strong>1.80</strong>", "url": "https://blog.rust-lang.org/", "description": "Release &amp; notes"},
+            {"title": "no url", "url": ""}
+        ]}});
+        let results = parse_brave(&body);
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                title: "Rust 1.80".into(),
+                url: "https://blog.rust-lang.org/".into(),
+                snippet: "Release & notes".into(),
+            }]
+        );
+        assert!(parse_brave(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn parse_searxng_results() {
+        let body = json!({"results": [
+            {"title": "Example", "url": "https://example.com", "content": "  An\n example  "}
+        ]});
+        let results = parse_searxng(&body);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].snippet, "An example");
+    }
+
+    #[test]
+    fn parse_duckduckgo_html_decodes_redirects_and_skips_ads() {
+        let html = r#"
+            <div class="result results_links result--ad">
+              <a rel="nofollow" class="result__a" href="https://duckduckgo.com/y.js?ad=1">Ad</a>
+              <a class="result__snippet" href="x">Buy now</a>
+            </div>
+            <div class="result results_links">
+              <h2 class="result__title">
+                <a rel="nofollow" class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fwww.rust-lang.org%2F&amp;rut=abc">The <b>Rust</b> Programming Language</a>
+              </h2>
+              <a class="result__snippet" href="x">A language empowering <b>everyone</b> &amp; more.</a>
+            </div>
+            <div class="result results_links">
+              <a rel="nofollow" class="result__a" href="https://docs.rs/">Docs.rs</a>
+            </div>
+        "#;
+        let results = parse_duckduckgo_html(html);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "The Rust Programming Language");
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].snippet, "A language empowering everyone & more.");
+        assert_eq!(results[1].url, "https://docs.rs/");
+        assert!(results[1].snippet.is_empty());
+    }
+
+    #[test]
+    fn format_results_numbers_entries() {
+        let results = vec![SearchResult {
+            title: "Example".into(),
+            url: "https://example.com".into(),
+            snippet: "Snippet".into(),
+        }];
+        let out = format_results("q", "brave", &results);
+        assert!(out.starts_with("Results for \"q\" (via brave):"));
+        assert!(out.contains("1. Example\n   https://example.com\n   Snippet"));
+        assert_eq!(
+            format_results("q", "brave", &[]),
+            "No results for \"q\" (via brave)."
+        );
+    }
+
+    #[tokio::test]
+    async fn execute_rejects_empty_query() {
+        let tool = WebSearchTool::from_config(&enabled("duckduckgo")).unwrap();
+        assert!(tool.execute(json!({})).await.is_err());
+        let result = tool.execute(json!({"query": "  "})).await.unwrap();
+        assert!(!result.success);
+    }
+}
@@ -0,0 +1,420 @@
+//! Speech-to-text for the `transcribe` tool and channel voice messages.
+//!
+//! Two backends are supported:
+//! - `api` — any OpenAI-compatible `/audio/transcriptions` endpoint (multipart upload)
+//! - `whisper_cpp` — a local whisper.cpp CLI; input is converted to 16 kHz mono WAV with `ffmpeg`
+
+use crate::config::TranscriptionConfig;
+use anyhow::{bail, Context, Result};
+use reqwest::multipart::{Form, Part};
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Upload limit for the hosted transcription API (the `OpenAI` API caps uploads at 25 MB).
+pub const MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
+
+const API_TIMEOUT_SECS: u64 = 120;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Backend {
+    Api {
+        url: String,
+        api_key: String,
+        model: String,
+    },
+    WhisperCpp {
+        bin: String,
+        model: String,
+    },
+}
+
+/// Configured speech-to-text engine.
+#[derive(Debug, Clone)]
+pub struct Transcriber {
+    backend: Backend,
+    language: Option<String>,
+    max_duration_secs: u64,
+    client: reqwest::Client,
+}
+
+impl Transcriber {
+    /// Build a transcriber from config. Returns `None` when transcription is
+    /// disabled or the selected backend is missing its credentials/model.
+    ///
+    /// For the `api` backend the key is resolved from `transcription.api_key`,
+    /// then `OPENAI_API_KEY`, then `fallback_api_key` (usually the main provider key).
+    pub fn from_config(
+        config: &TranscriptionConfig,
+        fallback_api_key: Option<&str>,
+    ) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let backend = match config.backend.as_str() {
+            "whisper_cpp" | "whisper.cpp" | "local" => {
+                let Some(model) = config.whisper_cpp_model.clone().filter(|m| !m.is_empty()) else {
+                    tracing::warn!("transcription: whisper_cpp backend needs `whisper_cpp_model`");
+                    return None;
+                };
+                Backend::WhisperCpp {
+                    bin: config.whisper_cpp_bin.clone(),
+                    model,
+                }
+            }
+            "api" => {
+                let api_key = config
+                    .api_key
+                    .clone()
+                    .or_else(|| std::env::var("OPENAI_API_KEY").ok())
+                    .or_else(|| fallback_api_key.map(str::to_string))
+                    .filter(|k| !k.is_empty());
+                let Some(api_key) = api_key else {
+                    tracing::warn!("transcription: api backend has no API key configured");
+                    return None;
+                };
+                Backend::Api {
+                    url: config.api_url.clone(),
+                    api_key,
+                    model: config.model.clone(),
+                }
+            }
+            other => {
+                tracing::warn!("transcription: unknown backend '{other}'");
+                return None;
+            }
+        };
+
+        Some(Self {
+            backend,
+            language: config.language.clone().filter(|l| !l.is_empty()),
+            max_duration_secs: config.max_duration_secs,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Build a transcriber from the full app config. The main provider key is
+    /// only reused when the default provider is `OpenAI` itself.
+    pub fn from_app_config(config: &crate::config::Config) -> Option<Self> {
+        let fallback_key = config
+            .api_key
+            .as_deref()
+            .filter(|_| config.default_provider.as_deref() == Some("openai"));
+        Self::from_config(&config.transcription, fallback_key)
+    }
+
+    pub fn max_duration_secs(&self) -> u64 {
+        self.max_duration_secs
+    }
+
+    /// Fail early when a recording's known duration exceeds the configured limit.
+    pub fn check_duration(&self, duration_secs: u64) -> Result<()> {
+        if self.max_duration_secs > 0 && duration_secs > self.max_duration_secs {
+            bail!(
+                "Audio is {duration_secs}s long (limit: {}s)",
+                self.max_duration_secs
+            );
+        }
+        Ok(())
+    }
+
+    /// Transcribe an audio file on disk.
+    pub async fn transcribe_file(&self, path: &Path, language: Option<&str>) -> Result<String> {
+        if let Some(secs) = probe_duration_secs(path).await {
+            self.check_duration(secs)?;
+        }
+        let bytes = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read audio file {}", path.display()))?;
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("audio.ogg");
+        self.transcribe_bytes(bytes, file_name, language).await
+    }
+
+    /// Transcribe in-memory audio. `file_name` carries the container format
+    /// (e.g. `voice.ogg`) so the backend can decode it.
+    pub async fn transcribe_bytes(
+        &self,
+        bytes: Vec<u8>,
+        file_name: &str,
+        language: Option<&str>,
+    ) -> Result<String> {
+        check_size(bytes.len())?;
+        let language = language
+            .filter(|l| !l.is_empty())
+            .or(self.language.as_deref());
+
+        let text = match &self.backend {
+            Backend::Api {
+                url,
+                api_key,
+                model,
+            } => {
+                let mut form = Form::new()
+                    .text("model", model.clone())
+                    .text("response_format", "json")
+                    .part("file", Part::bytes(bytes).file_name(file_name.to_string()));
+                if let Some(lang) = language {
+                    form = form.text("language", lang.to_string());
+                }
+
+                let resp = self
+                    .client
+                    .post(url)
+                    .bearer_auth(api_key)
+                    .multipart(form)
+                    .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+                    .send()
+                    .await
+                    .context("Transcription request failed")?;
+                if !resp.status().is_success() {
+                    let status = resp.status();
+                    let err = resp.text().await.unwrap_or_default();
+                    bail!("Transcription API error ({status}): {err}");
+                }
+                let body: serde_json::Value = resp.json().await?;
+                body.get("text")
+                    .and_then(serde_json::Value::as_str)
+                    .map(str::to_string)
+                    .context("Transcription API response has no `text` field")?
+            }
+            Backend::WhisperCpp { bin, model } => {
+                self.transcribe_local(bin, model, &bytes, file_name, language)
+                    .await?
+            }
+        };
+
+        Ok(text.trim().to_string())
+    }
+
+    async fn transcribe_local(
+        &self,
+        bin: &str,
+        model: &str,
+        bytes: &[u8],
+        file_name: &str,
+        language: Option<&str>,
+    ) -> Result<String> {
+        let tmp = std::env::temp_dir().join(format!("zeroclaw-stt-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&tmp).await?;
+        let result = self
+            .run_whisper_cpp(&tmp, bin, model, bytes, file_name, language)
+            .await;
+        let _ = tokio::fs::remove_dir_all(&tmp).await;
+        result
+    }
+
+    async fn run_whisper_cpp(
+        &self,
+        tmp: &Path,
+        bin: &str,
+        model: &str,
+        bytes: &[u8],
+        file_name: &str,
+        language: Option<&str>,
+    ) -> Result<String> {
+        let ext = Path::new(file_name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("ogg");
+        let input = tmp.join(format!("input.{ext}"));
+        let wav = tmp.join("input.wav");
+        tokio::fs::write(&input, bytes).await?;
+
+        let convert = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error", "-i"])
+            .arg(&input)
+            .args(["-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+            .arg(&wav)
+            .output()
+            .await
+            .context("Failed to run ffmpeg (required by the whisper_cpp backend)")?;
+        if !convert.status.success() {
+            bail!(
+                "ffmpeg failed to convert audio: {}",
+                String::from_utf8_lossy(&convert.stderr).trim()
+            );
+        }
+
+        if let Some(secs) = probe_duration_secs(&wav).await {
+            self.check_duration(secs)?;
+        }
+
+        let mut cmd = Command::new(bin);
+        cmd.arg("-m")
+            .arg(model)
+            .arg("-f")
+            .arg(&wav)
+            .args(["-nt", "-np"]);
+        if let Some(lang) = language {
+            cmd.args(["-l", lang]);
+        }
+        let output = cmd
+            .output()
+            .await
+            .with_context(|| format!("Failed to run whisper.cpp binary '{bin}'"))?;
+        if !output.status.success() {
+            bail!(
+                "whisper.cpp failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "))
+    }
+}
+
+fn check_size(len: usize) -> Result<()> {
+    if len == 0 {
+        bail!("Audio is empty");
+    }
+    if len > MAX_AUDIO_BYTES {
+        bail!("Audio too large: {len} bytes (limit: {MAX_AUDIO_BYTES} bytes)");
+    }
+    Ok(())
+}
+
+/// Best-effort duration lookup via `ffprobe`; `None` when ffprobe is missing
+/// or cannot parse the file.
+async fn probe_duration_secs(path: &Path) -> Option<u64> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "format=duration",
+            "-of",
+            "default=noprint_wrappers=1:nokey=1",
+        ])
+        .arg(path)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_duration(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_duration(raw: &str) -> Option<u64> {
+    let secs: f64 = raw.trim().parse().ok()?;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    (secs.is_finite() && secs >= 0.0).then(|| secs.ceil() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_config() -> TranscriptionConfig {
+        TranscriptionConfig {
+            enabled: true,
+            api_key: Some("sk-test".into()),
+            ..TranscriptionConfig::default()
+        }
+    }
+
+    #[test]
+    fn config_defaults() {
+        let cfg = TranscriptionConfig::default();
+        assert!(!cfg.enabled);
+        assert_eq!(cfg.backend, "api");
+        assert_eq!(cfg.model, "whisper-1");
+        assert_eq!(cfg.max_duration_secs, 300);
+        assert!(cfg.language.is_none());
+    }
+
+    #[test]
+    fn disabled_config_yields_none() {
+        assert!(Transcriber::from_config(&TranscriptionConfig::default(), Some("k")).is_none());
+    }
+
+    #[test]
+    fn api_backend_uses_configured_key() {
+        let t = Transcriber::from_config(&api_config(), Some("fallback")).unwrap();
+        assert_eq!(
+            t.backend,
+            Backend::Api {
+                url: "https://api.openai.com/v1/audio/transcriptions".into(),
+                api_key: "sk-test".into(),
+                model: "whisper-1".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn whisper_cpp_requires_model() {
+        let mut cfg = TranscriptionConfig {
+            enabled: true,
+            backend: "whisper_cpp".into(),
+            ..TranscriptionConfig::default()
+        };
+        assert!(Transcriber::from_config(&cfg, None).is_none());
+
+        cfg.whisper_cpp_model = Some("/models/ggml-base.bin".into());
+        let t = Transcriber::from_config(&cfg, None).unwrap();
+        assert!(matches!(t.backend, Backend::WhisperCpp { .. }));
+    }
+
+    #[test]
+    fn unknown_backend_yields_none() {
+        let cfg = TranscriptionConfig {
+            backend: "carrier-pigeon".into(),
+            ..api_config()
+        };
+        assert!(Transcriber::from_config(&cfg, None).is_none());
+    }
+
+    #[test]
+    fn duration_limit_enforced() {
+        let cfg = TranscriptionConfig {
+            max_duration_secs: 60,
+            ..api_config()
+        };
+        let t = Transcriber::from_config(&cfg, None).unwrap();
+        assert!(t.check_duration(60).is_ok());
+        assert!(t.check_duration(61).is_err());
+    }
+
+    #[test]
+    fn zero_duration_limit_means_unlimited() {
+        let cfg = TranscriptionConfig {
+            max_duration_secs: 0,
+            ..api_config()
+        };
+        let t = Transcriber::from_config(&cfg, None).unwrap();
+        assert!(t.check_duration(86_400).is_ok());
+    }
+
+    #[test]
+    fn size_limits() {
+        assert!(check_size(0).is_err());
+        assert!(check_size(1024).is_ok());
+        assert!(check_size(MAX_AUDIO_BYTES + 1).is_err());
+    }
+
+    #[test]
+    fn parse_ffprobe_duration() {
+        assert_eq!(parse_duration("12.3\n"), Some(13));
+        assert_eq!(parse_duration("0"), Some(0));
+        assert_eq!(parse_duration("N/A"), None);
+    }
+
+    #[tokio::test]
+    async fn empty_audio_rejected_before_network() {
+        let t = Transcriber::from_config(&api_config(), None).unwrap();
+        let err = t
+            .transcribe_bytes(Vec::new(), "voice.ogg", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("empty"));
+    }
+}
@@ -0,0 +1,312 @@
+//! Workspace file watcher. `[triggers.fswatch]` maps glob patterns to
+//! prompts; when matching files change, the daemon runs the agent with that
+//! prompt and the changed paths — e.g. review code on save, or re-index
+//! edited documents.
+//!
+//! Changes are found by comparing periodic snapshots (modified time + size)
+//! rather than with OS notifications (`notify`, inotify/FSEvents/kqueue),
+//! on purpose:
+//!
+//! - Notifications are not delivered for network mounts (NFS, SMB) or for
+//!   many container bind mounts, where workspaces often live; polling sees
+//!   every change the filesystem reports in its metadata.
+//! - Recursive inotify needs a watch per directory and fails once
+//!   `fs.inotify.max_user_watches` is reached on large trees, while a poll
+//!   is bounded by `MAX_WATCHED_FILES` and `ignore_dirs`.
+//! - Events would still need coalescing into one run per burst of saves,
+//!   and the files an agent run writes must not fire it again; comparing
+//!   against a snapshot taken after the run does both.
+//!
+//! The cost is latency of up to `poll_secs` and one metadata walk per poll.
+//! Rules are matched with [`crate::util::glob`].
+
+use crate::config::{Config, FsWatchRule};
+use crate::util::glob;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Stop tracking files past this many, so a workspace pointed at a huge tree
+/// can't turn every poll into a full disk walk.
+const MAX_WATCHED_FILES: usize = 20_000;
+/// Changed paths listed in one prompt; the rest are summarized as a count.
+const MAX_PATHS_IN_PROMPT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl ChangeKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Modified => "modified",
+            Self::Removed => "removed",
+        }
+    }
+
+    /// Combine two changes to the same path seen in successive polls.
+    /// `None` means the path ended up as it started (created, then removed).
+    fn then(self, next: Self) -> Option<Self> {
+        match (self, next) {
+            (Self::Created, Self::Removed) => None,
+            (Self::Created, _) => Some(Self::Created),
+            (Self::Removed, Self::Created) => Some(Self::Modified),
+            (_, next) => Some(next),
+        }
+    }
+}
+
+/// Workspace-relative path → (modified time, size).
+pub type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Record every regular file under `root`, skipping directories named in
+/// `ignore_dirs`. Symlinks are not followed.
+pub fn snapshot(root: &Path, ignore_dirs: &[String]) -> Snapshot {
+    let mut files = Snapshot::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                let name = entry.file_name();
+                if !ignore_dirs.iter().any(|d| name == d.as_str()) {
+                    stack.push(entry.path());
+                }
+            } else if file_type.is_file() {
+                if files.len() >= MAX_WATCHED_FILES {
+                    tracing::warn!(
+                        "fswatch: more than {MAX_WATCHED_FILES} files under {}; ignoring the rest",
+                        root.display()
+                    );
+                    return files;
+                }
+                let Ok(meta) = entry.metadata() else {
+                    continue;
+                };
+                if let Ok(rel) = entry.path().strip_prefix(root) {
+                    files.insert(rel.to_path_buf(), (meta.modified().ok(), meta.len()));
+                }
+            }
+        }
+    }
+    files
+}
+
+/// Paths that differ between two snapshots, in path order.
+pub fn diff(before: &Snapshot, after: &Snapshot) -> Vec<(PathBuf, ChangeKind)> {
+    let mut changes = Vec::new();
+    for (path, stamp) in after {
+        match before.get(path) {
+            None => changes.push((path.clone(), ChangeKind::Created)),
+            Some(old) if old != stamp => changes.push((path.clone(), ChangeKind::Modified)),
+            Some(_) => {}
+        }
+    }
+    for path in before.keys() {
+        if !after.contains_key(path) {
+            changes.push((path.clone(), ChangeKind::Removed));
+        }
+    }
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// One agent prompt per rule that matched at least one change.
+pub fn build_prompts(rules: &[FsWatchRule], changes: &[(PathBuf, ChangeKind)]) -> Vec<String> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let matched: Vec<(String, ChangeKind)> = changes
+                .iter()
+                .map(|(path, kind)| (slash_path(path), *kind))
+                .filter(|(path, _)| glob::matches(&rule.glob, path))
+                .collect();
+            if matched.is_empty() {
+                return None;
+            }
+
+            let mut prompt = format!("[File Trigger] {}\n\nChanged files:\n", rule.prompt.trim());
+            for (path, kind) in matched.iter().take(MAX_PATHS_IN_PROMPT) {
+                let _ = writeln!(prompt, "- {path} ({})", kind.label());
+            }
+            if matched.len() > MAX_PATHS_IN_PROMPT {
+                let _ = writeln!(
+                    prompt,
+                    "- ... and {} more",
+                    matched.len() - MAX_PATHS_IN_PROMPT
+                );
+            }
+            Some(prompt)
+        })
+        .collect()
+}
+
+async fn scan(root: PathBuf, ignore_dirs: Vec<String>) -> Result<Snapshot> {
+    Ok(tokio::task::spawn_blocking(move || snapshot(&root, &ignore_dirs)).await?)
+}
+
+/// Daemon worker: poll the workspace and run the agent for matching changes.
+pub async fn run(config: Config) -> Result<()> {
+    let watch = config.triggers.fswatch.clone();
+    let root = config.workspace_dir.clone();
+    let mut baseline = scan(root.clone(), watch.ignore_dirs.clone()).await?;
+    let mut pending: BTreeMap<PathBuf, ChangeKind> = BTreeMap::new();
+    let mut interval = tokio::time::interval(Duration::from_secs(watch.poll_secs.max(1)));
+
+    crate::health::mark_component_ok("fswatch");
+
+    loop {
+        interval.tick().await;
+
+        let current = scan(root.clone(), watch.ignore_dirs.clone()).await?;
+        let changes = diff(&baseline, &current);
+        baseline = current;
+
+        // Keep collecting until a poll sees nothing new, so a burst of saves
+        // (editor swap files, formatters) becomes a single run.
+        if !changes.is_empty() {
+            for (path, kind) in changes {
+                match pending.remove(&path) {
+                    Some(prev) => {
+                        if let Some(kind) = prev.then(kind) {
+                            pending.insert(path, kind);
+                        }
+                    }
+                    None => {
+                        pending.insert(path, kind);
+                    }
+                }
+            }
+            continue;
+        }
+        if pending.is_empty() {
+            continue;
+        }
+
+        let batch: Vec<(PathBuf, ChangeKind)> = std::mem::take(&mut pending).into_iter().collect();
+        for prompt in build_prompts(&watch.rules, &batch) {
+            let temp = config.default_temperature;
+            let _run = crate::daemon::shutdown::begin_run();
+            // Boxed: a whole agent run is too large to keep inline
+            if let Err(e) = Box::pin(crate::agent::run(
+                config.clone(),
+                Some(prompt),
+                None,
+                None,
+                temp,
+                crate::agent::TurnBudget::default(),
+                None,
+            ))
+            .await
+            {
+                crate::health::mark_component_error("fswatch", e.to_string());
+                tracing::warn!("File trigger run failed: {e}");
+            } else {
+                crate::health::mark_component_ok("fswatch");
+            }
+        }
+
+        // Files the agent wrote during the run must not trigger it again
+        baseline = scan(root.clone(), watch.ignore_dirs.clone()).await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn rule(glob: &str, prompt: &str) -> FsWatchRule {
+        FsWatchRule {
+            glob: glob.into(),
+            prompt: prompt.into(),
+        }
+    }
+
+    #[test]
+    fn snapshot_diff_reports_created_modified_removed() {
+        let tmp = TempDir::new().unwrap();
+        let ignore = vec!["target".to_string()];
+        std::fs::write(tmp.path().join("keep.md"), "a").unwrap();
+        std::fs::write(tmp.path().join("edit.md"), "a").unwrap();
+        std::fs::write(tmp.path().join("gone.md"), "a").unwrap();
+        let before = snapshot(tmp.path(), &ignore);
+
+        std::fs::write(tmp.path().join("edit.md"), "longer").unwrap();
+        std::fs::remove_file(tmp.path().join("gone.md")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("docs")).unwrap();
+        std::fs::write(tmp.path().join("docs/new.md"), "a").unwrap();
+        std::fs::create_dir_all(tmp.path().join("target")).unwrap();
+        std::fs::write(tmp.path().join("target/out.bin"), "a").unwrap();
+        let after = snapshot(tmp.path(), &ignore);
+
+        assert_eq!(
+            diff(&before, &after),
+            vec![
+                (PathBuf::from("docs/new.md"), ChangeKind::Created),
+                (PathBuf::from("edit.md"), ChangeKind::Modified),
+                (PathBuf::from("gone.md"), ChangeKind::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn changes_across_polls_combine() {
+        use ChangeKind::{Created, Modified, Removed};
+        assert_eq!(Created.then(Modified), Some(Created));
+        assert_eq!(Created.then(Removed), None);
+        assert_eq!(Removed.then(Created), Some(Modified));
+        assert_eq!(Modified.then(Removed), Some(Removed));
+    }
+
+    #[test]
+    fn build_prompts_fires_each_matching_rule_once() {
+        let rules = vec![
+            rule("src/**/*.rs", "Review the changed code."),
+            rule("*.md", "Re-index these documents."),
+            rule("*.py", "Never fires."),
+        ];
+        let changes = vec![
+            (PathBuf::from("docs/a.md"), ChangeKind::Created),
+            (PathBuf::from("src/lib.rs"), ChangeKind::Modified),
+            (PathBuf::from("src/tools/x.rs"), ChangeKind::Removed),
+        ];
+
+        let prompts = build_prompts(&rules, &changes);
+        assert_eq!(prompts.len(), 2);
+        assert_eq!(
+            prompts[0],
+            "[File Trigger] Review the changed code.\n\nChanged files:\n\
+             - src/lib.rs (modified)\n- src/tools/x.rs (removed)\n"
+        );
+        assert!(prompts[1].contains("- docs/a.md (created)"));
+        assert!(!prompts[1].contains("lib.rs"));
+    }
+
+    #[test]
+    fn build_prompts_caps_listed_paths() {
+        let changes: Vec<_> = (0..MAX_PATHS_IN_PROMPT + 3)
+            .map(|i| (PathBuf::from(format!("f{i}.txt")), ChangeKind::Created))
+            .collect();
+        let prompts = build_prompts(&[rule("*.txt", "Summarize.")], &changes);
+        assert!(prompts[0].ends_with("- ... and 3 more\n"));
+    }
+}
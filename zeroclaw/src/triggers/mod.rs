@@ -0,0 +1,3 @@
+//! Triggers start agent runs from events instead of user messages.
+
+pub mod fswatch;
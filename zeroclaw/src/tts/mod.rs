@@ -0,0 +1,297 @@
+//! Text-to-speech for the `speak` tool and voice-note replies on channels.
+//!
+//! Two backends are supported:
+//! - `api` — any `OpenAI`-compatible `/audio/speech` endpoint, asked for Ogg/Opus directly
+//! - `command` — a local engine (piper, espeak-ng, ...) that writes WAV, converted with `ffmpeg`
+//!
+//! Clips are always Ogg/Opus, the format Telegram and Discord play inline as voice notes.
+
+use crate::config::TtsConfig;
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+const API_TIMEOUT_SECS: u64 = 60;
+
+/// Synthesized audio ready to upload.
+#[derive(Debug, Clone)]
+pub struct SpeechClip {
+    pub bytes: Vec<u8>,
+    pub file_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Backend {
+    Api {
+        url: String,
+        api_key: String,
+        model: String,
+        voice: String,
+    },
+    Command {
+        template: String,
+    },
+}
+
+/// Configured speech synthesizer.
+#[derive(Debug, Clone)]
+pub struct Synthesizer {
+    backend: Backend,
+    max_chars: usize,
+    client: reqwest::Client,
+}
+
+impl Synthesizer {
+    /// Build a synthesizer from config. Returns `None` when TTS is disabled or
+    /// the selected backend is missing its key/command.
+    pub fn from_config(config: &TtsConfig, fallback_api_key: Option<&str>) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let backend = match config.backend.as_str() {
+            "api" => {
+                let api_key = config
+                    .api_key
+                    .clone()
+                    .or_else(|| std::env::var("OPENAI_API_KEY").ok())
+                    .or_else(|| fallback_api_key.map(str::to_string))
+                    .filter(|k| !k.is_empty());
+                let Some(api_key) = api_key else {
+                    tracing::warn!("tts: api backend has no API key configured");
+                    return None;
+                };
+                Backend::Api {
+                    url: config.api_url.clone(),
+                    api_key,
+                    model: config.model.clone(),
+                    voice: config.voice.clone(),
+                }
+            }
+            "command" | "local" => {
+                let Some(template) = config.command.clone().filter(|c| c.contains("{output}"))
+                else {
+                    tracing::warn!("tts: command backend needs `command` containing {{output}}");
+                    return None;
+                };
+                Backend::Command { template }
+            }
+            other => {
+                tracing::warn!("tts: unknown backend '{other}'");
+                return None;
+            }
+        };
+
+        Some(Self {
+            backend,
+            max_chars: config.max_chars,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Build a synthesizer from the full app config, reusing the main key
+    /// only when the default provider is `OpenAI`.
+    pub fn from_app_config(config: &crate::config::Config) -> Option<Self> {
+        let fallback_key = config
+            .api_key
+            .as_deref()
+            .filter(|_| config.default_provider.as_deref() == Some("openai"));
+        Self::from_config(&config.tts, fallback_key)
+    }
+
+    /// Whether `text` is short enough to be spoken.
+    pub fn accepts(&self, text: &str) -> bool {
+        let len = text.trim().chars().count();
+        len > 0 && len <= self.max_chars
+    }
+
+    /// Synthesize `text`, optionally overriding the configured voice (API backend only).
+    pub async fn synthesize(&self, text: &str, voice: Option<&str>) -> Result<SpeechClip> {
+        let text = text.trim();
+        if text.is_empty() {
+            bail!("Nothing to speak");
+        }
+        if !self.accepts(text) {
+            bail!(
+                "Text too long to speak: {} characters (limit: {})",
+                text.chars().count(),
+                self.max_chars
+            );
+        }
+
+        let bytes = match &self.backend {
+            Backend::Api {
+                url,
+                api_key,
+                model,
+                voice: default_voice,
+            } => {
+                let body = serde_json::json!({
+                    "model": model,
+                    "input": text,
+                    "voice": voice.filter(|v| !v.is_empty()).unwrap_or(default_voice),
+                    "response_format": "opus",
+                });
+                let resp = self
+                    .client
+                    .post(url)
+                    .bearer_auth(api_key)
+                    .json(&body)
+                    .timeout(Duration::from_secs(API_TIMEOUT_SECS))
+                    .send()
+                    .await
+                    .context("Speech request failed")?;
+                if !resp.status().is_success() {
+                    let status = resp.status();
+                    let err = resp.text().await.unwrap_or_default();
+                    bail!("Speech API error ({status}): {err}");
+                }
+                resp.bytes().await?.to_vec()
+            }
+            Backend::Command { template } => synthesize_local(template, text).await?,
+        };
+
+        if bytes.is_empty() {
+            bail!("Speech backend returned no audio");
+        }
+        Ok(SpeechClip {
+            bytes,
+            file_name: "voice.ogg".into(),
+        })
+    }
+}
+
+/// Split a command template into argv, substituting `{output}`.
+fn command_argv(template: &str, output: &Path) -> Vec<String> {
+    let output = output.to_string_lossy();
+    template
+        .split_whitespace()
+        .map(|arg| arg.replace("{output}", &output))
+        .collect()
+}
+
+async fn synthesize_local(template: &str, text: &str) -> Result<Vec<u8>> {
+    let tmp = std::env::temp_dir().join(format!("zeroclaw-tts-{}", uuid::Uuid::new_v4()));
+    tokio::fs::create_dir_all(&tmp).await?;
+    let result = run_local(template, text, &tmp).await;
+    let _ = tokio::fs::remove_dir_all(&tmp).await;
+    result
+}
+
+async fn run_local(template: &str, text: &str, tmp: &Path) -> Result<Vec<u8>> {
+    let wav = tmp.join("speech.wav");
+    let ogg = tmp.join("speech.ogg");
+
+    let parts = command_argv(template, &wav);
+    let (program, args) = parts.split_first().context("Empty TTS command")?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run TTS command '{program}'"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        bail!(
+            "TTS command failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let convert = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-i"])
+        .arg(&wav)
+        .args(["-c:a", "libopus", "-b:a", "32k"])
+        .arg(&ogg)
+        .output()
+        .await
+        .context("Failed to run ffmpeg (required by the command backend)")?;
+    if !convert.status.success() {
+        bail!(
+            "ffmpeg failed to encode speech: {}",
+            String::from_utf8_lossy(&convert.stderr).trim()
+        );
+    }
+
+    Ok(tokio::fs::read(&ogg).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_config() -> TtsConfig {
+        TtsConfig {
+            enabled: true,
+            api_key: Some("sk-test".into()),
+            ..TtsConfig::default()
+        }
+    }
+
+    #[test]
+    fn config_defaults() {
+        let cfg = TtsConfig::default();
+        assert!(!cfg.enabled);
+        assert_eq!(cfg.backend, "api");
+        assert_eq!(cfg.model, "tts-1");
+        assert_eq!(cfg.voice, "alloy");
+        assert!(cfg.command.is_none());
+    }
+
+    #[test]
+    fn disabled_config_yields_none() {
+        assert!(Synthesizer::from_config(&TtsConfig::default(), Some("k")).is_none());
+    }
+
+    #[test]
+    fn command_backend_requires_output_placeholder() {
+        let mut cfg = TtsConfig {
+            enabled: true,
+            backend: "command".into(),
+            command: Some("piper --model voice.onnx".into()),
+            ..TtsConfig::default()
+        };
+        assert!(Synthesizer::from_config(&cfg, None).is_none());
+
+        cfg.command = Some("piper --model voice.onnx --output_file {output}".into());
+        let s = Synthesizer::from_config(&cfg, None).unwrap();
+        assert!(matches!(s.backend, Backend::Command { .. }));
+    }
+
+    #[test]
+    fn command_argv_substitutes_output() {
+        let argv = command_argv("espeak-ng -w {output} --stdin", Path::new("/tmp/a.wav"));
+        assert_eq!(argv, vec!["espeak-ng", "-w", "/tmp/a.wav", "--stdin"]);
+    }
+
+    #[test]
+    fn accepts_respects_max_chars() {
+        let cfg = TtsConfig {
+            max_chars: 10,
+            ..api_config()
+        };
+        let s = Synthesizer::from_config(&cfg, None).unwrap();
+        assert!(s.accepts("short"));
+        assert!(!s.accepts("   "));
+        assert!(!s.accepts("definitely too long"));
+    }
+
+    #[tokio::test]
+    async fn synthesize_rejects_empty_and_long_text() {
+        let cfg = TtsConfig {
+            max_chars: 10,
+            ..api_config()
+        };
+        let s = Synthesizer::from_config(&cfg, None).unwrap();
+        assert!(s.synthesize("  ", None).await.is_err());
+        let err = s.synthesize("definitely too long", None).await.unwrap_err();
+        assert!(err.to_string().contains("too long"));
+    }
+}
@@ -0,0 +1,389 @@
+//! TUI state, key handling and drawing. Nothing here touches the terminal
+//! or the agent, so it can be driven and rendered in tests.
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, Paragraph, Wrap};
+use ratatui::Frame;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Lines kept in the tool pane.
+const MAX_TOOL_LINES: usize = 200;
+
+/// Output lines shown under each finished tool call.
+const TOOL_OUTPUT_LINES: usize = 4;
+
+/// Lines moved per Page Up / Page Down.
+const SCROLL_STEP: u16 = 5;
+
+/// Progress reported from a running turn.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TuiEvent {
+    ToolStarted {
+        tool: String,
+        args: String,
+    },
+    ToolFinished {
+        tool: String,
+        success: bool,
+        output: String,
+        duration: Duration,
+    },
+    MemoryHits(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Speaker {
+    User,
+    Agent,
+    Notice,
+}
+
+/// What a key press asks the event loop to do.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Send(String),
+    Quit,
+}
+
+pub struct App {
+    model: String,
+    /// USD per million input and output tokens
+    prices: Option<(f64, f64)>,
+    conversation: Vec<(Speaker, String)>,
+    tool_lines: VecDeque<Line<'static>>,
+    memory_hits: Vec<String>,
+    input: String,
+    /// Lines scrolled up from the end of the conversation
+    scroll: u16,
+    busy: bool,
+    tokens_in: u64,
+    tokens_out: u64,
+}
+
+impl App {
+    pub fn new(model: &str, prices: Option<(f64, f64)>) -> Self {
+        Self {
+            model: model.to_string(),
+            prices,
+            conversation: Vec::new(),
+            tool_lines: VecDeque::new(),
+            memory_hits: Vec::new(),
+            input: String::new(),
+            scroll: 0,
+            busy: false,
+            tokens_in: 0,
+            tokens_out: 0,
+        }
+    }
+
+    pub fn push(&mut self, speaker: Speaker, text: impl Into<String>) {
+        self.conversation.push((speaker, text.into()));
+        self.scroll = 0;
+    }
+
+    /// A turn ended, with or without an answer.
+    pub fn finish_turn(&mut self, tokens_in: u64, tokens_out: u64) {
+        self.busy = false;
+        self.tokens_in += tokens_in;
+        self.tokens_out += tokens_out;
+    }
+
+    pub fn cost(&self) -> Option<f64> {
+        let (input, output) = self.prices?;
+        #[allow(clippy::cast_precision_loss)]
+        let cost = (self.tokens_in as f64 * input + self.tokens_out as f64 * output) / 1e6;
+        Some(cost)
+    }
+
+    pub fn apply(&mut self, event: TuiEvent) {
+        match event {
+            TuiEvent::ToolStarted { tool, args } => {
+                self.push_tool_line(Line::from(vec![
+                    Span::styled("▶ ", Style::default().fg(Color::Yellow)),
+                    Span::styled(tool, Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(" {args}")),
+                ]));
+            }
+            TuiEvent::ToolFinished {
+                tool,
+                success,
+                output,
+                duration,
+            } => {
+                let (mark, color) = if success {
+                    ("✓ ", Color::Green)
+                } else {
+                    ("✗ ", Color::Red)
+                };
+                self.push_tool_line(Line::from(vec![
+                    Span::styled(mark, Style::default().fg(color)),
+                    Span::styled(tool, Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(" ({} ms)", duration.as_millis())),
+                ]));
+                let lines: Vec<&str> = output.lines().collect();
+                for line in lines.iter().take(TOOL_OUTPUT_LINES) {
+                    self.push_tool_line(Line::styled(
+                        format!("  {line}"),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                if lines.len() > TOOL_OUTPUT_LINES {
+                    self.push_tool_line(Line::styled(
+                        format!("  … {} more lines", lines.len() - TOOL_OUTPUT_LINES),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+            }
+            TuiEvent::MemoryHits(hits) => self.memory_hits = hits,
+        }
+    }
+
+    fn push_tool_line(&mut self, line: Line<'static>) {
+        if self.tool_lines.len() == MAX_TOOL_LINES {
+            self.tool_lines.pop_front();
+        }
+        self.tool_lines.push_back(line);
+    }
+
+    pub fn on_event(&mut self, event: &Event) -> Action {
+        let Event::Key(key) = event else {
+            return Action::None;
+        };
+        if key.kind != KeyEventKind::Press {
+            return Action::None;
+        }
+        match key.code {
+            KeyCode::Esc => Action::Quit,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+            KeyCode::Enter => self.submit(),
+            KeyCode::Backspace => {
+                self.input.pop();
+                Action::None
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                Action::None
+            }
+            KeyCode::PageUp => {
+                self.scroll = self.scroll.saturating_add(SCROLL_STEP);
+                Action::None
+            }
+            KeyCode::PageDown => {
+                self.scroll = self.scroll.saturating_sub(SCROLL_STEP);
+                Action::None
+            }
+            _ => Action::None,
+        }
+    }
+
+    /// Enter: send the input, unless a turn is still running.
+    fn submit(&mut self) -> Action {
+        let message = self.input.trim().to_string();
+        if message.is_empty() || self.busy {
+            return Action::None;
+        }
+        self.input.clear();
+        if message == "/quit" || message == "/exit" {
+            return Action::Quit;
+        }
+        self.push(Speaker::User, message.clone());
+        self.busy = true;
+        Action::Send(message)
+    }
+}
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [body, input, status] = Layout::vertical([
+        Constraint::Min(5),
+        Constraint::Length(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [conversation, side] =
+        Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(body);
+    let [tools, memory] =
+        Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(side);
+
+    draw_conversation(frame, app, conversation);
+
+    let visible = usize::from(tools.height.saturating_sub(2));
+    let skip = app.tool_lines.len().saturating_sub(visible);
+    let tool_items: Vec<ListItem> = app
+        .tool_lines
+        .iter()
+        .skip(skip)
+        .cloned()
+        .map(ListItem::new)
+        .collect();
+    frame.render_widget(
+        List::new(tool_items).block(Block::bordered().title(" Tools ")),
+        tools,
+    );
+
+    let hits: Vec<ListItem> = app
+        .memory_hits
+        .iter()
+        .map(|hit| ListItem::new(format!("• {hit}")))
+        .collect();
+    frame.render_widget(
+        List::new(hits).block(Block::bordered().title(" Memory ")),
+        memory,
+    );
+
+    let prompt = if app.busy { "… " } else { "> " };
+    frame.render_widget(
+        Paragraph::new(format!("{prompt}{}", app.input))
+            .block(Block::bordered().title(" Message (Enter to send, Esc to quit) ")),
+        input,
+    );
+
+    let cost = app
+        .cost()
+        .map_or_else(|| "cost n/a".to_string(), |cost| format!("${cost:.4}"));
+    let state = if app.busy { "thinking…" } else { "ready" };
+    frame.render_widget(
+        Paragraph::new(format!(
+            " {} │ tokens in {} · out {} │ {cost} │ {state}",
+            app.model, app.tokens_in, app.tokens_out
+        ))
+        .style(Style::default().add_modifier(Modifier::REVERSED)),
+        status,
+    );
+}
+
+fn draw_conversation(frame: &mut Frame, app: &App, area: Rect) {
+    let mut text = Text::default();
+    for (speaker, message) in &app.conversation {
+        let (label, color) = match speaker {
+            Speaker::User => ("you", Color::Cyan),
+            Speaker::Agent => ("agent", Color::Green),
+            Speaker::Notice => ("!", Color::Yellow),
+        };
+        text.push_line(Line::styled(
+            label,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+        for line in message.lines() {
+            text.push_line(Line::raw(line.to_string()));
+        }
+        text.push_line(Line::default());
+    }
+
+    // Follow the end of the conversation unless scrolled up
+    let width = usize::from(area.width.saturating_sub(2)).max(1);
+    let wrapped: usize = text
+        .lines
+        .iter()
+        .map(|line| line.width().div_ceil(width).max(1))
+        .sum();
+    let height = usize::from(area.height.saturating_sub(2));
+    let bottom = u16::try_from(wrapped.saturating_sub(height)).unwrap_or(u16::MAX);
+    let top = bottom.saturating_sub(app.scroll);
+
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(Block::bordered().title(" Conversation "))
+            .wrap(Wrap { trim: false })
+            .scroll((top, 0)),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyEvent;
+    use ratatui::Terminal;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            assert_eq!(app.on_event(&key(KeyCode::Char(c))), Action::None);
+        }
+    }
+
+    fn render(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(usize::from(buffer.area.width))
+            .map(|row| {
+                row.iter()
+                    .map(ratatui::buffer::Cell::symbol)
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn enter_sends_once_per_turn_and_escape_quits() {
+        let mut app = App::new("gpt-4o", None);
+        type_text(&mut app, "hello");
+        assert_eq!(
+            app.on_event(&key(KeyCode::Enter)),
+            Action::Send("hello".into())
+        );
+
+        type_text(&mut app, "again");
+        assert_eq!(app.on_event(&key(KeyCode::Enter)), Action::None);
+        app.finish_turn(10, 5);
+        assert_eq!(
+            app.on_event(&key(KeyCode::Enter)),
+            Action::Send("again".into())
+        );
+
+        assert_eq!(app.on_event(&key(KeyCode::Esc)), Action::Quit);
+        type_text(&mut app, "/quit");
+        app.finish_turn(0, 0);
+        assert_eq!(app.on_event(&key(KeyCode::Enter)), Action::Quit);
+    }
+
+    #[test]
+    fn panes_show_conversation_tools_memory_and_meters() {
+        let mut app = App::new("gpt-4o-mini", Some((0.15, 0.6)));
+        app.push(Speaker::User, "How big is the repo?");
+        app.apply(TuiEvent::ToolStarted {
+            tool: "shell".into(),
+            args: "du -sh .".into(),
+        });
+        app.apply(TuiEvent::ToolFinished {
+            tool: "shell".into(),
+            success: true,
+            output: "42M\t.".into(),
+            duration: Duration::from_millis(12),
+        });
+        app.apply(TuiEvent::MemoryHits(vec!["repo: lives in ~/src".into()]));
+        app.push(Speaker::Agent, "About 42 MB.");
+        app.finish_turn(1_000_000, 100_000);
+
+        assert!((app.cost().unwrap() - 0.21).abs() < 1e-9);
+        let screen = render(&app);
+        for expected in [
+            "Conversation",
+            "How big is the repo?",
+            "About 42 MB.",
+            "shell (12 ms)",
+            "42M",
+            "repo: lives in ~/src",
+            "tokens in 1000000 · out 100000",
+            "$0.2100",
+        ] {
+            assert!(
+                screen.contains(expected),
+                "missing {expected:?} in\n{screen}"
+            );
+        }
+    }
+}
@@ -0,0 +1,566 @@
+//! Full-screen terminal UI for agent conversations (`zeroclaw tui`, and
+//! `zc_agent_run_tui` over FFI).
+//!
+//! Panes show the conversation, tool calls with the start of their output
+//! as they finish, the memories recalled for the last message, and running
+//! token and cost totals. Turns run on a task of their own so the screen
+//! keeps updating while the agent works.
+
+pub mod app;
+
+use crate::agent::budget::{TurnBudget, TurnReply};
+use crate::agent::loop_::{self, agent_turn_with_budget};
+use crate::agent::settings::{self, TurnSettings};
+use crate::config::{Config, MemoryConfig, VerifierConfig};
+use crate::guardrails::Guardrails;
+use crate::hooks::{self, Hooks};
+use crate::knowledge;
+use crate::memory::{self, Memory, MemoryCategory};
+use crate::observability::{self, Observer};
+use crate::providers::{self, ChatMessage, Provider, VisionFallback};
+use crate::runtime;
+use crate::security::SecurityPolicy;
+use crate::tokens::Family;
+use crate::tools::{self, Tool, ToolResult};
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use app::{Action, App, Speaker, TuiEvent};
+use async_trait::async_trait;
+use ratatui::crossterm::event;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use uuid::Uuid;
+
+/// Everything a TUI session needs to run turns.
+pub struct TuiAgent {
+    pub provider: Box<dyn Provider>,
+    pub tools: Vec<Box<dyn Tool>>,
+    pub memory: Arc<dyn Memory>,
+    pub observer: Arc<dyn Observer>,
+    pub vision_fallback: Option<VisionFallback>,
+    pub system_prompt: String,
+    pub model: String,
+    pub temperature: f64,
+    pub budget: TurnBudget,
+    /// USD per million input and output tokens, for the cost meter
+    pub prices: Option<(f64, f64)>,
+    pub verifier: VerifierConfig,
+    /// `[identity.persona] language` for the TUI, enforced on answers
+    pub language: Option<String>,
+    pub guardrails: Guardrails,
+    /// Auto-save raw messages (off when facts are extracted instead)
+    pub auto_save: bool,
+    /// `[memory]`: recall filtering, dedup, fact extraction and the graph
+    pub memory_config: MemoryConfig,
+    /// Workspace the knowledge graph is kept in
+    pub workspace_dir: PathBuf,
+    /// `[hooks]`, run around each turn
+    pub hooks: Option<Arc<Hooks>>,
+    /// Settings each turn runs with
+    pub settings: Arc<TurnSettings>,
+}
+
+/// What a turn task shares with the next one.
+struct TurnContext {
+    provider: Box<dyn Provider>,
+    tools: Vec<Box<dyn Tool>>,
+    memory: Arc<dyn Memory>,
+    observer: Arc<dyn Observer>,
+    vision_fallback: Option<VisionFallback>,
+    model: String,
+    temperature: f64,
+    budget: TurnBudget,
+    verifier: VerifierConfig,
+    language: Option<String>,
+    guardrails: Guardrails,
+    auto_save: bool,
+    memory_config: MemoryConfig,
+    workspace_dir: PathBuf,
+    hooks: Option<Arc<Hooks>>,
+    settings: Arc<TurnSettings>,
+    events: UnboundedSender<TuiEvent>,
+}
+
+/// What a finished turn task hands back to the event loop.
+struct TurnOutcome {
+    history: Vec<ChatMessage>,
+    reply: Result<TurnReply>,
+    tokens_in: u64,
+    tokens_out: u64,
+}
+
+/// Reports each call of the tool at `index` to the TUI.
+struct TappedTool {
+    tools: Arc<Vec<Box<dyn Tool>>>,
+    index: usize,
+    events: UnboundedSender<TuiEvent>,
+}
+
+impl TappedTool {
+    fn inner(&self) -> &dyn Tool {
+        self.tools[self.index].as_ref()
+    }
+}
+
+#[async_trait]
+impl Tool for TappedTool {
+    fn name(&self) -> &str {
+        self.inner().name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner().description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.inner().parameters_schema()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> Result<ToolResult> {
+        let tool = self.name().to_string();
+        let _ = self.events.send(TuiEvent::ToolStarted {
+            tool: tool.clone(),
+            args: truncate_with_ellipsis(&args.to_string(), 60),
+        });
+        let start = Instant::now();
+        let result = self.inner().execute(args).await;
+        let (success, output) = match &result {
+            Ok(r) if r.success => (true, r.output.clone()),
+            Ok(r) => (false, r.error.clone().unwrap_or_else(|| r.output.clone())),
+            Err(e) => (false, e.to_string()),
+        };
+        let _ = self.events.send(TuiEvent::ToolFinished {
+            tool,
+            success,
+            output,
+            duration: start.elapsed(),
+        });
+        result
+    }
+}
+
+/// Run the TUI until the user quits. The tools are handed back afterwards.
+#[allow(clippy::too_many_lines)]
+pub async fn run(agent: TuiAgent) -> Result<Vec<Box<dyn Tool>>> {
+    let (events_tx, mut events) = mpsc::unbounded_channel();
+    let shared_tools = Arc::new(agent.tools);
+    let tapped: Vec<Box<dyn Tool>> = (0..shared_tools.len())
+        .map(|index| {
+            Box::new(TappedTool {
+                tools: shared_tools.clone(),
+                index,
+                events: events_tx.clone(),
+            }) as Box<dyn Tool>
+        })
+        .collect();
+    let context = Arc::new(TurnContext {
+        provider: agent.provider,
+        tools: tapped,
+        memory: agent.memory,
+        observer: agent.observer,
+        vision_fallback: agent.vision_fallback,
+        model: agent.model.clone(),
+        temperature: agent.temperature,
+        budget: agent.budget,
+        verifier: agent.verifier,
+        language: agent.language,
+        guardrails: agent.guardrails,
+        auto_save: agent.auto_save,
+        memory_config: agent.memory_config,
+        workspace_dir: agent.workspace_dir,
+        hooks: agent.hooks,
+        settings: agent.settings,
+        events: events_tx,
+    });
+    let mut app = App::new(&agent.model, agent.prices);
+    let mut history = Some(vec![ChatMessage::system(agent.system_prompt)]);
+    // What was said, without recalled context, for the summary on quitting
+    let mut transcript = Vec::new();
+    let mut sent = String::new();
+
+    // Terminal input is read on a thread of its own; polling lets it stop
+    let (keys_tx, mut keys) = mpsc::unbounded_channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let reader_stop = stop.clone();
+    let reader = std::thread::spawn(move || {
+        while !reader_stop.load(Ordering::Relaxed) {
+            match event::poll(Duration::from_millis(100)) {
+                Ok(true) => {
+                    let Ok(ev) = event::read() else { break };
+                    if keys_tx.send(ev).is_err() {
+                        break;
+                    }
+                }
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    });
+
+    let mut terminal = ratatui::try_init()?;
+    let mut turn: Option<tokio::task::JoinHandle<TurnOutcome>> = None;
+    let result: Result<()> = async {
+        loop {
+            terminal.draw(|frame| app::draw(frame, &app))?;
+            tokio::select! {
+                Some(ev) = keys.recv() => match app.on_event(&ev) {
+                    Action::Quit => break,
+                    Action::Send(message) => {
+                        let history = history.take().unwrap_or_default();
+                        sent.clone_from(&message);
+                        turn = Some(tokio::spawn(run_turn(context.clone(), history, message)));
+                    }
+                    Action::None => {}
+                },
+                Some(ev) = events.recv() => app.apply(ev),
+                joined = async { turn.as_mut().expect("guarded by is_some").await }, if turn.is_some() => {
+                    turn = None;
+                    let outcome = joined?;
+                    app.finish_turn(outcome.tokens_in, outcome.tokens_out);
+                    match outcome.reply {
+                        Ok(reply) => {
+                            memory::summary::remember_exchange(&mut transcript, &sent, &reply.answer);
+                            app.push(Speaker::Agent, reply.answer);
+                            if let Some(exhausted) = reply.exhausted {
+                                app.push(Speaker::Notice, format!("{exhausted}; the answer above is partial"));
+                            }
+                        }
+                        Err(e) => app.push(Speaker::Notice, format!("Error: {e}")),
+                    }
+                    history = Some(outcome.history);
+                }
+            }
+        }
+        Ok(())
+    }
+    .await;
+
+    ratatui::restore();
+    stop.store(true, Ordering::Relaxed);
+    let _ = reader.join();
+    // A turn cut short by quitting still holds the tools
+    if let Some(turn) = turn {
+        turn.abort();
+        let _ = turn.await;
+    }
+    memory::summary::on_close(
+        context.provider.as_ref(),
+        context.memory.as_ref(),
+        &context.memory_config,
+        &context.model,
+        "tui",
+        &transcript,
+    )
+    .await;
+    drop(context);
+    result?;
+    Arc::try_unwrap(shared_tools).map_err(|_| anyhow::anyhow!("TUI tools are still in use"))
+}
+
+#[allow(clippy::too_many_lines)]
+async fn run_turn(
+    context: Arc<TurnContext>,
+    mut history: Vec<ChatMessage>,
+    message: String,
+) -> TurnOutcome {
+    let hits = memory::recall::relevant(
+        context.memory.as_ref(),
+        &message,
+        &context.memory_config.recall,
+    )
+    .await;
+    let _ = context.events.send(TuiEvent::MemoryHits(
+        hits.iter()
+            .map(|e| format!("{}: {}", e.key, truncate_with_ellipsis(&e.content, 80)))
+            .collect(),
+    ));
+    let mut enriched = memory::recall::context_block(&hits);
+    enriched.push_str(&memory::graph::build_context(
+        &context.memory_config,
+        &context.workspace_dir,
+        &message,
+    ));
+    enriched.push_str(&message);
+    if context.auto_save {
+        let key = format!("user_msg_{}", Uuid::new_v4());
+        let _ = memory::dedup::store(
+            context.memory.as_ref(),
+            &key,
+            &message,
+            MemoryCategory::Conversation,
+            &context.memory_config.dedup,
+        )
+        .await;
+    }
+
+    history.push(ChatMessage::user(enriched));
+    let start = history.len();
+    let reply = settings::scope_with(
+        context.settings.clone(),
+        hooks::scope_with(
+            context.hooks.clone(),
+            agent_turn_with_budget(
+                context.provider.as_ref(),
+                &mut history,
+                &context.tools,
+                context.observer.as_ref(),
+                &context.model,
+                context.temperature,
+                context.vision_fallback.as_ref(),
+                context.budget,
+            ),
+        ),
+    )
+    .await;
+    let reply = match reply {
+        Ok(reply) if reply.exhausted.is_none() => {
+            let answer = crate::agent::verify::verify_answer(
+                context.provider.as_ref(),
+                &mut history,
+                reply.answer,
+                &context.model,
+                &context.verifier,
+            )
+            .await;
+            let answer = crate::agent::language::enforce(
+                context.provider.as_ref(),
+                &mut history,
+                answer,
+                &context.model,
+                context.language.as_deref(),
+            )
+            .await;
+            Ok(TurnReply {
+                answer,
+                exhausted: None,
+            })
+        }
+        other => other,
+    };
+    // Partial answers are delivered too, so they are checked as well
+    let reply = match reply {
+        Ok(mut reply) => {
+            reply.answer = context
+                .guardrails
+                .apply(
+                    context.provider.as_ref(),
+                    &mut history,
+                    reply.answer,
+                    &context.model,
+                    "tui",
+                )
+                .await;
+            Ok(reply)
+        }
+        Err(e) => Err(e),
+    };
+    let (tokens_in, tokens_out) = turn_usage(&history, start, &context.model);
+
+    if let (true, Ok(reply)) = (context.auto_save, &reply) {
+        let key = format!("assistant_resp_{}", Uuid::new_v4());
+        let summary = truncate_with_ellipsis(&reply.answer, 100);
+        let _ = memory::dedup::store(
+            context.memory.as_ref(),
+            &key,
+            &summary,
+            MemoryCategory::Daily,
+            &context.memory_config.dedup,
+        )
+        .await;
+    }
+    if let Ok(reply) = &reply {
+        memory::extract::after_turn(
+            context.provider.as_ref(),
+            context.memory.as_ref(),
+            &context.memory_config,
+            &context.model,
+            &message,
+            &reply.answer,
+        )
+        .await;
+    }
+    loop_::trim_history(&mut history, &context.model);
+    TurnOutcome {
+        history,
+        reply,
+        tokens_in,
+        tokens_out,
+    }
+}
+
+/// Estimated tokens sent and received for the assistant messages from
+/// `start` on: each was a reply to everything before it.
+fn turn_usage(history: &[ChatMessage], start: usize, model: &str) -> (u64, u64) {
+    let family = Family::for_model(model);
+    history
+        .iter()
+        .enumerate()
+        .skip(start)
+        .filter(|(_, m)| m.role == "assistant")
+        .fold((0, 0), |(sent, received), (i, m)| {
+            (
+                sent + family.count_messages(&history[..i]),
+                received + family.count_message(m),
+            )
+        })
+}
+
+/// `zeroclaw tui`: set up the agent from `config` and run the TUI.
+pub async fn run_from_config(
+    config: Config,
+    provider_override: Option<String>,
+    model_override: Option<String>,
+    temperature: f64,
+) -> Result<()> {
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+    let runtime: Arc<dyn runtime::RuntimeAdapter> =
+        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    crate::health::set_autonomy_limits(&config.autonomy);
+    let memory: Arc<dyn Memory> = Arc::from(memory::create_memory(
+        &config.memory,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )?);
+
+    let composio_key = if config.composio.enabled {
+        config.composio.api_key.as_deref()
+    } else {
+        None
+    };
+    let mut tools = tools::all_tools_with_runtime(
+        &security,
+        runtime,
+        memory.clone(),
+        composio_key,
+        &config.browser,
+    );
+    tools.extend(tools::optional_tools(&config, &security));
+    tools::route_to_nodes(&mut tools, &config.nodes, &security);
+    if config.knowledge.enabled && knowledge::supported(memory.as_ref()) {
+        knowledge::spawn_sync(&config, memory.clone());
+        tools.push(Box::new(tools::KbSearchTool::new(memory.clone())));
+    }
+
+    let provider_name = provider_override
+        .as_deref()
+        .or(config.default_provider.as_deref())
+        .unwrap_or("openrouter");
+    let model = model_override
+        .as_deref()
+        .or(config.default_model.as_deref())
+        .unwrap_or("anthropic/claude-sonnet-4-20250514")
+        .to_string();
+
+    let provider = providers::create_routed_provider(
+        provider_name,
+        config.api_key.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        &model,
+        observer.clone(),
+        &providers::ProviderSettings::from_config(&config),
+    )?;
+    let provider = providers::cache::wrap(provider, &config, provider_name);
+
+    let mut skills = crate::skills::load_skills(&config.workspace_dir);
+    let tool_names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+    crate::skills::package::retain_usable(&mut skills, &tool_names);
+    let mut system_prompt = crate::channels::build_system_prompt(
+        &config.workspace_dir,
+        &model,
+        &loop_::tool_descriptions(&config, &tools),
+        &skills,
+        Some(&crate::identity::readable_by(
+            config.identity.for_channel("tui"),
+            &security,
+        )),
+    );
+    system_prompt.push_str(&loop_::build_tool_instructions_with(
+        &tools,
+        config.tool_schema_for(&model),
+    ));
+
+    run(TuiAgent {
+        provider,
+        tools,
+        memory,
+        observer,
+        vision_fallback: VisionFallback::from_config(&config),
+        system_prompt,
+        budget: config.turn_budget_for(&model),
+        prices: config.prices_for(&model),
+        model,
+        temperature,
+        verifier: config.verifier.clone(),
+        language: crate::agent::language::configured(&config.identity, "tui"),
+        guardrails: Guardrails::from_config(&config.guardrails, &config.workspace_dir),
+        auto_save: config.memory.saves_raw_turns(),
+        memory_config: config.memory.clone(),
+        workspace_dir: config.workspace_dir.clone(),
+        hooks: Hooks::from_config(&config),
+        settings: Arc::new(TurnSettings::from_config(&config)),
+    })
+    .await
+    .map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::CalculatorTool;
+
+    #[test]
+    fn usage_counts_each_reply_against_the_prompt_before_it() {
+        let history = vec![
+            ChatMessage::system("You are an agent."),
+            ChatMessage::user("What is 2+2?"),
+            ChatMessage::assistant("<tool_call>calculator</tool_call>"),
+            ChatMessage::user("[Tool results]\n4"),
+            ChatMessage::assistant("4"),
+        ];
+        let family = Family::for_model("gpt-4o");
+        let (sent, received) = turn_usage(&history, 2, "gpt-4o");
+        assert_eq!(
+            sent,
+            family.count_messages(&history[..2]) + family.count_messages(&history[..4])
+        );
+        assert_eq!(
+            received,
+            family.count_message(&history[2]) + family.count_message(&history[4])
+        );
+    }
+
+    #[tokio::test]
+    async fn tapped_tools_report_calls_and_results() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let tools: Arc<Vec<Box<dyn Tool>>> = Arc::new(vec![Box::new(CalculatorTool::new())]);
+        let tapped = TappedTool {
+            tools,
+            index: 0,
+            events: tx,
+        };
+        assert_eq!(tapped.name(), "calculator");
+
+        let result = tapped
+            .execute(serde_json::json!({"expression": "6 * 7"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("42"));
+        assert!(matches!(
+            rx.recv().await,
+            Some(TuiEvent::ToolStarted { tool, .. }) if tool == "calculator"
+        ));
+        assert!(matches!(
+            rx.recv().await,
+            Some(TuiEvent::ToolFinished { success: true, output, .. }) if output.contains("42")
+        ));
+    }
+}
@@ -0,0 +1,84 @@
+//! Glob matching for workspace paths, shared by the path policy and the
+//! file-watch triggers.
+
+/// Match a workspace-relative, `/`-separated path against a glob. `*` and `?`
+/// stay within one path segment and `**` spans any number of segments. A
+/// pattern without `/` matches the file name in any directory, as in
+/// `.gitignore`.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if !pattern.contains('/') {
+        return path
+            .last()
+            .is_some_and(|name| segment_matches(pattern, name));
+    }
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    segments_match(&pattern, &path)
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, tail)| {
+            segment_matches(segment, name) && segments_match(rest, tail)
+        }),
+    }
+}
+
+/// Wildcard match of a single segment (`*` any run, `?` one character).
+fn segment_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it was tried against
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` swallow one more character
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_wildcards() {
+        assert!(matches("src/*.rs", "src/main.rs"));
+        assert!(!matches("src/*.rs", "src/tools/mod.rs"));
+        assert!(matches("notes/day-??.md", "notes/day-07.md"));
+        assert!(!matches("notes/day-??.md", "notes/day-7.md"));
+        assert!(matches("a*b*c", "aXXbYYc"));
+        assert!(!matches("a*b*c", "aXXbYY"));
+    }
+
+    #[test]
+    fn double_star_spans_directories() {
+        assert!(matches("docs/**/*.md", "docs/a.md"));
+        assert!(matches("docs/**/*.md", "docs/guide/setup/b.md"));
+        assert!(!matches("docs/**/*.md", "src/a.md"));
+        assert!(matches("**/Cargo.toml", "Cargo.toml"));
+        assert!(matches("src/**", "src/x/y.rs"));
+    }
+
+    #[test]
+    fn pattern_without_slash_matches_file_name_anywhere() {
+        assert!(matches("*.rs", "src/tools/mod.rs"));
+        assert!(matches("README.md", "README.md"));
+        assert!(!matches("*.rs", "src/main.py"));
+    }
+}
@@ -0,0 +1,187 @@
+//! A directory of JSON records, one `<id>.json` file each, as kept for
+//! sessions, jobs and workflow runs.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+/// A record stored in a [`JsonDir`], under its id.
+pub trait Record: Serialize + DeserializeOwned {
+    fn id(&self) -> &str;
+}
+
+/// File-backed store of `T` records rooted at `dir`. `noun` names a record
+/// in messages, e.g. "session".
+#[derive(Debug)]
+pub struct JsonDir<T> {
+    dir: PathBuf,
+    noun: &'static str,
+    records: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for JsonDir<T> {
+    fn clone(&self) -> Self {
+        Self {
+            dir: self.dir.clone(),
+            noun: self.noun,
+            records: PhantomData,
+        }
+    }
+}
+
+impl<T: Record> JsonDir<T> {
+    pub fn new(dir: PathBuf, noun: &'static str) -> Self {
+        Self {
+            dir,
+            noun,
+            records: PhantomData,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path_for(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    /// Write the record atomically (temp file + rename).
+    pub fn save(&self, record: &T) -> Result<()> {
+        let noun = self.noun;
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {noun} directory: {}", self.dir.display()))?;
+        let json = serde_json::to_string_pretty(record)
+            .with_context(|| format!("Failed to serialize {noun}"))?;
+        let path = self.path_for(record.id());
+        let tmp = self.dir.join(format!(".{}.tmp", record.id()));
+        fs::write(&tmp, json)
+            .with_context(|| format!("Failed to write {noun} file: {}", tmp.display()))?;
+        fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to persist {noun} file: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Load a record by full id or unambiguous id prefix.
+    pub fn load(&self, id: &str) -> Result<T> {
+        let id = self.resolve_id(id)?;
+        let path = self.path_for(&id);
+        let noun = self.noun;
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {noun} file: {}", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse {noun} file: {}", path.display()))
+    }
+
+    /// The full id of the record `id` names, by itself or as a prefix.
+    pub fn resolve_id(&self, id: &str) -> Result<String> {
+        let id = id.trim();
+        let noun = self.noun;
+        if id.is_empty() || id.contains(['/', '\\', '.']) {
+            anyhow::bail!("Invalid {noun} id: {id:?}");
+        }
+        if self.path_for(id).is_file() {
+            return Ok(id.to_string());
+        }
+
+        let matches: Vec<String> = self
+            .load_all()?
+            .into_iter()
+            .map(|record| record.id().to_string())
+            .filter(|candidate| candidate.starts_with(id))
+            .collect();
+        match matches.as_slice() {
+            [single] => Ok(single.clone()),
+            [] => anyhow::bail!("{} not found: {id}", capitalized(noun)),
+            _ => anyhow::bail!(
+                "{} id prefix '{id}' is ambiguous ({} matches)",
+                capitalized(noun),
+                matches.len()
+            ),
+        }
+    }
+
+    /// Every readable record, in no particular order. Files that do not
+    /// parse are skipped.
+    pub fn load_all(&self) -> Result<Vec<T>> {
+        let mut records = Vec::new();
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(records),
+            Err(e) => return Err(e.into()),
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(raw) = fs::read_to_string(&path) else {
+                continue;
+            };
+            match serde_json::from_str::<T>(&raw) {
+                Ok(record) => records.push(record),
+                Err(e) => {
+                    tracing::debug!("Skipping unreadable {} {}: {e}", self.noun, path.display());
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
+fn capitalized(noun: &str) -> String {
+    let mut chars = noun.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Note {
+        id: String,
+        text: String,
+    }
+
+    impl Record for Note {
+        fn id(&self) -> &str {
+            &self.id
+        }
+    }
+
+    fn note(id: &str) -> Note {
+        Note {
+            id: id.into(),
+            text: format!("note {id}"),
+        }
+    }
+
+    #[test]
+    fn records_round_trip_and_resolve_by_prefix() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = JsonDir::<Note>::new(tmp.path().join("notes"), "note");
+        assert!(store.load_all().unwrap().is_empty());
+
+        for id in ["abc123", "abd456"] {
+            store.save(&note(id)).unwrap();
+        }
+        std::fs::write(store.dir().join("broken.json"), "{").unwrap();
+        assert_eq!(store.load_all().unwrap().len(), 2);
+        assert_eq!(store.load("abc").unwrap(), note("abc123"));
+        assert_eq!(store.load(" abd456 ").unwrap(), note("abd456"));
+
+        let ambiguous = store.load("ab").unwrap_err().to_string();
+        assert!(ambiguous.contains("Note id prefix 'ab' is ambiguous"), "{ambiguous}");
+        let missing = store.load("zzz").unwrap_err().to_string();
+        assert_eq!(missing, "Note not found: zzz");
+        assert!(store.load("../abc123").is_err());
+    }
+}
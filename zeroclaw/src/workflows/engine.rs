@@ -0,0 +1,865 @@
+//! Runs workflow steps: wave by wave, with conditions and retries.
+
+use super::{
+    ApprovalRequest, ApprovalStep, Condition, Decision, RunStatus, RunStore, Step, StepKind,
+    StepRecord, StepStatus, Workflow, WorkflowRun,
+};
+use crate::agent::loop_;
+use crate::channels::build_system_prompt;
+use crate::config::Config;
+use crate::memory::{self, Memory};
+use crate::observability::{self, Observer, ObserverEvent};
+use crate::prompts::render_template;
+use crate::providers::{self, ChatMessage, Provider};
+use crate::runtime::{self, RuntimeAdapter};
+use crate::security::SecurityPolicy;
+use crate::tools::{self, Tool};
+use anyhow::Result;
+use chrono::Utc;
+use futures_util::future::join_all;
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Wait before retry `n` is `n` times this.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// How often the daemon checks workflow schedules and approval deadlines.
+const SCHEDULE_POLL_SECS: u64 = 30;
+
+/// Serializes approval decisions, so an answer and a timeout cannot both
+/// resume the same run.
+static CLAIM: Mutex<()> = Mutex::new(());
+
+/// Where `[workflows] dir` points, relative to the workspace unless absolute.
+pub fn workflows_dir(config: &Config) -> PathBuf {
+    let dir = Path::new(&config.workflows.dir);
+    if dir.is_absolute() {
+        dir.to_path_buf()
+    } else {
+        config.workspace_dir.join(dir)
+    }
+}
+
+/// Everything steps run with: the provider, a tool registry per step, and
+/// the workflow directory.
+pub struct WorkflowEngine {
+    config: Config,
+    provider: Arc<dyn Provider>,
+    mem: Arc<dyn Memory>,
+    runtime: Arc<dyn RuntimeAdapter>,
+    security: Arc<SecurityPolicy>,
+    observer: Arc<dyn Observer>,
+    model: String,
+    dir: PathBuf,
+    runs: RunStore,
+}
+
+impl WorkflowEngine {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let provider = providers::create_resilient_provider(
+            config.default_provider.as_deref().unwrap_or("openrouter"),
+            config.api_key.as_deref(),
+            &config.reliability,
+            &providers::ProviderSettings::from_config(config),
+        )?;
+        Self::with_provider(config, Arc::from(provider))
+    }
+
+    pub fn with_provider(config: &Config, provider: Arc<dyn Provider>) -> Result<Self> {
+        let workspace = &config.workspace_dir;
+        Ok(Self {
+            mem: Arc::from(memory::create_memory(
+                &config.memory,
+                workspace,
+                config.api_key.as_deref(),
+            )?),
+            runtime: Arc::from(runtime::create_runtime(&config.runtime)?),
+            security: Arc::new(SecurityPolicy::from_config(&config.autonomy, workspace)),
+            observer: Arc::from(observability::create_observer(&config.observability)),
+            model: config
+                .default_model
+                .clone()
+                .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into()),
+            dir: workflows_dir(config),
+            runs: RunStore::new(workspace),
+            config: config.clone(),
+            provider,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn list(&self) -> Vec<Workflow> {
+        super::list(&self.dir)
+    }
+
+    pub fn load(&self, name: &str) -> Result<Workflow> {
+        super::load(&self.dir, name)
+    }
+
+    pub fn runs(&self) -> &RunStore {
+        &self.runs
+    }
+
+    /// A fresh registry, narrowed to `allowed`. Workflows cannot start
+    /// other workflows.
+    fn tools(&self, allowed: Option<&[String]>) -> Vec<Box<dyn Tool>> {
+        let composio_key = if self.config.composio.enabled {
+            self.config.composio.api_key.as_deref()
+        } else {
+            None
+        };
+        let mut tools = tools::all_tools_with_runtime(
+            &self.security,
+            self.runtime.clone(),
+            self.mem.clone(),
+            composio_key,
+            &self.config.browser,
+        );
+        tools.extend(tools::optional_tools(&self.config, &self.security));
+        tools::route_to_nodes(&mut tools, &self.config.nodes, &self.security);
+        if self.config.knowledge.enabled && crate::knowledge::supported(self.mem.as_ref()) {
+            tools.push(Box::new(tools::KbSearchTool::new(self.mem.clone())) as Box<dyn Tool>);
+        }
+        tools.retain(|t| {
+            t.name() != "run_workflow"
+                && allowed.is_none_or(|names| names.iter().any(|n| n == t.name()))
+        });
+        tools
+    }
+
+    /// Start `workflow` and run it until it finishes or pauses for
+    /// approval. Never fails as a whole: the outcome of every step is in
+    /// the returned run.
+    pub async fn run(&self, workflow: &Workflow, input: &str) -> WorkflowRun {
+        let mut run = WorkflowRun::new(workflow, input);
+        tracing::info!("Workflow {} started", workflow.name);
+        self.advance(&mut run).await;
+        run
+    }
+
+    /// Continue a run whose approvals were settled by [`Self::decide`] or
+    /// [`Self::expire_approvals`].
+    pub async fn resume(&self, mut run: WorkflowRun) -> WorkflowRun {
+        tracing::info!("Workflow {} resumed", run.workflow);
+        self.advance(&mut run).await;
+        run
+    }
+
+    /// Run the steps still pending, wave by wave, saving the run as it
+    /// goes. Stops early when a wave leaves approval steps waiting.
+    async fn advance(&self, run: &mut WorkflowRun) {
+        let workflow = run.definition.clone();
+        let waves = match workflow.validate().and_then(|()| workflow.waves()) {
+            Ok(waves) => waves,
+            Err(e) => {
+                run.error = Some(format!("{e:#}"));
+                self.finish(run, RunStatus::Failed);
+                return;
+            }
+        };
+        self.save(run);
+
+        for wave in waves {
+            let vars = template_vars(run);
+            let mut runnable = Vec::new();
+            for i in wave {
+                if run.steps[i].status != StepStatus::Pending {
+                    continue;
+                }
+                let step = &workflow.steps[i];
+                if !should_run(step, run) {
+                    run.steps[i].status = StepStatus::Skipped;
+                } else if let Some(approval) = &step.approval {
+                    match self.request_approval(approval, &vars) {
+                        Ok(request) => {
+                            run.steps[i].status = StepStatus::AwaitingApproval;
+                            run.steps[i].approval = Some(request);
+                        }
+                        Err(e) => {
+                            run.steps[i].status = StepStatus::Failed;
+                            run.steps[i].error = Some(format!("{e:#}"));
+                        }
+                    }
+                } else {
+                    runnable.push(i);
+                }
+            }
+            let outcomes = join_all(
+                runnable
+                    .iter()
+                    .map(|&i| self.run_with_retries(&workflow.steps[i], &vars)),
+            )
+            .await;
+            for (i, (outcome, attempts)) in runnable.into_iter().zip(outcomes) {
+                let record = &mut run.steps[i];
+                record.attempts = attempts;
+                match outcome {
+                    Ok(output) => {
+                        record.status = StepStatus::Succeeded;
+                        record.output = Some(output);
+                    }
+                    Err(e) => {
+                        record.status = StepStatus::Failed;
+                        record.error = Some(format!("{e:#}"));
+                    }
+                }
+            }
+
+            if run
+                .steps
+                .iter()
+                .any(|s| s.status == StepStatus::AwaitingApproval)
+            {
+                run.status = RunStatus::Paused;
+                self.save(run);
+                tracing::info!("{}", run.summary());
+                self.notify_approvers(run).await;
+                return;
+            }
+            self.save(run);
+        }
+
+        let status = if run.steps.iter().any(|s| s.status == StepStatus::Failed) {
+            RunStatus::Failed
+        } else {
+            RunStatus::Succeeded
+        };
+        self.finish(run, status);
+    }
+
+    fn finish(&self, run: &mut WorkflowRun, status: RunStatus) {
+        run.status = status;
+        run.finished_at = Some(Utc::now());
+        self.save(run);
+        tracing::info!("{}", run.summary());
+    }
+
+    fn save(&self, run: &WorkflowRun) {
+        if let Err(e) = self.runs.save(run) {
+            tracing::warn!("Could not save workflow run {}: {e:#}", run.short_id());
+        }
+    }
+
+    /// The question an approval step asks, with the configured defaults.
+    fn request_approval(&self, approval: &ApprovalStep, vars: &Value) -> Result<ApprovalRequest> {
+        let settings = &self.config.workflows;
+        let minutes = approval
+            .timeout_minutes
+            .unwrap_or(settings.approval_timeout_minutes);
+        let now = Utc::now();
+        Ok(ApprovalRequest {
+            message: render(&approval.message, vars)?,
+            channel: approval
+                .channel
+                .clone()
+                .or_else(|| settings.approval_channel.clone()),
+            recipient: approval
+                .recipient
+                .clone()
+                .or_else(|| settings.approval_recipient.clone()),
+            requested_at: now,
+            deadline: now + chrono::Duration::minutes(i64::try_from(minutes).unwrap_or(i64::MAX)),
+            on_timeout: approval.on_timeout,
+        })
+    }
+
+    /// Ask on each waiting step's channel. Without one, the run can still
+    /// be decided over the gateway.
+    async fn notify_approvers(&self, run: &WorkflowRun) {
+        for step in &run.steps {
+            let Some(request) = step.approval.as_ref() else {
+                continue;
+            };
+            if step.status != StepStatus::AwaitingApproval {
+                continue;
+            }
+            let (Some(name), Some(recipient)) = (&request.channel, &request.recipient) else {
+                tracing::warn!(
+                    "Workflow {} waits for approval of '{}' but has no channel to ask on; \
+                     use POST /workflows/runs/{}/approve",
+                    run.workflow,
+                    step.id,
+                    run.short_id()
+                );
+                continue;
+            };
+            let Some(channel) = crate::channels::configured_channel(&self.config, name) else {
+                tracing::warn!("Workflow approval channel {name} is not configured");
+                continue;
+            };
+            let message = format!(
+                "⏸️ Workflow {} needs approval ({}):\n{}\n\nReply \"approve {}\" or \"deny {}\". \
+                 Without an answer by {} UTC it is {}.",
+                run.workflow,
+                step.id,
+                request.message,
+                run.short_id(),
+                run.short_id(),
+                request.deadline.format("%Y-%m-%d %H:%M"),
+                match request.on_timeout {
+                    Decision::Approve => "approved",
+                    Decision::Deny => "denied",
+                }
+            );
+            if let Err(e) = channel.send(&message, recipient).await {
+                tracing::warn!("Could not ask for workflow approval on {name}: {e}");
+            }
+        }
+    }
+
+    /// Approve or deny the waiting steps of paused run `id`. `from` is the
+    /// (channel, sender) a chat answer came from, which must be the one
+    /// that was asked; `None` for the gateway. Returns the run, ready for
+    /// [`Self::resume`] unless other approvals still wait.
+    pub fn decide(
+        &self,
+        id: &str,
+        decision: Decision,
+        from: Option<(&str, &str)>,
+    ) -> Result<WorkflowRun> {
+        let _claim = CLAIM
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut run = self.runs.load(id)?;
+        if run.status != RunStatus::Paused {
+            anyhow::bail!(
+                "Workflow run {} is not waiting for approval",
+                run.short_id()
+            );
+        }
+        let by = from.map_or_else(
+            || "gateway".to_string(),
+            |(ch, who)| format!("{who} on {ch}"),
+        );
+        let mut settled = 0;
+        for step in &mut run.steps {
+            let asked = match (&step.approval, from) {
+                (Some(request), Some((channel, sender))) => {
+                    request.channel.as_deref() == Some(channel)
+                        && request.recipient.as_deref() == Some(sender)
+                }
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            if step.status == StepStatus::AwaitingApproval && asked {
+                settle(step, decision, &by);
+                settled += 1;
+            }
+        }
+        if settled == 0 {
+            anyhow::bail!(
+                "Workflow run {} did not ask you for approval",
+                run.short_id()
+            );
+        }
+        self.claim_if_settled(&mut run);
+        Ok(run)
+    }
+
+    /// Take the timeout decision on approvals past their deadline. Returns
+    /// the runs that can be resumed.
+    pub fn expire_approvals(&self) -> Result<Vec<WorkflowRun>> {
+        let _claim = CLAIM
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = Utc::now();
+        let mut ready = Vec::new();
+        for mut run in self.runs.paused()? {
+            let mut expired = false;
+            for step in &mut run.steps {
+                let Some(on_timeout) = step
+                    .approval
+                    .as_ref()
+                    .filter(|r| r.deadline <= now)
+                    .map(|r| r.on_timeout)
+                else {
+                    continue;
+                };
+                if step.status == StepStatus::AwaitingApproval {
+                    settle(step, on_timeout, "timeout");
+                    expired = true;
+                }
+            }
+            if expired && self.claim_if_settled(&mut run) {
+                ready.push(run);
+            }
+        }
+        Ok(ready)
+    }
+
+    /// Mark `run` running once no approval waits; saves it either way.
+    fn claim_if_settled(&self, run: &mut WorkflowRun) -> bool {
+        let settled = !run
+            .steps
+            .iter()
+            .any(|s| s.status == StepStatus::AwaitingApproval);
+        if settled {
+            run.status = RunStatus::Running;
+        }
+        self.save(run);
+        settled
+    }
+
+    /// Fail runs a stopped daemon left mid-step; their steps may have had
+    /// side effects, so they are not run again. Paused runs are kept.
+    pub fn recover_interrupted(&self) -> Result<usize> {
+        let mut count = 0;
+        for mut run in self.runs.list()? {
+            if run.status == RunStatus::Running {
+                run.error = Some("Interrupted by a restart".into());
+                self.finish(&mut run, RunStatus::Failed);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Run `step`, retrying failures. Returns the outcome and attempts made.
+    async fn run_with_retries(&self, step: &Step, vars: &Value) -> (Result<String>, u32) {
+        let mut attempt = 1;
+        loop {
+            let outcome = self.run_step(step, vars).await;
+            if outcome.is_ok() || attempt > step.retries {
+                return (outcome, attempt);
+            }
+            if let Err(e) = &outcome {
+                tracing::warn!("Workflow step {} failed, retrying: {e:#}", step.id);
+            }
+            tokio::time::sleep(RETRY_DELAY * attempt).await;
+            attempt += 1;
+        }
+    }
+
+    async fn run_step(&self, step: &Step, vars: &Value) -> Result<String> {
+        match step.kind()? {
+            StepKind::Prompt(prompt) => {
+                let prompt = render(prompt, vars)?;
+                self.provider
+                    .chat_with_system(None, &prompt, &self.model, self.config.default_temperature)
+                    .await
+            }
+            StepKind::Tool(name, args) => {
+                let args = render_value(args.cloned().unwrap_or_else(|| json!({})), vars)?;
+                let tools = self.tools(None);
+                let tool = tools
+                    .iter()
+                    .find(|t| t.name() == name)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown tool: {name}"))?;
+                let started = Instant::now();
+                let result = tool.execute(args).await?;
+                self.observer.record_event(&ObserverEvent::ToolCall {
+                    tool: name.to_string(),
+                    duration: started.elapsed(),
+                    success: result.success,
+                });
+                if result.success {
+                    Ok(result.output)
+                } else {
+                    anyhow::bail!(
+                        "{}",
+                        result.error.unwrap_or_else(|| format!("{name} failed"))
+                    )
+                }
+            }
+            StepKind::Agent(agent) => {
+                let prompt = render(&agent.prompt, vars)?;
+                let model = agent.model.as_deref().unwrap_or(&self.model);
+                let tools = self.tools(agent.tools.as_deref());
+                let workspace = &self.config.workspace_dir;
+                let tool_descs = loop_::tool_descriptions(&self.config, &tools);
+                let mut system_prompt = build_system_prompt(
+                    workspace,
+                    model,
+                    &tool_descs,
+                    &crate::skills::load_skills(workspace),
+                    Some(&crate::identity::readable_by(
+                        self.config.identity.for_channel("workflows"),
+                        &self.security,
+                    )),
+                );
+                system_prompt.push_str(&loop_::build_tool_instructions_with(
+                    &tools,
+                    self.config.tool_schema_for(model),
+                ));
+                let mut history = vec![
+                    ChatMessage::system(system_prompt),
+                    ChatMessage::user(prompt),
+                ];
+                crate::agent::settings::scope(
+                    &self.config,
+                    crate::hooks::scope(
+                        &self.config,
+                        loop_::agent_turn_with_budget(
+                            self.provider.as_ref(),
+                            &mut history,
+                            &tools,
+                            self.observer.as_ref(),
+                            model,
+                            self.config.default_temperature,
+                            None,
+                            self.config.turn_budget_for(model),
+                        ),
+                    ),
+                )
+                .await?
+                .into_result()
+            }
+            StepKind::Approval(_) => anyhow::bail!("Approval steps are not run directly"),
+        }
+    }
+}
+
+fn settle(step: &mut StepRecord, decision: Decision, by: &str) {
+    match decision {
+        Decision::Approve => {
+            step.status = StepStatus::Succeeded;
+            step.output = Some(format!("Approved by {by}"));
+        }
+        Decision::Deny => {
+            step.status = StepStatus::Failed;
+            step.error = Some(format!("Denied by {by}"));
+        }
+    }
+}
+
+/// Read a chat answer to an approval request: "approve <run>" or
+/// "deny <run>", with the run id or its first characters.
+pub fn parse_decision(text: &str) -> Option<(Decision, &str)> {
+    let mut words = text.split_whitespace();
+    let decision = match words.next()?.to_lowercase().as_str() {
+        "approve" | "approved" => Decision::Approve,
+        "deny" | "denied" | "reject" => Decision::Deny,
+        _ => return None,
+    };
+    let id = words.next()?;
+    if words.next().is_some()
+        || id.len() < 4
+        || !id.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+    {
+        return None;
+    }
+    Some((decision, id))
+}
+
+/// Whether `step` runs, given its finished dependencies.
+fn should_run(step: &Step, run: &WorkflowRun) -> bool {
+    match &step.when {
+        Some(when) => condition_holds(when, run),
+        None => step.depends_on.iter().all(|dep| {
+            run.step(dep)
+                .is_some_and(|s| s.status == StepStatus::Succeeded)
+        }),
+    }
+}
+
+fn condition_holds(when: &Condition, run: &WorkflowRun) -> bool {
+    let Some(record) = run.step(&when.step) else {
+        return false;
+    };
+    if !matches!(record.status, StepStatus::Succeeded | StepStatus::Failed) {
+        return false;
+    }
+    if let Some(succeeded) = when.succeeded {
+        if (record.status == StepStatus::Succeeded) != succeeded {
+            return false;
+        }
+    }
+    when.contains.as_ref().is_none_or(|text| {
+        record
+            .output
+            .as_deref()
+            .is_some_and(|output| output.contains(text.as_str()))
+    })
+}
+
+/// `{{input}}` and `{{steps.<id>.output}}` / `.status` / `.error`.
+fn template_vars(run: &WorkflowRun) -> Value {
+    let steps: Map<String, Value> = run
+        .steps
+        .iter()
+        .map(|s| {
+            (
+                s.id.clone(),
+                json!({
+                    "output": s.output.clone().unwrap_or_default(),
+                    "status": s.status,
+                    "error": s.error.clone().unwrap_or_default(),
+                }),
+            )
+        })
+        .collect();
+    json!({"input": run.input, "steps": steps})
+}
+
+fn render(template: &str, vars: &Value) -> Result<String> {
+    render_template(template, vars).map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+/// Render every string inside `value`.
+fn render_value(value: Value, vars: &Value) -> Result<Value> {
+    Ok(match value {
+        Value::String(s) => Value::String(render(&s, vars)?),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|v| render_value(v, vars))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| Ok((k, render_value(v, vars)?)))
+                .collect::<Result<_>>()?,
+        ),
+        other => other,
+    })
+}
+
+/// Scheduled workflow loop, supervised by the daemon. Runs each workflow
+/// whose `schedule` came due since the last check.
+pub async fn run_scheduler(config: Config) -> Result<()> {
+    let engine = Arc::new(WorkflowEngine::from_config(&config)?);
+    let failed = engine.recover_interrupted()?;
+    if failed > 0 {
+        tracing::warn!("{failed} workflow run(s) were interrupted by a restart and marked failed");
+    }
+    let mut last_check = Utc::now();
+    let mut interval = tokio::time::interval(Duration::from_secs(SCHEDULE_POLL_SECS));
+
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+        crate::health::mark_component_ok("workflows");
+        if crate::daemon::shutdown::is_shutting_down() {
+            continue;
+        }
+        for run in engine.expire_approvals()? {
+            let engine = engine.clone();
+            tokio::spawn(async move { engine.resume(run).await });
+        }
+        for workflow in engine.list() {
+            let Some(schedule) = workflow.schedule.as_deref() else {
+                continue;
+            };
+            let due = crate::cron::next_run_for(schedule, last_check).is_ok_and(|next| next <= now);
+            if due {
+                let engine = engine.clone();
+                tokio::spawn(async move {
+                    let run = engine.run(&workflow, "").await;
+                    if run.status == RunStatus::Failed {
+                        crate::health::mark_component_error("workflows", run.summary());
+                    }
+                });
+            }
+        }
+        last_check = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::mock::ScriptedProvider;
+
+    /// Echoes prompts back.
+    fn echo() -> Arc<ScriptedProvider> {
+        Arc::new(ScriptedProvider::answering(|messages| {
+            format!("echo: {}", messages[messages.len() - 1].content)
+        }))
+    }
+
+    fn engine(tmp: &Path, provider: Arc<ScriptedProvider>) -> WorkflowEngine {
+        let config = Config {
+            workspace_dir: tmp.to_path_buf(),
+            memory: crate::config::MemoryConfig {
+                backend: "none".into(),
+                ..crate::config::MemoryConfig::default()
+            },
+            ..Config::default()
+        };
+        WorkflowEngine::with_provider(&config, provider).unwrap()
+    }
+
+    fn workflow(raw: &str) -> Workflow {
+        let mut workflow: Workflow = toml::from_str(raw).unwrap();
+        workflow.name = "test".into();
+        workflow
+    }
+
+    #[tokio::test]
+    async fn steps_pass_outputs_along_and_branch_on_conditions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let provider = echo();
+        let engine = engine(tmp.path(), provider.clone());
+        let wf = workflow(
+            r#"
+[[steps]]
+id = "sum"
+tool = "calculator"
+args = { expression = "{{input}} * 2" }
+
+[[steps]]
+id = "explain"
+depends_on = ["sum"]
+prompt = "Explain {{steps.sum.output}}"
+
+[[steps]]
+id = "on_failure"
+depends_on = ["sum"]
+when = { step = "sum", succeeded = false }
+prompt = "Report the failure"
+"#,
+        );
+
+        let run = engine.run(&wf, "21").await;
+        assert_eq!(run.status, RunStatus::Succeeded, "{}", run.summary());
+        let sum = run.step("sum").unwrap().output.clone().unwrap();
+        assert!(sum.contains("42"), "{sum}");
+        assert_eq!(run.step("on_failure").unwrap().status, StepStatus::Skipped);
+        assert_eq!(provider.requests()[0].last(), format!("Explain {sum}"));
+        assert_eq!(provider.calls(), 1);
+        assert_eq!(
+            run.final_output(),
+            Some(format!("echo: Explain {sum}").as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_steps_are_retried_and_skip_their_dependents() {
+        let tmp = tempfile::tempdir().unwrap();
+        let provider = echo();
+        let engine = engine(tmp.path(), provider.clone());
+        let wf = workflow(
+            r#"
+[[steps]]
+id = "divide"
+retries = 1
+tool = "calculator"
+args = { expression = "1 / 0" }
+
+[[steps]]
+id = "explain"
+depends_on = ["divide"]
+prompt = "Explain {{steps.divide.output}}"
+
+[[steps]]
+id = "report"
+depends_on = ["divide"]
+when = { step = "divide", succeeded = false }
+prompt = "It failed: {{steps.divide.error}}"
+"#,
+        );
+
+        let run = engine.run(&wf, "").await;
+        assert_eq!(run.status, RunStatus::Failed);
+        let divide = run.step("divide").unwrap();
+        assert_eq!(divide.status, StepStatus::Failed);
+        assert_eq!(divide.attempts, 2);
+        assert_eq!(run.step("explain").unwrap().status, StepStatus::Skipped);
+        assert_eq!(run.step("report").unwrap().status, StepStatus::Succeeded);
+        assert_eq!(provider.calls(), 1);
+    }
+
+    const GATED: &str = r#"
+[[steps]]
+id = "draft"
+prompt = "Draft {{input}}"
+
+[[steps]]
+id = "ok"
+depends_on = ["draft"]
+approval = { message = "Ship {{steps.draft.output}}?" }
+
+[[steps]]
+id = "ship"
+depends_on = ["ok"]
+prompt = "Ship it"
+"#;
+
+    #[tokio::test]
+    async fn approval_steps_pause_the_run_until_decided() {
+        let tmp = tempfile::tempdir().unwrap();
+        let provider = echo();
+        let wf = workflow(GATED);
+
+        let run = engine(tmp.path(), provider.clone()).run(&wf, "v2").await;
+        assert_eq!(run.status, RunStatus::Paused);
+        let ok = run.step("ok").unwrap();
+        assert_eq!(ok.status, StepStatus::AwaitingApproval);
+        assert_eq!(
+            ok.approval.as_ref().unwrap().message,
+            "Ship echo: Draft v2?"
+        );
+
+        // A restarted daemon finds the paused run and resumes it on approval
+        let restarted = engine(tmp.path(), provider.clone());
+        assert_eq!(restarted.recover_interrupted().unwrap(), 0);
+        let id = run.short_id();
+        assert!(restarted
+            .decide(id, Decision::Approve, Some(("telegram", "42")))
+            .is_err());
+        let decided = restarted.decide(id, Decision::Approve, None).unwrap();
+        assert_eq!(decided.status, RunStatus::Running);
+        assert!(restarted.decide(id, Decision::Deny, None).is_err());
+        let done = restarted.resume(decided).await;
+        assert_eq!(done.status, RunStatus::Succeeded, "{}", done.summary());
+        assert_eq!(done.final_output(), Some("echo: Ship it"));
+        assert_eq!(provider.calls(), 2);
+
+        let second = restarted.run(&wf, "v3").await;
+        let denied = restarted.decide(&second.id, Decision::Deny, None).unwrap();
+        let done = restarted.resume(denied).await;
+        assert_eq!(done.status, RunStatus::Failed);
+        assert_eq!(done.step("ship").unwrap().status, StepStatus::Skipped);
+        assert_eq!(
+            restarted.runs().load(&second.id).unwrap().status,
+            RunStatus::Failed
+        );
+    }
+
+    #[tokio::test]
+    async fn unanswered_approvals_take_their_timeout_decision() {
+        let tmp = tempfile::tempdir().unwrap();
+        let provider = echo();
+        let engine = engine(tmp.path(), provider);
+        let wf = workflow(
+            r#"
+[[steps]]
+id = "ok"
+approval = { message = "Go?", timeout_minutes = 0, on_timeout = "approve" }
+
+[[steps]]
+id = "go"
+depends_on = ["ok"]
+prompt = "Going"
+"#,
+        );
+
+        let paused = engine.run(&wf, "").await;
+        assert_eq!(paused.status, RunStatus::Paused);
+        let ready = engine.expire_approvals().unwrap();
+        assert_eq!(ready.len(), 1);
+        assert_eq!(
+            ready[0].step("ok").unwrap().output.as_deref(),
+            Some("Approved by timeout")
+        );
+        assert!(engine.expire_approvals().unwrap().is_empty());
+        let done = engine.resume(ready.into_iter().next().unwrap()).await;
+        assert_eq!(done.status, RunStatus::Succeeded);
+
+        assert_eq!(
+            parse_decision("Approve 1a2b3c4d"),
+            Some((Decision::Approve, "1a2b3c4d"))
+        );
+        assert_eq!(
+            parse_decision("deny 1a2b3c4d"),
+            Some((Decision::Deny, "1a2b3c4d"))
+        );
+        assert_eq!(parse_decision("approve the plan"), None);
+        assert_eq!(parse_decision("approve 1a2b3c4d please"), None);
+    }
+}
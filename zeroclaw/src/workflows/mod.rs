@@ -0,0 +1,526 @@
+//! Workflows — multi-step pipelines defined in files.
+//!
+//! Each `<workspace>/workflows/<name>.toml` (or `.yaml` / `.yml`) defines a
+//! DAG of steps. A step is a single model call (`prompt`), one tool call
+//! (`tool` + `args`), or a sub-agent with its own tool loop (`agent`).
+//! Steps name the steps they wait for in `depends_on`; steps whose
+//! dependencies are done run side by side. `{{input}}` and
+//! `{{steps.<id>.output}}` in prompts and string arguments are filled in
+//! from the run input and earlier outputs.
+//!
+//! A step runs once its dependencies succeeded, or, with `when`, once its
+//! condition on a dependency holds, so a step can handle another's
+//! failure. Failed steps are retried `retries` times. Workflows run from
+//! the `run_workflow` tool, `POST /workflows/:name/run`, or on their
+//! `schedule` in the daemon.
+//!
+//! An `approval` step pauses the run and asks on a channel; the run goes on
+//! once someone answers "approve <run>" or "deny <run>" there or over the
+//! gateway, or takes the step's `on_timeout` decision when nobody does.
+//! Runs are saved in `<workspace>/state/workflow_runs`, so a paused run
+//! survives a daemon restart.
+
+pub mod engine;
+pub mod runs;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+
+pub use engine::{parse_decision, run_scheduler, WorkflowEngine};
+pub use runs::RunStore;
+
+const EXTENSIONS: [&str; 3] = ["toml", "yaml", "yml"];
+
+/// A workflow file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Workflow {
+    /// File name without extension; set when loaded
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Cron expression (UTC) the daemon runs it on, e.g. "0 9 * * 1"
+    #[serde(default)]
+    pub schedule: Option<String>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    pub id: String,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Run only when this holds, instead of when every dependency succeeded
+    #[serde(default)]
+    pub when: Option<Condition>,
+    /// Extra attempts after a failure
+    #[serde(default)]
+    pub retries: u32,
+    /// One model call, without tools
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// One tool call, with `args`
+    #[serde(default)]
+    pub tool: Option<String>,
+    #[serde(default)]
+    pub args: Option<Value>,
+    /// A sub-agent turn with the tool loop
+    #[serde(default)]
+    pub agent: Option<AgentStep>,
+    /// A pause until someone approves or denies
+    #[serde(default)]
+    pub approval: Option<ApprovalStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentStep {
+    pub prompt: String,
+    /// Tools the sub-agent may use; unset allows all
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+    /// Model for this step (defaults to `default_model`)
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalStep {
+    /// What is asked; `{{steps.<id>.output}}` works here too
+    pub message: String,
+    /// Channel to ask on (defaults to `[workflows] approval_channel`)
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Who is asked there (defaults to `[workflows] approval_recipient`)
+    #[serde(default)]
+    pub recipient: Option<String>,
+    /// Minutes to wait (defaults to `[workflows] approval_timeout_minutes`)
+    #[serde(default)]
+    pub timeout_minutes: Option<u64>,
+    /// Decision taken when nobody answers in time
+    #[serde(default)]
+    pub on_timeout: Decision,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    Approve,
+    #[default]
+    Deny,
+}
+
+/// Condition on a dependency's outcome.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Condition {
+    /// A step listed in `depends_on`
+    pub step: String,
+    /// Whether that step must have succeeded (true) or failed (false)
+    #[serde(default)]
+    pub succeeded: Option<bool>,
+    /// Text its output must contain
+    #[serde(default)]
+    pub contains: Option<String>,
+}
+
+/// What a step does, once validated.
+#[derive(Debug, Clone, Copy)]
+pub enum StepKind<'a> {
+    Prompt(&'a str),
+    Tool(&'a str, Option<&'a Value>),
+    Agent(&'a AgentStep),
+    Approval(&'a ApprovalStep),
+}
+
+impl Step {
+    pub fn kind(&self) -> Result<StepKind<'_>> {
+        match (&self.prompt, &self.tool, &self.agent, &self.approval) {
+            (Some(prompt), None, None, None) => Ok(StepKind::Prompt(prompt)),
+            (None, Some(tool), None, None) => Ok(StepKind::Tool(tool, self.args.as_ref())),
+            (None, None, Some(agent), None) => Ok(StepKind::Agent(agent)),
+            (None, None, None, Some(approval)) => Ok(StepKind::Approval(approval)),
+            _ => anyhow::bail!(
+                "Step '{}' needs exactly one of prompt, tool, agent or approval",
+                self.id
+            ),
+        }
+    }
+}
+
+impl Workflow {
+    /// Parse a workflow file; the format follows the extension.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workflow: {}", path.display()))?;
+        let mut workflow: Workflow = match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => serde_yaml::from_str(&raw)
+                .with_context(|| format!("Invalid workflow YAML: {}", path.display()))?,
+            _ => toml::from_str(&raw)
+                .with_context(|| format!("Invalid workflow TOML: {}", path.display()))?,
+        };
+        workflow.name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        workflow
+            .validate()
+            .with_context(|| format!("Invalid workflow: {}", path.display()))?;
+        Ok(workflow)
+    }
+
+    /// Check step ids, dependencies, conditions and the schedule.
+    pub fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            anyhow::bail!("A workflow needs at least one step");
+        }
+        let mut ids = HashSet::new();
+        for step in &self.steps {
+            if step.id.trim().is_empty() || step.id.contains(['.', '{', '}']) {
+                anyhow::bail!("Invalid step id {:?}", step.id);
+            }
+            if !ids.insert(step.id.as_str()) {
+                anyhow::bail!("Duplicate step id '{}'", step.id);
+            }
+            step.kind()?;
+        }
+        for step in &self.steps {
+            for dep in &step.depends_on {
+                if dep == &step.id || !ids.contains(dep.as_str()) {
+                    anyhow::bail!("Step '{}' depends on unknown step '{dep}'", step.id);
+                }
+            }
+            if let Some(when) = &step.when {
+                if !step.depends_on.contains(&when.step) {
+                    anyhow::bail!(
+                        "Step '{}' has a condition on '{}', which it does not depend on",
+                        step.id,
+                        when.step
+                    );
+                }
+            }
+        }
+        if let Some(schedule) = &self.schedule {
+            crate::cron::next_run_for(schedule, Utc::now())?;
+        }
+        self.waves().map(|_| ())
+    }
+
+    /// Step indices grouped so each group only depends on earlier ones.
+    pub fn waves(&self) -> Result<Vec<Vec<usize>>> {
+        let index: HashMap<&str, usize> = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.id.as_str(), i))
+            .collect();
+        let mut done = vec![false; self.steps.len()];
+        let mut waves = Vec::new();
+        while done.iter().any(|d| !d) {
+            let wave: Vec<usize> = (0..self.steps.len())
+                .filter(|&i| !done[i])
+                .filter(|&i| {
+                    self.steps[i]
+                        .depends_on
+                        .iter()
+                        .all(|dep| index.get(dep.as_str()).is_some_and(|&d| done[d]))
+                })
+                .collect();
+            if wave.is_empty() {
+                anyhow::bail!("The steps' dependencies form a cycle");
+            }
+            for &i in &wave {
+                done[i] = true;
+            }
+            waves.push(wave);
+        }
+        Ok(waves)
+    }
+}
+
+/// Every valid workflow in `dir`, by name. Invalid files are logged and
+/// left out.
+pub fn list(dir: &Path) -> Vec<Workflow> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut workflows: Vec<Workflow> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| EXTENSIONS.contains(&e))
+        })
+        .filter_map(|path| match Workflow::from_file(&path) {
+            Ok(workflow) => Some(workflow),
+            Err(e) => {
+                tracing::warn!("Skipping workflow: {e:#}");
+                None
+            }
+        })
+        .collect();
+    workflows.sort_by(|a, b| a.name.cmp(&b.name));
+    workflows
+}
+
+/// Load workflow `name` from `dir`.
+pub fn load(dir: &Path, name: &str) -> Result<Workflow> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!("Invalid workflow name '{name}' (use letters, digits, '-' and '_')");
+    }
+    EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{name}.{ext}")))
+        .find(|path| path.is_file())
+        .map_or_else(
+            || anyhow::bail!("Workflow not found: {name}"),
+            |path| Workflow::from_file(&path),
+        )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    AwaitingApproval,
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+/// The outcome of one step in a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepRecord {
+    pub id: String,
+    pub status: StepStatus,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Set on approval steps once they ask
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalRequest>,
+}
+
+/// An approval step's question, as asked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+    pub on_timeout: Decision,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    /// Waiting on approval steps
+    Paused,
+    Succeeded,
+    Failed,
+}
+
+/// One execution of a workflow.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    pub id: String,
+    pub workflow: String,
+    pub status: RunStatus,
+    #[serde(default)]
+    pub input: String,
+    pub started_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub steps: Vec<StepRecord>,
+    /// Why the run could not start, e.g. an invalid definition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The workflow as it was when the run started; a paused run resumes
+    /// with it even if the file changed since
+    pub definition: Workflow,
+}
+
+impl WorkflowRun {
+    pub fn new(workflow: &Workflow, input: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            workflow: workflow.name.clone(),
+            status: RunStatus::Running,
+            input: input.to_string(),
+            started_at: Utc::now(),
+            finished_at: None,
+            steps: workflow
+                .steps
+                .iter()
+                .map(|step| StepRecord {
+                    id: step.id.clone(),
+                    status: StepStatus::Pending,
+                    attempts: 0,
+                    output: None,
+                    error: None,
+                    approval: None,
+                })
+                .collect(),
+            error: None,
+            definition: workflow.clone(),
+        }
+    }
+
+    /// First 8 characters of the id, enough to look the run up.
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(8)]
+    }
+
+    pub fn step(&self, id: &str) -> Option<&StepRecord> {
+        self.steps.iter().find(|s| s.id == id)
+    }
+
+    /// Output of the last step that produced one: the run's result.
+    pub fn final_output(&self) -> Option<&str> {
+        self.steps
+            .iter()
+            .rev()
+            .filter(|s| s.status == StepStatus::Succeeded)
+            .find_map(|s| s.output.as_deref())
+    }
+
+    /// One line per step, e.g. "- build: succeeded (2 attempts)".
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "Workflow {} (run {}) {}",
+            self.workflow,
+            self.short_id(),
+            match self.status {
+                RunStatus::Running => "is running",
+                RunStatus::Paused => "is waiting for approval",
+                RunStatus::Succeeded => "succeeded",
+                RunStatus::Failed => "failed",
+            }
+        );
+        if let Some(error) = &self.error {
+            let _ = write!(out, ": {error}");
+        }
+        for step in &self.steps {
+            let status = match step.status {
+                StepStatus::Pending => "pending",
+                StepStatus::AwaitingApproval => "awaiting approval",
+                StepStatus::Succeeded => "succeeded",
+                StepStatus::Failed => "failed",
+                StepStatus::Skipped => "skipped",
+            };
+            let _ = write!(out, "\n- {}: {status}", step.id);
+            if step.attempts > 1 {
+                let _ = write!(out, " ({} attempts)", step.attempts);
+            }
+            if let Some(error) = &step.error {
+                let _ = write!(out, " — {error}");
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE: &str = r#"
+description = "Draft release notes"
+schedule = "0 9 * * 1"
+
+[[steps]]
+id = "log"
+tool = "shell"
+args = { command = "git log --oneline -20" }
+
+[[steps]]
+id = "draft"
+depends_on = ["log"]
+retries = 1
+prompt = "Write release notes from:\n{{steps.log.output}}"
+
+[[steps]]
+id = "report_failure"
+depends_on = ["log"]
+when = { step = "log", succeeded = false }
+prompt = "Explain why git log failed"
+"#;
+
+    #[test]
+    fn toml_and_yaml_workflows_load_and_plan_in_waves() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("release.toml"), RELEASE).unwrap();
+        std::fs::write(
+            tmp.path().join("triage.yaml"),
+            "steps:\n  - id: read\n    agent:\n      prompt: Triage {{input}}\n      tools: [file_read]\n",
+        )
+        .unwrap();
+        std::fs::write(tmp.path().join("broken.toml"), "steps = []").unwrap();
+
+        let names: Vec<String> = list(tmp.path()).into_iter().map(|w| w.name).collect();
+        assert_eq!(names, ["release", "triage"]);
+
+        let release = load(tmp.path(), "release").unwrap();
+        assert_eq!(release.waves().unwrap(), vec![vec![0], vec![1, 2]]);
+        assert!(matches!(
+            release.steps[0].kind().unwrap(),
+            StepKind::Tool("shell", Some(_))
+        ));
+        let triage = load(tmp.path(), "triage").unwrap();
+        assert!(matches!(
+            triage.steps[0].kind().unwrap(),
+            StepKind::Agent(_)
+        ));
+        assert!(load(tmp.path(), "../release").is_err());
+        assert!(load(tmp.path(), "broken").is_err());
+    }
+
+    #[test]
+    fn invalid_definitions_are_rejected() {
+        let parse = |raw: &str| {
+            let mut workflow: Workflow = toml::from_str(raw).unwrap();
+            workflow.name = "w".into();
+            workflow.validate()
+        };
+        let cycle = r#"
+[[steps]]
+id = "a"
+depends_on = ["b"]
+prompt = "x"
+[[steps]]
+id = "b"
+depends_on = ["a"]
+prompt = "y"
+"#;
+        assert!(parse(cycle).unwrap_err().to_string().contains("cycle"));
+        let two_kinds = "[[steps]]\nid = \"a\"\nprompt = \"x\"\ntool = \"shell\"";
+        assert!(parse(two_kinds).is_err());
+        let unknown_dep = "[[steps]]\nid = \"a\"\ndepends_on = [\"z\"]\nprompt = \"x\"";
+        assert!(parse(unknown_dep).is_err());
+        let stray_condition =
+            "[[steps]]\nid = \"a\"\nprompt = \"x\"\n[[steps]]\nid = \"b\"\nprompt = \"y\"\nwhen = { step = \"a\" }";
+        assert!(parse(stray_condition).is_err());
+        let bad_schedule = "schedule = \"every day\"\n[[steps]]\nid = \"a\"\nprompt = \"x\"";
+        assert!(parse(bad_schedule).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+//! Saved workflow runs: `<workspace>/state/workflow_runs/<uuid>.json`.
+
+use super::{RunStatus, WorkflowRun};
+use crate::util::json_dir::{JsonDir, Record};
+use anyhow::Result;
+use std::path::Path;
+
+/// File-backed store of workflow runs.
+#[derive(Debug, Clone)]
+pub struct RunStore {
+    records: JsonDir<WorkflowRun>,
+}
+
+impl Record for WorkflowRun {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl RunStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            records: JsonDir::new(
+                workspace_dir.join("state").join("workflow_runs"),
+                "workflow run",
+            ),
+        }
+    }
+
+    /// Write the run atomically (temp file + rename).
+    pub fn save(&self, run: &WorkflowRun) -> Result<()> {
+        self.records.save(run)
+    }
+
+    /// Load a run by full id or unambiguous id prefix.
+    pub fn load(&self, id: &str) -> Result<WorkflowRun> {
+        self.records.load(id)
+    }
+
+    /// Every readable run, most recently started first.
+    pub fn list(&self) -> Result<Vec<WorkflowRun>> {
+        let mut runs = self.records.load_all()?;
+        runs.sort_by_key(|run| std::cmp::Reverse(run.started_at));
+        Ok(runs)
+    }
+
+    /// Runs waiting on approval steps.
+    pub fn paused(&self) -> Result<Vec<WorkflowRun>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|run| run.status == RunStatus::Paused)
+            .collect())
+    }
+}